@@ -0,0 +1,482 @@
+//! Usage: Local admin REST API (JSON; reuses the Tauri command handlers) for scripts and other
+//! apps that automate the hub without the GUI.
+//!
+//! Routes:
+//! - `GET  /health`
+//! - `GET  /gateway/status`, `POST /gateway/start`, `POST /gateway/stop`
+//! - `GET  /providers/:cli_key`
+//! - `GET  /usage/summary?range=today&cli_key=claude`
+//! - `GET  /request-logs?limit=N`
+//! - `GET  /sort-modes/active`, `PUT /sort-modes/active/:cli_key` (`{"mode_id": 1 | null}`)
+//! - `GET  /cli-proxy`, `PUT /cli-proxy/:cli_key` (`{"enabled": true}`)
+//!
+//! Every route requires the gateway access token as `Authorization: Bearer <token>`, loopback
+//! peers included, and any request carrying an `Origin` header is refused: the API has no browser
+//! clients, so this keeps web pages from driving it with cross-site form posts.
+//!
+//! Served in two places:
+//! - headless mode's own listener (a token is generated on first start if none is configured);
+//! - the gateway's axum server under `/__aio__/api`: loopback peers only (the API stays disabled
+//!   until a token is configured).
+
+use axum::body::Body;
+use axum::extract::{ConnectInfo, Path, Query, State};
+use axum::http::{header, HeaderMap, Request, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use serde::Deserialize;
+use std::net::SocketAddr;
+use tauri::Manager;
+
+use crate::app_state::{DbInitState, GatewayState};
+use crate::audit_log::{self, AuditOrigin};
+use crate::commands;
+use crate::settings;
+use crate::shared::security::constant_time_eq;
+
+#[derive(Clone)]
+struct AdminState {
+    app: crate::AppHandle,
+}
+
+struct AdminError(String);
+
+impl From<String> for AdminError {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl IntoResponse for AdminError {
+    fn into_response(self) -> Response {
+        let status = if self.0.starts_with("SEC_INVALID_INPUT") {
+            StatusCode::BAD_REQUEST
+        } else if self.0.starts_with("DB_NOT_FOUND") {
+            StatusCode::NOT_FOUND
+        } else {
+            StatusCode::INTERNAL_SERVER_ERROR
+        };
+        (status, Json(serde_json::json!({ "error": self.0 }))).into_response()
+    }
+}
+
+type AdminResult<T> = Result<Json<T>, AdminError>;
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| {
+            v.strip_prefix("Bearer ")
+                .or_else(|| v.strip_prefix("bearer "))
+        })
+        .map(str::trim)
+}
+
+/// Why a request was refused before reaching a handler.
+#[derive(Debug, PartialEq, Eq)]
+enum AccessDenied {
+    BrowserOrigin,
+    TokenNotConfigured,
+    BadToken,
+}
+
+impl IntoResponse for AccessDenied {
+    fn into_response(self) -> Response {
+        let (status, error) = match self {
+            Self::BrowserOrigin => (
+                StatusCode::FORBIDDEN,
+                "admin api does not accept browser requests",
+            ),
+            Self::TokenNotConfigured => (
+                StatusCode::FORBIDDEN,
+                "admin api is disabled until a gateway access token is configured",
+            ),
+            Self::BadToken => (
+                StatusCode::UNAUTHORIZED,
+                "admin api requires the gateway access token",
+            ),
+        };
+        (status, Json(serde_json::json!({ "error": error }))).into_response()
+    }
+}
+
+/// Shared gate for both listeners: no `Origin` header, and a bearer token matching `expected`
+/// (`None` when no token is configured or settings could not be read, which fails closed).
+fn check_access(headers: &HeaderMap, expected: Option<&str>) -> Result<(), AccessDenied> {
+    let has_origin = headers
+        .get(header::ORIGIN)
+        .is_some_and(|v| !matches!(v.to_str(), Ok(v) if v.trim().is_empty()));
+    if has_origin {
+        return Err(AccessDenied::BrowserOrigin);
+    }
+    let Some(expected) = expected else {
+        return Err(AccessDenied::TokenNotConfigured);
+    };
+    if bearer_token(headers)
+        .is_some_and(|key| constant_time_eq(key.as_bytes(), expected.as_bytes()))
+    {
+        return Ok(());
+    }
+    Err(AccessDenied::BadToken)
+}
+
+fn configured_token(app: &crate::AppHandle) -> Option<String> {
+    match settings::read(app) {
+        Ok(cfg) => settings::gateway_access_token(&cfg).map(str::to_string),
+        Err(err) => {
+            tracing::warn!(
+                "admin api: failed to read settings, rejecting request: {}",
+                err
+            );
+            None
+        }
+    }
+}
+
+/// Config changes made through this API are recorded with the `rest` origin.
+async fn mark_rest_origin(req: Request<Body>, next: Next) -> Response {
+    audit_log::with_origin(AuditOrigin::Rest, next.run(req)).await
+}
+
+async fn require_admin_access(
+    State(state): State<AdminState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let expected = configured_token(&state.app);
+    match check_access(req.headers(), expected.as_deref()) {
+        Ok(()) => next.run(req).await,
+        Err(denied) => {
+            tracing::warn!(peer = %peer, path = %req.uri().path(), reason = ?denied, "admin api access rejected");
+            denied.into_response()
+        }
+    }
+}
+
+async fn require_local_api_token(
+    State(state): State<AdminState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    if !peer.ip().to_canonical().is_loopback() {
+        tracing::warn!(peer = %peer, path = %req.uri().path(), "local api rejected non-loopback peer");
+        return (
+            StatusCode::FORBIDDEN,
+            Json(serde_json::json!({ "error": "local api only accepts loopback clients" })),
+        )
+            .into_response();
+    }
+
+    let expected = configured_token(&state.app);
+    match check_access(req.headers(), expected.as_deref()) {
+        Ok(()) => next.run(req).await,
+        Err(denied) => {
+            tracing::warn!(peer = %peer, path = %req.uri().path(), reason = ?denied, "local api access rejected");
+            denied.into_response()
+        }
+    }
+}
+
+async fn health() -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "status": "ok",
+        "mode": "headless",
+        "version": env!("CARGO_PKG_VERSION"),
+    }))
+}
+
+async fn gateway_status(State(state): State<AdminState>) -> Json<crate::gateway::GatewayStatus> {
+    Json(commands::gateway::gateway_status(
+        state.app.state::<GatewayState>(),
+    ))
+}
+
+async fn gateway_start(
+    State(state): State<AdminState>,
+) -> AdminResult<crate::gateway::GatewayStatus> {
+    let app = state.app;
+    let status =
+        commands::gateway::gateway_start(app.clone(), app.state::<DbInitState>(), None).await?;
+    Ok(Json(status))
+}
+
+async fn gateway_stop(
+    State(state): State<AdminState>,
+) -> AdminResult<crate::gateway::GatewayStatus> {
+    let app = state.app;
+    let status = commands::gateway::gateway_stop(app.clone(), app.state::<GatewayState>()).await?;
+    Ok(Json(status))
+}
+
+async fn providers_list(
+    State(state): State<AdminState>,
+    Path(cli_key): Path<String>,
+) -> AdminResult<Vec<crate::providers::ProviderSummary>> {
+    let app = state.app;
+    let rows =
+        commands::providers::providers_list(app.clone(), app.state::<DbInitState>(), cli_key, None)
+            .await?;
+    Ok(Json(rows))
+}
+
+#[derive(Debug, Deserialize)]
+struct UsageSummaryQuery {
+    range: Option<String>,
+    cli_key: Option<String>,
+}
+
+async fn usage_summary(
+    State(state): State<AdminState>,
+    Query(query): Query<UsageSummaryQuery>,
+) -> AdminResult<crate::usage_stats::UsageSummary> {
+    let app = state.app;
+    let summary = commands::usage::usage_summary(
+        app.clone(),
+        app.state::<DbInitState>(),
+        query.range.unwrap_or_else(|| "today".to_string()),
+        query.cli_key,
+    )
+    .await?;
+    Ok(Json(summary))
+}
+
+#[derive(Debug, Deserialize)]
+struct LimitQuery {
+    limit: Option<u32>,
+}
+
+async fn request_logs_list(
+    State(state): State<AdminState>,
+    Query(query): Query<LimitQuery>,
+) -> AdminResult<Vec<crate::request_logs::RequestLogSummary>> {
+    let app = state.app;
+    let rows = commands::request_logs::request_logs_list_all(
+        app.clone(),
+        app.state::<DbInitState>(),
+        query.limit,
+    )
+    .await?;
+    Ok(Json(rows))
+}
+
+async fn sort_mode_active_list(
+    State(state): State<AdminState>,
+) -> AdminResult<Vec<crate::sort_modes::SortModeActiveRow>> {
+    let app = state.app;
+    let rows = commands::sort_modes::sort_mode_active_list(app.clone(), app.state::<DbInitState>())
+        .await?;
+    Ok(Json(rows))
+}
+
+#[derive(Debug, Deserialize)]
+struct SortModeActiveBody {
+    mode_id: Option<i64>,
+}
+
+async fn sort_mode_active_set(
+    State(state): State<AdminState>,
+    Path(cli_key): Path<String>,
+    Json(body): Json<SortModeActiveBody>,
+) -> AdminResult<crate::sort_modes::SortModeActiveRow> {
+    let app = state.app;
+    let row = commands::sort_modes::sort_mode_active_set(
+        app.clone(),
+        app.state::<DbInitState>(),
+        app.state::<GatewayState>(),
+        cli_key,
+        body.mode_id,
+    )
+    .await?;
+    Ok(Json(row))
+}
+
+async fn cli_proxy_status(
+    State(state): State<AdminState>,
+) -> AdminResult<Vec<crate::cli_proxy::CliProxyStatus>> {
+    let rows = commands::cli_proxy::cli_proxy_status_all(state.app).await?;
+    Ok(Json(rows))
+}
+
+#[derive(Debug, Deserialize)]
+struct CliProxyBody {
+    enabled: bool,
+}
+
+async fn cli_proxy_set(
+    State(state): State<AdminState>,
+    Path(cli_key): Path<String>,
+    Json(body): Json<CliProxyBody>,
+) -> AdminResult<crate::cli_proxy::CliProxyResult> {
+    let app = state.app;
+    let result = commands::cli_proxy::cli_proxy_set_enabled(
+        app.clone(),
+        app.state::<DbInitState>(),
+        cli_key,
+        body.enabled,
+    )
+    .await?;
+    Ok(Json(result))
+}
+
+fn api_routes() -> Router<AdminState> {
+    Router::new()
+        .route("/health", get(health))
+        .route("/gateway/status", get(gateway_status))
+        .route("/gateway/start", post(gateway_start))
+        .route("/gateway/stop", post(gateway_stop))
+        .route("/providers/:cli_key", get(providers_list))
+        .route("/usage/summary", get(usage_summary))
+        .route("/request-logs", get(request_logs_list))
+        .route("/sort-modes/active", get(sort_mode_active_list))
+        .route("/sort-modes/active/:cli_key", put(sort_mode_active_set))
+        .route("/cli-proxy", get(cli_proxy_status))
+        .route("/cli-proxy/:cli_key", put(cli_proxy_set))
+        .route_layer(middleware::from_fn(mark_rest_origin))
+}
+
+fn build_router(state: AdminState) -> Router {
+    api_routes()
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_admin_access,
+        ))
+        .with_state(state)
+}
+
+/// The same API for nesting under the gateway's `/__aio__/api` prefix (token always required).
+pub(crate) fn gateway_local_api<S>(app: crate::AppHandle) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let state = AdminState { app };
+    api_routes()
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_local_api_token,
+        ))
+        .with_state(state)
+}
+
+/// Generates and saves a gateway access token when none is configured, so the admin listener is
+/// usable on a fresh headless install; the token is read from `settings.json`, never logged.
+fn ensure_access_token(app: &crate::AppHandle) -> crate::shared::error::AppResult<()> {
+    let mut cfg = settings::read(app)?;
+    if settings::gateway_access_token(&cfg).is_some() {
+        return Ok(());
+    }
+    cfg.schema_version = settings::SCHEMA_VERSION;
+    cfg.gateway_access_token = settings::generate_gateway_access_token();
+    settings::write(app, &cfg)?;
+    let path = settings::settings_path(app)
+        .map(|p| p.display().to_string())
+        .unwrap_or_default();
+    tracing::info!(
+        settings_path = %path,
+        "admin api: generated a gateway access token (see `gateway_access_token`)"
+    );
+    Ok(())
+}
+
+pub(crate) fn spawn(app: crate::AppHandle, addr: SocketAddr) {
+    tauri::async_runtime::spawn(async move {
+        let app_for_token = app.clone();
+        if let Err(err) = crate::blocking::run("admin_api_ensure_access_token", move || {
+            ensure_access_token(&app_for_token)
+        })
+        .await
+        {
+            tracing::warn!("admin api: failed to set up an access token: {}", err);
+        }
+
+        let listener = match tokio::net::TcpListener::bind(addr).await {
+            Ok(listener) => listener,
+            Err(err) => {
+                tracing::error!(addr = %addr, "admin api bind failed: {}", err);
+                return;
+            }
+        };
+        tracing::info!(addr = %addr, "admin api listening");
+
+        let router = build_router(AdminState { app });
+        if let Err(err) = axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        {
+            tracing::error!(addr = %addr, "admin api server runtime error: {}", err);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bearer_token, check_access, AccessDenied};
+    use axum::http::{HeaderMap, HeaderValue};
+
+    #[test]
+    fn bearer_token_accepts_both_prefix_cases() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(bearer_token(&headers), None);
+
+        headers.insert("authorization", HeaderValue::from_static("Bearer abc "));
+        assert_eq!(bearer_token(&headers), Some("abc"));
+
+        headers.insert("authorization", HeaderValue::from_static("bearer xyz"));
+        assert_eq!(bearer_token(&headers), Some("xyz"));
+
+        headers.insert("authorization", HeaderValue::from_static("Basic xyz"));
+        assert_eq!(bearer_token(&headers), None);
+    }
+
+    #[test]
+    fn check_access_requires_token() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            check_access(&headers, None),
+            Err(AccessDenied::TokenNotConfigured)
+        );
+        assert_eq!(
+            check_access(&headers, Some("secret")),
+            Err(AccessDenied::BadToken)
+        );
+
+        headers.insert("authorization", HeaderValue::from_static("Bearer wrong"));
+        assert_eq!(
+            check_access(&headers, Some("secret")),
+            Err(AccessDenied::BadToken)
+        );
+        assert_eq!(
+            check_access(&headers, None),
+            Err(AccessDenied::TokenNotConfigured)
+        );
+
+        headers.insert("authorization", HeaderValue::from_static("Bearer secret"));
+        assert_eq!(check_access(&headers, Some("secret")), Ok(()));
+    }
+
+    #[test]
+    fn check_access_rejects_any_origin_even_with_token() {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", HeaderValue::from_static("Bearer secret"));
+        headers.insert("origin", HeaderValue::from_static("https://evil.example"));
+        assert_eq!(
+            check_access(&headers, Some("secret")),
+            Err(AccessDenied::BrowserOrigin)
+        );
+
+        headers.insert("origin", HeaderValue::from_static("null"));
+        assert_eq!(
+            check_access(&headers, Some("secret")),
+            Err(AccessDenied::BrowserOrigin)
+        );
+
+        headers.insert("origin", HeaderValue::from_static(""));
+        assert_eq!(check_access(&headers, Some("secret")), Ok(()));
+    }
+}
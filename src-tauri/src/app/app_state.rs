@@ -12,7 +12,7 @@ pub(crate) struct GatewayState(pub(crate) Mutex<gateway::GatewayManager>);
 pub(crate) struct DbInitState(pub(crate) OnceCell<AppResult<db::Db>>);
 
 pub(crate) async fn ensure_db_ready(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     state: &DbInitState,
 ) -> AppResult<db::Db> {
     state
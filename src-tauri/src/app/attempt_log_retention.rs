@@ -0,0 +1,43 @@
+//! Usage: Background job that prunes per-attempt request log details past
+//! `attempt_log_retention_days` (request rows themselves are kept).
+
+use std::time::Duration;
+
+use crate::shared::time::now_unix_seconds;
+use crate::{blocking, db, request_logs, settings};
+
+const PRUNE_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+pub(crate) fn spawn(app: crate::AppHandle, db: db::Db) {
+    tracing::info!(
+        interval_s = PRUNE_INTERVAL.as_secs(),
+        "attempt log retention job started"
+    );
+
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(err) = prune_once(&app, &db).await {
+                tracing::warn!("attempt log retention prune failed: {}", err);
+            }
+        }
+    });
+}
+
+async fn prune_once(app: &crate::AppHandle, db: &db::Db) -> crate::shared::error::AppResult<()> {
+    let retention_days = settings::read(app)?.attempt_log_retention_days.max(1);
+    let cutoff_unix =
+        now_unix_seconds().saturating_sub(i64::from(retention_days).saturating_mul(24 * 60 * 60));
+
+    let pruned = blocking::run("attempt_log_retention_prune", {
+        let db = db.clone();
+        move || request_logs::prune_attempt_details_before(&db, cutoff_unix)
+    })
+    .await?;
+
+    if pruned > 0 {
+        tracing::info!(retention_days, pruned, "pruned old request attempt details");
+    }
+    Ok(())
+}
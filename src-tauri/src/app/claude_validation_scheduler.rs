@@ -0,0 +1,155 @@
+//! Usage: Background loop that runs scheduled Claude model validations and alerts on regressions.
+//!
+//! Contract:
+//! - Every 60s the due schedules (`claude_model_validation_schedules`) run one after another
+//!   through the regular validation workflow (results land in `claude_model_validation_runs`).
+//! - When a provider+model that passed its previous scheduled run fails, an error notice is sent.
+
+use std::time::Duration;
+
+use crate::shared::i18n;
+use crate::shared::time::now_unix_seconds;
+use crate::{
+    blocking, claude_model_validation, claude_model_validation_schedules, db, notice,
+    provider_validation,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+const NOTICE_ERROR_MAX_CHARS: usize = 200;
+
+pub(crate) fn spawn(app: crate::AppHandle, db: db::Db) {
+    tracing::info!(
+        interval_s = POLL_INTERVAL.as_secs(),
+        "claude validation scheduler started"
+    );
+
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(err) = tick(&app, &db).await {
+                tracing::warn!("claude validation scheduler tick failed: {}", err);
+            }
+        }
+    });
+}
+
+async fn tick(app: &crate::AppHandle, db: &db::Db) -> crate::shared::error::AppResult<()> {
+    let due = blocking::run("claude_validation_scheduler_due", {
+        let db = db.clone();
+        move || claude_model_validation_schedules::due_at(&db, now_unix_seconds())
+    })
+    .await?;
+
+    for target in due {
+        let (ok, error) = run_one(db, &target).await;
+
+        let previous_ok = blocking::run("claude_validation_scheduler_record", {
+            let db = db.clone();
+            let error = error.clone();
+            let schedule_id = target.schedule_id;
+            move || {
+                claude_model_validation_schedules::record_run(
+                    &db,
+                    schedule_id,
+                    now_unix_seconds(),
+                    ok,
+                    error.as_deref(),
+                )
+            }
+        })
+        .await;
+
+        let previous_ok = match previous_ok {
+            Ok(v) => v,
+            Err(err) => {
+                tracing::warn!(
+                    schedule_id = target.schedule_id,
+                    "claude validation scheduler failed to record run: {}",
+                    err
+                );
+                continue;
+            }
+        };
+
+        tracing::info!(
+            schedule_id = target.schedule_id,
+            provider_id = target.provider_id,
+            model = %target.model,
+            ok,
+            "scheduled claude model validation finished"
+        );
+
+        if claude_model_validation_schedules::is_regression(previous_ok, ok) {
+            emit_regression_notice(app, &target, error.as_deref());
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_one(
+    db: &db::Db,
+    target: &claude_model_validation_schedules::DueValidation,
+) -> (bool, Option<String>) {
+    let Some(base_url) =
+        provider_validation::first_base_url(&target.base_url, &target.base_urls_json)
+    else {
+        return (
+            false,
+            Some("SEC_INVALID_INPUT: provider has no base_url".to_string()),
+        );
+    };
+    let request_json = provider_validation::claude_probe_request_json(&target.model);
+    match claude_model_validation::validate_provider_model(
+        db.clone(),
+        target.provider_id,
+        &base_url,
+        &request_json,
+    )
+    .await
+    {
+        Ok(result) if result.ok => (true, None),
+        Ok(result) => {
+            let error = result
+                .error
+                .or_else(|| result.status.map(|status| format!("status={status}")));
+            (false, error)
+        }
+        Err(err) => (false, Some(err.to_string())),
+    }
+}
+
+fn emit_regression_notice(
+    app: &crate::AppHandle,
+    target: &claude_model_validation_schedules::DueValidation,
+    error: Option<&str>,
+) {
+    let title = i18n::format(
+        "notice.validation.title_regression",
+        &[("name", &target.provider_name)],
+    );
+    let mut lines = vec![
+        i18n::format(
+            "notice.line.provider",
+            &[("name", &target.provider_name), ("id", &target.provider_id)],
+        ),
+        i18n::format("notice.validation.line_model", &[("model", &target.model)]),
+    ];
+    if let Some(error) = error {
+        let error: String = error.chars().take(NOTICE_ERROR_MAX_CHARS).collect();
+        lines.push(i18n::format(
+            "notice.validation.line_error",
+            &[("error", &error)],
+        ));
+    }
+    lines.push(i18n::text("notice.validation.action").to_string());
+
+    let payload = notice::build(notice::NoticeLevel::Error, Some(title), lines.join("\n"));
+    if let Err(err) = notice::emit(app, payload) {
+        tracing::warn!(
+            "failed to emit claude validation regression notice: {}",
+            err
+        );
+    }
+}
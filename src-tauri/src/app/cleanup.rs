@@ -24,7 +24,7 @@ fn cleanup_notify() -> &'static Notify {
     CLEANUP_NOTIFY.get_or_init(Notify::new)
 }
 
-pub(crate) async fn cleanup_before_exit(app: &tauri::AppHandle) {
+pub(crate) async fn cleanup_before_exit(app: &crate::AppHandle) {
     let notify = cleanup_notify();
     match CLEANUP_STATE.compare_exchange(
         CLEANUP_STATE_IDLE,
@@ -33,6 +33,7 @@ pub(crate) async fn cleanup_before_exit(app: &tauri::AppHandle) {
         Ordering::Acquire,
     ) {
         Ok(_) => {
+            crate::mcp::supervise_stop_all();
             stop_gateway_best_effort(app).await;
             restore_cli_proxy_keep_state_best_effort(
                 app,
@@ -81,7 +82,7 @@ async fn wait_for_cleanup_done(notify: &Notify) {
 }
 
 pub(crate) async fn restore_cli_proxy_keep_state_best_effort(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     label: &'static str,
     context: &'static str,
     log_success: bool,
@@ -127,7 +128,7 @@ pub(crate) async fn restore_cli_proxy_keep_state_best_effort(
     }
 }
 
-pub(crate) async fn stop_gateway_best_effort(app: &tauri::AppHandle) {
+pub(crate) async fn stop_gateway_best_effort(app: &crate::AppHandle) {
     let running = {
         let state = app.state::<GatewayState>();
         let mut manager = state.0.lock_or_recover();
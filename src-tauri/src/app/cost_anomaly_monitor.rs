@@ -0,0 +1,171 @@
+//! Usage: Background loop that flags runaway sessions by cost (see `domain::cost_anomaly`).
+//!
+//! Contract:
+//! - Every 60s, when `enable_cost_anomaly_detection` is on, anomalous sessions get one desktop
+//!   notice; with `cost_anomaly_auto_throttle` they are also rate limited at the gateway.
+//! - A flagged session stays flagged (no repeat notice, throttle kept) until acknowledged via
+//!   `cost_anomaly_acknowledge`. After that it is not re-flagged for one detection window.
+//! - Flags live in memory only and are dropped on restart.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::gateway::session_throttle;
+use crate::shared::i18n;
+use crate::shared::mutex_ext::MutexExt;
+use crate::shared::time::now_unix_seconds;
+use crate::{blocking, cost_anomaly, db, notice, settings};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+struct MonitorState {
+    flagged: HashMap<String, cost_anomaly::CostAnomaly>,
+    // session_id -> acknowledged_at (unix seconds)
+    acknowledged: HashMap<String, i64>,
+}
+
+static STATE: OnceLock<Mutex<MonitorState>> = OnceLock::new();
+
+fn state() -> &'static Mutex<MonitorState> {
+    STATE.get_or_init(|| Mutex::new(MonitorState::default()))
+}
+
+pub(crate) fn spawn(app: crate::AppHandle, db: db::Db) {
+    tracing::info!(
+        interval_s = POLL_INTERVAL.as_secs(),
+        "cost anomaly monitor started"
+    );
+
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(err) = tick(&app, &db).await {
+                tracing::warn!("cost anomaly monitor tick failed: {}", err);
+            }
+        }
+    });
+}
+
+async fn tick(app: &crate::AppHandle, db: &db::Db) -> crate::shared::error::AppResult<()> {
+    let cfg = settings::read(app)?;
+    if !cfg.enable_cost_anomaly_detection {
+        return Ok(());
+    }
+
+    let now_unix = now_unix_seconds();
+    let anomalies = blocking::run("cost_anomaly_detect", {
+        let db = db.clone();
+        let multiplier = cfg.cost_anomaly_multiplier;
+        move || cost_anomaly::detect(&db, now_unix, multiplier)
+    })
+    .await?;
+
+    let mut fresh = Vec::new();
+    {
+        let mut state = state().lock_or_recover();
+        state
+            .acknowledged
+            .retain(|_, at| now_unix.saturating_sub(*at) < cost_anomaly::WINDOW_SECS);
+        for mut anomaly in anomalies {
+            if state.flagged.contains_key(&anomaly.session_id)
+                || state.acknowledged.contains_key(&anomaly.session_id)
+            {
+                continue;
+            }
+            if cfg.cost_anomaly_auto_throttle {
+                session_throttle::throttle(&anomaly.session_id);
+                anomaly.throttled = true;
+            }
+            state
+                .flagged
+                .insert(anomaly.session_id.clone(), anomaly.clone());
+            fresh.push(anomaly);
+        }
+    }
+
+    for anomaly in &fresh {
+        tracing::warn!(
+            session_id = %anomaly.session_id,
+            cli_key = %anomaly.cli_key,
+            provider_id = ?anomaly.provider_id,
+            recent_cost_usd = anomaly.recent_cost_usd,
+            baseline_cost_usd = anomaly.baseline_cost_usd,
+            throttled = anomaly.throttled,
+            "session cost anomaly detected"
+        );
+        emit_anomaly_notice(app, anomaly);
+    }
+    Ok(())
+}
+
+/// Currently flagged sessions, most recent first.
+pub(crate) fn list() -> Vec<cost_anomaly::CostAnomaly> {
+    let mut out: Vec<cost_anomaly::CostAnomaly> = state()
+        .lock_or_recover()
+        .flagged
+        .values()
+        .cloned()
+        .map(|mut anomaly| {
+            anomaly.throttled = session_throttle::is_throttled(&anomaly.session_id);
+            anomaly
+        })
+        .collect();
+    out.sort_by(|a, b| b.detected_at.cmp(&a.detected_at));
+    out
+}
+
+/// Clears the flag (and throttle) of `session_id`. Returns whether anything was cleared.
+pub(crate) fn acknowledge(session_id: &str) -> bool {
+    let was_flagged = {
+        let mut state = state().lock_or_recover();
+        let was_flagged = state.flagged.remove(session_id).is_some();
+        state
+            .acknowledged
+            .insert(session_id.to_string(), now_unix_seconds());
+        was_flagged
+    };
+    let was_throttled = session_throttle::release(session_id);
+    was_flagged || was_throttled
+}
+
+fn emit_anomaly_notice(app: &crate::AppHandle, anomaly: &cost_anomaly::CostAnomaly) {
+    let provider_name = anomaly.provider_name.as_deref().unwrap_or("-");
+    let title = i18n::format("notice.cost_anomaly.title", &[("name", &provider_name)]);
+    let mut lines = vec![
+        i18n::format("notice.line.cli", &[("cli_key", &anomaly.cli_key)]),
+        i18n::format(
+            "notice.cost_anomaly.line_session",
+            &[("session_id", &anomaly.session_id)],
+        ),
+    ];
+    if let Some(provider_id) = anomaly.provider_id {
+        lines.push(i18n::format(
+            "notice.line.provider",
+            &[("name", &provider_name), ("id", &provider_id)],
+        ));
+    }
+    lines.push(i18n::format(
+        "notice.cost_anomaly.line_cost",
+        &[
+            ("recent", &format!("{:.2}", anomaly.recent_cost_usd)),
+            ("baseline", &format!("{:.2}", anomaly.baseline_cost_usd)),
+            ("multiplier", &anomaly.multiplier),
+        ],
+    ));
+    lines.push(
+        i18n::text(if anomaly.throttled {
+            "notice.cost_anomaly.action_throttled"
+        } else {
+            "notice.cost_anomaly.action"
+        })
+        .to_string(),
+    );
+
+    let payload = notice::build(notice::NoticeLevel::Warning, Some(title), lines.join("\n"));
+    if let Err(err) = notice::emit(app, payload) {
+        tracing::warn!("failed to emit cost anomaly notice: {}", err);
+    }
+}
@@ -0,0 +1,192 @@
+//! Usage: Headless (daemon) mode: no webview window and no tray, while the DB, gateway, background
+//! jobs and a local admin HTTP API (see `admin_api`) still start.
+//!
+//! Two ways in:
+//! - the `headless` build (`cargo build --release --features headless`) always runs headless on
+//!   Tauri's mock runtime: no event loop, so no display server is needed (home servers, containers);
+//! - a regular build with `--headless` (or `AIO_CODING_HUB_HEADLESS=1`) skips the window and tray
+//!   but still runs the webview event loop, which needs a display on Linux.
+//!
+//! `--admin-addr <host:port>` (or `AIO_CODING_HUB_ADMIN_ADDR`) picks the admin API address.
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const HEADLESS_FLAG: &str = "--headless";
+const ADMIN_ADDR_FLAG: &str = "--admin-addr";
+const HEADLESS_ENV: &str = "AIO_CODING_HUB_HEADLESS";
+const ADMIN_ADDR_ENV: &str = "AIO_CODING_HUB_ADMIN_ADDR";
+const DEFAULT_ADMIN_ADDR: &str = "127.0.0.1:37200";
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HeadlessOptions {
+    pub(crate) admin_addr: SocketAddr,
+}
+
+fn env_flag_enabled(value: Option<String>) -> bool {
+    value
+        .map(|v| v.trim().to_ascii_lowercase())
+        .is_some_and(|v| v == "1" || v == "true" || v == "yes")
+}
+
+fn parse_admin_addr(raw: &str) -> Result<SocketAddr, String> {
+    raw.trim()
+        .parse()
+        .map_err(|e| format!("invalid admin address {raw:?} (expected host:port): {e}"))
+}
+
+fn parse(
+    args: impl IntoIterator<Item = String>,
+    headless_env: Option<String>,
+    admin_addr_env: Option<String>,
+) -> Result<Option<HeadlessOptions>, String> {
+    let mut headless = cfg!(feature = "headless") || env_flag_enabled(headless_env);
+    let mut admin_addr = admin_addr_env;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == HEADLESS_FLAG {
+            headless = true;
+        } else if arg == ADMIN_ADDR_FLAG {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("{ADMIN_ADDR_FLAG} requires a value"))?;
+            admin_addr = Some(value);
+        } else if let Some(value) = arg
+            .strip_prefix(ADMIN_ADDR_FLAG)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            admin_addr = Some(value.to_string());
+        }
+    }
+
+    if !headless {
+        return Ok(None);
+    }
+
+    let admin_addr = parse_admin_addr(admin_addr.as_deref().unwrap_or(DEFAULT_ADMIN_ADDR))?;
+    Ok(Some(HeadlessOptions { admin_addr }))
+}
+
+/// Reads headless options from the process arguments and environment; `None` means desktop mode.
+pub(crate) fn options_from_env() -> Result<Option<HeadlessOptions>, String> {
+    parse(
+        std::env::args().skip(1),
+        std::env::var(HEADLESS_ENV).ok(),
+        std::env::var(ADMIN_ADDR_ENV).ok(),
+    )
+}
+
+/// Marks the process as running headless (set once during setup).
+pub(crate) fn mark_active() {
+    ACTIVE.store(true, Ordering::Relaxed);
+}
+
+pub(crate) fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Turns Ctrl+C into a regular app exit so the exit cleanup (gateway stop, CLI proxy restore)
+/// still runs without a window or tray to quit from.
+#[cfg(not(feature = "headless"))]
+pub(crate) fn install_ctrl_c_exit(app: crate::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            tracing::warn!("headless: failed to listen for ctrl-c: {}", err);
+            return;
+        }
+        tracing::info!("headless: ctrl-c received, exiting");
+        app.exit(0);
+    });
+}
+
+/// Resolves on Ctrl+C, or on SIGTERM where the platform has it (systemd, `docker stop`).
+#[cfg(feature = "headless")]
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    result = tokio::signal::ctrl_c() => {
+                        if let Err(err) = result {
+                            tracing::warn!("headless: failed to listen for ctrl-c: {}", err);
+                        }
+                    }
+                    _ = sigterm.recv() => {}
+                }
+                return;
+            }
+            Err(err) => tracing::warn!("headless: failed to listen for SIGTERM: {}", err),
+        }
+    }
+    if let Err(err) = tokio::signal::ctrl_c().await {
+        tracing::warn!("headless: failed to listen for ctrl-c: {}", err);
+        std::future::pending::<()>().await;
+    }
+}
+
+/// Headless build main loop: the mock runtime has no event loop, so block until a shutdown signal
+/// and run the same exit cleanup the desktop build runs on `ExitRequested`.
+#[cfg(feature = "headless")]
+pub(crate) fn run_until_shutdown(app: &crate::AppHandle) {
+    tauri::async_runtime::block_on(async {
+        shutdown_signal().await;
+        tracing::info!("headless: shutdown signal received, cleaning up");
+        crate::app::cleanup::cleanup_before_exit(app).await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    #[cfg(not(feature = "headless"))]
+    fn desktop_mode_without_flag_or_env() {
+        assert_eq!(
+            parse(args(&["--admin-addr", "0.0.0.0:1"]), None, None),
+            Ok(None)
+        );
+        assert_eq!(parse(args(&[]), Some("0".into()), None), Ok(None));
+    }
+
+    #[test]
+    fn flag_enables_headless_with_default_admin_addr() {
+        let opts = parse(args(&["--headless"]), None, None).unwrap().unwrap();
+        assert_eq!(opts.admin_addr, DEFAULT_ADMIN_ADDR.parse().unwrap());
+
+        let opts = parse(args(&[]), Some("true".into()), None).unwrap();
+        assert!(opts.is_some());
+    }
+
+    #[test]
+    fn admin_addr_flag_overrides_env() {
+        let opts = parse(
+            args(&["--headless", "--admin-addr=127.0.0.1:4000"]),
+            None,
+            Some("127.0.0.1:5000".into()),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(opts.admin_addr.port(), 4000);
+
+        let opts = parse(args(&["--headless"]), None, Some("127.0.0.1:5000".into()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(opts.admin_addr.port(), 5000);
+    }
+
+    #[test]
+    fn invalid_admin_addr_is_rejected() {
+        assert!(parse(args(&["--headless", "--admin-addr", "nope"]), None, None).is_err());
+        assert!(parse(args(&["--headless", "--admin-addr"]), None, None).is_err());
+    }
+}
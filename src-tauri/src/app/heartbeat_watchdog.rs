@@ -139,7 +139,7 @@ impl HeartbeatWatchdogState {
     }
 }
 
-pub(crate) fn install(app: &tauri::AppHandle) {
+pub(crate) fn install(app: &crate::AppHandle) {
     tracing::info!(
         interval_s = HEARTBEAT_INTERVAL.as_secs(),
         timeout_s = PONG_TIMEOUT.as_secs(),
@@ -166,7 +166,7 @@ pub(crate) fn install(app: &tauri::AppHandle) {
     });
 }
 
-async fn check_and_recover_if_needed(app: &tauri::AppHandle) {
+async fn check_and_recover_if_needed(app: &crate::AppHandle) {
     let now = now_unix_millis();
     let state = app.state::<HeartbeatWatchdogState>();
     let snapshot = state.snapshot();
@@ -251,7 +251,7 @@ async fn check_and_recover_if_needed(app: &tauri::AppHandle) {
     }
 }
 
-async fn attempt_reload(window: &tauri::WebviewWindow) -> Result<(), AppError> {
+async fn attempt_reload(window: &tauri::WebviewWindow<crate::AppRuntime>) -> Result<(), AppError> {
     let mut errors: Vec<(&'static str, String)> = Vec::new();
 
     if let Err(err) = window.reload() {
@@ -14,7 +14,7 @@ const CLEANUP_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
 static TRACING_GUARD: OnceLock<Mutex<Option<WorkerGuard>>> = OnceLock::new();
 static TRACING_INIT: OnceLock<()> = OnceLock::new();
 
-pub(crate) fn init(app: &tauri::AppHandle) {
+pub(crate) fn init(app: &crate::AppHandle) {
     TRACING_INIT.get_or_init(|| {
         let app = app.clone();
         if let Err(err) = init_impl(&app) {
@@ -31,7 +31,7 @@ pub(crate) fn init(app: &tauri::AppHandle) {
     });
 }
 
-fn init_impl(app: &tauri::AppHandle) -> crate::shared::error::AppResult<()> {
+fn init_impl(app: &crate::AppHandle) -> crate::shared::error::AppResult<()> {
     let log_dir = ensure_log_dir(app)?;
     let env_filter = default_env_filter();
 
@@ -94,7 +94,7 @@ fn default_env_filter() -> tracing_subscriber::EnvFilter {
     })
 }
 
-fn ensure_log_dir(app: &tauri::AppHandle) -> crate::shared::error::AppResult<PathBuf> {
+fn ensure_log_dir(app: &crate::AppHandle) -> crate::shared::error::AppResult<PathBuf> {
     let base = app_paths::app_data_dir(app)?;
     let dir = base.join(LOG_SUBDIR);
     std::fs::create_dir_all(&dir)
@@ -102,7 +102,7 @@ fn ensure_log_dir(app: &tauri::AppHandle) -> crate::shared::error::AppResult<Pat
     Ok(dir)
 }
 
-fn spawn_cleanup_task(app: tauri::AppHandle, log_dir: PathBuf) {
+fn spawn_cleanup_task(app: crate::AppHandle, log_dir: PathBuf) {
     tauri::async_runtime::spawn(async move {
         let app_for_cleanup = app.clone();
         let log_dir_for_cleanup = log_dir.clone();
@@ -124,7 +124,7 @@ fn spawn_cleanup_task(app: tauri::AppHandle, log_dir: PathBuf) {
     });
 }
 
-fn cleanup_once(app: &tauri::AppHandle, log_dir: &Path) {
+fn cleanup_once(app: &crate::AppHandle, log_dir: &Path) {
     let retention_days = settings::log_retention_days_fail_open(app).max(1);
     match cleanup_logs(log_dir, retention_days) {
         Ok(deleted) if deleted > 0 => {
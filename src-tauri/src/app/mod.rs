@@ -1,8 +1,16 @@
 //! Usage: Application layer (Tauri-managed state, tray/window lifecycle, startup wiring).
 
+pub(crate) mod admin_api;
 pub(crate) mod app_state;
+pub(crate) mod attempt_log_retention;
+pub(crate) mod claude_validation_scheduler;
 pub(crate) mod cleanup;
+pub(crate) mod cost_anomaly_monitor;
+pub(crate) mod headless;
 pub(crate) mod heartbeat_watchdog;
 pub(crate) mod logging;
 pub(crate) mod notice;
+pub(crate) mod notification_sinks;
 pub(crate) mod resident;
+pub(crate) mod selftest;
+pub(crate) mod sort_mode_scheduler;
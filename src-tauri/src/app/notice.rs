@@ -4,13 +4,15 @@
 //! - 前端：`invoke("notice_send", { level, title?, body })` 触发通知
 //! - Rust 后台：调用 `notice::emit(app, payload)` 触发通知事件（由前端统一监听并发送系统通知）
 
+use crate::shared::i18n;
 use tauri::Emitter;
+use tauri_specta::Event;
 
-pub const NOTICE_EVENT_NAME: &str = "notice:notify";
+pub const NOTICE_EVENT_NAME: &str = NoticeEventPayload::NAME;
 
 const NOTICE_PREFIX: &str = "AIO Coding Hub";
 
-#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, Copy, serde::Deserialize, serde::Serialize, specta::Type)]
 #[serde(rename_all = "lowercase")]
 pub enum NoticeLevel {
     Info,
@@ -19,7 +21,8 @@ pub enum NoticeLevel {
     Error,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, specta::Type, tauri_specta::Event)]
+#[tauri_specta(event_name = "notice:notify")]
 pub struct NoticeEventPayload {
     pub level: NoticeLevel,
     pub title: String,
@@ -27,12 +30,12 @@ pub struct NoticeEventPayload {
 }
 
 fn default_title(level: NoticeLevel) -> &'static str {
-    match level {
-        NoticeLevel::Info => "提示",
-        NoticeLevel::Success => "成功",
-        NoticeLevel::Warning => "提醒",
-        NoticeLevel::Error => "错误",
-    }
+    i18n::text(match level {
+        NoticeLevel::Info => "notice.title.info",
+        NoticeLevel::Success => "notice.title.success",
+        NoticeLevel::Warning => "notice.title.warning",
+        NoticeLevel::Error => "notice.title.error",
+    })
 }
 
 fn normalize_optional_title(title: Option<String>) -> Option<String> {
@@ -58,7 +61,7 @@ pub fn build(level: NoticeLevel, title: Option<String>, body: String) -> NoticeE
 }
 
 pub fn emit(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     payload: NoticeEventPayload,
 ) -> crate::shared::error::AppResult<()> {
     app.emit(NOTICE_EVENT_NAME, payload)
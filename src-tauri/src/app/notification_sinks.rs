@@ -0,0 +1,319 @@
+//! Usage: External notification sinks (`notification_sinks` setting).
+//!
+//! Circuit breaker openings, provider budget alerts and gateway failures are forwarded to the
+//! configured sinks (generic JSON webhook, Slack-style incoming webhook, Telegram bot) next to
+//! the desktop notice. Delivery is best-effort on the async runtime; failures are only logged.
+
+use crate::notice::NoticeEventPayload;
+use crate::settings::{NotificationEventKind, NotificationSink, NotificationSinkKind};
+use crate::shared::error::AppResult;
+use crate::shared::i18n;
+use crate::shared::mutex_ext::MutexExt;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError, RwLock};
+use std::time::Duration;
+
+pub const MAX_NOTIFICATION_SINKS: usize = 20;
+const MAX_URL_LEN: usize = 2048;
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+/// Repeated gateway failures with the same cli + error code are forwarded at most this often.
+const GATEWAY_FAILURE_COOLDOWN_SECS: i64 = 5 * 60;
+const TELEGRAM_API_BASE: &str = "https://api.telegram.org";
+
+static SINKS: OnceLock<RwLock<Arc<Vec<NotificationSink>>>> = OnceLock::new();
+static HTTP_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+static GATEWAY_FAILURE_LAST_SENT: OnceLock<Mutex<HashMap<String, i64>>> = OnceLock::new();
+
+fn cell() -> &'static RwLock<Arc<Vec<NotificationSink>>> {
+    SINKS.get_or_init(|| RwLock::new(Arc::new(Vec::new())))
+}
+
+fn current() -> Arc<Vec<NotificationSink>> {
+    cell()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+fn http_client() -> &'static reqwest::Client {
+    HTTP_CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(SEND_TIMEOUT)
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+/// Call at startup and whenever the setting changes.
+pub fn configure(sinks: &[NotificationSink]) {
+    let enabled: Vec<NotificationSink> = sinks
+        .iter()
+        .filter(|sink| sink.enabled)
+        .take(MAX_NOTIFICATION_SINKS)
+        .cloned()
+        .collect();
+    *cell().write().unwrap_or_else(PoisonError::into_inner) = Arc::new(enabled);
+}
+
+/// Whether any enabled sink wants `kind`; lets callers skip building a payload.
+pub(crate) fn routes(kind: NotificationEventKind) -> bool {
+    current().iter().any(|sink| sink.events.contains(&kind))
+}
+
+pub(crate) fn validate_sinks(sinks: &[NotificationSink]) -> AppResult<()> {
+    if sinks.len() > MAX_NOTIFICATION_SINKS {
+        return Err(format!(
+            "SEC_INVALID_INPUT: at most {MAX_NOTIFICATION_SINKS} notification sinks are allowed"
+        )
+        .into());
+    }
+    for sink in sinks {
+        validate_sink(sink)?;
+    }
+    Ok(())
+}
+
+fn validate_sink(sink: &NotificationSink) -> AppResult<()> {
+    let url = sink.url.trim();
+    if url.is_empty() {
+        return Err("SEC_INVALID_INPUT: notification sink url is required".into());
+    }
+    if url.len() > MAX_URL_LEN {
+        return Err(format!(
+            "SEC_INVALID_INPUT: notification sink url must be at most {MAX_URL_LEN} characters"
+        )
+        .into());
+    }
+    match sink.kind {
+        NotificationSinkKind::Webhook | NotificationSinkKind::Slack => {
+            let parsed = reqwest::Url::parse(url)
+                .map_err(|e| format!("SEC_INVALID_INPUT: invalid notification sink url: {e}"))?;
+            if !matches!(parsed.scheme(), "http" | "https") {
+                return Err("SEC_INVALID_INPUT: notification sink url must be http(s)".into());
+            }
+        }
+        NotificationSinkKind::Telegram => {
+            if url.contains(['/', '?', '#']) || url.chars().any(char::is_whitespace) {
+                return Err("SEC_INVALID_INPUT: invalid telegram bot token".into());
+            }
+            if sink.telegram_chat_id.trim().is_empty() {
+                return Err("SEC_INVALID_INPUT: telegram chat id is required".into());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn event_name(kind: Option<NotificationEventKind>) -> &'static str {
+    match kind {
+        Some(NotificationEventKind::CircuitOpen) => "circuit_open",
+        Some(NotificationEventKind::BudgetAlert) => "budget_alert",
+        Some(NotificationEventKind::GatewayFailure) => "gateway_failure",
+        None => "test",
+    }
+}
+
+fn build_request(
+    sink: &NotificationSink,
+    kind: Option<NotificationEventKind>,
+    payload: &NoticeEventPayload,
+    sent_at: i64,
+) -> reqwest::RequestBuilder {
+    let text = format!("{}\n{}", payload.title, payload.body);
+    let (url, body) = match sink.kind {
+        NotificationSinkKind::Webhook => (
+            sink.url.trim().to_string(),
+            serde_json::json!({
+                "event": event_name(kind),
+                "level": payload.level,
+                "title": payload.title,
+                "body": payload.body,
+                "sent_at": sent_at,
+            }),
+        ),
+        NotificationSinkKind::Slack => (
+            sink.url.trim().to_string(),
+            serde_json::json!({ "text": text }),
+        ),
+        NotificationSinkKind::Telegram => (
+            format!("{TELEGRAM_API_BASE}/bot{}/sendMessage", sink.url.trim()),
+            serde_json::json!({
+                "chat_id": sink.telegram_chat_id.trim(),
+                "text": text,
+                "disable_web_page_preview": true,
+            }),
+        ),
+    };
+    http_client()
+        .post(url)
+        .header("Content-Type", "application/json")
+        .body(body.to_string())
+}
+
+async fn send(
+    sink: &NotificationSink,
+    kind: Option<NotificationEventKind>,
+    payload: &NoticeEventPayload,
+) -> AppResult<()> {
+    let sent_at = crate::shared::time::now_unix_seconds();
+    // The url may embed a token (Telegram, Slack), so errors are reported without it.
+    let resp = build_request(sink, kind, payload, sent_at)
+        .send()
+        .await
+        .map_err(|e| format!("NOTIFY_SEND: request failed: {}", e.without_url()))?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(format!("NOTIFY_SEND: sink responded with HTTP {}", status.as_u16()).into());
+    }
+    Ok(())
+}
+
+/// POSTs a JSON body to a webhook that is not a configured sink (per-provider webhooks).
+pub(crate) async fn post_json(url: &str, body: &serde_json::Value) -> AppResult<()> {
+    let resp = http_client()
+        .post(url)
+        .header("Content-Type", "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .map_err(|e| format!("NOTIFY_SEND: request failed: {}", e.without_url()))?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(format!(
+            "NOTIFY_SEND: webhook responded with HTTP {}",
+            status.as_u16()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn redacted(payload: &NoticeEventPayload) -> NoticeEventPayload {
+    NoticeEventPayload {
+        level: payload.level,
+        title: crate::redaction::redact_string(payload.title.clone()),
+        body: crate::redaction::redact_string(payload.body.clone()),
+    }
+}
+
+/// Forwards a notice to every enabled sink routed for `kind`.
+pub(crate) fn dispatch(kind: NotificationEventKind, payload: &NoticeEventPayload) {
+    let targets: Vec<NotificationSink> = current()
+        .iter()
+        .filter(|sink| sink.events.contains(&kind))
+        .cloned()
+        .collect();
+    if targets.is_empty() {
+        return;
+    }
+    let payload = redacted(payload);
+    tauri::async_runtime::spawn(async move {
+        for sink in targets {
+            if let Err(err) = send(&sink, Some(kind), &payload).await {
+                tracing::warn!(
+                    sink = %sink.name,
+                    event = event_name(Some(kind)),
+                    "notification sink delivery failed: {}",
+                    err
+                );
+            }
+        }
+    });
+}
+
+fn gateway_failure_due(key: String, now_unix: i64) -> bool {
+    let mut last_sent = GATEWAY_FAILURE_LAST_SENT
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock_or_recover();
+    last_sent.retain(|_, sent_at| now_unix - *sent_at < GATEWAY_FAILURE_COOLDOWN_SECS);
+    if last_sent.contains_key(&key) {
+        return false;
+    }
+    last_sent.insert(key, now_unix);
+    true
+}
+
+/// Forwards a failed gateway request, throttled per cli + error code.
+pub(crate) fn dispatch_gateway_failure(
+    trace_id: &str,
+    cli_key: &str,
+    error_code: &str,
+    status: Option<u16>,
+) {
+    if !routes(NotificationEventKind::GatewayFailure) {
+        return;
+    }
+    let now_unix = crate::shared::time::now_unix_seconds();
+    if !gateway_failure_due(format!("{cli_key}:{error_code}"), now_unix) {
+        return;
+    }
+    let status = status.map_or_else(|| "—".to_string(), |status| status.to_string());
+    let title = i18n::format("notice.gateway_failure.title", &[("cli_key", &cli_key)]);
+    let lines = [
+        i18n::format("notice.line.cli", &[("cli_key", &cli_key)]),
+        i18n::format(
+            "notice.gateway_failure.line_error",
+            &[("code", &error_code), ("status", &status)],
+        ),
+        i18n::format("notice.line.trace", &[("trace_id", &trace_id)]),
+    ];
+    let payload = crate::notice::build(
+        crate::notice::NoticeLevel::Error,
+        Some(title),
+        lines.join("\n"),
+    );
+    dispatch(NotificationEventKind::GatewayFailure, &payload);
+}
+
+/// Sends a test message to one sink and reports the delivery result.
+pub(crate) async fn send_test(sink: NotificationSink) -> AppResult<()> {
+    validate_sink(&sink)?;
+    let payload = crate::notice::build(
+        crate::notice::NoticeLevel::Info,
+        Some(i18n::text("notice.sink_test.title").to_string()),
+        i18n::text("notice.sink_test.body").to_string(),
+    );
+    send(&sink, None, &payload).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sink(kind: NotificationSinkKind, url: &str, chat_id: &str) -> NotificationSink {
+        NotificationSink {
+            kind,
+            url: url.to_string(),
+            telegram_chat_id: chat_id.to_string(),
+            ..NotificationSink::default()
+        }
+    }
+
+    #[test]
+    fn validates_sink_urls_and_telegram_fields() {
+        assert!(validate_sink(&sink(
+            NotificationSinkKind::Webhook,
+            "https://example.com/hook",
+            ""
+        ))
+        .is_ok());
+        assert!(
+            validate_sink(&sink(NotificationSinkKind::Slack, "ftp://example.com", "")).is_err()
+        );
+        assert!(validate_sink(&sink(NotificationSinkKind::Webhook, "  ", "")).is_err());
+        assert!(validate_sink(&sink(NotificationSinkKind::Telegram, "123:abc", "-100")).is_ok());
+        assert!(validate_sink(&sink(NotificationSinkKind::Telegram, "123:abc", "")).is_err());
+        assert!(validate_sink(&sink(NotificationSinkKind::Telegram, "123/abc", "-100")).is_err());
+    }
+
+    #[test]
+    fn gateway_failures_are_throttled_per_key() {
+        assert!(gateway_failure_due("claude:GW_TEST_A".to_string(), 1_000));
+        assert!(!gateway_failure_due("claude:GW_TEST_A".to_string(), 1_010));
+        assert!(gateway_failure_due("codex:GW_TEST_A".to_string(), 1_010));
+        assert!(gateway_failure_due(
+            "claude:GW_TEST_A".to_string(),
+            1_000 + GATEWAY_FAILURE_COOLDOWN_SECS
+        ));
+    }
+}
@@ -1,10 +1,16 @@
 //! Usage: Desktop resident mode (tray icon + window lifecycle hooks).
+//!
+//! The tray menu also carries per-CLI proxy toggles and the global "pause all routing" switch.
 
+#[cfg(desktop)]
+use crate::shared::i18n;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 const MAIN_WINDOW_LABEL: &str = "main";
 const TRAY_ID: &str = "main-tray";
 const TRAY_MENU_TOGGLE_ID: &str = "tray.toggle";
+const TRAY_MENU_CLI_PROXY_PREFIX: &str = "tray.cli_proxy.";
+const TRAY_MENU_PAUSE_ROUTING_ID: &str = "tray.pause_routing";
 const TRAY_MENU_QUIT_ID: &str = "tray.quit";
 
 pub struct ResidentState {
@@ -29,38 +35,148 @@ impl ResidentState {
     }
 }
 
+/// Creates the main window from `tauri.conf.json` (marked `create: false` there so headless mode
+/// can skip it).
+pub fn create_main_window(app: &crate::AppHandle) -> crate::shared::error::AppResult<()> {
+    let config = tauri::Manager::config(app)
+        .app
+        .windows
+        .iter()
+        .find(|w| w.label == MAIN_WINDOW_LABEL)
+        .cloned()
+        .ok_or_else(|| format!("window config `{MAIN_WINDOW_LABEL}` not found"))?;
+    tauri::WebviewWindowBuilder::from_config(app, &config)
+        .and_then(|builder| builder.build())
+        .map_err(|e| format!("failed to create main window: {e}"))?;
+    Ok(())
+}
+
 #[cfg(not(desktop))]
-pub fn setup_tray(_app: &tauri::AppHandle) -> crate::shared::error::AppResult<()> {
+pub fn setup_tray(_app: &crate::AppHandle) -> crate::shared::error::AppResult<()> {
     Ok(())
 }
 
 #[cfg(not(desktop))]
-pub fn show_main_window(_app: &tauri::AppHandle) {}
+pub fn refresh_tray_menu(_app: &crate::AppHandle) {}
+
+#[cfg(not(desktop))]
+pub fn show_main_window(_app: &crate::AppHandle) {}
 
 #[cfg(not(desktop))]
-pub fn on_window_event(_window: &tauri::Window, _event: &tauri::WindowEvent) {}
+pub fn on_window_event(_window: &tauri::Window<crate::AppRuntime>, _event: &tauri::WindowEvent) {}
 
 #[cfg(desktop)]
-use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use crate::app_state::DbInitState;
+#[cfg(desktop)]
+use crate::{blocking, cli_proxy, commands};
+#[cfg(desktop)]
+use tauri::menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem};
 #[cfg(desktop)]
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
 #[cfg(desktop)]
 use tauri::Manager;
 
 #[cfg(desktop)]
-pub fn setup_tray(app: &tauri::AppHandle) -> crate::shared::error::AppResult<()> {
-    let toggle_item = MenuItem::with_id(app, TRAY_MENU_TOGGLE_ID, "显示/隐藏", true, None::<&str>)
-        .map_err(|e| format!("failed to create tray toggle menu item: {e}"))?;
-    let quit_item = MenuItem::with_id(app, TRAY_MENU_QUIT_ID, "退出", true, None::<&str>)
-        .map_err(|e| format!("failed to create tray quit menu item: {e}"))?;
+fn build_tray_menu(
+    app: &crate::AppHandle,
+) -> crate::shared::error::AppResult<Menu<crate::AppRuntime>> {
+    let toggle_item = MenuItem::with_id(
+        app,
+        TRAY_MENU_TOGGLE_ID,
+        i18n::text("tray.toggle"),
+        true,
+        None::<&str>,
+    )
+    .map_err(|e| format!("failed to create tray toggle menu item: {e}"))?;
+
+    let statuses = cli_proxy::status_all(app).unwrap_or_else(|err| {
+        tracing::warn!("tray menu: failed to read cli proxy status: {}", err);
+        Vec::new()
+    });
+    let mut cli_proxy_items = Vec::new();
+    for cli_key in crate::shared::cli_key::SUPPORTED_CLI_KEYS {
+        let enabled = statuses
+            .iter()
+            .any(|status| status.cli_key == cli_key && status.enabled);
+        let item = CheckMenuItem::with_id(
+            app,
+            format!("{TRAY_MENU_CLI_PROXY_PREFIX}{cli_key}"),
+            i18n::format("tray.cli_proxy", &[("cli", &cli_label(cli_key))]),
+            true,
+            enabled,
+            None::<&str>,
+        )
+        .map_err(|e| format!("failed to create tray cli proxy menu item: {e}"))?;
+        cli_proxy_items.push(item);
+    }
+    let pause_item = CheckMenuItem::with_id(
+        app,
+        TRAY_MENU_PAUSE_ROUTING_ID,
+        i18n::text("tray.pause_routing"),
+        true,
+        crate::gateway::routing_pause::is_paused(),
+        None::<&str>,
+    )
+    .map_err(|e| format!("failed to create tray pause menu item: {e}"))?;
+
+    let quit_item = MenuItem::with_id(
+        app,
+        TRAY_MENU_QUIT_ID,
+        i18n::text("tray.quit"),
+        true,
+        None::<&str>,
+    )
+    .map_err(|e| format!("failed to create tray quit menu item: {e}"))?;
     let separator = PredefinedMenuItem::separator(app)
         .map_err(|e| format!("failed to create tray menu separator: {e}"))?;
+    let quit_separator = PredefinedMenuItem::separator(app)
+        .map_err(|e| format!("failed to create tray menu separator: {e}"))?;
 
-    let menu = Menu::with_items(app, &[&toggle_item, &separator, &quit_item])
-        .map_err(|e| format!("failed to create tray menu: {e}"))?;
+    let mut items: Vec<&dyn IsMenuItem<crate::AppRuntime>> = vec![&toggle_item, &separator];
+    items.extend(
+        cli_proxy_items
+            .iter()
+            .map(|item| item as &dyn IsMenuItem<crate::AppRuntime>),
+    );
+    items.push(&pause_item);
+    items.push(&quit_separator);
+    items.push(&quit_item);
+
+    Menu::with_items(app, &items).map_err(|e| format!("failed to create tray menu: {e}").into())
+}
 
-    let toggle_id = toggle_item.id().clone();
-    let quit_id = quit_item.id().clone();
+#[cfg(desktop)]
+fn cli_label(cli_key: &str) -> String {
+    let mut chars = cli_key.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Rebuilds the tray menu so its labels follow the current `ui_language`.
+#[cfg(desktop)]
+pub fn refresh_tray_menu(app: &crate::AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let result = build_tray_menu(app).and_then(|menu| {
+        tray.set_menu(Some(menu))
+            .map_err(|e| format!("failed to set tray menu: {e}").into())
+    });
+    if let Err(err) = result {
+        tracing::warn!("tray menu refresh failed: {}", err);
+    }
+}
+
+#[cfg(desktop)]
+pub fn setup_tray(app: &crate::AppHandle) -> crate::shared::error::AppResult<()> {
+    let menu = build_tray_menu(app)?;
+
+    // Menu items are rebuilt on language change; ids stay the same.
+    let toggle_id = tauri::menu::MenuId::new(TRAY_MENU_TOGGLE_ID);
+    let quit_id = tauri::menu::MenuId::new(TRAY_MENU_QUIT_ID);
+    let pause_routing_id = tauri::menu::MenuId::new(TRAY_MENU_PAUSE_ROUTING_ID);
 
     #[cfg(target_os = "macos")]
     let icon_bytes = include_bytes!("../../icons/trayTemplate.png");
@@ -87,6 +203,14 @@ pub fn setup_tray(app: &tauri::AppHandle) -> crate::shared::error::AppResult<()>
             }
             if event.id == toggle_id {
                 toggle_main_window(app);
+                return;
+            }
+            if event.id == pause_routing_id {
+                toggle_routing_pause(app);
+                return;
+            }
+            if let Some(cli_key) = event.id.as_ref().strip_prefix(TRAY_MENU_CLI_PROXY_PREFIX) {
+                toggle_cli_proxy(app, cli_key.to_string());
             }
         })
         .on_tray_icon_event(|tray, event| {
@@ -108,7 +232,47 @@ pub fn setup_tray(app: &tauri::AppHandle) -> crate::shared::error::AppResult<()>
 }
 
 #[cfg(desktop)]
-pub fn show_main_window(app: &tauri::AppHandle) {
+fn toggle_routing_pause(app: &crate::AppHandle) {
+    let paused = crate::gateway::routing_pause::toggle();
+    tracing::info!(paused, "gateway routing pause toggled from tray");
+    refresh_tray_menu(app);
+}
+
+/// Flips the CLI proxy through `cli_proxy_set_enabled`, which also refreshes the menu checkmarks.
+#[cfg(desktop)]
+fn toggle_cli_proxy(app: &crate::AppHandle, cli_key: String) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let enabled = blocking::run("tray_cli_proxy_is_enabled", {
+            let app = app.clone();
+            let cli_key = cli_key.clone();
+            move || cli_proxy::is_enabled(&app, &cli_key)
+        })
+        .await;
+        let enabled = match enabled {
+            Ok(v) => v,
+            Err(err) => {
+                tracing::warn!(cli_key = %cli_key, "tray: failed to read cli proxy state: {}", err);
+                refresh_tray_menu(&app);
+                return;
+            }
+        };
+
+        if let Err(err) = commands::cli_proxy::cli_proxy_set_enabled(
+            app.clone(),
+            app.state::<DbInitState>(),
+            cli_key.clone(),
+            !enabled,
+        )
+        .await
+        {
+            tracing::warn!(cli_key = %cli_key, "tray: cli proxy toggle failed: {}", err);
+        }
+    });
+}
+
+#[cfg(desktop)]
+pub fn show_main_window(app: &crate::AppHandle) {
     let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) else {
         return;
     };
@@ -125,13 +289,13 @@ pub fn show_main_window(app: &tauri::AppHandle) {
 /// The window starts hidden (via `visible: false` in tauri.conf.json).
 /// On macOS we also hide the dock icon so the app is tray-only.
 #[cfg(desktop)]
-pub fn hide_main_window_on_startup(_app: &tauri::AppHandle) {
+pub fn hide_main_window_on_startup(_app: &crate::AppHandle) {
     #[cfg(target_os = "macos")]
     set_dock_visibility(_app, false);
 }
 
 #[cfg(target_os = "macos")]
-fn set_dock_visibility(app: &tauri::AppHandle, visible: bool) {
+fn set_dock_visibility(app: &crate::AppHandle, visible: bool) {
     use tauri::ActivationPolicy;
 
     let policy = if visible {
@@ -150,7 +314,7 @@ fn set_dock_visibility(app: &tauri::AppHandle, visible: bool) {
 }
 
 #[cfg(desktop)]
-fn toggle_main_window(app: &tauri::AppHandle) {
+fn toggle_main_window(app: &crate::AppHandle) {
     let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) else {
         return;
     };
@@ -170,7 +334,7 @@ fn toggle_main_window(app: &tauri::AppHandle) {
 }
 
 #[cfg(desktop)]
-pub fn on_window_event(window: &tauri::Window, event: &tauri::WindowEvent) {
+pub fn on_window_event(window: &tauri::Window<crate::AppRuntime>, event: &tauri::WindowEvent) {
     if window.label() != MAIN_WINDOW_LABEL {
         return;
     }
@@ -0,0 +1,401 @@
+//! Usage: Startup self-test whose report is served by `app_selftest_report`.
+//!
+//! Runs once when the startup sequence settles (including its early exits on DB or gateway
+//! failure). Every check carries an `action` telling the user what to do about it; a failed
+//! critical check additionally emits a single `app:selftest_failed` event so the UI can surface
+//! it instead of the failure only reaching the log file.
+
+use crate::app_state::{ensure_db_ready, DbInitState, GatewayState};
+use crate::shared::cli_key::SUPPORTED_CLI_KEYS;
+use crate::shared::error::AppResult;
+use crate::shared::mutex_ext::MutexExt;
+use crate::shared::time::now_unix_seconds;
+use crate::{blocking, cli_proxy, db, settings, wsl};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+use tauri_specta::Event;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SelfTestStatus {
+    Ok,
+    Warning,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub(crate) struct SelfTestCheck {
+    /// `db_migrations` | `settings` | `gateway_port` | `cli_config_write` | `wsl_reachability`.
+    pub(crate) check: String,
+    pub(crate) status: SelfTestStatus,
+    /// A failed critical check leaves the gateway unusable and triggers `app:selftest_failed`.
+    pub(crate) critical: bool,
+    pub(crate) message: String,
+    /// What the user can do about a non-ok result.
+    pub(crate) action: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type, tauri_specta::Event)]
+#[tauri_specta(event_name = "app:selftest_failed")]
+pub(crate) struct SelfTestReport {
+    pub(crate) ran_at: i64,
+    /// `false` when any critical check failed.
+    pub(crate) ok: bool,
+    pub(crate) checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    fn new(ran_at: i64, checks: Vec<SelfTestCheck>) -> Self {
+        let ok = !checks
+            .iter()
+            .any(|c| c.critical && c.status == SelfTestStatus::Failed);
+        Self { ran_at, ok, checks }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct SelfTestState(Mutex<Option<SelfTestReport>>);
+
+impl SelfTestState {
+    /// Latest report; `None` until the startup sequence has finished.
+    pub(crate) fn report(&self) -> Option<SelfTestReport> {
+        self.0.lock_or_recover().clone()
+    }
+}
+
+fn check(
+    check: &str,
+    status: SelfTestStatus,
+    critical: bool,
+    message: impl Into<String>,
+    action: Option<String>,
+) -> SelfTestCheck {
+    SelfTestCheck {
+        check: check.to_string(),
+        status,
+        critical,
+        message: message.into(),
+        action,
+    }
+}
+
+fn check_db_migrations(app: &crate::AppHandle, db: &AppResult<db::Db>) -> SelfTestCheck {
+    let db_path = db::db_path(app)
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| "the app data directory".to_string());
+    let status = match db {
+        Ok(db) => db::schema_version_status(db),
+        Err(err) => Err(err.clone()),
+    };
+    match status {
+        Ok((current, latest)) if current >= latest => check(
+            "db_migrations",
+            SelfTestStatus::Ok,
+            true,
+            format!("schema v{current}"),
+            None,
+        ),
+        Ok((current, latest)) => check(
+            "db_migrations",
+            SelfTestStatus::Failed,
+            true,
+            format!("schema v{current} is behind v{latest}"),
+            Some("Restart the app to finish the pending migrations.".to_string()),
+        ),
+        Err(err) => check(
+            "db_migrations",
+            SelfTestStatus::Failed,
+            true,
+            err.to_string(),
+            Some(format!(
+                "Close other running copies of the app and restart; if it keeps failing, restore a backup of {db_path} or move it aside."
+            )),
+        ),
+    }
+}
+
+fn check_settings(app: &crate::AppHandle) -> SelfTestCheck {
+    match settings::read(app) {
+        Ok(cfg) => check(
+            "settings",
+            SelfTestStatus::Ok,
+            true,
+            format!("settings.json v{}", cfg.schema_version),
+            None,
+        ),
+        Err(err) => {
+            let path = settings::settings_path(app)
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|_| "settings.json".to_string());
+            check(
+                "settings",
+                SelfTestStatus::Failed,
+                true,
+                err.to_string(),
+                Some(format!(
+                    "Fix or remove {path}; defaults are in use until it parses again."
+                )),
+            )
+        }
+    }
+}
+
+fn check_gateway_port(app: &crate::AppHandle, db_ready: bool) -> SelfTestCheck {
+    let status = app.state::<GatewayState>().0.lock_or_recover().status();
+    if status.running {
+        return check(
+            "gateway_port",
+            SelfTestStatus::Ok,
+            true,
+            format!(
+                "gateway listening on {}",
+                status.listen_addr.unwrap_or_default()
+            ),
+            None,
+        );
+    }
+    if !db_ready {
+        return check(
+            "gateway_port",
+            SelfTestStatus::Skipped,
+            true,
+            "gateway was not started because the database is unavailable",
+            None,
+        );
+    }
+
+    let port = settings::read(app).unwrap_or_default().preferred_port;
+    if crate::commands::gateway::gateway_check_port_available(app.clone(), port) {
+        check(
+            "gateway_port",
+            SelfTestStatus::Failed,
+            true,
+            "gateway failed to start",
+            Some(
+                "Check the log for the start error, then start the gateway from the home page."
+                    .to_string(),
+            ),
+        )
+    } else {
+        check(
+            "gateway_port",
+            SelfTestStatus::Failed,
+            true,
+            format!("port {port} is unavailable"),
+            Some(format!(
+                "Stop the process using port {port} or choose another port in the gateway settings."
+            )),
+        )
+    }
+}
+
+/// Checks that `path` could be rewritten without touching it: an existing file is opened for
+/// append, otherwise a probe file is created and removed in the nearest existing ancestor.
+fn probe_writable(path: &Path) -> Result<(), String> {
+    if path.exists() {
+        return std::fs::OpenOptions::new()
+            .append(true)
+            .open(path)
+            .map(|_| ())
+            .map_err(|e| format!("{}: {e}", path.display()));
+    }
+
+    let dir = path
+        .ancestors()
+        .skip(1)
+        .find(|p| p.is_dir())
+        .ok_or_else(|| format!("{}: no existing parent directory", path.display()))?;
+    let probe = dir.join(format!(".aio-coding-hub-selftest-{}", std::process::id()));
+    std::fs::File::create(&probe).map_err(|e| format!("{}: {e}", dir.display()))?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
+fn check_cli_config_write(app: &crate::AppHandle) -> SelfTestCheck {
+    let mut failures = Vec::new();
+    for cli_key in SUPPORTED_CLI_KEYS {
+        match cli_proxy::config_file_paths(app, cli_key) {
+            Ok(paths) => failures.extend(paths.iter().filter_map(|p| probe_writable(p).err())),
+            Err(err) => failures.push(format!("{cli_key}: {err}")),
+        }
+    }
+
+    if failures.is_empty() {
+        return check(
+            "cli_config_write",
+            SelfTestStatus::Ok,
+            false,
+            "CLI config files are writable",
+            None,
+        );
+    }
+    check(
+        "cli_config_write",
+        SelfTestStatus::Failed,
+        false,
+        failures.join("; "),
+        Some(
+            "Fix the permissions on these paths; enabling the CLI proxy rewrites them.".to_string(),
+        ),
+    )
+}
+
+async fn check_wsl_reachability(app: &crate::AppHandle, enabled: bool) -> SelfTestCheck {
+    if !cfg!(windows) || !enabled {
+        return check(
+            "wsl_reachability",
+            SelfTestStatus::Skipped,
+            false,
+            "WSL auto-config is off",
+            None,
+        );
+    }
+
+    let report = match crate::commands::wsl::run_wsl_diagnose(app).await {
+        Ok(report) => report,
+        Err(err) => {
+            return check(
+                "wsl_reachability",
+                SelfTestStatus::Warning,
+                false,
+                err,
+                Some("Run the WSL diagnostics from the settings page.".to_string()),
+            )
+        }
+    };
+
+    let unreachable: Vec<String> = report
+        .findings
+        .iter()
+        .filter(|f| f.check == "host_reachable" && f.severity == wsl::WslFindingSeverity::Error)
+        .map(|f| match &f.distro {
+            Some(distro) => format!("{distro}: {}", f.message),
+            None => f.message.clone(),
+        })
+        .collect();
+    if unreachable.is_empty() {
+        return check(
+            "wsl_reachability",
+            SelfTestStatus::Ok,
+            false,
+            "WSL distros reach the gateway",
+            None,
+        );
+    }
+    check(
+        "wsl_reachability",
+        SelfTestStatus::Warning,
+        false,
+        unreachable.join("; "),
+        Some(
+            "Run the WSL diagnostics from the settings page and apply the suggested repairs."
+                .to_string(),
+        ),
+    )
+}
+
+async fn collect_checks(app: &crate::AppHandle) -> Vec<SelfTestCheck> {
+    let db = ensure_db_ready(app.clone(), app.state::<DbInitState>().inner()).await;
+    let db_ready = db.is_ok();
+
+    let (mut checks, wsl_enabled) = match blocking::run("app_selftest", {
+        let app = app.clone();
+        move || {
+            let checks = vec![
+                check_db_migrations(&app, &db),
+                check_settings(&app),
+                check_gateway_port(&app, db_ready),
+                check_cli_config_write(&app),
+            ];
+            Ok((
+                checks,
+                settings::read(&app).unwrap_or_default().wsl_auto_config,
+            ))
+        }
+    })
+    .await
+    {
+        Ok(v) => v,
+        Err(err) => (
+            vec![check(
+                "settings",
+                SelfTestStatus::Failed,
+                true,
+                format!("self-test task failed: {err}"),
+                Some("Restart the app.".to_string()),
+            )],
+            false,
+        ),
+    };
+
+    checks.push(check_wsl_reachability(app, wsl_enabled).await);
+    checks
+}
+
+/// Runs every check, stores the report and emits `app:selftest_failed` when a critical check
+/// failed.
+pub(crate) async fn run_startup(app: &crate::AppHandle) {
+    let report = SelfTestReport::new(now_unix_seconds(), collect_checks(app).await);
+
+    for c in &report.checks {
+        match c.status {
+            SelfTestStatus::Ok | SelfTestStatus::Skipped => {}
+            SelfTestStatus::Failed if c.critical => {
+                tracing::error!(check = %c.check, "startup self-test failed: {}", c.message)
+            }
+            _ => tracing::warn!(check = %c.check, "startup self-test: {}", c.message),
+        }
+    }
+
+    *app.state::<SelfTestState>().0.lock_or_recover() = Some(report.clone());
+    if !report.ok {
+        let _ = app.emit(SelfTestReport::NAME, report);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_is_not_ok_only_when_a_critical_check_failed() {
+        let report = SelfTestReport::new(
+            1,
+            vec![
+                check("settings", SelfTestStatus::Ok, true, "", None),
+                check("cli_config_write", SelfTestStatus::Failed, false, "", None),
+                check("gateway_port", SelfTestStatus::Skipped, true, "", None),
+            ],
+        );
+        assert!(report.ok);
+
+        let report = SelfTestReport::new(
+            1,
+            vec![check(
+                "db_migrations",
+                SelfTestStatus::Failed,
+                true,
+                "",
+                None,
+            )],
+        );
+        assert!(!report.ok);
+    }
+
+    #[test]
+    fn probe_writable_accepts_missing_file_under_writable_dir() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let missing = dir.path().join(".claude").join("settings.json");
+        assert!(probe_writable(&missing).is_ok());
+        assert!(!missing.exists());
+        assert_eq!(std::fs::read_dir(dir.path()).expect("read_dir").count(), 0);
+
+        let existing = dir.path().join("config.toml");
+        std::fs::write(&existing, "x = 1").expect("write");
+        assert!(probe_writable(&existing).is_ok());
+        assert_eq!(std::fs::read_to_string(&existing).expect("read"), "x = 1");
+    }
+}
@@ -0,0 +1,144 @@
+//! Usage: Background loop that flips `sort_mode_active` when a sort mode schedule crosses a
+//! boundary.
+//!
+//! Contract:
+//! - Every 30s each enabled schedule is evaluated; a switch happens only when the matching
+//!   rule changes (or on the first evaluation), so manual switches stick until the next boundary.
+//! - Each switch emits `sort_mode:schedule_switched` with [`SortModeScheduleSwitchedEvent`].
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+
+use crate::app_state::GatewayState;
+use crate::shared::mutex_ext::MutexExt;
+use crate::shared::time::now_unix_seconds;
+use crate::{blocking, db, sort_mode_schedules, sort_modes};
+
+pub(crate) const SCHEDULE_SWITCHED_EVENT: &str = "sort_mode:schedule_switched";
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SortModeScheduleSwitchedEvent {
+    cli_key: String,
+    previous_mode_id: Option<i64>,
+    mode_id: Option<i64>,
+    /// `None` when the schedule's `otherwise` mode applied.
+    rule_index: Option<usize>,
+    local_time: String,
+}
+
+/// Last (rule, mode) each CLI's schedule resolved to.
+type Slot = (Option<usize>, Option<i64>);
+
+static LAST_SLOTS: OnceLock<Mutex<HashMap<String, Slot>>> = OnceLock::new();
+
+fn last_slots() -> &'static Mutex<HashMap<String, Slot>> {
+    LAST_SLOTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Forget the remembered slot so the next tick re-applies the schedule (after it was edited).
+pub(crate) fn invalidate(cli_key: &str) {
+    last_slots().lock_or_recover().remove(cli_key);
+}
+
+pub(crate) fn spawn(app: crate::AppHandle, db: db::Db) {
+    tracing::info!(
+        interval_s = POLL_INTERVAL.as_secs(),
+        "sort mode scheduler started"
+    );
+
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(err) = tick(&app, &db).await {
+                tracing::warn!("sort mode scheduler tick failed: {}", err);
+            }
+        }
+    });
+}
+
+async fn tick(app: &crate::AppHandle, db: &db::Db) -> crate::shared::error::AppResult<()> {
+    let targets = blocking::run("sort_mode_scheduler_targets", {
+        let db = db.clone();
+        move || sort_mode_schedules::targets_at(&db, now_unix_seconds())
+    })
+    .await?;
+
+    let due: Vec<sort_mode_schedules::SortModeScheduleTarget> = {
+        let mut slots = last_slots().lock_or_recover();
+        slots.retain(|cli_key, _| targets.iter().any(|t| &t.cli_key == cli_key));
+        targets
+            .into_iter()
+            .filter(|t| {
+                slots.insert(t.cli_key.clone(), (t.rule_index, t.mode_id))
+                    != Some((t.rule_index, t.mode_id))
+            })
+            .collect()
+    };
+
+    for target in due {
+        let switched = blocking::run("sort_mode_scheduler_apply", {
+            let db = db.clone();
+            let cli_key = target.cli_key.clone();
+            move || -> crate::shared::error::AppResult<Option<Option<i64>>> {
+                let previous = sort_modes::list_active(&db)?
+                    .into_iter()
+                    .find(|row| row.cli_key == cli_key)
+                    .and_then(|row| row.mode_id);
+                if previous == target.mode_id {
+                    return Ok(None);
+                }
+                sort_modes::set_active(&db, &cli_key, target.mode_id)?;
+                Ok(Some(previous))
+            }
+        })
+        .await;
+
+        let previous_mode_id = match switched {
+            Ok(Some(previous)) => previous,
+            Ok(None) => continue,
+            Err(err) => {
+                // Retry on the next tick.
+                invalidate(&target.cli_key);
+                tracing::warn!(
+                    cli_key = %target.cli_key,
+                    "sort mode scheduler failed to switch active mode: {}",
+                    err
+                );
+                continue;
+            }
+        };
+
+        {
+            let state = app.state::<GatewayState>();
+            let manager = state.0.lock_or_recover();
+            manager.clear_cli_session_bindings(&target.cli_key);
+        }
+
+        tracing::info!(
+            cli_key = %target.cli_key,
+            previous_mode_id = ?previous_mode_id,
+            mode_id = ?target.mode_id,
+            rule_index = ?target.rule_index,
+            local_time = %target.local_time,
+            "sort mode scheduler switched active mode"
+        );
+
+        let payload = SortModeScheduleSwitchedEvent {
+            cli_key: target.cli_key,
+            previous_mode_id,
+            mode_id: target.mode_id,
+            rule_index: target.rule_index,
+            local_time: target.local_time,
+        };
+        if let Err(err) = app.emit(SCHEDULE_SWITCHED_EVENT, payload) {
+            tracing::debug!("emit sort mode schedule switch failed: {}", err);
+        }
+    }
+
+    Ok(())
+}
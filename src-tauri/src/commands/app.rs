@@ -12,7 +12,7 @@ fn sanitize_text(input: Option<String>, max_len: usize) -> Option<String> {
     Some(trimmed.chars().take(max_len).collect())
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
 pub(crate) struct AppAboutInfo {
     os: String,
     arch: String,
@@ -23,6 +23,7 @@ pub(crate) struct AppAboutInfo {
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) fn app_about_get() -> AppAboutInfo {
     let bundle_type = tauri::utils::platform::bundle_type();
     let run_mode = match bundle_type {
@@ -59,7 +60,8 @@ pub(crate) fn app_about_get() -> AppAboutInfo {
 }
 
 #[tauri::command]
-pub(crate) fn app_exit(app: tauri::AppHandle) -> Result<bool, String> {
+#[specta::specta]
+pub(crate) fn app_exit(app: crate::AppHandle) -> Result<bool, String> {
     std::thread::spawn(move || {
         std::thread::sleep(std::time::Duration::from_millis(200));
         app.exit(0);
@@ -68,7 +70,8 @@ pub(crate) fn app_exit(app: tauri::AppHandle) -> Result<bool, String> {
 }
 
 #[tauri::command]
-pub(crate) fn app_restart(app: tauri::AppHandle) -> Result<bool, String> {
+#[specta::specta]
+pub(crate) fn app_restart(app: crate::AppHandle) -> Result<bool, String> {
     std::thread::spawn(move || {
         std::thread::sleep(std::time::Duration::from_millis(200));
         tauri::async_runtime::block_on(crate::app::cleanup::cleanup_before_exit(&app));
@@ -78,13 +81,24 @@ pub(crate) fn app_restart(app: tauri::AppHandle) -> Result<bool, String> {
 }
 
 #[tauri::command]
-pub(crate) fn app_heartbeat_pong(app: tauri::AppHandle) -> Result<bool, String> {
+#[specta::specta]
+pub(crate) fn app_heartbeat_pong(app: crate::AppHandle) -> Result<bool, String> {
     let watchdog = app.state::<crate::app::heartbeat_watchdog::HeartbeatWatchdogState>();
     watchdog.record_pong();
     Ok(true)
 }
 
+/// Startup self-test report; `None` until the startup sequence has finished.
 #[tauri::command]
+#[specta::specta]
+pub(crate) fn app_selftest_report(
+    app: crate::AppHandle,
+) -> Option<crate::app::selftest::SelfTestReport> {
+    app.state::<crate::app::selftest::SelfTestState>().report()
+}
+
+#[tauri::command]
+#[specta::specta]
 pub(crate) fn app_frontend_error_report(
     source: String,
     message: String,
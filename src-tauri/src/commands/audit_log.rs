@@ -0,0 +1,36 @@
+//! Usage: Config change audit log related Tauri commands.
+
+use crate::app_state::{ensure_db_ready, DbInitState};
+use crate::commands::limit::normalize_limit;
+use crate::{audit_log, blocking};
+
+const AUDIT_LOG_DEFAULT_LIMIT: u32 = 200;
+const AUDIT_LOG_MAX_LIMIT: u32 = 2000;
+
+/// Newest first; every filter is optional and `until_unix` is exclusive.
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn audit_log_list(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    command: Option<String>,
+    origin: Option<audit_log::AuditOrigin>,
+    target: Option<String>,
+    since_unix: Option<i64>,
+    until_unix: Option<i64>,
+    limit: Option<u32>,
+) -> Result<Vec<audit_log::AuditLogEntry>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let filter = audit_log::AuditLogFilter {
+        command: command.filter(|c| !c.trim().is_empty()),
+        origin,
+        target: target.filter(|t| !t.trim().is_empty()),
+        since_unix,
+        until_unix,
+        limit: normalize_limit(limit, AUDIT_LOG_DEFAULT_LIMIT, 1, AUDIT_LOG_MAX_LIMIT),
+    };
+    blocking::run("audit_log_list", move || audit_log::list(&db, &filter))
+        .await
+        .map_err(Into::into)
+}
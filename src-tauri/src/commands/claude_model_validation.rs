@@ -1,11 +1,15 @@
 //! Usage: Claude provider model validation related Tauri commands.
 
 use crate::app_state::{ensure_db_ready, DbInitState};
-use crate::{blocking, claude_model_validation, claude_model_validation_history};
+use crate::{
+    blocking, claude_model_validation, claude_model_validation_history,
+    claude_model_validation_schedules,
+};
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn claude_provider_validate_model(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     provider_id: i64,
     base_url: String,
@@ -18,11 +22,13 @@ pub(crate) async fn claude_provider_validate_model(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn claude_provider_get_api_key_plaintext(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     provider_id: i64,
 ) -> Result<String, String> {
+    crate::observer_mode::ensure_secrets_allowed("claude_provider_get_api_key_plaintext")?;
     let db = ensure_db_ready(app, db_state.inner()).await?;
     claude_model_validation::get_provider_api_key_plaintext(db, provider_id)
         .await
@@ -30,8 +36,9 @@ pub(crate) async fn claude_provider_get_api_key_plaintext(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn claude_validation_history_list(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     provider_id: i64,
     limit: Option<u32>,
@@ -46,8 +53,9 @@ pub(crate) async fn claude_validation_history_list(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn claude_validation_history_clear_provider(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     provider_id: i64,
 ) -> Result<bool, String> {
@@ -58,3 +66,57 @@ pub(crate) async fn claude_validation_history_clear_provider(
     .await
     .map_err(Into::into)
 }
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn claude_validation_schedules_list(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    provider_id: Option<i64>,
+) -> Result<Vec<claude_model_validation_schedules::ClaudeModelValidationSchedule>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("claude_validation_schedules_list", move || {
+        claude_model_validation_schedules::list(&db, provider_id)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn claude_validation_schedule_upsert(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    provider_id: i64,
+    model: String,
+    interval_minutes: u32,
+    enabled: bool,
+) -> Result<claude_model_validation_schedules::ClaudeModelValidationSchedule, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("claude_validation_schedule_upsert", move || {
+        claude_model_validation_schedules::upsert(
+            &db,
+            provider_id,
+            &model,
+            interval_minutes,
+            enabled,
+        )
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn claude_validation_schedule_delete(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    schedule_id: i64,
+) -> Result<bool, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("claude_validation_schedule_delete", move || {
+        claude_model_validation_schedules::delete(&db, schedule_id)
+    })
+    .await
+    .map_err(Into::into)
+}
@@ -3,8 +3,9 @@
 use crate::{blocking, claude_settings, cli_manager, codex_config};
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cli_manager_claude_info_get(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
 ) -> Result<cli_manager::ClaudeCliInfo, String> {
     blocking::run("cli_manager_claude_info_get", move || {
         cli_manager::claude_info_get(&app)
@@ -14,8 +15,9 @@ pub(crate) async fn cli_manager_claude_info_get(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cli_manager_codex_info_get(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
 ) -> Result<cli_manager::SimpleCliInfo, String> {
     blocking::run("cli_manager_codex_info_get", move || {
         cli_manager::codex_info_get(&app)
@@ -25,8 +27,9 @@ pub(crate) async fn cli_manager_codex_info_get(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cli_manager_codex_config_get(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
 ) -> Result<codex_config::CodexConfigState, String> {
     blocking::run("cli_manager_codex_config_get", move || {
         codex_config::codex_config_get(&app)
@@ -36,8 +39,9 @@ pub(crate) async fn cli_manager_codex_config_get(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cli_manager_codex_config_set(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     patch: codex_config::CodexConfigPatch,
 ) -> Result<codex_config::CodexConfigState, String> {
     blocking::run("cli_manager_codex_config_set", move || {
@@ -48,8 +52,9 @@ pub(crate) async fn cli_manager_codex_config_set(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cli_manager_codex_config_toml_get(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
 ) -> Result<codex_config::CodexConfigTomlState, String> {
     blocking::run("cli_manager_codex_config_toml_get", move || {
         codex_config::codex_config_toml_get_raw(&app)
@@ -59,6 +64,7 @@ pub(crate) async fn cli_manager_codex_config_toml_get(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cli_manager_codex_config_toml_validate(
     toml: String,
 ) -> Result<codex_config::CodexConfigTomlValidationResult, String> {
@@ -70,8 +76,9 @@ pub(crate) async fn cli_manager_codex_config_toml_validate(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cli_manager_codex_config_toml_set(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     toml: String,
 ) -> Result<codex_config::CodexConfigState, String> {
     blocking::run("cli_manager_codex_config_toml_set", move || {
@@ -82,8 +89,9 @@ pub(crate) async fn cli_manager_codex_config_toml_set(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cli_manager_gemini_info_get(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
 ) -> Result<cli_manager::SimpleCliInfo, String> {
     blocking::run("cli_manager_gemini_info_get", move || {
         cli_manager::gemini_info_get(&app)
@@ -93,8 +101,9 @@ pub(crate) async fn cli_manager_gemini_info_get(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cli_manager_claude_env_set(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     mcp_timeout_ms: Option<u64>,
     disable_error_reporting: bool,
 ) -> Result<cli_manager::ClaudeEnvState, String> {
@@ -106,8 +115,9 @@ pub(crate) async fn cli_manager_claude_env_set(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cli_manager_claude_settings_get(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
 ) -> Result<claude_settings::ClaudeSettingsState, String> {
     blocking::run("cli_manager_claude_settings_get", move || {
         claude_settings::claude_settings_get(&app)
@@ -117,8 +127,9 @@ pub(crate) async fn cli_manager_claude_settings_get(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cli_manager_claude_settings_set(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     patch: claude_settings::ClaudeSettingsPatch,
 ) -> Result<claude_settings::ClaudeSettingsState, String> {
     blocking::run("cli_manager_claude_settings_set", move || {
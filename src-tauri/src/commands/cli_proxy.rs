@@ -2,13 +2,14 @@
 
 use crate::app_state::{ensure_db_ready, DbInitState, GatewayState};
 use crate::shared::mutex_ext::MutexExt;
-use crate::{blocking, cli_proxy, mcp, settings};
+use crate::{audit_log, blocking, cli_config_drift, cli_proxy, mcp, settings};
 use tauri::Emitter;
 use tauri::Manager;
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cli_proxy_status_all(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
 ) -> Result<Vec<cli_proxy::CliProxyStatus>, String> {
     blocking::run("cli_proxy_status_all", move || cli_proxy::status_all(&app))
         .await
@@ -16,13 +17,15 @@ pub(crate) async fn cli_proxy_status_all(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cli_proxy_set_enabled(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     cli_key: String,
     enabled: bool,
 ) -> Result<cli_proxy::CliProxyResult, String> {
     tracing::info!(cli_key = %cli_key, enabled = enabled, "cli proxy enabled state changing");
+    let origin = audit_log::current_origin();
 
     let base_origin = if enabled {
         let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
@@ -75,6 +78,14 @@ pub(crate) async fn cli_proxy_set_enabled(
     // Without this re-sync, MCP entries get lost during the backup/restore cycle.
     if let Ok(ref r) = result {
         if r.ok {
+            audit_log::record_with_app(
+                &app,
+                origin,
+                "cli_proxy_set_enabled",
+                Some(format!("cli:{cli_key}")),
+                format!("enabled: {enabled}"),
+            )
+            .await;
             match ensure_db_ready(app.clone(), db_state.inner()).await {
                 Ok(db) => {
                     let sync_app = app.clone();
@@ -110,12 +121,16 @@ pub(crate) async fn cli_proxy_set_enabled(
         _ => {}
     }
 
+    // Keep the tray checkmarks in sync whichever surface toggled the proxy.
+    crate::resident::refresh_tray_menu(&app);
+
     result
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cli_proxy_sync_enabled(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     base_origin: String,
 ) -> Result<Vec<cli_proxy::CliProxyResult>, String> {
     blocking::run("cli_proxy_sync_enabled", move || {
@@ -124,3 +139,18 @@ pub(crate) async fn cli_proxy_sync_enabled(
     .await
     .map_err(Into::into)
 }
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn cli_config_drift_report(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: Option<String>,
+) -> Result<cli_config_drift::CliConfigDriftReport, String> {
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    blocking::run("cli_config_drift_report", move || {
+        cli_config_drift::report(&app, &db, cli_key.as_deref())
+    })
+    .await
+    .map_err(Into::into)
+}
@@ -4,8 +4,9 @@ use crate::shared::error::AppError;
 use crate::{blocking, cli_sessions};
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cli_sessions_projects_list(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     source: String,
     wsl_distro: Option<String>,
 ) -> Result<Vec<cli_sessions::CliSessionsProjectSummary>, String> {
@@ -18,8 +19,9 @@ pub(crate) async fn cli_sessions_projects_list(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cli_sessions_sessions_list(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     source: String,
     project_id: String,
     wsl_distro: Option<String>,
@@ -38,8 +40,9 @@ pub(crate) async fn cli_sessions_sessions_list(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cli_sessions_messages_get(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     source: String,
     file_path: String,
     page: u32,
@@ -73,8 +76,9 @@ pub(crate) async fn cli_sessions_messages_get(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cli_sessions_session_delete(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     source: String,
     file_paths: Vec<String>,
     wsl_distro: Option<String>,
@@ -100,3 +104,38 @@ pub(crate) async fn cli_sessions_session_delete(
     .await
     .map_err(Into::into)
 }
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn cli_sessions_export(
+    app: crate::AppHandle,
+    source: String,
+    file_path: String,
+    format: String,
+    output_path: String,
+    wsl_distro: Option<String>,
+) -> Result<cli_sessions::CliSessionsExportResult, String> {
+    let source = source.parse::<cli_sessions::CliSessionsSource>()?;
+    let format = format.parse::<cli_sessions::CliSessionsExportFormat>()?;
+    let file_path = file_path.trim().to_string();
+    if file_path.is_empty() {
+        return Err(AppError::new("SEC_INVALID_INPUT", "filePath is required").into());
+    }
+    let output_path = output_path.trim().to_string();
+    if output_path.is_empty() {
+        return Err(AppError::new("SEC_INVALID_INPUT", "outputPath is required").into());
+    }
+
+    blocking::run("cli_sessions_export", move || {
+        cli_sessions::session_export(
+            &app,
+            source,
+            &file_path,
+            format,
+            &output_path,
+            wsl_distro.as_deref(),
+        )
+    })
+    .await
+    .map_err(Into::into)
+}
@@ -0,0 +1,67 @@
+//! Usage: Managed Codex config profile related Tauri commands.
+
+use crate::app_state::{ensure_db_ready, DbInitState};
+use crate::{blocking, codex_profiles};
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn codex_profiles_list(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    workspace_id: Option<i64>,
+) -> Result<codex_profiles::CodexProfilesListResult, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("codex_profiles_list", move || {
+        codex_profiles::list(&db, workspace_id)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn codex_profile_upsert(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    profile_id: Option<i64>,
+    name: String,
+    toml: String,
+) -> Result<codex_profiles::CodexProfile, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("codex_profile_upsert", move || {
+        codex_profiles::upsert(&db, profile_id, &name, &toml)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn codex_profile_delete(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    profile_id: i64,
+) -> Result<bool, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("codex_profile_delete", move || {
+        codex_profiles::delete(&db, profile_id)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn codex_profile_apply(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    workspace_id: i64,
+    profile_id: i64,
+) -> Result<codex_profiles::CodexProfileApplyResult, String> {
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    blocking::run("codex_profile_apply", move || {
+        codex_profiles::apply(&app, &db, workspace_id, profile_id)
+    })
+    .await
+    .map_err(Into::into)
+}
@@ -1,11 +1,12 @@
 //! Usage: Cost analytics related Tauri commands.
 
 use crate::app_state::{ensure_db_ready, DbInitState};
-use crate::{blocking, cost_stats};
+use crate::{blocking, cost_anomaly, cost_stats};
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cost_summary_v1(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     params: cost_stats::CostQueryParams,
 ) -> Result<cost_stats::CostSummaryV1, String> {
@@ -18,8 +19,9 @@ pub(crate) async fn cost_summary_v1(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cost_trend_v1(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     params: cost_stats::CostQueryParams,
 ) -> Result<Vec<cost_stats::CostTrendRowV1>, String> {
@@ -30,8 +32,9 @@ pub(crate) async fn cost_trend_v1(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cost_breakdown_provider_v1(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     params: cost_stats::CostQueryParams,
     limit: Option<u32>,
@@ -46,8 +49,9 @@ pub(crate) async fn cost_breakdown_provider_v1(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cost_breakdown_model_v1(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     params: cost_stats::CostQueryParams,
     limit: Option<u32>,
@@ -62,8 +66,9 @@ pub(crate) async fn cost_breakdown_model_v1(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cost_scatter_cli_provider_model_v1(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     params: cost_stats::CostQueryParams,
     limit: Option<u32>,
@@ -78,8 +83,9 @@ pub(crate) async fn cost_scatter_cli_provider_model_v1(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cost_top_requests_v1(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     params: cost_stats::CostQueryParams,
     limit: Option<u32>,
@@ -94,8 +100,9 @@ pub(crate) async fn cost_top_requests_v1(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn cost_backfill_missing_v1(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     params: cost_stats::CostQueryParams,
     max_rows: Option<u32>,
@@ -108,3 +115,21 @@ pub(crate) async fn cost_backfill_missing_v1(
     .await
     .map_err(Into::into)
 }
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn cost_anomalies_list() -> Result<Vec<cost_anomaly::CostAnomaly>, String> {
+    Ok(crate::app::cost_anomaly_monitor::list())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn cost_anomaly_acknowledge(session_id: String) -> Result<bool, String> {
+    let session_id = session_id.trim();
+    if session_id.is_empty() {
+        return Err("SEC_INVALID_INPUT: session_id is required".to_string());
+    }
+    let cleared = crate::app::cost_anomaly_monitor::acknowledge(session_id);
+    tracing::info!(session_id = %session_id, cleared, "cost anomaly acknowledged");
+    Ok(cleared)
+}
@@ -1,10 +1,14 @@
 //! Usage: Data reset / disk usage related Tauri commands.
 
 use crate::app_state::{ensure_db_ready, DbInitState, GatewayState};
+use crate::shared::mutex_ext::MutexExt;
+use crate::shared::time::now_unix_seconds;
 use crate::{app_paths, blocking, data_management};
+use tauri::Manager;
 
 #[tauri::command]
-pub(crate) async fn app_data_dir_get(app: tauri::AppHandle) -> Result<String, String> {
+#[specta::specta]
+pub(crate) async fn app_data_dir_get(app: crate::AppHandle) -> Result<String, String> {
     blocking::run(
         "app_data_dir_get",
         move || -> crate::shared::error::AppResult<String> {
@@ -17,8 +21,9 @@ pub(crate) async fn app_data_dir_get(app: tauri::AppHandle) -> Result<String, St
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn db_disk_usage_get(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
 ) -> Result<data_management::DbDiskUsage, String> {
     blocking::run("db_disk_usage_get", move || {
         data_management::db_disk_usage_get(&app)
@@ -28,8 +33,167 @@ pub(crate) async fn db_disk_usage_get(
 }
 
 #[tauri::command]
+#[specta::specta]
+pub(crate) async fn db_health_get(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+) -> Result<data_management::DbHealth, String> {
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    blocking::run("db_health_get", move || {
+        data_management::db_health_get(&app, &db)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+/// Runs a single SELECT on a read-only connection; refused in observer mode because the
+/// database holds provider keys.
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn db_query_readonly(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    sql: String,
+    max_rows: Option<u32>,
+) -> Result<data_management::DbQueryResult, String> {
+    crate::observer_mode::ensure_secrets_allowed("db_query_readonly")?;
+    ensure_db_ready(app.clone(), db_state.inner()).await?;
+    blocking::run("db_query_readonly", move || {
+        data_management::db_query_readonly(&app, &sql, max_rows)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn db_checkpoint_now(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+) -> Result<data_management::DbCheckpointResult, String> {
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    blocking::run("db_checkpoint_now", move || {
+        data_management::db_checkpoint_now(&app, &db)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn usage_history_import(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    source: String,
+    path: String,
+    cli_key: Option<String>,
+) -> Result<data_management::UsageImportResult, String> {
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    blocking::run("usage_history_import", move || {
+        data_management::import_usage_history(&app, &db, &source, &path, cli_key.as_deref())
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn app_config_export(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    path: String,
+    password: String,
+    include_secrets: bool,
+) -> Result<data_management::ConfigExportResult, String> {
+    if include_secrets {
+        crate::observer_mode::ensure_secrets_allowed("app_config_export with secrets")?;
+    }
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    blocking::run("app_config_export", move || {
+        data_management::config_export(&app, &db, &path, &password, include_secrets)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn app_config_import(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    path: String,
+    password: String,
+) -> Result<data_management::ConfigImportResult, String> {
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    let result = blocking::run("app_config_import", move || {
+        let result = data_management::config_import(&app, &db, &path, &password)?;
+        if let Ok(settings) = crate::settings::read(&app) {
+            crate::observer_mode::configure(settings.observer_mode);
+            crate::request_logs::configure_sampling(
+                settings.request_log_sample_percent,
+                settings.request_log_slow_threshold_ms,
+            );
+            crate::notification_sinks::configure(&settings.notification_sinks);
+        }
+        Ok(result)
+    })
+    .await?;
+    for cli_key in crate::shared::cli_key::SUPPORTED_CLI_KEYS {
+        crate::app::sort_mode_scheduler::invalidate(cli_key);
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn config_apply_file(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    gateway_state: tauri::State<'_, GatewayState>,
+    path: String,
+    dry_run: bool,
+) -> Result<data_management::ConfigApplyReport, String> {
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    let report = blocking::run("config_apply_file", move || {
+        let report = data_management::config_apply_file(&app, &db, &path, dry_run)?;
+        let settings_changed = report.changes.iter().any(|c| c.section == "settings");
+        if report.applied && settings_changed {
+            if let Ok(settings) = crate::settings::read(&app) {
+                crate::observer_mode::configure(settings.observer_mode);
+                crate::request_logs::configure_sampling(
+                    settings.request_log_sample_percent,
+                    settings.request_log_slow_threshold_ms,
+                );
+                crate::notification_sinks::configure(&settings.notification_sinks);
+            }
+        }
+        Ok::<_, crate::shared::error::AppError>(report)
+    })
+    .await?;
+
+    if report.applied {
+        for cli_key in crate::shared::cli_key::SUPPORTED_CLI_KEYS {
+            crate::app::sort_mode_scheduler::invalidate(cli_key);
+            let providers_changed = report
+                .changes
+                .iter()
+                .any(|c| c.section == "providers" && c.cli_key.as_deref() == Some(cli_key));
+            if providers_changed {
+                // Sessions may be pinned to a provider whose endpoint or key just changed.
+                gateway_state
+                    .0
+                    .lock_or_recover()
+                    .clear_cli_session_bindings(cli_key);
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[tauri::command]
+#[specta::specta]
 pub(crate) async fn request_logs_clear_all(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
 ) -> Result<data_management::ClearRequestLogsResult, String> {
     let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
@@ -41,8 +205,9 @@ pub(crate) async fn request_logs_clear_all(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn app_data_reset(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     state: tauri::State<'_, GatewayState>,
 ) -> Result<bool, String> {
     // Best-effort: stop gateway first to avoid concurrent writes locking sqlite files.
@@ -53,3 +218,52 @@ pub(crate) async fn app_data_reset(
     .await
     .map_err(Into::into)
 }
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn app_data_reset_scope(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    scope: data_management::DataResetScope,
+    dry_run: bool,
+    confirm_token: Option<String>,
+) -> Result<data_management::DataResetReport, String> {
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    blocking::run(
+        "app_data_reset_scope",
+        move || -> crate::shared::error::AppResult<data_management::DataResetReport> {
+            let mut report = if dry_run {
+                data_management::data_reset_plan(&app, &db, scope)?
+            } else {
+                data_management::data_reset_apply(&app, &db, scope, confirm_token.as_deref())?
+            };
+
+            if scope == data_management::DataResetScope::GatewayState {
+                // Circuits and session bindings also live in the running gateway.
+                let state = app.state::<GatewayState>();
+                let manager = state.0.lock_or_recover();
+                let sessions = if dry_run {
+                    manager
+                        .active_sessions(now_unix_seconds(), usize::MAX)
+                        .len()
+                } else {
+                    let mut cleared = 0;
+                    for cli_key in crate::shared::cli_key::SUPPORTED_CLI_KEYS {
+                        manager.circuit_reset_cli(&db, cli_key)?;
+                        cleared += manager.clear_cli_session_bindings(cli_key);
+                    }
+                    cleared
+                };
+                report.items.push(data_management::DataResetItem {
+                    kind: "session_bindings".to_string(),
+                    target: "gateway".to_string(),
+                    count: sessions as u64,
+                    error: None,
+                });
+            }
+            Ok(report)
+        },
+    )
+    .await
+    .map_err(Into::into)
+}
@@ -3,8 +3,9 @@
 use crate::{blocking, env_conflicts};
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn env_conflicts_check(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     cli_key: String,
 ) -> Result<Vec<env_conflicts::EnvConflict>, String> {
     blocking::run("env_conflicts_check", move || {
@@ -13,3 +14,31 @@ pub(crate) async fn env_conflicts_check(
     .await
     .map_err(Into::into)
 }
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn env_conflicts_fix(
+    app: crate::AppHandle,
+    targets: Vec<env_conflicts::EnvConflictFixTarget>,
+    action: env_conflicts::EnvConflictFixAction,
+    value: Option<String>,
+) -> Result<env_conflicts::EnvConflictFixResult, String> {
+    blocking::run("env_conflicts_fix", move || {
+        env_conflicts::fix_env_conflicts(&app, targets, action, value)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn env_conflicts_fix_undo(
+    app: crate::AppHandle,
+    backup_id: String,
+) -> Result<u32, String> {
+    blocking::run("env_conflicts_fix_undo", move || {
+        env_conflicts::undo_env_conflicts_fix(&app, &backup_id)
+    })
+    .await
+    .map_err(Into::into)
+}
@@ -2,6 +2,7 @@
 
 use crate::app_state::{ensure_db_ready, DbInitState, GatewayState};
 use crate::commands::limit::normalize_limit;
+use crate::shared::i18n;
 use crate::shared::mutex_ext::MutexExt;
 use crate::{blocking, cli_proxy, gateway, providers, request_logs, settings, wsl};
 use tauri::Emitter;
@@ -19,7 +20,7 @@ fn gateway_sessions_limit(limit: Option<u32>) -> usize {
     )
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
 pub(crate) struct GatewayActiveSessionSummary {
     cli_key: String,
     session_id: String,
@@ -35,13 +36,45 @@ pub(crate) struct GatewayActiveSessionSummary {
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) fn gateway_status(state: tauri::State<'_, GatewayState>) -> gateway::GatewayStatus {
     let manager = state.0.lock_or_recover();
     manager.status()
 }
 
 #[tauri::command]
-pub(crate) fn gateway_check_port_available(app: tauri::AppHandle, port: u16) -> bool {
+#[specta::specta]
+pub(crate) fn gateway_provider_quality_v1(
+    state: tauri::State<'_, GatewayState>,
+) -> Vec<gateway::GatewayProviderQuality> {
+    let manager = state.0.lock_or_recover();
+    manager.provider_quality()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) fn gateway_live_stats(
+    state: tauri::State<'_, GatewayState>,
+) -> Vec<gateway::GatewayProviderLiveStats> {
+    let manager = state.0.lock_or_recover();
+    manager.live_stats()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) fn gateway_oauth_pacing_v1() -> Vec<gateway::GatewayOAuthPacingState> {
+    gateway::oauth_pacing::snapshot(crate::shared::time::now_unix_seconds() * 1000)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) fn error_catalog_list() -> Vec<gateway::ErrorCatalogEntry> {
+    gateway::error_catalog_entries()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) fn gateway_check_port_available(app: crate::AppHandle, port: u16) -> bool {
     if port < 1024 {
         return false;
     }
@@ -64,8 +97,9 @@ pub(crate) fn gateway_check_port_available(app: tauri::AppHandle, port: u16) ->
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn gateway_sessions_list(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     state: tauri::State<'_, GatewayState>,
     limit: Option<u32>,
@@ -117,6 +151,11 @@ pub(crate) async fn gateway_sessions_list(
                 .unwrap_or_else(|| "Unknown".to_string());
 
             let stats = session_stats.get(&(cli_key.clone(), session_id.clone()));
+            let session_id = if crate::observer_mode::is_enabled() {
+                crate::observer_mode::mask_session_id(&session_id)
+            } else {
+                session_id
+            };
 
             GatewayActiveSessionSummary {
                 cli_key,
@@ -138,9 +177,131 @@ pub(crate) async fn gateway_sessions_list(
         .collect())
 }
 
+#[derive(Debug, Clone, serde::Serialize, specta::Type)]
+pub(crate) struct SessionBindingSummary {
+    cli_key: String,
+    session_id: String,
+    session_suffix: String,
+    provider_id: i64,
+    provider_name: Option<String>,
+    sort_mode_id: Option<i64>,
+    provider_tag: Option<String>,
+    pinned: bool,
+    expires_at: i64,
+}
+
+fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Raw routing state of live session bindings (pinned first); no request log aggregation.
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn session_bindings_list(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    state: tauri::State<'_, GatewayState>,
+    limit: Option<u32>,
+) -> Result<Vec<SessionBindingSummary>, String> {
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+
+    let limit = gateway_sessions_limit(limit);
+    let sessions = {
+        let manager = state.0.lock_or_recover();
+        manager.active_sessions(unix_now(), limit)
+    };
+    if sessions.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let provider_ids: Vec<i64> = sessions
+        .iter()
+        .map(|s| s.provider_id)
+        .filter(|id| *id > 0)
+        .collect();
+    let provider_names = blocking::run("providers_names_by_id", move || {
+        providers::names_by_id(&db, &provider_ids)
+    })
+    .await?;
+
+    let mask = crate::observer_mode::is_enabled();
+    Ok(sessions
+        .into_iter()
+        .map(|s| SessionBindingSummary {
+            provider_name: provider_names.get(&s.provider_id).cloned(),
+            session_id: if mask {
+                crate::observer_mode::mask_session_id(&s.session_id)
+            } else {
+                s.session_id
+            },
+            cli_key: s.cli_key,
+            session_suffix: s.session_suffix,
+            provider_id: s.provider_id,
+            sort_mode_id: s.sort_mode_id,
+            provider_tag: s.provider_tag,
+            pinned: s.pinned,
+            expires_at: s.expires_at,
+        })
+        .collect())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn session_binding_pin(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    state: tauri::State<'_, GatewayState>,
+    cli_key: String,
+    session_id: String,
+    provider_id: i64,
+) -> Result<bool, String> {
+    crate::shared::cli_key::validate_cli_key(&cli_key)?;
+    let session_id = session_id.trim().to_string();
+    if session_id.is_empty() {
+        return Err("SEC_INVALID_INPUT: session_id is required".to_string());
+    }
+
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    let provider_cli_key = blocking::run("providers_cli_key_by_id", move || {
+        providers::cli_key_by_id(&db, provider_id)
+    })
+    .await?;
+    if provider_cli_key.as_deref() != Some(cli_key.as_str()) {
+        return Err(format!(
+            "SEC_INVALID_INPUT: provider_id={provider_id} does not belong to cli_key={cli_key}"
+        ));
+    }
+
+    let pinned = {
+        let manager = state.0.lock_or_recover();
+        manager.pin_session_provider(&cli_key, &session_id, provider_id, unix_now())
+    };
+    let Some(pinned) = pinned else {
+        return Err("GATEWAY_ERROR: gateway is not running".to_string());
+    };
+    tracing::info!(cli_key = %cli_key, provider_id, "session pinned to provider");
+    Ok(pinned)
+}
+
 #[tauri::command]
+#[specta::specta]
+pub(crate) async fn session_binding_unpin(
+    state: tauri::State<'_, GatewayState>,
+    cli_key: String,
+    session_id: String,
+) -> Result<bool, String> {
+    crate::shared::cli_key::validate_cli_key(&cli_key)?;
+    let manager = state.0.lock_or_recover();
+    Ok(manager.unpin_session_provider(&cli_key, session_id.trim(), unix_now()))
+}
+
+#[tauri::command]
+#[specta::specta]
 pub(crate) async fn gateway_circuit_status(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     cli_key: String,
 ) -> Result<Vec<gateway::GatewayProviderCircuitStatus>, String> {
@@ -155,8 +316,9 @@ pub(crate) async fn gateway_circuit_status(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn gateway_circuit_reset_provider(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     provider_id: i64,
 ) -> Result<bool, String> {
@@ -175,8 +337,9 @@ pub(crate) async fn gateway_circuit_reset_provider(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn gateway_circuit_reset_cli(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     cli_key: String,
 ) -> Result<usize, String> {
@@ -191,8 +354,9 @@ pub(crate) async fn gateway_circuit_reset_cli(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn gateway_start(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     preferred_port: Option<u16>,
 ) -> Result<gateway::GatewayStatus, String> {
@@ -221,6 +385,133 @@ pub(crate) async fn gateway_start(
     Ok(status)
 }
 
+const GATEWAY_PORT_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct GatewayPortChangeProgress {
+    /// "bound" | "configs_synced" | "draining" | "done"
+    stage: &'static str,
+    from_port: u16,
+    to_port: u16,
+    message: String,
+}
+
+fn emit_port_change(app: &crate::AppHandle, stage: &'static str, from_port: u16, to_port: u16) {
+    let key = match stage {
+        "bound" => "gateway.port_change.bound",
+        "configs_synced" => "gateway.port_change.configs_synced",
+        "draining" => "gateway.port_change.draining",
+        _ => "gateway.port_change.done",
+    };
+    let message = i18n::format(key, &[("from_port", &from_port), ("to_port", &to_port)]);
+    let _ = app.emit(
+        "gateway:port_change",
+        GatewayPortChangeProgress {
+            stage,
+            from_port,
+            to_port,
+            message,
+        },
+    );
+}
+
+/// Moves a running gateway to `port` without a stop/start gap: the new listener is bound first,
+/// clients are re-pointed, and only then is the old listener drained (in-flight requests finish).
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn gateway_change_port(
+    app: crate::AppHandle,
+    port: u16,
+) -> Result<gateway::GatewayStatus, String> {
+    gateway::validate_port(port)?;
+
+    let (from_port, status, previous) = blocking::run("gateway_change_port", {
+        let app = app.clone();
+        move || -> crate::shared::error::AppResult<_> {
+            let state = app.state::<GatewayState>();
+            let mut manager = state.0.lock_or_recover();
+            let from_port = manager.status().port.unwrap_or_default();
+            let (status, previous) = manager.change_port(port)?;
+            Ok((from_port, status, previous))
+        }
+    })
+    .await?;
+    let Some(previous) = previous else {
+        return Ok(status);
+    };
+
+    let persisted = blocking::run("gateway_change_port_persist", {
+        let app = app.clone();
+        move || -> crate::shared::error::AppResult<()> {
+            let mut current = settings::read(&app)?;
+            if current.preferred_port != port {
+                current.preferred_port = port;
+                settings::write(&app, &current)?;
+            }
+            Ok(())
+        }
+    })
+    .await;
+    // Without the saved port the next start would come back on the old one: keep serving there.
+    if let Err(err) = persisted {
+        tracing::warn!(
+            port,
+            "gateway port change: preferred_port not saved, rolling back: {}",
+            err
+        );
+        let restored = {
+            let state = app.state::<GatewayState>();
+            let mut manager = state.0.lock_or_recover();
+            manager.restore_port(from_port, previous)
+        };
+        if let Ok((_, (new_shutdown, _new_task))) = restored {
+            let _ = new_shutdown.send(());
+        }
+        return Err(err.to_string());
+    }
+    let (old_shutdown, mut old_task) = previous;
+    emit_port_change(&app, "bound", from_port, port);
+    let _ = app.emit("gateway:status", status.clone());
+
+    if let Some(base_origin) = status.base_url.clone() {
+        let app_for_sync = app.clone();
+        let synced = blocking::run("cli_proxy_sync_enabled_after_port_change", move || {
+            cli_proxy::sync_enabled(&app_for_sync, &base_origin)
+        })
+        .await;
+        if let Err(err) = synced {
+            tracing::warn!(port, "gateway port change: cli proxy sync failed: {}", err);
+        }
+    }
+    #[cfg(windows)]
+    {
+        if let Err(err) = super::wsl::wsl_auto_sync_core(&app).await {
+            tracing::warn!(port, "gateway port change: WSL sync failed: {}", err);
+        }
+    }
+    if let Err(err) = super::ssh_clients::ssh_auto_sync_core(&app).await {
+        tracing::warn!(port, "gateway port change: SSH sync failed: {}", err);
+    }
+    emit_port_change(&app, "configs_synced", from_port, port);
+
+    emit_port_change(&app, "draining", from_port, port);
+    let _ = old_shutdown.send(());
+    if tokio::time::timeout(GATEWAY_PORT_DRAIN_TIMEOUT, &mut old_task)
+        .await
+        .is_err()
+    {
+        tracing::warn!(
+            from_port,
+            "gateway port change: drain timed out, aborting old listener"
+        );
+        old_task.abort();
+    }
+    emit_port_change(&app, "done", from_port, port);
+
+    tracing::info!(from_port, to_port = port, "gateway port changed");
+    Ok(status)
+}
+
 #[cfg(test)]
 mod tests {
     use super::gateway_sessions_limit;
@@ -235,8 +526,9 @@ mod tests {
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn gateway_stop(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     state: tauri::State<'_, GatewayState>,
 ) -> Result<gateway::GatewayStatus, String> {
     crate::app::cleanup::stop_gateway_best_effort(&app).await;
@@ -0,0 +1,114 @@
+//! Usage: Claude Code hook management related Tauri commands.
+
+use crate::app_state::{ensure_db_ready, DbInitState};
+use crate::{blocking, hooks};
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn hooks_list(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    workspace_id: i64,
+) -> Result<Vec<hooks::HookSummary>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("hooks_list", move || {
+        hooks::list_for_workspace(&db, workspace_id)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) fn hook_templates_list() -> Vec<hooks::HookTemplate> {
+    hooks::templates()
+}
+
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn hook_upsert(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    hook_id: Option<i64>,
+    workspace_id: i64,
+    name: String,
+    event: String,
+    matcher: String,
+    command: String,
+    timeout_secs: Option<u32>,
+) -> Result<hooks::HookSummary, String> {
+    #[cfg(windows)]
+    let app_for_wsl = app.clone();
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    let result = blocking::run("hook_upsert", move || {
+        hooks::upsert(
+            &app,
+            &db,
+            hook_id,
+            workspace_id,
+            &name,
+            &event,
+            &matcher,
+            &command,
+            timeout_secs,
+        )
+    })
+    .await
+    .map_err(Into::into);
+    #[cfg(windows)]
+    if result.is_ok() {
+        super::wsl::wsl_sync_trigger::trigger(app_for_wsl);
+    }
+    result
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn hook_set_enabled(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    workspace_id: i64,
+    hook_id: i64,
+    enabled: bool,
+) -> Result<hooks::HookSummary, String> {
+    #[cfg(windows)]
+    let app_for_wsl = app.clone();
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    let result = blocking::run("hook_set_enabled", move || {
+        hooks::set_enabled(&app, &db, workspace_id, hook_id, enabled)
+    })
+    .await
+    .map_err(Into::into);
+    #[cfg(windows)]
+    if result.is_ok() {
+        super::wsl::wsl_sync_trigger::trigger(app_for_wsl);
+    }
+    result
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn hook_delete(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    hook_id: i64,
+) -> Result<bool, String> {
+    #[cfg(windows)]
+    let app_for_wsl = app.clone();
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    let result = blocking::run(
+        "hook_delete",
+        move || -> crate::shared::error::AppResult<bool> {
+            hooks::delete(&app, &db, hook_id)?;
+            Ok(true)
+        },
+    )
+    .await
+    .map_err(Into::into);
+    #[cfg(windows)]
+    if result.is_ok() {
+        super::wsl::wsl_sync_trigger::trigger(app_for_wsl);
+    }
+    result
+}
@@ -4,8 +4,9 @@ use crate::app_state::{ensure_db_ready, DbInitState};
 use crate::{blocking, mcp};
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn mcp_servers_list(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     workspace_id: i64,
 ) -> Result<Vec<mcp::McpServerSummary>, String> {
@@ -18,9 +19,10 @@ pub(crate) async fn mcp_servers_list(
 }
 
 #[tauri::command]
+#[specta::specta]
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn mcp_server_upsert(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     server_id: Option<i64>,
     server_key: String,
@@ -62,8 +64,9 @@ pub(crate) async fn mcp_server_upsert(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn mcp_server_set_enabled(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     workspace_id: i64,
     server_id: i64,
@@ -85,8 +88,9 @@ pub(crate) async fn mcp_server_set_enabled(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn mcp_server_delete(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     server_id: i64,
 ) -> Result<bool, String> {
@@ -97,6 +101,7 @@ pub(crate) async fn mcp_server_delete(
         "mcp_server_delete",
         move || -> crate::shared::error::AppResult<bool> {
             mcp::delete(&app, &db, server_id)?;
+            mcp::supervise_stop(server_id);
             Ok(true)
         },
     )
@@ -110,13 +115,15 @@ pub(crate) async fn mcp_server_delete(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) fn mcp_parse_json(json_text: String) -> Result<mcp::McpParseResult, String> {
     mcp::parse_json(&json_text).map_err(Into::into)
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn mcp_import_servers(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     workspace_id: i64,
     servers: Vec<mcp::McpImportServer>,
@@ -137,8 +144,9 @@ pub(crate) async fn mcp_import_servers(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn mcp_import_from_workspace_cli(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     workspace_id: i64,
 ) -> Result<mcp::McpImportReport, String> {
@@ -156,3 +164,98 @@ pub(crate) async fn mcp_import_from_workspace_cli(
     }
     result
 }
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn mcp_sync_status(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: Option<String>,
+) -> Result<Vec<mcp::McpSyncCliStatus>, String> {
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    blocking::run("mcp_sync_status", move || {
+        mcp::sync_status(&app, &db, cli_key.as_deref())
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn mcp_sync_apply(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: String,
+) -> Result<mcp::McpSyncCliStatus, String> {
+    #[cfg(windows)]
+    let app_for_wsl = app.clone();
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    let result = blocking::run("mcp_sync_apply", move || {
+        mcp::apply_db_state(&app, &db, &cli_key)
+    })
+    .await
+    .map_err(Into::into);
+    #[cfg(windows)]
+    if result.is_ok() {
+        super::wsl::wsl_sync_trigger::trigger(app_for_wsl);
+    }
+    result
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn mcp_sync_adopt(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: String,
+    server_keys: Vec<String>,
+) -> Result<mcp::McpSyncCliStatus, String> {
+    #[cfg(windows)]
+    let app_for_wsl = app.clone();
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    let result = blocking::run("mcp_sync_adopt", move || {
+        mcp::adopt_external_edits(&app, &db, &cli_key, &server_keys)
+    })
+    .await
+    .map_err(Into::into);
+    #[cfg(windows)]
+    if result.is_ok() {
+        super::wsl::wsl_sync_trigger::trigger(app_for_wsl);
+    }
+    result
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn mcp_server_supervise_start(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    server_id: i64,
+) -> Result<mcp::McpServerRuntimeStatus, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("mcp_server_supervise_start", move || {
+        mcp::supervise_start(&db, server_id)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) fn mcp_server_supervise_stop(server_id: i64) -> Result<bool, String> {
+    Ok(mcp::supervise_stop(server_id))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn mcp_server_runtime_status() -> Result<Vec<mcp::McpServerRuntimeStatus>, String>
+{
+    blocking::run(
+        "mcp_server_runtime_status",
+        move || -> crate::shared::error::AppResult<Vec<mcp::McpServerRuntimeStatus>> {
+            Ok(mcp::runtime_status())
+        },
+    )
+    .await
+    .map_err(Into::into)
+}
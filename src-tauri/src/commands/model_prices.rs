@@ -1,11 +1,14 @@
 //! Usage: Model pricing related Tauri commands.
 
 use crate::app_state::{ensure_db_ready, DbInitState};
-use crate::{blocking, cost_stats, model_price_aliases, model_prices, model_prices_sync};
+use crate::{
+    blocking, cost_stats, model_price_aliases, model_prices, model_prices_import, model_prices_sync,
+};
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn model_prices_list(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     cli_key: String,
 ) -> Result<Vec<model_prices::ModelPriceSummary>, String> {
@@ -18,8 +21,9 @@ pub(crate) async fn model_prices_list(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn model_price_upsert(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     cli_key: String,
     model: String,
@@ -34,8 +38,33 @@ pub(crate) async fn model_price_upsert(
 }
 
 #[tauri::command]
+#[specta::specta]
+pub(crate) async fn model_prices_import(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    content: String,
+    format: Option<String>,
+    cli_key: Option<String>,
+    dry_run: bool,
+) -> Result<model_prices_import::ModelPricesImportReport, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("model_prices_import", move || {
+        model_prices_import::import(
+            &db,
+            &content,
+            format.as_deref(),
+            cli_key.as_deref(),
+            dry_run,
+        )
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
 pub(crate) async fn model_prices_sync_basellm(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     force: Option<bool>,
 ) -> Result<model_prices_sync::ModelPricesSyncReport, String> {
@@ -71,8 +100,9 @@ pub(crate) async fn model_prices_sync_basellm(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn model_price_aliases_get(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
 ) -> Result<model_price_aliases::ModelPriceAliasesV1, String> {
     blocking::run(
         "model_price_aliases_get",
@@ -85,8 +115,9 @@ pub(crate) async fn model_price_aliases_get(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn model_price_aliases_set(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     aliases: model_price_aliases::ModelPriceAliasesV1,
 ) -> Result<model_price_aliases::ModelPriceAliasesV1, String> {
     blocking::run("model_price_aliases_set", move || {
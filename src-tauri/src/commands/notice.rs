@@ -3,8 +3,9 @@
 use crate::notice;
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) fn notice_send(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     level: notice::NoticeLevel,
     title: Option<String>,
     body: String,
@@ -12,3 +13,12 @@ pub(crate) fn notice_send(
     notice::emit(&app, notice::build(level, title, body))?;
     Ok(true)
 }
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn notification_sink_test(
+    sink: crate::settings::NotificationSink,
+) -> Result<bool, String> {
+    crate::notification_sinks::send_test(sink).await?;
+    Ok(true)
+}
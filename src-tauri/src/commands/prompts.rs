@@ -4,8 +4,9 @@ use crate::app_state::{ensure_db_ready, DbInitState};
 use crate::{blocking, prompts};
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn prompts_list(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     workspace_id: i64,
 ) -> Result<Vec<prompts::PromptSummary>, String> {
@@ -18,8 +19,9 @@ pub(crate) async fn prompts_list(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn prompts_default_sync_from_files(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
 ) -> Result<prompts::DefaultPromptSyncReport, String> {
     #[cfg(windows)]
@@ -38,8 +40,9 @@ pub(crate) async fn prompts_default_sync_from_files(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn prompt_upsert(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     prompt_id: Option<i64>,
     workspace_id: i64,
@@ -63,8 +66,9 @@ pub(crate) async fn prompt_upsert(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn prompt_set_enabled(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     prompt_id: i64,
     enabled: bool,
@@ -85,8 +89,9 @@ pub(crate) async fn prompt_set_enabled(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn prompt_delete(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     prompt_id: i64,
 ) -> Result<bool, String> {
@@ -108,3 +113,44 @@ pub(crate) async fn prompt_delete(
     }
     result
 }
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn prompt_file_conflicts_list(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+) -> Result<Vec<prompts::PromptFileConflict>, String> {
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    blocking::run("prompt_file_conflicts_list", move || {
+        prompts::list_file_conflicts(&app, &db)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn prompt_file_conflict_resolve(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: String,
+    resolution: prompts::PromptConflictResolution,
+) -> Result<bool, String> {
+    #[cfg(windows)]
+    let app_for_wsl = app.clone();
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    let result = blocking::run(
+        "prompt_file_conflict_resolve",
+        move || -> crate::shared::error::AppResult<bool> {
+            prompts::resolve_file_conflict(&app, &db, &cli_key, resolution)?;
+            Ok(true)
+        },
+    )
+    .await
+    .map_err(Into::into);
+    #[cfg(windows)]
+    if result.is_ok() {
+        super::wsl::wsl_sync_trigger::trigger(app_for_wsl);
+    }
+    result
+}
@@ -4,8 +4,9 @@ use crate::app_state::{ensure_db_ready, DbInitState};
 use crate::{blocking, provider_limit_usage};
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn provider_limit_usage_v1(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     cli_key: Option<String>,
 ) -> Result<Vec<provider_limit_usage::ProviderLimitUsageRow>, String> {
@@ -16,3 +17,45 @@ pub(crate) async fn provider_limit_usage_v1(
     .await
     .map_err(Into::into)
 }
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_monthly_budgets_list(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: Option<String>,
+) -> Result<Vec<provider_limit_usage::ProviderMonthlyBudgetRow>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("provider_monthly_budgets_list", move || {
+        provider_limit_usage::monthly_budgets_list(&db, cli_key.as_deref())
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_monthly_budget_set_grace(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    provider_id: i64,
+    grace_usd: f64,
+) -> Result<provider_limit_usage::ProviderMonthlyBudgetRow, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let result = blocking::run("provider_monthly_budget_set_grace", move || {
+        provider_limit_usage::monthly_budget_set_grace(&db, provider_id, grace_usd)
+    })
+    .await
+    .map_err(Into::into);
+
+    if let Ok(ref row) = result {
+        tracing::info!(
+            provider_id = row.provider_id,
+            month = %row.month,
+            grace_usd = row.grace_usd,
+            "provider monthly budget grace changed"
+        );
+    }
+
+    result
+}
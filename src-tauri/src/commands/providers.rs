@@ -2,7 +2,11 @@
 
 use crate::app_state::{ensure_db_ready, DbInitState, GatewayState};
 use crate::shared::mutex_ext::MutexExt;
-use crate::{base_url_probe, blocking, providers};
+use crate::{
+    audit_log, base_url_probe, blocking, provider_credentials, provider_dns_options,
+    provider_maintenance, provider_model_catalog, provider_tag_defaults, provider_validation,
+    providers,
+};
 use serde_json::json;
 use std::path::{Path, PathBuf};
 use tauri::Emitter;
@@ -40,6 +44,18 @@ pub(crate) struct ProviderUpsertInput {
     pub limit_total_usd: Option<f64>,
     pub tags: Option<Vec<String>>,
     pub note: Option<String>,
+    pub provider_kind: Option<providers::ProviderKind>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ProviderMaintenanceWindowInput {
+    pub window_id: Option<i64>,
+    pub provider_id: i64,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub repeat_interval_secs: Option<i64>,
+    pub note: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -84,16 +100,56 @@ fn provider_runtime_reset_decision(
     }
 }
 
+fn provider_upsert_audit_summary(
+    previous: Option<&providers::ProviderSummary>,
+    previous_api_key: Option<&str>,
+    next: &providers::ProviderSummary,
+    submitted_api_key: Option<&str>,
+) -> String {
+    let Some(previous) = previous else {
+        return format!("created {:?} ({})", next.name, next.cli_key);
+    };
+    let before = serde_json::to_value(previous).unwrap_or_default();
+    let after = serde_json::to_value(next).unwrap_or_default();
+    let summary = audit_log::summarize_diff(&before, &after);
+    if !submitted_api_key_changed(previous_api_key, submitted_api_key) {
+        return summary;
+    }
+    if summary == "no changes" {
+        "api_key: changed".to_string()
+    } else {
+        format!("api_key: changed; {summary}")
+    }
+}
+
 #[tauri::command]
 #[specta::specta]
 pub(crate) async fn providers_list(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     cli_key: String,
+    include_archived: Option<bool>,
 ) -> Result<Vec<providers::ProviderSummary>, String> {
     let db = ensure_db_ready(app, db_state.inner()).await?;
     blocking::run("providers_list", move || {
-        providers::list_by_cli(&db, &cli_key)
+        providers::list_by_cli_with_archived(&db, &cli_key, include_archived.unwrap_or(false))
+    })
+    .await
+    .map_err(Into::into)
+    .map(crate::observer_mode::mask_provider_summaries)
+}
+
+/// Credential health of every provider (all clis when `cli_key` is omitted).
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn providers_credential_status(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: Option<String>,
+) -> Result<Vec<provider_credentials::ProviderCredentialStatus>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("providers_credential_status", move || {
+        provider_credentials::list(&db, cli_key.as_deref())
     })
     .await
     .map_err(Into::into)
@@ -102,7 +158,7 @@ pub(crate) async fn providers_list(
 #[tauri::command]
 #[specta::specta]
 pub(crate) async fn provider_upsert(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     gateway_state: tauri::State<'_, GatewayState>,
     input: ProviderUpsertInput,
@@ -128,13 +184,16 @@ pub(crate) async fn provider_upsert(
         limit_total_usd,
         tags,
         note,
+        provider_kind,
     } = input;
 
     let is_create = provider_id.is_none();
     let name_for_log = name.clone();
     let cli_key_for_log = cli_key.clone();
     let submitted_api_key = api_key.clone();
+    let origin = audit_log::current_origin();
     let db = ensure_db_ready(app, db_state.inner()).await?;
+    let db_for_catalog = db.clone();
     let result = blocking::run("provider_upsert", move || {
         let previous = match provider_id {
             Some(id) => {
@@ -171,6 +230,7 @@ pub(crate) async fn provider_upsert(
                 limit_total_usd,
                 tags,
                 note,
+                provider_kind,
             },
         )?;
 
@@ -180,6 +240,18 @@ pub(crate) async fn provider_upsert(
             &saved,
             submitted_api_key.as_deref(),
         );
+        audit_log::record(
+            &db,
+            origin,
+            "provider_upsert",
+            Some(format!("provider:{}", saved.id)),
+            provider_upsert_audit_summary(
+                previous.as_ref(),
+                previous_api_key.as_deref(),
+                &saved,
+                submitted_api_key.as_deref(),
+            ),
+        );
 
         Ok::<_, crate::shared::error::AppError>((saved, decision))
     })
@@ -203,6 +275,17 @@ pub(crate) async fn provider_upsert(
             );
         }
 
+        if provider.provider_kind == providers::ProviderKind::Aggregated {
+            if let Ok(client) = catalog_http_client() {
+                provider_model_catalog::spawn_refresh_if_stale(
+                    &db_for_catalog,
+                    &client,
+                    provider.id,
+                    provider.model_catalog_refreshed_at,
+                );
+            }
+        }
+
         if decision.clear_session_bindings {
             let cleared_sessions = {
                 let manager = gateway_state.0.lock_or_recover();
@@ -217,12 +300,13 @@ pub(crate) async fn provider_upsert(
         }
     }
 
-    result.map(|(provider, _)| provider)
+    result.map(|(provider, _)| crate::observer_mode::mask_provider_summary(provider))
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn provider_set_enabled(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     provider_id: i64,
     enabled: bool,
@@ -242,12 +326,197 @@ pub(crate) async fn provider_set_enabled(
         );
     }
 
-    result
+    result.map(crate::observer_mode::mask_provider_summary)
 }
 
 #[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_set_last_resort(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    provider_id: i64,
+    last_resort: bool,
+) -> Result<providers::ProviderSummary, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let result = blocking::run("provider_set_last_resort", move || {
+        providers::set_last_resort(&db, provider_id, last_resort)
+    })
+    .await
+    .map_err(Into::into);
+
+    if let Ok(ref provider) = result {
+        tracing::info!(
+            provider_id = provider.id,
+            last_resort = provider.last_resort,
+            "provider last-resort flag changed"
+        );
+    }
+
+    result.map(crate::observer_mode::mask_provider_summary)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_set_monthly_budget(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    provider_id: i64,
+    monthly_budget_usd: Option<f64>,
+) -> Result<providers::ProviderSummary, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("provider_set_monthly_budget", move || {
+        providers::set_monthly_budget(&db, provider_id, monthly_budget_usd)
+    })
+    .await
+    .map_err(Into::into)
+    .map(crate::observer_mode::mask_provider_summary)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_set_emulate_models_endpoint(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    provider_id: i64,
+    enabled: bool,
+) -> Result<providers::ProviderSummary, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let result = blocking::run("provider_set_emulate_models_endpoint", move || {
+        providers::set_emulate_models_endpoint(&db, provider_id, enabled)
+    })
+    .await
+    .map_err(Into::into);
+
+    if let Ok(ref provider) = result {
+        tracing::info!(
+            provider_id = provider.id,
+            emulate_models_endpoint = provider.emulate_models_endpoint,
+            "provider models endpoint emulation changed"
+        );
+    }
+
+    result.map(crate::observer_mode::mask_provider_summary)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_set_thinking_budget_cap(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    provider_id: i64,
+    thinking_budget_cap: Option<u32>,
+) -> Result<providers::ProviderSummary, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let result = blocking::run("provider_set_thinking_budget_cap", move || {
+        providers::set_thinking_budget_cap(&db, provider_id, thinking_budget_cap)
+    })
+    .await
+    .map_err(Into::into);
+
+    if let Ok(ref provider) = result {
+        tracing::info!(
+            provider_id = provider.id,
+            thinking_budget_cap = ?provider.thinking_budget_cap,
+            "provider thinking budget cap changed"
+        );
+    }
+
+    result.map(crate::observer_mode::mask_provider_summary)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_set_refusal_patterns(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    provider_id: i64,
+    refusal_patterns: Vec<String>,
+) -> Result<providers::ProviderSummary, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let result = blocking::run("provider_set_refusal_patterns", move || {
+        providers::set_refusal_patterns(&db, provider_id, refusal_patterns)
+    })
+    .await
+    .map_err(Into::into);
+
+    if let Ok(ref provider) = result {
+        tracing::info!(
+            provider_id = provider.id,
+            refusal_patterns = provider.refusal_patterns.len(),
+            "provider refusal patterns changed"
+        );
+    }
+
+    result.map(crate::observer_mode::mask_provider_summary)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_set_anthropic_headers(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    provider_id: i64,
+    anthropic_version: Option<String>,
+    anthropic_betas: Vec<String>,
+    anthropic_beta_strip: bool,
+) -> Result<providers::ProviderSummary, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let result = blocking::run("provider_set_anthropic_headers", move || {
+        providers::set_anthropic_headers(
+            &db,
+            provider_id,
+            anthropic_version,
+            anthropic_betas,
+            anthropic_beta_strip,
+        )
+    })
+    .await
+    .map_err(Into::into);
+
+    if let Ok(ref provider) = result {
+        tracing::info!(
+            provider_id = provider.id,
+            anthropic_version = ?provider.anthropic_version,
+            anthropic_betas = provider.anthropic_betas.len(),
+            anthropic_beta_strip = provider.anthropic_beta_strip,
+            "provider anthropic headers changed"
+        );
+    }
+
+    result.map(crate::observer_mode::mask_provider_summary)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_set_webhook_url(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    provider_id: i64,
+    webhook_url: Option<String>,
+) -> Result<providers::ProviderSummary, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let result = blocking::run("provider_set_webhook_url", move || {
+        providers::set_webhook_url(&db, provider_id, webhook_url)
+    })
+    .await
+    .map_err(Into::into);
+
+    if let Ok(ref provider) = result {
+        tracing::info!(
+            provider_id = provider.id,
+            webhook_enabled = provider.webhook_url.is_some(),
+            "provider webhook changed"
+        );
+    }
+
+    result.map(crate::observer_mode::mask_provider_summary)
+}
+
+/// Soft delete: archives the provider. `provider_purge` removes it for good.
+#[tauri::command]
+#[specta::specta]
 pub(crate) async fn provider_delete(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     provider_id: i64,
 ) -> Result<bool, String> {
@@ -255,7 +524,7 @@ pub(crate) async fn provider_delete(
     let result = blocking::run(
         "provider_delete",
         move || -> crate::shared::error::AppResult<bool> {
-            providers::delete(&db, provider_id)?;
+            providers::archive(&db, provider_id)?;
             Ok(true)
         },
     )
@@ -263,15 +532,280 @@ pub(crate) async fn provider_delete(
     .map_err(Into::into);
 
     if let Ok(true) = result {
-        tracing::info!(provider_id = provider_id, "provider deleted");
+        tracing::info!(provider_id = provider_id, "provider archived");
     }
 
     result
 }
 
 #[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_restore(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    provider_id: i64,
+) -> Result<providers::ProviderSummary, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let result = blocking::run("provider_restore", move || {
+        providers::unarchive(&db, provider_id)
+    })
+    .await
+    .map_err(Into::into);
+
+    if result.is_ok() {
+        tracing::info!(provider_id = provider_id, "provider restored");
+    }
+
+    result.map(crate::observer_mode::mask_provider_summary)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_usage_audit(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    provider_id: i64,
+) -> Result<providers::ProviderUsageAudit, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("provider_usage_audit", move || {
+        providers::usage_audit(&db, provider_id)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_purge(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    provider_id: i64,
+) -> Result<bool, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let result = blocking::run(
+        "provider_purge",
+        move || -> crate::shared::error::AppResult<bool> {
+            providers::purge(&db, provider_id)?;
+            Ok(true)
+        },
+    )
+    .await
+    .map_err(Into::into);
+
+    if let Ok(true) = result {
+        tracing::info!(provider_id = provider_id, "provider purged");
+    }
+
+    result
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_maintenance_windows_list(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    provider_id: i64,
+) -> Result<Vec<provider_maintenance::ProviderMaintenanceWindow>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("provider_maintenance_windows_list", move || {
+        provider_maintenance::list_windows(&db, provider_id)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_maintenance_window_upsert(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    input: ProviderMaintenanceWindowInput,
+) -> Result<provider_maintenance::ProviderMaintenanceWindow, String> {
+    let ProviderMaintenanceWindowInput {
+        window_id,
+        provider_id,
+        starts_at,
+        ends_at,
+        repeat_interval_secs,
+        note,
+    } = input;
+
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("provider_maintenance_window_upsert", move || {
+        provider_maintenance::upsert_window(
+            &db,
+            provider_maintenance::ProviderMaintenanceWindowUpsertParams {
+                window_id,
+                provider_id,
+                starts_at,
+                ends_at,
+                repeat_interval_secs,
+                note,
+            },
+        )
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_maintenance_window_delete(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    window_id: i64,
+) -> Result<bool, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run(
+        "provider_maintenance_window_delete",
+        move || -> crate::shared::error::AppResult<bool> {
+            provider_maintenance::delete_window(&db, window_id)?;
+            Ok(true)
+        },
+    )
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_dns_options_get(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    provider_id: i64,
+) -> Result<provider_dns_options::ProviderDnsOptions, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("provider_dns_options_get", move || {
+        provider_dns_options::get(&db, provider_id)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_dns_options_set(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    gateway_state: tauri::State<'_, GatewayState>,
+    provider_id: i64,
+    ip_strategy: provider_dns_options::DnsIpStrategy,
+    doh_url: Option<String>,
+    race_connections: bool,
+) -> Result<provider_dns_options::ProviderDnsOptions, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let options = blocking::run("provider_dns_options_set", move || {
+        provider_dns_options::set(
+            &db,
+            provider_id,
+            ip_strategy,
+            doh_url.as_deref(),
+            race_connections,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    gateway_state
+        .0
+        .lock_or_recover()
+        .set_provider_dns_options(options.clone());
+    tracing::info!(
+        provider_id = provider_id,
+        ip_strategy = options.ip_strategy.as_str(),
+        doh = options.doh_url.is_some(),
+        race_connections = options.race_connections,
+        "provider dns options updated"
+    );
+
+    Ok(options)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_default_tags_list(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+) -> Result<Vec<provider_tag_defaults::ProviderTagDefault>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("provider_default_tags_list", move || {
+        provider_tag_defaults::list(&db)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_default_tag_set(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: String,
+    tag: Option<String>,
+) -> Result<provider_tag_defaults::ProviderTagDefault, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let stored = blocking::run("provider_default_tag_set", move || {
+        provider_tag_defaults::set(&db, &cli_key, tag.as_deref())
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    tracing::info!(
+        cli_key = %stored.cli_key,
+        tag = stored.tag.as_deref().unwrap_or(""),
+        "provider default tag updated"
+    );
+
+    Ok(stored)
+}
+
+fn catalog_http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .user_agent(format!(
+            "aio-coding-hub-models/{}",
+            env!("CARGO_PKG_VERSION")
+        ))
+        .build()
+        .map_err(|e| format!("MODEL_CATALOG_HTTP_CLIENT_INIT: {e}"))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn provider_model_catalog_refresh(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    provider_id: i64,
+) -> Result<provider_model_catalog::ProviderModelCatalog, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let client = catalog_http_client()?;
+    provider_model_catalog::refresh(&db, &client, provider_id)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn providers_validate_all(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    claude_model: Option<String>,
+) -> Result<provider_validation::ProviderValidationReport, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let client = catalog_http_client()?;
+    let report = provider_validation::validate_all(db, client, claude_model).await?;
+    tracing::info!(
+        total = report.total,
+        passed = report.passed,
+        failed = report.failed,
+        "providers validated"
+    );
+    Ok(report)
+}
+
+#[tauri::command]
+#[specta::specta]
 pub(crate) async fn providers_reorder(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     gateway_state: tauri::State<'_, GatewayState>,
     cli_key: String,
@@ -299,12 +833,41 @@ pub(crate) async fn providers_reorder(
         );
     }
 
+    result.map(crate::observer_mode::mask_provider_summaries)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn providers_bulk_update(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    input: providers::ProviderBulkUpdate,
+    dry_run: bool,
+) -> Result<providers::ProviderBulkUpdateResult, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let result = blocking::run("providers_bulk_update", move || {
+        providers::bulk_update(&db, input, dry_run)
+    })
+    .await
+    .map_err(Into::into);
+
+    if let Ok(ref result) = result {
+        tracing::info!(
+            dry_run = result.dry_run,
+            applied = result.applied,
+            count = result.outcomes.len(),
+            failed = result.outcomes.iter().filter(|o| !o.ok).count(),
+            "providers bulk update"
+        );
+    }
+
     result
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn provider_claude_terminal_launch_command(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     provider_id: i64,
 ) -> Result<String, String> {
@@ -330,7 +893,7 @@ pub(crate) async fn provider_claude_terminal_launch_command(
 }
 
 fn ensure_gateway_base_origin(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     db: &crate::db::Db,
 ) -> crate::shared::error::AppResult<String> {
     let state = app.state::<GatewayState>();
@@ -487,11 +1050,13 @@ fn windows_double_quote(value: &str) -> String {
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn provider_get_api_key(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     provider_id: i64,
 ) -> Result<String, String> {
+    crate::observer_mode::ensure_secrets_allowed("provider_get_api_key")?;
     let db = ensure_db_ready(app, db_state.inner()).await?;
     blocking::run("provider_get_api_key", move || {
         providers::get_api_key_plaintext(&db, provider_id)
@@ -501,6 +1066,7 @@ pub(crate) async fn provider_get_api_key(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn base_url_ping_ms(base_url: String) -> Result<u64, String> {
     let client = reqwest::Client::builder()
         .user_agent(format!("aio-coding-hub-ping/{}", env!("CARGO_PKG_VERSION")))
@@ -510,8 +1076,9 @@ pub(crate) async fn base_url_ping_ms(base_url: String) -> Result<u64, String> {
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn provider_oauth_start_flow(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     cli_key: String,
     provider_id: i64,
@@ -671,8 +1238,9 @@ pub(crate) async fn provider_oauth_start_flow(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn provider_oauth_refresh(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     provider_id: i64,
 ) -> Result<serde_json::Value, String> {
@@ -764,8 +1332,9 @@ pub(crate) async fn provider_oauth_refresh(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn provider_oauth_disconnect(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     provider_id: i64,
 ) -> Result<serde_json::Value, String> {
@@ -779,8 +1348,9 @@ pub(crate) async fn provider_oauth_disconnect(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn provider_oauth_status(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     provider_id: i64,
 ) -> Result<serde_json::Value, String> {
@@ -816,8 +1386,9 @@ pub(crate) async fn provider_oauth_status(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn provider_oauth_fetch_limits(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     provider_id: i64,
 ) -> Result<serde_json::Value, String> {
@@ -1173,6 +1744,7 @@ mod tests {
             limit_weekly_usd: None,
             limit_monthly_usd: None,
             limit_total_usd: None,
+            monthly_budget_usd: None,
             tags: vec![],
             note: String::new(),
             created_at: 1,
@@ -1182,6 +1754,21 @@ mod tests {
             oauth_email: None,
             oauth_expires_at: None,
             oauth_last_error: None,
+            maintenance_active: None,
+            maintenance_next: None,
+            provider_kind: providers::ProviderKind::Standard,
+            model_catalog: Vec::new(),
+            model_catalog_refreshed_at: None,
+            model_catalog_error: None,
+            last_resort: false,
+            emulate_models_endpoint: false,
+            thinking_budget_cap: None,
+            archived_at: None,
+            refusal_patterns: Vec::new(),
+            anthropic_version: None,
+            anthropic_betas: Vec::new(),
+            anthropic_beta_strip: false,
+            webhook_url: None,
         };
 
         assert_eq!(
@@ -1234,6 +1821,7 @@ mod tests {
             limit_weekly_usd: None,
             limit_monthly_usd: None,
             limit_total_usd: None,
+            monthly_budget_usd: None,
             tags: vec![],
             note: String::new(),
             created_at: 1,
@@ -1243,6 +1831,21 @@ mod tests {
             oauth_email: None,
             oauth_expires_at: None,
             oauth_last_error: None,
+            maintenance_active: None,
+            maintenance_next: None,
+            provider_kind: providers::ProviderKind::Standard,
+            model_catalog: Vec::new(),
+            model_catalog_refreshed_at: None,
+            model_catalog_error: None,
+            last_resort: false,
+            emulate_models_endpoint: false,
+            thinking_budget_cap: None,
+            archived_at: None,
+            refusal_patterns: Vec::new(),
+            anthropic_version: None,
+            anthropic_betas: Vec::new(),
+            anthropic_beta_strip: false,
+            webhook_url: None,
         };
 
         let mut next = previous.clone();
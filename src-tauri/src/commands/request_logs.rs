@@ -22,8 +22,9 @@ fn request_attempt_logs_limit(limit: Option<u32>) -> usize {
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn request_logs_list(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     cli_key: String,
     limit: Option<u32>,
@@ -34,12 +35,14 @@ pub(crate) async fn request_logs_list(
         request_logs::list_recent(&db, &cli_key, limit)
     })
     .await
+    .map(crate::observer_mode::mask_request_log_summaries)
     .map_err(Into::into)
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn request_logs_list_all(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     limit: Option<u32>,
 ) -> Result<Vec<request_logs::RequestLogSummary>, String> {
@@ -49,12 +52,14 @@ pub(crate) async fn request_logs_list_all(
         request_logs::list_recent_all(&db, limit)
     })
     .await
+    .map(crate::observer_mode::mask_request_log_summaries)
     .map_err(Into::into)
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn request_logs_list_after_id(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     cli_key: String,
     after_id: i64,
@@ -66,12 +71,14 @@ pub(crate) async fn request_logs_list_after_id(
         request_logs::list_after_id(&db, &cli_key, after_id, limit)
     })
     .await
+    .map(crate::observer_mode::mask_request_log_summaries)
     .map_err(Into::into)
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn request_logs_list_after_id_all(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     after_id: i64,
     limit: Option<u32>,
@@ -82,12 +89,14 @@ pub(crate) async fn request_logs_list_after_id_all(
         request_logs::list_after_id_all(&db, after_id, limit)
     })
     .await
+    .map(crate::observer_mode::mask_request_log_summaries)
     .map_err(Into::into)
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn request_log_get(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     log_id: i64,
 ) -> Result<request_logs::RequestLogDetail, String> {
@@ -96,12 +105,14 @@ pub(crate) async fn request_log_get(
         request_logs::get_by_id(&db, log_id)
     })
     .await
+    .map(crate::observer_mode::mask_request_log_detail)
     .map_err(Into::into)
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn request_log_get_by_trace_id(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     trace_id: String,
 ) -> Result<Option<request_logs::RequestLogDetail>, String> {
@@ -110,12 +121,14 @@ pub(crate) async fn request_log_get_by_trace_id(
         request_logs::get_by_trace_id(&db, &trace_id)
     })
     .await
+    .map(|detail| detail.map(crate::observer_mode::mask_request_log_detail))
     .map_err(Into::into)
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn request_attempt_logs_by_trace_id(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     trace_id: String,
     limit: Option<u32>,
@@ -126,6 +139,22 @@ pub(crate) async fn request_attempt_logs_by_trace_id(
         request_attempt_logs::list_by_trace_id(&db, &trace_id, limit)
     })
     .await
+    .map(crate::observer_mode::mask_request_attempt_logs)
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn request_timeline_by_trace_id(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    trace_id: String,
+) -> Result<Option<request_attempt_logs::RequestTimeline>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("request_timeline_by_trace_id", move || {
+        request_attempt_logs::timeline_by_trace_id(&db, &trace_id)
+    })
+    .await
     .map_err(Into::into)
 }
 
@@ -0,0 +1,58 @@
+//! Usage: Request mirroring (shadow provider evaluation) related Tauri commands.
+
+use crate::app_state::{ensure_db_ready, DbInitState};
+use crate::commands::limit::normalize_limit;
+use crate::{blocking, request_mirror};
+
+const REQUEST_MIRROR_LOGS_DEFAULT_LIMIT: u32 = 100;
+const REQUEST_MIRROR_LOGS_MAX_LIMIT: u32 = 500;
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn request_mirror_logs_list(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    limit: Option<u32>,
+) -> Result<Vec<request_mirror::RequestMirrorLogRow>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let limit = normalize_limit(
+        limit,
+        REQUEST_MIRROR_LOGS_DEFAULT_LIMIT,
+        1,
+        REQUEST_MIRROR_LOGS_MAX_LIMIT,
+    );
+    blocking::run("request_mirror_logs_list", move || {
+        request_mirror::list_recent(&db, limit)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn request_mirror_stats(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    since_unix: Option<i64>,
+) -> Result<Vec<request_mirror::RequestMirrorProviderStats>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("request_mirror_stats", move || {
+        request_mirror::provider_stats(&db, since_unix)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn request_mirror_logs_clear(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+) -> Result<usize, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("request_mirror_logs_clear", move || {
+        request_mirror::clear(&db)
+    })
+    .await
+    .map_err(Into::into)
+}
@@ -1,6 +1,8 @@
 //! Usage: Settings-related Tauri commands.
 
-use crate::{blocking, resident, settings};
+use crate::shared::i18n;
+use crate::shared::mutex_ext::MutexExt;
+use crate::{audit_log, blocking, resident, settings};
 use tauri::Manager;
 
 /// Encapsulates all fields for the `settings_set` command.
@@ -15,11 +17,29 @@ pub(crate) struct SettingsUpdate {
     pub tray_enabled: Option<bool>,
     pub enable_cli_proxy_startup_recovery: Option<bool>,
     pub log_retention_days: u32,
+    pub attempt_log_retention_days: Option<u32>,
     pub provider_cooldown_seconds: Option<u32>,
     pub provider_base_url_ping_cache_ttl_seconds: Option<u32>,
     pub upstream_first_byte_timeout_seconds: Option<u32>,
     pub upstream_stream_idle_timeout_seconds: Option<u32>,
     pub upstream_request_timeout_non_streaming_seconds: Option<u32>,
+    pub count_tokens_hedge_delay_ms: Option<u32>,
+    pub count_tokens_local_estimate_enabled: Option<bool>,
+    pub first_byte_race_delay_ms: Option<u32>,
+    pub non_streaming_hedge_delay_ms: Option<u32>,
+    pub upstream_warm_pool_enabled: Option<bool>,
+    pub upstream_warm_pool_size: Option<u32>,
+    pub request_mirror_enabled: Option<bool>,
+    pub request_mirror_provider_id: Option<i64>,
+    pub request_mirror_percent: Option<u32>,
+    pub request_dedup_enabled: Option<bool>,
+    pub stream_truncation_recovery_enabled: Option<bool>,
+    pub sse_coalesce_flush_ms: Option<u32>,
+    pub sse_coalesce_flush_bytes: Option<u32>,
+    pub stream_stall_warn_seconds: Option<u32>,
+    pub stream_stall_keepalive_enabled: Option<bool>,
+    pub stream_first_byte_failover_enabled: Option<bool>,
+    pub stream_error_event_enabled: Option<bool>,
     pub intercept_anthropic_warmup_requests: Option<bool>,
     pub enable_thinking_signature_rectifier: Option<bool>,
     pub enable_thinking_budget_rectifier: Option<bool>,
@@ -35,25 +55,28 @@ pub(crate) struct SettingsUpdate {
     pub failover_max_providers_to_try: u32,
     pub circuit_breaker_failure_threshold: Option<u32>,
     pub circuit_breaker_open_duration_minutes: Option<u32>,
+    pub circuit_breaker_half_open_probe_enabled: Option<bool>,
     pub update_releases_url: Option<String>,
     pub wsl_auto_config: Option<bool>,
     pub wsl_target_cli: Option<settings::WslTargetCli>,
     pub wsl_host_address_mode: Option<settings::WslHostAddressMode>,
     pub wsl_custom_host_address: Option<String>,
+    pub ui_language: Option<i18n::UiLanguage>,
 }
 
 #[tauri::command]
 #[specta::specta]
-pub(crate) async fn settings_get(app: tauri::AppHandle) -> Result<settings::AppSettings, String> {
+pub(crate) async fn settings_get(app: crate::AppHandle) -> Result<settings::AppSettings, String> {
     blocking::run("settings_get", move || settings::read(&app))
         .await
+        .map(crate::observer_mode::mask_app_settings)
         .map_err(Into::into)
 }
 
 #[tauri::command]
 #[specta::specta]
 pub(crate) async fn settings_set(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     update: SettingsUpdate,
 ) -> Result<settings::AppSettings, String> {
     let SettingsUpdate {
@@ -65,11 +88,29 @@ pub(crate) async fn settings_set(
         tray_enabled,
         enable_cli_proxy_startup_recovery,
         log_retention_days,
+        attempt_log_retention_days,
         provider_cooldown_seconds,
         provider_base_url_ping_cache_ttl_seconds,
         upstream_first_byte_timeout_seconds,
         upstream_stream_idle_timeout_seconds,
         upstream_request_timeout_non_streaming_seconds,
+        count_tokens_hedge_delay_ms,
+        count_tokens_local_estimate_enabled,
+        first_byte_race_delay_ms,
+        non_streaming_hedge_delay_ms,
+        upstream_warm_pool_enabled,
+        upstream_warm_pool_size,
+        request_mirror_enabled,
+        request_mirror_provider_id,
+        request_mirror_percent,
+        request_dedup_enabled,
+        stream_truncation_recovery_enabled,
+        sse_coalesce_flush_ms,
+        sse_coalesce_flush_bytes,
+        stream_stall_warn_seconds,
+        stream_stall_keepalive_enabled,
+        stream_first_byte_failover_enabled,
+        stream_error_event_enabled,
         intercept_anthropic_warmup_requests,
         enable_thinking_signature_rectifier,
         enable_thinking_budget_rectifier,
@@ -85,11 +126,13 @@ pub(crate) async fn settings_set(
         failover_max_providers_to_try,
         circuit_breaker_failure_threshold,
         circuit_breaker_open_duration_minutes,
+        circuit_breaker_half_open_probe_enabled,
         update_releases_url,
         wsl_auto_config,
         wsl_target_cli,
         wsl_host_address_mode,
         wsl_custom_host_address,
+        ui_language,
     } = update;
 
     // Capture WSL-related update flags before values are moved into the closure
@@ -102,16 +145,20 @@ pub(crate) async fn settings_set(
         || wsl_host_address_mode.is_some()
         || wsl_custom_host_address.is_some();
 
+    let origin = audit_log::current_origin();
     let app_for_work = app.clone();
-    let next_settings = blocking::run(
+    let (next_settings, previous_ui_language, audit_summary) = blocking::run(
         "settings_set",
-        move || -> crate::shared::error::AppResult<settings::AppSettings> {
+        move || -> crate::shared::error::AppResult<(settings::AppSettings, i18n::UiLanguage, String)> {
             let previous = settings::read(&app_for_work).unwrap_or_default();
+            let previous_json = serde_json::to_value(&previous).unwrap_or_default();
             let update_releases_url = update_releases_url.unwrap_or(previous.update_releases_url);
             let tray_enabled = tray_enabled.unwrap_or(previous.tray_enabled);
             let start_minimized = start_minimized.unwrap_or(previous.start_minimized);
             let enable_cli_proxy_startup_recovery = enable_cli_proxy_startup_recovery
                 .unwrap_or(previous.enable_cli_proxy_startup_recovery);
+            let attempt_log_retention_days =
+                attempt_log_retention_days.unwrap_or(previous.attempt_log_retention_days);
             let provider_cooldown_seconds =
                 provider_cooldown_seconds.unwrap_or(previous.provider_cooldown_seconds);
             let gateway_listen_mode = gateway_listen_mode.unwrap_or(previous.gateway_listen_mode);
@@ -136,6 +183,40 @@ pub(crate) async fn settings_set(
             let upstream_request_timeout_non_streaming_seconds =
                 upstream_request_timeout_non_streaming_seconds
                     .unwrap_or(previous.upstream_request_timeout_non_streaming_seconds);
+            let count_tokens_hedge_delay_ms =
+                count_tokens_hedge_delay_ms.unwrap_or(previous.count_tokens_hedge_delay_ms);
+            let count_tokens_local_estimate_enabled = count_tokens_local_estimate_enabled
+                .unwrap_or(previous.count_tokens_local_estimate_enabled);
+            let first_byte_race_delay_ms =
+                first_byte_race_delay_ms.unwrap_or(previous.first_byte_race_delay_ms);
+            let non_streaming_hedge_delay_ms =
+                non_streaming_hedge_delay_ms.unwrap_or(previous.non_streaming_hedge_delay_ms);
+            let upstream_warm_pool_enabled =
+                upstream_warm_pool_enabled.unwrap_or(previous.upstream_warm_pool_enabled);
+            let upstream_warm_pool_size =
+                upstream_warm_pool_size.unwrap_or(previous.upstream_warm_pool_size);
+            let request_mirror_enabled =
+                request_mirror_enabled.unwrap_or(previous.request_mirror_enabled);
+            let request_mirror_provider_id =
+                request_mirror_provider_id.unwrap_or(previous.request_mirror_provider_id);
+            let request_mirror_percent =
+                request_mirror_percent.unwrap_or(previous.request_mirror_percent);
+            let request_dedup_enabled =
+                request_dedup_enabled.unwrap_or(previous.request_dedup_enabled);
+            let stream_truncation_recovery_enabled = stream_truncation_recovery_enabled
+                .unwrap_or(previous.stream_truncation_recovery_enabled);
+            let sse_coalesce_flush_ms =
+                sse_coalesce_flush_ms.unwrap_or(previous.sse_coalesce_flush_ms);
+            let sse_coalesce_flush_bytes =
+                sse_coalesce_flush_bytes.unwrap_or(previous.sse_coalesce_flush_bytes);
+            let stream_stall_warn_seconds =
+                stream_stall_warn_seconds.unwrap_or(previous.stream_stall_warn_seconds);
+            let stream_stall_keepalive_enabled =
+                stream_stall_keepalive_enabled.unwrap_or(previous.stream_stall_keepalive_enabled);
+            let stream_first_byte_failover_enabled = stream_first_byte_failover_enabled
+                .unwrap_or(previous.stream_first_byte_failover_enabled);
+            let stream_error_event_enabled =
+                stream_error_event_enabled.unwrap_or(previous.stream_error_event_enabled);
             let intercept_anthropic_warmup_requests = intercept_anthropic_warmup_requests
                 .unwrap_or(previous.intercept_anthropic_warmup_requests);
             let enable_thinking_signature_rectifier = enable_thinking_signature_rectifier
@@ -162,6 +243,9 @@ pub(crate) async fn settings_set(
                 .unwrap_or(previous.circuit_breaker_failure_threshold);
             let circuit_breaker_open_duration_minutes = circuit_breaker_open_duration_minutes
                 .unwrap_or(previous.circuit_breaker_open_duration_minutes);
+            let circuit_breaker_half_open_probe_enabled = circuit_breaker_half_open_probe_enabled
+                .unwrap_or(previous.circuit_breaker_half_open_probe_enabled);
+            let ui_language = ui_language.unwrap_or(previous.ui_language);
             let mut next_auto_start = auto_start;
 
             #[cfg(desktop)]
@@ -197,21 +281,42 @@ pub(crate) async fn settings_set(
                 wsl_target_cli,
                 wsl_host_address_mode,
                 wsl_custom_host_address,
+                gateway_access_token: previous.gateway_access_token,
                 auto_start: next_auto_start,
                 start_minimized,
                 tray_enabled,
                 enable_cli_proxy_startup_recovery,
                 log_retention_days,
+                attempt_log_retention_days,
                 provider_cooldown_seconds,
                 provider_base_url_ping_cache_ttl_seconds,
                 upstream_first_byte_timeout_seconds,
                 upstream_stream_idle_timeout_seconds,
                 upstream_request_timeout_non_streaming_seconds,
+                count_tokens_hedge_delay_ms,
+                count_tokens_local_estimate_enabled,
+                first_byte_race_delay_ms,
+                non_streaming_hedge_delay_ms,
+                upstream_warm_pool_enabled,
+                upstream_warm_pool_size,
+                request_mirror_enabled,
+                request_mirror_provider_id,
+                request_mirror_percent,
+                request_dedup_enabled,
+                stream_truncation_recovery_enabled,
+                sse_coalesce_flush_ms,
+                sse_coalesce_flush_bytes,
+                stream_stall_warn_seconds,
+                stream_stall_keepalive_enabled,
+                stream_first_byte_failover_enabled,
+                stream_error_event_enabled,
                 update_releases_url,
                 failover_max_attempts_per_provider,
                 failover_max_providers_to_try,
                 circuit_breaker_failure_threshold,
                 circuit_breaker_open_duration_minutes,
+                circuit_breaker_half_open_probe_enabled,
+                retry_backoff: previous.retry_backoff,
                 enable_circuit_breaker_notice: previous.enable_circuit_breaker_notice,
                 verbose_provider_error,
                 intercept_anthropic_warmup_requests,
@@ -227,16 +332,47 @@ pub(crate) async fn settings_set(
                 response_fixer_fix_truncated_json,
                 response_fixer_max_json_depth: previous.response_fixer_max_json_depth,
                 response_fixer_max_fix_size: previous.response_fixer_max_fix_size,
+                response_fixer_spill_to_disk: previous.response_fixer_spill_to_disk,
+                response_fixer_spill_max_size: previous.response_fixer_spill_max_size,
+                response_fixer_transcode_charsets: previous.response_fixer_transcode_charsets,
+                redaction_rules: previous.redaction_rules,
+                env_conflict_ignore_rules: previous.env_conflict_ignore_rules,
+                model_denylist: previous.model_denylist,
+                model_capabilities: previous.model_capabilities,
+                capability_gate_policy: previous.capability_gate_policy,
+                storage_postgres_url: previous.storage_postgres_url,
+                ui_language,
+                captured_response_headers: previous.captured_response_headers,
+                upstream_error_body_capture_bytes: previous.upstream_error_body_capture_bytes,
+                session_binding_ttl_seconds: previous.session_binding_ttl_seconds,
+                access_log_format: previous.access_log_format,
+                access_log_retention_days: previous.access_log_retention_days,
+                enable_cost_anomaly_detection: previous.enable_cost_anomaly_detection,
+                cost_anomaly_multiplier: previous.cost_anomaly_multiplier,
+                cost_anomaly_auto_throttle: previous.cost_anomaly_auto_throttle,
+                observer_mode: previous.observer_mode,
+                request_log_sample_percent: previous.request_log_sample_percent,
+                request_log_slow_threshold_ms: previous.request_log_slow_threshold_ms,
+                notification_sinks: previous.notification_sinks,
+                ssh_hosts: previous.ssh_hosts,
             };
 
             let next_settings = settings::write(&app_for_work, &settings)?;
-            Ok(next_settings)
+            let audit_summary = audit_log::summarize_diff(
+                &previous_json,
+                &serde_json::to_value(&next_settings).unwrap_or_default(),
+            );
+            Ok((next_settings, previous.ui_language, audit_summary))
         },
     )
     .await?;
+    audit_log::record_with_app(&app, origin, "settings_set", None, audit_summary).await;
 
     app.state::<resident::ResidentState>()
         .set_tray_enabled(next_settings.tray_enabled);
+    if next_settings.ui_language != previous_ui_language {
+        resident::refresh_tray_menu(&app);
+    }
 
     // Trigger WSL auto-sync when wsl_auto_config is enabled and relevant fields changed
     #[cfg(windows)]
@@ -265,13 +401,14 @@ pub(crate) async fn settings_set(
         "settings updated"
     );
 
-    Ok(next_settings)
+    Ok(crate::observer_mode::mask_app_settings(next_settings))
 }
 
 #[tauri::command]
+#[specta::specta]
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn settings_gateway_rectifier_set(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     verbose_provider_error: bool,
     intercept_anthropic_warmup_requests: bool,
     enable_thinking_signature_rectifier: bool,
@@ -283,6 +420,9 @@ pub(crate) async fn settings_gateway_rectifier_set(
     response_fixer_fix_truncated_json: bool,
     response_fixer_max_json_depth: u32,
     response_fixer_max_fix_size: u32,
+    response_fixer_spill_to_disk: Option<bool>,
+    response_fixer_spill_max_size: Option<u32>,
+    response_fixer_transcode_charsets: Option<Vec<settings::ResponseFixerCharset>>,
 ) -> Result<settings::AppSettings, String> {
     let app_for_work = app.clone();
     let result = blocking::run("settings_gateway_rectifier_set", move || {
@@ -301,6 +441,15 @@ pub(crate) async fn settings_gateway_rectifier_set(
         settings.response_fixer_fix_truncated_json = response_fixer_fix_truncated_json;
         settings.response_fixer_max_json_depth = response_fixer_max_json_depth;
         settings.response_fixer_max_fix_size = response_fixer_max_fix_size;
+        if let Some(v) = response_fixer_spill_to_disk {
+            settings.response_fixer_spill_to_disk = v;
+        }
+        if let Some(v) = response_fixer_spill_max_size {
+            settings.response_fixer_spill_max_size = v;
+        }
+        if let Some(v) = response_fixer_transcode_charsets {
+            settings.response_fixer_transcode_charsets = v;
+        }
 
         settings::write(&app_for_work, &settings)
     })
@@ -320,12 +469,13 @@ pub(crate) async fn settings_gateway_rectifier_set(
         );
     }
 
-    result
+    result.map(crate::observer_mode::mask_app_settings)
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn settings_circuit_breaker_notice_set(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     enable_circuit_breaker_notice: bool,
 ) -> Result<settings::AppSettings, String> {
     let app_for_work = app.clone();
@@ -336,12 +486,467 @@ pub(crate) async fn settings_circuit_breaker_notice_set(
         settings::write(&app_for_work, &settings)
     })
     .await
+    .map(crate::observer_mode::mask_app_settings)
     .map_err(Into::into)
 }
 
 #[tauri::command]
+#[specta::specta]
+pub(crate) async fn settings_redaction_rules_set(
+    app: crate::AppHandle,
+    redaction_rules: Vec<settings::RedactionRule>,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    let next_settings = blocking::run("settings_redaction_rules_set", move || {
+        crate::redaction::validate_rules(&redaction_rules)?;
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.redaction_rules = redaction_rules;
+        settings::write(&app_for_work, &settings)
+    })
+    .await?;
+
+    crate::redaction::configure(&next_settings.redaction_rules);
+    tracing::info!(
+        rules = next_settings.redaction_rules.len(),
+        "redaction rules updated"
+    );
+    Ok(crate::observer_mode::mask_app_settings(next_settings))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn settings_env_conflict_ignore_rules_set(
+    app: crate::AppHandle,
+    env_conflict_ignore_rules: Vec<settings::EnvConflictIgnoreRule>,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    let next_settings = blocking::run("settings_env_conflict_ignore_rules_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.env_conflict_ignore_rules = env_conflict_ignore_rules;
+        settings::write(&app_for_work, &settings)
+    })
+    .await?;
+
+    tracing::info!(
+        rules = next_settings.env_conflict_ignore_rules.len(),
+        "env conflict ignore rules updated"
+    );
+    Ok(crate::observer_mode::mask_app_settings(next_settings))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn settings_model_denylist_set(
+    app: crate::AppHandle,
+    model_denylist: Vec<settings::ModelDenyRule>,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    let next_settings = blocking::run("settings_model_denylist_set", move || {
+        if let Some(rule) = model_denylist
+            .iter()
+            .find(|rule| !crate::shared::cli_key::is_supported_cli_key(rule.cli_key.trim()))
+        {
+            return Err(format!(
+                "SEC_INVALID_INPUT: unknown cli_key in model_denylist: {}",
+                rule.cli_key
+            )
+            .into());
+        }
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.model_denylist = model_denylist;
+        settings::write(&app_for_work, &settings)
+    })
+    .await?;
+
+    tracing::info!(
+        rules = next_settings.model_denylist.len(),
+        "model denylist updated"
+    );
+    Ok(crate::observer_mode::mask_app_settings(next_settings))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn settings_model_capabilities_set(
+    app: crate::AppHandle,
+    model_capabilities: Vec<settings::ModelCapabilityRule>,
+    capability_gate_policy: settings::CapabilityGatePolicy,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    let next_settings = blocking::run("settings_model_capabilities_set", move || {
+        if let Some(rule) = model_capabilities
+            .iter()
+            .find(|rule| !crate::shared::cli_key::is_supported_cli_key(rule.cli_key.trim()))
+        {
+            return Err(format!(
+                "SEC_INVALID_INPUT: unknown cli_key in model_capabilities: {}",
+                rule.cli_key
+            )
+            .into());
+        }
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.model_capabilities = model_capabilities;
+        settings.capability_gate_policy = capability_gate_policy;
+        settings::write(&app_for_work, &settings)
+    })
+    .await?;
+
+    tracing::info!(
+        rules = next_settings.model_capabilities.len(),
+        policy = ?next_settings.capability_gate_policy,
+        "model capabilities updated"
+    );
+    Ok(crate::observer_mode::mask_app_settings(next_settings))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn settings_captured_response_headers_set(
+    app: crate::AppHandle,
+    captured_response_headers: Vec<String>,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    let next_settings = blocking::run("settings_captured_response_headers_set", move || {
+        if captured_response_headers.len() > settings::MAX_CAPTURED_RESPONSE_HEADERS {
+            return Err(format!(
+                "SEC_INVALID_INPUT: at most {} captured response headers are allowed",
+                settings::MAX_CAPTURED_RESPONSE_HEADERS
+            )
+            .into());
+        }
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.captured_response_headers =
+            settings::normalize_captured_response_headers(&captured_response_headers);
+        settings::write(&app_for_work, &settings)
+    })
+    .await?;
+
+    crate::gateway::response_headers::configure(&next_settings.captured_response_headers);
+    tracing::info!(
+        headers = next_settings.captured_response_headers.len(),
+        "captured response headers updated"
+    );
+    Ok(crate::observer_mode::mask_app_settings(next_settings))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn settings_upstream_error_body_capture_set(
+    app: crate::AppHandle,
+    upstream_error_body_capture_bytes: u32,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    let next_settings = blocking::run("settings_upstream_error_body_capture_set", move || {
+        if upstream_error_body_capture_bytes > settings::MAX_UPSTREAM_ERROR_BODY_CAPTURE_BYTES {
+            return Err(format!(
+                "SEC_INVALID_INPUT: upstream_error_body_capture_bytes must be <= {}",
+                settings::MAX_UPSTREAM_ERROR_BODY_CAPTURE_BYTES
+            )
+            .into());
+        }
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.upstream_error_body_capture_bytes = upstream_error_body_capture_bytes;
+        settings::write(&app_for_work, &settings)
+    })
+    .await?;
+
+    crate::gateway::error_body_capture::configure(next_settings.upstream_error_body_capture_bytes);
+    tracing::info!(
+        max_bytes = next_settings.upstream_error_body_capture_bytes,
+        "upstream error body capture updated"
+    );
+    Ok(crate::observer_mode::mask_app_settings(next_settings))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn settings_session_binding_ttl_set(
+    app: crate::AppHandle,
+    session_binding_ttl_seconds: u32,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    let next_settings = blocking::run("settings_session_binding_ttl_set", move || {
+        if !(settings::MIN_SESSION_BINDING_TTL_SECONDS..=settings::MAX_SESSION_BINDING_TTL_SECONDS)
+            .contains(&session_binding_ttl_seconds)
+        {
+            return Err(format!(
+                "SEC_INVALID_INPUT: session_binding_ttl_seconds must be between {} and {}",
+                settings::MIN_SESSION_BINDING_TTL_SECONDS,
+                settings::MAX_SESSION_BINDING_TTL_SECONDS
+            )
+            .into());
+        }
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.session_binding_ttl_seconds = session_binding_ttl_seconds;
+        settings::write(&app_for_work, &settings)
+    })
+    .await?;
+
+    {
+        let state = app.state::<crate::app_state::GatewayState>();
+        let manager = state.0.lock_or_recover();
+        manager.set_session_ttl_secs(next_settings.session_binding_ttl_seconds as i64);
+    }
+    tracing::info!(
+        ttl_seconds = next_settings.session_binding_ttl_seconds,
+        "session binding ttl updated"
+    );
+    Ok(crate::observer_mode::mask_app_settings(next_settings))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn settings_retry_backoff_set(
+    app: crate::AppHandle,
+    retry_backoff: settings::RetryBackoffSettings,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    let next_settings = blocking::run("settings_retry_backoff_set", move || {
+        for (name, policy) in [
+            ("rate_limited", retry_backoff.rate_limited),
+            ("upstream_error", retry_backoff.upstream_error),
+            ("network_error", retry_backoff.network_error),
+        ] {
+            if policy.max_ms > settings::MAX_RETRY_BACKOFF_MS || policy.base_ms > policy.max_ms {
+                return Err(format!(
+                    "SEC_INVALID_INPUT: retry_backoff.{name} requires base_ms <= max_ms <= {}",
+                    settings::MAX_RETRY_BACKOFF_MS
+                )
+                .into());
+            }
+        }
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.retry_backoff = retry_backoff;
+        settings::write(&app_for_work, &settings)
+    })
+    .await?;
+
+    tracing::info!(
+        rate_limited = ?next_settings.retry_backoff.rate_limited.strategy,
+        upstream_error = ?next_settings.retry_backoff.upstream_error.strategy,
+        network_error = ?next_settings.retry_backoff.network_error.strategy,
+        "retry backoff updated"
+    );
+    Ok(crate::observer_mode::mask_app_settings(next_settings))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn settings_access_log_set(
+    app: crate::AppHandle,
+    access_log_format: settings::AccessLogFormat,
+    access_log_retention_days: u32,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    let next_settings = blocking::run("settings_access_log_set", move || {
+        if access_log_retention_days == 0
+            || access_log_retention_days > settings::MAX_ACCESS_LOG_RETENTION_DAYS
+        {
+            return Err(format!(
+                "SEC_INVALID_INPUT: access_log_retention_days must be between 1 and {}",
+                settings::MAX_ACCESS_LOG_RETENTION_DAYS
+            )
+            .into());
+        }
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.access_log_format = access_log_format;
+        settings.access_log_retention_days = access_log_retention_days;
+        settings::write(&app_for_work, &settings)
+    })
+    .await?;
+
+    crate::gateway::access_log::configure(
+        &app,
+        next_settings.access_log_format,
+        next_settings.access_log_retention_days,
+    );
+    tracing::info!(
+        format = ?next_settings.access_log_format,
+        retention_days = next_settings.access_log_retention_days,
+        "access log settings updated"
+    );
+    Ok(crate::observer_mode::mask_app_settings(next_settings))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn settings_cost_anomaly_set(
+    app: crate::AppHandle,
+    enable_cost_anomaly_detection: bool,
+    cost_anomaly_multiplier: f64,
+    cost_anomaly_auto_throttle: bool,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    let next_settings = blocking::run("settings_cost_anomaly_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.enable_cost_anomaly_detection = enable_cost_anomaly_detection;
+        settings.cost_anomaly_multiplier = cost_anomaly_multiplier;
+        settings.cost_anomaly_auto_throttle = cost_anomaly_auto_throttle;
+        settings::write(&app_for_work, &settings)
+    })
+    .await?;
+
+    tracing::info!(
+        enabled = next_settings.enable_cost_anomaly_detection,
+        multiplier = next_settings.cost_anomaly_multiplier,
+        auto_throttle = next_settings.cost_anomaly_auto_throttle,
+        "cost anomaly settings updated"
+    );
+    Ok(crate::observer_mode::mask_app_settings(next_settings))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn settings_request_log_sampling_set(
+    app: crate::AppHandle,
+    request_log_sample_percent: u32,
+    request_log_slow_threshold_ms: u32,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    let next_settings = blocking::run("settings_request_log_sampling_set", move || {
+        if !(1..=100).contains(&request_log_sample_percent) {
+            return Err(
+                "SEC_INVALID_INPUT: request_log_sample_percent must be between 1 and 100"
+                    .to_string()
+                    .into(),
+            );
+        }
+        if request_log_slow_threshold_ms == 0
+            || request_log_slow_threshold_ms > settings::MAX_REQUEST_LOG_SLOW_THRESHOLD_MS
+        {
+            return Err(format!(
+                "SEC_INVALID_INPUT: request_log_slow_threshold_ms must be between 1 and {}",
+                settings::MAX_REQUEST_LOG_SLOW_THRESHOLD_MS
+            )
+            .into());
+        }
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.request_log_sample_percent = request_log_sample_percent;
+        settings.request_log_slow_threshold_ms = request_log_slow_threshold_ms;
+        settings::write(&app_for_work, &settings)
+    })
+    .await?;
+
+    crate::request_logs::configure_sampling(
+        next_settings.request_log_sample_percent,
+        next_settings.request_log_slow_threshold_ms,
+    );
+    tracing::info!(
+        sample_percent = next_settings.request_log_sample_percent,
+        slow_threshold_ms = next_settings.request_log_slow_threshold_ms,
+        "request log sampling updated"
+    );
+    Ok(crate::observer_mode::mask_app_settings(next_settings))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn settings_storage_postgres_url_set(
+    app: crate::AppHandle,
+    storage_postgres_url: String,
+) -> Result<settings::AppSettings, String> {
+    // A masked connection URL would be saved back over the real one.
+    crate::observer_mode::ensure_secrets_allowed("settings_storage_postgres_url_set")?;
+    let app_for_work = app.clone();
+    let next_settings = blocking::run("settings_storage_postgres_url_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.storage_postgres_url = storage_postgres_url.trim().to_string();
+        settings::write(&app_for_work, &settings)
+    })
+    .await?;
+
+    tracing::info!(
+        configured = !next_settings.storage_postgres_url.is_empty(),
+        "storage postgres url updated"
+    );
+    Ok(crate::observer_mode::mask_app_settings(next_settings))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn settings_notification_sinks_set(
+    app: crate::AppHandle,
+    notification_sinks: Vec<settings::NotificationSink>,
+) -> Result<settings::AppSettings, String> {
+    // Masked sink URLs would be saved back over the real ones.
+    crate::observer_mode::ensure_secrets_allowed("settings_notification_sinks_set")?;
+    let app_for_work = app.clone();
+    let next_settings = blocking::run("settings_notification_sinks_set", move || {
+        crate::notification_sinks::validate_sinks(&notification_sinks)?;
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.notification_sinks = notification_sinks;
+        settings::write(&app_for_work, &settings)
+    })
+    .await?;
+
+    crate::notification_sinks::configure(&next_settings.notification_sinks);
+    tracing::info!(
+        sinks = next_settings.notification_sinks.len(),
+        "notification sinks updated"
+    );
+    Ok(crate::observer_mode::mask_app_settings(next_settings))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn settings_ssh_hosts_set(
+    app: crate::AppHandle,
+    ssh_hosts: Vec<settings::SshHost>,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    let next_settings = blocking::run("settings_ssh_hosts_set", move || {
+        crate::ssh_clients::validate_hosts(&ssh_hosts)?;
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.ssh_hosts = ssh_hosts;
+        settings::write(&app_for_work, &settings)
+    })
+    .await?;
+
+    tracing::info!(hosts = next_settings.ssh_hosts.len(), "ssh hosts updated");
+    Ok(crate::observer_mode::mask_app_settings(next_settings))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn settings_observer_mode_set(
+    app: crate::AppHandle,
+    observer_mode: bool,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    let next_settings = blocking::run("settings_observer_mode_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.observer_mode = observer_mode;
+        settings::write(&app_for_work, &settings)
+    })
+    .await?;
+
+    crate::observer_mode::configure(next_settings.observer_mode);
+    tracing::info!(
+        enabled = next_settings.observer_mode,
+        "observer mode updated"
+    );
+    Ok(crate::observer_mode::mask_app_settings(next_settings))
+}
+
+#[tauri::command]
+#[specta::specta]
 pub(crate) async fn settings_codex_session_id_completion_set(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     enable_codex_session_id_completion: bool,
 ) -> Result<settings::AppSettings, String> {
     let app_for_work = app.clone();
@@ -352,12 +957,75 @@ pub(crate) async fn settings_codex_session_id_completion_set(
         settings::write(&app_for_work, &settings)
     })
     .await
+    .map(crate::observer_mode::mask_app_settings)
     .map_err(Into::into)
 }
 
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn settings_gateway_access_token_rotate(
+    app: crate::AppHandle,
+) -> Result<settings::AppSettings, String> {
+    crate::observer_mode::ensure_secrets_allowed("settings_gateway_access_token_rotate")?;
+    set_gateway_access_token(app, settings::generate_gateway_access_token()).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn settings_gateway_access_token_clear(
+    app: crate::AppHandle,
+) -> Result<settings::AppSettings, String> {
+    set_gateway_access_token(app, String::new()).await
+}
+
+async fn set_gateway_access_token(
+    app: crate::AppHandle,
+    token: String,
+) -> Result<settings::AppSettings, String> {
+    let app_for_work = app.clone();
+    let next_settings = blocking::run("settings_gateway_access_token_set", move || {
+        let mut settings = settings::read(&app_for_work).unwrap_or_default();
+        settings.schema_version = settings::SCHEMA_VERSION;
+        settings.gateway_access_token = token;
+        settings::write(&app_for_work, &settings)
+    })
+    .await?;
+
+    tracing::info!(
+        enabled = settings::gateway_access_token(&next_settings).is_some(),
+        "gateway access token updated"
+    );
+
+    // Best-effort: rewrite CLI configs so clients keep presenting the current token.
+    let base_origin = {
+        let state = app.state::<crate::app_state::GatewayState>();
+        let manager = state.0.lock_or_recover();
+        manager.status().base_url
+    };
+    if let Some(base_origin) = base_origin {
+        let app_for_sync = app.clone();
+        let _ = blocking::run("cli_proxy_sync_enabled_after_access_token", move || {
+            crate::cli_proxy::sync_enabled(&app_for_sync, &base_origin)
+        })
+        .await;
+    }
+
+    #[cfg(windows)]
+    {
+        let sync_app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(err) = wsl_auto_sync_after_settings(&sync_app).await {
+                tracing::warn!("WSL auto-sync after access token change failed: {}", err);
+            }
+        });
+    }
+
+    Ok(crate::observer_mode::mask_app_settings(next_settings))
+}
+
 /// Background WSL sync triggered after settings change.
 /// Delegates to the shared `wsl_auto_sync_core` which handles all precondition checks.
 #[cfg(windows)]
-async fn wsl_auto_sync_after_settings(app: &tauri::AppHandle) -> Result<(), String> {
+async fn wsl_auto_sync_after_settings(app: &crate::AppHandle) -> Result<(), String> {
     super::wsl::wsl_auto_sync_core(app).await
 }
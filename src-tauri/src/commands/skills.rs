@@ -4,8 +4,9 @@ use crate::app_state::{ensure_db_ready, DbInitState};
 use crate::{blocking, skills};
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn skill_repos_list(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
 ) -> Result<Vec<skills::SkillRepoSummary>, String> {
     let db = ensure_db_ready(app, db_state.inner()).await?;
@@ -15,8 +16,9 @@ pub(crate) async fn skill_repos_list(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn skill_repo_upsert(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     repo_id: Option<i64>,
     git_url: String,
@@ -32,8 +34,9 @@ pub(crate) async fn skill_repo_upsert(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn skill_repo_delete(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     repo_id: i64,
 ) -> Result<bool, String> {
@@ -50,8 +53,42 @@ pub(crate) async fn skill_repo_delete(
 }
 
 #[tauri::command]
+#[specta::specta]
+pub(crate) async fn skill_repo_auth_set(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    repo_id: i64,
+    auth_kind: skills::SkillRepoAuthKind,
+    token: Option<String>,
+    ssh_key_path: Option<String>,
+) -> Result<skills::SkillRepoSummary, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("skill_repo_auth_set", move || {
+        skills::repo_auth_set(&db, repo_id, auth_kind, token, ssh_key_path)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn skill_repo_test_access(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    repo_id: i64,
+) -> Result<skills::SkillRepoAccessReport, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("skill_repo_test_access", move || {
+        skills::repo_test_access(&db, repo_id)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
 pub(crate) async fn skills_installed_list(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     workspace_id: i64,
 ) -> Result<Vec<skills::InstalledSkillSummary>, String> {
@@ -64,8 +101,9 @@ pub(crate) async fn skills_installed_list(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn skills_discover_available(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     refresh: bool,
 ) -> Result<Vec<skills::AvailableSkillSummary>, String> {
@@ -77,9 +115,10 @@ pub(crate) async fn skills_discover_available(
 }
 
 #[tauri::command]
+#[specta::specta]
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn skill_install(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     workspace_id: i64,
     git_url: String,
@@ -105,8 +144,45 @@ pub(crate) async fn skill_install(
 }
 
 #[tauri::command]
+#[specta::specta]
+pub(crate) async fn skill_dependencies_preview(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    workspace_id: i64,
+    git_url: String,
+    branch: String,
+    source_subdir: String,
+) -> Result<skills::SkillDependencyReport, String> {
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    tauri::async_runtime::spawn_blocking(move || {
+        skills::dependencies_preview(&app, &db, workspace_id, &git_url, &branch, &source_subdir)
+    })
+    .await
+    .map_err(|e| format!("SKILL_TASK_JOIN: {e}"))?
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn skill_dependencies_check(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    workspace_id: i64,
+    skill_id: i64,
+) -> Result<skills::SkillDependencyReport, String> {
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    tauri::async_runtime::spawn_blocking(move || {
+        skills::dependencies_check(&app, &db, workspace_id, skill_id)
+    })
+    .await
+    .map_err(|e| format!("SKILL_TASK_JOIN: {e}"))?
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
 pub(crate) async fn skill_set_enabled(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     workspace_id: i64,
     skill_id: i64,
@@ -122,8 +198,9 @@ pub(crate) async fn skill_set_enabled(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn skill_uninstall(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     skill_id: i64,
 ) -> Result<bool, String> {
@@ -135,8 +212,9 @@ pub(crate) async fn skill_uninstall(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn skill_return_to_local(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     workspace_id: i64,
     skill_id: i64,
@@ -151,8 +229,9 @@ pub(crate) async fn skill_return_to_local(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn skills_local_list(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     workspace_id: i64,
 ) -> Result<Vec<skills::LocalSkillSummary>, String> {
@@ -164,8 +243,9 @@ pub(crate) async fn skills_local_list(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn skill_import_local(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     workspace_id: i64,
     dir_name: String,
@@ -180,8 +260,9 @@ pub(crate) async fn skill_import_local(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn skills_import_local_batch(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     workspace_id: i64,
     dir_names: Vec<String>,
@@ -196,8 +277,70 @@ pub(crate) async fn skills_import_local_batch(
 }
 
 #[tauri::command]
+#[specta::specta]
+pub(crate) async fn skills_check_updates(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+) -> Result<Vec<skills::SkillUpdateStatus>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    tauri::async_runtime::spawn_blocking(move || skills::check_updates(&db))
+        .await
+        .map_err(|e| format!("SKILL_TASK_JOIN: {e}"))?
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn skill_set_pin(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    skill_id: i64,
+    pinned_ref: Option<String>,
+) -> Result<skills::InstalledSkillSummary, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("skill_set_pin", move || {
+        skills::set_pin(&db, skill_id, pinned_ref.as_deref())
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn skill_update(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    skill_id: i64,
+    dry_run: bool,
+) -> Result<skills::SkillUpdateReport, String> {
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    tauri::async_runtime::spawn_blocking(move || skills::update(&app, &db, skill_id, dry_run))
+        .await
+        .map_err(|e| format!("SKILL_TASK_JOIN: {e}"))?
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn skill_package(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    source_dir: String,
+    output_dir: Option<String>,
+    push_repo_id: Option<i64>,
+) -> Result<skills::SkillPackageReport, String> {
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    blocking::run("skill_package", move || {
+        skills::package(&app, &db, &source_dir, output_dir.as_deref(), push_repo_id)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
 pub(crate) async fn skills_paths_get(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     cli_key: String,
 ) -> Result<skills::SkillsPaths, String> {
     blocking::run("skills_paths_get", move || {
@@ -1,12 +1,19 @@
 //! Usage: Provider sort modes related Tauri commands.
 
 use crate::app_state::{ensure_db_ready, DbInitState, GatewayState};
+use crate::shared::error::AppResult;
 use crate::shared::mutex_ext::MutexExt;
-use crate::{blocking, sort_modes};
+use crate::{audit_log, blocking, provider_scores, sort_mode_schedules, sort_modes};
+
+/// `None` is the default provider ordering.
+fn format_mode_id(mode_id: Option<i64>) -> String {
+    mode_id.map_or_else(|| "default".to_string(), |id| id.to_string())
+}
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn sort_modes_list(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
 ) -> Result<Vec<sort_modes::SortModeSummary>, String> {
     let db = ensure_db_ready(app, db_state.inner()).await?;
@@ -16,45 +23,108 @@ pub(crate) async fn sort_modes_list(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn sort_mode_create(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     name: String,
 ) -> Result<sort_modes::SortModeSummary, String> {
+    let origin = audit_log::current_origin();
     let db = ensure_db_ready(app, db_state.inner()).await?;
-    blocking::run("sort_mode_create", move || {
-        sort_modes::create_mode(&db, &name)
-    })
+    blocking::run(
+        "sort_mode_create",
+        move || -> AppResult<sort_modes::SortModeSummary> {
+            let mode = sort_modes::create_mode(&db, &name)?;
+            audit_log::record(
+                &db,
+                origin,
+                "sort_mode_create",
+                Some(format!("sort_mode:{}", mode.id)),
+                format!("created {:?}", mode.name),
+            );
+            Ok(mode)
+        },
+    )
     .await
     .map_err(Into::into)
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn sort_mode_rename(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     mode_id: i64,
     name: String,
 ) -> Result<sort_modes::SortModeSummary, String> {
+    let origin = audit_log::current_origin();
     let db = ensure_db_ready(app, db_state.inner()).await?;
-    blocking::run("sort_mode_rename", move || {
-        sort_modes::rename_mode(&db, mode_id, &name)
-    })
+    blocking::run(
+        "sort_mode_rename",
+        move || -> AppResult<sort_modes::SortModeSummary> {
+            let mode = sort_modes::rename_mode(&db, mode_id, &name)?;
+            audit_log::record(
+                &db,
+                origin,
+                "sort_mode_rename",
+                Some(format!("sort_mode:{mode_id}")),
+                format!("name: {:?}", mode.name),
+            );
+            Ok(mode)
+        },
+    )
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn sort_mode_set_strategy(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    mode_id: i64,
+    strategy: String,
+) -> Result<sort_modes::SortModeSummary, String> {
+    let origin = audit_log::current_origin();
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run(
+        "sort_mode_set_strategy",
+        move || -> AppResult<sort_modes::SortModeSummary> {
+            let mode = sort_modes::set_mode_strategy(&db, mode_id, &strategy)?;
+            audit_log::record(
+                &db,
+                origin,
+                "sort_mode_set_strategy",
+                Some(format!("sort_mode:{mode_id}")),
+                format!("strategy: {}", mode.strategy),
+            );
+            Ok(mode)
+        },
+    )
     .await
     .map_err(Into::into)
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn sort_mode_delete(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     mode_id: i64,
 ) -> Result<bool, String> {
+    let origin = audit_log::current_origin();
     let db = ensure_db_ready(app, db_state.inner()).await?;
     blocking::run(
         "sort_mode_delete",
         move || -> crate::shared::error::AppResult<bool> {
             sort_modes::delete_mode(&db, mode_id)?;
+            audit_log::record(
+                &db,
+                origin,
+                "sort_mode_delete",
+                Some(format!("sort_mode:{mode_id}")),
+                "deleted".to_string(),
+            );
             Ok(true)
         },
     )
@@ -63,8 +133,9 @@ pub(crate) async fn sort_mode_delete(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn sort_mode_active_list(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
 ) -> Result<Vec<sort_modes::SortModeActiveRow>, String> {
     let db = ensure_db_ready(app, db_state.inner()).await?;
@@ -76,18 +147,31 @@ pub(crate) async fn sort_mode_active_list(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn sort_mode_active_set(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     gateway_state: tauri::State<'_, GatewayState>,
     cli_key: String,
     mode_id: Option<i64>,
 ) -> Result<sort_modes::SortModeActiveRow, String> {
+    let origin = audit_log::current_origin();
     let db = ensure_db_ready(app, db_state.inner()).await?;
     let cli_key_for_db = cli_key.clone();
-    let row = blocking::run("sort_mode_active_set", move || {
-        sort_modes::set_active(&db, &cli_key_for_db, mode_id)
-    })
+    let row = blocking::run(
+        "sort_mode_active_set",
+        move || -> AppResult<sort_modes::SortModeActiveRow> {
+            let row = sort_modes::set_active(&db, &cli_key_for_db, mode_id)?;
+            audit_log::record(
+                &db,
+                origin,
+                "sort_mode_active_set",
+                Some(format!("cli:{cli_key_for_db}")),
+                format!("active mode: {}", format_mode_id(mode_id)),
+            );
+            Ok(row)
+        },
+    )
     .await?;
 
     {
@@ -99,8 +183,9 @@ pub(crate) async fn sort_mode_active_set(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn sort_mode_providers_list(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     mode_id: i64,
     cli_key: String,
@@ -114,34 +199,232 @@ pub(crate) async fn sort_mode_providers_list(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn sort_mode_providers_set_order(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     mode_id: i64,
     cli_key: String,
     ordered_provider_ids: Vec<i64>,
 ) -> Result<Vec<sort_modes::SortModeProviderRow>, String> {
+    let origin = audit_log::current_origin();
     let db = ensure_db_ready(app, db_state.inner()).await?;
-    blocking::run("sort_mode_providers_set_order", move || {
-        sort_modes::set_mode_providers_order(&db, mode_id, &cli_key, ordered_provider_ids)
+    blocking::run(
+        "sort_mode_providers_set_order",
+        move || -> AppResult<Vec<sort_modes::SortModeProviderRow>> {
+            let summary = format!("order: {ordered_provider_ids:?}");
+            let rows =
+                sort_modes::set_mode_providers_order(&db, mode_id, &cli_key, ordered_provider_ids)?;
+            audit_log::record(
+                &db,
+                origin,
+                "sort_mode_providers_set_order",
+                Some(format!("sort_mode:{mode_id}/cli:{cli_key}")),
+                summary,
+            );
+            Ok(rows)
+        },
+    )
+    .await
+    .map_err(Into::into)
+}
+
+/// Ranks the providers of `mode_id` (or the default order) by score; apply the result with
+/// `sort_mode_providers_set_order` / `providers_reorder`.
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn sort_mode_suggest_order(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: String,
+    mode_id: Option<i64>,
+) -> Result<provider_scores::SortOrderSuggestion, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("sort_mode_suggest_order", move || {
+        provider_scores::suggest_order(&db, &cli_key, mode_id)
     })
     .await
     .map_err(Into::into)
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn sort_mode_provider_set_enabled(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     mode_id: i64,
     cli_key: String,
     provider_id: i64,
     enabled: bool,
 ) -> Result<sort_modes::SortModeProviderRow, String> {
+    let origin = audit_log::current_origin();
     let db = ensure_db_ready(app, db_state.inner()).await?;
-    blocking::run("sort_mode_provider_set_enabled", move || {
-        sort_modes::set_mode_provider_enabled(&db, mode_id, &cli_key, provider_id, enabled)
+    blocking::run(
+        "sort_mode_provider_set_enabled",
+        move || -> AppResult<sort_modes::SortModeProviderRow> {
+            let row = sort_modes::set_mode_provider_enabled(
+                &db,
+                mode_id,
+                &cli_key,
+                provider_id,
+                enabled,
+            )?;
+            audit_log::record(
+                &db,
+                origin,
+                "sort_mode_provider_set_enabled",
+                Some(format!("sort_mode:{mode_id}/cli:{cli_key}")),
+                format!("provider {provider_id} enabled: {enabled}"),
+            );
+            Ok(row)
+        },
+    )
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn sort_mode_provider_set_time_window(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    mode_id: i64,
+    cli_key: String,
+    provider_id: i64,
+    active_start: Option<String>,
+    active_end: Option<String>,
+) -> Result<sort_modes::SortModeProviderRow, String> {
+    let origin = audit_log::current_origin();
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run(
+        "sort_mode_provider_set_time_window",
+        move || -> AppResult<sort_modes::SortModeProviderRow> {
+            let row = sort_modes::set_mode_provider_time_window(
+                &db,
+                mode_id,
+                &cli_key,
+                provider_id,
+                active_start.as_deref(),
+                active_end.as_deref(),
+            )?;
+            audit_log::record(
+                &db,
+                origin,
+                "sort_mode_provider_set_time_window",
+                Some(format!("sort_mode:{mode_id}/cli:{cli_key}")),
+                format!(
+                    "provider {provider_id} window: {:?} → {:?}",
+                    row.active_start, row.active_end
+                ),
+            );
+            Ok(row)
+        },
+    )
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn sort_mode_fallback_get(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: String,
+) -> Result<sort_modes::SortModeFallbackChain, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("sort_mode_fallback_get", move || {
+        sort_modes::get_fallback_chain(&db, &cli_key)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn sort_mode_fallback_set(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: String,
+    mode_ids: Vec<Option<i64>>,
+) -> Result<sort_modes::SortModeFallbackChain, String> {
+    let origin = audit_log::current_origin();
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run(
+        "sort_mode_fallback_set",
+        move || -> AppResult<sort_modes::SortModeFallbackChain> {
+            let chain = sort_modes::set_fallback_chain(&db, &cli_key, mode_ids)?;
+            let modes: Vec<String> = chain.mode_ids.iter().copied().map(format_mode_id).collect();
+            audit_log::record(
+                &db,
+                origin,
+                "sort_mode_fallback_set",
+                Some(format!("cli:{cli_key}")),
+                format!("fallback chain: [{}]", modes.join(", ")),
+            );
+            Ok(chain)
+        },
+    )
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn sort_mode_schedule_get(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: String,
+) -> Result<sort_mode_schedules::SortModeSchedule, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("sort_mode_schedule_get", move || {
+        sort_mode_schedules::get_schedule(&db, &cli_key)
     })
     .await
     .map_err(Into::into)
 }
+
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn sort_mode_schedule_set(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: String,
+    enabled: bool,
+    utc_offset_minutes: Option<i32>,
+    rules: Vec<sort_mode_schedules::SortModeScheduleRule>,
+    otherwise_enabled: bool,
+    otherwise_mode_id: Option<i64>,
+) -> Result<sort_mode_schedules::SortModeSchedule, String> {
+    let origin = audit_log::current_origin();
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    let schedule = blocking::run(
+        "sort_mode_schedule_set",
+        move || -> AppResult<sort_mode_schedules::SortModeSchedule> {
+            let rule_count = rules.len();
+            let schedule = sort_mode_schedules::set_schedule(
+                &db,
+                &cli_key,
+                enabled,
+                utc_offset_minutes,
+                rules,
+                otherwise_enabled,
+                otherwise_mode_id,
+            )?;
+            audit_log::record(
+                &db,
+                origin,
+                "sort_mode_schedule_set",
+                Some(format!("cli:{cli_key}")),
+                format!("enabled: {enabled}; rules: {rule_count}"),
+            );
+            Ok(schedule)
+        },
+    )
+    .await?;
+
+    // Apply the edited schedule on the scheduler's next tick instead of waiting for a boundary.
+    crate::app::sort_mode_scheduler::invalidate(schedule.cli_key.as_str());
+    Ok(schedule)
+}
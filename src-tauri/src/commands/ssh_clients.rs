@@ -0,0 +1,110 @@
+//! Usage: SSH remote host related Tauri commands.
+
+use crate::app_state::GatewayState;
+use crate::shared::mutex_ext::MutexExt;
+use crate::{blocking, settings, ssh_clients};
+use tauri::Manager;
+
+fn running_gateway_port(app: &crate::AppHandle) -> Option<u16> {
+    let state = app.state::<GatewayState>();
+    let manager = state.0.lock_or_recover();
+    manager.status().port
+}
+
+fn find_host(app: &crate::AppHandle, name: &str) -> Result<settings::SshHost, String> {
+    let cfg = settings::read(app).unwrap_or_default();
+    cfg.ssh_hosts
+        .into_iter()
+        .find(|host| host.name == name)
+        .ok_or_else(|| format!("SEC_INVALID_INPUT: unknown ssh host={name}"))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn ssh_host_check(
+    app: crate::AppHandle,
+    name: String,
+) -> Result<ssh_clients::SshHostCheckReport, String> {
+    let host = find_host(&app, name.trim())?;
+    let port = running_gateway_port(&app).ok_or_else(|| "gateway is not running".to_string())?;
+    let report = blocking::run(
+        "ssh_host_check",
+        move || -> crate::shared::error::AppResult<ssh_clients::SshHostCheckReport> {
+            Ok(ssh_clients::check(&host, port))
+        },
+    )
+    .await?;
+    tracing::info!(
+        host = %report.name,
+        ssh_ok = report.ssh_ok,
+        gateway_reachable = report.gateway_reachable,
+        "ssh host check finished"
+    );
+    Ok(report)
+}
+
+/// Configures one host by name, or every configured host when `name` is omitted.
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn ssh_configure_clients(
+    app: crate::AppHandle,
+    name: Option<String>,
+) -> Result<ssh_clients::SshConfigureReport, String> {
+    let hosts = match name.as_deref().map(str::trim) {
+        Some(name) => vec![find_host(&app, name)?],
+        None => settings::read(&app).unwrap_or_default().ssh_hosts,
+    };
+    if hosts.is_empty() {
+        return Ok(ssh_clients::SshConfigureReport {
+            ok: false,
+            message: "no ssh hosts configured".to_string(),
+            hosts: Vec::new(),
+        });
+    }
+    let port = running_gateway_port(&app).ok_or_else(|| "gateway is not running".to_string())?;
+
+    let app_for_sync = app.clone();
+    let report = blocking::run(
+        "ssh_configure_clients",
+        move || -> crate::shared::error::AppResult<ssh_clients::SshConfigureReport> {
+            Ok(ssh_clients::configure_clients(&app_for_sync, &hosts, port))
+        },
+    )
+    .await?;
+    tracing::info!(ok = report.ok, message = %report.message, "ssh configure clients finished");
+    Ok(report)
+}
+
+/// Re-points `auto_sync` hosts at the running gateway (startup and port changes).
+pub(crate) async fn ssh_auto_sync_core(app: &crate::AppHandle) -> Result<(), String> {
+    let hosts: Vec<settings::SshHost> = settings::read(app)
+        .unwrap_or_default()
+        .ssh_hosts
+        .into_iter()
+        .filter(|host| host.auto_sync)
+        .collect();
+    if hosts.is_empty() {
+        return Ok(());
+    }
+    let Some(port) = running_gateway_port(app) else {
+        tracing::debug!("SSH auto-sync: gateway not running, skipping");
+        return Ok(());
+    };
+
+    let app_for_sync = app.clone();
+    let report = blocking::run(
+        "ssh_auto_sync",
+        move || -> crate::shared::error::AppResult<ssh_clients::SshConfigureReport> {
+            Ok(ssh_clients::configure_clients(&app_for_sync, &hosts, port))
+        },
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    tracing::info!(
+        ok = report.ok,
+        message = %report.message,
+        "SSH auto-sync completed"
+    );
+    Ok(())
+}
@@ -0,0 +1,108 @@
+//! Usage: Claude Code subagent management related Tauri commands.
+
+use crate::app_state::{ensure_db_ready, DbInitState};
+use crate::{blocking, subagents};
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn subagents_list(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    workspace_id: i64,
+) -> Result<Vec<subagents::SubagentSummary>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("subagents_list", move || {
+        subagents::list_for_workspace(&db, workspace_id)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn subagents_upsert(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    subagent_id: Option<i64>,
+    workspace_id: i64,
+    name: String,
+    description: String,
+    prompt: String,
+    tools: Vec<String>,
+    model: Option<String>,
+) -> Result<subagents::SubagentSummary, String> {
+    #[cfg(windows)]
+    let app_for_wsl = app.clone();
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    let result = blocking::run("subagents_upsert", move || {
+        subagents::upsert(
+            &app,
+            &db,
+            subagent_id,
+            workspace_id,
+            &name,
+            &description,
+            &prompt,
+            &tools,
+            model.as_deref(),
+        )
+    })
+    .await
+    .map_err(Into::into);
+    #[cfg(windows)]
+    if result.is_ok() {
+        super::wsl::wsl_sync_trigger::trigger(app_for_wsl);
+    }
+    result
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn subagents_set_enabled(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    workspace_id: i64,
+    subagent_id: i64,
+    enabled: bool,
+) -> Result<subagents::SubagentSummary, String> {
+    #[cfg(windows)]
+    let app_for_wsl = app.clone();
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    let result = blocking::run("subagents_set_enabled", move || {
+        subagents::set_enabled(&app, &db, workspace_id, subagent_id, enabled)
+    })
+    .await
+    .map_err(Into::into);
+    #[cfg(windows)]
+    if result.is_ok() {
+        super::wsl::wsl_sync_trigger::trigger(app_for_wsl);
+    }
+    result
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn subagents_delete(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    subagent_id: i64,
+) -> Result<bool, String> {
+    #[cfg(windows)]
+    let app_for_wsl = app.clone();
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    let result = blocking::run(
+        "subagents_delete",
+        move || -> crate::shared::error::AppResult<bool> {
+            subagents::delete(&app, &db, subagent_id)?;
+            Ok(true)
+        },
+    )
+    .await
+    .map_err(Into::into);
+    #[cfg(windows)]
+    if result.is_ok() {
+        super::wsl::wsl_sync_trigger::trigger(app_for_wsl);
+    }
+    result
+}
@@ -4,8 +4,9 @@ use crate::app_state::{ensure_db_ready, DbInitState};
 use crate::{blocking, usage_stats};
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn usage_summary(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     range: String,
     cli_key: Option<String>,
@@ -19,8 +20,9 @@ pub(crate) async fn usage_summary(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn usage_summary_v2(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     params: usage_stats::UsageQueryParams,
 ) -> Result<usage_stats::UsageSummary, String> {
@@ -33,8 +35,9 @@ pub(crate) async fn usage_summary_v2(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn usage_leaderboard_provider(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     range: String,
     cli_key: Option<String>,
@@ -50,8 +53,9 @@ pub(crate) async fn usage_leaderboard_provider(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn usage_leaderboard_day(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     range: String,
     cli_key: Option<String>,
@@ -67,8 +71,9 @@ pub(crate) async fn usage_leaderboard_day(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn usage_leaderboard_v2(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     scope: String,
     params: usage_stats::UsageQueryParams,
@@ -84,8 +89,9 @@ pub(crate) async fn usage_leaderboard_v2(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn usage_hourly_series(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     days: u32,
 ) -> Result<Vec<usage_stats::UsageHourlyRow>, String> {
@@ -99,8 +105,9 @@ pub(crate) async fn usage_hourly_series(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn usage_provider_cache_rate_trend_v1(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     params: usage_stats::UsageQueryParams,
     limit: Option<u32>,
@@ -114,3 +121,48 @@ pub(crate) async fn usage_provider_cache_rate_trend_v1(
     .await
     .map_err(Into::into)
 }
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn usage_forecast_v1(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: Option<String>,
+) -> Result<usage_stats::UsageForecastV1, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("usage_forecast_v1", move || {
+        usage_stats::usage_forecast_v1(&db, cli_key.as_deref())
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn usage_bandwidth_v1(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    params: usage_stats::UsageQueryParams,
+) -> Result<Vec<usage_stats::UsageBandwidthRowV1>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("usage_bandwidth_v1", move || {
+        usage_stats::usage_bandwidth_v1(&db, &params)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn usage_heatmap_v1(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    params: usage_stats::UsageQueryParams,
+) -> Result<Vec<usage_stats::UsageHeatmapCellV1>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("usage_heatmap_v1", move || {
+        usage_stats::usage_heatmap_v1(&db, &params)
+    })
+    .await
+    .map_err(Into::into)
+}
@@ -1,11 +1,12 @@
 //! Usage: Workspace (profile) related Tauri commands.
 
 use crate::app_state::{ensure_db_ready, DbInitState};
-use crate::{blocking, workspace_switch, workspaces};
+use crate::{blocking, workspace_snapshots, workspace_switch, workspaces};
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn workspaces_list(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     cli_key: String,
 ) -> Result<workspaces::WorkspacesListResult, String> {
@@ -18,8 +19,9 @@ pub(crate) async fn workspaces_list(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn workspace_create(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     cli_key: String,
     name: String,
@@ -34,8 +36,9 @@ pub(crate) async fn workspace_create(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn workspace_rename(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     workspace_id: i64,
     name: String,
@@ -49,8 +52,9 @@ pub(crate) async fn workspace_rename(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn workspace_delete(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     workspace_id: i64,
 ) -> Result<bool, String> {
@@ -63,8 +67,9 @@ pub(crate) async fn workspace_delete(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn workspace_preview(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     workspace_id: i64,
 ) -> Result<workspace_switch::WorkspacePreview, String> {
@@ -77,8 +82,9 @@ pub(crate) async fn workspace_preview(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn workspace_apply(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
     workspace_id: i64,
 ) -> Result<workspace_switch::WorkspaceApplyReport, String> {
@@ -89,3 +95,33 @@ pub(crate) async fn workspace_apply(
     .await
     .map_err(Into::into)
 }
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn workspace_snapshot_list(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    cli_key: String,
+) -> Result<Vec<workspace_snapshots::WorkspaceSnapshotSummary>, String> {
+    let db = ensure_db_ready(app, db_state.inner()).await?;
+    blocking::run("workspace_snapshot_list", move || {
+        workspace_snapshots::list(&db, &cli_key)
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn workspace_rollback(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+    snapshot_id: i64,
+) -> Result<workspace_snapshots::WorkspaceRollbackReport, String> {
+    let db = ensure_db_ready(app.clone(), db_state.inner()).await?;
+    blocking::run("workspace_rollback", move || {
+        workspace_snapshots::rollback(&app, &db, snapshot_id)
+    })
+    .await
+    .map_err(Into::into)
+}
@@ -10,6 +10,7 @@ use tauri::Emitter;
 use tauri::Manager;
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn wsl_detect() -> wsl::WslDetection {
     blocking::run(
         "wsl_detect",
@@ -23,6 +24,7 @@ pub(crate) async fn wsl_detect() -> wsl::WslDetection {
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn wsl_host_address_get() -> Option<String> {
     blocking::run(
         "wsl_host_address_get",
@@ -35,6 +37,7 @@ pub(crate) async fn wsl_host_address_get() -> Option<String> {
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn wsl_config_status_get(
     distros: Option<Vec<String>>,
 ) -> Vec<wsl::WslDistroConfigStatus> {
@@ -61,8 +64,9 @@ pub(crate) async fn wsl_config_status_get(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub(crate) async fn wsl_configure_clients(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db_state: tauri::State<'_, DbInitState>,
 ) -> Result<wsl::WslConfigureReport, String> {
     if !cfg!(windows) {
@@ -177,11 +181,113 @@ pub(crate) async fn wsl_configure_clients(
     Ok(report)
 }
 
+/// Host WSL distros should use for the current listen mode (`None` = invalid custom address).
+fn wsl_gateway_host(cfg: &settings::AppSettings) -> Option<String> {
+    match cfg.gateway_listen_mode {
+        settings::GatewayListenMode::Localhost => Some("127.0.0.1".to_string()),
+        settings::GatewayListenMode::WslAuto | settings::GatewayListenMode::Lan => {
+            Some(wsl::resolve_wsl_host(cfg))
+        }
+        settings::GatewayListenMode::Custom => {
+            let parsed =
+                gateway::listen::parse_custom_listen_address(&cfg.gateway_custom_listen_address)
+                    .ok()?;
+            if gateway::listen::is_wildcard_host(&parsed.host) {
+                Some(wsl::resolve_wsl_host(cfg))
+            } else {
+                Some(parsed.host)
+            }
+        }
+    }
+}
+
+fn running_gateway_port(app: &crate::AppHandle) -> Option<u16> {
+    let state = app.state::<GatewayState>();
+    let manager = state.0.lock_or_recover();
+    manager.status().port
+}
+
+pub(crate) async fn run_wsl_diagnose(
+    app: &crate::AppHandle,
+) -> Result<wsl::WslDiagnosticsReport, String> {
+    let port = running_gateway_port(app);
+    blocking::run("wsl_diagnose", {
+        let app = app.clone();
+        move || -> crate::shared::error::AppResult<wsl::WslDiagnosticsReport> {
+            let cfg = settings::read(&app).unwrap_or_default();
+            let host = wsl_gateway_host(&cfg);
+            Ok(wsl::diagnose(&wsl::WslDiagnoseInput {
+                listen_mode: cfg.gateway_listen_mode,
+                host: host.as_deref(),
+                port,
+                targets: &cfg.wsl_target_cli,
+            }))
+        }
+    })
+    .await
+    .map_err(Into::into)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn wsl_diagnose(
+    app: crate::AppHandle,
+) -> Result<wsl::WslDiagnosticsReport, String> {
+    run_wsl_diagnose(&app).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub(crate) async fn wsl_repair(
+    app: crate::AppHandle,
+    db_state: tauri::State<'_, DbInitState>,
+) -> Result<wsl::WslRepairReport, String> {
+    let before = run_wsl_diagnose(&app).await?;
+
+    let mut outcomes = Vec::new();
+    for action in before.repair_actions() {
+        let result = match action {
+            wsl::WslRepairAction::AddFirewallRule => match running_gateway_port(&app) {
+                Some(port) => blocking::run("wsl_repair_firewall_rule", move || {
+                    wsl::ensure_gateway_firewall_rule(port)
+                })
+                .await
+                .map(|()| format!("inbound firewall rule added for TCP {port}"))
+                .map_err(|e| e.to_string()),
+                None => Err("gateway is not running".to_string()),
+            },
+            wsl::WslRepairAction::ConfigureClients => {
+                match wsl_configure_clients(app.clone(), db_state.clone()).await {
+                    Ok(report) if report.ok => Ok(report.message),
+                    Ok(report) => Err(report.message),
+                    Err(err) => Err(err),
+                }
+            }
+        };
+        let (ok, message) = match result {
+            Ok(message) => (true, message),
+            Err(message) => (false, message),
+        };
+        tracing::info!(action = ?action, ok, message = %message, "wsl repair step finished");
+        outcomes.push(wsl::WslRepairOutcome {
+            action,
+            ok,
+            message,
+        });
+    }
+
+    let diagnostics = run_wsl_diagnose(&app).await?;
+    Ok(wsl::WslRepairReport {
+        outcomes,
+        diagnostics,
+    })
+}
+
 /// Core WSL auto-sync logic shared by settings-change sync and MCP/Prompt-change sync.
 /// Checks preconditions (wsl_auto_config enabled, listen mode != Localhost),
 /// detects WSL, resolves host, gathers sync data, and configures CLI clients.
 #[cfg(windows)]
-pub(crate) async fn wsl_auto_sync_core(app: &tauri::AppHandle) -> Result<(), String> {
+pub(crate) async fn wsl_auto_sync_core(app: &crate::AppHandle) -> Result<(), String> {
     use crate::app_state::{ensure_db_ready, DbInitState, GatewayState};
     use crate::shared::mutex_ext::MutexExt;
 
@@ -317,14 +423,14 @@ pub(crate) mod wsl_sync_trigger {
 
     /// Fire-and-forget trigger. Notifies the background debounce task to schedule a WSL sync.
     /// If the background task hasn't been spawned yet, it will be spawned on first call.
-    pub(crate) fn trigger(app: tauri::AppHandle) {
+    pub(crate) fn trigger(app: crate::AppHandle) {
         if !TASK_SPAWNED.swap(true, Ordering::SeqCst) {
             tauri::async_runtime::spawn(debounce_loop(app));
         }
         trigger_notify().notify_one();
     }
 
-    async fn debounce_loop(app: tauri::AppHandle) {
+    async fn debounce_loop(app: crate::AppHandle) {
         const DEBOUNCE: Duration = Duration::from_millis(500);
         let notify = trigger_notify();
 
@@ -350,7 +456,7 @@ pub(crate) mod wsl_sync_trigger {
 /// If the current listen mode is localhost, emit an event to prompt the user to switch.
 #[cfg(windows)]
 pub(crate) async fn wsl_auto_configure_on_startup(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     db: db::Db,
     listen_mode: settings::GatewayListenMode,
     gateway_port: Option<u16>,
@@ -389,7 +495,7 @@ pub(crate) async fn wsl_auto_configure_on_startup(
 
 #[cfg(windows)]
 async fn do_wsl_auto_configure(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     db: db::Db,
     distros: &[String],
     listen_mode: settings::GatewayListenMode,
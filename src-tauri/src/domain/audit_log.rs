@@ -0,0 +1,391 @@
+//! Usage: Audit trail of configuration changes (`config_audit_log` table).
+//!
+//! Mutating commands (provider upserts, settings, sort modes, CLI proxy toggles) record one row
+//! each with a short diff summary and where the change came from: the desktop UI, the REST admin
+//! API, or a headless instance. Recording is best-effort; a failed insert is logged and never
+//! fails the change itself. Secret-looking fields are reported as changed without their values.
+
+use crate::app_state::{ensure_db_ready, DbInitState};
+use crate::shared::error::{db_err, AppError};
+use crate::shared::time::now_unix_seconds;
+use crate::{blocking, db};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use tauri::Manager;
+
+const KEEP_MAX_ROWS: i64 = 10_000;
+const MAX_VALUE_CHARS: usize = 60;
+const MAX_SUMMARY_CHARS: usize = 2_000;
+const IGNORED_KEYS: &[&str] = &["created_at", "updated_at", "schema_version"];
+const SECRET_KEY_MARKERS: &[&str] = &["token", "api_key", "secret", "password"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOrigin {
+    Ui,
+    Rest,
+    Headless,
+}
+
+impl AuditOrigin {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Ui => "ui",
+            Self::Rest => "rest",
+            Self::Headless => "headless",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "ui" => Some(Self::Ui),
+            "rest" => Some(Self::Rest),
+            "headless" => Some(Self::Headless),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct AuditLogEntry {
+    pub id: i64,
+    pub created_at: i64,
+    pub command: String,
+    pub origin: AuditOrigin,
+    /// What was changed, e.g. `provider:12`, `sort_mode:3` or `cli:claude`.
+    pub target: Option<String>,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogFilter {
+    pub command: Option<String>,
+    pub origin: Option<AuditOrigin>,
+    pub target: Option<String>,
+    pub since_unix: Option<i64>,
+    pub until_unix: Option<i64>,
+    pub limit: usize,
+}
+
+tokio::task_local! {
+    static REQUEST_ORIGIN: AuditOrigin;
+}
+
+/// Runs `fut` with `origin` as the origin of any change it records (used by the admin API).
+pub(crate) async fn with_origin<F: Future>(origin: AuditOrigin, fut: F) -> F::Output {
+    REQUEST_ORIGIN.scope(origin, fut).await
+}
+
+/// Origin of the running command. Task-locals do not reach `spawn_blocking`, so callers read
+/// this in the async command body and pass it down.
+pub(crate) fn current_origin() -> AuditOrigin {
+    REQUEST_ORIGIN
+        .try_with(|origin| *origin)
+        .unwrap_or_else(|_| {
+            if crate::app::headless::is_active() {
+                AuditOrigin::Headless
+            } else {
+                AuditOrigin::Ui
+            }
+        })
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_KEY_MARKERS.iter().any(|marker| key.contains(marker))
+}
+
+fn truncate_chars(value: &str, max: usize) -> String {
+    if value.chars().count() <= max {
+        return value.to_string();
+    }
+    let mut out: String = value.chars().take(max).collect();
+    out.push('…');
+    out
+}
+
+fn display_value(value: Option<&serde_json::Value>) -> String {
+    let text = match value {
+        None | Some(serde_json::Value::Null) => "null".to_string(),
+        Some(serde_json::Value::String(s)) => format!("{s:?}"),
+        Some(other) => other.to_string(),
+    };
+    truncate_chars(&text, MAX_VALUE_CHARS)
+}
+
+/// One `key: old → new` line per changed top-level field, sorted by key.
+pub(crate) fn summarize_diff(before: &serde_json::Value, after: &serde_json::Value) -> String {
+    let empty = serde_json::Map::new();
+    let before = before.as_object().unwrap_or(&empty);
+    let after = after.as_object().unwrap_or(&empty);
+
+    let mut keys: Vec<&String> = before.keys().chain(after.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    let changes: Vec<String> = keys
+        .into_iter()
+        .filter(|key| !IGNORED_KEYS.contains(&key.as_str()))
+        .filter(|key| before.get(key.as_str()) != after.get(key.as_str()))
+        .map(|key| {
+            if is_secret_key(key) {
+                format!("{key}: changed")
+            } else {
+                format!(
+                    "{key}: {} → {}",
+                    display_value(before.get(key.as_str())),
+                    display_value(after.get(key.as_str()))
+                )
+            }
+        })
+        .collect();
+
+    if changes.is_empty() {
+        "no changes".to_string()
+    } else {
+        changes.join("; ")
+    }
+}
+
+fn insert_and_prune(
+    db: &db::Db,
+    origin: AuditOrigin,
+    command: &str,
+    target: Option<&str>,
+    summary: &str,
+) -> crate::shared::error::AppResult<()> {
+    let mut conn = db.open_connection()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| db_err!("failed to start transaction: {e}"))?;
+
+    tx.execute(
+        r#"
+INSERT INTO config_audit_log(created_at, command, origin, target, summary)
+VALUES (?1, ?2, ?3, ?4, ?5)
+"#,
+        params![
+            now_unix_seconds(),
+            command,
+            origin.as_str(),
+            target,
+            truncate_chars(summary, MAX_SUMMARY_CHARS)
+        ],
+    )
+    .map_err(|e| db_err!("failed to insert config_audit_log: {e}"))?;
+
+    tx.execute(
+        r#"
+DELETE FROM config_audit_log
+WHERE id NOT IN (
+  SELECT id
+  FROM config_audit_log
+  ORDER BY id DESC
+  LIMIT ?1
+)
+"#,
+        params![KEEP_MAX_ROWS],
+    )
+    .map_err(|e| db_err!("failed to prune config_audit_log: {e}"))?;
+
+    tx.commit()
+        .map_err(|e| db_err!("failed to commit transaction: {e}"))?;
+    Ok(())
+}
+
+/// Records one change; failures are logged only.
+pub(crate) fn record(
+    db: &db::Db,
+    origin: AuditOrigin,
+    command: &str,
+    target: Option<String>,
+    summary: String,
+) {
+    if let Err(err) = insert_and_prune(db, origin, command, target.as_deref(), &summary) {
+        tracing::warn!(command, "failed to record config audit entry: {}", err);
+    }
+}
+
+/// [`record`] for commands that do not hold a database handle themselves.
+pub(crate) async fn record_with_app(
+    app: &crate::AppHandle,
+    origin: AuditOrigin,
+    command: &'static str,
+    target: Option<String>,
+    summary: String,
+) {
+    let db = match ensure_db_ready(app.clone(), app.state::<DbInitState>().inner()).await {
+        Ok(db) => db,
+        Err(err) => {
+            tracing::warn!(command, "failed to record config audit entry: {}", err);
+            return;
+        }
+    };
+    let _ = blocking::run("config_audit_log_record", move || {
+        record(&db, origin, command, target, summary);
+        Ok::<_, AppError>(())
+    })
+    .await;
+}
+
+pub fn list(
+    db: &db::Db,
+    filter: &AuditLogFilter,
+) -> crate::shared::error::AppResult<Vec<AuditLogEntry>> {
+    let conn = db.open_connection()?;
+
+    let mut stmt = conn
+        .prepare_cached(
+            r#"
+    SELECT id, created_at, command, origin, target, summary
+    FROM config_audit_log
+    WHERE (?1 IS NULL OR command = ?1)
+      AND (?2 IS NULL OR origin = ?2)
+      AND (?3 IS NULL OR target = ?3)
+      AND (?4 IS NULL OR created_at >= ?4)
+      AND (?5 IS NULL OR created_at < ?5)
+    ORDER BY id DESC
+    LIMIT ?6
+    "#,
+        )
+        .map_err(|e| db_err!("failed to prepare config_audit_log query: {e}"))?;
+
+    let rows = stmt
+        .query_map(
+            params![
+                filter.command.as_deref(),
+                filter.origin.map(AuditOrigin::as_str),
+                filter.target.as_deref(),
+                filter.since_unix,
+                filter.until_unix,
+                filter.limit as i64
+            ],
+            |row| {
+                let origin: String = row.get("origin")?;
+                Ok(AuditLogEntry {
+                    id: row.get("id")?,
+                    created_at: row.get("created_at")?,
+                    command: row.get("command")?,
+                    origin: AuditOrigin::parse(&origin).unwrap_or(AuditOrigin::Ui),
+                    target: row.get("target")?,
+                    summary: row.get("summary")?,
+                })
+            },
+        )
+        .map_err(|e| db_err!("failed to list config_audit_log: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| db_err!("failed to read config_audit_log row: {e}"))?);
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn summarize_diff_lists_changed_keys_and_hides_secrets() {
+        let before = json!({
+            "name": "relay",
+            "enabled": true,
+            "api_key_hint": "sk-1",
+            "updated_at": 1,
+            "note": "x".repeat(100),
+        });
+        let after = json!({
+            "name": "relay-2",
+            "enabled": true,
+            "api_key_hint": "sk-2",
+            "updated_at": 2,
+            "priority": 3,
+        });
+        assert_eq!(
+            summarize_diff(&before, &after),
+            format!(
+                "api_key_hint: changed; name: \"relay\" → \"relay-2\"; note: \"{}…\" → null; priority: null → 3",
+                "x".repeat(MAX_VALUE_CHARS - 1)
+            )
+        );
+        assert_eq!(summarize_diff(&before, &before), "no changes");
+    }
+
+    #[test]
+    fn list_filters_by_command_origin_and_time() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let db = db::init_for_tests(&dir.path().join("test.db")).expect("init db");
+
+        record(
+            &db,
+            AuditOrigin::Ui,
+            "settings_set",
+            None,
+            "preferred_port: 37123 → 37124".to_string(),
+        );
+        record(
+            &db,
+            AuditOrigin::Rest,
+            "cli_proxy_set_enabled",
+            Some("cli:claude".to_string()),
+            "enabled: true".to_string(),
+        );
+
+        let all = list(
+            &db,
+            &AuditLogFilter {
+                limit: 10,
+                ..Default::default()
+            },
+        )
+        .expect("list");
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].command, "cli_proxy_set_enabled");
+        assert_eq!(all[0].origin, AuditOrigin::Rest);
+        assert_eq!(all[0].target.as_deref(), Some("cli:claude"));
+
+        let rest = list(
+            &db,
+            &AuditLogFilter {
+                origin: Some(AuditOrigin::Rest),
+                limit: 10,
+                ..Default::default()
+            },
+        )
+        .expect("list rest");
+        assert_eq!(rest.len(), 1);
+
+        let settings = list(
+            &db,
+            &AuditLogFilter {
+                command: Some("settings_set".to_string()),
+                limit: 10,
+                ..Default::default()
+            },
+        )
+        .expect("list settings");
+        assert_eq!(settings.len(), 1);
+        assert_eq!(settings[0].origin, AuditOrigin::Ui);
+
+        let future = list(
+            &db,
+            &AuditLogFilter {
+                since_unix: Some(now_unix_seconds() + 60),
+                limit: 10,
+                ..Default::default()
+            },
+        )
+        .expect("list future");
+        assert!(future.is_empty());
+    }
+
+    #[tokio::test]
+    async fn with_origin_overrides_the_default_origin() {
+        assert_eq!(
+            with_origin(AuditOrigin::Rest, async { current_origin() }).await,
+            AuditOrigin::Rest
+        );
+    }
+}
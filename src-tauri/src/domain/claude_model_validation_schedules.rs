@@ -0,0 +1,339 @@
+//! Usage: Scheduled Claude model validation per provider+model (definitions + run bookkeeping).
+//!
+//! Runs themselves go through `claude_model_validation::validate_provider_model`, so every
+//! scheduled run also lands in `claude_model_validation_runs`.
+
+use crate::db;
+use crate::shared::error::db_err;
+use crate::shared::time::now_unix_seconds;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+pub const MIN_INTERVAL_MINUTES: u32 = 5;
+pub const MAX_INTERVAL_MINUTES: u32 = 7 * 24 * 60;
+const MAX_MODEL_CHARS: usize = 200;
+const MAX_ERROR_CHARS: usize = 500;
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ClaudeModelValidationSchedule {
+    pub id: i64,
+    pub provider_id: i64,
+    pub model: String,
+    pub interval_minutes: u32,
+    pub enabled: bool,
+    pub last_run_at: Option<i64>,
+    /// `None` until the first scheduled run finished.
+    pub last_ok: Option<bool>,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// A schedule whose interval elapsed, with the provider data needed to run it.
+#[derive(Debug, Clone)]
+pub(crate) struct DueValidation {
+    pub(crate) schedule_id: i64,
+    pub(crate) provider_id: i64,
+    pub(crate) provider_name: String,
+    pub(crate) model: String,
+    pub(crate) base_url: String,
+    pub(crate) base_urls_json: String,
+}
+
+const SCHEDULE_FIELDS: &str = "
+  id,
+  provider_id,
+  model,
+  interval_minutes,
+  enabled,
+  last_run_at,
+  last_ok,
+  last_error,
+  created_at,
+  updated_at
+";
+
+fn row_to_schedule(
+    row: &rusqlite::Row<'_>,
+) -> Result<ClaudeModelValidationSchedule, rusqlite::Error> {
+    Ok(ClaudeModelValidationSchedule {
+        id: row.get("id")?,
+        provider_id: row.get("provider_id")?,
+        model: row.get("model")?,
+        interval_minutes: row.get::<_, i64>("interval_minutes")?.max(0) as u32,
+        enabled: row.get::<_, i64>("enabled")? != 0,
+        last_run_at: row.get("last_run_at")?,
+        last_ok: row.get::<_, Option<i64>>("last_ok")?.map(|v| v != 0),
+        last_error: row.get("last_error")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+fn normalize_model(model: &str) -> crate::shared::error::AppResult<String> {
+    let model = model.trim();
+    if model.is_empty() {
+        return Err("SEC_INVALID_INPUT: model is required".into());
+    }
+    if model.chars().count() > MAX_MODEL_CHARS {
+        return Err(
+            format!("SEC_INVALID_INPUT: model is too long (max {MAX_MODEL_CHARS} chars)").into(),
+        );
+    }
+    Ok(model.to_string())
+}
+
+fn validate_interval(interval_minutes: u32) -> crate::shared::error::AppResult<()> {
+    if !(MIN_INTERVAL_MINUTES..=MAX_INTERVAL_MINUTES).contains(&interval_minutes) {
+        return Err(format!(
+            "SEC_INVALID_INPUT: interval_minutes must be between {MIN_INTERVAL_MINUTES} and {MAX_INTERVAL_MINUTES}"
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn ensure_provider_is_claude(
+    conn: &Connection,
+    provider_id: i64,
+) -> crate::shared::error::AppResult<()> {
+    if provider_id <= 0 {
+        return Err(format!("SEC_INVALID_INPUT: invalid provider_id={provider_id}").into());
+    }
+    let cli_key: Option<String> = conn
+        .query_row(
+            "SELECT cli_key FROM providers WHERE id = ?1",
+            params![provider_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query provider cli_key: {e}"))?;
+    match cli_key.as_deref() {
+        None => Err("DB_NOT_FOUND: provider not found".into()),
+        Some("claude") => Ok(()),
+        Some(_) => Err(format!(
+            "SEC_INVALID_INPUT: only cli_key=claude is supported (provider_id={provider_id})"
+        )
+        .into()),
+    }
+}
+
+fn read_schedule(
+    conn: &Connection,
+    id: i64,
+) -> crate::shared::error::AppResult<ClaudeModelValidationSchedule> {
+    let sql =
+        format!("SELECT{SCHEDULE_FIELDS}FROM claude_model_validation_schedules WHERE id = ?1");
+    conn.query_row(&sql, params![id], row_to_schedule)
+        .optional()
+        .map_err(|e| db_err!("failed to query claude_model_validation_schedule: {e}"))?
+        .ok_or_else(|| "DB_NOT_FOUND: validation schedule not found".into())
+}
+
+pub fn list(
+    db: &db::Db,
+    provider_id: Option<i64>,
+) -> crate::shared::error::AppResult<Vec<ClaudeModelValidationSchedule>> {
+    let conn = db.open_connection()?;
+    let sql = format!(
+        "SELECT{SCHEDULE_FIELDS}FROM claude_model_validation_schedules WHERE ?1 IS NULL OR provider_id = ?1 ORDER BY provider_id ASC, model ASC"
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| db_err!("failed to prepare validation schedules query: {e}"))?;
+    let rows = stmt
+        .query_map(params![provider_id], row_to_schedule)
+        .map_err(|e| db_err!("failed to list validation schedules: {e}"))?;
+
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row.map_err(|e| db_err!("failed to read validation schedule row: {e}"))?);
+    }
+    Ok(out)
+}
+
+/// Creates or updates the schedule for `provider_id` + `model`.
+pub fn upsert(
+    db: &db::Db,
+    provider_id: i64,
+    model: &str,
+    interval_minutes: u32,
+    enabled: bool,
+) -> crate::shared::error::AppResult<ClaudeModelValidationSchedule> {
+    let model = normalize_model(model)?;
+    validate_interval(interval_minutes)?;
+
+    let conn = db.open_connection()?;
+    ensure_provider_is_claude(&conn, provider_id)?;
+
+    let now = now_unix_seconds();
+    conn.execute(
+        r#"
+INSERT INTO claude_model_validation_schedules(
+  provider_id,
+  model,
+  interval_minutes,
+  enabled,
+  created_at,
+  updated_at
+) VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+ON CONFLICT(provider_id, model) DO UPDATE SET
+  interval_minutes = excluded.interval_minutes,
+  enabled = excluded.enabled,
+  updated_at = excluded.updated_at
+"#,
+        params![
+            provider_id,
+            model,
+            interval_minutes as i64,
+            enabled as i64,
+            now
+        ],
+    )
+    .map_err(|e| db_err!("failed to upsert claude_model_validation_schedule: {e}"))?;
+
+    let id: i64 = conn
+        .query_row(
+            "SELECT id FROM claude_model_validation_schedules WHERE provider_id = ?1 AND model = ?2",
+            params![provider_id, model],
+            |row| row.get(0),
+        )
+        .map_err(|e| db_err!("failed to query claude_model_validation_schedule id: {e}"))?;
+
+    read_schedule(&conn, id)
+}
+
+pub fn delete(db: &db::Db, id: i64) -> crate::shared::error::AppResult<bool> {
+    let conn = db.open_connection()?;
+    let changed = conn
+        .execute(
+            "DELETE FROM claude_model_validation_schedules WHERE id = ?1",
+            params![id],
+        )
+        .map_err(|e| db_err!("failed to delete claude_model_validation_schedule: {e}"))?;
+    Ok(changed > 0)
+}
+
+/// Enabled schedules of enabled claude providers whose interval has elapsed at `now_unix`.
+pub(crate) fn due_at(
+    db: &db::Db,
+    now_unix: i64,
+) -> crate::shared::error::AppResult<Vec<DueValidation>> {
+    let conn = db.open_connection()?;
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT
+  s.id,
+  s.provider_id,
+  p.name,
+  s.model,
+  p.base_url,
+  p.base_urls_json
+FROM claude_model_validation_schedules s
+JOIN providers p ON p.id = s.provider_id
+WHERE s.enabled = 1
+  AND p.enabled = 1
+  AND p.cli_key = 'claude'
+  AND (s.last_run_at IS NULL OR s.last_run_at + s.interval_minutes * 60 <= ?1)
+ORDER BY COALESCE(s.last_run_at, 0) ASC, s.id ASC
+"#,
+        )
+        .map_err(|e| db_err!("failed to prepare due validation schedules query: {e}"))?;
+    let rows = stmt
+        .query_map(params![now_unix], |row| {
+            Ok(DueValidation {
+                schedule_id: row.get(0)?,
+                provider_id: row.get(1)?,
+                provider_name: row.get(2)?,
+                model: row.get(3)?,
+                base_url: row.get(4)?,
+                base_urls_json: row.get(5)?,
+            })
+        })
+        .map_err(|e| db_err!("failed to list due validation schedules: {e}"))?;
+
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row.map_err(|e| db_err!("failed to read due validation schedule row: {e}"))?);
+    }
+    Ok(out)
+}
+
+/// Stores the outcome of a scheduled run and returns the previous `last_ok`.
+pub(crate) fn record_run(
+    db: &db::Db,
+    schedule_id: i64,
+    ran_at: i64,
+    ok: bool,
+    error: Option<&str>,
+) -> crate::shared::error::AppResult<Option<bool>> {
+    let error: Option<String> = error
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| v.chars().take(MAX_ERROR_CHARS).collect());
+
+    let mut conn = db.open_connection()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| db_err!("failed to start transaction: {e}"))?;
+
+    let previous: Option<Option<i64>> = tx
+        .query_row(
+            "SELECT last_ok FROM claude_model_validation_schedules WHERE id = ?1",
+            params![schedule_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query validation schedule last_ok: {e}"))?;
+    let Some(previous) = previous else {
+        return Err("DB_NOT_FOUND: validation schedule not found".into());
+    };
+
+    tx.execute(
+        r#"
+UPDATE claude_model_validation_schedules
+SET last_run_at = ?2,
+    last_ok = ?3,
+    last_error = ?4
+WHERE id = ?1
+"#,
+        params![schedule_id, ran_at, ok as i64, error],
+    )
+    .map_err(|e| db_err!("failed to record validation schedule run: {e}"))?;
+
+    tx.commit()
+        .map_err(|e| db_err!("failed to commit transaction: {e}"))?;
+
+    Ok(previous.map(|v| v != 0))
+}
+
+/// A regression is a failing run right after a passing one (first runs never alert).
+pub(crate) fn is_regression(previous_ok: Option<bool>, ok: bool) -> bool {
+    previous_ok == Some(true) && !ok
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_inputs_and_detects_regressions() {
+        assert_eq!(
+            normalize_model(" claude-sonnet-4-5 ").unwrap(),
+            "claude-sonnet-4-5"
+        );
+        assert!(normalize_model("  ").is_err());
+        assert!(normalize_model(&"x".repeat(MAX_MODEL_CHARS + 1)).is_err());
+
+        assert!(validate_interval(MIN_INTERVAL_MINUTES).is_ok());
+        assert!(validate_interval(MAX_INTERVAL_MINUTES).is_ok());
+        assert!(validate_interval(MIN_INTERVAL_MINUTES - 1).is_err());
+        assert!(validate_interval(MAX_INTERVAL_MINUTES + 1).is_err());
+
+        assert!(is_regression(Some(true), false));
+        assert!(!is_regression(None, false));
+        assert!(!is_regression(Some(false), false));
+        assert!(!is_regression(Some(true), true));
+    }
+}
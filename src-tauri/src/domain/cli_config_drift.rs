@@ -0,0 +1,304 @@
+//! Usage: Compare what the hub believes it wrote into CLI config files with what is on disk.
+//!
+//! Covers the three writers that own parts of user config files: the CLI proxy (env/base URL
+//! keys), MCP sync (server entries) and prompt sync (the whole prompt file). Entries the hub
+//! never wrote are not reported; MCP servers the user added by hand are left to `mcp_sync_status`.
+
+use crate::db;
+use crate::mcp::{self, McpDriftStatus};
+use crate::shared::cli_key::{validate_cli_key, SUPPORTED_CLI_KEYS};
+use crate::shared::security::mask_token;
+use crate::shared::time::now_unix_seconds;
+use crate::{cli_proxy, mcp_sync, prompt_sync};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CliConfigDriftStatus {
+    InSync,
+    /// Present on disk with a different value.
+    Changed,
+    /// Written by the hub but no longer on disk.
+    Missing,
+    /// Still on disk although the hub no longer manages it (MCP servers only).
+    Stale,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct CliConfigDriftKey {
+    /// Dotted path inside the file, e.g. `env.ANTHROPIC_BASE_URL` or `mcp_servers.fetch`.
+    pub key: String,
+    pub status: CliConfigDriftStatus,
+    /// Secrets are masked; prompt files report no values.
+    pub expected: Option<serde_json::Value>,
+    pub actual: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct CliConfigDriftFile {
+    /// `cli_proxy` | `mcp` | `prompt`.
+    pub area: String,
+    pub path: String,
+    pub exists: bool,
+    pub parse_error: Option<String>,
+    pub in_sync: bool,
+    pub keys: Vec<CliConfigDriftKey>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct CliConfigDriftCli {
+    pub cli_key: String,
+    pub in_sync: bool,
+    pub files: Vec<CliConfigDriftFile>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct CliConfigDriftReport {
+    pub generated_at: i64,
+    pub in_sync: bool,
+    pub clis: Vec<CliConfigDriftCli>,
+}
+
+fn drift_file(
+    area: &str,
+    path: String,
+    exists: bool,
+    parse_error: Option<String>,
+    keys: Vec<CliConfigDriftKey>,
+) -> CliConfigDriftFile {
+    let in_sync = parse_error.is_none()
+        && keys
+            .iter()
+            .all(|k| k.status == CliConfigDriftStatus::InSync);
+    CliConfigDriftFile {
+        area: area.to_string(),
+        path,
+        exists,
+        parse_error,
+        in_sync,
+        keys,
+    }
+}
+
+fn proxy_key_drift(state: cli_proxy::ProxyKeyState) -> CliConfigDriftKey {
+    let status = match state.actual.as_deref() {
+        None => CliConfigDriftStatus::Missing,
+        Some(actual) if actual == state.expected => CliConfigDriftStatus::InSync,
+        Some(_) => CliConfigDriftStatus::Changed,
+    };
+    let shown = |value: String| {
+        serde_json::Value::String(if state.secret {
+            mask_token(&value)
+        } else {
+            value
+        })
+    };
+    CliConfigDriftKey {
+        key: state.key.to_string(),
+        status,
+        expected: Some(shown(state.expected)),
+        actual: state.actual.map(shown),
+    }
+}
+
+fn proxy_files(
+    app: &crate::AppHandle,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<Vec<CliConfigDriftFile>> {
+    let Some(files) = cli_proxy::managed_key_states(app, cli_key)? else {
+        return Ok(Vec::new());
+    };
+    Ok(files
+        .into_iter()
+        .map(|file| {
+            // A parse error leaves every value unknown rather than missing.
+            let keys = if file.parse_error.is_some() {
+                Vec::new()
+            } else {
+                file.keys.into_iter().map(proxy_key_drift).collect()
+            };
+            drift_file(
+                "cli_proxy",
+                file.path.to_string_lossy().into_owned(),
+                file.exists,
+                file.parse_error,
+                keys,
+            )
+        })
+        .collect())
+}
+
+fn mcp_status(status: McpDriftStatus) -> Option<CliConfigDriftStatus> {
+    match status {
+        McpDriftStatus::InSync => Some(CliConfigDriftStatus::InSync),
+        McpDriftStatus::Modified => Some(CliConfigDriftStatus::Changed),
+        McpDriftStatus::Missing => Some(CliConfigDriftStatus::Missing),
+        McpDriftStatus::Stale => Some(CliConfigDriftStatus::Stale),
+        McpDriftStatus::External => None,
+    }
+}
+
+fn mcp_file(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<Option<CliConfigDriftFile>> {
+    let Some(status) = mcp::sync_status(app, db, Some(cli_key))?.into_iter().next() else {
+        return Ok(None);
+    };
+    let table = if cli_key == "codex" {
+        "mcp_servers"
+    } else {
+        "mcpServers"
+    };
+    let keys: Vec<CliConfigDriftKey> = status
+        .servers
+        .into_iter()
+        .filter_map(|server| {
+            Some(CliConfigDriftKey {
+                key: format!("{table}.{}", server.server_key),
+                status: mcp_status(server.status)?,
+                expected: server.desired,
+                actual: server.actual,
+            })
+        })
+        .collect();
+    if keys.is_empty() && status.parse_error.is_none() {
+        return Ok(None);
+    }
+
+    let path = mcp_sync::mcp_target_path(app, cli_key)?;
+    Ok(Some(drift_file(
+        "mcp",
+        path.to_string_lossy().into_owned(),
+        status.target_exists,
+        status.parse_error,
+        keys,
+    )))
+}
+
+fn prompt_file(
+    app: &crate::AppHandle,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<Option<CliConfigDriftFile>> {
+    if !prompt_sync::is_applied(app, cli_key)? {
+        return Ok(None);
+    }
+    let path = prompt_sync::prompt_target_path(app, cli_key)?;
+    let (exists, status) = match prompt_sync::detect_drift(app, cli_key)? {
+        None => (true, CliConfigDriftStatus::InSync),
+        Some(drift) if drift.local_bytes.is_none() => (false, CliConfigDriftStatus::Missing),
+        Some(_) => (true, CliConfigDriftStatus::Changed),
+    };
+    Ok(Some(drift_file(
+        "prompt",
+        path.to_string_lossy().into_owned(),
+        exists,
+        None,
+        vec![CliConfigDriftKey {
+            key: "content".to_string(),
+            status,
+            expected: None,
+            actual: None,
+        }],
+    )))
+}
+
+fn cli_report(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<CliConfigDriftCli> {
+    let mut files = proxy_files(app, cli_key)?;
+    files.extend(mcp_file(app, db, cli_key)?);
+    files.extend(prompt_file(app, cli_key)?);
+    Ok(CliConfigDriftCli {
+        cli_key: cli_key.to_string(),
+        in_sync: files.iter().all(|f| f.in_sync),
+        files,
+    })
+}
+
+/// Per-file, per-key drift for `cli_key`, or for every supported CLI when `None`.
+pub fn report(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    cli_key: Option<&str>,
+) -> crate::shared::error::AppResult<CliConfigDriftReport> {
+    let clis = match cli_key.map(str::trim) {
+        Some(cli_key) => {
+            validate_cli_key(cli_key)?;
+            vec![cli_report(app, db, cli_key)?]
+        }
+        None => SUPPORTED_CLI_KEYS
+            .iter()
+            .map(|cli_key| cli_report(app, db, cli_key))
+            .collect::<crate::shared::error::AppResult<_>>()?,
+    };
+    Ok(CliConfigDriftReport {
+        generated_at: now_unix_seconds(),
+        in_sync: clis.iter().all(|c| c.in_sync),
+        clis,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key_state(expected: &str, actual: Option<&str>, secret: bool) -> cli_proxy::ProxyKeyState {
+        cli_proxy::ProxyKeyState {
+            key: "env.ANTHROPIC_AUTH_TOKEN",
+            expected: expected.to_string(),
+            actual: actual.map(str::to_string),
+            secret,
+        }
+    }
+
+    #[test]
+    fn proxy_key_drift_classifies_and_masks_secrets() {
+        let row = proxy_key_drift(key_state("http://a", Some("http://a"), false));
+        assert_eq!(row.status, CliConfigDriftStatus::InSync);
+        assert_eq!(row.actual, Some(serde_json::json!("http://a")));
+
+        let row = proxy_key_drift(key_state("http://a", None, false));
+        assert_eq!(row.status, CliConfigDriftStatus::Missing);
+        assert_eq!(row.actual, None);
+
+        let row = proxy_key_drift(key_state(
+            "gateway-token-123456",
+            Some("other-token-abcdef"),
+            true,
+        ));
+        assert_eq!(row.status, CliConfigDriftStatus::Changed);
+        assert_eq!(
+            row.expected,
+            Some(serde_json::json!(mask_token("gateway-token-123456")))
+        );
+        assert_eq!(
+            row.actual,
+            Some(serde_json::json!(mask_token("other-token-abcdef")))
+        );
+    }
+
+    #[test]
+    fn drift_file_is_out_of_sync_on_parse_error_or_any_drifted_key() {
+        assert!(drift_file("mcp", String::new(), true, None, Vec::new()).in_sync);
+        assert!(!drift_file("mcp", String::new(), true, Some("bad".into()), Vec::new()).in_sync);
+
+        let keys = vec![
+            proxy_key_drift(key_state("a", Some("a"), false)),
+            proxy_key_drift(key_state("a", Some("b"), false)),
+        ];
+        assert!(!drift_file("cli_proxy", String::new(), true, None, keys).in_sync);
+    }
+
+    #[test]
+    fn external_mcp_entries_are_not_reported() {
+        assert_eq!(mcp_status(McpDriftStatus::External), None);
+        assert_eq!(
+            mcp_status(McpDriftStatus::Modified),
+            Some(CliConfigDriftStatus::Changed)
+        );
+    }
+}
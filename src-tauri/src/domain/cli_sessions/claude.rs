@@ -108,13 +108,13 @@ fn file_times(path: &Path) -> (Option<i64>, Option<i64>) {
     (created, modified)
 }
 
-fn home_dir(app: &tauri::AppHandle) -> AppResult<PathBuf> {
+fn home_dir(app: &crate::AppHandle) -> AppResult<PathBuf> {
     app.path()
         .home_dir()
         .map_err(|e| AppError::new("INTERNAL_ERROR", format!("failed to resolve home dir: {e}")))
 }
 
-fn claude_projects_dir(app: &tauri::AppHandle) -> AppResult<PathBuf> {
+fn claude_projects_dir(app: &crate::AppHandle) -> AppResult<PathBuf> {
     Ok(home_dir(app)?.join(".claude").join("projects"))
 }
 
@@ -398,7 +398,7 @@ fn count_messages(path: &Path) -> u32 {
 }
 
 fn resolve_and_validate_session_file_path(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     file_path: &str,
 ) -> AppResult<PathBuf> {
     let root = claude_projects_dir(app)?;
@@ -422,7 +422,7 @@ fn resolve_and_validate_session_file_path(
     Ok(resolved)
 }
 
-pub fn projects_list(app: &tauri::AppHandle) -> AppResult<Vec<CliSessionsProjectSummary>> {
+pub fn projects_list(app: &crate::AppHandle) -> AppResult<Vec<CliSessionsProjectSummary>> {
     let projects_dir = claude_projects_dir(app)?;
     if !projects_dir.exists() {
         return Ok(Vec::new());
@@ -477,7 +477,7 @@ pub fn projects_list(app: &tauri::AppHandle) -> AppResult<Vec<CliSessionsProject
 }
 
 pub fn sessions_list(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     project_id: &str,
 ) -> AppResult<Vec<CliSessionsSessionSummary>> {
     validate_project_id(project_id)?;
@@ -602,7 +602,7 @@ pub fn sessions_list(
 }
 
 pub fn messages_get(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     file_path: &str,
     page: usize,
     page_size: usize,
@@ -639,7 +639,7 @@ pub fn messages_get(
     })
 }
 
-pub fn session_delete(app: &tauri::AppHandle, file_path: &str) -> AppResult<bool> {
+pub fn session_delete(app: &crate::AppHandle, file_path: &str) -> AppResult<bool> {
     let resolved = resolve_and_validate_session_file_path(app, file_path)?;
     fs::remove_file(&resolved).map_err(|e| {
         AppError::new(
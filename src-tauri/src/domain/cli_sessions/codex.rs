@@ -43,7 +43,7 @@ fn file_times(path: &Path) -> (Option<i64>, Option<i64>) {
     (created, modified)
 }
 
-fn scan_all_session_files(app: &tauri::AppHandle) -> AppResult<Vec<PathBuf>> {
+fn scan_all_session_files(app: &crate::AppHandle) -> AppResult<Vec<PathBuf>> {
     let sessions_dir = crate::codex_paths::codex_sessions_dir(app)?;
     if !sessions_dir.exists() {
         return Ok(Vec::new());
@@ -451,7 +451,7 @@ fn count_messages(path: &Path) -> u32 {
 }
 
 fn resolve_and_validate_session_file_path(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     file_path: &str,
 ) -> AppResult<PathBuf> {
     let root = crate::codex_paths::codex_sessions_dir(app)?;
@@ -474,7 +474,7 @@ fn resolve_and_validate_session_file_path(
     Ok(resolved)
 }
 
-pub fn projects_list(app: &tauri::AppHandle) -> AppResult<Vec<CliSessionsProjectSummary>> {
+pub fn projects_list(app: &crate::AppHandle) -> AppResult<Vec<CliSessionsProjectSummary>> {
     let files = scan_all_session_files(app)?;
     if files.is_empty() {
         return Ok(Vec::new());
@@ -523,7 +523,7 @@ pub fn projects_list(app: &tauri::AppHandle) -> AppResult<Vec<CliSessionsProject
 }
 
 pub fn sessions_list(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     project_id: &str,
 ) -> AppResult<Vec<CliSessionsSessionSummary>> {
     let cwd = project_id.trim();
@@ -593,7 +593,7 @@ pub fn sessions_list(
 }
 
 pub fn messages_get(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     file_path: &str,
     page: usize,
     page_size: usize,
@@ -630,7 +630,7 @@ pub fn messages_get(
     })
 }
 
-pub fn session_delete(app: &tauri::AppHandle, file_path: &str) -> AppResult<bool> {
+pub fn session_delete(app: &crate::AppHandle, file_path: &str) -> AppResult<bool> {
     let resolved = resolve_and_validate_session_file_path(app, file_path)?;
     fs::remove_file(&resolved).map_err(|e| {
         AppError::new(
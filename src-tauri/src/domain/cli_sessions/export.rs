@@ -0,0 +1,405 @@
+//! Usage: Render a CLI session transcript to shareable Markdown or standalone HTML.
+
+use super::types::{CliSessionsDisplayContentBlock, CliSessionsDisplayMessage};
+use crate::shared::error::AppError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CliSessionsExportFormat {
+    Markdown,
+    Html,
+}
+
+impl CliSessionsExportFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CliSessionsExportFormat::Markdown => "markdown",
+            CliSessionsExportFormat::Html => "html",
+        }
+    }
+}
+
+impl std::str::FromStr for CliSessionsExportFormat {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "markdown" | "md" => Ok(CliSessionsExportFormat::Markdown),
+            "html" => Ok(CliSessionsExportFormat::Html),
+            other => Err(AppError::new(
+                "SEC_INVALID_INPUT",
+                format!("unknown export format: {other}"),
+            )),
+        }
+    }
+}
+
+pub(super) struct ExportHeader<'a> {
+    pub(super) source: &'a str,
+    pub(super) session_id: &'a str,
+    pub(super) file_path: &'a str,
+}
+
+pub(super) fn render(
+    format: CliSessionsExportFormat,
+    header: &ExportHeader<'_>,
+    messages: &[CliSessionsDisplayMessage],
+) -> String {
+    match format {
+        CliSessionsExportFormat::Markdown => render_markdown(header, messages),
+        CliSessionsExportFormat::Html => render_html(header, messages),
+    }
+}
+
+fn role_label(role: &str) -> String {
+    match role {
+        "user" => "User".to_string(),
+        "assistant" => "Assistant".to_string(),
+        "tool" => "Tool".to_string(),
+        "system" => "System".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Pick a backtick fence longer than any run inside `content`, so nested fences survive.
+fn md_fence(content: &str) -> String {
+    let mut longest = 0usize;
+    let mut current = 0usize;
+    for ch in content.chars() {
+        if ch == '`' {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn md_code_block(out: &mut String, lang: &str, content: &str) {
+    let fence = md_fence(content);
+    out.push_str(&fence);
+    out.push_str(lang);
+    out.push('\n');
+    out.push_str(content.trim_end_matches('\n'));
+    out.push('\n');
+    out.push_str(&fence);
+    out.push_str("\n\n");
+}
+
+fn md_details(out: &mut String, summary: &str, body: &str) {
+    out.push_str("<details>\n<summary>");
+    out.push_str(summary);
+    out.push_str("</summary>\n\n");
+    out.push_str(body.trim());
+    out.push_str("\n\n</details>\n\n");
+}
+
+fn render_markdown(header: &ExportHeader<'_>, messages: &[CliSessionsDisplayMessage]) -> String {
+    let mut out = String::new();
+    out.push_str(&format!(
+        "# {} session {}\n\n",
+        header.source, header.session_id
+    ));
+    out.push_str(&format!("- Source: `{}`\n", header.file_path));
+    out.push_str(&format!("- Messages: {}\n\n", messages.len()));
+
+    for message in messages {
+        out.push_str("---\n\n");
+        out.push_str(&format!("## {}", role_label(&message.role)));
+        if let Some(ts) = message.timestamp.as_deref() {
+            out.push_str(&format!(" · {ts}"));
+        }
+        if let Some(model) = message.model.as_deref() {
+            out.push_str(&format!(" · `{model}`"));
+        }
+        out.push_str("\n\n");
+
+        for block in &message.content {
+            match block {
+                CliSessionsDisplayContentBlock::Text { text } => {
+                    out.push_str(text.trim_end());
+                    out.push_str("\n\n");
+                }
+                CliSessionsDisplayContentBlock::Thinking { thinking } => {
+                    md_details(&mut out, "Thinking", thinking);
+                }
+                CliSessionsDisplayContentBlock::Reasoning { text } => {
+                    md_details(&mut out, "Reasoning", text);
+                }
+                CliSessionsDisplayContentBlock::ToolUse { id, name, input } => {
+                    out.push_str(&format!("**Tool call** `{name}` (`{id}`)\n\n"));
+                    md_code_block(&mut out, "json", input);
+                }
+                CliSessionsDisplayContentBlock::ToolResult {
+                    tool_use_id,
+                    content,
+                    is_error,
+                } => {
+                    let label = if *is_error {
+                        "Tool error"
+                    } else {
+                        "Tool result"
+                    };
+                    out.push_str(&format!("**{label}** (`{tool_use_id}`)\n\n"));
+                    md_code_block(&mut out, "", content);
+                }
+                CliSessionsDisplayContentBlock::FunctionCall {
+                    name,
+                    arguments,
+                    call_id,
+                } => {
+                    out.push_str(&format!("**Function call** `{name}` (`{call_id}`)\n\n"));
+                    md_code_block(&mut out, "json", arguments);
+                }
+                CliSessionsDisplayContentBlock::FunctionCallOutput { call_id, output } => {
+                    out.push_str(&format!("**Function output** (`{call_id}`)\n\n"));
+                    md_code_block(&mut out, "", output);
+                }
+            }
+        }
+    }
+
+    out
+}
+
+fn html_escape(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    for ch in raw.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+fn html_pre(out: &mut String, class: &str, content: &str) {
+    out.push_str(&format!("<pre class=\"{class}\"><code>"));
+    out.push_str(&html_escape(content.trim_end_matches('\n')));
+    out.push_str("</code></pre>\n");
+}
+
+/// Render message text, turning ``` fenced sections into `<pre>` blocks.
+fn html_text(out: &mut String, text: &str) {
+    let mut prose = String::new();
+    let mut code: Option<String> = None;
+
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            match code.take() {
+                Some(buf) => html_pre(out, "code", &buf),
+                None => {
+                    html_prose(out, &prose);
+                    prose.clear();
+                    code = Some(String::new());
+                }
+            }
+        } else if let Some(buf) = code.as_mut() {
+            buf.push_str(line);
+            buf.push('\n');
+        } else {
+            prose.push_str(line);
+            prose.push('\n');
+        }
+    }
+
+    if let Some(buf) = code {
+        html_pre(out, "code", &buf);
+    }
+    html_prose(out, &prose);
+}
+
+fn html_prose(out: &mut String, prose: &str) {
+    let prose = prose.trim();
+    if prose.is_empty() {
+        return;
+    }
+    out.push_str("<div class=\"text\">");
+    out.push_str(&html_escape(prose));
+    out.push_str("</div>\n");
+}
+
+const HTML_STYLE: &str = "body{font-family:-apple-system,BlinkMacSystemFont,\"Segoe UI\",sans-serif;max-width:960px;margin:2rem auto;padding:0 1rem;color:#1f2328;line-height:1.5}\
+header{border-bottom:1px solid #d0d7de;margin-bottom:1rem}\
+.msg{border:1px solid #d0d7de;border-radius:8px;padding:.75rem 1rem;margin:1rem 0}\
+.msg.user{background:#f6f8fa}\
+.meta{font-size:.85rem;color:#59636e;margin-bottom:.5rem}\
+.role{font-weight:600;color:#1f2328}\
+.text{white-space:pre-wrap;word-break:break-word}\
+pre{background:#0d1117;color:#e6edf3;padding:.75rem;border-radius:6px;overflow-x:auto}\
+pre.error{border-left:4px solid #cf222e}\
+.label{font-weight:600;margin:.5rem 0 .25rem}\
+details{margin:.5rem 0;color:#59636e}";
+
+fn render_html(header: &ExportHeader<'_>, messages: &[CliSessionsDisplayMessage]) -> String {
+    let title = html_escape(&format!("{} session {}", header.source, header.session_id));
+
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str(&format!("<title>{title}</title>\n"));
+    out.push_str(&format!("<style>{HTML_STYLE}</style>\n</head>\n<body>\n"));
+    out.push_str(&format!(
+        "<header><h1>{title}</h1><p class=\"meta\">Source: <code>{}</code> · Messages: {}</p></header>\n",
+        html_escape(header.file_path),
+        messages.len()
+    ));
+
+    for message in messages {
+        out.push_str(&format!(
+            "<section class=\"msg {}\">\n<div class=\"meta\"><span class=\"role\">{}</span>",
+            html_escape(&message.role),
+            html_escape(&role_label(&message.role))
+        ));
+        if let Some(ts) = message.timestamp.as_deref() {
+            out.push_str(&format!(" · <time>{}</time>", html_escape(ts)));
+        }
+        if let Some(model) = message.model.as_deref() {
+            out.push_str(&format!(" · <code>{}</code>", html_escape(model)));
+        }
+        out.push_str("</div>\n");
+
+        for block in &message.content {
+            match block {
+                CliSessionsDisplayContentBlock::Text { text } => html_text(&mut out, text),
+                CliSessionsDisplayContentBlock::Thinking { thinking: text }
+                | CliSessionsDisplayContentBlock::Reasoning { text } => {
+                    out.push_str("<details><summary>Thinking</summary>\n");
+                    html_prose(&mut out, text);
+                    out.push_str("</details>\n");
+                }
+                CliSessionsDisplayContentBlock::ToolUse { id, name, input } => {
+                    out.push_str(&format!(
+                        "<div class=\"label\">Tool call <code>{}</code> <small>{}</small></div>\n",
+                        html_escape(name),
+                        html_escape(id)
+                    ));
+                    html_pre(&mut out, "tool", input);
+                }
+                CliSessionsDisplayContentBlock::ToolResult {
+                    tool_use_id,
+                    content,
+                    is_error,
+                } => {
+                    let label = if *is_error {
+                        "Tool error"
+                    } else {
+                        "Tool result"
+                    };
+                    out.push_str(&format!(
+                        "<div class=\"label\">{label} <small>{}</small></div>\n",
+                        html_escape(tool_use_id)
+                    ));
+                    html_pre(&mut out, if *is_error { "error" } else { "tool" }, content);
+                }
+                CliSessionsDisplayContentBlock::FunctionCall {
+                    name,
+                    arguments,
+                    call_id,
+                } => {
+                    out.push_str(&format!(
+                        "<div class=\"label\">Function call <code>{}</code> <small>{}</small></div>\n",
+                        html_escape(name),
+                        html_escape(call_id)
+                    ));
+                    html_pre(&mut out, "tool", arguments);
+                }
+                CliSessionsDisplayContentBlock::FunctionCallOutput { call_id, output } => {
+                    out.push_str(&format!(
+                        "<div class=\"label\">Function output <small>{}</small></div>\n",
+                        html_escape(call_id)
+                    ));
+                    html_pre(&mut out, "tool", output);
+                }
+            }
+        }
+
+        out.push_str("</section>\n");
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header() -> ExportHeader<'static> {
+        ExportHeader {
+            source: "claude",
+            session_id: "abc",
+            file_path: "/tmp/abc.jsonl",
+        }
+    }
+
+    fn messages() -> Vec<CliSessionsDisplayMessage> {
+        vec![
+            CliSessionsDisplayMessage {
+                uuid: None,
+                role: "user".to_string(),
+                timestamp: Some("2026-01-01T00:00:00Z".to_string()),
+                model: None,
+                content: vec![CliSessionsDisplayContentBlock::Text {
+                    text: "fix <this>\n```rust\nfn main() {}\n```".to_string(),
+                }],
+            },
+            CliSessionsDisplayMessage {
+                uuid: None,
+                role: "assistant".to_string(),
+                timestamp: None,
+                model: Some("claude-sonnet".to_string()),
+                content: vec![
+                    CliSessionsDisplayContentBlock::ToolUse {
+                        id: "t1".to_string(),
+                        name: "Bash".to_string(),
+                        input: "{\"command\":\"ls\"}".to_string(),
+                    },
+                    CliSessionsDisplayContentBlock::ToolResult {
+                        tool_use_id: "t1".to_string(),
+                        content: "```\nnested\n```".to_string(),
+                        is_error: true,
+                    },
+                ],
+            },
+        ]
+    }
+
+    #[test]
+    fn format_parses_aliases() {
+        assert_eq!(
+            "md".parse::<CliSessionsExportFormat>().unwrap(),
+            CliSessionsExportFormat::Markdown
+        );
+        assert_eq!(
+            "html".parse::<CliSessionsExportFormat>().unwrap(),
+            CliSessionsExportFormat::Html
+        );
+        assert!("pdf".parse::<CliSessionsExportFormat>().is_err());
+    }
+
+    #[test]
+    fn markdown_includes_roles_timestamps_and_tool_calls() {
+        let md = render(CliSessionsExportFormat::Markdown, &header(), &messages());
+        assert!(md.starts_with("# claude session abc\n"));
+        assert!(md.contains("## User · 2026-01-01T00:00:00Z"));
+        assert!(md.contains("## Assistant · `claude-sonnet`"));
+        assert!(md.contains("**Tool call** `Bash` (`t1`)"));
+        assert!(md.contains("**Tool error** (`t1`)"));
+        // Nested fences in tool output get a longer outer fence.
+        assert!(md.contains("````\n```\nnested\n```\n````"));
+    }
+
+    #[test]
+    fn html_escapes_text_and_renders_code_blocks() {
+        let html = render(CliSessionsExportFormat::Html, &header(), &messages());
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("fix &lt;this&gt;"));
+        assert!(html.contains("<pre class=\"code\"><code>fn main() {}</code></pre>"));
+        assert!(html.contains("<pre class=\"error\">"));
+        assert!(!html.contains("<this>"));
+    }
+}
@@ -0,0 +1,534 @@
+//! Usage: Gemini CLI session scanning/parsing from `~/.gemini/tmp/<project_hash>/chats/session-*.json`.
+//!
+//! Gemini CLI keys projects by a SHA-256 of the project root, so the project id/label is that hash
+//! directory name; each session is a single JSON document rather than JSONL.
+
+use super::{
+    truncate_string, validate_path_under_root, CliSessionsDisplayContentBlock,
+    CliSessionsDisplayMessage, CliSessionsPaginatedMessages, CliSessionsProjectSummary,
+    CliSessionsSessionSummary,
+};
+use crate::shared::error::{AppError, AppResult};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::Manager;
+
+const FIRST_PROMPT_MAX_LEN: usize = 200;
+const MAX_TEXT_BLOCK_SIZE: usize = 20_000;
+const MAX_ARGS_SIZE: usize = 10_000;
+const MAX_OUTPUT_BLOCK_SIZE: usize = 30_000;
+
+fn unix_seconds_from_system_time(time: SystemTime) -> Option<i64> {
+    time.duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs() as i64)
+}
+
+fn file_times(path: &Path) -> (Option<i64>, Option<i64>) {
+    let meta = match fs::metadata(path) {
+        Ok(m) => m,
+        Err(_) => return (None, None),
+    };
+    let created = meta.created().ok().and_then(unix_seconds_from_system_time);
+    let modified = meta.modified().ok().and_then(unix_seconds_from_system_time);
+    (created, modified)
+}
+
+fn gemini_tmp_dir(app: &crate::AppHandle) -> AppResult<PathBuf> {
+    let home = app
+        .path()
+        .home_dir()
+        .map_err(|e| AppError::new("INTERNAL_ERROR", format!("failed to resolve home dir: {e}")))?;
+    Ok(home.join(".gemini").join("tmp"))
+}
+
+fn wsl_gemini_tmp_dir(distro: &str) -> AppResult<PathBuf> {
+    let home = crate::wsl::resolve_wsl_home_unc(distro)?;
+    Ok(home.join(".gemini").join("tmp"))
+}
+
+fn short_project_name(project_id: &str) -> String {
+    if project_id.len() == 64 && project_id.chars().all(|c| c.is_ascii_hexdigit()) {
+        project_id[..12].to_string()
+    } else {
+        project_id.to_string()
+    }
+}
+
+fn session_files(project_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(project_dir.join("chats")) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension().map(|e| e == "json").unwrap_or(false)
+                && path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .map(|n| n.starts_with("session-"))
+                    .unwrap_or(false)
+        })
+        .collect()
+}
+
+fn read_session(path: &Path) -> Option<Value> {
+    let bytes = fs::read(path).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+fn value_to_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => serde_json::to_string_pretty(other).unwrap_or_else(|_| other.to_string()),
+    }
+}
+
+/// `content` is either a plain string or a list of Gemini API parts.
+fn content_text(content: Option<&Value>) -> String {
+    match content {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .filter_map(|part| match part {
+                Value::String(s) => Some(s.as_str()),
+                other => other.get("text").and_then(|v| v.as_str()),
+            })
+            .collect::<Vec<&str>>()
+            .join("\n"),
+        Some(Value::Object(part)) => part
+            .get("text")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        _ => String::new(),
+    }
+}
+
+fn tool_result_text(result: &Value) -> String {
+    let Some(parts) = result.as_array() else {
+        return value_to_text(result);
+    };
+    parts
+        .iter()
+        .map(
+            |part| match part.get("functionResponse").and_then(|r| r.get("response")) {
+                Some(response) => response
+                    .get("output")
+                    .map(value_to_text)
+                    .unwrap_or_else(|| value_to_text(response)),
+                None => value_to_text(part),
+            },
+        )
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn parse_messages(session: &Value) -> Vec<CliSessionsDisplayMessage> {
+    let Some(records) = session.get("messages").and_then(|v| v.as_array()) else {
+        return Vec::new();
+    };
+
+    let mut messages = Vec::new();
+    for record in records {
+        let role = match record.get("type").and_then(|v| v.as_str()) {
+            Some("user") => "user",
+            Some("gemini") => "assistant",
+            // info/error/warning entries are CLI notices, not conversation turns.
+            _ => continue,
+        };
+        let uuid = record
+            .get("id")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let timestamp = record
+            .get("timestamp")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let model = record
+            .get("model")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let mut content = Vec::new();
+        for thought in record
+            .get("thoughts")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+        {
+            let subject = thought
+                .get("subject")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let description = thought
+                .get("description")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let text = match (subject.trim(), description.trim()) {
+                ("", d) => d.to_string(),
+                (s, "") => s.to_string(),
+                (s, d) => format!("**{s}**\n{d}"),
+            };
+            if !text.is_empty() {
+                content.push(CliSessionsDisplayContentBlock::Thinking {
+                    thinking: truncate_string(&text, MAX_TEXT_BLOCK_SIZE),
+                });
+            }
+        }
+
+        let text = content_text(record.get("content"));
+        if !text.trim().is_empty() {
+            content.push(CliSessionsDisplayContentBlock::Text {
+                text: truncate_string(text.trim(), MAX_TEXT_BLOCK_SIZE),
+            });
+        }
+
+        let mut tool_results = Vec::new();
+        for call in record
+            .get("toolCalls")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+        {
+            let id = call
+                .get("id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+            let name = call
+                .get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string();
+            let input = call.get("args").map(value_to_text).unwrap_or_default();
+            content.push(CliSessionsDisplayContentBlock::ToolUse {
+                id: id.clone(),
+                name,
+                input: truncate_string(&input, MAX_ARGS_SIZE),
+            });
+
+            let output = call
+                .get("result")
+                .map(tool_result_text)
+                .filter(|s| !s.trim().is_empty())
+                .or_else(|| call.get("resultDisplay").map(value_to_text));
+            if let Some(output) = output {
+                let status = call.get("status").and_then(|v| v.as_str()).unwrap_or("");
+                tool_results.push(CliSessionsDisplayContentBlock::ToolResult {
+                    tool_use_id: id,
+                    content: truncate_string(&output, MAX_OUTPUT_BLOCK_SIZE),
+                    is_error: matches!(status, "error" | "cancelled"),
+                });
+            }
+        }
+
+        if content.is_empty() {
+            continue;
+        }
+        messages.push(CliSessionsDisplayMessage {
+            uuid,
+            role: role.to_string(),
+            timestamp: timestamp.clone(),
+            model,
+            content,
+        });
+        if !tool_results.is_empty() {
+            messages.push(CliSessionsDisplayMessage {
+                uuid: None,
+                role: "tool".to_string(),
+                timestamp,
+                model: None,
+                content: tool_results,
+            });
+        }
+    }
+    messages
+}
+
+fn first_prompt(session: &Value) -> Option<String> {
+    session
+        .get("messages")?
+        .as_array()?
+        .iter()
+        .filter(|record| record.get("type").and_then(|v| v.as_str()) == Some("user"))
+        .map(|record| content_text(record.get("content")))
+        .find(|text| !text.trim().is_empty())
+        .map(|text| truncate_string(text.trim(), FIRST_PROMPT_MAX_LEN))
+}
+
+fn message_count(session: &Value) -> u32 {
+    session
+        .get("messages")
+        .and_then(|v| v.as_array())
+        .map(|records| {
+            records
+                .iter()
+                .filter(|record| {
+                    matches!(
+                        record.get("type").and_then(|v| v.as_str()),
+                        Some("user" | "gemini")
+                    )
+                })
+                .count() as u32
+        })
+        .unwrap_or(0)
+}
+
+fn list_projects(root: &Path, distro: Option<&str>) -> AppResult<Vec<CliSessionsProjectSummary>> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Ok(Vec::new());
+    };
+
+    let mut out = Vec::new();
+    for entry in entries.flatten() {
+        let project_dir = entry.path();
+        if !project_dir.is_dir() {
+            continue;
+        }
+        let files = session_files(&project_dir);
+        if files.is_empty() {
+            continue;
+        }
+        let project_id = entry.file_name().to_string_lossy().to_string();
+        let last_modified = files.iter().filter_map(|f| file_times(f).1).max();
+        out.push(CliSessionsProjectSummary {
+            source: "gemini".to_string(),
+            display_path: project_id.clone(),
+            short_name: short_project_name(&project_id),
+            id: project_id,
+            session_count: files.len(),
+            last_modified,
+            model_provider: None,
+            wsl_distro: distro.map(str::to_string),
+        });
+    }
+    out.sort_by(|a, b| b.last_modified.cmp(&a.last_modified));
+    Ok(out)
+}
+
+fn list_sessions(
+    root: &Path,
+    project_id: &str,
+    distro: Option<&str>,
+) -> AppResult<Vec<CliSessionsSessionSummary>> {
+    let project_id = project_id.trim();
+    if project_id.is_empty() {
+        return Err(AppError::new("SEC_INVALID_INPUT", "projectId is required"));
+    }
+    if project_id.contains(['/', '\\']) || project_id == ".." {
+        return Err(AppError::new("SEC_INVALID_INPUT", "invalid projectId"));
+    }
+
+    let project_dir = root.join(project_id);
+    let mut out = Vec::new();
+    for file_path in session_files(&project_dir) {
+        if validate_path_under_root(&file_path, root).is_err() {
+            continue;
+        }
+        let Some(session) = read_session(&file_path) else {
+            continue;
+        };
+        let session_id = session
+            .get("sessionId")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| {
+                file_path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("unknown")
+                    .to_string()
+            });
+        let (created_at, modified_at) = file_times(&file_path);
+
+        out.push(CliSessionsSessionSummary {
+            source: "gemini".to_string(),
+            session_id,
+            file_path: file_path.to_string_lossy().to_string(),
+            first_prompt: first_prompt(&session),
+            message_count: message_count(&session),
+            created_at,
+            modified_at,
+            git_branch: None,
+            project_path: Some(project_id.to_string()),
+            is_sidechain: None,
+            cwd: None,
+            model_provider: None,
+            cli_version: None,
+            wsl_distro: distro.map(str::to_string),
+        });
+    }
+    out.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
+    Ok(out)
+}
+
+fn resolve_session_file(root: &Path, file_path: &str) -> AppResult<PathBuf> {
+    let raw = PathBuf::from(file_path);
+    if raw.extension().map(|e| e != "json").unwrap_or(true) {
+        return Err(AppError::new(
+            "SEC_INVALID_INPUT",
+            "filePath must be a .json file",
+        ));
+    }
+    validate_path_under_root(&raw, root)
+}
+
+fn get_messages(
+    root: &Path,
+    file_path: &str,
+    page: usize,
+    page_size: usize,
+    from_end: bool,
+) -> AppResult<CliSessionsPaginatedMessages> {
+    let resolved = resolve_session_file(root, file_path)?;
+    let session = read_session(&resolved)
+        .ok_or_else(|| AppError::new("INTERNAL_ERROR", "failed to read gemini session file"))?;
+    let messages = parse_messages(&session);
+
+    let total = messages.len();
+    let (start, end, has_more) = if from_end {
+        let end = total.saturating_sub(page.saturating_mul(page_size));
+        let start = end.saturating_sub(page_size);
+        (start, end, start > 0)
+    } else {
+        let start = page.saturating_mul(page_size);
+        let end = start.saturating_add(page_size).min(total);
+        (start, end, end < total)
+    };
+
+    let page_messages = if start < end && end <= total {
+        messages[start..end].to_vec()
+    } else {
+        Vec::new()
+    };
+
+    Ok(CliSessionsPaginatedMessages {
+        messages: page_messages,
+        total,
+        page,
+        page_size,
+        has_more,
+    })
+}
+
+fn delete_session(root: &Path, file_path: &str) -> AppResult<bool> {
+    let resolved = resolve_session_file(root, file_path)?;
+    fs::remove_file(&resolved).map_err(|e| {
+        AppError::new(
+            "INTERNAL_ERROR",
+            format!("failed to delete session file: {e}"),
+        )
+    })?;
+    Ok(true)
+}
+
+pub fn projects_list(app: &crate::AppHandle) -> AppResult<Vec<CliSessionsProjectSummary>> {
+    list_projects(&gemini_tmp_dir(app)?, None)
+}
+
+pub fn sessions_list(
+    app: &crate::AppHandle,
+    project_id: &str,
+) -> AppResult<Vec<CliSessionsSessionSummary>> {
+    list_sessions(&gemini_tmp_dir(app)?, project_id, None)
+}
+
+pub fn messages_get(
+    app: &crate::AppHandle,
+    file_path: &str,
+    page: usize,
+    page_size: usize,
+    from_end: bool,
+) -> AppResult<CliSessionsPaginatedMessages> {
+    get_messages(&gemini_tmp_dir(app)?, file_path, page, page_size, from_end)
+}
+
+pub fn session_delete(app: &crate::AppHandle, file_path: &str) -> AppResult<bool> {
+    delete_session(&gemini_tmp_dir(app)?, file_path)
+}
+
+// ── WSL support ─────────────────────────────────────────────────────────────
+
+pub fn wsl_projects_list(distro: &str) -> AppResult<Vec<CliSessionsProjectSummary>> {
+    list_projects(&wsl_gemini_tmp_dir(distro)?, Some(distro))
+}
+
+pub fn wsl_sessions_list(
+    distro: &str,
+    project_id: &str,
+) -> AppResult<Vec<CliSessionsSessionSummary>> {
+    list_sessions(&wsl_gemini_tmp_dir(distro)?, project_id, Some(distro))
+}
+
+pub fn wsl_messages_get(
+    distro: &str,
+    file_path: &str,
+    page: usize,
+    page_size: usize,
+    from_end: bool,
+) -> AppResult<CliSessionsPaginatedMessages> {
+    get_messages(
+        &wsl_gemini_tmp_dir(distro)?,
+        file_path,
+        page,
+        page_size,
+        from_end,
+    )
+}
+
+pub fn wsl_session_delete(distro: &str, file_path: &str) -> AppResult<bool> {
+    delete_session(&wsl_gemini_tmp_dir(distro)?, file_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_messages_maps_turns_thoughts_and_tool_calls() {
+        let session = json!({
+            "sessionId": "abc",
+            "messages": [
+                { "id": "1", "type": "user", "content": [{ "text": "list files" }] },
+                { "id": "2", "type": "info", "content": "Switched model" },
+                {
+                    "id": "3",
+                    "type": "gemini",
+                    "model": "gemini-2.5-pro",
+                    "content": "Here they are.",
+                    "thoughts": [{ "subject": "Plan", "description": "Run ls" }],
+                    "toolCalls": [{
+                        "id": "call-1",
+                        "name": "list_directory",
+                        "args": { "path": "." },
+                        "status": "success",
+                        "result": [{ "functionResponse": { "response": { "output": "a.txt" } } }]
+                    }]
+                }
+            ]
+        });
+
+        assert_eq!(first_prompt(&session).as_deref(), Some("list files"));
+        assert_eq!(message_count(&session), 2);
+
+        let messages = parse_messages(&session);
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[1].role, "assistant");
+        assert_eq!(messages[1].model.as_deref(), Some("gemini-2.5-pro"));
+        assert!(matches!(
+            &messages[1].content[0],
+            CliSessionsDisplayContentBlock::Thinking { thinking } if thinking == "**Plan**\nRun ls"
+        ));
+        assert!(matches!(
+            &messages[2].content[0],
+            CliSessionsDisplayContentBlock::ToolResult { tool_use_id, content, is_error: false }
+                if tool_use_id == "call-1" && content == "a.txt"
+        ));
+    }
+}
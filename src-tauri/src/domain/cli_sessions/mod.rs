@@ -1,12 +1,15 @@
-//! Usage: Browse historical sessions from local Claude/Codex CLI logs (projects → sessions → messages).
+//! Usage: Browse historical sessions from local Claude/Codex/Gemini CLI logs (projects → sessions → messages).
 
 mod claude;
 mod codex;
+mod export;
+mod gemini;
 mod types;
 
+pub use export::CliSessionsExportFormat;
 pub use types::{
-    CliSessionsDisplayContentBlock, CliSessionsDisplayMessage, CliSessionsPaginatedMessages,
-    CliSessionsProjectSummary, CliSessionsSessionSummary,
+    CliSessionsDisplayContentBlock, CliSessionsDisplayMessage, CliSessionsExportResult,
+    CliSessionsPaginatedMessages, CliSessionsProjectSummary, CliSessionsSessionSummary,
 };
 
 use crate::shared::error::{AppError, AppResult};
@@ -16,6 +19,7 @@ use std::path::{Path, PathBuf};
 pub enum CliSessionsSource {
     Claude,
     Codex,
+    Gemini,
 }
 
 impl std::str::FromStr for CliSessionsSource {
@@ -25,6 +29,7 @@ impl std::str::FromStr for CliSessionsSource {
         match s.trim() {
             "claude" => Ok(CliSessionsSource::Claude),
             "codex" => Ok(CliSessionsSource::Codex),
+            "gemini" => Ok(CliSessionsSource::Gemini),
             other => Err(AppError::new(
                 "SEC_INVALID_INPUT",
                 format!("unknown source: {other}"),
@@ -42,7 +47,7 @@ fn normalize_page_size(raw: usize) -> usize {
 }
 
 pub fn projects_list(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     source: CliSessionsSource,
     wsl_distro: Option<&str>,
 ) -> AppResult<Vec<CliSessionsProjectSummary>> {
@@ -51,16 +56,18 @@ pub fn projects_list(
         return match source {
             CliSessionsSource::Claude => claude::wsl_projects_list(distro),
             CliSessionsSource::Codex => codex::wsl_projects_list(distro),
+            CliSessionsSource::Gemini => gemini::wsl_projects_list(distro),
         };
     }
     match source {
         CliSessionsSource::Claude => claude::projects_list(app),
         CliSessionsSource::Codex => codex::projects_list(app),
+        CliSessionsSource::Gemini => gemini::projects_list(app),
     }
 }
 
 pub fn sessions_list(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     source: CliSessionsSource,
     project_id: &str,
     wsl_distro: Option<&str>,
@@ -70,16 +77,18 @@ pub fn sessions_list(
         return match source {
             CliSessionsSource::Claude => claude::wsl_sessions_list(distro, project_id),
             CliSessionsSource::Codex => codex::wsl_sessions_list(distro, project_id),
+            CliSessionsSource::Gemini => gemini::wsl_sessions_list(distro, project_id),
         };
     }
     match source {
         CliSessionsSource::Claude => claude::sessions_list(app, project_id),
         CliSessionsSource::Codex => codex::sessions_list(app, project_id),
+        CliSessionsSource::Gemini => gemini::sessions_list(app, project_id),
     }
 }
 
 pub fn messages_get(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     source: CliSessionsSource,
     file_path: &str,
     page: usize,
@@ -97,6 +106,9 @@ pub fn messages_get(
             CliSessionsSource::Codex => {
                 codex::wsl_messages_get(distro, file_path, page, page_size, from_end)
             }
+            CliSessionsSource::Gemini => {
+                gemini::wsl_messages_get(distro, file_path, page, page_size, from_end)
+            }
         };
     }
     match source {
@@ -104,12 +116,16 @@ pub fn messages_get(
             claude::messages_get(app, file_path, page, page_size, from_end)
         }
         CliSessionsSource::Codex => codex::messages_get(app, file_path, page, page_size, from_end),
+        CliSessionsSource::Gemini => {
+            gemini::messages_get(app, file_path, page, page_size, from_end)
+        }
     }
 }
 
-/// Delete a session file. file_path must be a .jsonl file within a valid root directory.
+/// Delete a session file. file_path must be a session file (.jsonl, .json for Gemini) within a
+/// valid root directory.
 pub fn session_delete(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     source: CliSessionsSource,
     file_path: &str,
     wsl_distro: Option<&str>,
@@ -119,14 +135,88 @@ pub fn session_delete(
         return match source {
             CliSessionsSource::Claude => claude::wsl_session_delete(distro, file_path),
             CliSessionsSource::Codex => codex::wsl_session_delete(distro, file_path),
+            CliSessionsSource::Gemini => gemini::wsl_session_delete(distro, file_path),
         };
     }
     match source {
         CliSessionsSource::Claude => claude::session_delete(app, file_path),
         CliSessionsSource::Codex => codex::session_delete(app, file_path),
+        CliSessionsSource::Gemini => gemini::session_delete(app, file_path),
     }
 }
 
+/// Render a whole session to Markdown/HTML and write it to `output_path` (absolute path).
+pub fn session_export(
+    app: &crate::AppHandle,
+    source: CliSessionsSource,
+    file_path: &str,
+    format: CliSessionsExportFormat,
+    output_path: &str,
+    wsl_distro: Option<&str>,
+) -> AppResult<CliSessionsExportResult> {
+    let output = PathBuf::from(output_path);
+    if !output.is_absolute() {
+        return Err(AppError::new(
+            "SEC_INVALID_INPUT",
+            "outputPath must be an absolute path",
+        ));
+    }
+
+    // Single page covering the whole transcript, oldest first.
+    let page = if let Some(distro) = wsl_distro {
+        crate::wsl::validate_distro(distro)?;
+        match source {
+            CliSessionsSource::Claude => {
+                claude::wsl_messages_get(distro, file_path, 0, usize::MAX, false)?
+            }
+            CliSessionsSource::Codex => {
+                codex::wsl_messages_get(distro, file_path, 0, usize::MAX, false)?
+            }
+            CliSessionsSource::Gemini => {
+                gemini::wsl_messages_get(distro, file_path, 0, usize::MAX, false)?
+            }
+        }
+    } else {
+        match source {
+            CliSessionsSource::Claude => {
+                claude::messages_get(app, file_path, 0, usize::MAX, false)?
+            }
+            CliSessionsSource::Codex => codex::messages_get(app, file_path, 0, usize::MAX, false)?,
+            CliSessionsSource::Gemini => {
+                gemini::messages_get(app, file_path, 0, usize::MAX, false)?
+            }
+        }
+    };
+
+    let session_id = Path::new(file_path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let source_label = match source {
+        CliSessionsSource::Claude => "claude",
+        CliSessionsSource::Codex => "codex",
+        CliSessionsSource::Gemini => "gemini",
+    };
+    let rendered = export::render(
+        format,
+        &export::ExportHeader {
+            source: source_label,
+            session_id: &session_id,
+            file_path,
+        },
+        &page.messages,
+    );
+
+    crate::shared::fs::write_file_atomic(&output, rendered.as_bytes())?;
+
+    Ok(CliSessionsExportResult {
+        output_path: output.to_string_lossy().to_string(),
+        format: format.as_str().to_string(),
+        message_count: page.messages.len(),
+        bytes: rendered.len(),
+    })
+}
+
 pub(super) fn truncate_string(raw: &str, max_len: usize) -> String {
     if raw.len() <= max_len {
         return raw.to_string();
@@ -2,7 +2,7 @@
 
 use serde::Serialize;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct CliSessionsProjectSummary {
     pub source: String,
     pub id: String,
@@ -14,7 +14,7 @@ pub struct CliSessionsProjectSummary {
     pub wsl_distro: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct CliSessionsSessionSummary {
     pub source: String,
     pub session_id: String,
@@ -32,7 +32,7 @@ pub struct CliSessionsSessionSummary {
     pub wsl_distro: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct CliSessionsPaginatedMessages {
     pub messages: Vec<CliSessionsDisplayMessage>,
     pub total: usize,
@@ -41,7 +41,7 @@ pub struct CliSessionsPaginatedMessages {
     pub has_more: bool,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct CliSessionsDisplayMessage {
     pub uuid: Option<String>,
     pub role: String,
@@ -50,7 +50,7 @@ pub struct CliSessionsDisplayMessage {
     pub content: Vec<CliSessionsDisplayContentBlock>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum CliSessionsDisplayContentBlock {
     Text {
@@ -82,3 +82,11 @@ pub enum CliSessionsDisplayContentBlock {
         output: String,
     },
 }
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct CliSessionsExportResult {
+    pub output_path: String,
+    pub format: String,
+    pub message_count: usize,
+    pub bytes: usize,
+}
@@ -0,0 +1,320 @@
+//! Usage: Named Codex `config.toml` profiles (model defaults, approval modes, MCP wiring).
+//!
+//! A profile stores a complete `config.toml`. Applying one backs up the current file to
+//! `<app_data>/codex-profile-backups/`, writes the profile atomically and records it as the
+//! active profile of the (codex) workspace.
+
+use crate::shared::error::db_err;
+use crate::shared::text::normalize_name;
+use crate::shared::time::now_unix_seconds;
+use crate::{app_paths, codex_config, codex_paths, db, workspaces};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const BACKUP_DIR_NAME: &str = "codex-profile-backups";
+const KEEP_MAX_BACKUPS: usize = 20;
+const MAX_NAME_CHARS: usize = 100;
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct CodexProfile {
+    pub id: i64,
+    pub name: String,
+    pub toml: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct CodexProfilesListResult {
+    /// Profile last applied to the requested workspace, if any.
+    pub active_id: Option<i64>,
+    pub items: Vec<CodexProfile>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct CodexProfileApplyResult {
+    pub workspace_id: i64,
+    pub profile_id: i64,
+    pub config_path: String,
+    /// Copy of the replaced config.toml; `None` when there was no file to replace.
+    pub backup_path: Option<String>,
+    pub applied_at: i64,
+}
+
+fn row_to_profile(row: &rusqlite::Row<'_>) -> Result<CodexProfile, rusqlite::Error> {
+    Ok(CodexProfile {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        toml: row.get("toml")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+fn normalize_profile_name(name: &str) -> crate::shared::error::AppResult<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("SEC_INVALID_INPUT: profile name is required".into());
+    }
+    if name.chars().count() > MAX_NAME_CHARS {
+        return Err(format!(
+            "SEC_INVALID_INPUT: profile name is too long (max {MAX_NAME_CHARS} chars)"
+        )
+        .into());
+    }
+    Ok(name.to_string())
+}
+
+fn validate_toml(toml: &str) -> crate::shared::error::AppResult<()> {
+    let validation = codex_config::codex_config_toml_validate_raw(toml.to_string())?;
+    if validation.ok {
+        return Ok(());
+    }
+    let message = validation
+        .error
+        .map(|err| err.message)
+        .unwrap_or_else(|| "invalid TOML".to_string());
+    Err(format!("SEC_INVALID_INPUT: invalid profile config.toml: {message}").into())
+}
+
+fn ensure_codex_workspace(
+    conn: &Connection,
+    workspace_id: i64,
+) -> crate::shared::error::AppResult<()> {
+    let cli_key = workspaces::get_cli_key_by_id(conn, workspace_id)?;
+    if cli_key != "codex" {
+        return Err(format!(
+            "SEC_INVALID_INPUT: codex profiles only apply to codex workspaces (workspace_id={workspace_id})"
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn get_by_id(conn: &Connection, id: i64) -> crate::shared::error::AppResult<CodexProfile> {
+    conn.query_row(
+        "SELECT id, name, toml, created_at, updated_at FROM codex_config_profiles WHERE id = ?1",
+        params![id],
+        row_to_profile,
+    )
+    .optional()
+    .map_err(|e| db_err!("failed to query codex profile: {e}"))?
+    .ok_or_else(|| "DB_NOT_FOUND: codex profile not found".into())
+}
+
+fn active_id(conn: &Connection, workspace_id: i64) -> crate::shared::error::AppResult<Option<i64>> {
+    conn.query_row(
+        "SELECT profile_id FROM workspace_codex_profile WHERE workspace_id = ?1",
+        params![workspace_id],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| db_err!("failed to query active codex profile: {e}"))
+}
+
+pub fn list(
+    db: &db::Db,
+    workspace_id: Option<i64>,
+) -> crate::shared::error::AppResult<CodexProfilesListResult> {
+    let conn = db.open_connection()?;
+    let active_id = match workspace_id {
+        Some(workspace_id) => active_id(&conn, workspace_id)?,
+        None => None,
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, toml, created_at, updated_at FROM codex_config_profiles ORDER BY name ASC, id ASC",
+        )
+        .map_err(|e| db_err!("failed to prepare codex profiles query: {e}"))?;
+    let rows = stmt
+        .query_map([], row_to_profile)
+        .map_err(|e| db_err!("failed to list codex profiles: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| db_err!("failed to read codex profile row: {e}"))?);
+    }
+    Ok(CodexProfilesListResult { active_id, items })
+}
+
+/// Creates a profile (`id = None`) or replaces the name and contents of an existing one.
+pub fn upsert(
+    db: &db::Db,
+    id: Option<i64>,
+    name: &str,
+    toml: &str,
+) -> crate::shared::error::AppResult<CodexProfile> {
+    let name = normalize_profile_name(name)?;
+    validate_toml(toml)?;
+    let mut toml = toml.to_string();
+    if !toml.ends_with('\n') {
+        toml.push('\n');
+    }
+
+    let conn = db.open_connection()?;
+    let now = now_unix_seconds();
+    let map_constraint = |e: rusqlite::Error| match e {
+        rusqlite::Error::SqliteFailure(err, _)
+            if err.code == rusqlite::ErrorCode::ConstraintViolation =>
+        {
+            crate::shared::error::AppError::new(
+                "DB_CONSTRAINT",
+                format!("codex profile already exists for name={name}"),
+            )
+        }
+        other => db_err!("failed to save codex profile: {other}"),
+    };
+
+    let id = match id {
+        Some(id) => {
+            let changed = conn
+                .execute(
+                    "UPDATE codex_config_profiles SET name = ?2, normalized_name = ?3, toml = ?4, updated_at = ?5 WHERE id = ?1",
+                    params![id, name, normalize_name(&name), toml, now],
+                )
+                .map_err(map_constraint)?;
+            if changed == 0 {
+                return Err("DB_NOT_FOUND: codex profile not found".into());
+            }
+            id
+        }
+        None => {
+            conn.execute(
+                r#"
+INSERT INTO codex_config_profiles(
+  name,
+  normalized_name,
+  toml,
+  created_at,
+  updated_at
+) VALUES (?1, ?2, ?3, ?4, ?4)
+"#,
+                params![name, normalize_name(&name), toml, now],
+            )
+            .map_err(map_constraint)?;
+            conn.last_insert_rowid()
+        }
+    };
+
+    get_by_id(&conn, id)
+}
+
+pub fn delete(db: &db::Db, id: i64) -> crate::shared::error::AppResult<bool> {
+    let conn = db.open_connection()?;
+    let changed = conn
+        .execute(
+            "DELETE FROM codex_config_profiles WHERE id = ?1",
+            params![id],
+        )
+        .map_err(|e| db_err!("failed to delete codex profile: {e}"))?;
+    Ok(changed > 0)
+}
+
+fn backups_root<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> crate::shared::error::AppResult<PathBuf> {
+    Ok(app_paths::app_data_dir(app)?.join(BACKUP_DIR_NAME))
+}
+
+/// Oldest backup files beyond `keep` (names sort chronologically: `config.<unix_ms>.toml`).
+fn backups_to_prune(mut names: Vec<String>, keep: usize) -> Vec<String> {
+    names.sort_by_key(|name| {
+        name.strip_prefix("config.")
+            .and_then(|rest| rest.strip_suffix(".toml"))
+            .and_then(|ts| ts.parse::<i64>().ok())
+            .unwrap_or(0)
+    });
+    let excess = names.len().saturating_sub(keep);
+    names.truncate(excess);
+    names
+}
+
+fn backup_config(dir: &Path, current: &[u8]) -> crate::shared::error::AppResult<PathBuf> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("failed to create codex profile backup dir: {e}"))?;
+    let now_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let path = dir.join(format!("config.{now_ms}.toml"));
+    crate::shared::fs::write_file_atomic(&path, current)?;
+
+    let names: Vec<String> = std::fs::read_dir(dir)
+        .map_err(|e| format!("failed to read codex profile backup dir: {e}"))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with("config.") && name.ends_with(".toml"))
+        .collect();
+    for name in backups_to_prune(names, KEEP_MAX_BACKUPS) {
+        let _ = std::fs::remove_file(dir.join(name));
+    }
+    Ok(path)
+}
+
+/// Writes `profile_id` to `$CODEX_HOME/config.toml` and marks it active for `workspace_id`.
+pub fn apply<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    db: &db::Db,
+    workspace_id: i64,
+    profile_id: i64,
+) -> crate::shared::error::AppResult<CodexProfileApplyResult> {
+    let conn = db.open_connection()?;
+    ensure_codex_workspace(&conn, workspace_id)?;
+    let profile = get_by_id(&conn, profile_id)?;
+    validate_toml(&profile.toml)?;
+
+    let config_path = codex_paths::codex_config_toml_path(app)?;
+    let backup_path = match crate::shared::fs::read_optional_file(&config_path)? {
+        Some(current) => Some(backup_config(&backups_root(app)?, &current)?),
+        None => None,
+    };
+
+    codex_config::codex_config_toml_set_raw(app, profile.toml)?;
+
+    let now = now_unix_seconds();
+    conn.execute(
+        r#"
+INSERT INTO workspace_codex_profile(workspace_id, profile_id, applied_at)
+VALUES (?1, ?2, ?3)
+ON CONFLICT(workspace_id) DO UPDATE SET
+  profile_id = excluded.profile_id,
+  applied_at = excluded.applied_at
+"#,
+        params![workspace_id, profile_id, now],
+    )
+    .map_err(|e| db_err!("failed to record active codex profile: {e}"))?;
+
+    Ok(CodexProfileApplyResult {
+        workspace_id,
+        profile_id,
+        config_path: config_path.to_string_lossy().to_string(),
+        backup_path: backup_path.map(|p| p.to_string_lossy().to_string()),
+        applied_at: now,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_names_and_prunes_oldest_backups() {
+        assert_eq!(normalize_profile_name(" fast ").unwrap(), "fast");
+        assert!(normalize_profile_name("  ").is_err());
+        assert!(normalize_profile_name(&"x".repeat(MAX_NAME_CHARS + 1)).is_err());
+
+        let names = vec![
+            "config.300.toml".to_string(),
+            "config.100.toml".to_string(),
+            "config.200.toml".to_string(),
+        ];
+        assert_eq!(
+            backups_to_prune(names.clone(), 2),
+            vec!["config.100.toml".to_string()]
+        );
+        assert!(backups_to_prune(names, 3).is_empty());
+    }
+}
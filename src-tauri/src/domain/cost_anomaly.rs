@@ -0,0 +1,165 @@
+//! Usage: Cost anomaly detection for runaway sessions (request_logs aggregation).
+//!
+//! A session is anomalous when its cost over the last `WINDOW_SECS` exceeds `multiplier` times
+//! its average cost per window over the preceding `LOOKBACK_SECS`. Sessions without earlier
+//! spend have no baseline and are never flagged.
+
+use crate::db;
+use crate::shared::error::db_err;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+
+pub(crate) const WINDOW_SECS: i64 = 30 * 60;
+const LOOKBACK_SECS: i64 = 24 * 60 * 60;
+/// Recent spend below this never alerts, whatever the ratio.
+const MIN_RECENT_COST_USD: f64 = 1.0;
+const USD_FEMTO_DENOM: f64 = 1_000_000_000_000_000.0;
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct CostAnomaly {
+    pub session_id: String,
+    pub cli_key: String,
+    pub provider_id: Option<i64>,
+    pub provider_name: Option<String>,
+    /// Cost over the last 30 minutes.
+    pub recent_cost_usd: f64,
+    /// Average cost per 30 minutes before that.
+    pub baseline_cost_usd: f64,
+    pub multiplier: f64,
+    pub detected_at: i64,
+    pub throttled: bool,
+}
+
+struct SessionCost {
+    session_id: String,
+    cli_key: String,
+    recent_femto: i64,
+    prior_femto: i64,
+    first_at: i64,
+}
+
+fn cost_usd_from_femto(v: i64) -> f64 {
+    (v.max(0) as f64) / USD_FEMTO_DENOM
+}
+
+/// Average spend per window between the session's first request and `window_start`.
+fn baseline_per_window(prior_cost_usd: f64, first_at: i64, window_start: i64) -> f64 {
+    let span = window_start.saturating_sub(first_at).max(1);
+    let windows = (span as f64 / WINDOW_SECS as f64).ceil().max(1.0);
+    prior_cost_usd / windows
+}
+
+fn is_anomalous(recent_cost_usd: f64, baseline_cost_usd: f64, multiplier: f64) -> bool {
+    recent_cost_usd >= MIN_RECENT_COST_USD
+        && baseline_cost_usd > 0.0
+        && recent_cost_usd > baseline_cost_usd * multiplier
+}
+
+/// Sessions that are anomalous at `now_unix`.
+pub(crate) fn detect(
+    db: &db::Db,
+    now_unix: i64,
+    multiplier: f64,
+) -> crate::shared::error::AppResult<Vec<CostAnomaly>> {
+    let window_start = now_unix.saturating_sub(WINDOW_SECS);
+    let lookback_start = now_unix.saturating_sub(LOOKBACK_SECS);
+
+    let conn = db.open_connection()?;
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT
+  session_id,
+  cli_key,
+  COALESCE(SUM(CASE WHEN created_at >= ?2 THEN cost_usd_femto ELSE 0 END), 0) AS recent_femto,
+  COALESCE(SUM(CASE WHEN created_at < ?2 THEN cost_usd_femto ELSE 0 END), 0) AS prior_femto,
+  MIN(created_at) AS first_at
+FROM request_logs
+WHERE created_at >= ?1
+  AND session_id IS NOT NULL
+  AND session_id != ''
+  AND excluded_from_stats = 0
+  AND cost_usd_femto IS NOT NULL
+GROUP BY session_id, cli_key
+HAVING recent_femto > 0 AND prior_femto > 0
+"#,
+        )
+        .map_err(|e| db_err!("failed to prepare session cost query: {e}"))?;
+    let rows = stmt
+        .query_map(params![lookback_start, window_start], |row| {
+            Ok(SessionCost {
+                session_id: row.get("session_id")?,
+                cli_key: row.get("cli_key")?,
+                recent_femto: row.get("recent_femto")?,
+                prior_femto: row.get("prior_femto")?,
+                first_at: row.get("first_at")?,
+            })
+        })
+        .map_err(|e| db_err!("failed to query session costs: {e}"))?;
+
+    let mut out = Vec::new();
+    for row in rows {
+        let row = row.map_err(|e| db_err!("failed to read session cost row: {e}"))?;
+        let recent_cost_usd = cost_usd_from_femto(row.recent_femto);
+        let baseline_cost_usd = baseline_per_window(
+            cost_usd_from_femto(row.prior_femto),
+            row.first_at,
+            window_start,
+        );
+        if !is_anomalous(recent_cost_usd, baseline_cost_usd, multiplier) {
+            continue;
+        }
+
+        let provider: Option<(Option<i64>, Option<String>)> = conn
+            .query_row(
+                r#"
+SELECT r.final_provider_id, p.name
+FROM request_logs r
+LEFT JOIN providers p ON p.id = r.final_provider_id
+WHERE r.session_id = ?1 AND r.cli_key = ?2 AND r.created_at >= ?3
+ORDER BY r.created_at_ms DESC
+LIMIT 1
+"#,
+                params![row.session_id, row.cli_key, window_start],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .optional()
+            .map_err(|e| db_err!("failed to query session provider: {e}"))?;
+        let (provider_id, provider_name) = provider.unwrap_or((None, None));
+
+        out.push(CostAnomaly {
+            session_id: row.session_id,
+            cli_key: row.cli_key,
+            provider_id,
+            provider_name,
+            recent_cost_usd,
+            baseline_cost_usd,
+            multiplier,
+            detected_at: now_unix,
+            throttled: false,
+        });
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn baseline_averages_prior_spend_per_window() {
+        // 2h of history before the window = 4 windows.
+        assert_eq!(baseline_per_window(8.0, 0, 4 * WINDOW_SECS), 2.0);
+        // A partial window counts as a whole one; a first request right at the boundary as one.
+        assert_eq!(baseline_per_window(3.0, 0, WINDOW_SECS + 1), 1.5);
+        assert_eq!(baseline_per_window(3.0, 100, 100), 3.0);
+    }
+
+    #[test]
+    fn anomaly_needs_ratio_and_minimum_spend() {
+        assert!(is_anomalous(12.0, 2.0, 5.0));
+        assert!(!is_anomalous(10.0, 2.0, 5.0));
+        assert!(!is_anomalous(0.5, 0.01, 5.0));
+        assert!(!is_anomalous(12.0, 0.0, 5.0));
+    }
+}
@@ -10,7 +10,7 @@ const USD_FEMTO_DENOM: f64 = 1_000_000_000_000_000.0;
 const SQL_MODEL_KEY_EXPR: &str = "COALESCE(NULLIF(TRIM(requested_model), ''), 'Unknown')";
 
 /// Common query parameters shared by all cost analytics endpoints.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, specta::Type)]
 #[serde(rename_all = "camelCase")]
 pub struct CostQueryParams {
     pub period: String,
@@ -21,7 +21,7 @@ pub struct CostQueryParams {
     pub model: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct CostSummaryV1 {
     pub requests_total: i64,
     pub requests_success: i64,
@@ -31,7 +31,7 @@ pub struct CostSummaryV1 {
     pub avg_cost_usd_per_covered_success: Option<f64>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct CostTrendRowV1 {
     pub day: String,
     pub hour: Option<i64>,
@@ -40,7 +40,7 @@ pub struct CostTrendRowV1 {
     pub cost_covered_success: i64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct CostProviderBreakdownRowV1 {
     pub cli_key: String,
     pub provider_id: i64,
@@ -48,9 +48,14 @@ pub struct CostProviderBreakdownRowV1 {
     pub requests_success: i64,
     pub cost_covered_success: i64,
     pub cost_usd: f64,
+    /// Successful requests sent with an extended thinking budget.
+    pub thinking_requests: i64,
+    /// Sum of the thinking budgets sent upstream. Thinking is billed as output tokens without a
+    /// separate count, so this is an upper bound of the thinking spend.
+    pub thinking_budget_tokens: i64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct CostModelBreakdownRowV1 {
     pub model: String,
     pub requests_success: i64,
@@ -58,7 +63,7 @@ pub struct CostModelBreakdownRowV1 {
     pub cost_usd: f64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct CostTopRequestRowV1 {
     pub log_id: i64,
     pub trace_id: String,
@@ -75,7 +80,7 @@ pub struct CostTopRequestRowV1 {
     pub created_at: i64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct CostScatterCliProviderModelRowV1 {
     pub cli_key: String,
     pub provider_name: String,
@@ -85,7 +90,7 @@ pub struct CostScatterCliProviderModelRowV1 {
     pub total_duration_ms: i64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct CostBackfillReportV1 {
     pub scanned: i64,
     pub updated: i64,
@@ -400,7 +405,9 @@ SELECT
   COALESCE(p.name, 'Unknown') AS provider_name,
   COUNT(*) AS requests_success,
   SUM(CASE WHEN r.cost_usd_femto IS NOT NULL THEN 1 ELSE 0 END) AS cost_covered_success,
-  SUM(COALESCE(r.cost_usd_femto, 0)) AS total_cost_usd_femto
+  SUM(COALESCE(r.cost_usd_femto, 0)) AS total_cost_usd_femto,
+  SUM(CASE WHEN r.thinking_budget_tokens IS NOT NULL THEN 1 ELSE 0 END) AS thinking_requests,
+  SUM(COALESCE(r.thinking_budget_tokens, 0)) AS thinking_budget_tokens
 FROM request_logs r
 LEFT JOIN providers p ON p.id = r.final_provider_id
 WHERE r.excluded_from_stats = 0
@@ -436,6 +443,11 @@ LIMIT ?6
                     .get::<_, Option<i64>>("total_cost_usd_femto")?
                     .unwrap_or(0)
                     .max(0);
+                let thinking_requests: i64 =
+                    row.get::<_, Option<i64>>("thinking_requests")?.unwrap_or(0);
+                let thinking_budget_tokens: i64 = row
+                    .get::<_, Option<i64>>("thinking_budget_tokens")?
+                    .unwrap_or(0);
 
                 Ok(CostProviderBreakdownRowV1 {
                     cli_key,
@@ -444,6 +456,8 @@ LIMIT ?6
                     requests_success: requests_success.max(0),
                     cost_covered_success: cost_covered_success.max(0),
                     cost_usd: cost_usd_from_femto(total_cost_usd_femto),
+                    thinking_requests: thinking_requests.max(0),
+                    thinking_budget_tokens: thinking_budget_tokens.max(0),
                 })
             },
         )
@@ -0,0 +1,530 @@
+//! Usage: Claude Code hook definitions (DB persistence, per-workspace enablement, settings.json sync).
+
+use crate::claude_settings::{self, ClaudeHooksSyncBackup, ManagedClaudeHook};
+use crate::db;
+use crate::shared::error::db_err;
+use crate::shared::time::now_unix_seconds;
+use crate::workspaces;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+const HOOKS_CLI_KEY: &str = "claude";
+const HOOK_EVENTS: [&str; 9] = [
+    "PreToolUse",
+    "PostToolUse",
+    "Notification",
+    "UserPromptSubmit",
+    "Stop",
+    "SubagentStop",
+    "PreCompact",
+    "SessionStart",
+    "SessionEnd",
+];
+const MAX_HOOK_TIMEOUT_SECS: u32 = 60 * 60;
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct HookSummary {
+    pub id: i64,
+    pub name: String,
+    pub event: String,
+    pub matcher: String,
+    pub command: String,
+    pub timeout_secs: Option<u32>,
+    pub enabled: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct HookTemplate {
+    pub key: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub event: &'static str,
+    pub matcher: &'static str,
+    pub command: &'static str,
+    pub timeout_secs: Option<u32>,
+}
+
+const HOOK_TEMPLATES: [HookTemplate; 4] = [
+    HookTemplate {
+        key: "prettier_after_edit",
+        name: "编辑后自动格式化",
+        description: "Edit/Write 完成后对改动文件执行 Prettier",
+        event: "PostToolUse",
+        matcher: "Edit|MultiEdit|Write",
+        command:
+            "jq -r '.tool_input.file_path // empty' | xargs -r npx --no-install prettier --write",
+        timeout_secs: Some(60),
+    },
+    HookTemplate {
+        key: "bash_command_log",
+        name: "记录 Bash 命令",
+        description: "执行前把 Bash 命令追加到 ~/.claude/bash-command-log.txt",
+        event: "PreToolUse",
+        matcher: "Bash",
+        command: "jq -r '.tool_input.command' >> ~/.claude/bash-command-log.txt",
+        timeout_secs: None,
+    },
+    HookTemplate {
+        key: "desktop_notify",
+        name: "等待输入时桌面通知",
+        description: "Claude Code 需要确认或等待输入时弹出系统通知",
+        event: "Notification",
+        matcher: "",
+        command: "notify-send 'Claude Code' 'Claude Code 正在等待你的输入'",
+        timeout_secs: None,
+    },
+    HookTemplate {
+        key: "tests_on_stop",
+        name: "结束时运行测试",
+        description: "回复结束后运行 npm test，失败输出会反馈给 Claude",
+        event: "Stop",
+        matcher: "",
+        command: "npm test --silent",
+        timeout_secs: Some(300),
+    },
+];
+
+pub fn templates() -> Vec<HookTemplate> {
+    HOOK_TEMPLATES.to_vec()
+}
+
+fn normalize_hook_name(name: &str) -> crate::shared::error::AppResult<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("SEC_INVALID_INPUT: hook name is required"
+            .to_string()
+            .into());
+    }
+    Ok(name.to_string())
+}
+
+fn normalize_hook_event(event: &str) -> crate::shared::error::AppResult<String> {
+    let event = event.trim();
+    if !HOOK_EVENTS.contains(&event) {
+        return Err(format!("SEC_INVALID_INPUT: unsupported hook event={event}").into());
+    }
+    Ok(event.to_string())
+}
+
+fn normalize_hook_command(command: &str) -> crate::shared::error::AppResult<String> {
+    let command = command.trim();
+    if command.is_empty() {
+        return Err("SEC_INVALID_INPUT: hook command is required"
+            .to_string()
+            .into());
+    }
+    Ok(command.to_string())
+}
+
+fn validate_timeout_secs(timeout_secs: Option<u32>) -> crate::shared::error::AppResult<()> {
+    match timeout_secs {
+        Some(v) if v == 0 || v > MAX_HOOK_TIMEOUT_SECS => Err(format!(
+            "SEC_INVALID_INPUT: hook timeout_secs must be between 1 and {MAX_HOOK_TIMEOUT_SECS}"
+        )
+        .into()),
+        _ => Ok(()),
+    }
+}
+
+fn ensure_claude_workspace(
+    conn: &Connection,
+    workspace_id: i64,
+) -> crate::shared::error::AppResult<()> {
+    let cli_key = workspaces::get_cli_key_by_id(conn, workspace_id)?;
+    if cli_key != HOOKS_CLI_KEY {
+        return Err(format!(
+            "SEC_INVALID_INPUT: hooks are only supported for claude workspaces (cli_key={cli_key})"
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn row_to_summary(row: &rusqlite::Row<'_>) -> Result<HookSummary, rusqlite::Error> {
+    Ok(HookSummary {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        event: row.get("event")?,
+        matcher: row.get("matcher")?,
+        command: row.get("command")?,
+        timeout_secs: row
+            .get::<_, Option<i64>>("timeout_secs")?
+            .and_then(|v| u32::try_from(v).ok()),
+        enabled: row.get::<_, i64>("enabled")? != 0,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+fn get_by_id_for_workspace(
+    conn: &Connection,
+    workspace_id: i64,
+    hook_id: i64,
+) -> crate::shared::error::AppResult<HookSummary> {
+    conn.query_row(
+        r#"
+SELECT
+  h.id,
+  h.name,
+  h.event,
+  h.matcher,
+  h.command,
+  h.timeout_secs,
+  CASE WHEN e.hook_id IS NULL THEN 0 ELSE 1 END AS enabled,
+  h.created_at,
+  h.updated_at
+FROM claude_hooks h
+LEFT JOIN workspace_hook_enabled e
+  ON e.workspace_id = ?1 AND e.hook_id = h.id
+WHERE h.id = ?2
+"#,
+        params![workspace_id, hook_id],
+        row_to_summary,
+    )
+    .optional()
+    .map_err(|e| db_err!("failed to query hook: {e}"))?
+    .ok_or_else(|| crate::shared::error::AppError::from("DB_NOT_FOUND: hook not found"))
+}
+
+pub fn list_for_workspace(
+    db: &db::Db,
+    workspace_id: i64,
+) -> crate::shared::error::AppResult<Vec<HookSummary>> {
+    let conn = db.open_connection()?;
+    ensure_claude_workspace(&conn, workspace_id)?;
+
+    let mut stmt = conn
+        .prepare_cached(
+            r#"
+    SELECT
+      h.id,
+      h.name,
+      h.event,
+      h.matcher,
+      h.command,
+      h.timeout_secs,
+      CASE WHEN e.hook_id IS NULL THEN 0 ELSE 1 END AS enabled,
+      h.created_at,
+      h.updated_at
+    FROM claude_hooks h
+    LEFT JOIN workspace_hook_enabled e
+      ON e.workspace_id = ?1 AND e.hook_id = h.id
+    ORDER BY h.event ASC, h.id ASC
+    "#,
+        )
+        .map_err(|e| db_err!("failed to prepare query: {e}"))?;
+
+    let rows = stmt
+        .query_map([workspace_id], row_to_summary)
+        .map_err(|e| db_err!("failed to list hooks: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| db_err!("failed to read hook row: {e}"))?);
+    }
+    Ok(items)
+}
+
+fn list_enabled_for_workspace(
+    conn: &Connection,
+    workspace_id: i64,
+) -> crate::shared::error::AppResult<Vec<ManagedClaudeHook>> {
+    let mut stmt = conn
+        .prepare_cached(
+            r#"
+    SELECT h.event, h.matcher, h.command, h.timeout_secs
+    FROM claude_hooks h
+    JOIN workspace_hook_enabled e
+      ON e.hook_id = h.id
+    WHERE e.workspace_id = ?1
+    ORDER BY h.event ASC, h.id ASC
+    "#,
+        )
+        .map_err(|e| db_err!("failed to prepare enabled hooks query: {e}"))?;
+
+    let rows = stmt
+        .query_map([workspace_id], |row| {
+            Ok(ManagedClaudeHook {
+                event: row.get(0)?,
+                matcher: row.get(1)?,
+                command: row.get(2)?,
+                timeout_secs: row
+                    .get::<_, Option<i64>>(3)?
+                    .and_then(|v| u32::try_from(v).ok()),
+            })
+        })
+        .map_err(|e| db_err!("failed to query enabled hooks: {e}"))?;
+
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row.map_err(|e| db_err!("failed to read enabled hook row: {e}"))?);
+    }
+    Ok(out)
+}
+
+pub(crate) fn sync_cli_for_workspace(
+    app: &crate::AppHandle,
+    conn: &Connection,
+    workspace_id: i64,
+) -> crate::shared::error::AppResult<()> {
+    ensure_claude_workspace(conn, workspace_id)?;
+    let hooks = list_enabled_for_workspace(conn, workspace_id)?;
+    claude_settings::claude_settings_sync_hooks(app, &hooks)
+}
+
+fn sync_active_workspace(
+    app: &crate::AppHandle,
+    conn: &Connection,
+) -> crate::shared::error::AppResult<()> {
+    match workspaces::active_id_by_cli(conn, HOOKS_CLI_KEY)? {
+        Some(workspace_id) => sync_cli_for_workspace(app, conn, workspace_id),
+        None => claude_settings::claude_settings_sync_hooks(app, &[]),
+    }
+}
+
+fn hook_identity_taken(
+    conn: &Connection,
+    hook_id: Option<i64>,
+    event: &str,
+    matcher: &str,
+    command: &str,
+) -> crate::shared::error::AppResult<bool> {
+    let existing: Option<i64> = conn
+        .query_row(
+            r#"
+SELECT id
+FROM claude_hooks
+WHERE event = ?1 AND matcher = ?2 AND command = ?3 AND (?4 IS NULL OR id != ?4)
+LIMIT 1
+"#,
+            params![event, matcher, command, hook_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query hook identity: {e}"))?;
+    Ok(existing.is_some())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn upsert(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    hook_id: Option<i64>,
+    workspace_id: i64,
+    name: &str,
+    event: &str,
+    matcher: &str,
+    command: &str,
+    timeout_secs: Option<u32>,
+) -> crate::shared::error::AppResult<HookSummary> {
+    let name = normalize_hook_name(name)?;
+    let event = normalize_hook_event(event)?;
+    let matcher = matcher.trim().to_string();
+    let command = normalize_hook_command(command)?;
+    validate_timeout_secs(timeout_secs)?;
+
+    let mut conn = db.open_connection()?;
+    ensure_claude_workspace(&conn, workspace_id)?;
+    let now = now_unix_seconds();
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| db_err!("failed to start transaction: {e}"))?;
+
+    // Identical (event, matcher, command) entries would be indistinguishable in settings.json.
+    if hook_identity_taken(&tx, hook_id, &event, &matcher, &command)? {
+        return Err(crate::shared::error::AppError::new(
+            "DB_CONSTRAINT",
+            format!("hook already exists for event={event}, matcher={matcher}"),
+        ));
+    }
+
+    let id = match hook_id {
+        None => {
+            tx.execute(
+                r#"
+INSERT INTO claude_hooks(
+  name,
+  event,
+  matcher,
+  command,
+  timeout_secs,
+  created_at,
+  updated_at
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
+"#,
+                params![name, event, matcher, command, timeout_secs, now],
+            )
+            .map_err(|e| db_err!("failed to insert hook: {e}"))?;
+            tx.last_insert_rowid()
+        }
+        Some(id) => {
+            let changed = tx
+                .execute(
+                    r#"
+UPDATE claude_hooks
+SET
+  name = ?1,
+  event = ?2,
+  matcher = ?3,
+  command = ?4,
+  timeout_secs = ?5,
+  updated_at = ?6
+WHERE id = ?7
+"#,
+                    params![name, event, matcher, command, timeout_secs, now, id],
+                )
+                .map_err(|e| db_err!("failed to update hook: {e}"))?;
+            if changed == 0 {
+                return Err("DB_NOT_FOUND: hook not found".to_string().into());
+            }
+            id
+        }
+    };
+
+    let backup = ClaudeHooksSyncBackup::capture(app)?;
+    if let Err(err) = sync_active_workspace(app, &tx) {
+        backup.restore();
+        return Err(err);
+    }
+
+    if let Err(err) = tx.commit() {
+        backup.restore();
+        return Err(db_err!("failed to commit: {err}"));
+    }
+
+    get_by_id_for_workspace(&conn, workspace_id, id)
+}
+
+pub fn set_enabled(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    workspace_id: i64,
+    hook_id: i64,
+    enabled: bool,
+) -> crate::shared::error::AppResult<HookSummary> {
+    let mut conn = db.open_connection()?;
+    let now = now_unix_seconds();
+    let tx = conn
+        .transaction()
+        .map_err(|e| db_err!("failed to start transaction: {e}"))?;
+
+    ensure_claude_workspace(&tx, workspace_id)?;
+    let _ = get_by_id_for_workspace(&tx, workspace_id, hook_id)?;
+    let should_sync = workspaces::is_active_workspace(&tx, workspace_id)?;
+
+    if enabled {
+        tx.execute(
+            r#"
+INSERT INTO workspace_hook_enabled(workspace_id, hook_id, created_at, updated_at)
+VALUES (?1, ?2, ?3, ?3)
+ON CONFLICT(workspace_id, hook_id) DO UPDATE SET
+  updated_at = excluded.updated_at
+"#,
+            params![workspace_id, hook_id, now],
+        )
+        .map_err(|e| db_err!("failed to enable hook: {e}"))?;
+    } else {
+        tx.execute(
+            "DELETE FROM workspace_hook_enabled WHERE workspace_id = ?1 AND hook_id = ?2",
+            params![workspace_id, hook_id],
+        )
+        .map_err(|e| db_err!("failed to disable hook: {e}"))?;
+    }
+
+    let backup = if should_sync {
+        Some(ClaudeHooksSyncBackup::capture(app)?)
+    } else {
+        None
+    };
+
+    if should_sync {
+        if let Err(err) = sync_cli_for_workspace(app, &tx, workspace_id) {
+            if let Some(backup) = backup {
+                backup.restore();
+            }
+            return Err(err);
+        }
+    }
+
+    if let Err(err) = tx.commit() {
+        if let Some(backup) = backup {
+            backup.restore();
+        }
+        return Err(db_err!("failed to commit: {err}"));
+    }
+
+    get_by_id_for_workspace(&conn, workspace_id, hook_id)
+}
+
+pub fn delete(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    hook_id: i64,
+) -> crate::shared::error::AppResult<()> {
+    let mut conn = db.open_connection()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| db_err!("failed to start transaction: {e}"))?;
+
+    let changed = tx
+        .execute("DELETE FROM claude_hooks WHERE id = ?1", params![hook_id])
+        .map_err(|e| db_err!("failed to delete hook: {e}"))?;
+    if changed == 0 {
+        return Err("DB_NOT_FOUND: hook not found".to_string().into());
+    }
+
+    let backup = ClaudeHooksSyncBackup::capture(app)?;
+    if let Err(err) = sync_active_workspace(app, &tx) {
+        backup.restore();
+        return Err(err);
+    }
+
+    if let Err(err) = tx.commit() {
+        backup.restore();
+        return Err(db_err!("failed to commit: {err}"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_hook_event_accepts_known_events_only() {
+        assert_eq!(normalize_hook_event(" PreToolUse ").unwrap(), "PreToolUse");
+        assert!(normalize_hook_event("pretooluse").is_err());
+        assert!(normalize_hook_event("").is_err());
+    }
+
+    #[test]
+    fn normalize_hook_name_and_command_reject_blank_values() {
+        assert!(normalize_hook_name("   ").is_err());
+        assert!(normalize_hook_command("\n").is_err());
+        assert_eq!(normalize_hook_command("  echo hi ").unwrap(), "echo hi");
+    }
+
+    #[test]
+    fn validate_timeout_secs_bounds() {
+        assert!(validate_timeout_secs(None).is_ok());
+        assert!(validate_timeout_secs(Some(30)).is_ok());
+        assert!(validate_timeout_secs(Some(0)).is_err());
+        assert!(validate_timeout_secs(Some(MAX_HOOK_TIMEOUT_SECS + 1)).is_err());
+    }
+
+    #[test]
+    fn templates_use_supported_events() {
+        for template in templates() {
+            assert!(
+                normalize_hook_event(template.event).is_ok(),
+                "{}",
+                template.key
+            );
+            assert!(normalize_hook_command(template.command).is_ok());
+        }
+    }
+}
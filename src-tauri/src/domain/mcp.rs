@@ -3,14 +3,21 @@
 mod backups;
 mod cli_specs;
 mod db;
+mod drift;
 mod import;
 mod local_swap;
+mod supervisor;
 mod sync;
 mod types;
 mod validate;
 
 pub use db::{delete, list_for_workspace, set_enabled, upsert};
+pub use drift::{adopt_external_edits, apply_db_state, sync_status};
 pub use import::{import_servers, import_servers_from_workspace_cli, parse_json};
 pub(crate) use local_swap::swap_local_mcp_servers_for_workspace_switch;
+pub use supervisor::{runtime_status, supervise_start, supervise_stop, supervise_stop_all};
 pub(crate) use sync::{list_enabled_for_cli, sync_cli_for_workspace, sync_one_cli};
-pub use types::{McpImportReport, McpImportServer, McpParseResult, McpServerSummary};
+pub use types::{
+    McpDriftStatus, McpImportReport, McpImportServer, McpParseResult, McpRuntimeState,
+    McpServerRuntimeStatus, McpServerSummary, McpSyncCliStatus,
+};
@@ -104,7 +104,7 @@ fn row_to_summary(row: &rusqlite::Row<'_>) -> Result<McpServerSummary, rusqlite:
     })
 }
 
-fn get_by_id(
+pub(super) fn get_by_id(
     conn: &Connection,
     server_id: i64,
 ) -> crate::shared::error::AppResult<McpServerSummary> {
@@ -214,7 +214,7 @@ pub fn list_for_workspace(
 
 #[allow(clippy::too_many_arguments)]
 pub fn upsert(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     db: &db::Db,
     server_id: Option<i64>,
     server_key: &str,
@@ -402,7 +402,7 @@ WHERE id = ?11
 }
 
 pub fn set_enabled(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     db: &db::Db,
     workspace_id: i64,
     server_id: i64,
@@ -463,7 +463,7 @@ ON CONFLICT(workspace_id, server_id) DO UPDATE SET
 }
 
 pub fn delete(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     db: &db::Db,
     server_id: i64,
 ) -> crate::shared::error::AppResult<()> {
@@ -639,3 +639,136 @@ WHERE id = ?11
         }
     }
 }
+
+/// Write an on-disk server spec back into `mcp_servers`, keyed by `server_key`.
+///
+/// Existing rows keep their display name; unknown keys are inserted verbatim (no suffixing) so the
+/// adopted entry keeps matching the key already present in the CLI config.
+pub(super) fn adopt_spec_by_key(
+    tx: &Connection,
+    input: &McpImportServer,
+    now: i64,
+) -> crate::shared::error::AppResult<i64> {
+    let server_key = input.server_key.trim();
+    validate_server_key(server_key)?;
+    let transport = input.transport.trim().to_lowercase();
+    validate_transport(&transport)?;
+
+    let command = input
+        .command
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty());
+    let url = input
+        .url
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty());
+    let cwd = input
+        .cwd
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty());
+
+    if transport == "stdio" && command.is_none() {
+        return Err(format!(
+            "SEC_INVALID_INPUT: stdio command is required for server='{server_key}'"
+        )
+        .into());
+    }
+    if transport == "http" && url.is_none() {
+        return Err(
+            format!("SEC_INVALID_INPUT: http url is required for server='{server_key}'").into(),
+        );
+    }
+
+    let args: Vec<String> = input
+        .args
+        .iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    let args_json = args_to_json(&args)?;
+    let env_json = map_to_json(&input.env, "env")?;
+    let headers_json = map_to_json(&input.headers, "headers")?;
+
+    let existing_id: Option<i64> = tx
+        .query_row(
+            "SELECT id FROM mcp_servers WHERE server_key = ?1",
+            params![server_key],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query mcp server_key: {e}"))?;
+
+    match existing_id {
+        Some(id) => {
+            tx.execute(
+                r#"
+UPDATE mcp_servers
+SET
+  transport = ?1,
+  command = ?2,
+  args_json = ?3,
+  env_json = ?4,
+  cwd = ?5,
+  url = ?6,
+  headers_json = ?7,
+  updated_at = ?8
+WHERE id = ?9
+"#,
+                params![
+                    transport,
+                    command,
+                    args_json,
+                    env_json,
+                    cwd,
+                    url,
+                    headers_json,
+                    now,
+                    id
+                ],
+            )
+            .map_err(|e| db_err!("failed to adopt mcp server: {e}"))?;
+            Ok(id)
+        }
+        None => {
+            let name = input.name.trim();
+            let name = if name.is_empty() { server_key } else { name };
+            tx.execute(
+                r#"
+INSERT INTO mcp_servers(
+  server_key,
+  name,
+  normalized_name,
+  transport,
+  command,
+  args_json,
+  env_json,
+  cwd,
+  url,
+  headers_json,
+  created_at,
+  updated_at
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+"#,
+                params![
+                    server_key,
+                    name,
+                    normalize_name(name),
+                    transport,
+                    command,
+                    args_json,
+                    env_json,
+                    cwd,
+                    url,
+                    headers_json,
+                    now,
+                    now
+                ],
+            )
+            .map_err(|e| db_err!("failed to insert adopted mcp server: {e}"))?;
+            Ok(tx.last_insert_rowid())
+        }
+    }
+}
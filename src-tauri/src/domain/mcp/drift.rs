@@ -0,0 +1,294 @@
+//! Usage: Detect drift between DB-managed MCP servers and the actual CLI config files.
+
+use crate::db;
+use crate::mcp_sync;
+use crate::shared::error::db_err;
+use crate::shared::time::now_unix_seconds;
+use crate::workspaces;
+use rusqlite::{params, Connection};
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::backups::{CliBackupSnapshots, SingleCliBackup};
+use super::cli_specs::{validate_cli_key, MCP_CLI_KEYS};
+use super::db::adopt_spec_by_key;
+use super::import::{parse_codex_toml_mcp_servers, parse_json_mcp_servers_map};
+use super::sync::{list_enabled_for_cli, sync_all_cli, sync_one_cli};
+use super::types::{McpDriftServer, McpDriftStatus, McpImportServer, McpSyncCliStatus};
+
+fn read_actual_servers<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<Option<Vec<McpImportServer>>> {
+    let bytes = mcp_sync::read_target_bytes(app, cli_key)
+        .map_err(|e| format!("SYSTEM_ERROR: failed to read {cli_key} target config: {e}"))?;
+    let Some(raw) = bytes else {
+        return Ok(None);
+    };
+
+    let text = String::from_utf8(raw)
+        .map_err(|e| format!("SEC_INVALID_INPUT: {cli_key} target config is not utf8: {e}"))?;
+    if text.trim().is_empty() {
+        return Ok(Some(Vec::new()));
+    }
+
+    let servers = if cli_key == "codex" {
+        parse_codex_toml_mcp_servers(&text)?
+    } else {
+        let root: serde_json::Value = serde_json::from_str(&text)
+            .map_err(|e| format!("SEC_INVALID_INPUT: invalid {cli_key} config json: {e}"))?;
+        match root.get("mcpServers").and_then(|v| v.as_object()) {
+            Some(servers_obj) => parse_json_mcp_servers_map(servers_obj)?,
+            None => Vec::new(),
+        }
+    };
+
+    Ok(Some(servers))
+}
+
+fn import_to_sync(server: &McpImportServer) -> mcp_sync::McpServerForSync {
+    mcp_sync::McpServerForSync {
+        server_key: server.server_key.clone(),
+        transport: server.transport.trim().to_lowercase(),
+        command: server.command.clone(),
+        args: server
+            .args
+            .iter()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        env: server.env.clone(),
+        cwd: server.cwd.clone(),
+        url: server.url.clone(),
+        headers: server.headers.clone(),
+    }
+}
+
+/// Classify every server key seen in the DB, the sync manifest or the target file.
+fn diff_specs(
+    desired: &BTreeMap<String, serde_json::Value>,
+    actual: &BTreeMap<String, serde_json::Value>,
+    managed_keys: &[String],
+) -> Vec<McpDriftServer> {
+    let managed: BTreeSet<&str> = managed_keys.iter().map(String::as_str).collect();
+    let mut keys: BTreeSet<&str> = desired.keys().map(String::as_str).collect();
+    keys.extend(actual.keys().map(String::as_str));
+
+    let mut out = Vec::with_capacity(keys.len());
+    for key in keys {
+        let want = desired.get(key);
+        let have = actual.get(key);
+        let status = match (want, have) {
+            (Some(want), Some(have)) if want == have => McpDriftStatus::InSync,
+            (Some(_), Some(_)) => McpDriftStatus::Modified,
+            (Some(_), None) => McpDriftStatus::Missing,
+            (None, Some(_)) if managed.contains(key) => McpDriftStatus::Stale,
+            (None, Some(_)) => McpDriftStatus::External,
+            (None, None) => continue,
+        };
+        out.push(McpDriftServer {
+            server_key: key.to_string(),
+            status,
+            desired: want.cloned(),
+            actual: have.cloned(),
+        });
+    }
+    out
+}
+
+fn cli_status<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    conn: &Connection,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<McpSyncCliStatus> {
+    validate_cli_key(cli_key)?;
+
+    let workspace_id = workspaces::active_id_by_cli(conn, cli_key)?;
+    let managed_keys = mcp_sync::read_managed_keys(app, cli_key)?;
+
+    let mut desired = BTreeMap::new();
+    for server in list_enabled_for_cli(conn, cli_key)? {
+        let spec = mcp_sync::render_server_spec(cli_key, &server)?;
+        desired.insert(server.server_key, spec);
+    }
+
+    let (target_exists, actual_servers, parse_error) = match read_actual_servers(app, cli_key) {
+        Ok(Some(servers)) => (true, servers, None),
+        Ok(None) => (false, Vec::new(), None),
+        Err(err) => (true, Vec::new(), Some(err.to_string())),
+    };
+
+    let mut actual = BTreeMap::new();
+    for server in &actual_servers {
+        // Entries the writer cannot render (e.g. unknown transport) always count as drift.
+        let spec = mcp_sync::render_server_spec(cli_key, &import_to_sync(server))
+            .unwrap_or(serde_json::Value::Null);
+        actual.insert(server.server_key.clone(), spec);
+    }
+
+    let servers = if parse_error.is_some() {
+        Vec::new()
+    } else {
+        diff_specs(&desired, &actual, &managed_keys)
+    };
+
+    // External entries are left alone by sync, so they do not count as drift.
+    let in_sync = parse_error.is_none()
+        && servers
+            .iter()
+            .all(|s| matches!(s.status, McpDriftStatus::InSync | McpDriftStatus::External));
+
+    Ok(McpSyncCliStatus {
+        cli_key: cli_key.to_string(),
+        workspace_id,
+        target_exists,
+        parse_error,
+        in_sync,
+        servers,
+    })
+}
+
+pub fn sync_status<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    db: &db::Db,
+    cli_key: Option<&str>,
+) -> crate::shared::error::AppResult<Vec<McpSyncCliStatus>> {
+    let conn = db.open_connection()?;
+    match cli_key.map(str::trim) {
+        Some(cli_key) => Ok(vec![cli_status(app, &conn, cli_key)?]),
+        None => MCP_CLI_KEYS
+            .iter()
+            .map(|cli_key| cli_status(app, &conn, cli_key))
+            .collect(),
+    }
+}
+
+/// Overwrite drifted entries in the `cli_key` config with the DB state.
+pub fn apply_db_state<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    db: &db::Db,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<McpSyncCliStatus> {
+    let cli_key = cli_key.trim();
+    validate_cli_key(cli_key)?;
+
+    let conn = db.open_connection()?;
+    let backup = SingleCliBackup::capture(app, cli_key)?;
+    if let Err(err) = sync_one_cli(app, &conn, cli_key) {
+        backup.restore(app, cli_key);
+        return Err(err);
+    }
+
+    cli_status(app, &conn, cli_key)
+}
+
+/// Adopt on-disk edits of `server_keys` back into the DB instead of overwriting them.
+///
+/// Modified entries update the existing server row; external entries are inserted and enabled
+/// for the CLI's active workspace. All CLIs are re-synced because server rows are shared.
+pub fn adopt_external_edits<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    db: &db::Db,
+    cli_key: &str,
+    server_keys: &[String],
+) -> crate::shared::error::AppResult<McpSyncCliStatus> {
+    let cli_key = cli_key.trim();
+    validate_cli_key(cli_key)?;
+    if server_keys.is_empty() {
+        return Err("SEC_INVALID_INPUT: server_keys is required".into());
+    }
+
+    let actual = read_actual_servers(app, cli_key)?
+        .ok_or_else(|| format!("SEC_INVALID_INPUT: {cli_key} target config not found"))?;
+
+    let mut conn = db.open_connection()?;
+    let now = now_unix_seconds();
+    let tx = conn
+        .transaction()
+        .map_err(|e| db_err!("failed to start transaction: {e}"))?;
+
+    let workspace_id = workspaces::active_id_by_cli(&tx, cli_key)?
+        .ok_or_else(|| format!("DB_NOT_FOUND: active workspace not found for cli_key={cli_key}"))?;
+
+    let snapshots = CliBackupSnapshots::capture_all(app)?;
+
+    for server_key in server_keys {
+        let server_key = server_key.trim();
+        let Some(server) = actual.iter().find(|s| s.server_key == server_key) else {
+            return Err(format!(
+                "SEC_INVALID_INPUT: server '{server_key}' not found in {cli_key} config"
+            )
+            .into());
+        };
+
+        let id = adopt_spec_by_key(&tx, server, now)?;
+        tx.execute(
+            r#"
+INSERT INTO workspace_mcp_enabled(workspace_id, server_id, created_at, updated_at)
+VALUES (?1, ?2, ?3, ?3)
+ON CONFLICT(workspace_id, server_id) DO UPDATE SET
+  updated_at = excluded.updated_at
+"#,
+            params![workspace_id, id, now],
+        )
+        .map_err(|e| db_err!("failed to enable adopted mcp server: {e}"))?;
+    }
+
+    if let Err(err) = sync_all_cli(app, &tx) {
+        snapshots.restore_all(app);
+        return Err(err);
+    }
+
+    if let Err(err) = tx.commit() {
+        snapshots.restore_all(app);
+        return Err(db_err!("failed to commit: {err}"));
+    }
+
+    cli_status(app, &conn, cli_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn specs(items: &[(&str, serde_json::Value)]) -> BTreeMap<String, serde_json::Value> {
+        items
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    fn status_of(rows: &[McpDriftServer], key: &str) -> Option<McpDriftStatus> {
+        rows.iter().find(|r| r.server_key == key).map(|r| r.status)
+    }
+
+    #[test]
+    fn diff_specs_classifies_each_key() {
+        let desired = specs(&[
+            ("same", json!({"command": "a"})),
+            ("edited", json!({"command": "b"})),
+            ("gone", json!({"command": "c"})),
+        ]);
+        let actual = specs(&[
+            ("same", json!({"command": "a"})),
+            ("edited", json!({"command": "b2"})),
+            ("leftover", json!({"command": "d"})),
+            ("handmade", json!({"command": "e"})),
+        ]);
+        let managed = vec!["same".to_string(), "leftover".to_string()];
+
+        let rows = diff_specs(&desired, &actual, &managed);
+        assert_eq!(rows.len(), 5);
+        assert_eq!(status_of(&rows, "same"), Some(McpDriftStatus::InSync));
+        assert_eq!(status_of(&rows, "edited"), Some(McpDriftStatus::Modified));
+        assert_eq!(status_of(&rows, "gone"), Some(McpDriftStatus::Missing));
+        assert_eq!(status_of(&rows, "leftover"), Some(McpDriftStatus::Stale));
+        assert_eq!(status_of(&rows, "handmade"), Some(McpDriftStatus::External));
+    }
+
+    #[test]
+    fn diff_specs_is_empty_when_nothing_is_configured() {
+        let rows = diff_specs(&BTreeMap::new(), &BTreeMap::new(), &["old".to_string()]);
+        assert!(rows.is_empty());
+    }
+}
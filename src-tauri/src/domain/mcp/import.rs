@@ -204,7 +204,7 @@ fn parse_json_server_entry(
     })
 }
 
-fn parse_json_mcp_servers_map(
+pub(super) fn parse_json_mcp_servers_map(
     servers_obj: &serde_json::Map<String, serde_json::Value>,
 ) -> Result<Vec<McpImportServer>, String> {
     let mut used_keys = HashSet::new();
@@ -246,7 +246,9 @@ fn extract_toml_string_map(value: Option<&toml::Value>) -> BTreeMap<String, Stri
     out
 }
 
-fn parse_codex_toml_mcp_servers(toml_text: &str) -> Result<Vec<McpImportServer>, String> {
+pub(super) fn parse_codex_toml_mcp_servers(
+    toml_text: &str,
+) -> Result<Vec<McpImportServer>, String> {
     let root: toml::Value = toml::from_str(toml_text)
         .map_err(|e| format!("SEC_INVALID_INPUT: invalid codex toml: {e}"))?;
     let Some(servers) = root.get("mcp_servers").and_then(|v| v.as_table()) else {
@@ -0,0 +1,422 @@
+//! Usage: Optional supervised mode for stdio MCP servers.
+//!
+//! The hub launches its own instance of the server command, keeps stdin open so the server stays
+//! idle instead of exiting, restarts it with exponential backoff after crashes, forwards stderr to
+//! the app log (and keeps a short tail) and samples CPU/memory on demand. This runs independently
+//! of the instances the CLIs launch themselves; it exists to debug flaky servers.
+
+use super::db::get_by_id;
+use super::types::{McpRuntimeState, McpServerRuntimeStatus, McpServerSummary};
+use crate::db;
+use crate::shared::error::AppResult;
+use crate::shared::mutex_ext::MutexExt;
+use crate::shared::time::now_unix_seconds;
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+const STDERR_TAIL_LINES: usize = 50;
+const STDERR_LINE_MAX_CHARS: usize = 2000;
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+const RESTART_BACKOFF_BASE: Duration = Duration::from_secs(1);
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(30);
+/// A run that lasted at least this long resets the consecutive crash counter.
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+/// Consecutive quick crashes before supervision gives up (state `failed`).
+const MAX_CONSECUTIVE_CRASHES: u32 = 10;
+
+#[derive(Debug)]
+struct RuntimeInner {
+    state: McpRuntimeState,
+    pid: Option<u32>,
+    started_at: Option<i64>,
+    restart_count: u32,
+    last_exit_code: Option<i32>,
+    last_exit_at: Option<i64>,
+    last_error: Option<String>,
+    stderr_tail: VecDeque<String>,
+}
+
+#[derive(Debug)]
+struct SupervisedServer {
+    server: McpServerSummary,
+    stop: AtomicBool,
+    inner: Mutex<RuntimeInner>,
+}
+
+impl SupervisedServer {
+    fn is_finished(&self) -> bool {
+        matches!(
+            self.inner.lock_or_recover().state,
+            McpRuntimeState::Stopped | McpRuntimeState::Failed
+        )
+    }
+
+    fn push_stderr(&self, line: String) {
+        let mut inner = self.inner.lock_or_recover();
+        if inner.stderr_tail.len() >= STDERR_TAIL_LINES {
+            inner.stderr_tail.pop_front();
+        }
+        inner.stderr_tail.push_back(line);
+    }
+
+    fn status(&self, stats: Option<ProcessStats>) -> McpServerRuntimeStatus {
+        let inner = self.inner.lock_or_recover();
+        McpServerRuntimeStatus {
+            server_id: self.server.id,
+            server_key: self.server.server_key.clone(),
+            name: self.server.name.clone(),
+            state: inner.state,
+            pid: inner.pid,
+            started_at: inner.started_at,
+            restart_count: inner.restart_count,
+            last_exit_code: inner.last_exit_code,
+            last_exit_at: inner.last_exit_at,
+            last_error: inner.last_error.clone(),
+            cpu_percent: stats.and_then(|s| s.cpu_percent),
+            memory_bytes: stats.and_then(|s| s.memory_bytes),
+            stderr_tail: inner.stderr_tail.iter().cloned().collect(),
+        }
+    }
+}
+
+static SUPERVISORS: OnceLock<Mutex<HashMap<i64, Arc<SupervisedServer>>>> = OnceLock::new();
+
+fn supervisors() -> &'static Mutex<HashMap<i64, Arc<SupervisedServer>>> {
+    SUPERVISORS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub fn supervise_start(db: &db::Db, server_id: i64) -> AppResult<McpServerRuntimeStatus> {
+    let conn = db.open_connection()?;
+    let server = get_by_id(&conn, server_id)?;
+    if server.transport != "stdio" {
+        return Err(
+            "SEC_INVALID_INPUT: only stdio MCP servers can be supervised"
+                .to_string()
+                .into(),
+        );
+    }
+    if server.command.as_deref().unwrap_or("").trim().is_empty() {
+        return Err("SEC_INVALID_INPUT: MCP server command is empty"
+            .to_string()
+            .into());
+    }
+
+    let mut map = supervisors().lock_or_recover();
+    if let Some(existing) = map.get(&server_id) {
+        if !existing.is_finished() {
+            return Ok(existing.status(None));
+        }
+    }
+
+    let supervised = Arc::new(SupervisedServer {
+        server,
+        stop: AtomicBool::new(false),
+        inner: Mutex::new(RuntimeInner {
+            state: McpRuntimeState::Starting,
+            pid: None,
+            started_at: None,
+            restart_count: 0,
+            last_exit_code: None,
+            last_exit_at: None,
+            last_error: None,
+            stderr_tail: VecDeque::new(),
+        }),
+    });
+    map.insert(server_id, supervised.clone());
+    drop(map);
+
+    let worker = supervised.clone();
+    std::thread::Builder::new()
+        .name(format!("mcp-supervisor-{server_id}"))
+        .spawn(move || supervise_loop(worker))
+        .map_err(|e| format!("SYSTEM_ERROR: failed to spawn MCP supervisor thread: {e}"))?;
+
+    Ok(supervised.status(None))
+}
+
+/// Returns `false` when the server was not supervised.
+pub fn supervise_stop(server_id: i64) -> bool {
+    let removed = supervisors().lock_or_recover().remove(&server_id);
+    match removed {
+        Some(supervised) => {
+            supervised.stop.store(true, Ordering::SeqCst);
+            true
+        }
+        None => false,
+    }
+}
+
+pub fn supervise_stop_all() {
+    let drained: Vec<_> = supervisors()
+        .lock_or_recover()
+        .drain()
+        .map(|(_, v)| v)
+        .collect();
+    for supervised in drained {
+        supervised.stop.store(true, Ordering::SeqCst);
+    }
+}
+
+pub fn runtime_status() -> Vec<McpServerRuntimeStatus> {
+    let servers: Vec<_> = supervisors().lock_or_recover().values().cloned().collect();
+    let mut out: Vec<McpServerRuntimeStatus> = servers
+        .iter()
+        .map(|supervised| {
+            let pid = supervised.inner.lock_or_recover().pid;
+            supervised.status(pid.and_then(sample_process_stats))
+        })
+        .collect();
+    out.sort_by(|a, b| a.server_key.cmp(&b.server_key));
+    out
+}
+
+fn build_command(server: &McpServerSummary) -> Command {
+    let program = server.command.as_deref().unwrap_or("").trim();
+
+    // Node-based servers are usually launched through `npx` / `*.cmd` shims on Windows.
+    #[cfg(windows)]
+    let mut cmd = {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(program);
+        cmd.creation_flags(CREATE_NO_WINDOW);
+        cmd
+    };
+    #[cfg(not(windows))]
+    let mut cmd = Command::new(program);
+
+    cmd.args(&server.args)
+        .envs(&server.env)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(cwd) = server.cwd.as_deref().filter(|v| !v.trim().is_empty()) {
+        cmd.current_dir(cwd);
+    }
+    cmd
+}
+
+fn restart_backoff(consecutive_crashes: u32) -> Duration {
+    let shift = consecutive_crashes.saturating_sub(1).min(16);
+    RESTART_BACKOFF_BASE
+        .saturating_mul(1u32 << shift)
+        .min(RESTART_BACKOFF_MAX)
+}
+
+fn spawn_output_readers(supervised: &Arc<SupervisedServer>, child: &mut Child) {
+    if let Some(stdout) = child.stdout.take() {
+        // Drain stdout so the server never blocks on a full pipe; nobody speaks JSON-RPC to it.
+        std::thread::spawn(move || {
+            let mut stdout = stdout;
+            let _ = std::io::copy(&mut stdout, &mut std::io::sink());
+        });
+    }
+    if let Some(stderr) = child.stderr.take() {
+        let supervised = supervised.clone();
+        std::thread::spawn(move || {
+            let server_key = supervised.server.server_key.clone();
+            for line in BufReader::new(stderr).lines() {
+                let Ok(line) = line else { break };
+                let line: String = line.chars().take(STDERR_LINE_MAX_CHARS).collect();
+                tracing::info!(server_key = %server_key, "mcp stderr: {}", line);
+                supervised.push_stderr(line);
+            }
+        });
+    }
+}
+
+fn wait_or_stop(supervised: &SupervisedServer, child: &mut Child) -> Option<Option<i32>> {
+    loop {
+        if supervised.stop.load(Ordering::SeqCst) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return None;
+        }
+        match child.try_wait() {
+            Ok(Some(status)) => return Some(status.code()),
+            Ok(None) => std::thread::sleep(POLL_INTERVAL),
+            Err(err) => {
+                tracing::warn!(
+                    server_key = %supervised.server.server_key,
+                    "mcp supervisor wait failed: {}",
+                    err
+                );
+                let _ = child.kill();
+                return Some(child.wait().ok().and_then(|s| s.code()));
+            }
+        }
+    }
+}
+
+fn sleep_or_stop(supervised: &SupervisedServer, delay: Duration) -> bool {
+    let deadline = Instant::now() + delay;
+    while Instant::now() < deadline {
+        if supervised.stop.load(Ordering::SeqCst) {
+            return false;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    !supervised.stop.load(Ordering::SeqCst)
+}
+
+fn supervise_loop(supervised: Arc<SupervisedServer>) {
+    let server_key = supervised.server.server_key.clone();
+    let mut consecutive_crashes: u32 = 0;
+
+    loop {
+        if supervised.stop.load(Ordering::SeqCst) {
+            break;
+        }
+
+        let spawned_at = Instant::now();
+        let exit_code = match build_command(&supervised.server).spawn() {
+            Ok(mut child) => {
+                {
+                    let mut inner = supervised.inner.lock_or_recover();
+                    inner.state = McpRuntimeState::Running;
+                    inner.pid = Some(child.id());
+                    inner.started_at = Some(now_unix_seconds());
+                    inner.last_error = None;
+                }
+                tracing::info!(server_key = %server_key, pid = child.id(), "mcp server started");
+                spawn_output_readers(&supervised, &mut child);
+                // Holding stdin open keeps stdio servers alive while idle.
+                let _stdin = child.stdin.take();
+                match wait_or_stop(&supervised, &mut child) {
+                    Some(code) => code,
+                    None => break,
+                }
+            }
+            Err(err) => {
+                supervised.inner.lock_or_recover().last_error =
+                    Some(format!("failed to spawn: {err}"));
+                None
+            }
+        };
+
+        if spawned_at.elapsed() >= STABLE_UPTIME {
+            consecutive_crashes = 0;
+        }
+        consecutive_crashes = consecutive_crashes.saturating_add(1);
+        tracing::warn!(
+            server_key = %server_key,
+            exit_code = ?exit_code,
+            consecutive_crashes,
+            "mcp server exited"
+        );
+
+        let give_up = consecutive_crashes >= MAX_CONSECUTIVE_CRASHES;
+        {
+            let mut inner = supervised.inner.lock_or_recover();
+            inner.pid = None;
+            inner.last_exit_code = exit_code;
+            inner.last_exit_at = Some(now_unix_seconds());
+            inner.state = if give_up {
+                McpRuntimeState::Failed
+            } else {
+                McpRuntimeState::Backoff
+            };
+        }
+        if give_up {
+            tracing::error!(server_key = %server_key, "mcp supervisor gave up after repeated crashes");
+            return;
+        }
+
+        if !sleep_or_stop(&supervised, restart_backoff(consecutive_crashes)) {
+            break;
+        }
+        supervised.inner.lock_or_recover().restart_count += 1;
+    }
+
+    let mut inner = supervised.inner.lock_or_recover();
+    inner.state = McpRuntimeState::Stopped;
+    inner.pid = None;
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ProcessStats {
+    cpu_percent: Option<f64>,
+    memory_bytes: Option<u64>,
+}
+
+#[cfg(unix)]
+fn sample_process_stats(pid: u32) -> Option<ProcessStats> {
+    let out = Command::new("ps")
+        .args(["-o", "%cpu=,rss=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    parse_ps_output(&String::from_utf8_lossy(&out.stdout))
+}
+
+#[cfg(windows)]
+fn sample_process_stats(pid: u32) -> Option<ProcessStats> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+    let out = Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {pid}"), "/FO", "CSV", "/NH"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    // "node.exe","1234","Console","1","45,312 K"
+    let text = String::from_utf8_lossy(&out.stdout);
+    let mem_field = text.lines().next()?.rsplit("\",\"").next()?;
+    let kib: u64 = mem_field
+        .chars()
+        .filter(|c| c.is_ascii_digit())
+        .collect::<String>()
+        .parse()
+        .ok()?;
+    Some(ProcessStats {
+        cpu_percent: None,
+        memory_bytes: Some(kib * 1024),
+    })
+}
+
+#[cfg(not(any(unix, windows)))]
+fn sample_process_stats(_pid: u32) -> Option<ProcessStats> {
+    None
+}
+
+/// Parses `ps -o %cpu=,rss=` output (CPU percent, resident set size in KiB).
+#[cfg_attr(not(unix), allow(dead_code))]
+fn parse_ps_output(text: &str) -> Option<ProcessStats> {
+    let mut fields = text.split_whitespace();
+    let cpu_percent = fields.next()?.replace(',', ".").parse::<f64>().ok();
+    let memory_bytes = fields
+        .next()
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(|kib| kib * 1024);
+    Some(ProcessStats {
+        cpu_percent,
+        memory_bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restart_backoff_doubles_and_caps() {
+        assert_eq!(restart_backoff(1), Duration::from_secs(1));
+        assert_eq!(restart_backoff(2), Duration::from_secs(2));
+        assert_eq!(restart_backoff(4), Duration::from_secs(8));
+        assert_eq!(restart_backoff(9), RESTART_BACKOFF_MAX);
+    }
+
+    #[test]
+    fn parse_ps_output_reads_cpu_and_rss() {
+        let stats = parse_ps_output("  3.5 20480\n").expect("stats");
+        assert_eq!(stats.cpu_percent, Some(3.5));
+        assert_eq!(stats.memory_bytes, Some(20480 * 1024));
+        assert!(parse_ps_output("").is_none());
+    }
+}
@@ -3,7 +3,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct McpServerSummary {
     pub id: i64,
     pub server_key: String,
@@ -20,7 +20,7 @@ pub struct McpServerSummary {
     pub updated_at: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct McpImportServer {
     pub server_key: String,
     pub name: String,
@@ -34,20 +34,83 @@ pub struct McpImportServer {
     pub enabled: bool,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct McpParseResult {
     pub servers: Vec<McpImportServer>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct McpImportReport {
     pub inserted: u32,
     pub updated: u32,
     pub skipped: Vec<McpImportSkip>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct McpImportSkip {
     pub name: String,
     pub reason: String,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum McpDriftStatus {
+    /// Enabled in the DB and the on-disk entry matches.
+    InSync,
+    /// Enabled in the DB but the on-disk entry was edited outside the hub.
+    Modified,
+    /// Enabled in the DB but absent from the CLI config.
+    Missing,
+    /// Previously synced by the hub, no longer enabled, but still present on disk.
+    Stale,
+    /// Present on disk and never managed by the hub.
+    External,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct McpDriftServer {
+    pub server_key: String,
+    pub status: McpDriftStatus,
+    pub desired: Option<serde_json::Value>,
+    pub actual: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct McpSyncCliStatus {
+    pub cli_key: String,
+    pub workspace_id: Option<i64>,
+    pub target_exists: bool,
+    pub parse_error: Option<String>,
+    pub in_sync: bool,
+    pub servers: Vec<McpDriftServer>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum McpRuntimeState {
+    Starting,
+    Running,
+    /// Exited unexpectedly; waiting before the next restart.
+    Backoff,
+    Stopped,
+    /// Gave up after repeated crashes.
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct McpServerRuntimeStatus {
+    pub server_id: i64,
+    pub server_key: String,
+    pub name: String,
+    pub state: McpRuntimeState,
+    pub pid: Option<u32>,
+    pub started_at: Option<i64>,
+    pub restart_count: u32,
+    pub last_exit_code: Option<i32>,
+    pub last_exit_at: Option<i64>,
+    pub last_error: Option<String>,
+    pub cpu_percent: Option<f64>,
+    pub memory_bytes: Option<u64>,
+    /// Most recent stderr lines (oldest first).
+    pub stderr_tail: Vec<String>,
+}
@@ -3,19 +3,36 @@
 //! Note: Some modules may still call infra helpers during the migration; Phase 3 focuses on
 //! physical structure + stable API boundaries first.
 
+pub(crate) mod audit_log;
 pub(crate) mod claude_model_validation;
 pub(crate) mod claude_model_validation_history;
+pub(crate) mod claude_model_validation_schedules;
 pub(crate) mod claude_plugins;
+pub(crate) mod cli_config_drift;
 pub(crate) mod cli_sessions;
+pub(crate) mod codex_profiles;
 pub(crate) mod cost;
+pub(crate) mod cost_anomaly;
 pub(crate) mod cost_stats;
+pub(crate) mod hooks;
 pub(crate) mod mcp;
 pub(crate) mod prompts;
+pub(crate) mod provider_credentials;
+pub(crate) mod provider_dns_options;
 pub(crate) mod provider_limit_usage;
+pub(crate) mod provider_maintenance;
+pub(crate) mod provider_model_catalog;
+pub(crate) mod provider_scores;
+pub(crate) mod provider_tag_defaults;
+pub(crate) mod provider_validation;
 pub(crate) mod providers;
+pub(crate) mod request_mirror;
 pub(crate) mod skills;
+pub(crate) mod sort_mode_schedules;
 pub(crate) mod sort_modes;
+pub(crate) mod subagents;
 pub(crate) mod usage;
 pub(crate) mod usage_stats;
+pub(crate) mod workspace_snapshots;
 pub(crate) mod workspace_switch;
 pub(crate) mod workspaces;
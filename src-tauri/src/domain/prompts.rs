@@ -9,7 +9,7 @@ use crate::workspaces;
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::Serialize;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct PromptSummary {
     pub id: i64,
     pub workspace_id: i64,
@@ -21,18 +21,39 @@ pub struct PromptSummary {
     pub updated_at: i64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct DefaultPromptSyncItem {
     pub cli_key: String,
     pub action: String,
     pub message: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct DefaultPromptSyncReport {
     pub items: Vec<DefaultPromptSyncItem>,
 }
 
+/// An enabled prompt whose CLI file was edited outside the hub since the last sync.
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct PromptFileConflict {
+    pub cli_key: String,
+    pub prompt_id: Option<i64>,
+    pub prompt_name: Option<String>,
+    pub target_path: String,
+    pub db_content: Option<String>,
+    /// `None` when the file was deleted.
+    pub local_content: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, serde::Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptConflictResolution {
+    /// Copy the file content into the applied prompt.
+    AdoptLocal,
+    /// Replace the file with the applied prompt's content.
+    Overwrite,
+}
+
 fn validate_cli_key(cli_key: &str) -> crate::shared::error::AppResult<()> {
     crate::shared::cli_key::validate_cli_key(cli_key)
 }
@@ -59,6 +80,14 @@ fn row_default_lookup(row: &rusqlite::Row<'_>) -> Result<(i64, bool, String), ru
 }
 
 fn get_by_id(conn: &Connection, prompt_id: i64) -> crate::shared::error::AppResult<PromptSummary> {
+    find_by_id(conn, prompt_id)?
+        .ok_or_else(|| crate::shared::error::AppError::from("DB_NOT_FOUND: prompt not found"))
+}
+
+fn find_by_id(
+    conn: &Connection,
+    prompt_id: i64,
+) -> crate::shared::error::AppResult<Option<PromptSummary>> {
     conn.query_row(
         r#"
 SELECT
@@ -78,8 +107,7 @@ WHERE p.id = ?1
         row_to_summary,
     )
     .optional()
-    .map_err(|e| db_err!("failed to query prompt: {e}"))?
-    .ok_or_else(|| crate::shared::error::AppError::from("DB_NOT_FOUND: prompt not found"))
+    .map_err(|e| db_err!("failed to query prompt: {e}"))
 }
 
 pub fn list_by_workspace(
@@ -126,7 +154,7 @@ fn list_cli_keys() -> [&'static str; 3] {
 }
 
 fn read_prompt_file_utf8(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     cli_key: &str,
 ) -> crate::shared::error::AppResult<Option<String>> {
     let Some(bytes) = prompt_sync::read_target_bytes(app, cli_key)? else {
@@ -173,7 +201,7 @@ fn count_prompts_by_workspace(
 }
 
 pub fn default_sync_from_files(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     db: &db::Db,
 ) -> crate::shared::error::AppResult<DefaultPromptSyncReport> {
     let conn = db.open_connection()?;
@@ -322,6 +350,88 @@ INSERT INTO prompts(
     Ok(DefaultPromptSyncReport { items })
 }
 
+pub fn list_file_conflicts(
+    app: &crate::AppHandle,
+    db: &db::Db,
+) -> crate::shared::error::AppResult<Vec<PromptFileConflict>> {
+    let conn = db.open_connection()?;
+    let mut items = Vec::new();
+
+    for cli_key in list_cli_keys() {
+        let Some(drift) = prompt_sync::detect_drift(app, cli_key)? else {
+            continue;
+        };
+        let prompt = match drift.applied_prompt_id {
+            Some(id) => find_by_id(&conn, id)?,
+            None => None,
+        };
+        items.push(PromptFileConflict {
+            cli_key: cli_key.to_string(),
+            prompt_id: prompt.as_ref().map(|p| p.id),
+            prompt_name: prompt.as_ref().map(|p| p.name.clone()),
+            target_path: drift.target_path,
+            db_content: prompt.map(|p| p.content),
+            local_content: drift
+                .local_bytes
+                .map(|bytes| String::from_utf8_lossy(&bytes).into_owned()),
+        });
+    }
+
+    Ok(items)
+}
+
+pub fn resolve_file_conflict(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    cli_key: &str,
+    resolution: PromptConflictResolution,
+) -> crate::shared::error::AppResult<()> {
+    validate_cli_key(cli_key)?;
+    let Some(drift) = prompt_sync::detect_drift(app, cli_key)? else {
+        return Ok(());
+    };
+
+    let conn = db.open_connection()?;
+    let prompt = match drift.applied_prompt_id {
+        Some(id) => find_by_id(&conn, id)?,
+        None => None,
+    };
+    let Some(prompt) = prompt else {
+        return Err(format!("DB_NOT_FOUND: applied prompt not found for cli_key={cli_key}").into());
+    };
+
+    match resolution {
+        PromptConflictResolution::AdoptLocal => {
+            let Some(bytes) = drift.local_bytes else {
+                return Err(format!(
+                    "SEC_INVALID_INPUT: {} was deleted; nothing to adopt",
+                    drift.target_path
+                )
+                .into());
+            };
+            let content = String::from_utf8(bytes).map_err(|_| {
+                crate::shared::error::AppError::from(format!(
+                    "PROMPT_SYNC_INVALID_UTF8: cli_key={cli_key}"
+                ))
+            })?;
+
+            conn.execute(
+                "UPDATE prompts SET content = ?1, updated_at = ?2 WHERE id = ?3",
+                params![
+                    normalize_prompt_content(&content),
+                    now_unix_seconds(),
+                    prompt.id
+                ],
+            )
+            .map_err(|e| db_err!("failed to update prompt: {e}"))?;
+            prompt_sync::mark_synced(app, cli_key)
+        }
+        PromptConflictResolution::Overwrite => {
+            prompt_sync::overwrite_enabled_prompt(app, cli_key, prompt.id, &prompt.content)
+        }
+    }
+}
+
 fn clear_enabled_for_workspace(
     tx: &Connection,
     workspace_id: i64,
@@ -349,7 +459,7 @@ fn normalize_prompt_content(content: &str) -> String {
 }
 
 pub fn upsert(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     db: &db::Db,
     prompt_id: Option<i64>,
     workspace_id: i64,
@@ -519,7 +629,7 @@ WHERE id = ?5
 }
 
 pub fn set_enabled(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     db: &db::Db,
     prompt_id: i64,
     enabled: bool,
@@ -603,7 +713,7 @@ pub fn set_enabled(
 }
 
 pub fn delete(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     db: &db::Db,
     prompt_id: i64,
 ) -> crate::shared::error::AppResult<()> {
@@ -652,7 +762,7 @@ pub fn delete(
 }
 
 pub fn sync_cli_for_workspace(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     conn: &Connection,
     workspace_id: i64,
 ) -> crate::shared::error::AppResult<()> {
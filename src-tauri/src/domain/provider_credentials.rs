@@ -0,0 +1,178 @@
+//! Usage: Per-provider credential health (credential age, recent auth outcomes, OAuth expiry and
+//! refresh state) for the credentials panel.
+
+use crate::shared::error::db_err;
+use crate::shared::time::now_unix_seconds;
+use crate::{db, request_attempt_logs};
+use rusqlite::params;
+use serde::Serialize;
+use std::collections::HashMap;
+
+const ACTIVITY_WINDOW_SECS: i64 = 30 * 24 * 60 * 60;
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ProviderCredentialStatus {
+    pub provider_id: i64,
+    pub cli_key: String,
+    pub name: String,
+    pub enabled: bool,
+    pub auth_mode: String,
+    /// OAuth: seconds since the last token refresh; API key: since the provider was last saved.
+    pub credential_age_secs: Option<i64>,
+    /// Latest 2xx from this provider within the activity window.
+    pub last_success_at: Option<i64>,
+    /// Latest 401/403 from this provider within the activity window.
+    pub last_auth_failure_at: Option<i64>,
+    pub last_auth_failure_status: Option<i64>,
+    /// OAuth only; negative once the access token has expired.
+    pub oauth_expires_in_secs: Option<i64>,
+    /// OAuth only: `false` when the last refresh left an error behind.
+    pub oauth_last_refresh_ok: Option<bool>,
+    pub oauth_last_error: Option<String>,
+    pub window_secs: i64,
+}
+
+struct CredentialRow {
+    provider_id: i64,
+    cli_key: String,
+    name: String,
+    enabled: bool,
+    auth_mode: String,
+    updated_at: i64,
+    oauth_expires_at: Option<i64>,
+    oauth_last_refreshed_at: Option<i64>,
+    oauth_last_error: Option<String>,
+}
+
+fn to_status(
+    row: CredentialRow,
+    activity: Option<&request_attempt_logs::ProviderAuthActivity>,
+    now: i64,
+) -> ProviderCredentialStatus {
+    let is_oauth = row.auth_mode == "oauth";
+    let credential_set_at = if is_oauth {
+        row.oauth_last_refreshed_at
+    } else {
+        Some(row.updated_at)
+    };
+    ProviderCredentialStatus {
+        provider_id: row.provider_id,
+        cli_key: row.cli_key,
+        name: row.name,
+        enabled: row.enabled,
+        credential_age_secs: credential_set_at.map(|ts| now.saturating_sub(ts).max(0)),
+        last_success_at: activity.and_then(|a| a.last_success_at),
+        last_auth_failure_at: activity.and_then(|a| a.last_auth_failure_at),
+        last_auth_failure_status: activity.and_then(|a| a.last_auth_failure_status),
+        oauth_expires_in_secs: row
+            .oauth_expires_at
+            .filter(|_| is_oauth)
+            .map(|ts| ts.saturating_sub(now)),
+        oauth_last_refresh_ok: is_oauth.then(|| row.oauth_last_error.is_none()),
+        oauth_last_error: row.oauth_last_error.filter(|_| is_oauth),
+        auth_mode: row.auth_mode,
+        window_secs: ACTIVITY_WINDOW_SECS,
+    }
+}
+
+/// Credential status for every non-archived provider, optionally limited to one cli.
+pub fn list(
+    db: &db::Db,
+    cli_key: Option<&str>,
+) -> crate::shared::error::AppResult<Vec<ProviderCredentialStatus>> {
+    let cli_key = cli_key.map(str::trim).filter(|v| !v.is_empty());
+    let rows = {
+        let conn = db.open_connection()?;
+        let mut stmt = conn
+            .prepare_cached(
+                r#"
+SELECT
+  id,
+  cli_key,
+  name,
+  enabled,
+  auth_mode,
+  updated_at,
+  oauth_expires_at,
+  oauth_last_refreshed_at,
+  oauth_last_error
+FROM providers
+WHERE archived_at IS NULL
+  AND (?1 IS NULL OR cli_key = ?1)
+ORDER BY cli_key ASC, sort_order ASC, id DESC
+"#,
+            )
+            .map_err(|e| db_err!("failed to prepare credential status query: {e}"))?;
+        let rows = stmt
+            .query_map(params![cli_key], |row| {
+                Ok(CredentialRow {
+                    provider_id: row.get("id")?,
+                    cli_key: row.get("cli_key")?,
+                    name: row.get("name")?,
+                    enabled: row.get::<_, i64>("enabled")? != 0,
+                    auth_mode: row.get("auth_mode")?,
+                    updated_at: row.get("updated_at")?,
+                    oauth_expires_at: row.get("oauth_expires_at")?,
+                    oauth_last_refreshed_at: row.get("oauth_last_refreshed_at")?,
+                    oauth_last_error: row.get("oauth_last_error")?,
+                })
+            })
+            .map_err(|e| db_err!("failed to query credential status: {e}"))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| db_err!("failed to read credential status row: {e}"))?
+    };
+
+    let now = now_unix_seconds();
+    let activity =
+        request_attempt_logs::provider_auth_activity_since(db, now - ACTIVITY_WINDOW_SECS)?;
+    let activity_by_id: HashMap<i64, &request_attempt_logs::ProviderAuthActivity> =
+        activity.iter().map(|a| (a.provider_id, a)).collect();
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let activity = activity_by_id.get(&row.provider_id).copied();
+            to_status(row, activity, now)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(auth_mode: &str) -> CredentialRow {
+        CredentialRow {
+            provider_id: 1,
+            cli_key: "codex".to_string(),
+            name: "p".to_string(),
+            enabled: true,
+            auth_mode: auth_mode.to_string(),
+            updated_at: 1_000,
+            oauth_expires_at: Some(1_500),
+            oauth_last_refreshed_at: Some(1_200),
+            oauth_last_error: Some("invalid_grant".to_string()),
+        }
+    }
+
+    #[test]
+    fn oauth_fields_only_apply_to_oauth_providers() {
+        let oauth = to_status(row("oauth"), None, 2_000);
+        assert_eq!(oauth.credential_age_secs, Some(800));
+        assert_eq!(oauth.oauth_expires_in_secs, Some(-500));
+        assert_eq!(oauth.oauth_last_refresh_ok, Some(false));
+
+        let activity = request_attempt_logs::ProviderAuthActivity {
+            provider_id: 1,
+            last_success_at: Some(1_900),
+            last_auth_failure_at: None,
+            last_auth_failure_status: None,
+        };
+        let api_key = to_status(row("api_key"), Some(&activity), 2_000);
+        assert_eq!(api_key.credential_age_secs, Some(1_000));
+        assert_eq!(api_key.oauth_expires_in_secs, None);
+        assert_eq!(api_key.oauth_last_refresh_ok, None);
+        assert_eq!(api_key.oauth_last_error, None);
+        assert_eq!(api_key.last_success_at, Some(1_900));
+    }
+}
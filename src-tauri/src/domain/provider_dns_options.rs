@@ -0,0 +1,263 @@
+//! Usage: Per-provider DNS options for upstream connections (address family, DoH resolver,
+//! connection racing).
+
+use crate::db;
+use crate::shared::error::db_err;
+use crate::shared::time::now_unix_seconds;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const MAX_DOH_URL_CHARS: usize = 512;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum DnsIpStrategy {
+    /// Use every address the resolver returns.
+    #[default]
+    System,
+    /// Drop AAAA results (relays with broken IPv6).
+    Ipv4Only,
+}
+
+impl DnsIpStrategy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::Ipv4Only => "ipv4_only",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "system" => Some(Self::System),
+            "ipv4_only" => Some(Self::Ipv4Only),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, specta::Type)]
+pub struct ProviderDnsOptions {
+    pub provider_id: i64,
+    pub ip_strategy: DnsIpStrategy,
+    /// DNS-over-HTTPS endpoint speaking the JSON API (`?name=&type=`); `None` = system resolver.
+    pub doh_url: Option<String>,
+    /// Probe all resolved addresses in parallel and connect to the fastest first.
+    pub race_connections: bool,
+}
+
+impl ProviderDnsOptions {
+    pub fn defaults(provider_id: i64) -> Self {
+        Self {
+            provider_id,
+            ip_strategy: DnsIpStrategy::System,
+            doh_url: None,
+            race_connections: false,
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.ip_strategy == DnsIpStrategy::System
+            && self.doh_url.is_none()
+            && !self.race_connections
+    }
+}
+
+fn row_to_options(row: &rusqlite::Row<'_>) -> Result<ProviderDnsOptions, rusqlite::Error> {
+    let ip_strategy: String = row.get("ip_strategy")?;
+    Ok(ProviderDnsOptions {
+        provider_id: row.get("provider_id")?,
+        ip_strategy: DnsIpStrategy::parse(&ip_strategy).unwrap_or_default(),
+        doh_url: row.get("doh_url")?,
+        race_connections: row.get::<_, i64>("race_connections")? != 0,
+    })
+}
+
+fn normalize_doh_url(doh_url: Option<&str>) -> crate::shared::error::AppResult<Option<String>> {
+    let Some(raw) = doh_url.map(str::trim).filter(|v| !v.is_empty()) else {
+        return Ok(None);
+    };
+    if raw.chars().count() > MAX_DOH_URL_CHARS {
+        return Err(format!(
+            "SEC_INVALID_INPUT: doh_url is too long (max {MAX_DOH_URL_CHARS} chars)"
+        )
+        .into());
+    }
+    let parsed =
+        reqwest::Url::parse(raw).map_err(|e| format!("SEC_INVALID_INPUT: invalid doh_url: {e}"))?;
+    if parsed.scheme() != "https" || parsed.host_str().is_none() {
+        return Err("SEC_INVALID_INPUT: doh_url must be an https:// URL".into());
+    }
+    if parsed.query().is_some() {
+        return Err("SEC_INVALID_INPUT: doh_url must not contain a query string".into());
+    }
+    Ok(Some(parsed.to_string()))
+}
+
+fn ensure_provider_exists(
+    conn: &Connection,
+    provider_id: i64,
+) -> crate::shared::error::AppResult<()> {
+    if provider_id <= 0 {
+        return Err("SEC_INVALID_INPUT: invalid provider_id".into());
+    }
+
+    let exists: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM providers WHERE id = ?1",
+            params![provider_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query provider: {e}"))?;
+
+    if exists.is_none() {
+        return Err("DB_NOT_FOUND: provider not found".into());
+    }
+    Ok(())
+}
+
+fn read_options(
+    conn: &Connection,
+    provider_id: i64,
+) -> crate::shared::error::AppResult<ProviderDnsOptions> {
+    let options = conn
+        .query_row(
+            r#"
+SELECT
+  provider_id,
+  ip_strategy,
+  doh_url,
+  race_connections
+FROM provider_dns_options
+WHERE provider_id = ?1
+"#,
+            params![provider_id],
+            row_to_options,
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query provider_dns_options: {e}"))?;
+    Ok(options.unwrap_or_else(|| ProviderDnsOptions::defaults(provider_id)))
+}
+
+pub fn get(db: &db::Db, provider_id: i64) -> crate::shared::error::AppResult<ProviderDnsOptions> {
+    let conn = db.open_connection()?;
+    ensure_provider_exists(&conn, provider_id)?;
+    read_options(&conn, provider_id)
+}
+
+/// Non-default options keyed by provider id (what the gateway resolver needs).
+pub(crate) fn load_all(
+    db: &db::Db,
+) -> crate::shared::error::AppResult<HashMap<i64, ProviderDnsOptions>> {
+    let conn = db.open_connection()?;
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT
+  provider_id,
+  ip_strategy,
+  doh_url,
+  race_connections
+FROM provider_dns_options
+"#,
+        )
+        .map_err(|e| db_err!("failed to prepare provider_dns_options query: {e}"))?;
+    let rows = stmt
+        .query_map([], row_to_options)
+        .map_err(|e| db_err!("failed to list provider_dns_options: {e}"))?;
+
+    let mut out = HashMap::new();
+    for row in rows {
+        let options = row.map_err(|e| db_err!("failed to read provider_dns_options row: {e}"))?;
+        if !options.is_default() {
+            out.insert(options.provider_id, options);
+        }
+    }
+    Ok(out)
+}
+
+/// Stores the options; resetting everything to defaults removes the row.
+pub fn set(
+    db: &db::Db,
+    provider_id: i64,
+    ip_strategy: DnsIpStrategy,
+    doh_url: Option<&str>,
+    race_connections: bool,
+) -> crate::shared::error::AppResult<ProviderDnsOptions> {
+    let options = ProviderDnsOptions {
+        provider_id,
+        ip_strategy,
+        doh_url: normalize_doh_url(doh_url)?,
+        race_connections,
+    };
+
+    let conn = db.open_connection()?;
+    ensure_provider_exists(&conn, provider_id)?;
+
+    if options.is_default() {
+        conn.execute(
+            "DELETE FROM provider_dns_options WHERE provider_id = ?1",
+            params![provider_id],
+        )
+        .map_err(|e| db_err!("failed to delete provider_dns_options: {e}"))?;
+        return Ok(options);
+    }
+
+    let now = now_unix_seconds();
+    conn.execute(
+        r#"
+INSERT INTO provider_dns_options(
+  provider_id,
+  ip_strategy,
+  doh_url,
+  race_connections,
+  created_at,
+  updated_at
+) VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+ON CONFLICT(provider_id) DO UPDATE SET
+  ip_strategy = excluded.ip_strategy,
+  doh_url = excluded.doh_url,
+  race_connections = excluded.race_connections,
+  updated_at = excluded.updated_at
+"#,
+        params![
+            provider_id,
+            options.ip_strategy.as_str(),
+            options.doh_url,
+            options.race_connections as i64,
+            now
+        ],
+    )
+    .map_err(|e| db_err!("failed to upsert provider_dns_options: {e}"))?;
+
+    read_options(&conn, provider_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_doh_url_requires_https_without_query() {
+        assert_eq!(normalize_doh_url(None).unwrap(), None);
+        assert_eq!(normalize_doh_url(Some("  ")).unwrap(), None);
+        assert_eq!(
+            normalize_doh_url(Some(" https://cloudflare-dns.com/dns-query ")).unwrap(),
+            Some("https://cloudflare-dns.com/dns-query".to_string())
+        );
+        assert!(normalize_doh_url(Some("http://1.1.1.1/dns-query")).is_err());
+        assert!(normalize_doh_url(Some("https://dns.google/resolve?name=x")).is_err());
+        assert!(normalize_doh_url(Some("not a url")).is_err());
+    }
+
+    #[test]
+    fn ip_strategy_round_trips_through_storage_names() {
+        for strategy in [DnsIpStrategy::System, DnsIpStrategy::Ipv4Only] {
+            assert_eq!(DnsIpStrategy::parse(strategy.as_str()), Some(strategy));
+        }
+        assert_eq!(DnsIpStrategy::parse("ipv6_only"), None);
+        assert!(ProviderDnsOptions::defaults(1).is_default());
+    }
+}
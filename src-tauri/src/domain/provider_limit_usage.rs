@@ -8,7 +8,16 @@ use serde::Serialize;
 
 const USD_FEMTO_DENOM: f64 = 1_000_000_000_000_000.0;
 
-#[derive(Debug, Clone, Serialize)]
+/// Share of the monthly budget at which the early-warning notice fires.
+pub(crate) const MONTHLY_BUDGET_WARN_RATIO: f64 = 0.8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BudgetNotice {
+    Warn80,
+    Exhausted100,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct ProviderLimitUsageRow {
     pub cli_key: String,
     pub provider_id: i64,
@@ -35,6 +44,25 @@ pub struct ProviderLimitUsageRow {
     pub window_monthly_start_ts: i64,
 }
 
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ProviderMonthlyBudgetRow {
+    pub cli_key: String,
+    pub provider_id: i64,
+    pub provider_name: String,
+    /// Calendar month in local time, `YYYY-MM`.
+    pub month: String,
+    pub budget_usd: f64,
+    /// Extra allowance granted for this month only.
+    pub grace_usd: f64,
+    pub spent_usd: f64,
+    pub remaining_usd: f64,
+    pub blocked: bool,
+    pub notified_80_at: Option<i64>,
+    pub notified_100_at: Option<i64>,
+    pub month_start_ts: i64,
+    pub next_month_start_ts: i64,
+}
+
 fn validate_cli_key(cli_key: &str) -> crate::shared::error::AppResult<()> {
     crate::shared::cli_key::validate_cli_key(cli_key)
 }
@@ -327,3 +355,234 @@ pub fn list_v1(
 
     Ok(out)
 }
+
+/// Local-time calendar month key (`YYYY-MM`) for `now_unix`.
+pub(crate) fn month_key(
+    conn: &Connection,
+    now_unix: i64,
+) -> crate::shared::error::AppResult<String> {
+    conn.query_row(
+        "SELECT strftime('%Y-%m', ?1, 'unixepoch', 'localtime')",
+        params![now_unix],
+        |row| row.get::<_, String>(0),
+    )
+    .map_err(|e| db_err!("failed to compute month key: {e}"))
+}
+
+fn month_bounds(conn: &Connection, now_unix: i64) -> crate::shared::error::AppResult<(i64, i64)> {
+    conn.query_row(
+        r#"
+SELECT
+  CAST(strftime('%s', ?1, 'unixepoch','localtime','start of month','utc') AS INTEGER) AS start_ts,
+  CAST(strftime('%s', ?1, 'unixepoch','localtime','start of month','+1 month','utc') AS INTEGER) AS next_reset
+"#,
+        params![now_unix],
+        |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+    )
+    .map_err(|e| db_err!("failed to compute monthly bounds: {e}"))
+}
+
+type MonthlyBudgetState = (f64, Option<i64>, Option<i64>);
+
+fn monthly_budget_state(
+    conn: &Connection,
+    provider_id: i64,
+    month: &str,
+) -> crate::shared::error::AppResult<MonthlyBudgetState> {
+    Ok(conn
+        .query_row(
+            r#"
+SELECT grace_usd, notified_80_at, notified_100_at
+FROM provider_monthly_budgets
+WHERE provider_id = ?1 AND month = ?2
+"#,
+            params![provider_id, month],
+            |row| {
+                Ok((
+                    row.get::<_, f64>(0)?,
+                    row.get::<_, Option<i64>>(1)?,
+                    row.get::<_, Option<i64>>(2)?,
+                ))
+            },
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query provider monthly budget: {e}"))?
+        .unwrap_or((0.0, None, None)))
+}
+
+/// Grace allowance granted for `month`; 0 when none was granted.
+pub(crate) fn month_grace_usd(
+    conn: &Connection,
+    provider_id: i64,
+    month: &str,
+) -> crate::shared::error::AppResult<f64> {
+    let (grace_usd, _, _) = monthly_budget_state(conn, provider_id, month)?;
+    Ok(if grace_usd.is_finite() {
+        grace_usd.max(0.0)
+    } else {
+        0.0
+    })
+}
+
+/// Marks `notice` as sent for `month`; only the first caller per month gets `true`, so each
+/// threshold notifies once even under concurrent requests.
+pub(crate) fn claim_budget_notice(
+    conn: &Connection,
+    provider_id: i64,
+    month: &str,
+    notice: BudgetNotice,
+    now_unix: i64,
+) -> crate::shared::error::AppResult<bool> {
+    conn.execute(
+        r#"
+INSERT OR IGNORE INTO provider_monthly_budgets(provider_id, month, created_at, updated_at)
+VALUES (?1, ?2, ?3, ?3)
+"#,
+        params![provider_id, month, now_unix],
+    )
+    .map_err(|e| db_err!("failed to insert provider monthly budget: {e}"))?;
+
+    let sql = match notice {
+        BudgetNotice::Warn80 => {
+            "UPDATE provider_monthly_budgets SET notified_80_at = ?3, updated_at = ?3 WHERE provider_id = ?1 AND month = ?2 AND notified_80_at IS NULL"
+        }
+        BudgetNotice::Exhausted100 => {
+            "UPDATE provider_monthly_budgets SET notified_100_at = ?3, updated_at = ?3 WHERE provider_id = ?1 AND month = ?2 AND notified_100_at IS NULL"
+        }
+    };
+    let changed = conn
+        .execute(sql, params![provider_id, month, now_unix])
+        .map_err(|e| db_err!("failed to claim provider budget notice: {e}"))?;
+    Ok(changed > 0)
+}
+
+fn monthly_budget_row(
+    conn: &Connection,
+    now_unix: i64,
+    provider: (i64, String, String, f64),
+) -> crate::shared::error::AppResult<ProviderMonthlyBudgetRow> {
+    let (provider_id, cli_key, provider_name, budget_usd) = provider;
+    let month = month_key(conn, now_unix)?;
+    let (month_start_ts, next_month_start_ts) = month_bounds(conn, now_unix)?;
+    let (_, notified_80_at, notified_100_at) = monthly_budget_state(conn, provider_id, &month)?;
+    let grace_usd = month_grace_usd(conn, provider_id, &month)?;
+    let spent_usd = cost_usd_from_femto(aggregate_cost_for_provider(
+        conn,
+        provider_id,
+        Some(month_start_ts),
+    )?);
+    let effective_usd = budget_usd + grace_usd;
+
+    Ok(ProviderMonthlyBudgetRow {
+        cli_key,
+        provider_id,
+        provider_name,
+        month,
+        budget_usd,
+        grace_usd,
+        spent_usd,
+        remaining_usd: (effective_usd - spent_usd).max(0.0),
+        blocked: spent_usd >= effective_usd,
+        notified_80_at,
+        notified_100_at,
+        month_start_ts,
+        next_month_start_ts,
+    })
+}
+
+fn now_unix(conn: &Connection) -> crate::shared::error::AppResult<i64> {
+    conn.query_row("SELECT CAST(strftime('%s', 'now') AS INTEGER)", [], |row| {
+        row.get::<_, i64>(0)
+    })
+    .map_err(|e| db_err!("failed to get current timestamp: {e}"))
+}
+
+pub fn monthly_budgets_list(
+    db: &db::Db,
+    cli_key: Option<&str>,
+) -> crate::shared::error::AppResult<Vec<ProviderMonthlyBudgetRow>> {
+    let cli_key = normalize_cli_filter(cli_key)?;
+    let conn = db.open_connection()?;
+    let now = now_unix(&conn)?;
+
+    let mut stmt = conn
+        .prepare(
+            r#"
+        SELECT id, cli_key, name, monthly_budget_usd
+        FROM providers
+        WHERE (?1 IS NULL OR cli_key = ?1)
+          AND monthly_budget_usd IS NOT NULL
+        ORDER BY cli_key ASC, sort_order ASC, id DESC
+    "#,
+        )
+        .map_err(|e| db_err!("failed to prepare monthly budget query: {e}"))?;
+    let rows = stmt
+        .query_map(params![cli_key], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, f64>(3)?,
+            ))
+        })
+        .map_err(|e| db_err!("failed to query monthly budgets: {e}"))?;
+
+    let mut providers = Vec::new();
+    for row in rows {
+        providers.push(row.map_err(|e| db_err!("failed to read monthly budget row: {e}"))?);
+    }
+
+    providers
+        .into_iter()
+        .map(|provider| monthly_budget_row(&conn, now, provider))
+        .collect()
+}
+
+/// Grants `grace_usd` on top of the provider's budget for the current month only (0 revokes
+/// it). Re-arms the 100% notice so exhausting the grace notifies again.
+pub fn monthly_budget_set_grace(
+    db: &db::Db,
+    provider_id: i64,
+    grace_usd: f64,
+) -> crate::shared::error::AppResult<ProviderMonthlyBudgetRow> {
+    if !grace_usd.is_finite() || grace_usd < 0.0 {
+        return Err("SEC_INVALID_INPUT: grace_usd must be a finite number >= 0".into());
+    }
+
+    let conn = db.open_connection()?;
+    let provider: Option<(i64, String, String, Option<f64>)> = conn
+        .query_row(
+            "SELECT id, cli_key, name, monthly_budget_usd FROM providers WHERE id = ?1",
+            params![provider_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query provider: {e}"))?;
+    let Some((provider_id, cli_key, provider_name, budget_usd)) = provider else {
+        return Err("DB_NOT_FOUND: provider not found".into());
+    };
+    let Some(budget_usd) = budget_usd else {
+        return Err("SEC_INVALID_INPUT: provider has no monthly budget".into());
+    };
+
+    let now = now_unix(&conn)?;
+    let month = month_key(&conn, now)?;
+    conn.execute(
+        r#"
+INSERT INTO provider_monthly_budgets(provider_id, month, grace_usd, created_at, updated_at)
+VALUES (?1, ?2, ?3, ?4, ?4)
+ON CONFLICT(provider_id, month) DO UPDATE SET
+  grace_usd = excluded.grace_usd,
+  notified_100_at = NULL,
+  updated_at = excluded.updated_at
+"#,
+        params![provider_id, month, grace_usd, now],
+    )
+    .map_err(|e| db_err!("failed to update provider monthly budget grace: {e}"))?;
+
+    monthly_budget_row(
+        &conn,
+        now,
+        (provider_id, cli_key, provider_name, budget_usd),
+    )
+}
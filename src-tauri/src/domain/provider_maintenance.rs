@@ -0,0 +1,399 @@
+//! Usage: Provider maintenance windows (scheduled periods during which the gateway skips a provider).
+
+use crate::db;
+use crate::shared::error::db_err;
+use crate::shared::time::now_unix_seconds;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashMap;
+
+const MAX_NOTE_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ProviderMaintenanceWindow {
+    pub id: i64,
+    pub provider_id: i64,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    /// When set, the window repeats every `repeat_interval_secs` after `starts_at`.
+    pub repeat_interval_secs: Option<i64>,
+    pub note: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// A single concrete occurrence of a (possibly repeating) maintenance window.
+#[derive(Debug, Clone, Copy, Serialize, specta::Type, PartialEq, Eq)]
+pub struct ProviderMaintenanceOccurrence {
+    pub window_id: i64,
+    pub starts_at: i64,
+    pub ends_at: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProviderMaintenanceWindowUpsertParams {
+    pub window_id: Option<i64>,
+    pub provider_id: i64,
+    pub starts_at: i64,
+    pub ends_at: i64,
+    pub repeat_interval_secs: Option<i64>,
+    pub note: Option<String>,
+}
+
+fn row_to_window(row: &rusqlite::Row<'_>) -> Result<ProviderMaintenanceWindow, rusqlite::Error> {
+    Ok(ProviderMaintenanceWindow {
+        id: row.get("id")?,
+        provider_id: row.get("provider_id")?,
+        starts_at: row.get("starts_at")?,
+        ends_at: row.get("ends_at")?,
+        repeat_interval_secs: row.get("repeat_interval_secs")?,
+        note: row.get("note")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+/// Return the occurrence that is active at `now_unix`, or the next upcoming one.
+pub(crate) fn occurrence_at(
+    window: &ProviderMaintenanceWindow,
+    now_unix: i64,
+) -> Option<ProviderMaintenanceOccurrence> {
+    let duration = window.ends_at.saturating_sub(window.starts_at);
+    if duration <= 0 {
+        return None;
+    }
+
+    let starts_at = match window.repeat_interval_secs.filter(|v| *v > 0) {
+        Some(interval) if now_unix >= window.starts_at => {
+            let elapsed_periods = (now_unix - window.starts_at) / interval;
+            let current = window
+                .starts_at
+                .saturating_add(elapsed_periods.saturating_mul(interval));
+            if now_unix < current.saturating_add(duration) {
+                current
+            } else {
+                current.saturating_add(interval)
+            }
+        }
+        _ => window.starts_at,
+    };
+    let ends_at = starts_at.saturating_add(duration);
+
+    (now_unix < ends_at).then_some(ProviderMaintenanceOccurrence {
+        window_id: window.id,
+        starts_at,
+        ends_at,
+    })
+}
+
+/// Split the occurrences of `windows` into the active one (latest end wins) and the next upcoming.
+pub(crate) fn active_and_next(
+    windows: &[ProviderMaintenanceWindow],
+    now_unix: i64,
+) -> (
+    Option<ProviderMaintenanceOccurrence>,
+    Option<ProviderMaintenanceOccurrence>,
+) {
+    let mut active: Option<ProviderMaintenanceOccurrence> = None;
+    let mut next: Option<ProviderMaintenanceOccurrence> = None;
+    for occurrence in windows.iter().filter_map(|w| occurrence_at(w, now_unix)) {
+        if occurrence.starts_at <= now_unix {
+            if active
+                .map(|a| occurrence.ends_at > a.ends_at)
+                .unwrap_or(true)
+            {
+                active = Some(occurrence);
+            }
+        } else if next
+            .map(|n| occurrence.starts_at < n.starts_at)
+            .unwrap_or(true)
+        {
+            next = Some(occurrence);
+        }
+    }
+    (active, next)
+}
+
+fn ensure_provider_exists(
+    conn: &Connection,
+    provider_id: i64,
+) -> crate::shared::error::AppResult<()> {
+    if provider_id <= 0 {
+        return Err("SEC_INVALID_INPUT: invalid provider_id".into());
+    }
+
+    let exists: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM providers WHERE id = ?1",
+            params![provider_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query provider: {e}"))?;
+
+    if exists.is_none() {
+        return Err("DB_NOT_FOUND: provider not found".into());
+    }
+    Ok(())
+}
+
+fn read_window(
+    conn: &Connection,
+    window_id: i64,
+) -> crate::shared::error::AppResult<ProviderMaintenanceWindow> {
+    conn.query_row(
+        r#"
+SELECT
+  id,
+  provider_id,
+  starts_at,
+  ends_at,
+  repeat_interval_secs,
+  note,
+  created_at,
+  updated_at
+FROM provider_maintenance_windows
+WHERE id = ?1
+"#,
+        params![window_id],
+        row_to_window,
+    )
+    .optional()
+    .map_err(|e| db_err!("failed to query provider_maintenance_window: {e}"))?
+    .ok_or_else(|| "DB_NOT_FOUND: maintenance window not found".into())
+}
+
+/// Load windows for the given providers, grouped by provider id.
+pub(crate) fn list_windows_by_provider(
+    conn: &Connection,
+    provider_ids: &[i64],
+) -> crate::shared::error::AppResult<HashMap<i64, Vec<ProviderMaintenanceWindow>>> {
+    let mut out: HashMap<i64, Vec<ProviderMaintenanceWindow>> = HashMap::new();
+    if provider_ids.is_empty() {
+        return Ok(out);
+    }
+
+    let placeholders = db::sql_placeholders(provider_ids.len());
+    let sql = format!(
+        r#"
+SELECT
+  id,
+  provider_id,
+  starts_at,
+  ends_at,
+  repeat_interval_secs,
+  note,
+  created_at,
+  updated_at
+FROM provider_maintenance_windows
+WHERE provider_id IN ({placeholders})
+ORDER BY starts_at ASC, id ASC
+"#
+    );
+
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| db_err!("failed to prepare provider_maintenance_windows query: {e}"))?;
+    let rows = stmt
+        .query_map(params_from_iter(provider_ids.iter()), row_to_window)
+        .map_err(|e| db_err!("failed to list provider_maintenance_windows: {e}"))?;
+
+    for row in rows {
+        let window =
+            row.map_err(|e| db_err!("failed to read provider_maintenance_window row: {e}"))?;
+        out.entry(window.provider_id).or_default().push(window);
+    }
+    Ok(out)
+}
+
+/// End of the currently active maintenance occurrence for each provider that is in maintenance.
+pub(crate) fn active_until_by_provider(
+    db: &db::Db,
+    provider_ids: &[i64],
+    now_unix: i64,
+) -> crate::shared::error::AppResult<HashMap<i64, i64>> {
+    let conn = db.open_connection()?;
+    let windows = list_windows_by_provider(&conn, provider_ids)?;
+    Ok(windows
+        .into_iter()
+        .filter_map(|(provider_id, windows)| {
+            let (active, _) = active_and_next(&windows, now_unix);
+            active.map(|a| (provider_id, a.ends_at))
+        })
+        .collect())
+}
+
+pub fn list_windows(
+    db: &db::Db,
+    provider_id: i64,
+) -> crate::shared::error::AppResult<Vec<ProviderMaintenanceWindow>> {
+    let conn = db.open_connection()?;
+    ensure_provider_exists(&conn, provider_id)?;
+    let mut windows = list_windows_by_provider(&conn, &[provider_id])?;
+    Ok(windows.remove(&provider_id).unwrap_or_default())
+}
+
+pub fn upsert_window(
+    db: &db::Db,
+    input: ProviderMaintenanceWindowUpsertParams,
+) -> crate::shared::error::AppResult<ProviderMaintenanceWindow> {
+    let ProviderMaintenanceWindowUpsertParams {
+        window_id,
+        provider_id,
+        starts_at,
+        ends_at,
+        repeat_interval_secs,
+        note,
+    } = input;
+
+    if starts_at <= 0 || ends_at <= starts_at {
+        return Err("SEC_INVALID_INPUT: ends_at must be after starts_at".into());
+    }
+    if let Some(interval) = repeat_interval_secs {
+        if interval < ends_at - starts_at {
+            return Err(
+                "SEC_INVALID_INPUT: repeat_interval_secs must not be shorter than the window"
+                    .into(),
+            );
+        }
+    }
+    let note = note.unwrap_or_default().trim().to_string();
+    if note.chars().count() > MAX_NOTE_CHARS {
+        return Err(
+            format!("SEC_INVALID_INPUT: note is too long (max {MAX_NOTE_CHARS} chars)").into(),
+        );
+    }
+
+    let conn = db.open_connection()?;
+    ensure_provider_exists(&conn, provider_id)?;
+    let now = now_unix_seconds();
+
+    let id = match window_id {
+        Some(window_id) => {
+            let existing = read_window(&conn, window_id)?;
+            if existing.provider_id != provider_id {
+                return Err("SEC_INVALID_INPUT: window does not belong to provider".into());
+            }
+            conn.execute(
+                r#"
+UPDATE provider_maintenance_windows
+SET
+  starts_at = ?1,
+  ends_at = ?2,
+  repeat_interval_secs = ?3,
+  note = ?4,
+  updated_at = ?5
+WHERE id = ?6
+"#,
+                params![
+                    starts_at,
+                    ends_at,
+                    repeat_interval_secs,
+                    note,
+                    now,
+                    window_id
+                ],
+            )
+            .map_err(|e| db_err!("failed to update provider_maintenance_window: {e}"))?;
+            window_id
+        }
+        None => {
+            conn.execute(
+                r#"
+INSERT INTO provider_maintenance_windows(
+  provider_id,
+  starts_at,
+  ends_at,
+  repeat_interval_secs,
+  note,
+  created_at,
+  updated_at
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
+"#,
+                params![
+                    provider_id,
+                    starts_at,
+                    ends_at,
+                    repeat_interval_secs,
+                    note,
+                    now
+                ],
+            )
+            .map_err(|e| db_err!("failed to insert provider_maintenance_window: {e}"))?;
+            conn.last_insert_rowid()
+        }
+    };
+
+    read_window(&conn, id)
+}
+
+pub fn delete_window(db: &db::Db, window_id: i64) -> crate::shared::error::AppResult<()> {
+    let conn = db.open_connection()?;
+    let changed = conn
+        .execute(
+            "DELETE FROM provider_maintenance_windows WHERE id = ?1",
+            params![window_id],
+        )
+        .map_err(|e| db_err!("failed to delete provider_maintenance_window: {e}"))?;
+    if changed == 0 {
+        return Err("DB_NOT_FOUND: maintenance window not found".into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(
+        id: i64,
+        starts_at: i64,
+        ends_at: i64,
+        repeat: Option<i64>,
+    ) -> ProviderMaintenanceWindow {
+        ProviderMaintenanceWindow {
+            id,
+            provider_id: 1,
+            starts_at,
+            ends_at,
+            repeat_interval_secs: repeat,
+            note: String::new(),
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn one_shot_window_is_upcoming_then_active_then_gone() {
+        let w = window(1, 100, 200, None);
+        assert_eq!(occurrence_at(&w, 50).map(|o| o.starts_at), Some(100));
+        assert_eq!(occurrence_at(&w, 150).map(|o| o.ends_at), Some(200));
+        assert_eq!(occurrence_at(&w, 200), None);
+    }
+
+    #[test]
+    fn repeating_window_rolls_forward() {
+        let day = 86_400;
+        let w = window(1, 1_000, 1_000 + 3_600, Some(day));
+
+        let active = occurrence_at(&w, 1_000 + 2 * day + 60).expect("active");
+        assert_eq!(active.starts_at, 1_000 + 2 * day);
+        assert_eq!(active.ends_at, 1_000 + 2 * day + 3_600);
+
+        let next = occurrence_at(&w, 1_000 + 2 * day + 7_200).expect("next");
+        assert_eq!(next.starts_at, 1_000 + 3 * day);
+    }
+
+    #[test]
+    fn active_and_next_picks_longest_active_and_earliest_upcoming() {
+        let windows = vec![
+            window(1, 100, 200, None),
+            window(2, 50, 300, None),
+            window(3, 500, 600, None),
+            window(4, 400, 450, None),
+        ];
+        let (active, next) = active_and_next(&windows, 150);
+        assert_eq!(active.map(|o| o.window_id), Some(2));
+        assert_eq!(next.map(|o| o.window_id), Some(4));
+    }
+}
@@ -0,0 +1,322 @@
+//! Usage: Model catalogs for aggregated (OpenRouter-style) providers: fetch `/models`, cache in DB.
+
+use crate::db;
+use crate::providers::ProviderKind;
+use crate::shared::error::db_err;
+use crate::shared::mutex_ext::MutexExt;
+use crate::shared::time::now_unix_seconds;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Catalogs older than this are refreshed in the background when the gateway uses the provider.
+pub(crate) const CATALOG_REFRESH_TTL_SECS: i64 = 6 * 60 * 60;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+const MAX_CATALOG_MODELS: usize = 5000;
+const MAX_ERROR_LEN: usize = 500;
+
+static REFRESH_IN_FLIGHT: OnceLock<Mutex<HashSet<i64>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ProviderModelCatalog {
+    pub provider_id: i64,
+    pub models: Vec<String>,
+    pub refreshed_at: Option<i64>,
+    pub error: Option<String>,
+}
+
+struct CatalogSource {
+    cli_key: String,
+    kind: ProviderKind,
+    base_urls: Vec<String>,
+    api_key: String,
+}
+
+pub(crate) fn models_from_json(raw: &str) -> Vec<String> {
+    serde_json::from_str::<Vec<String>>(raw).unwrap_or_default()
+}
+
+pub(crate) fn is_stale(refreshed_at: Option<i64>, now_unix: i64) -> bool {
+    match refreshed_at {
+        Some(at) => now_unix.saturating_sub(at) >= CATALOG_REFRESH_TTL_SECS,
+        None => true,
+    }
+}
+
+/// `https://openrouter.ai/api/v1` → `.../api/v1/models`; `https://host` → `https://host/v1/models`.
+fn models_url(base_url: &str, cli_key: &str) -> String {
+    let base = base_url.trim().trim_end_matches('/');
+    let last_segment = base.rsplit('/').next().unwrap_or("");
+    let has_version = last_segment.starts_with('v')
+        && last_segment[1..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_digit());
+    if has_version {
+        format!("{base}/models")
+    } else if cli_key == "gemini" {
+        format!("{base}/v1beta/models")
+    } else {
+        format!("{base}/v1/models")
+    }
+}
+
+/// Accepts OpenAI/Anthropic (`data[].id`) and Gemini (`models[].name`) list shapes.
+fn parse_model_ids(value: &serde_json::Value) -> Vec<String> {
+    let items = value
+        .get("data")
+        .or_else(|| value.get("models"))
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    let ids: BTreeSet<String> = items
+        .iter()
+        .filter_map(|item| {
+            item.get("id")
+                .or_else(|| item.get("name"))
+                .and_then(|v| v.as_str())
+        })
+        .map(|id| id.trim().trim_start_matches("models/").to_string())
+        .filter(|id| !id.is_empty())
+        .collect();
+
+    ids.into_iter().take(MAX_CATALOG_MODELS).collect()
+}
+
+fn load_source(db: &db::Db, provider_id: i64) -> crate::shared::error::AppResult<CatalogSource> {
+    let conn = db.open_connection()?;
+    let row: Option<(String, String, String, String, String)> = conn
+        .query_row(
+            "SELECT cli_key, provider_kind, base_url, base_urls_json, api_key_plaintext FROM providers WHERE id = ?1",
+            params![provider_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query provider: {e}"))?;
+    let Some((cli_key, kind_raw, base_url, base_urls_json, api_key)) = row else {
+        return Err("DB_NOT_FOUND: provider not found".into());
+    };
+
+    let mut base_urls = models_from_json(&base_urls_json);
+    if base_urls.is_empty() && !base_url.trim().is_empty() {
+        base_urls.push(base_url);
+    }
+
+    Ok(CatalogSource {
+        cli_key,
+        kind: ProviderKind::parse(&kind_raw).unwrap_or(ProviderKind::Standard),
+        base_urls,
+        api_key,
+    })
+}
+
+fn save(
+    db: &db::Db,
+    provider_id: i64,
+    result: &Result<Vec<String>, String>,
+    now_unix: i64,
+) -> crate::shared::error::AppResult<()> {
+    let conn = db.open_connection()?;
+    match result {
+        Ok(models) => {
+            let json = serde_json::to_string(models).map_err(|e| format!("SYSTEM_ERROR: {e}"))?;
+            conn.execute(
+                "UPDATE providers SET model_catalog_json = ?1, model_catalog_refreshed_at = ?2, model_catalog_error = NULL WHERE id = ?3",
+                params![json, now_unix, provider_id],
+            )
+        }
+        // Keep the last good catalog; only record the error and the attempt time.
+        Err(err) => conn.execute(
+            "UPDATE providers SET model_catalog_refreshed_at = ?1, model_catalog_error = ?2 WHERE id = ?3",
+            params![now_unix, err, provider_id],
+        ),
+    }
+    .map_err(|e| db_err!("failed to update provider model catalog: {e}"))?;
+    Ok(())
+}
+
+pub fn get(db: &db::Db, provider_id: i64) -> crate::shared::error::AppResult<ProviderModelCatalog> {
+    let conn = db.open_connection()?;
+    let row: Option<(String, Option<i64>, Option<String>)> = conn
+        .query_row(
+            "SELECT model_catalog_json, model_catalog_refreshed_at, model_catalog_error FROM providers WHERE id = ?1",
+            params![provider_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query provider model catalog: {e}"))?;
+    let Some((json, refreshed_at, error)) = row else {
+        return Err("DB_NOT_FOUND: provider not found".into());
+    };
+    Ok(ProviderModelCatalog {
+        provider_id,
+        models: models_from_json(&json),
+        refreshed_at,
+        error,
+    })
+}
+
+async fn fetch(client: &reqwest::Client, source: &CatalogSource) -> Result<Vec<String>, String> {
+    let mut last_err = "no base_url configured".to_string();
+    for base_url in &source.base_urls {
+        let url = models_url(base_url, &source.cli_key);
+        let mut req = client
+            .get(&url)
+            .timeout(FETCH_TIMEOUT)
+            .bearer_auth(source.api_key.trim());
+        req = match source.cli_key.as_str() {
+            "claude" => req
+                .header("x-api-key", source.api_key.trim())
+                .header("anthropic-version", "2023-06-01"),
+            "gemini" => req.header("x-goog-api-key", source.api_key.trim()),
+            _ => req,
+        };
+
+        let resp = match req.send().await {
+            Ok(resp) => resp,
+            Err(err) => {
+                last_err = format!("{url}: {err}");
+                continue;
+            }
+        };
+        let status = resp.status();
+        if !status.is_success() {
+            last_err = format!("{url}: HTTP {}", status.as_u16());
+            continue;
+        }
+        match resp.json::<serde_json::Value>().await {
+            Ok(body) => {
+                let models = parse_model_ids(&body);
+                if models.is_empty() {
+                    last_err = format!("{url}: response contained no models");
+                    continue;
+                }
+                return Ok(models);
+            }
+            Err(err) => last_err = format!("{url}: invalid JSON: {err}"),
+        }
+    }
+    let mut err = last_err;
+    err.truncate(MAX_ERROR_LEN);
+    Err(err)
+}
+
+/// Lists the upstream models for any provider without touching the stored catalog (health checks).
+pub(crate) async fn probe(
+    db: &db::Db,
+    client: &reqwest::Client,
+    provider_id: i64,
+) -> Result<Vec<String>, String> {
+    let source = load_source(db, provider_id).map_err(|e| e.to_string())?;
+    fetch(client, &source).await
+}
+
+pub async fn refresh(
+    db: &db::Db,
+    client: &reqwest::Client,
+    provider_id: i64,
+) -> crate::shared::error::AppResult<ProviderModelCatalog> {
+    if provider_id <= 0 {
+        return Err("SEC_INVALID_INPUT: provider_id must be > 0".into());
+    }
+    let source = load_source(db, provider_id)?;
+    if source.kind != ProviderKind::Aggregated {
+        return Err(
+            "SEC_INVALID_INPUT: model catalog is only available for aggregated providers".into(),
+        );
+    }
+
+    let result = fetch(client, &source).await;
+    match &result {
+        Ok(models) => tracing::info!(
+            provider_id,
+            models = models.len(),
+            "provider model catalog refreshed"
+        ),
+        Err(err) => tracing::warn!(
+            provider_id,
+            "provider model catalog refresh failed: {}",
+            err
+        ),
+    }
+    save(db, provider_id, &result, now_unix_seconds())?;
+    get(db, provider_id)
+}
+
+/// Fire-and-forget refresh for a stale catalog; at most one refresh per provider at a time.
+pub(crate) fn spawn_refresh_if_stale(
+    db: &db::Db,
+    client: &reqwest::Client,
+    provider_id: i64,
+    refreshed_at: Option<i64>,
+) {
+    if !is_stale(refreshed_at, now_unix_seconds()) {
+        return;
+    }
+    let in_flight = REFRESH_IN_FLIGHT.get_or_init(|| Mutex::new(HashSet::new()));
+    if !in_flight.lock_or_recover().insert(provider_id) {
+        return;
+    }
+
+    let db = db.clone();
+    let client = client.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(err) = refresh(&db, &client, provider_id).await {
+            tracing::warn!(
+                provider_id,
+                "background model catalog refresh failed: {}",
+                err
+            );
+        }
+        if let Some(in_flight) = REFRESH_IN_FLIGHT.get() {
+            in_flight.lock_or_recover().remove(&provider_id);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn models_url_respects_versioned_base_urls() {
+        assert_eq!(
+            models_url("https://openrouter.ai/api/v1/", "codex"),
+            "https://openrouter.ai/api/v1/models"
+        );
+        assert_eq!(
+            models_url("https://openrouter.ai/api", "claude"),
+            "https://openrouter.ai/api/v1/models"
+        );
+        assert_eq!(
+            models_url("https://example.com", "gemini"),
+            "https://example.com/v1beta/models"
+        );
+    }
+
+    #[test]
+    fn parse_model_ids_supports_openai_and_gemini_shapes() {
+        let openai = serde_json::json!({
+            "data": [{"id": "openai/gpt-4o"}, {"id": "anthropic/claude-sonnet-4"}, {"id": "openai/gpt-4o"}]
+        });
+        assert_eq!(
+            parse_model_ids(&openai),
+            vec!["anthropic/claude-sonnet-4", "openai/gpt-4o"]
+        );
+
+        let gemini = serde_json::json!({
+            "models": [{"name": "models/gemini-2.5-pro"}, {"name": ""}]
+        });
+        assert_eq!(parse_model_ids(&gemini), vec!["gemini-2.5-pro"]);
+    }
+
+    #[test]
+    fn catalog_staleness_uses_ttl() {
+        assert!(is_stale(None, 100));
+        assert!(!is_stale(Some(100), 100 + CATALOG_REFRESH_TTL_SECS - 1));
+        assert!(is_stale(Some(100), 100 + CATALOG_REFRESH_TTL_SECS));
+    }
+}
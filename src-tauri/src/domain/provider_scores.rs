@@ -0,0 +1,299 @@
+//! Usage: Per-provider scores (success rate, p95 latency, cost, quota headroom) and the provider
+//! order they suggest for a sort mode.
+
+use crate::shared::time::now_unix_seconds;
+use crate::{db, provider_limit_usage, providers, request_attempt_logs, sort_modes};
+use serde::Serialize;
+use std::collections::HashMap;
+
+const SCORE_WINDOW_SECS: i64 = 7 * 24 * 60 * 60;
+/// Below this many attempts the success/latency components are treated as unknown.
+const MIN_ATTEMPTS: usize = 5;
+const NEUTRAL: f64 = 0.5;
+
+const WEIGHT_SUCCESS: f64 = 0.4;
+const WEIGHT_LATENCY: f64 = 0.25;
+const WEIGHT_COST: f64 = 0.2;
+const WEIGHT_HEADROOM: f64 = 0.15;
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ProviderScore {
+    pub provider_id: i64,
+    pub provider_name: String,
+    /// Weighted score in `0..=100`.
+    pub score: f64,
+    pub attempts: u32,
+    pub success_rate: Option<f64>,
+    pub p95_duration_ms: Option<i64>,
+    pub cost_multiplier: f64,
+    /// Smallest remaining share across configured spend limits (`None` without limits).
+    pub quota_headroom: Option<f64>,
+    pub explanations: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SortOrderSuggestion {
+    pub cli_key: String,
+    /// `None` = the default provider order (no sort mode).
+    pub mode_id: Option<i64>,
+    pub window_secs: i64,
+    pub current_order: Vec<i64>,
+    pub suggested_order: Vec<i64>,
+    pub changed: bool,
+    /// In suggested order.
+    pub providers: Vec<ProviderScore>,
+}
+
+struct ScoreInput<'a> {
+    provider_id: i64,
+    provider_name: &'a str,
+    cost_multiplier: f64,
+    health: Option<&'a request_attempt_logs::ProviderAttemptHealth>,
+    quota_headroom: Option<f64>,
+}
+
+fn quota_headroom(row: &provider_limit_usage::ProviderLimitUsageRow) -> Option<f64> {
+    [
+        (row.limit_5h_usd, row.usage_5h_usd),
+        (row.limit_daily_usd, row.usage_daily_usd),
+        (row.limit_weekly_usd, row.usage_weekly_usd),
+        (row.limit_monthly_usd, row.usage_monthly_usd),
+        (row.limit_total_usd, row.usage_total_usd),
+    ]
+    .into_iter()
+    .filter_map(|(limit, usage)| {
+        let limit = limit.filter(|v| *v > 0.0)?;
+        Some((1.0 - usage / limit).clamp(0.0, 1.0))
+    })
+    .reduce(f64::min)
+}
+
+fn known_health<'a>(
+    input: &ScoreInput<'a>,
+) -> Option<&'a request_attempt_logs::ProviderAttemptHealth> {
+    input.health.filter(|h| h.attempts >= MIN_ATTEMPTS)
+}
+
+fn score_providers(inputs: &[ScoreInput<'_>]) -> Vec<ProviderScore> {
+    let best_p95 = inputs
+        .iter()
+        .filter_map(|input| known_health(input)?.p95_duration_ms)
+        .filter(|v| *v > 0)
+        .min();
+    let best_cost = inputs
+        .iter()
+        .map(|input| input.cost_multiplier)
+        .filter(|v| *v > 0.0)
+        .reduce(f64::min);
+
+    inputs
+        .iter()
+        .map(|input| {
+            let mut explanations = Vec::new();
+            let health = known_health(input);
+
+            let success = match health {
+                Some(h) => {
+                    explanations.push(format!(
+                        "success rate {:.1}% over {} attempts",
+                        h.success_rate() * 100.0,
+                        h.attempts
+                    ));
+                    h.success_rate()
+                }
+                None => {
+                    let attempts = input.health.map_or(0, |h| h.attempts);
+                    explanations.push(format!(
+                        "only {attempts} recent attempts; success and latency scored neutral"
+                    ));
+                    NEUTRAL
+                }
+            };
+
+            let p95 = health.and_then(|h| h.p95_duration_ms);
+            let latency = match (p95, best_p95) {
+                (Some(p95), Some(best)) if p95 > 0 => {
+                    explanations.push(format!("p95 latency {p95} ms (best {best} ms)"));
+                    best as f64 / p95 as f64
+                }
+                _ => NEUTRAL,
+            };
+
+            let cost = match best_cost {
+                _ if input.cost_multiplier <= 0.0 => 1.0,
+                Some(best) => best / input.cost_multiplier,
+                None => 1.0,
+            };
+            explanations.push(format!("cost multiplier x{}", input.cost_multiplier));
+
+            let headroom = match input.quota_headroom {
+                Some(v) => {
+                    explanations.push(format!(
+                        "{:.0}% of the tightest spend limit left",
+                        v * 100.0
+                    ));
+                    v
+                }
+                None => 1.0,
+            };
+
+            let score = 100.0
+                * (WEIGHT_SUCCESS * success
+                    + WEIGHT_LATENCY * latency
+                    + WEIGHT_COST * cost
+                    + WEIGHT_HEADROOM * headroom);
+            ProviderScore {
+                provider_id: input.provider_id,
+                provider_name: input.provider_name.to_string(),
+                score: (score * 10.0).round() / 10.0,
+                attempts: input.health.map_or(0, |h| h.attempts as u32),
+                success_rate: health.map(|h| h.success_rate()),
+                p95_duration_ms: p95,
+                cost_multiplier: input.cost_multiplier,
+                quota_headroom: input.quota_headroom,
+                explanations,
+            }
+        })
+        .collect()
+}
+
+/// Stable ranking by descending score: equal scores keep the current order.
+fn rank(mut scores: Vec<ProviderScore>) -> Vec<ProviderScore> {
+    scores.sort_by(|a, b| b.score.total_cmp(&a.score));
+    scores
+}
+
+pub fn suggest_order(
+    db: &db::Db,
+    cli_key: &str,
+    mode_id: Option<i64>,
+) -> crate::shared::error::AppResult<SortOrderSuggestion> {
+    let cli_key = cli_key.trim();
+    let all_providers = providers::list_by_cli(db, cli_key)?;
+    let current_order: Vec<i64> = match mode_id {
+        Some(mode_id) => sort_modes::list_mode_providers(db, mode_id, cli_key)?
+            .into_iter()
+            .map(|row| row.provider_id)
+            .collect(),
+        None => all_providers.iter().map(|p| p.id).collect(),
+    };
+
+    let since = now_unix_seconds().saturating_sub(SCORE_WINDOW_SECS);
+    let health = request_attempt_logs::provider_health_since(db, cli_key, since)?;
+    let health_by_id: HashMap<i64, _> = health.iter().map(|h| (h.provider_id, h)).collect();
+    let headroom_by_id: HashMap<i64, Option<f64>> =
+        provider_limit_usage::list_v1(db, Some(cli_key))?
+            .iter()
+            .map(|row| (row.provider_id, quota_headroom(row)))
+            .collect();
+    let provider_by_id: HashMap<i64, &providers::ProviderSummary> =
+        all_providers.iter().map(|p| (p.id, p)).collect();
+
+    let inputs: Vec<ScoreInput<'_>> = current_order
+        .iter()
+        .filter_map(|id| provider_by_id.get(id))
+        .map(|provider| ScoreInput {
+            provider_id: provider.id,
+            provider_name: &provider.name,
+            cost_multiplier: provider.cost_multiplier,
+            health: health_by_id.get(&provider.id).copied(),
+            quota_headroom: headroom_by_id.get(&provider.id).copied().flatten(),
+        })
+        .collect();
+
+    let ranked = rank(score_providers(&inputs));
+    let suggested_order: Vec<i64> = ranked.iter().map(|s| s.provider_id).collect();
+    Ok(SortOrderSuggestion {
+        cli_key: cli_key.to_string(),
+        mode_id,
+        window_secs: SCORE_WINDOW_SECS,
+        changed: suggested_order != current_order,
+        current_order,
+        suggested_order,
+        providers: ranked,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::request_attempt_logs::ProviderAttemptHealth;
+
+    fn health(
+        provider_id: i64,
+        attempts: usize,
+        successes: usize,
+        p95: i64,
+    ) -> ProviderAttemptHealth {
+        ProviderAttemptHealth {
+            provider_id,
+            attempts,
+            successes,
+            p95_duration_ms: Some(p95),
+        }
+    }
+
+    fn input<'a>(
+        provider_id: i64,
+        cost_multiplier: f64,
+        health: Option<&'a ProviderAttemptHealth>,
+        quota_headroom: Option<f64>,
+    ) -> ScoreInput<'a> {
+        ScoreInput {
+            provider_id,
+            provider_name: "p",
+            cost_multiplier,
+            health,
+            quota_headroom,
+        }
+    }
+
+    #[test]
+    fn ranks_reliable_fast_cheap_providers_first() {
+        let flaky = health(1, 20, 10, 800);
+        let solid = health(2, 20, 20, 1600);
+        let scores = rank(score_providers(&[
+            input(1, 1.0, Some(&flaky), None),
+            input(2, 1.0, Some(&solid), None),
+            input(3, 2.0, None, None),
+        ]));
+        let order: Vec<i64> = scores.iter().map(|s| s.provider_id).collect();
+        assert_eq!(order, vec![2, 1, 3]);
+        assert_eq!(scores[0].success_rate, Some(1.0));
+        assert!(scores[2].explanations[0].starts_with("only 0 recent attempts"));
+    }
+
+    #[test]
+    fn quota_headroom_uses_tightest_limit_and_lowers_score() {
+        let row = provider_limit_usage::ProviderLimitUsageRow {
+            cli_key: "claude".to_string(),
+            provider_id: 1,
+            provider_name: "p".to_string(),
+            enabled: true,
+            limit_5h_usd: Some(10.0),
+            limit_daily_usd: Some(20.0),
+            daily_reset_mode: None,
+            daily_reset_time: None,
+            limit_weekly_usd: None,
+            limit_monthly_usd: None,
+            limit_total_usd: None,
+            usage_5h_usd: 9.0,
+            usage_daily_usd: 9.0,
+            usage_weekly_usd: 0.0,
+            usage_monthly_usd: 0.0,
+            usage_total_usd: 0.0,
+            window_5h_start_ts: 0,
+            window_daily_start_ts: 0,
+            window_weekly_start_ts: 0,
+            window_monthly_start_ts: 0,
+        };
+        let headroom = quota_headroom(&row).expect("limits configured");
+        assert!((headroom - 0.1).abs() < 1e-9);
+
+        let scores = rank(score_providers(&[
+            input(1, 1.0, None, Some(headroom)),
+            input(2, 1.0, None, None),
+        ]));
+        assert_eq!(scores[0].provider_id, 2);
+    }
+}
@@ -0,0 +1,135 @@
+//! Usage: Per-CLI default provider tag used by the gateway when a request names no tag
+//! (`x-aio-provider-tag`).
+
+use crate::db;
+use crate::shared::error::db_err;
+use crate::shared::time::now_unix_seconds;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+
+const MAX_TAG_CHARS: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, specta::Type)]
+pub struct ProviderTagDefault {
+    pub cli_key: String,
+    /// `None` = no default; every provider of the cli stays eligible.
+    pub tag: Option<String>,
+}
+
+/// Trimmed tag, `None` when blank. Shared with the request header parser.
+pub(crate) fn normalize_tag(raw: &str) -> crate::shared::error::AppResult<Option<String>> {
+    let tag = raw.trim();
+    if tag.is_empty() {
+        return Ok(None);
+    }
+    if tag.chars().count() > MAX_TAG_CHARS {
+        return Err(
+            format!("SEC_INVALID_INPUT: tag is too long (max {MAX_TAG_CHARS} chars)").into(),
+        );
+    }
+    Ok(Some(tag.to_string()))
+}
+
+pub fn list(db: &db::Db) -> crate::shared::error::AppResult<Vec<ProviderTagDefault>> {
+    let conn = db.open_connection()?;
+    let mut stmt = conn
+        .prepare("SELECT cli_key, tag FROM provider_tag_defaults ORDER BY cli_key ASC")
+        .map_err(|e| db_err!("failed to prepare provider_tag_defaults query: {e}"))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(ProviderTagDefault {
+                cli_key: row.get("cli_key")?,
+                tag: row.get("tag")?,
+            })
+        })
+        .map_err(|e| db_err!("failed to list provider_tag_defaults: {e}"))?;
+
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row.map_err(|e| db_err!("failed to read provider_tag_defaults row: {e}"))?);
+    }
+    Ok(out)
+}
+
+pub(crate) fn get_for_cli(
+    db: &db::Db,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<Option<String>> {
+    let conn = db.open_connection()?;
+    conn.query_row(
+        "SELECT tag FROM provider_tag_defaults WHERE cli_key = ?1",
+        params![cli_key],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| db_err!("failed to query provider_tag_defaults: {e}"))
+}
+
+/// Stores the default tag for `cli_key`; a blank or missing tag removes it.
+pub fn set(
+    db: &db::Db,
+    cli_key: &str,
+    tag: Option<&str>,
+) -> crate::shared::error::AppResult<ProviderTagDefault> {
+    crate::shared::cli_key::validate_cli_key(cli_key)?;
+    let tag = match tag {
+        Some(raw) => normalize_tag(raw)?,
+        None => None,
+    };
+
+    let conn = db.open_connection()?;
+    match tag.as_deref() {
+        Some(tag) => {
+            let now = now_unix_seconds();
+            conn.execute(
+                r#"
+INSERT INTO provider_tag_defaults(cli_key, tag, created_at, updated_at)
+VALUES (?1, ?2, ?3, ?3)
+ON CONFLICT(cli_key) DO UPDATE SET
+  tag = excluded.tag,
+  updated_at = excluded.updated_at
+"#,
+                params![cli_key, tag, now],
+            )
+            .map_err(|e| db_err!("failed to upsert provider_tag_defaults: {e}"))?;
+        }
+        None => {
+            conn.execute(
+                "DELETE FROM provider_tag_defaults WHERE cli_key = ?1",
+                params![cli_key],
+            )
+            .map_err(|e| db_err!("failed to delete provider_tag_defaults: {e}"))?;
+        }
+    }
+
+    Ok(ProviderTagDefault {
+        cli_key: cli_key.to_string(),
+        tag,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_stores_clears_and_validates_default_tags() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let db = crate::db::init_for_tests(&dir.path().join("test.db")).expect("init db");
+
+        let stored = set(&db, "claude", Some(" cheap ")).expect("set tag");
+        assert_eq!(stored.tag.as_deref(), Some("cheap"));
+        assert_eq!(
+            get_for_cli(&db, "claude").expect("get").as_deref(),
+            Some("cheap")
+        );
+        assert_eq!(get_for_cli(&db, "codex").expect("get"), None);
+        assert_eq!(list(&db).expect("list"), vec![stored]);
+
+        assert_eq!(set(&db, "claude", Some("  ")).expect("clear").tag, None);
+        assert!(list(&db).expect("list").is_empty());
+
+        assert!(set(&db, "unknown", Some("cheap")).is_err());
+        assert!(set(&db, "claude", Some(&"x".repeat(MAX_TAG_CHARS + 1))).is_err());
+    }
+}
@@ -1,6 +1,9 @@
 //! Usage: Provider configuration persistence and gateway selection helpers.
 
 use crate::db;
+use crate::provider_limit_usage;
+use crate::provider_maintenance::{self, ProviderMaintenanceOccurrence};
+use crate::provider_model_catalog;
 use crate::shared::error::db_err;
 use crate::shared::sqlite::enabled_to_int;
 use crate::shared::time::now_unix_seconds;
@@ -8,7 +11,14 @@ use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
-const DEFAULT_PRIORITY: i64 = 100;
+mod bulk;
+
+pub use bulk::{
+    bulk_update, ProviderBulkChange, ProviderBulkOutcome, ProviderBulkUpdate,
+    ProviderBulkUpdateResult,
+};
+
+pub(crate) const DEFAULT_PRIORITY: i64 = 100;
 const MAX_MODEL_NAME_LEN: usize = 200;
 const MAX_LIMIT_USD: f64 = 1_000_000_000.0;
 
@@ -52,6 +62,37 @@ impl ProviderAuthMode {
     }
 }
 
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, specta::Type, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    /// Single upstream; models are whatever the CLI asks for.
+    #[default]
+    Standard,
+    /// Multi-model aggregator (OpenRouter-style) with a `/models` catalog.
+    Aggregated,
+    /// Another aio-coding-hub gateway; the API key is that hub's gateway access token.
+    Federated,
+}
+
+impl ProviderKind {
+    pub(crate) fn parse(input: &str) -> Option<Self> {
+        match input.trim() {
+            "standard" => Some(Self::Standard),
+            "aggregated" => Some(Self::Aggregated),
+            "federated" => Some(Self::Federated),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Standard => "standard",
+            Self::Aggregated => "aggregated",
+            Self::Federated => "federated",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ProviderUpsertParams {
     pub provider_id: Option<i64>,
@@ -74,6 +115,7 @@ pub struct ProviderUpsertParams {
     pub limit_total_usd: Option<f64>,
     pub tags: Option<Vec<String>>,
     pub note: Option<String>,
+    pub provider_kind: Option<ProviderKind>,
 }
 
 fn parse_reset_time_hms(input: &str) -> Option<(u8, u8, u8)> {
@@ -253,7 +295,7 @@ fn tags_from_json(raw: &str) -> Vec<String> {
         .collect()
 }
 
-fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+pub(crate) fn normalize_tags(tags: Vec<String>) -> Vec<String> {
     let mut seen = HashSet::new();
     tags.into_iter()
         .map(|v| v.trim().to_string())
@@ -267,6 +309,8 @@ fn normalize_tags(tags: Vec<String>) -> Vec<String> {
 pub enum ProviderBaseUrlMode {
     Order,
     Ping,
+    /// Send each attempt to the first two base_urls at once; the first to answer serves it.
+    Race,
 }
 
 impl ProviderBaseUrlMode {
@@ -274,6 +318,7 @@ impl ProviderBaseUrlMode {
         match input.trim() {
             "order" => Some(Self::Order),
             "ping" => Some(Self::Ping),
+            "race" => Some(Self::Race),
             _ => None,
         }
     }
@@ -282,6 +327,7 @@ impl ProviderBaseUrlMode {
         match self {
             Self::Order => "order",
             Self::Ping => "ping",
+            Self::Race => "race",
         }
     }
 }
@@ -304,6 +350,8 @@ pub struct ProviderSummary {
     pub limit_weekly_usd: Option<f64>,
     pub limit_monthly_usd: Option<f64>,
     pub limit_total_usd: Option<f64>,
+    /// Hard calendar-month budget; the provider is blocked once this month's spend reaches it.
+    pub monthly_budget_usd: Option<f64>,
     pub tags: Vec<String>,
     pub note: String,
     pub created_at: i64,
@@ -313,6 +361,31 @@ pub struct ProviderSummary {
     pub oauth_email: Option<String>,
     pub oauth_expires_at: Option<i64>,
     pub oauth_last_error: Option<String>,
+    pub maintenance_active: Option<ProviderMaintenanceOccurrence>,
+    pub maintenance_next: Option<ProviderMaintenanceOccurrence>,
+    pub provider_kind: ProviderKind,
+    pub model_catalog: Vec<String>,
+    pub model_catalog_refreshed_at: Option<i64>,
+    pub model_catalog_error: Option<String>,
+    /// Kept out of normal routing; tried once when every other provider failed.
+    pub last_resort: bool,
+    /// claude only: the gateway answers `GET /v1/models` from the model slots itself.
+    pub emulate_models_endpoint: bool,
+    /// claude only: caps `thinking.budget_tokens` on outgoing requests; `0` strips thinking.
+    pub thinking_budget_cap: Option<u32>,
+    /// Set when the provider was deleted (archived); it keeps its row so stats still resolve
+    /// its name, and is removed for good by `purge`.
+    pub archived_at: Option<i64>,
+    /// Case-insensitive phrases marking a 2xx answer as a relay refusal; empty = detection off.
+    pub refusal_patterns: Vec<String>,
+    /// claude only: overrides the `anthropic-version` header sent upstream.
+    pub anthropic_version: Option<String>,
+    /// claude only: betas merged into `anthropic-beta` on outgoing requests.
+    pub anthropic_betas: Vec<String>,
+    /// claude only: drop client-sent betas not in `anthropic_betas` (relays rejecting unknown betas).
+    pub anthropic_beta_strip: bool,
+    /// POSTed when the provider's circuit opens or closes, or it gets quarantined.
+    pub webhook_url: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -330,8 +403,19 @@ pub(crate) struct ProviderForGateway {
     pub limit_weekly_usd: Option<f64>,
     pub limit_monthly_usd: Option<f64>,
     pub limit_total_usd: Option<f64>,
+    pub monthly_budget_usd: Option<f64>,
     pub auth_mode: String,
     pub oauth_provider_type: Option<String>,
+    pub provider_kind: ProviderKind,
+    pub model_catalog: Vec<String>,
+    pub model_catalog_refreshed_at: Option<i64>,
+    pub emulate_models_endpoint: bool,
+    pub thinking_budget_cap: Option<u32>,
+    pub refusal_patterns: Vec<String>,
+    pub anthropic_version: Option<String>,
+    pub anthropic_betas: Vec<String>,
+    pub anthropic_beta_strip: bool,
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -351,7 +435,9 @@ fn validate_cli_key(cli_key: &str) -> crate::shared::error::AppResult<()> {
     crate::shared::cli_key::validate_cli_key(cli_key)
 }
 
-fn normalize_base_urls(base_urls: Vec<String>) -> crate::shared::error::AppResult<Vec<String>> {
+pub(crate) fn normalize_base_urls(
+    base_urls: Vec<String>,
+) -> crate::shared::error::AppResult<Vec<String>> {
     let mut out: Vec<String> = Vec::with_capacity(base_urls.len().max(1));
     let mut seen: HashSet<String> = HashSet::with_capacity(base_urls.len());
 
@@ -414,6 +500,52 @@ fn base_urls_from_row(base_url_fallback: &str, base_urls_json: &str) -> Vec<Stri
     parsed
 }
 
+/// Anthropic rejects `thinking.budget_tokens` below this value.
+pub(crate) const THINKING_BUDGET_CAP_MIN: u32 = 1024;
+const THINKING_BUDGET_CAP_MAX: u32 = 128_000;
+
+fn thinking_budget_cap_from_row(row: &rusqlite::Row<'_>) -> Result<Option<u32>, rusqlite::Error> {
+    Ok(row
+        .get::<_, Option<i64>>("thinking_budget_cap")?
+        .and_then(|v| u32::try_from(v).ok()))
+}
+
+const MAX_REFUSAL_PATTERNS: usize = 32;
+const MAX_REFUSAL_PATTERN_CHARS: usize = 200;
+
+fn refusal_patterns_from_row(row: &rusqlite::Row<'_>) -> Result<Vec<String>, rusqlite::Error> {
+    let raw: String = row.get("refusal_patterns_json")?;
+    Ok(tags_from_json(&raw))
+}
+
+const MAX_WEBHOOK_URL_LEN: usize = 2048;
+
+const MAX_ANTHROPIC_BETAS: usize = 32;
+const MAX_ANTHROPIC_BETA_CHARS: usize = 100;
+
+fn anthropic_betas_from_row(row: &rusqlite::Row<'_>) -> Result<Vec<String>, rusqlite::Error> {
+    let raw: String = row.get("anthropic_betas_json")?;
+    Ok(tags_from_json(&raw))
+}
+
+/// `YYYY-MM-DD`, the shape of every published Anthropic API version.
+fn is_valid_anthropic_version(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() == 10
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        })
+}
+
+fn is_valid_anthropic_beta(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_ANTHROPIC_BETA_CHARS
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
+
 fn row_to_summary(row: &rusqlite::Row<'_>) -> Result<ProviderSummary, rusqlite::Error> {
     let cli_key: String = row.get("cli_key")?;
     let base_url_fallback: String = row.get("base_url")?;
@@ -423,6 +555,8 @@ fn row_to_summary(row: &rusqlite::Row<'_>) -> Result<ProviderSummary, rusqlite::
     let base_url_mode_raw: String = row.get("base_url_mode")?;
     let daily_reset_mode_raw: String = row.get("daily_reset_mode")?;
     let daily_reset_time_raw: String = row.get("daily_reset_time")?;
+    let provider_kind_raw: String = row.get("provider_kind")?;
+    let model_catalog_json: String = row.get("model_catalog_json")?;
     let base_url_mode =
         ProviderBaseUrlMode::parse(&base_url_mode_raw).unwrap_or(ProviderBaseUrlMode::Order);
     let daily_reset_mode =
@@ -450,6 +584,7 @@ fn row_to_summary(row: &rusqlite::Row<'_>) -> Result<ProviderSummary, rusqlite::
         limit_weekly_usd: row.get("limit_weekly_usd")?,
         limit_monthly_usd: row.get("limit_monthly_usd")?,
         limit_total_usd: row.get("limit_total_usd")?,
+        monthly_budget_usd: row.get("monthly_budget_usd")?,
         tags: tags_from_json(&tags_json),
         note: row.get("note")?,
         created_at: row.get("created_at")?,
@@ -461,6 +596,20 @@ fn row_to_summary(row: &rusqlite::Row<'_>) -> Result<ProviderSummary, rusqlite::
         oauth_email: row.get("oauth_email")?,
         oauth_expires_at: row.get("oauth_expires_at")?,
         oauth_last_error: row.get("oauth_last_error")?,
+        maintenance_active: None,
+        maintenance_next: None,
+        provider_kind: ProviderKind::parse(&provider_kind_raw).unwrap_or_default(),
+        model_catalog: provider_model_catalog::models_from_json(&model_catalog_json),
+        model_catalog_refreshed_at: row.get("model_catalog_refreshed_at")?,
+        model_catalog_error: row.get("model_catalog_error")?,
+        last_resort: row.get::<_, i64>("last_resort")? != 0,
+        emulate_models_endpoint: row.get::<_, i64>("emulate_models_endpoint")? != 0,
+        thinking_budget_cap: thinking_budget_cap_from_row(row)?,
+        refusal_patterns: refusal_patterns_from_row(row)?,
+        anthropic_version: row.get("anthropic_version")?,
+        anthropic_betas: anthropic_betas_from_row(row)?,
+        anthropic_beta_strip: row.get::<_, i64>("anthropic_beta_strip")? != 0,
+        webhook_url: row.get("webhook_url")?,
     })
 }
 
@@ -472,6 +621,22 @@ impl ProviderForGateway {
     ) -> String {
         self.claude_models.map_model(requested_model, has_thinking)
     }
+
+    /// Aggregated providers only accept models from their catalog. Fails open when the catalog
+    /// has not been fetched yet; Claude slot mappings are checked on the mapped model names.
+    pub(crate) fn catalog_allows_model(&self, requested_model: &str) -> bool {
+        if self.provider_kind != ProviderKind::Aggregated || self.model_catalog.is_empty() {
+            return true;
+        }
+        let requested_model = requested_model.trim();
+        [
+            requested_model.to_string(),
+            self.get_effective_claude_model(requested_model, false),
+            self.get_effective_claude_model(requested_model, true),
+        ]
+        .iter()
+        .any(|model| self.model_catalog.iter().any(|m| m == model))
+    }
 }
 
 pub(crate) fn get_by_id(
@@ -500,13 +665,27 @@ SELECT
   limit_weekly_usd,
   limit_monthly_usd,
   limit_total_usd,
+  monthly_budget_usd,
   created_at,
   updated_at,
   auth_mode,
   oauth_provider_type,
   oauth_email,
   oauth_expires_at,
-  oauth_last_error
+  oauth_last_error,
+  provider_kind,
+  model_catalog_json,
+  model_catalog_refreshed_at,
+  model_catalog_error,
+  last_resort,
+  emulate_models_endpoint,
+  thinking_budget_cap,
+  archived_at,
+  refusal_patterns_json,
+  anthropic_version,
+  anthropic_betas_json,
+  anthropic_beta_strip,
+  webhook_url
 FROM providers
 WHERE id = ?1
 "#,
@@ -693,9 +872,18 @@ pub(crate) fn cli_key_by_id(
     Ok(cli_key)
 }
 
+/// Lists the providers of a cli, leaving out archived ones.
 pub fn list_by_cli(
     db: &db::Db,
     cli_key: &str,
+) -> crate::shared::error::AppResult<Vec<ProviderSummary>> {
+    list_by_cli_with_archived(db, cli_key, false)
+}
+
+pub fn list_by_cli_with_archived(
+    db: &db::Db,
+    cli_key: &str,
+    include_archived: bool,
 ) -> crate::shared::error::AppResult<Vec<ProviderSummary>> {
     validate_cli_key(cli_key)?;
     let conn = db.open_connection()?;
@@ -723,22 +911,40 @@ SELECT
   limit_weekly_usd,
   limit_monthly_usd,
   limit_total_usd,
+  monthly_budget_usd,
   created_at,
   updated_at,
   auth_mode,
   oauth_provider_type,
   oauth_email,
   oauth_expires_at,
-  oauth_last_error
+  oauth_last_error,
+  provider_kind,
+  model_catalog_json,
+  model_catalog_refreshed_at,
+  model_catalog_error,
+  last_resort,
+  emulate_models_endpoint,
+  thinking_budget_cap,
+  archived_at,
+  refusal_patterns_json,
+  anthropic_version,
+  anthropic_betas_json,
+  anthropic_beta_strip,
+  webhook_url
 FROM providers
 WHERE cli_key = ?1
+  AND (?2 = 1 OR archived_at IS NULL)
 ORDER BY sort_order ASC, id DESC
 "#,
         )
         .map_err(|e| db_err!("failed to prepare query: {e}"))?;
 
     let rows = stmt
-        .query_map(params![cli_key], row_to_summary)
+        .query_map(
+            params![cli_key, enabled_to_int(include_archived)],
+            row_to_summary,
+        )
         .map_err(|e| db_err!("failed to list providers: {e}"))?;
 
     let mut items = Vec::new();
@@ -746,6 +952,17 @@ ORDER BY sort_order ASC, id DESC
         items.push(row.map_err(|e| db_err!("failed to read provider row: {e}"))?);
     }
 
+    let provider_ids: Vec<i64> = items.iter().map(|p| p.id).collect();
+    let windows = provider_maintenance::list_windows_by_provider(&conn, &provider_ids)?;
+    let now = now_unix_seconds();
+    for item in items.iter_mut() {
+        if let Some(windows) = windows.get(&item.id) {
+            let (active, next) = provider_maintenance::active_and_next(windows, now);
+            item.maintenance_active = active;
+            item.maintenance_next = next;
+        }
+    }
+
     Ok(items)
 }
 
@@ -761,6 +978,8 @@ fn map_gateway_provider_row(
     let claude_models_json: String = row.get("claude_models_json")?;
     let daily_reset_mode_raw: String = row.get("daily_reset_mode")?;
     let daily_reset_time_raw: String = row.get("daily_reset_time")?;
+    let provider_kind_raw: String = row.get("provider_kind")?;
+    let model_catalog_json: String = row.get("model_catalog_json")?;
     let base_url_mode =
         ProviderBaseUrlMode::parse(&base_url_mode_raw).unwrap_or(ProviderBaseUrlMode::Order);
     let daily_reset_mode =
@@ -784,10 +1003,35 @@ fn map_gateway_provider_row(
         limit_weekly_usd: row.get("limit_weekly_usd")?,
         limit_monthly_usd: row.get("limit_monthly_usd")?,
         limit_total_usd: row.get("limit_total_usd")?,
+        monthly_budget_usd: row.get("monthly_budget_usd")?,
         auth_mode: row
             .get::<_, Option<String>>("auth_mode")?
             .unwrap_or_else(|| "api_key".to_string()),
         oauth_provider_type: row.get("oauth_provider_type")?,
+        provider_kind: ProviderKind::parse(&provider_kind_raw).unwrap_or_default(),
+        model_catalog: provider_model_catalog::models_from_json(&model_catalog_json),
+        model_catalog_refreshed_at: row.get("model_catalog_refreshed_at")?,
+        emulate_models_endpoint: cli_key == "claude"
+            && row.get::<_, i64>("emulate_models_endpoint")? != 0,
+        thinking_budget_cap: if cli_key == "claude" {
+            thinking_budget_cap_from_row(row)?
+        } else {
+            None
+        },
+        refusal_patterns: refusal_patterns_from_row(row)?,
+        anthropic_version: if cli_key == "claude" {
+            row.get("anthropic_version")?
+        } else {
+            None
+        },
+        anthropic_betas: if cli_key == "claude" {
+            anthropic_betas_from_row(row)?
+        } else {
+            Vec::new()
+        },
+        anthropic_beta_strip: cli_key == "claude"
+            && row.get::<_, i64>("anthropic_beta_strip")? != 0,
+        tags: tags_from_json(&row.get::<_, String>("tags_json")?),
     })
 }
 
@@ -815,14 +1059,26 @@ SELECT
   p.limit_weekly_usd,
   p.limit_monthly_usd,
   p.limit_total_usd,
+  p.monthly_budget_usd,
   p.auth_mode,
-  p.oauth_provider_type
+  p.oauth_provider_type,
+  p.provider_kind,
+  p.model_catalog_json,
+  p.model_catalog_refreshed_at,
+  p.emulate_models_endpoint,
+  p.thinking_budget_cap,
+  p.refusal_patterns_json,
+  p.anthropic_version,
+  p.anthropic_betas_json,
+  p.anthropic_beta_strip,
+  p.tags_json
 FROM sort_mode_providers mp
 JOIN providers p ON p.id = mp.provider_id
 WHERE mp.mode_id = ?1
   AND mp.cli_key = ?2
   AND p.cli_key = ?2
   AND mp.enabled = 1
+  AND p.last_resort = 0
 ORDER BY mp.sort_order ASC
 "#,
         )
@@ -864,11 +1120,23 @@ SELECT
   limit_weekly_usd,
   limit_monthly_usd,
   limit_total_usd,
+  monthly_budget_usd,
   auth_mode,
-  oauth_provider_type
+  oauth_provider_type,
+  provider_kind,
+  model_catalog_json,
+  model_catalog_refreshed_at,
+  emulate_models_endpoint,
+  thinking_budget_cap,
+  refusal_patterns_json,
+  anthropic_version,
+  anthropic_betas_json,
+  anthropic_beta_strip,
+  tags_json
 FROM providers
 WHERE cli_key = ?1
   AND enabled = 1
+  AND last_resort = 0
 ORDER BY sort_order ASC, id DESC
 "#,
         )
@@ -935,7 +1203,112 @@ pub(crate) fn list_enabled_for_gateway_in_mode(
     }
 }
 
-fn next_sort_order(conn: &Connection, cli_key: &str) -> crate::shared::error::AppResult<i64> {
+/// Loads a single provider for gateway use regardless of its enabled flag (used by request
+/// mirroring, where the shadow provider is usually kept out of normal routing).
+pub(crate) fn get_for_gateway(
+    db: &db::Db,
+    cli_key: &str,
+    provider_id: i64,
+) -> crate::shared::error::AppResult<Option<ProviderForGateway>> {
+    validate_cli_key(cli_key)?;
+    let conn = db.open_connection()?;
+    conn.query_row(
+        r#"
+SELECT
+  id,
+  name,
+  base_url,
+  base_urls_json,
+  base_url_mode,
+  api_key_plaintext,
+  claude_models_json,
+  limit_5h_usd,
+  limit_daily_usd,
+  daily_reset_mode,
+  daily_reset_time,
+  limit_weekly_usd,
+  limit_monthly_usd,
+  limit_total_usd,
+  monthly_budget_usd,
+  auth_mode,
+  oauth_provider_type,
+  provider_kind,
+  model_catalog_json,
+  model_catalog_refreshed_at,
+  emulate_models_endpoint,
+  thinking_budget_cap,
+  refusal_patterns_json,
+  anthropic_version,
+  anthropic_betas_json,
+  anthropic_beta_strip,
+  tags_json
+FROM providers
+WHERE id = ?1
+  AND cli_key = ?2
+  AND archived_at IS NULL
+"#,
+        params![provider_id, cli_key],
+        |row| map_gateway_provider_row(row, cli_key),
+    )
+    .optional()
+    .map_err(|e| db_err!("failed to query gateway provider: {e}"))
+}
+
+/// Loads the enabled last-resort provider of a cli (at most one is flagged per cli).
+pub(crate) fn get_last_resort_for_gateway(
+    db: &db::Db,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<Option<ProviderForGateway>> {
+    validate_cli_key(cli_key)?;
+    let conn = db.open_connection()?;
+    conn.query_row(
+        r#"
+SELECT
+  id,
+  name,
+  base_url,
+  base_urls_json,
+  base_url_mode,
+  api_key_plaintext,
+  claude_models_json,
+  limit_5h_usd,
+  limit_daily_usd,
+  daily_reset_mode,
+  daily_reset_time,
+  limit_weekly_usd,
+  limit_monthly_usd,
+  limit_total_usd,
+  monthly_budget_usd,
+  auth_mode,
+  oauth_provider_type,
+  provider_kind,
+  model_catalog_json,
+  model_catalog_refreshed_at,
+  emulate_models_endpoint,
+  thinking_budget_cap,
+  refusal_patterns_json,
+  anthropic_version,
+  anthropic_betas_json,
+  anthropic_beta_strip,
+  tags_json
+FROM providers
+WHERE cli_key = ?1
+  AND enabled = 1
+  AND last_resort = 1
+ORDER BY sort_order ASC, id DESC
+LIMIT 1
+"#,
+        params![cli_key],
+        |row| map_gateway_provider_row(row, cli_key),
+    )
+    .optional()
+    .map_err(|e| db_err!("failed to query last-resort provider: {e}"))
+}
+
+pub(crate) fn next_sort_order(
+    conn: &Connection,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<i64> {
     conn.query_row(
         "SELECT COALESCE(MAX(sort_order), -1) + 1 FROM providers WHERE cli_key = ?1",
         params![cli_key],
@@ -969,6 +1342,7 @@ pub fn upsert(
         limit_total_usd,
         tags,
         note,
+        provider_kind,
     } = input;
     let cli_key = cli_key.trim();
     validate_cli_key(cli_key)?;
@@ -982,6 +1356,13 @@ pub fn upsert(
 
     let requested_auth_mode = auth_mode.unwrap_or(ProviderAuthMode::ApiKey);
     let is_oauth = requested_auth_mode == ProviderAuthMode::Oauth;
+    if is_oauth && provider_kind == Some(ProviderKind::Federated) {
+        return Err(
+            "SEC_INVALID_INPUT: federated providers authenticate with the remote gateway access token, not oauth"
+                .to_string()
+                .into(),
+        );
+    }
 
     let base_urls = if is_oauth {
         // OAuth providers don't need base URLs — the adapter knows the endpoint.
@@ -1086,9 +1467,10 @@ INSERT INTO providers(
   limit_total_usd,
   tags_json,
   note,
+  provider_kind,
   created_at,
   updated_at
-) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, '{}', '{}', ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, '{}', '{}', ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24)
 "#,
                 params![
                     cli_key,
@@ -1112,6 +1494,7 @@ INSERT INTO providers(
                     limit_total_usd,
                     tags_json_value,
                     note_value,
+                    provider_kind.unwrap_or_default().as_str(),
                     now,
                     now
                 ],
@@ -1145,12 +1528,13 @@ INSERT INTO providers(
                 String,
                 String,
                 String,
+                String,
             );
             let existing: Option<ExistingProviderRow> = tx
                 .query_row(
-                    "SELECT cli_key, api_key_plaintext, priority, claude_models_json, auth_mode, daily_reset_mode, daily_reset_time, tags_json, note FROM providers WHERE id = ?1",
+                    "SELECT cli_key, api_key_plaintext, priority, claude_models_json, auth_mode, daily_reset_mode, daily_reset_time, tags_json, note, provider_kind FROM providers WHERE id = ?1",
                     params![id],
-                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?, row.get(7)?, row.get(8)?)),
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?, row.get(7)?, row.get(8)?, row.get(9)?)),
                 )
                 .optional()
                 .map_err(|e| db_err!("failed to query provider: {e}"))?;
@@ -1165,6 +1549,7 @@ INSERT INTO providers(
                 existing_daily_reset_time_raw,
                 existing_tags_json,
                 existing_note,
+                existing_provider_kind_raw,
             )) = existing
             else {
                 return Err("DB_NOT_FOUND: provider not found".to_string().into());
@@ -1246,6 +1631,10 @@ INSERT INTO providers(
                 None => existing_note,
             };
 
+            let next_provider_kind = provider_kind
+                .or_else(|| ProviderKind::parse(&existing_provider_kind_raw))
+                .unwrap_or_default();
+
             tx.execute(
                 r#"
 UPDATE providers
@@ -1271,8 +1660,9 @@ SET
   limit_total_usd = ?17,
   tags_json = ?18,
   note = ?19,
-  updated_at = ?20
-WHERE id = ?21
+  provider_kind = ?20,
+  updated_at = ?21
+WHERE id = ?22
 "#,
                 params![
                     name,
@@ -1294,6 +1684,7 @@ WHERE id = ?21
                     next_limit_total_usd,
                     next_tags_json,
                     next_note,
+                    next_provider_kind.as_str(),
                     now,
                     id
                 ],
@@ -1323,6 +1714,9 @@ pub fn set_enabled(
     enabled: bool,
 ) -> crate::shared::error::AppResult<ProviderSummary> {
     let conn = db.open_connection()?;
+    if enabled && archived_at(&conn, provider_id)?.is_some() {
+        return Err("SEC_INVALID_INPUT: provider is archived; restore it first".into());
+    }
     let now = now_unix_seconds();
     let changed = conn
         .execute(
@@ -1338,6 +1732,300 @@ pub fn set_enabled(
     get_by_id(&conn, provider_id)
 }
 
+/// Flags (or unflags) a provider as its cli's last-resort fallback; any previous last-resort
+/// provider of the same cli is unflagged.
+pub fn set_last_resort(
+    db: &db::Db,
+    provider_id: i64,
+    last_resort: bool,
+) -> crate::shared::error::AppResult<ProviderSummary> {
+    let mut conn = db.open_connection()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| db_err!("failed to start transaction: {e}"))?;
+    let now = now_unix_seconds();
+
+    let cli_key: String = tx
+        .query_row(
+            "SELECT cli_key FROM providers WHERE id = ?1",
+            params![provider_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query provider: {e}"))?
+        .ok_or_else(|| crate::shared::error::AppError::from("DB_NOT_FOUND: provider not found"))?;
+
+    if last_resort {
+        tx.execute(
+            "UPDATE providers SET last_resort = 0, updated_at = ?1 WHERE cli_key = ?2 AND last_resort = 1 AND id <> ?3",
+            params![now, cli_key, provider_id],
+        )
+        .map_err(|e| db_err!("failed to clear last-resort provider: {e}"))?;
+    }
+    tx.execute(
+        "UPDATE providers SET last_resort = ?1, updated_at = ?2 WHERE id = ?3",
+        params![enabled_to_int(last_resort), now, provider_id],
+    )
+    .map_err(|e| db_err!("failed to update provider: {e}"))?;
+
+    let summary = get_by_id(&tx, provider_id)?;
+    tx.commit()
+        .map_err(|e| db_err!("failed to commit transaction: {e}"))?;
+    Ok(summary)
+}
+
+/// Toggles `GET /v1/models` emulation for a claude provider.
+pub fn set_emulate_models_endpoint(
+    db: &db::Db,
+    provider_id: i64,
+    enabled: bool,
+) -> crate::shared::error::AppResult<ProviderSummary> {
+    let conn = db.open_connection()?;
+    let cli_key: String = conn
+        .query_row(
+            "SELECT cli_key FROM providers WHERE id = ?1",
+            params![provider_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query provider: {e}"))?
+        .ok_or_else(|| crate::shared::error::AppError::from("DB_NOT_FOUND: provider not found"))?;
+    if cli_key != "claude" {
+        return Err(format!(
+            "SEC_INVALID_INPUT: models endpoint emulation only supports cli_key=claude (provider_id={provider_id})"
+        )
+        .into());
+    }
+
+    conn.execute(
+        "UPDATE providers SET emulate_models_endpoint = ?1, updated_at = ?2 WHERE id = ?3",
+        params![enabled_to_int(enabled), now_unix_seconds(), provider_id],
+    )
+    .map_err(|e| db_err!("failed to update provider: {e}"))?;
+    get_by_id(&conn, provider_id)
+}
+
+/// Sets (or clears with `None`) the extended thinking budget cap of a claude provider.
+pub fn set_thinking_budget_cap(
+    db: &db::Db,
+    provider_id: i64,
+    thinking_budget_cap: Option<u32>,
+) -> crate::shared::error::AppResult<ProviderSummary> {
+    if let Some(cap) = thinking_budget_cap {
+        if cap != 0 && !(THINKING_BUDGET_CAP_MIN..=THINKING_BUDGET_CAP_MAX).contains(&cap) {
+            return Err(format!(
+                "SEC_INVALID_INPUT: thinking_budget_cap must be 0 (strip) or {THINKING_BUDGET_CAP_MIN}..={THINKING_BUDGET_CAP_MAX}"
+            )
+            .into());
+        }
+    }
+
+    let conn = db.open_connection()?;
+    let cli_key: String = conn
+        .query_row(
+            "SELECT cli_key FROM providers WHERE id = ?1",
+            params![provider_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query provider: {e}"))?
+        .ok_or_else(|| crate::shared::error::AppError::from("DB_NOT_FOUND: provider not found"))?;
+    if cli_key != "claude" {
+        return Err(format!(
+            "SEC_INVALID_INPUT: thinking budget cap only supports cli_key=claude (provider_id={provider_id})"
+        )
+        .into());
+    }
+
+    conn.execute(
+        "UPDATE providers SET thinking_budget_cap = ?1, updated_at = ?2 WHERE id = ?3",
+        params![thinking_budget_cap, now_unix_seconds(), provider_id],
+    )
+    .map_err(|e| db_err!("failed to update provider: {e}"))?;
+    get_by_id(&conn, provider_id)
+}
+
+/// Replaces the refusal patterns of a provider; an empty list turns refusal failover off.
+pub fn set_refusal_patterns(
+    db: &db::Db,
+    provider_id: i64,
+    refusal_patterns: Vec<String>,
+) -> crate::shared::error::AppResult<ProviderSummary> {
+    let refusal_patterns = normalize_tags(refusal_patterns);
+    if refusal_patterns.len() > MAX_REFUSAL_PATTERNS {
+        return Err(format!(
+            "SEC_INVALID_INPUT: at most {MAX_REFUSAL_PATTERNS} refusal patterns are allowed"
+        )
+        .into());
+    }
+    if let Some(pattern) = refusal_patterns
+        .iter()
+        .find(|p| p.chars().count() > MAX_REFUSAL_PATTERN_CHARS)
+    {
+        return Err(format!(
+            "SEC_INVALID_INPUT: refusal pattern must be at most {MAX_REFUSAL_PATTERN_CHARS} characters: {pattern}"
+        )
+        .into());
+    }
+    let refusal_patterns_json = serde_json::to_string(&refusal_patterns)
+        .map_err(|e| format!("SYSTEM_ERROR: failed to serialize refusal patterns: {e}"))?;
+
+    let conn = db.open_connection()?;
+    let changed = conn
+        .execute(
+            "UPDATE providers SET refusal_patterns_json = ?1, updated_at = ?2 WHERE id = ?3",
+            params![refusal_patterns_json, now_unix_seconds(), provider_id],
+        )
+        .map_err(|e| db_err!("failed to update provider: {e}"))?;
+    if changed == 0 {
+        return Err("DB_NOT_FOUND: provider not found".into());
+    }
+    get_by_id(&conn, provider_id)
+}
+
+/// Sets the `anthropic-version` override and beta flags of a claude provider.
+pub fn set_anthropic_headers(
+    db: &db::Db,
+    provider_id: i64,
+    anthropic_version: Option<String>,
+    anthropic_betas: Vec<String>,
+    anthropic_beta_strip: bool,
+) -> crate::shared::error::AppResult<ProviderSummary> {
+    let anthropic_version = anthropic_version
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    if let Some(version) = anthropic_version.as_deref() {
+        if !is_valid_anthropic_version(version) {
+            return Err(format!(
+                "SEC_INVALID_INPUT: anthropic_version must look like 2023-06-01: {version}"
+            )
+            .into());
+        }
+    }
+    let anthropic_betas = normalize_tags(anthropic_betas);
+    if anthropic_betas.len() > MAX_ANTHROPIC_BETAS {
+        return Err(format!(
+            "SEC_INVALID_INPUT: at most {MAX_ANTHROPIC_BETAS} anthropic betas are allowed"
+        )
+        .into());
+    }
+    if let Some(beta) = anthropic_betas.iter().find(|b| !is_valid_anthropic_beta(b)) {
+        return Err(format!("SEC_INVALID_INPUT: invalid anthropic beta flag: {beta}").into());
+    }
+    let anthropic_betas_json = serde_json::to_string(&anthropic_betas)
+        .map_err(|e| format!("SYSTEM_ERROR: failed to serialize anthropic betas: {e}"))?;
+
+    let conn = db.open_connection()?;
+    let cli_key: String = conn
+        .query_row(
+            "SELECT cli_key FROM providers WHERE id = ?1",
+            params![provider_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query provider: {e}"))?
+        .ok_or_else(|| crate::shared::error::AppError::from("DB_NOT_FOUND: provider not found"))?;
+    if cli_key != "claude" {
+        return Err(format!(
+            "SEC_INVALID_INPUT: anthropic headers only support cli_key=claude (provider_id={provider_id})"
+        )
+        .into());
+    }
+
+    conn.execute(
+        r#"
+UPDATE providers
+SET
+  anthropic_version = ?1,
+  anthropic_betas_json = ?2,
+  anthropic_beta_strip = ?3,
+  updated_at = ?4
+WHERE id = ?5
+"#,
+        params![
+            anthropic_version,
+            anthropic_betas_json,
+            enabled_to_int(anthropic_beta_strip),
+            now_unix_seconds(),
+            provider_id
+        ],
+    )
+    .map_err(|e| db_err!("failed to update provider: {e}"))?;
+    get_by_id(&conn, provider_id)
+}
+
+/// Sets (or clears with `None`) the provider's circuit/quarantine webhook.
+pub fn set_webhook_url(
+    db: &db::Db,
+    provider_id: i64,
+    webhook_url: Option<String>,
+) -> crate::shared::error::AppResult<ProviderSummary> {
+    let webhook_url = webhook_url
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    if let Some(url) = webhook_url.as_deref() {
+        if url.len() > MAX_WEBHOOK_URL_LEN {
+            return Err(format!(
+                "SEC_INVALID_INPUT: webhook_url must be at most {MAX_WEBHOOK_URL_LEN} characters"
+            )
+            .into());
+        }
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|e| format!("SEC_INVALID_INPUT: invalid webhook_url: {e}"))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err("SEC_INVALID_INPUT: webhook_url must be http(s)".into());
+        }
+    }
+
+    let conn = db.open_connection()?;
+    let changed = conn
+        .execute(
+            "UPDATE providers SET webhook_url = ?1, updated_at = ?2 WHERE id = ?3",
+            params![webhook_url, now_unix_seconds(), provider_id],
+        )
+        .map_err(|e| db_err!("failed to update provider: {e}"))?;
+    if changed == 0 {
+        return Err("DB_NOT_FOUND: provider not found".into());
+    }
+    get_by_id(&conn, provider_id)
+}
+
+pub(crate) fn webhook_url(
+    db: &db::Db,
+    provider_id: i64,
+) -> crate::shared::error::AppResult<Option<String>> {
+    let conn = db.open_connection()?;
+    let url: Option<Option<String>> = conn
+        .query_row(
+            "SELECT webhook_url FROM providers WHERE id = ?1",
+            params![provider_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query provider webhook_url: {e}"))?;
+    Ok(url.flatten())
+}
+
+/// Sets (or clears with `None`) the provider's hard calendar-month budget.
+pub fn set_monthly_budget(
+    db: &db::Db,
+    provider_id: i64,
+    monthly_budget_usd: Option<f64>,
+) -> crate::shared::error::AppResult<ProviderSummary> {
+    let monthly_budget_usd = validate_limit_usd("monthly_budget_usd", monthly_budget_usd)?;
+    let conn = db.open_connection()?;
+    let changed = conn
+        .execute(
+            "UPDATE providers SET monthly_budget_usd = ?1, updated_at = ?2 WHERE id = ?3",
+            params![monthly_budget_usd, now_unix_seconds(), provider_id],
+        )
+        .map_err(|e| db_err!("failed to update provider monthly budget: {e}"))?;
+    if changed == 0 {
+        return Err("DB_NOT_FOUND: provider not found".into());
+    }
+    get_by_id(&conn, provider_id)
+}
+
 pub fn delete(db: &db::Db, provider_id: i64) -> crate::shared::error::AppResult<()> {
     let conn = db.open_connection()?;
     let changed = conn
@@ -1351,6 +2039,137 @@ pub fn delete(db: &db::Db, provider_id: i64) -> crate::shared::error::AppResult<
     Ok(())
 }
 
+fn archived_at(
+    conn: &Connection,
+    provider_id: i64,
+) -> crate::shared::error::AppResult<Option<i64>> {
+    conn.query_row(
+        "SELECT archived_at FROM providers WHERE id = ?1",
+        params![provider_id],
+        |row| row.get::<_, Option<i64>>(0),
+    )
+    .optional()
+    .map_err(|e| db_err!("failed to query provider: {e}"))?
+    .ok_or_else(|| "DB_NOT_FOUND: provider not found".into())
+}
+
+/// First phase of deleting a provider: hides it from lists and routing (disabled, unflagged as
+/// last resort, dropped from sort modes) while its row stays for stats joins.
+pub fn archive(db: &db::Db, provider_id: i64) -> crate::shared::error::AppResult<ProviderSummary> {
+    let mut conn = db.open_connection()?;
+    if archived_at(&conn, provider_id)?.is_some() {
+        return get_by_id(&conn, provider_id);
+    }
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| db_err!("failed to start transaction: {e}"))?;
+    let now = now_unix_seconds();
+    tx.execute(
+        "UPDATE providers SET archived_at = ?1, enabled = 0, last_resort = 0, updated_at = ?1 WHERE id = ?2",
+        params![now, provider_id],
+    )
+    .map_err(|e| db_err!("failed to archive provider: {e}"))?;
+    tx.execute(
+        "DELETE FROM sort_mode_providers WHERE provider_id = ?1",
+        params![provider_id],
+    )
+    .map_err(|e| db_err!("failed to remove provider from sort modes: {e}"))?;
+    tx.commit().map_err(|e| db_err!("failed to commit: {e}"))?;
+
+    get_by_id(&conn, provider_id)
+}
+
+/// Brings an archived provider back; it stays disabled until enabled explicitly.
+pub fn unarchive(
+    db: &db::Db,
+    provider_id: i64,
+) -> crate::shared::error::AppResult<ProviderSummary> {
+    let conn = db.open_connection()?;
+    let changed = conn
+        .execute(
+            "UPDATE providers SET archived_at = NULL, updated_at = ?1 WHERE id = ?2",
+            params![now_unix_seconds(), provider_id],
+        )
+        .map_err(|e| db_err!("failed to restore provider: {e}"))?;
+    if changed == 0 {
+        return Err("DB_NOT_FOUND: provider not found".into());
+    }
+    get_by_id(&conn, provider_id)
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ProviderUsageAudit {
+    pub provider_id: i64,
+    pub cli_key: String,
+    pub name: String,
+    pub archived_at: Option<i64>,
+    /// Request logs whose final provider is this one; purging keeps them but loses the name.
+    pub request_log_count: i64,
+    pub last_used_at: Option<i64>,
+    pub total_cost_usd: f64,
+    /// Grace allowance granted on this month's budget.
+    pub current_month_grace_usd: f64,
+    /// A granted budget grace is still in effect this month; purge is refused until it lapses.
+    pub budget_hold: bool,
+}
+
+pub fn usage_audit(
+    db: &db::Db,
+    provider_id: i64,
+) -> crate::shared::error::AppResult<ProviderUsageAudit> {
+    let conn = db.open_connection()?;
+    let provider = get_by_id(&conn, provider_id)?;
+    let (request_log_count, last_used_at, cost_femto) = conn
+        .query_row(
+            r#"
+SELECT COUNT(*), MAX(created_at), COALESCE(SUM(cost_usd_femto), 0)
+FROM request_logs
+WHERE final_provider_id = ?1
+"#,
+            params![provider_id],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, Option<i64>>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            },
+        )
+        .map_err(|e| db_err!("failed to audit provider usage: {e}"))?;
+    let month = provider_limit_usage::month_key(&conn, now_unix_seconds())?;
+    let current_month_grace_usd =
+        provider_limit_usage::month_grace_usd(&conn, provider_id, &month)?;
+
+    Ok(ProviderUsageAudit {
+        provider_id,
+        cli_key: provider.cli_key,
+        name: provider.name,
+        archived_at: provider.archived_at,
+        request_log_count,
+        last_used_at,
+        total_cost_usd: cost_femto as f64 / 1_000_000_000_000_000.0,
+        current_month_grace_usd,
+        budget_hold: current_month_grace_usd > 0.0,
+    })
+}
+
+/// Second phase of deleting a provider: removes an archived provider for good.
+pub fn purge(db: &db::Db, provider_id: i64) -> crate::shared::error::AppResult<()> {
+    let audit = usage_audit(db, provider_id)?;
+    if audit.archived_at.is_none() {
+        return Err("SEC_INVALID_INPUT: only archived providers can be purged".into());
+    }
+    if audit.budget_hold {
+        return Err(format!(
+            "SEC_INVALID_INPUT: provider has a budget hold this month (grace {:.2} USD)",
+            audit.current_month_grace_usd
+        )
+        .into());
+    }
+    delete(db, provider_id)
+}
+
 pub fn reorder(
     db: &db::Db,
     cli_key: &str,
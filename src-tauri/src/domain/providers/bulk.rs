@@ -0,0 +1,344 @@
+//! Usage: Atomic multi-provider edits (enable state, tags, cost multiplier, sort mode membership).
+
+use super::{normalize_tags, tags_from_json, validate_cli_key};
+use crate::db;
+use crate::shared::error::db_err;
+use crate::shared::sqlite::enabled_to_int;
+use crate::shared::time::now_unix_seconds;
+use crate::sort_modes;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+const MAX_BULK_PROVIDERS: usize = 500;
+const MAX_COST_MULTIPLIER: f64 = 1000.0;
+
+/// Every `Some` field is applied to each listed provider; `None` leaves that field untouched.
+#[derive(Debug, Clone, Default, Deserialize, specta::Type)]
+pub struct ProviderBulkUpdate {
+    pub provider_ids: Vec<i64>,
+    pub enabled: Option<bool>,
+    /// Replaces the tag list.
+    pub tags: Option<Vec<String>>,
+    /// Sets the multiplier outright; mutually exclusive with `cost_multiplier_scale`.
+    pub cost_multiplier: Option<f64>,
+    /// Multiplies each provider's current multiplier.
+    pub cost_multiplier_scale: Option<f64>,
+    /// Removes the providers from this sort mode (no-op for providers not in it).
+    pub remove_from_sort_mode_id: Option<i64>,
+    /// Appends the providers to the end of this sort mode (no-op for providers already in it).
+    pub add_to_sort_mode_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ProviderBulkChange {
+    pub field: String,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ProviderBulkOutcome {
+    pub provider_id: i64,
+    pub name: Option<String>,
+    pub ok: bool,
+    pub error: Option<String>,
+    pub changes: Vec<ProviderBulkChange>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ProviderBulkUpdateResult {
+    pub dry_run: bool,
+    /// False when `dry_run` is set or any provider failed validation (nothing is written then).
+    pub applied: bool,
+    pub outcomes: Vec<ProviderBulkOutcome>,
+}
+
+struct BulkCurrent {
+    name: String,
+    cli_key: String,
+    enabled: bool,
+    tags: Vec<String>,
+    cost_multiplier: f64,
+}
+
+fn change(field: &str, from: impl ToString, to: impl ToString) -> ProviderBulkChange {
+    ProviderBulkChange {
+        field: field.to_string(),
+        from: from.to_string(),
+        to: to.to_string(),
+    }
+}
+
+fn validate_request(
+    conn: &Connection,
+    input: &ProviderBulkUpdate,
+) -> crate::shared::error::AppResult<()> {
+    if input.provider_ids.is_empty() {
+        return Err("SEC_INVALID_INPUT: provider_ids is required".into());
+    }
+    if input.provider_ids.len() > MAX_BULK_PROVIDERS {
+        return Err(
+            format!("SEC_INVALID_INPUT: too many provider_ids (max {MAX_BULK_PROVIDERS})").into(),
+        );
+    }
+    let mut seen = HashSet::new();
+    for id in &input.provider_ids {
+        if !seen.insert(*id) {
+            return Err(format!("SEC_INVALID_INPUT: duplicate provider_id={id}").into());
+        }
+    }
+
+    if input.cost_multiplier.is_some() && input.cost_multiplier_scale.is_some() {
+        return Err(
+            "SEC_INVALID_INPUT: cost_multiplier and cost_multiplier_scale are mutually exclusive"
+                .into(),
+        );
+    }
+    if let Some(scale) = input.cost_multiplier_scale {
+        if !scale.is_finite() || scale < 0.0 {
+            return Err("SEC_INVALID_INPUT: cost_multiplier_scale must be >= 0".into());
+        }
+    }
+
+    if let (Some(from), Some(to)) = (input.remove_from_sort_mode_id, input.add_to_sort_mode_id) {
+        if from == to {
+            return Err("SEC_INVALID_INPUT: source and target sort mode are the same".into());
+        }
+    }
+    for mode_id in [input.remove_from_sort_mode_id, input.add_to_sort_mode_id]
+        .into_iter()
+        .flatten()
+    {
+        sort_modes::ensure_mode_exists(conn, mode_id)?;
+    }
+
+    let has_change = input.enabled.is_some()
+        || input.tags.is_some()
+        || input.cost_multiplier.is_some()
+        || input.cost_multiplier_scale.is_some()
+        || input.remove_from_sort_mode_id.is_some()
+        || input.add_to_sort_mode_id.is_some();
+    if !has_change {
+        return Err("SEC_INVALID_INPUT: no changes requested".into());
+    }
+    Ok(())
+}
+
+fn read_current(
+    conn: &Connection,
+    provider_id: i64,
+) -> crate::shared::error::AppResult<Option<BulkCurrent>> {
+    conn.query_row(
+        "SELECT name, cli_key, enabled, tags_json, cost_multiplier FROM providers WHERE id = ?1",
+        params![provider_id],
+        |row| {
+            Ok(BulkCurrent {
+                name: row.get(0)?,
+                cli_key: row.get(1)?,
+                enabled: row.get::<_, i64>(2)? != 0,
+                tags: tags_from_json(&row.get::<_, String>(3)?),
+                cost_multiplier: row.get(4)?,
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| db_err!("failed to query provider: {e}"))
+}
+
+fn in_sort_mode(
+    conn: &Connection,
+    mode_id: i64,
+    cli_key: &str,
+    provider_id: i64,
+) -> crate::shared::error::AppResult<bool> {
+    conn.query_row(
+        "SELECT 1 FROM sort_mode_providers WHERE mode_id = ?1 AND cli_key = ?2 AND provider_id = ?3",
+        params![mode_id, cli_key, provider_id],
+        |_| Ok(()),
+    )
+    .optional()
+    .map(|v| v.is_some())
+    .map_err(|e| db_err!("failed to query sort_mode_providers: {e}"))
+}
+
+/// Validates one provider and, unless `apply` is false, writes its changes.
+fn process_provider(
+    conn: &Connection,
+    input: &ProviderBulkUpdate,
+    provider_id: i64,
+    apply: bool,
+    now: i64,
+) -> crate::shared::error::AppResult<ProviderBulkOutcome> {
+    let Some(current) = read_current(conn, provider_id)? else {
+        return Ok(ProviderBulkOutcome {
+            provider_id,
+            name: None,
+            ok: false,
+            error: Some("DB_NOT_FOUND: provider not found".to_string()),
+            changes: Vec::new(),
+        });
+    };
+    let mut outcome = ProviderBulkOutcome {
+        provider_id,
+        name: Some(current.name.clone()),
+        ok: true,
+        error: None,
+        changes: Vec::new(),
+    };
+    if validate_cli_key(&current.cli_key).is_err() {
+        outcome.ok = false;
+        outcome.error = Some(format!(
+            "SEC_INVALID_INPUT: unknown cli_key={}",
+            current.cli_key
+        ));
+        return Ok(outcome);
+    }
+
+    let next_cost_multiplier = match (input.cost_multiplier, input.cost_multiplier_scale) {
+        (Some(value), _) => value,
+        (None, Some(scale)) => current.cost_multiplier * scale,
+        (None, None) => current.cost_multiplier,
+    };
+    if !next_cost_multiplier.is_finite()
+        || !(0.0..=MAX_COST_MULTIPLIER).contains(&next_cost_multiplier)
+    {
+        outcome.ok = false;
+        outcome.error = Some(format!(
+            "SEC_INVALID_INPUT: cost_multiplier must be within [0, {MAX_COST_MULTIPLIER}] (got {next_cost_multiplier})"
+        ));
+        return Ok(outcome);
+    }
+
+    let next_enabled = input.enabled.unwrap_or(current.enabled);
+    if next_enabled != current.enabled {
+        outcome
+            .changes
+            .push(change("enabled", current.enabled, next_enabled));
+    }
+    let next_tags = input.tags.clone().map(normalize_tags);
+    if let Some(tags) = next_tags.as_ref().filter(|tags| **tags != current.tags) {
+        outcome
+            .changes
+            .push(change("tags", current.tags.join(", "), tags.join(", ")));
+    }
+    if next_cost_multiplier != current.cost_multiplier {
+        outcome.changes.push(change(
+            "cost_multiplier",
+            current.cost_multiplier,
+            next_cost_multiplier,
+        ));
+    }
+
+    let remove_from = match input.remove_from_sort_mode_id {
+        Some(mode_id) if in_sort_mode(conn, mode_id, &current.cli_key, provider_id)? => {
+            outcome
+                .changes
+                .push(change("sort_mode", mode_id, "removed"));
+            Some(mode_id)
+        }
+        _ => None,
+    };
+    let add_to = match input.add_to_sort_mode_id {
+        Some(mode_id) if !in_sort_mode(conn, mode_id, &current.cli_key, provider_id)? => {
+            outcome.changes.push(change("sort_mode", "added", mode_id));
+            Some(mode_id)
+        }
+        _ => None,
+    };
+
+    if !apply || outcome.changes.is_empty() {
+        return Ok(outcome);
+    }
+
+    let tags_json = match next_tags {
+        Some(tags) => Some(serde_json::to_string(&tags).map_err(|e| format!("SYSTEM_ERROR: {e}"))?),
+        None => None,
+    };
+    conn.execute(
+        r#"
+UPDATE providers
+SET
+  enabled = ?1,
+  tags_json = COALESCE(?2, tags_json),
+  cost_multiplier = ?3,
+  updated_at = ?4
+WHERE id = ?5
+"#,
+        params![
+            enabled_to_int(next_enabled),
+            tags_json,
+            next_cost_multiplier,
+            now,
+            provider_id
+        ],
+    )
+    .map_err(|e| db_err!("failed to update provider {provider_id}: {e}"))?;
+
+    if let Some(mode_id) = remove_from {
+        conn.execute(
+            "DELETE FROM sort_mode_providers WHERE mode_id = ?1 AND cli_key = ?2 AND provider_id = ?3",
+            params![mode_id, current.cli_key, provider_id],
+        )
+        .map_err(|e| db_err!("failed to remove provider {provider_id} from sort mode: {e}"))?;
+    }
+    if let Some(mode_id) = add_to {
+        conn.execute(
+            r#"
+INSERT INTO sort_mode_providers(
+  mode_id,
+  cli_key,
+  provider_id,
+  sort_order,
+  enabled,
+  created_at,
+  updated_at
+)
+SELECT ?1, ?2, ?3, COALESCE(MAX(sort_order) + 1, 0), 1, ?4, ?4
+FROM sort_mode_providers
+WHERE mode_id = ?1 AND cli_key = ?2
+"#,
+            params![mode_id, current.cli_key, provider_id, now],
+        )
+        .map_err(|e| db_err!("failed to add provider {provider_id} to sort mode: {e}"))?;
+    }
+
+    Ok(outcome)
+}
+
+/// Applies `input` to every listed provider in one transaction. Any per-provider failure rolls
+/// the whole batch back; `dry_run` only validates and reports what would change.
+pub fn bulk_update(
+    db: &db::Db,
+    input: ProviderBulkUpdate,
+    dry_run: bool,
+) -> crate::shared::error::AppResult<ProviderBulkUpdateResult> {
+    let mut conn = db.open_connection()?;
+    validate_request(&conn, &input)?;
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| db_err!("failed to start transaction: {e}"))?;
+    let now = now_unix_seconds();
+
+    let mut outcomes = Vec::with_capacity(input.provider_ids.len());
+    let mut all_ok = true;
+    for provider_id in &input.provider_ids {
+        // Once one provider fails the batch can't commit; keep validating the rest for the report.
+        let outcome = process_provider(&tx, &input, *provider_id, !dry_run && all_ok, now)?;
+        all_ok &= outcome.ok;
+        outcomes.push(outcome);
+    }
+
+    let applied = !dry_run && all_ok;
+    if applied {
+        tx.commit()
+            .map_err(|e| db_err!("failed to commit transaction: {e}"))?;
+    }
+
+    Ok(ProviderBulkUpdateResult {
+        dry_run,
+        applied,
+        outcomes,
+    })
+}
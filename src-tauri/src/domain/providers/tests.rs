@@ -138,6 +138,12 @@ fn base_url_mode_parse_ping() {
     assert_eq!(mode.as_str(), "ping");
 }
 
+#[test]
+fn base_url_mode_parse_race() {
+    let mode = ProviderBaseUrlMode::parse("race").unwrap();
+    assert_eq!(mode.as_str(), "race");
+}
+
 #[test]
 fn base_url_mode_parse_invalid() {
     assert!(ProviderBaseUrlMode::parse("random").is_none());
@@ -389,6 +395,7 @@ fn create_oauth_provider_for_cas_test(db: &crate::db::Db, name: &str) -> i64 {
             limit_total_usd: None,
             tags: None,
             note: None,
+            provider_kind: None,
         },
     )
     .expect("create oauth provider")
@@ -513,3 +520,17 @@ fn update_oauth_tokens_cas_allows_initial_null_then_blocks_repeat_null() {
     assert_eq!(after.oauth_access_token, "null_first_access");
     assert!(after.oauth_last_refreshed_at.is_some());
 }
+
+#[test]
+fn anthropic_version_and_beta_validation() {
+    assert!(is_valid_anthropic_version("2023-06-01"));
+    assert!(!is_valid_anthropic_version("2023-6-01"));
+    assert!(!is_valid_anthropic_version("latest"));
+
+    assert!(is_valid_anthropic_beta("interleaved-thinking-2025-05-14"));
+    assert!(!is_valid_anthropic_beta("a,b"));
+    assert!(!is_valid_anthropic_beta(""));
+    assert!(!is_valid_anthropic_beta(
+        &"x".repeat(MAX_ANTHROPIC_BETA_CHARS + 1)
+    ));
+}
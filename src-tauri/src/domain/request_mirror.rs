@@ -0,0 +1,257 @@
+//! Usage: Persistence for mirrored (shadow) gateway requests.
+//!
+//! Mirrored attempts live in their own table so they never count towards request logs,
+//! usage stats, provider limits or cost alerts.
+
+use crate::db;
+use crate::shared::error::db_err;
+use rusqlite::params;
+use serde::Serialize;
+
+const KEEP_MAX_ROWS: i64 = 5000;
+
+#[derive(Debug, Clone)]
+pub struct RequestMirrorLogInsert {
+    pub trace_id: String,
+    pub cli_key: String,
+    pub provider_id: i64,
+    pub provider_name: String,
+    pub method: String,
+    pub path: String,
+    pub requested_model: Option<String>,
+    pub status: Option<i64>,
+    pub error: Option<String>,
+    pub ttfb_ms: Option<i64>,
+    pub duration_ms: i64,
+    pub input_tokens: Option<i64>,
+    pub output_tokens: Option<i64>,
+    pub cache_read_input_tokens: Option<i64>,
+    pub cache_creation_input_tokens: Option<i64>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct RequestMirrorLogRow {
+    pub id: i64,
+    pub trace_id: String,
+    pub cli_key: String,
+    pub provider_id: i64,
+    pub provider_name: String,
+    pub method: String,
+    pub path: String,
+    pub requested_model: Option<String>,
+    pub status: Option<i64>,
+    pub error: Option<String>,
+    pub ttfb_ms: Option<i64>,
+    pub duration_ms: i64,
+    pub input_tokens: Option<i64>,
+    pub output_tokens: Option<i64>,
+    pub cache_read_input_tokens: Option<i64>,
+    pub cache_creation_input_tokens: Option<i64>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct RequestMirrorProviderStats {
+    pub provider_id: i64,
+    pub provider_name: String,
+    pub cli_key: String,
+    pub requests_total: i64,
+    pub requests_success: i64,
+    pub avg_ttfb_ms: Option<f64>,
+    pub avg_duration_ms: Option<f64>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+}
+
+pub fn insert_and_prune(
+    db: &db::Db,
+    item: &RequestMirrorLogInsert,
+) -> crate::shared::error::AppResult<()> {
+    let mut conn = db.open_connection()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| db_err!("failed to start transaction: {e}"))?;
+
+    tx.execute(
+        r#"
+INSERT INTO request_mirror_logs(
+  trace_id,
+  cli_key,
+  provider_id,
+  provider_name,
+  method,
+  path,
+  requested_model,
+  status,
+  error,
+  ttfb_ms,
+  duration_ms,
+  input_tokens,
+  output_tokens,
+  cache_read_input_tokens,
+  cache_creation_input_tokens,
+  created_at
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
+"#,
+        params![
+            item.trace_id,
+            item.cli_key,
+            item.provider_id,
+            item.provider_name,
+            item.method,
+            item.path,
+            item.requested_model,
+            item.status,
+            item.error,
+            item.ttfb_ms,
+            item.duration_ms,
+            item.input_tokens,
+            item.output_tokens,
+            item.cache_read_input_tokens,
+            item.cache_creation_input_tokens,
+            item.created_at,
+        ],
+    )
+    .map_err(|e| db_err!("failed to insert request_mirror_log: {e}"))?;
+
+    tx.execute(
+        r#"
+DELETE FROM request_mirror_logs
+WHERE id NOT IN (
+  SELECT id
+  FROM request_mirror_logs
+  ORDER BY id DESC
+  LIMIT ?1
+)
+"#,
+        params![KEEP_MAX_ROWS],
+    )
+    .map_err(|e| db_err!("failed to prune request_mirror_logs: {e}"))?;
+
+    tx.commit()
+        .map_err(|e| db_err!("failed to commit transaction: {e}"))?;
+    Ok(())
+}
+
+pub fn list_recent(
+    db: &db::Db,
+    limit: usize,
+) -> crate::shared::error::AppResult<Vec<RequestMirrorLogRow>> {
+    let conn = db.open_connection()?;
+
+    let mut stmt = conn
+        .prepare_cached(
+            r#"
+    SELECT
+      id,
+      trace_id,
+      cli_key,
+      provider_id,
+      provider_name,
+      method,
+      path,
+      requested_model,
+      status,
+      error,
+      ttfb_ms,
+      duration_ms,
+      input_tokens,
+      output_tokens,
+      cache_read_input_tokens,
+      cache_creation_input_tokens,
+      created_at
+    FROM request_mirror_logs
+    ORDER BY id DESC
+    LIMIT ?1
+    "#,
+        )
+        .map_err(|e| db_err!("failed to prepare request_mirror_logs query: {e}"))?;
+
+    let rows = stmt
+        .query_map(params![limit as i64], |row| {
+            Ok(RequestMirrorLogRow {
+                id: row.get("id")?,
+                trace_id: row.get("trace_id")?,
+                cli_key: row.get("cli_key")?,
+                provider_id: row.get("provider_id")?,
+                provider_name: row.get("provider_name")?,
+                method: row.get("method")?,
+                path: row.get("path")?,
+                requested_model: row.get("requested_model")?,
+                status: row.get("status")?,
+                error: row.get("error")?,
+                ttfb_ms: row.get("ttfb_ms")?,
+                duration_ms: row.get("duration_ms")?,
+                input_tokens: row.get("input_tokens")?,
+                output_tokens: row.get("output_tokens")?,
+                cache_read_input_tokens: row.get("cache_read_input_tokens")?,
+                cache_creation_input_tokens: row.get("cache_creation_input_tokens")?,
+                created_at: row.get("created_at")?,
+            })
+        })
+        .map_err(|e| db_err!("failed to list request_mirror_logs: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| db_err!("failed to read request_mirror_log row: {e}"))?);
+    }
+    Ok(items)
+}
+
+pub fn provider_stats(
+    db: &db::Db,
+    since_unix: Option<i64>,
+) -> crate::shared::error::AppResult<Vec<RequestMirrorProviderStats>> {
+    let conn = db.open_connection()?;
+
+    let mut stmt = conn
+        .prepare_cached(
+            r#"
+    SELECT
+      provider_id,
+      MAX(provider_name) AS provider_name,
+      MAX(cli_key) AS cli_key,
+      COUNT(*) AS requests_total,
+      SUM(CASE WHEN status >= 200 AND status < 300 AND error IS NULL THEN 1 ELSE 0 END)
+        AS requests_success,
+      AVG(ttfb_ms) AS avg_ttfb_ms,
+      AVG(duration_ms) AS avg_duration_ms,
+      COALESCE(SUM(input_tokens), 0) AS input_tokens,
+      COALESCE(SUM(output_tokens), 0) AS output_tokens
+    FROM request_mirror_logs
+    WHERE (?1 IS NULL OR created_at >= ?1)
+    GROUP BY provider_id
+    ORDER BY requests_total DESC, provider_id ASC
+    "#,
+        )
+        .map_err(|e| db_err!("failed to prepare request_mirror stats query: {e}"))?;
+
+    let rows = stmt
+        .query_map(params![since_unix], |row| {
+            Ok(RequestMirrorProviderStats {
+                provider_id: row.get("provider_id")?,
+                provider_name: row.get("provider_name")?,
+                cli_key: row.get("cli_key")?,
+                requests_total: row.get("requests_total")?,
+                requests_success: row.get("requests_success")?,
+                avg_ttfb_ms: row.get("avg_ttfb_ms")?,
+                avg_duration_ms: row.get("avg_duration_ms")?,
+                input_tokens: row.get("input_tokens")?,
+                output_tokens: row.get("output_tokens")?,
+            })
+        })
+        .map_err(|e| db_err!("failed to query request_mirror stats: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| db_err!("failed to read request_mirror stats row: {e}"))?);
+    }
+    Ok(items)
+}
+
+pub fn clear(db: &db::Db) -> crate::shared::error::AppResult<usize> {
+    let conn = db.open_connection()?;
+    conn.execute("DELETE FROM request_mirror_logs", [])
+        .map_err(|e| db_err!("failed to clear request_mirror_logs: {e}"))
+}
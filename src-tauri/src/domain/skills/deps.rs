@@ -0,0 +1,289 @@
+//! Usage: Resolve SKILL.md dependency manifests (skills, MCP servers, binaries on PATH).
+
+use super::installed::get_skill_by_id;
+use super::paths::ssot_skills_root;
+use super::repo_auth::auth_for_git_url;
+use super::repo_cache::ensure_repo_cache;
+use super::skill_md::{
+    find_skill_md_files, parse_skill_dependencies, parse_skill_md, SkillDependencies,
+};
+use super::types::{
+    AvailableSkillSummary, SkillDependency, SkillDependencyKind, SkillDependencyReport,
+    SkillDependencyStatus,
+};
+use super::util::validate_relative_subdir;
+use crate::shared::error::db_err;
+use crate::shared::text::normalize_name;
+use crate::{cli_manager, db, workspaces};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+
+/// Source repository searched for install candidates of missing skill dependencies.
+struct CandidateRepo<'a> {
+    dir: &'a Path,
+    git_url: &'a str,
+    branch: &'a str,
+}
+
+fn status_of(found: Option<(i64, bool)>) -> (SkillDependencyStatus, Option<String>) {
+    match found {
+        Some((id, true)) => (SkillDependencyStatus::Satisfied, Some(id.to_string())),
+        Some((id, false)) => (SkillDependencyStatus::Disabled, Some(id.to_string())),
+        None => (SkillDependencyStatus::Missing, None),
+    }
+}
+
+fn lookup_skill(
+    conn: &Connection,
+    workspace_id: i64,
+    name: &str,
+) -> crate::shared::error::AppResult<Option<(i64, bool)>> {
+    conn.query_row(
+        r#"
+SELECT
+  s.id,
+  CASE WHEN e.skill_id IS NULL THEN 0 ELSE 1 END AS enabled
+FROM skills s
+LEFT JOIN workspace_skill_enabled e
+  ON e.workspace_id = ?1 AND e.skill_id = s.id
+WHERE s.normalized_name = ?2 OR s.skill_key = ?3
+ORDER BY enabled DESC, s.id ASC
+LIMIT 1
+"#,
+        params![workspace_id, normalize_name(name), name],
+        |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)? != 0)),
+    )
+    .optional()
+    .map_err(|e| db_err!("failed to query skill dependency: {e}"))
+}
+
+fn lookup_mcp_server(
+    conn: &Connection,
+    workspace_id: i64,
+    name: &str,
+) -> crate::shared::error::AppResult<Option<(i64, bool)>> {
+    conn.query_row(
+        r#"
+SELECT
+  s.id,
+  CASE WHEN e.server_id IS NULL THEN 0 ELSE 1 END AS enabled
+FROM mcp_servers s
+LEFT JOIN workspace_mcp_enabled e
+  ON e.workspace_id = ?1 AND e.server_id = s.id
+WHERE s.server_key = ?2 OR lower(s.name) = lower(?2)
+ORDER BY enabled DESC, s.id ASC
+LIMIT 1
+"#,
+        params![workspace_id, name],
+        |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)? != 0)),
+    )
+    .optional()
+    .map_err(|e| db_err!("failed to query mcp server dependency: {e}"))
+}
+
+fn find_install_candidate(repo: &CandidateRepo<'_>, name: &str) -> Option<AvailableSkillSummary> {
+    let target = normalize_name(name);
+    find_skill_md_files(repo.dir)
+        .ok()?
+        .into_iter()
+        .find_map(|skill_md| {
+            let (skill_name, description) = parse_skill_md(&skill_md).ok()?;
+            if normalize_name(&skill_name) != target {
+                return None;
+            }
+            let source_subdir = skill_md
+                .parent()?
+                .strip_prefix(repo.dir)
+                .ok()?
+                .to_string_lossy()
+                .replace('\\', "/")
+                .trim_matches('/')
+                .to_string();
+            (!source_subdir.is_empty()).then(|| AvailableSkillSummary {
+                name: skill_name,
+                description,
+                source_git_url: repo.git_url.to_string(),
+                source_branch: repo.branch.to_string(),
+                source_subdir,
+                installed: false,
+            })
+        })
+}
+
+fn resolve(
+    conn: &Connection,
+    workspace_id: i64,
+    skill_name: &str,
+    deps: &SkillDependencies,
+    repo: Option<&CandidateRepo<'_>>,
+) -> crate::shared::error::AppResult<SkillDependencyReport> {
+    let mut dependencies = Vec::new();
+
+    for name in &deps.skills {
+        let (status, detail) = status_of(lookup_skill(conn, workspace_id, name)?);
+        let install_candidate = match (status, repo) {
+            (SkillDependencyStatus::Missing, Some(repo)) => find_install_candidate(repo, name),
+            _ => None,
+        };
+        dependencies.push(SkillDependency {
+            kind: SkillDependencyKind::Skill,
+            name: name.clone(),
+            status,
+            detail,
+            install_candidate,
+        });
+    }
+
+    for name in &deps.mcp_servers {
+        let (status, detail) = status_of(lookup_mcp_server(conn, workspace_id, name)?);
+        dependencies.push(SkillDependency {
+            kind: SkillDependencyKind::McpServer,
+            name: name.clone(),
+            status,
+            detail,
+            install_candidate: None,
+        });
+    }
+
+    for name in &deps.binaries {
+        let path = cli_manager::find_executable_on_path(name);
+        dependencies.push(SkillDependency {
+            kind: SkillDependencyKind::Binary,
+            name: name.clone(),
+            status: if path.is_some() {
+                SkillDependencyStatus::Satisfied
+            } else {
+                SkillDependencyStatus::Missing
+            },
+            detail: path.map(|p| p.to_string_lossy().to_string()),
+            install_candidate: None,
+        });
+    }
+
+    Ok(SkillDependencyReport {
+        skill_name: skill_name.to_string(),
+        satisfied: dependencies
+            .iter()
+            .all(|d| d.status == SkillDependencyStatus::Satisfied),
+        dependencies,
+    })
+}
+
+fn unmet_summary(report: &SkillDependencyReport) -> Option<String> {
+    let unmet: Vec<String> = report
+        .dependencies
+        .iter()
+        .filter(|d| d.status != SkillDependencyStatus::Satisfied)
+        .map(|d| {
+            let kind = match d.kind {
+                SkillDependencyKind::Skill => "skill",
+                SkillDependencyKind::McpServer => "mcp server",
+                SkillDependencyKind::Binary => "binary",
+            };
+            let status = match d.status {
+                SkillDependencyStatus::Disabled => "not enabled",
+                _ => "missing",
+            };
+            format!("{kind} '{}' ({status})", d.name)
+        })
+        .collect();
+    (!unmet.is_empty()).then(|| unmet.join(", "))
+}
+
+/// Blocks enabling `skill_name` in the workspace until every dependency in `skill_md` resolves.
+pub(super) fn ensure_dependencies_met(
+    conn: &Connection,
+    workspace_id: i64,
+    skill_name: &str,
+    skill_md: &Path,
+) -> crate::shared::error::AppResult<()> {
+    if !skill_md.exists() {
+        return Ok(());
+    }
+    let deps = parse_skill_dependencies(skill_md)?;
+    if deps.is_empty() {
+        return Ok(());
+    }
+    let report = resolve(conn, workspace_id, skill_name, &deps, None)?;
+    match unmet_summary(&report) {
+        None => Ok(()),
+        Some(unmet) => {
+            Err(format!("SKILL_DEPENDENCIES_MISSING: '{skill_name}' requires {unmet}").into())
+        }
+    }
+}
+
+/// Dependency report for an installed skill in `workspace_id`.
+pub fn dependencies_check(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    workspace_id: i64,
+    skill_id: i64,
+) -> crate::shared::error::AppResult<SkillDependencyReport> {
+    let conn = db.open_connection()?;
+    let _ = workspaces::get_cli_key_by_id(&conn, workspace_id)?;
+    let skill = get_skill_by_id(&conn, skill_id)?;
+
+    let skill_md = ssot_skills_root(app)?
+        .join(&skill.skill_key)
+        .join("SKILL.md");
+    let deps = if skill_md.exists() {
+        parse_skill_dependencies(&skill_md)?
+    } else {
+        SkillDependencies::default()
+    };
+
+    // Best-effort: install suggestions come from the cached source repo (local imports have none).
+    let repo_dir = if deps.skills.is_empty() || skill.source_git_url.starts_with("local://") {
+        None
+    } else {
+        let auth = auth_for_git_url(&conn, &skill.source_git_url)?;
+        ensure_repo_cache(
+            app,
+            &auth,
+            &skill.source_git_url,
+            &skill.source_branch,
+            false,
+        )
+        .ok()
+    };
+    let repo = repo_dir.as_deref().map(|dir| CandidateRepo {
+        dir,
+        git_url: &skill.source_git_url,
+        branch: &skill.source_branch,
+    });
+
+    resolve(&conn, workspace_id, &skill.name, &deps, repo.as_ref())
+}
+
+/// Dependency report for a repository skill before it is installed.
+pub fn dependencies_preview(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    workspace_id: i64,
+    git_url: &str,
+    branch: &str,
+    source_subdir: &str,
+) -> crate::shared::error::AppResult<SkillDependencyReport> {
+    validate_relative_subdir(source_subdir)?;
+    let conn = db.open_connection()?;
+    let _ = workspaces::get_cli_key_by_id(&conn, workspace_id)?;
+
+    let auth = auth_for_git_url(&conn, git_url)?;
+    let repo_dir = ensure_repo_cache(app, &auth, git_url, branch, false)?;
+    let skill_md = repo_dir.join(source_subdir.trim()).join("SKILL.md");
+    if !skill_md.exists() {
+        return Err("SEC_INVALID_INPUT: SKILL.md not found in source_subdir"
+            .to_string()
+            .into());
+    }
+    let (name, _) = parse_skill_md(&skill_md)?;
+    let deps = parse_skill_dependencies(&skill_md)?;
+
+    let repo = CandidateRepo {
+        dir: &repo_dir,
+        git_url: git_url.trim(),
+        branch: branch.trim(),
+    };
+    resolve(&conn, workspace_id, &name, &deps, Some(&repo))
+}
@@ -1,5 +1,6 @@
 use super::git_url::canonical_git_url_key;
 use super::installed::installed_source_set;
+use super::repo_auth::auth_for_git_url;
 use super::repo_cache::ensure_repo_cache;
 use super::skill_md::{find_skill_md_files, parse_skill_md};
 use super::types::AvailableSkillSummary;
@@ -9,7 +10,7 @@ use crate::shared::text::normalize_name;
 use std::collections::{BTreeMap, HashSet};
 
 pub fn discover_available(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     db: &db::Db,
     refresh: bool,
 ) -> crate::shared::error::AppResult<Vec<AvailableSkillSummary>> {
@@ -100,7 +101,8 @@ pub fn discover_available(
 
     let mut out = Vec::new();
     for (git_url, branch) in repos {
-        let repo_dir = ensure_repo_cache(app, &git_url, &branch, refresh)?;
+        let auth = auth_for_git_url(&conn, &git_url)?;
+        let repo_dir = ensure_repo_cache(app, &auth, &git_url, &branch, refresh)?;
         let skill_mds = find_skill_md_files(&repo_dir)?;
 
         let mut best_by_name: BTreeMap<String, AvailableSkillSummary> = BTreeMap::new();
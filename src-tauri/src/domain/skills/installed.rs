@@ -1,3 +1,4 @@
+use super::repo_cache::commits_match;
 use super::types::InstalledSkillSummary;
 use crate::db;
 use crate::shared::error::db_err;
@@ -7,6 +8,15 @@ use rusqlite::{params, Connection, OptionalExtension};
 use std::collections::HashSet;
 
 fn row_to_installed(row: &rusqlite::Row<'_>) -> Result<InstalledSkillSummary, rusqlite::Error> {
+    let pinned_ref: Option<String> = row.get("pinned_ref")?;
+    let installed_commit: Option<String> = row.get("installed_commit")?;
+    let latest_commit: Option<String> = row.get("latest_commit")?;
+    let update_available = update_available(installed_commit.as_deref(), latest_commit.as_deref());
+    let last_update_changed_files = row
+        .get::<_, Option<String>>("last_update_changed_files")?
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default();
+
     Ok(InstalledSkillSummary {
         id: row.get("id")?,
         skill_key: row.get("skill_key")?,
@@ -16,6 +26,11 @@ fn row_to_installed(row: &rusqlite::Row<'_>) -> Result<InstalledSkillSummary, ru
         source_branch: row.get("source_branch")?,
         source_subdir: row.get("source_subdir")?,
         enabled: row.get::<_, i64>("enabled")? != 0,
+        pinned_ref,
+        installed_commit,
+        latest_commit,
+        update_available,
+        last_update_changed_files,
         created_at: row.get("created_at")?,
         updated_at: row.get("updated_at")?,
     })
@@ -39,6 +54,10 @@ pub fn installed_list_for_workspace(
       s.source_branch,
       s.source_subdir,
       CASE WHEN e.skill_id IS NULL THEN 0 ELSE 1 END AS enabled,
+      s.pinned_ref,
+      s.installed_commit,
+      s.latest_commit,
+      s.last_update_changed_files,
       s.created_at,
       s.updated_at
     FROM skills s
@@ -60,6 +79,15 @@ pub fn installed_list_for_workspace(
     Ok(out)
 }
 
+pub(super) fn update_available(installed: Option<&str>, latest: Option<&str>) -> bool {
+    match (installed, latest) {
+        (Some(installed), Some(latest)) => !commits_match(installed, latest),
+        // Installed before pins were tracked: any known remote commit is worth offering.
+        (None, Some(_)) => true,
+        _ => false,
+    }
+}
+
 pub(super) fn installed_source_set(
     conn: &Connection,
 ) -> crate::shared::error::AppResult<HashSet<String>> {
@@ -102,6 +130,10 @@ SELECT
   source_branch,
   source_subdir,
   0 AS enabled,
+  pinned_ref,
+  installed_commit,
+  latest_commit,
+  last_update_changed_files,
   created_at,
   updated_at
 FROM skills
@@ -131,6 +163,10 @@ SELECT
   s.source_branch,
   s.source_subdir,
   CASE WHEN e.skill_id IS NULL THEN 0 ELSE 1 END AS enabled,
+  s.pinned_ref,
+  s.installed_commit,
+  s.latest_commit,
+  s.last_update_changed_files,
   s.created_at,
   s.updated_at
 FROM skills s
@@ -14,7 +14,7 @@ use crate::workspaces;
 use rusqlite::params;
 
 pub fn local_list(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     db: &db::Db,
     workspace_id: i64,
 ) -> crate::shared::error::AppResult<Vec<LocalSkillSummary>> {
@@ -83,7 +83,7 @@ pub fn local_list(
 }
 
 pub fn import_local(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     db: &db::Db,
     workspace_id: i64,
     dir_name: &str,
@@ -215,7 +215,7 @@ ON CONFLICT(workspace_id, skill_id) DO UPDATE SET
 }
 
 pub fn import_local_batch(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     db: &db::Db,
     workspace_id: i64,
     dir_names: Vec<String>,
@@ -1,5 +1,6 @@
 //! Usage: Skills domain (repositories, installed skills, local import, and CLI integration).
 
+mod deps;
 mod discover;
 mod fs_ops;
 mod git_url;
@@ -7,24 +8,34 @@ mod installed;
 mod local;
 mod local_swap;
 mod ops;
+mod package;
 mod paths;
+mod repo_auth;
 mod repo_cache;
 mod repos;
 mod skill_md;
 mod types;
+mod updates;
 mod util;
 
+pub use deps::{dependencies_check, dependencies_preview};
 pub use discover::discover_available;
 pub use installed::installed_list_for_workspace;
 pub use local::{import_local, import_local_batch, local_list};
 pub(crate) use local_swap::swap_local_skills_for_workspace_switch;
 pub use ops::{install, return_to_local, set_enabled, sync_cli_for_workspace, uninstall};
+pub use package::package;
 pub use paths::paths_get;
+pub use repo_auth::{repo_auth_set, repo_test_access};
 pub use repos::{repo_delete, repo_upsert, repos_list};
 pub use types::{
-    AvailableSkillSummary, InstalledSkillSummary, LocalSkillSummary, SkillImportLocalBatchReport,
-    SkillRepoSummary, SkillsPaths,
+    AvailableSkillSummary, InstalledSkillSummary, LocalSkillSummary, SkillChangedFile,
+    SkillDependency, SkillDependencyKind, SkillDependencyReport, SkillDependencyStatus,
+    SkillImportLocalBatchReport, SkillPackageFile, SkillPackagePush, SkillPackageReport,
+    SkillRepoAccessReport, SkillRepoAuthKind, SkillRepoSummary, SkillUpdateReport,
+    SkillUpdateStatus, SkillsPaths,
 };
+pub use updates::{check_updates, set_pin, update};
 
 #[cfg(test)]
 mod tests;
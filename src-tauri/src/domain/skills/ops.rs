@@ -1,9 +1,11 @@
+use super::deps::ensure_dependencies_met;
 use super::fs_ops::{
     copy_dir_recursive, has_skill_md, is_managed_dir, is_symlink, remove_managed_dir, remove_marker,
 };
 use super::installed::{generate_unique_skill_key, get_skill_by_id, get_skill_by_id_for_workspace};
 use super::paths::{cli_skills_root, ensure_skills_roots, ssot_skills_root, validate_cli_key};
-use super::repo_cache::ensure_repo_cache;
+use super::repo_auth::auth_for_git_url;
+use super::repo_cache::{ensure_repo_cache, repo_cache_head_commit};
 use super::skill_md::parse_skill_md;
 use super::types::InstalledSkillSummary;
 use super::util::validate_relative_subdir;
@@ -27,7 +29,7 @@ fn is_external_local_skill_dir(path: &Path) -> crate::shared::error::AppResult<b
     Ok(path.is_dir() && has_skill_md(path))
 }
 
-fn local_source_cli_key(source_git_url: &str) -> Option<&str> {
+pub(super) fn local_source_cli_key(source_git_url: &str) -> Option<&str> {
     source_git_url
         .strip_prefix("local://")
         .map(str::trim)
@@ -63,7 +65,7 @@ fn ensure_ssot_dir_exists<R: tauri::Runtime>(
     Ok(())
 }
 
-fn sync_to_cli<R: tauri::Runtime>(
+pub(super) fn sync_to_cli<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     cli_key: &str,
     skill_key: &str,
@@ -171,7 +173,7 @@ fn delete_skill_row(conn: &Connection, skill_id: i64) -> crate::shared::error::A
 
 #[allow(clippy::too_many_arguments)]
 pub fn install(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     db: &db::Db,
     workspace_id: i64,
     git_url: &str,
@@ -208,7 +210,9 @@ LIMIT 1
             .into());
     }
 
-    let repo_dir = ensure_repo_cache(app, git_url, branch, true)?;
+    let auth = auth_for_git_url(&conn, git_url)?;
+    let repo_dir = ensure_repo_cache(app, &auth, git_url, branch, true)?;
+    let installed_commit = repo_cache_head_commit(&repo_dir);
     let src_dir = repo_dir.join(source_subdir.trim());
     if !src_dir.exists() {
         return Err(format!("SKILL_SOURCE_NOT_FOUND: {}", src_dir.display()).into());
@@ -223,6 +227,9 @@ LIMIT 1
 
     let (name, description) = parse_skill_md(&skill_md)?;
     let normalized_name = normalize_name(&name);
+    if enabled {
+        ensure_dependencies_met(&conn, workspace_id, &name, &skill_md)?;
+    }
 
     let tx = conn
         .transaction()
@@ -245,9 +252,10 @@ INSERT INTO skills(
   source_git_url,
   source_branch,
   source_subdir,
+  installed_commit,
   created_at,
   updated_at
-) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
 "#,
         params![
             skill_key,
@@ -257,6 +265,7 @@ INSERT INTO skills(
             git_url.trim(),
             branch.trim(),
             source_subdir.trim(),
+            installed_commit,
             now,
             now
         ],
@@ -335,6 +344,14 @@ pub fn set_enabled<R: tauri::Runtime>(
     let ssot_root = ssot_skills_root(app)?;
     let ssot_dir = ssot_root.join(&current.skill_key);
     ensure_ssot_dir_exists(app, &current, &ssot_dir)?;
+    if enabled {
+        ensure_dependencies_met(
+            &conn,
+            workspace_id,
+            &current.name,
+            &ssot_dir.join("SKILL.md"),
+        )?;
+    }
 
     if should_sync {
         if enabled {
@@ -446,7 +463,7 @@ pub fn return_to_local<R: tauri::Runtime>(
 }
 
 pub fn sync_cli_for_workspace(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     conn: &Connection,
     workspace_id: i64,
 ) -> crate::shared::error::AppResult<()> {
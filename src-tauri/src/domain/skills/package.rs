@@ -0,0 +1,523 @@
+//! Usage: Package a local skill directory into a distributable archive and optionally publish it
+//! to a skill repo branch, from where other machines install it through the regular repo path.
+
+use super::fs_ops::is_symlink;
+use super::git_url::normalize_repo_branch;
+use super::paths::packages_root;
+use super::repo_auth::{auth_for_git_url, RepoAuth};
+use super::repo_cache::{
+    detect_checked_out_branch, git_command, is_remote_branch_not_found, run_git, run_git_capture,
+};
+use super::repos::get_repo_by_id;
+use super::skill_md::parse_skill_md;
+use super::types::{SkillPackageFile, SkillPackagePush, SkillPackageReport};
+use super::util::{now_unix_nanos, validate_dir_name};
+use crate::db;
+use crate::shared::time::now_unix_seconds;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::{Cursor, Write};
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+pub(super) const PACKAGE_MANIFEST_FILE: &str = ".aio-coding-hub.skill-package.json";
+const PACKAGE_FORMAT_VERSION: u32 = 1;
+
+const MAX_FILES: usize = 500;
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_TOTAL_BYTES: u64 = 20 * 1024 * 1024;
+const MAX_NAME_CHARS: usize = 64;
+const MAX_DESCRIPTION_CHARS: usize = 1024;
+
+/// VCS metadata, OS litter and this app's own markers are never shipped.
+const SKIPPED_NAMES: &[&str] = &[
+    ".git",
+    ".DS_Store",
+    "Thumbs.db",
+    ".aio-coding-hub.managed",
+    PACKAGE_MANIFEST_FILE,
+];
+
+struct ValidatedSkill {
+    name: String,
+    description: String,
+    /// Relative `/`-separated path -> absolute path.
+    files: BTreeMap<String, PathBuf>,
+    total_bytes: u64,
+    warnings: Vec<String>,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+fn collect_files(
+    root: &Path,
+    dir: &Path,
+    out: &mut BTreeMap<String, PathBuf>,
+    problems: &mut Vec<String>,
+) -> crate::shared::error::AppResult<()> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("failed to read dir {}: {e}", dir.display()))?;
+    for entry in entries {
+        let entry =
+            entry.map_err(|e| format!("failed to read dir entry {}: {e}", dir.display()))?;
+        let path = entry.path();
+        let file_name = entry.file_name();
+        if SKIPPED_NAMES.contains(&file_name.to_string_lossy().as_ref()) {
+            continue;
+        }
+        let rel = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        if is_symlink(&path)? {
+            problems.push(format!("{rel}: symlinks are not allowed"));
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(root, &path, out, problems)?;
+        } else {
+            out.insert(rel, path);
+        }
+    }
+    Ok(())
+}
+
+/// Relative targets of markdown links and images (`[x](path)`, `![x](path "title")`).
+fn referenced_paths(markdown: &str) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    let mut rest = markdown;
+    while let Some(idx) = rest.find("](") {
+        rest = &rest[idx + 2..];
+        let Some(end) = rest.find(')') else {
+            break;
+        };
+        let raw = &rest[..end];
+        rest = &rest[end + 1..];
+
+        let target = raw
+            .split_whitespace()
+            .next()
+            .unwrap_or("")
+            .trim_matches(|c| c == '<' || c == '>');
+        let target = target.split(['#', '?']).next().unwrap_or("");
+        if target.is_empty() || target.contains("://") || target.starts_with("mailto:") {
+            continue;
+        }
+        let target = target.replace("%20", " ");
+        if !out.contains(&target) {
+            out.push(target);
+        }
+    }
+    out
+}
+
+/// Resolves `target` against the directory of `from` (both skill-relative); `None` when the
+/// result would leave the skill directory.
+fn resolve_reference(from: &str, target: &str) -> Option<String> {
+    let mut parts: Vec<&str> = from.split('/').collect();
+    parts.pop();
+    for comp in Path::new(target).components() {
+        match comp {
+            Component::CurDir => {}
+            Component::Normal(part) => parts.push(part.to_str()?),
+            Component::ParentDir => {
+                parts.pop()?;
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(parts.join("/"))
+}
+
+fn validate_skill(dir: &Path, dir_name: &str) -> crate::shared::error::AppResult<ValidatedSkill> {
+    let skill_md = dir.join("SKILL.md");
+    if !skill_md.is_file() {
+        return Err(format!(
+            "SKILL_PACKAGE_INVALID: SKILL.md not found in {}",
+            dir.display()
+        )
+        .into());
+    }
+    let (name, description) = parse_skill_md(&skill_md)?;
+
+    let mut problems = Vec::new();
+    let mut warnings = Vec::new();
+
+    if name.chars().count() > MAX_NAME_CHARS {
+        problems.push(format!(
+            "SKILL.md: name is longer than {MAX_NAME_CHARS} characters"
+        ));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+        warnings.push(format!(
+            "SKILL.md: name '{name}' should only use lowercase letters, digits and '-'"
+        ));
+    }
+    if name != dir_name {
+        warnings.push(format!(
+            "SKILL.md: name '{name}' differs from directory name '{dir_name}'"
+        ));
+    }
+    if description.is_empty() {
+        problems.push("SKILL.md: missing 'description'".to_string());
+    } else if description.chars().count() > MAX_DESCRIPTION_CHARS {
+        problems.push(format!(
+            "SKILL.md: description is longer than {MAX_DESCRIPTION_CHARS} characters"
+        ));
+    }
+
+    let mut files = BTreeMap::new();
+    collect_files(dir, dir, &mut files, &mut problems)?;
+
+    if files.len() > MAX_FILES {
+        problems.push(format!("too many files: {} (max {MAX_FILES})", files.len()));
+    }
+    let mut total_bytes = 0_u64;
+    for (rel, path) in &files {
+        let size = std::fs::metadata(path)
+            .map_err(|e| format!("failed to read metadata {}: {e}", path.display()))?
+            .len();
+        if size > MAX_FILE_BYTES {
+            problems.push(format!(
+                "{rel}: {size} bytes exceeds the per-file limit of {MAX_FILE_BYTES}"
+            ));
+        }
+        total_bytes += size;
+    }
+    if total_bytes > MAX_TOTAL_BYTES {
+        problems.push(format!(
+            "skill is {total_bytes} bytes, exceeds the limit of {MAX_TOTAL_BYTES}"
+        ));
+    }
+
+    for (rel, path) in &files {
+        if !rel.to_ascii_lowercase().ends_with(".md") {
+            continue;
+        }
+        let Ok(text) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        for target in referenced_paths(&text) {
+            if target.starts_with('/') {
+                warnings.push(format!(
+                    "{rel}: absolute link '{target}' will not resolve on other machines"
+                ));
+                continue;
+            }
+            match resolve_reference(rel, &target) {
+                None => problems.push(format!(
+                    "{rel}: link '{target}' points outside the skill directory"
+                )),
+                Some(resolved) => {
+                    let prefix = format!("{resolved}/");
+                    let exists = resolved.is_empty()
+                        || files.contains_key(&resolved)
+                        || files.keys().any(|k| k.starts_with(&prefix));
+                    if !exists {
+                        problems.push(format!("{rel}: referenced file '{target}' is missing"));
+                    }
+                }
+            }
+        }
+    }
+
+    if !problems.is_empty() {
+        return Err(format!("SKILL_PACKAGE_INVALID: {}", problems.join("; ")).into());
+    }
+
+    Ok(ValidatedSkill {
+        name,
+        description,
+        files,
+        total_bytes,
+        warnings,
+    })
+}
+
+/// Writes `<dir_name>.zip` (single root dir, the layout the repo installer expects) with a
+/// manifest inside and `<dir_name>.zip.sha256` next to it.
+fn write_archive(
+    dir_name: &str,
+    skill: &ValidatedSkill,
+    out_dir: &Path,
+) -> crate::shared::error::AppResult<(PathBuf, String, Vec<SkillPackageFile>)> {
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let mut manifest_files = Vec::with_capacity(skill.files.len());
+
+    for (rel, path) in &skill.files {
+        let bytes =
+            std::fs::read(path).map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+        writer
+            .start_file(format!("{dir_name}/{rel}"), options)
+            .map_err(|e| format!("SKILL_ZIP_ERROR: failed to add {rel}: {e}"))?;
+        writer
+            .write_all(&bytes)
+            .map_err(|e| format!("SKILL_ZIP_ERROR: failed to write {rel}: {e}"))?;
+        manifest_files.push(SkillPackageFile {
+            path: rel.clone(),
+            size: bytes.len() as u64,
+            sha256: sha256_hex(&bytes),
+        });
+    }
+
+    let manifest = serde_json::json!({
+        "format_version": PACKAGE_FORMAT_VERSION,
+        "name": skill.name,
+        "description": skill.description,
+        "dir_name": dir_name,
+        "created_at": now_unix_seconds(),
+        "files": manifest_files,
+    });
+    let manifest = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| format!("SKILL_PACKAGE_ERROR: failed to serialize manifest: {e}"))?;
+    writer
+        .start_file(format!("{dir_name}/{PACKAGE_MANIFEST_FILE}"), options)
+        .map_err(|e| format!("SKILL_ZIP_ERROR: failed to add manifest: {e}"))?;
+    writer
+        .write_all(&manifest)
+        .map_err(|e| format!("SKILL_ZIP_ERROR: failed to write manifest: {e}"))?;
+    let archive = writer
+        .finish()
+        .map_err(|e| format!("SKILL_ZIP_ERROR: failed to finish archive: {e}"))?
+        .into_inner();
+
+    std::fs::create_dir_all(out_dir)
+        .map_err(|e| format!("failed to create {}: {e}", out_dir.display()))?;
+    let archive_name = format!("{dir_name}.zip");
+    let archive_path = out_dir.join(&archive_name);
+    std::fs::write(&archive_path, &archive)
+        .map_err(|e| format!("failed to write {}: {e}", archive_path.display()))?;
+
+    let checksum = sha256_hex(&archive);
+    let checksum_path = out_dir.join(format!("{archive_name}.sha256"));
+    std::fs::write(&checksum_path, format!("{checksum}  {archive_name}\n"))
+        .map_err(|e| format!("failed to write {}: {e}", checksum_path.display()))?;
+
+    Ok((archive_path, checksum, manifest_files))
+}
+
+/// Validates `source_dir` and writes the archive into `out_dir`; nothing is written when
+/// validation fails.
+pub(super) fn build_package(
+    source_dir: &Path,
+    out_dir: &Path,
+) -> crate::shared::error::AppResult<(SkillPackageReport, BTreeMap<String, PathBuf>)> {
+    if !source_dir.is_absolute() {
+        return Err("SEC_INVALID_INPUT: source_dir must be an absolute path".into());
+    }
+    if !source_dir.is_dir() {
+        return Err(format!(
+            "SEC_INVALID_INPUT: source_dir is not a directory: {}",
+            source_dir.display()
+        )
+        .into());
+    }
+    let dir_name = source_dir
+        .file_name()
+        .and_then(|v| v.to_str())
+        .ok_or_else(|| "SEC_INVALID_INPUT: source_dir has no usable directory name".to_string())?;
+    let dir_name = validate_dir_name(dir_name)?;
+
+    let skill = validate_skill(source_dir, &dir_name)?;
+    let (archive_path, checksum, files) = write_archive(&dir_name, &skill, out_dir)?;
+
+    let report = SkillPackageReport {
+        name: skill.name,
+        description: skill.description,
+        dir_name,
+        archive_path: archive_path.to_string_lossy().to_string(),
+        checksum_sha256: checksum,
+        files,
+        total_bytes: skill.total_bytes,
+        warnings: skill.warnings,
+        push: None,
+    };
+    Ok((report, skill.files))
+}
+
+fn git_in(dir: &Path) -> Command {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C").arg(dir);
+    cmd
+}
+
+fn clone_for_publish(
+    auth: &RepoAuth,
+    git_url: &str,
+    branch: &str,
+    dir: &Path,
+) -> crate::shared::error::AppResult<String> {
+    if branch != "auto" {
+        let mut cmd = git_command(auth);
+        cmd.args(["clone", "--depth", "1", "--branch", branch, git_url])
+            .arg(dir);
+        match run_git(cmd) {
+            Ok(()) => return Ok(branch.to_string()),
+            Err(err) if is_remote_branch_not_found(&err.to_string()) => {}
+            Err(err) => return Err(err),
+        }
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    let mut cmd = git_command(auth);
+    cmd.args(["clone", "--depth", "1", git_url]).arg(dir);
+    run_git(cmd)?;
+    if branch == "auto" {
+        return detect_checked_out_branch(dir);
+    }
+
+    // Configured branch does not exist yet: publishing creates it from the default branch.
+    let mut cmd = git_in(dir);
+    cmd.args(["checkout", "-b", branch]);
+    run_git(cmd)?;
+    Ok(branch.to_string())
+}
+
+fn publish_in(
+    auth: &RepoAuth,
+    work_dir: &Path,
+    git_url: &str,
+    branch: &str,
+    dir_name: &str,
+    name: &str,
+    files: &BTreeMap<String, PathBuf>,
+) -> crate::shared::error::AppResult<(String, String, bool)> {
+    let branch = clone_for_publish(auth, git_url, branch, work_dir)?;
+
+    let target = work_dir.join(dir_name);
+    if target.exists() {
+        std::fs::remove_dir_all(&target)
+            .map_err(|e| format!("failed to remove {}: {e}", target.display()))?;
+    }
+    for (rel, src) in files {
+        let dst = target.join(rel);
+        if let Some(parent) = dst.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("failed to create {}: {e}", parent.display()))?;
+        }
+        std::fs::copy(src, &dst)
+            .map_err(|e| format!("failed to copy {} -> {}: {e}", src.display(), dst.display()))?;
+    }
+
+    let mut cmd = git_in(work_dir);
+    cmd.args(["add", "-A", "--"]).arg(dir_name);
+    run_git(cmd)?;
+
+    let mut cmd = git_in(work_dir);
+    cmd.args(["status", "--porcelain", "--"]).arg(dir_name);
+    let changed = !run_git_capture(cmd)?.is_empty();
+
+    if changed {
+        let mut identity = git_in(work_dir);
+        identity.args(["config", "user.email"]);
+        let has_identity = run_git_capture(identity).is_ok_and(|v| !v.is_empty());
+
+        let mut cmd = Command::new("git");
+        if !has_identity {
+            cmd.args([
+                "-c",
+                "user.name=aio-coding-hub",
+                "-c",
+                "user.email=aio-coding-hub@localhost",
+            ]);
+        }
+        cmd.arg("-C")
+            .arg(work_dir)
+            .args(["commit", "-m", &format!("Publish skill {name}")]);
+        run_git(cmd)?;
+
+        let mut cmd = git_command(auth);
+        cmd.arg("-C")
+            .arg(work_dir)
+            .args(["push", "origin", &format!("HEAD:refs/heads/{branch}")]);
+        run_git(cmd)?;
+    }
+
+    let mut cmd = git_in(work_dir);
+    cmd.args(["rev-parse", "HEAD"]);
+    let commit = run_git_capture(cmd)?;
+    Ok((branch, commit, changed))
+}
+
+fn publish(
+    db: &db::Db,
+    repo_id: i64,
+    dir_name: &str,
+    name: &str,
+    files: &BTreeMap<String, PathBuf>,
+) -> crate::shared::error::AppResult<SkillPackagePush> {
+    let (repo, auth) = {
+        let conn = db.open_connection()?;
+        let repo = get_repo_by_id(&conn, repo_id)?;
+        let auth = auth_for_git_url(&conn, &repo.git_url)?;
+        (repo, auth)
+    };
+    let branch = normalize_repo_branch(&repo.branch);
+    if branch.starts_with('-') {
+        return Err("SEC_INVALID_INPUT: repo branch must not start with '-'".into());
+    }
+
+    let work_dir =
+        std::env::temp_dir().join(format!("aio-coding-hub-skill-publish-{}", now_unix_nanos()));
+    let result = publish_in(
+        &auth,
+        &work_dir,
+        &repo.git_url,
+        &branch,
+        dir_name,
+        name,
+        files,
+    );
+    let _ = std::fs::remove_dir_all(&work_dir);
+    let (branch, commit, changed) = result?;
+
+    Ok(SkillPackagePush {
+        repo_id,
+        git_url: repo.git_url,
+        branch,
+        commit,
+        changed,
+    })
+}
+
+/// Packages the skill at `source_dir`; with `push_repo_id` the skill directory is also committed
+/// to that repo's configured branch as `<dir_name>/` and pushed.
+pub fn package(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    source_dir: &str,
+    output_dir: Option<&str>,
+    push_repo_id: Option<i64>,
+) -> crate::shared::error::AppResult<SkillPackageReport> {
+    let out_dir = match output_dir.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(dir) if Path::new(dir).is_absolute() => PathBuf::from(dir),
+        Some(_) => {
+            return Err("SEC_INVALID_INPUT: output_dir must be an absolute path".into());
+        }
+        None => packages_root(app)?,
+    };
+    let (mut report, files) = build_package(Path::new(source_dir.trim()), &out_dir)?;
+    if let Some(repo_id) = push_repo_id {
+        report.push = Some(publish(
+            db,
+            repo_id,
+            &report.dir_name,
+            &report.name,
+            &files,
+        )?);
+    }
+    Ok(report)
+}
@@ -28,6 +28,12 @@ pub(super) fn repos_root<R: tauri::Runtime>(
     Ok(app_paths::app_data_dir(app)?.join("skill-repos"))
 }
 
+pub(super) fn packages_root<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> crate::shared::error::AppResult<PathBuf> {
+    Ok(app_paths::app_data_dir(app)?.join("skill-packages"))
+}
+
 pub(super) fn cli_skills_root<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     cli_key: &str,
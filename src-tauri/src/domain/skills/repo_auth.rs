@@ -0,0 +1,255 @@
+//! Usage: Credentials (PAT or SSH key) for skill repos hosted on private Git servers.
+//!
+//! Rows live in `skill_repo_credentials`, keyed by canonical git URL: every path that fetches a
+//! repo (discover, install, updates, dependency preview) only has the URL, and the key keeps the
+//! credentials out of config archives, which export `skill_repos` as-is. Tokens never leave the
+//! backend; summaries only report which kind of credential is configured.
+
+use super::git_url::canonical_git_url_key;
+use super::repo_cache::resolve_remote_commit;
+use super::repos::get_repo_by_id;
+use super::types::{SkillRepoAccessReport, SkillRepoAuthKind, SkillRepoSummary};
+use crate::db;
+use crate::shared::error::db_err;
+use crate::shared::time::now_unix_seconds;
+use base64::Engine;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::PathBuf;
+use std::process::Command;
+
+const MAX_TOKEN_LEN: usize = 4096;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) enum RepoAuth {
+    #[default]
+    None,
+    Token(String),
+    SshKey(PathBuf),
+}
+
+impl RepoAuth {
+    pub(super) fn kind(&self) -> SkillRepoAuthKind {
+        match self {
+            Self::None => SkillRepoAuthKind::None,
+            Self::Token(_) => SkillRepoAuthKind::Token,
+            Self::SshKey(_) => SkillRepoAuthKind::SshKey,
+        }
+    }
+
+    /// Bearer token for the GitHub API; SSH keys cannot authenticate API calls.
+    pub(super) fn github_token(&self) -> Option<&str> {
+        match self {
+            Self::Token(token) => Some(token),
+            _ => None,
+        }
+    }
+
+    /// SSH keys only work over git's own transport, so GitHub zipball shortcuts are skipped.
+    pub(super) fn requires_git_transport(&self) -> bool {
+        matches!(self, Self::SshKey(_))
+    }
+
+    /// Passes the credential through the environment (never argv, which other users can read)
+    /// and turns off interactive prompts so a bad credential fails instead of hanging.
+    pub(super) fn apply_to_git(&self, cmd: &mut Command) {
+        cmd.env("GIT_TERMINAL_PROMPT", "0");
+        match self {
+            Self::None => {}
+            Self::Token(token) => {
+                let basic = base64::engine::general_purpose::STANDARD
+                    .encode(format!("x-access-token:{token}"));
+                cmd.env("GIT_CONFIG_COUNT", "1")
+                    .env("GIT_CONFIG_KEY_0", "http.extraHeader")
+                    .env(
+                        "GIT_CONFIG_VALUE_0",
+                        format!("Authorization: Basic {basic}"),
+                    );
+            }
+            Self::SshKey(path) => {
+                let path = path.to_string_lossy().replace('\'', r"'\''");
+                cmd.env(
+                    "GIT_SSH_COMMAND",
+                    format!(
+                        "ssh -i '{path}' -o IdentitiesOnly=yes -o BatchMode=yes -o StrictHostKeyChecking=accept-new"
+                    ),
+                );
+            }
+        }
+    }
+}
+
+pub(super) fn repo_key(git_url: &str) -> String {
+    let key = canonical_git_url_key(git_url);
+    if key.is_empty() {
+        git_url.trim().to_ascii_lowercase()
+    } else {
+        key
+    }
+}
+
+fn auth_from_row(kind: &str, token: Option<String>, ssh_key_path: Option<String>) -> RepoAuth {
+    match (kind, token, ssh_key_path) {
+        ("token", Some(token), _) if !token.is_empty() => RepoAuth::Token(token),
+        ("ssh_key", _, Some(path)) if !path.is_empty() => RepoAuth::SshKey(PathBuf::from(path)),
+        _ => RepoAuth::None,
+    }
+}
+
+pub(super) fn auth_for_git_url(
+    conn: &Connection,
+    git_url: &str,
+) -> crate::shared::error::AppResult<RepoAuth> {
+    let row = conn
+        .query_row(
+            "SELECT auth_kind, token, ssh_key_path FROM skill_repo_credentials WHERE repo_key = ?1",
+            params![repo_key(git_url)],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                ))
+            },
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query skill repo credentials: {e}"))?;
+    Ok(row
+        .map(|(kind, token, path)| auth_from_row(&kind, token, path))
+        .unwrap_or_default())
+}
+
+/// Fills the credential fields of a summary; the token itself stays in the database.
+pub(super) fn attach_auth_summary(
+    conn: &Connection,
+    repo: &mut SkillRepoSummary,
+) -> crate::shared::error::AppResult<()> {
+    let auth = auth_for_git_url(conn, &repo.git_url)?;
+    repo.auth_kind = auth.kind();
+    repo.ssh_key_path = match auth {
+        RepoAuth::SshKey(path) => Some(path.to_string_lossy().into_owned()),
+        _ => None,
+    };
+    Ok(())
+}
+
+pub(super) fn delete_auth_for_git_url(
+    conn: &Connection,
+    git_url: &str,
+) -> crate::shared::error::AppResult<()> {
+    conn.execute(
+        "DELETE FROM skill_repo_credentials WHERE repo_key = ?1",
+        params![repo_key(git_url)],
+    )
+    .map_err(|e| db_err!("failed to delete skill repo credentials: {e}"))?;
+    Ok(())
+}
+
+/// Set or clear the credential of a repo. `token` is required for `token`, `ssh_key_path` (an
+/// existing private key file) for `ssh_key`; `none` removes the stored credential.
+pub fn repo_auth_set(
+    db: &db::Db,
+    repo_id: i64,
+    auth_kind: SkillRepoAuthKind,
+    token: Option<String>,
+    ssh_key_path: Option<String>,
+) -> crate::shared::error::AppResult<SkillRepoSummary> {
+    let conn = db.open_connection()?;
+    let mut repo = get_repo_by_id(&conn, repo_id)?;
+
+    let (kind, token, ssh_key_path) = match auth_kind {
+        SkillRepoAuthKind::None => {
+            delete_auth_for_git_url(&conn, &repo.git_url)?;
+            attach_auth_summary(&conn, &mut repo)?;
+            return Ok(repo);
+        }
+        SkillRepoAuthKind::Token => {
+            let token = token.as_deref().map(str::trim).unwrap_or_default();
+            if token.is_empty() {
+                return Err("SEC_INVALID_INPUT: token is required".into());
+            }
+            if token.len() > MAX_TOKEN_LEN || token.chars().any(char::is_control) {
+                return Err("SEC_INVALID_INPUT: token is malformed".into());
+            }
+            ("token", Some(token.to_string()), None)
+        }
+        SkillRepoAuthKind::SshKey => {
+            let path = ssh_key_path.as_deref().map(str::trim).unwrap_or_default();
+            if path.is_empty() {
+                return Err("SEC_INVALID_INPUT: ssh_key_path is required".into());
+            }
+            if !std::path::Path::new(path).is_file() {
+                return Err(format!("SEC_INVALID_INPUT: ssh key not found: {path}").into());
+            }
+            ("ssh_key", None, Some(path.to_string()))
+        }
+    };
+
+    conn.execute(
+        r#"
+INSERT INTO skill_repo_credentials(repo_key, auth_kind, token, ssh_key_path, updated_at)
+VALUES (?1, ?2, ?3, ?4, ?5)
+ON CONFLICT(repo_key) DO UPDATE SET
+  auth_kind = excluded.auth_kind,
+  token = excluded.token,
+  ssh_key_path = excluded.ssh_key_path,
+  updated_at = excluded.updated_at
+"#,
+        params![
+            repo_key(&repo.git_url),
+            kind,
+            token,
+            ssh_key_path,
+            now_unix_seconds()
+        ],
+    )
+    .map_err(|e| db_err!("failed to save skill repo credentials: {e}"))?;
+
+    attach_auth_summary(&conn, &mut repo)?;
+    Ok(repo)
+}
+
+/// Resolve the repo's branch on the remote with its stored credential, without touching the
+/// local cache.
+pub fn repo_test_access(
+    db: &db::Db,
+    repo_id: i64,
+) -> crate::shared::error::AppResult<SkillRepoAccessReport> {
+    let conn = db.open_connection()?;
+    let repo = get_repo_by_id(&conn, repo_id)?;
+    let auth = auth_for_git_url(&conn, &repo.git_url)?;
+    drop(conn);
+
+    let auth_kind = auth.kind();
+    Ok(
+        match resolve_remote_commit(&auth, &repo.git_url, &repo.branch) {
+            Ok(commit) => SkillRepoAccessReport {
+                ok: true,
+                auth_kind,
+                commit: Some(commit),
+                error_code: None,
+                message: None,
+            },
+            Err(err) => SkillRepoAccessReport {
+                ok: false,
+                auth_kind,
+                commit: None,
+                error_code: Some(err.code().to_string()),
+                message: Some(err.message().to_string()),
+            },
+        },
+    )
+}
+
+/// Maps git's stderr for rejected or missing credentials to `SKILL_REPO_AUTH_FAILED`.
+pub(super) fn is_git_auth_failure(stderr: &str) -> bool {
+    let e = stderr.to_ascii_lowercase();
+    e.contains("authentication failed")
+        || e.contains("could not read username")
+        || e.contains("could not read password")
+        || e.contains("terminal prompts disabled")
+        || e.contains("permission denied (publickey")
+        || e.contains("access denied")
+        || e.contains("the requested url returned error: 401")
+        || e.contains("the requested url returned error: 403")
+        || e.contains("invalid username or password")
+}
@@ -1,5 +1,6 @@
 use super::git_url::{normalize_repo_branch, parse_github_owner_repo};
 use super::paths::repos_root;
+use super::repo_auth::{is_git_auth_failure, RepoAuth};
 use super::util::now_unix_nanos;
 use std::io::{Cursor, Write};
 use std::path::{Component, Path, PathBuf};
@@ -8,6 +9,7 @@ use std::time::{Duration, SystemTime};
 
 const REPO_BRANCH_FILE: &str = ".aio-coding-hub.repo-branch";
 const REPO_SNAPSHOT_MARKER_FILE: &str = ".aio-coding-hub.repo-snapshot";
+const REPO_COMMIT_FILE: &str = ".aio-coding-hub.repo-commit";
 
 fn fnv1a64(input: &str) -> u64 {
     let mut hash: u64 = 0xcbf29ce484222325;
@@ -19,7 +21,7 @@ fn fnv1a64(input: &str) -> u64 {
 }
 
 fn repo_cache_dir(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     git_url: &str,
     branch: &str,
 ) -> crate::shared::error::AppResult<PathBuf> {
@@ -120,7 +122,22 @@ fn remove_path_if_exists(path: &Path) -> crate::shared::error::AppResult<()> {
         .map_err(|e| format!("failed to remove {}: {e}", path.display()).into())
 }
 
-fn run_git(mut cmd: Command) -> crate::shared::error::AppResult<()> {
+fn git_error(msg: &str) -> String {
+    if is_git_auth_failure(msg) {
+        format!("SKILL_REPO_AUTH_FAILED: {msg}")
+    } else {
+        format!("SKILL_GIT_ERROR: {msg}")
+    }
+}
+
+/// `git` invocation that talks to a remote with the repo's credential.
+pub(super) fn git_command(auth: &RepoAuth) -> Command {
+    let mut cmd = Command::new("git");
+    auth.apply_to_git(&mut cmd);
+    cmd
+}
+
+pub(super) fn run_git(mut cmd: Command) -> crate::shared::error::AppResult<()> {
     #[cfg(windows)]
     {
         use std::os::windows::process::CommandExt;
@@ -136,10 +153,10 @@ fn run_git(mut cmd: Command) -> crate::shared::error::AppResult<()> {
     let stderr = String::from_utf8_lossy(&out.stderr).trim().to_string();
     let stdout = String::from_utf8_lossy(&out.stdout).trim().to_string();
     let msg = if !stderr.is_empty() { stderr } else { stdout };
-    Err(format!("SKILL_GIT_ERROR: {msg}").into())
+    Err(git_error(&msg).into())
 }
 
-fn run_git_capture(mut cmd: Command) -> crate::shared::error::AppResult<String> {
+pub(super) fn run_git_capture(mut cmd: Command) -> crate::shared::error::AppResult<String> {
     #[cfg(windows)]
     {
         use std::os::windows::process::CommandExt;
@@ -155,10 +172,10 @@ fn run_git_capture(mut cmd: Command) -> crate::shared::error::AppResult<String>
     let stderr = String::from_utf8_lossy(&out.stderr).trim().to_string();
     let stdout = String::from_utf8_lossy(&out.stdout).trim().to_string();
     let msg = if !stderr.is_empty() { stderr } else { stdout };
-    Err(format!("SKILL_GIT_ERROR: {msg}").into())
+    Err(git_error(&msg).into())
 }
 
-fn is_remote_branch_not_found(err: &str) -> bool {
+pub(super) fn is_remote_branch_not_found(err: &str) -> bool {
     let e = err.to_ascii_lowercase();
     (e.contains("remote branch") && e.contains("not found"))
         || e.contains("couldn't find remote ref")
@@ -182,7 +199,56 @@ fn write_repo_branch(dir: &Path, branch: &str) -> crate::shared::error::AppResul
     Ok(())
 }
 
-fn detect_checked_out_branch(dir: &Path) -> crate::shared::error::AppResult<String> {
+fn write_repo_commit(dir: &Path, commit: &str) -> crate::shared::error::AppResult<()> {
+    let path = dir.join(REPO_COMMIT_FILE);
+    std::fs::write(&path, format!("{}\n", commit.trim()))
+        .map_err(|e| format!("failed to write {}: {e}", path.display()))?;
+    Ok(())
+}
+
+fn is_hex_commit(value: &str) -> bool {
+    value.len() >= 7 && value.len() <= 40 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// GitHub zipballs extract to `{owner}-{repo}-{short_sha}`; the suffix is the only commit hint
+/// a snapshot carries.
+fn zipball_root_commit(extracted_root: &Path) -> Option<String> {
+    let name = extracted_root.file_name()?.to_str()?;
+    let (_, suffix) = name.rsplit_once('-')?;
+    is_hex_commit(suffix).then(|| suffix.to_ascii_lowercase())
+}
+
+/// Commit of a repo cache dir: `HEAD` for git clones, the recorded (possibly short) sha for
+/// GitHub snapshots.
+pub(super) fn repo_cache_head_commit(dir: &Path) -> Option<String> {
+    if dir.join(".git").exists() {
+        let mut cmd = Command::new("git");
+        cmd.arg("-C").arg(dir).arg("rev-parse").arg("HEAD");
+        return run_git_capture(cmd)
+            .ok()
+            .filter(|v| is_hex_commit(v))
+            .map(|v| v.to_ascii_lowercase());
+    }
+    let text = std::fs::read_to_string(dir.join(REPO_COMMIT_FILE)).ok()?;
+    let commit = text.trim();
+    is_hex_commit(commit).then(|| commit.to_ascii_lowercase())
+}
+
+/// Compare two commit ids where either side may be abbreviated.
+pub(super) fn commits_match(a: &str, b: &str) -> bool {
+    let a = a.trim().to_ascii_lowercase();
+    let b = b.trim().to_ascii_lowercase();
+    if !is_hex_commit(&a) || !is_hex_commit(&b) {
+        return false;
+    }
+    if a.len() <= b.len() {
+        b.starts_with(&a)
+    } else {
+        a.starts_with(&b)
+    }
+}
+
+pub(super) fn detect_checked_out_branch(dir: &Path) -> crate::shared::error::AppResult<String> {
     let mut cmd = Command::new("git");
     cmd.arg("-C")
         .arg(dir)
@@ -221,14 +287,18 @@ pub(super) fn github_api_url(segments: &[&str]) -> crate::shared::error::AppResu
 
 fn github_default_branch(
     client: &reqwest::Client,
+    token: Option<&str>,
     owner: &str,
     repo: &str,
 ) -> crate::shared::error::AppResult<String> {
     let url = github_api_url(&["repos", owner, repo])?;
     let client = client.clone();
     tauri::async_runtime::block_on(async move {
-        let resp = client
-            .get(url)
+        let mut req = client.get(url);
+        if let Some(token) = token {
+            req = req.bearer_auth(token);
+        }
+        let resp = req
             .header("Accept", "application/vnd.github+json")
             .send()
             .await
@@ -241,7 +311,13 @@ fn github_default_branch(
             .map_err(|e| format!("SKILL_HTTP_ERROR: failed to read github response: {e}"))?;
 
         if status == reqwest::StatusCode::NOT_FOUND {
-            return Err("SKILL_GITHUB_REPO_NOT_FOUND: repository not found".to_string());
+            return Err(
+                "SKILL_GITHUB_REPO_NOT_FOUND: repository not found (private repos need a token)"
+                    .to_string(),
+            );
+        }
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err("SKILL_REPO_AUTH_FAILED: github rejected the repo token".to_string());
         }
         if status == reqwest::StatusCode::FORBIDDEN {
             return Err(
@@ -272,6 +348,7 @@ fn github_default_branch(
 
 fn github_download_zipball(
     client: &reqwest::Client,
+    token: Option<&str>,
     owner: &str,
     repo: &str,
     r#ref: &str,
@@ -279,8 +356,11 @@ fn github_download_zipball(
     let url = github_api_url(&["repos", owner, repo, "zipball", r#ref])?;
     let client = client.clone();
     tauri::async_runtime::block_on(async move {
-        let resp = client
-            .get(url)
+        let mut req = client.get(url);
+        if let Some(token) = token {
+            req = req.bearer_auth(token);
+        }
+        let resp = req
             .header("Accept", "application/vnd.github+json")
             .send()
             .await
@@ -290,6 +370,9 @@ fn github_download_zipball(
         if status == reqwest::StatusCode::NOT_FOUND {
             return Err("SKILL_GITHUB_REF_NOT_FOUND: branch/ref not found".to_string());
         }
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err("SKILL_REPO_AUTH_FAILED: github rejected the repo token".to_string());
+        }
         if status == reqwest::StatusCode::FORBIDDEN {
             return Err(
                 "SKILL_GITHUB_FORBIDDEN: github request forbidden (rate limit?)".to_string(),
@@ -409,7 +492,8 @@ fn write_repo_snapshot_marker(
 }
 
 fn ensure_github_repo_snapshot(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
+    token: Option<&str>,
     git_url: &str,
     owner: &str,
     repo: &str,
@@ -451,7 +535,7 @@ fn ensure_github_repo_snapshot(
     if branch == "auto" {
         // Common default branches: avoid GitHub API unless needed (rate limits).
         for candidate in ["main", "master"] {
-            match github_download_zipball(&client, owner, repo, candidate) {
+            match github_download_zipball(&client, token, owner, repo, candidate) {
                 Ok(bytes) => {
                     effective_branch = candidate.to_string();
                     zip_bytes = Some(bytes);
@@ -464,9 +548,9 @@ fn ensure_github_repo_snapshot(
         }
 
         if zip_bytes.is_none() {
-            match github_default_branch(&client, owner, repo) {
+            match github_default_branch(&client, token, owner, repo) {
                 Ok(default_branch) => {
-                    match github_download_zipball(&client, owner, repo, &default_branch) {
+                    match github_download_zipball(&client, token, owner, repo, &default_branch) {
                         Ok(bytes) => {
                             effective_branch = default_branch;
                             zip_bytes = Some(bytes);
@@ -482,7 +566,7 @@ fn ensure_github_repo_snapshot(
             }
         }
     } else {
-        match github_download_zipball(&client, owner, repo, branch) {
+        match github_download_zipball(&client, token, owner, repo, branch) {
             Ok(bytes) => {
                 effective_branch = branch.to_string();
                 zip_bytes = Some(bytes);
@@ -529,6 +613,9 @@ fn ensure_github_repo_snapshot(
 
     write_repo_branch(&extracted_root, &effective_branch)?;
     write_repo_snapshot_marker(&extracted_root, git_url, &effective_branch)?;
+    if let Some(commit) = zipball_root_commit(&extracted_root) {
+        write_repo_commit(&extracted_root, &commit)?;
+    }
 
     // Atomic-ish swap: move old dir away, then move new dir into place.
     let backup = parent.join(format!(".{dir_name}.old-{nonce}"));
@@ -561,7 +648,8 @@ fn ensure_github_repo_snapshot(
 }
 
 fn ensure_git_repo_cache(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
+    auth: &RepoAuth,
     git_url: &str,
     branch: &str,
     refresh: bool,
@@ -592,7 +680,7 @@ fn ensure_git_repo_cache(
         }
 
         if branch == "auto" {
-            let mut cmd = Command::new("git");
+            let mut cmd = git_command(auth);
             cmd.arg("clone")
                 .arg("--depth")
                 .arg("1")
@@ -609,7 +697,7 @@ fn ensure_git_repo_cache(
             return Ok(dir);
         }
 
-        let mut cmd = Command::new("git");
+        let mut cmd = git_command(auth);
         cmd.arg("clone")
             .arg("--depth")
             .arg("1")
@@ -630,7 +718,7 @@ fn ensure_git_repo_cache(
 
                 remove_path_if_exists(&dir)?;
 
-                let mut cmd = Command::new("git");
+                let mut cmd = git_command(auth);
                 cmd.arg("clone")
                     .arg("--depth")
                     .arg("1")
@@ -661,7 +749,7 @@ fn ensure_git_repo_cache(
         }
     }
 
-    let mut cmd = Command::new("git");
+    let mut cmd = git_command(auth);
     cmd.arg("-C")
         .arg(&dir)
         .arg("fetch")
@@ -677,7 +765,7 @@ fn ensure_git_repo_cache(
 
         remove_path_if_exists(&dir)?;
 
-        let mut cmd = Command::new("git");
+        let mut cmd = git_command(auth);
         cmd.arg("clone")
             .arg("--depth")
             .arg("1")
@@ -694,7 +782,7 @@ fn ensure_git_repo_cache(
         return Ok(dir);
     }
 
-    let mut cmd = Command::new("git");
+    let mut cmd = git_command(auth);
     cmd.arg("-C")
         .arg(&dir)
         .arg("checkout")
@@ -703,7 +791,7 @@ fn ensure_git_repo_cache(
         .arg(format!("origin/{effective_branch}"));
     run_git(cmd)?;
 
-    let mut cmd = Command::new("git");
+    let mut cmd = git_command(auth);
     cmd.arg("-C")
         .arg(&dir)
         .arg("reset")
@@ -715,7 +803,8 @@ fn ensure_git_repo_cache(
 }
 
 pub(super) fn ensure_repo_cache(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
+    auth: &RepoAuth,
     git_url: &str,
     branch: &str,
     refresh: bool,
@@ -727,9 +816,221 @@ pub(super) fn ensure_repo_cache(
 
     let branch = normalize_repo_branch(branch);
 
-    if let Some((owner, repo)) = parse_github_owner_repo(git_url) {
-        return ensure_github_repo_snapshot(app, git_url, &owner, &repo, &branch, refresh);
+    if let Some((owner, repo)) =
+        parse_github_owner_repo(git_url).filter(|_| !auth.requires_git_transport())
+    {
+        return ensure_github_repo_snapshot(
+            app,
+            auth.github_token(),
+            git_url,
+            &owner,
+            &repo,
+            &branch,
+            refresh,
+        );
+    }
+
+    ensure_git_repo_cache(app, auth, git_url, &branch, refresh)
+}
+
+fn github_ref_commit(
+    client: &reqwest::Client,
+    token: Option<&str>,
+    owner: &str,
+    repo: &str,
+    r#ref: &str,
+) -> crate::shared::error::AppResult<String> {
+    let url = github_api_url(&["repos", owner, repo, "commits", r#ref])?;
+    let client = client.clone();
+    tauri::async_runtime::block_on(async move {
+        let mut req = client.get(url);
+        if let Some(token) = token {
+            req = req.bearer_auth(token);
+        }
+        let resp = req
+            .header("Accept", "application/vnd.github.sha")
+            .send()
+            .await
+            .map_err(|e| format!("SKILL_HTTP_ERROR: github request failed: {e}"))?;
+
+        let status = resp.status();
+        let body = resp
+            .text()
+            .await
+            .map_err(|e| format!("SKILL_HTTP_ERROR: failed to read github response: {e}"))?;
+
+        if status == reqwest::StatusCode::NOT_FOUND
+            || status == reqwest::StatusCode::UNPROCESSABLE_ENTITY
+        {
+            return Err("SKILL_GITHUB_REF_NOT_FOUND: branch/ref not found".to_string());
+        }
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            return Err("SKILL_REPO_AUTH_FAILED: github rejected the repo token".to_string());
+        }
+        if status == reqwest::StatusCode::FORBIDDEN {
+            return Err(
+                "SKILL_GITHUB_FORBIDDEN: github request forbidden (rate limit?)".to_string(),
+            );
+        }
+        if !status.is_success() {
+            return Err(format!(
+                "SKILL_GITHUB_HTTP_ERROR: github returned http status {}",
+                status
+            ));
+        }
+
+        let sha = body.trim().to_ascii_lowercase();
+        if !is_hex_commit(&sha) {
+            return Err("SKILL_GITHUB_PARSE_ERROR: unexpected commit sha response".to_string());
+        }
+        Ok(sha)
+    })
+    .map_err(Into::into)
+}
+
+fn git_ls_remote_commit(
+    auth: &RepoAuth,
+    git_url: &str,
+    r#ref: &str,
+) -> crate::shared::error::AppResult<String> {
+    let mut cmd = git_command(auth);
+    cmd.arg("ls-remote").arg(git_url).arg(r#ref);
+    let out = run_git_capture(cmd)?;
+
+    // Prefer the peeled commit of annotated tags (`refs/tags/x^{}`) over the tag object itself.
+    let mut first: Option<&str> = None;
+    for line in out.lines() {
+        let Some((sha, name)) = line.split_once('\t') else {
+            continue;
+        };
+        if name.ends_with("^{}") {
+            return Ok(sha.trim().to_ascii_lowercase());
+        }
+        first.get_or_insert(sha.trim());
+    }
+    first
+        .map(|v| v.to_ascii_lowercase())
+        .ok_or_else(|| format!("SKILL_GIT_REF_NOT_FOUND: {}", r#ref).into())
+}
+
+/// Resolve a branch, tag or commit on the remote to a commit sha without touching the cache.
+pub(super) fn resolve_remote_commit(
+    auth: &RepoAuth,
+    git_url: &str,
+    r#ref: &str,
+) -> crate::shared::error::AppResult<String> {
+    let git_url = git_url.trim();
+    if git_url.is_empty() {
+        return Err("SEC_INVALID_INPUT: git_url is required".into());
+    }
+
+    let r#ref = normalize_repo_branch(r#ref);
+    let r#ref = if r#ref == "auto" {
+        "HEAD"
+    } else {
+        r#ref.as_str()
+    };
+
+    if let Some((owner, repo)) =
+        parse_github_owner_repo(git_url).filter(|_| !auth.requires_git_transport())
+    {
+        let client = build_github_client()?;
+        return github_ref_commit(&client, auth.github_token(), &owner, &repo, r#ref);
+    }
+
+    if r#ref.len() == 40 && is_hex_commit(r#ref) {
+        return Ok(r#ref.to_ascii_lowercase());
+    }
+    git_ls_remote_commit(auth, git_url, r#ref)
+}
+
+/// Fetch (or reuse) an immutable checkout of `git_url` at `commit`.
+pub(super) fn ensure_repo_at_commit(
+    app: &crate::AppHandle,
+    auth: &RepoAuth,
+    git_url: &str,
+    commit: &str,
+) -> crate::shared::error::AppResult<PathBuf> {
+    let git_url = git_url.trim();
+    let commit = commit.trim().to_ascii_lowercase();
+    if !is_hex_commit(&commit) {
+        return Err("SEC_INVALID_INPUT: invalid commit sha".into());
+    }
+
+    let dir = repo_cache_dir(app, git_url, &format!("commit:{commit}"))?;
+    if dir.join(REPO_COMMIT_FILE).exists() {
+        return Ok(dir);
+    }
+
+    if let Some(parent) = dir.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("failed to create {}: {e}", parent.display()))?;
     }
 
-    ensure_git_repo_cache(app, git_url, &branch, refresh)
+    let _lock = RepoLockGuard::acquire(lock_path_for_repo_dir(&dir))?;
+    if dir.join(REPO_COMMIT_FILE).exists() {
+        return Ok(dir);
+    }
+    remove_path_if_exists(&dir)?;
+
+    if let Some((owner, repo)) =
+        parse_github_owner_repo(git_url).filter(|_| !auth.requires_git_transport())
+    {
+        let client = build_github_client()?;
+        let zip_bytes =
+            github_download_zipball(&client, auth.github_token(), &owner, &repo, &commit)?;
+
+        let parent = dir
+            .parent()
+            .ok_or_else(|| "SEC_INVALID_INPUT: invalid repo cache dir".to_string())?;
+        let staging = parent.join(format!(".commit-{commit}.staging-{}", now_unix_nanos()));
+        let _ = remove_path_if_exists(&staging);
+
+        let result = unzip_repo_zip(&zip_bytes, &staging).and_then(|extracted_root| {
+            write_repo_commit(&extracted_root, &commit)?;
+            std::fs::rename(&extracted_root, &dir).map_err(|e| {
+                format!(
+                    "SKILL_REPO_UPDATE_FAILED: failed to activate repo snapshot {}: {e}",
+                    dir.display()
+                )
+                .into()
+            })
+        });
+        let _ = remove_path_if_exists(&staging);
+        result?;
+        return Ok(dir);
+    }
+
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("failed to create {}: {e}", dir.display()))?;
+    let result = (|| {
+        let mut cmd = git_command(auth);
+        cmd.arg("-C").arg(&dir).arg("init").arg("--quiet");
+        run_git(cmd)?;
+
+        let mut cmd = git_command(auth);
+        cmd.arg("-C")
+            .arg(&dir)
+            .arg("fetch")
+            .arg("--depth")
+            .arg("1")
+            .arg(git_url)
+            .arg(&commit);
+        run_git(cmd)?;
+
+        let mut cmd = git_command(auth);
+        cmd.arg("-C")
+            .arg(&dir)
+            .arg("checkout")
+            .arg("--detach")
+            .arg("FETCH_HEAD");
+        run_git(cmd)?;
+
+        write_repo_commit(&dir, &commit)
+    })();
+    if let Err(err) = result {
+        let _ = remove_path_if_exists(&dir);
+        return Err(err);
+    }
+    Ok(dir)
 }
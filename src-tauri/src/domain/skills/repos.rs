@@ -1,5 +1,6 @@
 use super::git_url::{canonical_git_url_key, normalize_repo_branch};
-use super::types::SkillRepoSummary;
+use super::repo_auth::{attach_auth_summary, delete_auth_for_git_url};
+use super::types::{SkillRepoAuthKind, SkillRepoSummary};
 use crate::db;
 use crate::shared::error::db_err;
 use crate::shared::sqlite::enabled_to_int;
@@ -13,14 +14,20 @@ fn row_to_repo(row: &rusqlite::Row<'_>) -> Result<SkillRepoSummary, rusqlite::Er
         git_url: row.get("git_url")?,
         branch: row.get("branch")?,
         enabled: row.get::<_, i64>("enabled")? != 0,
+        auth_kind: SkillRepoAuthKind::None,
+        ssh_key_path: None,
         created_at: row.get("created_at")?,
         updated_at: row.get("updated_at")?,
     })
 }
 
-fn get_repo_by_id(conn: &Connection, repo_id: i64) -> Result<SkillRepoSummary, String> {
-    conn.query_row(
-        r#"
+pub(super) fn get_repo_by_id(
+    conn: &Connection,
+    repo_id: i64,
+) -> crate::shared::error::AppResult<SkillRepoSummary> {
+    let mut repo = conn
+        .query_row(
+            r#"
 SELECT
   id,
   git_url,
@@ -31,12 +38,14 @@ SELECT
 FROM skill_repos
 WHERE id = ?1
 "#,
-        params![repo_id],
-        row_to_repo,
-    )
-    .optional()
-    .map_err(|e| db_err!("failed to query repo: {e}"))?
-    .ok_or_else(|| "DB_NOT_FOUND: skill repo not found".to_string())
+            params![repo_id],
+            row_to_repo,
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query repo: {e}"))?
+        .ok_or_else(|| "DB_NOT_FOUND: skill repo not found".to_string())?;
+    attach_auth_summary(conn, &mut repo)?;
+    Ok(repo)
 }
 
 pub fn repos_list(db: &db::Db) -> crate::shared::error::AppResult<Vec<SkillRepoSummary>> {
@@ -81,6 +90,9 @@ pub fn repos_list(db: &db::Db) -> crate::shared::error::AppResult<Vec<SkillRepoS
             deduped.push(row);
         }
     }
+    for repo in &mut deduped {
+        attach_auth_summary(&conn, repo)?;
+    }
 
     Ok(deduped)
 }
@@ -211,11 +223,13 @@ WHERE id = ?5
 
 pub fn repo_delete(db: &db::Db, repo_id: i64) -> crate::shared::error::AppResult<()> {
     let conn = db.open_connection()?;
+    let repo = get_repo_by_id(&conn, repo_id)?;
     let changed = conn
         .execute("DELETE FROM skill_repos WHERE id = ?1", params![repo_id])
         .map_err(|e| db_err!("failed to delete skill repo: {e}"))?;
     if changed == 0 {
         return Err("DB_NOT_FOUND: skill repo not found".to_string().into());
     }
+    delete_auth_for_git_url(&conn, &repo.git_url)?;
     Ok(())
 }
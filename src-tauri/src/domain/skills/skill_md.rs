@@ -41,8 +41,7 @@ fn parse_front_matter(text: &str) -> BTreeMap<String, String> {
     out
 }
 
-pub(super) fn parse_skill_md(skill_md_path: &Path) -> Result<(String, String), String> {
-    let text = read_to_string(skill_md_path)?;
+fn front_matter_block(text: &str) -> Result<String, String> {
     let text = text.trim_start();
     let mut lines = text.lines();
     let Some(first) = lines.next() else {
@@ -60,6 +59,12 @@ pub(super) fn parse_skill_md(skill_md_path: &Path) -> Result<(String, String), S
         fm.push_str(line);
         fm.push('\n');
     }
+    Ok(fm)
+}
+
+pub(super) fn parse_skill_md(skill_md_path: &Path) -> Result<(String, String), String> {
+    let text = read_to_string(skill_md_path)?;
+    let fm = front_matter_block(&text)?;
 
     let map = parse_front_matter(&fm);
     let name = map.get("name").cloned().unwrap_or_default();
@@ -72,6 +77,96 @@ pub(super) fn parse_skill_md(skill_md_path: &Path) -> Result<(String, String), S
     Ok((name.trim().to_string(), desc.trim().to_string()))
 }
 
+/// Prerequisites declared in SKILL.md front matter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct SkillDependencies {
+    pub skills: Vec<String>,
+    pub mcp_servers: Vec<String>,
+    pub binaries: Vec<String>,
+}
+
+impl SkillDependencies {
+    pub(super) fn is_empty(&self) -> bool {
+        self.skills.is_empty() && self.mcp_servers.is_empty() && self.binaries.is_empty()
+    }
+}
+
+fn push_dependency(list: &mut Vec<String>, raw: &str) {
+    let value = strip_quotes(raw).trim();
+    if !value.is_empty() && !list.iter().any(|v| v == value) {
+        list.push(value.to_string());
+    }
+}
+
+/// Parses the `requires:` (or `dependencies:`) block of the front matter:
+///
+/// ```yaml
+/// requires:
+///   skills: [git-helper]
+///   mcp_servers:
+///     - context7
+///   binaries: rg, jq
+/// ```
+fn parse_dependencies(front_matter: &str) -> SkillDependencies {
+    let mut deps = SkillDependencies::default();
+    let mut in_block = false;
+    let mut current_key: Option<&'static str> = None;
+
+    for line in front_matter.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '\t']) {
+            in_block = matches!(
+                trimmed.split_once(':'),
+                Some((k, v)) if matches!(k.trim(), "requires" | "dependencies") && v.trim().is_empty()
+            );
+            current_key = None;
+            continue;
+        }
+        if !in_block {
+            continue;
+        }
+
+        let value = if let Some(item) = trimmed.strip_prefix("- ") {
+            item
+        } else if let Some((k, v)) = trimmed.split_once(':') {
+            current_key = match k.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+                "skills" => Some("skills"),
+                "mcp" | "mcp_servers" => Some("mcp_servers"),
+                "bins" | "binaries" | "commands" => Some("binaries"),
+                _ => None,
+            };
+            v
+        } else {
+            continue;
+        };
+
+        let list = match current_key {
+            Some("skills") => &mut deps.skills,
+            Some("mcp_servers") => &mut deps.mcp_servers,
+            Some("binaries") => &mut deps.binaries,
+            _ => continue,
+        };
+        let value = value.trim();
+        let value = value
+            .strip_prefix('[')
+            .and_then(|v| v.strip_suffix(']'))
+            .unwrap_or(value);
+        for item in value.split(',') {
+            push_dependency(list, item);
+        }
+    }
+
+    deps
+}
+
+pub(super) fn parse_skill_dependencies(skill_md_path: &Path) -> Result<SkillDependencies, String> {
+    let text = read_to_string(skill_md_path)?;
+    Ok(parse_dependencies(&front_matter_block(&text)?))
+}
+
 pub(super) fn find_skill_md_files(root: &Path) -> Result<Vec<PathBuf>, String> {
     let mut out = Vec::new();
     let mut stack = vec![root.to_path_buf()];
@@ -1,16 +1,38 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillRepoAuthKind {
+    None,
+    Token,
+    SshKey,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct SkillRepoSummary {
     pub id: i64,
     pub git_url: String,
     pub branch: String,
     pub enabled: bool,
+    /// Credential used for private hosts; a stored token is never returned.
+    pub auth_kind: SkillRepoAuthKind,
+    pub ssh_key_path: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SkillRepoAccessReport {
+    pub ok: bool,
+    pub auth_kind: SkillRepoAuthKind,
+    /// Remote commit of the repo branch when access succeeded.
+    pub commit: Option<String>,
+    /// e.g. `SKILL_REPO_AUTH_FAILED`, `SKILL_GITHUB_REPO_NOT_FOUND`, `SKILL_GIT_ERROR`.
+    pub error_code: Option<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct InstalledSkillSummary {
     pub id: i64,
     pub skill_key: String,
@@ -20,11 +42,18 @@ pub struct InstalledSkillSummary {
     pub source_branch: String,
     pub source_subdir: String,
     pub enabled: bool,
+    /// Tag, branch or commit the skill is pinned to; `None` tracks `source_branch`.
+    pub pinned_ref: Option<String>,
+    pub installed_commit: Option<String>,
+    /// Remote commit seen by the last `skills_check_updates` run.
+    pub latest_commit: Option<String>,
+    pub update_available: bool,
+    pub last_update_changed_files: Vec<SkillChangedFile>,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct AvailableSkillSummary {
     pub name: String,
     pub description: String,
@@ -34,14 +63,14 @@ pub struct AvailableSkillSummary {
     pub installed: bool,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct SkillsPaths {
     pub ssot_dir: String,
     pub repos_dir: String,
     pub cli_dir: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct LocalSkillSummary {
     pub dir_name: String,
     pub path: String,
@@ -49,16 +78,115 @@ pub struct LocalSkillSummary {
     pub description: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct SkillImportIssue {
     pub dir_name: String,
     pub error_code: Option<String>,
     pub message: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct SkillImportLocalBatchReport {
     pub imported: Vec<InstalledSkillSummary>,
     pub skipped: Vec<SkillImportIssue>,
     pub failed: Vec<SkillImportIssue>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SkillChangedFile {
+    pub path: String,
+    /// One of `added`, `modified`, `removed`.
+    pub change: String,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SkillUpdateStatus {
+    pub skill_id: i64,
+    pub skill_key: String,
+    pub name: String,
+    pub pinned_ref: Option<String>,
+    pub installed_commit: Option<String>,
+    pub latest_commit: Option<String>,
+    pub update_available: bool,
+    pub error: Option<String>,
+    pub checked_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SkillUpdateReport {
+    pub skill_id: i64,
+    pub from_commit: Option<String>,
+    pub to_commit: String,
+    pub changed_files: Vec<SkillChangedFile>,
+    /// `false` for previews (`dry_run`), where nothing on disk or in the db was touched.
+    pub applied: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillDependencyKind {
+    Skill,
+    McpServer,
+    Binary,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillDependencyStatus {
+    Satisfied,
+    /// Installed/configured but not enabled in the target workspace.
+    Disabled,
+    Missing,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SkillDependency {
+    pub kind: SkillDependencyKind,
+    pub name: String,
+    pub status: SkillDependencyStatus,
+    /// Resolved binary path, or the id of the installed skill / MCP server.
+    pub detail: Option<String>,
+    /// For missing skills: a matching skill found in the same source repository.
+    pub install_candidate: Option<AvailableSkillSummary>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SkillDependencyReport {
+    pub skill_name: String,
+    /// True when every declared dependency is satisfied; enabling is blocked otherwise.
+    pub satisfied: bool,
+    pub dependencies: Vec<SkillDependency>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SkillPackageFile {
+    /// Path relative to the skill directory, `/`-separated.
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SkillPackagePush {
+    pub repo_id: i64,
+    pub git_url: String,
+    pub branch: String,
+    pub commit: String,
+    /// `false` when the repo already held an identical copy and nothing was pushed.
+    pub changed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SkillPackageReport {
+    pub name: String,
+    pub description: String,
+    pub dir_name: String,
+    pub archive_path: String,
+    /// SHA-256 of the archive, also written next to it as `<archive>.sha256`.
+    pub checksum_sha256: String,
+    pub files: Vec<SkillPackageFile>,
+    pub total_bytes: u64,
+    /// Non-blocking lint findings; blocking ones fail the command instead.
+    pub warnings: Vec<String>,
+    pub push: Option<SkillPackagePush>,
+}
@@ -0,0 +1,418 @@
+//! Usage: Version pinning and update checks for skills installed from git repos.
+
+use super::fs_ops::{copy_dir_recursive, has_skill_md, is_managed_dir};
+use super::installed::{get_skill_by_id, update_available};
+use super::ops::{local_source_cli_key, sync_to_cli};
+use super::paths::{cli_skills_root, ensure_skills_roots, ssot_skills_root};
+use super::repo_auth::auth_for_git_url;
+use super::repo_cache::{commits_match, ensure_repo_at_commit, resolve_remote_commit};
+use super::skill_md::parse_skill_md;
+use super::types::{InstalledSkillSummary, SkillChangedFile, SkillUpdateReport, SkillUpdateStatus};
+use super::util::{now_unix_nanos, validate_relative_subdir};
+use crate::db;
+use crate::shared::cli_key::SUPPORTED_CLI_KEYS;
+use crate::shared::error::db_err;
+use crate::shared::text::normalize_name;
+use crate::shared::time::now_unix_seconds;
+use rusqlite::{params, Connection};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+const MAX_PINNED_REF_LEN: usize = 200;
+
+const CHANGE_ADDED: &str = "added";
+const CHANGE_MODIFIED: &str = "modified";
+const CHANGE_REMOVED: &str = "removed";
+
+pub(super) fn validate_pinned_ref(value: &str) -> crate::shared::error::AppResult<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err("SEC_INVALID_INPUT: pinned_ref is required".into());
+    }
+    if value.len() > MAX_PINNED_REF_LEN {
+        return Err("SEC_INVALID_INPUT: pinned_ref is too long".into());
+    }
+    // Refs are passed to git as arguments: reject option-looking and path-escaping values.
+    if value.starts_with('-') || value.contains("..") || value.ends_with('/') {
+        return Err(format!("SEC_INVALID_INPUT: invalid pinned_ref={value}").into());
+    }
+    let valid_chars = value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '/'));
+    if !valid_chars {
+        return Err(format!("SEC_INVALID_INPUT: invalid pinned_ref={value}").into());
+    }
+    Ok(value.to_string())
+}
+
+fn is_repo_skill(skill: &InstalledSkillSummary) -> bool {
+    local_source_cli_key(&skill.source_git_url).is_none()
+}
+
+fn target_ref(skill: &InstalledSkillSummary) -> &str {
+    skill
+        .pinned_ref
+        .as_deref()
+        .unwrap_or(skill.source_branch.as_str())
+}
+
+fn is_ignored_entry(name: &str) -> bool {
+    name == ".git" || name.starts_with(".aio-coding-hub.")
+}
+
+fn collect_files(
+    root: &Path,
+    dir: &Path,
+    out: &mut BTreeMap<String, PathBuf>,
+) -> crate::shared::error::AppResult<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("failed to read dir {}: {e}", dir.display()))?;
+    for entry in entries {
+        let entry =
+            entry.map_err(|e| format!("failed to read dir entry {}: {e}", dir.display()))?;
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if is_ignored_entry(&name) {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(root, &path, out)?;
+            continue;
+        }
+        let rel = path
+            .strip_prefix(root)
+            .map_err(|e| format!("failed to relativize {}: {e}", path.display()))?
+            .to_string_lossy()
+            .replace('\\', "/");
+        out.insert(rel, path);
+    }
+    Ok(())
+}
+
+/// File-level diff between the installed SSOT copy and a candidate source dir.
+pub(super) fn diff_skill_dirs(
+    current_dir: &Path,
+    next_dir: &Path,
+) -> crate::shared::error::AppResult<Vec<SkillChangedFile>> {
+    let mut current = BTreeMap::new();
+    collect_files(current_dir, current_dir, &mut current)?;
+    let mut next = BTreeMap::new();
+    collect_files(next_dir, next_dir, &mut next)?;
+
+    let mut changes = Vec::new();
+    for (rel, next_path) in &next {
+        let change = match current.get(rel) {
+            None => CHANGE_ADDED,
+            Some(current_path) => {
+                let a = std::fs::read(current_path)
+                    .map_err(|e| format!("failed to read {}: {e}", current_path.display()))?;
+                let b = std::fs::read(next_path)
+                    .map_err(|e| format!("failed to read {}: {e}", next_path.display()))?;
+                if a == b {
+                    continue;
+                }
+                CHANGE_MODIFIED
+            }
+        };
+        changes.push(SkillChangedFile {
+            path: rel.clone(),
+            change: change.to_string(),
+        });
+    }
+    for rel in current.keys() {
+        if !next.contains_key(rel) {
+            changes.push(SkillChangedFile {
+                path: rel.clone(),
+                change: CHANGE_REMOVED.to_string(),
+            });
+        }
+    }
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(changes)
+}
+
+fn list_skill_ids(conn: &Connection) -> crate::shared::error::AppResult<Vec<i64>> {
+    let mut stmt = conn
+        .prepare_cached("SELECT id FROM skills ORDER BY id ASC")
+        .map_err(|e| db_err!("failed to prepare skills query: {e}"))?;
+    let rows = stmt
+        .query_map([], |row| row.get::<_, i64>(0))
+        .map_err(|e| db_err!("failed to list skills: {e}"))?;
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row.map_err(|e| db_err!("failed to read skill row: {e}"))?);
+    }
+    Ok(out)
+}
+
+/// Compare each repo skill's installed commit against the remote commit of its pin (or branch),
+/// recording the result on the skill row. Per-skill failures are reported, not raised.
+pub fn check_updates(db: &db::Db) -> crate::shared::error::AppResult<Vec<SkillUpdateStatus>> {
+    let conn = db.open_connection()?;
+    let now = now_unix_seconds();
+
+    // Several skills usually come from the same repo; resolve each (url, ref) once.
+    let mut resolved: HashMap<(String, String), Result<String, String>> = HashMap::new();
+    let mut out = Vec::new();
+
+    for skill_id in list_skill_ids(&conn)? {
+        let skill = get_skill_by_id(&conn, skill_id)?;
+        if !is_repo_skill(&skill) {
+            continue;
+        }
+
+        let key = (skill.source_git_url.clone(), target_ref(&skill).to_string());
+        let remote = resolved
+            .entry(key)
+            .or_insert_with_key(|(url, r#ref)| {
+                auth_for_git_url(&conn, url)
+                    .and_then(|auth| resolve_remote_commit(&auth, url, r#ref))
+                    .map_err(|e| e.to_string())
+            })
+            .clone();
+
+        let (latest_commit, error) = match remote {
+            Ok(commit) => {
+                conn.execute(
+                    "UPDATE skills SET latest_commit = ?1, update_checked_at = ?2 WHERE id = ?3",
+                    params![commit, now, skill.id],
+                )
+                .map_err(|e| db_err!("failed to record skill update check: {e}"))?;
+                (Some(commit), None)
+            }
+            Err(err) => {
+                tracing::warn!(
+                    skill_id = skill.id,
+                    source_git_url = %skill.source_git_url,
+                    "skill update check failed: {}",
+                    err
+                );
+                (skill.latest_commit.clone(), Some(err))
+            }
+        };
+
+        out.push(SkillUpdateStatus {
+            skill_id: skill.id,
+            skill_key: skill.skill_key,
+            name: skill.name,
+            pinned_ref: skill.pinned_ref,
+            update_available: update_available(
+                skill.installed_commit.as_deref(),
+                latest_commit.as_deref(),
+            ),
+            installed_commit: skill.installed_commit,
+            latest_commit,
+            error,
+            checked_at: now,
+        });
+    }
+
+    Ok(out)
+}
+
+/// Pin a skill to a tag/branch/commit, or pass `None` to follow its source branch again.
+pub fn set_pin(
+    db: &db::Db,
+    skill_id: i64,
+    pinned_ref: Option<&str>,
+) -> crate::shared::error::AppResult<InstalledSkillSummary> {
+    let conn = db.open_connection()?;
+    let skill = get_skill_by_id(&conn, skill_id)?;
+    if !is_repo_skill(&skill) {
+        return Err("SKILL_PIN_UNSUPPORTED: local skills have no remote to pin".into());
+    }
+
+    let pinned_ref = match pinned_ref.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(v) => Some(validate_pinned_ref(v)?),
+        None => None,
+    };
+
+    // The pin changes what "latest" means; drop the stale check result.
+    conn.execute(
+        r#"
+UPDATE skills
+SET pinned_ref = ?1, latest_commit = NULL, update_checked_at = NULL, updated_at = ?2
+WHERE id = ?3
+"#,
+        params![pinned_ref, now_unix_seconds(), skill_id],
+    )
+    .map_err(|e| db_err!("failed to update skill pin: {e}"))?;
+
+    get_skill_by_id(&conn, skill_id)
+}
+
+fn resync_managed_targets(
+    app: &crate::AppHandle,
+    skill_key: &str,
+    ssot_dir: &Path,
+) -> crate::shared::error::AppResult<()> {
+    for cli_key in SUPPORTED_CLI_KEYS {
+        let target = cli_skills_root(app, cli_key)?.join(skill_key);
+        if target.exists() && is_managed_dir(&target) {
+            sync_to_cli(app, cli_key, skill_key, ssot_dir)?;
+        }
+    }
+    Ok(())
+}
+
+fn restore_ssot_backup(app: &crate::AppHandle, skill_key: &str, ssot_dir: &Path, backup: &Path) {
+    let _ = std::fs::remove_dir_all(ssot_dir);
+    if std::fs::rename(backup, ssot_dir).is_ok() {
+        let _ = resync_managed_targets(app, skill_key, ssot_dir);
+    }
+}
+
+/// Move an installed skill to the remote commit of its pin (or branch).
+///
+/// With `dry_run` only the changed-files preview is computed.
+pub fn update(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    skill_id: i64,
+    dry_run: bool,
+) -> crate::shared::error::AppResult<SkillUpdateReport> {
+    ensure_skills_roots(app)?;
+
+    let conn = db.open_connection()?;
+    let skill = get_skill_by_id(&conn, skill_id)?;
+    if !is_repo_skill(&skill) {
+        return Err("SKILL_UPDATE_UNSUPPORTED: local skills cannot be updated from a repo".into());
+    }
+    validate_relative_subdir(&skill.source_subdir)?;
+
+    let auth = auth_for_git_url(&conn, &skill.source_git_url)?;
+    let to_commit = resolve_remote_commit(&auth, &skill.source_git_url, target_ref(&skill))?;
+    let repo_dir = ensure_repo_at_commit(app, &auth, &skill.source_git_url, &to_commit)?;
+    let src_dir = repo_dir.join(skill.source_subdir.trim());
+    if !src_dir.is_dir() || !has_skill_md(&src_dir) {
+        return Err(format!(
+            "SKILL_SOURCE_NOT_FOUND: {} at {}",
+            skill.source_subdir, to_commit
+        )
+        .into());
+    }
+
+    let ssot_root = ssot_skills_root(app)?;
+    let ssot_dir = ssot_root.join(&skill.skill_key);
+    let changed_files = diff_skill_dirs(&ssot_dir, &src_dir)?;
+
+    let report = SkillUpdateReport {
+        skill_id,
+        from_commit: skill.installed_commit.clone(),
+        to_commit: to_commit.clone(),
+        changed_files,
+        applied: false,
+    };
+    if dry_run {
+        return Ok(report);
+    }
+
+    let (name, description) = parse_skill_md(&src_dir.join("SKILL.md"))?;
+    let now = now_unix_seconds();
+    let changed_files_json = serde_json::to_string(&report.changed_files)
+        .map_err(|e| format!("SYSTEM_ERROR: failed to serialize changed files: {e}"))?;
+
+    let unchanged = report.changed_files.is_empty()
+        && skill
+            .installed_commit
+            .as_deref()
+            .is_some_and(|v| commits_match(v, &to_commit));
+
+    if !report.changed_files.is_empty() {
+        // Swap the SSOT dir: copy into staging, move the old copy aside, activate the new one.
+        let nonce = now_unix_nanos();
+        let staging = ssot_root.join(format!(".{}.update-{nonce}", skill.skill_key));
+        let backup = ssot_root.join(format!(".{}.old-{nonce}", skill.skill_key));
+        if let Err(err) = copy_dir_recursive(&src_dir, &staging) {
+            let _ = std::fs::remove_dir_all(&staging);
+            return Err(err);
+        }
+        let had_ssot = ssot_dir.exists();
+        if had_ssot {
+            if let Err(err) = std::fs::rename(&ssot_dir, &backup) {
+                let _ = std::fs::remove_dir_all(&staging);
+                return Err(format!(
+                    "SKILL_UPDATE_FAILED: failed to move {} aside: {err}",
+                    ssot_dir.display()
+                )
+                .into());
+            }
+        }
+        if let Err(err) = std::fs::rename(&staging, &ssot_dir) {
+            let _ = std::fs::remove_dir_all(&staging);
+            if had_ssot {
+                let _ = std::fs::rename(&backup, &ssot_dir);
+            }
+            return Err(format!(
+                "SKILL_UPDATE_FAILED: failed to activate {}: {err}",
+                ssot_dir.display()
+            )
+            .into());
+        }
+
+        if let Err(err) = resync_managed_targets(app, &skill.skill_key, &ssot_dir) {
+            if had_ssot {
+                restore_ssot_backup(app, &skill.skill_key, &ssot_dir, &backup);
+            }
+            return Err(err);
+        }
+
+        if let Err(err) = conn.execute(
+            r#"
+UPDATE skills
+SET name = ?1,
+    normalized_name = ?2,
+    description = ?3,
+    installed_commit = ?4,
+    latest_commit = ?4,
+    update_checked_at = ?5,
+    last_update_changed_files = ?6,
+    updated_at = ?5
+WHERE id = ?7
+"#,
+            params![
+                name.trim(),
+                normalize_name(&name),
+                description,
+                to_commit,
+                now,
+                changed_files_json,
+                skill_id
+            ],
+        ) {
+            if had_ssot {
+                restore_ssot_backup(app, &skill.skill_key, &ssot_dir, &backup);
+            }
+            return Err(db_err!("failed to record skill update: {err}"));
+        }
+
+        let _ = std::fs::remove_dir_all(&backup);
+    } else if !unchanged {
+        // Same content under a new commit: just move the pin forward.
+        conn.execute(
+            r#"
+UPDATE skills
+SET installed_commit = ?1, latest_commit = ?1, update_checked_at = ?2, updated_at = ?2
+WHERE id = ?3
+"#,
+            params![to_commit, now, skill_id],
+        )
+        .map_err(|e| db_err!("failed to record skill update: {e}"))?;
+    }
+
+    tracing::info!(
+        skill_id,
+        skill_key = %skill.skill_key,
+        to_commit = %to_commit,
+        changed_files = report.changed_files.len(),
+        "skill updated"
+    );
+
+    Ok(SkillUpdateReport {
+        applied: true,
+        ..report
+    })
+}
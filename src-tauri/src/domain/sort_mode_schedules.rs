@@ -0,0 +1,472 @@
+//! Usage: Time/calendar based switching of the active sort mode (rules + evaluation).
+
+use crate::db;
+use crate::shared::error::db_err;
+use crate::shared::time::now_unix_seconds;
+use crate::sort_modes;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// Bit 0 = Sunday ... bit 6 = Saturday (matches SQLite `strftime('%w')`).
+const ALL_DAYS_MASK: u32 = 0b111_1111;
+const MAX_RULES_PER_CLI: usize = 32;
+/// UTC-12:00 ..= UTC+14:00.
+const MIN_UTC_OFFSET_MINUTES: i32 = -12 * 60;
+const MAX_UTC_OFFSET_MINUTES: i32 = 14 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SortModeScheduleRule {
+    /// Weekdays the rule applies to, bit 0 = Sunday ... bit 6 = Saturday. For windows that wrap
+    /// midnight the day is the one the window starts on.
+    pub days_mask: u32,
+    /// `HH:MM` window (end exclusive, may wrap midnight); both `None` covers the whole day.
+    pub start: Option<String>,
+    pub end: Option<String>,
+    /// Mode to activate; `None` is the default provider ordering.
+    pub mode_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SortModeSchedule {
+    pub cli_key: String,
+    pub enabled: bool,
+    /// Fixed UTC offset the rules are evaluated in; `None` follows the system time zone.
+    pub utc_offset_minutes: Option<i32>,
+    /// Evaluated in order, first match wins.
+    pub rules: Vec<SortModeScheduleRule>,
+    /// When no rule matches, switch to `otherwise_mode_id`; when off, the current mode is kept.
+    pub otherwise_enabled: bool,
+    pub otherwise_mode_id: Option<i64>,
+    pub updated_at: i64,
+}
+
+/// Mode a schedule asks for at a given instant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SortModeScheduleTarget {
+    pub(crate) cli_key: String,
+    pub(crate) mode_id: Option<i64>,
+    /// Index of the matching rule; `None` when the `otherwise` mode applies.
+    pub(crate) rule_index: Option<usize>,
+    pub(crate) local_time: String,
+}
+
+fn bool_to_int(value: bool) -> i64 {
+    if value {
+        1
+    } else {
+        0
+    }
+}
+
+fn normalize_rule(
+    rule: SortModeScheduleRule,
+) -> crate::shared::error::AppResult<SortModeScheduleRule> {
+    if rule.days_mask == 0 || rule.days_mask & !ALL_DAYS_MASK != 0 {
+        return Err(format!(
+            "SEC_INVALID_INPUT: invalid days_mask={} (expected 1..=127)",
+            rule.days_mask
+        )
+        .into());
+    }
+    let window = sort_modes::normalize_time_window(rule.start.as_deref(), rule.end.as_deref())?;
+    let (start, end) = match window {
+        Some((start, end)) => (Some(start), Some(end)),
+        None => (None, None),
+    };
+    Ok(SortModeScheduleRule {
+        days_mask: rule.days_mask,
+        start,
+        end,
+        mode_id: rule.mode_id,
+    })
+}
+
+fn validate_utc_offset(utc_offset_minutes: Option<i32>) -> crate::shared::error::AppResult<()> {
+    match utc_offset_minutes {
+        Some(v) if !(MIN_UTC_OFFSET_MINUTES..=MAX_UTC_OFFSET_MINUTES).contains(&v) => {
+            Err(format!("SEC_INVALID_INPUT: utc_offset_minutes out of range: {v}").into())
+        }
+        _ => Ok(()),
+    }
+}
+
+fn day_enabled(days_mask: u32, weekday: u32) -> bool {
+    days_mask & (1 << (weekday % 7)) != 0
+}
+
+/// `weekday` follows `strftime('%w')` (0 = Sunday); `now` is zero-padded `HH:MM`.
+fn rule_matches(rule: &SortModeScheduleRule, weekday: u32, now: &str) -> bool {
+    let (Some(start), Some(end)) = (rule.start.as_deref(), rule.end.as_deref()) else {
+        return day_enabled(rule.days_mask, weekday);
+    };
+    if start <= end {
+        return day_enabled(rule.days_mask, weekday)
+            && sort_modes::is_within_time_window(start, end, now);
+    }
+    // Overnight window: the part after midnight belongs to the previous day's rule.
+    let yesterday = (weekday + 6) % 7;
+    (now >= start && day_enabled(rule.days_mask, weekday))
+        || (now < end && day_enabled(rule.days_mask, yesterday))
+}
+
+fn select_target(
+    schedule: &SortModeSchedule,
+    weekday: u32,
+    now: &str,
+) -> Option<(Option<usize>, Option<i64>)> {
+    if let Some((idx, rule)) = schedule
+        .rules
+        .iter()
+        .enumerate()
+        .find(|(_, rule)| rule_matches(rule, weekday, now))
+    {
+        return Some((Some(idx), rule.mode_id));
+    }
+    schedule
+        .otherwise_enabled
+        .then_some((None, schedule.otherwise_mode_id))
+}
+
+fn local_clock(
+    conn: &Connection,
+    now_unix: i64,
+    utc_offset_minutes: Option<i32>,
+) -> crate::shared::error::AppResult<(u32, String)> {
+    let modifier = match utc_offset_minutes {
+        Some(offset) => format!("{offset:+} minutes"),
+        None => "localtime".to_string(),
+    };
+    let (weekday, local_time): (String, String) = conn
+        .query_row(
+            "SELECT strftime('%w', ?1, 'unixepoch', ?2), strftime('%H:%M', ?1, 'unixepoch', ?2)",
+            params![now_unix, modifier],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|e| db_err!("failed to compute schedule local time: {e}"))?;
+    let weekday = weekday
+        .parse::<u32>()
+        .map_err(|e| db_err!("invalid weekday from sqlite: {weekday}: {e}"))?;
+    Ok((weekday, local_time))
+}
+
+fn read_rules(
+    conn: &Connection,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<Vec<SortModeScheduleRule>> {
+    let mut stmt = conn
+        .prepare_cached(
+            r#"
+SELECT
+  days_mask,
+  start_time,
+  end_time,
+  mode_id
+FROM sort_mode_schedule_rules
+WHERE cli_key = ?1
+ORDER BY position ASC
+"#,
+        )
+        .map_err(|e| db_err!("failed to prepare sort_mode_schedule_rules query: {e}"))?;
+
+    let rows = stmt
+        .query_map(params![cli_key], |row| {
+            Ok(SortModeScheduleRule {
+                days_mask: row
+                    .get::<_, i64>("days_mask")?
+                    .clamp(0, ALL_DAYS_MASK as i64) as u32,
+                start: row.get("start_time")?,
+                end: row.get("end_time")?,
+                mode_id: row.get("mode_id")?,
+            })
+        })
+        .map_err(|e| db_err!("failed to list sort_mode_schedule_rules: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| db_err!("failed to read sort_mode_schedule_rule row: {e}"))?);
+    }
+    Ok(items)
+}
+
+fn read_schedule(
+    conn: &Connection,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<SortModeSchedule> {
+    let header = conn
+        .query_row(
+            r#"
+SELECT
+  enabled,
+  utc_offset_minutes,
+  otherwise_enabled,
+  otherwise_mode_id,
+  updated_at
+FROM sort_mode_schedules
+WHERE cli_key = ?1
+"#,
+            params![cli_key],
+            |row| {
+                Ok((
+                    row.get::<_, i64>("enabled")? != 0,
+                    row.get::<_, Option<i32>>("utc_offset_minutes")?,
+                    row.get::<_, i64>("otherwise_enabled")? != 0,
+                    row.get::<_, Option<i64>>("otherwise_mode_id")?,
+                    row.get::<_, i64>("updated_at")?,
+                ))
+            },
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query sort_mode_schedule: {e}"))?;
+
+    let (enabled, utc_offset_minutes, otherwise_enabled, otherwise_mode_id, updated_at) =
+        header.unwrap_or((false, None, false, None, 0));
+    Ok(SortModeSchedule {
+        cli_key: cli_key.to_string(),
+        enabled,
+        utc_offset_minutes,
+        rules: read_rules(conn, cli_key)?,
+        otherwise_enabled,
+        otherwise_mode_id,
+        updated_at,
+    })
+}
+
+pub fn get_schedule(
+    db: &db::Db,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<SortModeSchedule> {
+    let cli_key = cli_key.trim();
+    crate::shared::cli_key::validate_cli_key(cli_key)?;
+    let conn = db.open_connection()?;
+    read_schedule(&conn, cli_key)
+}
+
+pub fn set_schedule(
+    db: &db::Db,
+    cli_key: &str,
+    enabled: bool,
+    utc_offset_minutes: Option<i32>,
+    rules: Vec<SortModeScheduleRule>,
+    otherwise_enabled: bool,
+    otherwise_mode_id: Option<i64>,
+) -> crate::shared::error::AppResult<SortModeSchedule> {
+    let cli_key = cli_key.trim();
+    crate::shared::cli_key::validate_cli_key(cli_key)?;
+    validate_utc_offset(utc_offset_minutes)?;
+    if rules.len() > MAX_RULES_PER_CLI {
+        return Err(format!(
+            "SEC_INVALID_INPUT: too many schedule rules (max {MAX_RULES_PER_CLI})"
+        )
+        .into());
+    }
+    let rules = rules
+        .into_iter()
+        .map(normalize_rule)
+        .collect::<crate::shared::error::AppResult<Vec<_>>>()?;
+
+    let mut conn = db.open_connection()?;
+    for mode_id in rules
+        .iter()
+        .filter_map(|rule| rule.mode_id)
+        .chain(otherwise_mode_id.filter(|_| otherwise_enabled))
+    {
+        sort_modes::ensure_mode_exists(&conn, mode_id)?;
+    }
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| db_err!("failed to start transaction: {e}"))?;
+
+    let now = now_unix_seconds();
+    tx.execute(
+        r#"
+INSERT INTO sort_mode_schedules(
+  cli_key,
+  enabled,
+  utc_offset_minutes,
+  otherwise_enabled,
+  otherwise_mode_id,
+  updated_at
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+ON CONFLICT(cli_key) DO UPDATE SET
+  enabled = excluded.enabled,
+  utc_offset_minutes = excluded.utc_offset_minutes,
+  otherwise_enabled = excluded.otherwise_enabled,
+  otherwise_mode_id = excluded.otherwise_mode_id,
+  updated_at = excluded.updated_at
+"#,
+        params![
+            cli_key,
+            bool_to_int(enabled),
+            utc_offset_minutes,
+            bool_to_int(otherwise_enabled),
+            otherwise_mode_id,
+            now
+        ],
+    )
+    .map_err(|e| db_err!("failed to upsert sort_mode_schedule: {e}"))?;
+
+    tx.execute(
+        "DELETE FROM sort_mode_schedule_rules WHERE cli_key = ?1",
+        params![cli_key],
+    )
+    .map_err(|e| db_err!("failed to clear sort_mode_schedule_rules: {e}"))?;
+
+    for (idx, rule) in rules.iter().enumerate() {
+        tx.execute(
+            r#"
+INSERT INTO sort_mode_schedule_rules(
+  cli_key,
+  position,
+  days_mask,
+  start_time,
+  end_time,
+  mode_id
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+"#,
+            params![
+                cli_key,
+                idx as i64,
+                rule.days_mask as i64,
+                rule.start.as_deref(),
+                rule.end.as_deref(),
+                rule.mode_id
+            ],
+        )
+        .map_err(|e| db_err!("failed to insert sort_mode_schedule_rule: {e}"))?;
+    }
+
+    tx.commit()
+        .map_err(|e| db_err!("failed to commit transaction: {e}"))?;
+
+    read_schedule(&conn, cli_key)
+}
+
+/// Evaluate every enabled schedule at `now_unix`; CLIs without a matching rule (and without an
+/// `otherwise` mode) are omitted.
+pub(crate) fn targets_at(
+    db: &db::Db,
+    now_unix: i64,
+) -> crate::shared::error::AppResult<Vec<SortModeScheduleTarget>> {
+    let conn = db.open_connection()?;
+    let cli_keys = {
+        let mut stmt = conn
+            .prepare_cached("SELECT cli_key FROM sort_mode_schedules WHERE enabled = 1")
+            .map_err(|e| db_err!("failed to prepare sort_mode_schedules query: {e}"))?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| db_err!("failed to list sort_mode_schedules: {e}"))?;
+        let mut items = Vec::new();
+        for row in rows {
+            items.push(row.map_err(|e| db_err!("failed to read sort_mode_schedule row: {e}"))?);
+        }
+        items
+    };
+
+    let mut out = Vec::new();
+    for cli_key in cli_keys {
+        let schedule = read_schedule(&conn, &cli_key)?;
+        let (weekday, local_time) = local_clock(&conn, now_unix, schedule.utc_offset_minutes)?;
+        if let Some((rule_index, mode_id)) = select_target(&schedule, weekday, &local_time) {
+            out.push(SortModeScheduleTarget {
+                cli_key,
+                mode_id,
+                rule_index,
+                local_time,
+            });
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(
+        days_mask: u32,
+        window: Option<(&str, &str)>,
+        mode_id: Option<i64>,
+    ) -> SortModeScheduleRule {
+        SortModeScheduleRule {
+            days_mask,
+            start: window.map(|(s, _)| s.to_string()),
+            end: window.map(|(_, e)| e.to_string()),
+            mode_id,
+        }
+    }
+
+    const WEEKDAYS: u32 = 0b011_1110;
+
+    fn schedule(
+        rules: Vec<SortModeScheduleRule>,
+        otherwise: Option<Option<i64>>,
+    ) -> SortModeSchedule {
+        SortModeSchedule {
+            cli_key: "claude".to_string(),
+            enabled: true,
+            utc_offset_minutes: None,
+            rules,
+            otherwise_enabled: otherwise.is_some(),
+            otherwise_mode_id: otherwise.flatten(),
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn work_hours_rule_with_otherwise_fallback() {
+        let s = schedule(
+            vec![rule(WEEKDAYS, Some(("09:00", "18:00")), Some(1))],
+            Some(Some(2)),
+        );
+
+        // Monday 10:00 -> work-hours.
+        assert_eq!(select_target(&s, 1, "10:00"), Some((Some(0), Some(1))));
+        // Monday 18:00 (end exclusive) and Saturday -> cheap.
+        assert_eq!(select_target(&s, 1, "18:00"), Some((None, Some(2))));
+        assert_eq!(select_target(&s, 6, "10:00"), Some((None, Some(2))));
+    }
+
+    #[test]
+    fn no_match_without_otherwise_keeps_current_mode() {
+        let s = schedule(
+            vec![rule(WEEKDAYS, Some(("09:00", "18:00")), Some(1))],
+            None,
+        );
+        assert_eq!(select_target(&s, 0, "10:00"), None);
+    }
+
+    #[test]
+    fn overnight_rule_belongs_to_start_day() {
+        // Friday 22:00 -> 06:00.
+        let s = schedule(vec![rule(1 << 5, Some(("22:00", "06:00")), None)], None);
+        assert_eq!(select_target(&s, 5, "23:00"), Some((Some(0), None)));
+        assert_eq!(select_target(&s, 6, "05:59"), Some((Some(0), None)));
+        assert_eq!(select_target(&s, 5, "05:00"), None);
+        assert_eq!(select_target(&s, 6, "23:00"), None);
+    }
+
+    #[test]
+    fn whole_day_rule_and_first_match_wins() {
+        let s = schedule(
+            vec![
+                rule(0b100_0001, None, Some(3)),
+                rule(ALL_DAYS_MASK, Some(("00:00", "23:59")), Some(4)),
+            ],
+            None,
+        );
+        assert_eq!(select_target(&s, 0, "12:00"), Some((Some(0), Some(3))));
+        assert_eq!(select_target(&s, 3, "12:00"), Some((Some(1), Some(4))));
+    }
+
+    #[test]
+    fn normalize_rule_validates_days_and_window() {
+        assert!(normalize_rule(rule(0, None, None)).is_err());
+        assert!(normalize_rule(rule(128, None, None)).is_err());
+        assert!(normalize_rule(rule(1, Some(("9:00", "9:00")), None)).is_err());
+        let normalized = normalize_rule(rule(1, Some(("9:00", "18:00")), None)).unwrap();
+        assert_eq!(normalized.start.as_deref(), Some("09:00"));
+        assert!(validate_utc_offset(Some(15 * 60)).is_err());
+        assert!(validate_utc_offset(Some(-5 * 60)).is_ok());
+    }
+}
@@ -7,25 +7,51 @@ use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
 use serde::Serialize;
 use std::collections::{HashMap, HashSet};
 
-#[derive(Debug, Clone, Serialize)]
+pub const SORT_MODE_STRATEGY_MANUAL: &str = "manual";
+pub const SORT_MODE_STRATEGY_ADAPTIVE: &str = "adaptive";
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct SortModeSummary {
     pub id: i64,
     pub name: String,
+    /// `manual` keeps the configured order; `adaptive` re-ranks providers by recent p95 latency
+    /// and success rate.
+    pub strategy: String,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct SortModeActiveRow {
     pub cli_key: String,
     pub mode_id: Option<i64>,
     pub updated_at: i64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct SortModeProviderRow {
     pub provider_id: i64,
     pub enabled: bool,
+    /// Local-time window (`HH:MM`, end exclusive, may wrap midnight) during which the entry keeps
+    /// its position; outside it the gateway moves the provider behind in-window entries.
+    pub active_start: Option<String>,
+    pub active_end: Option<String>,
+}
+
+/// Time-window routing outcome for one sort mode at a given instant.
+#[derive(Debug, Clone)]
+pub(crate) struct SortModeTimeWindows {
+    pub(crate) local_time: String,
+    pub(crate) active_provider_ids: Vec<i64>,
+    pub(crate) deferred_provider_ids: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SortModeFallbackChain {
+    pub cli_key: String,
+    /// Modes tried in order when the active mode has no available provider; `None` is the
+    /// default provider ordering.
+    pub mode_ids: Vec<Option<i64>>,
 }
 
 fn enabled_to_int(enabled: bool) -> i64 {
@@ -40,11 +66,65 @@ fn enabled_from_int(value: i64) -> bool {
     value != 0
 }
 
+fn row_to_mode_provider(row: &rusqlite::Row<'_>) -> Result<SortModeProviderRow, rusqlite::Error> {
+    let enabled_raw: i64 = row.get("enabled")?;
+    Ok(SortModeProviderRow {
+        provider_id: row.get("provider_id")?,
+        enabled: enabled_from_int(enabled_raw),
+        active_start: row.get("active_start")?,
+        active_end: row.get("active_end")?,
+    })
+}
+
+fn normalize_time_of_day(raw: &str) -> crate::shared::error::AppResult<String> {
+    let raw = raw.trim();
+    let parsed = raw.split_once(':').and_then(|(h, m)| {
+        let hour = h.parse::<u32>().ok().filter(|v| *v < 24)?;
+        let minute = m.parse::<u32>().ok().filter(|v| *v < 60)?;
+        (m.len() == 2).then_some((hour, minute))
+    });
+    match parsed {
+        Some((hour, minute)) => Ok(format!("{hour:02}:{minute:02}")),
+        None => {
+            Err(format!("SEC_INVALID_INPUT: invalid time of day (expected HH:MM): {raw}").into())
+        }
+    }
+}
+
+pub(crate) fn normalize_time_window(
+    active_start: Option<&str>,
+    active_end: Option<&str>,
+) -> crate::shared::error::AppResult<Option<(String, String)>> {
+    let active_start = active_start.map(str::trim).filter(|v| !v.is_empty());
+    let active_end = active_end.map(str::trim).filter(|v| !v.is_empty());
+    match (active_start, active_end) {
+        (None, None) => Ok(None),
+        (Some(start), Some(end)) => {
+            let start = normalize_time_of_day(start)?;
+            let end = normalize_time_of_day(end)?;
+            if start == end {
+                return Err("SEC_INVALID_INPUT: time window start and end must differ".into());
+            }
+            Ok(Some((start, end)))
+        }
+        _ => Err("SEC_INVALID_INPUT: time window requires both start and end".into()),
+    }
+}
+
+/// `start`/`end`/`now` are zero-padded `HH:MM`, so string order equals time order.
+pub(crate) fn is_within_time_window(start: &str, end: &str, now: &str) -> bool {
+    if start <= end {
+        start <= now && now < end
+    } else {
+        now >= start || now < end
+    }
+}
+
 fn validate_cli_key(cli_key: &str) -> crate::shared::error::AppResult<()> {
     crate::shared::cli_key::validate_cli_key(cli_key)
 }
 
-fn validate_mode_name(name: &str) -> crate::shared::error::AppResult<String> {
+pub(crate) fn validate_mode_name(name: &str) -> crate::shared::error::AppResult<String> {
     let name = name.trim();
     if name.is_empty() {
         return Err("SEC_INVALID_INPUT: mode name is required".into());
@@ -62,16 +142,28 @@ fn validate_mode_name(name: &str) -> crate::shared::error::AppResult<String> {
     Ok(name.to_string())
 }
 
+pub(crate) fn validate_strategy(strategy: &str) -> crate::shared::error::AppResult<&'static str> {
+    match strategy.trim() {
+        SORT_MODE_STRATEGY_MANUAL => Ok(SORT_MODE_STRATEGY_MANUAL),
+        SORT_MODE_STRATEGY_ADAPTIVE => Ok(SORT_MODE_STRATEGY_ADAPTIVE),
+        other => Err(format!("SEC_INVALID_INPUT: unknown sort mode strategy: {other}").into()),
+    }
+}
+
 fn row_to_mode_summary(row: &rusqlite::Row<'_>) -> Result<SortModeSummary, rusqlite::Error> {
     Ok(SortModeSummary {
         id: row.get("id")?,
         name: row.get("name")?,
+        strategy: row.get("strategy")?,
         created_at: row.get("created_at")?,
         updated_at: row.get("updated_at")?,
     })
 }
 
-fn ensure_mode_exists(conn: &Connection, mode_id: i64) -> crate::shared::error::AppResult<()> {
+pub(crate) fn ensure_mode_exists(
+    conn: &Connection,
+    mode_id: i64,
+) -> crate::shared::error::AppResult<()> {
     if mode_id <= 0 {
         return Err("SEC_INVALID_INPUT: invalid mode_id".into());
     }
@@ -127,6 +219,7 @@ pub fn list_modes(db: &db::Db) -> crate::shared::error::AppResult<Vec<SortModeSu
 SELECT
   id,
   name,
+  strategy,
   created_at,
   updated_at
 FROM sort_modes
@@ -179,6 +272,7 @@ INSERT INTO sort_modes(
 SELECT
   id,
   name,
+  strategy,
   created_at,
   updated_at
 FROM sort_modes
@@ -221,6 +315,7 @@ pub fn rename_mode(
 SELECT
   id,
   name,
+  strategy,
   created_at,
   updated_at
 FROM sort_modes
@@ -232,6 +327,53 @@ WHERE id = ?1
     .map_err(|e| db_err!("failed to query sort_mode: {e}"))
 }
 
+pub fn set_mode_strategy(
+    db: &db::Db,
+    mode_id: i64,
+    strategy: &str,
+) -> crate::shared::error::AppResult<SortModeSummary> {
+    let strategy = validate_strategy(strategy)?;
+    let conn = db.open_connection()?;
+    ensure_mode_exists(&conn, mode_id)?;
+    let now = now_unix_seconds();
+
+    conn.execute(
+        "UPDATE sort_modes SET strategy = ?1, updated_at = ?2 WHERE id = ?3",
+        params![strategy, now, mode_id],
+    )
+    .map_err(|e| db_err!("failed to update sort_mode strategy: {e}"))?;
+
+    conn.query_row(
+        r#"
+SELECT
+  id,
+  name,
+  strategy,
+  created_at,
+  updated_at
+FROM sort_modes
+WHERE id = ?1
+"#,
+        params![mode_id],
+        row_to_mode_summary,
+    )
+    .map_err(|e| db_err!("failed to query sort_mode: {e}"))
+}
+
+/// Whether the gateway should rank this mode's providers by recent latency/success.
+pub(crate) fn is_adaptive(db: &db::Db, mode_id: i64) -> crate::shared::error::AppResult<bool> {
+    let conn = db.open_connection()?;
+    let strategy: Option<String> = conn
+        .query_row(
+            "SELECT strategy FROM sort_modes WHERE id = ?1",
+            params![mode_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query sort_mode strategy: {e}"))?;
+    Ok(strategy.as_deref() == Some(SORT_MODE_STRATEGY_ADAPTIVE))
+}
+
 pub fn delete_mode(db: &db::Db, mode_id: i64) -> crate::shared::error::AppResult<()> {
     let conn = db.open_connection()?;
     ensure_mode_exists(&conn, mode_id)?;
@@ -324,7 +466,9 @@ pub fn list_mode_providers(
             r#"
 SELECT
   provider_id,
-  enabled
+  enabled,
+  active_start,
+  active_end
 FROM sort_mode_providers
 WHERE mode_id = ?1
   AND cli_key = ?2
@@ -334,14 +478,7 @@ ORDER BY sort_order ASC
         .map_err(|e| db_err!("failed to prepare sort_mode_providers query: {e}"))?;
 
     let rows = stmt
-        .query_map(params![mode_id, cli_key], |row| {
-            let provider_id: i64 = row.get(0)?;
-            let enabled_raw: i64 = row.get(1)?;
-            Ok(SortModeProviderRow {
-                provider_id,
-                enabled: enabled_from_int(enabled_raw),
-            })
-        })
+        .query_map(params![mode_id, cli_key], row_to_mode_provider)
         .map_err(|e| db_err!("failed to list sort_mode_providers: {e}"))?;
 
     let mut items = Vec::new();
@@ -417,15 +554,31 @@ pub fn set_mode_providers_order(
     let tx = conn
         .transaction()
         .map_err(|e| db_err!("failed to start transaction: {e}"))?;
+    write_mode_providers(&tx, mode_id, cli_key, &ordered_provider_ids)?;
+    tx.commit()
+        .map_err(|e| db_err!("failed to commit transaction: {e}"))?;
 
-    let mut existing_enabled: HashMap<i64, bool> = HashMap::new();
+    list_mode_providers(db, mode_id, cli_key)
+}
+
+/// Replaces the provider order of one mode/cli inside the caller's transaction, keeping the
+/// enabled flag and time window of providers that stay in the list.
+pub(crate) fn write_mode_providers(
+    tx: &Connection,
+    mode_id: i64,
+    cli_key: &str,
+    ordered_provider_ids: &[i64],
+) -> crate::shared::error::AppResult<()> {
+    let mut existing: HashMap<i64, SortModeProviderRow> = HashMap::new();
     {
         let mut stmt = tx
             .prepare_cached(
                 r#"
 SELECT
   provider_id,
-  enabled
+  enabled,
+  active_start,
+  active_end
 FROM sort_mode_providers
 WHERE mode_id = ?1
   AND cli_key = ?2
@@ -433,16 +586,11 @@ WHERE mode_id = ?1
             )
             .map_err(|e| db_err!("failed to prepare sort_mode_providers query: {e}"))?;
         let rows = stmt
-            .query_map(params![mode_id, cli_key], |row| {
-                let provider_id: i64 = row.get(0)?;
-                let enabled_raw: i64 = row.get(1)?;
-                Ok((provider_id, enabled_from_int(enabled_raw)))
-            })
+            .query_map(params![mode_id, cli_key], row_to_mode_provider)
             .map_err(|e| db_err!("failed to list sort_mode_providers: {e}"))?;
         for row in rows {
-            let (provider_id, enabled) =
-                row.map_err(|e| db_err!("failed to read sort_mode_provider row: {e}"))?;
-            existing_enabled.insert(provider_id, enabled);
+            let row = row.map_err(|e| db_err!("failed to read sort_mode_provider row: {e}"))?;
+            existing.insert(row.provider_id, row);
         }
     }
 
@@ -454,7 +602,8 @@ WHERE mode_id = ?1
 
     let now = now_unix_seconds();
     for (idx, provider_id) in ordered_provider_ids.iter().enumerate() {
-        let enabled = existing_enabled.get(provider_id).copied().unwrap_or(true);
+        let previous = existing.get(provider_id);
+        let enabled = previous.map(|row| row.enabled).unwrap_or(true);
         tx.execute(
             r#"
 INSERT INTO sort_mode_providers(
@@ -463,9 +612,11 @@ INSERT INTO sort_mode_providers(
   provider_id,
   sort_order,
   enabled,
+  active_start,
+  active_end,
   created_at,
   updated_at
-) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
 "#,
             params![
                 mode_id,
@@ -473,17 +624,15 @@ INSERT INTO sort_mode_providers(
                 provider_id,
                 idx as i64,
                 enabled_to_int(enabled),
+                previous.and_then(|row| row.active_start.as_deref()),
+                previous.and_then(|row| row.active_end.as_deref()),
                 now,
                 now
             ],
         )
         .map_err(|e| db_err!("failed to insert sort_mode_provider: {e}"))?;
     }
-
-    tx.commit()
-        .map_err(|e| db_err!("failed to commit transaction: {e}"))?;
-
-    list_mode_providers(db, mode_id, cli_key)
+    Ok(())
 }
 
 pub fn set_mode_provider_enabled(
@@ -520,25 +669,271 @@ WHERE mode_id = ?3
         return Err("DB_NOT_FOUND: sort_mode_provider not found".into());
     }
 
+    read_mode_provider(&conn, mode_id, cli_key, provider_id)
+}
+
+fn read_mode_provider(
+    conn: &Connection,
+    mode_id: i64,
+    cli_key: &str,
+    provider_id: i64,
+) -> crate::shared::error::AppResult<SortModeProviderRow> {
     conn.query_row(
         r#"
 SELECT
   provider_id,
-  enabled
+  enabled,
+  active_start,
+  active_end
 FROM sort_mode_providers
 WHERE mode_id = ?1
   AND cli_key = ?2
   AND provider_id = ?3
 "#,
         params![mode_id, cli_key, provider_id],
-        |row| {
-            let provider_id: i64 = row.get(0)?;
-            let enabled_raw: i64 = row.get(1)?;
-            Ok(SortModeProviderRow {
-                provider_id,
-                enabled: enabled_from_int(enabled_raw),
-            })
-        },
+        row_to_mode_provider,
     )
     .map_err(|e| db_err!("failed to read sort_mode_provider: {e}"))
 }
+
+pub fn set_mode_provider_time_window(
+    db: &db::Db,
+    mode_id: i64,
+    cli_key: &str,
+    provider_id: i64,
+    active_start: Option<&str>,
+    active_end: Option<&str>,
+) -> crate::shared::error::AppResult<SortModeProviderRow> {
+    let cli_key = cli_key.trim();
+    validate_cli_key(cli_key)?;
+    if provider_id <= 0 {
+        return Err("SEC_INVALID_INPUT: invalid provider_id".into());
+    }
+    let window = normalize_time_window(active_start, active_end)?;
+
+    let conn = db.open_connection()?;
+    ensure_mode_exists(&conn, mode_id)?;
+    ensure_providers_belong_to_cli(&conn, cli_key, &[provider_id])?;
+
+    let (active_start, active_end) = match window {
+        Some((start, end)) => (Some(start), Some(end)),
+        None => (None, None),
+    };
+    let now = now_unix_seconds();
+    let changed = conn
+        .execute(
+            r#"
+UPDATE sort_mode_providers
+SET active_start = ?1, active_end = ?2, updated_at = ?3
+WHERE mode_id = ?4
+  AND cli_key = ?5
+  AND provider_id = ?6
+"#,
+            params![active_start, active_end, now, mode_id, cli_key, provider_id],
+        )
+        .map_err(|e| db_err!("failed to update sort_mode_provider time window: {e}"))?;
+    if changed == 0 {
+        return Err("DB_NOT_FOUND: sort_mode_provider not found".into());
+    }
+
+    read_mode_provider(&conn, mode_id, cli_key, provider_id)
+}
+
+/// Evaluate the mode's time windows at `now_unix` (server local time, as computed by SQLite).
+///
+/// Returns `None` when no enabled entry of the mode carries a window.
+pub(crate) fn time_windows_at(
+    db: &db::Db,
+    mode_id: i64,
+    cli_key: &str,
+    now_unix: i64,
+) -> crate::shared::error::AppResult<Option<SortModeTimeWindows>> {
+    let conn = db.open_connection()?;
+    let mut stmt = conn
+        .prepare_cached(
+            r#"
+SELECT
+  provider_id,
+  active_start,
+  active_end,
+  strftime('%H:%M', ?3, 'unixepoch', 'localtime') AS local_time
+FROM sort_mode_providers
+WHERE mode_id = ?1
+  AND cli_key = ?2
+  AND enabled = 1
+  AND active_start IS NOT NULL
+  AND active_end IS NOT NULL
+ORDER BY sort_order ASC
+"#,
+        )
+        .map_err(|e| db_err!("failed to prepare sort_mode time window query: {e}"))?;
+
+    let rows = stmt
+        .query_map(params![mode_id, cli_key, now_unix], |row| {
+            Ok((
+                row.get::<_, i64>("provider_id")?,
+                row.get::<_, String>("active_start")?,
+                row.get::<_, String>("active_end")?,
+                row.get::<_, String>("local_time")?,
+            ))
+        })
+        .map_err(|e| db_err!("failed to query sort_mode time windows: {e}"))?;
+
+    let mut out: Option<SortModeTimeWindows> = None;
+    for row in rows {
+        let (provider_id, start, end, local_time) =
+            row.map_err(|e| db_err!("failed to read sort_mode time window row: {e}"))?;
+        let windows = out.get_or_insert_with(|| SortModeTimeWindows {
+            local_time,
+            active_provider_ids: Vec::new(),
+            deferred_provider_ids: Vec::new(),
+        });
+        if is_within_time_window(&start, &end, &windows.local_time) {
+            windows.active_provider_ids.push(provider_id);
+        } else {
+            windows.deferred_provider_ids.push(provider_id);
+        }
+    }
+    Ok(out)
+}
+
+pub(crate) fn read_fallback_chain(
+    conn: &Connection,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<Vec<Option<i64>>> {
+    let mut stmt = conn
+        .prepare_cached(
+            r#"
+SELECT
+  mode_id
+FROM sort_mode_fallbacks
+WHERE cli_key = ?1
+ORDER BY position ASC
+"#,
+        )
+        .map_err(|e| db_err!("failed to prepare sort_mode_fallbacks query: {e}"))?;
+
+    let rows = stmt
+        .query_map(params![cli_key], |row| row.get::<_, Option<i64>>(0))
+        .map_err(|e| db_err!("failed to list sort_mode_fallbacks: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| db_err!("failed to read sort_mode_fallback row: {e}"))?);
+    }
+    Ok(items)
+}
+
+pub fn get_fallback_chain(
+    db: &db::Db,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<SortModeFallbackChain> {
+    let cli_key = cli_key.trim();
+    validate_cli_key(cli_key)?;
+
+    let conn = db.open_connection()?;
+    let mode_ids = read_fallback_chain(&conn, cli_key)?;
+    Ok(SortModeFallbackChain {
+        cli_key: cli_key.to_string(),
+        mode_ids,
+    })
+}
+
+pub fn set_fallback_chain(
+    db: &db::Db,
+    cli_key: &str,
+    mode_ids: Vec<Option<i64>>,
+) -> crate::shared::error::AppResult<SortModeFallbackChain> {
+    let cli_key = cli_key.trim();
+    validate_cli_key(cli_key)?;
+
+    let mut conn = db.open_connection()?;
+    let mut seen = HashSet::new();
+    for mode_id in &mode_ids {
+        if !seen.insert(*mode_id) {
+            return Err(match mode_id {
+                Some(id) => format!("SEC_INVALID_INPUT: duplicate mode_id={id}"),
+                None => "SEC_INVALID_INPUT: duplicate default mode".to_string(),
+            }
+            .into());
+        }
+        if let Some(mode_id) = mode_id {
+            ensure_mode_exists(&conn, *mode_id)?;
+        }
+    }
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| db_err!("failed to start transaction: {e}"))?;
+    write_fallback_chain(&tx, cli_key, &mode_ids)?;
+    tx.commit()
+        .map_err(|e| db_err!("failed to commit transaction: {e}"))?;
+
+    get_fallback_chain(db, cli_key)
+}
+
+/// Replaces the fallback chain of `cli_key` inside the caller's transaction.
+pub(crate) fn write_fallback_chain(
+    tx: &Connection,
+    cli_key: &str,
+    mode_ids: &[Option<i64>],
+) -> crate::shared::error::AppResult<()> {
+    tx.execute(
+        "DELETE FROM sort_mode_fallbacks WHERE cli_key = ?1",
+        params![cli_key],
+    )
+    .map_err(|e| db_err!("failed to clear sort_mode_fallbacks: {e}"))?;
+
+    let now = now_unix_seconds();
+    for (idx, mode_id) in mode_ids.iter().enumerate() {
+        tx.execute(
+            r#"
+INSERT INTO sort_mode_fallbacks(
+  cli_key,
+  position,
+  mode_id,
+  updated_at
+) VALUES (?1, ?2, ?3, ?4)
+"#,
+            params![cli_key, idx as i64, mode_id, now],
+        )
+        .map_err(|e| db_err!("failed to insert sort_mode_fallback: {e}"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_within_time_window, normalize_time_window, validate_strategy};
+
+    #[test]
+    fn time_window_supports_same_day_and_overnight_ranges() {
+        assert!(is_within_time_window("09:00", "18:00", "09:00"));
+        assert!(is_within_time_window("09:00", "18:00", "17:59"));
+        assert!(!is_within_time_window("09:00", "18:00", "18:00"));
+
+        assert!(is_within_time_window("22:00", "06:00", "23:30"));
+        assert!(is_within_time_window("22:00", "06:00", "00:15"));
+        assert!(!is_within_time_window("22:00", "06:00", "12:00"));
+    }
+
+    #[test]
+    fn normalize_time_window_validates_input() {
+        assert_eq!(normalize_time_window(None, Some(" ")).unwrap(), None);
+        assert_eq!(
+            normalize_time_window(Some("7:05"), Some("23:00")).unwrap(),
+            Some(("07:05".to_string(), "23:00".to_string()))
+        );
+        assert!(normalize_time_window(Some("22:00"), None).is_err());
+        assert!(normalize_time_window(Some("24:00"), Some("06:00")).is_err());
+        assert!(normalize_time_window(Some("10:00"), Some("10:00")).is_err());
+        assert!(normalize_time_window(Some("10:5"), Some("11:00")).is_err());
+    }
+
+    #[test]
+    fn validate_strategy_accepts_known_values_only() {
+        assert_eq!(validate_strategy(" adaptive ").unwrap(), "adaptive");
+        assert_eq!(validate_strategy("manual").unwrap(), "manual");
+        assert!(validate_strategy("random").is_err());
+    }
+}
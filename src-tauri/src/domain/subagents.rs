@@ -0,0 +1,479 @@
+//! Usage: Claude Code subagent definitions (DB persistence, per-workspace enablement, agents/*.md sync).
+
+use crate::db;
+use crate::shared::error::db_err;
+use crate::shared::time::now_unix_seconds;
+use crate::subagent_sync::{self, ManagedSubagent, SubagentSyncBackup};
+use crate::workspaces;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+const SUBAGENTS_CLI_KEY: &str = "claude";
+const MAX_SUBAGENT_NAME_LEN: usize = 64;
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SubagentSummary {
+    pub id: i64,
+    pub name: String,
+    pub description: String,
+    pub prompt: String,
+    pub tools: Vec<String>,
+    pub model: Option<String>,
+    pub enabled: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Subagent names double as file names (`<name>.md`): lowercase letters, digits and hyphens.
+fn normalize_subagent_name(name: &str) -> crate::shared::error::AppResult<String> {
+    let name = name.trim();
+    let valid = !name.is_empty()
+        && name.len() <= MAX_SUBAGENT_NAME_LEN
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if !valid {
+        return Err(format!(
+            "SEC_INVALID_INPUT: subagent name must be 1-{MAX_SUBAGENT_NAME_LEN} lowercase letters, digits or hyphens (name={name})"
+        )
+        .into());
+    }
+    Ok(name.to_string())
+}
+
+fn normalize_required_text(field: &str, value: &str) -> crate::shared::error::AppResult<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(format!("SEC_INVALID_INPUT: subagent {field} is required").into());
+    }
+    Ok(value.to_string())
+}
+
+fn normalize_tools(tools: &[String]) -> crate::shared::error::AppResult<Vec<String>> {
+    let mut out: Vec<String> = Vec::new();
+    for tool in tools {
+        let tool = tool.trim();
+        if tool.is_empty() {
+            continue;
+        }
+        if tool.contains(',') || tool.contains('\n') {
+            return Err(format!("SEC_INVALID_INPUT: invalid subagent tool={tool}").into());
+        }
+        if !out.iter().any(|v| v == tool) {
+            out.push(tool.to_string());
+        }
+    }
+    Ok(out)
+}
+
+fn normalize_model(model: Option<&str>) -> crate::shared::error::AppResult<Option<String>> {
+    let Some(model) = model.map(str::trim).filter(|v| !v.is_empty()) else {
+        return Ok(None);
+    };
+    if model.chars().any(char::is_whitespace) {
+        return Err(format!("SEC_INVALID_INPUT: invalid subagent model={model}").into());
+    }
+    Ok(Some(model.to_string()))
+}
+
+fn ensure_claude_workspace(
+    conn: &Connection,
+    workspace_id: i64,
+) -> crate::shared::error::AppResult<()> {
+    let cli_key = workspaces::get_cli_key_by_id(conn, workspace_id)?;
+    if cli_key != SUBAGENTS_CLI_KEY {
+        return Err(format!(
+            "SEC_INVALID_INPUT: subagents are only supported for claude workspaces (cli_key={cli_key})"
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn parse_tools_json(raw: &str) -> Vec<String> {
+    serde_json::from_str(raw).unwrap_or_default()
+}
+
+fn row_to_summary(row: &rusqlite::Row<'_>) -> Result<SubagentSummary, rusqlite::Error> {
+    Ok(SubagentSummary {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        description: row.get("description")?,
+        prompt: row.get("prompt")?,
+        tools: parse_tools_json(&row.get::<_, String>("tools_json")?),
+        model: row.get("model")?,
+        enabled: row.get::<_, i64>("enabled")? != 0,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+fn get_by_id_for_workspace(
+    conn: &Connection,
+    workspace_id: i64,
+    subagent_id: i64,
+) -> crate::shared::error::AppResult<SubagentSummary> {
+    conn.query_row(
+        r#"
+SELECT
+  a.id,
+  a.name,
+  a.description,
+  a.prompt,
+  a.tools_json,
+  a.model,
+  CASE WHEN e.subagent_id IS NULL THEN 0 ELSE 1 END AS enabled,
+  a.created_at,
+  a.updated_at
+FROM claude_subagents a
+LEFT JOIN workspace_subagent_enabled e
+  ON e.workspace_id = ?1 AND e.subagent_id = a.id
+WHERE a.id = ?2
+"#,
+        params![workspace_id, subagent_id],
+        row_to_summary,
+    )
+    .optional()
+    .map_err(|e| db_err!("failed to query subagent: {e}"))?
+    .ok_or_else(|| crate::shared::error::AppError::from("DB_NOT_FOUND: subagent not found"))
+}
+
+pub fn list_for_workspace(
+    db: &db::Db,
+    workspace_id: i64,
+) -> crate::shared::error::AppResult<Vec<SubagentSummary>> {
+    let conn = db.open_connection()?;
+    ensure_claude_workspace(&conn, workspace_id)?;
+
+    let mut stmt = conn
+        .prepare_cached(
+            r#"
+    SELECT
+      a.id,
+      a.name,
+      a.description,
+      a.prompt,
+      a.tools_json,
+      a.model,
+      CASE WHEN e.subagent_id IS NULL THEN 0 ELSE 1 END AS enabled,
+      a.created_at,
+      a.updated_at
+    FROM claude_subagents a
+    LEFT JOIN workspace_subagent_enabled e
+      ON e.workspace_id = ?1 AND e.subagent_id = a.id
+    ORDER BY a.name ASC
+    "#,
+        )
+        .map_err(|e| db_err!("failed to prepare query: {e}"))?;
+
+    let rows = stmt
+        .query_map([workspace_id], row_to_summary)
+        .map_err(|e| db_err!("failed to list subagents: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        items.push(row.map_err(|e| db_err!("failed to read subagent row: {e}"))?);
+    }
+    Ok(items)
+}
+
+fn list_enabled_for_workspace(
+    conn: &Connection,
+    workspace_id: i64,
+) -> crate::shared::error::AppResult<Vec<ManagedSubagent>> {
+    let mut stmt = conn
+        .prepare_cached(
+            r#"
+    SELECT a.name, a.description, a.prompt, a.tools_json, a.model
+    FROM claude_subagents a
+    JOIN workspace_subagent_enabled e
+      ON e.subagent_id = a.id
+    WHERE e.workspace_id = ?1
+    ORDER BY a.name ASC
+    "#,
+        )
+        .map_err(|e| db_err!("failed to prepare enabled subagents query: {e}"))?;
+
+    let rows = stmt
+        .query_map([workspace_id], |row| {
+            Ok(ManagedSubagent {
+                name: row.get(0)?,
+                description: row.get(1)?,
+                prompt: row.get(2)?,
+                tools: parse_tools_json(&row.get::<_, String>(3)?),
+                model: row.get(4)?,
+            })
+        })
+        .map_err(|e| db_err!("failed to query enabled subagents: {e}"))?;
+
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row.map_err(|e| db_err!("failed to read enabled subagent row: {e}"))?);
+    }
+    Ok(out)
+}
+
+pub(crate) fn sync_cli_for_workspace(
+    app: &crate::AppHandle,
+    conn: &Connection,
+    workspace_id: i64,
+) -> crate::shared::error::AppResult<()> {
+    ensure_claude_workspace(conn, workspace_id)?;
+    let agents = list_enabled_for_workspace(conn, workspace_id)?;
+    subagent_sync::sync_subagents(app, &agents)
+}
+
+fn sync_active_workspace(
+    app: &crate::AppHandle,
+    conn: &Connection,
+) -> crate::shared::error::AppResult<()> {
+    match workspaces::active_id_by_cli(conn, SUBAGENTS_CLI_KEY)? {
+        Some(workspace_id) => sync_cli_for_workspace(app, conn, workspace_id),
+        None => subagent_sync::sync_subagents(app, &[]),
+    }
+}
+
+fn name_taken(
+    conn: &Connection,
+    subagent_id: Option<i64>,
+    name: &str,
+) -> crate::shared::error::AppResult<bool> {
+    let existing: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM claude_subagents WHERE name = ?1 AND (?2 IS NULL OR id != ?2) LIMIT 1",
+            params![name, subagent_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query subagent name: {e}"))?;
+    Ok(existing.is_some())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn upsert(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    subagent_id: Option<i64>,
+    workspace_id: i64,
+    name: &str,
+    description: &str,
+    prompt: &str,
+    tools: &[String],
+    model: Option<&str>,
+) -> crate::shared::error::AppResult<SubagentSummary> {
+    let name = normalize_subagent_name(name)?;
+    let description = normalize_required_text("description", description)?;
+    let prompt = normalize_required_text("prompt", prompt)?;
+    let tools = normalize_tools(tools)?;
+    let model = normalize_model(model)?;
+    let tools_json = serde_json::to_string(&tools)
+        .map_err(|e| format!("failed to serialize subagent tools: {e}"))?;
+
+    let mut conn = db.open_connection()?;
+    ensure_claude_workspace(&conn, workspace_id)?;
+    let now = now_unix_seconds();
+
+    let tx = conn
+        .transaction()
+        .map_err(|e| db_err!("failed to start transaction: {e}"))?;
+
+    if name_taken(&tx, subagent_id, &name)? {
+        return Err(crate::shared::error::AppError::new(
+            "DB_CONSTRAINT",
+            format!("subagent already exists: name={name}"),
+        ));
+    }
+
+    let id = match subagent_id {
+        None => {
+            tx.execute(
+                r#"
+INSERT INTO claude_subagents(
+  name,
+  description,
+  prompt,
+  tools_json,
+  model,
+  created_at,
+  updated_at
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
+"#,
+                params![name, description, prompt, tools_json, model, now],
+            )
+            .map_err(|e| db_err!("failed to insert subagent: {e}"))?;
+            tx.last_insert_rowid()
+        }
+        Some(id) => {
+            let changed = tx
+                .execute(
+                    r#"
+UPDATE claude_subagents
+SET
+  name = ?1,
+  description = ?2,
+  prompt = ?3,
+  tools_json = ?4,
+  model = ?5,
+  updated_at = ?6
+WHERE id = ?7
+"#,
+                    params![name, description, prompt, tools_json, model, now, id],
+                )
+                .map_err(|e| db_err!("failed to update subagent: {e}"))?;
+            if changed == 0 {
+                return Err("DB_NOT_FOUND: subagent not found".to_string().into());
+            }
+            id
+        }
+    };
+
+    let backup = SubagentSyncBackup::capture(app)?;
+    if let Err(err) = sync_active_workspace(app, &tx) {
+        backup.restore();
+        return Err(err);
+    }
+
+    if let Err(err) = tx.commit() {
+        backup.restore();
+        return Err(db_err!("failed to commit: {err}"));
+    }
+
+    get_by_id_for_workspace(&conn, workspace_id, id)
+}
+
+pub fn set_enabled(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    workspace_id: i64,
+    subagent_id: i64,
+    enabled: bool,
+) -> crate::shared::error::AppResult<SubagentSummary> {
+    let mut conn = db.open_connection()?;
+    let now = now_unix_seconds();
+    let tx = conn
+        .transaction()
+        .map_err(|e| db_err!("failed to start transaction: {e}"))?;
+
+    ensure_claude_workspace(&tx, workspace_id)?;
+    let _ = get_by_id_for_workspace(&tx, workspace_id, subagent_id)?;
+    let should_sync = workspaces::is_active_workspace(&tx, workspace_id)?;
+
+    if enabled {
+        tx.execute(
+            r#"
+INSERT INTO workspace_subagent_enabled(workspace_id, subagent_id, created_at, updated_at)
+VALUES (?1, ?2, ?3, ?3)
+ON CONFLICT(workspace_id, subagent_id) DO UPDATE SET
+  updated_at = excluded.updated_at
+"#,
+            params![workspace_id, subagent_id, now],
+        )
+        .map_err(|e| db_err!("failed to enable subagent: {e}"))?;
+    } else {
+        tx.execute(
+            "DELETE FROM workspace_subagent_enabled WHERE workspace_id = ?1 AND subagent_id = ?2",
+            params![workspace_id, subagent_id],
+        )
+        .map_err(|e| db_err!("failed to disable subagent: {e}"))?;
+    }
+
+    let backup = if should_sync {
+        Some(SubagentSyncBackup::capture(app)?)
+    } else {
+        None
+    };
+
+    if should_sync {
+        if let Err(err) = sync_cli_for_workspace(app, &tx, workspace_id) {
+            if let Some(backup) = backup {
+                backup.restore();
+            }
+            return Err(err);
+        }
+    }
+
+    if let Err(err) = tx.commit() {
+        if let Some(backup) = backup {
+            backup.restore();
+        }
+        return Err(db_err!("failed to commit: {err}"));
+    }
+
+    get_by_id_for_workspace(&conn, workspace_id, subagent_id)
+}
+
+pub fn delete(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    subagent_id: i64,
+) -> crate::shared::error::AppResult<()> {
+    let mut conn = db.open_connection()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| db_err!("failed to start transaction: {e}"))?;
+
+    let changed = tx
+        .execute(
+            "DELETE FROM claude_subagents WHERE id = ?1",
+            params![subagent_id],
+        )
+        .map_err(|e| db_err!("failed to delete subagent: {e}"))?;
+    if changed == 0 {
+        return Err("DB_NOT_FOUND: subagent not found".to_string().into());
+    }
+
+    let backup = SubagentSyncBackup::capture(app)?;
+    if let Err(err) = sync_active_workspace(app, &tx) {
+        backup.restore();
+        return Err(err);
+    }
+
+    if let Err(err) = tx.commit() {
+        backup.restore();
+        return Err(db_err!("failed to commit: {err}"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_subagent_name_accepts_file_safe_names_only() {
+        assert_eq!(
+            normalize_subagent_name(" code-reviewer ").unwrap(),
+            "code-reviewer"
+        );
+        assert!(normalize_subagent_name("").is_err());
+        assert!(normalize_subagent_name("Code Reviewer").is_err());
+        assert!(normalize_subagent_name("../etc").is_err());
+        assert!(normalize_subagent_name("-lead").is_err());
+        assert!(normalize_subagent_name(&"a".repeat(MAX_SUBAGENT_NAME_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn normalize_tools_trims_and_dedupes() {
+        let tools = vec![
+            " Read ".to_string(),
+            "".to_string(),
+            "Grep".to_string(),
+            "Read".to_string(),
+        ];
+        assert_eq!(normalize_tools(&tools).unwrap(), vec!["Read", "Grep"]);
+        assert!(normalize_tools(&["Read, Grep".to_string()]).is_err());
+    }
+
+    #[test]
+    fn normalize_model_treats_blank_as_inherit() {
+        assert_eq!(normalize_model(None).unwrap(), None);
+        assert_eq!(normalize_model(Some("  ")).unwrap(), None);
+        assert_eq!(
+            normalize_model(Some(" sonnet ")).unwrap(),
+            Some("sonnet".to_string())
+        );
+        assert!(normalize_model(Some("claude sonnet")).is_err());
+    }
+}
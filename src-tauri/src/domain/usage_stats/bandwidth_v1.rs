@@ -0,0 +1,94 @@
+use crate::db;
+use crate::shared::error::db_err;
+use rusqlite::{params_from_iter, Connection};
+
+use super::filters::build_optional_range_cli_provider_filters;
+use super::{resolve_query_params, UsageBandwidthRowV1, UsageQueryParams};
+
+/// Request/response body bytes per provider and local day, for capacity planning.
+///
+/// Failed requests are included: their request bodies still went upstream.
+pub(super) fn bandwidth_v1_with_conn(
+    conn: &Connection,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    cli_key: Option<&str>,
+    provider_id: Option<i64>,
+) -> Result<Vec<UsageBandwidthRowV1>, String> {
+    let (where_clause, where_params) = build_optional_range_cli_provider_filters(
+        "r.created_at",
+        "r.cli_key",
+        "r.final_provider_id",
+        start_ts,
+        end_ts,
+        cli_key,
+        provider_id,
+    );
+
+    let sql = format!(
+        r#"
+SELECT
+  strftime('%Y-%m-%d', r.created_at, 'unixepoch','localtime') AS day,
+  r.cli_key AS cli_key,
+  r.final_provider_id AS provider_id,
+  MAX(p.name) AS provider_name,
+  COUNT(*) AS requests,
+  SUM(CASE WHEN r.request_bytes IS NOT NULL OR r.response_bytes IS NOT NULL THEN 1 ELSE 0 END) AS requests_with_bytes,
+  SUM(COALESCE(r.request_bytes, 0)) AS request_bytes,
+  SUM(COALESCE(r.response_bytes, 0)) AS response_bytes
+FROM request_logs r
+LEFT JOIN providers p ON p.id = r.final_provider_id
+WHERE r.excluded_from_stats = 0
+AND r.final_provider_id IS NOT NULL
+AND r.final_provider_id > 0
+{where_clause}
+GROUP BY day, r.cli_key, r.final_provider_id
+ORDER BY day ASC, response_bytes DESC
+"#
+    );
+
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| db_err!("failed to prepare bandwidth query: {e}"))?;
+    let rows = stmt
+        .query_map(params_from_iter(where_params), |row| {
+            let count = |name: &str| -> Result<i64, rusqlite::Error> {
+                Ok(row.get::<_, Option<i64>>(name)?.unwrap_or(0).max(0))
+            };
+            Ok(UsageBandwidthRowV1 {
+                day: row.get("day")?,
+                cli_key: row.get("cli_key")?,
+                provider_id: row.get("provider_id")?,
+                provider_name: row
+                    .get::<_, Option<String>>("provider_name")?
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty()),
+                requests: count("requests")?,
+                requests_with_bytes: count("requests_with_bytes")?,
+                request_bytes: count("request_bytes")?,
+                response_bytes: count("response_bytes")?,
+            })
+        })
+        .map_err(|e| db_err!("failed to run bandwidth query: {e}"))?;
+
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row.map_err(|e| db_err!("failed to read bandwidth row: {e}"))?);
+    }
+    Ok(out)
+}
+
+pub fn usage_bandwidth_v1(
+    db: &db::Db,
+    params: &UsageQueryParams,
+) -> crate::shared::error::AppResult<Vec<UsageBandwidthRowV1>> {
+    let conn = db.open_connection()?;
+    let resolved = resolve_query_params(&conn, params)?;
+    Ok(bandwidth_v1_with_conn(
+        &conn,
+        resolved.start_ts,
+        resolved.end_ts,
+        resolved.cli_key,
+        resolved.provider_id,
+    )?)
+}
@@ -0,0 +1,333 @@
+use crate::db;
+use crate::shared::error::db_err;
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+
+use super::{
+    compute_start_ts_last_n_days, normalize_cli_filter, sql_effective_total_tokens_expr,
+    UsageForecastRowV1, UsageForecastV1,
+};
+
+/// Full days (ending yesterday) the model is fitted on; four of each weekday.
+const HISTORY_DAYS: usize = 28;
+/// ~95% band for normally distributed daily residuals.
+const BAND_Z: f64 = 1.96;
+const FEMTO_PER_USD: f64 = 1_000_000_000_000_000.0;
+
+/// Linear trend over deseasonalized daily values, scaled back by a weekday factor.
+#[derive(Debug, Clone)]
+pub(super) struct DailyModel {
+    intercept: f64,
+    slope: f64,
+    weekday_factors: [f64; 7],
+    residual_sd: f64,
+}
+
+impl DailyModel {
+    pub(super) fn predict(&self, x: f64, weekday: usize) -> f64 {
+        ((self.intercept + self.slope * x) * self.weekday_factors[weekday % 7]).max(0.0)
+    }
+}
+
+/// Fits `values[i]` (day `x = i`, weekday `weekdays[i]`).
+pub(super) fn fit_daily_model(values: &[f64], weekdays: &[usize]) -> DailyModel {
+    let n = values.len();
+    let overall_mean = if n == 0 {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / n as f64
+    };
+
+    let mut weekday_factors = [1.0; 7];
+    if overall_mean > 0.0 {
+        let mut sums = [0.0; 7];
+        let mut counts = [0usize; 7];
+        for (value, weekday) in values.iter().zip(weekdays) {
+            sums[weekday % 7] += value;
+            counts[weekday % 7] += 1;
+        }
+        for ((factor, sum), count) in weekday_factors.iter_mut().zip(sums).zip(counts) {
+            if count > 0 {
+                *factor = sum / count as f64 / overall_mean;
+            }
+        }
+    }
+
+    // Least squares on deseasonalized points; weekdays that never see usage carry no signal.
+    let points: Vec<(f64, f64)> = values
+        .iter()
+        .zip(weekdays)
+        .enumerate()
+        .filter(|(_, (_, weekday))| weekday_factors[*weekday % 7] > f64::EPSILON)
+        .map(|(x, (value, weekday))| (x as f64, value / weekday_factors[weekday % 7]))
+        .collect();
+    let (intercept, slope) = if points.len() < 2 {
+        (overall_mean, 0.0)
+    } else {
+        let m = points.len() as f64;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / m;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / m;
+        let sxx: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+        let sxy: f64 = points
+            .iter()
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum();
+        let slope = if sxx > 0.0 { sxy / sxx } else { 0.0 };
+        (mean_y - slope * mean_x, slope)
+    };
+
+    let mut model = DailyModel {
+        intercept,
+        slope,
+        weekday_factors,
+        residual_sd: 0.0,
+    };
+    if n > 2 {
+        let sse: f64 = values
+            .iter()
+            .zip(weekdays)
+            .enumerate()
+            .map(|(x, (value, weekday))| (value - model.predict(x as f64, *weekday)).powi(2))
+            .sum();
+        model.residual_sd = (sse / (n - 2) as f64).sqrt();
+    }
+    model
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(super) struct MonthCalendar {
+    /// 1-based day of month for today.
+    pub(super) day_of_month: usize,
+    pub(super) days_in_month: usize,
+    /// 0 = Sunday (sqlite `%w`).
+    pub(super) today_weekday: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct MonthProjection {
+    pub(super) month_to_date: f64,
+    pub(super) expected: f64,
+    pub(super) low: f64,
+    pub(super) high: f64,
+    /// Whole days from today until the projected spend reaches `budget` (0 = today); `None`
+    /// when it is not reached before the month (and the budget) resets.
+    pub(super) days_until: Option<u32>,
+}
+
+/// `by_days_ago[d]` is the value `d` days before today (`0` = today so far).
+pub(super) fn project_month(
+    by_days_ago: &[f64],
+    calendar: MonthCalendar,
+    budget: Option<f64>,
+) -> MonthProjection {
+    let value = |days_ago: usize| by_days_ago.get(days_ago).copied().unwrap_or(0.0);
+    let weekday_of = |days_ago: usize| (calendar.today_weekday + 7 * 5 - days_ago % 7) % 7;
+
+    let history: Vec<f64> = (1..=HISTORY_DAYS).rev().map(value).collect();
+    let weekdays: Vec<usize> = (1..=HISTORY_DAYS).rev().map(weekday_of).collect();
+    let model = fit_daily_model(&history, &weekdays);
+
+    let month_to_date: f64 = (0..calendar.day_of_month).map(value).sum();
+    let today_x = HISTORY_DAYS as f64;
+    let remaining_days = calendar.days_in_month.saturating_sub(calendar.day_of_month);
+    let mut daily_remaining =
+        vec![(model.predict(today_x, calendar.today_weekday) - value(0)).max(0.0)];
+    for k in 1..=remaining_days {
+        daily_remaining.push(model.predict(today_x + k as f64, calendar.today_weekday + k));
+    }
+
+    let expected = month_to_date + daily_remaining.iter().sum::<f64>();
+    let band = BAND_Z * model.residual_sd * (daily_remaining.len() as f64).sqrt();
+
+    let days_until = budget.and_then(|budget| {
+        let left = budget - month_to_date;
+        if left <= 0.0 {
+            return Some(0);
+        }
+        let mut spent = 0.0;
+        daily_remaining.iter().enumerate().find_map(|(day, v)| {
+            spent += v;
+            (spent >= left).then_some(day as u32)
+        })
+    });
+
+    MonthProjection {
+        month_to_date,
+        expected,
+        low: (expected - band).max(month_to_date),
+        high: expected + band,
+        days_until,
+    }
+}
+
+fn read_calendar(conn: &Connection) -> crate::shared::error::AppResult<(String, MonthCalendar)> {
+    conn.query_row(
+        r#"
+SELECT
+  strftime('%Y-%m', 'now', 'localtime'),
+  CAST(strftime('%d', 'now', 'localtime') AS INTEGER),
+  CAST(strftime('%d', 'now', 'localtime', 'start of month', '+1 month', '-1 day') AS INTEGER),
+  CAST(strftime('%w', 'now', 'localtime') AS INTEGER)
+"#,
+        [],
+        |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                MonthCalendar {
+                    day_of_month: row.get::<_, i64>(1)?.max(1) as usize,
+                    days_in_month: row.get::<_, i64>(2)?.max(1) as usize,
+                    today_weekday: row.get::<_, i64>(3)?.clamp(0, 6) as usize,
+                },
+            ))
+        },
+    )
+    .map_err(|e| db_err!("failed to compute forecast calendar: {e}"))
+}
+
+#[derive(Default)]
+struct ProviderSeries {
+    tokens: Vec<f64>,
+    cost_usd: Vec<f64>,
+}
+
+pub(super) fn usage_forecast_v1_with_conn(
+    conn: &Connection,
+    cli_key: Option<&str>,
+) -> crate::shared::error::AppResult<UsageForecastV1> {
+    let (month, calendar) = read_calendar(conn)?;
+    let lookback_days = (HISTORY_DAYS + 1).max(calendar.day_of_month);
+    let start_ts = compute_start_ts_last_n_days(conn, lookback_days as u32)?;
+
+    let sql = format!(
+        r#"
+SELECT
+  final_provider_id AS provider_id,
+  CAST(
+    ROUND(
+      julianday('now', 'localtime', 'start of day')
+      - julianday(created_at, 'unixepoch', 'localtime', 'start of day')
+    ) AS INTEGER
+  ) AS days_ago,
+  SUM({tokens_expr}) AS tokens,
+  SUM(CASE WHEN cost_usd_femto > 0 THEN cost_usd_femto ELSE 0 END) AS cost_usd_femto
+FROM request_logs
+WHERE excluded_from_stats = 0
+  AND final_provider_id IS NOT NULL
+  AND created_at >= ?1
+  AND (?2 IS NULL OR cli_key = ?2)
+GROUP BY provider_id, days_ago
+"#,
+        tokens_expr = sql_effective_total_tokens_expr()
+    );
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| db_err!("failed to prepare usage forecast query: {e}"))?;
+    let rows = stmt
+        .query_map(params![start_ts, cli_key], |row| {
+            Ok((
+                row.get::<_, i64>("provider_id")?,
+                row.get::<_, i64>("days_ago")?,
+                row.get::<_, Option<i64>>("tokens")?.unwrap_or(0),
+                row.get::<_, Option<i64>>("cost_usd_femto")?.unwrap_or(0),
+            ))
+        })
+        .map_err(|e| db_err!("failed to run usage forecast query: {e}"))?;
+
+    let mut series: HashMap<i64, ProviderSeries> = HashMap::new();
+    for row in rows {
+        let (provider_id, days_ago, tokens, cost_femto) =
+            row.map_err(|e| db_err!("failed to read usage forecast row: {e}"))?;
+        let Ok(days_ago) = usize::try_from(days_ago) else {
+            continue;
+        };
+        if days_ago >= lookback_days {
+            continue;
+        }
+        let entry = series.entry(provider_id).or_insert_with(|| ProviderSeries {
+            tokens: vec![0.0; lookback_days],
+            cost_usd: vec![0.0; lookback_days],
+        });
+        entry.tokens[days_ago] += tokens as f64;
+        entry.cost_usd[days_ago] += cost_femto as f64 / FEMTO_PER_USD;
+    }
+
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT id, cli_key, name, limit_monthly_usd, monthly_budget_usd
+FROM providers
+WHERE (?1 IS NULL OR cli_key = ?1)
+"#,
+        )
+        .map_err(|e| db_err!("failed to prepare forecast providers query: {e}"))?;
+    let providers = stmt
+        .query_map(params![cli_key], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<f64>>(3)?,
+                row.get::<_, Option<f64>>(4)?,
+            ))
+        })
+        .map_err(|e| db_err!("failed to query forecast providers: {e}"))?;
+
+    let mut out = Vec::new();
+    for provider in providers {
+        let (provider_id, cli_key, provider_name, limit_monthly_usd, monthly_budget_usd) =
+            provider.map_err(|e| db_err!("failed to read forecast provider row: {e}"))?;
+        // The tighter of the hard monthly limit and the soft monthly budget.
+        let budget_usd = match (limit_monthly_usd, monthly_budget_usd) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+        .filter(|v| v.is_finite() && *v > 0.0);
+        let data = series.remove(&provider_id);
+        if data.is_none() && budget_usd.is_none() {
+            continue;
+        }
+        let data = data.unwrap_or_default();
+
+        let tokens = project_month(&data.tokens, calendar, None);
+        let cost = project_month(&data.cost_usd, calendar, budget_usd);
+        out.push(UsageForecastRowV1 {
+            provider_id,
+            cli_key,
+            provider_name,
+            month_to_date_tokens: tokens.month_to_date.round() as i64,
+            expected_total_tokens: tokens.expected.round() as i64,
+            expected_total_tokens_low: tokens.low.round() as i64,
+            expected_total_tokens_high: tokens.high.round() as i64,
+            month_to_date_cost_usd: cost.month_to_date,
+            expected_total_cost_usd: cost.expected,
+            expected_total_cost_usd_low: cost.low,
+            expected_total_cost_usd_high: cost.high,
+            budget_usd,
+            days_until_budget_exhausted: cost.days_until,
+        });
+    }
+    out.sort_by(|a, b| {
+        b.expected_total_cost_usd
+            .total_cmp(&a.expected_total_cost_usd)
+            .then(b.expected_total_tokens.cmp(&a.expected_total_tokens))
+            .then(a.provider_id.cmp(&b.provider_id))
+    });
+
+    Ok(UsageForecastV1 {
+        month,
+        day_of_month: calendar.day_of_month as u32,
+        days_in_month: calendar.days_in_month as u32,
+        history_days: HISTORY_DAYS as u32,
+        rows: out,
+    })
+}
+
+/// Projects end-of-month tokens and USD per provider from the last `HISTORY_DAYS` full days.
+pub fn usage_forecast_v1(
+    db: &db::Db,
+    cli_key: Option<&str>,
+) -> crate::shared::error::AppResult<UsageForecastV1> {
+    let cli_key = normalize_cli_filter(cli_key)?;
+    let conn = db.open_connection()?;
+    usage_forecast_v1_with_conn(&conn, cli_key)
+}
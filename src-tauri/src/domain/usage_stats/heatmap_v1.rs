@@ -0,0 +1,123 @@
+use crate::db;
+use crate::shared::error::db_err;
+use rusqlite::{params_from_iter, Connection};
+
+use super::filters::build_optional_range_cli_provider_filters;
+use super::{resolve_query_params, UsageHeatmapCellV1, UsageQueryParams};
+
+const FEMTO_PER_USD: f64 = 1_000_000_000_000_000.0;
+
+/// Token/cost totals per local weekday × hour, to see when spend happens.
+///
+/// Logged requests and sampled-out successes (hourly rollups) are summed; only non-empty cells
+/// are returned.
+pub(super) fn heatmap_v1_with_conn(
+    conn: &Connection,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    cli_key: Option<&str>,
+    provider_id: Option<i64>,
+) -> Result<Vec<UsageHeatmapCellV1>, String> {
+    let (log_where, where_params) = build_optional_range_cli_provider_filters(
+        "created_at",
+        "cli_key",
+        "final_provider_id",
+        start_ts,
+        end_ts,
+        cli_key,
+        provider_id,
+    );
+    // Same filters in the same order, so both halves share the numbered placeholders.
+    let (rollup_where, _) = build_optional_range_cli_provider_filters(
+        "bucket_start",
+        "cli_key",
+        "final_provider_id",
+        start_ts,
+        end_ts,
+        cli_key,
+        provider_id,
+    );
+
+    let sql = format!(
+        r#"
+SELECT
+  weekday,
+  hour,
+  SUM(requests) AS requests,
+  SUM(input_tokens) AS input_tokens,
+  SUM(output_tokens) AS output_tokens,
+  SUM(total_tokens) AS total_tokens,
+  SUM(cost_usd_femto) AS cost_usd_femto
+FROM (
+SELECT
+  CAST(strftime('%w', created_at, 'unixepoch', 'localtime') AS INTEGER) AS weekday,
+  CAST(strftime('%H', created_at, 'unixepoch', 'localtime') AS INTEGER) AS hour,
+  COUNT(*) AS requests,
+  SUM(COALESCE(input_tokens, 0)) AS input_tokens,
+  SUM(COALESCE(output_tokens, 0)) AS output_tokens,
+  SUM(COALESCE(total_tokens, COALESCE(input_tokens, 0) + COALESCE(output_tokens, 0))) AS total_tokens,
+  SUM(CASE WHEN cost_usd_femto > 0 THEN cost_usd_femto ELSE 0 END) AS cost_usd_femto
+FROM request_logs
+WHERE excluded_from_stats = 0
+{log_where}
+GROUP BY weekday, hour
+UNION ALL
+SELECT
+  CAST(strftime('%w', bucket_start, 'unixepoch', 'localtime') AS INTEGER) AS weekday,
+  CAST(strftime('%H', bucket_start, 'unixepoch', 'localtime') AS INTEGER) AS hour,
+  SUM(request_count) AS requests,
+  SUM(input_tokens) AS input_tokens,
+  SUM(output_tokens) AS output_tokens,
+  SUM(total_tokens) AS total_tokens,
+  SUM(cost_usd_femto) AS cost_usd_femto
+FROM request_log_rollups
+WHERE 1 = 1
+{rollup_where}
+GROUP BY weekday, hour
+)
+GROUP BY weekday, hour
+ORDER BY weekday ASC, hour ASC
+"#
+    );
+
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| db_err!("failed to prepare usage heatmap query: {e}"))?;
+    let rows = stmt
+        .query_map(params_from_iter(where_params), |row| {
+            let count = |name: &str| -> Result<i64, rusqlite::Error> {
+                Ok(row.get::<_, Option<i64>>(name)?.unwrap_or(0).max(0))
+            };
+            Ok(UsageHeatmapCellV1 {
+                weekday: row.get("weekday")?,
+                hour: row.get("hour")?,
+                requests: count("requests")?,
+                input_tokens: count("input_tokens")?,
+                output_tokens: count("output_tokens")?,
+                total_tokens: count("total_tokens")?,
+                cost_usd: count("cost_usd_femto")? as f64 / FEMTO_PER_USD,
+            })
+        })
+        .map_err(|e| db_err!("failed to run usage heatmap query: {e}"))?;
+
+    let mut out = Vec::new();
+    for row in rows {
+        out.push(row.map_err(|e| db_err!("failed to read usage heatmap row: {e}"))?);
+    }
+    Ok(out)
+}
+
+pub fn usage_heatmap_v1(
+    db: &db::Db,
+    params: &UsageQueryParams,
+) -> crate::shared::error::AppResult<Vec<UsageHeatmapCellV1>> {
+    let conn = db.open_connection()?;
+    let resolved = resolve_query_params(&conn, params)?;
+    Ok(heatmap_v1_with_conn(
+        &conn,
+        resolved.start_ts,
+        resolved.end_ts,
+        resolved.cli_key,
+        resolved.provider_id,
+    )?)
+}
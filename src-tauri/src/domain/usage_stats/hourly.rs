@@ -12,7 +12,17 @@ pub fn hourly_series(
     let days = days.clamp(1, 60);
     let start_ts = compute_start_ts_last_n_days(&conn, days)?;
 
+    // Sampled-out successes live in request_log_rollups; union them in so counts stay exact.
     let mut stmt = conn.prepare_cached(r#"
+    	SELECT
+    	  day,
+    	  hour,
+    	  SUM(requests_total) AS requests_total,
+    	  SUM(requests_with_usage) AS requests_with_usage,
+    	  SUM(requests_success) AS requests_success,
+    	  SUM(requests_failed) AS requests_failed,
+    	  SUM(total_tokens) AS total_tokens
+    	FROM (
     	SELECT
     	  strftime('%Y-%m-%d', created_at, 'unixepoch', 'localtime') AS day,
     	  CAST(strftime('%H', created_at, 'unixepoch', 'localtime') AS INTEGER) AS hour,
@@ -43,6 +53,20 @@ pub fn hourly_series(
     	WHERE excluded_from_stats = 0
     	AND created_at >= ?1
     	GROUP BY day, hour
+    	UNION ALL
+    	SELECT
+    	  strftime('%Y-%m-%d', bucket_start, 'unixepoch', 'localtime') AS day,
+    	  CAST(strftime('%H', bucket_start, 'unixepoch', 'localtime') AS INTEGER) AS hour,
+    	  SUM(request_count) AS requests_total,
+    	  SUM(requests_with_usage) AS requests_with_usage,
+    	  SUM(request_count) AS requests_success,
+    	  0 AS requests_failed,
+    	  SUM(total_tokens) AS total_tokens
+    	FROM request_log_rollups
+    	WHERE bucket_start >= ?1
+    	GROUP BY day, hour
+    	)
+    	GROUP BY day, hour
     	ORDER BY day ASC, hour ASC
     	"#)
         .map_err(|e| db_err!("failed to prepare hourly series query: {e}"))?;
@@ -1,8 +1,11 @@
 //! Usage: Usage analytics queries and aggregation helpers backed by sqlite.
 
+mod bandwidth_v1;
 mod bounds;
 mod cache_rate_trend_v1;
 mod filters;
+mod forecast_v1;
+mod heatmap_v1;
 mod hourly;
 mod input;
 mod leaderboard_range;
@@ -11,15 +14,19 @@ mod summary;
 mod tokens;
 mod types;
 
+pub use bandwidth_v1::usage_bandwidth_v1;
 pub use cache_rate_trend_v1::provider_cache_rate_trend_v1;
+pub use forecast_v1::usage_forecast_v1;
+pub use heatmap_v1::usage_heatmap_v1;
 pub use hourly::hourly_series;
 pub use input::UsageQueryParams;
 pub use leaderboard_range::{leaderboard_day, leaderboard_provider};
 pub use leaderboard_v2::leaderboard_v2;
 pub use summary::{summary, summary_v2};
 pub use types::{
-    UsageDayRow, UsageHourlyRow, UsageLeaderboardRow, UsageProviderCacheRateTrendRowV1,
-    UsageProviderRow, UsageSummary,
+    UsageBandwidthRowV1, UsageDayRow, UsageForecastRowV1, UsageForecastV1, UsageHeatmapCellV1,
+    UsageHourlyRow, UsageLeaderboardRow, UsageProviderCacheRateTrendRowV1, UsageProviderRow,
+    UsageSummary,
 };
 
 use bounds::{compute_bounds_v2, compute_start_ts, compute_start_ts_last_n_days};
@@ -27,6 +27,83 @@ fn build_summary_where_clause(
     (clause, values)
 }
 
+/// Totals of sampled-out successful requests (see `request_logs::sampling`).
+struct RollupTotals {
+    requests: i64,
+    requests_with_usage: i64,
+    duration_ms_sum: i64,
+    ttfb_ms_sum: i64,
+    ttfb_count: i64,
+    generation_ms_sum: i64,
+    output_tokens_for_rate_sum: i64,
+    input_tokens: i64,
+    output_tokens: i64,
+    total_tokens: i64,
+    cache_read_input_tokens: i64,
+    cache_creation_input_tokens: i64,
+    cache_creation_5m_input_tokens: i64,
+    cache_creation_1h_input_tokens: i64,
+}
+
+fn rollup_totals(
+    conn: &Connection,
+    start_ts: Option<i64>,
+    end_ts: Option<i64>,
+    cli_key: Option<&str>,
+    provider_id: Option<i64>,
+) -> Result<RollupTotals, String> {
+    let (filter_sql, params_vec) = build_optional_range_cli_provider_filters(
+        "bucket_start",
+        "cli_key",
+        "final_provider_id",
+        start_ts,
+        end_ts,
+        cli_key,
+        provider_id,
+    );
+    let sql = format!(
+        r#"
+SELECT
+  COALESCE(SUM(request_count), 0),
+  COALESCE(SUM(requests_with_usage), 0),
+  COALESCE(SUM(duration_ms_sum), 0),
+  COALESCE(SUM(ttfb_ms_sum), 0),
+  COALESCE(SUM(ttfb_count), 0),
+  COALESCE(SUM(generation_ms_sum), 0),
+  COALESCE(SUM(output_tokens_for_rate_sum), 0),
+  COALESCE(SUM(input_tokens), 0),
+  COALESCE(SUM(output_tokens), 0),
+  COALESCE(SUM(total_tokens), 0),
+  COALESCE(SUM(cache_read_input_tokens), 0),
+  COALESCE(SUM(cache_creation_input_tokens), 0),
+  COALESCE(SUM(cache_creation_5m_input_tokens), 0),
+  COALESCE(SUM(cache_creation_1h_input_tokens), 0)
+FROM request_log_rollups
+WHERE 1 = 1{filter_sql}
+"#
+    );
+
+    conn.query_row(&sql, params_from_iter(params_vec), |row| {
+        Ok(RollupTotals {
+            requests: row.get(0)?,
+            requests_with_usage: row.get(1)?,
+            duration_ms_sum: row.get(2)?,
+            ttfb_ms_sum: row.get(3)?,
+            ttfb_count: row.get(4)?,
+            generation_ms_sum: row.get(5)?,
+            output_tokens_for_rate_sum: row.get(6)?,
+            input_tokens: row.get(7)?,
+            output_tokens: row.get(8)?,
+            total_tokens: row.get(9)?,
+            cache_read_input_tokens: row.get(10)?,
+            cache_creation_input_tokens: row.get(11)?,
+            cache_creation_5m_input_tokens: row.get(12)?,
+            cache_creation_1h_input_tokens: row.get(13)?,
+        })
+    })
+    .map_err(|e| format!("DB_ERROR: failed to query usage rollups: {e}"))
+}
+
 pub(super) fn summary_query(
     conn: &Connection,
     start_ts: Option<i64>,
@@ -38,6 +115,7 @@ pub(super) fn summary_query(
     let effective_total_expr = sql_effective_total_tokens_expr();
     let (where_sql, params_vec) =
         build_summary_where_clause(start_ts, end_ts, cli_key, provider_id);
+    let rollup = rollup_totals(conn, start_ts, end_ts, cli_key, provider_id)?;
     let sql = format!(
         r#"
 	SELECT
@@ -110,22 +188,21 @@ pub(super) fn summary_query(
     );
 
     conn.query_row(&sql, params_from_iter(params_vec), |row| {
-        let requests_success = row.get::<_, Option<i64>>("requests_success")?.unwrap_or(0);
-        let success_duration_ms_sum = row
-            .get::<_, Option<i64>>("success_duration_ms_sum")?
-            .unwrap_or(0);
-        let success_ttfb_ms_sum = row
-            .get::<_, Option<i64>>("success_ttfb_ms_sum")?
-            .unwrap_or(0);
-        let success_ttfb_ms_count = row
-            .get::<_, Option<i64>>("success_ttfb_ms_count")?
-            .unwrap_or(0);
-        let success_generation_ms_sum = row
-            .get::<_, Option<i64>>("success_generation_ms_sum")?
-            .unwrap_or(0);
-        let success_output_tokens_for_rate_sum = row
-            .get::<_, Option<i64>>("success_output_tokens_for_rate_sum")?
-            .unwrap_or(0);
+        let sum = |name: &str, rollup_value: i64| -> rusqlite::Result<i64> {
+            Ok(row
+                .get::<_, Option<i64>>(name)?
+                .unwrap_or(0)
+                .saturating_add(rollup_value))
+        };
+        let requests_success = sum("requests_success", rollup.requests)?;
+        let success_duration_ms_sum = sum("success_duration_ms_sum", rollup.duration_ms_sum)?;
+        let success_ttfb_ms_sum = sum("success_ttfb_ms_sum", rollup.ttfb_ms_sum)?;
+        let success_ttfb_ms_count = sum("success_ttfb_ms_count", rollup.ttfb_count)?;
+        let success_generation_ms_sum = sum("success_generation_ms_sum", rollup.generation_ms_sum)?;
+        let success_output_tokens_for_rate_sum = sum(
+            "success_output_tokens_for_rate_sum",
+            rollup.output_tokens_for_rate_sum,
+        )?;
 
         let avg_duration_ms = if requests_success > 0 {
             Some(success_duration_ms_sum / requests_success)
@@ -146,15 +223,15 @@ pub(super) fn summary_query(
             None
         };
 
-        let input_tokens = row.get::<_, Option<i64>>("input_tokens")?.unwrap_or(0);
-        let output_tokens = row.get::<_, Option<i64>>("output_tokens")?.unwrap_or(0);
+        let input_tokens = sum("input_tokens", rollup.input_tokens)?;
+        let output_tokens = sum("output_tokens", rollup.output_tokens)?;
         let io_total_tokens = input_tokens.saturating_add(output_tokens);
 
         Ok(UsageSummary {
-            requests_total: row.get::<_, i64>("requests_total")?,
-            requests_with_usage: row
-                .get::<_, Option<i64>>("requests_with_usage")?
-                .unwrap_or(0),
+            requests_total: row
+                .get::<_, i64>("requests_total")?
+                .saturating_add(rollup.requests),
+            requests_with_usage: sum("requests_with_usage", rollup.requests_with_usage)?,
             requests_success,
             requests_failed: row.get::<_, Option<i64>>("requests_failed")?.unwrap_or(0),
             avg_duration_ms,
@@ -163,19 +240,23 @@ pub(super) fn summary_query(
             input_tokens,
             output_tokens,
             io_total_tokens,
-            total_tokens: row.get::<_, Option<i64>>("total_tokens")?.unwrap_or(0),
-            cache_read_input_tokens: row
-                .get::<_, Option<i64>>("cache_read_input_tokens")?
-                .unwrap_or(0),
-            cache_creation_input_tokens: row
-                .get::<_, Option<i64>>("cache_creation_input_tokens")?
-                .unwrap_or(0),
-            cache_creation_5m_input_tokens: row
-                .get::<_, Option<i64>>("cache_creation_5m_input_tokens")?
-                .unwrap_or(0),
-            cache_creation_1h_input_tokens: row
-                .get::<_, Option<i64>>("cache_creation_1h_input_tokens")?
-                .unwrap_or(0),
+            total_tokens: sum("total_tokens", rollup.total_tokens)?,
+            cache_read_input_tokens: sum(
+                "cache_read_input_tokens",
+                rollup.cache_read_input_tokens,
+            )?,
+            cache_creation_input_tokens: sum(
+                "cache_creation_input_tokens",
+                rollup.cache_creation_input_tokens,
+            )?,
+            cache_creation_5m_input_tokens: sum(
+                "cache_creation_5m_input_tokens",
+                rollup.cache_creation_5m_input_tokens,
+            )?,
+            cache_creation_1h_input_tokens: sum(
+                "cache_creation_1h_input_tokens",
+                rollup.cache_creation_1h_input_tokens,
+            )?,
         })
     })
     .map_err(|e| format!("DB_ERROR: failed to query usage summary: {e}"))
@@ -1,4 +1,7 @@
+use super::bandwidth_v1::bandwidth_v1_with_conn;
 use super::cache_rate_trend_v1::provider_cache_rate_trend_v1_with_conn;
+use super::forecast_v1::{fit_daily_model, project_month, MonthCalendar};
+use super::heatmap_v1::heatmap_v1_with_conn;
 use super::leaderboard_v2::leaderboard_v2_with_conn;
 use super::summary::summary_query;
 use super::*;
@@ -32,8 +35,35 @@ fn setup_conn() -> Connection {
 	  cost_usd_femto INTEGER,
 	  usage_json TEXT,
 	  excluded_from_stats INTEGER NOT NULL DEFAULT 0,
+	  request_bytes INTEGER,
+	  response_bytes INTEGER,
 	  created_at INTEGER NOT NULL
 	);
+
+	CREATE TABLE request_log_rollups (
+	  bucket_start INTEGER NOT NULL,
+	  cli_key TEXT NOT NULL,
+	  final_provider_id INTEGER NOT NULL DEFAULT 0,
+	  requested_model TEXT NOT NULL DEFAULT '',
+	  request_count INTEGER NOT NULL DEFAULT 0,
+	  requests_with_usage INTEGER NOT NULL DEFAULT 0,
+	  duration_ms_sum INTEGER NOT NULL DEFAULT 0,
+	  ttfb_ms_sum INTEGER NOT NULL DEFAULT 0,
+	  ttfb_count INTEGER NOT NULL DEFAULT 0,
+	  generation_ms_sum INTEGER NOT NULL DEFAULT 0,
+	  output_tokens_for_rate_sum INTEGER NOT NULL DEFAULT 0,
+	  input_tokens INTEGER NOT NULL DEFAULT 0,
+	  output_tokens INTEGER NOT NULL DEFAULT 0,
+	  total_tokens INTEGER NOT NULL DEFAULT 0,
+	  cache_read_input_tokens INTEGER NOT NULL DEFAULT 0,
+	  cache_creation_input_tokens INTEGER NOT NULL DEFAULT 0,
+	  cache_creation_5m_input_tokens INTEGER NOT NULL DEFAULT 0,
+	  cache_creation_1h_input_tokens INTEGER NOT NULL DEFAULT 0,
+	  cost_usd_femto INTEGER NOT NULL DEFAULT 0,
+	  request_bytes INTEGER NOT NULL DEFAULT 0,
+	  response_bytes INTEGER NOT NULL DEFAULT 0,
+	  PRIMARY KEY(bucket_start, cli_key, final_provider_id, requested_model)
+	);
 	"#,
     )
     .expect("create schema");
@@ -427,6 +457,81 @@ INSERT INTO request_logs (
     assert_eq!(rows_day[0].requests_success, 2);
 }
 
+#[test]
+fn v1_bandwidth_sums_bytes_per_provider_and_day() {
+    let conn = setup_conn();
+
+    conn.execute(
+        r#"INSERT INTO providers (id, name) VALUES (?1, ?2);"#,
+        params![7, "Relay"],
+    )
+    .expect("insert provider");
+
+    let start_ts_today = compute_start_ts(&conn, UsageRange::Today)
+        .expect("compute_start_ts today")
+        .expect("start ts exists");
+
+    for (created_at, status, request_bytes, response_bytes) in [
+        (start_ts_today + 60, 200i64, Some(1_000i64), Some(4_000i64)),
+        (start_ts_today + 120, 502i64, Some(500i64), None),
+        // Logged before byte tracking existed.
+        (start_ts_today + 180, 200i64, None, None),
+        (
+            start_ts_today - 86_400 + 60,
+            200i64,
+            Some(10i64),
+            Some(20i64),
+        ),
+    ] {
+        conn.execute(
+            r#"
+INSERT INTO request_logs (
+  cli_key,
+  attempts_json,
+  final_provider_id,
+  status,
+  duration_ms,
+  request_bytes,
+  response_bytes,
+  created_at
+) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8);
+            "#,
+            params![
+                "claude",
+                "[]",
+                7,
+                status,
+                1000,
+                request_bytes,
+                response_bytes,
+                created_at
+            ],
+        )
+        .expect("insert request log");
+    }
+
+    let rows = bandwidth_v1_with_conn(
+        &conn,
+        Some(start_ts_today - 86_400),
+        Some(start_ts_today + 86_400),
+        Some("claude"),
+        None,
+    )
+    .expect("bandwidth_v1_with_conn");
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].request_bytes, 10);
+    assert_eq!(rows[0].response_bytes, 20);
+
+    let today = &rows[1];
+    assert_eq!(today.provider_id, 7);
+    assert_eq!(today.provider_name.as_deref(), Some("Relay"));
+    assert_eq!(today.requests, 3);
+    assert_eq!(today.requests_with_bytes, 2);
+    assert_eq!(today.request_bytes, 1_500);
+    assert_eq!(today.response_bytes, 4_000);
+}
+
 #[test]
 fn v2_queries_apply_provider_filter() {
     let conn = setup_conn();
@@ -528,3 +633,128 @@ INSERT INTO request_logs (
     assert_eq!(cache_rows.len(), 1);
     assert_eq!(cache_rows[0].key, "codex:123");
 }
+
+#[test]
+fn summary_includes_sampled_out_rollups() {
+    let conn = setup_conn();
+
+    conn.execute(
+        r#"
+INSERT INTO request_log_rollups (
+  bucket_start,
+  cli_key,
+  final_provider_id,
+  requested_model,
+  request_count,
+  requests_with_usage,
+  duration_ms_sum,
+  input_tokens,
+  output_tokens,
+  total_tokens
+) VALUES (3600, 'claude', 7, 'claude-test', 4, 4, 2000, 400, 40, 440);
+        "#,
+        [],
+    )
+    .expect("insert rollup");
+
+    let summary = summary_query(&conn, None, None, None, None).expect("summary_query");
+    assert_eq!(summary.requests_total, 4);
+    assert_eq!(summary.requests_success, 4);
+    assert_eq!(summary.requests_failed, 0);
+    assert_eq!(summary.avg_duration_ms, Some(500));
+    assert_eq!(summary.input_tokens, 400);
+    assert_eq!(summary.total_tokens, 440);
+
+    let other_provider = summary_query(&conn, None, None, None, Some(8)).expect("filtered summary");
+    assert_eq!(other_provider.requests_total, 0);
+}
+
+#[test]
+fn forecast_projects_weekday_pattern_and_budget_runway() {
+    // Weekdays (Mon-Fri) spend 10/day, weekends nothing.
+    let weekdays: Vec<usize> = (0..28).map(|i| i % 7).collect();
+    let values: Vec<f64> = weekdays
+        .iter()
+        .map(|w| if *w == 0 || *w == 6 { 0.0 } else { 10.0 })
+        .collect();
+    let model = fit_daily_model(&values, &weekdays);
+    assert!((model.predict(28.0, 3) - 10.0).abs() < 1e-6);
+    assert!(model.predict(28.0, 0).abs() < 1e-6);
+
+    // Today is Wednesday the 10th of a 30-day month with 4.0 spent so far today.
+    let calendar = MonthCalendar {
+        day_of_month: 10,
+        days_in_month: 30,
+        today_weekday: 3,
+    };
+    let mut by_days_ago = vec![4.0];
+    by_days_ago.extend((1..=28).map(|d| {
+        if matches!((3 + 35 - d) % 7, 0 | 6) {
+            0.0
+        } else {
+            10.0
+        }
+    }));
+
+    let projection = project_month(&by_days_ago, calendar, Some(99.0));
+    // 9 full days so far (7 weekdays) + 4 today.
+    assert!((projection.month_to_date - 74.0).abs() < 1e-6);
+    // Rest of today (6) + 20 more days of which 14 are weekdays.
+    assert!((projection.expected - (74.0 + 6.0 + 140.0)).abs() < 1e-6);
+    assert!((projection.low - projection.expected).abs() < 1e-6);
+    // 25 budget left: today 6, Thu 10, then Fri crosses it.
+    assert_eq!(projection.days_until, Some(2));
+
+    assert_eq!(
+        project_month(&by_days_ago, calendar, Some(10_000.0)).days_until,
+        None
+    );
+    assert_eq!(
+        project_month(&by_days_ago, calendar, Some(50.0)).days_until,
+        Some(0)
+    );
+}
+
+#[test]
+fn heatmap_merges_logs_and_rollups_into_one_cell() {
+    let conn = setup_conn();
+    let ts = 1_700_000_000_i64;
+
+    for (provider_id, excluded, cost_femto) in
+        [(7, 0, 500_000_000_000_000_i64), (7, 1, 1), (8, 0, 1)]
+    {
+        conn.execute(
+            r#"
+INSERT INTO request_logs (
+  cli_key, attempts_json, final_provider_id, status, duration_ms,
+  input_tokens, output_tokens, total_tokens, cost_usd_femto, excluded_from_stats, created_at
+) VALUES ('claude', '[]', ?1, 200, 100, 10, 5, 15, ?2, ?3, ?4)
+            "#,
+            params![provider_id, cost_femto, excluded, ts],
+        )
+        .expect("insert request log");
+    }
+    conn.execute(
+        r#"
+INSERT INTO request_log_rollups (
+  bucket_start, cli_key, final_provider_id, requested_model, request_count,
+  input_tokens, output_tokens, total_tokens, cost_usd_femto
+) VALUES (?1, 'claude', 7, 'claude-test', 3, 30, 15, 45, 250000000000000)
+        "#,
+        params![ts],
+    )
+    .expect("insert rollup");
+
+    let cells = heatmap_v1_with_conn(&conn, None, None, Some("claude"), Some(7)).expect("heatmap");
+    assert_eq!(cells.len(), 1);
+    let cell = &cells[0];
+    assert!((0..7).contains(&cell.weekday));
+    assert!((0..24).contains(&cell.hour));
+    assert_eq!(cell.requests, 4);
+    assert_eq!(cell.input_tokens, 40);
+    assert_eq!(cell.total_tokens, 60);
+    assert!((cell.cost_usd - 0.75).abs() < 1e-9);
+
+    let later = heatmap_v1_with_conn(&conn, Some(ts + 1), None, None, None).expect("heatmap");
+    assert!(later.is_empty());
+}
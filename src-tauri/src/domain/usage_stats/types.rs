@@ -1,6 +1,6 @@
 use serde::Serialize;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct UsageSummary {
     pub requests_total: i64,
     pub requests_with_usage: i64,
@@ -19,7 +19,7 @@ pub struct UsageSummary {
     pub cache_creation_1h_input_tokens: i64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct UsageProviderRow {
     pub cli_key: String,
     pub provider_id: i64,
@@ -39,7 +39,7 @@ pub struct UsageProviderRow {
     pub cache_creation_1h_input_tokens: i64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct UsageDayRow {
     pub day: String,
     pub requests_total: i64,
@@ -52,7 +52,7 @@ pub struct UsageDayRow {
     pub cache_creation_1h_input_tokens: i64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct UsageHourlyRow {
     pub day: String,
     pub hour: i64,
@@ -63,7 +63,7 @@ pub struct UsageHourlyRow {
     pub total_tokens: i64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct UsageProviderCacheRateTrendRowV1 {
     pub day: String,
     pub hour: Option<i64>,
@@ -74,7 +74,34 @@ pub struct UsageProviderCacheRateTrendRowV1 {
     pub requests_success: i64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct UsageBandwidthRowV1 {
+    /// `YYYY-MM-DD` (local time).
+    pub day: String,
+    pub cli_key: String,
+    pub provider_id: i64,
+    pub provider_name: Option<String>,
+    pub requests: i64,
+    /// Requests with recorded body sizes (older logs have none).
+    pub requests_with_bytes: i64,
+    pub request_bytes: i64,
+    pub response_bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct UsageHeatmapCellV1 {
+    /// Local weekday, `0` = Sunday … `6` = Saturday.
+    pub weekday: i64,
+    /// Local hour `0..=23`.
+    pub hour: i64,
+    pub requests: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub total_tokens: i64,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct UsageLeaderboardRow {
     pub key: String,
     pub name: String,
@@ -92,3 +119,32 @@ pub struct UsageLeaderboardRow {
     pub avg_output_tokens_per_second: Option<f64>,
     pub cost_usd: Option<f64>,
 }
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct UsageForecastRowV1 {
+    pub provider_id: i64,
+    pub cli_key: String,
+    pub provider_name: String,
+    pub month_to_date_tokens: i64,
+    pub expected_total_tokens: i64,
+    pub expected_total_tokens_low: i64,
+    pub expected_total_tokens_high: i64,
+    pub month_to_date_cost_usd: f64,
+    pub expected_total_cost_usd: f64,
+    pub expected_total_cost_usd_low: f64,
+    pub expected_total_cost_usd_high: f64,
+    /// Tighter of `limit_monthly_usd` / `monthly_budget_usd`.
+    pub budget_usd: Option<f64>,
+    /// 0 = today; `None` when the budget is not reached before the month ends.
+    pub days_until_budget_exhausted: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct UsageForecastV1 {
+    /// `YYYY-MM` (local time).
+    pub month: String,
+    pub day_of_month: u32,
+    pub days_in_month: u32,
+    pub history_days: u32,
+    pub rows: Vec<UsageForecastRowV1>,
+}
@@ -0,0 +1,478 @@
+//! Usage: Workspace snapshots (captured before every apply) and one-click rollback.
+//!
+//! A snapshot records the effective state of one cli: the active workspace, its enabled
+//! prompt/MCP servers/skills and the raw bytes of the CLI config files the sync layer writes
+//! (prompt + MCP targets and their manifests). Rollback restores all of it or nothing.
+
+use crate::db;
+use crate::mcp_sync;
+use crate::prompt_sync;
+use crate::shared::error::db_err;
+use crate::shared::time::now_unix_seconds;
+use crate::{mcp, prompts, skills, workspace_switch, workspaces};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+const KEEP_MAX_SNAPSHOTS_PER_CLI: i64 = 20;
+
+pub(crate) const SNAPSHOT_REASON_APPLY: &str = "workspace_apply";
+const SNAPSHOT_REASON_ROLLBACK: &str = "rollback";
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct WorkspaceSnapshotSummary {
+    pub id: i64,
+    pub cli_key: String,
+    pub workspace_id: Option<i64>,
+    /// `workspace_apply` or `rollback` (state captured right before a rollback).
+    pub reason: String,
+    pub enabled_prompt_name: Option<String>,
+    pub enabled_mcp_server_keys: Vec<String>,
+    pub enabled_skill_keys: Vec<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct WorkspaceRollbackReport {
+    pub cli_key: String,
+    pub snapshot_id: i64,
+    pub workspace_id: i64,
+    /// Snapshot of the state replaced by this rollback, so it can be undone.
+    pub undo_snapshot_id: i64,
+    pub rolled_back_at: i64,
+}
+
+/// Raw file contents, base64-encoded; `None` means the file did not exist.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+struct SnapshotFiles {
+    prompt_target: Option<String>,
+    prompt_manifest: Option<String>,
+    mcp_target: Option<String>,
+    mcp_manifest: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+struct SnapshotState {
+    workspace_id: Option<i64>,
+    enabled_prompt_id: Option<i64>,
+    enabled_prompt_name: Option<String>,
+    enabled_mcp_server_ids: Vec<i64>,
+    enabled_mcp_server_keys: Vec<String>,
+    enabled_skill_ids: Vec<i64>,
+    enabled_skill_keys: Vec<String>,
+    files: SnapshotFiles,
+}
+
+struct RawFiles {
+    prompt_target: Option<Vec<u8>>,
+    prompt_manifest: Option<Vec<u8>>,
+    mcp_target: Option<Vec<u8>>,
+    mcp_manifest: Option<Vec<u8>>,
+}
+
+fn encode(bytes: Option<Vec<u8>>) -> Option<String> {
+    bytes.map(|b| STANDARD.encode(b))
+}
+
+fn decode(value: Option<&str>) -> crate::shared::error::AppResult<Option<Vec<u8>>> {
+    value
+        .map(|v| {
+            STANDARD
+                .decode(v)
+                .map_err(|e| format!("SYSTEM_ERROR: corrupted workspace snapshot file: {e}").into())
+        })
+        .transpose()
+}
+
+fn read_files(app: &crate::AppHandle, cli_key: &str) -> crate::shared::error::AppResult<RawFiles> {
+    Ok(RawFiles {
+        prompt_target: prompt_sync::read_target_bytes(app, cli_key)?,
+        prompt_manifest: prompt_sync::read_manifest_bytes(app, cli_key)?,
+        mcp_target: mcp_sync::read_target_bytes(app, cli_key)?,
+        mcp_manifest: mcp_sync::read_manifest_bytes(app, cli_key)?,
+    })
+}
+
+fn write_files(
+    app: &crate::AppHandle,
+    cli_key: &str,
+    files: RawFiles,
+) -> crate::shared::error::AppResult<()> {
+    prompt_sync::restore_target_bytes(app, cli_key, files.prompt_target)?;
+    prompt_sync::restore_manifest_bytes(app, cli_key, files.prompt_manifest)?;
+    mcp_sync::restore_target_bytes(app, cli_key, files.mcp_target)?;
+    mcp_sync::restore_manifest_bytes(app, cli_key, files.mcp_manifest)?;
+    Ok(())
+}
+
+fn list_pairs(
+    conn: &Connection,
+    sql: &str,
+    workspace_id: i64,
+) -> crate::shared::error::AppResult<(Vec<i64>, Vec<String>)> {
+    let mut stmt = conn
+        .prepare_cached(sql)
+        .map_err(|e| db_err!("failed to prepare workspace snapshot query: {e}"))?;
+    let rows = stmt
+        .query_map(params![workspace_id], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|e| db_err!("failed to query workspace snapshot items: {e}"))?;
+
+    let mut ids = Vec::new();
+    let mut keys = Vec::new();
+    for row in rows {
+        let (id, key) = row.map_err(|e| db_err!("failed to read workspace snapshot item: {e}"))?;
+        ids.push(id);
+        keys.push(key);
+    }
+    Ok((ids, keys))
+}
+
+fn capture_state(
+    app: &crate::AppHandle,
+    conn: &Connection,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<SnapshotState> {
+    let workspace_id = workspaces::active_id_by_cli(conn, cli_key)?;
+    let mut state = SnapshotState {
+        workspace_id,
+        ..Default::default()
+    };
+
+    if let Some(workspace_id) = workspace_id {
+        let prompt: Option<(i64, String)> = conn
+            .query_row(
+                r#"
+SELECT id, name
+FROM prompts
+WHERE workspace_id = ?1 AND enabled = 1
+ORDER BY updated_at DESC, id DESC
+LIMIT 1
+"#,
+                params![workspace_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(|e| db_err!("failed to query enabled prompt: {e}"))?;
+        state.enabled_prompt_id = prompt.as_ref().map(|p| p.0);
+        state.enabled_prompt_name = prompt.map(|p| p.1);
+
+        (state.enabled_mcp_server_ids, state.enabled_mcp_server_keys) = list_pairs(
+            conn,
+            r#"
+SELECT s.id, s.server_key
+FROM mcp_servers s
+JOIN workspace_mcp_enabled e ON e.server_id = s.id
+WHERE e.workspace_id = ?1
+ORDER BY s.server_key ASC
+"#,
+            workspace_id,
+        )?;
+        (state.enabled_skill_ids, state.enabled_skill_keys) = list_pairs(
+            conn,
+            r#"
+SELECT s.id, s.skill_key
+FROM skills s
+JOIN workspace_skill_enabled e ON e.skill_id = s.id
+WHERE e.workspace_id = ?1
+ORDER BY s.skill_key ASC
+"#,
+            workspace_id,
+        )?;
+    }
+
+    let files = read_files(app, cli_key)?;
+    state.files = SnapshotFiles {
+        prompt_target: encode(files.prompt_target),
+        prompt_manifest: encode(files.prompt_manifest),
+        mcp_target: encode(files.mcp_target),
+        mcp_manifest: encode(files.mcp_manifest),
+    };
+    Ok(state)
+}
+
+/// Capture the cli's current effective state and keep the most recent snapshots only.
+pub(crate) fn capture(
+    app: &crate::AppHandle,
+    conn: &Connection,
+    cli_key: &str,
+    reason: &str,
+) -> crate::shared::error::AppResult<i64> {
+    let state = capture_state(app, conn, cli_key)?;
+    let state_json = serde_json::to_string(&state)
+        .map_err(|e| format!("SYSTEM_ERROR: failed to serialize workspace snapshot: {e}"))?;
+
+    conn.execute(
+        r#"
+INSERT INTO workspace_snapshots(
+  cli_key,
+  workspace_id,
+  reason,
+  state_json,
+  created_at
+) VALUES (?1, ?2, ?3, ?4, ?5)
+"#,
+        params![
+            cli_key,
+            state.workspace_id,
+            reason,
+            state_json,
+            now_unix_seconds()
+        ],
+    )
+    .map_err(|e| db_err!("failed to insert workspace snapshot: {e}"))?;
+    let id = conn.last_insert_rowid();
+
+    conn.execute(
+        r#"
+DELETE FROM workspace_snapshots
+WHERE cli_key = ?1
+  AND id NOT IN (
+    SELECT id
+    FROM workspace_snapshots
+    WHERE cli_key = ?1
+    ORDER BY id DESC
+    LIMIT ?2
+  )
+"#,
+        params![cli_key, KEEP_MAX_SNAPSHOTS_PER_CLI],
+    )
+    .map_err(|e| db_err!("failed to prune workspace snapshots: {e}"))?;
+
+    Ok(id)
+}
+
+fn parse_state(state_json: &str) -> crate::shared::error::AppResult<SnapshotState> {
+    serde_json::from_str(state_json)
+        .map_err(|e| format!("SYSTEM_ERROR: corrupted workspace snapshot: {e}").into())
+}
+
+pub fn list(
+    db: &db::Db,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<Vec<WorkspaceSnapshotSummary>> {
+    let cli_key = cli_key.trim();
+    crate::shared::cli_key::validate_cli_key(cli_key)?;
+    let conn = db.open_connection()?;
+
+    let mut stmt = conn
+        .prepare_cached(
+            r#"
+SELECT
+  id,
+  cli_key,
+  workspace_id,
+  reason,
+  state_json,
+  created_at
+FROM workspace_snapshots
+WHERE cli_key = ?1
+ORDER BY id DESC
+"#,
+        )
+        .map_err(|e| db_err!("failed to prepare workspace_snapshots query: {e}"))?;
+
+    let rows = stmt
+        .query_map(params![cli_key], |row| {
+            Ok((
+                row.get::<_, i64>("id")?,
+                row.get::<_, String>("cli_key")?,
+                row.get::<_, Option<i64>>("workspace_id")?,
+                row.get::<_, String>("reason")?,
+                row.get::<_, String>("state_json")?,
+                row.get::<_, i64>("created_at")?,
+            ))
+        })
+        .map_err(|e| db_err!("failed to list workspace_snapshots: {e}"))?;
+
+    let mut items = Vec::new();
+    for row in rows {
+        let (id, cli_key, workspace_id, reason, state_json, created_at) =
+            row.map_err(|e| db_err!("failed to read workspace_snapshot row: {e}"))?;
+        let state = parse_state(&state_json).unwrap_or_default();
+        items.push(WorkspaceSnapshotSummary {
+            id,
+            cli_key,
+            workspace_id,
+            reason,
+            enabled_prompt_name: state.enabled_prompt_name,
+            enabled_mcp_server_keys: state.enabled_mcp_server_keys,
+            enabled_skill_keys: state.enabled_skill_keys,
+            created_at,
+        });
+    }
+    Ok(items)
+}
+
+/// Restore the workspace's enabled prompt/MCP/skill rows; items deleted since are ignored.
+fn restore_enabled_rows(
+    conn: &Connection,
+    workspace_id: i64,
+    state: &SnapshotState,
+) -> crate::shared::error::AppResult<()> {
+    let now = now_unix_seconds();
+
+    conn.execute(
+        "UPDATE prompts SET enabled = 0 WHERE workspace_id = ?1 AND enabled = 1",
+        params![workspace_id],
+    )
+    .map_err(|e| db_err!("failed to clear enabled prompts: {e}"))?;
+    if let Some(prompt_id) = state.enabled_prompt_id {
+        conn.execute(
+            "UPDATE prompts SET enabled = 1, updated_at = ?1 WHERE id = ?2 AND workspace_id = ?3",
+            params![now, prompt_id, workspace_id],
+        )
+        .map_err(|e| db_err!("failed to restore enabled prompt: {e}"))?;
+    }
+
+    for (table, column, ref_table, ids) in [
+        (
+            "workspace_mcp_enabled",
+            "server_id",
+            "mcp_servers",
+            &state.enabled_mcp_server_ids,
+        ),
+        (
+            "workspace_skill_enabled",
+            "skill_id",
+            "skills",
+            &state.enabled_skill_ids,
+        ),
+    ] {
+        conn.execute(
+            &format!("DELETE FROM {table} WHERE workspace_id = ?1"),
+            params![workspace_id],
+        )
+        .map_err(|e| db_err!("failed to clear {table}: {e}"))?;
+        if ids.is_empty() {
+            continue;
+        }
+        let placeholders = vec!["?"; ids.len()].join(", ");
+        let sql = format!(
+            "INSERT INTO {table}(workspace_id, {column}, created_at, updated_at) \
+             SELECT {workspace_id}, id, {now}, {now} FROM {ref_table} WHERE id IN ({placeholders})"
+        );
+        conn.execute(&sql, params_from_iter(ids.iter()))
+            .map_err(|e| db_err!("failed to restore {table}: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Resync the CLI from the (uncommitted) restored rows, then put the snapshot's exact file
+/// bytes back on top.
+fn sync_and_restore_files(
+    app: &crate::AppHandle,
+    conn: &Connection,
+    cli_key: &str,
+    workspace_id: i64,
+    files: &SnapshotFiles,
+) -> crate::shared::error::AppResult<()> {
+    prompts::sync_cli_for_workspace(app, conn, workspace_id)?;
+    mcp::sync_cli_for_workspace(app, conn, workspace_id)?;
+    skills::sync_cli_for_workspace(app, conn, workspace_id)?;
+    write_files(
+        app,
+        cli_key,
+        RawFiles {
+            prompt_target: decode(files.prompt_target.as_deref())?,
+            prompt_manifest: decode(files.prompt_manifest.as_deref())?,
+            mcp_target: decode(files.mcp_target.as_deref())?,
+            mcp_manifest: decode(files.mcp_manifest.as_deref())?,
+        },
+    )
+}
+
+pub fn rollback(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    snapshot_id: i64,
+) -> crate::shared::error::AppResult<WorkspaceRollbackReport> {
+    if snapshot_id <= 0 {
+        return Err("SEC_INVALID_INPUT: invalid snapshot_id".into());
+    }
+
+    let mut conn = db.open_connection()?;
+    let (cli_key, state_json): (String, String) = conn
+        .query_row(
+            "SELECT cli_key, state_json FROM workspace_snapshots WHERE id = ?1",
+            params![snapshot_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query workspace snapshot: {e}"))?
+        .ok_or_else(|| "DB_NOT_FOUND: workspace snapshot not found".to_string())?;
+    let state = parse_state(&state_json)?;
+    let Some(workspace_id) = state.workspace_id else {
+        return Err("SEC_INVALID_INPUT: snapshot has no active workspace to restore".into());
+    };
+    // Fails with DB_NOT_FOUND when the workspace was deleted after the snapshot.
+    workspaces::get_cli_key_by_id(&conn, workspace_id)?;
+
+    let undo_snapshot_id = capture(app, &conn, &cli_key, SNAPSHOT_REASON_ROLLBACK)?;
+
+    // Switching workspaces also swaps local plugins/skills; it rolls itself back on failure.
+    if workspaces::active_id_by_cli(&conn, &cli_key)? != Some(workspace_id) {
+        workspace_switch::switch_to(app, db, workspace_id)?;
+    }
+
+    let files_before = read_files(app, &cli_key)?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| db_err!("failed to start transaction: {e}"))?;
+    let restored = restore_enabled_rows(&tx, workspace_id, &state)
+        .and_then(|()| sync_and_restore_files(app, &tx, &cli_key, workspace_id, &state.files));
+    if let Err(err) = restored.and_then(|()| {
+        tx.commit()
+            .map_err(|e| db_err!("failed to commit transaction: {e}"))
+    }) {
+        let _ = write_files(app, &cli_key, files_before);
+        if let Ok(conn) = db.open_connection() {
+            let _ = skills::sync_cli_for_workspace(app, &conn, workspace_id);
+        }
+        return Err(err);
+    }
+
+    Ok(WorkspaceRollbackReport {
+        cli_key,
+        snapshot_id,
+        workspace_id,
+        undo_snapshot_id,
+        rolled_back_at: now_unix_seconds(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode, parse_state, SnapshotFiles, SnapshotState};
+
+    #[test]
+    fn snapshot_state_round_trips_file_bytes() {
+        let state = SnapshotState {
+            workspace_id: Some(3),
+            enabled_prompt_id: Some(7),
+            enabled_prompt_name: Some("default".to_string()),
+            enabled_mcp_server_ids: vec![1, 2],
+            enabled_mcp_server_keys: vec!["fs".to_string(), "git".to_string()],
+            enabled_skill_ids: Vec::new(),
+            enabled_skill_keys: Vec::new(),
+            files: SnapshotFiles {
+                prompt_target: encode(Some(b"# prompt\n".to_vec())),
+                prompt_manifest: None,
+                mcp_target: encode(Some(vec![0, 159, 146, 150])),
+                mcp_manifest: None,
+            },
+        };
+
+        let json = serde_json::to_string(&state).unwrap();
+        let parsed = parse_state(&json).unwrap();
+        assert_eq!(parsed, state);
+        assert_eq!(
+            decode(parsed.files.mcp_target.as_deref()).unwrap(),
+            Some(vec![0, 159, 146, 150])
+        );
+        assert_eq!(decode(None).unwrap(), None);
+        assert!(decode(Some("not base64!")).is_err());
+    }
+}
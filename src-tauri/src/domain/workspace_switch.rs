@@ -1,30 +1,32 @@
 //! Usage: Workspace (profile) preview/apply orchestration.
 
 use crate::claude_plugins;
+use crate::claude_settings::ClaudeHooksSyncBackup;
 use crate::db;
 use crate::mcp_sync;
 use crate::prompt_sync;
 use crate::shared::error::db_err;
 use crate::shared::time::now_unix_seconds;
-use crate::{mcp, prompts, skills, workspaces};
+use crate::subagent_sync::SubagentSyncBackup;
+use crate::{hooks, mcp, prompts, skills, subagents, workspace_snapshots, workspaces};
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::Serialize;
 use std::collections::HashSet;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct WorkspaceEnabledPromptPreview {
     pub name: String,
     pub excerpt: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct WorkspacePromptsPreview {
     pub from_enabled: Option<WorkspaceEnabledPromptPreview>,
     pub to_enabled: Option<WorkspaceEnabledPromptPreview>,
     pub will_change: bool,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct WorkspaceItemsPreview {
     pub from_enabled: Vec<String>,
     pub to_enabled: Vec<String>,
@@ -32,7 +34,7 @@ pub struct WorkspaceItemsPreview {
     pub removed: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct WorkspacePreview {
     pub cli_key: String,
     pub from_workspace_id: Option<i64>,
@@ -42,7 +44,7 @@ pub struct WorkspacePreview {
     pub skills: WorkspaceItemsPreview,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct WorkspaceApplyReport {
     pub cli_key: String,
     pub from_workspace_id: Option<i64>,
@@ -237,8 +239,27 @@ pub fn preview(
     })
 }
 
+/// Switch to `workspace_id`, snapshotting the current state first so it can be rolled back.
 pub fn apply(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
+    db: &db::Db,
+    workspace_id: i64,
+) -> crate::shared::error::AppResult<WorkspaceApplyReport> {
+    let conn = db.open_connection()?;
+    let cli_key = workspaces::get_cli_key_by_id(&conn, workspace_id)?;
+    if workspaces::active_id_by_cli(&conn, &cli_key)? != Some(workspace_id) {
+        workspace_snapshots::capture(
+            app,
+            &conn,
+            &cli_key,
+            workspace_snapshots::SNAPSHOT_REASON_APPLY,
+        )?;
+    }
+    switch_to(app, db, workspace_id)
+}
+
+pub(crate) fn switch_to(
+    app: &crate::AppHandle,
     db: &db::Db,
     workspace_id: i64,
 ) -> crate::shared::error::AppResult<WorkspaceApplyReport> {
@@ -312,12 +333,82 @@ pub fn apply(
         None
     };
 
+    let hooks_backup = if cli_key == "claude" {
+        let synced = ClaudeHooksSyncBackup::capture(app).and_then(|backup| {
+            match hooks::sync_cli_for_workspace(app, &conn, workspace_id) {
+                Ok(()) => Ok(backup),
+                Err(err) => {
+                    backup.restore();
+                    Err(err)
+                }
+            }
+        });
+        match synced {
+            Ok(backup) => Some(backup),
+            Err(err) => {
+                let _ = prompt_sync::restore_target_bytes(app, &cli_key, prev_prompt_target);
+                let _ = prompt_sync::restore_manifest_bytes(app, &cli_key, prev_prompt_manifest);
+                let _ = mcp_sync::restore_target_bytes(app, &cli_key, prev_mcp_target);
+                let _ = mcp_sync::restore_manifest_bytes(app, &cli_key, prev_mcp_manifest);
+
+                if let Some(swap) = local_plugins_swap.take() {
+                    swap.rollback();
+                }
+
+                return Err(err);
+            }
+        }
+    } else {
+        None
+    };
+
+    let subagents_backup = if cli_key == "claude" {
+        let synced = SubagentSyncBackup::capture(app).and_then(|backup| {
+            match subagents::sync_cli_for_workspace(app, &conn, workspace_id) {
+                Ok(()) => Ok(backup),
+                Err(err) => {
+                    backup.restore();
+                    Err(err)
+                }
+            }
+        });
+        match synced {
+            Ok(backup) => Some(backup),
+            Err(err) => {
+                let _ = prompt_sync::restore_target_bytes(app, &cli_key, prev_prompt_target);
+                let _ = prompt_sync::restore_manifest_bytes(app, &cli_key, prev_prompt_manifest);
+                let _ = mcp_sync::restore_target_bytes(app, &cli_key, prev_mcp_target);
+                let _ = mcp_sync::restore_manifest_bytes(app, &cli_key, prev_mcp_manifest);
+
+                if let Some(backup) = hooks_backup {
+                    backup.restore();
+                }
+
+                if let Some(swap) = local_plugins_swap.take() {
+                    swap.rollback();
+                }
+
+                return Err(err);
+            }
+        }
+    } else {
+        None
+    };
+
     if let Err(err) = skills::sync_cli_for_workspace(app, &conn, workspace_id) {
         let _ = prompt_sync::restore_target_bytes(app, &cli_key, prev_prompt_target);
         let _ = prompt_sync::restore_manifest_bytes(app, &cli_key, prev_prompt_manifest);
         let _ = mcp_sync::restore_target_bytes(app, &cli_key, prev_mcp_target);
         let _ = mcp_sync::restore_manifest_bytes(app, &cli_key, prev_mcp_manifest);
 
+        if let Some(backup) = hooks_backup {
+            backup.restore();
+        }
+
+        if let Some(backup) = subagents_backup {
+            backup.restore();
+        }
+
         if let Some(swap) = local_plugins_swap.take() {
             swap.rollback();
         }
@@ -342,6 +433,14 @@ pub fn apply(
             let _ = mcp_sync::restore_target_bytes(app, &cli_key, prev_mcp_target);
             let _ = mcp_sync::restore_manifest_bytes(app, &cli_key, prev_mcp_manifest);
 
+            if let Some(backup) = hooks_backup {
+                backup.restore();
+            }
+
+            if let Some(backup) = subagents_backup {
+                backup.restore();
+            }
+
             if let Some(swap) = local_plugins_swap.take() {
                 swap.rollback();
             }
@@ -362,6 +461,14 @@ pub fn apply(
 
         local_skills_swap.rollback();
 
+        if let Some(backup) = hooks_backup {
+            backup.restore();
+        }
+
+        if let Some(backup) = subagents_backup {
+            backup.restore();
+        }
+
         if let Some(swap) = local_plugins_swap.take() {
             swap.rollback();
         }
@@ -1,32 +1,63 @@
+pub(crate) mod access_log;
+mod anthropic_headers;
 mod claude_metadata_user_id_injection;
+mod claude_models_endpoint;
 mod codex_session_id;
+pub(crate) mod error_body_capture;
 pub(crate) mod events;
 pub(crate) mod listen;
 mod manager;
+mod model_capabilities;
 pub(crate) mod oauth;
+pub(crate) mod oauth_pacing;
+mod provider_webhook;
 mod proxy;
+mod refusal_detector;
 mod response_fixer;
+pub(crate) mod response_headers;
 mod routes;
+pub(crate) mod routing_pause;
 pub(crate) mod session_manager;
+pub(crate) mod session_throttle;
 mod streams;
+mod thinking_budget_governor;
 mod thinking_budget_rectifier;
 mod thinking_signature_rectifier;
+mod token_estimate;
+mod upstream_dns;
+mod upstream_warm_pool;
 pub(crate) mod util;
 mod warmup;
 
+pub(crate) use manager::validate_port;
 pub use manager::GatewayManager;
+pub(crate) use proxy::{error_catalog_entries, ErrorCatalogEntry};
 
 use serde::Serialize;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type, tauri_specta::Event)]
+#[tauri_specta(event_name = "gateway:status")]
 pub struct GatewayStatus {
     pub running: bool,
     pub port: Option<u16>,
     pub base_url: Option<String>,
     pub listen_addr: Option<String>,
+    pub warm_pool: Vec<GatewayUpstreamWarmStatus>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct GatewayUpstreamWarmStatus {
+    pub provider_id: i64,
+    pub cli_key: String,
+    pub provider_name: String,
+    pub origin: String,
+    pub warm_connections: u32,
+    pub http_version: Option<String>,
+    pub last_warmed_at: Option<i64>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct GatewayProviderCircuitStatus {
     pub provider_id: i64,
     pub state: String,
@@ -35,3 +66,61 @@ pub struct GatewayProviderCircuitStatus {
     pub open_until: Option<i64>,
     pub cooldown_until: Option<i64>,
 }
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct GatewayProviderQuality {
+    pub provider_id: i64,
+    pub cli_key: String,
+    pub provider_name: String,
+    pub window_seconds: u32,
+    pub total: u32,
+    pub errors: u32,
+    pub truncated: u32,
+    pub error_rate: f64,
+    pub truncated_rate: f64,
+    pub quarantined_until: Option<i64>,
+    pub quarantine_count: u32,
+}
+
+/// Realtime per-provider load, computed from in-memory gateway counters.
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct GatewayProviderLiveStats {
+    pub provider_id: i64,
+    pub cli_key: String,
+    pub provider_name: String,
+    /// Upstream attempts currently in flight (streams count until the body relay ends).
+    pub in_flight: u32,
+    pub active_streams: u32,
+    pub requests_last_minute: u32,
+    /// Rolling average time to first byte over the last 20 successful attempts.
+    pub avg_ttft_ms: Option<u32>,
+    pub circuit_state: String,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct GatewayOAuthQuotaWindow {
+    /// `primary` / `secondary` (Codex usage windows) or `requests` (`x-ratelimit-*`).
+    pub name: String,
+    pub used_percent: Option<f64>,
+    pub remaining_requests: Option<f64>,
+    pub window_minutes: Option<u32>,
+    pub resets_at: i64,
+    /// Learned usage percent one request costs.
+    pub percent_per_request: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct GatewayOAuthPacingState {
+    pub provider_id: i64,
+    pub cli_key: String,
+    pub provider_name: String,
+    /// Sustainable rate learned from quota headers; `None` while no window constrains it.
+    pub requests_per_minute: Option<f64>,
+    pub tokens: f64,
+    pub burst: f64,
+    pub paced_requests: u64,
+    pub total_delay_ms: u64,
+    pub last_delay_ms: u64,
+    pub observed_at: i64,
+    pub windows: Vec<GatewayOAuthQuotaWindow>,
+}
@@ -0,0 +1,249 @@
+//! Usage: Optional on-disk gateway access log (Common Log Format or JSON lines), independent of
+//! SQLite, for external tools such as GoAccess or lnav.
+//!
+//! - Written from the request-end pipeline, one line per finished request.
+//! - Files live in `<app data>/logs/access/` as `access.YYYY-MM-DD.log` (daily UTC rotation);
+//!   at most `access_log_retention_days` files are kept.
+//! - The request-end pipeline does not carry the peer address, so the CLF host field is `-`.
+//!   The authuser field holds the CLI key.
+
+use crate::settings::AccessLogFormat;
+use crate::shared::mutex_ext::MutexExt;
+use crate::{app_paths, redaction};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+const ACCESS_LOG_FILE_PREFIX: &str = "access";
+const ACCESS_LOG_FILE_SUFFIX: &str = "log";
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+struct Sink {
+    format: AccessLogFormat,
+    writer: NonBlocking,
+    // Dropping the guard flushes pending lines (on reconfigure / shutdown).
+    _guard: WorkerGuard,
+}
+
+static SINK: OnceLock<Mutex<Option<Sink>>> = OnceLock::new();
+
+fn sink() -> &'static Mutex<Option<Sink>> {
+    SINK.get_or_init(|| Mutex::new(None))
+}
+
+/// One finished gateway request.
+pub(crate) struct AccessLogEntry<'a> {
+    pub(crate) created_at_ms: i64,
+    pub(crate) trace_id: &'a str,
+    pub(crate) cli_key: &'a str,
+    pub(crate) method: &'a str,
+    pub(crate) path: &'a str,
+    pub(crate) query: Option<&'a str>,
+    pub(crate) status: Option<u16>,
+    pub(crate) error_code: Option<&'a str>,
+    pub(crate) duration_ms: u128,
+    pub(crate) ttfb_ms: Option<u128>,
+    /// Body bytes relayed to the client, when the relay recorded them.
+    pub(crate) bytes: Option<u64>,
+    pub(crate) requested_model: Option<&'a str>,
+    pub(crate) provider_name: Option<&'a str>,
+}
+
+fn access_log_dir(app: &crate::AppHandle) -> crate::shared::error::AppResult<PathBuf> {
+    let dir = app_paths::app_data_dir(app)?.join("logs").join("access");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("failed to create access log dir {}: {e}", dir.display()))?;
+    Ok(dir)
+}
+
+fn open_sink(
+    app: &crate::AppHandle,
+    format: AccessLogFormat,
+    retention_days: u32,
+) -> crate::shared::error::AppResult<Sink> {
+    let dir = access_log_dir(app)?;
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(ACCESS_LOG_FILE_PREFIX)
+        .filename_suffix(ACCESS_LOG_FILE_SUFFIX)
+        .max_log_files(retention_days.max(1) as usize)
+        .build(&dir)
+        .map_err(|e| format!("failed to open access log in {}: {e}", dir.display()))?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    Ok(Sink {
+        format,
+        writer,
+        _guard: guard,
+    })
+}
+
+/// Apply the access log settings; call at startup and whenever they change.
+pub(crate) fn configure(app: &crate::AppHandle, format: AccessLogFormat, retention_days: u32) {
+    let next = match format {
+        AccessLogFormat::Off => None,
+        AccessLogFormat::Clf | AccessLogFormat::Json => {
+            match open_sink(app, format, retention_days) {
+                Ok(sink) => Some(sink),
+                Err(err) => {
+                    tracing::warn!("access log disabled: {}", err);
+                    None
+                }
+            }
+        }
+    };
+    *sink().lock_or_recover() = next;
+}
+
+/// Append `entry` to the access log (no-op while the log is off).
+pub(crate) fn record(entry: &AccessLogEntry<'_>) {
+    let mut guard = sink().lock_or_recover();
+    let Some(sink) = guard.as_mut() else {
+        return;
+    };
+    let line = match sink.format {
+        AccessLogFormat::Off => return,
+        AccessLogFormat::Clf => clf_line(entry),
+        AccessLogFormat::Json => json_line(entry),
+    };
+    if let Err(err) = sink.writer.write_all(line.as_bytes()) {
+        tracing::debug!("access log write failed: {}", err);
+    }
+}
+
+/// Proleptic Gregorian (year, month, day) for days since 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// `10/Oct/2000:13:55:36 +0000` (UTC).
+fn clf_timestamp(unix_ms: i64) -> String {
+    let secs = unix_ms.div_euclid(1000);
+    let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+    let tod = secs.rem_euclid(86_400);
+    format!(
+        "{day:02}/{}/{year:04}:{:02}:{:02}:{:02} +0000",
+        MONTHS[(month - 1) as usize],
+        tod / 3600,
+        (tod % 3600) / 60,
+        tod % 60
+    )
+}
+
+fn request_target(entry: &AccessLogEntry<'_>) -> String {
+    let target = match entry.query.filter(|q| !q.is_empty()) {
+        Some(query) => format!("{}?{query}", entry.path),
+        None => entry.path.to_string(),
+    };
+    redaction::redact_string(target)
+}
+
+fn clf_field(value: &str) -> String {
+    let value: String = value
+        .chars()
+        .map(|c| if c.is_whitespace() { '_' } else { c })
+        .collect();
+    if value.is_empty() {
+        "-".to_string()
+    } else {
+        value
+    }
+}
+
+fn clf_line(entry: &AccessLogEntry<'_>) -> String {
+    format!(
+        "- - {} [{}] \"{} {} HTTP/1.1\" {} {}\n",
+        clf_field(entry.cli_key),
+        clf_timestamp(entry.created_at_ms),
+        entry.method,
+        request_target(entry).replace('"', "%22"),
+        entry
+            .status
+            .map_or_else(|| "-".to_string(), |v| v.to_string()),
+        entry
+            .bytes
+            .map_or_else(|| "-".to_string(), |v| v.to_string()),
+    )
+}
+
+fn json_line(entry: &AccessLogEntry<'_>) -> String {
+    let value = serde_json::json!({
+        "time": clf_timestamp(entry.created_at_ms),
+        "created_at_ms": entry.created_at_ms,
+        "trace_id": entry.trace_id,
+        "cli_key": entry.cli_key,
+        "method": entry.method,
+        "target": request_target(entry),
+        "status": entry.status,
+        "error_code": entry.error_code,
+        "duration_ms": entry.duration_ms as u64,
+        "ttfb_ms": entry.ttfb_ms.map(|v| v as u64),
+        "bytes": entry.bytes,
+        "requested_model": entry.requested_model,
+        "provider": entry.provider_name,
+    });
+    let mut line = value.to_string();
+    line.push('\n');
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> AccessLogEntry<'static> {
+        AccessLogEntry {
+            created_at_ms: 971_185_336_000,
+            trace_id: "t-1",
+            cli_key: "claude",
+            method: "POST",
+            path: "/v1/messages",
+            query: Some("beta=true"),
+            status: Some(200),
+            error_code: None,
+            duration_ms: 1234,
+            ttfb_ms: Some(321),
+            bytes: Some(2048),
+            requested_model: Some("claude-sonnet-4-5"),
+            provider_name: Some("Relay A"),
+        }
+    }
+
+    #[test]
+    fn clf_line_uses_utc_timestamp_and_dashes_for_unknowns() {
+        assert_eq!(clf_timestamp(0), "01/Jan/1970:00:00:00 +0000");
+        assert_eq!(
+            clf_line(&entry()),
+            "- - claude [10/Oct/2000:13:42:16 +0000] \"POST /v1/messages?beta=true HTTP/1.1\" 200 2048\n"
+        );
+
+        let mut aborted = entry();
+        aborted.status = None;
+        aborted.bytes = None;
+        aborted.query = None;
+        assert!(clf_line(&aborted).ends_with("\"POST /v1/messages HTTP/1.1\" - -\n"));
+    }
+
+    #[test]
+    fn json_line_is_one_object_per_line() {
+        let line = json_line(&entry());
+        assert!(line.ends_with('\n'));
+        let value: serde_json::Value = serde_json::from_str(line.trim_end()).unwrap();
+        assert_eq!(value["trace_id"], "t-1");
+        assert_eq!(value["status"], 200);
+        assert_eq!(value["target"], "/v1/messages?beta=true");
+        assert_eq!(value["provider"], "Relay A");
+    }
+}
@@ -0,0 +1,125 @@
+//! Usage: Per-provider `anthropic-version` / `anthropic-beta` overrides for claude relays
+//! (`providers.anthropic_version`, `providers.anthropic_betas_json`, `providers.anthropic_beta_strip`).
+
+use axum::http::{HeaderMap, HeaderValue};
+
+const VERSION_HEADER: &str = "anthropic-version";
+const BETA_HEADER: &str = "anthropic-beta";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct AppliedAnthropicHeaders {
+    pub(super) version: Option<String>,
+    pub(super) betas: Vec<String>,
+    /// Client betas dropped because the provider only accepts its configured list.
+    pub(super) stripped_betas: Vec<String>,
+}
+
+fn client_betas(headers: &HeaderMap) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for value in headers.get_all(BETA_HEADER) {
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+        for beta in value.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+            if !out.iter().any(|v| v == beta) {
+                out.push(beta.to_string());
+            }
+        }
+    }
+    out
+}
+
+/// Applies the provider's header config; returns `None` when nothing is configured.
+///
+/// Configured betas are merged into the client's list. With `strip_client_betas` the client's
+/// betas are dropped first, for relays that reject betas they do not know.
+pub(super) fn apply(
+    headers: &mut HeaderMap,
+    version: Option<&str>,
+    betas: &[String],
+    strip_client_betas: bool,
+) -> Option<AppliedAnthropicHeaders> {
+    if version.is_none() && betas.is_empty() && !strip_client_betas {
+        return None;
+    }
+
+    if let Some(value) = version.and_then(|v| HeaderValue::from_str(v).ok()) {
+        headers.insert(VERSION_HEADER, value);
+    }
+
+    let mut merged = client_betas(headers);
+    let mut stripped_betas = Vec::new();
+    if strip_client_betas {
+        stripped_betas = std::mem::take(&mut merged)
+            .into_iter()
+            .filter(|beta| !betas.contains(beta))
+            .collect();
+    }
+    for beta in betas {
+        if !merged.contains(beta) {
+            merged.push(beta.clone());
+        }
+    }
+
+    headers.remove(BETA_HEADER);
+    if !merged.is_empty() {
+        if let Ok(value) = HeaderValue::from_str(&merged.join(",")) {
+            headers.insert(BETA_HEADER, value);
+        }
+    }
+
+    Some(AppliedAnthropicHeaders {
+        version: headers
+            .get(VERSION_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string),
+        betas: merged,
+        stripped_betas,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn betas(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn apply_merges_configured_betas_and_pins_version() {
+        let mut headers = HeaderMap::new();
+        headers.insert(VERSION_HEADER, HeaderValue::from_static("2023-06-01"));
+        headers.insert(BETA_HEADER, HeaderValue::from_static("a-1, b-2"));
+
+        let applied = apply(
+            &mut headers,
+            Some("2024-01-01"),
+            &betas(&["b-2", "c-3"]),
+            false,
+        )
+        .expect("applied");
+
+        assert_eq!(applied.version.as_deref(), Some("2024-01-01"));
+        assert_eq!(applied.betas, betas(&["a-1", "b-2", "c-3"]));
+        assert!(applied.stripped_betas.is_empty());
+        assert_eq!(headers.get(BETA_HEADER).unwrap(), "a-1,b-2,c-3");
+        assert_eq!(headers.get(VERSION_HEADER).unwrap(), "2024-01-01");
+    }
+
+    #[test]
+    fn apply_strips_unknown_client_betas() {
+        let mut headers = HeaderMap::new();
+        headers.insert(BETA_HEADER, HeaderValue::from_static("a-1,c-3"));
+
+        let applied = apply(&mut headers, None, &betas(&["c-3"]), true).expect("applied");
+        assert_eq!(applied.betas, betas(&["c-3"]));
+        assert_eq!(applied.stripped_betas, betas(&["a-1"]));
+
+        let applied = apply(&mut headers, None, &[], true).expect("applied");
+        assert!(applied.betas.is_empty());
+        assert!(headers.get(BETA_HEADER).is_none());
+
+        assert_eq!(apply(&mut headers, None, &[], false), None);
+    }
+}
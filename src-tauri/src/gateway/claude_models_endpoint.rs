@@ -0,0 +1,139 @@
+//! Usage: Gateway-synthesized `GET /v1/models` for claude providers with emulation enabled.
+//!
+//! The catalog lists the provider's configured model slots first, then the Claude model aliases
+//! the slots serve (requests for an alias are rewritten by the slot mapping before forwarding).
+
+use crate::providers::ClaudeModels;
+use serde_json::json;
+
+/// Model family (as matched by slot mapping), model id, display name.
+const KNOWN_CLAUDE_ALIASES: &[(&str, &str, &str)] = &[
+    ("opus", "claude-opus-4-5", "Claude Opus 4.5"),
+    ("opus", "claude-opus-4-1", "Claude Opus 4.1"),
+    ("sonnet", "claude-sonnet-4-5", "Claude Sonnet 4.5"),
+    ("sonnet", "claude-sonnet-4-0", "Claude Sonnet 4"),
+    ("haiku", "claude-haiku-4-5", "Claude Haiku 4.5"),
+    ("haiku", "claude-3-5-haiku-latest", "Claude Haiku 3.5"),
+];
+
+pub(super) fn is_models_list_request(method: &axum::http::Method, forwarded_path: &str) -> bool {
+    method == axum::http::Method::GET && forwarded_path.trim_end_matches('/') == "/v1/models"
+}
+
+fn family_is_served(models: &ClaudeModels, family: &str) -> bool {
+    // Without any slot the provider serves upstream model ids as-is.
+    if !models.has_any() || models.main_model.is_some() {
+        return true;
+    }
+    match family {
+        "opus" => models.opus_model.is_some(),
+        "sonnet" => models.sonnet_model.is_some(),
+        "haiku" => models.haiku_model.is_some(),
+        _ => false,
+    }
+}
+
+/// `(id, display_name)` pairs in catalog order, without duplicates.
+fn catalog_entries(models: &ClaudeModels) -> Vec<(String, String)> {
+    let mut out: Vec<(String, String)> = Vec::new();
+    let mut push = |id: &str, display_name: &str| {
+        if !out.iter().any(|(existing, _)| existing == id) {
+            out.push((id.to_string(), display_name.to_string()));
+        }
+    };
+
+    for slot in [
+        models.main_model.as_deref(),
+        models.reasoning_model.as_deref(),
+        models.opus_model.as_deref(),
+        models.sonnet_model.as_deref(),
+        models.haiku_model.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        push(slot, slot);
+    }
+
+    for (family, id, display_name) in KNOWN_CLAUDE_ALIASES {
+        if family_is_served(models, family) {
+            push(id, display_name);
+        }
+    }
+    out
+}
+
+/// Anthropic list-models response body.
+pub(super) fn build_models_response_body(models: &ClaudeModels) -> serde_json::Value {
+    let entries = catalog_entries(models);
+    let data: Vec<serde_json::Value> = entries
+        .iter()
+        .map(|(id, display_name)| {
+            json!({
+                "type": "model",
+                "id": id,
+                "display_name": display_name,
+                "created_at": "1970-01-01T00:00:00Z",
+            })
+        })
+        .collect();
+
+    json!({
+        "data": data,
+        "has_more": false,
+        "first_id": entries.first().map(|(id, _)| id),
+        "last_id": entries.last().map(|(id, _)| id),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_slots_first_then_served_aliases() {
+        let models = ClaudeModels {
+            sonnet_model: Some("glm-4.6".to_string()),
+            haiku_model: Some("glm-4.5-air".to_string()),
+            ..ClaudeModels::default()
+        };
+        let body = build_models_response_body(&models);
+        let ids: Vec<&str> = body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                "glm-4.6",
+                "glm-4.5-air",
+                "claude-sonnet-4-5",
+                "claude-sonnet-4-0",
+                "claude-haiku-4-5",
+                "claude-3-5-haiku-latest",
+            ]
+        );
+        assert_eq!(body["first_id"], "glm-4.6");
+        assert_eq!(body["has_more"], false);
+
+        let unmapped = build_models_response_body(&ClaudeModels::default());
+        assert_eq!(
+            unmapped["data"].as_array().unwrap().len(),
+            KNOWN_CLAUDE_ALIASES.len()
+        );
+
+        let method = axum::http::Method::GET;
+        assert!(is_models_list_request(&method, "/v1/models"));
+        assert!(is_models_list_request(&method, "/v1/models/"));
+        assert!(!is_models_list_request(
+            &method,
+            "/v1/models/claude-opus-4-5"
+        ));
+        assert!(!is_models_list_request(
+            &axum::http::Method::POST,
+            "/v1/models"
+        ));
+    }
+}
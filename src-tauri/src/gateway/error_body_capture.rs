@@ -0,0 +1,67 @@
+//! Usage: Keep a redacted, truncated copy of upstream 4xx/5xx bodies on attempt logs.
+//!
+//! The limit comes from settings (`upstream_error_body_capture_bytes`, 0 = disabled). Bodies pass
+//! through the redaction engine before truncation so a secret cut in half is still masked.
+
+use crate::redaction;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+const TRUNCATED_MARKER: &str = "…[truncated]";
+
+static MAX_BYTES: AtomicU32 =
+    AtomicU32::new(crate::settings::DEFAULT_UPSTREAM_ERROR_BODY_CAPTURE_BYTES);
+
+/// Swap in a new limit; call at startup and whenever settings change.
+pub(crate) fn configure(max_bytes: u32) {
+    MAX_BYTES.store(max_bytes, Ordering::Relaxed);
+}
+
+pub(crate) fn enabled() -> bool {
+    MAX_BYTES.load(Ordering::Relaxed) > 0
+}
+
+fn capture_with(max_bytes: usize, body: &[u8]) -> Option<String> {
+    if max_bytes == 0 {
+        return None;
+    }
+    let text = String::from_utf8_lossy(body);
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    let mut out = redaction::redact_string(text.to_string());
+    if out.len() > max_bytes {
+        let mut end = max_bytes;
+        while !out.is_char_boundary(end) {
+            end -= 1;
+        }
+        out.truncate(end);
+        out.push_str(TRUNCATED_MARKER);
+    }
+    Some(out)
+}
+
+/// Redacted prefix of an upstream error body, or `None` when capture is off or the body is empty.
+pub(crate) fn capture(body: &[u8]) -> Option<String> {
+    capture_with(MAX_BYTES.load(Ordering::Relaxed) as usize, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capture_truncates_on_char_boundary_and_redacts() {
+        let body = br#"{"error":{"message":"invalid x-api-key sk-ant-0123456789abcdefXYZ"}}"#;
+        let captured = capture_with(4096, body).expect("captured");
+        assert!(captured.starts_with(r#"{"error":"#));
+        assert!(!captured.contains("0123456789abcdef"));
+
+        let captured = capture_with(4, "  错误信息  ".as_bytes()).expect("captured");
+        assert_eq!(captured, format!("错{TRUNCATED_MARKER}"));
+
+        assert_eq!(capture_with(0, body), None);
+        assert_eq!(capture_with(4096, b" \n "), None);
+    }
+}
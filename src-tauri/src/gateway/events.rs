@@ -1,11 +1,19 @@
-use crate::{circuit_breaker, notice, settings, usage};
+use super::provider_webhook;
+use crate::settings::NotificationEventKind;
+use crate::shared::i18n;
+use crate::{
+    circuit_breaker, notice, notification_sinks, observer_mode, redaction, settings, usage,
+};
 use serde::Serialize;
+use std::collections::BTreeMap;
 use tauri::Emitter;
+use tauri_specta::Event;
 
 pub(in crate::gateway) mod decision_chain {
     pub(in crate::gateway) const SELECTION_METHOD_SESSION_REUSE: &str = "session_reuse";
     pub(in crate::gateway) const SELECTION_METHOD_ORDERED: &str = "ordered";
     pub(in crate::gateway) const SELECTION_METHOD_FILTERED: &str = "filtered";
+    pub(in crate::gateway) const SELECTION_METHOD_CIRCUIT_PROBE: &str = "circuit_probe";
 
     pub(in crate::gateway) const REASON_REQUEST_SUCCESS: &str = "request_success";
     pub(in crate::gateway) const REASON_RETRY_SUCCESS: &str = "retry_success";
@@ -17,7 +25,13 @@ pub(in crate::gateway) mod decision_chain {
     pub(in crate::gateway) const REASON_ABORTED: &str = "aborted";
     pub(in crate::gateway) const REASON_CIRCUIT_OPEN: &str = "circuit_open";
     pub(in crate::gateway) const REASON_CIRCUIT_COOLDOWN: &str = "circuit_cooldown";
+    pub(in crate::gateway) const REASON_CIRCUIT_PROBE_SUCCESS: &str = "circuit_probe_success";
+    pub(in crate::gateway) const REASON_CIRCUIT_PROBE_FAILED: &str = "circuit_probe_failed";
     pub(in crate::gateway) const REASON_RATE_LIMITED: &str = "rate_limited";
+    pub(in crate::gateway) const REASON_BUDGET_EXHAUSTED: &str = "budget_exhausted";
+    pub(in crate::gateway) const REASON_PROVIDER_MAINTENANCE: &str = "provider_maintenance";
+    pub(in crate::gateway) const REASON_MODEL_NOT_IN_CATALOG: &str = "model_not_in_catalog";
+    pub(in crate::gateway) const REASON_MODEL_CAPABILITY_MISSING: &str = "model_capability_missing";
 
     /// Determine how the provider was selected for this attempt.
     /// Only meaningful for the first attempt (provider_index=1, retry_index=1).
@@ -50,7 +64,7 @@ pub(in crate::gateway) mod decision_chain {
     }
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, specta::Type)]
 pub(super) struct FailoverAttempt {
     pub(super) provider_id: i64,
     pub(super) provider_name: String,
@@ -72,10 +86,36 @@ pub(super) struct FailoverAttempt {
     pub(super) circuit_state_after: Option<&'static str>,
     pub(super) circuit_failure_count: Option<u32>,
     pub(super) circuit_failure_threshold: Option<u32>,
+    /// Provider error catalog code when the upstream error body was recognized.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) error_catalog_code: Option<&'static str>,
+    /// Allow-listed upstream response headers (`captured_response_headers` setting).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) response_headers: Option<BTreeMap<String, String>>,
+    /// Redacted, truncated upstream error body (`upstream_error_body_capture_bytes` setting).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) error_body: Option<String>,
+    /// Wait applied after this attempt before retrying the same provider (`retry_backoff` setting).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) retry_backoff_ms: Option<u64>,
+    /// Set on the attempt whose body was relayed; flattened so attempts_json keeps flat keys.
+    /// Left out of the bindings: an optional flattened struct has no faithful TS shape.
+    #[serde(flatten)]
+    #[specta(skip)]
+    pub(super) stream_stats: Option<AttemptStreamStats>,
 }
 
-#[derive(Debug, Serialize, Clone)]
-struct GatewayRequestEvent {
+/// Body relay stats for one attempt: bytes, chunks and the longest pause between chunks.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub(super) struct AttemptStreamStats {
+    pub(super) bytes_received: u64,
+    pub(super) chunk_count: u64,
+    pub(super) max_chunk_gap_ms: u64,
+}
+
+#[derive(Debug, Serialize, Clone, specta::Type, tauri_specta::Event)]
+#[tauri_specta(event_name = "gateway:request")]
+pub(crate) struct GatewayRequestEvent {
     trace_id: String,
     cli_key: String,
     method: String,
@@ -96,8 +136,9 @@ struct GatewayRequestEvent {
     cache_creation_1h_input_tokens: Option<i64>,
 }
 
-#[derive(Debug, Serialize, Clone)]
-struct GatewayRequestStartEvent {
+#[derive(Debug, Serialize, Clone, specta::Type, tauri_specta::Event)]
+#[tauri_specta(event_name = "gateway:request_start")]
+pub(crate) struct GatewayRequestStartEvent {
     trace_id: String,
     cli_key: String,
     method: String,
@@ -107,8 +148,9 @@ struct GatewayRequestStartEvent {
     ts: i64,
 }
 
-#[derive(Debug, Serialize, Clone)]
-pub(super) struct GatewayAttemptEvent {
+#[derive(Debug, Serialize, Clone, specta::Type, tauri_specta::Event)]
+#[tauri_specta(event_name = "gateway:attempt")]
+pub(crate) struct GatewayAttemptEvent {
     pub(super) trace_id: String,
     pub(super) cli_key: String,
     pub(super) method: String,
@@ -129,8 +171,9 @@ pub(super) struct GatewayAttemptEvent {
     pub(super) circuit_failure_threshold: Option<u32>,
 }
 
-#[derive(Debug, Serialize, Clone)]
-pub(super) struct GatewayCircuitEvent {
+#[derive(Debug, Serialize, Clone, specta::Type, tauri_specta::Event)]
+#[tauri_specta(event_name = "gateway:circuit")]
+pub(crate) struct GatewayCircuitEvent {
     pub(super) trace_id: String,
     pub(super) cli_key: String,
     pub(super) provider_id: i64,
@@ -146,8 +189,26 @@ pub(super) struct GatewayCircuitEvent {
     pub(super) ts: i64,
 }
 
-#[derive(Debug, Serialize, Clone)]
-pub(super) struct GatewayLogEvent {
+#[derive(Debug, Serialize, Clone, specta::Type, tauri_specta::Event)]
+#[tauri_specta(event_name = "gateway:stream_stall")]
+pub(crate) struct GatewayStreamStallEvent {
+    pub(super) trace_id: String,
+    pub(super) cli_key: String,
+    pub(super) provider_id: i64,
+    pub(super) provider_name: String,
+    /// `stalled` once upstream has been silent past the soft threshold, `resumed` when data
+    /// flows again.
+    pub(super) state: &'static str,
+    pub(super) idle_ms: u64,
+    /// Hard idle timeout that will abort the stream, if one is configured.
+    pub(super) idle_timeout_ms: Option<u64>,
+    pub(super) keepalive: bool,
+    pub(super) ts: i64,
+}
+
+#[derive(Debug, Serialize, Clone, specta::Type, tauri_specta::Event)]
+#[tauri_specta(event_name = "gateway:log")]
+pub(crate) struct GatewayLogEvent {
     pub(super) level: &'static str,
     pub(super) error_code: &'static str,
     pub(super) message: String,
@@ -157,7 +218,7 @@ pub(super) struct GatewayLogEvent {
 }
 
 pub(crate) fn emit_gateway_log(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     level: &'static str,
     error_code: &'static str,
     message: String,
@@ -165,17 +226,27 @@ pub(crate) fn emit_gateway_log(
     let payload = GatewayLogEvent {
         level,
         error_code,
-        message,
+        message: redaction::redact_string(message),
         requested_port: 0,
         bound_port: 0,
         base_url: String::new(),
     };
-    let _ = app.emit("gateway:log", payload);
+    let _ = app.emit(GatewayLogEvent::NAME, payload);
+}
+
+fn observer_masked(path: String, query: Option<String>) -> (String, Option<String>) {
+    if !observer_mode::is_enabled() {
+        return (path, query);
+    }
+    (
+        observer_mode::mask_path(&path),
+        observer_mode::mask_query(query),
+    )
 }
 
 #[allow(clippy::too_many_arguments)]
 pub(super) fn emit_request_event(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     trace_id: String,
     cli_key: String,
     method: String,
@@ -190,6 +261,19 @@ pub(super) fn emit_request_event(
     usage: Option<usage::UsageMetrics>,
 ) {
     let usage = usage.unwrap_or_default();
+    let (path, query) = observer_masked(path, query);
+    let drop_captures = observer_mode::is_enabled();
+    let attempts = attempts
+        .into_iter()
+        .map(|mut attempt| {
+            attempt.reason = attempt.reason.map(redaction::redact_string);
+            if drop_captures {
+                attempt.response_headers = None;
+                attempt.error_body = None;
+            }
+            attempt
+        })
+        .collect();
     let payload = GatewayRequestEvent {
         trace_id,
         cli_key,
@@ -211,12 +295,12 @@ pub(super) fn emit_request_event(
         cache_creation_1h_input_tokens: usage.cache_creation_1h_input_tokens,
     };
 
-    let _ = app.emit("gateway:request", payload);
+    let _ = app.emit(GatewayRequestEvent::NAME, payload);
 }
 
 #[allow(clippy::too_many_arguments)]
 pub(super) fn emit_request_start_event(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     trace_id: String,
     cli_key: String,
     method: String,
@@ -225,6 +309,7 @@ pub(super) fn emit_request_start_event(
     requested_model: Option<String>,
     ts: i64,
 ) {
+    let (path, query) = observer_masked(path, query);
     let payload = GatewayRequestStartEvent {
         trace_id,
         cli_key,
@@ -234,20 +319,25 @@ pub(super) fn emit_request_start_event(
         requested_model,
         ts,
     };
-    let _ = app.emit("gateway:request_start", payload);
+    let _ = app.emit(GatewayRequestStartEvent::NAME, payload);
 }
 
-pub(super) fn emit_attempt_event(app: &tauri::AppHandle, payload: GatewayAttemptEvent) {
-    let _ = app.emit("gateway:attempt", payload);
+pub(super) fn emit_attempt_event(app: &crate::AppHandle, mut payload: GatewayAttemptEvent) {
+    (payload.path, payload.query) = observer_masked(payload.path, payload.query);
+    let _ = app.emit(GatewayAttemptEvent::NAME, payload);
 }
 
-pub(super) fn emit_circuit_event(app: &tauri::AppHandle, payload: GatewayCircuitEvent) {
-    let _ = app.emit("gateway:circuit", payload);
+pub(super) fn emit_stream_stall_event(app: &crate::AppHandle, payload: GatewayStreamStallEvent) {
+    let _ = app.emit(GatewayStreamStallEvent::NAME, payload);
+}
+
+pub(super) fn emit_circuit_event(app: &crate::AppHandle, payload: GatewayCircuitEvent) {
+    let _ = app.emit(GatewayCircuitEvent::NAME, payload);
 }
 
 #[allow(clippy::too_many_arguments)]
 pub(super) fn emit_circuit_transition(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     trace_id: &str,
     cli_key: &str,
     provider_id: i64,
@@ -273,70 +363,117 @@ pub(super) fn emit_circuit_transition(
     };
 
     emit_circuit_event(app, payload);
+    provider_webhook::dispatch_circuit_transition(
+        app,
+        trace_id,
+        cli_key,
+        provider_id,
+        provider_name,
+        base_url,
+        transition,
+        now_unix,
+    );
+
+    // HALF_OPEN only means a probe is pending; notify once the probe settles the circuit.
+    if transition.next_state == circuit_breaker::CircuitState::HalfOpen {
+        return;
+    }
 
     let enable_notice = settings::read(app)
         .ok()
         .map(|cfg| cfg.enable_circuit_breaker_notice)
         .unwrap_or(false);
-    if !enable_notice {
+    let route_to_sinks = transition.next_state == circuit_breaker::CircuitState::Open
+        && notification_sinks::routes(NotificationEventKind::CircuitOpen);
+    if !enable_notice && !route_to_sinks {
         return;
     }
 
-    let prev_state_text = match transition.prev_state {
-        circuit_breaker::CircuitState::Closed => "正常",
-        circuit_breaker::CircuitState::Open => "熔断",
-    };
-    let next_state_text = match transition.next_state {
-        circuit_breaker::CircuitState::Closed => "正常",
-        circuit_breaker::CircuitState::Open => "熔断",
+    let state_text = |state: circuit_breaker::CircuitState| match state {
+        circuit_breaker::CircuitState::Closed => i18n::text("notice.circuit.state_closed"),
+        circuit_breaker::CircuitState::Open => i18n::text("notice.circuit.state_open"),
+        circuit_breaker::CircuitState::HalfOpen => i18n::text("notice.circuit.state_half_open"),
     };
 
-    let (level, title) = match transition.next_state {
-        circuit_breaker::CircuitState::Open => (
-            notice::NoticeLevel::Warning,
-            format!("熔断触发：{provider_name}"),
-        ),
-        circuit_breaker::CircuitState::Closed => (
-            notice::NoticeLevel::Success,
-            format!("熔断恢复：{provider_name}"),
-        ),
+    let (level, title_key) = match transition.next_state {
+        circuit_breaker::CircuitState::Open => {
+            (notice::NoticeLevel::Warning, "notice.circuit.title_open")
+        }
+        circuit_breaker::CircuitState::Closed | circuit_breaker::CircuitState::HalfOpen => {
+            (notice::NoticeLevel::Success, "notice.circuit.title_closed")
+        }
     };
+    let title = i18n::format(title_key, &[("name", &provider_name)]);
 
     let reason_text = match transition.reason {
-        "FAILURE_THRESHOLD_REACHED" => "失败次数达到阈值",
-        "OPEN_EXPIRED" => "熔断到期自动恢复",
+        "FAILURE_THRESHOLD_REACHED" => i18n::text("notice.circuit.reason_failure_threshold"),
+        "OPEN_EXPIRED" => i18n::text("notice.circuit.reason_open_expired"),
+        "PROBE_SUCCEEDED" => i18n::text("notice.circuit.reason_probe_succeeded"),
+        "PROBE_FAILED" => i18n::text("notice.circuit.reason_probe_failed"),
         other => other,
     };
 
     let mut lines: Vec<String> = Vec::with_capacity(10);
-    lines.push(format!("CLI：{cli_key}"));
-    lines.push(format!("Provider：{provider_name} (id={provider_id})"));
-    lines.push(format!("Base URL：{base_url}"));
-    lines.push(format!("状态：{prev_state_text} → {next_state_text}"));
-    lines.push(format!(
-        "失败：{} / {}",
-        transition.snapshot.failure_count, transition.snapshot.failure_threshold
+    lines.push(i18n::format("notice.line.cli", &[("cli_key", &cli_key)]));
+    lines.push(i18n::format(
+        "notice.line.provider",
+        &[("name", &provider_name), ("id", &provider_id)],
+    ));
+    lines.push(i18n::format(
+        "notice.circuit.line_base_url",
+        &[("base_url", &base_url)],
+    ));
+    lines.push(i18n::format(
+        "notice.circuit.line_state",
+        &[
+            ("prev", &state_text(transition.prev_state)),
+            ("next", &state_text(transition.next_state)),
+        ],
+    ));
+    lines.push(i18n::format(
+        "notice.circuit.line_failures",
+        &[
+            ("count", &transition.snapshot.failure_count),
+            ("threshold", &transition.snapshot.failure_threshold),
+        ],
+    ));
+    lines.push(i18n::format(
+        "notice.circuit.line_reason",
+        &[("text", &reason_text), ("code", &transition.reason)],
     ));
-    lines.push(format!("原因：{reason_text}（{}）", transition.reason));
 
     match transition.snapshot.open_until {
         Some(open_until) => {
             let remaining_secs = open_until.saturating_sub(now_unix);
             let remaining_minutes = remaining_secs.saturating_add(59) / 60;
             if remaining_secs > 0 {
-                lines.push(format!(
-                    "熔断至：{open_until}（约 {remaining_minutes} 分钟后）"
+                lines.push(i18n::format(
+                    "notice.circuit.line_open_until",
+                    &[("until", &open_until), ("minutes", &remaining_minutes)],
                 ));
             } else {
-                lines.push(format!("熔断至：{open_until}（已到期）"));
+                lines.push(i18n::format(
+                    "notice.circuit.line_open_until_expired",
+                    &[("until", &open_until)],
+                ));
             }
         }
-        None => lines.push("熔断至：—".to_string()),
+        None => lines.push(i18n::text("notice.circuit.line_open_until_none").to_string()),
     }
 
-    lines.push(format!("Trace：{trace_id}"));
+    lines.push(i18n::format(
+        "notice.line.trace",
+        &[("trace_id", &trace_id)],
+    ));
 
-    if let Err(err) = notice::emit(app, notice::build(level, Some(title), lines.join("\n"))) {
+    let payload = notice::build(level, Some(title), lines.join("\n"));
+    if route_to_sinks {
+        notification_sinks::dispatch(NotificationEventKind::CircuitOpen, &payload);
+    }
+    if !enable_notice {
+        return;
+    }
+    if let Err(err) = notice::emit(app, payload) {
         tracing::warn!("failed to emit circuit breaker notice: {}", err);
     }
 }
@@ -1,27 +1,50 @@
+use crate::shared::mutex_ext::MutexExt;
 use crate::{
-    circuit_breaker, db, provider_circuit_breakers, providers, request_logs, session_manager,
-    settings, wsl,
+    app_paths, circuit_breaker, db, provider_circuit_breakers, provider_dns_options, providers,
+    request_logs, session_manager, settings, wsl,
 };
 use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tauri::Emitter;
+use tauri_specta::Event;
 use tokio::sync::oneshot;
 
 use super::codex_session_id::CodexSessionIdCache;
 use super::events::GatewayLogEvent;
 use super::listen;
-use super::proxy::{GatewayErrorCode, ProviderBaseUrlPingCache, RecentErrorCache};
+use super::proxy::{
+    circuit_probe, live_stats, AdaptiveOrderCache, GatewayErrorCode, InflightRequests,
+    ProviderBaseUrlPingCache, ProviderLiveStats, ProviderQualityTracker, RecentErrorCache,
+};
+use super::response_fixer;
 use super::routes::build_router;
+use super::upstream_dns::UpstreamDnsResolver;
+use super::upstream_warm_pool::{self, UpstreamWarmPool};
 use super::util::now_unix_seconds;
-use super::{GatewayProviderCircuitStatus, GatewayStatus};
+use super::{
+    GatewayProviderCircuitStatus, GatewayProviderLiveStats, GatewayProviderQuality, GatewayStatus,
+};
 
 struct RunningGateway {
     port: u16,
     base_url: String,
     listen_addr: String,
+    bind_host: String,
+    /// Host used in `base_url` (loopback when listening on a wildcard address).
+    base_host: String,
+    /// The custom listen address pins the port, so it can't be changed at runtime.
+    fixed_port: bool,
+    router: axum::Router,
     circuit: Arc<circuit_breaker::CircuitBreaker>,
     session: Arc<session_manager::SessionManager>,
+    quality: Arc<Mutex<ProviderQualityTracker>>,
+    live_stats: Arc<Mutex<ProviderLiveStats>>,
+    warm_pool: Arc<Mutex<UpstreamWarmPool>>,
+    dns: UpstreamDnsResolver,
+    warm_pool_task: tauri::async_runtime::JoinHandle<()>,
+    live_stats_task: tauri::async_runtime::JoinHandle<()>,
+    circuit_probe_task: tauri::async_runtime::JoinHandle<()>,
     shutdown: oneshot::Sender<()>,
     task: tauri::async_runtime::JoinHandle<()>,
     log_task: tauri::async_runtime::JoinHandle<()>,
@@ -30,6 +53,26 @@ struct RunningGateway {
     oauth_refresh_task: tauri::async_runtime::JoinHandle<()>,
 }
 
+impl RunningGateway {
+    fn set_active_listener(
+        &mut self,
+        port: u16,
+        (shutdown, task): GatewayListenerHandles,
+    ) -> GatewayListenerHandles {
+        self.port = port;
+        self.listen_addr = listen::format_host_port(&self.bind_host, port);
+        self.base_url = format!("http://{}", listen::format_host_port(&self.base_host, port));
+        (
+            std::mem::replace(&mut self.shutdown, shutdown),
+            std::mem::replace(&mut self.task, task),
+        )
+    }
+}
+
+/// Shutdown signal and serve task of one listener (see `GatewayManager::change_port`).
+pub(crate) type GatewayListenerHandles =
+    (oneshot::Sender<()>, tauri::async_runtime::JoinHandle<()>);
+
 type RunningGatewayHandles = (
     oneshot::Sender<()>,
     tauri::async_runtime::JoinHandle<()>,
@@ -40,6 +83,10 @@ type RunningGatewayHandles = (
 );
 
 const UPSTREAM_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+// Kept above the warm pool's re-warm interval so warmed connections survive between passes.
+const UPSTREAM_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+const UPSTREAM_TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+const UPSTREAM_HTTP2_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
 
 #[derive(Default)]
 pub struct GatewayManager {
@@ -48,7 +95,7 @@ pub struct GatewayManager {
 
 #[derive(Clone)]
 pub(super) struct GatewayAppState {
-    pub(super) app: tauri::AppHandle,
+    pub(super) app: crate::AppHandle,
     pub(super) db: db::Db,
     pub(super) client: reqwest::Client,
     pub(super) log_tx: tokio::sync::mpsc::Sender<request_logs::RequestLogInsert>,
@@ -57,6 +104,12 @@ pub(super) struct GatewayAppState {
     pub(super) codex_session_cache: Arc<Mutex<CodexSessionIdCache>>,
     pub(super) recent_errors: Arc<Mutex<RecentErrorCache>>,
     pub(super) latency_cache: Arc<Mutex<ProviderBaseUrlPingCache>>,
+    pub(super) quality: Arc<Mutex<ProviderQualityTracker>>,
+    pub(super) live_stats: Arc<Mutex<ProviderLiveStats>>,
+    pub(super) warm_pool: Arc<Mutex<UpstreamWarmPool>>,
+    pub(super) adaptive_order: Arc<Mutex<AdaptiveOrderCache>>,
+    pub(super) inflight_requests: Arc<Mutex<InflightRequests>>,
+    pub(super) dns: UpstreamDnsResolver,
 }
 fn port_candidates(preferred: Option<u16>) -> impl Iterator<Item = u16> {
     let mut candidates = Vec::with_capacity(
@@ -79,12 +132,55 @@ fn port_candidates(preferred: Option<u16>) -> impl Iterator<Item = u16> {
     candidates.into_iter()
 }
 
+/// Ports `change_port` accepts: unprivileged, and not above the gateway's port range.
+pub(crate) fn validate_port(port: u16) -> crate::shared::error::AppResult<()> {
+    if !(1024..=settings::MAX_GATEWAY_PORT).contains(&port) {
+        return Err(format!(
+            "SEC_INVALID_INPUT: port must be between 1024 and {}",
+            settings::MAX_GATEWAY_PORT
+        )
+        .into());
+    }
+    Ok(())
+}
+
 fn bind_host_port(bind_host: &str, port: u16) -> Option<std::net::TcpListener> {
     let std_listener = std::net::TcpListener::bind((bind_host, port)).ok()?;
     std_listener.set_nonblocking(true).ok()?;
     Some(std_listener)
 }
 
+fn spawn_server(
+    router: axum::Router,
+    std_listener: std::net::TcpListener,
+    shutdown_rx: oneshot::Receiver<()>,
+) -> tauri::async_runtime::JoinHandle<()> {
+    let bind_addr = std_listener
+        .local_addr()
+        .unwrap_or_else(|_| SocketAddr::from(([127, 0, 0, 1], 0)));
+    tauri::async_runtime::spawn(async move {
+        let listener = match tokio::net::TcpListener::from_std(std_listener) {
+            Ok(l) => l,
+            Err(err) => {
+                tracing::error!(bind_addr = %bind_addr, "gateway listener initialization failed: {}", err);
+                return;
+            }
+        };
+
+        let serve = axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(async move {
+            let _ = shutdown_rx.await;
+        });
+
+        if let Err(err) = serve.await {
+            tracing::error!(bind_addr = %bind_addr, "gateway server runtime error: {}", err);
+        }
+    })
+}
+
 fn bind_first_available(
     bind_host: &str,
     preferred: Option<u16>,
@@ -111,12 +207,14 @@ impl GatewayManager {
                 port: Some(r.port),
                 base_url: Some(r.base_url.clone()),
                 listen_addr: Some(r.listen_addr.clone()),
+                warm_pool: r.warm_pool.lock_or_recover().snapshot(),
             },
             None => GatewayStatus {
                 running: false,
                 port: None,
                 base_url: None,
                 listen_addr: None,
+                warm_pool: Vec::new(),
             },
         }
     }
@@ -139,9 +237,36 @@ impl GatewayManager {
         }
     }
 
+    pub fn set_session_ttl_secs(&self, ttl_secs: i64) {
+        if let Some(r) = &self.running {
+            r.session.set_ttl_secs(ttl_secs);
+        }
+    }
+
+    /// Returns `None` when the gateway is not running.
+    pub fn pin_session_provider(
+        &self,
+        cli_key: &str,
+        session_id: &str,
+        provider_id: i64,
+        now_unix: i64,
+    ) -> Option<bool> {
+        self.running.as_ref().map(|r| {
+            r.session
+                .pin_provider(cli_key, session_id, provider_id, now_unix)
+        })
+    }
+
+    pub fn unpin_session_provider(&self, cli_key: &str, session_id: &str, now_unix: i64) -> bool {
+        match &self.running {
+            Some(r) => r.session.unpin_provider(cli_key, session_id, now_unix),
+            None => false,
+        }
+    }
+
     pub fn start(
         &mut self,
-        app: &tauri::AppHandle,
+        app: &crate::AppHandle,
         db: db::Db,
         preferred_port: Option<u16>,
     ) -> crate::shared::error::AppResult<GatewayStatus> {
@@ -182,9 +307,6 @@ impl GatewayManager {
             _ => bind_host.clone(),
         };
         let base_url = format!("http://{}", listen::format_host_port(&base_host, port));
-        let bind_addr = std_listener
-            .local_addr()
-            .unwrap_or_else(|_| SocketAddr::from(([127, 0, 0, 1], port)));
 
         if fixed_port.is_none() && port != requested_port {
             if let Ok(mut current) = settings::read(app) {
@@ -202,18 +324,54 @@ impl GatewayManager {
                 bound_port: port,
                 base_url: base_url.clone(),
             };
-            let _ = app.emit("gateway:log", payload);
+            let _ = app.emit(GatewayLogEvent::NAME, payload);
         }
 
-        let client = reqwest::Client::builder()
+        let dns_options = match provider_dns_options::load_all(&db) {
+            Ok(v) => v,
+            Err(err) => {
+                tracing::warn!(
+                    "provider dns options load failed, using system dns: {}",
+                    err
+                );
+                Default::default()
+            }
+        };
+        let dns = UpstreamDnsResolver::new(dns_options);
+
+        let mut client_builder = reqwest::Client::builder()
             .user_agent(format!(
                 "aio-coding-hub-gateway/{}",
                 env!("CARGO_PKG_VERSION")
             ))
             .connect_timeout(UPSTREAM_CONNECT_TIMEOUT)
+            .pool_idle_timeout(UPSTREAM_POOL_IDLE_TIMEOUT)
+            .tcp_keepalive(UPSTREAM_TCP_KEEPALIVE)
+            .dns_resolver(Arc::new(dns.clone()));
+        // HTTP/2 (via ALPN) is only offered when the warm pool is on; toggling it takes effect
+        // on the next gateway start.
+        client_builder = if cfg.upstream_warm_pool_enabled {
+            client_builder
+                .http2_keep_alive_interval(UPSTREAM_HTTP2_KEEPALIVE_INTERVAL)
+                .http2_keep_alive_while_idle(true)
+        } else {
+            client_builder.http1_only()
+        };
+        let client = client_builder
             .build()
             .map_err(|e| format!("{}: {e}", GatewayErrorCode::HttpClientInit.as_str()))?;
 
+        match app_paths::app_data_dir(app) {
+            Ok(dir) => {
+                if let Err(err) = response_fixer::init_spill_dir(dir) {
+                    tracing::warn!("response fixer spill dir initialization failed: {}", err);
+                }
+            }
+            Err(err) => {
+                tracing::warn!("response fixer spill dir unavailable: {}", err);
+            }
+        }
+
         let (log_tx, log_task) = request_logs::start_buffered_writer(app.clone(), db.clone());
         let (circuit_tx, circuit_task) =
             provider_circuit_breakers::start_buffered_writer(db.clone());
@@ -226,13 +384,15 @@ impl GatewayManager {
             }
         };
 
-        let circuit_config = match settings::read(app) {
-            Ok(cfg) => circuit_breaker::CircuitBreakerConfig {
+        let app_settings = settings::read(app).ok();
+        let circuit_config = match &app_settings {
+            Some(cfg) => circuit_breaker::CircuitBreakerConfig {
                 failure_threshold: cfg.circuit_breaker_failure_threshold.max(1),
                 open_duration_secs: (cfg.circuit_breaker_open_duration_minutes as i64)
                     .saturating_mul(60),
+                half_open_probe: cfg.circuit_breaker_half_open_probe_enabled,
             },
-            Err(_) => circuit_breaker::CircuitBreakerConfig::default(),
+            None => circuit_breaker::CircuitBreakerConfig::default(),
         };
         let circuit = Arc::new(circuit_breaker::CircuitBreaker::new(
             circuit_config,
@@ -241,9 +401,19 @@ impl GatewayManager {
         ));
         let circuit_for_manager = circuit.clone();
         let session = Arc::new(session_manager::SessionManager::new());
+        if let Some(cfg) = &app_settings {
+            session.set_ttl_secs(cfg.session_binding_ttl_seconds as i64);
+        }
         let codex_session_cache = Arc::new(Mutex::new(CodexSessionIdCache::default()));
         let recent_errors = Arc::new(Mutex::new(RecentErrorCache::default()));
         let latency_cache = Arc::new(Mutex::new(ProviderBaseUrlPingCache::default()));
+        let quality = Arc::new(Mutex::new(ProviderQualityTracker::default()));
+        let warm_pool = Arc::new(Mutex::new(UpstreamWarmPool::default()));
+        let warm_pool_task =
+            upstream_warm_pool::spawn(app.clone(), db.clone(), client.clone(), warm_pool.clone());
+        let live_stats = Arc::new(Mutex::new(ProviderLiveStats::default()));
+        let live_stats_task =
+            live_stats::spawn_emitter(app.clone(), live_stats.clone(), circuit.clone());
 
         let state = GatewayAppState {
             app: app.clone(),
@@ -255,9 +425,16 @@ impl GatewayManager {
             codex_session_cache,
             recent_errors,
             latency_cache,
+            quality: quality.clone(),
+            live_stats: live_stats.clone(),
+            warm_pool: warm_pool.clone(),
+            adaptive_order: Arc::new(Mutex::new(AdaptiveOrderCache::default())),
+            inflight_requests: Arc::new(Mutex::new(InflightRequests::default())),
+            dns: dns.clone(),
         };
 
-        let app = build_router(state);
+        let circuit_probe_task = circuit_probe::spawn(state.clone());
+        let router = build_router(state);
         let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
 
         // Spawn the background OAuth token refresh loop.
@@ -265,30 +442,25 @@ impl GatewayManager {
             tokio::sync::watch::channel(false);
         let oauth_refresh_task = super::oauth::refresh_loop::spawn(db, oauth_refresh_shutdown_rx);
 
-        let task = tauri::async_runtime::spawn(async move {
-            let listener = match tokio::net::TcpListener::from_std(std_listener) {
-                Ok(l) => l,
-                Err(err) => {
-                    tracing::error!(bind_addr = %bind_addr, "gateway listener initialization failed: {}", err);
-                    return;
-                }
-            };
-
-            let serve = axum::serve(listener, app).with_graceful_shutdown(async move {
-                let _ = shutdown_rx.await;
-            });
-
-            if let Err(err) = serve.await {
-                tracing::error!(bind_addr = %bind_addr, "gateway server runtime error: {}", err);
-            }
-        });
+        let task = spawn_server(router.clone(), std_listener, shutdown_rx);
 
         self.running = Some(RunningGateway {
             port,
             base_url,
             listen_addr,
+            bind_host,
+            base_host,
+            fixed_port: fixed_port.is_some(),
+            router,
             circuit: circuit_for_manager,
             session,
+            quality,
+            live_stats,
+            warm_pool,
+            dns,
+            warm_pool_task,
+            live_stats_task,
+            circuit_probe_task,
             shutdown: shutdown_tx,
             task,
             log_task,
@@ -300,9 +472,58 @@ impl GatewayManager {
         Ok(self.status())
     }
 
+    /// Starts serving on `port` alongside the current listener and makes it the active one.
+    ///
+    /// Returns the previous listener so the caller can drain it once clients have moved over.
+    pub fn change_port(
+        &mut self,
+        port: u16,
+    ) -> crate::shared::error::AppResult<(GatewayStatus, Option<GatewayListenerHandles>)> {
+        let Some(running) = self.running.as_mut() else {
+            return Err("SEC_INVALID_INPUT: gateway is not running".into());
+        };
+        if running.port == port {
+            return Ok((self.status(), None));
+        }
+        if running.fixed_port {
+            return Err(
+                "SEC_INVALID_INPUT: port is fixed by the custom listen address; change it there"
+                    .into(),
+            );
+        }
+        validate_port(port)?;
+
+        let std_listener = bind_host_port(&running.bind_host, port).ok_or_else(|| {
+            format!(
+                "{}: failed to bind {}:{port}",
+                GatewayErrorCode::PortInUse.as_str(),
+                running.bind_host
+            )
+        })?;
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let task = spawn_server(running.router.clone(), std_listener, shutdown_rx);
+
+        let previous = running.set_active_listener(port, (shutdown_tx, task));
+        Ok((self.status(), Some(previous)))
+    }
+
+    /// Undoes `change_port`: makes `previous` (still serving on `port`) the active listener again
+    /// and returns the listener it replaced, for the caller to shut down.
+    pub fn restore_port(
+        &mut self,
+        port: u16,
+        previous: GatewayListenerHandles,
+    ) -> crate::shared::error::AppResult<(GatewayStatus, GatewayListenerHandles)> {
+        let Some(running) = self.running.as_mut() else {
+            return Err("SEC_INVALID_INPUT: gateway is not running".into());
+        };
+        let replaced = running.set_active_listener(port, previous);
+        Ok((self.status(), replaced))
+    }
+
     pub fn circuit_status(
         &self,
-        app: &tauri::AppHandle,
+        app: &crate::AppHandle,
         db: &db::Db,
         cli_key: &str,
     ) -> crate::shared::error::AppResult<Vec<GatewayProviderCircuitStatus>> {
@@ -377,6 +598,30 @@ impl GatewayManager {
             .collect())
     }
 
+    pub fn live_stats(&self) -> Vec<GatewayProviderLiveStats> {
+        match &self.running {
+            Some(r) => live_stats::snapshot_with_circuit(&r.live_stats, &r.circuit),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn provider_quality(&self) -> Vec<GatewayProviderQuality> {
+        match &self.running {
+            Some(r) => r
+                .quality
+                .lock_or_recover()
+                .snapshot(now_unix_seconds() as i64),
+            None => Vec::new(),
+        }
+    }
+
+    /// Applies to connections opened after the call; the options are already persisted.
+    pub fn set_provider_dns_options(&self, options: provider_dns_options::ProviderDnsOptions) {
+        if let Some(r) = &self.running {
+            r.dns.set_provider_options(options);
+        }
+    }
+
     pub fn circuit_reset_provider(
         &self,
         db: &db::Db,
@@ -426,6 +671,10 @@ impl GatewayManager {
         self.running.take().map(|r| {
             // Signal the OAuth refresh loop to stop.
             let _ = r.oauth_refresh_shutdown.send(true);
+            // Warm probes hold no state worth finishing.
+            r.warm_pool_task.abort();
+            r.live_stats_task.abort();
+            r.circuit_probe_task.abort();
             (
                 r.shutdown,
                 r.task,
@@ -440,8 +689,8 @@ impl GatewayManager {
 
 #[cfg(test)]
 mod tests {
-    use super::{GatewayManager, RunningGateway};
-    use crate::{circuit_breaker, session_manager};
+    use super::{GatewayManager, RunningGateway, UpstreamDnsResolver};
+    use crate::{circuit_breaker, session_manager, settings};
     use std::collections::HashMap;
     use std::sync::Arc;
     use tokio::sync::oneshot;
@@ -463,8 +712,19 @@ mod tests {
             port: 1,
             base_url: "http://127.0.0.1:1".to_string(),
             listen_addr: "127.0.0.1:1".to_string(),
+            bind_host: "127.0.0.1".to_string(),
+            base_host: "127.0.0.1".to_string(),
+            fixed_port: false,
+            router: axum::Router::new(),
             circuit,
             session,
+            quality: Default::default(),
+            live_stats: Default::default(),
+            warm_pool: Default::default(),
+            dns: UpstreamDnsResolver::new(HashMap::new()),
+            warm_pool_task: tauri::async_runtime::JoinHandle::Tokio(rt.spawn(async {})),
+            live_stats_task: tauri::async_runtime::JoinHandle::Tokio(rt.spawn(async {})),
+            circuit_probe_task: tauri::async_runtime::JoinHandle::Tokio(rt.spawn(async {})),
             shutdown: shutdown_tx,
             task: tauri::async_runtime::JoinHandle::Tokio(rt.spawn(async {})),
             log_task: tauri::async_runtime::JoinHandle::Tokio(rt.spawn(async {})),
@@ -515,4 +775,71 @@ mod tests {
             Some(Some(2))
         );
     }
+
+    /// A port in the gateway range that is free right now, still bound by the returned listener.
+    fn reserve_port() -> (u16, std::net::TcpListener) {
+        (settings::DEFAULT_GATEWAY_PORT..=settings::MAX_GATEWAY_PORT)
+            .find_map(|port| {
+                std::net::TcpListener::bind(("127.0.0.1", port))
+                    .ok()
+                    .map(|l| (port, l))
+            })
+            .expect("free port in gateway range")
+    }
+
+    #[test]
+    fn change_port_moves_listener_and_can_be_restored() {
+        let rt = tokio::runtime::Runtime::new().expect("runtime");
+        let session = Arc::new(session_manager::SessionManager::new());
+        let mut manager = GatewayManager {
+            running: Some(build_running_gateway(&rt, session)),
+        };
+        let (port, reserved) = reserve_port();
+        drop(reserved);
+
+        let (status, previous) = manager.change_port(port).expect("change port");
+        assert_eq!(status.port, Some(port));
+        assert_eq!(
+            status.base_url.as_deref(),
+            Some(format!("http://127.0.0.1:{port}").as_str())
+        );
+        let previous = previous.expect("previous listener");
+        assert!(std::net::TcpListener::bind(("127.0.0.1", port)).is_err());
+
+        // Same port again is a no-op.
+        let (_, none) = manager.change_port(port).expect("same port");
+        assert!(none.is_none());
+
+        let (status, (new_shutdown, _new_task)) =
+            manager.restore_port(1, previous).expect("restore port");
+        assert_eq!(status.port, Some(1));
+        assert_eq!(status.base_url.as_deref(), Some("http://127.0.0.1:1"));
+        let _ = new_shutdown.send(());
+    }
+
+    #[test]
+    fn change_port_rejects_taken_and_out_of_range_ports() {
+        let rt = tokio::runtime::Runtime::new().expect("runtime");
+        let session = Arc::new(session_manager::SessionManager::new());
+        let mut manager = GatewayManager {
+            running: Some(build_running_gateway(&rt, session)),
+        };
+
+        let (port, _taken) = reserve_port();
+        let Err(err) = manager.change_port(port) else {
+            panic!("port {port} is in use");
+        };
+        assert_eq!(err.code(), "GW_PORT_IN_USE");
+
+        for port in [80, settings::MAX_GATEWAY_PORT + 1] {
+            let Err(err) = manager.change_port(port) else {
+                panic!("port {port} is out of range");
+            };
+            assert_eq!(err.code(), "SEC_INVALID_INPUT");
+        }
+        assert!(super::validate_port(settings::MAX_GATEWAY_PORT).is_ok());
+
+        // Failed changes leave the running listener untouched.
+        assert_eq!(manager.status().port, Some(1));
+    }
 }
@@ -0,0 +1,523 @@
+//! Usage: Capability gate for `settings.model_capabilities` (vision, tool use, thinking, context).
+//!
+//! The request's features are detected once from its body; every attempt then looks up the rule
+//! for the provider and the model it will actually receive. Features the model lacks either skip
+//! the provider or are stripped from the upstream body, per `settings.capability_gate_policy`.
+//! Unknown capabilities (`None`) never gate anything.
+
+use super::token_estimate;
+use crate::settings::{self, CapabilityGatePolicy, ModelCapabilityRule};
+use serde_json::{Map, Value};
+
+const IMAGE_PLACEHOLDER: &str = "[image removed: the selected model does not support images]";
+/// Keys holding conversation content, across Claude, OpenAI (chat/responses) and Gemini bodies.
+const CONVERSATION_KEYS: &[&str] = &[
+    "messages",
+    "input",
+    "contents",
+    "system",
+    "systemInstruction",
+    "system_instruction",
+];
+const TOOL_KEYS: &[&str] = &[
+    "tools",
+    "tool_choice",
+    "parallel_tool_calls",
+    "functions",
+    "function_call",
+    "toolConfig",
+    "tool_config",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RequestFeature {
+    Vision,
+    ToolUse,
+    Thinking,
+    Context,
+}
+
+impl RequestFeature {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Vision => "vision",
+            Self::ToolUse => "tool_use",
+            Self::Thinking => "thinking",
+            Self::Context => "max_context",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct RequestFeatures {
+    pub(crate) vision: bool,
+    pub(crate) tool_use: bool,
+    /// Tool calls or results already in the conversation; tools cannot be stripped then.
+    pub(crate) tool_history: bool,
+    pub(crate) thinking: bool,
+    pub(crate) input_tokens: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum GateDecision {
+    Allow,
+    Skip(Vec<RequestFeature>),
+    Strip(Vec<RequestFeature>),
+}
+
+/// Request-wide gate state, built once per request when the table has rules for the CLI.
+#[derive(Debug, Clone)]
+pub(crate) struct CapabilityGate {
+    pub(crate) policy: CapabilityGatePolicy,
+    pub(crate) rules: Vec<ModelCapabilityRule>,
+    pub(crate) features: RequestFeatures,
+}
+
+impl CapabilityGate {
+    pub(crate) fn new(
+        cfg: &settings::AppSettings,
+        cli_key: &str,
+        introspection_json: Option<&Value>,
+    ) -> Option<Self> {
+        if cfg.capability_gate_policy == CapabilityGatePolicy::Off {
+            return None;
+        }
+        let rules: Vec<ModelCapabilityRule> = cfg
+            .model_capabilities
+            .iter()
+            .filter(|rule| rule.cli_key == cli_key)
+            .cloned()
+            .collect();
+        if rules.is_empty() {
+            return None;
+        }
+        Some(Self {
+            policy: cfg.capability_gate_policy,
+            rules,
+            features: detect_features(cli_key, introspection_json),
+        })
+    }
+
+    /// Decision for `model` on `provider_id`, with the rule it came from.
+    pub(crate) fn decide(
+        &self,
+        cli_key: &str,
+        provider_id: i64,
+        model: &str,
+    ) -> (GateDecision, Option<&ModelCapabilityRule>) {
+        match settings::matching_model_capability_rule(&self.rules, cli_key, provider_id, model) {
+            Some(rule) => (decide(rule, &self.features, self.policy), Some(rule)),
+            None => (GateDecision::Allow, None),
+        }
+    }
+}
+
+fn is_image_part(map: &Map<String, Value>) -> bool {
+    if matches!(
+        map.get("type").and_then(Value::as_str),
+        Some("image" | "input_image" | "image_url")
+    ) {
+        return true;
+    }
+    ["inlineData", "inline_data", "fileData", "file_data"]
+        .iter()
+        .filter_map(|key| map.get(*key))
+        .any(|data| {
+            data.get("mimeType")
+                .or_else(|| data.get("mime_type"))
+                .and_then(Value::as_str)
+                .is_some_and(|mime| mime.starts_with("image/"))
+        })
+}
+
+fn is_tool_history_part(map: &Map<String, Value>) -> bool {
+    matches!(
+        map.get("type").and_then(Value::as_str),
+        Some("tool_use" | "tool_result" | "function_call" | "function_call_output")
+    ) || matches!(
+        map.get("role").and_then(Value::as_str),
+        Some("tool" | "function")
+    ) || map
+        .get("tool_calls")
+        .and_then(Value::as_array)
+        .is_some_and(|calls| !calls.is_empty())
+        || map.contains_key("functionCall")
+        || map.contains_key("functionResponse")
+}
+
+fn any_part(value: &Value, pred: &dyn Fn(&Map<String, Value>) -> bool) -> bool {
+    match value {
+        Value::Object(map) => pred(map) || map.values().any(|v| any_part(v, pred)),
+        Value::Array(items) => items.iter().any(|v| any_part(v, pred)),
+        _ => false,
+    }
+}
+
+fn conversation_has(root: &Map<String, Value>, pred: &dyn Fn(&Map<String, Value>) -> bool) -> bool {
+    CONVERSATION_KEYS
+        .iter()
+        .filter_map(|key| root.get(*key))
+        .any(|value| any_part(value, pred))
+}
+
+fn non_empty_array(value: Option<&Value>) -> bool {
+    value
+        .and_then(Value::as_array)
+        .is_some_and(|items| !items.is_empty())
+}
+
+fn requests_thinking(root: &Map<String, Value>) -> bool {
+    // Claude `thinking`, OpenAI Responses `reasoning`, Chat Completions `reasoning_effort`.
+    let claude = root
+        .get("thinking")
+        .and_then(|v| v.get("type"))
+        .and_then(Value::as_str)
+        .is_some_and(|kind| kind != "disabled");
+    let responses = root
+        .get("reasoning")
+        .filter(|v| v.is_object())
+        .is_some_and(|v| v.get("effort").and_then(Value::as_str) != Some("none"));
+    let chat = root
+        .get("reasoning_effort")
+        .and_then(Value::as_str)
+        .is_some_and(|effort| effort != "none");
+    let gemini = ["generationConfig", "generation_config"]
+        .iter()
+        .filter_map(|key| root.get(*key))
+        .filter_map(|config| {
+            config
+                .get("thinkingConfig")
+                .or_else(|| config.get("thinking_config"))
+        })
+        .any(|thinking| {
+            thinking
+                .get("thinkingBudget")
+                .or_else(|| thinking.get("thinking_budget"))
+                .and_then(Value::as_i64)
+                != Some(0)
+        });
+    claude || responses || chat || gemini
+}
+
+/// Unwraps the Gemini Code Assist envelope (`{"request": {...}}`) used for Gemini OAuth.
+fn request_object(root: &Value) -> Option<&Map<String, Value>> {
+    let map = root.as_object()?;
+    match map.get("request").and_then(Value::as_object) {
+        Some(inner) if !map.contains_key("contents") => Some(inner),
+        _ => Some(map),
+    }
+}
+
+/// Requests without a JSON body report no features and never get gated.
+pub(crate) fn detect_features(
+    cli_key: &str,
+    introspection_json: Option<&Value>,
+) -> RequestFeatures {
+    let Some(json) = introspection_json else {
+        return RequestFeatures::default();
+    };
+    let input_tokens = if cli_key == "claude" {
+        token_estimate::estimate_count_tokens_request(json)
+    } else {
+        token_estimate::estimate_text_tokens(&json.to_string())
+    };
+    let Some(root) = request_object(json) else {
+        return RequestFeatures::default();
+    };
+    RequestFeatures {
+        vision: conversation_has(root, &is_image_part),
+        tool_use: non_empty_array(root.get("tools")) || non_empty_array(root.get("functions")),
+        tool_history: conversation_has(root, &is_tool_history_part),
+        thinking: requests_thinking(root),
+        input_tokens,
+    }
+}
+
+fn missing_features(rule: &ModelCapabilityRule, features: &RequestFeatures) -> Vec<RequestFeature> {
+    let mut missing = Vec::new();
+    if features.vision && rule.vision == Some(false) {
+        missing.push(RequestFeature::Vision);
+    }
+    if features.tool_use && rule.tool_use == Some(false) {
+        missing.push(RequestFeature::ToolUse);
+    }
+    if features.thinking && rule.thinking == Some(false) {
+        missing.push(RequestFeature::Thinking);
+    }
+    if rule
+        .max_context_tokens
+        .is_some_and(|max| features.input_tokens > u64::from(max))
+    {
+        missing.push(RequestFeature::Context);
+    }
+    missing
+}
+
+pub(crate) fn decide(
+    rule: &ModelCapabilityRule,
+    features: &RequestFeatures,
+    policy: CapabilityGatePolicy,
+) -> GateDecision {
+    let missing = missing_features(rule, features);
+    if missing.is_empty() {
+        return GateDecision::Allow;
+    }
+    match policy {
+        CapabilityGatePolicy::Off => GateDecision::Allow,
+        CapabilityGatePolicy::SkipProvider => GateDecision::Skip(missing),
+        CapabilityGatePolicy::StripFeature => {
+            let strippable = missing.iter().all(|feature| match feature {
+                RequestFeature::Context => false,
+                RequestFeature::ToolUse => !features.tool_history,
+                RequestFeature::Vision | RequestFeature::Thinking => true,
+            });
+            if strippable {
+                GateDecision::Strip(missing)
+            } else {
+                GateDecision::Skip(missing)
+            }
+        }
+    }
+}
+
+/// Image parts become a text part of the same dialect, so no message ends up empty.
+fn replace_image_parts(value: &mut Value) {
+    match value {
+        Value::Object(map) if is_image_part(map) => {
+            *value = match map.get("type").and_then(Value::as_str) {
+                Some("input_image") => {
+                    serde_json::json!({ "type": "input_text", "text": IMAGE_PLACEHOLDER })
+                }
+                Some(_) => serde_json::json!({ "type": "text", "text": IMAGE_PLACEHOLDER }),
+                None => serde_json::json!({ "text": IMAGE_PLACEHOLDER }),
+            };
+        }
+        Value::Object(map) => map.values_mut().for_each(replace_image_parts),
+        Value::Array(items) => items.iter_mut().for_each(replace_image_parts),
+        _ => {}
+    }
+}
+
+fn strip_from_request(root: &mut Map<String, Value>, features: &[RequestFeature]) {
+    for feature in features {
+        match feature {
+            RequestFeature::Vision => {
+                for key in CONVERSATION_KEYS {
+                    if let Some(value) = root.get_mut(*key) {
+                        replace_image_parts(value);
+                    }
+                }
+            }
+            RequestFeature::ToolUse => {
+                for key in TOOL_KEYS {
+                    root.remove(*key);
+                }
+            }
+            RequestFeature::Thinking => {
+                root.remove("thinking");
+                root.remove("reasoning");
+                root.remove("reasoning_effort");
+                for key in ["generationConfig", "generation_config"] {
+                    if let Some(config) = root.get_mut(key).and_then(Value::as_object_mut) {
+                        config.remove("thinkingConfig");
+                        config.remove("thinking_config");
+                    }
+                }
+            }
+            RequestFeature::Context => {}
+        }
+    }
+}
+
+/// Removes `features` from the request body (see [`decide`] for what can be stripped).
+pub(crate) fn strip_features(root: &mut Value, features: &[RequestFeature]) {
+    let Some(map) = root.as_object_mut() else {
+        return;
+    };
+    if !map.contains_key("contents") {
+        if let Some(inner) = map.get_mut("request").and_then(Value::as_object_mut) {
+            strip_from_request(inner, features);
+            return;
+        }
+    }
+    strip_from_request(map, features);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rule(
+        vision: Option<bool>,
+        tool_use: Option<bool>,
+        thinking: Option<bool>,
+    ) -> ModelCapabilityRule {
+        ModelCapabilityRule {
+            cli_key: "claude".to_string(),
+            pattern: "*".to_string(),
+            vision,
+            tool_use,
+            thinking,
+            ..Default::default()
+        }
+    }
+
+    fn detect(cli_key: &str, body: &Value) -> RequestFeatures {
+        detect_features(cli_key, Some(body))
+    }
+
+    #[test]
+    fn detects_features_across_request_formats() {
+        let claude = json!({
+            "model": "claude-sonnet-4-5",
+            "thinking": { "type": "enabled", "budget_tokens": 2048 },
+            "tools": [{ "name": "read", "input_schema": { "type": "object" } }],
+            "messages": [{ "role": "user", "content": [
+                { "type": "image", "source": { "type": "base64", "media_type": "image/png", "data": "AA" } },
+                { "type": "text", "text": "what is this?" }
+            ] }]
+        });
+        let features = detect("claude", &claude);
+        assert!(features.vision && features.tool_use && features.thinking);
+        assert!(!features.tool_history);
+        assert!(features.input_tokens > 0);
+
+        let responses = json!({
+            "model": "gpt-5",
+            "reasoning": { "effort": "high" },
+            "input": [
+                { "role": "user", "content": [{ "type": "input_image", "image_url": "data:," }] },
+                { "type": "function_call_output", "call_id": "c1", "output": "ok" }
+            ]
+        });
+        let features = detect("codex", &responses);
+        assert!(features.vision && features.thinking && features.tool_history);
+        assert!(!features.tool_use);
+
+        let gemini = json!({
+            "request": {
+                "contents": [{ "role": "user", "parts": [
+                    { "inlineData": { "mimeType": "image/jpeg", "data": "AA" } }
+                ] }],
+                "generationConfig": { "thinkingConfig": { "thinkingBudget": 0 } }
+            }
+        });
+        let features = detect("gemini", &gemini);
+        assert!(features.vision);
+        assert!(!features.thinking);
+
+        let plain = json!({ "messages": [{ "role": "user", "content": "hi" }] });
+        let features = detect("codex", &plain);
+        assert!(!features.vision && !features.tool_use && !features.thinking);
+    }
+
+    #[test]
+    fn decide_follows_policy_and_leaves_unknown_capabilities_alone() {
+        let features = RequestFeatures {
+            vision: true,
+            tool_use: true,
+            thinking: true,
+            input_tokens: 1_000,
+            ..Default::default()
+        };
+        let no_vision = rule(Some(false), None, Some(true));
+        assert_eq!(
+            decide(&no_vision, &features, CapabilityGatePolicy::SkipProvider),
+            GateDecision::Skip(vec![RequestFeature::Vision])
+        );
+        assert_eq!(
+            decide(&no_vision, &features, CapabilityGatePolicy::StripFeature),
+            GateDecision::Strip(vec![RequestFeature::Vision])
+        );
+        assert_eq!(
+            decide(&no_vision, &features, CapabilityGatePolicy::Off),
+            GateDecision::Allow
+        );
+        assert_eq!(
+            decide(
+                &rule(None, None, None),
+                &features,
+                CapabilityGatePolicy::SkipProvider
+            ),
+            GateDecision::Allow
+        );
+
+        let small_context = ModelCapabilityRule {
+            max_context_tokens: Some(500),
+            ..rule(None, None, Some(false))
+        };
+        assert_eq!(
+            decide(
+                &small_context,
+                &features,
+                CapabilityGatePolicy::StripFeature
+            ),
+            GateDecision::Skip(vec![RequestFeature::Thinking, RequestFeature::Context])
+        );
+
+        let with_history = RequestFeatures {
+            tool_history: true,
+            ..features
+        };
+        assert_eq!(
+            decide(
+                &rule(None, Some(false), None),
+                &with_history,
+                CapabilityGatePolicy::StripFeature
+            ),
+            GateDecision::Skip(vec![RequestFeature::ToolUse])
+        );
+    }
+
+    #[test]
+    fn strip_features_removes_tools_thinking_and_images() {
+        let mut body = json!({
+            "model": "claude-sonnet-4-5",
+            "thinking": { "type": "enabled", "budget_tokens": 2048 },
+            "tools": [{ "name": "read" }],
+            "tool_choice": { "type": "auto" },
+            "messages": [{ "role": "user", "content": [
+                { "type": "image", "source": { "type": "url", "url": "https://x/y.png" } },
+                { "type": "text", "text": "describe" }
+            ] }]
+        });
+        strip_features(
+            &mut body,
+            &[
+                RequestFeature::Vision,
+                RequestFeature::ToolUse,
+                RequestFeature::Thinking,
+            ],
+        );
+        assert_eq!(
+            body,
+            json!({
+                "model": "claude-sonnet-4-5",
+                "messages": [{ "role": "user", "content": [
+                    { "type": "text", "text": IMAGE_PLACEHOLDER },
+                    { "type": "text", "text": "describe" }
+                ] }]
+            })
+        );
+
+        let mut gemini = json!({
+            "request": {
+                "contents": [{ "parts": [{ "fileData": { "mimeType": "image/png", "fileUri": "gs://x" } }] }],
+                "generationConfig": { "temperature": 1, "thinkingConfig": { "thinkingBudget": 1024 } }
+            }
+        });
+        strip_features(
+            &mut gemini,
+            &[RequestFeature::Vision, RequestFeature::Thinking],
+        );
+        assert_eq!(
+            gemini["request"]["contents"][0]["parts"][0],
+            json!({ "text": IMAGE_PLACEHOLDER })
+        );
+        assert_eq!(
+            gemini["request"]["generationConfig"],
+            json!({ "temperature": 1 })
+        );
+    }
+}
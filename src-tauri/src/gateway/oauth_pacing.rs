@@ -0,0 +1,421 @@
+//! Usage: Quota-aware request pacing per OAuth account (token bucket per provider).
+//!
+//! Every OAuth response teaches us the account's quota windows: Codex ChatGPT accounts report
+//! `x-codex-{primary,secondary}-*` (5h / weekly usage percent), other upstreams
+//! `x-ratelimit-{remaining,reset}-requests`. From them we estimate how many requests the account
+//! can still afford before each window resets and refill the bucket at that rate, so bursts are
+//! spaced out instead of tripping the upstream quota cooldown. Accounts without learned quota
+//! are never delayed. State is in-memory.
+
+use super::{GatewayOAuthPacingState, GatewayOAuthQuotaWindow};
+use crate::shared::mutex_ext::MutexExt;
+use axum::http::HeaderMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, OnceLock};
+
+const BURST: f64 = 4.0;
+const MAX_DELAY_MS: u64 = 15_000;
+/// Usage percent a percent-based window should reach at most before it resets.
+const TARGET_USED_PERCENT: f64 = 95.0;
+const COST_EWMA_ALPHA: f64 = 0.3;
+/// Floor for the time left in a window, so a window about to reset does not stall requests.
+const MIN_WINDOW_REMAINING_MS: i64 = 1_000;
+
+const CODEX_WINDOWS: [&str; 2] = ["primary", "secondary"];
+const REQUESTS_WINDOW: &str = "requests";
+
+#[derive(Debug, Clone, Default)]
+struct QuotaWindow {
+    used_percent: Option<f64>,
+    remaining_requests: Option<f64>,
+    window_minutes: Option<u32>,
+    resets_at_ms: i64,
+    /// Learned usage percent one request costs (percent windows only).
+    percent_per_request: Option<f64>,
+    /// Usage percent and request count since the last cost sample; percents are reported
+    /// rounded, so a sample is taken once usage moved by at least one point.
+    baseline_used_percent: Option<f64>,
+    requests_since_baseline: u32,
+    requests_since_observed: u32,
+}
+
+impl QuotaWindow {
+    /// Requests the window can still afford at `now_ms`; `None` when it does not constrain.
+    fn affordable_requests(&self, now_ms: i64) -> Option<f64> {
+        if now_ms >= self.resets_at_ms {
+            return None;
+        }
+        let pending = f64::from(self.requests_since_observed);
+        let affordable = match (self.remaining_requests, self.used_percent) {
+            (Some(remaining), _) => remaining - pending,
+            (None, Some(used)) => {
+                let cost = self.percent_per_request.filter(|v| *v > 0.0)?;
+                (TARGET_USED_PERCENT - used) / cost - pending
+            }
+            (None, None) => return None,
+        };
+        Some(affordable.max(0.0))
+    }
+
+    fn rate_per_ms(&self, now_ms: i64) -> Option<f64> {
+        let affordable = self.affordable_requests(now_ms)?;
+        let remaining_ms = (self.resets_at_ms - now_ms).max(MIN_WINDOW_REMAINING_MS);
+        Some(affordable / remaining_ms as f64)
+    }
+
+    fn observe_used_percent(&mut self, used: f64) {
+        match self.baseline_used_percent {
+            Some(baseline) if used < baseline => {
+                // Window reset: keep the learned cost, restart sampling.
+                self.baseline_used_percent = Some(used);
+                self.requests_since_baseline = 0;
+            }
+            Some(baseline) if used - baseline >= 1.0 && self.requests_since_baseline > 0 => {
+                let sample = (used - baseline) / f64::from(self.requests_since_baseline);
+                self.percent_per_request = Some(match self.percent_per_request {
+                    Some(prev) => prev + COST_EWMA_ALPHA * (sample - prev),
+                    None => sample,
+                });
+                self.baseline_used_percent = Some(used);
+                self.requests_since_baseline = 0;
+            }
+            Some(_) => {}
+            None => {
+                self.baseline_used_percent = Some(used);
+                self.requests_since_baseline = 0;
+            }
+        }
+        self.used_percent = Some(used);
+    }
+}
+
+struct ObservedWindow {
+    name: &'static str,
+    used_percent: Option<f64>,
+    remaining_requests: Option<f64>,
+    window_minutes: Option<u32>,
+    resets_at_ms: i64,
+}
+
+#[derive(Debug, Clone)]
+struct AccountPacing {
+    cli_key: String,
+    provider_name: String,
+    windows: BTreeMap<&'static str, QuotaWindow>,
+    tokens: f64,
+    refilled_at_ms: i64,
+    paced_requests: u64,
+    total_delay_ms: u64,
+    last_delay_ms: u64,
+    observed_at_ms: i64,
+}
+
+impl AccountPacing {
+    fn rate_per_ms(&self, now_ms: i64) -> Option<f64> {
+        self.windows
+            .values()
+            .filter_map(|window| window.rate_per_ms(now_ms))
+            .reduce(f64::min)
+    }
+
+    fn tokens_at(&self, rate_per_ms: f64, now_ms: i64) -> f64 {
+        let elapsed_ms = now_ms.saturating_sub(self.refilled_at_ms).max(0) as f64;
+        (self.tokens + elapsed_ms * rate_per_ms).min(BURST)
+    }
+}
+
+static ACCOUNTS: OnceLock<Mutex<HashMap<i64, AccountPacing>>> = OnceLock::new();
+
+fn accounts() -> &'static Mutex<HashMap<i64, AccountPacing>> {
+    ACCOUNTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn header_f64(headers: &HeaderMap, name: &str) -> Option<f64> {
+    headers
+        .get(name)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite() && *v >= 0.0)
+}
+
+/// Parses reset durations as sent by OpenAI-style upstreams (`"1s"`, `"6m0s"`, `"20ms"`,
+/// `"1h2m3.5s"`) or plain seconds.
+fn parse_reset_duration_ms(raw: &str) -> Option<i64> {
+    let raw = raw.trim();
+    if let Ok(secs) = raw.parse::<f64>() {
+        return (secs.is_finite() && secs >= 0.0).then_some((secs * 1000.0) as i64);
+    }
+
+    let mut total_ms = 0.0;
+    let mut number = String::new();
+    let mut chars = raw.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() || c == '.' {
+            number.push(c);
+            continue;
+        }
+        let value: f64 = number.parse().ok()?;
+        number.clear();
+        let unit_ms = match c {
+            'h' => 3_600_000.0,
+            'm' => {
+                if chars.peek() == Some(&'s') {
+                    chars.next();
+                    1.0
+                } else {
+                    60_000.0
+                }
+            }
+            's' => 1_000.0,
+            _ => return None,
+        };
+        total_ms += value * unit_ms;
+    }
+    number.is_empty().then_some(total_ms as i64)
+}
+
+/// Learns quota windows from an OAuth account's response headers.
+pub(in crate::gateway) fn observe(
+    provider_id: i64,
+    cli_key: &str,
+    provider_name: &str,
+    headers: &HeaderMap,
+    now_ms: i64,
+) {
+    let mut parsed: Vec<ObservedWindow> = Vec::new();
+    for name in CODEX_WINDOWS {
+        let Some(used) = header_f64(headers, &format!("x-codex-{name}-used-percent")) else {
+            continue;
+        };
+        let window_minutes = header_f64(headers, &format!("x-codex-{name}-window-minutes"))
+            .map(|v| v as u32)
+            .filter(|v| *v > 0);
+        let reset_ms = header_f64(headers, &format!("x-codex-{name}-reset-after-seconds"))
+            .map(|secs| (secs * 1000.0) as i64)
+            .or_else(|| window_minutes.map(|minutes| i64::from(minutes) * 60_000));
+        let Some(reset_ms) = reset_ms else {
+            continue;
+        };
+        parsed.push(ObservedWindow {
+            name,
+            used_percent: Some(used),
+            remaining_requests: None,
+            window_minutes,
+            resets_at_ms: now_ms + reset_ms,
+        });
+    }
+
+    let remaining = header_f64(headers, "x-ratelimit-remaining-requests");
+    let reset_ms = headers
+        .get("x-ratelimit-reset-requests")
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_reset_duration_ms);
+    if let (Some(remaining), Some(reset_ms)) = (remaining, reset_ms) {
+        parsed.push(ObservedWindow {
+            name: REQUESTS_WINDOW,
+            used_percent: None,
+            remaining_requests: Some(remaining),
+            window_minutes: None,
+            resets_at_ms: now_ms + reset_ms,
+        });
+    }
+
+    if parsed.is_empty() {
+        return;
+    }
+
+    let mut accounts = accounts().lock_or_recover();
+    let account = accounts
+        .entry(provider_id)
+        .or_insert_with(|| AccountPacing {
+            cli_key: cli_key.to_string(),
+            provider_name: provider_name.to_string(),
+            windows: BTreeMap::new(),
+            tokens: BURST,
+            refilled_at_ms: now_ms,
+            paced_requests: 0,
+            total_delay_ms: 0,
+            last_delay_ms: 0,
+            observed_at_ms: now_ms,
+        });
+    account.cli_key = cli_key.to_string();
+    account.provider_name = provider_name.to_string();
+    account.observed_at_ms = now_ms;
+
+    for observed in parsed {
+        let window = account.windows.entry(observed.name).or_default();
+        if let Some(used) = observed.used_percent {
+            window.observe_used_percent(used);
+        }
+        window.remaining_requests = observed.remaining_requests;
+        window.window_minutes = observed.window_minutes;
+        window.resets_at_ms = observed.resets_at_ms;
+        window.requests_since_observed = 0;
+    }
+}
+
+/// Reserves a request slot for `provider_id` and returns how long to wait before sending it.
+pub(in crate::gateway) fn acquire(provider_id: i64, now_ms: i64) -> u64 {
+    let mut accounts = accounts().lock_or_recover();
+    let Some(account) = accounts.get_mut(&provider_id) else {
+        return 0;
+    };
+
+    let rate = account.rate_per_ms(now_ms);
+    for window in account.windows.values_mut() {
+        window.requests_since_observed = window.requests_since_observed.saturating_add(1);
+        window.requests_since_baseline = window.requests_since_baseline.saturating_add(1);
+    }
+
+    let Some(rate) = rate else {
+        account.tokens = BURST;
+        account.refilled_at_ms = now_ms;
+        account.last_delay_ms = 0;
+        return 0;
+    };
+
+    let tokens = account.tokens_at(rate, now_ms);
+    let delay_ms = if tokens >= 1.0 {
+        0
+    } else if rate <= 0.0 {
+        MAX_DELAY_MS
+    } else {
+        (((1.0 - tokens) / rate).ceil() as u64).min(MAX_DELAY_MS)
+    };
+    account.tokens = (tokens - 1.0).max(-BURST);
+    account.refilled_at_ms = now_ms;
+    account.last_delay_ms = delay_ms;
+    if delay_ms > 0 {
+        account.paced_requests = account.paced_requests.saturating_add(1);
+        account.total_delay_ms = account.total_delay_ms.saturating_add(delay_ms);
+    }
+    delay_ms
+}
+
+pub(crate) fn snapshot(now_ms: i64) -> Vec<GatewayOAuthPacingState> {
+    let accounts = accounts().lock_or_recover();
+    let mut out: Vec<GatewayOAuthPacingState> = accounts
+        .iter()
+        .map(|(provider_id, account)| {
+            let rate = account.rate_per_ms(now_ms);
+            GatewayOAuthPacingState {
+                provider_id: *provider_id,
+                cli_key: account.cli_key.clone(),
+                provider_name: account.provider_name.clone(),
+                requests_per_minute: rate.map(|rate| rate * 60_000.0),
+                tokens: rate
+                    .map(|rate| account.tokens_at(rate, now_ms))
+                    .unwrap_or(BURST),
+                burst: BURST,
+                paced_requests: account.paced_requests,
+                total_delay_ms: account.total_delay_ms,
+                last_delay_ms: account.last_delay_ms,
+                observed_at: account.observed_at_ms / 1000,
+                windows: account
+                    .windows
+                    .iter()
+                    .map(|(name, window)| GatewayOAuthQuotaWindow {
+                        name: name.to_string(),
+                        used_percent: window.used_percent,
+                        remaining_requests: window.remaining_requests,
+                        window_minutes: window.window_minutes,
+                        resets_at: window.resets_at_ms / 1000,
+                        percent_per_request: window.percent_per_request,
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+    out.sort_by_key(|state| state.provider_id);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn codex_headers(used_percent: u32, reset_after_seconds: u32) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-codex-primary-used-percent",
+            HeaderValue::from_str(&used_percent.to_string()).unwrap(),
+        );
+        headers.insert(
+            "x-codex-primary-window-minutes",
+            HeaderValue::from_static("300"),
+        );
+        headers.insert(
+            "x-codex-primary-reset-after-seconds",
+            HeaderValue::from_str(&reset_after_seconds.to_string()).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn parses_openai_style_reset_durations() {
+        assert_eq!(parse_reset_duration_ms("1s"), Some(1_000));
+        assert_eq!(parse_reset_duration_ms("6m0s"), Some(360_000));
+        assert_eq!(parse_reset_duration_ms("20ms"), Some(20));
+        assert_eq!(parse_reset_duration_ms("1h2m3.5s"), Some(3_723_500));
+        assert_eq!(parse_reset_duration_ms("2.5"), Some(2_500));
+        assert_eq!(parse_reset_duration_ms("soon"), None);
+    }
+
+    #[test]
+    fn learns_codex_cost_and_spaces_requests_once_burst_is_spent() {
+        let provider_id = -31_160;
+        assert_eq!(acquire(provider_id, 0), 0, "unknown accounts are not paced");
+
+        // 100 requests moved usage from 10% to 60%: 0.5% per request, 70 requests left
+        // before the 95% target, 700s until reset => one request every 10s.
+        observe(provider_id, "codex", "acc", &codex_headers(10, 700), 0);
+        for _ in 0..100 {
+            assert_eq!(acquire(provider_id, 0), 0, "no cost learned yet");
+        }
+        observe(provider_id, "codex", "acc", &codex_headers(60, 700), 0);
+
+        let state = snapshot(0)
+            .into_iter()
+            .find(|s| s.provider_id == provider_id)
+            .unwrap();
+        assert_eq!(state.windows[0].percent_per_request, Some(0.5));
+        let rpm = state.requests_per_minute.unwrap();
+        assert!((rpm - 6.0).abs() < 0.01, "rpm={rpm}");
+
+        for _ in 0..BURST as usize {
+            assert_eq!(acquire(provider_id, 0), 0);
+        }
+        let delay = acquire(provider_id, 0);
+        assert!((9_000..=MAX_DELAY_MS).contains(&delay), "delay={delay}");
+
+        let state = snapshot(0)
+            .into_iter()
+            .find(|s| s.provider_id == provider_id)
+            .unwrap();
+        assert_eq!(state.paced_requests, 1);
+
+        // Once the window has reset the account is no longer constrained.
+        assert_eq!(acquire(provider_id, 701_000), 0);
+    }
+
+    #[test]
+    fn remaining_request_headers_drive_the_rate() {
+        let provider_id = -31_161;
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-ratelimit-remaining-requests",
+            HeaderValue::from_static("0"),
+        );
+        headers.insert("x-ratelimit-reset-requests", HeaderValue::from_static("1m"));
+        observe(provider_id, "codex", "acc", &headers, 0);
+
+        for _ in 0..BURST as usize {
+            assert_eq!(acquire(provider_id, 0), 0);
+        }
+        assert_eq!(acquire(provider_id, 0), MAX_DELAY_MS);
+    }
+}
@@ -0,0 +1,334 @@
+//! Usage: Per-provider webhook (`providers.webhook_url`) for circuit and quarantine changes.
+//!
+//! Fired when a provider's circuit opens from closed, when it closes again, and when the
+//! provider gets quarantined. The body carries the triggering trace id plus the provider's recent
+//! failed attempts and their error codes, so whoever runs the relay can look them up on their
+//! side. Delivery is best-effort on the async runtime; failures are only logged.
+
+use crate::app_state::{ensure_db_ready, DbInitState};
+use crate::circuit_breaker::{CircuitState, CircuitTransition};
+use crate::shared::error::AppResult;
+use crate::shared::mutex_ext::MutexExt;
+use crate::{blocking, notification_sinks, providers, redaction};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use tauri::Manager;
+
+const MAX_TRACE_SAMPLES: usize = 10;
+/// Failures older than this no longer count towards the "current" error codes.
+const SAMPLE_TTL_SECS: i64 = 30 * 60;
+
+static RECENT_FAILURES: OnceLock<Mutex<HashMap<i64, VecDeque<TraceSample>>>> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct TraceSample {
+    trace_id: String,
+    error_code: &'static str,
+    at: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WebhookEvent {
+    CircuitOpen,
+    CircuitClosed,
+    Quarantined,
+}
+
+impl WebhookEvent {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::CircuitOpen => "circuit_open",
+            Self::CircuitClosed => "circuit_closed",
+            Self::Quarantined => "quarantined",
+        }
+    }
+}
+
+/// Only the first opening and the final close are reported; half-open probes that fail just
+/// re-open an already reported circuit.
+fn event_for_transition(transition: &CircuitTransition) -> Option<WebhookEvent> {
+    match (transition.prev_state, transition.next_state) {
+        (CircuitState::Closed, CircuitState::Open) => Some(WebhookEvent::CircuitOpen),
+        (CircuitState::Open | CircuitState::HalfOpen, CircuitState::Closed) => {
+            Some(WebhookEvent::CircuitClosed)
+        }
+        _ => None,
+    }
+}
+
+fn push_sample(
+    recent: &mut HashMap<i64, VecDeque<TraceSample>>,
+    provider_id: i64,
+    sample: TraceSample,
+) {
+    let samples = recent.entry(provider_id).or_default();
+    samples.push_back(sample);
+    while samples.len() > MAX_TRACE_SAMPLES {
+        samples.pop_front();
+    }
+}
+
+/// Newest first, dropping samples past [`SAMPLE_TTL_SECS`].
+fn current_samples(
+    recent: &mut HashMap<i64, VecDeque<TraceSample>>,
+    provider_id: i64,
+    now_unix: i64,
+) -> Vec<TraceSample> {
+    let Some(samples) = recent.get_mut(&provider_id) else {
+        return Vec::new();
+    };
+    samples.retain(|s| now_unix - s.at < SAMPLE_TTL_SECS);
+    samples.iter().rev().cloned().collect()
+}
+
+/// Remembers a failed attempt so later webhooks for the provider can include it.
+pub(in crate::gateway) fn record_failure(
+    provider_id: i64,
+    trace_id: &str,
+    error_code: &'static str,
+    now_unix: i64,
+) {
+    let mut recent = RECENT_FAILURES
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock_or_recover();
+    push_sample(
+        &mut recent,
+        provider_id,
+        TraceSample {
+            trace_id: trace_id.to_string(),
+            error_code,
+            at: now_unix,
+        },
+    );
+}
+
+struct WebhookArgs<'a> {
+    event: WebhookEvent,
+    trace_id: &'a str,
+    cli_key: &'a str,
+    provider_id: i64,
+    provider_name: &'a str,
+    base_url: Option<&'a str>,
+    reason: &'a str,
+    details: serde_json::Value,
+    now_unix: i64,
+}
+
+fn build_body(args: &WebhookArgs<'_>, samples: &[TraceSample]) -> serde_json::Value {
+    let mut error_codes: BTreeMap<&str, usize> = BTreeMap::new();
+    for sample in samples {
+        *error_codes.entry(sample.error_code).or_default() += 1;
+    }
+    serde_json::json!({
+        "event": args.event.as_str(),
+        "provider": {
+            "id": args.provider_id,
+            "name": args.provider_name,
+            "cli_key": args.cli_key,
+            "base_url": args.base_url.map(|url| redaction::redact_string(url.to_string())),
+        },
+        "reason": args.reason,
+        "trace_id": args.trace_id,
+        "details": args.details,
+        "error_codes": error_codes,
+        "trace_samples": samples,
+        "sent_at": args.now_unix,
+    })
+}
+
+async fn webhook_url(app: &crate::AppHandle, provider_id: i64) -> AppResult<Option<String>> {
+    let db = ensure_db_ready(app.clone(), app.state::<DbInitState>().inner()).await?;
+    blocking::run("provider_webhook_url", move || {
+        providers::webhook_url(&db, provider_id)
+    })
+    .await
+}
+
+fn dispatch(app: &crate::AppHandle, args: WebhookArgs<'_>) {
+    let samples = {
+        let mut recent = RECENT_FAILURES
+            .get_or_init(|| Mutex::new(HashMap::new()))
+            .lock_or_recover();
+        current_samples(&mut recent, args.provider_id, args.now_unix)
+    };
+    let body = build_body(&args, &samples);
+    let provider_id = args.provider_id;
+    let event = args.event.as_str();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let url = match webhook_url(&app, provider_id).await {
+            Ok(Some(url)) => url,
+            Ok(None) => return,
+            Err(err) => {
+                tracing::warn!(
+                    provider_id,
+                    event,
+                    "provider webhook lookup failed: {}",
+                    err
+                );
+                return;
+            }
+        };
+        if let Err(err) = notification_sinks::post_json(&url, &body).await {
+            tracing::warn!(
+                provider_id,
+                event,
+                "provider webhook delivery failed: {}",
+                err
+            );
+        }
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(in crate::gateway) fn dispatch_circuit_transition(
+    app: &crate::AppHandle,
+    trace_id: &str,
+    cli_key: &str,
+    provider_id: i64,
+    provider_name: &str,
+    base_url: &str,
+    transition: &CircuitTransition,
+    now_unix: i64,
+) {
+    let Some(event) = event_for_transition(transition) else {
+        return;
+    };
+    dispatch(
+        app,
+        WebhookArgs {
+            event,
+            trace_id,
+            cli_key,
+            provider_id,
+            provider_name,
+            base_url: Some(base_url),
+            reason: transition.reason,
+            details: serde_json::json!({
+                "prev_state": transition.prev_state.as_str(),
+                "next_state": transition.next_state.as_str(),
+                "failure_count": transition.snapshot.failure_count,
+                "failure_threshold": transition.snapshot.failure_threshold,
+                "open_until": transition.snapshot.open_until,
+            }),
+            now_unix,
+        },
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(in crate::gateway) fn dispatch_quarantine(
+    app: &crate::AppHandle,
+    trace_id: &str,
+    cli_key: &str,
+    provider_id: i64,
+    provider_name: &str,
+    reason: &str,
+    rate: f64,
+    samples: usize,
+    until: i64,
+    now_unix: i64,
+) {
+    dispatch(
+        app,
+        WebhookArgs {
+            event: WebhookEvent::Quarantined,
+            trace_id,
+            cli_key,
+            provider_id,
+            provider_name,
+            base_url: None,
+            reason,
+            details: serde_json::json!({
+                "rate": rate,
+                "samples": samples,
+                "until": until,
+            }),
+            now_unix,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit_breaker::CircuitSnapshot;
+
+    fn transition(prev_state: CircuitState, next_state: CircuitState) -> CircuitTransition {
+        CircuitTransition {
+            prev_state,
+            next_state,
+            reason: "TEST",
+            snapshot: CircuitSnapshot {
+                state: next_state,
+                failure_count: 0,
+                failure_threshold: 5,
+                open_until: None,
+                cooldown_until: None,
+            },
+        }
+    }
+
+    #[test]
+    fn only_first_open_and_final_close_fire() {
+        use CircuitState::*;
+        assert_eq!(
+            event_for_transition(&transition(Closed, Open)),
+            Some(WebhookEvent::CircuitOpen)
+        );
+        assert_eq!(
+            event_for_transition(&transition(HalfOpen, Closed)),
+            Some(WebhookEvent::CircuitClosed)
+        );
+        assert_eq!(event_for_transition(&transition(HalfOpen, Open)), None);
+        assert_eq!(event_for_transition(&transition(Open, HalfOpen)), None);
+    }
+
+    #[test]
+    fn body_lists_recent_samples_newest_first_with_error_code_counts() {
+        let mut recent = HashMap::new();
+        let sample = |trace_id: &str, error_code, at| TraceSample {
+            trace_id: trace_id.to_string(),
+            error_code,
+            at,
+        };
+        push_sample(&mut recent, 1, sample("old", "GW_UPSTREAM_5XX", 0));
+        for i in 0..MAX_TRACE_SAMPLES {
+            let code = if i % 2 == 0 {
+                "GW_UPSTREAM_5XX"
+            } else {
+                "GW_UPSTREAM_TIMEOUT"
+            };
+            push_sample(&mut recent, 1, sample(&format!("t{i}"), code, 1_000));
+        }
+        push_sample(&mut recent, 2, sample("other", "GW_STREAM_ERROR", 1_000));
+
+        let samples = current_samples(&mut recent, 1, 1_000 + SAMPLE_TTL_SECS - 1);
+        assert_eq!(samples.len(), MAX_TRACE_SAMPLES);
+        assert_eq!(samples[0].trace_id, format!("t{}", MAX_TRACE_SAMPLES - 1));
+        assert!(samples.iter().all(|s| s.trace_id != "old"));
+
+        let body = build_body(
+            &WebhookArgs {
+                event: WebhookEvent::CircuitOpen,
+                trace_id: "trigger",
+                cli_key: "claude",
+                provider_id: 1,
+                provider_name: "relay",
+                base_url: Some("https://relay.example.com"),
+                reason: "FAILURE_THRESHOLD_REACHED",
+                details: serde_json::json!({}),
+                now_unix: 2_000,
+            },
+            &samples,
+        );
+        assert_eq!(body["event"], "circuit_open");
+        assert_eq!(body["trace_id"], "trigger");
+        assert_eq!(body["error_codes"]["GW_UPSTREAM_5XX"], 5);
+        assert_eq!(body["error_codes"]["GW_UPSTREAM_TIMEOUT"], 5);
+        assert_eq!(body["trace_samples"].as_array().map(Vec::len), Some(10));
+
+        assert!(current_samples(&mut recent, 1, 1_000 + SAMPLE_TTL_SECS).is_empty());
+    }
+}
@@ -1,5 +1,13 @@
 //! Usage: Best-effort drop guard to log client-aborted requests.
+//!
+//! hyper drops the handler future as soon as it sees the client connection close (EOF while a
+//! request is in flight), which drops the pending upstream reqwest future with it. The guard
+//! runs on that drop and records the upstream attempt that was cut short as
+//! `client_disconnected`. An attempt stays in flight from send until the failover loop records
+//! its outcome, i.e. after the response body was read, so a disconnect while a non-streaming
+//! (or error) body is still downloading is attributed to it too.
 
+use crate::gateway::events::FailoverAttempt;
 use crate::{db, request_logs};
 use std::time::Instant;
 
@@ -9,7 +17,7 @@ use super::request_end::{
 use super::{ErrorCategory, GatewayErrorCode};
 
 pub(super) struct RequestAbortGuard {
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db: db::Db,
     log_tx: tokio::sync::mpsc::Sender<request_logs::RequestLogInsert>,
     trace_id: String,
@@ -21,12 +29,51 @@ pub(super) struct RequestAbortGuard {
     created_at: i64,
     started: Instant,
     armed: bool,
+    tracker: AttemptTracker,
+}
+
+/// Attempts recorded so far plus the upstream attempt still in flight.
+#[derive(Default)]
+struct AttemptTracker {
+    attempts: Vec<FailoverAttempt>,
+    in_flight: Option<(FailoverAttempt, Instant)>,
+}
+
+impl AttemptTracker {
+    fn begin(&mut self, attempts: &[FailoverAttempt], attempt: FailoverAttempt, started: Instant) {
+        self.attempts.clear();
+        self.attempts.extend_from_slice(attempts);
+        self.in_flight = Some((attempt, started));
+    }
+
+    fn end(&mut self) {
+        self.in_flight = None;
+    }
+
+    fn in_flight_provider_id(&self) -> Option<i64> {
+        self.in_flight.as_ref().map(|(a, _)| a.provider_id)
+    }
+
+    fn take_on_disconnect(&mut self) -> Vec<FailoverAttempt> {
+        let mut attempts = std::mem::take(&mut self.attempts);
+        if let Some((mut attempt, attempt_started)) = self.in_flight.take() {
+            attempt.outcome = "client_disconnected".to_string();
+            attempt.error_category = Some(ErrorCategory::ClientAbort.as_str());
+            attempt.error_code = Some(GatewayErrorCode::RequestAborted.as_str());
+            attempt.decision = Some("abort");
+            attempt.reason =
+                Some("client closed the connection; upstream request cancelled".into());
+            attempt.attempt_duration_ms = Some(attempt_started.elapsed().as_millis());
+            attempts.push(attempt);
+        }
+        attempts
+    }
 }
 
 impl RequestAbortGuard {
     #[allow(clippy::too_many_arguments)]
     pub(super) fn new(
-        app: tauri::AppHandle,
+        app: crate::AppHandle,
         db: db::Db,
         log_tx: tokio::sync::mpsc::Sender<request_logs::RequestLogInsert>,
         trace_id: String,
@@ -51,12 +98,29 @@ impl RequestAbortGuard {
             created_at,
             started,
             armed: true,
+            tracker: AttemptTracker::default(),
         }
     }
 
     pub(super) fn disarm(&mut self) {
         self.armed = false;
     }
+
+    /// Remember the attempts so far and the upstream request about to be sent, so a client
+    /// disconnect while it is pending (or its body is being read) is attributed to it.
+    pub(super) fn begin_attempt(
+        &mut self,
+        attempts: &[FailoverAttempt],
+        attempt: FailoverAttempt,
+        attempt_started: Instant,
+    ) {
+        self.tracker.begin(attempts, attempt, attempt_started);
+    }
+
+    /// The pending attempt is done and is about to be recorded by the failover loop itself.
+    pub(super) fn end_attempt(&mut self) {
+        self.tracker.end();
+    }
 }
 
 impl Drop for RequestAbortGuard {
@@ -66,6 +130,26 @@ impl Drop for RequestAbortGuard {
         }
 
         let duration_ms = self.started.elapsed().as_millis();
+        let in_flight_provider_id = self.tracker.in_flight_provider_id();
+        let attempts = self.tracker.take_on_disconnect();
+        if let Some(provider_id) = in_flight_provider_id {
+            tracing::info!(
+                trace_id = %self.trace_id,
+                cli_key = %self.cli_key,
+                provider_id,
+                "client disconnected; cancelled pending upstream request"
+            );
+        }
+        let special_settings_json = serde_json::to_string(&[serde_json::json!({
+            "type": "client_abort",
+            "scope": "request",
+            "reason": "client_disconnected",
+            "detected_by": "connection_closed",
+            "duration_ms": duration_ms,
+            "in_flight_provider_id": in_flight_provider_id,
+        })])
+        .ok();
+
         emit_request_event_and_spawn_request_log(RequestEndArgs {
             deps: RequestEndDeps::new(&self.app, &self.db, &self.log_tx),
             trace_id: self.trace_id.as_str(),
@@ -80,8 +164,8 @@ impl Drop for RequestAbortGuard {
             duration_ms,
             event_ttfb_ms: None,
             log_ttfb_ms: None,
-            attempts: &[],
-            special_settings_json: None,
+            attempts: &attempts,
+            special_settings_json,
             session_id: None,
             requested_model: None,
             created_at_ms: self.created_at_ms,
@@ -89,6 +173,155 @@ impl Drop for RequestAbortGuard {
             usage_metrics: None,
             log_usage_metrics: None,
             usage: None,
+            request_bytes: None,
+            response_bytes: None,
+            thinking_budget_tokens: None,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use std::io::{Read, Write};
+    use std::sync::{Arc, Mutex};
+    use tokio::sync::oneshot;
+
+    fn started_attempt(provider_id: i64) -> FailoverAttempt {
+        FailoverAttempt {
+            provider_id,
+            provider_name: format!("P{provider_id}"),
+            base_url: "http://127.0.0.1".to_string(),
+            outcome: "started".to_string(),
+            status: None,
+            provider_index: Some(1),
+            retry_index: Some(1),
+            session_reuse: None,
+            error_category: None,
+            error_code: None,
+            decision: None,
+            reason: None,
+            selection_method: None,
+            reason_code: None,
+            attempt_started_ms: Some(0),
+            attempt_duration_ms: None,
+            circuit_state_before: None,
+            circuit_state_after: None,
+            circuit_failure_count: None,
+            circuit_failure_threshold: None,
+            error_catalog_code: None,
+            response_headers: None,
+            error_body: None,
+            retry_backoff_ms: None,
+            stream_stats: None,
+        }
+    }
+
+    /// Reports what the guard would log when the handler future is dropped.
+    struct ReportOnDrop {
+        tracker: AttemptTracker,
+        report: Arc<Mutex<Option<oneshot::Sender<Vec<FailoverAttempt>>>>>,
+    }
+
+    impl Drop for ReportOnDrop {
+        fn drop(&mut self) {
+            if let Some(tx) = self.report.lock().unwrap().take() {
+                let _ = tx.send(self.tracker.take_on_disconnect());
+            }
+        }
+    }
+
+    #[test]
+    fn ended_attempt_is_not_reported_as_disconnected() {
+        let mut tracker = AttemptTracker::default();
+        let failed = FailoverAttempt {
+            outcome: "upstream_error".to_string(),
+            ..started_attempt(1)
+        };
+        tracker.begin(&[], started_attempt(1), Instant::now());
+        tracker.end();
+        tracker.begin(&[failed], started_attempt(2), Instant::now());
+
+        let attempts = tracker.take_on_disconnect();
+        let outcomes: Vec<_> = attempts.iter().map(|a| a.outcome.as_str()).collect();
+        assert_eq!(outcomes, ["upstream_error", "client_disconnected"]);
+        assert_eq!(attempts[1].provider_id, 2);
+
+        tracker.begin(&[], started_attempt(3), Instant::now());
+        tracker.end();
+        assert!(tracker.take_on_disconnect().is_empty());
+    }
+
+    #[tokio::test]
+    async fn client_drop_during_body_read_logs_client_disconnected() {
+        // Upstream answers with headers, then stalls halfway through a non-streaming body.
+        let upstream = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let upstream_addr = upstream.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut socket, _) = upstream.accept().unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf);
+            let _ = socket.write_all(
+                b"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 64\r\n\r\n{\"id\":",
+            );
+            std::thread::sleep(std::time::Duration::from_secs(30));
+        });
+
+        // Gateway stand-in following the failover loop: begin, send, headers, body, end.
+        let (report_tx, report_rx) = oneshot::channel();
+        let report = Arc::new(Mutex::new(Some(report_tx)));
+        let (headers_tx, headers_rx) = oneshot::channel::<()>();
+        let headers_tx = Arc::new(Mutex::new(Some(headers_tx)));
+        let app = axum::Router::new().route(
+            "/v1/messages",
+            get(move || {
+                let report = report.clone();
+                let headers_tx = headers_tx.clone();
+                async move {
+                    let mut guard = ReportOnDrop {
+                        tracker: AttemptTracker::default(),
+                        report,
+                    };
+                    guard.tracker.begin(&[], started_attempt(7), Instant::now());
+                    let client = reqwest::Client::builder().no_proxy().build().unwrap();
+                    let resp = client
+                        .get(format!("http://{upstream_addr}/"))
+                        .send()
+                        .await
+                        .unwrap();
+                    if let Some(tx) = headers_tx.lock().unwrap().take() {
+                        let _ = tx.send(());
+                    }
+                    let _ = resp.bytes().await;
+                    guard.tracker.end();
+                    axum::http::StatusCode::OK
+                }
+            }),
+        );
+        let gateway = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let gateway_addr = gateway.local_addr().unwrap();
+        tokio::spawn(async move {
+            let _ = axum::serve(gateway, app).await;
         });
+
+        let mut client = std::net::TcpStream::connect(gateway_addr).unwrap();
+        client
+            .write_all(b"GET /v1/messages HTTP/1.1\r\nhost: localhost\r\n\r\n")
+            .unwrap();
+        headers_rx.await.unwrap();
+        drop(client);
+
+        let attempts = tokio::time::timeout(std::time::Duration::from_secs(10), report_rx)
+            .await
+            .expect("handler dropped after client disconnect")
+            .unwrap();
+        assert_eq!(attempts.len(), 1);
+        assert_eq!(attempts[0].provider_id, 7);
+        assert_eq!(attempts[0].outcome, "client_disconnected");
+        assert_eq!(
+            attempts[0].error_code,
+            Some(GatewayErrorCode::RequestAborted.as_str())
+        );
     }
 }
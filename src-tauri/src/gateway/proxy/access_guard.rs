@@ -0,0 +1,162 @@
+//! Usage: Gateway access token guard for non-loopback clients (LAN/custom/WSL listen modes).
+
+use super::errors::error_response;
+use super::GatewayErrorCode;
+use crate::gateway::manager::GatewayAppState;
+use crate::gateway::util::new_trace_id;
+use crate::settings;
+use crate::shared::security::constant_time_eq;
+use axum::body::Body;
+use axum::extract::{ConnectInfo, State};
+use axum::http::{header, HeaderMap, Request, StatusCode};
+use axum::middleware::Next;
+use axum::response::Response;
+use std::net::{IpAddr, SocketAddr};
+
+pub(in crate::gateway) const GATEWAY_KEY_HEADER: &str = "x-aio-gateway-key";
+
+/// Headers CLIs may carry the token in: the dedicated header, or the CLI's own API key header
+/// (the proxy configs write the token as the placeholder API key).
+fn presented_keys(headers: &HeaderMap) -> impl Iterator<Item = &str> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| {
+            v.strip_prefix("Bearer ")
+                .or_else(|| v.strip_prefix("bearer "))
+        });
+
+    [GATEWAY_KEY_HEADER, "x-api-key", "x-goog-api-key"]
+        .into_iter()
+        .filter_map(|name| headers.get(name).and_then(|v| v.to_str().ok()))
+        .chain(bearer)
+        .map(str::trim)
+}
+
+fn is_loopback(ip: IpAddr) -> bool {
+    ip.to_canonical().is_loopback()
+}
+
+fn is_authorized(peer: IpAddr, expected: Option<&str>, headers: &HeaderMap) -> bool {
+    let Some(expected) = expected else {
+        return true;
+    };
+    if is_loopback(peer) {
+        return true;
+    }
+    presented_keys(headers).any(|key| constant_time_eq(key.as_bytes(), expected.as_bytes()))
+}
+
+/// Unreadable settings fail closed: the token may be set, so only loopback peers get through.
+fn is_authorized_with_settings(
+    peer: IpAddr,
+    cfg: Option<&settings::AppSettings>,
+    headers: &HeaderMap,
+) -> bool {
+    match cfg {
+        Some(cfg) => is_authorized(peer, settings::gateway_access_token(cfg), headers),
+        None => is_loopback(peer),
+    }
+}
+
+pub(in crate::gateway) async fn require_gateway_access_token(
+    State(state): State<GatewayAppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let cfg = settings::read(&state.app)
+        .inspect_err(|err| tracing::warn!("gateway access guard: settings read failed: {}", err))
+        .ok();
+    if is_authorized_with_settings(peer.ip(), cfg.as_ref(), req.headers()) {
+        return next.run(req).await;
+    }
+
+    tracing::warn!(peer = %peer, path = %req.uri().path(), "gateway access token rejected");
+    error_response(
+        StatusCode::UNAUTHORIZED,
+        new_trace_id(),
+        GatewayErrorCode::Unauthorized.as_str(),
+        format!(
+            "gateway access token required for non-local clients (send {GATEWAY_KEY_HEADER} or Authorization: Bearer)"
+        ),
+        Vec::new(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn lan() -> IpAddr {
+        "192.168.1.20".parse().unwrap()
+    }
+
+    #[test]
+    fn no_token_configured_allows_everyone() {
+        assert!(is_authorized(lan(), None, &HeaderMap::new()));
+    }
+
+    #[test]
+    fn loopback_peers_skip_token_check() {
+        let headers = HeaderMap::new();
+        assert!(is_authorized(
+            "127.0.0.1".parse().unwrap(),
+            Some("t"),
+            &headers
+        ));
+        assert!(is_authorized("::1".parse().unwrap(), Some("t"), &headers));
+        assert!(is_authorized(
+            "::ffff:127.0.0.1".parse().unwrap(),
+            Some("t"),
+            &headers
+        ));
+    }
+
+    #[test]
+    fn lan_peers_need_matching_token() {
+        let mut headers = HeaderMap::new();
+        assert!(!is_authorized(lan(), Some("secret"), &headers));
+
+        headers.insert(GATEWAY_KEY_HEADER, HeaderValue::from_static("wrong"));
+        assert!(!is_authorized(lan(), Some("secret"), &headers));
+
+        headers.insert(GATEWAY_KEY_HEADER, HeaderValue::from_static("secret"));
+        assert!(is_authorized(lan(), Some("secret"), &headers));
+    }
+
+    #[test]
+    fn lan_peers_may_use_cli_api_key_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer secret"),
+        );
+        assert!(is_authorized(lan(), Some("secret"), &headers));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-goog-api-key", HeaderValue::from_static("secret"));
+        assert!(is_authorized(lan(), Some("secret"), &headers));
+    }
+
+    #[test]
+    fn unreadable_settings_reject_non_loopback_peers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(GATEWAY_KEY_HEADER, HeaderValue::from_static("anything"));
+        assert!(!is_authorized_with_settings(lan(), None, &headers));
+        assert!(!is_authorized_with_settings(lan(), None, &HeaderMap::new()));
+        assert!(is_authorized_with_settings(
+            "127.0.0.1".parse().unwrap(),
+            None,
+            &HeaderMap::new()
+        ));
+
+        let open = settings::AppSettings::default();
+        assert!(is_authorized_with_settings(
+            lan(),
+            Some(&open),
+            &HeaderMap::new()
+        ));
+    }
+}
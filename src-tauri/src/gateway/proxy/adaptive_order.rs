@@ -0,0 +1,117 @@
+//! Usage: Latency/success-aware provider ranking for sort modes using the `adaptive` strategy.
+//!
+//! Attempt health is aggregated from the recent `request_logs.attempts_json` window and cached
+//! per cli for a few minutes, so ranking never hits the database on every request.
+
+use crate::request_attempt_logs::ProviderAttemptHealth;
+use std::collections::HashMap;
+
+pub(super) const HEALTH_WINDOW_SECS: i64 = 30 * 60;
+const REFRESH_SECS: i64 = 3 * 60;
+const MIN_ATTEMPTS: usize = 5;
+const HEALTHY_SUCCESS_RATE: f64 = 0.8;
+
+#[derive(Debug)]
+struct CachedHealth {
+    refreshed_at: i64,
+    health: Vec<ProviderAttemptHealth>,
+}
+
+#[derive(Debug, Default)]
+pub(in crate::gateway) struct AdaptiveOrderCache {
+    by_cli: HashMap<String, CachedHealth>,
+}
+
+impl AdaptiveOrderCache {
+    /// Cached health for `cli_key`, or `None` when it is missing or older than the refresh period.
+    pub(super) fn fresh(&self, cli_key: &str, now_unix: i64) -> Option<Vec<ProviderAttemptHealth>> {
+        self.by_cli
+            .get(cli_key)
+            .filter(|cached| now_unix.saturating_sub(cached.refreshed_at) < REFRESH_SECS)
+            .map(|cached| cached.health.clone())
+    }
+
+    pub(super) fn insert(
+        &mut self,
+        cli_key: &str,
+        now_unix: i64,
+        health: Vec<ProviderAttemptHealth>,
+    ) {
+        self.by_cli.insert(
+            cli_key.to_string(),
+            CachedHealth {
+                refreshed_at: now_unix,
+                health,
+            },
+        );
+    }
+}
+
+/// Rank `provider_ids`: healthy providers (enough attempts, high success rate) first by ascending
+/// p95 latency, then providers without enough data in their configured order, then unhealthy
+/// providers by descending success rate.
+pub(super) fn rank_provider_ids(
+    provider_ids: &[i64],
+    health: &[ProviderAttemptHealth],
+) -> Vec<i64> {
+    let by_id: HashMap<i64, &ProviderAttemptHealth> =
+        health.iter().map(|item| (item.provider_id, item)).collect();
+
+    let mut ranked = provider_ids.to_vec();
+    // Stable sort: ties keep the configured order.
+    ranked.sort_by_key(|provider_id| match by_id.get(provider_id) {
+        Some(item) if item.attempts >= MIN_ATTEMPTS => {
+            if item.success_rate() >= HEALTHY_SUCCESS_RATE {
+                (0, item.p95_duration_ms.unwrap_or(i64::MAX))
+            } else {
+                (2, -((item.success_rate() * 1000.0) as i64))
+            }
+        }
+        _ => (1, 0),
+    });
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rank_provider_ids, AdaptiveOrderCache, REFRESH_SECS};
+    use crate::request_attempt_logs::ProviderAttemptHealth;
+
+    fn health(
+        provider_id: i64,
+        attempts: usize,
+        successes: usize,
+        p95: i64,
+    ) -> ProviderAttemptHealth {
+        ProviderAttemptHealth {
+            provider_id,
+            attempts,
+            successes,
+            p95_duration_ms: Some(p95),
+        }
+    }
+
+    #[test]
+    fn rank_prefers_fast_healthy_providers_and_demotes_failing_ones() {
+        let stats = vec![
+            health(1, 20, 20, 4000),
+            health(2, 20, 19, 800),
+            health(3, 20, 5, 100),
+            health(4, 2, 2, 50),
+            health(6, 20, 10, 100),
+        ];
+        assert_eq!(
+            rank_provider_ids(&[1, 2, 3, 4, 5, 6], &stats),
+            vec![2, 1, 4, 5, 6, 3]
+        );
+    }
+
+    #[test]
+    fn cache_expires_after_refresh_period() {
+        let mut cache = AdaptiveOrderCache::default();
+        cache.insert("claude", 100, vec![health(1, 10, 10, 500)]);
+        assert!(cache.fresh("claude", 100 + REFRESH_SECS - 1).is_some());
+        assert!(cache.fresh("claude", 100 + REFRESH_SECS).is_none());
+        assert!(cache.fresh("codex", 100).is_none());
+    }
+}
@@ -1,9 +1,14 @@
-//! Usage: In-memory caches for gateway proxy behavior (error dedupe, base_url latency picks).
+//! Usage: In-memory caches for gateway proxy behavior (error dedupe, session failure budget,
+//! base_url latency picks).
 
 use axum::http::StatusCode;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
 const RECENT_ERROR_CACHE_MAX_ENTRIES: usize = 512;
+/// Failed upstream attempts a single session may accumulate inside the budget window.
+pub(super) const SESSION_FAILURE_BUDGET_MAX_ATTEMPTS: usize = 20;
+pub(super) const SESSION_FAILURE_BUDGET_WINDOW_SECS: i64 = 5 * 60;
+const SESSION_FAILURE_BUDGET_MAX_SESSIONS: usize = 1024;
 
 #[derive(Debug, Clone)]
 pub(super) struct CachedGatewayError {
@@ -19,6 +24,8 @@ pub(super) struct CachedGatewayError {
 #[derive(Debug, Default)]
 pub(in crate::gateway) struct RecentErrorCache {
     errors: HashMap<u64, CachedGatewayError>,
+    /// Unix seconds of recent failed attempts, keyed by session id (oldest first).
+    session_failures: HashMap<String, VecDeque<i64>>,
 }
 
 impl RecentErrorCache {
@@ -77,6 +84,71 @@ impl RecentErrorCache {
     fn prune_expired(&mut self, now_unix: i64) {
         self.errors.retain(|_, v| v.expires_at_unix > now_unix);
     }
+
+    pub(super) fn record_session_failures(
+        &mut self,
+        now_unix: i64,
+        session_id: &str,
+        failed_attempts: usize,
+    ) {
+        if failed_attempts == 0 {
+            return;
+        }
+        self.prune_session_failures(now_unix);
+
+        if !self.session_failures.contains_key(session_id)
+            && self.session_failures.len() >= SESSION_FAILURE_BUDGET_MAX_SESSIONS
+        {
+            if let Some(oldest_key) = self
+                .session_failures
+                .iter()
+                .min_by_key(|(_, v)| v.back().copied().unwrap_or(i64::MIN))
+                .map(|(k, _)| k.clone())
+            {
+                self.session_failures.remove(&oldest_key);
+            }
+        }
+
+        let failures = self
+            .session_failures
+            .entry(session_id.to_string())
+            .or_default();
+        for _ in 0..failed_attempts {
+            failures.push_back(now_unix);
+        }
+        while failures.len() > SESSION_FAILURE_BUDGET_MAX_ATTEMPTS {
+            failures.pop_front();
+        }
+    }
+
+    /// Returns the seconds until the session may retry when its failure budget is exhausted.
+    pub(super) fn session_failure_budget_retry_after(
+        &mut self,
+        now_unix: i64,
+        session_id: &str,
+    ) -> Option<u64> {
+        self.prune_session_failures(now_unix);
+
+        let failures = self.session_failures.get(session_id)?;
+        if failures.len() < SESSION_FAILURE_BUDGET_MAX_ATTEMPTS {
+            return None;
+        }
+        let oldest = *failures.front()?;
+        let remaining = oldest
+            .saturating_add(SESSION_FAILURE_BUDGET_WINDOW_SECS)
+            .saturating_sub(now_unix);
+        Some(remaining.max(1) as u64)
+    }
+
+    fn prune_session_failures(&mut self, now_unix: i64) {
+        let cutoff = now_unix.saturating_sub(SESSION_FAILURE_BUDGET_WINDOW_SECS);
+        self.session_failures.retain(|_, failures| {
+            while failures.front().is_some_and(|ts| *ts <= cutoff) {
+                failures.pop_front();
+            }
+            !failures.is_empty()
+        });
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -132,7 +204,10 @@ impl ProviderBaseUrlPingCache {
 
 #[cfg(test)]
 mod tests {
-    use super::{CachedGatewayError, RecentErrorCache};
+    use super::{
+        CachedGatewayError, RecentErrorCache, SESSION_FAILURE_BUDGET_MAX_ATTEMPTS,
+        SESSION_FAILURE_BUDGET_WINDOW_SECS,
+    };
     use axum::http::StatusCode;
 
     fn cached_error(expires_at_unix: i64, fingerprint_debug: &str) -> CachedGatewayError {
@@ -181,4 +256,30 @@ mod tests {
         let second_read = cache.get_error(110, 12, "fp-correct");
         assert!(second_read.is_none());
     }
+
+    #[test]
+    fn session_failure_budget_trips_and_recovers_after_window() {
+        let mut cache = RecentErrorCache::default();
+        cache.record_session_failures(100, "sess-a", SESSION_FAILURE_BUDGET_MAX_ATTEMPTS - 1);
+        assert_eq!(
+            cache.session_failure_budget_retry_after(100, "sess-a"),
+            None
+        );
+
+        cache.record_session_failures(160, "sess-a", 3);
+        assert_eq!(
+            cache.session_failure_budget_retry_after(170, "sess-a"),
+            Some((100 + SESSION_FAILURE_BUDGET_WINDOW_SECS - 170) as u64)
+        );
+        assert_eq!(
+            cache.session_failure_budget_retry_after(170, "sess-b"),
+            None
+        );
+
+        let after_window = 100 + SESSION_FAILURE_BUDGET_WINDOW_SECS;
+        assert_eq!(
+            cache.session_failure_budget_retry_after(after_window, "sess-a"),
+            None
+        );
+    }
 }
@@ -0,0 +1,480 @@
+//! Usage: Active half-open circuit probing (`circuit_breaker_half_open_probe_enabled`).
+//!
+//! Once a provider's open period is over its circuit sits HALF_OPEN and user requests skip it. A
+//! background loop sends a tiny synthetic request (claude `count_tokens`, the models list for the
+//! other CLIs, the remote hub's `/health` for federated providers) and settles the circuit with the result. Every probe is written to the request log
+//! as its own excluded-from-stats entry whose attempts carry `selection_method = "circuit_probe"`.
+
+use super::request_end::{
+    emit_request_event_and_spawn_request_log, RequestEndArgs, RequestEndDeps,
+};
+use super::{federation, GatewayErrorCode};
+use crate::gateway::anthropic_headers;
+use crate::gateway::error_body_capture;
+use crate::gateway::events::{decision_chain as dc, emit_circuit_transition, FailoverAttempt};
+use crate::gateway::manager::GatewayAppState;
+use crate::gateway::util::{
+    build_target_url, ensure_cli_required_headers, inject_provider_auth, new_trace_id,
+    now_unix_millis, now_unix_seconds,
+};
+use crate::{circuit_breaker, providers};
+use axum::http::{header, HeaderMap, HeaderValue, Method};
+use std::time::{Duration, Instant};
+
+const PROBE_TICK_INTERVAL: Duration = Duration::from_secs(10);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(15);
+/// Used when the provider maps no claude model of its own.
+const DEFAULT_CLAUDE_PROBE_MODEL: &str = "claude-haiku-4-5";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ProbeRequest {
+    method: Method,
+    path: &'static str,
+    body: Option<serde_json::Value>,
+    model: Option<String>,
+}
+
+fn claude_probe_model(models: &providers::ClaudeModels, model_catalog: &[String]) -> String {
+    [&models.haiku_model, &models.main_model]
+        .into_iter()
+        .flatten()
+        .chain(model_catalog.first())
+        .map(|m| m.trim())
+        .find(|m| !m.is_empty())
+        .unwrap_or(DEFAULT_CLAUDE_PROBE_MODEL)
+        .to_string()
+}
+
+fn models_list_request(cli_key: &str) -> ProbeRequest {
+    ProbeRequest {
+        method: Method::GET,
+        path: if cli_key == "gemini" {
+            "/v1beta/models"
+        } else {
+            "/v1/models"
+        },
+        body: None,
+        model: None,
+    }
+}
+
+fn federation_health_request() -> ProbeRequest {
+    ProbeRequest {
+        method: Method::GET,
+        path: "/health",
+        body: None,
+        model: None,
+    }
+}
+
+/// Requests to try in order; the next one is only sent when the endpoint itself is missing.
+fn probe_requests(
+    cli_key: &str,
+    claude_models: &providers::ClaudeModels,
+    model_catalog: &[String],
+) -> Vec<ProbeRequest> {
+    if cli_key != "claude" {
+        return vec![models_list_request(cli_key)];
+    }
+    let model = claude_probe_model(claude_models, model_catalog);
+    vec![
+        ProbeRequest {
+            method: Method::POST,
+            path: super::CLAUDE_COUNT_TOKENS_PATH,
+            body: Some(serde_json::json!({
+                "model": model,
+                "messages": [{ "role": "user", "content": "ping" }],
+            })),
+            model: Some(model),
+        },
+        models_list_request(cli_key),
+    ]
+}
+
+fn endpoint_missing(status: u16) -> bool {
+    matches!(status, 404 | 405)
+}
+
+fn probe_error_code(status: Option<u16>, timed_out: bool) -> &'static str {
+    match status {
+        Some(s) if s >= 500 => GatewayErrorCode::Upstream5xx.as_str(),
+        Some(_) => GatewayErrorCode::Upstream4xx.as_str(),
+        None if timed_out => GatewayErrorCode::UpstreamTimeout.as_str(),
+        None => GatewayErrorCode::UpstreamConnectFailed.as_str(),
+    }
+}
+
+struct ProbeOutcome {
+    status: Option<u16>,
+    error_code: Option<&'static str>,
+    reason: Option<String>,
+    error_body: Option<String>,
+    started_ms: u128,
+    duration_ms: u128,
+}
+
+impl ProbeOutcome {
+    fn success(&self) -> bool {
+        self.error_code.is_none()
+    }
+}
+
+async fn send_probe(
+    client: &reqwest::Client,
+    cli_key: &str,
+    provider: &providers::ProviderForGateway,
+    base_url: &str,
+    request: &ProbeRequest,
+    started: Instant,
+) -> ProbeOutcome {
+    let attempt_started = Instant::now();
+    let started_ms = attempt_started.duration_since(started).as_millis();
+    let finish = |status: Option<u16>,
+                  error_code: Option<&'static str>,
+                  reason: Option<String>,
+                  error_body: Option<String>| ProbeOutcome {
+        status,
+        error_code,
+        reason,
+        error_body,
+        started_ms,
+        duration_ms: attempt_started.elapsed().as_millis(),
+    };
+
+    let url = match build_target_url(base_url, request.path, None) {
+        Ok(url) => url,
+        Err(err) => {
+            return finish(
+                None,
+                Some(GatewayErrorCode::InvalidBaseUrl.as_str()),
+                Some(err),
+                None,
+            )
+        }
+    };
+
+    let mut headers = HeaderMap::new();
+    if federation::is_federated(provider) {
+        federation::inject_headers(&mut headers, &provider.api_key_plaintext);
+    } else {
+        ensure_cli_required_headers(cli_key, &mut headers);
+        inject_provider_auth(cli_key, &provider.api_key_plaintext, &mut headers);
+    }
+    if cli_key == "claude" && !federation::is_federated(provider) {
+        anthropic_headers::apply(
+            &mut headers,
+            provider.anthropic_version.as_deref(),
+            &provider.anthropic_betas,
+            provider.anthropic_beta_strip,
+        );
+    }
+
+    let mut builder = client
+        .request(request.method.clone(), url)
+        .headers(headers)
+        .timeout(PROBE_TIMEOUT);
+    if let Some(body) = &request.body {
+        builder = builder
+            .header(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            )
+            .body(body.to_string());
+    }
+
+    match builder.send().await {
+        Ok(resp) => {
+            let status = resp.status();
+            if status.is_success() {
+                return finish(Some(status.as_u16()), None, None, None);
+            }
+            let body = resp.bytes().await.unwrap_or_default();
+            finish(
+                Some(status.as_u16()),
+                Some(probe_error_code(Some(status.as_u16()), false)),
+                Some(format!("probe returned status {}", status.as_u16())),
+                error_body_capture::capture(&body),
+            )
+        }
+        Err(err) => finish(
+            None,
+            Some(probe_error_code(None, err.is_timeout())),
+            Some(err.to_string()),
+            None,
+        ),
+    }
+}
+
+fn emit_transition(
+    state: &GatewayAppState,
+    trace_id: &str,
+    cli_key: &str,
+    provider: &providers::ProviderForGateway,
+    base_url: &str,
+    transition: Option<&circuit_breaker::CircuitTransition>,
+) {
+    if let Some(t) = transition {
+        emit_circuit_transition(
+            &state.app,
+            trace_id,
+            cli_key,
+            provider.id,
+            &provider.name,
+            base_url,
+            t,
+            now_unix_seconds() as i64,
+        );
+    }
+}
+
+async fn probe_provider(
+    state: &GatewayAppState,
+    cli_key: &str,
+    provider: &providers::ProviderForGateway,
+    opened: Option<circuit_breaker::CircuitTransition>,
+) {
+    let trace_id = format!("probe-{}", new_trace_id());
+    let base_url = provider.base_urls.first().cloned().unwrap_or_default();
+    emit_transition(
+        state,
+        &trace_id,
+        cli_key,
+        provider,
+        &base_url,
+        opened.as_ref(),
+    );
+
+    let created_at_ms = now_unix_millis() as i64;
+    let created_at = now_unix_seconds() as i64;
+    let started = Instant::now();
+    let requests = if federation::is_federated(provider) {
+        vec![federation_health_request()]
+    } else {
+        probe_requests(cli_key, &provider.claude_models, &provider.model_catalog)
+    };
+    let mut attempts: Vec<FailoverAttempt> = Vec::new();
+    let mut last: Option<(&ProbeRequest, ProbeOutcome)> = None;
+    for (index, request) in requests.iter().enumerate() {
+        let outcome = send_probe(
+            &state.client,
+            cli_key,
+            provider,
+            &base_url,
+            request,
+            started,
+        )
+        .await;
+        let retry_next = !outcome.success()
+            && outcome.status.is_some_and(endpoint_missing)
+            && index + 1 < requests.len();
+        attempts.push(FailoverAttempt {
+            provider_id: provider.id,
+            provider_name: provider.name.clone(),
+            base_url: base_url.clone(),
+            outcome: if outcome.success() {
+                "success"
+            } else {
+                "failed"
+            }
+            .to_string(),
+            status: outcome.status,
+            provider_index: Some(1),
+            retry_index: Some(index as u32 + 1),
+            session_reuse: Some(false),
+            error_category: None,
+            error_code: outcome.error_code,
+            decision: Some(if outcome.success() { "success" } else { "skip" }),
+            reason: outcome.reason.clone(),
+            selection_method: Some(dc::SELECTION_METHOD_CIRCUIT_PROBE),
+            reason_code: Some(if outcome.success() {
+                dc::REASON_CIRCUIT_PROBE_SUCCESS
+            } else {
+                dc::REASON_CIRCUIT_PROBE_FAILED
+            }),
+            attempt_started_ms: Some(outcome.started_ms),
+            attempt_duration_ms: Some(outcome.duration_ms),
+            circuit_state_before: Some(circuit_breaker::CircuitState::HalfOpen.as_str()),
+            circuit_state_after: None,
+            circuit_failure_count: None,
+            circuit_failure_threshold: None,
+            error_catalog_code: None,
+            response_headers: None,
+            error_body: outcome.error_body.clone(),
+            retry_backoff_ms: None,
+            stream_stats: None,
+        });
+        last = Some((request, outcome));
+        if !retry_next {
+            break;
+        }
+    }
+    let Some((request, outcome)) = last else {
+        return;
+    };
+
+    let change = state.circuit.record_probe_result(
+        provider.id,
+        now_unix_seconds() as i64,
+        outcome.success(),
+    );
+    if let Some(attempt) = attempts.last_mut() {
+        attempt.circuit_state_after = Some(change.after.state.as_str());
+        attempt.circuit_failure_count = Some(change.after.failure_count);
+        attempt.circuit_failure_threshold = Some(change.after.failure_threshold);
+    }
+    emit_transition(
+        state,
+        &trace_id,
+        cli_key,
+        provider,
+        &base_url,
+        change.transition.as_ref(),
+    );
+    tracing::info!(
+        trace_id = %trace_id,
+        cli_key = %cli_key,
+        provider_id = provider.id,
+        success = outcome.success(),
+        status = ?outcome.status,
+        "circuit half-open probe finished"
+    );
+
+    let duration_ms = started.elapsed().as_millis();
+    let special_settings_json = serde_json::to_string(&[serde_json::json!({
+        "type": "circuit_probe",
+        "scope": "request",
+        "hit": true,
+        "providerId": provider.id,
+        "providerName": provider.name,
+        "success": outcome.success(),
+        "circuitStateAfter": change.after.state.as_str(),
+    })])
+    .ok();
+    emit_request_event_and_spawn_request_log(RequestEndArgs {
+        deps: RequestEndDeps::new(&state.app, &state.db, &state.log_tx),
+        trace_id: &trace_id,
+        cli_key,
+        method: request.method.as_str(),
+        path: request.path,
+        query: None,
+        excluded_from_stats: true,
+        status: outcome.status,
+        error_category: None,
+        error_code: outcome.error_code,
+        duration_ms,
+        event_ttfb_ms: None,
+        log_ttfb_ms: None,
+        attempts: &attempts,
+        special_settings_json,
+        session_id: None,
+        requested_model: request.model.clone(),
+        created_at_ms,
+        created_at,
+        usage_metrics: None,
+        log_usage_metrics: None,
+        usage: None,
+        request_bytes: None,
+        response_bytes: None,
+        thinking_budget_tokens: None,
+    });
+}
+
+fn load_provider(
+    db: &crate::db::Db,
+    provider_id: i64,
+) -> crate::shared::error::AppResult<Option<(String, providers::ProviderForGateway)>> {
+    let Some(cli_key) = providers::cli_key_by_id(db, provider_id)? else {
+        return Ok(None);
+    };
+    Ok(providers::get_for_gateway(db, &cli_key, provider_id)?.map(|p| (cli_key, p)))
+}
+
+async fn probe_once(state: &GatewayAppState) {
+    let candidates = state
+        .circuit
+        .take_probe_candidates(now_unix_seconds() as i64);
+    for (provider_id, opened) in candidates {
+        let loaded = crate::blocking::run("circuit_probe_provider", {
+            let db = state.db.clone();
+            move || load_provider(&db, provider_id)
+        })
+        .await;
+        let provider = match loaded {
+            Ok(provider) => provider,
+            Err(err) => {
+                tracing::warn!(
+                    provider_id,
+                    "circuit probe: provider lookup failed: {}",
+                    err
+                );
+                continue;
+            }
+        };
+
+        match provider {
+            Some((cli_key, provider))
+                if provider.auth_mode != "oauth"
+                    && !provider.base_urls.is_empty()
+                    && !provider.api_key_plaintext.trim().is_empty() =>
+            {
+                probe_provider(state, &cli_key, &provider, opened).await;
+            }
+            // Nothing to probe with (deleted, oauth or no key): fall back to closing on expiry.
+            _ => {
+                tracing::debug!(
+                    provider_id,
+                    "circuit probe: provider not probeable, closing"
+                );
+                state.circuit.reset(provider_id, now_unix_seconds() as i64);
+            }
+        }
+    }
+}
+
+/// Spawns the probe loop; the gateway aborts the returned handle on stop. Probes run one at a
+/// time, so a slow provider is never probed twice concurrently.
+pub(in crate::gateway) fn spawn(state: GatewayAppState) -> tauri::async_runtime::JoinHandle<()> {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(PROBE_TICK_INTERVAL).await;
+            probe_once(&state).await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probe_requests_prefer_count_tokens_for_claude() {
+        let models = providers::ClaudeModels {
+            haiku_model: Some("relay-haiku".to_string()),
+            ..Default::default()
+        };
+
+        let requests = probe_requests("claude", &models, &[]);
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, Method::POST);
+        assert_eq!(requests[0].path, "/v1/messages/count_tokens");
+        assert_eq!(requests[0].model.as_deref(), Some("relay-haiku"));
+        assert_eq!(requests[1].path, "/v1/models");
+
+        let fallback = probe_requests("claude", &Default::default(), &["cat-1".to_string()]);
+        assert_eq!(fallback[0].model.as_deref(), Some("cat-1"));
+
+        let requests = probe_requests("gemini", &models, &[]);
+        assert_eq!(requests, vec![models_list_request("gemini")]);
+        assert_eq!(requests[0].path, "/v1beta/models");
+    }
+
+    #[test]
+    fn probe_error_code_classifies_status_and_transport_errors() {
+        assert_eq!(probe_error_code(Some(503), false), "GW_UPSTREAM_5XX");
+        assert_eq!(probe_error_code(Some(401), false), "GW_UPSTREAM_4XX");
+        assert_eq!(probe_error_code(None, true), "GW_UPSTREAM_TIMEOUT");
+        assert_eq!(probe_error_code(None, false), "GW_UPSTREAM_CONNECT_FAILED");
+        assert!(endpoint_missing(404));
+        assert!(!endpoint_missing(400));
+    }
+}
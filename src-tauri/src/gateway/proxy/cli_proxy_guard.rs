@@ -25,7 +25,7 @@ pub(super) struct CliProxyEnabledSnapshot {
 }
 
 pub(super) fn cli_proxy_enabled_cached(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     cli_key: &str,
 ) -> CliProxyEnabledSnapshot {
     static CLI_PROXY_ENABLED_CACHE: OnceLock<Mutex<HashMap<String, CliProxyEnabledCacheEntry>>> =
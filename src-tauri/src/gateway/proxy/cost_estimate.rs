@@ -0,0 +1,179 @@
+//! Usage: Per-call cost estimate returned to the CLI (`x-aio-*` headers or a trailing SSE comment).
+
+use axum::body::Bytes;
+use axum::http::{HeaderMap, HeaderValue};
+
+use crate::{blocking, db, request_logs};
+
+const FEMTO_PER_USD: i64 = 1_000_000_000_000_000;
+
+const HEADER_COST_USD: &str = "x-aio-cost-usd";
+const HEADER_INPUT_TOKENS: &str = "x-aio-input-tokens";
+const HEADER_OUTPUT_TOKENS: &str = "x-aio-output-tokens";
+const HEADER_CACHE_READ_TOKENS: &str = "x-aio-cache-read-tokens";
+const HEADER_CACHE_CREATION_TOKENS: &str = "x-aio-cache-creation-tokens";
+
+pub(in crate::gateway) struct CallCostEstimate {
+    cost_usd_femto: Option<i64>,
+    metrics: crate::usage::UsageMetrics,
+}
+
+/// Exact decimal rendering of a femto-USD amount (no float rounding), e.g. `0.0123`.
+fn format_usd(cost_usd_femto: i64) -> String {
+    let value = cost_usd_femto.max(0);
+    let whole = value / FEMTO_PER_USD;
+    let frac = value % FEMTO_PER_USD;
+    if frac == 0 {
+        return whole.to_string();
+    }
+    let frac = format!("{frac:015}");
+    format!("{whole}.{}", frac.trim_end_matches('0'))
+}
+
+fn cache_creation_tokens(metrics: &crate::usage::UsageMetrics) -> Option<i64> {
+    metrics.cache_creation_input_tokens.or_else(|| {
+        match (
+            metrics.cache_creation_5m_input_tokens,
+            metrics.cache_creation_1h_input_tokens,
+        ) {
+            (None, None) => None,
+            (a, b) => Some(a.unwrap_or(0).saturating_add(b.unwrap_or(0))),
+        }
+    })
+}
+
+impl CallCostEstimate {
+    /// Prices the call from the final provider/model; pricing failures only drop the cost field.
+    pub(in crate::gateway) async fn compute(
+        app: &crate::AppHandle,
+        db: &db::Db,
+        cli_key: &str,
+        provider_id: i64,
+        model: Option<&str>,
+        metrics: crate::usage::UsageMetrics,
+    ) -> Self {
+        let cost_usd_femto = match model.map(str::trim).filter(|v| !v.is_empty()) {
+            Some(model) => {
+                let app = app.clone();
+                let db = db.clone();
+                let cli_key = cli_key.to_string();
+                let model = model.to_string();
+                let priced = metrics.clone();
+                blocking::run("gateway_cost_estimate", move || {
+                    request_logs::estimate_cost_usd_femto(
+                        &app,
+                        &db,
+                        &cli_key,
+                        provider_id,
+                        &model,
+                        &priced,
+                    )
+                })
+                .await
+                .unwrap_or_else(|err| {
+                    tracing::debug!("gateway cost estimate failed: {}", err);
+                    None
+                })
+            }
+            None => None,
+        };
+
+        Self {
+            cost_usd_femto,
+            metrics,
+        }
+    }
+
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        let mut out = Vec::new();
+        if let Some(cost) = self.cost_usd_femto {
+            out.push((HEADER_COST_USD, format_usd(cost)));
+        }
+        for (name, value) in [
+            (HEADER_INPUT_TOKENS, self.metrics.input_tokens),
+            (HEADER_OUTPUT_TOKENS, self.metrics.output_tokens),
+            (
+                HEADER_CACHE_READ_TOKENS,
+                self.metrics.cache_read_input_tokens,
+            ),
+            (
+                HEADER_CACHE_CREATION_TOKENS,
+                cache_creation_tokens(&self.metrics),
+            ),
+        ] {
+            if let Some(value) = value {
+                out.push((name, value.to_string()));
+            }
+        }
+        out
+    }
+
+    pub(in crate::gateway) fn insert_headers(&self, headers: &mut HeaderMap) {
+        for (name, value) in self.fields() {
+            if let Ok(value) = HeaderValue::from_str(&value) {
+                headers.insert(name, value);
+            }
+        }
+    }
+
+    /// SSE comment line (ignored by SSE parsers), e.g. `: x-aio-cost-usd=0.01 x-aio-input-tokens=10`.
+    pub(in crate::gateway) fn sse_comment(&self) -> Option<Bytes> {
+        let fields = self.fields();
+        if fields.is_empty() {
+            return None;
+        }
+        let body = fields
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        Some(Bytes::from(format!(": {body}\n\n")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_usd_is_exact() {
+        assert_eq!(format_usd(0), "0");
+        assert_eq!(format_usd(FEMTO_PER_USD * 2), "2");
+        assert_eq!(format_usd(12_300_000_000_000), "0.0123");
+        assert_eq!(format_usd(1), "0.000000000000001");
+        assert_eq!(format_usd(-5), "0");
+    }
+
+    #[test]
+    fn sse_comment_lists_known_fields_only() {
+        let estimate = CallCostEstimate {
+            cost_usd_femto: Some(12_300_000_000_000),
+            metrics: crate::usage::UsageMetrics {
+                input_tokens: Some(10),
+                output_tokens: Some(5),
+                cache_creation_5m_input_tokens: Some(3),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            estimate.sse_comment().unwrap(),
+            Bytes::from_static(
+                b": x-aio-cost-usd=0.0123 x-aio-input-tokens=10 x-aio-output-tokens=5 x-aio-cache-creation-tokens=3\n\n"
+            )
+        );
+
+        let mut headers = HeaderMap::new();
+        estimate.insert_headers(&mut headers);
+        assert_eq!(headers.get(HEADER_COST_USD).unwrap(), "0.0123");
+        assert!(headers.get(HEADER_CACHE_READ_TOKENS).is_none());
+    }
+
+    #[test]
+    fn sse_comment_is_skipped_without_usage() {
+        let estimate = CallCostEstimate {
+            cost_usd_femto: None,
+            metrics: crate::usage::UsageMetrics::default(),
+        };
+        assert!(estimate.sse_comment().is_none());
+    }
+}
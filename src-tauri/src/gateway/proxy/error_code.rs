@@ -23,8 +23,16 @@ pub(in crate::gateway) enum GatewayErrorCode {
     ResponseBuildError,
     ProviderRateLimited,
     ProviderCircuitOpen,
+    ProviderMaintenance,
+    ProviderModelUnsupported,
     CliProxyDisabled,
     CliProxyGuardError,
+    RoutingPaused,
+    SessionThrottled,
+    SessionFailureBudgetExceeded,
+    ModelDenied,
+    ContentRefusal,
+    Unauthorized,
     HttpClientInit,
     AttemptLogChannelClosed,
     AttemptLogEnqueueTimeout,
@@ -60,8 +68,16 @@ impl GatewayErrorCode {
             Self::ResponseBuildError => "GW_RESPONSE_BUILD_ERROR",
             Self::ProviderRateLimited => "GW_PROVIDER_RATE_LIMITED",
             Self::ProviderCircuitOpen => "GW_PROVIDER_CIRCUIT_OPEN",
+            Self::ProviderMaintenance => "GW_PROVIDER_MAINTENANCE",
+            Self::ProviderModelUnsupported => "GW_PROVIDER_MODEL_UNSUPPORTED",
             Self::CliProxyDisabled => "GW_CLI_PROXY_DISABLED",
             Self::CliProxyGuardError => "GW_CLI_PROXY_GUARD_ERROR",
+            Self::RoutingPaused => "GW_ROUTING_PAUSED",
+            Self::SessionThrottled => "GW_SESSION_THROTTLED",
+            Self::SessionFailureBudgetExceeded => "GW_SESSION_FAILURE_BUDGET_EXCEEDED",
+            Self::ModelDenied => "GW_MODEL_DENIED",
+            Self::ContentRefusal => "GW_CONTENT_REFUSAL",
+            Self::Unauthorized => "GW_UNAUTHORIZED",
             Self::HttpClientInit => "GW_HTTP_CLIENT_INIT",
             Self::AttemptLogChannelClosed => "GW_ATTEMPT_LOG_CHANNEL_CLOSED",
             Self::AttemptLogEnqueueTimeout => "GW_ATTEMPT_LOG_ENQUEUE_TIMEOUT",
@@ -99,8 +115,16 @@ impl GatewayErrorCode {
             "GW_RESPONSE_BUILD_ERROR" => Self::ResponseBuildError,
             "GW_PROVIDER_RATE_LIMITED" => Self::ProviderRateLimited,
             "GW_PROVIDER_CIRCUIT_OPEN" => Self::ProviderCircuitOpen,
+            "GW_PROVIDER_MAINTENANCE" => Self::ProviderMaintenance,
+            "GW_PROVIDER_MODEL_UNSUPPORTED" => Self::ProviderModelUnsupported,
             "GW_CLI_PROXY_DISABLED" => Self::CliProxyDisabled,
             "GW_CLI_PROXY_GUARD_ERROR" => Self::CliProxyGuardError,
+            "GW_ROUTING_PAUSED" => Self::RoutingPaused,
+            "GW_SESSION_THROTTLED" => Self::SessionThrottled,
+            "GW_SESSION_FAILURE_BUDGET_EXCEEDED" => Self::SessionFailureBudgetExceeded,
+            "GW_MODEL_DENIED" => Self::ModelDenied,
+            "GW_CONTENT_REFUSAL" => Self::ContentRefusal,
+            "GW_UNAUTHORIZED" => Self::Unauthorized,
             "GW_HTTP_CLIENT_INIT" => Self::HttpClientInit,
             "GW_ATTEMPT_LOG_CHANNEL_CLOSED" => Self::AttemptLogChannelClosed,
             "GW_ATTEMPT_LOG_ENQUEUE_TIMEOUT" => Self::AttemptLogEnqueueTimeout,
@@ -131,6 +155,7 @@ mod tests {
             GatewayErrorCode::UpstreamTimeout,
             GatewayErrorCode::StreamError,
             GatewayErrorCode::ProviderRateLimited,
+            GatewayErrorCode::RoutingPaused,
             GatewayErrorCode::PortInUse,
             GatewayErrorCode::RequestLogDropped,
         ];
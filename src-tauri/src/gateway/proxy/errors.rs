@@ -7,15 +7,20 @@ use axum::{
 };
 use serde::Serialize;
 
+pub(super) mod catalog;
+
 use super::failover::FailoverDecision;
 use super::{ErrorCategory, GatewayErrorCode};
 use crate::gateway::events::FailoverAttempt;
+use crate::shared::i18n;
 
 #[derive(Debug, Serialize)]
 struct GatewayErrorResponse {
     trace_id: String,
     error_code: &'static str,
     message: String,
+    // Human-readable text for `error_code` in the configured `ui_language`.
+    localized_message: &'static str,
     attempts: Vec<FailoverAttempt>,
     #[serde(skip_serializing_if = "Option::is_none")]
     retry_after_seconds: Option<u64>,
@@ -112,6 +117,7 @@ pub(super) fn error_response_with_retry_after(
         trace_id: trace_id.clone(),
         error_code,
         message,
+        localized_message: i18n::text(error_code),
         attempts,
         retry_after_seconds,
     };
@@ -0,0 +1,220 @@
+//! Usage: Typed catalog of common upstream provider errors with user-facing remediation hints.
+//!
+//! Classification only annotates attempts/request logs; it never changes failover decisions.
+
+use serde::Serialize;
+
+/// Limit how much of the upstream error body we scan (defensive against huge error payloads).
+const MAX_SCAN_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ProviderErrorKind {
+    QuotaExceeded,
+    InvalidApiKey,
+    ModelNotFound,
+    RegionBlocked,
+    RateLimited,
+}
+
+impl ProviderErrorKind {
+    pub(crate) const ALL: [Self; 5] = [
+        Self::QuotaExceeded,
+        Self::InvalidApiKey,
+        Self::ModelNotFound,
+        Self::RegionBlocked,
+        Self::RateLimited,
+    ];
+
+    pub(crate) const fn as_str(self) -> &'static str {
+        match self {
+            Self::QuotaExceeded => "PROVIDER_QUOTA_EXCEEDED",
+            Self::InvalidApiKey => "PROVIDER_INVALID_API_KEY",
+            Self::ModelNotFound => "PROVIDER_MODEL_NOT_FOUND",
+            Self::RegionBlocked => "PROVIDER_REGION_BLOCKED",
+            Self::RateLimited => "PROVIDER_RATE_LIMITED",
+        }
+    }
+
+    const fn title(self) -> &'static str {
+        match self {
+            Self::QuotaExceeded => "额度或余额不足",
+            Self::InvalidApiKey => "API Key 无效",
+            Self::ModelNotFound => "模型不存在",
+            Self::RegionBlocked => "地区不受支持",
+            Self::RateLimited => "请求频率超限",
+        }
+    }
+
+    const fn hint(self) -> &'static str {
+        match self {
+            Self::QuotaExceeded => {
+                "供应商账户额度已用尽或余额不足：请充值/升级套餐，或暂时禁用该供应商以切换到其他供应商。"
+            }
+            Self::InvalidApiKey => {
+                "供应商拒绝了当前 API Key：请检查 Key 是否填写完整、是否已过期或被撤销，并确认 Base URL 与 Key 属于同一平台。"
+            }
+            Self::ModelNotFound => {
+                "供应商不支持请求的模型：请检查模型名称是否正确，或在供应商设置中配置模型映射。"
+            }
+            Self::RegionBlocked => {
+                "供应商不向当前地区提供服务：请更换网络出口/代理，或改用支持当前地区的供应商。"
+            }
+            Self::RateLimited => {
+                "供应商限制了请求频率：请稍后重试、降低并发，或添加更多供应商分担流量。"
+            }
+        }
+    }
+
+    /// Statuses this kind is recognized under; an empty list accepts any 4xx/5xx.
+    const fn statuses(self) -> &'static [u16] {
+        match self {
+            Self::QuotaExceeded => &[400, 402, 403, 429],
+            Self::InvalidApiKey => &[401, 403],
+            Self::ModelNotFound => &[400, 404],
+            Self::RegionBlocked => &[400, 403, 451],
+            Self::RateLimited => &[429],
+        }
+    }
+
+    const fn needles(self) -> &'static [&'static str] {
+        match self {
+            Self::QuotaExceeded => &[
+                "insufficient_quota",
+                "exceeded your current quota",
+                "quota exceeded",
+                "insufficient balance",
+                "insufficient_balance",
+                "credit balance is too low",
+                "余额不足",
+                "额度不足",
+            ],
+            Self::InvalidApiKey => &[
+                "invalid_api_key",
+                "invalid api key",
+                "invalid x-api-key",
+                "incorrect api key",
+                "api key not valid",
+                "authentication_error",
+                "无效的令牌",
+                "令牌已过期",
+            ],
+            Self::ModelNotFound => &[
+                "model_not_found",
+                "model not found",
+                "no such model",
+                "unknown model",
+                "模型不存在",
+            ],
+            Self::RegionBlocked => &[
+                "unsupported_country_region_territory",
+                "country, region, or territory not supported",
+                "not available in your country",
+                "not available in your region",
+                "user location is not supported",
+            ],
+            Self::RateLimited => &["rate_limit", "rate limit", "too many requests"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub(crate) struct ErrorCatalogEntry {
+    pub(crate) code: &'static str,
+    pub(crate) title: &'static str,
+    pub(crate) hint: &'static str,
+}
+
+pub(crate) fn error_catalog_entries() -> Vec<ErrorCatalogEntry> {
+    ProviderErrorKind::ALL
+        .iter()
+        .map(|kind| ErrorCatalogEntry {
+            code: kind.as_str(),
+            title: kind.title(),
+            hint: kind.hint(),
+        })
+        .collect()
+}
+
+/// Classify an upstream error response body. Order matters: the more specific kinds (region,
+/// quota) are checked before the generic ones they often overlap with (auth, rate limit).
+pub(crate) fn classify(status: u16, body: &[u8]) -> Option<ProviderErrorKind> {
+    if status < 400 || body.is_empty() {
+        return None;
+    }
+
+    let scan = &body[..body.len().min(MAX_SCAN_BYTES)];
+    let haystack_lower = String::from_utf8_lossy(scan).to_lowercase();
+
+    [
+        ProviderErrorKind::RegionBlocked,
+        ProviderErrorKind::QuotaExceeded,
+        ProviderErrorKind::InvalidApiKey,
+        ProviderErrorKind::ModelNotFound,
+        ProviderErrorKind::RateLimited,
+    ]
+    .into_iter()
+    .find(|kind| {
+        kind.statuses().contains(&status)
+            && kind
+                .needles()
+                .iter()
+                .any(|needle| haystack_lower.contains(needle))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify, error_catalog_entries, ProviderErrorKind};
+
+    #[test]
+    fn classify_matches_common_provider_bodies() {
+        assert_eq!(
+            classify(
+                429,
+                br#"{"error":{"type":"insufficient_quota","message":"You exceeded your current quota"}}"#
+            ),
+            Some(ProviderErrorKind::QuotaExceeded)
+        );
+        assert_eq!(
+            classify(
+                401,
+                br#"{"type":"error","error":{"type":"authentication_error","message":"invalid x-api-key"}}"#
+            ),
+            Some(ProviderErrorKind::InvalidApiKey)
+        );
+        assert_eq!(
+            classify(
+                404,
+                br#"{"error":{"code":"model_not_found","message":"The model `gpt-9` does not exist"}}"#
+            ),
+            Some(ProviderErrorKind::ModelNotFound)
+        );
+        assert_eq!(
+            classify(
+                403,
+                br#"{"error":{"code":"unsupported_country_region_territory"}}"#
+            ),
+            Some(ProviderErrorKind::RegionBlocked)
+        );
+        assert_eq!(
+            classify(429, br#"{"error":{"type":"rate_limit_error"}}"#),
+            Some(ProviderErrorKind::RateLimited)
+        );
+    }
+
+    #[test]
+    fn classify_requires_matching_status() {
+        assert_eq!(classify(200, br#"{"error":"invalid api key"}"#), None);
+        assert_eq!(classify(500, br#"{"error":"invalid api key"}"#), None);
+        assert_eq!(classify(401, b""), None);
+    }
+
+    #[test]
+    fn catalog_lists_every_kind_once() {
+        let entries = error_catalog_entries();
+        assert_eq!(entries.len(), ProviderErrorKind::ALL.len());
+        assert!(entries
+            .iter()
+            .all(|e| !e.title.is_empty() && !e.hint.is_empty()));
+    }
+}
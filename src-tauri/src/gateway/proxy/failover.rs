@@ -1,6 +1,7 @@
 //! Usage: Failover policy helpers (retry/switch decisions, provider selection, base_url picking).
 
 use crate::providers;
+use crate::settings::{RetryBackoffPolicy, RetryBackoffSettings, RetryBackoffStrategy};
 use crate::shared::mutex_ext::MutexExt;
 use std::collections::HashSet;
 use std::time::Duration;
@@ -25,19 +26,59 @@ impl FailoverDecision {
     }
 }
 
+/// Error class selecting the `retry_backoff` policy for a same-provider retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum RetryBackoffCategory {
+    RateLimited,
+    UpstreamError,
+    NetworkError,
+}
+
+impl RetryBackoffCategory {
+    /// Other 4xx retries only follow a rewritten request (rectifiers), so they never wait.
+    pub(super) fn from_status(status: reqwest::StatusCode) -> Option<Self> {
+        match status.as_u16() {
+            408 | 429 => Some(Self::RateLimited),
+            500..=599 => Some(Self::UpstreamError),
+            _ => None,
+        }
+    }
+
+    fn policy(self, settings: &RetryBackoffSettings) -> RetryBackoffPolicy {
+        match self {
+            Self::RateLimited => settings.rate_limited,
+            Self::UpstreamError => settings.upstream_error,
+            Self::NetworkError => settings.network_error,
+        }
+    }
+}
+
+fn backoff_delay_ms(policy: RetryBackoffPolicy, retry_index: u32, jitter_roll: u32) -> u64 {
+    let base_ms = policy.base_ms as u64;
+    let max_ms = (policy.max_ms as u64).max(base_ms);
+    match policy.strategy {
+        RetryBackoffStrategy::None => 0,
+        RetryBackoffStrategy::Fixed => base_ms,
+        RetryBackoffStrategy::Linear => base_ms
+            .saturating_mul(retry_index.max(1) as u64)
+            .min(max_ms),
+        RetryBackoffStrategy::ExponentialJitter => {
+            let exp = retry_index.max(1).saturating_sub(1).min(20);
+            let ceiling = base_ms.saturating_mul(1u64 << exp).min(max_ms);
+            let half = ceiling / 2;
+            ceiling - half + (jitter_roll as u64) % (half + 1)
+        }
+    }
+}
+
 pub(super) fn retry_backoff_delay(
-    status: reqwest::StatusCode,
+    settings: &RetryBackoffSettings,
+    category: Option<RetryBackoffCategory>,
     retry_index: u32,
 ) -> Option<Duration> {
-    if !matches!(status.as_u16(), 408 | 429) {
-        return None;
-    }
-
-    let retry_index = retry_index.max(1);
-    let base_ms = 80u64;
-    let max_ms = 800u64;
-    let ms = base_ms.saturating_mul(retry_index as u64).min(max_ms);
-    Some(Duration::from_millis(ms))
+    let policy = category?.policy(settings);
+    let ms = backoff_delay_ms(policy, retry_index, rand::random::<u32>());
+    (ms > 0).then_some(Duration::from_millis(ms))
 }
 
 pub(super) fn should_reuse_provider(body_json: Option<&serde_json::Value>) -> bool {
@@ -228,5 +269,25 @@ pub(super) async fn select_provider_base_url_for_request(
     Ok(best_base_url)
 }
 
+/// Base_url raced against `primary` for providers in `race` mode: the other one of the first
+/// two usable base_urls. OAuth providers always talk to their adapter's fixed endpoint.
+pub(super) fn race_partner_base_url(
+    provider: &providers::ProviderForGateway,
+    primary: &str,
+) -> Option<String> {
+    if provider.auth_mode == "oauth"
+        || !matches!(provider.base_url_mode, providers::ProviderBaseUrlMode::Race)
+    {
+        return None;
+    }
+    provider
+        .base_urls
+        .iter()
+        .filter(|u| !u.trim().is_empty())
+        .take(2)
+        .find(|u| u.as_str() != primary)
+        .cloned()
+}
+
 #[cfg(test)]
 mod tests;
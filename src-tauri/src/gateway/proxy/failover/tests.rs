@@ -1,10 +1,13 @@
 use super::{
-    resolve_primary_provider_base_url, retry_backoff_delay, select_next_provider_id_from_order,
-    should_reuse_provider,
+    backoff_delay_ms, race_partner_base_url, resolve_primary_provider_base_url,
+    retry_backoff_delay, select_next_provider_id_from_order, should_reuse_provider,
+    RetryBackoffCategory,
 };
 use crate::providers;
+use crate::settings::{RetryBackoffPolicy, RetryBackoffSettings, RetryBackoffStrategy};
 use serde_json::json;
 use std::collections::HashSet;
+use std::time::Duration;
 
 fn set(ids: &[i64]) -> HashSet<i64> {
     ids.iter().copied().collect()
@@ -29,11 +32,63 @@ fn provider_for_base_url_test(
         limit_weekly_usd: None,
         limit_monthly_usd: None,
         limit_total_usd: None,
+        monthly_budget_usd: None,
         auth_mode: auth_mode.to_string(),
         oauth_provider_type: oauth_provider_type.map(str::to_string),
+        provider_kind: providers::ProviderKind::Standard,
+        model_catalog: Vec::new(),
+        model_catalog_refreshed_at: None,
+        emulate_models_endpoint: false,
+        thinking_budget_cap: None,
+        refusal_patterns: Vec::new(),
+        anthropic_version: None,
+        anthropic_betas: Vec::new(),
+        anthropic_beta_strip: false,
+        tags: Vec::new(),
     }
 }
 
+#[test]
+fn race_partner_is_the_other_of_the_first_two_base_urls() {
+    let mut provider = provider_for_base_url_test(
+        "api_key",
+        vec![
+            "",
+            "https://a.example.com",
+            "https://b.example.com",
+            "https://c.example.com",
+        ],
+        None,
+    );
+    assert_eq!(
+        race_partner_base_url(&provider, "https://a.example.com"),
+        None
+    );
+
+    provider.base_url_mode = providers::ProviderBaseUrlMode::Race;
+    assert_eq!(
+        race_partner_base_url(&provider, "https://a.example.com").as_deref(),
+        Some("https://b.example.com")
+    );
+
+    provider.base_urls.truncate(2);
+    assert_eq!(
+        race_partner_base_url(&provider, "https://a.example.com"),
+        None
+    );
+
+    let mut oauth = provider_for_base_url_test(
+        "oauth",
+        vec!["https://a.example.com", "https://b.example.com"],
+        Some("codex_oauth"),
+    );
+    oauth.base_url_mode = providers::ProviderBaseUrlMode::Race;
+    assert_eq!(
+        race_partner_base_url(&oauth, "https://chatgpt.com/backend-api/codex"),
+        None
+    );
+}
+
 #[test]
 fn oauth_primary_base_url_uses_adapter_default_even_with_legacy_base_urls() {
     let provider = provider_for_base_url_test(
@@ -109,59 +164,122 @@ fn select_next_provider_id_handles_empty_order() {
     assert_eq!(select_next_provider_id_from_order(1, &[], &current), None);
 }
 
+fn exponential_jitter_policy() -> RetryBackoffPolicy {
+    RetryBackoffPolicy {
+        strategy: RetryBackoffStrategy::ExponentialJitter,
+        base_ms: 80,
+        max_ms: 800,
+    }
+}
+
 #[test]
 fn retry_backoff_delay_returns_none_for_non_retryable_status() {
-    assert!(retry_backoff_delay(reqwest::StatusCode::BAD_REQUEST, 1).is_none());
-    assert!(retry_backoff_delay(reqwest::StatusCode::UNAUTHORIZED, 1).is_none());
-    assert!(retry_backoff_delay(reqwest::StatusCode::INTERNAL_SERVER_ERROR, 1).is_none());
+    let settings = RetryBackoffSettings::default();
+    for status in [
+        reqwest::StatusCode::BAD_REQUEST,
+        reqwest::StatusCode::UNAUTHORIZED,
+        reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+    ] {
+        let category = RetryBackoffCategory::from_status(status);
+        assert!(retry_backoff_delay(&settings, category, 1).is_none());
+    }
+    assert_eq!(
+        RetryBackoffCategory::from_status(reqwest::StatusCode::BAD_GATEWAY),
+        Some(RetryBackoffCategory::UpstreamError)
+    );
 }
 
 #[test]
 fn retry_backoff_delay_returns_delay_for_408_429() {
-    // 408 Request Timeout
-    let delay = retry_backoff_delay(reqwest::StatusCode::REQUEST_TIMEOUT, 1);
-    assert!(delay.is_some());
-    assert!(delay.unwrap().as_millis() >= 80);
-
-    // 429 Too Many Requests
-    let delay = retry_backoff_delay(reqwest::StatusCode::TOO_MANY_REQUESTS, 1);
-    assert!(delay.is_some());
-    assert!(delay.unwrap().as_millis() >= 80);
+    let settings = RetryBackoffSettings::default();
+    for status in [
+        reqwest::StatusCode::REQUEST_TIMEOUT,
+        reqwest::StatusCode::TOO_MANY_REQUESTS,
+    ] {
+        let category = RetryBackoffCategory::from_status(status);
+        let delay = retry_backoff_delay(&settings, category, 1);
+        assert_eq!(delay, Some(Duration::from_millis(80)));
+    }
 }
 
 #[test]
-fn retry_backoff_delay_increases_with_retry_index() {
-    let delay1 = retry_backoff_delay(reqwest::StatusCode::TOO_MANY_REQUESTS, 1)
-        .unwrap()
-        .as_millis();
-    let delay2 = retry_backoff_delay(reqwest::StatusCode::TOO_MANY_REQUESTS, 2)
-        .unwrap()
-        .as_millis();
-    let delay3 = retry_backoff_delay(reqwest::StatusCode::TOO_MANY_REQUESTS, 3)
-        .unwrap()
-        .as_millis();
-
-    assert!(delay2 > delay1);
-    assert!(delay3 > delay2);
+fn retry_backoff_delay_defaults_to_linear_80ms_steps_without_jitter() {
+    let settings = RetryBackoffSettings::default();
+    let category = Some(RetryBackoffCategory::RateLimited);
+    let delays: Vec<u128> = (1..=11)
+        .map(|retry_index| {
+            retry_backoff_delay(&settings, category, retry_index)
+                .unwrap()
+                .as_millis()
+        })
+        .collect();
+    assert_eq!(
+        delays,
+        vec![80, 160, 240, 320, 400, 480, 560, 640, 720, 800, 800]
+    );
 }
 
 #[test]
 fn retry_backoff_delay_caps_at_max() {
     // Very high retry index should cap at 800ms
-    let delay = retry_backoff_delay(reqwest::StatusCode::TOO_MANY_REQUESTS, 100)
-        .unwrap()
-        .as_millis();
+    let delay = retry_backoff_delay(
+        &RetryBackoffSettings::default(),
+        Some(RetryBackoffCategory::RateLimited),
+        100,
+    )
+    .unwrap()
+    .as_millis();
     assert_eq!(delay, 800);
 }
 
 #[test]
 fn retry_backoff_delay_treats_zero_retry_index_as_first_retry() {
-    let delay = retry_backoff_delay(reqwest::StatusCode::TOO_MANY_REQUESTS, 0)
-        .unwrap()
-        .as_millis();
+    let delay = retry_backoff_delay(
+        &RetryBackoffSettings::default(),
+        Some(RetryBackoffCategory::RateLimited),
+        0,
+    )
+    .unwrap()
+    .as_millis();
     assert_eq!(delay, 80);
 }
 
+#[test]
+fn exponential_jitter_grows_caps_and_randomizes_the_upper_half() {
+    let policy = exponential_jitter_policy();
+    assert_eq!(backoff_delay_ms(policy, 1, 0), 40);
+    assert_eq!(backoff_delay_ms(policy, 1, 40), 80);
+    assert_eq!(backoff_delay_ms(policy, 2, 0), 80);
+    assert_eq!(backoff_delay_ms(policy, 3, 0), 160);
+    assert_eq!(
+        backoff_delay_ms(policy, 0, 40),
+        backoff_delay_ms(policy, 1, 40)
+    );
+    assert_eq!(backoff_delay_ms(policy, 100, 400), 800);
+    assert_eq!(backoff_delay_ms(policy, 100, 0), 400);
+}
+
+#[test]
+fn retry_backoff_fixed_and_none_strategies() {
+    let fixed = RetryBackoffPolicy {
+        strategy: RetryBackoffStrategy::Fixed,
+        base_ms: 250,
+        max_ms: 1000,
+    };
+    assert_eq!(backoff_delay_ms(fixed, 1, 7), 250);
+    assert_eq!(backoff_delay_ms(fixed, 9, 7), 250);
+
+    let settings = RetryBackoffSettings {
+        network_error: fixed,
+        ..RetryBackoffSettings::default()
+    };
+    assert_eq!(
+        retry_backoff_delay(&settings, Some(RetryBackoffCategory::NetworkError), 3),
+        Some(Duration::from_millis(250))
+    );
+    assert!(retry_backoff_delay(&settings, Some(RetryBackoffCategory::UpstreamError), 3).is_none());
+}
+
 #[test]
 fn should_reuse_provider_returns_false_for_none() {
     assert!(!should_reuse_provider(None));
@@ -0,0 +1,70 @@
+//! Usage: Forward requests to a remote aio-coding-hub gateway (`provider_kind = federated`).
+//!
+//! A federated provider's base URL is the remote hub's gateway and its API key is the remote
+//! hub's gateway access token; the remote hub picks one of its own providers and injects their
+//! credentials. Forwarded requests are tagged so the remote hub never federates them again, which
+//! keeps two hubs that point at each other from looping.
+
+use super::access_guard::GATEWAY_KEY_HEADER;
+use crate::providers::{ProviderForGateway, ProviderKind};
+use axum::http::{HeaderMap, HeaderValue};
+
+/// Set on requests one hub forwards to another.
+pub(super) const FEDERATION_HOP_HEADER: &str = "x-aio-federation-hop";
+
+pub(super) fn is_federated(provider: &ProviderForGateway) -> bool {
+    provider.provider_kind == ProviderKind::Federated && provider.auth_mode != "oauth"
+}
+
+/// Whether the request came from another hub's federated provider.
+pub(super) fn arrived_via_federation(headers: &HeaderMap) -> bool {
+    headers.contains_key(FEDERATION_HOP_HEADER)
+}
+
+/// The remote gateway routes by cli prefix (`/claude/v1/messages`).
+pub(super) fn forwarded_path(cli_key: &str, forwarded_path: &str) -> String {
+    format!("/{cli_key}{forwarded_path}")
+}
+
+/// Authenticate against the remote gateway and mark the hop; provider auth headers must already
+/// be cleared.
+pub(super) fn inject_headers(headers: &mut HeaderMap, access_token: &str) {
+    if let Ok(value) = HeaderValue::from_str(access_token.trim()) {
+        headers.insert(GATEWAY_KEY_HEADER, value);
+    }
+    headers.insert(FEDERATION_HOP_HEADER, HeaderValue::from_static("1"));
+}
+
+/// Drop federated providers (a federated request is served by the remote hub's own providers
+/// only); returns the removed ids.
+pub(super) fn drop_federated(providers: &mut Vec<ProviderForGateway>) -> Vec<i64> {
+    let mut removed = Vec::new();
+    providers.retain(|p| {
+        let keep = !is_federated(p);
+        if !keep {
+            removed.push(p.id);
+        }
+        keep
+    });
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inject_headers_sets_access_token_and_hop_marker() {
+        let mut headers = HeaderMap::new();
+        assert!(!arrived_via_federation(&headers));
+
+        inject_headers(&mut headers, " remote-token ");
+        assert_eq!(headers.get(GATEWAY_KEY_HEADER).unwrap(), "remote-token");
+        assert!(arrived_via_federation(&headers));
+
+        assert_eq!(
+            forwarded_path("claude", "/v1/messages"),
+            "/claude/v1/messages"
+        );
+    }
+}
@@ -0,0 +1,321 @@
+//! Usage: Hedged forwarding between the top two providers, per endpoint class.
+//!
+//! - `CountTokens`: latency-sensitive claude count_tokens requests.
+//! - `NonStreaming`: other non-streaming requests.
+//! - `Streaming`: streaming requests whose first provider is slow to start streaming.
+//!
+//! The primary attempt starts immediately; if it has not succeeded after `delay`, the second
+//! provider is raced against it and the first success wins. For streaming requests an attempt
+//! "succeeds" once its response is handed back, i.e. after the first-byte probe when
+//! `upstream_first_byte_timeout` is set. The loser future is dropped (cancelled).
+
+use super::failover_loop;
+use crate::gateway::proxy::request_context::{HedgeEndpointClass, RequestContext};
+use crate::shared::mutex_ext::MutexExt;
+use axum::response::Response;
+use std::future::Future;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Trigger {
+    PrimaryFailed,
+    DelayElapsed,
+}
+
+impl Trigger {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::PrimaryFailed => "primary_failed",
+            Self::DelayElapsed => "delay_elapsed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Winner {
+    Primary,
+    Hedge,
+}
+
+impl Winner {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Primary => "primary",
+            Self::Hedge => "hedge",
+        }
+    }
+}
+
+pub(super) async fn run(
+    mut primary: RequestContext,
+    delay: Duration,
+    class: HedgeEndpointClass,
+) -> Response {
+    let Some(hedge) = primary.fork_hedge() else {
+        return failover_loop::run(primary).await;
+    };
+
+    // Inner guards are disarmed so dropping the losing attempt does not log an abort;
+    // this outer guard still covers the client going away mid-race.
+    let mut abort_guard = primary.new_abort_guard();
+    primary.abort_guard.disarm();
+
+    let special_settings = primary.special_settings.clone();
+    let primary_provider_id = primary.providers.first().map(|p| p.id);
+    let hedge_provider_id = hedge.providers.first().map(|p| p.id);
+
+    let mut setting_index = None;
+    let (resp, winner) = race(failover_loop::run(primary), delay, |trigger| {
+        let mut settings = special_settings.lock_or_recover();
+        settings.push(serde_json::json!({
+            "type": class.special_setting_type(),
+            "scope": "request",
+            "hit": true,
+            "delayMs": delay.as_millis() as u64,
+            "trigger": trigger.as_str(),
+            "primaryProviderId": primary_provider_id,
+            "hedgeProviderId": hedge_provider_id,
+        }));
+        setting_index = Some(settings.len() - 1);
+        failover_loop::run(hedge)
+    })
+    .await;
+
+    if let (Some(index), Some(winner)) = (setting_index, winner) {
+        if let Some(entry) = special_settings
+            .lock_or_recover()
+            .get_mut(index)
+            .and_then(|v| v.as_object_mut())
+        {
+            entry.insert("winner".to_string(), winner.as_str().into());
+        }
+    }
+
+    abort_guard.disarm();
+    resp
+}
+
+/// Runs `primary`, starting the hedge via `start_hedge` once `delay` elapses or the primary
+/// fails, and returns the first success. The losing future is dropped before returning.
+/// `winner` is `None` when the hedge never started.
+async fn race<P, H, F>(primary: P, delay: Duration, start_hedge: F) -> (Response, Option<Winner>)
+where
+    P: Future<Output = Response>,
+    H: Future<Output = Response>,
+    F: FnOnce(Trigger) -> H,
+{
+    tokio::pin!(primary);
+
+    let primary_early = tokio::select! {
+        resp = &mut primary => Some(resp),
+        _ = tokio::time::sleep(delay) => None,
+    };
+
+    let primary_failed = match primary_early {
+        Some(resp) if resp.status().is_success() => return (resp, None),
+        other => other,
+    };
+
+    let trigger = if primary_failed.is_some() {
+        Trigger::PrimaryFailed
+    } else {
+        Trigger::DelayElapsed
+    };
+    let hedge = start_hedge(trigger);
+    tokio::pin!(hedge);
+
+    let (resp, winner) = match primary_failed {
+        Some(primary_resp) => pick_response(primary_resp, hedge.await),
+        None => {
+            tokio::select! {
+                resp = &mut primary => {
+                    if resp.status().is_success() {
+                        (resp, Winner::Primary)
+                    } else {
+                        pick_response(resp, hedge.await)
+                    }
+                }
+                resp = &mut hedge => {
+                    if resp.status().is_success() {
+                        (resp, Winner::Hedge)
+                    } else {
+                        pick_response(primary.await, resp)
+                    }
+                }
+            }
+        }
+    };
+    (resp, Some(winner))
+}
+
+/// Prefer a successful hedge; otherwise surface the primary attempt's response.
+fn pick_response(primary: Response, hedge: Response) -> (Response, Winner) {
+    if !primary.status().is_success() && hedge.status().is_success() {
+        (hedge, Winner::Hedge)
+    } else {
+        (primary, Winner::Primary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pick_response, race, HedgeEndpointClass, Trigger, Winner};
+    use axum::http::StatusCode;
+    use axum::response::{IntoResponse, Response};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    fn resp(status: StatusCode) -> Response {
+        status.into_response()
+    }
+
+    /// Sets the flag when the owning future is dropped, i.e. cancelled or finished.
+    struct SetOnDrop(Arc<AtomicBool>);
+
+    impl Drop for SetOnDrop {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    async fn respond_after(
+        wait: Duration,
+        status: StatusCode,
+        dropped: Arc<AtomicBool>,
+    ) -> Response {
+        let _guard = SetOnDrop(dropped);
+        tokio::time::sleep(wait).await;
+        resp(status)
+    }
+
+    #[test]
+    fn pick_response_prefers_successful_hedge_over_failed_primary() {
+        let (picked, winner) = pick_response(resp(StatusCode::BAD_GATEWAY), resp(StatusCode::OK));
+        assert_eq!(picked.status(), StatusCode::OK);
+        assert_eq!(winner, Winner::Hedge);
+    }
+
+    #[test]
+    fn pick_response_keeps_primary_when_both_fail() {
+        let (picked, winner) = pick_response(
+            resp(StatusCode::BAD_GATEWAY),
+            resp(StatusCode::SERVICE_UNAVAILABLE),
+        );
+        assert_eq!(picked.status(), StatusCode::BAD_GATEWAY);
+        assert_eq!(winner, Winner::Primary);
+    }
+
+    #[test]
+    fn pick_response_keeps_successful_primary() {
+        let (picked, winner) = pick_response(resp(StatusCode::OK), resp(StatusCode::OK));
+        assert_eq!(picked.status(), StatusCode::OK);
+        assert_eq!(winner, Winner::Primary);
+    }
+
+    #[test]
+    fn endpoint_classes_record_distinct_special_setting_types() {
+        assert_eq!(
+            HedgeEndpointClass::CountTokens.special_setting_type(),
+            "count_tokens_hedge"
+        );
+        assert_eq!(
+            HedgeEndpointClass::NonStreaming.special_setting_type(),
+            "non_streaming_hedge"
+        );
+        assert_eq!(
+            HedgeEndpointClass::Streaming.special_setting_type(),
+            "first_byte_race"
+        );
+    }
+
+    #[tokio::test]
+    async fn hedge_fires_after_delay_and_cancels_slow_primary() {
+        let delay = Duration::from_millis(50);
+        let primary_dropped = Arc::new(AtomicBool::new(false));
+        let hedge_started = Arc::new(Mutex::new(None));
+        let started = Instant::now();
+
+        let primary = respond_after(
+            Duration::from_secs(30),
+            StatusCode::OK,
+            primary_dropped.clone(),
+        );
+        let (picked, winner) = race(primary, delay, |trigger| {
+            *hedge_started.lock().unwrap() = Some((trigger, started.elapsed()));
+            async { resp(StatusCode::CREATED) }
+        })
+        .await;
+
+        assert_eq!(picked.status(), StatusCode::CREATED);
+        assert_eq!(winner, Some(Winner::Hedge));
+        let recorded = *hedge_started.lock().unwrap();
+        let (trigger, fired_after) = recorded.expect("hedge started");
+        assert_eq!(trigger, Trigger::DelayElapsed);
+        assert!(fired_after >= delay, "hedge fired after {fired_after:?}");
+        assert!(started.elapsed() < Duration::from_secs(30));
+        assert!(primary_dropped.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn primary_win_after_delay_cancels_hedge() {
+        let hedge_dropped = Arc::new(AtomicBool::new(false));
+
+        let primary = async {
+            tokio::time::sleep(Duration::from_millis(80)).await;
+            resp(StatusCode::OK)
+        };
+        let (picked, winner) = race(primary, Duration::from_millis(20), |_| {
+            respond_after(
+                Duration::from_secs(30),
+                StatusCode::CREATED,
+                hedge_dropped.clone(),
+            )
+        })
+        .await;
+
+        assert_eq!(picked.status(), StatusCode::OK);
+        assert_eq!(winner, Some(Winner::Primary));
+        assert!(hedge_dropped.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn fast_primary_success_never_starts_hedge() {
+        let hedge_started = AtomicBool::new(false);
+
+        let (picked, winner) = race(
+            async { resp(StatusCode::OK) },
+            Duration::from_millis(50),
+            |_| {
+                hedge_started.store(true, Ordering::SeqCst);
+                async { resp(StatusCode::CREATED) }
+            },
+        )
+        .await;
+
+        assert_eq!(picked.status(), StatusCode::OK);
+        assert_eq!(winner, None);
+        assert!(!hedge_started.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn failed_primary_starts_hedge_before_delay() {
+        let started = Instant::now();
+        let mut trigger_seen = None;
+
+        let (picked, winner) = race(
+            async { resp(StatusCode::BAD_GATEWAY) },
+            Duration::from_secs(30),
+            |trigger| {
+                trigger_seen = Some(trigger);
+                async { resp(StatusCode::OK) }
+            },
+        )
+        .await;
+
+        assert_eq!(picked.status(), StatusCode::OK);
+        assert_eq!(winner, Some(Winner::Hedge));
+        assert_eq!(trigger_seen, Some(Trigger::PrimaryFailed));
+        assert!(started.elapsed() < Duration::from_secs(30));
+    }
+}
@@ -5,7 +5,11 @@ use axum::response::Response;
 
 #[path = "../handler/failover_loop/mod.rs"]
 mod failover_loop;
+mod hedging;
 
 pub(super) async fn forward(ctx: RequestContext) -> Response {
-    failover_loop::run(ctx).await
+    match ctx.hedge {
+        Some((delay, class)) => hedging::run(ctx, delay, class).await,
+        None => failover_loop::run(ctx).await,
+    }
 }
@@ -0,0 +1,149 @@
+//! Usage: Answer claude `count_tokens` locally when no provider is available (opt-in setting).
+//!
+//! The CLI blocks its context display on this endpoint; an approximate count keeps it responsive
+//! while every provider is down. The response carries `x-aio-token-estimate` so it is never
+//! mistaken for an upstream count.
+
+use super::super::request_end::{
+    emit_request_event_and_spawn_request_log, RequestEndArgs, RequestEndDeps,
+};
+use crate::gateway::events::{decision_chain as dc, emit_request_start_event, FailoverAttempt};
+use crate::gateway::manager::GatewayAppState;
+use crate::gateway::token_estimate;
+use crate::shared::mutex_ext::MutexExt;
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::sync::Mutex;
+
+const ESTIMATE_BASE_URL: &str = "/__aio__/count_tokens_estimate";
+
+pub(super) struct CountTokensEstimateCtx<'a> {
+    pub(super) state: &'a GatewayAppState,
+    pub(super) trace_id: &'a str,
+    pub(super) method_hint: &'a str,
+    pub(super) forwarded_path: &'a str,
+    pub(super) query: Option<&'a str>,
+    pub(super) requested_model: Option<String>,
+    pub(super) special_settings: &'a Mutex<Vec<serde_json::Value>>,
+    pub(super) created_at_ms: i64,
+    pub(super) created_at: i64,
+    pub(super) duration_ms: u128,
+}
+
+/// Estimate from the parsed body; an unparsable body is counted as plain text.
+pub(super) fn estimate_input_tokens(
+    introspection_json: Option<&serde_json::Value>,
+    body_bytes: &[u8],
+) -> u64 {
+    match introspection_json {
+        Some(root) => token_estimate::estimate_count_tokens_request(root),
+        None => token_estimate::estimate_text_tokens(&String::from_utf8_lossy(body_bytes)).max(1),
+    }
+}
+
+pub(super) fn respond(ctx: CountTokensEstimateCtx<'_>, input_tokens: u64) -> Response {
+    let response_body = serde_json::json!({ "input_tokens": input_tokens });
+    let special_settings_json = {
+        let mut settings = ctx.special_settings.lock_or_recover();
+        settings.push(serde_json::json!({
+            "type": "count_tokens_estimate",
+            "scope": "request",
+            "hit": true,
+            "reason": "no_available_provider",
+            "inputTokens": input_tokens,
+        }));
+        serde_json::to_string(&*settings).ok()
+    };
+
+    emit_request_start_event(
+        &ctx.state.app,
+        ctx.trace_id.to_string(),
+        "claude".to_string(),
+        ctx.method_hint.to_string(),
+        ctx.forwarded_path.to_string(),
+        ctx.query.map(str::to_string),
+        ctx.requested_model.clone(),
+        ctx.created_at,
+    );
+
+    let attempts = [FailoverAttempt {
+        provider_id: 0,
+        provider_name: "Estimate".to_string(),
+        base_url: ESTIMATE_BASE_URL.to_string(),
+        outcome: "success".to_string(),
+        status: Some(StatusCode::OK.as_u16()),
+        provider_index: None,
+        retry_index: None,
+        session_reuse: Some(false),
+        error_category: None,
+        error_code: None,
+        decision: Some("success"),
+        reason: None,
+        selection_method: None,
+        reason_code: Some(dc::REASON_REQUEST_SUCCESS),
+        attempt_started_ms: None,
+        attempt_duration_ms: None,
+        circuit_state_before: None,
+        circuit_state_after: None,
+        circuit_failure_count: None,
+        circuit_failure_threshold: None,
+        error_catalog_code: None,
+        response_headers: None,
+        error_body: None,
+        retry_backoff_ms: None,
+        stream_stats: None,
+    }];
+
+    emit_request_event_and_spawn_request_log(RequestEndArgs {
+        deps: RequestEndDeps::from_state(ctx.state),
+        trace_id: ctx.trace_id,
+        cli_key: "claude",
+        method: ctx.method_hint,
+        path: ctx.forwarded_path,
+        query: ctx.query,
+        excluded_from_stats: true,
+        status: Some(StatusCode::OK.as_u16()),
+        error_category: None,
+        error_code: None,
+        duration_ms: ctx.duration_ms,
+        event_ttfb_ms: Some(ctx.duration_ms),
+        log_ttfb_ms: Some(ctx.duration_ms),
+        attempts: &attempts,
+        special_settings_json,
+        session_id: None,
+        requested_model: ctx.requested_model,
+        created_at_ms: ctx.created_at_ms,
+        created_at: ctx.created_at,
+        usage_metrics: None,
+        log_usage_metrics: None,
+        usage: None,
+        request_bytes: None,
+        response_bytes: None,
+        thinking_budget_tokens: None,
+    });
+
+    let mut resp = (StatusCode::OK, Json(response_body)).into_response();
+    resp.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json; charset=utf-8"),
+    );
+    resp.headers_mut().insert(
+        "x-aio-token-estimate",
+        HeaderValue::from_static("approximate"),
+    );
+    resp.headers_mut().insert(
+        "x-aio-intercepted",
+        HeaderValue::from_static("count_tokens_estimate"),
+    );
+    resp.headers_mut().insert(
+        "x-aio-intercepted-by",
+        HeaderValue::from_static("aio-coding-hub"),
+    );
+    if let Ok(v) = HeaderValue::from_str(ctx.trace_id) {
+        resp.headers_mut().insert("x-trace-id", v);
+    }
+    resp
+}
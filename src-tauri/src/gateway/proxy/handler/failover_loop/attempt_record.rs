@@ -1,9 +1,11 @@
 //! Usage: Shared helpers to record SystemError attempts and apply failover decisions.
 
+use super::super::super::failover::{retry_backoff_delay, RetryBackoffCategory};
 use super::super::super::provider_router;
 use super::super::super::status_override;
 use super::*;
 use crate::gateway::events::decision_chain as dc;
+use crate::settings::RetryBackoffSettings;
 
 pub(super) struct RecordSystemFailureArgs<'a> {
     pub(super) ctx: CommonCtx<'a>,
@@ -17,6 +19,24 @@ pub(super) struct RecordSystemFailureArgs<'a> {
     pub(super) reason: String,
 }
 
+/// Sleeps per the `retry_backoff` policy before a same-provider retry and records the actual
+/// wait on the attempt that triggered it, so attempt timings never include our own delay.
+pub(super) async fn wait_retry_backoff(
+    retry_backoff: &RetryBackoffSettings,
+    category: Option<RetryBackoffCategory>,
+    retry_index: u32,
+    attempts: &mut [FailoverAttempt],
+) {
+    let Some(delay) = retry_backoff_delay(retry_backoff, category, retry_index) else {
+        return;
+    };
+    let waited = Instant::now();
+    tokio::time::sleep(delay).await;
+    if let Some(attempt) = attempts.last_mut() {
+        attempt.retry_backoff_ms = Some(waited.elapsed().as_millis() as u64);
+    }
+}
+
 pub(super) async fn record_system_failure_and_decide(
     args: RecordSystemFailureArgs<'_>,
 ) -> LoopControl {
@@ -56,6 +76,8 @@ async fn record_system_failure_and_decide_impl(
         provider_base_url_base,
         provider_index,
         session_reuse,
+        thinking_budget_tokens: _,
+        refusal_patterns: _,
     } = provider_ctx;
 
     let AttemptCtx {
@@ -73,12 +95,13 @@ async fn record_system_failure_and_decide_impl(
         last_error_category,
         last_error_code,
         circuit_snapshot,
-        abort_guard: _,
+        abort_guard,
     } = loop_state;
 
     let category = ErrorCategory::SystemError;
     let effective_status = status_override::effective_status(status, Some(error_code));
 
+    abort_guard.end_attempt();
     attempts.push(FailoverAttempt {
         provider_id,
         provider_name: provider_name_base.clone(),
@@ -100,6 +123,11 @@ async fn record_system_failure_and_decide_impl(
         circuit_state_after: None,
         circuit_failure_count: Some(circuit_before.failure_count),
         circuit_failure_threshold: Some(circuit_before.failure_threshold),
+        error_catalog_code: None,
+        response_headers: None,
+        error_body: None,
+        retry_backoff_ms: None,
+        stream_stats: None,
     });
 
     emit_attempt_event_and_log_with_circuit_before(
@@ -134,7 +162,16 @@ async fn record_system_failure_and_decide_impl(
     }
 
     match decision {
-        FailoverDecision::RetrySameProvider => LoopControl::ContinueRetry,
+        FailoverDecision::RetrySameProvider => {
+            wait_retry_backoff(
+                &ctx.retry_backoff,
+                Some(RetryBackoffCategory::NetworkError),
+                retry_index,
+                attempts,
+            )
+            .await;
+            LoopControl::ContinueRetry
+        }
         FailoverDecision::SwitchProvider => {
             failed_provider_ids.insert(provider_id);
             LoopControl::BreakRetry
@@ -0,0 +1,114 @@
+//! Usage: Model capability gate for a provider attempt (see `gateway::model_capabilities`).
+
+use super::context::CommonCtx;
+use crate::gateway::model_capabilities::{
+    strip_features, CapabilityGate, GateDecision, RequestFeature,
+};
+use crate::gateway::util::body_for_introspection;
+use crate::providers;
+use crate::shared::mutex_ext::MutexExt;
+use axum::body::Bytes;
+use axum::http::HeaderMap;
+
+/// The model the provider will be asked for, after Claude slot mapping.
+fn upstream_model(
+    ctx: CommonCtx<'_>,
+    provider: &providers::ProviderForGateway,
+    introspection_json: Option<&serde_json::Value>,
+) -> Option<String> {
+    let requested = ctx.requested_model.as_deref()?;
+    if ctx.cli_key != "claude" || !provider.claude_models.has_any() {
+        return Some(requested.to_string());
+    }
+    let has_thinking = introspection_json
+        .and_then(|v| v.get("thinking"))
+        .and_then(|v| v.get("type"))
+        .and_then(|v| v.as_str())
+        == Some("enabled");
+    Some(provider.get_effective_claude_model(requested, has_thinking))
+}
+
+fn feature_names(features: &[RequestFeature]) -> Vec<&'static str> {
+    features.iter().map(|f| f.as_str()).collect()
+}
+
+/// Decides whether `provider` can take the request; skips and strips are recorded in
+/// special settings. Returns the skip reason for the attempt record when it cannot.
+pub(super) fn decide(
+    ctx: CommonCtx<'_>,
+    gate: &CapabilityGate,
+    provider: &providers::ProviderForGateway,
+    introspection_json: Option<&serde_json::Value>,
+) -> Result<Vec<RequestFeature>, String> {
+    let Some(model) = upstream_model(ctx, provider, introspection_json) else {
+        return Ok(Vec::new());
+    };
+    let (decision, rule) = gate.decide(ctx.cli_key, provider.id, &model);
+    let (skip, features) = match decision {
+        GateDecision::Allow => return Ok(Vec::new()),
+        GateDecision::Skip(missing) => (true, missing),
+        GateDecision::Strip(missing) => (false, missing),
+    };
+    let names = feature_names(&features);
+
+    ctx.special_settings
+        .lock_or_recover()
+        .push(serde_json::json!({
+            "type": "model_capability_gate",
+            "scope": "attempt",
+            "hit": true,
+            "applied": true,
+            "providerId": provider.id,
+            "providerName": provider.name.clone(),
+            "model": model,
+            "pattern": rule.map(|r| r.pattern.clone()),
+            "policy": gate.policy,
+            "action": if skip { "skip" } else { "strip" },
+            "features": names,
+            "inputTokens": gate.features.input_tokens,
+            "maxContextTokens": rule.and_then(|r| r.max_context_tokens),
+        }));
+
+    if skip {
+        return Err(format!(
+            "provider skipped: model {model} lacks {}",
+            names.join(", ")
+        ));
+    }
+    Ok(features)
+}
+
+pub(super) struct StripUnsupportedFeaturesInput<'a> {
+    pub(super) features: &'a [RequestFeature],
+    pub(super) base_headers: &'a HeaderMap,
+    pub(super) upstream_body_bytes: &'a mut Bytes,
+    pub(super) strip_request_content_encoding: &'a mut bool,
+}
+
+/// Removes the features [`decide`] asked to strip from the upstream body.
+pub(super) fn strip_if_needed(input: StripUnsupportedFeaturesInput<'_>) {
+    let StripUnsupportedFeaturesInput {
+        features,
+        base_headers,
+        upstream_body_bytes,
+        strip_request_content_encoding,
+    } = input;
+    if features.is_empty() {
+        return;
+    }
+
+    let body_for_parse = if *strip_request_content_encoding {
+        std::borrow::Cow::Borrowed(upstream_body_bytes.as_ref())
+    } else {
+        body_for_introspection(base_headers, upstream_body_bytes.as_ref())
+    };
+    let Ok(mut root) = serde_json::from_slice::<serde_json::Value>(body_for_parse.as_ref()) else {
+        return;
+    };
+    strip_features(&mut root, features);
+    let Ok(encoded) = serde_json::to_vec(&root) else {
+        return;
+    };
+    *upstream_body_bytes = Bytes::from(encoded);
+    *strip_request_content_encoding = true;
+}
@@ -6,7 +6,10 @@ use crate::circuit_breaker;
 use crate::gateway::events::FailoverAttempt;
 use crate::gateway::manager::GatewayAppState;
 use crate::gateway::response_fixer;
-use crate::gateway::streams::StreamFinalizeCtx;
+use crate::gateway::streams::{
+    SseCoalesceConfig, StreamFailureConfig, StreamFinalizeCtx, StreamNetStats, StreamStallConfig,
+};
+use crate::settings::RetryBackoffSettings;
 use axum::response::Response;
 use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
@@ -29,6 +32,7 @@ pub(super) struct CommonCtxArgs<'a> {
     pub(super) effective_sort_mode_id: Option<i64>,
     pub(super) special_settings: &'a Arc<Mutex<Vec<serde_json::Value>>>,
     pub(super) provider_cooldown_secs: i64,
+    pub(super) retry_backoff: RetryBackoffSettings,
     pub(super) upstream_first_byte_timeout_secs: u32,
     pub(super) upstream_first_byte_timeout: Option<Duration>,
     pub(super) upstream_stream_idle_timeout: Option<Duration>,
@@ -38,7 +42,11 @@ pub(super) struct CommonCtxArgs<'a> {
     pub(super) enable_response_fixer: bool,
     pub(super) response_fixer_stream_config: response_fixer::ResponseFixerConfig,
     pub(super) response_fixer_non_stream_config: response_fixer::ResponseFixerConfig,
+    pub(super) sse_coalesce: SseCoalesceConfig,
+    pub(super) stream_stall: StreamStallConfig,
+    pub(super) stream_failure: StreamFailureConfig,
     pub(super) introspection_body: &'a [u8],
+    pub(super) request_bytes: u64,
 }
 
 #[derive(Clone, Copy)]
@@ -57,6 +65,7 @@ pub(super) struct CommonCtx<'a> {
     pub(super) effective_sort_mode_id: Option<i64>,
     pub(super) special_settings: &'a Arc<Mutex<Vec<serde_json::Value>>>,
     pub(super) provider_cooldown_secs: i64,
+    pub(super) retry_backoff: RetryBackoffSettings,
     pub(super) upstream_first_byte_timeout_secs: u32,
     pub(super) upstream_first_byte_timeout: Option<Duration>,
     pub(super) upstream_stream_idle_timeout: Option<Duration>,
@@ -66,7 +75,11 @@ pub(super) struct CommonCtx<'a> {
     pub(super) enable_response_fixer: bool,
     pub(super) response_fixer_stream_config: response_fixer::ResponseFixerConfig,
     pub(super) response_fixer_non_stream_config: response_fixer::ResponseFixerConfig,
+    pub(super) sse_coalesce: SseCoalesceConfig,
+    pub(super) stream_stall: StreamStallConfig,
+    pub(super) stream_failure: StreamFailureConfig,
     pub(super) introspection_body: &'a [u8],
+    pub(super) request_bytes: u64,
 }
 
 impl<'a> CommonCtx<'a> {
@@ -86,6 +99,7 @@ impl<'a> CommonCtx<'a> {
             effective_sort_mode_id: args.effective_sort_mode_id,
             special_settings: args.special_settings,
             provider_cooldown_secs: args.provider_cooldown_secs,
+            retry_backoff: args.retry_backoff,
             upstream_first_byte_timeout_secs: args.upstream_first_byte_timeout_secs,
             upstream_first_byte_timeout: args.upstream_first_byte_timeout,
             upstream_stream_idle_timeout: args.upstream_stream_idle_timeout,
@@ -95,7 +109,11 @@ impl<'a> CommonCtx<'a> {
             enable_response_fixer: args.enable_response_fixer,
             response_fixer_stream_config: args.response_fixer_stream_config,
             response_fixer_non_stream_config: args.response_fixer_non_stream_config,
+            sse_coalesce: args.sse_coalesce,
+            stream_stall: args.stream_stall,
+            stream_failure: args.stream_failure,
             introspection_body: args.introspection_body,
+            request_bytes: args.request_bytes,
         }
     }
 }
@@ -121,6 +139,7 @@ pub(super) struct CommonCtxOwned<'a> {
     pub(super) effective_sort_mode_id: Option<i64>,
     pub(super) special_settings: Arc<Mutex<Vec<serde_json::Value>>>,
     pub(super) provider_cooldown_secs: i64,
+    pub(super) retry_backoff: RetryBackoffSettings,
     pub(super) upstream_first_byte_timeout_secs: u32,
     pub(super) upstream_first_byte_timeout: Option<Duration>,
     pub(super) upstream_stream_idle_timeout: Option<Duration>,
@@ -129,6 +148,10 @@ pub(super) struct CommonCtxOwned<'a> {
     pub(super) enable_response_fixer: bool,
     pub(super) response_fixer_stream_config: response_fixer::ResponseFixerConfig,
     pub(super) response_fixer_non_stream_config: response_fixer::ResponseFixerConfig,
+    pub(super) sse_coalesce: SseCoalesceConfig,
+    pub(super) stream_stall: StreamStallConfig,
+    pub(super) stream_failure: StreamFailureConfig,
+    pub(super) request_bytes: u64,
 }
 
 impl<'a> From<CommonCtx<'a>> for CommonCtxOwned<'a> {
@@ -148,6 +171,7 @@ impl<'a> From<CommonCtx<'a>> for CommonCtxOwned<'a> {
             effective_sort_mode_id: ctx.effective_sort_mode_id,
             special_settings: Arc::clone(ctx.special_settings),
             provider_cooldown_secs: ctx.provider_cooldown_secs,
+            retry_backoff: ctx.retry_backoff,
             upstream_first_byte_timeout_secs: ctx.upstream_first_byte_timeout_secs,
             upstream_first_byte_timeout: ctx.upstream_first_byte_timeout,
             upstream_stream_idle_timeout: ctx.upstream_stream_idle_timeout,
@@ -156,6 +180,10 @@ impl<'a> From<CommonCtx<'a>> for CommonCtxOwned<'a> {
             enable_response_fixer: ctx.enable_response_fixer,
             response_fixer_stream_config: ctx.response_fixer_stream_config,
             response_fixer_non_stream_config: ctx.response_fixer_non_stream_config,
+            sse_coalesce: ctx.sse_coalesce,
+            stream_stall: ctx.stream_stall,
+            stream_failure: ctx.stream_failure,
+            request_bytes: ctx.request_bytes,
         }
     }
 }
@@ -167,6 +195,8 @@ pub(super) struct ProviderCtx<'a> {
     pub(super) provider_base_url_base: &'a String,
     pub(super) provider_index: u32,
     pub(super) session_reuse: Option<bool>,
+    pub(super) thinking_budget_tokens: Option<u64>,
+    pub(super) refusal_patterns: &'a [String],
 }
 
 pub(super) struct ProviderCtxOwned {
@@ -175,6 +205,8 @@ pub(super) struct ProviderCtxOwned {
     pub(super) provider_base_url_base: String,
     pub(super) provider_index: u32,
     pub(super) session_reuse: Option<bool>,
+    pub(super) thinking_budget_tokens: Option<u64>,
+    pub(super) refusal_patterns: Vec<String>,
 }
 
 impl<'a> From<ProviderCtx<'a>> for ProviderCtxOwned {
@@ -185,6 +217,8 @@ impl<'a> From<ProviderCtx<'a>> for ProviderCtxOwned {
             provider_base_url_base: ctx.provider_base_url_base.clone(),
             provider_index: ctx.provider_index,
             session_reuse: ctx.session_reuse,
+            thinking_budget_tokens: ctx.thinking_budget_tokens,
+            refusal_patterns: ctx.refusal_patterns.to_vec(),
         }
     }
 }
@@ -205,6 +239,7 @@ pub(super) fn build_stream_finalize_ctx(
         log_tx: ctx.state.log_tx.clone(),
         circuit: ctx.state.circuit.clone(),
         session: ctx.state.session.clone(),
+        quality: ctx.state.quality.clone(),
         session_id: ctx.session_id.clone(),
         sort_mode_id: ctx.effective_sort_mode_id,
         trace_id: ctx.trace_id.clone(),
@@ -227,6 +262,10 @@ pub(super) fn build_stream_finalize_ctx(
         provider_id: provider_ctx.provider_id,
         provider_name: provider_ctx.provider_name_base.clone(),
         base_url: provider_ctx.provider_base_url_base.clone(),
+        net_stats: Arc::new(StreamNetStats::new()),
+        request_bytes: ctx.request_bytes,
+        thinking_budget_tokens: provider_ctx.thinking_budget_tokens,
+        live_attempt: None,
     }
 }
 
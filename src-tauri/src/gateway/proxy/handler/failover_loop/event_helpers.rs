@@ -25,6 +25,8 @@ pub(super) async fn emit_attempt_event_and_log(
         provider_base_url_base,
         provider_index: _,
         session_reuse,
+        thinking_budget_tokens: _,
+        refusal_patterns: _,
     } = provider_ctx;
     let AttemptCtx {
         attempt_index,
@@ -34,6 +34,8 @@ pub(super) struct AllUnavailableInput<'a> {
     pub(super) skipped_open: usize,
     pub(super) skipped_cooldown: usize,
     pub(super) skipped_limits: usize,
+    pub(super) skipped_maintenance: usize,
+    pub(super) skipped_model: usize,
     pub(super) fingerprint_key: u64,
     pub(super) fingerprint_debug: String,
     pub(super) unavailable_fingerprint_key: u64,
@@ -60,6 +62,8 @@ pub(super) async fn all_providers_unavailable(input: AllUnavailableInput<'_>) ->
         skipped_open,
         skipped_cooldown,
         skipped_limits,
+        skipped_maintenance,
+        skipped_model,
         fingerprint_key,
         fingerprint_debug,
         unavailable_fingerprint_key,
@@ -73,7 +77,7 @@ pub(super) async fn all_providers_unavailable(input: AllUnavailableInput<'_>) ->
         .map(|v| v as u64);
 
     let detailed_message = format!(
-        "no provider available (skipped: open={skipped_open}, cooldown={skipped_cooldown}, limits={skipped_limits}) for cli_key={cli_key}",
+        "no provider available (skipped: open={skipped_open}, cooldown={skipped_cooldown}, limits={skipped_limits}, maintenance={skipped_maintenance}, model={skipped_model}) for cli_key={cli_key}",
     );
     let message = if verbose_provider_error {
         detailed_message
@@ -89,6 +93,8 @@ pub(super) async fn all_providers_unavailable(input: AllUnavailableInput<'_>) ->
         skipped_open = skipped_open,
         skipped_cooldown = skipped_cooldown,
         skipped_limits = skipped_limits,
+        skipped_maintenance = skipped_maintenance,
+        skipped_model = skipped_model,
         "all providers unavailable"
     );
 
@@ -103,7 +109,7 @@ pub(super) async fn all_providers_unavailable(input: AllUnavailableInput<'_>) ->
 
     let duration_ms = started.elapsed().as_millis();
     emit_request_event_and_enqueue_request_log(RequestEndArgs {
-        deps: RequestEndDeps::new(&state.app, &state.db, &state.log_tx),
+        deps: RequestEndDeps::from_state(state),
         trace_id: trace_id.as_str(),
         cli_key: cli_key.as_str(),
         method: method_hint.as_str(),
@@ -125,6 +131,9 @@ pub(super) async fn all_providers_unavailable(input: AllUnavailableInput<'_>) ->
         usage_metrics: None,
         log_usage_metrics: None,
         usage: None,
+        request_bytes: None,
+        response_bytes: None,
+        thinking_budget_tokens: None,
     })
     .await;
 
@@ -180,6 +189,7 @@ pub(super) struct AllFailedInput<'a> {
     pub(super) requested_model: Option<String>,
     pub(super) special_settings: Arc<Mutex<Vec<serde_json::Value>>>,
     pub(super) verbose_provider_error: bool,
+    pub(super) request_bytes: u64,
 }
 
 pub(super) async fn all_providers_failed(input: AllFailedInput<'_>) -> Response {
@@ -201,6 +211,7 @@ pub(super) async fn all_providers_failed(input: AllFailedInput<'_>) -> Response
         requested_model,
         special_settings,
         verbose_provider_error,
+        request_bytes,
     } = input;
 
     let final_error_code = last_error_code.unwrap_or(GatewayErrorCode::UpstreamAllFailed.as_str());
@@ -229,7 +240,7 @@ pub(super) async fn all_providers_failed(input: AllFailedInput<'_>) -> Response
 
     let duration_ms = started.elapsed().as_millis();
     emit_request_event_and_enqueue_request_log(RequestEndArgs {
-        deps: RequestEndDeps::new(&state.app, &state.db, &state.log_tx),
+        deps: RequestEndDeps::from_state(state),
         trace_id: trace_id.as_str(),
         cli_key: cli_key.as_str(),
         method: method_hint.as_str(),
@@ -251,6 +262,9 @@ pub(super) async fn all_providers_failed(input: AllFailedInput<'_>) -> Response
         usage_metrics: None,
         log_usage_metrics: None,
         usage: None,
+        request_bytes: Some(request_bytes),
+        response_bytes: None,
+        thinking_budget_tokens: None,
     })
     .await;
 
@@ -0,0 +1,75 @@
+//! Usage: Last-resort provider fallback (tried once after every regular provider failed).
+
+use crate::providers;
+use crate::shared::mutex_ext::MutexExt;
+use axum::http::{HeaderMap, HeaderValue};
+use std::sync::{Arc, Mutex};
+
+const FALLBACK_HEADER: &str = "x-aio-fallback";
+const FALLBACK_PROVIDER_HEADER: &str = "x-aio-fallback-provider";
+
+/// The last-resort provider that served (or is serving) the request.
+pub(super) struct LastResortServed {
+    pub(super) provider_id: i64,
+    pub(super) provider_name: String,
+}
+
+pub(super) fn begin(
+    special_settings: &Arc<Mutex<Vec<serde_json::Value>>>,
+    provider: &providers::ProviderForGateway,
+) -> LastResortServed {
+    let mut settings = special_settings.lock_or_recover();
+    settings.push(serde_json::json!({
+        "type": "last_resort_provider",
+        "scope": "request",
+        "hit": true,
+        "providerId": provider.id,
+        "providerName": provider.name,
+    }));
+    LastResortServed {
+        provider_id: provider.id,
+        provider_name: provider.name.clone(),
+    }
+}
+
+/// Tell the CLI user the answer came from the fallback model, not their usual providers.
+pub(super) fn annotate_response(headers: &mut HeaderMap, served: &LastResortServed) {
+    headers.insert(FALLBACK_HEADER, HeaderValue::from_static("last-resort"));
+    // Header values must be visible ASCII; fall back to the id for other provider names.
+    let provider = HeaderValue::from_str(served.provider_name.trim())
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| HeaderValue::from(served.provider_id));
+    headers.insert(FALLBACK_PROVIDER_HEADER, provider);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn annotate_response_falls_back_to_provider_id_for_non_ascii_names() {
+        let mut headers = HeaderMap::new();
+        annotate_response(
+            &mut headers,
+            &LastResortServed {
+                provider_id: 7,
+                provider_name: "local-ollama".to_string(),
+            },
+        );
+        assert_eq!(headers.get(FALLBACK_HEADER).unwrap(), "last-resort");
+        assert_eq!(
+            headers.get(FALLBACK_PROVIDER_HEADER).unwrap(),
+            "local-ollama"
+        );
+
+        annotate_response(
+            &mut headers,
+            &LastResortServed {
+                provider_id: 7,
+                provider_name: "本地模型".to_string(),
+            },
+        );
+        assert_eq!(headers.get(FALLBACK_PROVIDER_HEADER).unwrap(), "7");
+    }
+}
@@ -1,25 +1,29 @@
 //! Usage: Gateway proxy failover loop (provider iteration + retries + upstream response handling).
 
 mod attempt_record;
+mod capability_gate;
 mod claude_metadata_user_id_injection;
 mod claude_model_mapping;
 mod context;
 mod event_helpers;
 mod finalize;
+mod last_resort;
 mod provider_gate;
 mod provider_limits;
+mod refusal_failover;
 mod request_end_helpers;
 mod send;
 mod send_timeout;
 mod success_event_stream;
 mod success_non_stream;
+mod thinking_budget_governor;
 mod thinking_signature_rectifier_400;
 mod upstream_error;
 
 use super::super::request_context::RequestContext;
 use attempt_record::{
     record_system_failure_and_decide, record_system_failure_and_decide_no_cooldown,
-    RecordSystemFailureArgs,
+    wait_retry_backoff, RecordSystemFailureArgs,
 };
 use event_helpers::{
     emit_attempt_event_and_log, emit_attempt_event_and_log_with_circuit_before,
@@ -31,15 +35,19 @@ use request_end_helpers::{
 
 use super::super::{
     errors::{classify_upstream_status, error_response},
-    failover::{retry_backoff_delay, select_provider_base_url_for_request, FailoverDecision},
-    gemini_oauth,
+    failover::{
+        race_partner_base_url, select_provider_base_url_for_request, FailoverDecision,
+        RetryBackoffCategory,
+    },
+    federation, gemini_oauth,
     http_util::{
         build_response, has_gzip_content_encoding, has_non_identity_content_encoding,
         is_event_stream, maybe_gunzip_response_body_bytes_with_limit,
     },
-    ErrorCategory, GatewayErrorCode,
+    ErrorCategory, GatewayErrorCode, LiveAttemptGuard,
 };
 
+use crate::shared::mutex_ext::MutexExt;
 use crate::usage;
 use axum::{
     body::{Body, Bytes},
@@ -52,18 +60,22 @@ use std::collections::HashSet;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
+use crate::gateway::anthropic_headers;
 use crate::gateway::events::{
     decision_chain as dc, emit_attempt_event, FailoverAttempt, GatewayAttemptEvent,
 };
+use crate::gateway::model_capabilities::RequestFeature;
+use crate::gateway::oauth_pacing;
 use crate::gateway::response_fixer;
 use crate::gateway::streams::{
-    spawn_usage_sse_relay_body, FirstChunkStream, GunzipStream, TimingOnlyTeeStream,
-    UsageBodyBufferTeeStream, UsageSseTeeStream,
+    client_sse_body, spawn_usage_sse_relay_body, sse_error_event, FirstChunkStream, GunzipStream,
+    StitchingStream, StreamStitchRetry, TimingOnlyTeeStream, UsageBodyBufferTeeStream,
+    UsageSseTeeStream,
 };
 use crate::gateway::thinking_signature_rectifier;
 use crate::gateway::util::{
     body_for_introspection, build_target_url, ensure_cli_required_headers, inject_provider_auth,
-    now_unix_seconds, strip_hop_headers,
+    now_unix_millis, now_unix_seconds, strip_hop_headers,
 };
 
 use context::{
@@ -448,7 +460,19 @@ fn maybe_enforce_codex_chatgpt_store_false(
     }
 }
 
-pub(super) async fn run(mut input: RequestContext) -> Response {
+pub(super) async fn run(input: RequestContext) -> Response {
+    let mut last_resort_served = None;
+    let mut resp = run_providers(input, &mut last_resort_served).await;
+    if let Some(served) = last_resort_served.filter(|_| resp.status().is_success()) {
+        last_resort::annotate_response(resp.headers_mut(), &served);
+    }
+    resp
+}
+
+async fn run_providers(
+    mut input: RequestContext,
+    last_resort_served: &mut Option<last_resort::LastResortServed>,
+) -> Response {
     let method = input.req_method.clone();
     let started = input.started;
     let created_at_ms = input.created_at_ms;
@@ -470,6 +494,7 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
         effective_sort_mode_id: input.effective_sort_mode_id,
         special_settings: &input.special_settings,
         provider_cooldown_secs: input.provider_cooldown_secs,
+        retry_backoff: input.retry_backoff,
         upstream_first_byte_timeout_secs: input.upstream_first_byte_timeout_secs,
         upstream_first_byte_timeout: input.upstream_first_byte_timeout,
         upstream_stream_idle_timeout: input.upstream_stream_idle_timeout,
@@ -479,7 +504,11 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
         enable_response_fixer: input.enable_response_fixer,
         response_fixer_stream_config: input.response_fixer_stream_config,
         response_fixer_non_stream_config: input.response_fixer_non_stream_config,
+        sse_coalesce: input.sse_coalesce,
+        stream_stall: input.stream_stall,
+        stream_failure: input.stream_failure,
         introspection_body: introspection_body.as_ref(),
+        request_bytes: input.body_bytes.len() as u64,
     });
     let mut attempts: Vec<FailoverAttempt> = Vec::new();
     let mut failed_provider_ids: HashSet<i64> = HashSet::new();
@@ -492,10 +521,44 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
     let mut skipped_open: usize = 0;
     let mut skipped_cooldown: usize = 0;
     let mut skipped_limits: usize = 0;
+    let mut skipped_maintenance: usize = 0;
+    let mut skipped_model: usize = 0;
+
+    let last_resort_provider = input.last_resort_provider.take();
+    let regular_provider_count = input.providers.len();
+
+    // Fail open: a maintenance lookup error must not block requests.
+    let maintenance_until_by_provider = {
+        let provider_ids: Vec<i64> = input
+            .providers
+            .iter()
+            .chain(last_resort_provider.iter())
+            .map(|p| p.id)
+            .collect();
+        crate::provider_maintenance::active_until_by_provider(
+            &input.state.db,
+            &provider_ids,
+            created_at,
+        )
+        .unwrap_or_default()
+    };
 
-    for provider in input.providers.iter() {
-        if providers_tried >= max_providers_to_try {
-            break;
+    for (provider_pos, provider) in input
+        .providers
+        .iter()
+        .chain(last_resort_provider.iter())
+        .enumerate()
+    {
+        let is_last_resort = provider_pos >= regular_provider_count;
+        if is_last_resort {
+            // Only when every regular provider failed; with no attempt at all the request keeps
+            // its "all providers unavailable" semantics.
+            if attempts.is_empty() || failed_provider_ids.contains(&provider.id) {
+                break;
+            }
+            *last_resort_served = Some(last_resort::begin(&input.special_settings, provider));
+        } else if providers_tried >= max_providers_to_try {
+            continue;
         }
 
         let provider_id = provider.id;
@@ -516,15 +579,50 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
 
         let skipped_open_before = skipped_open;
         let skipped_cooldown_before = skipped_cooldown;
+        let skipped_maintenance_before = skipped_maintenance;
         let Some(gate_allow) = provider_gate::gate_provider(provider_gate::ProviderGateInput {
             ctx,
             provider_id,
             provider_name_base: &provider_name_base,
             provider_base_url_display: &provider_base_url_display,
+            maintenance_until: maintenance_until_by_provider.get(&provider_id).copied(),
             earliest_available_unix: &mut earliest_available_unix,
             skipped_open: &mut skipped_open,
             skipped_cooldown: &mut skipped_cooldown,
+            skipped_maintenance: &mut skipped_maintenance,
         }) else {
+            if skipped_maintenance > skipped_maintenance_before {
+                // Record skipped provider (scheduled maintenance window)
+                attempts.push(FailoverAttempt {
+                    provider_id,
+                    provider_name: provider_name_base.clone(),
+                    base_url: provider_base_url_display.clone(),
+                    outcome: "skipped".to_string(),
+                    status: None,
+                    provider_index: None,
+                    retry_index: None,
+                    session_reuse: None,
+                    error_category: Some("maintenance"),
+                    error_code: Some(GatewayErrorCode::ProviderMaintenance.as_str()),
+                    decision: Some("skip"),
+                    reason: Some("provider skipped by maintenance window".to_string()),
+                    selection_method: Some(dc::SELECTION_METHOD_FILTERED),
+                    reason_code: Some(dc::REASON_PROVIDER_MAINTENANCE),
+                    attempt_started_ms: Some(started.elapsed().as_millis()),
+                    attempt_duration_ms: Some(0),
+                    circuit_state_before: None,
+                    circuit_state_after: None,
+                    circuit_failure_count: None,
+                    circuit_failure_threshold: None,
+                    error_catalog_code: None,
+                    response_headers: None,
+                    error_body: None,
+                    retry_backoff_ms: None,
+                    stream_stats: None,
+                });
+                continue;
+            }
+
             let (reason_code, reason_label) = if skipped_open > skipped_open_before {
                 (Some(dc::REASON_CIRCUIT_OPEN), "open")
             } else if skipped_cooldown > skipped_cooldown_before {
@@ -557,17 +655,24 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
                 circuit_state_after: None,
                 circuit_failure_count: None,
                 circuit_failure_threshold: None,
+                error_catalog_code: None,
+                response_headers: None,
+                error_body: None,
+                retry_backoff_ms: None,
+                stream_stats: None,
             });
             continue;
         };
 
-        if !provider_limits::gate_provider(provider_limits::ProviderLimitsInput {
-            ctx,
-            provider,
-            earliest_available_unix: &mut earliest_available_unix,
-            skipped_limits: &mut skipped_limits,
-        }) {
-            // Record skipped provider (rate limit gate)
+        if let Err(limit_skip) =
+            provider_limits::gate_provider(provider_limits::ProviderLimitsInput {
+                ctx,
+                provider,
+                earliest_available_unix: &mut earliest_available_unix,
+                skipped_limits: &mut skipped_limits,
+            })
+        {
+            // Record skipped provider (rate limit / monthly budget gate)
             attempts.push(FailoverAttempt {
                 provider_id,
                 provider_name: provider_name_base.clone(),
@@ -580,20 +685,112 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
                 error_category: Some("rate_limit"),
                 error_code: Some(GatewayErrorCode::ProviderRateLimited.as_str()),
                 decision: Some("skip"),
-                reason: Some("provider skipped by rate limit".to_string()),
+                reason: Some(limit_skip.reason()),
                 selection_method: Some(dc::SELECTION_METHOD_FILTERED),
-                reason_code: Some(dc::REASON_RATE_LIMITED),
+                reason_code: Some(if limit_skip.budget_exhausted() {
+                    dc::REASON_BUDGET_EXHAUSTED
+                } else {
+                    dc::REASON_RATE_LIMITED
+                }),
                 attempt_started_ms: Some(started.elapsed().as_millis()),
                 attempt_duration_ms: Some(0),
                 circuit_state_before: None,
                 circuit_state_after: None,
                 circuit_failure_count: None,
                 circuit_failure_threshold: None,
+                error_catalog_code: None,
+                response_headers: None,
+                error_body: None,
+                retry_backoff_ms: None,
+                stream_stats: None,
             });
             continue;
         }
 
         // NOTE: model whitelist filtering removed (Claude uses slot-based model mapping).
+        // Aggregated providers are the exception: they expose an explicit `/models` catalog.
+        if provider.provider_kind == crate::providers::ProviderKind::Aggregated {
+            crate::provider_model_catalog::spawn_refresh_if_stale(
+                &input.state.db,
+                &input.state.client,
+                provider_id,
+                provider.model_catalog_refreshed_at,
+            );
+
+            if let Some(requested_model) = input.requested_model.as_deref() {
+                if !provider.catalog_allows_model(requested_model) {
+                    skipped_model = skipped_model.saturating_add(1);
+                    attempts.push(FailoverAttempt {
+                        provider_id,
+                        provider_name: provider_name_base.clone(),
+                        base_url: provider_base_url_display.clone(),
+                        outcome: "skipped".to_string(),
+                        status: None,
+                        provider_index: None,
+                        retry_index: None,
+                        session_reuse: None,
+                        error_category: Some("model"),
+                        error_code: Some(GatewayErrorCode::ProviderModelUnsupported.as_str()),
+                        decision: Some("skip"),
+                        reason: Some(format!(
+                            "provider skipped: model {requested_model} not in catalog"
+                        )),
+                        selection_method: Some(dc::SELECTION_METHOD_FILTERED),
+                        reason_code: Some(dc::REASON_MODEL_NOT_IN_CATALOG),
+                        attempt_started_ms: Some(started.elapsed().as_millis()),
+                        attempt_duration_ms: Some(0),
+                        circuit_state_before: None,
+                        circuit_state_after: None,
+                        circuit_failure_count: None,
+                        circuit_failure_threshold: None,
+                        error_catalog_code: None,
+                        response_headers: None,
+                        error_body: None,
+                        retry_backoff_ms: None,
+                        stream_stats: None,
+                    });
+                    continue;
+                }
+            }
+        }
+
+        let stripped_features = match input.capability_gate.as_deref().map(|gate| {
+            capability_gate::decide(ctx, gate, provider, input.introspection_json.as_ref())
+        }) {
+            None => Vec::new(),
+            Some(Ok(features)) => features,
+            Some(Err(reason)) => {
+                skipped_model = skipped_model.saturating_add(1);
+                attempts.push(FailoverAttempt {
+                    provider_id,
+                    provider_name: provider_name_base.clone(),
+                    base_url: provider_base_url_display.clone(),
+                    outcome: "skipped".to_string(),
+                    status: None,
+                    provider_index: None,
+                    retry_index: None,
+                    session_reuse: None,
+                    error_category: Some("model"),
+                    error_code: Some(GatewayErrorCode::ProviderModelUnsupported.as_str()),
+                    decision: Some("skip"),
+                    reason: Some(reason),
+                    selection_method: Some(dc::SELECTION_METHOD_FILTERED),
+                    reason_code: Some(dc::REASON_MODEL_CAPABILITY_MISSING),
+                    attempt_started_ms: Some(started.elapsed().as_millis()),
+                    attempt_duration_ms: Some(0),
+                    circuit_state_before: None,
+                    circuit_state_after: None,
+                    circuit_failure_count: None,
+                    circuit_failure_threshold: None,
+                    error_catalog_code: None,
+                    response_headers: None,
+                    error_body: None,
+                    retry_backoff_ms: None,
+                    stream_stats: None,
+                });
+                continue;
+            }
+        };
 
         // Resolve effective credential (API key or OAuth token with inline refresh).
         let mut effective_credential =
@@ -632,6 +829,11 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
                         circuit_state_after: None,
                         circuit_failure_count: None,
                         circuit_failure_threshold: None,
+                        error_catalog_code: None,
+                        response_headers: None,
+                        error_body: None,
+                        retry_backoff_ms: None,
+                        stream_stats: None,
                     });
                     continue;
                 }
@@ -640,6 +842,8 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
         // OAuth providers get at least 2 retry attempts (to handle 401 reactive refresh).
         let provider_max_attempts = if provider.auth_mode == "oauth" {
             input.max_attempts_per_provider.max(2)
+        } else if is_last_resort {
+            1
         } else {
             input.max_attempts_per_provider
         };
@@ -684,6 +888,11 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
                     circuit_state_after: None,
                     circuit_failure_count: None,
                     circuit_failure_threshold: None,
+                    error_catalog_code: None,
+                    response_headers: None,
+                    error_body: None,
+                    retry_backoff_ms: None,
+                    stream_stats: None,
                 });
                 continue;
             }
@@ -699,6 +908,7 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
             base_urls_count = provider.base_urls.len(),
             "resolved provider base_url for request"
         );
+        let race_partner_base = race_partner_base_url(provider, &provider_base_url_base);
 
         // Detect Codex ChatGPT backend for special handling.
         let use_codex_chatgpt_backend =
@@ -764,6 +974,11 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
                         circuit_state_after: None,
                         circuit_failure_count: None,
                         circuit_failure_threshold: None,
+                        error_catalog_code: None,
+                        response_headers: None,
+                        error_body: None,
+                        retry_backoff_ms: None,
+                        stream_stats: None,
                     });
                     continue;
                 }
@@ -772,7 +987,12 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
             None
         };
 
-        let mut upstream_forwarded_path = input.forwarded_path.clone();
+        let is_federated = federation::is_federated(provider);
+        let mut upstream_forwarded_path = if is_federated {
+            federation::forwarded_path(&input.cli_key, &input.forwarded_path)
+        } else {
+            input.forwarded_path.clone()
+        };
         let mut upstream_query = input.query.clone();
         let mut upstream_body_bytes = input.body_bytes.clone();
         let mut strip_request_content_encoding = input.strip_request_content_encoding_seed;
@@ -838,6 +1058,11 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
                         circuit_state_after: None,
                         circuit_failure_count: None,
                         circuit_failure_threshold: None,
+                        error_catalog_code: None,
+                        response_headers: None,
+                        error_body: None,
+                        retry_backoff_ms: None,
+                        stream_stats: None,
                     });
                     continue;
                 }
@@ -852,12 +1077,14 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
             Some(id) => (id == provider_id && provider_index == 1).then_some(true),
             None => None,
         };
-        let provider_ctx = ProviderCtx {
+        let mut provider_ctx = ProviderCtx {
             provider_id,
             provider_name_base: &provider_name_base,
             provider_base_url_base: &provider_base_url_base,
             provider_index,
             session_reuse,
+            thinking_budget_tokens: None,
+            refusal_patterns: &provider.refusal_patterns,
         };
 
         claude_model_mapping::apply_if_needed(
@@ -887,6 +1114,28 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
             },
         );
 
+        provider_ctx.thinking_budget_tokens = thinking_budget_governor::apply_if_needed(
+            thinking_budget_governor::ApplyThinkingBudgetGovernorInput {
+                ctx,
+                provider,
+                introspection_json: input.introspection_json.as_ref(),
+                base_headers: &input.base_headers,
+                forwarded_path: upstream_forwarded_path.as_str(),
+                upstream_body_bytes: &mut upstream_body_bytes,
+                strip_request_content_encoding: &mut strip_request_content_encoding,
+            },
+        );
+
+        capability_gate::strip_if_needed(capability_gate::StripUnsupportedFeaturesInput {
+            features: &stripped_features,
+            base_headers: &input.base_headers,
+            upstream_body_bytes: &mut upstream_body_bytes,
+            strip_request_content_encoding: &mut strip_request_content_encoding,
+        });
+        if stripped_features.contains(&RequestFeature::Thinking) {
+            provider_ctx.thinking_budget_tokens = None;
+        }
+
         // Codex ChatGPT backend: normalize path and enforce store=false.
         if use_codex_chatgpt_backend {
             upstream_forwarded_path =
@@ -899,6 +1148,20 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
         }
 
         for retry_index in 1..=provider_max_attempts {
+            if provider.auth_mode == "oauth" {
+                let pacing_delay_ms = oauth_pacing::acquire(provider_id, now_unix_millis() as i64);
+                if pacing_delay_ms > 0 {
+                    tracing::info!(
+                        trace_id = %input.trace_id,
+                        cli_key = %input.cli_key,
+                        provider_id = provider_id,
+                        delay_ms = pacing_delay_ms,
+                        "oauth account paced before upstream request"
+                    );
+                    tokio::time::sleep(std::time::Duration::from_millis(pacing_delay_ms)).await;
+                }
+            }
+
             let attempt_index = attempts.len().saturating_add(1) as u32;
             let attempt_started_ms = started.elapsed().as_millis();
             let attempt_started = Instant::now();
@@ -966,6 +1229,11 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
                 }
             };
 
+            // Race mode: the same attempt also goes to the second base_url; first to answer wins.
+            let race_partner_url = race_partner_base.as_deref().and_then(|base| {
+                build_target_url(base, &upstream_forwarded_path, upstream_query.as_deref()).ok()
+            });
+
             // Realtime routing UX: emit an attempt event as soon as a provider is selected (before awaiting upstream).
             //
             // Note: do NOT enqueue attempt_logs for this "started" event (avoid DB noise/IO); completion events still get persisted.
@@ -1047,6 +1315,11 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
                                 circuit_state_after: None,
                                 circuit_failure_count: Some(circuit_before.failure_count),
                                 circuit_failure_threshold: Some(circuit_before.failure_threshold),
+                                error_catalog_code: None,
+                                response_headers: None,
+                                error_body: None,
+                                retry_backoff_ms: None,
+                                stream_stats: None,
                             });
                             break; // break retry loop, switch provider
                         }
@@ -1061,8 +1334,47 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
                         break;
                     }
                 }
+            } else if is_federated {
+                federation::inject_headers(&mut headers, effective_credential.trim());
+                if retry_index == 1 {
+                    input
+                        .special_settings
+                        .lock_or_recover()
+                        .push(serde_json::json!({
+                            "type": "federation",
+                            "scope": "attempt",
+                            "hit": true,
+                            "providerId": provider_id,
+                            "providerName": provider_name_base.clone(),
+                            "remotePath": upstream_forwarded_path.clone(),
+                        }));
+                }
             } else {
                 inject_provider_auth(&input.cli_key, effective_credential.trim(), &mut headers);
+                // OAuth adapters send a fixed beta set the account needs; relays get the override.
+                if input.cli_key == "claude" {
+                    let applied = anthropic_headers::apply(
+                        &mut headers,
+                        provider.anthropic_version.as_deref(),
+                        &provider.anthropic_betas,
+                        provider.anthropic_beta_strip,
+                    );
+                    if let Some(applied) = applied.filter(|_| retry_index == 1) {
+                        input
+                            .special_settings
+                            .lock_or_recover()
+                            .push(serde_json::json!({
+                                "type": "anthropic_headers",
+                                "scope": "attempt",
+                                "hit": true,
+                                "providerId": provider_id,
+                                "providerName": provider_name_base.clone(),
+                                "anthropicVersion": applied.version,
+                                "anthropicBeta": applied.betas,
+                                "strippedBetas": applied.stripped_betas,
+                            }));
+                    }
+                }
             }
             if use_codex_chatgpt_backend {
                 maybe_inject_codex_chatgpt_headers(
@@ -1074,21 +1386,145 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
                 headers.remove(header::CONTENT_ENCODING);
             }
 
-            let send_result = send::send_upstream(
-                ctx,
-                method.clone(),
-                url,
-                headers,
-                upstream_body_bytes.clone(),
-            )
-            .await;
+            input.abort_guard.begin_attempt(
+                &attempts,
+                FailoverAttempt {
+                    provider_id,
+                    provider_name: provider_name_base.clone(),
+                    base_url: provider_base_url_display.clone(),
+                    outcome: "started".to_string(),
+                    status: None,
+                    provider_index: Some(attempt_index),
+                    retry_index: Some(retry_index),
+                    session_reuse,
+                    error_category: None,
+                    error_code: None,
+                    decision: None,
+                    reason: None,
+                    selection_method: None,
+                    reason_code: None,
+                    attempt_started_ms: Some(attempt_started_ms),
+                    attempt_duration_ms: None,
+                    circuit_state_before: Some(circuit_before.state.as_str()),
+                    circuit_state_after: None,
+                    circuit_failure_count: Some(circuit_before.failure_count),
+                    circuit_failure_threshold: Some(circuit_before.failure_threshold),
+                    error_catalog_code: None,
+                    response_headers: None,
+                    error_body: None,
+                    retry_backoff_ms: None,
+                    stream_stats: None,
+                },
+                attempt_started,
+            );
+            // Truncated Anthropic message streams may be resumed with a prefilled re-request.
+            let mut stitch_retry = (input.stream_truncation_recovery_enabled
+                && input.cli_key == "claude"
+                && upstream_forwarded_path.trim_end_matches('/') == "/v1/messages"
+                && !headers.contains_key(header::CONTENT_ENCODING))
+            .then(|| StreamStitchRetry {
+                client: input.state.client.clone(),
+                method: method.clone(),
+                url: url.clone(),
+                headers: headers.clone(),
+                body: upstream_body_bytes.clone(),
+            });
+            let dns_host = input.state.dns.bind(provider_id, &url);
+            let live_attempt = LiveAttemptGuard::begin(
+                &input.state.live_stats,
+                provider_id,
+                &input.cli_key,
+                &provider_name_base,
+            );
+            let (send_result, race_side) = match race_partner_url.clone() {
+                Some(partner_url) => {
+                    input.state.dns.bind(provider_id, &partner_url);
+                    let (result, side) = send::send_upstream_race(
+                        ctx,
+                        method.clone(),
+                        url,
+                        partner_url,
+                        headers,
+                        upstream_body_bytes.clone(),
+                    )
+                    .await;
+                    (result, Some(side))
+                }
+                None => (
+                    send::send_upstream(
+                        ctx,
+                        method.clone(),
+                        url,
+                        headers,
+                        upstream_body_bytes.clone(),
+                    )
+                    .await,
+                    None,
+                ),
+            };
+            if let Some(resolution) = dns_host
+                .as_deref()
+                .and_then(|host| input.state.dns.take_resolution(host, attempt_started))
+            {
+                let diagnostics = resolution.special_setting(retry_index);
+                tracing::debug!(trace_id = %input.trace_id, dns = %diagnostics, "upstream dns diagnostics");
+                input.special_settings.lock_or_recover().push(diagnostics);
+            }
+
+            // Attribute the attempt to whichever raced base_url served it.
+            let partner_served_base_url = match (race_side, race_partner_base.as_ref()) {
+                (Some(side), Some(partner_base)) => {
+                    let served = match side {
+                        send::RaceSide::Primary => &provider_base_url_base,
+                        send::RaceSide::Partner => partner_base,
+                    };
+                    input
+                        .special_settings
+                        .lock_or_recover()
+                        .push(serde_json::json!({
+                            "type": "base_url_race",
+                            "scope": "attempt",
+                            "hit": true,
+                            "providerId": provider_id,
+                            "providerName": provider_name_base.clone(),
+                            "retryIndex": retry_index,
+                            "candidates": [provider_base_url_base.clone(), partner_base.clone()],
+                            "servedBaseUrl": served.clone(),
+                        }));
+                    (side == send::RaceSide::Partner).then(|| partner_base.clone())
+                }
+                _ => None,
+            };
+            let provider_ctx = match partner_served_base_url.as_ref() {
+                Some(base_url) => ProviderCtx {
+                    provider_base_url_base: base_url,
+                    ..provider_ctx
+                },
+                None => provider_ctx,
+            };
+            if let (Some(retry), Some(partner_url)) = (
+                stitch_retry.as_mut(),
+                race_partner_url.filter(|_| partner_served_base_url.is_some()),
+            ) {
+                retry.url = partner_url;
+            }
 
             match send_result {
                 send::SendResult::Ok(resp) => {
                     let status = resp.status();
                     let response_headers = resp.headers().clone();
+                    if provider.auth_mode == "oauth" {
+                        oauth_pacing::observe(
+                            provider_id,
+                            &input.cli_key,
+                            &provider_name_base,
+                            &response_headers,
+                            now_unix_millis() as i64,
+                        );
+                    }
 
                     if status.is_success() {
+                        live_attempt.record_ttft(attempt_started.elapsed());
                         if is_event_stream(&response_headers) {
                             let loop_state = LoopState::new(
                                 &mut attempts,
@@ -1106,6 +1542,8 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
                                 resp,
                                 status,
                                 response_headers,
+                                stitch_retry,
+                                live_attempt,
                             )
                             .await
                             {
@@ -1280,6 +1718,8 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
             skipped_open,
             skipped_cooldown,
             skipped_limits,
+            skipped_maintenance,
+            skipped_model,
             fingerprint_key: input.fingerprint_key,
             fingerprint_debug: input.fingerprint_debug.clone(),
             unavailable_fingerprint_key: input.unavailable_fingerprint_key,
@@ -1307,6 +1747,7 @@ pub(super) async fn run(mut input: RequestContext) -> Response {
         requested_model: owned.requested_model,
         special_settings: owned.special_settings,
         verbose_provider_error: input.verbose_provider_error,
+        request_bytes: input.body_bytes.len() as u64,
     })
     .await
 }
@@ -1,4 +1,4 @@
-//! Usage: Provider gating helpers (circuit allow/skip + event emission).
+//! Usage: Provider gating helpers (maintenance/circuit allow/skip + event emission).
 
 use super::super::super::provider_router;
 use super::context::CommonCtx;
@@ -10,9 +10,11 @@ pub(super) struct ProviderGateInput<'a> {
     pub(super) provider_id: i64,
     pub(super) provider_name_base: &'a String,
     pub(super) provider_base_url_display: &'a String,
+    pub(super) maintenance_until: Option<i64>,
     pub(super) earliest_available_unix: &'a mut Option<i64>,
     pub(super) skipped_open: &'a mut usize,
     pub(super) skipped_cooldown: &'a mut usize,
+    pub(super) skipped_maintenance: &'a mut usize,
 }
 
 pub(super) struct ProviderGateAllow {
@@ -25,12 +27,22 @@ pub(super) fn gate_provider(input: ProviderGateInput<'_>) -> Option<ProviderGate
         provider_id,
         provider_name_base,
         provider_base_url_display,
+        maintenance_until,
         earliest_available_unix,
         skipped_open,
         skipped_cooldown,
+        skipped_maintenance,
     } = input;
 
     let now_unix = now_unix_seconds() as i64;
+    if let Some(until) = maintenance_until.filter(|t| *t > now_unix) {
+        *skipped_maintenance += 1;
+        if earliest_available_unix.map(|t| until < t).unwrap_or(true) {
+            *earliest_available_unix = Some(until);
+        }
+        return None;
+    }
+
     provider_router::gate_provider(provider_router::GateProviderArgs {
         app: Some(&ctx.state.app),
         circuit: ctx.state.circuit.as_ref(),
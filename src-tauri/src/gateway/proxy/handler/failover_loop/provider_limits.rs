@@ -1,8 +1,10 @@
-//! Usage: Provider spend-limit gating (5h/daily/weekly/monthly/total).
+//! Usage: Provider spend-limit gating (5h/daily/weekly/monthly/total/monthly budget).
 
 use super::context::CommonCtx;
-use crate::providers;
+use crate::provider_limit_usage::{self, BudgetNotice};
 use crate::shared::error::db_err;
+use crate::shared::i18n;
+use crate::{notice, providers};
 use rusqlite::{params, Connection};
 
 pub(super) struct ProviderLimitsInput<'a> {
@@ -12,6 +14,38 @@ pub(super) struct ProviderLimitsInput<'a> {
     pub(super) skipped_limits: &'a mut usize,
 }
 
+/// Why the gate skipped a provider: every limit window that is exhausted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct LimitSkip {
+    pub(super) windows: Vec<&'static str>,
+}
+
+impl LimitSkip {
+    pub(super) fn budget_exhausted(&self) -> bool {
+        self.windows.contains(&LIMIT_MONTHLY_BUDGET)
+    }
+
+    pub(super) fn reason(&self) -> String {
+        if self.budget_exhausted() {
+            return format!(
+                "provider skipped by monthly budget ({})",
+                self.windows.join(", ")
+            );
+        }
+        format!(
+            "provider skipped by rate limit ({})",
+            self.windows.join(", ")
+        )
+    }
+}
+
+const LIMIT_5H: &str = "5h";
+const LIMIT_DAILY: &str = "daily";
+const LIMIT_WEEKLY: &str = "weekly";
+const LIMIT_MONTHLY: &str = "monthly";
+const LIMIT_TOTAL: &str = "total";
+const LIMIT_MONTHLY_BUDGET: &str = "monthly_budget";
+
 const USD_FEMTO_DENOM: f64 = 1_000_000_000_000_000.0;
 const WINDOW_5H_SECS: i64 = 5 * 60 * 60;
 const WINDOW_24H_SECS: i64 = 24 * 60 * 60;
@@ -71,6 +105,106 @@ fn has_any_limit(provider: &providers::ProviderForGateway) -> bool {
         || provider.limit_weekly_usd.is_some()
         || provider.limit_monthly_usd.is_some()
         || provider.limit_total_usd.is_some()
+        || provider.monthly_budget_usd.is_some()
+}
+
+fn budget_warn_reached(effective_budget_usd: f64, spent_femto: i64) -> bool {
+    if effective_budget_usd <= 0.0 {
+        return false;
+    }
+    limit_exceeded(
+        effective_budget_usd * provider_limit_usage::MONTHLY_BUDGET_WARN_RATIO,
+        spent_femto,
+    )
+}
+
+fn emit_budget_notice(
+    app: &crate::AppHandle,
+    cli_key: &str,
+    provider: &providers::ProviderForGateway,
+    month: &str,
+    kind: BudgetNotice,
+    effective_budget_usd: f64,
+    spent_femto: i64,
+) {
+    let spent_usd = (spent_femto.max(0) as f64) / USD_FEMTO_DENOM;
+    let (level, title_key, action_key) = match kind {
+        BudgetNotice::Warn80 => (
+            notice::NoticeLevel::Warning,
+            "notice.budget.title_warn",
+            "notice.budget.action_warn",
+        ),
+        BudgetNotice::Exhausted100 => (
+            notice::NoticeLevel::Error,
+            "notice.budget.title_exhausted",
+            "notice.budget.action_exhausted",
+        ),
+    };
+    let title = i18n::format(title_key, &[("name", &provider.name)]);
+    let lines = [
+        i18n::format("notice.line.cli", &[("cli_key", &cli_key)]),
+        i18n::format(
+            "notice.line.provider",
+            &[("name", &provider.name), ("id", &provider.id)],
+        ),
+        i18n::format("notice.budget.line_month", &[("month", &month)]),
+        i18n::format(
+            "notice.budget.line_spent",
+            &[
+                ("spent", &format!("{spent_usd:.2}")),
+                ("budget", &format!("{effective_budget_usd:.2}")),
+            ],
+        ),
+        i18n::text(action_key).to_string(),
+    ];
+    let payload = notice::build(level, Some(title), lines.join("\n"));
+    crate::notification_sinks::dispatch(
+        crate::settings::NotificationEventKind::BudgetAlert,
+        &payload,
+    );
+    if let Err(err) = notice::emit(app, payload) {
+        tracing::warn!("failed to emit provider budget notice: {}", err);
+    }
+}
+
+/// Hard calendar-month budget check (budget + this month's grace). Fires the 80%/100% notices
+/// once per month and fails open on db errors.
+fn monthly_budget_exhausted(
+    conn: &Connection,
+    ctx: CommonCtx<'_>,
+    provider: &providers::ProviderForGateway,
+    budget_usd: f64,
+    spent_femto: i64,
+    now_unix: i64,
+) -> bool {
+    let Ok(month) = provider_limit_usage::month_key(conn, now_unix) else {
+        return false;
+    };
+    let grace_usd = provider_limit_usage::month_grace_usd(conn, provider.id, &month).unwrap_or(0.0);
+    let effective_budget_usd = budget_usd + grace_usd;
+
+    let kind = if limit_exceeded(effective_budget_usd, spent_femto) {
+        BudgetNotice::Exhausted100
+    } else if budget_warn_reached(effective_budget_usd, spent_femto) {
+        BudgetNotice::Warn80
+    } else {
+        return false;
+    };
+
+    if let Ok(true) =
+        provider_limit_usage::claim_budget_notice(conn, provider.id, &month, kind, now_unix)
+    {
+        emit_budget_notice(
+            &ctx.state.app,
+            ctx.cli_key,
+            provider,
+            &month,
+            kind,
+            effective_budget_usd,
+            spent_femto,
+        );
+    }
+    kind == BudgetNotice::Exhausted100
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -355,7 +489,9 @@ fn resolve_fixed_5h_start(
     Ok(now_unix)
 }
 
-pub(super) fn gate_provider(input: ProviderLimitsInput<'_>) -> bool {
+/// `Ok` lets the provider through (also on db errors: fail open); `Err` names the exhausted
+/// limit windows.
+pub(super) fn gate_provider(input: ProviderLimitsInput<'_>) -> Result<(), LimitSkip> {
     let ProviderLimitsInput {
         ctx,
         provider,
@@ -364,12 +500,12 @@ pub(super) fn gate_provider(input: ProviderLimitsInput<'_>) -> bool {
     } = input;
 
     if !has_any_limit(provider) {
-        return true;
+        return Ok(());
     }
 
     let conn = match ctx.state.db.open_connection() {
         Ok(conn) => conn,
-        Err(_) => return true,
+        Err(_) => return Ok(()),
     };
 
     let now_unix = ctx.created_at;
@@ -379,7 +515,7 @@ pub(super) fn gate_provider(input: ProviderLimitsInput<'_>) -> bool {
     let start_5h = if provider.limit_5h_usd.is_some() {
         match resolve_fixed_5h_start(&conn, provider.id, now_unix) {
             Ok(ts) => Some(ts),
-            Err(_) => return true,
+            Err(_) => return Ok(()),
         }
     } else {
         None
@@ -397,7 +533,7 @@ pub(super) fn gate_provider(input: ProviderLimitsInput<'_>) -> bool {
                     provider.daily_reset_time.as_str(),
                 ) {
                     Ok(v) => v,
-                    Err(_) => return true,
+                    Err(_) => return Ok(()),
                 };
                 (None, Some(start), Some(next))
             }
@@ -407,20 +543,21 @@ pub(super) fn gate_provider(input: ProviderLimitsInput<'_>) -> bool {
     let (start_weekly, next_weekly) = if provider.limit_weekly_usd.is_some() {
         match compute_weekly_bounds(&conn, now_unix) {
             Ok((start, next)) => (Some(start), Some(next)),
-            Err(_) => return true,
+            Err(_) => return Ok(()),
         }
     } else {
         (None, None)
     };
 
-    let (start_monthly, next_monthly) = if provider.limit_monthly_usd.is_some() {
-        match compute_monthly_bounds(&conn, now_unix) {
-            Ok((start, next)) => (Some(start), Some(next)),
-            Err(_) => return true,
-        }
-    } else {
-        (None, None)
-    };
+    let (start_monthly, next_monthly) =
+        if provider.limit_monthly_usd.is_some() || provider.monthly_budget_usd.is_some() {
+            match compute_monthly_bounds(&conn, now_unix) {
+                Ok((start, next)) => (Some(start), Some(next)),
+                Err(_) => return Ok(()),
+            }
+        } else {
+            (None, None)
+        };
 
     let needs_total = provider.limit_total_usd.is_some();
     let min_start = if needs_total {
@@ -449,17 +586,17 @@ pub(super) fn gate_provider(input: ProviderLimitsInput<'_>) -> bool {
         },
     ) {
         Ok(v) => v,
-        Err(_) => return true,
+        Err(_) => return Ok(()),
     };
 
-    let mut exceeded = false;
+    let mut windows: Vec<&'static str> = Vec::new();
     let mut provider_next_available: Option<i64> = None;
     let mut need_rolling_5h = false;
     let mut need_rolling_daily = false;
 
     if let Some(limit) = provider.limit_5h_usd {
         if limit_exceeded(limit, sums.spent_5h) {
-            exceeded = true;
+            windows.push(LIMIT_5H);
             need_rolling_5h = true;
         }
     }
@@ -468,13 +605,13 @@ pub(super) fn gate_provider(input: ProviderLimitsInput<'_>) -> bool {
         match provider.daily_reset_mode {
             providers::DailyResetMode::Rolling => {
                 if limit_exceeded(limit, sums.spent_daily_rolling) {
-                    exceeded = true;
+                    windows.push(LIMIT_DAILY);
                     need_rolling_daily = true;
                 }
             }
             providers::DailyResetMode::Fixed => {
                 if limit_exceeded(limit, sums.spent_daily_fixed) {
-                    exceeded = true;
+                    windows.push(LIMIT_DAILY);
                     if let Some(next_reset) = next_daily_fixed {
                         update_latest(&mut provider_next_available, next_reset);
                     }
@@ -485,7 +622,7 @@ pub(super) fn gate_provider(input: ProviderLimitsInput<'_>) -> bool {
 
     if let Some(limit) = provider.limit_weekly_usd {
         if limit_exceeded(limit, sums.spent_weekly) {
-            exceeded = true;
+            windows.push(LIMIT_WEEKLY);
             if let Some(next_reset) = next_weekly {
                 update_latest(&mut provider_next_available, next_reset);
             }
@@ -494,7 +631,7 @@ pub(super) fn gate_provider(input: ProviderLimitsInput<'_>) -> bool {
 
     if let Some(limit) = provider.limit_monthly_usd {
         if limit_exceeded(limit, sums.spent_monthly) {
-            exceeded = true;
+            windows.push(LIMIT_MONTHLY);
             if let Some(next_reset) = next_monthly {
                 update_latest(&mut provider_next_available, next_reset);
             }
@@ -503,12 +640,28 @@ pub(super) fn gate_provider(input: ProviderLimitsInput<'_>) -> bool {
 
     if let Some(limit) = provider.limit_total_usd {
         if limit_exceeded(limit, sums.spent_total) {
-            exceeded = true;
+            windows.push(LIMIT_TOTAL);
         }
     }
 
-    if !exceeded {
-        return true;
+    if let Some(budget_usd) = provider.monthly_budget_usd {
+        if monthly_budget_exhausted(
+            &conn,
+            ctx,
+            provider,
+            budget_usd,
+            sums.spent_monthly,
+            now_unix,
+        ) {
+            windows.push(LIMIT_MONTHLY_BUDGET);
+            if let Some(next_reset) = next_monthly {
+                update_latest(&mut provider_next_available, next_reset);
+            }
+        }
+    }
+
+    if windows.is_empty() {
+        return Ok(());
     }
 
     if need_rolling_5h || need_rolling_daily {
@@ -566,7 +719,7 @@ pub(super) fn gate_provider(input: ProviderLimitsInput<'_>) -> bool {
     if let Some(next) = provider_next_available {
         update_earliest(earliest_available_unix, next);
     }
-    false
+    Err(LimitSkip { windows })
 }
 
 #[cfg(test)]
@@ -782,6 +935,32 @@ mod tests {
         assert_eq!(parse_reset_time_hms_lossy(""), (0, 0, 0));
     }
 
+    #[test]
+    fn budget_warn_reached_at_80_percent() {
+        let femto_per_usd = USD_FEMTO_DENOM as i64;
+        assert!(!budget_warn_reached(10.0, 7 * femto_per_usd));
+        assert!(budget_warn_reached(10.0, 8 * femto_per_usd));
+        assert!(!budget_warn_reached(0.0, femto_per_usd));
+    }
+
+    #[test]
+    fn limit_skip_reason_names_exhausted_windows() {
+        let skip = LimitSkip {
+            windows: vec![LIMIT_DAILY, LIMIT_MONTHLY_BUDGET],
+        };
+        assert!(skip.budget_exhausted());
+        assert_eq!(
+            skip.reason(),
+            "provider skipped by monthly budget (daily, monthly_budget)"
+        );
+
+        let skip = LimitSkip {
+            windows: vec![LIMIT_5H],
+        };
+        assert!(!skip.budget_exhausted());
+        assert_eq!(skip.reason(), "provider skipped by rate limit (5h)");
+    }
+
     #[test]
     fn min_start_ts_returns_minimum() {
         assert_eq!(min_start_ts(&[Some(100), Some(50), Some(200)]), Some(50));
@@ -0,0 +1,93 @@
+//! Usage: Fail over when a provider answers 2xx with one of its configured refusal phrases.
+
+use super::*;
+use crate::gateway::refusal_detector::MAX_REFUSAL_FAILOVERS;
+
+/// Checks only run with patterns configured and while the per-request failover cap is unspent.
+pub(super) fn should_check(patterns: &[String], attempts: &[FailoverAttempt]) -> bool {
+    let refusals = attempts
+        .iter()
+        .filter(|attempt| attempt.error_code == Some(GatewayErrorCode::ContentRefusal.as_str()))
+        .count();
+    !patterns.is_empty() && refusals < MAX_REFUSAL_FAILOVERS
+}
+
+/// Records the refusal on the attempt and moves on to the next provider (no cooldown: the
+/// relay itself is healthy).
+pub(super) async fn fail_over(
+    ctx: CommonCtx<'_>,
+    provider_ctx: ProviderCtx<'_>,
+    attempt_ctx: AttemptCtx<'_>,
+    loop_state: LoopState<'_>,
+    status: StatusCode,
+    pattern: &str,
+) -> LoopControl {
+    let error_code = GatewayErrorCode::ContentRefusal.as_str();
+    let decision = FailoverDecision::SwitchProvider;
+    let outcome = format!(
+        "content_refusal: category={} code={} decision={}",
+        ErrorCategory::SystemError.as_str(),
+        error_code,
+        decision.as_str(),
+    );
+
+    record_system_failure_and_decide_no_cooldown(RecordSystemFailureArgs {
+        ctx,
+        provider_ctx,
+        attempt_ctx,
+        loop_state,
+        status: Some(status.as_u16()),
+        error_code,
+        decision,
+        outcome,
+        reason: format!("response matched refusal pattern {pattern:?}"),
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(error_code: Option<&'static str>) -> FailoverAttempt {
+        FailoverAttempt {
+            provider_id: 1,
+            provider_name: "p".to_string(),
+            base_url: "https://example.com".to_string(),
+            outcome: "x".to_string(),
+            status: Some(200),
+            provider_index: Some(1),
+            retry_index: Some(1),
+            session_reuse: None,
+            error_category: None,
+            error_code,
+            decision: None,
+            reason: None,
+            selection_method: None,
+            reason_code: None,
+            attempt_started_ms: None,
+            attempt_duration_ms: None,
+            circuit_state_before: None,
+            circuit_state_after: None,
+            circuit_failure_count: None,
+            circuit_failure_threshold: None,
+            error_catalog_code: None,
+            response_headers: None,
+            error_body: None,
+            retry_backoff_ms: None,
+            stream_stats: None,
+        }
+    }
+
+    #[test]
+    fn refusal_checks_stop_after_the_cap() {
+        let patterns = vec!["I can't help".to_string()];
+        let refusal = Some(GatewayErrorCode::ContentRefusal.as_str());
+        assert!(!should_check(&[], &[]));
+        assert!(should_check(&patterns, &[attempt(None), attempt(refusal)]));
+        let spent: Vec<FailoverAttempt> = (0..MAX_REFUSAL_FAILOVERS)
+            .map(|_| attempt(refusal))
+            .collect();
+        assert!(!should_check(&patterns, &spent));
+    }
+}
@@ -1,6 +1,8 @@
-//! Usage: Upstream request sending helpers (first-byte timeout aware).
+//! Usage: Upstream request sending helpers (first-byte timeout aware, base_url racing).
 
 use super::context::CommonCtx;
+use crate::gateway::util::now_unix_seconds;
+use crate::shared::mutex_ext::MutexExt;
 use axum::body::Bytes;
 use axum::http::{HeaderMap, Method};
 
@@ -17,6 +19,11 @@ pub(super) async fn send_upstream(
     headers: HeaderMap,
     body: Bytes,
 ) -> SendResult {
+    ctx.state
+        .warm_pool
+        .lock_or_recover()
+        .mark_used(&url, now_unix_seconds() as i64);
+
     let send = ctx
         .state
         .client
@@ -38,3 +45,53 @@ pub(super) async fn send_upstream(
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum RaceSide {
+    Primary,
+    Partner,
+}
+
+/// Orders raced results: a usable response beats a 5xx, which beats a transport failure.
+fn race_rank(result: &SendResult) -> u8 {
+    match result {
+        SendResult::Ok(resp) if !resp.status().is_server_error() => 2,
+        SendResult::Ok(_) => 1,
+        SendResult::Err(_) | SendResult::Timeout => 0,
+    }
+}
+
+/// Sends the same request to `primary` and `partner` at once. The first usable response wins
+/// and the other request is dropped; otherwise both are awaited and the better result is kept
+/// (the primary's on a tie).
+pub(super) async fn send_upstream_race(
+    ctx: CommonCtx<'_>,
+    method: Method,
+    primary: reqwest::Url,
+    partner: reqwest::Url,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (SendResult, RaceSide) {
+    let primary_fut = send_upstream(ctx, method.clone(), primary, headers.clone(), body.clone());
+    let partner_fut = send_upstream(ctx, method, partner, headers, body);
+    tokio::pin!(primary_fut);
+    tokio::pin!(partner_fut);
+
+    let (first, first_side) = tokio::select! {
+        res = &mut primary_fut => (res, RaceSide::Primary),
+        res = &mut partner_fut => (res, RaceSide::Partner),
+    };
+    if race_rank(&first) == 2 {
+        return (first, first_side);
+    }
+
+    let (primary, partner) = match first_side {
+        RaceSide::Primary => (first, partner_fut.await),
+        RaceSide::Partner => (primary_fut.await, first),
+    };
+    if race_rank(&partner) > race_rank(&primary) {
+        (partner, RaceSide::Partner)
+    } else {
+        (primary, RaceSide::Primary)
+    }
+}
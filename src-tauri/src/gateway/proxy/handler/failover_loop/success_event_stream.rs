@@ -2,6 +2,7 @@
 
 use super::super::super::gemini_oauth;
 use super::*;
+use crate::gateway::refusal_detector;
 
 #[allow(clippy::too_many_arguments)]
 pub(super) async fn handle_success_event_stream(
@@ -12,6 +13,8 @@ pub(super) async fn handle_success_event_stream(
     resp: reqwest::Response,
     status: StatusCode,
     mut response_headers: HeaderMap,
+    stitch_retry: Option<StreamStitchRetry>,
+    mut live_attempt: LiveAttemptGuard,
 ) -> LoopControl {
     let common = CommonCtxOwned::from(ctx);
     let provider_ctx_owned = ProviderCtxOwned::from(provider_ctx);
@@ -77,6 +80,14 @@ pub(super) async fn handle_success_event_stream(
                     }
                 }
             }
+            // Still wait for the first chunk so failures before any output can fail over.
+            None if common.stream_failure.first_byte_failover => match resp.chunk().await {
+                Ok(Some(chunk)) => {
+                    FirstChunkProbe::Ok(Some(chunk), Some(started.elapsed().as_millis()))
+                }
+                Ok(None) => FirstChunkProbe::Ok(None, None),
+                Err(err) => FirstChunkProbe::ReadError(err),
+            },
             None => FirstChunkProbe::Skipped,
         };
         let probe_is_empty_event_stream = matches!(probe, FirstChunkProbe::Ok(None, None));
@@ -164,11 +175,81 @@ pub(super) async fn handle_success_event_stream(
             FirstChunkProbe::Skipped => {}
         }
 
-        if upstream_first_byte_timeout.is_some()
-            && first_chunk.is_none()
-            && initial_first_byte_ms.is_none()
-            && probe_is_empty_event_stream
+        if (200..300).contains(&status.as_u16())
+            && refusal_failover::should_check(&provider_ctx_owned.refusal_patterns, attempts)
         {
+            match refusal_detector::peek_event_stream(&mut resp, first_chunk.take()).await {
+                Ok(peeked) => {
+                    if peeked.is_some() && initial_first_byte_ms.is_none() {
+                        initial_first_byte_ms = Some(started.elapsed().as_millis());
+                    }
+                    let text = peeked
+                        .as_deref()
+                        .map(refusal_detector::event_stream_text)
+                        .unwrap_or_default();
+                    if let Some(pattern) =
+                        refusal_detector::find_refusal(&provider_ctx_owned.refusal_patterns, &text)
+                    {
+                        return refusal_failover::fail_over(
+                            ctx,
+                            provider_ctx,
+                            attempt_ctx,
+                            LoopState {
+                                attempts,
+                                failed_provider_ids,
+                                last_error_category,
+                                last_error_code,
+                                circuit_snapshot,
+                                abort_guard,
+                            },
+                            status,
+                            pattern,
+                        )
+                        .await;
+                    }
+                    first_chunk = peeked;
+                }
+                Err(err) => {
+                    let error_code = GatewayErrorCode::StreamError.as_str();
+                    let decision = if retry_index < max_attempts_per_provider {
+                        FailoverDecision::RetrySameProvider
+                    } else {
+                        FailoverDecision::SwitchProvider
+                    };
+
+                    let outcome = format!(
+                        "stream_refusal_peek_error: category={} code={} decision={}",
+                        ErrorCategory::SystemError.as_str(),
+                        error_code,
+                        decision.as_str(),
+                    );
+
+                    return record_system_failure_and_decide(RecordSystemFailureArgs {
+                        ctx,
+                        provider_ctx,
+                        attempt_ctx,
+                        loop_state: LoopState {
+                            attempts,
+                            failed_provider_ids,
+                            last_error_category,
+                            last_error_code,
+                            circuit_snapshot,
+                            abort_guard,
+                        },
+                        status: Some(status.as_u16()),
+                        error_code,
+                        decision,
+                        outcome,
+                        reason: format!(
+                            "read error while checking for a refusal (event-stream): {err}"
+                        ),
+                    })
+                    .await;
+                }
+            }
+        }
+
+        if first_chunk.is_none() && initial_first_byte_ms.is_none() && probe_is_empty_event_stream {
             let error_code = GatewayErrorCode::StreamError.as_str();
             let decision = if retry_index < max_attempts_per_provider {
                 FailoverDecision::RetrySameProvider
@@ -228,6 +309,11 @@ pub(super) async fn handle_success_event_stream(
             circuit_state_after: None,
             circuit_failure_count: Some(circuit_before.failure_count),
             circuit_failure_threshold: Some(circuit_before.failure_threshold),
+            error_catalog_code: None,
+            response_headers: crate::gateway::response_headers::capture(&response_headers),
+            error_body: None,
+            retry_backoff_ms: None,
+            stream_stats: None,
         });
 
         emit_attempt_event_and_log_with_circuit_before(
@@ -239,7 +325,7 @@ pub(super) async fn handle_success_event_stream(
         )
         .await;
 
-        let ctx = build_stream_finalize_ctx(
+        let mut ctx = build_stream_finalize_ctx(
             &common,
             &provider_ctx_owned,
             attempts.as_slice(),
@@ -247,6 +333,9 @@ pub(super) async fn handle_success_event_stream(
             None,
             None,
         );
+        live_attempt.start_stream();
+        ctx.live_attempt = Some(live_attempt);
+        let net_stats = ctx.net_stats.clone();
 
         let should_gunzip = has_gzip_content_encoding(&response_headers);
         if should_gunzip {
@@ -266,18 +355,32 @@ pub(super) async fn handle_success_event_stream(
             );
         }
 
-        let use_sse_relay = common.cli_key == "codex"
-            && matches!(
-                common.forwarded_path.trim_end_matches('/'),
-                "/v1/responses" | "/responses"
-            );
+        let is_responses_path = matches!(
+            common.forwarded_path.trim_end_matches('/'),
+            "/v1/responses" | "/responses"
+        );
+        let use_sse_relay = common.cli_key == "codex" && is_responses_path;
+        let response_fixer_stream_config = response_fixer::ResponseFixerConfig {
+            fix_responses_events: response_fixer_stream_config.fix_sse_format && is_responses_path,
+            ..response_fixer_stream_config
+        };
+
+        let error_event = common
+            .stream_failure
+            .error_event
+            .then(|| sse_error_event(&common.cli_key, &common.forwarded_path, &common.trace_id));
 
         let body = match (enable_response_fixer_for_this_response, should_gunzip) {
             (true, true) => {
-                let upstream =
-                    GunzipStream::new(FirstChunkStream::new(first_chunk, resp.bytes_stream()));
+                let upstream = GunzipStream::new(FirstChunkStream::new(
+                    first_chunk,
+                    resp.bytes_stream(),
+                    net_stats,
+                ));
                 let upstream =
                     gemini_oauth::GeminiOAuthSseStream::new(upstream, gemini_oauth_response_mode);
+                let upstream =
+                    StitchingStream::new(upstream, stitch_retry, common.special_settings.clone());
                 let upstream = response_fixer::ResponseFixerStream::new(
                     upstream,
                     response_fixer_stream_config,
@@ -289,6 +392,9 @@ pub(super) async fn handle_success_event_stream(
                         ctx,
                         upstream_stream_idle_timeout,
                         initial_first_byte_ms,
+                        common.sse_coalesce,
+                        common.stream_stall,
+                        error_event,
                     )
                 } else {
                     let stream = UsageSseTeeStream::new(
@@ -297,13 +403,15 @@ pub(super) async fn handle_success_event_stream(
                         upstream_stream_idle_timeout,
                         initial_first_byte_ms,
                     );
-                    Body::from_stream(stream)
+                    client_sse_body(stream, error_event)
                 }
             }
             (true, false) => {
-                let upstream = FirstChunkStream::new(first_chunk, resp.bytes_stream());
+                let upstream = FirstChunkStream::new(first_chunk, resp.bytes_stream(), net_stats);
                 let upstream =
                     gemini_oauth::GeminiOAuthSseStream::new(upstream, gemini_oauth_response_mode);
+                let upstream =
+                    StitchingStream::new(upstream, stitch_retry, common.special_settings.clone());
                 let upstream = response_fixer::ResponseFixerStream::new(
                     upstream,
                     response_fixer_stream_config,
@@ -315,6 +423,9 @@ pub(super) async fn handle_success_event_stream(
                         ctx,
                         upstream_stream_idle_timeout,
                         initial_first_byte_ms,
+                        common.sse_coalesce,
+                        common.stream_stall,
+                        error_event,
                     )
                 } else {
                     let stream = UsageSseTeeStream::new(
@@ -323,20 +434,28 @@ pub(super) async fn handle_success_event_stream(
                         upstream_stream_idle_timeout,
                         initial_first_byte_ms,
                     );
-                    Body::from_stream(stream)
+                    client_sse_body(stream, error_event)
                 }
             }
             (false, true) => {
-                let upstream =
-                    GunzipStream::new(FirstChunkStream::new(first_chunk, resp.bytes_stream()));
+                let upstream = GunzipStream::new(FirstChunkStream::new(
+                    first_chunk,
+                    resp.bytes_stream(),
+                    net_stats,
+                ));
                 let upstream =
                     gemini_oauth::GeminiOAuthSseStream::new(upstream, gemini_oauth_response_mode);
+                let upstream =
+                    StitchingStream::new(upstream, stitch_retry, common.special_settings.clone());
                 if use_sse_relay {
                     spawn_usage_sse_relay_body(
                         upstream,
                         ctx,
                         upstream_stream_idle_timeout,
                         initial_first_byte_ms,
+                        common.sse_coalesce,
+                        common.stream_stall,
+                        error_event,
                     )
                 } else {
                     let stream = UsageSseTeeStream::new(
@@ -345,19 +464,24 @@ pub(super) async fn handle_success_event_stream(
                         upstream_stream_idle_timeout,
                         initial_first_byte_ms,
                     );
-                    Body::from_stream(stream)
+                    client_sse_body(stream, error_event)
                 }
             }
             (false, false) => {
-                let upstream = FirstChunkStream::new(first_chunk, resp.bytes_stream());
+                let upstream = FirstChunkStream::new(first_chunk, resp.bytes_stream(), net_stats);
                 let upstream =
                     gemini_oauth::GeminiOAuthSseStream::new(upstream, gemini_oauth_response_mode);
+                let upstream =
+                    StitchingStream::new(upstream, stitch_retry, common.special_settings.clone());
                 if use_sse_relay {
                     spawn_usage_sse_relay_body(
                         upstream,
                         ctx,
                         upstream_stream_idle_timeout,
                         initial_first_byte_ms,
+                        common.sse_coalesce,
+                        common.stream_stall,
+                        error_event,
                     )
                 } else {
                     let stream = UsageSseTeeStream::new(
@@ -366,7 +490,7 @@ pub(super) async fn handle_success_event_stream(
                         upstream_stream_idle_timeout,
                         initial_first_byte_ms,
                     );
-                    Body::from_stream(stream)
+                    client_sse_body(stream, error_event)
                 }
             }
         };
@@ -1,7 +1,8 @@
 //! Usage: Handle successful non-SSE upstream responses inside `failover_loop::run`.
 
-use super::super::super::{gemini_oauth, provider_router, GatewayErrorCode};
+use super::super::super::{gemini_oauth, provider_router, CallCostEstimate, GatewayErrorCode};
 use super::*;
+use crate::gateway::refusal_detector;
 use crate::shared::mutex_ext::MutexExt;
 
 #[allow(clippy::too_many_arguments)]
@@ -79,6 +80,11 @@ pub(super) async fn handle_success_non_stream(
                     circuit_state_after: None,
                     circuit_failure_count: Some(circuit_before.failure_count),
                     circuit_failure_threshold: Some(circuit_before.failure_threshold),
+                    error_catalog_code: None,
+                    response_headers: crate::gateway::response_headers::capture(&response_headers),
+                    error_body: None,
+                    retry_backoff_ms: None,
+                    stream_stats: None,
                 });
 
                 emit_attempt_event_and_log_with_circuit_before(
@@ -160,6 +166,11 @@ pub(super) async fn handle_success_non_stream(
                     circuit_state_after: None,
                     circuit_failure_count: Some(circuit_before.failure_count),
                     circuit_failure_threshold: Some(circuit_before.failure_threshold),
+                    error_catalog_code: None,
+                    response_headers: crate::gateway::response_headers::capture(&response_headers),
+                    error_body: None,
+                    retry_backoff_ms: None,
+                    stream_stats: None,
                 });
 
                 emit_attempt_event_and_log_with_circuit_before(
@@ -296,6 +307,38 @@ pub(super) async fn handle_success_non_stream(
         }
     };
 
+    body_bytes = maybe_gunzip_response_body_bytes_with_limit(
+        body_bytes,
+        &mut response_headers,
+        MAX_NON_SSE_BODY_BYTES,
+    );
+
+    if (200..300).contains(&status.as_u16())
+        && refusal_failover::should_check(&provider_ctx_owned.refusal_patterns, attempts)
+    {
+        let text = refusal_detector::json_body_text(&body_bytes);
+        if let Some(pattern) =
+            refusal_detector::find_refusal(&provider_ctx_owned.refusal_patterns, &text)
+        {
+            return refusal_failover::fail_over(
+                ctx,
+                provider_ctx,
+                attempt_ctx,
+                LoopState {
+                    attempts,
+                    failed_provider_ids,
+                    last_error_category,
+                    last_error_code,
+                    circuit_snapshot,
+                    abort_guard,
+                },
+                status,
+                pattern,
+            )
+            .await;
+        }
+    }
+
     let outcome = "success".to_string();
 
     attempts.push(FailoverAttempt {
@@ -319,6 +362,11 @@ pub(super) async fn handle_success_non_stream(
         circuit_state_after: None,
         circuit_failure_count: Some(circuit_before.failure_count),
         circuit_failure_threshold: Some(circuit_before.failure_threshold),
+        error_catalog_code: None,
+        response_headers: crate::gateway::response_headers::capture(&response_headers),
+        error_body: None,
+        retry_backoff_ms: None,
+        stream_stats: None,
     });
 
     emit_attempt_event_and_log_with_circuit_before(
@@ -330,12 +378,6 @@ pub(super) async fn handle_success_non_stream(
     )
     .await;
 
-    body_bytes = maybe_gunzip_response_body_bytes_with_limit(
-        body_bytes,
-        &mut response_headers,
-        MAX_NON_SSE_BODY_BYTES,
-    );
-
     body_bytes = gemini_oauth::translate_response_body(body_bytes, gemini_oauth_response_mode);
     if gemini_oauth_response_mode.is_some() {
         response_headers.remove(header::CONTENT_LENGTH);
@@ -368,6 +410,22 @@ pub(super) async fn handle_success_non_stream(
         }
     });
 
+    if (200..300).contains(&status.as_u16()) {
+        if let Some(metrics) = usage_metrics.clone() {
+            CallCostEstimate::compute(
+                &state.app,
+                &state.db,
+                common.cli_key.as_str(),
+                provider_id,
+                requested_model_for_log.as_deref(),
+                metrics,
+            )
+            .await
+            .insert_headers(&mut response_headers);
+        }
+    }
+
+    let response_bytes = body_bytes.len() as u64;
     let body = Body::from(body_bytes);
     let mut builder = Response::builder().status(status);
     for (k, v) in response_headers.iter() {
@@ -425,7 +483,7 @@ pub(super) async fn handle_success_non_stream(
 
     let duration_ms = started.elapsed().as_millis();
     emit_request_event_and_enqueue_request_log(RequestEndArgs {
-        deps: RequestEndDeps::new(&state.app, &state.db, &state.log_tx),
+        deps: RequestEndDeps::from_state(state),
         trace_id: common.trace_id.as_str(),
         cli_key: common.cli_key.as_str(),
         method: common.method_hint.as_str(),
@@ -447,6 +505,9 @@ pub(super) async fn handle_success_non_stream(
         usage_metrics,
         log_usage_metrics: None,
         usage,
+        request_bytes: Some(common.request_bytes),
+        response_bytes: Some(response_bytes),
+        thinking_budget_tokens: provider_ctx_owned.thinking_budget_tokens,
     })
     .await;
     abort_guard.disarm();
@@ -0,0 +1,77 @@
+//! Usage: Claude extended thinking budget cap for a provider attempt.
+
+use super::context::CommonCtx;
+use crate::gateway::thinking_budget_governor::{
+    govern_anthropic_request_message, requested_budget_tokens,
+};
+use crate::gateway::util::body_for_introspection;
+use crate::providers;
+use crate::shared::mutex_ext::MutexExt;
+use axum::body::Bytes;
+use axum::http::HeaderMap;
+
+pub(super) struct ApplyThinkingBudgetGovernorInput<'a> {
+    pub(super) ctx: CommonCtx<'a>,
+    pub(super) provider: &'a providers::ProviderForGateway,
+    pub(super) introspection_json: Option<&'a serde_json::Value>,
+    pub(super) base_headers: &'a HeaderMap,
+    pub(super) forwarded_path: &'a str,
+    pub(super) upstream_body_bytes: &'a mut Bytes,
+    pub(super) strip_request_content_encoding: &'a mut bool,
+}
+
+/// Returns the thinking budget sent upstream for this attempt (`None` without extended thinking).
+pub(super) fn apply_if_needed(input: ApplyThinkingBudgetGovernorInput<'_>) -> Option<u64> {
+    let ApplyThinkingBudgetGovernorInput {
+        ctx,
+        provider,
+        introspection_json,
+        base_headers,
+        forwarded_path,
+        upstream_body_bytes,
+        strip_request_content_encoding,
+    } = input;
+    if ctx.cli_key != "claude" || forwarded_path != "/v1/messages" {
+        return None;
+    }
+
+    let requested = introspection_json.and_then(requested_budget_tokens)?;
+    let Some(cap) = provider.thinking_budget_cap else {
+        return Some(requested);
+    };
+    if cap != 0 && requested <= u64::from(cap) {
+        return Some(requested);
+    }
+
+    let body_for_parse = if *strip_request_content_encoding {
+        std::borrow::Cow::Borrowed(upstream_body_bytes.as_ref())
+    } else {
+        body_for_introspection(base_headers, upstream_body_bytes.as_ref())
+    };
+    let Ok(mut root) = serde_json::from_slice::<serde_json::Value>(body_for_parse.as_ref()) else {
+        return Some(requested);
+    };
+    let Some(result) = govern_anthropic_request_message(&mut root, cap) else {
+        return Some(requested);
+    };
+    let Ok(encoded) = serde_json::to_vec(&root) else {
+        return Some(requested);
+    };
+    *upstream_body_bytes = Bytes::from(encoded);
+    *strip_request_content_encoding = true;
+
+    let mut settings = ctx.special_settings.lock_or_recover();
+    settings.push(serde_json::json!({
+        "type": "thinking_budget_governor",
+        "scope": "attempt",
+        "hit": true,
+        "applied": true,
+        "providerId": provider.id,
+        "providerName": provider.name.clone(),
+        "action": result.action.as_str(),
+        "cap": cap,
+        "requestedBudgetTokens": result.requested_budget_tokens,
+        "effectiveBudgetTokens": result.effective_budget_tokens,
+    }));
+    result.effective_budget_tokens
+}
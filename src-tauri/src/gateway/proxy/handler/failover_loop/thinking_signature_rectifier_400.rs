@@ -38,6 +38,7 @@ pub(super) async fn handle_thinking_rectifiers_400(
         requested_model,
         special_settings,
         provider_cooldown_secs,
+        retry_backoff,
         max_attempts_per_provider,
         enable_response_fixer,
         response_fixer_non_stream_config,
@@ -50,6 +51,7 @@ pub(super) async fn handle_thinking_rectifiers_400(
         provider_base_url_base,
         provider_index,
         session_reuse,
+        thinking_budget_tokens: _,
     } = ProviderCtxOwned::from(provider_ctx);
 
     let AttemptCtx {
@@ -91,7 +93,7 @@ pub(super) async fn handle_thinking_rectifiers_400(
                     client_attempts,
                 );
                 emit_request_event_and_enqueue_request_log(RequestEndArgs {
-                    deps: RequestEndDeps::new(&state.app, &state.db, &state.log_tx),
+                    deps: RequestEndDeps::from_state(state),
                     trace_id: trace_id.as_str(),
                     cli_key: cli_key.as_str(),
                     method: method_hint.as_str(),
@@ -113,6 +115,9 @@ pub(super) async fn handle_thinking_rectifiers_400(
                     usage_metrics: None,
                     log_usage_metrics: None,
                     usage: None,
+                    request_bytes: Some(ctx.request_bytes),
+                    response_bytes: None,
+                    thinking_budget_tokens: None,
                 })
                 .await;
                 abort_guard.disarm();
@@ -351,6 +356,7 @@ pub(super) async fn handle_thinking_rectifiers_400(
         let selection_method = dc::selection_method(provider_index, retry_index, session_reuse);
         let reason_code = category.reason_code();
 
+        abort_guard.end_attempt();
         attempts.push(FailoverAttempt {
             provider_id,
             provider_name: provider_name_base.clone(),
@@ -372,6 +378,11 @@ pub(super) async fn handle_thinking_rectifiers_400(
             circuit_state_after,
             circuit_failure_count,
             circuit_failure_threshold,
+            error_catalog_code: None,
+            response_headers: crate::gateway::response_headers::capture(&response_headers),
+            error_body: crate::gateway::error_body_capture::capture(body_for_scan.as_ref()),
+            retry_backoff_ms: None,
+            stream_stats: None,
         });
 
         emit_attempt_event_and_log(
@@ -394,9 +405,13 @@ pub(super) async fn handle_thinking_rectifiers_400(
 
         match decision {
             FailoverDecision::RetrySameProvider => {
-                if let Some(delay) = retry_backoff_delay(status, retry_index) {
-                    tokio::time::sleep(delay).await;
-                }
+                wait_retry_backoff(
+                    &retry_backoff,
+                    RetryBackoffCategory::from_status(status),
+                    retry_index,
+                    attempts,
+                )
+                .await;
                 return LoopControl::ContinueRetry;
             }
             FailoverDecision::SwitchProvider => {
@@ -437,7 +452,7 @@ pub(super) async fn handle_thinking_rectifiers_400(
                 let duration_ms = started.elapsed().as_millis();
 
                 emit_request_event_and_enqueue_request_log(RequestEndArgs {
-                    deps: RequestEndDeps::new(&state.app, &state.db, &state.log_tx),
+                    deps: RequestEndDeps::from_state(state),
                     trace_id: trace_id.as_str(),
                     cli_key: cli_key.as_str(),
                     method: method_hint.as_str(),
@@ -459,6 +474,9 @@ pub(super) async fn handle_thinking_rectifiers_400(
                     usage_metrics: None,
                     log_usage_metrics: None,
                     usage: None,
+                    request_bytes: Some(ctx.request_bytes),
+                    response_bytes: Some(body_to_return.len() as u64),
+                    thinking_budget_tokens: None,
                 })
                 .await;
 
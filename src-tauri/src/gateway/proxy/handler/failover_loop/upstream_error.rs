@@ -1,9 +1,9 @@
 //! Usage: Handle upstream non-success responses and reqwest errors inside `failover_loop::run`.
 
 use super::super::super::errors::{
-    classify_reqwest_error, classify_upstream_status, error_response,
+    catalog, classify_reqwest_error, classify_upstream_status, error_response,
 };
-use super::super::super::failover::{retry_backoff_delay, FailoverDecision};
+use super::super::super::failover::{FailoverDecision, RetryBackoffCategory};
 use super::super::super::http_util::{
     build_response, has_gzip_content_encoding, has_non_identity_content_encoding,
     maybe_gunzip_response_body_bytes_with_limit,
@@ -14,7 +14,7 @@ use super::super::super::upstream_client_error_rules;
 use super::super::super::{ErrorCategory, GatewayErrorCode};
 use super::attempt_record::{
     record_system_failure_and_decide, record_system_failure_and_decide_no_cooldown,
-    RecordSystemFailureArgs,
+    wait_retry_backoff, RecordSystemFailureArgs,
 };
 use super::context::{
     AttemptCtx, CommonCtx, CommonCtxOwned, LoopControl, LoopState, ProviderCtx,
@@ -159,6 +159,8 @@ pub(super) async fn handle_non_success_response(
         provider_base_url_base,
         provider_index,
         session_reuse,
+        thinking_budget_tokens: _,
+        refusal_patterns: _,
     } = provider_ctx;
 
     let AttemptCtx {
@@ -192,6 +194,8 @@ pub(super) async fn handle_non_success_response(
     let mut abort_response_headers: Option<axum::http::HeaderMap> = None;
     let mut matched_rule_id: Option<&'static str> = None;
     let mut matched_429_concurrency_limit = false;
+    let mut error_catalog_code: Option<&'static str> = None;
+    let mut captured_body: Option<Bytes> = None;
     if !is_count_tokens
         && (upstream_client_error_rules::should_attempt_non_retryable_match(
             status,
@@ -216,6 +220,9 @@ pub(super) async fn handle_non_success_response(
                     &mut headers_for_scan,
                     MAX_NON_SSE_BODY_BYTES,
                 );
+                captured_body = Some(body_for_scan.clone());
+                error_catalog_code = catalog::classify(status.as_u16(), body_for_scan.as_ref())
+                    .map(|kind| kind.as_str());
                 if status.as_u16() == 429 {
                     matched_429_concurrency_limit =
                         upstream_client_error_rules::match_429_concurrency_limit(
@@ -237,6 +244,71 @@ pub(super) async fn handle_non_success_response(
         }
     }
 
+    // The body is discarded on retry/failover anyway, so read it (bounded) to classify the
+    // error for the catalog. On abort it is relayed to the client and must stay untouched.
+    if error_catalog_code.is_none()
+        && status.is_client_error()
+        && !matches!(decision, FailoverDecision::Abort)
+    {
+        if let Some(r) = resp.take() {
+            if let Ok(bytes) = read_response_body_with_optional_limit(
+                r,
+                Some(upstream_client_error_rules::max_body_read_bytes()),
+            )
+            .await
+            {
+                let mut headers_for_scan = response_headers.clone();
+                strip_hop_headers(&mut headers_for_scan);
+                let body_for_scan = maybe_gunzip_response_body_bytes_with_limit(
+                    bytes,
+                    &mut headers_for_scan,
+                    MAX_NON_SSE_BODY_BYTES,
+                );
+                error_catalog_code = catalog::classify(status.as_u16(), body_for_scan.as_ref())
+                    .map(|kind| kind.as_str());
+                captured_body = Some(body_for_scan);
+            }
+        }
+    }
+
+    // Keep the upstream reason for the attempt log. A body that is relayed on abort is only read
+    // when its declared length is small, then relayed from memory like a matched rule above.
+    if captured_body.is_none() && crate::gateway::error_body_capture::enabled() {
+        let relay_after_read = matches!(decision, FailoverDecision::Abort);
+        let readable = !relay_after_read
+            || resp
+                .as_ref()
+                .and_then(|r| r.content_length())
+                .is_some_and(|len| len <= upstream_client_error_rules::max_body_read_bytes());
+        if readable {
+            if let Some(r) = resp.take() {
+                let read_result = if relay_after_read {
+                    r.bytes().await
+                } else {
+                    read_response_body_with_optional_limit(
+                        r,
+                        Some(upstream_client_error_rules::max_body_read_bytes()),
+                    )
+                    .await
+                };
+                if let Ok(bytes) = read_result {
+                    let mut headers_for_scan = response_headers.clone();
+                    strip_hop_headers(&mut headers_for_scan);
+                    let body_for_scan = maybe_gunzip_response_body_bytes_with_limit(
+                        bytes,
+                        &mut headers_for_scan,
+                        MAX_NON_SSE_BODY_BYTES,
+                    );
+                    if relay_after_read {
+                        abort_body_bytes = Some(body_for_scan.clone());
+                        abort_response_headers = Some(headers_for_scan);
+                    }
+                    captured_body = Some(body_for_scan);
+                }
+            }
+        }
+    }
+
     let mut circuit_state_before = Some(circuit_before.state.as_str());
     let mut circuit_state_after: Option<&'static str> = None;
     let mut circuit_failure_count = Some(circuit_before.failure_count);
@@ -300,6 +372,8 @@ pub(super) async fn handle_non_success_response(
     let selection_method = dc::selection_method(provider_index, retry_index, session_reuse);
     let reason_code = category.reason_code();
 
+    // The error body has been read; from here on the attempt is recorded as an upstream error.
+    abort_guard.end_attempt();
     attempts.push(FailoverAttempt {
         provider_id,
         provider_name: provider_name_base.clone(),
@@ -321,6 +395,13 @@ pub(super) async fn handle_non_success_response(
         circuit_state_after,
         circuit_failure_count,
         circuit_failure_threshold,
+        error_catalog_code,
+        response_headers: crate::gateway::response_headers::capture(&response_headers),
+        error_body: captured_body
+            .as_deref()
+            .and_then(crate::gateway::error_body_capture::capture),
+        retry_backoff_ms: None,
+        stream_stats: None,
     });
 
     emit_attempt_event_and_log(
@@ -343,9 +424,13 @@ pub(super) async fn handle_non_success_response(
 
     match decision {
         FailoverDecision::RetrySameProvider => {
-            if let Some(delay) = retry_backoff_delay(status, retry_index) {
-                tokio::time::sleep(delay).await;
-            }
+            wait_retry_backoff(
+                &ctx.retry_backoff,
+                RetryBackoffCategory::from_status(status),
+                retry_index,
+                attempts,
+            )
+            .await;
             LoopControl::ContinueRetry
         }
         FailoverDecision::SwitchProvider => {
@@ -399,7 +484,7 @@ pub(super) async fn handle_non_success_response(
                 let duration_ms = started.elapsed().as_millis();
 
                 emit_request_event_and_enqueue_request_log(RequestEndArgs {
-                    deps: RequestEndDeps::new(&state.app, &state.db, &state.log_tx),
+                    deps: RequestEndDeps::from_state(state),
                     trace_id: trace_id.as_str(),
                     cli_key: cli_key.as_str(),
                     method: method_hint.as_str(),
@@ -421,6 +506,9 @@ pub(super) async fn handle_non_success_response(
                     usage_metrics: None,
                     log_usage_metrics: None,
                     usage: None,
+                    request_bytes: Some(ctx.request_bytes),
+                    response_bytes: Some(body_bytes.len() as u64),
+                    thinking_budget_tokens: None,
                 })
                 .await;
 
@@ -438,7 +526,7 @@ pub(super) async fn handle_non_success_response(
             let duration_ms = started.elapsed().as_millis();
 
             emit_request_event_and_enqueue_request_log(RequestEndArgs {
-                deps: RequestEndDeps::new(&state.app, &state.db, &state.log_tx),
+                deps: RequestEndDeps::from_state(state),
                 trace_id: trace_id.as_str(),
                 cli_key: cli_key.as_str(),
                 method: method_hint.as_str(),
@@ -460,6 +548,9 @@ pub(super) async fn handle_non_success_response(
                 usage_metrics: None,
                 log_usage_metrics: None,
                 usage: None,
+                request_bytes: Some(ctx.request_bytes),
+                response_bytes: None,
+                thinking_budget_tokens: None,
             })
             .await;
 
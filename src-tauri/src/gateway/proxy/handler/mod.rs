@@ -2,7 +2,14 @@
 //!
 //! Note: this module is being split into smaller submodules under `handler/`.
 
-use super::request_context::{RequestContext, RequestContextParts};
+use super::access_guard::GATEWAY_KEY_HEADER;
+use super::caches::{SESSION_FAILURE_BUDGET_MAX_ATTEMPTS, SESSION_FAILURE_BUDGET_WINDOW_SECS};
+use super::federation;
+use super::mirror::{self, MirrorRequest};
+use super::request_context::{
+    build_base_headers, HedgeEndpointClass, RequestContext, RequestContextParts,
+};
+use super::request_dedup::{self, DedupHitCtx, DedupJoin};
 use super::request_end::{
     emit_request_event_and_enqueue_request_log, emit_request_event_and_spawn_request_log,
     RequestEndArgs, RequestEndDeps,
@@ -13,8 +20,9 @@ use super::{
 };
 use super::{ErrorCategory, GatewayErrorCode};
 use provider_selection::{
-    resolve_session_bound_provider_id, resolve_session_routing_decision,
-    select_providers_with_session_binding, ProviderSelection, SessionRoutingDecision,
+    has_available_provider, resolve_provider_tag_filter, resolve_session_bound_provider_id,
+    resolve_session_routing_decision, select_providers_with_session_binding, ProviderSelection,
+    SessionRoutingDecision,
 };
 
 use crate::shared::mutex_ext::MutexExt;
@@ -31,6 +39,7 @@ use std::time::Instant;
 use super::super::codex_session_id;
 use super::super::events::{decision_chain as dc, emit_gateway_log, emit_request_start_event};
 use super::super::manager::GatewayAppState;
+use super::super::model_capabilities::CapabilityGate;
 use super::super::response_fixer;
 use super::super::util::{
     body_for_introspection, infer_requested_model_info, new_trace_id, now_unix_millis,
@@ -39,6 +48,8 @@ use super::super::util::{
 use super::super::warmup;
 use request_fingerprint::{apply_recent_error_cache_gate, build_request_fingerprints};
 
+mod count_tokens_estimate;
+mod models_emulation;
 mod provider_order;
 mod provider_selection;
 mod request_fingerprint;
@@ -50,7 +61,11 @@ type SpecialSettings = Arc<Mutex<Vec<serde_json::Value>>>;
 
 #[derive(Debug, Clone, Copy)]
 enum EarlyErrorKind {
+    RoutingPaused,
+    SessionThrottled,
+    SessionFailureBudgetExceeded,
     CliProxyDisabled,
+    ModelDenied,
     BodyTooLarge,
     InvalidCliKey,
     NoEnabledProvider,
@@ -66,12 +81,36 @@ struct EarlyErrorContract {
 
 fn early_error_contract(kind: EarlyErrorKind) -> EarlyErrorContract {
     match kind {
+        EarlyErrorKind::RoutingPaused => EarlyErrorContract {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            error_code: GatewayErrorCode::RoutingPaused.as_str(),
+            error_category: None,
+            excluded_from_stats: true,
+        },
+        EarlyErrorKind::SessionThrottled => EarlyErrorContract {
+            status: StatusCode::TOO_MANY_REQUESTS,
+            error_code: GatewayErrorCode::SessionThrottled.as_str(),
+            error_category: Some(ErrorCategory::NonRetryableClientError.as_str()),
+            excluded_from_stats: true,
+        },
+        EarlyErrorKind::SessionFailureBudgetExceeded => EarlyErrorContract {
+            status: StatusCode::TOO_MANY_REQUESTS,
+            error_code: GatewayErrorCode::SessionFailureBudgetExceeded.as_str(),
+            error_category: Some(ErrorCategory::NonRetryableClientError.as_str()),
+            excluded_from_stats: true,
+        },
         EarlyErrorKind::CliProxyDisabled => EarlyErrorContract {
             status: StatusCode::FORBIDDEN,
             error_code: GatewayErrorCode::CliProxyDisabled.as_str(),
             error_category: Some(ErrorCategory::NonRetryableClientError.as_str()),
             excluded_from_stats: true,
         },
+        EarlyErrorKind::ModelDenied => EarlyErrorContract {
+            status: StatusCode::FORBIDDEN,
+            error_code: GatewayErrorCode::ModelDenied.as_str(),
+            error_category: Some(ErrorCategory::NonRetryableClientError.as_str()),
+            excluded_from_stats: true,
+        },
         EarlyErrorKind::BodyTooLarge => EarlyErrorContract {
             status: StatusCode::PAYLOAD_TOO_LARGE,
             error_code: GatewayErrorCode::BodyTooLarge.as_str(),
@@ -101,6 +140,22 @@ fn no_enabled_provider_message(cli_key: &str) -> String {
     format!("no enabled provider for cli_key={cli_key}")
 }
 
+fn routing_paused_message() -> &'static str {
+    crate::shared::i18n::text(GatewayErrorCode::RoutingPaused.as_str())
+}
+
+fn session_throttled_message(session_id: &str) -> String {
+    format!("会话 {session_id} 因费用异常已被限速：请在应用中确认该异常后恢复")
+}
+
+fn session_failure_budget_message(session_id: &str, retry_after_seconds: u64) -> String {
+    format!(
+        "会话 {session_id} 在 {} 分钟内已失败 {} 次，网关暂停转发以避免重试风暴：请检查请求或供应商状态，{retry_after_seconds} 秒后重试",
+        SESSION_FAILURE_BUDGET_WINDOW_SECS / 60,
+        SESSION_FAILURE_BUDGET_MAX_ATTEMPTS
+    )
+}
+
 fn cli_proxy_disabled_message(cli_key: &str, error: Option<&str>) -> String {
     match error {
         Some(err) => format!(
@@ -110,15 +165,30 @@ fn cli_proxy_disabled_message(cli_key: &str, error: Option<&str>) -> String {
     }
 }
 
+fn model_denied_message(model: &str, rule: &settings::ModelDenyRule) -> String {
+    format!(
+        "模型 {model} 已被禁用（命中模型黑名单规则 {}:{}）：如需使用请在设置中移除该规则",
+        rule.cli_key, rule.pattern
+    )
+}
+
 fn extract_forced_provider_id(headers: &axum::http::HeaderMap) -> Option<i64> {
     let raw = headers.get("x-aio-provider-id")?.to_str().ok()?.trim();
     let provider_id = raw.parse::<i64>().ok()?;
     (provider_id > 0).then_some(provider_id)
 }
 
+fn extract_provider_tag(headers: &axum::http::HeaderMap) -> Option<String> {
+    let raw = headers.get("x-aio-provider-tag")?.to_str().ok()?;
+    crate::provider_tag_defaults::normalize_tag(raw)
+        .ok()
+        .flatten()
+}
+
 fn force_provider_if_requested(
     providers: &mut Vec<crate::providers::ProviderForGateway>,
     provider_id: Option<i64>,
+    source: &'static str,
     special_settings: &SpecialSettings,
 ) {
     let Some(provider_id) = provider_id else {
@@ -139,6 +209,7 @@ fn force_provider_if_requested(
                 "scope": "request",
                 "hit": true,
                 "providerId": provider_id,
+                "source": source,
             }),
         );
     } else {
@@ -163,6 +234,18 @@ fn cli_proxy_guard_special_settings_json(
     .to_string()
 }
 
+fn model_denylist_special_settings_json(model: &str, rule: &settings::ModelDenyRule) -> String {
+    serde_json::json!([{
+        "type": "model_denylist",
+        "scope": "request",
+        "hit": true,
+        "model": model,
+        "cliKey": rule.cli_key,
+        "pattern": rule.pattern,
+    }])
+    .to_string()
+}
+
 fn new_special_settings() -> SpecialSettings {
     Arc::new(Mutex::new(Vec::new()))
 }
@@ -258,7 +341,7 @@ fn early_error_request_end_args<'a>(
     requested_model: Option<String>,
 ) -> RequestEndArgs<'a> {
     RequestEndArgs {
-        deps: RequestEndDeps::new(&ctx.state.app, &ctx.state.db, &ctx.state.log_tx),
+        deps: RequestEndDeps::from_state(ctx.state),
         trace_id: ctx.trace_id,
         cli_key: ctx.cli_key,
         method: ctx.method_hint,
@@ -280,6 +363,9 @@ fn early_error_request_end_args<'a>(
         usage_metrics: None,
         log_usage_metrics: None,
         usage: None,
+        request_bytes: None,
+        response_bytes: None,
+        thinking_budget_tokens: None,
     }
 }
 
@@ -347,9 +433,35 @@ struct HandlerRuntimeSettings {
     max_attempts_per_provider: u32,
     max_providers_to_try: u32,
     provider_cooldown_secs: i64,
+    retry_backoff: settings::RetryBackoffSettings,
     upstream_first_byte_timeout_secs: u32,
     upstream_stream_idle_timeout_secs: u32,
     upstream_request_timeout_non_streaming_secs: u32,
+    count_tokens_hedge_delay_ms: u32,
+    count_tokens_local_estimate: bool,
+    non_streaming_hedge_delay_ms: u32,
+    first_byte_race_delay_ms: u32,
+    request_mirror_provider_id: i64,
+    request_mirror_percent: u32,
+    request_dedup_enabled: bool,
+    stream_truncation_recovery_enabled: bool,
+    sse_coalesce_flush_ms: u32,
+    sse_coalesce_flush_bytes: u32,
+    stream_stall_warn_seconds: u32,
+    stream_stall_keepalive_enabled: bool,
+    stream_first_byte_failover_enabled: bool,
+    stream_error_event_enabled: bool,
+}
+
+impl HandlerRuntimeSettings {
+    /// Hedge delay configured for the request's endpoint class (0 = hedging disabled).
+    fn hedge_delay_ms(&self, class: HedgeEndpointClass) -> u32 {
+        match class {
+            HedgeEndpointClass::CountTokens => self.count_tokens_hedge_delay_ms,
+            HedgeEndpointClass::NonStreaming => self.non_streaming_hedge_delay_ms,
+            HedgeEndpointClass::Streaming => self.first_byte_race_delay_ms,
+        }
+    }
 }
 
 fn handler_runtime_settings(
@@ -388,6 +500,17 @@ fn handler_runtime_settings(
     let response_fixer_max_fix_size = settings_cfg
         .map(|cfg| cfg.response_fixer_max_fix_size)
         .unwrap_or(response_fixer::DEFAULT_MAX_FIX_SIZE as u32);
+    let response_fixer_transcode_charsets = settings_cfg
+        .map(|cfg| {
+            response_fixer::TranscodeCharsets::from_allow_list(
+                &cfg.response_fixer_transcode_charsets,
+            )
+        })
+        .unwrap_or_default();
+    let response_fixer_spill_max_size = settings_cfg
+        .filter(|cfg| cfg.response_fixer_spill_to_disk)
+        .map(|cfg| cfg.response_fixer_spill_max_size)
+        .unwrap_or(0);
 
     let mut max_attempts_per_provider = settings_cfg
         .map(|cfg| cfg.failover_max_attempts_per_provider.max(1))
@@ -401,6 +524,20 @@ fn handler_runtime_settings(
         max_providers_to_try = 1;
     }
 
+    // count_tokens probes are cheap and not worth evaluating against a shadow provider.
+    let request_mirror = settings_cfg
+        .filter(|cfg| cfg.request_mirror_enabled && !is_claude_count_tokens)
+        .map(|cfg| (cfg.request_mirror_provider_id, cfg.request_mirror_percent));
+
+    let request_dedup_enabled = settings_cfg
+        .map(|cfg| cfg.request_dedup_enabled)
+        .unwrap_or(false);
+
+    let stream_truncation_recovery_enabled = settings_cfg
+        .map(|cfg| cfg.stream_truncation_recovery_enabled)
+        .unwrap_or(false)
+        && !is_claude_count_tokens;
+
     HandlerRuntimeSettings {
         verbose_provider_error,
         intercept_warmup: settings_cfg
@@ -415,6 +552,10 @@ fn handler_runtime_settings(
             fix_truncated_json: response_fixer_fix_truncated_json,
             max_json_depth: response_fixer_max_json_depth as usize,
             max_fix_size: response_fixer_max_fix_size as usize,
+            spill_max_size: 0,
+            // Enabled per response once the upstream path is known to be a Responses API call.
+            fix_responses_events: false,
+            transcode_charsets: response_fixer_transcode_charsets,
         },
         response_fixer_non_stream_config: response_fixer::ResponseFixerConfig {
             fix_encoding: response_fixer_fix_encoding,
@@ -422,6 +563,9 @@ fn handler_runtime_settings(
             fix_truncated_json: response_fixer_fix_truncated_json,
             max_json_depth: response_fixer_max_json_depth as usize,
             max_fix_size: response_fixer_max_fix_size as usize,
+            spill_max_size: response_fixer_spill_max_size as usize,
+            fix_responses_events: false,
+            transcode_charsets: response_fixer_transcode_charsets,
         },
         provider_base_url_ping_cache_ttl_seconds: settings_cfg
             .map(|cfg| cfg.provider_base_url_ping_cache_ttl_seconds)
@@ -438,6 +582,9 @@ fn handler_runtime_settings(
         provider_cooldown_secs: settings_cfg
             .map(|cfg| cfg.provider_cooldown_seconds as i64)
             .unwrap_or(settings::DEFAULT_PROVIDER_COOLDOWN_SECONDS as i64),
+        retry_backoff: settings_cfg
+            .map(|cfg| cfg.retry_backoff)
+            .unwrap_or_default(),
         upstream_first_byte_timeout_secs: settings_cfg
             .map(|cfg| cfg.upstream_first_byte_timeout_seconds)
             .unwrap_or(settings::DEFAULT_UPSTREAM_FIRST_BYTE_TIMEOUT_SECONDS),
@@ -447,9 +594,52 @@ fn handler_runtime_settings(
         upstream_request_timeout_non_streaming_secs: settings_cfg
             .map(|cfg| cfg.upstream_request_timeout_non_streaming_seconds)
             .unwrap_or(settings::DEFAULT_UPSTREAM_REQUEST_TIMEOUT_NON_STREAMING_SECONDS),
+        count_tokens_hedge_delay_ms: settings_cfg
+            .map(|cfg| cfg.count_tokens_hedge_delay_ms)
+            .unwrap_or(settings::DEFAULT_COUNT_TOKENS_HEDGE_DELAY_MS),
+        count_tokens_local_estimate: is_claude_count_tokens
+            && settings_cfg.is_some_and(|cfg| cfg.count_tokens_local_estimate_enabled),
+        non_streaming_hedge_delay_ms: settings_cfg
+            .map(|cfg| cfg.non_streaming_hedge_delay_ms)
+            .unwrap_or(settings::DEFAULT_NON_STREAMING_HEDGE_DELAY_MS),
+        first_byte_race_delay_ms: settings_cfg
+            .map(|cfg| cfg.first_byte_race_delay_ms)
+            .unwrap_or(settings::DEFAULT_FIRST_BYTE_RACE_DELAY_MS),
+        request_mirror_provider_id: request_mirror.map(|(id, _)| id).unwrap_or(0),
+        request_mirror_percent: request_mirror.map(|(_, pct)| pct).unwrap_or(0),
+        request_dedup_enabled,
+        stream_truncation_recovery_enabled,
+        sse_coalesce_flush_ms: settings_cfg
+            .map(|cfg| cfg.sse_coalesce_flush_ms)
+            .unwrap_or(settings::DEFAULT_SSE_COALESCE_FLUSH_MS),
+        sse_coalesce_flush_bytes: settings_cfg
+            .map(|cfg| cfg.sse_coalesce_flush_bytes)
+            .unwrap_or(settings::DEFAULT_SSE_COALESCE_FLUSH_BYTES),
+        stream_stall_warn_seconds: settings_cfg
+            .map(|cfg| cfg.stream_stall_warn_seconds)
+            .unwrap_or(settings::DEFAULT_STREAM_STALL_WARN_SECONDS),
+        stream_stall_keepalive_enabled: settings_cfg
+            .is_some_and(|cfg| cfg.stream_stall_keepalive_enabled),
+        stream_first_byte_failover_enabled: settings_cfg
+            .map(|cfg| cfg.stream_first_byte_failover_enabled)
+            .unwrap_or(true),
+        stream_error_event_enabled: settings_cfg
+            .map(|cfg| cfg.stream_error_event_enabled)
+            .unwrap_or(true),
     }
 }
 
+/// Streaming requests (SSE body flag or Gemini `streamGenerateContent`) hedge as the
+/// `streaming` endpoint class (first-byte race).
+fn is_stream_request(forwarded_path: &str, introspection_json: Option<&serde_json::Value>) -> bool {
+    forwarded_path.contains("streamGenerateContent")
+        || introspection_json.is_some_and(|root| {
+            root.get("stream")
+                .and_then(serde_json::Value::as_bool)
+                .unwrap_or(false)
+        })
+}
+
 struct WarmupInterceptCtx<'a> {
     state: &'a GatewayAppState,
     trace_id: &'a str,
@@ -522,10 +712,15 @@ fn respond_warmup_intercept(ctx: &WarmupInterceptCtx<'_>) -> Response {
         circuit_state_after: None,
         circuit_failure_count: None,
         circuit_failure_threshold: None,
+        error_catalog_code: None,
+        response_headers: None,
+        error_body: None,
+        retry_backoff_ms: None,
+        stream_stats: None,
     }];
 
     emit_request_event_and_spawn_request_log(RequestEndArgs {
-        deps: RequestEndDeps::new(&ctx.state.app, &ctx.state.db, &ctx.state.log_tx),
+        deps: RequestEndDeps::from_state(ctx.state),
         trace_id: ctx.trace_id,
         cli_key: ctx.cli_key,
         method: ctx.method_hint,
@@ -547,6 +742,9 @@ fn respond_warmup_intercept(ctx: &WarmupInterceptCtx<'_>) -> Response {
         usage_metrics: Some(usage::UsageMetrics::default()),
         log_usage_metrics: Some(warmup_log_usage_metrics()),
         usage: None,
+        request_bytes: None,
+        response_bytes: None,
+        thinking_budget_tokens: None,
     });
 
     let mut resp = (StatusCode::OK, Json(response_body)).into_response();
@@ -682,8 +880,34 @@ pub(in crate::gateway) async fn proxy_impl(
         (parts.headers, body)
     };
 
+    if super::super::routing_pause::is_paused() {
+        let contract = early_error_contract(EarlyErrorKind::RoutingPaused);
+        let log_ctx = build_early_error_log_ctx(
+            &state,
+            &started,
+            trace_id.as_str(),
+            cli_key.as_str(),
+            method_hint.as_str(),
+            forwarded_path.as_str(),
+            query.as_deref(),
+            created_at_ms,
+            created_at,
+        );
+
+        return respond_early_error_with_enqueue(
+            &log_ctx,
+            contract,
+            routing_paused_message().to_string(),
+            None,
+            None,
+            None,
+        )
+        .await;
+    }
+
     let forced_provider_id = extract_forced_provider_id(&headers);
     let bypass_cli_proxy_guard = forced_provider_id.is_some();
+    let header_provider_tag = extract_provider_tag(&headers);
 
     if crate::shared::cli_key::is_supported_cli_key(cli_key.as_str()) && !bypass_cli_proxy_guard {
         let enabled_snapshot = cli_proxy_enabled_cached(&state.app, &cli_key);
@@ -733,6 +957,10 @@ pub(in crate::gateway) async fn proxy_impl(
     }
 
     headers.remove("x-aio-provider-id");
+    headers.remove("x-aio-provider-tag");
+    let via_federation = federation::arrived_via_federation(&headers);
+    headers.remove(federation::FEDERATION_HOP_HEADER);
+    headers.remove(GATEWAY_KEY_HEADER);
 
     let mut body_bytes = match to_bytes(body, MAX_REQUEST_BODY_BYTES).await {
         Ok(bytes) => bytes,
@@ -774,6 +1002,38 @@ pub(in crate::gateway) async fn proxy_impl(
     let requested_model = requested_model_info.model;
     let requested_model_location = requested_model_info.location;
 
+    if let Some(model) = requested_model.as_deref() {
+        let denied_by = settings::read(&state.app).ok().and_then(|cfg| {
+            settings::matching_model_deny_rule(&cfg.model_denylist, &cli_key, model).cloned()
+        });
+        if let Some(rule) = denied_by {
+            let contract = early_error_contract(EarlyErrorKind::ModelDenied);
+            let message = model_denied_message(model, &rule);
+            let special_settings_json = model_denylist_special_settings_json(model, &rule);
+            let log_ctx = build_early_error_log_ctx(
+                &state,
+                &started,
+                trace_id.as_str(),
+                cli_key.as_str(),
+                method_hint.as_str(),
+                forwarded_path.as_str(),
+                query.as_deref(),
+                created_at_ms,
+                created_at,
+            );
+
+            return respond_early_error_with_enqueue(
+                &log_ctx,
+                contract,
+                message,
+                Some(special_settings_json),
+                None,
+                requested_model,
+            )
+            .await;
+        }
+    }
+
     let RuntimeWarmupDecision {
         runtime_settings,
         is_warmup_request,
@@ -827,14 +1087,106 @@ pub(in crate::gateway) async fn proxy_impl(
         is_claude_count_tokens,
     );
 
+    if let Some(throttled_session_id) = session_id.as_deref() {
+        if !super::super::session_throttle::try_acquire(throttled_session_id, created_at_ms) {
+            let contract = early_error_contract(EarlyErrorKind::SessionThrottled);
+            let message = session_throttled_message(throttled_session_id);
+            let log_ctx = build_early_error_log_ctx(
+                &state,
+                &started,
+                trace_id.as_str(),
+                cli_key.as_str(),
+                method_hint.as_str(),
+                forwarded_path.as_str(),
+                query.as_deref(),
+                created_at_ms,
+                created_at,
+            );
+
+            return respond_early_error_with_enqueue(
+                &log_ctx,
+                contract,
+                message,
+                None,
+                session_id,
+                requested_model,
+            )
+            .await;
+        }
+
+        let budget_retry_after = state
+            .recent_errors
+            .lock_or_recover()
+            .session_failure_budget_retry_after(created_at, throttled_session_id);
+        if let Some(retry_after_seconds) = budget_retry_after {
+            let contract = early_error_contract(EarlyErrorKind::SessionFailureBudgetExceeded);
+            let message = session_failure_budget_message(throttled_session_id, retry_after_seconds);
+            let log_ctx = build_early_error_log_ctx(
+                &state,
+                &started,
+                trace_id.as_str(),
+                cli_key.as_str(),
+                method_hint.as_str(),
+                forwarded_path.as_str(),
+                query.as_deref(),
+                created_at_ms,
+                created_at,
+            );
+
+            let mut resp = respond_early_error_with_enqueue(
+                &log_ctx,
+                contract,
+                message,
+                None,
+                session_id,
+                requested_model,
+            )
+            .await;
+            if let Ok(value) = HeaderValue::from_str(&retry_after_seconds.to_string()) {
+                resp.headers_mut().insert(header::RETRY_AFTER, value);
+            }
+            return resp;
+        }
+    }
+
+    // A session pinned from the UI is locked to its provider just like the header does.
+    let (forced_provider_id, provider_lock_source) = match forced_provider_id {
+        Some(provider_id) => (Some(provider_id), "header"),
+        None => (
+            session_id
+                .as_deref()
+                .and_then(|sid| state.session.get_pinned_provider(&cli_key, sid)),
+            "session_pin",
+        ),
+    };
+
+    // A forced provider is an explicit pick; tags would only get in its way.
+    let provider_tag_filter = if forced_provider_id.is_none() {
+        resolve_provider_tag_filter(
+            &state,
+            &cli_key,
+            session_id.as_deref(),
+            header_provider_tag,
+            created_at,
+        )
+    } else {
+        None
+    };
+
     let ProviderSelection {
         effective_sort_mode_id,
         mut providers,
         bound_provider_order,
+        sort_mode_fallback,
+        time_window,
+        adaptive_order,
+        tag_filter,
+        mut last_resort,
     } = match select_providers_with_session_binding(
         &state,
         &cli_key,
         session_id.as_deref(),
+        provider_tag_filter.as_ref(),
         created_at,
     ) {
         Ok(selection) => selection,
@@ -859,7 +1211,85 @@ pub(in crate::gateway) async fn proxy_impl(
         }
     };
 
-    force_provider_if_requested(&mut providers, forced_provider_id, &special_settings);
+    if let Some(filter) = tag_filter {
+        push_special_setting(
+            &special_settings,
+            serde_json::json!({
+                "type": "provider_tag_filter",
+                "scope": "request",
+                "hit": true,
+                "tag": filter.tag,
+                "source": filter.source,
+                "matchedProviderIds": filter.matched_provider_ids,
+                "excludedProviderIds": filter.excluded_provider_ids,
+            }),
+        );
+    }
+
+    if let Some(fallback) = sort_mode_fallback {
+        push_special_setting(
+            &special_settings,
+            serde_json::json!({
+                "type": "sort_mode_fallback",
+                "scope": "request",
+                "hit": true,
+                "fromModeId": fallback.from_mode_id,
+                "toModeId": fallback.to_mode_id,
+                "chainIndex": fallback.chain_index,
+            }),
+        );
+    }
+
+    if let Some(adaptive) = adaptive_order {
+        push_special_setting(
+            &special_settings,
+            serde_json::json!({
+                "type": "sort_mode_adaptive",
+                "scope": "request",
+                "hit": true,
+                "modeId": adaptive.mode_id,
+                "rankedProviderIds": adaptive.ranked_provider_ids,
+            }),
+        );
+    }
+
+    if let Some(window) = time_window {
+        push_special_setting(
+            &special_settings,
+            serde_json::json!({
+                "type": "sort_mode_time_window",
+                "scope": "request",
+                "hit": !window.deferred_provider_ids.is_empty(),
+                "modeId": window.mode_id,
+                "localTime": window.local_time,
+                "activeProviderIds": window.active_provider_ids,
+                "deferredProviderIds": window.deferred_provider_ids,
+            }),
+        );
+    }
+
+    if via_federation {
+        let mut dropped_provider_ids = federation::drop_federated(&mut providers);
+        if let Some(p) = last_resort.take_if(|p| federation::is_federated(p)) {
+            dropped_provider_ids.push(p.id);
+        }
+        push_special_setting(
+            &special_settings,
+            serde_json::json!({
+                "type": "federation_inbound",
+                "scope": "request",
+                "hit": true,
+                "droppedProviderIds": dropped_provider_ids,
+            }),
+        );
+    }
+
+    force_provider_if_requested(
+        &mut providers,
+        forced_provider_id,
+        provider_lock_source,
+        &special_settings,
+    );
 
     // NOTE: model whitelist filtering removed (Claude uses slot-based model mapping).
 
@@ -875,6 +1305,29 @@ pub(in crate::gateway) async fn proxy_impl(
         bound_provider_order.as_deref(),
     );
 
+    if runtime_settings.count_tokens_local_estimate
+        && forced_provider_id.is_none()
+        && !has_available_provider(&state.circuit, &providers, last_resort.as_ref(), created_at)
+    {
+        let input_tokens =
+            count_tokens_estimate::estimate_input_tokens(introspection_json.as_ref(), &body_bytes);
+        return count_tokens_estimate::respond(
+            count_tokens_estimate::CountTokensEstimateCtx {
+                state: &state,
+                trace_id: trace_id.as_str(),
+                method_hint: method_hint.as_str(),
+                forwarded_path: forwarded_path.as_str(),
+                query: query.as_deref(),
+                requested_model,
+                special_settings: &special_settings,
+                created_at_ms,
+                created_at,
+                duration_ms: started.elapsed().as_millis(),
+            },
+            input_tokens,
+        );
+    }
+
     if providers.is_empty() {
         let contract = early_error_contract(EarlyErrorKind::NoEnabledProvider);
         let message = no_enabled_provider_message(&cli_key);
@@ -901,6 +1354,24 @@ pub(in crate::gateway) async fn proxy_impl(
         .await;
     }
 
+    if let Some(provider) =
+        models_emulation::emulating_provider(&cli_key, &method, &forwarded_path, &providers)
+    {
+        return models_emulation::respond(
+            &models_emulation::ModelsEmulationCtx {
+                state: &state,
+                trace_id: trace_id.as_str(),
+                method_hint: method_hint.as_str(),
+                forwarded_path: forwarded_path.as_str(),
+                query: query.as_deref(),
+                created_at_ms,
+                created_at,
+                duration_ms: started.elapsed().as_millis(),
+            },
+            provider,
+        );
+    }
+
     let fingerprints = build_request_fingerprints(
         &cli_key,
         effective_sort_mode_id,
@@ -918,6 +1389,51 @@ pub(in crate::gateway) async fn proxy_impl(
         Err(resp) => return *resp,
     };
 
+    let dedup_join = if runtime_settings.request_dedup_enabled
+        && forced_provider_id.is_none()
+        && request_dedup::is_dedup_candidate(&forwarded_path, introspection_json.as_ref())
+    {
+        request_dedup::join(
+            &state.inflight_requests,
+            fingerprints.fingerprint_key,
+            &fingerprints.fingerprint_debug,
+            &trace_id,
+        )
+    } else {
+        DedupJoin::Bypass
+    };
+    let dedup_leader = match dedup_join {
+        DedupJoin::Leader(leader) => Some(leader),
+        DedupJoin::Follower(follower) => {
+            let leader_trace_id = follower.leader_trace_id.clone();
+            let wait_started = Instant::now();
+            if let Some(shared) = follower.wait().await {
+                return request_dedup::respond_hit(
+                    DedupHitCtx {
+                        state: &state,
+                        trace_id: trace_id.as_str(),
+                        leader_trace_id: leader_trace_id.as_str(),
+                        cli_key: cli_key.as_str(),
+                        method_hint: method_hint.as_str(),
+                        forwarded_path: forwarded_path.as_str(),
+                        query: query.as_deref(),
+                        session_id,
+                        requested_model,
+                        special_settings: &special_settings,
+                        started,
+                        waited_ms: wait_started.elapsed().as_millis(),
+                        created_at_ms,
+                        created_at,
+                    },
+                    &shared,
+                );
+            }
+            // The leader ended without a shareable response: forward this request normally.
+            None
+        }
+        DedupJoin::Bypass => None,
+    };
+
     emit_request_start_event(
         &state.app,
         trace_id.clone(),
@@ -929,7 +1445,41 @@ pub(in crate::gateway) async fn proxy_impl(
         created_at,
     );
 
-    super::forwarder::forward(RequestContext::from_handler_parts(RequestContextParts {
+    // Forced-provider requests are probes/tests, not real traffic worth evaluating.
+    if forced_provider_id.is_none() {
+        mirror::maybe_spawn(
+            &state,
+            runtime_settings.request_mirror_provider_id,
+            runtime_settings.request_mirror_percent,
+            providers.first().map(|p| p.id),
+            || MirrorRequest {
+                trace_id: trace_id.clone(),
+                cli_key: cli_key.clone(),
+                method: method.clone(),
+                forwarded_path: forwarded_path.clone(),
+                query: query.clone(),
+                requested_model: requested_model.clone(),
+                requested_model_location,
+                introspection_json: introspection_json.clone(),
+                base_headers: build_base_headers(headers.clone()),
+                body_bytes: body_bytes.clone(),
+                strip_request_content_encoding: strip_request_content_encoding_seed,
+                provider_base_url_ping_cache_ttl_seconds: runtime_settings
+                    .provider_base_url_ping_cache_ttl_seconds,
+            },
+        );
+    }
+
+    let hedge_class = HedgeEndpointClass::classify(
+        is_claude_count_tokens,
+        is_stream_request(&forwarded_path, introspection_json.as_ref()),
+    );
+
+    let capability_gate = settings::read(&state.app).ok().and_then(|cfg| {
+        CapabilityGate::new(&cfg, &cli_key, introspection_json.as_ref()).map(Arc::new)
+    });
+
+    let resp = super::forwarder::forward(RequestContext::from_handler_parts(RequestContextParts {
         state,
         cli_key,
         forwarded_path,
@@ -945,6 +1495,8 @@ pub(in crate::gateway) async fn proxy_impl(
         requested_model_location,
         effective_sort_mode_id,
         providers,
+        // Forced-provider requests must only ever reach the forced provider.
+        last_resort_provider: last_resort.filter(|_| forced_provider_id.is_none()),
         session_bound_provider_id,
         headers,
         body_bytes,
@@ -957,10 +1509,13 @@ pub(in crate::gateway) async fn proxy_impl(
         max_attempts_per_provider: runtime_settings.max_attempts_per_provider,
         max_providers_to_try: runtime_settings.max_providers_to_try,
         provider_cooldown_secs: runtime_settings.provider_cooldown_secs,
+        retry_backoff: runtime_settings.retry_backoff,
         upstream_first_byte_timeout_secs: runtime_settings.upstream_first_byte_timeout_secs,
         upstream_stream_idle_timeout_secs: runtime_settings.upstream_stream_idle_timeout_secs,
         upstream_request_timeout_non_streaming_secs: runtime_settings
             .upstream_request_timeout_non_streaming_secs,
+        hedge_class,
+        hedge_delay_ms: runtime_settings.hedge_delay_ms(hedge_class),
         fingerprint_key: fingerprints.fingerprint_key,
         fingerprint_debug: fingerprints.fingerprint_debug,
         unavailable_fingerprint_key: fingerprints.unavailable_fingerprint_key,
@@ -972,8 +1527,21 @@ pub(in crate::gateway) async fn proxy_impl(
         enable_response_fixer: runtime_settings.enable_response_fixer,
         response_fixer_stream_config: runtime_settings.response_fixer_stream_config,
         response_fixer_non_stream_config: runtime_settings.response_fixer_non_stream_config,
+        stream_truncation_recovery_enabled: runtime_settings.stream_truncation_recovery_enabled,
+        sse_coalesce_flush_ms: runtime_settings.sse_coalesce_flush_ms,
+        sse_coalesce_flush_bytes: runtime_settings.sse_coalesce_flush_bytes,
+        stream_stall_warn_seconds: runtime_settings.stream_stall_warn_seconds,
+        stream_stall_keepalive_enabled: runtime_settings.stream_stall_keepalive_enabled,
+        stream_first_byte_failover_enabled: runtime_settings.stream_first_byte_failover_enabled,
+        stream_error_event_enabled: runtime_settings.stream_error_event_enabled,
+        capability_gate,
     }))
-    .await
+    .await;
+
+    match dedup_leader {
+        Some(leader) => leader.publish(resp).await,
+        None => resp,
+    }
 }
 
 #[cfg(test)]
@@ -981,9 +1549,9 @@ mod tests {
     use super::{
         body_too_large_message, build_request_fingerprints, cli_proxy_disabled_message,
         cli_proxy_guard_special_settings_json, early_error_contract, handler_runtime_settings,
-        no_enabled_provider_message, resolve_session_routing_decision,
+        is_stream_request, no_enabled_provider_message, resolve_session_routing_decision,
         should_intercept_warmup_request, warmup_intercept_special_settings_json,
-        warmup_log_usage_metrics, EarlyErrorKind,
+        warmup_log_usage_metrics, EarlyErrorKind, HedgeEndpointClass,
     };
     use crate::gateway::proxy::{ErrorCategory, GatewayErrorCode};
     use crate::settings;
@@ -1005,8 +1573,19 @@ mod tests {
             limit_weekly_usd: None,
             limit_monthly_usd: None,
             limit_total_usd: None,
+            monthly_budget_usd: None,
             auth_mode: "api_key".to_string(),
             oauth_provider_type: None,
+            provider_kind: crate::providers::ProviderKind::Standard,
+            model_catalog: Vec::new(),
+            model_catalog_refreshed_at: None,
+            emulate_models_endpoint: false,
+            thinking_budget_cap: None,
+            refusal_patterns: Vec::new(),
+            anthropic_version: None,
+            anthropic_betas: Vec::new(),
+            anthropic_beta_strip: false,
+            tags: Vec::new(),
         }
     }
 
@@ -1055,6 +1634,12 @@ mod tests {
 
     #[test]
     fn early_error_contracts_match_expected_status_and_codes() {
+        let paused = early_error_contract(EarlyErrorKind::RoutingPaused);
+        assert_eq!(paused.status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(paused.error_code, GatewayErrorCode::RoutingPaused.as_str());
+        assert_eq!(paused.error_category, None);
+        assert!(paused.excluded_from_stats);
+
         let cli_proxy = early_error_contract(EarlyErrorKind::CliProxyDisabled);
         assert_eq!(cli_proxy.status, StatusCode::FORBIDDEN);
         assert_eq!(
@@ -1067,6 +1652,14 @@ mod tests {
         );
         assert!(cli_proxy.excluded_from_stats);
 
+        let model_denied = early_error_contract(EarlyErrorKind::ModelDenied);
+        assert_eq!(model_denied.status, StatusCode::FORBIDDEN);
+        assert_eq!(
+            model_denied.error_code,
+            GatewayErrorCode::ModelDenied.as_str()
+        );
+        assert!(model_denied.excluded_from_stats);
+
         let body_too_large = early_error_contract(EarlyErrorKind::BodyTooLarge);
         assert_eq!(body_too_large.status, StatusCode::PAYLOAD_TOO_LARGE);
         assert_eq!(
@@ -1146,6 +1739,96 @@ mod tests {
         assert_eq!(runtime.max_providers_to_try, 1);
     }
 
+    #[test]
+    fn handler_runtime_settings_picks_hedge_delay_per_endpoint_class() {
+        let cfg = settings::AppSettings {
+            count_tokens_hedge_delay_ms: 300,
+            non_streaming_hedge_delay_ms: 5000,
+            first_byte_race_delay_ms: 2000,
+            ..Default::default()
+        };
+        let runtime = handler_runtime_settings(Some(&cfg), false);
+
+        assert_eq!(runtime.hedge_delay_ms(HedgeEndpointClass::CountTokens), 300);
+        assert_eq!(
+            runtime.hedge_delay_ms(HedgeEndpointClass::NonStreaming),
+            5000
+        );
+        assert_eq!(runtime.hedge_delay_ms(HedgeEndpointClass::Streaming), 2000);
+        assert_eq!(
+            handler_runtime_settings(None, false).hedge_delay_ms(HedgeEndpointClass::NonStreaming),
+            0
+        );
+    }
+
+    #[test]
+    fn handler_runtime_settings_estimates_only_count_tokens_when_enabled() {
+        let cfg = settings::AppSettings {
+            count_tokens_local_estimate_enabled: true,
+            ..Default::default()
+        };
+
+        assert!(handler_runtime_settings(Some(&cfg), true).count_tokens_local_estimate);
+        assert!(!handler_runtime_settings(Some(&cfg), false).count_tokens_local_estimate);
+        assert!(
+            !handler_runtime_settings(Some(&settings::AppSettings::default()), true)
+                .count_tokens_local_estimate
+        );
+        assert!(!handler_runtime_settings(None, true).count_tokens_local_estimate);
+    }
+
+    #[test]
+    fn hedge_endpoint_class_puts_count_tokens_before_stream_flag() {
+        assert_eq!(
+            HedgeEndpointClass::classify(true, true),
+            HedgeEndpointClass::CountTokens
+        );
+        assert_eq!(
+            HedgeEndpointClass::classify(false, true),
+            HedgeEndpointClass::Streaming
+        );
+        assert_eq!(
+            HedgeEndpointClass::classify(false, false),
+            HedgeEndpointClass::NonStreaming
+        );
+    }
+
+    #[test]
+    fn is_stream_request_detects_body_flag_and_gemini_stream_path() {
+        let stream = serde_json::json!({"model": "m", "stream": true});
+        let non_stream = serde_json::json!({"model": "m"});
+
+        assert!(is_stream_request("/v1/messages", Some(&stream)));
+        assert!(!is_stream_request("/v1/messages", Some(&non_stream)));
+        assert!(!is_stream_request("/v1/messages", None));
+        assert!(is_stream_request(
+            "/v1beta/models/g:streamGenerateContent",
+            None
+        ));
+    }
+
+    #[test]
+    fn handler_runtime_settings_mirrors_only_when_enabled_and_not_count_tokens() {
+        let mut cfg = settings::AppSettings {
+            request_mirror_provider_id: 42,
+            request_mirror_percent: 25,
+            ..Default::default()
+        };
+        assert_eq!(
+            handler_runtime_settings(Some(&cfg), false).request_mirror_provider_id,
+            0
+        );
+
+        cfg.request_mirror_enabled = true;
+        let runtime = handler_runtime_settings(Some(&cfg), false);
+        assert_eq!(runtime.request_mirror_provider_id, 42);
+        assert_eq!(runtime.request_mirror_percent, 25);
+        assert_eq!(
+            handler_runtime_settings(Some(&cfg), true).request_mirror_provider_id,
+            0
+        );
+    }
+
     #[test]
     fn apply_session_reuse_binding_noop_when_reuse_disabled() {
         let mut providers = vec![provider(11), provider(22), provider(33)];
@@ -1328,12 +2011,31 @@ mod tests {
         assert_eq!(super::extract_forced_provider_id(&headers), None);
     }
 
+    #[test]
+    fn extract_provider_tag_trims_and_ignores_blank_or_oversized_values() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(super::extract_provider_tag(&headers), None);
+
+        headers.insert("x-aio-provider-tag", HeaderValue::from_static(" cheap "));
+        assert_eq!(
+            super::extract_provider_tag(&headers).as_deref(),
+            Some("cheap")
+        );
+
+        headers.insert("x-aio-provider-tag", HeaderValue::from_static("  "));
+        assert_eq!(super::extract_provider_tag(&headers), None);
+
+        let oversized = HeaderValue::from_str(&"x".repeat(65)).expect("header value");
+        headers.insert("x-aio-provider-tag", oversized);
+        assert_eq!(super::extract_provider_tag(&headers), None);
+    }
+
     #[test]
     fn force_provider_if_requested_keeps_only_selected_provider() {
         let mut providers = vec![provider(1), provider(2), provider(3)];
         let special_settings = super::new_special_settings();
 
-        super::force_provider_if_requested(&mut providers, Some(2), &special_settings);
+        super::force_provider_if_requested(&mut providers, Some(2), "header", &special_settings);
 
         assert_eq!(provider_ids(&providers), vec![2]);
     }
@@ -1343,7 +2045,12 @@ mod tests {
         let mut providers = vec![provider(1), provider(2), provider(3)];
         let special_settings = super::new_special_settings();
 
-        super::force_provider_if_requested(&mut providers, Some(99), &special_settings);
+        super::force_provider_if_requested(
+            &mut providers,
+            Some(99),
+            "session_pin",
+            &special_settings,
+        );
 
         assert!(providers.is_empty());
     }
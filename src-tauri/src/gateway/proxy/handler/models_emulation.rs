@@ -0,0 +1,142 @@
+//! Usage: Answer claude `GET /v1/models` from the first provider's model slots (per-provider toggle).
+
+use super::super::request_end::{
+    emit_request_event_and_spawn_request_log, RequestEndArgs, RequestEndDeps,
+};
+use crate::gateway::claude_models_endpoint;
+use crate::gateway::events::{decision_chain as dc, emit_request_start_event, FailoverAttempt};
+use crate::gateway::manager::GatewayAppState;
+use crate::providers;
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+
+const EMULATED_BASE_URL: &str = "/__aio__/models";
+
+pub(super) struct ModelsEmulationCtx<'a> {
+    pub(super) state: &'a GatewayAppState,
+    pub(super) trace_id: &'a str,
+    pub(super) method_hint: &'a str,
+    pub(super) forwarded_path: &'a str,
+    pub(super) query: Option<&'a str>,
+    pub(super) created_at_ms: i64,
+    pub(super) created_at: i64,
+    pub(super) duration_ms: u128,
+}
+
+/// Returns the provider whose catalog should answer this request, if emulation applies.
+pub(super) fn emulating_provider<'p>(
+    cli_key: &str,
+    method: &axum::http::Method,
+    forwarded_path: &str,
+    providers: &'p [providers::ProviderForGateway],
+) -> Option<&'p providers::ProviderForGateway> {
+    if cli_key != "claude"
+        || !claude_models_endpoint::is_models_list_request(method, forwarded_path)
+    {
+        return None;
+    }
+    providers.first().filter(|p| p.emulate_models_endpoint)
+}
+
+pub(super) fn respond(
+    ctx: &ModelsEmulationCtx<'_>,
+    provider: &providers::ProviderForGateway,
+) -> Response {
+    let response_body = claude_models_endpoint::build_models_response_body(&provider.claude_models);
+    let response_bytes = serde_json::to_vec(&response_body)
+        .map(|v| v.len() as u64)
+        .ok();
+    let special_settings_json = serde_json::json!([{
+        "type": "models_endpoint_emulation",
+        "scope": "request",
+        "hit": true,
+        "providerId": provider.id,
+    }])
+    .to_string();
+
+    emit_request_start_event(
+        &ctx.state.app,
+        ctx.trace_id.to_string(),
+        "claude".to_string(),
+        ctx.method_hint.to_string(),
+        ctx.forwarded_path.to_string(),
+        ctx.query.map(str::to_string),
+        None,
+        ctx.created_at,
+    );
+
+    let attempts = [FailoverAttempt {
+        provider_id: provider.id,
+        provider_name: provider.name.clone(),
+        base_url: EMULATED_BASE_URL.to_string(),
+        outcome: "success".to_string(),
+        status: Some(StatusCode::OK.as_u16()),
+        provider_index: None,
+        retry_index: None,
+        session_reuse: Some(false),
+        error_category: None,
+        error_code: None,
+        decision: Some("success"),
+        reason: None,
+        selection_method: None,
+        reason_code: Some(dc::REASON_REQUEST_SUCCESS),
+        attempt_started_ms: None,
+        attempt_duration_ms: None,
+        circuit_state_before: None,
+        circuit_state_after: None,
+        circuit_failure_count: None,
+        circuit_failure_threshold: None,
+        error_catalog_code: None,
+        response_headers: None,
+        error_body: None,
+        retry_backoff_ms: None,
+        stream_stats: None,
+    }];
+
+    emit_request_event_and_spawn_request_log(RequestEndArgs {
+        deps: RequestEndDeps::from_state(ctx.state),
+        trace_id: ctx.trace_id,
+        cli_key: "claude",
+        method: ctx.method_hint,
+        path: ctx.forwarded_path,
+        query: ctx.query,
+        excluded_from_stats: true,
+        status: Some(StatusCode::OK.as_u16()),
+        error_category: None,
+        error_code: None,
+        duration_ms: ctx.duration_ms,
+        event_ttfb_ms: Some(ctx.duration_ms),
+        log_ttfb_ms: Some(ctx.duration_ms),
+        attempts: &attempts,
+        special_settings_json: Some(special_settings_json),
+        session_id: None,
+        requested_model: None,
+        created_at_ms: ctx.created_at_ms,
+        created_at: ctx.created_at,
+        usage_metrics: None,
+        log_usage_metrics: None,
+        usage: None,
+        request_bytes: None,
+        response_bytes,
+        thinking_budget_tokens: None,
+    });
+
+    let mut resp = (StatusCode::OK, Json(response_body)).into_response();
+    resp.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json; charset=utf-8"),
+    );
+    resp.headers_mut()
+        .insert("x-aio-intercepted", HeaderValue::from_static("models"));
+    resp.headers_mut().insert(
+        "x-aio-intercepted-by",
+        HeaderValue::from_static("aio-coding-hub"),
+    );
+    if let Ok(v) = HeaderValue::from_str(ctx.trace_id) {
+        resp.headers_mut().insert("x-trace-id", v);
+    }
+    resp
+}
@@ -33,6 +33,22 @@ pub(super) fn reorder_providers_by_bound_order(
     *providers = reordered;
 }
 
+/// Move `deferred_ids` behind the remaining providers, keeping relative order in both groups.
+pub(super) fn defer_providers(
+    providers: &mut Vec<providers::ProviderForGateway>,
+    deferred_ids: &[i64],
+) {
+    if deferred_ids.is_empty() || providers.len() <= 1 {
+        return;
+    }
+
+    let (kept, deferred): (Vec<_>, Vec<_>) = providers
+        .drain(..)
+        .partition(|item| !deferred_ids.contains(&item.id));
+    providers.extend(kept);
+    providers.extend(deferred);
+}
+
 pub(super) fn apply_session_provider_preference(
     providers: &mut Vec<providers::ProviderForGateway>,
     bound_provider_id: i64,
@@ -70,7 +86,9 @@ pub(super) fn apply_session_provider_preference(
 
 #[cfg(test)]
 mod tests {
-    use super::{apply_session_provider_preference, reorder_providers_by_bound_order};
+    use super::{
+        apply_session_provider_preference, defer_providers, reorder_providers_by_bound_order,
+    };
     use crate::providers;
 
     fn provider(id: i64) -> providers::ProviderForGateway {
@@ -88,8 +106,19 @@ mod tests {
             limit_weekly_usd: None,
             limit_monthly_usd: None,
             limit_total_usd: None,
+            monthly_budget_usd: None,
             auth_mode: "api_key".to_string(),
             oauth_provider_type: None,
+            provider_kind: providers::ProviderKind::Standard,
+            model_catalog: Vec::new(),
+            model_catalog_refreshed_at: None,
+            emulate_models_endpoint: false,
+            thinking_budget_cap: None,
+            refusal_patterns: Vec::new(),
+            anthropic_version: None,
+            anthropic_betas: Vec::new(),
+            anthropic_beta_strip: false,
+            tags: Vec::new(),
         }
     }
 
@@ -104,6 +133,13 @@ mod tests {
         assert_eq!(ids(&providers), vec![3, 1, 2, 4]);
     }
 
+    #[test]
+    fn defer_providers_moves_deferred_to_tail_in_order() {
+        let mut providers = vec![provider(1), provider(2), provider(3), provider(4)];
+        defer_providers(&mut providers, &[3, 1]);
+        assert_eq!(ids(&providers), vec![2, 4, 1, 3]);
+    }
+
     #[test]
     fn apply_session_preference_promotes_bound_provider_when_present() {
         let mut providers = vec![provider(11), provider(22), provider(33)];
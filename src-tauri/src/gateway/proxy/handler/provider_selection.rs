@@ -1,19 +1,299 @@
+use super::super::adaptive_order::{rank_provider_ids, HEALTH_WINDOW_SECS};
 use super::super::failover::should_reuse_provider;
 use super::provider_order;
 use crate::gateway::manager::GatewayAppState;
-use crate::providers;
-use crate::{circuit_breaker, session_manager};
+use crate::shared::mutex_ext::MutexExt;
+use crate::{circuit_breaker, db, request_attempt_logs, session_manager};
+use crate::{provider_tag_defaults, providers, sort_modes};
 
 pub(super) struct ProviderSelection {
     pub(super) effective_sort_mode_id: Option<i64>,
     pub(super) providers: Vec<providers::ProviderForGateway>,
     pub(super) bound_provider_order: Option<Vec<i64>>,
+    pub(super) sort_mode_fallback: Option<SortModeFallback>,
+    pub(super) time_window: Option<SortModeTimeWindowRouting>,
+    pub(super) adaptive_order: Option<SortModeAdaptiveRouting>,
+    pub(super) tag_filter: Option<ProviderTagRouting>,
+    /// Tried once after every provider above has failed (never part of `providers`).
+    pub(super) last_resort: Option<providers::ProviderForGateway>,
+}
+
+/// Time-window routing applied to the effective sort mode (recorded in special_settings).
+pub(super) struct SortModeTimeWindowRouting {
+    pub(super) mode_id: i64,
+    pub(super) local_time: String,
+    pub(super) active_provider_ids: Vec<i64>,
+    pub(super) deferred_provider_ids: Vec<i64>,
+}
+
+/// Latency/success ranking applied to an `adaptive` sort mode (recorded in special_settings).
+pub(super) struct SortModeAdaptiveRouting {
+    pub(super) mode_id: i64,
+    pub(super) ranked_provider_ids: Vec<i64>,
+}
+
+/// Tag a request is restricted to, and where it came from.
+pub(super) struct ProviderTagFilter {
+    pub(super) tag: String,
+    /// `header`, `session` or `cli_default`.
+    pub(super) source: &'static str,
+}
+
+/// Tag filter applied to the candidate list before ordering (recorded in special_settings).
+pub(super) struct ProviderTagRouting {
+    pub(super) tag: String,
+    pub(super) source: &'static str,
+    pub(super) matched_provider_ids: Vec<i64>,
+    pub(super) excluded_provider_ids: Vec<i64>,
+}
+
+pub(super) struct SortModeFallback {
+    pub(super) from_mode_id: Option<i64>,
+    pub(super) to_mode_id: Option<i64>,
+    pub(super) chain_index: usize,
+}
+
+fn is_provider_available(
+    circuit: &circuit_breaker::CircuitBreaker,
+    provider_id: i64,
+    now_unix: i64,
+) -> bool {
+    // Read-only mirror of `should_allow`: an expired OPEN state counts as available unless it
+    // has to pass a half-open probe first.
+    let snap = circuit.snapshot(provider_id, now_unix);
+    let open = match snap.state {
+        circuit_breaker::CircuitState::Closed => false,
+        circuit_breaker::CircuitState::Open => {
+            circuit.half_open_probe() || snap.open_until.map(|t| now_unix < t).unwrap_or(false)
+        }
+        circuit_breaker::CircuitState::HalfOpen => circuit.half_open_probe(),
+    };
+    let cooling_down = snap.cooldown_until.map(|t| now_unix < t).unwrap_or(false);
+    !open && !cooling_down
+}
+
+fn any_provider_available(
+    circuit: &circuit_breaker::CircuitBreaker,
+    providers: &[providers::ProviderForGateway],
+    now_unix: i64,
+) -> bool {
+    providers
+        .iter()
+        .any(|p| is_provider_available(circuit, p.id, now_unix))
+}
+
+/// Whether routing has anything to try right now, the last-resort provider included.
+pub(super) fn has_available_provider(
+    circuit: &circuit_breaker::CircuitBreaker,
+    providers: &[providers::ProviderForGateway],
+    last_resort: Option<&providers::ProviderForGateway>,
+    now_unix: i64,
+) -> bool {
+    any_provider_available(circuit, providers, now_unix)
+        || last_resort.is_some_and(|p| is_provider_available(circuit, p.id, now_unix))
+}
+
+fn provider_has_tag(provider: &providers::ProviderForGateway, tag: &str) -> bool {
+    provider.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+}
+
+/// Header tag first, then the tag the session picked earlier, then the cli default. Best-effort:
+/// a failed default lookup leaves the request unfiltered.
+pub(super) fn resolve_provider_tag_filter(
+    state: &GatewayAppState,
+    cli_key: &str,
+    session_id: Option<&str>,
+    header_tag: Option<String>,
+    created_at: i64,
+) -> Option<ProviderTagFilter> {
+    if let Some(tag) = header_tag {
+        return Some(ProviderTagFilter {
+            tag,
+            source: "header",
+        });
+    }
+    if let Some(tag) = session_id.and_then(|sid| {
+        state
+            .session
+            .get_bound_provider_tag(cli_key, sid, created_at)
+    }) {
+        return Some(ProviderTagFilter {
+            tag,
+            source: "session",
+        });
+    }
+    match provider_tag_defaults::get_for_cli(&state.db, cli_key) {
+        Ok(tag) => tag.map(|tag| ProviderTagFilter {
+            tag,
+            source: "cli_default",
+        }),
+        Err(err) => {
+            tracing::warn!(
+                cli_key = %cli_key,
+                "provider default tag lookup failed: {}",
+                err
+            );
+            None
+        }
+    }
+}
+
+/// Drop providers without the filter's tag. An empty result is kept: the request asked for a
+/// tag nobody carries, which must not silently widen to every provider.
+fn apply_provider_tag_filter(
+    filter: &ProviderTagFilter,
+    providers: &mut Vec<providers::ProviderForGateway>,
+) -> ProviderTagRouting {
+    let (matched, excluded): (Vec<_>, Vec<_>) = providers
+        .drain(..)
+        .partition(|p| provider_has_tag(p, &filter.tag));
+    *providers = matched;
+    ProviderTagRouting {
+        tag: filter.tag.clone(),
+        source: filter.source,
+        matched_provider_ids: providers.iter().map(|p| p.id).collect(),
+        excluded_provider_ids: excluded.iter().map(|p| p.id).collect(),
+    }
+}
+
+/// Demote providers whose sort-mode time window is not active right now. Best-effort: a failed
+/// lookup keeps the configured order.
+fn apply_sort_mode_time_windows(
+    db: &db::Db,
+    cli_key: &str,
+    mode_id: Option<i64>,
+    now_unix: i64,
+    providers: &mut Vec<providers::ProviderForGateway>,
+) -> Option<SortModeTimeWindowRouting> {
+    let mode_id = mode_id?;
+    let windows = match sort_modes::time_windows_at(db, mode_id, cli_key, now_unix) {
+        Ok(windows) => windows?,
+        Err(err) => {
+            tracing::warn!(
+                cli_key = %cli_key,
+                mode_id,
+                "sort mode time windows lookup failed: {}",
+                err
+            );
+            return None;
+        }
+    };
+
+    provider_order::defer_providers(providers, &windows.deferred_provider_ids);
+    Some(SortModeTimeWindowRouting {
+        mode_id,
+        local_time: windows.local_time,
+        active_provider_ids: windows.active_provider_ids,
+        deferred_provider_ids: windows.deferred_provider_ids,
+    })
+}
+
+/// Re-rank providers of an `adaptive` sort mode by rolling attempt health. Best-effort: a failed
+/// lookup keeps the configured order.
+fn apply_sort_mode_adaptive_order(
+    state: &GatewayAppState,
+    cli_key: &str,
+    mode_id: Option<i64>,
+    now_unix: i64,
+    providers: &mut Vec<providers::ProviderForGateway>,
+) -> Option<SortModeAdaptiveRouting> {
+    let mode_id = mode_id?;
+    if providers.len() <= 1 {
+        return None;
+    }
+    match sort_modes::is_adaptive(&state.db, mode_id) {
+        Ok(true) => {}
+        Ok(false) => return None,
+        Err(err) => {
+            tracing::warn!(
+                cli_key = %cli_key,
+                mode_id,
+                "sort mode strategy lookup failed: {}",
+                err
+            );
+            return None;
+        }
+    }
+
+    let cached = state
+        .adaptive_order
+        .lock_or_recover()
+        .fresh(cli_key, now_unix);
+    let health = match cached {
+        Some(health) => health,
+        None => {
+            let since = now_unix.saturating_sub(HEALTH_WINDOW_SECS);
+            match request_attempt_logs::provider_health_since(&state.db, cli_key, since) {
+                Ok(health) => {
+                    state.adaptive_order.lock_or_recover().insert(
+                        cli_key,
+                        now_unix,
+                        health.clone(),
+                    );
+                    health
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        cli_key = %cli_key,
+                        mode_id,
+                        "adaptive sort mode health lookup failed: {}",
+                        err
+                    );
+                    return None;
+                }
+            }
+        }
+    };
+
+    let provider_ids: Vec<i64> = providers.iter().map(|p| p.id).collect();
+    let ranked_provider_ids = rank_provider_ids(&provider_ids, &health);
+    provider_order::reorder_providers_by_bound_order(providers, &ranked_provider_ids);
+    Some(SortModeAdaptiveRouting {
+        mode_id,
+        ranked_provider_ids,
+    })
+}
+
+/// Walk the cli's fallback chain and return the first mode with an available provider (carrying
+/// `tag`, when set).
+pub(super) fn resolve_sort_mode_fallback(
+    db: &db::Db,
+    circuit: &circuit_breaker::CircuitBreaker,
+    cli_key: &str,
+    from_mode_id: Option<i64>,
+    tag: Option<&str>,
+    now_unix: i64,
+) -> crate::shared::error::AppResult<Option<(SortModeFallback, Vec<providers::ProviderForGateway>)>>
+{
+    let chain = sort_modes::get_fallback_chain(db, cli_key)?;
+    for (chain_index, to_mode_id) in chain.mode_ids.into_iter().enumerate() {
+        if to_mode_id == from_mode_id {
+            continue;
+        }
+        let providers = providers::list_enabled_for_gateway_in_mode(db, cli_key, to_mode_id)?;
+        let available = providers
+            .iter()
+            .filter(|p| tag.is_none_or(|tag| provider_has_tag(p, tag)))
+            .any(|p| is_provider_available(circuit, p.id, now_unix));
+        if available {
+            return Ok(Some((
+                SortModeFallback {
+                    from_mode_id,
+                    to_mode_id,
+                    chain_index,
+                },
+                providers,
+            )));
+        }
+    }
+    Ok(None)
 }
 
 pub(super) fn select_providers_with_session_binding(
     state: &GatewayAppState,
     cli_key: &str,
     session_id: Option<&str>,
+    tag_filter: Option<&ProviderTagFilter>,
     created_at: i64,
 ) -> crate::shared::error::AppResult<ProviderSelection> {
     let bound_sort_mode_id = session_id.and_then(|sid| {
@@ -22,7 +302,7 @@ pub(super) fn select_providers_with_session_binding(
             .get_bound_sort_mode_id(cli_key, sid, created_at)
     });
 
-    let (effective_sort_mode_id, mut providers) = match bound_sort_mode_id {
+    let (mut effective_sort_mode_id, mut providers) = match bound_sort_mode_id {
         Some(sort_mode_id) => {
             let providers =
                 providers::list_enabled_for_gateway_in_mode(&state.db, cli_key, sort_mode_id)?;
@@ -35,6 +315,23 @@ pub(super) fn select_providers_with_session_binding(
         }
     };
 
+    let mut tag_routing =
+        tag_filter.map(|filter| apply_provider_tag_filter(filter, &mut providers));
+    let mut adaptive_order = apply_sort_mode_adaptive_order(
+        state,
+        cli_key,
+        effective_sort_mode_id,
+        created_at,
+        &mut providers,
+    );
+    let mut time_window = apply_sort_mode_time_windows(
+        &state.db,
+        cli_key,
+        effective_sort_mode_id,
+        created_at,
+        &mut providers,
+    );
+
     let mut bound_provider_order: Option<Vec<i64>> = None;
     if let Some(sid) = session_id {
         let provider_order: Vec<i64> = providers.iter().map(|p| p.id).collect();
@@ -45,6 +342,11 @@ pub(super) fn select_providers_with_session_binding(
             Some(provider_order),
             created_at,
         );
+        if let Some(filter) = tag_filter.filter(|f| f.source == "header") {
+            state
+                .session
+                .bind_provider_tag(cli_key, sid, &filter.tag, created_at);
+        }
 
         bound_provider_order = state
             .session
@@ -55,10 +357,51 @@ pub(super) fn select_providers_with_session_binding(
         }
     }
 
+    // The session stays bound to its own mode, so requests return to it once it recovers.
+    let mut sort_mode_fallback = None;
+    if !any_provider_available(&state.circuit, &providers, created_at) {
+        if let Some((fallback, fallback_providers)) = resolve_sort_mode_fallback(
+            &state.db,
+            &state.circuit,
+            cli_key,
+            effective_sort_mode_id,
+            tag_filter.map(|f| f.tag.as_str()),
+            created_at,
+        )? {
+            effective_sort_mode_id = fallback.to_mode_id;
+            providers = fallback_providers;
+            tag_routing =
+                tag_filter.map(|filter| apply_provider_tag_filter(filter, &mut providers));
+            bound_provider_order = None;
+            sort_mode_fallback = Some(fallback);
+            adaptive_order = apply_sort_mode_adaptive_order(
+                state,
+                cli_key,
+                effective_sort_mode_id,
+                created_at,
+                &mut providers,
+            );
+            time_window = apply_sort_mode_time_windows(
+                &state.db,
+                cli_key,
+                effective_sort_mode_id,
+                created_at,
+                &mut providers,
+            );
+        }
+    }
+
+    let last_resort = providers::get_last_resort_for_gateway(&state.db, cli_key)?;
+
     Ok(ProviderSelection {
         effective_sort_mode_id,
         providers,
         bound_provider_order,
+        sort_mode_fallback,
+        time_window,
+        adaptive_order,
+        tag_filter: tag_routing,
+        last_resort,
     })
 }
 
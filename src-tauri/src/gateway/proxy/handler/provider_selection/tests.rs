@@ -1,6 +1,9 @@
-use super::resolve_session_bound_provider_id;
+use super::{
+    apply_provider_tag_filter, resolve_session_bound_provider_id, resolve_sort_mode_fallback,
+    ProviderTagFilter,
+};
 use crate::circuit_breaker;
-use crate::{providers, session_manager};
+use crate::{providers, session_manager, sort_modes};
 use std::collections::HashMap;
 
 fn ids(items: &[providers::ProviderForGateway]) -> Vec<i64> {
@@ -8,6 +11,15 @@ fn ids(items: &[providers::ProviderForGateway]) -> Vec<i64> {
 }
 
 fn insert_provider(db: &crate::db::Db, name: &str, enabled: bool) -> providers::ProviderSummary {
+    insert_tagged_provider(db, name, enabled, &[])
+}
+
+fn insert_tagged_provider(
+    db: &crate::db::Db,
+    name: &str,
+    enabled: bool,
+    tags: &[&str],
+) -> providers::ProviderSummary {
     providers::upsert(
         db,
         providers::ProviderUpsertParams {
@@ -29,8 +41,9 @@ fn insert_provider(db: &crate::db::Db, name: &str, enabled: bool) -> providers::
             limit_weekly_usd: None,
             limit_monthly_usd: None,
             limit_total_usd: None,
-            tags: None,
+            tags: (!tags.is_empty()).then(|| tags.iter().map(|t| t.to_string()).collect()),
             note: None,
+            provider_kind: None,
         },
     )
     .expect("insert provider")
@@ -210,3 +223,129 @@ fn resolve_session_bound_provider_id_clears_stale_binding_when_bound_provider_no
     assert_eq!(ids(&candidates), vec![id2]);
     assert_eq!(session.get_bound_provider("claude", "sess_1", now), None);
 }
+
+#[test]
+fn resolve_sort_mode_fallback_skips_modes_without_available_providers() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let db_path = dir.path().join("test.db");
+    let db = crate::db::init_for_tests(&db_path).expect("init db");
+
+    let id1 = insert_provider(&db, "P1", true).id;
+    let id2 = insert_provider(&db, "P2", true).id;
+    let id3 = insert_provider(&db, "P3", true).id;
+
+    let mode_a = sort_modes::create_mode(&db, "A").expect("create mode a").id;
+    let mode_b = sort_modes::create_mode(&db, "B").expect("create mode b").id;
+    let mode_c = sort_modes::create_mode(&db, "C").expect("create mode c").id;
+    sort_modes::set_mode_providers_order(&db, mode_a, "claude", vec![id1]).expect("order a");
+    sort_modes::set_mode_providers_order(&db, mode_b, "claude", vec![id2]).expect("order b");
+    sort_modes::set_mode_providers_order(&db, mode_c, "claude", vec![id3]).expect("order c");
+    sort_modes::set_fallback_chain(
+        &db,
+        "claude",
+        vec![Some(mode_a), Some(mode_b), Some(mode_c)],
+    )
+    .expect("set chain");
+
+    let circuit = circuit_breaker::CircuitBreaker::new(
+        circuit_breaker::CircuitBreakerConfig::default(),
+        HashMap::new(),
+        None,
+    );
+    let now = 1000;
+    for provider_id in [id1, id2] {
+        for _ in 0..circuit_breaker::CircuitBreakerConfig::default().failure_threshold {
+            circuit.record_failure(provider_id, now);
+        }
+    }
+
+    let (fallback, selected) =
+        resolve_sort_mode_fallback(&db, &circuit, "claude", Some(mode_a), None, now)
+            .expect("resolve fallback")
+            .expect("fallback mode");
+    assert_eq!(fallback.from_mode_id, Some(mode_a));
+    assert_eq!(fallback.to_mode_id, Some(mode_c));
+    assert_eq!(fallback.chain_index, 2);
+    assert_eq!(ids(&selected), vec![id3]);
+
+    circuit.trigger_cooldown(id3, now, 60);
+    let none = resolve_sort_mode_fallback(&db, &circuit, "claude", Some(mode_a), None, now)
+        .expect("resolve fallback");
+    assert!(none.is_none());
+}
+
+#[test]
+fn provider_tag_filter_restricts_candidates_and_fallback() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let db_path = dir.path().join("test.db");
+    let db = crate::db::init_for_tests(&db_path).expect("init db");
+
+    let id1 = insert_tagged_provider(&db, "P1", true, &["fast"]).id;
+    let id2 = insert_tagged_provider(&db, "P2", true, &["Cheap", "fast"]).id;
+    let id3 = insert_provider(&db, "P3", true).id;
+
+    let mut candidates =
+        providers::list_enabled_for_gateway_in_mode(&db, "claude", None).expect("list enabled");
+    let filter = ProviderTagFilter {
+        tag: "cheap".to_string(),
+        source: "header",
+    };
+    let routing = apply_provider_tag_filter(&filter, &mut candidates);
+    assert_eq!(ids(&candidates), vec![id2]);
+    assert_eq!(routing.matched_provider_ids, vec![id2]);
+    assert_eq!(routing.excluded_provider_ids.len(), 2);
+    assert!(routing.excluded_provider_ids.contains(&id1));
+    assert!(routing.excluded_provider_ids.contains(&id3));
+
+    let mode_a = sort_modes::create_mode(&db, "A").expect("create mode a").id;
+    let mode_b = sort_modes::create_mode(&db, "B").expect("create mode b").id;
+    let mode_c = sort_modes::create_mode(&db, "C").expect("create mode c").id;
+    sort_modes::set_mode_providers_order(&db, mode_b, "claude", vec![id3]).expect("order b");
+    sort_modes::set_mode_providers_order(&db, mode_c, "claude", vec![id3, id2]).expect("order c");
+    sort_modes::set_fallback_chain(&db, "claude", vec![Some(mode_b), Some(mode_c)])
+        .expect("set chain");
+
+    let circuit = circuit_breaker::CircuitBreaker::new(
+        circuit_breaker::CircuitBreakerConfig::default(),
+        HashMap::new(),
+        None,
+    );
+    let (fallback, _) =
+        resolve_sort_mode_fallback(&db, &circuit, "claude", Some(mode_a), Some("cheap"), 1000)
+            .expect("resolve fallback")
+            .expect("fallback mode");
+    assert_eq!(fallback.to_mode_id, Some(mode_c));
+
+    let none = resolve_sort_mode_fallback(&db, &circuit, "claude", Some(mode_a), Some("gpu"), 1000)
+        .expect("resolve fallback");
+    assert!(none.is_none());
+}
+
+#[test]
+fn last_resort_provider_is_kept_out_of_regular_routing() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let db_path = dir.path().join("test.db");
+    let db = crate::db::init_for_tests(&db_path).expect("init db");
+
+    let p1 = insert_provider(&db, "P1", true);
+    let p2 = insert_provider(&db, "P2", true);
+    let local = insert_provider(&db, "Local", true);
+
+    providers::set_last_resort(&db, p2.id, true).expect("flag p2");
+    let flagged = providers::set_last_resort(&db, local.id, true).expect("flag local");
+    assert!(flagged.last_resort);
+
+    let enabled =
+        providers::list_enabled_for_gateway_in_mode(&db, "claude", None).expect("list enabled");
+    assert_eq!(ids(&enabled), vec![p1.id, p2.id]);
+
+    let last_resort = providers::get_last_resort_for_gateway(&db, "claude")
+        .expect("get last resort")
+        .expect("last resort provider");
+    assert_eq!(last_resort.id, local.id);
+
+    providers::set_last_resort(&db, local.id, false).expect("unflag local");
+    assert!(providers::get_last_resort_for_gateway(&db, "claude")
+        .expect("get last resort")
+        .is_none());
+}
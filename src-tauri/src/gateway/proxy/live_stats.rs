@@ -0,0 +1,263 @@
+//! Usage: In-memory per-provider live counters for the realtime ops panel.
+//!
+//! Every upstream attempt holds a [`LiveAttemptGuard`] while it is in flight; event-stream
+//! responses hand the guard to the stream finalize context so the provider stays "busy" until
+//! the body relay ends. Snapshots are pushed as `gateway:live_stats` events and served by
+//! `gateway_live_stats` without touching the database.
+
+use crate::circuit_breaker;
+use crate::gateway::GatewayProviderLiveStats;
+use crate::shared::mutex_ext::MutexExt;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::Emitter;
+
+use crate::gateway::util::{now_unix_millis, now_unix_seconds};
+
+pub(in crate::gateway) const LIVE_STATS_EVENT_NAME: &str = "gateway:live_stats";
+
+const RECENT_WINDOW_MS: i64 = 60_000;
+const TTFT_SAMPLES: usize = 20;
+/// Idle providers (nothing in flight, no recent request) are dropped after this long.
+const IDLE_EVICT_MS: i64 = 10 * 60_000;
+const EMIT_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Default)]
+struct ProviderCounters {
+    cli_key: String,
+    provider_name: String,
+    in_flight: u32,
+    active_streams: u32,
+    recent_starts_ms: VecDeque<i64>,
+    ttft_samples_ms: VecDeque<u32>,
+    last_activity_ms: i64,
+}
+
+impl ProviderCounters {
+    fn prune(&mut self, now_ms: i64) {
+        let cutoff = now_ms.saturating_sub(RECENT_WINDOW_MS);
+        while self.recent_starts_ms.front().is_some_and(|at| *at < cutoff) {
+            self.recent_starts_ms.pop_front();
+        }
+    }
+
+    fn avg_ttft_ms(&self) -> Option<u32> {
+        if self.ttft_samples_ms.is_empty() {
+            return None;
+        }
+        let sum: u64 = self.ttft_samples_ms.iter().map(|v| u64::from(*v)).sum();
+        Some((sum / self.ttft_samples_ms.len() as u64) as u32)
+    }
+}
+
+#[derive(Debug, Default)]
+pub(in crate::gateway) struct ProviderLiveStats {
+    providers: HashMap<i64, ProviderCounters>,
+}
+
+impl ProviderLiveStats {
+    fn begin(&mut self, provider_id: i64, cli_key: &str, provider_name: &str, now_ms: i64) {
+        let counters = self.providers.entry(provider_id).or_default();
+        if counters.cli_key != cli_key {
+            counters.cli_key = cli_key.to_string();
+        }
+        if counters.provider_name != provider_name {
+            counters.provider_name = provider_name.to_string();
+        }
+        counters.in_flight = counters.in_flight.saturating_add(1);
+        counters.prune(now_ms);
+        counters.recent_starts_ms.push_back(now_ms);
+        counters.last_activity_ms = now_ms;
+    }
+
+    fn end(&mut self, provider_id: i64, streaming: bool, now_ms: i64) {
+        if let Some(counters) = self.providers.get_mut(&provider_id) {
+            counters.in_flight = counters.in_flight.saturating_sub(1);
+            if streaming {
+                counters.active_streams = counters.active_streams.saturating_sub(1);
+            }
+            counters.last_activity_ms = now_ms;
+        }
+    }
+
+    fn start_stream(&mut self, provider_id: i64) {
+        if let Some(counters) = self.providers.get_mut(&provider_id) {
+            counters.active_streams = counters.active_streams.saturating_add(1);
+        }
+    }
+
+    fn record_ttft(&mut self, provider_id: i64, ttft_ms: u32) {
+        if let Some(counters) = self.providers.get_mut(&provider_id) {
+            if counters.ttft_samples_ms.len() >= TTFT_SAMPLES {
+                counters.ttft_samples_ms.pop_front();
+            }
+            counters.ttft_samples_ms.push_back(ttft_ms);
+        }
+    }
+
+    /// Circuit state is filled in by the caller (see [`snapshot_with_circuit`]).
+    fn snapshot(&mut self, now_ms: i64) -> Vec<GatewayProviderLiveStats> {
+        self.providers.retain(|_, counters| {
+            counters.prune(now_ms);
+            counters.in_flight > 0
+                || !counters.recent_starts_ms.is_empty()
+                || now_ms.saturating_sub(counters.last_activity_ms) < IDLE_EVICT_MS
+        });
+        let mut out: Vec<GatewayProviderLiveStats> = self
+            .providers
+            .iter()
+            .map(|(provider_id, counters)| GatewayProviderLiveStats {
+                provider_id: *provider_id,
+                cli_key: counters.cli_key.clone(),
+                provider_name: counters.provider_name.clone(),
+                in_flight: counters.in_flight,
+                active_streams: counters.active_streams,
+                requests_last_minute: counters.recent_starts_ms.len() as u32,
+                avg_ttft_ms: counters.avg_ttft_ms(),
+                circuit_state: circuit_breaker::CircuitState::Closed.as_str().to_string(),
+            })
+            .collect();
+        out.sort_by(|a, b| {
+            a.cli_key
+                .cmp(&b.cli_key)
+                .then(a.provider_id.cmp(&b.provider_id))
+        });
+        out
+    }
+}
+
+pub(in crate::gateway) fn snapshot_with_circuit(
+    stats: &Mutex<ProviderLiveStats>,
+    circuit: &circuit_breaker::CircuitBreaker,
+) -> Vec<GatewayProviderLiveStats> {
+    let mut out = stats.lock_or_recover().snapshot(now_unix_millis() as i64);
+    let now_unix = now_unix_seconds() as i64;
+    for item in &mut out {
+        item.circuit_state = circuit
+            .snapshot(item.provider_id, now_unix)
+            .state
+            .as_str()
+            .to_string();
+    }
+    out
+}
+
+/// Decrements the provider's in-flight (and, once streaming, active stream) count on drop.
+pub(in crate::gateway) struct LiveAttemptGuard {
+    stats: Arc<Mutex<ProviderLiveStats>>,
+    provider_id: i64,
+    streaming: bool,
+}
+
+impl LiveAttemptGuard {
+    pub(in crate::gateway) fn begin(
+        stats: &Arc<Mutex<ProviderLiveStats>>,
+        provider_id: i64,
+        cli_key: &str,
+        provider_name: &str,
+    ) -> Self {
+        stats.lock_or_recover().begin(
+            provider_id,
+            cli_key,
+            provider_name,
+            now_unix_millis() as i64,
+        );
+        Self {
+            stats: stats.clone(),
+            provider_id,
+            streaming: false,
+        }
+    }
+
+    pub(in crate::gateway) fn record_ttft(&self, ttft: Duration) {
+        let ttft_ms = u32::try_from(ttft.as_millis()).unwrap_or(u32::MAX);
+        self.stats
+            .lock_or_recover()
+            .record_ttft(self.provider_id, ttft_ms);
+    }
+
+    pub(in crate::gateway) fn start_stream(&mut self) {
+        if self.streaming {
+            return;
+        }
+        self.streaming = true;
+        self.stats.lock_or_recover().start_stream(self.provider_id);
+    }
+}
+
+impl Drop for LiveAttemptGuard {
+    fn drop(&mut self) {
+        self.stats.lock_or_recover().end(
+            self.provider_id,
+            self.streaming,
+            now_unix_millis() as i64,
+        );
+    }
+}
+
+/// Pushes a snapshot every [`EMIT_INTERVAL`] while there is something to show (plus one empty
+/// snapshot once the gateway goes idle).
+pub(in crate::gateway) fn spawn_emitter(
+    app: crate::AppHandle,
+    stats: Arc<Mutex<ProviderLiveStats>>,
+    circuit: Arc<circuit_breaker::CircuitBreaker>,
+) -> tauri::async_runtime::JoinHandle<()> {
+    tauri::async_runtime::spawn(async move {
+        let mut was_empty = true;
+        loop {
+            tokio::time::sleep(EMIT_INTERVAL).await;
+            let snapshot = snapshot_with_circuit(&stats, &circuit);
+            let is_empty = snapshot
+                .iter()
+                .all(|item| item.in_flight == 0 && item.requests_last_minute == 0);
+            if is_empty && was_empty {
+                continue;
+            }
+            was_empty = is_empty;
+            let _ = app.emit(LIVE_STATS_EVENT_NAME, snapshot);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_in_flight_streams_and_ttft() {
+        let stats = Arc::new(Mutex::new(ProviderLiveStats::default()));
+
+        let first = LiveAttemptGuard::begin(&stats, 7, "claude", "p7");
+        let mut second = LiveAttemptGuard::begin(&stats, 7, "claude", "p7");
+        first.record_ttft(Duration::from_millis(100));
+        second.record_ttft(Duration::from_millis(300));
+        second.start_stream();
+
+        let snap = stats.lock_or_recover().snapshot(now_unix_millis() as i64);
+        assert_eq!(snap.len(), 1);
+        assert_eq!(snap[0].in_flight, 2);
+        assert_eq!(snap[0].active_streams, 1);
+        assert_eq!(snap[0].requests_last_minute, 2);
+        assert_eq!(snap[0].avg_ttft_ms, Some(200));
+
+        drop(first);
+        drop(second);
+        let snap = stats.lock_or_recover().snapshot(now_unix_millis() as i64);
+        assert_eq!(snap[0].in_flight, 0);
+        assert_eq!(snap[0].active_streams, 0);
+        assert_eq!(snap[0].requests_last_minute, 2);
+    }
+
+    #[test]
+    fn recent_requests_expire_and_idle_providers_are_evicted() {
+        let mut stats = ProviderLiveStats::default();
+        stats.begin(1, "codex", "p1", 1_000);
+        stats.end(1, false, 2_000);
+
+        let snap = stats.snapshot(2_000 + RECENT_WINDOW_MS);
+        assert_eq!(snap[0].requests_last_minute, 0);
+
+        assert!(stats.snapshot(2_000 + IDLE_EVICT_MS).is_empty());
+    }
+}
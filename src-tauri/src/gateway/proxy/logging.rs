@@ -1,6 +1,6 @@
 //! Usage: Best-effort enqueue to DB log tasks with backpressure and fallbacks.
 
-use crate::{db, request_logs};
+use crate::{db, redaction, request_logs};
 use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::time::Duration;
 
@@ -48,6 +48,9 @@ fn request_log_insert_from_args(
         created_at,
         usage_metrics,
         usage,
+        request_bytes,
+        response_bytes,
+        thinking_budget_tokens,
     } = args;
 
     if !crate::shared::cli_key::is_supported_cli_key(cli_key.as_str()) {
@@ -75,12 +78,12 @@ fn request_log_insert_from_args(
         path,
         query,
         excluded_from_stats,
-        special_settings_json,
+        special_settings_json: special_settings_json.map(|raw| redaction::redact_json_str(&raw)),
         status: status.map(|v| v as i64),
         error_code: error_code.map(str::to_string),
         duration_ms,
         ttfb_ms,
-        attempts_json,
+        attempts_json: redaction::redact_json_str(&attempts_json),
         input_tokens: metrics.input_tokens,
         output_tokens: metrics.output_tokens,
         total_tokens: metrics.total_tokens,
@@ -92,11 +95,14 @@ fn request_log_insert_from_args(
         requested_model,
         created_at_ms,
         created_at,
+        request_bytes: request_bytes.map(|v| v.min(i64::MAX as u64) as i64),
+        response_bytes: response_bytes.map(|v| v.min(i64::MAX as u64) as i64),
+        thinking_budget_tokens: thinking_budget_tokens.map(|v| v.min(i64::MAX as u64) as i64),
     })
 }
 
 pub(super) async fn enqueue_request_log_with_backpressure(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     db: &db::Db,
     log_tx: &tokio::sync::mpsc::Sender<request_logs::RequestLogInsert>,
     args: super::RequestLogEnqueueArgs,
@@ -113,7 +119,7 @@ pub(super) async fn enqueue_request_log_with_backpressure(
     let reserve = tokio::time::timeout(LOG_ENQUEUE_MAX_WAIT, log_tx.reserve()).await;
     match reserve {
         Ok(Ok(permit)) => {
-            permit.send(insert);
+            request_logs::send_buffered(permit, insert);
         }
         Ok(Err(_)) => {
             emit_gateway_log(
@@ -128,7 +134,7 @@ pub(super) async fn enqueue_request_log_with_backpressure(
             request_logs::spawn_write_through(app.clone(), db.clone(), insert);
         }
         Err(_) => {
-            match log_tx.try_send(insert) {
+            match request_logs::try_send_buffered(log_tx, insert) {
                 Ok(()) => {
                     emit_gateway_log(
                         app,
@@ -196,7 +202,7 @@ pub(super) async fn enqueue_request_log_with_backpressure(
 }
 
 pub(in crate::gateway) fn spawn_enqueue_request_log_with_backpressure(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db: db::Db,
     log_tx: tokio::sync::mpsc::Sender<request_logs::RequestLogInsert>,
     args: super::RequestLogEnqueueArgs,
@@ -231,6 +237,9 @@ mod tests {
             created_at: 0,
             usage_metrics: None,
             usage: None,
+            request_bytes: None,
+            response_bytes: None,
+            thinking_budget_tokens: None,
         }
     }
 
@@ -0,0 +1,331 @@
+//! Usage: Request mirroring (fire-and-forget shadow copies to an evaluation provider).
+//!
+//! A sampled share of requests is re-sent to the configured shadow provider after the real
+//! request has been dispatched. The shadow response is drained and discarded; only status,
+//! latency and token usage are stored in `request_mirror_logs`. Mirrors never touch session
+//! binding, circuit breakers, provider limits, request logs or cost alerts.
+
+use super::failover::select_provider_base_url_for_request;
+use super::model_rewrite::{
+    replace_model_in_body_json, replace_model_in_path, replace_model_in_query,
+};
+use crate::gateway::manager::GatewayAppState;
+use crate::gateway::util::{
+    build_target_url, ensure_cli_required_headers, inject_provider_auth, now_unix_seconds,
+    RequestedModelLocation,
+};
+use crate::{providers, request_mirror, usage};
+use axum::body::Bytes;
+use axum::http::{header, HeaderMap, Method};
+use std::time::{Duration, Instant};
+
+const MIRROR_REQUEST_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+const MAX_MIRROR_JSON_BODY_BYTES: usize = 8 * 1024 * 1024;
+
+pub(super) struct MirrorRequest {
+    pub(super) trace_id: String,
+    pub(super) cli_key: String,
+    pub(super) method: Method,
+    pub(super) forwarded_path: String,
+    pub(super) query: Option<String>,
+    pub(super) requested_model: Option<String>,
+    pub(super) requested_model_location: Option<RequestedModelLocation>,
+    pub(super) introspection_json: Option<serde_json::Value>,
+    pub(super) base_headers: HeaderMap,
+    pub(super) body_bytes: Bytes,
+    pub(super) strip_request_content_encoding: bool,
+    pub(super) provider_base_url_ping_cache_ttl_seconds: u32,
+}
+
+/// `roll` is uniform in `0..100`.
+fn should_mirror(percent: u32, roll: u32) -> bool {
+    roll < percent.min(100)
+}
+
+/// Sample the request and, if selected, mirror it in the background.
+///
+/// `primary_provider_id` is the provider the real request goes to first; mirroring to the same
+/// provider would only double its load, so it is skipped.
+pub(super) fn maybe_spawn(
+    state: &GatewayAppState,
+    mirror_provider_id: i64,
+    mirror_percent: u32,
+    primary_provider_id: Option<i64>,
+    request: impl FnOnce() -> MirrorRequest,
+) {
+    if mirror_provider_id <= 0 || primary_provider_id == Some(mirror_provider_id) {
+        return;
+    }
+    if !should_mirror(mirror_percent, rand::random::<u32>() % 100) {
+        return;
+    }
+
+    let state = state.clone();
+    let request = request();
+    tauri::async_runtime::spawn(async move {
+        run(state, mirror_provider_id, request).await;
+    });
+}
+
+async fn run(state: GatewayAppState, provider_id: i64, request: MirrorRequest) {
+    let created_at = now_unix_seconds() as i64;
+    let started = Instant::now();
+
+    let provider = match crate::blocking::run("request_mirror_provider", {
+        let db = state.db.clone();
+        let cli_key = request.cli_key.clone();
+        move || providers::get_for_gateway(&db, &cli_key, provider_id)
+    })
+    .await
+    {
+        Ok(Some(provider)) => provider,
+        // Shadow provider belongs to another CLI (or was deleted): nothing to evaluate here.
+        Ok(None) => return,
+        Err(err) => {
+            tracing::warn!(
+                provider_id,
+                "request mirror: failed to load provider: {}",
+                err
+            );
+            return;
+        }
+    };
+
+    let outcome = send(&state, &provider, &request).await;
+    let item = request_mirror::RequestMirrorLogInsert {
+        trace_id: request.trace_id,
+        cli_key: request.cli_key,
+        provider_id: provider.id,
+        provider_name: provider.name,
+        method: request.method.to_string(),
+        path: request.forwarded_path,
+        requested_model: request.requested_model,
+        status: outcome.status.map(i64::from),
+        error: outcome.error,
+        ttfb_ms: outcome.ttfb_ms,
+        duration_ms: started.elapsed().as_millis() as i64,
+        input_tokens: outcome.usage.as_ref().and_then(|u| u.input_tokens),
+        output_tokens: outcome.usage.as_ref().and_then(|u| u.output_tokens),
+        cache_read_input_tokens: outcome
+            .usage
+            .as_ref()
+            .and_then(|u| u.cache_read_input_tokens),
+        cache_creation_input_tokens: outcome
+            .usage
+            .as_ref()
+            .and_then(|u| u.cache_creation_input_tokens),
+        created_at,
+    };
+
+    let db = state.db.clone();
+    if let Err(err) = crate::blocking::run("request_mirror_log_insert", move || {
+        request_mirror::insert_and_prune(&db, &item)
+    })
+    .await
+    {
+        tracing::warn!(
+            provider_id,
+            "request mirror: failed to record result: {}",
+            err
+        );
+    }
+}
+
+#[derive(Default)]
+struct MirrorOutcome {
+    status: Option<u16>,
+    error: Option<String>,
+    ttfb_ms: Option<i64>,
+    usage: Option<usage::UsageMetrics>,
+}
+
+impl MirrorOutcome {
+    fn failed(error: impl Into<String>) -> Self {
+        Self {
+            error: Some(error.into()),
+            ..Default::default()
+        }
+    }
+}
+
+async fn send(
+    state: &GatewayAppState,
+    provider: &providers::ProviderForGateway,
+    request: &MirrorRequest,
+) -> MirrorOutcome {
+    if provider.auth_mode == "oauth" {
+        return MirrorOutcome::failed("oauth providers are not supported as mirror target");
+    }
+    let api_key = provider.api_key_plaintext.trim();
+    if api_key.is_empty() {
+        return MirrorOutcome::failed("provider api_key is empty");
+    }
+
+    let base_url = match select_provider_base_url_for_request(
+        state,
+        provider,
+        &request.cli_key,
+        request.provider_base_url_ping_cache_ttl_seconds,
+    )
+    .await
+    {
+        Ok(base_url) => base_url,
+        Err(err) => return MirrorOutcome::failed(err),
+    };
+
+    let mut forwarded_path = request.forwarded_path.clone();
+    let mut query = request.query.clone();
+    let mut body_bytes = request.body_bytes.clone();
+    let mut strip_content_encoding = request.strip_request_content_encoding;
+    apply_claude_model_mapping(
+        provider,
+        request,
+        &mut forwarded_path,
+        &mut query,
+        &mut body_bytes,
+        &mut strip_content_encoding,
+    );
+
+    let url = match build_target_url(&base_url, &forwarded_path, query.as_deref()) {
+        Ok(url) => url,
+        Err(err) => return MirrorOutcome::failed(err),
+    };
+
+    let mut headers = request.base_headers.clone();
+    ensure_cli_required_headers(&request.cli_key, &mut headers);
+    inject_provider_auth(&request.cli_key, api_key, &mut headers);
+    if strip_content_encoding {
+        headers.remove(header::CONTENT_ENCODING);
+    }
+
+    let started = Instant::now();
+    let send = state
+        .client
+        .request(request.method.clone(), url)
+        .headers(headers)
+        .body(body_bytes)
+        .timeout(MIRROR_REQUEST_TIMEOUT)
+        .send();
+    let mut resp = match send.await {
+        Ok(resp) => resp,
+        Err(err) => return MirrorOutcome::failed(err.to_string()),
+    };
+
+    let status = resp.status();
+    let is_sse = resp
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("text/event-stream"));
+
+    let mut ttfb_ms = None;
+    let mut sse_tracker = is_sse.then(|| usage::SseUsageTracker::new(&request.cli_key));
+    let mut json_body: Vec<u8> = Vec::new();
+    let mut error = None;
+    loop {
+        match resp.chunk().await {
+            Ok(Some(chunk)) => {
+                if ttfb_ms.is_none() {
+                    ttfb_ms = Some(started.elapsed().as_millis() as i64);
+                }
+                match sse_tracker.as_mut() {
+                    Some(tracker) => tracker.ingest_chunk(&chunk),
+                    None if json_body.len() + chunk.len() <= MAX_MIRROR_JSON_BODY_BYTES => {
+                        json_body.extend_from_slice(&chunk)
+                    }
+                    None => {}
+                }
+            }
+            Ok(None) => break,
+            Err(err) => {
+                error = Some(err.to_string());
+                break;
+            }
+        }
+    }
+
+    let usage = match sse_tracker {
+        Some(tracker) => tracker.finalize(),
+        None => usage::parse_usage_from_json_bytes(&json_body),
+    }
+    .map(|extract| extract.metrics);
+
+    MirrorOutcome {
+        status: Some(status.as_u16()),
+        error: error.or_else(|| {
+            (!status.is_success()).then(|| format!("upstream returned status {}", status.as_u16()))
+        }),
+        ttfb_ms,
+        usage,
+    }
+}
+
+fn apply_claude_model_mapping(
+    provider: &providers::ProviderForGateway,
+    request: &MirrorRequest,
+    forwarded_path: &mut String,
+    query: &mut Option<String>,
+    body_bytes: &mut Bytes,
+    strip_content_encoding: &mut bool,
+) {
+    if request.cli_key != "claude" || !provider.claude_models.has_any() {
+        return;
+    }
+    let Some(requested_model) = request.requested_model.as_deref() else {
+        return;
+    };
+
+    let has_thinking = request
+        .introspection_json
+        .as_ref()
+        .and_then(|v| v.get("thinking"))
+        .and_then(|v| v.get("type"))
+        .and_then(|v| v.as_str())
+        == Some("enabled");
+    let effective_model = provider.get_effective_claude_model(requested_model, has_thinking);
+    if effective_model == requested_model {
+        return;
+    }
+
+    match request
+        .requested_model_location
+        .unwrap_or(RequestedModelLocation::BodyJson)
+    {
+        RequestedModelLocation::BodyJson => {
+            let Some(root) = request.introspection_json.as_ref() else {
+                return;
+            };
+            let mut next = root.clone();
+            if replace_model_in_body_json(&mut next, &effective_model) {
+                if let Ok(bytes) = serde_json::to_vec(&next) {
+                    *body_bytes = Bytes::from(bytes);
+                    *strip_content_encoding = true;
+                }
+            }
+        }
+        RequestedModelLocation::Query => {
+            if let Some(q) = query.as_deref() {
+                *query = Some(replace_model_in_query(q, &effective_model));
+            }
+        }
+        RequestedModelLocation::Path => {
+            if let Some(next_path) = replace_model_in_path(forwarded_path, &effective_model) {
+                *forwarded_path = next_path;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::should_mirror;
+
+    #[test]
+    fn should_mirror_respects_percent_bounds() {
+        assert!(!should_mirror(0, 0));
+        assert!(should_mirror(10, 9));
+        assert!(!should_mirror(10, 10));
+        assert!(should_mirror(100, 99));
+        assert!(should_mirror(250, 99));
+    }
+}
@@ -1,27 +1,45 @@
 //! Usage: Gateway proxy module facade (exports the proxy handler + shared types).
 
 mod abort_guard;
+mod access_guard;
+mod adaptive_order;
 mod caches;
+pub(in crate::gateway) mod circuit_probe;
 mod cli_proxy_guard;
+mod cost_estimate;
 mod error_code;
 mod errors;
 mod failover;
+mod federation;
 mod forwarder;
 mod gemini_oauth;
 mod handler;
 mod http_util;
+pub(in crate::gateway) mod live_stats;
 mod logging;
+mod mirror;
 mod model_rewrite;
+mod provider_quality;
 pub(in crate::gateway) mod provider_router;
 mod request_context;
+mod request_dedup;
 mod request_end;
 pub(in crate::gateway) mod status_override;
 mod types;
 mod upstream_client_error_rules;
 
+pub(super) use access_guard::require_gateway_access_token;
+pub(super) use adaptive_order::AdaptiveOrderCache;
 pub(super) use caches::{ProviderBaseUrlPingCache, RecentErrorCache};
+pub(in crate::gateway) use cost_estimate::CallCostEstimate;
 pub(super) use error_code::GatewayErrorCode;
+pub(crate) use errors::catalog::{error_catalog_entries, ErrorCatalogEntry};
+pub(in crate::gateway) use live_stats::{LiveAttemptGuard, ProviderLiveStats};
 pub(in crate::gateway) use logging::spawn_enqueue_request_log_with_backpressure;
+pub(in crate::gateway) use provider_quality::{
+    record_request_end as record_provider_quality, ProviderQualityArgs, ProviderQualityTracker,
+};
+pub(super) use request_dedup::InflightRequests;
 pub(super) use types::ErrorCategory;
 
 pub(super) use handler::proxy_impl;
@@ -51,6 +69,9 @@ pub(super) struct RequestLogEnqueueArgs {
     pub(super) created_at: i64,
     pub(super) usage_metrics: Option<crate::usage::UsageMetrics>,
     pub(super) usage: Option<crate::usage::UsageExtract>,
+    pub(super) request_bytes: Option<u64>,
+    pub(super) response_bytes: Option<u64>,
+    pub(super) thinking_budget_tokens: Option<u64>,
 }
 
 #[cfg(test)]
@@ -0,0 +1,442 @@
+//! Usage: Sliding-window provider response quality tracking and auto-quarantine on anomalies.
+
+use super::{provider_router, ErrorCategory, GatewayErrorCode};
+use crate::gateway::events::FailoverAttempt;
+use crate::gateway::provider_webhook;
+use crate::gateway::GatewayProviderQuality;
+use crate::shared::i18n;
+use crate::shared::mutex_ext::MutexExt;
+use crate::{circuit_breaker, notice};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+const WINDOW_SECS: i64 = 5 * 60;
+const MIN_SAMPLES: usize = 10;
+const MAX_SAMPLES_PER_PROVIDER: usize = 512;
+const ERROR_RATE_THRESHOLD: f64 = 0.5;
+const TRUNCATED_RATE_THRESHOLD: f64 = 0.3;
+const QUARANTINE_SECS: i64 = 10 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SampleOutcome {
+    Success,
+    Error,
+    Truncated,
+}
+
+#[derive(Debug, Default)]
+struct ProviderWindow {
+    cli_key: String,
+    provider_name: String,
+    samples: VecDeque<(i64, SampleOutcome)>,
+    quarantined_until: Option<i64>,
+    quarantine_count: u32,
+}
+
+impl ProviderWindow {
+    fn prune(&mut self, now_unix: i64) {
+        let cutoff = now_unix.saturating_sub(WINDOW_SECS);
+        while self.samples.front().is_some_and(|(at, _)| *at < cutoff) {
+            self.samples.pop_front();
+        }
+        if self
+            .quarantined_until
+            .is_some_and(|until| until <= now_unix)
+        {
+            self.quarantined_until = None;
+        }
+    }
+
+    fn counts(&self) -> (usize, usize, usize) {
+        let total = self.samples.len();
+        let errors = self
+            .samples
+            .iter()
+            .filter(|(_, o)| *o == SampleOutcome::Error)
+            .count();
+        let truncated = self
+            .samples
+            .iter()
+            .filter(|(_, o)| *o == SampleOutcome::Truncated)
+            .count();
+        (total, errors, truncated)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Quarantine {
+    provider_id: i64,
+    cli_key: String,
+    provider_name: String,
+    reason: &'static str,
+    rate: f64,
+    samples: usize,
+    until: i64,
+}
+
+fn rate(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64
+    }
+}
+
+#[derive(Debug, Default)]
+pub(in crate::gateway) struct ProviderQualityTracker {
+    providers: HashMap<i64, ProviderWindow>,
+}
+
+impl ProviderQualityTracker {
+    fn record(
+        &mut self,
+        now_unix: i64,
+        provider_id: i64,
+        cli_key: &str,
+        provider_name: &str,
+        outcome: SampleOutcome,
+    ) -> Option<Quarantine> {
+        let window = self.providers.entry(provider_id).or_default();
+        window.cli_key = cli_key.to_string();
+        window.provider_name = provider_name.to_string();
+        window.prune(now_unix);
+        if window.samples.len() >= MAX_SAMPLES_PER_PROVIDER {
+            window.samples.pop_front();
+        }
+        window.samples.push_back((now_unix, outcome));
+
+        if window.quarantined_until.is_some() {
+            return None;
+        }
+        let (total, errors, truncated) = window.counts();
+        if total < MIN_SAMPLES {
+            return None;
+        }
+
+        let error_rate = rate(errors, total);
+        let truncated_rate = rate(truncated, total);
+        let (reason, rate) = if error_rate >= ERROR_RATE_THRESHOLD {
+            ("ERROR_RATE", error_rate)
+        } else if truncated_rate >= TRUNCATED_RATE_THRESHOLD {
+            ("TRUNCATED_STREAM_RATE", truncated_rate)
+        } else {
+            return None;
+        };
+
+        let until = now_unix.saturating_add(QUARANTINE_SECS);
+        window.quarantined_until = Some(until);
+        window.quarantine_count = window.quarantine_count.saturating_add(1);
+        // Start a fresh window so the provider is judged on post-quarantine traffic only.
+        window.samples.clear();
+
+        Some(Quarantine {
+            provider_id,
+            cli_key: window.cli_key.clone(),
+            provider_name: window.provider_name.clone(),
+            reason,
+            rate,
+            samples: total,
+            until,
+        })
+    }
+
+    pub(in crate::gateway) fn snapshot(&mut self, now_unix: i64) -> Vec<GatewayProviderQuality> {
+        let mut out: Vec<GatewayProviderQuality> = self
+            .providers
+            .iter_mut()
+            .map(|(provider_id, window)| {
+                window.prune(now_unix);
+                let (total, errors, truncated) = window.counts();
+                GatewayProviderQuality {
+                    provider_id: *provider_id,
+                    cli_key: window.cli_key.clone(),
+                    provider_name: window.provider_name.clone(),
+                    window_seconds: WINDOW_SECS as u32,
+                    total: total as u32,
+                    errors: errors as u32,
+                    truncated: truncated as u32,
+                    error_rate: rate(errors, total),
+                    truncated_rate: rate(truncated, total),
+                    quarantined_until: window.quarantined_until,
+                    quarantine_count: window.quarantine_count,
+                }
+            })
+            .collect();
+        out.sort_by(|a, b| {
+            a.cli_key
+                .cmp(&b.cli_key)
+                .then(a.provider_id.cmp(&b.provider_id))
+        });
+        out
+    }
+}
+
+fn is_truncated_stream(error_code: &str) -> bool {
+    error_code == GatewayErrorCode::StreamError.as_str()
+        || error_code == GatewayErrorCode::StreamIdleTimeout.as_str()
+}
+
+fn classify(error_code: Option<&str>, error_category: Option<&str>) -> Option<SampleOutcome> {
+    let Some(code) = error_code else {
+        return Some(SampleOutcome::Success);
+    };
+    if GatewayErrorCode::from_str(code).is_some_and(GatewayErrorCode::is_client_abort)
+        || error_category == Some(ErrorCategory::ClientAbort.as_str())
+        || error_category == Some(ErrorCategory::NonRetryableClientError.as_str())
+        || error_category == Some(ErrorCategory::ResourceNotFound.as_str())
+    {
+        // Not the provider's fault; keep it out of the window.
+        return None;
+    }
+    if is_truncated_stream(code) {
+        Some(SampleOutcome::Truncated)
+    } else {
+        Some(SampleOutcome::Error)
+    }
+}
+
+/// Map a finished request onto per-provider samples: every attempt that actually hit a provider
+/// counts, and the final attempt takes the request-level outcome (so stream truncation after a
+/// successful header phase is attributed to the provider that served it).
+fn samples_for_request<'a>(
+    attempts: &'a [FailoverAttempt],
+    error_code: Option<&'a str>,
+    error_category: Option<&'a str>,
+) -> impl Iterator<Item = (&'a FailoverAttempt, SampleOutcome)> + 'a {
+    let last = attempts
+        .iter()
+        .rposition(|a| a.provider_id > 0 && a.outcome != "skipped" && a.outcome != "started");
+    attempts
+        .iter()
+        .enumerate()
+        .filter(|(_, a)| a.provider_id > 0 && a.outcome != "skipped" && a.outcome != "started")
+        .filter_map(move |(idx, a)| {
+            let outcome = if Some(idx) == last {
+                classify(
+                    error_code.or(a.error_code),
+                    error_category.or(a.error_category),
+                )
+            } else {
+                classify(a.error_code, a.error_category)
+            }?;
+            Some((a, outcome))
+        })
+}
+
+pub(in crate::gateway) struct ProviderQualityArgs<'a> {
+    pub(in crate::gateway) app: &'a crate::AppHandle,
+    pub(in crate::gateway) circuit: &'a circuit_breaker::CircuitBreaker,
+    pub(in crate::gateway) tracker: &'a Mutex<ProviderQualityTracker>,
+    pub(in crate::gateway) trace_id: &'a str,
+    pub(in crate::gateway) cli_key: &'a str,
+    pub(in crate::gateway) attempts: &'a [FailoverAttempt],
+    pub(in crate::gateway) error_code: Option<&'a str>,
+    pub(in crate::gateway) error_category: Option<&'a str>,
+    pub(in crate::gateway) now_unix: i64,
+}
+
+pub(in crate::gateway) fn record_request_end(args: ProviderQualityArgs<'_>) {
+    for attempt in args.attempts.iter().filter(|a| a.provider_id > 0) {
+        if let Some(error_code) = attempt.error_code {
+            provider_webhook::record_failure(
+                attempt.provider_id,
+                args.trace_id,
+                error_code,
+                args.now_unix,
+            );
+        }
+    }
+
+    let quarantines: Vec<Quarantine> = {
+        let mut tracker = args.tracker.lock_or_recover();
+        samples_for_request(args.attempts, args.error_code, args.error_category)
+            .filter_map(|(attempt, outcome)| {
+                tracker.record(
+                    args.now_unix,
+                    attempt.provider_id,
+                    args.cli_key,
+                    &attempt.provider_name,
+                    outcome,
+                )
+            })
+            .collect()
+    };
+
+    for q in quarantines {
+        provider_router::trigger_cooldown(
+            args.circuit,
+            q.provider_id,
+            args.now_unix,
+            QUARANTINE_SECS,
+        );
+        tracing::warn!(
+            trace_id = %args.trace_id,
+            cli_key = %q.cli_key,
+            provider_id = q.provider_id,
+            provider_name = %q.provider_name,
+            reason = q.reason,
+            rate = q.rate,
+            samples = q.samples,
+            until = q.until,
+            "provider quarantined due to response anomalies"
+        );
+        emit_quarantine_notice(args.app, args.trace_id, &q);
+        provider_webhook::dispatch_quarantine(
+            args.app,
+            args.trace_id,
+            &q.cli_key,
+            q.provider_id,
+            &q.provider_name,
+            q.reason,
+            q.rate,
+            q.samples,
+            q.until,
+            args.now_unix,
+        );
+    }
+}
+
+fn emit_quarantine_notice(app: &crate::AppHandle, trace_id: &str, q: &Quarantine) {
+    let reason_text = match q.reason {
+        "ERROR_RATE" => i18n::text("notice.quarantine.reason_error_rate"),
+        "TRUNCATED_STREAM_RATE" => i18n::text("notice.quarantine.reason_truncated_stream_rate"),
+        other => other,
+    };
+    let lines = [
+        i18n::format("notice.line.cli", &[("cli_key", &q.cli_key)]),
+        i18n::format(
+            "notice.line.provider",
+            &[("name", &q.provider_name), ("id", &q.provider_id)],
+        ),
+        i18n::format(
+            "notice.quarantine.line_reason",
+            &[
+                ("text", &reason_text),
+                ("percent", &format!("{:.0}", q.rate * 100.0)),
+                ("samples", &q.samples),
+            ],
+        ),
+        i18n::format(
+            "notice.quarantine.line_until",
+            &[("until", &q.until), ("minutes", &(QUARANTINE_SECS / 60))],
+        ),
+        i18n::format("notice.line.trace", &[("trace_id", &trace_id)]),
+    ];
+    let payload = notice::build(
+        notice::NoticeLevel::Warning,
+        Some(i18n::format(
+            "notice.quarantine.title",
+            &[("name", &q.provider_name)],
+        )),
+        lines.join("\n"),
+    );
+    if let Err(err) = notice::emit(app, payload) {
+        tracing::warn!("failed to emit provider quarantine notice: {}", err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(
+        provider_id: i64,
+        outcome: &str,
+        error_code: Option<&'static str>,
+    ) -> FailoverAttempt {
+        FailoverAttempt {
+            provider_id,
+            provider_name: format!("p{provider_id}"),
+            base_url: "https://example.com".to_string(),
+            outcome: outcome.to_string(),
+            status: None,
+            provider_index: None,
+            retry_index: None,
+            session_reuse: None,
+            error_category: None,
+            error_code,
+            decision: None,
+            reason: None,
+            selection_method: None,
+            reason_code: None,
+            attempt_started_ms: None,
+            attempt_duration_ms: None,
+            circuit_state_before: None,
+            circuit_state_after: None,
+            circuit_failure_count: None,
+            circuit_failure_threshold: None,
+            error_catalog_code: None,
+            response_headers: None,
+            error_body: None,
+            retry_backoff_ms: None,
+            stream_stats: None,
+        }
+    }
+
+    #[test]
+    fn quarantines_once_error_rate_exceeds_threshold() {
+        let mut tracker = ProviderQualityTracker::default();
+        for i in 0..(MIN_SAMPLES - 1) {
+            let outcome = if i % 2 == 0 {
+                SampleOutcome::Error
+            } else {
+                SampleOutcome::Success
+            };
+            assert!(tracker.record(100, 1, "claude", "p1", outcome).is_none());
+        }
+
+        let q = tracker
+            .record(100, 1, "claude", "p1", SampleOutcome::Error)
+            .expect("quarantine");
+        assert_eq!(q.reason, "ERROR_RATE");
+        assert_eq!(q.until, 100 + QUARANTINE_SECS);
+
+        let stats = tracker.snapshot(101);
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].quarantined_until, Some(100 + QUARANTINE_SECS));
+        assert_eq!(stats[0].quarantine_count, 1);
+        assert_eq!(stats[0].total, 0);
+    }
+
+    #[test]
+    fn truncated_streams_trigger_quarantine_and_old_samples_expire() {
+        let mut tracker = ProviderQualityTracker::default();
+        for _ in 0..MIN_SAMPLES {
+            tracker.record(0, 2, "codex", "p2", SampleOutcome::Truncated);
+        }
+        assert_eq!(tracker.snapshot(1)[0].quarantine_count, 1);
+
+        let mut tracker = ProviderQualityTracker::default();
+        for _ in 0..(MIN_SAMPLES - 1) {
+            tracker.record(0, 2, "codex", "p2", SampleOutcome::Truncated);
+        }
+        // Everything above has aged out of the window, so one more failure is not enough.
+        assert!(tracker
+            .record(WINDOW_SECS + 1, 2, "codex", "p2", SampleOutcome::Truncated)
+            .is_none());
+        assert_eq!(tracker.snapshot(WINDOW_SECS + 1)[0].total, 1);
+    }
+
+    #[test]
+    fn samples_attribute_final_outcome_to_last_real_attempt() {
+        let attempts = vec![
+            attempt(1, "skipped", None),
+            attempt(2, "failed", Some("GW_UPSTREAM_5XX")),
+            attempt(3, "success", None),
+        ];
+        let samples: Vec<(i64, SampleOutcome)> =
+            samples_for_request(&attempts, Some("GW_STREAM_ERROR"), None)
+                .map(|(a, o)| (a.provider_id, o))
+                .collect();
+        assert_eq!(
+            samples,
+            vec![(2, SampleOutcome::Error), (3, SampleOutcome::Truncated)]
+        );
+
+        let samples: Vec<(i64, SampleOutcome)> =
+            samples_for_request(&attempts, Some("GW_STREAM_ABORTED"), None)
+                .map(|(a, o)| (a.provider_id, o))
+                .collect();
+        assert_eq!(samples, vec![(2, SampleOutcome::Error)]);
+    }
+}
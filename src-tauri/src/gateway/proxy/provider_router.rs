@@ -4,7 +4,7 @@ use crate::circuit_breaker;
 use crate::gateway::events::{emit_circuit_event, emit_circuit_transition, GatewayCircuitEvent};
 
 pub(super) struct GateProviderArgs<'a> {
-    pub(super) app: Option<&'a tauri::AppHandle>,
+    pub(super) app: Option<&'a crate::AppHandle>,
     pub(super) circuit: &'a circuit_breaker::CircuitBreaker,
     pub(super) trace_id: &'a str,
     pub(super) cli_key: &'a str,
@@ -56,6 +56,9 @@ pub(super) fn gate_provider(
     let reason = if snap.state == circuit_breaker::CircuitState::Open {
         *skipped_open = skipped_open.saturating_add(1);
         "SKIP_OPEN"
+    } else if snap.state == circuit_breaker::CircuitState::HalfOpen {
+        *skipped_open = skipped_open.saturating_add(1);
+        "SKIP_HALF_OPEN"
     } else {
         *skipped_cooldown = skipped_cooldown.saturating_add(1);
         "SKIP_COOLDOWN"
@@ -95,7 +98,7 @@ pub(super) fn gate_provider(
 }
 
 pub(in crate::gateway) struct RecordCircuitArgs<'a> {
-    pub(in crate::gateway) app: Option<&'a tauri::AppHandle>,
+    pub(in crate::gateway) app: Option<&'a crate::AppHandle>,
     pub(in crate::gateway) circuit: &'a circuit_breaker::CircuitBreaker,
     pub(in crate::gateway) trace_id: &'a str,
     pub(in crate::gateway) cli_key: &'a str,
@@ -108,7 +111,7 @@ pub(in crate::gateway) struct RecordCircuitArgs<'a> {
 impl<'a> RecordCircuitArgs<'a> {
     #[allow(clippy::too_many_arguments)]
     pub(in crate::gateway) fn new(
-        app: Option<&'a tauri::AppHandle>,
+        app: Option<&'a crate::AppHandle>,
         circuit: &'a circuit_breaker::CircuitBreaker,
         trace_id: &'a str,
         cli_key: &'a str,
@@ -250,6 +253,7 @@ mod tests {
         let cb = breaker(circuit_breaker::CircuitBreakerConfig {
             failure_threshold: 5,
             open_duration_secs: 60,
+            half_open_probe: false,
         });
         let pid = 1;
         let now = 1_000;
@@ -284,6 +288,7 @@ mod tests {
         let cb = breaker(circuit_breaker::CircuitBreakerConfig {
             failure_threshold: 1,
             open_duration_secs: 60,
+            half_open_probe: false,
         });
         let pid = 1;
         let now = 1_000;
@@ -322,6 +327,7 @@ mod tests {
         let cb = breaker(circuit_breaker::CircuitBreakerConfig {
             failure_threshold: 5,
             open_duration_secs: 60,
+            half_open_probe: false,
         });
         let pid = 1;
         let now = 1_000;
@@ -359,6 +365,7 @@ mod tests {
         let cb = breaker(circuit_breaker::CircuitBreakerConfig {
             failure_threshold: 1,
             open_duration_secs: 10,
+            half_open_probe: false,
         });
         let pid = 1;
         let now = 1_000;
@@ -395,6 +402,7 @@ mod tests {
         let cb = breaker(circuit_breaker::CircuitBreakerConfig {
             failure_threshold: 1,
             open_duration_secs: 60,
+            half_open_probe: false,
         });
         let pid = 1;
         let now = 1_000;
@@ -419,6 +427,7 @@ mod tests {
         let cb = breaker(circuit_breaker::CircuitBreakerConfig {
             failure_threshold: 5,
             open_duration_secs: 60,
+            half_open_probe: false,
         });
         let pid = 1;
         let now = 1_000;
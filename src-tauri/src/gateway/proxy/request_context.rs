@@ -2,14 +2,47 @@
 
 use super::abort_guard::RequestAbortGuard;
 use crate::gateway::manager::GatewayAppState;
+use crate::gateway::model_capabilities::CapabilityGate;
 use crate::gateway::response_fixer;
+use crate::gateway::streams::{SseCoalesceConfig, StreamFailureConfig, StreamStallConfig};
 use crate::gateway::util::{strip_hop_headers, RequestedModelLocation};
 use crate::providers;
+use crate::settings::RetryBackoffSettings;
 use axum::body::Bytes;
 use axum::http::{header, HeaderMap, HeaderValue, Method};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
+/// Endpoint classes that each carry their own hedge delay setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum HedgeEndpointClass {
+    /// claude count_tokens probes (non-streaming, latency-sensitive).
+    CountTokens,
+    NonStreaming,
+    /// Raced on the first byte (`first_byte_race_delay_ms`).
+    Streaming,
+}
+
+impl HedgeEndpointClass {
+    pub(super) fn classify(is_count_tokens: bool, is_stream: bool) -> Self {
+        if is_count_tokens {
+            Self::CountTokens
+        } else if is_stream {
+            Self::Streaming
+        } else {
+            Self::NonStreaming
+        }
+    }
+
+    pub(super) fn special_setting_type(self) -> &'static str {
+        match self {
+            Self::CountTokens => "count_tokens_hedge",
+            Self::NonStreaming => "non_streaming_hedge",
+            Self::Streaming => "first_byte_race",
+        }
+    }
+}
+
 pub(super) struct RequestContext {
     pub(super) state: GatewayAppState,
     pub(super) cli_key: String,
@@ -26,6 +59,7 @@ pub(super) struct RequestContext {
     pub(super) requested_model_location: Option<RequestedModelLocation>,
     pub(super) effective_sort_mode_id: Option<i64>,
     pub(super) providers: Vec<providers::ProviderForGateway>,
+    pub(super) last_resort_provider: Option<providers::ProviderForGateway>,
     pub(super) session_bound_provider_id: Option<i64>,
     pub(super) base_headers: HeaderMap,
     pub(super) body_bytes: Bytes,
@@ -37,10 +71,12 @@ pub(super) struct RequestContext {
     pub(super) max_attempts_per_provider: u32,
     pub(super) max_providers_to_try: u32,
     pub(super) provider_cooldown_secs: i64,
+    pub(super) retry_backoff: RetryBackoffSettings,
     pub(super) upstream_first_byte_timeout_secs: u32,
     pub(super) upstream_first_byte_timeout: Option<Duration>,
     pub(super) upstream_stream_idle_timeout: Option<Duration>,
     pub(super) upstream_request_timeout_non_streaming: Option<Duration>,
+    pub(super) hedge: Option<(Duration, HedgeEndpointClass)>,
     pub(super) fingerprint_key: u64,
     pub(super) fingerprint_debug: String,
     pub(super) unavailable_fingerprint_key: u64,
@@ -52,6 +88,11 @@ pub(super) struct RequestContext {
     pub(super) enable_response_fixer: bool,
     pub(super) response_fixer_stream_config: response_fixer::ResponseFixerConfig,
     pub(super) response_fixer_non_stream_config: response_fixer::ResponseFixerConfig,
+    pub(super) stream_truncation_recovery_enabled: bool,
+    pub(super) sse_coalesce: SseCoalesceConfig,
+    pub(super) stream_stall: StreamStallConfig,
+    pub(super) stream_failure: StreamFailureConfig,
+    pub(super) capability_gate: Option<Arc<CapabilityGate>>,
 }
 
 impl RequestContext {
@@ -72,6 +113,7 @@ impl RequestContext {
             requested_model_location,
             effective_sort_mode_id,
             providers,
+            last_resort_provider,
             session_bound_provider_id,
             headers,
             body_bytes,
@@ -83,9 +125,12 @@ impl RequestContext {
             max_attempts_per_provider,
             max_providers_to_try,
             provider_cooldown_secs,
+            retry_backoff,
             upstream_first_byte_timeout_secs,
             upstream_stream_idle_timeout_secs,
             upstream_request_timeout_non_streaming_secs,
+            hedge_class,
+            hedge_delay_ms,
             fingerprint_key,
             fingerprint_debug,
             unavailable_fingerprint_key,
@@ -96,6 +141,14 @@ impl RequestContext {
             enable_response_fixer,
             response_fixer_stream_config,
             response_fixer_non_stream_config,
+            stream_truncation_recovery_enabled,
+            sse_coalesce_flush_ms,
+            sse_coalesce_flush_bytes,
+            stream_stall_warn_seconds,
+            stream_stall_keepalive_enabled,
+            stream_first_byte_failover_enabled,
+            stream_error_event_enabled,
+            capability_gate,
         } = parts;
 
         let max_attempts_per_provider = Self::normalize_max_attempts_per_provider(
@@ -114,6 +167,12 @@ impl RequestContext {
             upstream_request_timeout_non_streaming_secs,
         );
 
+        let hedge = if hedge_delay_ms == 0 {
+            None
+        } else {
+            Some((Duration::from_millis(hedge_delay_ms as u64), hedge_class))
+        };
+
         let abort_guard = RequestAbortGuard::new(
             state.app.clone(),
             state.db.clone(),
@@ -146,6 +205,7 @@ impl RequestContext {
             requested_model_location,
             effective_sort_mode_id,
             providers,
+            last_resort_provider,
             session_bound_provider_id,
             base_headers,
             body_bytes,
@@ -157,10 +217,12 @@ impl RequestContext {
             max_attempts_per_provider,
             max_providers_to_try,
             provider_cooldown_secs,
+            retry_backoff,
             upstream_first_byte_timeout_secs,
             upstream_first_byte_timeout,
             upstream_stream_idle_timeout,
             upstream_request_timeout_non_streaming,
+            hedge,
             fingerprint_key,
             fingerprint_debug,
             unavailable_fingerprint_key,
@@ -172,7 +234,101 @@ impl RequestContext {
             enable_response_fixer,
             response_fixer_stream_config,
             response_fixer_non_stream_config,
+            stream_truncation_recovery_enabled,
+            sse_coalesce: SseCoalesceConfig::new(sse_coalesce_flush_ms, sse_coalesce_flush_bytes),
+            stream_stall: StreamStallConfig::new(
+                stream_stall_warn_seconds,
+                stream_stall_keepalive_enabled,
+            ),
+            stream_failure: StreamFailureConfig::new(
+                stream_first_byte_failover_enabled,
+                stream_error_event_enabled,
+            ),
+            capability_gate,
+        }
+    }
+
+    /// Fresh abort guard for this request (same trace id and timing as the inner one).
+    pub(super) fn new_abort_guard(&self) -> RequestAbortGuard {
+        RequestAbortGuard::new(
+            self.state.app.clone(),
+            self.state.db.clone(),
+            self.state.log_tx.clone(),
+            self.trace_id.clone(),
+            self.cli_key.clone(),
+            self.method_hint.clone(),
+            self.forwarded_path.clone(),
+            self.query.clone(),
+            self.created_at_ms,
+            self.created_at,
+            self.started,
+        )
+    }
+
+    /// Move the second provider into a sibling context for a hedged attempt.
+    ///
+    /// The sibling shares trace id and special settings, so whichever attempt finishes last
+    /// owns the request log row. Its abort guard is disarmed; the caller keeps an outer guard.
+    pub(super) fn fork_hedge(&mut self) -> Option<RequestContext> {
+        if self.providers.len() < 2 {
+            return None;
         }
+        let hedge_provider = self.providers.remove(1);
+
+        let mut abort_guard = self.new_abort_guard();
+        abort_guard.disarm();
+
+        Some(Self {
+            state: self.state.clone(),
+            cli_key: self.cli_key.clone(),
+            forwarded_path: self.forwarded_path.clone(),
+            req_method: self.req_method.clone(),
+            method_hint: self.method_hint.clone(),
+            query: self.query.clone(),
+            trace_id: self.trace_id.clone(),
+            started: self.started,
+            created_at_ms: self.created_at_ms,
+            created_at: self.created_at,
+            session_id: self.session_id.clone(),
+            requested_model: self.requested_model.clone(),
+            requested_model_location: self.requested_model_location,
+            effective_sort_mode_id: self.effective_sort_mode_id,
+            providers: vec![hedge_provider],
+            last_resort_provider: None,
+            session_bound_provider_id: None,
+            base_headers: self.base_headers.clone(),
+            body_bytes: self.body_bytes.clone(),
+            introspection_json: self.introspection_json.clone(),
+            strip_request_content_encoding_seed: self.strip_request_content_encoding_seed,
+            special_settings: self.special_settings.clone(),
+            provider_base_url_ping_cache_ttl_seconds: self.provider_base_url_ping_cache_ttl_seconds,
+            verbose_provider_error: self.verbose_provider_error,
+            max_attempts_per_provider: self.max_attempts_per_provider,
+            max_providers_to_try: 1,
+            provider_cooldown_secs: self.provider_cooldown_secs,
+            retry_backoff: self.retry_backoff,
+            upstream_first_byte_timeout_secs: self.upstream_first_byte_timeout_secs,
+            upstream_first_byte_timeout: self.upstream_first_byte_timeout,
+            upstream_stream_idle_timeout: self.upstream_stream_idle_timeout,
+            upstream_request_timeout_non_streaming: self.upstream_request_timeout_non_streaming,
+            hedge: None,
+            fingerprint_key: self.fingerprint_key,
+            fingerprint_debug: self.fingerprint_debug.clone(),
+            unavailable_fingerprint_key: self.unavailable_fingerprint_key,
+            unavailable_fingerprint_debug: self.unavailable_fingerprint_debug.clone(),
+            abort_guard,
+            enable_thinking_signature_rectifier: self.enable_thinking_signature_rectifier,
+            enable_thinking_budget_rectifier: self.enable_thinking_budget_rectifier,
+            enable_claude_metadata_user_id_injection: self.enable_claude_metadata_user_id_injection,
+            enable_response_fixer: self.enable_response_fixer,
+            response_fixer_stream_config: self.response_fixer_stream_config,
+            response_fixer_non_stream_config: self.response_fixer_non_stream_config,
+            stream_truncation_recovery_enabled: self.stream_truncation_recovery_enabled,
+            sse_coalesce: self.sse_coalesce,
+            stream_stall: self.stream_stall,
+            stream_failure: self.stream_failure,
+            capability_gate: self.capability_gate.clone(),
+        })
     }
 
     fn normalize_max_attempts_per_provider(
@@ -203,7 +359,7 @@ impl RequestContext {
     }
 }
 
-fn build_base_headers(mut headers: HeaderMap) -> HeaderMap {
+pub(super) fn build_base_headers(mut headers: HeaderMap) -> HeaderMap {
     strip_hop_headers(&mut headers);
     headers.remove(header::HOST);
     headers.remove(header::CONTENT_LENGTH);
@@ -238,6 +394,7 @@ pub(super) struct RequestContextParts {
     pub(super) requested_model_location: Option<RequestedModelLocation>,
     pub(super) effective_sort_mode_id: Option<i64>,
     pub(super) providers: Vec<providers::ProviderForGateway>,
+    pub(super) last_resort_provider: Option<providers::ProviderForGateway>,
     pub(super) session_bound_provider_id: Option<i64>,
     pub(super) headers: HeaderMap,
     pub(super) body_bytes: Bytes,
@@ -249,9 +406,12 @@ pub(super) struct RequestContextParts {
     pub(super) max_attempts_per_provider: u32,
     pub(super) max_providers_to_try: u32,
     pub(super) provider_cooldown_secs: i64,
+    pub(super) retry_backoff: RetryBackoffSettings,
     pub(super) upstream_first_byte_timeout_secs: u32,
     pub(super) upstream_stream_idle_timeout_secs: u32,
     pub(super) upstream_request_timeout_non_streaming_secs: u32,
+    pub(super) hedge_class: HedgeEndpointClass,
+    pub(super) hedge_delay_ms: u32,
     pub(super) fingerprint_key: u64,
     pub(super) fingerprint_debug: String,
     pub(super) unavailable_fingerprint_key: u64,
@@ -262,4 +422,12 @@ pub(super) struct RequestContextParts {
     pub(super) enable_response_fixer: bool,
     pub(super) response_fixer_stream_config: response_fixer::ResponseFixerConfig,
     pub(super) response_fixer_non_stream_config: response_fixer::ResponseFixerConfig,
+    pub(super) stream_truncation_recovery_enabled: bool,
+    pub(super) sse_coalesce_flush_ms: u32,
+    pub(super) sse_coalesce_flush_bytes: u32,
+    pub(super) stream_stall_warn_seconds: u32,
+    pub(super) stream_stall_keepalive_enabled: bool,
+    pub(super) stream_first_byte_failover_enabled: bool,
+    pub(super) stream_error_event_enabled: bool,
+    pub(super) capability_gate: Option<Arc<CapabilityGate>>,
 }
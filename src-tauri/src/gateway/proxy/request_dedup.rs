@@ -0,0 +1,402 @@
+//! Usage: Coalesce identical concurrent non-stream requests onto a single upstream call.
+//!
+//! The first request for a fingerprint becomes the leader; identical requests that arrive while
+//! it is in flight wait for its buffered response instead of consuming provider attempts.
+//! Streaming (or not fully buffered) responses are never shared: waiters then forward normally.
+
+use super::http_util::is_event_stream;
+use super::request_end::{
+    emit_request_event_and_spawn_request_log, RequestEndArgs, RequestEndDeps,
+};
+use crate::gateway::events::{decision_chain as dc, emit_request_start_event, FailoverAttempt};
+use crate::gateway::manager::GatewayAppState;
+use crate::shared::mutex_ext::MutexExt;
+use axum::body::{to_bytes, Body, Bytes, HttpBody};
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::response::Response;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+
+/// Upper bound for how long a follower waits before forwarding on its own.
+const FOLLOWER_MAX_WAIT: Duration = Duration::from_secs(10 * 60);
+const DEDUP_BASE_URL: &str = "/__aio__/dedup";
+
+#[derive(Debug)]
+pub(super) struct SharedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+type SharedSlot = Option<Arc<SharedResponse>>;
+
+#[derive(Debug)]
+struct InflightEntry {
+    token: u64,
+    fingerprint_debug: String,
+    leader_trace_id: String,
+    rx: watch::Receiver<SharedSlot>,
+}
+
+#[derive(Debug, Default)]
+pub(in crate::gateway) struct InflightRequests {
+    entries: HashMap<u64, InflightEntry>,
+    next_token: u64,
+}
+
+pub(super) enum DedupJoin {
+    Leader(DedupLeader),
+    Follower(DedupFollower),
+    Bypass,
+}
+
+pub(super) struct DedupLeader {
+    registry: Arc<Mutex<InflightRequests>>,
+    fingerprint_key: u64,
+    token: u64,
+    tx: watch::Sender<SharedSlot>,
+}
+
+pub(super) struct DedupFollower {
+    pub(super) leader_trace_id: String,
+    rx: watch::Receiver<SharedSlot>,
+}
+
+/// Only JSON bodies that do not ask for a stream are worth coalescing.
+pub(super) fn is_dedup_candidate(
+    forwarded_path: &str,
+    introspection_json: Option<&serde_json::Value>,
+) -> bool {
+    if forwarded_path.contains("streamGenerateContent") {
+        return false;
+    }
+    introspection_json.is_some_and(|root| {
+        !root
+            .get("stream")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false)
+    })
+}
+
+pub(super) fn join(
+    registry: &Arc<Mutex<InflightRequests>>,
+    fingerprint_key: u64,
+    fingerprint_debug: &str,
+    trace_id: &str,
+) -> DedupJoin {
+    let mut inflight = registry.lock_or_recover();
+
+    if let Some(entry) = inflight.entries.get(&fingerprint_key) {
+        if entry.fingerprint_debug != fingerprint_debug {
+            // Hash collision: never hand one request another request's response.
+            return DedupJoin::Bypass;
+        }
+        if entry.rx.has_changed().is_ok() {
+            return DedupJoin::Follower(DedupFollower {
+                leader_trace_id: entry.leader_trace_id.clone(),
+                rx: entry.rx.clone(),
+            });
+        }
+    }
+
+    inflight.next_token = inflight.next_token.wrapping_add(1);
+    let token = inflight.next_token;
+    let (tx, rx) = watch::channel(None);
+    inflight.entries.insert(
+        fingerprint_key,
+        InflightEntry {
+            token,
+            fingerprint_debug: fingerprint_debug.to_string(),
+            leader_trace_id: trace_id.to_string(),
+            rx,
+        },
+    );
+
+    DedupJoin::Leader(DedupLeader {
+        registry: registry.clone(),
+        fingerprint_key,
+        token,
+        tx,
+    })
+}
+
+impl DedupLeader {
+    /// Shares `resp` with waiting followers when its body is already fully buffered, and hands
+    /// the (rebuilt) response back to the leader's own client.
+    pub(super) async fn publish(self, resp: Response) -> Response {
+        if is_event_stream(resp.headers()) || resp.body().size_hint().exact().is_none() {
+            return resp;
+        }
+
+        let (parts, body) = resp.into_parts();
+        let body = match to_bytes(body, usize::MAX).await {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                tracing::warn!("request dedup: failed to buffer leader response: {}", err);
+                return Response::from_parts(parts, Body::empty());
+            }
+        };
+
+        let _ = self.tx.send(Some(Arc::new(SharedResponse {
+            status: parts.status,
+            headers: parts.headers.clone(),
+            body: body.clone(),
+        })));
+
+        Response::from_parts(parts, Body::from(body))
+    }
+}
+
+impl Drop for DedupLeader {
+    fn drop(&mut self) {
+        let mut inflight = self.registry.lock_or_recover();
+        if inflight
+            .entries
+            .get(&self.fingerprint_key)
+            .is_some_and(|entry| entry.token == self.token)
+        {
+            inflight.entries.remove(&self.fingerprint_key);
+        }
+    }
+}
+
+impl DedupFollower {
+    /// Waits for the leader's response; `None` when the leader ended without a shareable one.
+    pub(super) async fn wait(mut self) -> Option<Arc<SharedResponse>> {
+        let wait = async {
+            loop {
+                if let Some(shared) = self.rx.borrow_and_update().clone() {
+                    return Some(shared);
+                }
+                if self.rx.changed().await.is_err() {
+                    return self.rx.borrow().clone();
+                }
+            }
+        };
+        tokio::time::timeout(FOLLOWER_MAX_WAIT, wait)
+            .await
+            .ok()
+            .flatten()
+    }
+}
+
+pub(super) struct DedupHitCtx<'a> {
+    pub(super) state: &'a GatewayAppState,
+    pub(super) trace_id: &'a str,
+    pub(super) leader_trace_id: &'a str,
+    pub(super) cli_key: &'a str,
+    pub(super) method_hint: &'a str,
+    pub(super) forwarded_path: &'a str,
+    pub(super) query: Option<&'a str>,
+    pub(super) session_id: Option<String>,
+    pub(super) requested_model: Option<String>,
+    pub(super) special_settings: &'a Mutex<Vec<serde_json::Value>>,
+    pub(super) started: Instant,
+    pub(super) waited_ms: u128,
+    pub(super) created_at_ms: i64,
+    pub(super) created_at: i64,
+}
+
+fn dedup_attempt(status: StatusCode) -> FailoverAttempt {
+    FailoverAttempt {
+        provider_id: 0,
+        provider_name: "Dedup".to_string(),
+        base_url: DEDUP_BASE_URL.to_string(),
+        outcome: "success".to_string(),
+        status: Some(status.as_u16()),
+        provider_index: None,
+        retry_index: None,
+        session_reuse: Some(false),
+        error_category: None,
+        error_code: None,
+        decision: Some("success"),
+        reason: None,
+        selection_method: None,
+        reason_code: Some(dc::REASON_REQUEST_SUCCESS),
+        attempt_started_ms: None,
+        attempt_duration_ms: None,
+        circuit_state_before: None,
+        circuit_state_after: None,
+        circuit_failure_count: None,
+        circuit_failure_threshold: None,
+        error_catalog_code: None,
+        response_headers: None,
+        error_body: None,
+        retry_backoff_ms: None,
+        stream_stats: None,
+    }
+}
+
+/// Replies to a follower with the leader's response. The log row is excluded from stats because
+/// the leader's row already accounts for the upstream usage.
+pub(super) fn respond_hit(ctx: DedupHitCtx<'_>, shared: &SharedResponse) -> Response {
+    emit_request_start_event(
+        &ctx.state.app,
+        ctx.trace_id.to_string(),
+        ctx.cli_key.to_string(),
+        ctx.method_hint.to_string(),
+        ctx.forwarded_path.to_string(),
+        ctx.query.map(str::to_string),
+        ctx.requested_model.clone(),
+        ctx.created_at,
+    );
+
+    let special_settings_json = {
+        let mut settings = ctx.special_settings.lock_or_recover();
+        settings.push(serde_json::json!({
+            "type": "request_dedup",
+            "scope": "request",
+            "hit": true,
+            "leaderTraceId": ctx.leader_trace_id,
+            "waitedMs": ctx.waited_ms as u64,
+        }));
+        serde_json::to_string(&*settings).ok()
+    };
+
+    let duration_ms = ctx.started.elapsed().as_millis();
+    let attempts = [dedup_attempt(shared.status)];
+    emit_request_event_and_spawn_request_log(RequestEndArgs {
+        deps: RequestEndDeps::new(&ctx.state.app, &ctx.state.db, &ctx.state.log_tx),
+        trace_id: ctx.trace_id,
+        cli_key: ctx.cli_key,
+        method: ctx.method_hint,
+        path: ctx.forwarded_path,
+        query: ctx.query,
+        excluded_from_stats: true,
+        status: Some(shared.status.as_u16()),
+        error_category: None,
+        error_code: None,
+        duration_ms,
+        event_ttfb_ms: Some(duration_ms),
+        log_ttfb_ms: Some(duration_ms),
+        attempts: &attempts,
+        special_settings_json,
+        session_id: ctx.session_id,
+        requested_model: ctx.requested_model,
+        created_at_ms: ctx.created_at_ms,
+        created_at: ctx.created_at,
+        usage_metrics: None,
+        log_usage_metrics: None,
+        usage: None,
+        request_bytes: None,
+        response_bytes: None,
+        thinking_budget_tokens: None,
+    });
+
+    let mut resp = Response::new(Body::from(shared.body.clone()));
+    *resp.status_mut() = shared.status;
+    *resp.headers_mut() = shared.headers.clone();
+    if let Ok(v) = HeaderValue::from_str(ctx.trace_id) {
+        resp.headers_mut().insert("x-trace-id", v);
+    }
+    if let Ok(v) = HeaderValue::from_str(ctx.leader_trace_id) {
+        resp.headers_mut().insert("x-aio-dedup-leader-trace-id", v);
+    }
+    resp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::header;
+
+    fn registry() -> Arc<Mutex<InflightRequests>> {
+        Arc::new(Mutex::new(InflightRequests::default()))
+    }
+
+    fn json_response(body: &'static str) -> Response {
+        let mut resp = Response::new(Body::from(body));
+        resp.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        );
+        resp
+    }
+
+    #[test]
+    fn dedup_candidate_requires_non_stream_json() {
+        let non_stream = serde_json::json!({"model": "m", "stream": false});
+        let stream = serde_json::json!({"model": "m", "stream": true});
+        let no_flag = serde_json::json!({"model": "m"});
+
+        assert!(is_dedup_candidate("/v1/messages", Some(&non_stream)));
+        assert!(is_dedup_candidate("/v1/messages", Some(&no_flag)));
+        assert!(!is_dedup_candidate("/v1/messages", Some(&stream)));
+        assert!(!is_dedup_candidate("/v1/messages", None));
+        assert!(!is_dedup_candidate(
+            "/v1beta/models/g:streamGenerateContent",
+            Some(&no_flag)
+        ));
+    }
+
+    #[test]
+    fn join_assigns_leader_then_followers_and_clears_on_drop() {
+        let registry = registry();
+
+        let leader = match join(&registry, 7, "fp", "t1") {
+            DedupJoin::Leader(leader) => leader,
+            _ => panic!("first request should lead"),
+        };
+        match join(&registry, 7, "fp", "t2") {
+            DedupJoin::Follower(follower) => assert_eq!(follower.leader_trace_id, "t1"),
+            _ => panic!("identical request should follow"),
+        }
+        assert!(matches!(
+            join(&registry, 7, "other-fp", "t3"),
+            DedupJoin::Bypass
+        ));
+
+        drop(leader);
+        assert!(registry.lock_or_recover().entries.is_empty());
+        assert!(matches!(
+            join(&registry, 7, "fp", "t4"),
+            DedupJoin::Leader(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn follower_receives_buffered_leader_response() {
+        let registry = registry();
+        let DedupJoin::Leader(leader) = join(&registry, 1, "fp", "t1") else {
+            panic!("expected leader");
+        };
+        let DedupJoin::Follower(follower) = join(&registry, 1, "fp", "t2") else {
+            panic!("expected follower");
+        };
+
+        let waiter = tokio::spawn(follower.wait());
+        let resp = leader.publish(json_response(r#"{"ok":true}"#)).await;
+        let leader_body = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(leader_body.as_ref(), br#"{"ok":true}"#);
+
+        let shared = waiter
+            .await
+            .unwrap()
+            .expect("follower should get the response");
+        assert_eq!(shared.status, StatusCode::OK);
+        assert_eq!(shared.body.as_ref(), br#"{"ok":true}"#);
+        assert!(registry.lock_or_recover().entries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn follower_falls_back_when_leader_response_is_a_stream() {
+        let registry = registry();
+        let DedupJoin::Leader(leader) = join(&registry, 1, "fp", "t1") else {
+            panic!("expected leader");
+        };
+        let DedupJoin::Follower(follower) = join(&registry, 1, "fp", "t2") else {
+            panic!("expected follower");
+        };
+
+        let mut resp = Response::new(Body::from("data: {}\n\n"));
+        resp.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("text/event-stream"),
+        );
+        let _ = leader.publish(resp).await;
+
+        assert!(follower.wait().await.is_none());
+    }
+}
@@ -1,24 +1,55 @@
 //! Usage: Shared helpers to emit request-end events and enqueue request logs consistently.
 
+use super::caches::RecentErrorCache;
 use super::logging::enqueue_request_log_with_backpressure;
 use super::status_override;
-use super::{spawn_enqueue_request_log_with_backpressure, RequestLogEnqueueArgs};
+use super::{
+    record_provider_quality, spawn_enqueue_request_log_with_backpressure, ProviderQualityArgs,
+    ProviderQualityTracker, RequestLogEnqueueArgs,
+};
 use crate::gateway::events::{emit_request_event, FailoverAttempt};
-use crate::{db, request_logs};
+use crate::gateway::manager::GatewayAppState;
+use crate::gateway::util::now_unix_seconds;
+use crate::shared::mutex_ext::MutexExt;
+use crate::{circuit_breaker, db, request_logs};
+use std::sync::Mutex;
 
 pub(super) struct RequestEndDeps<'a> {
-    pub(super) app: &'a tauri::AppHandle,
+    pub(super) app: &'a crate::AppHandle,
     pub(super) db: &'a db::Db,
     pub(super) log_tx: &'a tokio::sync::mpsc::Sender<request_logs::RequestLogInsert>,
+    quality: Option<(
+        &'a circuit_breaker::CircuitBreaker,
+        &'a Mutex<ProviderQualityTracker>,
+    )>,
+    recent_errors: Option<&'a Mutex<RecentErrorCache>>,
 }
 
 impl<'a> RequestEndDeps<'a> {
     pub(super) fn new(
-        app: &'a tauri::AppHandle,
+        app: &'a crate::AppHandle,
         db: &'a db::Db,
         log_tx: &'a tokio::sync::mpsc::Sender<request_logs::RequestLogInsert>,
     ) -> Self {
-        Self { app, db, log_tx }
+        Self {
+            app,
+            db,
+            log_tx,
+            quality: None,
+            recent_errors: None,
+        }
+    }
+
+    /// Like `new`, but also feeds provider quality tracking (requests that reached a provider)
+    /// and the per-session failure budget.
+    pub(super) fn from_state(state: &'a GatewayAppState) -> Self {
+        Self {
+            app: &state.app,
+            db: &state.db,
+            log_tx: &state.log_tx,
+            quality: Some((state.circuit.as_ref(), state.quality.as_ref())),
+            recent_errors: Some(state.recent_errors.as_ref()),
+        }
     }
 }
 
@@ -45,6 +76,22 @@ pub(super) struct RequestEndArgs<'a> {
     pub(super) usage_metrics: Option<crate::usage::UsageMetrics>,
     pub(super) log_usage_metrics: Option<crate::usage::UsageMetrics>,
     pub(super) usage: Option<crate::usage::UsageExtract>,
+    /// Client request body size; `None` when the request never reached a provider.
+    pub(super) request_bytes: Option<u64>,
+    /// Body bytes returned to the client; falls back to the final attempt's stream stats.
+    pub(super) response_bytes: Option<u64>,
+    /// Extended thinking budget sent to the final provider (see `thinking_budget_governor`).
+    pub(super) thinking_budget_tokens: Option<u64>,
+}
+
+/// Attempts that actually reached a provider and failed; skipped providers and client aborts are
+/// not charged to the session failure budget.
+fn count_failed_attempts(attempts: &[FailoverAttempt]) -> usize {
+    attempts
+        .iter()
+        .filter(|attempt| attempt.outcome != "skipped" && attempt.error_code.is_some())
+        .filter(|attempt| !status_override::is_client_abort(attempt.error_code))
+        .count()
 }
 
 struct PreparedRequestEnd<'a> {
@@ -62,6 +109,45 @@ fn prepare_request_end(args: RequestEndArgs<'_>) -> PreparedRequestEnd<'_> {
     let excluded_from_stats = args.excluded_from_stats
         || super::is_claude_count_tokens_request(args.cli_key, args.path)
         || status_override::is_client_abort(args.error_code);
+    if let Some((circuit, tracker)) = args.deps.quality {
+        if !args.excluded_from_stats
+            && !super::is_claude_count_tokens_request(args.cli_key, args.path)
+        {
+            record_provider_quality(ProviderQualityArgs {
+                app: args.deps.app,
+                circuit,
+                tracker,
+                trace_id: args.trace_id,
+                cli_key: args.cli_key,
+                attempts: args.attempts,
+                error_code: args.error_code,
+                error_category: args.error_category,
+                now_unix: now_unix_seconds() as i64,
+            });
+        }
+    }
+    if let (Some(recent_errors), Some(session_id)) =
+        (args.deps.recent_errors, args.session_id.as_deref())
+    {
+        let failed_attempts = count_failed_attempts(args.attempts);
+        if failed_attempts > 0 {
+            recent_errors.lock_or_recover().record_session_failures(
+                now_unix_seconds() as i64,
+                session_id,
+                failed_attempts,
+            );
+        }
+    }
+    if let Some(error_code) = args.error_code {
+        if !excluded_from_stats {
+            crate::notification_sinks::dispatch_gateway_failure(
+                args.trace_id,
+                args.cli_key,
+                error_code,
+                status,
+            );
+        }
+    }
     let (attempts, attempts_json) = if args.attempts.is_empty() {
         (Vec::new(), "[]".to_string())
     } else {
@@ -70,6 +156,28 @@ fn prepare_request_end(args: RequestEndArgs<'_>) -> PreparedRequestEnd<'_> {
         (attempts, attempts_json)
     };
 
+    let last_attempt = args.attempts.last();
+    let response_bytes = args.response_bytes.or_else(|| {
+        last_attempt
+            .and_then(|attempt| attempt.stream_stats.as_ref())
+            .map(|stats| stats.bytes_received)
+    });
+    crate::gateway::access_log::record(&crate::gateway::access_log::AccessLogEntry {
+        created_at_ms: args.created_at_ms,
+        trace_id: args.trace_id,
+        cli_key: args.cli_key,
+        method: args.method,
+        path: args.path,
+        query: args.query,
+        status,
+        error_code: args.error_code,
+        duration_ms: args.duration_ms,
+        ttfb_ms: args.log_ttfb_ms,
+        bytes: response_bytes,
+        requested_model: args.requested_model.as_deref(),
+        provider_name: last_attempt.map(|attempt| attempt.provider_name.as_str()),
+    });
+
     let log_args = RequestLogEnqueueArgs {
         trace_id: args.trace_id.to_string(),
         cli_key: args.cli_key.to_string(),
@@ -89,6 +197,9 @@ fn prepare_request_end(args: RequestEndArgs<'_>) -> PreparedRequestEnd<'_> {
         created_at: args.created_at,
         usage_metrics: args.log_usage_metrics,
         usage: args.usage,
+        request_bytes: args.request_bytes,
+        response_bytes,
+        thinking_budget_tokens: args.thinking_budget_tokens,
     };
 
     PreparedRequestEnd {
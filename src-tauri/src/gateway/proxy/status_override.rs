@@ -10,10 +10,13 @@ pub(in crate::gateway) fn status_override_for_error_code(error_code: Option<&str
         | GatewayErrorCode::UpstreamConnectFailed
         | GatewayErrorCode::UpstreamBodyReadError
         | GatewayErrorCode::UpstreamAllFailed => Some(502),
-        GatewayErrorCode::AllProvidersUnavailable | GatewayErrorCode::NoEnabledProvider => {
-            Some(503)
+        GatewayErrorCode::AllProvidersUnavailable
+        | GatewayErrorCode::NoEnabledProvider
+        | GatewayErrorCode::RoutingPaused => Some(503),
+        GatewayErrorCode::CliProxyDisabled | GatewayErrorCode::ModelDenied => Some(403),
+        GatewayErrorCode::SessionThrottled | GatewayErrorCode::SessionFailureBudgetExceeded => {
+            Some(429)
         }
-        GatewayErrorCode::CliProxyDisabled => Some(403),
         GatewayErrorCode::InvalidCliKey => Some(400),
         GatewayErrorCode::BodyTooLarge => Some(413),
         GatewayErrorCode::ResponseBuildError
@@ -0,0 +1,153 @@
+//! Usage: Detect refusal-shaped 2xx responses injected by relays (`providers.refusal_patterns_json`).
+//!
+//! Patterns are matched case-insensitively against the leading assistant text of the response,
+//! so a phrase quoted deep inside a normal answer does not count.
+
+use axum::body::Bytes;
+use std::time::Duration;
+
+/// Refusal failovers allowed per request; once spent the response is relayed as-is.
+pub(super) const MAX_REFUSAL_FAILOVERS: usize = 2;
+/// Only the start of the assistant text is checked.
+const SCAN_CHARS: usize = 1024;
+/// Event streams are held back until this many bytes arrived (or the peek timed out).
+const SSE_PEEK_BYTES: usize = 4 * 1024;
+const SSE_PEEK_TIMEOUT: Duration = Duration::from_secs(3);
+
+fn collect_text(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                match (key.as_str(), value) {
+                    (
+                        "text" | "content" | "delta" | "output_text",
+                        serde_json::Value::String(s),
+                    ) => out.push_str(s),
+                    (_, serde_json::Value::Object(_) | serde_json::Value::Array(_)) => {
+                        collect_text(value, out)
+                    }
+                    _ => {}
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_text(item, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Assistant text of a JSON body (Anthropic, OpenAI chat/responses and Gemini shapes); the raw
+/// body when it is not JSON.
+pub(super) fn json_body_text(body: &[u8]) -> String {
+    match serde_json::from_slice::<serde_json::Value>(body) {
+        Ok(root) => {
+            let mut out = String::new();
+            collect_text(&root, &mut out);
+            out
+        }
+        Err(_) => String::from_utf8_lossy(body).into_owned(),
+    }
+}
+
+/// Concatenated text deltas of the (possibly truncated) event-stream prefix.
+pub(super) fn event_stream_text(prefix: &[u8]) -> String {
+    let mut out = String::new();
+    for line in String::from_utf8_lossy(prefix).lines() {
+        let Some(data) = line.strip_prefix("data:") else {
+            continue;
+        };
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(data.trim()) {
+            collect_text(&value, &mut out);
+        }
+    }
+    out
+}
+
+/// First configured pattern found in the leading part of `text`.
+pub(super) fn find_refusal<'a>(patterns: &'a [String], text: &str) -> Option<&'a str> {
+    let head: String = text
+        .chars()
+        .take(SCAN_CHARS)
+        .collect::<String>()
+        .to_lowercase();
+    patterns
+        .iter()
+        .map(|pattern| pattern.as_str())
+        .find(|pattern| !pattern.is_empty() && head.contains(&pattern.to_lowercase()))
+}
+
+/// Reads ahead of `first` so the opening text deltas can be checked before anything is relayed.
+pub(super) async fn peek_event_stream(
+    resp: &mut reqwest::Response,
+    first: Option<Bytes>,
+) -> Result<Option<Bytes>, reqwest::Error> {
+    let mut buf = Vec::with_capacity(SSE_PEEK_BYTES);
+    if let Some(first) = first {
+        buf.extend_from_slice(&first);
+    }
+    let deadline = tokio::time::Instant::now() + SSE_PEEK_TIMEOUT;
+    while buf.len() < SSE_PEEK_BYTES {
+        match tokio::time::timeout_at(deadline, resp.chunk()).await {
+            Ok(Ok(Some(chunk))) => buf.extend_from_slice(&chunk),
+            Ok(Ok(None)) | Err(_) => break,
+            Ok(Err(err)) => return Err(err),
+        }
+    }
+    Ok((!buf.is_empty()).then(|| Bytes::from(buf)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns() -> Vec<String> {
+        vec!["I can't help with".to_string(), "内容违规".to_string()]
+    }
+
+    #[test]
+    fn matches_leading_text_of_json_bodies() {
+        let body = br#"{"content":[{"type":"text","text":"Sorry, I CAN'T HELP WITH that."}]}"#;
+        assert_eq!(
+            find_refusal(&patterns(), &json_body_text(body)),
+            Some("I can't help with")
+        );
+
+        let openai = r#"{"choices":[{"message":{"role":"assistant","content":"检测到内容违规"}}]}"#;
+        assert_eq!(
+            find_refusal(&patterns(), &json_body_text(openai.as_bytes())),
+            Some("内容违规")
+        );
+
+        let late = format!(
+            r#"{{"content":[{{"type":"text","text":"{}I can't help with"}}]}}"#,
+            "x".repeat(SCAN_CHARS)
+        );
+        assert_eq!(
+            find_refusal(&patterns(), &json_body_text(late.as_bytes())),
+            None
+        );
+    }
+
+    #[test]
+    fn joins_event_stream_deltas_before_matching() {
+        let prefix = concat!(
+            "event: message_start\n",
+            "data: {\"type\":\"message_start\",\"message\":{\"content\":[]}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"delta\":{\"type\":\"text_delta\",\"text\":\"I can\"}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"delta\":{\"type\":\"text_delta\",\"text\":\"'t help with this\"}}\n\n",
+        );
+        assert_eq!(
+            find_refusal(&patterns(), &event_stream_text(prefix.as_bytes())),
+            Some("I can't help with")
+        );
+        assert_eq!(
+            find_refusal(&[], &event_stream_text(prefix.as_bytes())),
+            None
+        );
+    }
+}
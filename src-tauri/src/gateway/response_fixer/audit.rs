@@ -4,6 +4,7 @@ use std::time::Instant;
 
 use super::encoding::EncodingFixer;
 use super::json::JsonFixer;
+use super::spill;
 use super::ResponseFixerConfig;
 
 #[derive(Debug, Default, Clone)]
@@ -14,6 +15,8 @@ pub(super) struct ResponseFixerApplied {
     pub(super) sse_details: Option<&'static str>,
     pub(super) json_applied: bool,
     pub(super) json_details: Option<&'static str>,
+    pub(super) responses_applied: bool,
+    pub(super) responses_details: Option<&'static str>,
 }
 
 fn build_fixers_applied(
@@ -33,6 +36,13 @@ fn build_fixers_applied(
             "details": applied.sse_details,
         }));
     }
+    if applied.responses_applied {
+        out.push(serde_json::json!({
+            "fixer": "responses",
+            "applied": true,
+            "details": applied.responses_details,
+        }));
+    }
     out.push(serde_json::json!({
         "fixer": "json",
         "applied": applied.json_applied,
@@ -69,7 +79,7 @@ pub(super) fn process_non_stream(
     let total_bytes_processed = data.len();
 
     if config.fix_encoding {
-        let res = EncodingFixer::fix_bytes(data);
+        let res = EncodingFixer::fix_bytes(data, config.transcode_charsets);
         if res.applied {
             applied.encoding_applied = true;
             applied.encoding_details = res.details;
@@ -78,8 +88,14 @@ pub(super) fn process_non_stream(
     }
 
     if config.fix_truncated_json {
-        let fixer = JsonFixer::new(config.max_json_depth, config.max_fix_size);
-        let res = fixer.fix_bytes(data);
+        let spill_dir = spill::dir()
+            .filter(|_| data.len() > config.max_fix_size && data.len() <= config.spill_max_size);
+        let res = match spill_dir {
+            Some(dir) => {
+                spill::fix_bytes(dir, data, config.max_json_depth, spill::SPILL_TIME_BUDGET)
+            }
+            None => JsonFixer::new(config.max_json_depth, config.max_fix_size).fix_bytes(data),
+        };
         if res.applied {
             applied.json_applied = true;
             applied.json_details = res.details;
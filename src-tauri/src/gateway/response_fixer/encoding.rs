@@ -1,3 +1,4 @@
+use crate::settings::ResponseFixerCharset;
 use axum::body::Bytes;
 
 #[derive(Debug)]
@@ -7,6 +8,40 @@ pub(super) struct FixBytesOutcome {
     pub(super) details: Option<&'static str>,
 }
 
+/// Legacy charsets tried, in allow-list order, on bodies that are not valid UTF-8.
+#[derive(Debug, Clone, Copy, Default)]
+pub(in crate::gateway) struct TranscodeCharsets([Option<ResponseFixerCharset>; 3]);
+
+impl TranscodeCharsets {
+    pub(in crate::gateway) fn from_allow_list(allow_list: &[ResponseFixerCharset]) -> Self {
+        let mut out = [None; 3];
+        for (slot, charset) in out.iter_mut().zip(allow_list) {
+            *slot = Some(*charset);
+        }
+        Self(out)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = ResponseFixerCharset> + '_ {
+        self.0.iter().flatten().copied()
+    }
+}
+
+fn transcode_encoding(charset: ResponseFixerCharset) -> &'static encoding_rs::Encoding {
+    match charset {
+        ResponseFixerCharset::Gbk => encoding_rs::GB18030,
+        ResponseFixerCharset::Big5 => encoding_rs::BIG5,
+        ResponseFixerCharset::ShiftJis => encoding_rs::SHIFT_JIS,
+    }
+}
+
+fn transcode_details(charset: ResponseFixerCharset) -> &'static str {
+    match charset {
+        ResponseFixerCharset::Gbk => "transcoded_gbk",
+        ResponseFixerCharset::Big5 => "transcoded_big5",
+        ResponseFixerCharset::ShiftJis => "transcoded_shift_jis",
+    }
+}
+
 pub(super) struct EncodingFixer;
 
 impl EncodingFixer {
@@ -36,6 +71,31 @@ impl EncodingFixer {
         Some(out)
     }
 
+    /// UTF-8 with a few corrupt bytes still has more well-formed multibyte characters than
+    /// invalid sequences; legacy CJK text almost never forms valid UTF-8 sequences.
+    fn looks_like_broken_utf8(data: &[u8]) -> bool {
+        let mut valid_multibyte = 0usize;
+        let mut invalid = 0usize;
+        for chunk in data.utf8_chunks() {
+            valid_multibyte += chunk.valid().chars().filter(|c| !c.is_ascii()).count();
+            if !chunk.invalid().is_empty() {
+                invalid += 1;
+            }
+        }
+        valid_multibyte >= invalid
+    }
+
+    fn transcode(data: &[u8], charsets: TranscodeCharsets) -> Option<(String, &'static str)> {
+        if Self::looks_like_broken_utf8(data) {
+            return None;
+        }
+        charsets.iter().find_map(|charset| {
+            transcode_encoding(charset)
+                .decode_without_bom_handling_and_without_replacement(data)
+                .map(|text| (text.into_owned(), transcode_details(charset)))
+        })
+    }
+
     fn can_fix(data: &[u8]) -> bool {
         if Self::has_utf8_bom(data) || Self::has_utf16_bom(data) {
             return true;
@@ -46,7 +106,7 @@ impl EncodingFixer {
         !Self::is_valid_utf8(data)
     }
 
-    pub(super) fn fix_bytes(input: Bytes) -> FixBytesOutcome {
+    pub(super) fn fix_bytes(input: Bytes, charsets: TranscodeCharsets) -> FixBytesOutcome {
         if !Self::can_fix(input.as_ref()) {
             return FixBytesOutcome {
                 data: input,
@@ -88,6 +148,14 @@ impl EncodingFixer {
             };
         }
 
+        if let Some((text, details)) = Self::transcode(data.as_ref(), charsets) {
+            return FixBytesOutcome {
+                data: Bytes::from(text.into_bytes()),
+                applied: true,
+                details: Some(details),
+            };
+        }
+
         // 有损修复：用 replacement char 替换无效序列，再重新编码，保证输出一定是合法 UTF-8。
         let lossy = String::from_utf8_lossy(data.as_ref());
         FixBytesOutcome {
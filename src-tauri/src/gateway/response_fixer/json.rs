@@ -2,6 +2,62 @@ use axum::body::Bytes;
 
 use super::encoding::FixBytesOutcome;
 
+/// How many input bytes the repair loop consumes between `RepairSink::aborted` checks.
+const ABORT_CHECK_INTERVAL: usize = 64 * 1024;
+
+/// Output target for `JsonFixer::repair_into`.
+///
+/// Repair only ever looks back past trailing whitespace for a `,` or `:`, so a sink does not
+/// need to keep the whole output addressable (see the disk-backed sink in `spill`).
+pub(super) trait RepairSink {
+    fn push(&mut self, byte: u8);
+
+    fn extend_from_slice(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.push(byte);
+        }
+    }
+
+    /// Drops the last `,` if only whitespace follows it.
+    fn remove_trailing_comma(&mut self);
+
+    /// Whether the last non-whitespace byte is `:`.
+    fn ends_with_colon(&self) -> bool;
+
+    /// Lets a sink stop the repair early (I/O failure, time budget).
+    fn aborted(&self) -> bool {
+        false
+    }
+}
+
+impl RepairSink for Vec<u8> {
+    fn push(&mut self, byte: u8) {
+        Vec::push(self, byte);
+    }
+
+    fn extend_from_slice(&mut self, bytes: &[u8]) {
+        Vec::extend_from_slice(self, bytes);
+    }
+
+    fn remove_trailing_comma(&mut self) {
+        let mut idx = self.len();
+        while idx > 0 && JsonFixer::is_whitespace(self[idx - 1]) {
+            idx -= 1;
+        }
+        if idx > 0 && self[idx - 1] == b',' {
+            self.truncate(idx - 1);
+        }
+    }
+
+    fn ends_with_colon(&self) -> bool {
+        let mut idx = self.len();
+        while idx > 0 && JsonFixer::is_whitespace(self[idx - 1]) {
+            idx -= 1;
+        }
+        idx > 0 && self[idx - 1] == b':'
+    }
+}
+
 pub(super) struct JsonFixer {
     max_depth: usize,
     max_size: usize,
@@ -15,11 +71,11 @@ impl JsonFixer {
         }
     }
 
-    fn is_whitespace(byte: u8) -> bool {
+    pub(super) fn is_whitespace(byte: u8) -> bool {
         byte == b' ' || byte == b'\t' || byte == b'\n' || byte == b'\r'
     }
 
-    fn looks_like_json(data: &[u8]) -> bool {
+    pub(super) fn looks_like_json(data: &[u8]) -> bool {
         for b in data {
             if Self::is_whitespace(*b) {
                 continue;
@@ -29,25 +85,8 @@ impl JsonFixer {
         false
     }
 
-    fn remove_trailing_comma(out: &mut Vec<u8>) {
-        let mut idx = out.len();
-        while idx > 0 && Self::is_whitespace(out[idx - 1]) {
-            idx -= 1;
-        }
-        if idx > 0 && out[idx - 1] == b',' {
-            out.truncate(idx - 1);
-        }
-    }
-
-    fn needs_null_value(out: &[u8], stack: &[u8]) -> bool {
-        if stack.last().copied() != Some(b'}') {
-            return false;
-        }
-        let mut idx = out.len();
-        while idx > 0 && Self::is_whitespace(out[idx - 1]) {
-            idx -= 1;
-        }
-        idx > 0 && out[idx - 1] == b':'
+    fn needs_null_value<S: RepairSink>(out: &S, stack: &[u8]) -> bool {
+        stack.last().copied() == Some(b'}') && out.ends_with_colon()
     }
 
     fn can_fix(&self, data: &[u8]) -> bool {
@@ -76,22 +115,32 @@ impl JsonFixer {
 
     fn repair(&self, data: &[u8]) -> Option<Vec<u8>> {
         let mut out: Vec<u8> = Vec::with_capacity(data.len().saturating_add(8));
+        self.repair_into(data, &mut out)?;
+        Some(out)
+    }
+
+    pub(super) fn repair_into<S: RepairSink>(&self, data: &[u8], out: &mut S) -> Option<()> {
         let mut stack: Vec<u8> = Vec::new();
 
         let mut in_string = false;
         let mut escape_next = false;
         let mut depth = 0usize;
 
-        for &byte in data {
+        for (idx, &byte) in data.iter().enumerate() {
+            if idx % ABORT_CHECK_INTERVAL == 0 && out.aborted() {
+                return None;
+            }
+
+            // 反斜杠延后到下一个字节再写出，末尾不完整的转义序列因此会被直接丢弃
             if escape_next {
                 escape_next = false;
+                out.push(b'\\');
                 out.push(byte);
                 continue;
             }
 
             if in_string && byte == b'\\' {
                 escape_next = true;
-                out.push(byte);
                 continue;
             }
 
@@ -122,7 +171,7 @@ impl JsonFixer {
                         continue;
                     }
                     b'}' | b']' => {
-                        Self::remove_trailing_comma(&mut out);
+                        out.remove_trailing_comma();
                         if stack.last().copied() == Some(byte) {
                             stack.pop();
                             depth = depth.saturating_sub(1);
@@ -137,29 +186,27 @@ impl JsonFixer {
             out.push(byte);
         }
 
-        // 末尾不完整的转义序列：去掉最后一个反斜杠
-        if escape_next {
-            out.pop();
-        }
-
         // 闭合未关闭的字符串
         if in_string {
             out.push(b'"');
         }
 
-        Self::remove_trailing_comma(&mut out);
+        out.remove_trailing_comma();
 
         // 对象末尾冒号无值：补 null
-        if Self::needs_null_value(&out, &stack) {
+        if Self::needs_null_value(out, &stack) {
             out.extend_from_slice(b"null");
         }
 
         while let Some(close) = stack.pop() {
-            Self::remove_trailing_comma(&mut out);
+            out.remove_trailing_comma();
             out.push(close);
         }
 
-        Some(out)
+        if out.aborted() {
+            return None;
+        }
+        Some(())
     }
 }
 
@@ -1,12 +1,17 @@
 mod audit;
 mod encoding;
 mod json;
+mod responses;
+mod spill;
 mod sse;
 mod stream;
 
+pub(super) use encoding::TranscodeCharsets;
+
 use axum::body::Bytes;
 use futures_core::Stream;
 use serde_json::Value;
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
@@ -21,6 +26,14 @@ pub(super) struct ResponseFixerConfig {
     pub(super) fix_truncated_json: bool,
     pub(super) max_json_depth: usize,
     pub(super) max_fix_size: usize,
+    /// Non-stream bodies above `max_fix_size` but within this size are repaired via a temp
+    /// file instead of being skipped (0 = disabled).
+    pub(super) spill_max_size: usize,
+    /// Stream-only: repair OpenAI Responses API event framing (`event:` / `data:` pairing,
+    /// JSON payloads split across `data:` lines).
+    pub(super) fix_responses_events: bool,
+    /// Charsets the encoding stage may transcode non-UTF-8 bodies from (`fix_encoding` only).
+    pub(super) transcode_charsets: TranscodeCharsets,
 }
 
 #[derive(Debug)]
@@ -38,6 +51,12 @@ pub(super) fn special_settings_json(shared: &Arc<Mutex<Vec<Value>>>) -> Option<S
     Some(serde_json::to_string(&*guard).unwrap_or_else(|_| "[]".to_string()))
 }
 
+/// Enables the disk-spill path under `<app data>/response-fixer-spill` and removes files left
+/// over from a previous run. Until this succeeds oversized bodies are skipped as before.
+pub(super) fn init_spill_dir(app_data_dir: PathBuf) -> std::io::Result<()> {
+    spill::init_dir(app_data_dir.join(spill::SPILL_DIR_NAME))
+}
+
 pub(super) fn process_non_stream(body: Bytes, config: ResponseFixerConfig) -> NonStreamFixOutcome {
     audit::process_non_stream(body, config)
 }
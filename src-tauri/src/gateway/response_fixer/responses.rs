@@ -0,0 +1,244 @@
+//! Usage: OpenAI Responses API aware SSE repair (`event: <type>` + `data: {"type": <type>}` blocks).
+//!
+//! Runs on line-complete SSE bytes (after `SseFixer`, before the per-line JSON fixer) and keeps
+//! state across chunks so a JSON payload split over several `data:` lines can be reassembled
+//! instead of being "repaired" into two truncated objects.
+
+use axum::body::Bytes;
+use serde_json::Value;
+
+use super::encoding::FixBytesOutcome;
+
+pub(super) const DETAILS_JSON_REASSEMBLED: &str = "json_reassembled";
+pub(super) const DETAILS_EVENT_TYPE_INSERTED: &str = "event_type_inserted";
+pub(super) const DETAILS_EVENT_TYPE_CORRECTED: &str = "event_type_corrected";
+pub(super) const DETAILS_EVENT_SEPARATOR_INSERTED: &str = "event_separator_inserted";
+
+const EVENT_PREFIX: &[u8] = b"event:";
+const DATA_PREFIX: &[u8] = b"data:";
+
+enum Payload {
+    Complete(Value),
+    Incomplete,
+    Other,
+}
+
+pub(super) struct ResponsesEventFixer {
+    max_pending: usize,
+    /// `event:` value of the current block, held until its `data:` line shows the real type.
+    pending_event: Option<Vec<u8>>,
+    /// Accumulated payload of a JSON object that has not been closed yet.
+    split_json: Option<Vec<u8>>,
+    block_has_data: bool,
+}
+
+impl ResponsesEventFixer {
+    pub(super) fn new(max_pending: usize) -> Self {
+        Self {
+            max_pending,
+            pending_event: None,
+            split_json: None,
+            block_has_data: false,
+        }
+    }
+
+    fn field_value<'a>(line: &'a [u8], prefix: &[u8]) -> Option<&'a [u8]> {
+        let rest = line.strip_prefix(prefix)?;
+        Some(rest.strip_prefix(b" ").unwrap_or(rest))
+    }
+
+    fn classify(payload: &[u8]) -> Payload {
+        if !payload.starts_with(b"{") {
+            return Payload::Other;
+        }
+        match serde_json::from_slice::<Value>(payload) {
+            Ok(value) => Payload::Complete(value),
+            Err(err) if err.is_eof() => Payload::Incomplete,
+            Err(_) => Payload::Other,
+        }
+    }
+
+    fn event_type(value: &Value) -> Option<&str> {
+        value
+            .get("type")
+            .and_then(Value::as_str)
+            .filter(|t| !t.is_empty())
+    }
+
+    fn push_line(out: &mut Vec<u8>, prefix: &[u8], value: &[u8]) {
+        out.extend_from_slice(prefix);
+        out.push(b' ');
+        out.extend_from_slice(value);
+        out.push(b'\n');
+    }
+
+    fn flush_pending_event(&mut self, out: &mut Vec<u8>) {
+        if let Some(event) = self.pending_event.take() {
+            Self::push_line(out, EVENT_PREFIX, &event);
+        }
+    }
+
+    /// Gives up on an unfinished split payload and emits it as one `data:` line, leaving the
+    /// truncated-JSON fixer to deal with it.
+    fn flush_split_json(&mut self, out: &mut Vec<u8>) {
+        if let Some(payload) = self.split_json.take() {
+            self.flush_pending_event(out);
+            Self::push_line(out, DATA_PREFIX, &payload);
+            self.block_has_data = true;
+        }
+    }
+
+    fn emit_payload(
+        &mut self,
+        out: &mut Vec<u8>,
+        payload: &[u8],
+        value: Option<&Value>,
+        details: &mut Option<&'static str>,
+    ) {
+        let actual_type = value.and_then(Self::event_type);
+        match (self.pending_event.take(), actual_type) {
+            (Some(declared), Some(actual)) if declared != actual.as_bytes() => {
+                details.get_or_insert(DETAILS_EVENT_TYPE_CORRECTED);
+                Self::push_line(out, EVENT_PREFIX, actual.as_bytes());
+            }
+            (Some(declared), _) => Self::push_line(out, EVENT_PREFIX, &declared),
+            (None, Some(actual)) if !self.block_has_data => {
+                details.get_or_insert(DETAILS_EVENT_TYPE_INSERTED);
+                Self::push_line(out, EVENT_PREFIX, actual.as_bytes());
+            }
+            (None, _) => {}
+        }
+        Self::push_line(out, DATA_PREFIX, payload);
+        self.block_has_data = true;
+    }
+
+    /// Appends one more piece to the pending split payload and emits it once it parses.
+    fn append_split(
+        &mut self,
+        out: &mut Vec<u8>,
+        piece: &[u8],
+        details: &mut Option<&'static str>,
+    ) {
+        let mut joined = self.split_json.take().unwrap_or_default();
+        joined.extend_from_slice(piece);
+        match Self::classify(&joined) {
+            Payload::Complete(value) => {
+                details.get_or_insert(DETAILS_JSON_REASSEMBLED);
+                self.emit_payload(out, &joined, Some(&value), details);
+            }
+            Payload::Incomplete if joined.len() <= self.max_pending => {
+                self.split_json = Some(joined);
+            }
+            _ => {
+                self.split_json = Some(joined);
+                self.flush_split_json(out);
+            }
+        }
+    }
+
+    fn handle_data(
+        &mut self,
+        out: &mut Vec<u8>,
+        payload: &[u8],
+        details: &mut Option<&'static str>,
+    ) {
+        if self.split_json.is_some() {
+            self.append_split(out, payload, details);
+            return;
+        }
+
+        match Self::classify(payload) {
+            Payload::Complete(value) => self.emit_payload(out, payload, Some(&value), details),
+            Payload::Incomplete if payload.len() <= self.max_pending => {
+                self.split_json = Some(payload.to_vec());
+            }
+            _ => self.emit_payload(out, payload, None, details),
+        }
+    }
+
+    fn handle_line(&mut self, out: &mut Vec<u8>, line: &[u8], details: &mut Option<&'static str>) {
+        if line.is_empty() {
+            self.flush_split_json(out);
+            self.flush_pending_event(out);
+            out.push(b'\n');
+            self.block_has_data = false;
+            return;
+        }
+
+        if let Some(event) = Self::field_value(line, EVENT_PREFIX) {
+            self.flush_split_json(out);
+            if self.block_has_data || self.pending_event.is_some() {
+                // A new event started without the blank line that terminates the previous one.
+                details.get_or_insert(DETAILS_EVENT_SEPARATOR_INSERTED);
+                self.flush_pending_event(out);
+                out.push(b'\n');
+                self.block_has_data = false;
+            }
+            self.pending_event = Some(event.to_vec());
+            return;
+        }
+
+        if let Some(payload) = Self::field_value(line, DATA_PREFIX) {
+            self.handle_data(out, payload, details);
+            return;
+        }
+
+        let is_known_field =
+            line.starts_with(b":") || line.starts_with(b"id:") || line.starts_with(b"retry:");
+        if self.split_json.is_some() && !is_known_field {
+            // Continuation of a split payload that lost its `data:` prefix.
+            self.append_split(out, line, details);
+            return;
+        }
+
+        // id:/retry:/comments and anything unrecognised pass through untouched.
+        out.extend_from_slice(line);
+        out.push(b'\n');
+    }
+
+    /// Processes line-complete SSE bytes. Output may lag input while an event's type or a split
+    /// payload is pending; `finish` releases whatever is still held.
+    pub(super) fn process(&mut self, input: Bytes) -> FixBytesOutcome {
+        let mut out: Vec<u8> = Vec::with_capacity(input.len() + 32);
+        let mut details: Option<&'static str> = None;
+
+        let bytes = input.as_ref();
+        let mut start = 0usize;
+        while start < bytes.len() {
+            let end = bytes[start..]
+                .iter()
+                .position(|b| *b == b'\n')
+                .map(|offset| start + offset)
+                .unwrap_or(bytes.len());
+            let mut line = &bytes[start..end];
+            if line.last() == Some(&b'\r') {
+                line = &line[..line.len() - 1];
+            }
+            self.handle_line(&mut out, line, &mut details);
+            start = end + 1;
+        }
+
+        if details.is_none() && out.as_slice() == bytes {
+            return FixBytesOutcome {
+                data: input,
+                applied: false,
+                details: None,
+            };
+        }
+
+        FixBytesOutcome {
+            data: Bytes::from(out),
+            applied: details.is_some(),
+            details,
+        }
+    }
+
+    /// Releases held-back bytes at end of stream (or before switching to passthrough).
+    pub(super) fn finish(&mut self) -> Bytes {
+        let mut out = Vec::new();
+        self.flush_split_json(&mut out);
+        self.flush_pending_event(&mut out);
+        self.block_has_data = false;
+        Bytes::from(out)
+    }
+}
@@ -0,0 +1,262 @@
+//! Usage: Disk-backed truncated-JSON repair for non-stream bodies above `max_fix_size`.
+//!
+//! The repaired output is written to a temp file under the app data dir and validated from
+//! disk without building a `serde_json::Value`, so large payloads can be fixed without the
+//! in-memory parse tree the regular path needs.
+
+use axum::body::Bytes;
+use serde::de::IgnoredAny;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, SystemTime};
+
+use super::encoding::FixBytesOutcome;
+use super::json::{JsonFixer, RepairSink};
+
+pub(super) const SPILL_DIR_NAME: &str = "response-fixer-spill";
+/// Wall-clock budget for one spilled repair (write + validate + read back).
+pub(super) const SPILL_TIME_BUDGET: Duration = Duration::from_secs(5);
+const SPILL_FILE_PREFIX: &str = "spill-";
+const SPILL_STALE_AFTER: Duration = Duration::from_secs(10 * 60);
+const SPILL_WRITE_BUFFER_BYTES: usize = 64 * 1024;
+
+static SPILL_DIR: OnceLock<PathBuf> = OnceLock::new();
+static SPILL_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Creates the spill dir, drops leftovers from earlier runs and enables spilling.
+pub(super) fn init_dir(dir: PathBuf) -> std::io::Result<()> {
+    fs::create_dir_all(&dir)?;
+    let removed = cleanup_stale(&dir, SPILL_STALE_AFTER);
+    if removed > 0 {
+        tracing::info!(removed, "response fixer: removed stale spill files");
+    }
+    let _ = SPILL_DIR.set(dir);
+    Ok(())
+}
+
+pub(super) fn dir() -> Option<&'static Path> {
+    SPILL_DIR.get().map(PathBuf::as_path)
+}
+
+pub(super) fn cleanup_stale(dir: &Path, older_than: Duration) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    let now = SystemTime::now();
+    let mut removed = 0usize;
+    for entry in entries.flatten() {
+        if !entry
+            .file_name()
+            .to_string_lossy()
+            .starts_with(SPILL_FILE_PREFIX)
+        {
+            continue;
+        }
+        let stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_none_or(|age| age >= older_than);
+        if stale && fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+/// Temp file that is removed when dropped, whichever way the repair ends.
+struct SpillFile {
+    path: PathBuf,
+}
+
+impl SpillFile {
+    fn create(dir: &Path) -> std::io::Result<(Self, File)> {
+        let seq = SPILL_SEQ.fetch_add(1, Ordering::Relaxed);
+        let path = dir.join(format!(
+            "{SPILL_FILE_PREFIX}{}-{seq}.json",
+            std::process::id()
+        ));
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        Ok((Self { path }, file))
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Streams repaired bytes to disk, holding back only the trailing run of whitespace / `,` / `:`
+/// that repair may still rewrite.
+struct FileSink {
+    writer: BufWriter<File>,
+    tail: Vec<u8>,
+    deadline: Instant,
+    error: Option<std::io::Error>,
+}
+
+impl FileSink {
+    fn new(file: File, deadline: Instant) -> Self {
+        Self {
+            writer: BufWriter::with_capacity(SPILL_WRITE_BUFFER_BYTES, file),
+            tail: Vec::new(),
+            deadline,
+            error: None,
+        }
+    }
+
+    fn is_held_back(byte: u8) -> bool {
+        JsonFixer::is_whitespace(byte) || byte == b',' || byte == b':'
+    }
+
+    fn write_all(&mut self, bytes: &[u8]) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(err) = self.writer.write_all(bytes) {
+            self.error = Some(err);
+        }
+    }
+
+    fn flush_tail(&mut self) {
+        if self.tail.is_empty() {
+            return;
+        }
+        let tail = std::mem::take(&mut self.tail);
+        self.write_all(&tail);
+    }
+
+    fn finish(mut self) -> std::io::Result<()> {
+        self.flush_tail();
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+        self.writer.flush()?;
+        self.writer.get_ref().sync_data()
+    }
+}
+
+impl RepairSink for FileSink {
+    fn push(&mut self, byte: u8) {
+        if Self::is_held_back(byte) {
+            self.tail.push(byte);
+            return;
+        }
+        self.flush_tail();
+        self.write_all(&[byte]);
+    }
+
+    fn remove_trailing_comma(&mut self) {
+        let mut idx = self.tail.len();
+        while idx > 0 && JsonFixer::is_whitespace(self.tail[idx - 1]) {
+            idx -= 1;
+        }
+        if idx > 0 && self.tail[idx - 1] == b',' {
+            self.tail.truncate(idx - 1);
+        }
+    }
+
+    fn ends_with_colon(&self) -> bool {
+        self.tail
+            .iter()
+            .rev()
+            .find(|b| !JsonFixer::is_whitespace(**b))
+            .is_some_and(|b| *b == b':')
+    }
+
+    fn aborted(&self) -> bool {
+        self.error.is_some() || Instant::now() >= self.deadline
+    }
+}
+
+fn skipped(input: Bytes, details: &'static str) -> FixBytesOutcome {
+    FixBytesOutcome {
+        data: input,
+        applied: false,
+        details: Some(details),
+    }
+}
+
+/// Same contract as `JsonFixer::fix_bytes`, but the repaired copy lives on disk until it has
+/// been validated. Applied results carry `details = "spilled_to_disk"`.
+pub(super) fn fix_bytes(
+    dir: &Path,
+    input: Bytes,
+    max_depth: usize,
+    time_budget: Duration,
+) -> FixBytesOutcome {
+    if !JsonFixer::looks_like_json(input.as_ref()) {
+        return FixBytesOutcome {
+            data: input,
+            applied: false,
+            details: None,
+        };
+    }
+
+    if serde_json::from_slice::<IgnoredAny>(input.as_ref()).is_ok() {
+        return FixBytesOutcome {
+            data: input,
+            applied: false,
+            details: None,
+        };
+    }
+
+    let deadline = Instant::now() + time_budget;
+    let (spill, file) = match SpillFile::create(dir) {
+        Ok(v) => v,
+        Err(err) => {
+            tracing::warn!("response fixer: failed to create spill file: {}", err);
+            return skipped(input, "spill_io_failed");
+        }
+    };
+
+    let fixer = JsonFixer::new(max_depth, input.len());
+    let mut sink = FileSink::new(file, deadline);
+    let repaired = fixer.repair_into(input.as_ref(), &mut sink);
+    let timed_out = Instant::now() >= deadline;
+    if let Err(err) = sink.finish() {
+        tracing::warn!("response fixer: failed to write spill file: {}", err);
+        return skipped(input, "spill_io_failed");
+    }
+    if timed_out {
+        return skipped(input, "spill_timeout");
+    }
+    if repaired.is_none() {
+        return skipped(input, "repair_failed");
+    }
+
+    let validated = File::open(&spill.path)
+        .map(BufReader::new)
+        .map(|reader| serde_json::from_reader::<_, IgnoredAny>(reader).is_ok());
+    match validated {
+        Ok(true) => {}
+        Ok(false) => return skipped(input, "validate_repaired_failed"),
+        Err(err) => {
+            tracing::warn!("response fixer: failed to reopen spill file: {}", err);
+            return skipped(input, "spill_io_failed");
+        }
+    }
+    if Instant::now() >= deadline {
+        return skipped(input, "spill_timeout");
+    }
+
+    match fs::read(&spill.path) {
+        Ok(bytes) => FixBytesOutcome {
+            data: Bytes::from(bytes),
+            applied: true,
+            details: Some("spilled_to_disk"),
+        },
+        Err(err) => {
+            tracing::warn!("response fixer: failed to read back spill file: {}", err);
+            skipped(input, "spill_io_failed")
+        }
+    }
+}
@@ -11,6 +11,7 @@ use super::audit::build_special_setting;
 use super::audit::ResponseFixerApplied;
 use super::encoding::EncodingFixer;
 use super::json::{fix_sse_json_lines, JsonFixer};
+use super::responses::ResponsesEventFixer;
 use super::sse::SseFixer;
 use super::ResponseFixerConfig;
 
@@ -166,6 +167,7 @@ where
     started: Instant,
     total_bytes_processed: usize,
     applied: ResponseFixerApplied,
+    responses: Option<ResponsesEventFixer>,
     buffer: ChunkBuffer,
     passthrough: bool,
     queued: VecDeque<Bytes>,
@@ -190,6 +192,9 @@ where
             started: Instant::now(),
             total_bytes_processed: 0,
             applied: ResponseFixerApplied::default(),
+            responses: config
+                .fix_responses_events
+                .then(|| ResponsesEventFixer::new(config.max_fix_size)),
             buffer: ChunkBuffer::new(),
             passthrough: false,
             queued: VecDeque::new(),
@@ -205,8 +210,10 @@ where
         }
         self.finalized = true;
 
-        let hit =
-            self.applied.encoding_applied || self.applied.sse_applied || self.applied.json_applied;
+        let hit = self.applied.encoding_applied
+            || self.applied.sse_applied
+            || self.applied.json_applied
+            || self.applied.responses_applied;
         if !hit {
             return;
         }
@@ -229,7 +236,7 @@ where
         let mut data = input;
 
         if self.config.fix_encoding {
-            let res = EncodingFixer::fix_bytes(data);
+            let res = EncodingFixer::fix_bytes(data, self.config.transcode_charsets);
             if res.applied {
                 self.applied.encoding_applied = true;
                 if self.applied.encoding_details.is_none() {
@@ -248,6 +255,19 @@ where
             data = res.data;
         }
 
+        if let Some(fixer) = self.responses.as_mut() {
+            let res = fixer.process(data);
+            if res.applied {
+                self.applied.responses_applied = true;
+                self.applied.responses_details = self.applied.responses_details.or(res.details);
+            }
+            data = res.data;
+        }
+
+        self.fix_json_lines(data)
+    }
+
+    fn fix_json_lines(&mut self, mut data: Bytes) -> Bytes {
         if self.config.fix_truncated_json {
             let json_fixer = JsonFixer::new(self.config.max_json_depth, self.config.max_fix_size);
             let res = fix_sse_json_lines(data, &json_fixer);
@@ -260,6 +280,32 @@ where
 
         data
     }
+
+    /// Releases whatever the Responses fixer is still holding back; it is dropped afterwards so
+    /// later bytes (passthrough or end of stream) are not reordered behind it.
+    fn finish_responses(&mut self) -> Option<Bytes> {
+        let mut fixer = self.responses.take()?;
+        let held = fixer.finish();
+        if held.is_empty() {
+            return None;
+        }
+        Some(self.fix_json_lines(held))
+    }
+
+    fn drain_buffer(&mut self) {
+        if self.buffer.len() > 0 && !self.passthrough {
+            let drained = Bytes::from(self.buffer.drain());
+            let fixed = self.process_bytes(drained);
+            if !fixed.is_empty() {
+                self.queued.push_back(fixed);
+            }
+        } else {
+            self.buffer.clear();
+        }
+        if let Some(held) = self.finish_responses() {
+            self.queued.push_back(held);
+        }
+    }
 }
 
 impl<S> Stream for ResponseFixerStreamInner<S>
@@ -288,31 +334,13 @@ where
             match Pin::new(&mut this.upstream).poll_next(cx) {
                 Poll::Pending => return Poll::Pending,
                 Poll::Ready(None) => {
-                    if this.buffer.len() > 0 && !this.passthrough {
-                        let drained = Bytes::from(this.buffer.drain());
-                        let fixed = this.process_bytes(drained);
-                        if !fixed.is_empty() {
-                            this.queued.push_back(fixed);
-                        }
-                    } else {
-                        this.buffer.clear();
-                    }
-
+                    this.drain_buffer();
                     this.upstream_done = true;
                     this.finalize_if_needed();
                     continue;
                 }
                 Poll::Ready(Some(Err(err))) => {
-                    if this.buffer.len() > 0 && !this.passthrough {
-                        let drained = Bytes::from(this.buffer.drain());
-                        let fixed = this.process_bytes(drained);
-                        if !fixed.is_empty() {
-                            this.queued.push_back(fixed);
-                        }
-                    } else {
-                        this.buffer.clear();
-                    }
-
+                    this.drain_buffer();
                     this.pending_error = Some(err);
                     this.upstream_done = true;
                     this.finalize_if_needed();
@@ -329,6 +357,9 @@ where
                     // 安全保护：如果长时间无换行，buffer 会持续增长。达到上限后降级为透传，避免内存无界增长。
                     if this.buffer.len().saturating_add(chunk.len()) > this.config.max_fix_size {
                         this.passthrough = true;
+                        if let Some(held) = this.finish_responses() {
+                            this.queued.push_back(held);
+                        }
                         this.buffer.flush_to(&mut this.queued);
                         this.queued.push_back(chunk);
                         continue;
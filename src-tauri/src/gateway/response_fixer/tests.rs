@@ -1,22 +1,26 @@
 use super::encoding::EncodingFixer;
 use super::json::JsonFixer;
+use super::spill;
 use super::sse::SseFixer;
 use super::{
-    process_non_stream, ResponseFixerConfig, ResponseFixerStream, DEFAULT_MAX_FIX_SIZE,
-    DEFAULT_MAX_JSON_DEPTH,
+    process_non_stream, ResponseFixerConfig, ResponseFixerStream, TranscodeCharsets,
+    DEFAULT_MAX_FIX_SIZE, DEFAULT_MAX_JSON_DEPTH,
 };
+use crate::settings::ResponseFixerCharset;
 use axum::body::Bytes;
 use futures_core::Stream;
+use serde_json::Value;
 use std::collections::VecDeque;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
+use std::time::Duration;
 
 #[test]
 fn encoding_fixer_valid_utf8_passthrough() {
     let input = Bytes::from_static("Hello 世界".as_bytes());
-    let res = EncodingFixer::fix_bytes(input.clone());
+    let res = EncodingFixer::fix_bytes(input.clone(), TranscodeCharsets::default());
     assert!(!res.applied);
     assert_eq!(res.data, input);
 }
@@ -27,7 +31,7 @@ fn encoding_fixer_removes_utf8_bom() {
     bytes.extend_from_slice(&[0xef, 0xbb, 0xbf]);
     bytes.extend_from_slice(b"Hello");
     let input = Bytes::from(bytes);
-    let res = EncodingFixer::fix_bytes(input);
+    let res = EncodingFixer::fix_bytes(input, TranscodeCharsets::default());
     assert!(res.applied);
     assert_eq!(std::str::from_utf8(res.data.as_ref()).unwrap(), "Hello");
 }
@@ -36,7 +40,7 @@ fn encoding_fixer_removes_utf8_bom() {
 fn encoding_fixer_removes_utf16_bom() {
     // UTF-16LE BOM + "A"（0x41 0x00）
     let input = Bytes::from_static(&[0xff, 0xfe, 0x41, 0x00]);
-    let res = EncodingFixer::fix_bytes(input);
+    let res = EncodingFixer::fix_bytes(input, TranscodeCharsets::default());
     assert!(res.applied);
     assert_eq!(std::str::from_utf8(res.data.as_ref()).unwrap(), "A");
 }
@@ -44,16 +48,57 @@ fn encoding_fixer_removes_utf16_bom() {
 #[test]
 fn encoding_fixer_removes_null_bytes() {
     let input = Bytes::from_static(&[0x48, 0x65, 0x00, 0x6c, 0x6c, 0x6f]);
-    let res = EncodingFixer::fix_bytes(input);
+    let res = EncodingFixer::fix_bytes(input, TranscodeCharsets::default());
     assert!(res.applied);
     assert_eq!(std::str::from_utf8(res.data.as_ref()).unwrap(), "Hello");
 }
 
+fn all_charsets() -> TranscodeCharsets {
+    TranscodeCharsets::from_allow_list(&[
+        ResponseFixerCharset::Gbk,
+        ResponseFixerCharset::Big5,
+        ResponseFixerCharset::ShiftJis,
+    ])
+}
+
+#[test]
+fn encoding_fixer_transcodes_gbk_error_body() {
+    // "中文" in GBK.
+    let mut bytes = br#"{"error":""#.to_vec();
+    bytes.extend_from_slice(&[0xd6, 0xd0, 0xce, 0xc4]);
+    bytes.extend_from_slice(br#""}"#);
+    let res = EncodingFixer::fix_bytes(Bytes::from(bytes.clone()), all_charsets());
+    assert!(res.applied);
+    assert_eq!(res.details, Some("transcoded_gbk"));
+    assert_eq!(
+        std::str::from_utf8(res.data.as_ref()).unwrap(),
+        r#"{"error":"中文"}"#
+    );
+
+    // Empty allow-list: falls back to the lossy UTF-8 repair.
+    let res = EncodingFixer::fix_bytes(Bytes::from(bytes), TranscodeCharsets::default());
+    assert_eq!(res.details, Some("lossy_utf8_decode_encode"));
+}
+
+#[test]
+fn encoding_fixer_keeps_lossy_repair_for_broken_utf8() {
+    let mut bytes = "世界".as_bytes().to_vec();
+    bytes.push(0xe4);
+    bytes.extend_from_slice(b"ok");
+    let res = EncodingFixer::fix_bytes(Bytes::from(bytes), all_charsets());
+    assert!(res.applied);
+    assert_eq!(res.details, Some("lossy_utf8_decode_encode"));
+    assert_eq!(
+        std::str::from_utf8(res.data.as_ref()).unwrap(),
+        "世界\u{fffd}ok"
+    );
+}
+
 #[test]
 fn encoding_fixer_lossy_fix_invalid_utf8() {
     // 0xC3 0x28 是无效 UTF-8 序列
     let input = Bytes::from_static(&[0xc3, 0x28, 0x61]);
-    let res = EncodingFixer::fix_bytes(input);
+    let res = EncodingFixer::fix_bytes(input, TranscodeCharsets::default());
     assert!(res.applied);
     assert!(std::str::from_utf8(res.data.as_ref()).is_ok());
 }
@@ -184,6 +229,70 @@ fn json_fixer_depth_and_size_protection() {
     assert_eq!(res.data.as_ref(), input.as_ref());
 }
 
+fn spill_dir_entries(dir: &std::path::Path) -> usize {
+    std::fs::read_dir(dir).unwrap().count()
+}
+
+#[test]
+fn spill_fix_matches_in_memory_repair_and_cleans_up() {
+    let dir = tempfile::tempdir().unwrap();
+    let fixer = JsonFixer::new(200, 1024 * 1024);
+
+    for input in [
+        Bytes::from_static(br#"{"key":"value""#),
+        Bytes::from_static(br#"[1, 2, 3"#),
+        Bytes::from_static(br#"{"a": 1,}"#),
+        Bytes::from_static(br#"[1, 2 ,  ]"#),
+        Bytes::from_static(b"{\"key\":"),
+        Bytes::from_static(b"{\"key\": , "),
+        Bytes::from_static(br#"{"s":"a\"#),
+        Bytes::from_static(br#"{"key":"value", "outer": {"inner": [1, 2"#),
+    ] {
+        let expected = fixer.fix_bytes(input.clone());
+        let res = spill::fix_bytes(dir.path(), input, 200, Duration::from_secs(5));
+        assert!(res.applied);
+        assert_eq!(res.details, Some("spilled_to_disk"));
+        assert_eq!(res.data.as_ref(), expected.data.as_ref());
+        assert_eq!(spill_dir_entries(dir.path()), 0);
+    }
+}
+
+#[test]
+fn spill_fix_passes_through_valid_json_without_touching_disk() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = Bytes::from_static(br#"{"a":[1,2,3]}"#);
+    let res = spill::fix_bytes(dir.path(), input.clone(), 200, Duration::from_secs(5));
+    assert!(!res.applied);
+    assert_eq!(res.data.as_ref(), input.as_ref());
+    assert_eq!(spill_dir_entries(dir.path()), 0);
+}
+
+#[test]
+fn spill_fix_respects_time_budget() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = Bytes::from_static(br#"{"key":"value""#);
+    let res = spill::fix_bytes(dir.path(), input.clone(), 200, Duration::ZERO);
+    assert!(!res.applied);
+    assert_eq!(res.details, Some("spill_timeout"));
+    assert_eq!(res.data.as_ref(), input.as_ref());
+    assert_eq!(spill_dir_entries(dir.path()), 0);
+}
+
+#[test]
+fn spill_cleanup_only_removes_spill_files() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("spill-1-0.json"), b"{").unwrap();
+    std::fs::write(dir.path().join("keep.txt"), b"x").unwrap();
+
+    assert_eq!(
+        spill::cleanup_stale(dir.path(), Duration::from_secs(3600)),
+        0
+    );
+    assert_eq!(spill::cleanup_stale(dir.path(), Duration::ZERO), 1);
+    assert!(dir.path().join("keep.txt").exists());
+    assert_eq!(spill_dir_entries(dir.path()), 1);
+}
+
 #[test]
 fn response_fixer_non_stream_writes_special_setting_when_hit() {
     let config = ResponseFixerConfig {
@@ -192,6 +301,9 @@ fn response_fixer_non_stream_writes_special_setting_when_hit() {
         fix_truncated_json: true,
         max_json_depth: DEFAULT_MAX_JSON_DEPTH,
         max_fix_size: DEFAULT_MAX_FIX_SIZE,
+        spill_max_size: 0,
+        fix_responses_events: false,
+        transcode_charsets: TranscodeCharsets::default(),
     };
 
     let mut bom_json = Vec::new();
@@ -259,6 +371,9 @@ async fn response_fixer_stream_fixes_truncated_json_in_data_line_across_chunks()
         fix_truncated_json: true,
         max_json_depth: DEFAULT_MAX_JSON_DEPTH,
         max_fix_size: DEFAULT_MAX_FIX_SIZE,
+        spill_max_size: 0,
+        fix_responses_events: false,
+        transcode_charsets: TranscodeCharsets::default(),
     };
 
     let upstream = VecBytesStream::new(vec![
@@ -285,6 +400,9 @@ async fn response_fixer_stream_valid_sse_should_not_write_special_settings() {
         fix_truncated_json: true,
         max_json_depth: DEFAULT_MAX_JSON_DEPTH,
         max_fix_size: DEFAULT_MAX_FIX_SIZE,
+        spill_max_size: 0,
+        fix_responses_events: false,
+        transcode_charsets: TranscodeCharsets::default(),
     };
 
     let upstream = VecBytesStream::new(vec![Ok(Bytes::from_static(b"data: {\"a\":1}\n\n"))]);
@@ -305,6 +423,9 @@ async fn response_fixer_stream_degrades_when_exceeding_max_fix_size_without_newl
         fix_truncated_json: true,
         max_json_depth: DEFAULT_MAX_JSON_DEPTH,
         max_fix_size: 12,
+        spill_max_size: 0,
+        fix_responses_events: false,
+        transcode_charsets: TranscodeCharsets::default(),
     };
 
     let upstream = VecBytesStream::new(vec![
@@ -325,3 +446,98 @@ async fn response_fixer_stream_degrades_when_exceeding_max_fix_size_without_newl
     let settings = special_settings.lock().unwrap();
     assert!(settings.is_empty());
 }
+
+fn responses_stream_config() -> ResponseFixerConfig {
+    ResponseFixerConfig {
+        fix_encoding: true,
+        fix_sse_format: true,
+        fix_truncated_json: true,
+        max_json_depth: DEFAULT_MAX_JSON_DEPTH,
+        max_fix_size: DEFAULT_MAX_FIX_SIZE,
+        spill_max_size: 0,
+        fix_responses_events: true,
+        transcode_charsets: TranscodeCharsets::default(),
+    }
+}
+
+fn responses_fixer_entry(settings: &[Value]) -> Value {
+    settings[0]["fixersApplied"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["fixer"] == "responses")
+        .cloned()
+        .expect("responses fixer entry")
+}
+
+#[tokio::test]
+async fn response_fixer_stream_reassembles_responses_json_split_across_chunks() {
+    let special_settings = Arc::new(Mutex::new(Vec::new()));
+    let upstream = VecBytesStream::new(vec![
+        Ok(Bytes::from_static(
+            b"event: response.output_item.delta\ndata: {\"type\":\"response.output_item.delta\",",
+        )),
+        Ok(Bytes::from_static(b"\ndata: \"delta\":\"hi\"}\n\n")),
+    ]);
+
+    let stream = ResponseFixerStream::new(
+        upstream,
+        responses_stream_config(),
+        special_settings.clone(),
+    );
+    let out = collect_ok_bytes(stream).await;
+    assert_eq!(
+        out,
+        b"event: response.output_item.delta\ndata: {\"type\":\"response.output_item.delta\",\"delta\":\"hi\"}\n\n"
+    );
+
+    let settings = special_settings.lock().unwrap();
+    assert_eq!(settings.len(), 1);
+    let entry = responses_fixer_entry(&settings);
+    assert_eq!(entry["applied"], true);
+    assert_eq!(entry["details"], "json_reassembled");
+}
+
+#[tokio::test]
+async fn response_fixer_stream_repairs_responses_event_framing() {
+    let special_settings = Arc::new(Mutex::new(Vec::new()));
+    let upstream = VecBytesStream::new(vec![Ok(Bytes::from_static(
+        b"event: response.created\ndata: {\"type\":\"response.output_item.delta\"}\nevent: response.completed\ndata: {\"type\":\"response.completed\"}\n\ndata: {\"type\":\"response.done\"}\n\n",
+    ))]);
+
+    let stream = ResponseFixerStream::new(
+        upstream,
+        responses_stream_config(),
+        special_settings.clone(),
+    );
+    let out = collect_ok_bytes(stream).await;
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "event: response.output_item.delta\ndata: {\"type\":\"response.output_item.delta\"}\n\n\
+         event: response.completed\ndata: {\"type\":\"response.completed\"}\n\n\
+         event: response.done\ndata: {\"type\":\"response.done\"}\n\n"
+    );
+
+    let settings = special_settings.lock().unwrap();
+    let entry = responses_fixer_entry(&settings);
+    assert_eq!(entry["details"], "event_type_corrected");
+}
+
+#[tokio::test]
+async fn response_fixer_stream_flushes_unfinished_responses_json_at_end() {
+    let special_settings = Arc::new(Mutex::new(Vec::new()));
+    let upstream = VecBytesStream::new(vec![Ok(Bytes::from_static(
+        b"event: response.output_item.delta\ndata: {\"type\":\"response.output_item.delta\",\"delta\":\"h\n",
+    ))]);
+
+    let stream = ResponseFixerStream::new(
+        upstream,
+        responses_stream_config(),
+        special_settings.clone(),
+    );
+    let out = collect_ok_bytes(stream).await;
+    assert_eq!(
+        out,
+        b"event: response.output_item.delta\ndata: {\"type\":\"response.output_item.delta\",\"delta\":\"h\"}\n"
+    );
+}
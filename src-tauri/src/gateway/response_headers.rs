@@ -0,0 +1,119 @@
+//! Usage: Capture allow-listed upstream response headers onto attempt and request logs.
+//!
+//! The allow-list comes from settings (`captured_response_headers`): exact lowercase names, or a
+//! prefix ending in `*` (e.g. `anthropic-ratelimit-*`). Values pass through the redaction engine.
+
+use crate::redaction;
+use axum::http::HeaderMap;
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+
+const MAX_VALUE_CHARS: usize = 256;
+
+static ALLOW_LIST: OnceLock<RwLock<Arc<[String]>>> = OnceLock::new();
+
+fn cell() -> &'static RwLock<Arc<[String]>> {
+    ALLOW_LIST.get_or_init(|| RwLock::new(Arc::from(Vec::new())))
+}
+
+/// Swap in a new allow-list; call at startup and whenever settings change.
+pub(crate) fn configure(names: &[String]) {
+    let next: Arc<[String]> = crate::settings::normalize_captured_response_headers(names).into();
+    *cell().write().unwrap_or_else(PoisonError::into_inner) = next;
+}
+
+fn is_allowed(allow_list: &[String], name: &str) -> bool {
+    allow_list
+        .iter()
+        .any(|entry| match entry.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => entry == name,
+        })
+}
+
+fn capture_with(allow_list: &[String], headers: &HeaderMap) -> Option<BTreeMap<String, String>> {
+    if allow_list.is_empty() {
+        return None;
+    }
+
+    let mut out: BTreeMap<String, String> = BTreeMap::new();
+    for (name, value) in headers {
+        let name = name.as_str();
+        if !is_allowed(allow_list, name) {
+            continue;
+        }
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+        out.entry(name.to_string())
+            .and_modify(|joined| {
+                joined.push_str(", ");
+                joined.push_str(value);
+            })
+            .or_insert_with(|| value.to_string());
+    }
+
+    if out.is_empty() {
+        return None;
+    }
+    for value in out.values_mut() {
+        if value.chars().count() > MAX_VALUE_CHARS {
+            *value = value.chars().take(MAX_VALUE_CHARS).collect();
+        }
+        *value = redaction::redact_string(std::mem::take(value));
+    }
+    Some(out)
+}
+
+/// Allow-listed headers of an upstream response, or `None` when nothing matched.
+pub(crate) fn capture(headers: &HeaderMap) -> Option<BTreeMap<String, String>> {
+    let allow_list = cell()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    capture_with(&allow_list, headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn capture_matches_exact_and_prefix_entries() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-backend-model",
+            HeaderValue::from_static("claude-sonnet-4-5"),
+        );
+        headers.insert(
+            "anthropic-ratelimit-tokens-remaining",
+            HeaderValue::from_static("42"),
+        );
+        headers.append("x-served-by", HeaderValue::from_static("a"));
+        headers.append("x-served-by", HeaderValue::from_static("b"));
+        headers.insert(
+            "x-api-key",
+            HeaderValue::from_static("sk-ant-0123456789abcdefXYZ"),
+        );
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+
+        let allow_list = vec![
+            "x-backend-model".to_string(),
+            "anthropic-ratelimit-*".to_string(),
+            "x-served-by".to_string(),
+            "x-api-key".to_string(),
+        ];
+        let captured = capture_with(&allow_list, &headers).expect("captured");
+
+        assert_eq!(captured.len(), 4);
+        assert_eq!(captured["x-backend-model"], "claude-sonnet-4-5");
+        assert_eq!(captured["anthropic-ratelimit-tokens-remaining"], "42");
+        assert_eq!(captured["x-served-by"], "a, b");
+        assert!(!captured["x-api-key"].contains("0123456789abcdef"));
+        assert!(!captured.contains_key("content-type"));
+
+        assert_eq!(capture_with(&[], &headers), None);
+        assert_eq!(capture_with(&["x-missing".to_string()], &headers), None);
+    }
+}
@@ -2,6 +2,7 @@ use axum::{
     body::Body,
     extract::{Path, State},
     http::Request,
+    middleware,
     response::Response,
     routing::{any, get},
     Json, Router,
@@ -9,7 +10,7 @@ use axum::{
 use serde::Serialize;
 
 use super::manager::GatewayAppState;
-use super::proxy::proxy_impl;
+use super::proxy::{proxy_impl, require_gateway_access_token};
 use super::util::now_unix_seconds;
 
 #[derive(Debug, Serialize)]
@@ -84,10 +85,14 @@ async fn proxy_openai_v1_root(
     proxy_impl(state, "codex".to_string(), "/v1".to_string(), req).await
 }
 
+/// Prefix of the local admin REST API served next to the proxy routes.
+const LOCAL_API_PREFIX: &str = "/__aio__/api";
+
 pub(super) fn build_router(state: GatewayAppState) -> Router {
-    Router::new()
-        .route("/", get(root))
-        .route("/health", get(health))
+    // Proxy routes require the gateway access token from non-loopback clients; `/` and
+    // `/health` stay open so LAN probes can still detect the gateway. The local API enforces
+    // its own loopback + token check.
+    let proxied = Router::new()
         .route(
             "/:cli_key/_aio/provider/:provider_id/*path",
             any(proxy_cli_with_provider_any),
@@ -95,5 +100,18 @@ pub(super) fn build_router(state: GatewayAppState) -> Router {
         .route("/v1", any(proxy_openai_v1_root))
         .route("/v1/*path", any(proxy_openai_v1_any))
         .route("/:cli_key/*path", any(proxy_cli_any))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_gateway_access_token,
+        ));
+
+    Router::new()
+        .route("/", get(root))
+        .route("/health", get(health))
+        .nest(
+            LOCAL_API_PREFIX,
+            crate::app::admin_api::gateway_local_api(state.app.clone()),
+        )
+        .merge(proxied)
         .with_state(state)
 }
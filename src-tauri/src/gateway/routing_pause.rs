@@ -0,0 +1,55 @@
+//! Usage: Global "pause all routing" switch (tray menu); while paused the gateway answers every
+//! proxied request with 503 `GW_ROUTING_PAUSED`.
+//!
+//! Runtime-only: the switch resets to "not paused" on restart.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static SWITCH: PauseSwitch = PauseSwitch::new();
+
+struct PauseSwitch {
+    paused: AtomicBool,
+}
+
+impl PauseSwitch {
+    const fn new() -> Self {
+        Self {
+            paused: AtomicBool::new(false),
+        }
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Flips the switch and returns the new state (the tray check mark to show).
+    fn toggle(&self) -> bool {
+        !self.paused.fetch_xor(true, Ordering::Relaxed)
+    }
+}
+
+pub(crate) fn is_paused() -> bool {
+    SWITCH.is_paused()
+}
+
+/// Tray "pause all routing" click: pauses when routing, resumes when paused.
+pub(crate) fn toggle() -> bool {
+    SWITCH.toggle()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PauseSwitch;
+
+    #[test]
+    fn toggle_pauses_then_resumes() {
+        let switch = PauseSwitch::new();
+        assert!(!switch.is_paused());
+
+        assert!(switch.toggle());
+        assert!(switch.is_paused());
+
+        assert!(!switch.toggle());
+        assert!(!switch.is_paused());
+    }
+}
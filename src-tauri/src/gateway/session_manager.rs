@@ -4,9 +4,10 @@ use serde_json::Value;
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::Mutex;
 
-const DEFAULT_SESSION_TTL_SECS: i64 = 300;
+pub(crate) const DEFAULT_SESSION_TTL_SECS: i64 = 300;
 const MAX_SESSION_ID_LEN: usize = 256;
 const MAX_BINDINGS: usize = 5000;
 const SESSION_SUFFIX_LEN: usize = 8;
@@ -18,12 +19,16 @@ pub struct ActiveSessionSnapshot {
     pub session_id: String,
     pub session_suffix: String,
     pub provider_id: i64,
+    pub sort_mode_id: Option<i64>,
+    pub provider_tag: Option<String>,
+    /// Pinned bindings never expire; `expires_at` is kept for display only.
+    pub pinned: bool,
     pub expires_at: i64,
 }
 
 #[derive(Debug)]
 pub struct SessionManager {
-    ttl_secs: i64,
+    ttl_secs: AtomicI64,
     bindings: Mutex<HashMap<SessionKey, SessionBinding>>,
 }
 
@@ -32,9 +37,18 @@ struct SessionBinding {
     provider_id: i64,
     sort_mode_id: Option<i64>,
     provider_order: Option<Vec<i64>>,
+    provider_tag: Option<String>,
+    // Set by `pin_provider`: routes every request of the session to `provider_id`.
+    pinned: bool,
     expires_at: i64,
 }
 
+impl SessionBinding {
+    fn is_live(&self, now_unix: i64) -> bool {
+        self.pinned || self.expires_at > now_unix
+    }
+}
+
 #[derive(Debug, Clone, Eq)]
 struct SessionKey {
     cli_key: String,
@@ -57,11 +71,20 @@ impl Hash for SessionKey {
 impl SessionManager {
     pub fn new() -> Self {
         Self {
-            ttl_secs: DEFAULT_SESSION_TTL_SECS,
+            ttl_secs: AtomicI64::new(DEFAULT_SESSION_TTL_SECS),
             bindings: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Applies to bindings created or refreshed from now on.
+    pub fn set_ttl_secs(&self, ttl_secs: i64) {
+        self.ttl_secs.store(ttl_secs.max(1), Ordering::Relaxed);
+    }
+
+    fn expires_at(&self, now_unix: i64) -> i64 {
+        now_unix.saturating_add(self.ttl_secs.load(Ordering::Relaxed).max(1))
+    }
+
     pub fn clear_cli_bindings(&self, cli_key: &str) -> usize {
         let cli_key = cli_key.trim();
         if cli_key.is_empty() {
@@ -162,7 +185,7 @@ impl SessionManager {
 
         let mut guard = self.bindings.lock_or_recover();
         match guard.get(&key) {
-            Some(binding) if binding.expires_at > now_unix => {
+            Some(binding) if binding.is_live(now_unix) => {
                 (binding.provider_id > 0).then_some(binding.provider_id)
             }
             Some(_) => {
@@ -187,7 +210,7 @@ impl SessionManager {
 
         let mut guard = self.bindings.lock_or_recover();
         match guard.get(&key) {
-            Some(binding) if binding.expires_at > now_unix => Some(binding.sort_mode_id),
+            Some(binding) if binding.is_live(now_unix) => Some(binding.sort_mode_id),
             Some(_) => {
                 guard.remove(&key);
                 None
@@ -216,16 +239,11 @@ impl SessionManager {
         };
 
         let mut guard = self.bindings.lock_or_recover();
-        if guard.len() >= MAX_BINDINGS {
-            drop_expired(&mut guard, now_unix);
-            if guard.len() >= MAX_BINDINGS {
-                guard.clear();
-            }
-        }
+        make_room(&mut guard, now_unix);
 
         if let Some(existing) = guard.get_mut(&key) {
-            if existing.expires_at > now_unix {
-                existing.expires_at = now_unix.saturating_add(self.ttl_secs.max(1));
+            if existing.is_live(now_unix) {
+                existing.expires_at = self.expires_at(now_unix);
                 if existing.provider_order.is_none() {
                     existing.provider_order = provider_order;
                 }
@@ -240,7 +258,9 @@ impl SessionManager {
                 provider_id: 0,
                 sort_mode_id,
                 provider_order,
-                expires_at: now_unix.saturating_add(self.ttl_secs.max(1)),
+                provider_tag: None,
+                pinned: false,
+                expires_at: self.expires_at(now_unix),
             },
         );
     }
@@ -258,7 +278,29 @@ impl SessionManager {
 
         let mut guard = self.bindings.lock_or_recover();
         match guard.get(&key) {
-            Some(binding) if binding.expires_at > now_unix => binding.provider_order.clone(),
+            Some(binding) if binding.is_live(now_unix) => binding.provider_order.clone(),
+            Some(_) => {
+                guard.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn get_bound_provider_tag(
+        &self,
+        cli_key: &str,
+        session_id: &str,
+        now_unix: i64,
+    ) -> Option<String> {
+        let key = SessionKey {
+            cli_key: cli_key.to_string(),
+            session_id: session_id.to_string(),
+        };
+
+        let mut guard = self.bindings.lock_or_recover();
+        match guard.get(&key) {
+            Some(binding) if binding.is_live(now_unix) => binding.provider_tag.clone(),
             Some(_) => {
                 guard.remove(&key);
                 None
@@ -267,6 +309,22 @@ impl SessionManager {
         }
     }
 
+    // Remember the tag a request selected so later requests of the session default to it.
+    // Only updates a live binding (see `bind_sort_mode`).
+    pub fn bind_provider_tag(&self, cli_key: &str, session_id: &str, tag: &str, now_unix: i64) {
+        let key = SessionKey {
+            cli_key: cli_key.to_string(),
+            session_id: session_id.to_string(),
+        };
+
+        let mut guard = self.bindings.lock_or_recover();
+        if let Some(binding) = guard.get_mut(&key) {
+            if binding.is_live(now_unix) {
+                binding.provider_tag = Some(tag.to_string());
+            }
+        }
+    }
+
     pub fn bind_success(
         &self,
         cli_key: &str,
@@ -285,17 +343,15 @@ impl SessionManager {
         };
 
         let mut guard = self.bindings.lock_or_recover();
-        if guard.len() >= MAX_BINDINGS {
-            drop_expired(&mut guard, now_unix);
-            if guard.len() >= MAX_BINDINGS {
-                guard.clear();
-            }
-        }
+        make_room(&mut guard, now_unix);
 
-        let expires_at = now_unix.saturating_add(self.ttl_secs.max(1));
+        let expires_at = self.expires_at(now_unix);
         if let Some(existing) = guard.get_mut(&key) {
-            if existing.expires_at > now_unix {
-                existing.provider_id = provider_id;
+            if existing.is_live(now_unix) {
+                // A pin outranks whatever provider happened to serve the request.
+                if !existing.pinned {
+                    existing.provider_id = provider_id;
+                }
                 existing.expires_at = expires_at;
                 if existing.sort_mode_id.is_none() {
                     existing.sort_mode_id = sort_mode_id;
@@ -311,6 +367,8 @@ impl SessionManager {
                 provider_id,
                 sort_mode_id,
                 provider_order: None,
+                provider_tag: None,
+                pinned: false,
                 expires_at,
             },
         );
@@ -328,6 +386,7 @@ impl SessionManager {
 
         let mut guard = self.bindings.lock_or_recover();
         match guard.get_mut(&key) {
+            Some(binding) if binding.pinned => false,
             Some(binding) if binding.expires_at > now_unix => {
                 binding.provider_id = 0;
                 true
@@ -355,14 +414,97 @@ impl SessionManager {
                 session_id: k.session_id.clone(),
                 session_suffix: session_suffix(&k.session_id),
                 provider_id: v.provider_id,
+                sort_mode_id: v.sort_mode_id,
+                provider_tag: v.provider_tag.clone(),
+                pinned: v.pinned,
                 expires_at: v.expires_at,
             })
             .collect();
 
-        rows.sort_by(|a, b| b.expires_at.cmp(&a.expires_at));
+        rows.sort_by(|a, b| {
+            b.pinned
+                .cmp(&a.pinned)
+                .then_with(|| b.expires_at.cmp(&a.expires_at))
+        });
         rows.truncate(limit);
         rows
     }
+
+    pub fn get_pinned_provider(&self, cli_key: &str, session_id: &str) -> Option<i64> {
+        let key = SessionKey {
+            cli_key: cli_key.to_string(),
+            session_id: session_id.to_string(),
+        };
+
+        let guard = self.bindings.lock_or_recover();
+        guard
+            .get(&key)
+            .filter(|binding| binding.pinned && binding.provider_id > 0)
+            .map(|binding| binding.provider_id)
+    }
+
+    // Force every request of the session onto `provider_id` until `unpin_provider`.
+    // Works for sessions the gateway has not seen yet.
+    pub fn pin_provider(
+        &self,
+        cli_key: &str,
+        session_id: &str,
+        provider_id: i64,
+        now_unix: i64,
+    ) -> bool {
+        if cli_key.trim().is_empty() || session_id.trim().is_empty() || provider_id <= 0 {
+            return false;
+        }
+
+        let key = SessionKey {
+            cli_key: cli_key.to_string(),
+            session_id: session_id.to_string(),
+        };
+
+        let mut guard = self.bindings.lock_or_recover();
+        make_room(&mut guard, now_unix);
+
+        let expires_at = self.expires_at(now_unix);
+        match guard.get_mut(&key) {
+            Some(existing) if existing.is_live(now_unix) => {
+                existing.provider_id = provider_id;
+                existing.pinned = true;
+                existing.expires_at = expires_at;
+            }
+            _ => {
+                guard.insert(
+                    key,
+                    SessionBinding {
+                        provider_id,
+                        sort_mode_id: None,
+                        provider_order: None,
+                        provider_tag: None,
+                        pinned: true,
+                        expires_at,
+                    },
+                );
+            }
+        }
+        true
+    }
+
+    // Release a pin; the binding stays as a regular one and expires after the TTL.
+    pub fn unpin_provider(&self, cli_key: &str, session_id: &str, now_unix: i64) -> bool {
+        let key = SessionKey {
+            cli_key: cli_key.to_string(),
+            session_id: session_id.to_string(),
+        };
+
+        let mut guard = self.bindings.lock_or_recover();
+        match guard.get_mut(&key) {
+            Some(binding) if binding.pinned => {
+                binding.pinned = false;
+                binding.expires_at = self.expires_at(now_unix);
+                true
+            }
+            _ => false,
+        }
+    }
 }
 
 fn header_string(headers: &HeaderMap, key: &str) -> Option<String> {
@@ -631,7 +773,17 @@ fn session_suffix(session_id: &str) -> String {
 }
 
 fn drop_expired(map: &mut HashMap<SessionKey, SessionBinding>, now_unix: i64) {
-    map.retain(|_, v| v.expires_at > now_unix);
+    map.retain(|_, v| v.is_live(now_unix));
+}
+
+// Keep the map bounded; pins survive even a full reset.
+fn make_room(map: &mut HashMap<SessionKey, SessionBinding>, now_unix: i64) {
+    if map.len() >= MAX_BINDINGS {
+        drop_expired(map, now_unix);
+        if map.len() >= MAX_BINDINGS {
+            map.retain(|_, v| v.pinned);
+        }
+    }
 }
 
 #[cfg(test)]
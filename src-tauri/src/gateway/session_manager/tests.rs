@@ -35,6 +35,83 @@ fn clear_cli_bindings_removes_only_target_cli() {
     );
 }
 
+#[test]
+fn bind_provider_tag_requires_live_binding_and_expires_with_it() {
+    let manager = SessionManager::new();
+    let now_unix = 100;
+
+    manager.bind_provider_tag("claude", "session_a", "cheap", now_unix);
+    assert_eq!(
+        manager.get_bound_provider_tag("claude", "session_a", now_unix),
+        None
+    );
+
+    manager.bind_sort_mode("claude", "session_a", None, None, now_unix);
+    manager.bind_provider_tag("claude", "session_a", "cheap", now_unix);
+    assert_eq!(
+        manager
+            .get_bound_provider_tag("claude", "session_a", now_unix)
+            .as_deref(),
+        Some("cheap")
+    );
+    assert_eq!(
+        manager.get_bound_provider_tag("codex", "session_a", now_unix),
+        None
+    );
+    assert_eq!(
+        manager.get_bound_provider_tag("claude", "session_a", now_unix + DEFAULT_SESSION_TTL_SECS),
+        None
+    );
+}
+
+#[test]
+fn pinned_binding_survives_ttl_and_wins_over_bind_success() {
+    let manager = SessionManager::new();
+    let now_unix = 100;
+
+    assert!(!manager.pin_provider("claude", "session_a", 0, now_unix));
+    assert!(manager.pin_provider("claude", "session_a", 7, now_unix));
+    manager.bind_success("claude", "session_a", 9, None, now_unix);
+    assert!(!manager.clear_bound_provider("claude", "session_a", now_unix));
+
+    let later = now_unix + DEFAULT_SESSION_TTL_SECS * 10;
+    assert_eq!(
+        manager.get_bound_provider("claude", "session_a", later),
+        Some(7)
+    );
+    assert_eq!(manager.get_pinned_provider("claude", "session_a"), Some(7));
+    assert_eq!(manager.get_pinned_provider("codex", "session_a"), None);
+
+    let sessions = manager.list_active(later, 10);
+    assert_eq!(sessions.len(), 1);
+    assert!(sessions[0].pinned);
+
+    assert!(manager.unpin_provider("claude", "session_a", later));
+    assert!(!manager.unpin_provider("claude", "session_a", later));
+    assert_eq!(manager.get_pinned_provider("claude", "session_a"), None);
+    assert_eq!(
+        manager.get_bound_provider("claude", "session_a", later + DEFAULT_SESSION_TTL_SECS),
+        None
+    );
+}
+
+#[test]
+fn set_ttl_secs_applies_to_new_bindings() {
+    let manager = SessionManager::new();
+    let now_unix = 100;
+
+    manager.set_ttl_secs(30);
+    manager.bind_success("claude", "session_a", 3, None, now_unix);
+    assert_eq!(
+        manager.get_bound_provider("claude", "session_a", now_unix + 29),
+        Some(3)
+    );
+    assert_eq!(
+        manager.get_bound_provider("claude", "session_a", now_unix + 30),
+        None
+    );
+}
+
 #[test]
 fn extract_session_id_fallback_uses_message_fingerprint_and_ignores_user_agent() {
     let body = serde_json::json!({
@@ -0,0 +1,69 @@
+//! Usage: Per-session rate limit for sessions flagged by cost anomaly detection.
+//!
+//! A throttled session may send one request per `MIN_INTERVAL_MS`; everything in between is
+//! rejected with 429 `GW_SESSION_THROTTLED`. State is in-memory and lasts until released.
+
+use crate::shared::mutex_ext::MutexExt;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+const MIN_INTERVAL_MS: i64 = 60_000;
+
+static THROTTLED: OnceLock<Mutex<HashMap<String, Option<i64>>>> = OnceLock::new();
+
+fn throttled() -> &'static Mutex<HashMap<String, Option<i64>>> {
+    THROTTLED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub(crate) fn throttle(session_id: &str) {
+    throttled()
+        .lock_or_recover()
+        .entry(session_id.to_string())
+        .or_insert(None);
+}
+
+/// Returns whether the session was throttled.
+pub(crate) fn release(session_id: &str) -> bool {
+    throttled().lock_or_recover().remove(session_id).is_some()
+}
+
+pub(crate) fn is_throttled(session_id: &str) -> bool {
+    throttled().lock_or_recover().contains_key(session_id)
+}
+
+/// Whether a request of `session_id` may pass at `now_ms` (records the pass).
+pub(crate) fn try_acquire(session_id: &str, now_ms: i64) -> bool {
+    let mut throttled = throttled().lock_or_recover();
+    let Some(last_allowed_ms) = throttled.get_mut(session_id) else {
+        return true;
+    };
+    if let Some(last) = *last_allowed_ms {
+        if now_ms.saturating_sub(last) < MIN_INTERVAL_MS {
+            return false;
+        }
+    }
+    *last_allowed_ms = Some(now_ms);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throttled_session_passes_once_per_interval_until_released() {
+        let session_id = "sess-throttle-test";
+        assert!(try_acquire(session_id, 0));
+        assert!(try_acquire(session_id, 1));
+
+        throttle(session_id);
+        assert!(is_throttled(session_id));
+        assert!(try_acquire(session_id, 1_000));
+        assert!(!try_acquire(session_id, 1_000 + MIN_INTERVAL_MS - 1));
+        assert!(try_acquire(session_id, 1_000 + MIN_INTERVAL_MS));
+
+        assert!(release(session_id));
+        assert!(!release(session_id));
+        assert!(try_acquire(session_id, 1_000 + MIN_INTERVAL_MS + 1));
+    }
+}
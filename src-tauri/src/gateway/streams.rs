@@ -6,16 +6,28 @@ pub(super) use types::StreamFinalizeCtx;
 mod finalize;
 mod request_end;
 
+mod net_stats;
+pub(super) use net_stats::StreamNetStats;
+
 mod relay;
 pub(super) use relay::{FirstChunkStream, RelayBodyStream};
 
 mod gunzip;
 pub(super) use gunzip::GunzipStream;
 
+mod stall_watchdog;
+pub(super) use stall_watchdog::StreamStallConfig;
+
 mod usage_tee;
 pub(super) use usage_tee::{
-    spawn_usage_sse_relay_body, UsageBodyBufferTeeStream, UsageSseTeeStream,
+    spawn_usage_sse_relay_body, SseCoalesceConfig, UsageBodyBufferTeeStream, UsageSseTeeStream,
 };
 
 mod timing;
 pub(super) use timing::TimingOnlyTeeStream;
+
+mod error_event;
+pub(super) use error_event::{client_sse_body, sse_error_event, StreamFailureConfig};
+
+mod stitch;
+pub(super) use stitch::{StitchingStream, StreamStitchRetry};
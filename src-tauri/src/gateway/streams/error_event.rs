@@ -0,0 +1,298 @@
+//! Usage: End SSE bodies that fail after output with a well-formed error event.
+//!
+//! Once response headers are out the attempt can no longer fail over, so an upstream read error
+//! used to cut the body wherever it happened, often inside an event. Events are now forwarded only
+//! once complete; on error the unfinished tail is dropped and an error event in the client's wire
+//! format (carrying the trace id) ends the stream.
+
+use axum::body::{Body, Bytes};
+use bytes::BytesMut;
+use futures_core::Stream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use super::super::proxy::GatewayErrorCode;
+
+/// How streaming attempts handle upstream failures (`settings.stream_*`).
+#[derive(Debug, Clone, Copy, Default)]
+pub(in crate::gateway) struct StreamFailureConfig {
+    /// Wait for the first upstream chunk before answering, even without a first-byte timeout, so
+    /// errors and empty streams before any output still fail over.
+    pub(in crate::gateway) first_byte_failover: bool,
+    /// End bodies that fail after output with an error event (see module docs).
+    pub(in crate::gateway) error_event: bool,
+}
+
+impl StreamFailureConfig {
+    pub(in crate::gateway) fn new(first_byte_failover: bool, error_event: bool) -> Self {
+        Self {
+            first_byte_failover,
+            error_event,
+        }
+    }
+}
+
+/// End offset of the last complete event in `buf`, looking only at boundaries ending after `from`.
+fn last_event_boundary(buf: &[u8], from: usize) -> Option<usize> {
+    (from.max(2)..=buf.len()).rev().find(|&end| {
+        let head = &buf[..end];
+        head.ends_with(b"\n\n") || head.ends_with(b"\r\n\r\n")
+    })
+}
+
+/// Error event in the wire format the client expects for `cli_key` / `path`.
+pub(in crate::gateway) fn sse_error_event(cli_key: &str, path: &str, trace_id: &str) -> Bytes {
+    let code = GatewayErrorCode::StreamError.as_str();
+    let message =
+        format!("upstream stream failed after partial output ({code}); trace_id={trace_id}");
+    let is_responses_path = matches!(path.trim_end_matches('/'), "/v1/responses" | "/responses");
+
+    let (event, data) = match cli_key {
+        "claude" => (
+            Some("error"),
+            serde_json::json!({
+                "type": "error",
+                "error": { "type": "api_error", "message": message },
+                "trace_id": trace_id,
+            }),
+        ),
+        "codex" if is_responses_path => (
+            Some("response.failed"),
+            serde_json::json!({
+                "type": "response.failed",
+                "response": {
+                    "status": "failed",
+                    "error": { "code": "server_error", "message": message },
+                },
+                "trace_id": trace_id,
+            }),
+        ),
+        "gemini" => (
+            None,
+            serde_json::json!({
+                "error": { "code": 502, "message": message, "status": "UNAVAILABLE" },
+                "trace_id": trace_id,
+            }),
+        ),
+        _ => (
+            None,
+            serde_json::json!({
+                "error": {
+                    "message": message,
+                    "type": "server_error",
+                    "code": code,
+                    "trace_id": trace_id,
+                },
+            }),
+        ),
+    };
+
+    let mut out = String::new();
+    if let Some(event) = event {
+        out.push_str("event: ");
+        out.push_str(event);
+        out.push('\n');
+    }
+    out.push_str("data: ");
+    out.push_str(&data.to_string());
+    out.push_str("\n\n");
+    Bytes::from(out)
+}
+
+pub(in crate::gateway) struct SseErrorEventStream<S>
+where
+    S: Stream<Item = Result<Bytes, reqwest::Error>> + Unpin,
+{
+    inner: S,
+    pending: BytesMut,
+    error_event: Option<Bytes>,
+    done: bool,
+}
+
+impl<S> SseErrorEventStream<S>
+where
+    S: Stream<Item = Result<Bytes, reqwest::Error>> + Unpin,
+{
+    pub(in crate::gateway) fn new(inner: S, error_event: Bytes) -> Self {
+        Self {
+            inner,
+            pending: BytesMut::new(),
+            error_event: Some(error_event),
+            done: false,
+        }
+    }
+}
+
+impl<S> Stream for SseErrorEventStream<S>
+where
+    S: Stream<Item = Result<Bytes, reqwest::Error>> + Unpin,
+{
+    type Item = Result<Bytes, reqwest::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.as_mut().get_mut();
+        loop {
+            if this.done {
+                return Poll::Ready(None);
+            }
+            match Pin::new(&mut this.inner).poll_next(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Some(Ok(chunk))) => {
+                    let scanned = this.pending.len().saturating_sub(3);
+                    this.pending.extend_from_slice(&chunk);
+                    if let Some(end) = last_event_boundary(&this.pending, scanned) {
+                        return Poll::Ready(Some(Ok(this.pending.split_to(end).freeze())));
+                    }
+                }
+                Poll::Ready(Some(Err(err))) => {
+                    this.done = true;
+                    tracing::warn!(
+                        dropped_bytes = this.pending.len(),
+                        "upstream stream failed after output, ending with an error event: {}",
+                        err
+                    );
+                    this.pending.clear();
+                    return Poll::Ready(this.error_event.take().map(Ok));
+                }
+                Poll::Ready(None) => {
+                    // A clean end keeps whatever the upstream sent, terminated or not.
+                    this.done = true;
+                    if this.pending.is_empty() {
+                        return Poll::Ready(None);
+                    }
+                    return Poll::Ready(Some(Ok(this.pending.split().freeze())));
+                }
+            }
+        }
+    }
+}
+
+/// Client body for a relayed SSE stream; `error_event: None` forwards the stream unchanged.
+pub(in crate::gateway) fn client_sse_body<S>(stream: S, error_event: Option<Bytes>) -> Body
+where
+    S: Stream<Item = Result<Bytes, reqwest::Error>> + Unpin + Send + 'static,
+{
+    match error_event {
+        Some(event) => Body::from_stream(SseErrorEventStream::new(stream, event)),
+        None => Body::from_stream(stream),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    struct VecBytesStream(VecDeque<Result<Bytes, reqwest::Error>>);
+
+    impl Stream for VecBytesStream {
+        type Item = Result<Bytes, reqwest::Error>;
+
+        fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            Poll::Ready(self.0.pop_front())
+        }
+    }
+
+    fn reqwest_error() -> reqwest::Error {
+        reqwest::Client::new()
+            .get("not a url")
+            .build()
+            .expect_err("invalid url")
+    }
+
+    async fn collect(items: Vec<Result<Bytes, reqwest::Error>>) -> Vec<Bytes> {
+        let mut stream = SseErrorEventStream::new(
+            VecBytesStream(items.into_iter().collect()),
+            Bytes::from_static(b"event: error\ndata: {}\n\n"),
+        );
+        let mut out = Vec::new();
+        while let Some(item) = std::future::poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await
+        {
+            out.push(item.expect("error events replace upstream errors"));
+        }
+        out
+    }
+
+    #[tokio::test]
+    async fn upstream_error_drops_the_unfinished_event_and_ends_with_the_error_event() {
+        let out = collect(vec![
+            Ok(Bytes::from_static(b"data: a\n\ndata: b")),
+            Ok(Bytes::from_static(b"\n\ndata: c")),
+            Err(reqwest_error()),
+            Ok(Bytes::from_static(b"data: never\n\n")),
+        ])
+        .await;
+        assert_eq!(
+            out,
+            vec![
+                Bytes::from_static(b"data: a\n\n"),
+                Bytes::from_static(b"data: b\n\n"),
+                Bytes::from_static(b"event: error\ndata: {}\n\n"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn clean_end_forwards_the_unterminated_tail() {
+        let out = collect(vec![
+            Ok(Bytes::from_static(b"data: a\n")),
+            Ok(Bytes::from_static(b"\ndata: [DONE]")),
+        ])
+        .await;
+        assert_eq!(
+            out,
+            vec![
+                Bytes::from_static(b"data: a\n\n"),
+                Bytes::from_static(b"data: [DONE]"),
+            ]
+        );
+    }
+
+    fn event_json(event: &Bytes) -> serde_json::Value {
+        let text = std::str::from_utf8(event).expect("utf8");
+        assert!(text.ends_with("\n\n"), "{text}");
+        let data = text
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .expect("data line");
+        serde_json::from_str(data).expect("json")
+    }
+
+    #[test]
+    fn last_event_boundary_finds_the_latest_complete_event() {
+        assert_eq!(last_event_boundary(b"data: a\n\ndata: b", 0), Some(9));
+        assert_eq!(last_event_boundary(b"data: a\n\ndata: b\n\n", 0), Some(18));
+        assert_eq!(last_event_boundary(b"data: a\r\n\r\n", 0), Some(11));
+        assert_eq!(last_event_boundary(b"data: a\n", 0), None);
+        assert_eq!(last_event_boundary(b"data: a\n\ndata: b", 12), None);
+    }
+
+    #[test]
+    fn sse_error_event_matches_each_client_format() {
+        let claude = sse_error_event("claude", "/v1/messages", "t1");
+        assert!(claude.starts_with(b"event: error\n"));
+        let value = event_json(&claude);
+        assert_eq!(value["type"], "error");
+        assert_eq!(value["error"]["type"], "api_error");
+        assert_eq!(value["trace_id"], "t1");
+        assert!(value["error"]["message"]
+            .as_str()
+            .is_some_and(|m| m.contains("trace_id=t1")));
+
+        let responses = sse_error_event("codex", "/v1/responses/", "t2");
+        assert!(responses.starts_with(b"event: response.failed\n"));
+        assert_eq!(event_json(&responses)["response"]["status"], "failed");
+
+        let chat = sse_error_event("codex", "/v1/chat/completions", "t3");
+        assert!(chat.starts_with(b"data: "));
+        let value = event_json(&chat);
+        assert_eq!(
+            value["error"]["code"],
+            GatewayErrorCode::StreamError.as_str()
+        );
+        assert_eq!(value["error"]["trace_id"], "t3");
+
+        let gemini = sse_error_event("gemini", "/v1beta/models/x:streamGenerateContent", "t4");
+        assert_eq!(event_json(&gemini)["error"]["status"], "UNAVAILABLE");
+    }
+}
@@ -0,0 +1,79 @@
+//! Usage: Network-level byte/chunk accounting for a single upstream attempt body.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use super::super::events::AttemptStreamStats;
+
+/// Shared between the raw upstream adapter (which records chunks) and the finalize ctx
+/// (which snapshots them into the attempt log). Only one task writes at a time.
+pub(in crate::gateway) struct StreamNetStats {
+    origin: Instant,
+    bytes_received: AtomicU64,
+    chunk_count: AtomicU64,
+    last_chunk_ms: AtomicU64,
+    max_chunk_gap_ms: AtomicU64,
+}
+
+impl StreamNetStats {
+    pub(in crate::gateway) fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+            bytes_received: AtomicU64::new(0),
+            chunk_count: AtomicU64::new(0),
+            last_chunk_ms: AtomicU64::new(0),
+            max_chunk_gap_ms: AtomicU64::new(0),
+        }
+    }
+
+    pub(in crate::gateway) fn record_chunk(&self, len: usize) {
+        let now_ms = self.origin.elapsed().as_millis().min(u64::MAX as u128) as u64;
+        let prev_count = self.chunk_count.fetch_add(1, Ordering::Relaxed);
+        let prev_ms = self.last_chunk_ms.swap(now_ms, Ordering::Relaxed);
+        if prev_count > 0 {
+            self.max_chunk_gap_ms
+                .fetch_max(now_ms.saturating_sub(prev_ms), Ordering::Relaxed);
+        }
+        self.bytes_received.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    pub(in crate::gateway) fn snapshot(&self) -> Option<AttemptStreamStats> {
+        let chunk_count = self.chunk_count.load(Ordering::Relaxed);
+        if chunk_count == 0 {
+            return None;
+        }
+        Some(AttemptStreamStats {
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            chunk_count,
+            max_chunk_gap_ms: self.max_chunk_gap_ms.load(Ordering::Relaxed),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_is_none_until_first_chunk() {
+        let stats = StreamNetStats::new();
+        assert!(stats.snapshot().is_none());
+
+        stats.record_chunk(10);
+        stats.record_chunk(5);
+        let snap = stats.snapshot().expect("stats after chunks");
+        assert_eq!(snap.bytes_received, 15);
+        assert_eq!(snap.chunk_count, 2);
+    }
+
+    #[test]
+    fn max_gap_tracks_longest_pause_between_chunks() {
+        let stats = StreamNetStats::new();
+        stats.record_chunk(1);
+        std::thread::sleep(std::time::Duration::from_millis(30));
+        stats.record_chunk(1);
+        stats.record_chunk(1);
+        let snap = stats.snapshot().expect("stats after chunks");
+        assert!(snap.max_chunk_gap_ms >= 25, "gap={}", snap.max_chunk_gap_ms);
+    }
+}
@@ -3,8 +3,11 @@
 use axum::body::Bytes;
 use futures_core::Stream;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 
+use super::StreamNetStats;
+
 pub(in crate::gateway) struct RelayBodyStream {
     rx: tokio::sync::mpsc::Receiver<Result<Bytes, reqwest::Error>>,
 }
@@ -32,14 +35,23 @@ where
 {
     first: Option<Bytes>,
     rest: S,
+    stats: Arc<StreamNetStats>,
 }
 
 impl<S> FirstChunkStream<S>
 where
     S: Stream<Item = Result<Bytes, reqwest::Error>> + Unpin,
 {
-    pub(in crate::gateway) fn new(first: Option<Bytes>, rest: S) -> Self {
-        Self { first, rest }
+    /// Records wire-level chunk stats into `stats`; the probed first chunk counts on construction.
+    pub(in crate::gateway) fn new(
+        first: Option<Bytes>,
+        rest: S,
+        stats: Arc<StreamNetStats>,
+    ) -> Self {
+        if let Some(first) = first.as_ref() {
+            stats.record_chunk(first.len());
+        }
+        Self { first, rest, stats }
     }
 }
 
@@ -54,6 +66,10 @@ where
         if let Some(first) = this.first.take() {
             return Poll::Ready(Some(Ok(first)));
         }
-        Pin::new(&mut this.rest).poll_next(cx)
+        let next = Pin::new(&mut this.rest).poll_next(cx);
+        if let Poll::Ready(Some(Ok(chunk))) = &next {
+            this.stats.record_chunk(chunk.len());
+        }
+        next
     }
 }
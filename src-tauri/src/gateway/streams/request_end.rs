@@ -2,11 +2,28 @@
 
 use super::finalize::finalize_circuit_and_session;
 use super::StreamFinalizeCtx;
-use crate::gateway::events::emit_request_event;
+use crate::gateway::events::{emit_request_event, FailoverAttempt};
 use crate::gateway::proxy::{
-    spawn_enqueue_request_log_with_backpressure, status_override, RequestLogEnqueueArgs,
+    record_provider_quality, spawn_enqueue_request_log_with_backpressure, status_override,
+    ProviderQualityArgs, RequestLogEnqueueArgs,
 };
 use crate::gateway::response_fixer;
+use crate::gateway::util::now_unix_seconds;
+
+/// Attach the relayed body's network stats to the final attempt (the one that was streamed).
+fn attempts_with_stream_stats(ctx: &StreamFinalizeCtx) -> (Vec<FailoverAttempt>, String) {
+    let mut attempts = ctx.attempts.clone();
+    let Some(stats) = ctx.net_stats.snapshot() else {
+        return (attempts, ctx.attempts_json.clone());
+    };
+    let Some(last) = attempts.last_mut() else {
+        return (attempts, ctx.attempts_json.clone());
+    };
+    last.stream_stats = Some(stats);
+    let attempts_json =
+        serde_json::to_string(&attempts).unwrap_or_else(|_| ctx.attempts_json.clone());
+    (attempts, attempts_json)
+}
 
 pub(super) fn emit_request_event_and_spawn_request_log(
     ctx: &StreamFinalizeCtx,
@@ -22,11 +39,30 @@ pub(super) fn emit_request_event_and_spawn_request_log(
     let effective_excluded_from_stats =
         ctx.excluded_from_stats || status_override::is_client_abort(error_code);
 
+    if !ctx.excluded_from_stats {
+        record_provider_quality(ProviderQualityArgs {
+            app: &ctx.app,
+            circuit: ctx.circuit.as_ref(),
+            tracker: ctx.quality.as_ref(),
+            trace_id: &ctx.trace_id,
+            cli_key: &ctx.cli_key,
+            attempts: &ctx.attempts,
+            error_code,
+            error_category: effective_error_category,
+            now_unix: now_unix_seconds() as i64,
+        });
+    }
+
     let trace_id = ctx.trace_id.clone();
     let cli_key = ctx.cli_key.clone();
     let method = ctx.method.clone();
     let path = ctx.path.clone();
     let query = ctx.query.clone();
+    let (attempts, attempts_json) = attempts_with_stream_stats(ctx);
+    let response_bytes = attempts
+        .last()
+        .and_then(|attempt| attempt.stream_stats.as_ref())
+        .map(|stats| stats.bytes_received);
 
     emit_request_event(
         &ctx.app,
@@ -40,7 +76,7 @@ pub(super) fn emit_request_event_and_spawn_request_log(
         error_code,
         duration_ms,
         ttfb_ms,
-        ctx.attempts.clone(),
+        attempts,
         usage_metrics,
     );
 
@@ -61,12 +97,15 @@ pub(super) fn emit_request_event_and_spawn_request_log(
             error_code,
             duration_ms,
             ttfb_ms,
-            attempts_json: ctx.attempts_json.clone(),
+            attempts_json,
             requested_model,
             created_at_ms: ctx.created_at_ms,
             created_at: ctx.created_at,
             usage_metrics: None,
             usage,
+            request_bytes: Some(ctx.request_bytes),
+            response_bytes,
+            thinking_budget_tokens: ctx.thinking_budget_tokens,
         },
     );
 }
@@ -0,0 +1,174 @@
+//! Usage: Soft stall detection for relayed SSE streams, ahead of the hard idle timeout.
+//!
+//! A stream that stops producing data without closing is flagged (event + request log note) once
+//! upstream has been silent for `warn_after`. The hard idle timeout still decides when to give up;
+//! until then an optional SSE comment keeps the client connection from being dropped by proxies.
+
+use std::time::Duration;
+use tokio::time::Instant;
+
+use super::super::events::{emit_stream_stall_event, GatewayStreamStallEvent};
+use super::super::util::now_unix_seconds;
+use super::StreamFinalizeCtx;
+
+pub(super) const SSE_KEEPALIVE_COMMENT: &[u8] = b": keep-alive\n\n";
+
+/// `warn_after: None` disables the watchdog.
+#[derive(Debug, Clone, Copy, Default)]
+pub(in crate::gateway) struct StreamStallConfig {
+    pub(in crate::gateway) warn_after: Option<Duration>,
+    pub(in crate::gateway) keepalive: bool,
+}
+
+impl StreamStallConfig {
+    pub(in crate::gateway) fn new(warn_secs: u32, keepalive: bool) -> Self {
+        Self {
+            warn_after: (warn_secs > 0).then(|| Duration::from_secs(u64::from(warn_secs))),
+            keepalive,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum StallTick {
+    /// Upstream just crossed the soft threshold.
+    Stalled { idle: Duration },
+    /// Still silent; only reported when keep-alives are enabled.
+    StillStalled,
+}
+
+pub(super) struct StallWatchdog {
+    config: StreamStallConfig,
+    last_chunk_at: Instant,
+    stalled: bool,
+    next_check: Option<Instant>,
+}
+
+impl StallWatchdog {
+    pub(super) fn new(config: StreamStallConfig, now: Instant) -> Self {
+        Self {
+            config,
+            last_chunk_at: now,
+            stalled: false,
+            next_check: config.warn_after.map(|d| now + d),
+        }
+    }
+
+    pub(super) fn keepalive(&self) -> bool {
+        self.config.keepalive
+    }
+
+    pub(super) fn deadline(&self) -> Option<Instant> {
+        self.next_check
+    }
+
+    /// Upstream produced data; returns how long it was silent if this ends a flagged stall.
+    pub(super) fn on_chunk(&mut self, now: Instant) -> Option<Duration> {
+        let idle = now.saturating_duration_since(self.last_chunk_at);
+        self.last_chunk_at = now;
+        self.next_check = self.config.warn_after.map(|d| now + d);
+        std::mem::take(&mut self.stalled).then_some(idle)
+    }
+
+    /// The deadline passed without upstream data.
+    pub(super) fn on_deadline(&mut self, now: Instant) -> StallTick {
+        // Keep-alives repeat at the soft threshold; without them one flag per stall is enough.
+        self.next_check = match self.config.warn_after {
+            Some(d) if self.config.keepalive => Some(now + d),
+            _ => None,
+        };
+        if std::mem::replace(&mut self.stalled, true) {
+            StallTick::StillStalled
+        } else {
+            StallTick::Stalled {
+                idle: now.saturating_duration_since(self.last_chunk_at),
+            }
+        }
+    }
+}
+
+fn duration_ms(d: Duration) -> u64 {
+    d.as_millis().min(u64::MAX as u128) as u64
+}
+
+pub(super) fn report_stall(
+    ctx: &StreamFinalizeCtx,
+    state: &'static str,
+    idle: Duration,
+    idle_timeout: Option<Duration>,
+    keepalive: bool,
+) {
+    let idle_ms = duration_ms(idle);
+    if state == "stalled" {
+        tracing::warn!(
+            trace_id = %ctx.trace_id,
+            cli_key = %ctx.cli_key,
+            provider_id = ctx.provider_id,
+            idle_ms,
+            keepalive,
+            "upstream stream stalled"
+        );
+        if let Ok(mut guard) = ctx.special_settings.lock() {
+            guard.push(serde_json::json!({
+                "type": "stream_stall",
+                "idle_ms": idle_ms,
+                "keepalive": keepalive,
+                "ts": now_unix_seconds() as i64,
+            }));
+        }
+    }
+    emit_stream_stall_event(
+        &ctx.app,
+        GatewayStreamStallEvent {
+            trace_id: ctx.trace_id.clone(),
+            cli_key: ctx.cli_key.clone(),
+            provider_id: ctx.provider_id,
+            provider_name: ctx.provider_name.clone(),
+            state,
+            idle_ms,
+            idle_timeout_ms: idle_timeout.map(duration_ms),
+            keepalive,
+            ts: now_unix_seconds() as i64,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_once_per_stall_and_rearms_on_data() {
+        let start = Instant::now();
+        let s = |v: u64| start + Duration::from_secs(v);
+        let mut w = StallWatchdog::new(StreamStallConfig::new(10, false), start);
+        assert_eq!(w.deadline(), Some(s(10)));
+
+        assert_eq!(w.on_chunk(s(4)), None);
+        assert_eq!(w.deadline(), Some(s(14)));
+        assert_eq!(
+            w.on_deadline(s(14)),
+            StallTick::Stalled {
+                idle: Duration::from_secs(10)
+            }
+        );
+        assert_eq!(w.deadline(), None);
+
+        assert_eq!(w.on_chunk(s(30)), Some(Duration::from_secs(26)));
+        assert_eq!(w.deadline(), Some(s(40)));
+        assert!(StallWatchdog::new(StreamStallConfig::new(0, true), start)
+            .deadline()
+            .is_none());
+    }
+
+    #[test]
+    fn keepalive_mode_keeps_ticking_while_stalled() {
+        let start = Instant::now();
+        let s = |v: u64| start + Duration::from_secs(v);
+        let mut w = StallWatchdog::new(StreamStallConfig::new(5, true), start);
+        assert!(matches!(w.on_deadline(s(5)), StallTick::Stalled { .. }));
+        assert_eq!(w.deadline(), Some(s(10)));
+        assert_eq!(w.on_deadline(s(10)), StallTick::StillStalled);
+        assert_eq!(w.deadline(), Some(s(15)));
+    }
+}
@@ -0,0 +1,629 @@
+//! Usage: Opt-in recovery for Anthropic SSE streams that die mid-response.
+//!
+//! Events are forwarded only once complete, so a truncated tail never reaches the client. When the
+//! upstream errors or ends before the message is finished and only text blocks were streamed, the
+//! request is re-sent once with the received text as an assistant prefill; the continuation's
+//! `message_start` is dropped and its blocks are re-indexed onto the original stream.
+//! Usage totals then only cover the original `message_start` and the continuation's final delta.
+
+use axum::body::Bytes;
+use axum::http::{header, HeaderMap, Method};
+use futures_core::Stream;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use crate::shared::mutex_ext::MutexExt;
+
+/// Everything needed to re-send the original upstream request.
+pub(in crate::gateway) struct StreamStitchRetry {
+    pub(in crate::gateway) client: reqwest::Client,
+    pub(in crate::gateway) method: Method,
+    pub(in crate::gateway) url: reqwest::Url,
+    pub(in crate::gateway) headers: HeaderMap,
+    pub(in crate::gateway) body: Bytes,
+}
+
+type ContinuationFuture = Pin<Box<dyn Future<Output = Option<reqwest::Response>> + Send>>;
+type ContinuationStream = Pin<Box<dyn Stream<Item = Result<Bytes, reqwest::Error>> + Send>>;
+
+enum Phase {
+    Original,
+    Retrying(ContinuationFuture),
+    Continuation(ContinuationStream),
+    Done,
+}
+
+#[derive(Debug)]
+struct StitchTracker {
+    started: bool,
+    finished: bool,
+    text_only: bool,
+    text: String,
+    open_index: Option<u64>,
+    next_index: u64,
+}
+
+impl Default for StitchTracker {
+    fn default() -> Self {
+        Self {
+            started: false,
+            finished: false,
+            text_only: true,
+            text: String::new(),
+            open_index: None,
+            next_index: 0,
+        }
+    }
+}
+
+impl StitchTracker {
+    fn observe(&mut self, event: &Value) {
+        let index = event.get("index").and_then(Value::as_u64);
+        match event.get("type").and_then(Value::as_str) {
+            Some("message_start") => self.started = true,
+            Some("content_block_start") => {
+                let block_type = event
+                    .get("content_block")
+                    .and_then(|v| v.get("type"))
+                    .and_then(Value::as_str);
+                if block_type != Some("text") {
+                    self.text_only = false;
+                }
+                if let Some(index) = index {
+                    self.open_index = Some(index);
+                    self.next_index = self.next_index.max(index.saturating_add(1));
+                }
+            }
+            Some("content_block_delta") => {
+                let delta = event.get("delta");
+                match delta.and_then(|v| v.get("type")).and_then(Value::as_str) {
+                    Some("text_delta") => {
+                        if let Some(text) =
+                            delta.and_then(|v| v.get("text")).and_then(Value::as_str)
+                        {
+                            self.text.push_str(text);
+                        }
+                    }
+                    _ => self.text_only = false,
+                }
+            }
+            Some("content_block_stop") => self.open_index = None,
+            Some("message_delta") => {
+                let stop_reason = event
+                    .get("delta")
+                    .and_then(|v| v.get("stop_reason"))
+                    .is_some_and(|v| !v.is_null());
+                if stop_reason {
+                    self.finished = true;
+                }
+            }
+            Some("message_stop") | Some("error") => self.finished = true,
+            _ => {}
+        }
+    }
+
+    fn can_stitch(&self) -> bool {
+        self.started && !self.finished && self.text_only && !self.text.trim().is_empty()
+    }
+}
+
+/// Maps continuation events onto the original stream (`None` = drop the event).
+#[derive(Debug, Clone, Copy)]
+struct ContinuationRemap {
+    open_index: Option<u64>,
+    next_index: u64,
+}
+
+impl ContinuationRemap {
+    fn remap(&self, mut event: Value) -> Option<Value> {
+        let event_type = event.get("type").and_then(Value::as_str)?;
+        if event_type == "message_start" {
+            return None;
+        }
+        let Some(index) = event.get("index").and_then(Value::as_u64) else {
+            return Some(event);
+        };
+        // The prefilled text block is still open on the client side: keep writing into it.
+        if event_type == "content_block_start" && index == 0 && self.open_index.is_some() {
+            return None;
+        }
+        let mapped = match self.open_index {
+            Some(open) => open.saturating_add(index),
+            None => self.next_index.saturating_add(index),
+        };
+        event["index"] = Value::from(mapped);
+        Some(event)
+    }
+}
+
+/// Original request body plus the received text as the assistant prefill.
+fn continuation_body(original: &[u8], received_text: &str) -> Option<Bytes> {
+    let mut value: Value = serde_json::from_slice(original).ok()?;
+    // Prefill is not accepted once extended thinking is on.
+    let thinking = value
+        .get("thinking")
+        .and_then(|v| v.get("type"))
+        .and_then(Value::as_str);
+    if thinking.is_some_and(|t| t != "disabled") {
+        return None;
+    }
+
+    let messages = value.get_mut("messages")?.as_array_mut()?;
+    let last_is_assistant = messages
+        .last()
+        .and_then(|m| m.get("role"))
+        .and_then(Value::as_str)
+        == Some("assistant");
+
+    if last_is_assistant {
+        let last = messages.last_mut()?;
+        match last.get_mut("content")? {
+            Value::String(existing) => {
+                let prefill = format!("{existing}{received_text}");
+                *existing = prefill.trim_end().to_string();
+            }
+            Value::Array(blocks) => {
+                let prefix = received_text.trim_end();
+                if prefix.is_empty() {
+                    return None;
+                }
+                blocks.push(serde_json::json!({ "type": "text", "text": prefix }));
+            }
+            _ => return None,
+        }
+    } else {
+        let prefix = received_text.trim_end();
+        if prefix.is_empty() {
+            return None;
+        }
+        messages.push(serde_json::json!({
+            "role": "assistant",
+            "content": [{ "type": "text", "text": prefix }],
+        }));
+    }
+
+    serde_json::to_vec(&value).ok().map(Bytes::from)
+}
+
+fn find_event_end(buffer: &[u8]) -> Option<usize> {
+    let mut index = 0;
+    let mut line_start = 0;
+    while index < buffer.len() {
+        let byte = buffer[index];
+        if byte == b'\n' || byte == b'\r' {
+            let is_blank = index == line_start;
+            index += 1;
+            if byte == b'\r' && buffer.get(index) == Some(&b'\n') {
+                index += 1;
+            }
+            if is_blank {
+                return Some(index);
+            }
+            line_start = index;
+        } else {
+            index += 1;
+        }
+    }
+    None
+}
+
+fn parse_event_json(event: &[u8]) -> Option<Value> {
+    let text = std::str::from_utf8(event).ok()?;
+    let data = text
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if data.is_empty() {
+        return None;
+    }
+    serde_json::from_str(&data).ok()
+}
+
+fn render_event(event: &Value) -> Bytes {
+    let event_type = event
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or("message");
+    Bytes::from(format!("event: {event_type}\ndata: {event}\n\n"))
+}
+
+fn is_stitchable_response(resp: &reqwest::Response) -> bool {
+    let headers = resp.headers();
+    let event_stream = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.to_ascii_lowercase().contains("text/event-stream"));
+    let identity = headers
+        .get(header::CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .is_none_or(|v| v.trim().eq_ignore_ascii_case("identity"));
+    resp.status().is_success() && event_stream && identity
+}
+
+pub(in crate::gateway) struct StitchingStream<S>
+where
+    S: Stream<Item = Result<Bytes, reqwest::Error>> + Unpin,
+{
+    upstream: S,
+    retry: Option<StreamStitchRetry>,
+    special_settings: Arc<Mutex<Vec<Value>>>,
+    phase: Phase,
+    buffer: Vec<u8>,
+    queued: VecDeque<Bytes>,
+    tracker: StitchTracker,
+    remap: Option<ContinuationRemap>,
+    stitch_reason: &'static str,
+    pending_error: Option<reqwest::Error>,
+}
+
+impl<S> StitchingStream<S>
+where
+    S: Stream<Item = Result<Bytes, reqwest::Error>> + Unpin,
+{
+    /// Passes `upstream` through untouched when `retry` is `None`.
+    pub(in crate::gateway) fn new(
+        upstream: S,
+        retry: Option<StreamStitchRetry>,
+        special_settings: Arc<Mutex<Vec<Value>>>,
+    ) -> Self {
+        Self {
+            upstream,
+            retry,
+            special_settings,
+            phase: Phase::Original,
+            buffer: Vec::new(),
+            queued: VecDeque::new(),
+            tracker: StitchTracker::default(),
+            remap: None,
+            stitch_reason: "",
+            pending_error: None,
+        }
+    }
+
+    fn queue_original_events(&mut self) {
+        while let Some(end) = find_event_end(&self.buffer) {
+            let event = self.buffer.drain(..end).collect::<Vec<u8>>();
+            if let Some(value) = parse_event_json(&event) {
+                self.tracker.observe(&value);
+            }
+            self.queued.push_back(Bytes::from(event));
+        }
+    }
+
+    fn queue_continuation_events(&mut self, remap: ContinuationRemap) {
+        while let Some(end) = find_event_end(&self.buffer) {
+            let event = self.buffer.drain(..end).collect::<Vec<u8>>();
+            match parse_event_json(&event) {
+                Some(value) => {
+                    if let Some(mapped) = remap.remap(value) {
+                        self.queued.push_back(render_event(&mapped));
+                    }
+                }
+                // Comments and keep-alives carry no block index.
+                None => self.queued.push_back(Bytes::from(event)),
+            }
+        }
+    }
+
+    fn record(&self, hit: bool) {
+        self.special_settings
+            .lock_or_recover()
+            .push(serde_json::json!({
+                "type": "stitched",
+                "scope": "response",
+                "hit": hit,
+                "reason": self.stitch_reason,
+                "prefixChars": self.tracker.text.chars().count(),
+            }));
+    }
+
+    /// Upstream ended or failed: start the continuation request, or finish as-is.
+    fn on_original_end(&mut self, error: Option<reqwest::Error>) {
+        self.stitch_reason = if error.is_some() {
+            "upstream_error"
+        } else {
+            "upstream_eof"
+        };
+        self.pending_error = error;
+
+        let body = match self.retry.take() {
+            Some(retry) if self.tracker.can_stitch() => {
+                continuation_body(&retry.body, &self.tracker.text).map(|body| (retry, body))
+            }
+            _ => None,
+        };
+        let Some((retry, body)) = body else {
+            let tail = std::mem::take(&mut self.buffer);
+            if !tail.is_empty() {
+                self.queued.push_back(Bytes::from(tail));
+            }
+            self.phase = Phase::Done;
+            return;
+        };
+
+        // The unterminated tail is superseded by the continuation.
+        self.buffer.clear();
+        tracing::info!(
+            reason = self.stitch_reason,
+            prefix_chars = self.tracker.text.chars().count(),
+            "stream truncated; requesting continuation"
+        );
+        let send = retry
+            .client
+            .request(retry.method, retry.url)
+            .headers(retry.headers)
+            .body(body)
+            .send();
+        let reason = self.stitch_reason;
+        self.phase = Phase::Retrying(Box::pin(async move {
+            match send.await {
+                Ok(resp) if is_stitchable_response(&resp) => Some(resp),
+                Ok(resp) => {
+                    tracing::warn!(
+                        reason,
+                        status = resp.status().as_u16(),
+                        "stream continuation rejected"
+                    );
+                    None
+                }
+                Err(err) => {
+                    tracing::warn!(reason, "stream continuation failed: {}", err);
+                    None
+                }
+            }
+        }));
+        self.remap = Some(ContinuationRemap {
+            open_index: self.tracker.open_index,
+            next_index: self.tracker.next_index,
+        });
+    }
+}
+
+impl<S> Stream for StitchingStream<S>
+where
+    S: Stream<Item = Result<Bytes, reqwest::Error>> + Unpin,
+{
+    type Item = Result<Bytes, reqwest::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.as_mut().get_mut();
+
+        // Nothing to recover with: plain pass-through.
+        if this.retry.is_none() && matches!(this.phase, Phase::Original) {
+            return Pin::new(&mut this.upstream).poll_next(cx);
+        }
+
+        loop {
+            if let Some(chunk) = this.queued.pop_front() {
+                return Poll::Ready(Some(Ok(chunk)));
+            }
+
+            match &mut this.phase {
+                Phase::Original => match Pin::new(&mut this.upstream).poll_next(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(Some(Ok(chunk))) => {
+                        this.buffer.extend_from_slice(chunk.as_ref());
+                        this.queue_original_events();
+                    }
+                    Poll::Ready(Some(Err(err))) => this.on_original_end(Some(err)),
+                    Poll::Ready(None) => this.on_original_end(None),
+                },
+                Phase::Retrying(fut) => match fut.as_mut().poll(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(Some(resp)) => {
+                        this.record(true);
+                        this.pending_error = None;
+                        this.phase = Phase::Continuation(Box::pin(resp.bytes_stream()));
+                    }
+                    Poll::Ready(None) => {
+                        this.record(false);
+                        this.phase = Phase::Done;
+                    }
+                },
+                Phase::Continuation(stream) => match stream.as_mut().poll_next(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(Some(Ok(chunk))) => {
+                        this.buffer.extend_from_slice(chunk.as_ref());
+                        if let Some(remap) = this.remap {
+                            this.queue_continuation_events(remap);
+                        }
+                    }
+                    Poll::Ready(Some(Err(err))) => {
+                        this.phase = Phase::Done;
+                        return Poll::Ready(Some(Err(err)));
+                    }
+                    Poll::Ready(None) => {
+                        let tail = std::mem::take(&mut this.buffer);
+                        if !tail.is_empty() {
+                            this.queued.push_back(Bytes::from(tail));
+                        }
+                        this.phase = Phase::Done;
+                    }
+                },
+                Phase::Done => {
+                    return match this.pending_error.take() {
+                        Some(err) => Poll::Ready(Some(Err(err))),
+                        None => Poll::Ready(None),
+                    };
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tracker_for(events: &[Value]) -> StitchTracker {
+        let mut tracker = StitchTracker::default();
+        for event in events {
+            tracker.observe(event);
+        }
+        tracker
+    }
+
+    #[test]
+    fn tracker_accumulates_text_until_truncation() {
+        let tracker = tracker_for(&[
+            json!({"type": "message_start", "message": {}}),
+            json!({"type": "content_block_start", "index": 0, "content_block": {"type": "text", "text": ""}}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "Hello, "}}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "wor"}}),
+        ]);
+        assert!(tracker.can_stitch());
+        assert_eq!(tracker.text, "Hello, wor");
+        assert_eq!(tracker.open_index, Some(0));
+        assert_eq!(tracker.next_index, 1);
+    }
+
+    #[test]
+    fn tracker_refuses_finished_or_tool_streams() {
+        let finished = tracker_for(&[
+            json!({"type": "message_start", "message": {}}),
+            json!({"type": "content_block_start", "index": 0, "content_block": {"type": "text", "text": ""}}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "Hi"}}),
+            json!({"type": "content_block_stop", "index": 0}),
+            json!({"type": "message_delta", "delta": {"stop_reason": "end_turn"}}),
+        ]);
+        assert!(!finished.can_stitch());
+
+        let tool = tracker_for(&[
+            json!({"type": "message_start", "message": {}}),
+            json!({"type": "content_block_start", "index": 0, "content_block": {"type": "text", "text": ""}}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "Hi"}}),
+            json!({"type": "content_block_start", "index": 1, "content_block": {"type": "tool_use"}}),
+        ]);
+        assert!(!tool.can_stitch());
+    }
+
+    #[test]
+    fn remap_continues_open_block_and_shifts_new_blocks() {
+        let remap = ContinuationRemap {
+            open_index: Some(2),
+            next_index: 3,
+        };
+        assert!(remap
+            .remap(json!({"type": "message_start", "message": {}}))
+            .is_none());
+        assert!(remap
+            .remap(json!({"type": "content_block_start", "index": 0, "content_block": {"type": "text"}}))
+            .is_none());
+        assert_eq!(
+            remap
+                .remap(json!({"type": "content_block_delta", "index": 0, "delta": {}}))
+                .unwrap()["index"],
+            2
+        );
+        assert_eq!(
+            remap
+                .remap(json!({"type": "content_block_start", "index": 1, "content_block": {}}))
+                .unwrap()["index"],
+            3
+        );
+        assert_eq!(
+            remap.remap(json!({"type": "message_stop"})).unwrap(),
+            json!({"type": "message_stop"})
+        );
+
+        let closed = ContinuationRemap {
+            open_index: None,
+            next_index: 3,
+        };
+        assert_eq!(
+            closed
+                .remap(json!({"type": "content_block_start", "index": 0, "content_block": {}}))
+                .unwrap()["index"],
+            3
+        );
+    }
+
+    #[test]
+    fn continuation_body_appends_trimmed_prefill() {
+        let original = json!({
+            "model": "claude",
+            "stream": true,
+            "messages": [{"role": "user", "content": "hi"}],
+        });
+        let body = continuation_body(original.to_string().as_bytes(), "Hello, wor \n").unwrap();
+        let value: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            value["messages"][1],
+            json!({"role": "assistant", "content": [{"type": "text", "text": "Hello, wor"}]})
+        );
+
+        let prefilled = json!({
+            "messages": [
+                {"role": "user", "content": "hi"},
+                {"role": "assistant", "content": "{"},
+            ],
+        });
+        let body = continuation_body(prefilled.to_string().as_bytes(), "\"a\": 1").unwrap();
+        let value: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(value["messages"][1]["content"], "{\"a\": 1");
+
+        let thinking = json!({
+            "thinking": {"type": "enabled", "budget_tokens": 1024},
+            "messages": [{"role": "user", "content": "hi"}],
+        });
+        assert!(continuation_body(thinking.to_string().as_bytes(), "Hello").is_none());
+    }
+
+    #[test]
+    fn find_event_end_handles_lf_and_crlf() {
+        assert_eq!(find_event_end(b"data: {}\n\nrest"), Some(10));
+        assert_eq!(find_event_end(b"data: {}\r\n\r\n"), Some(12));
+        assert_eq!(find_event_end(b"data: {\"type\""), None);
+    }
+
+    struct VecBytesStream(VecDeque<Result<Bytes, reqwest::Error>>);
+
+    impl Stream for VecBytesStream {
+        type Item = Result<Bytes, reqwest::Error>;
+
+        fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            Poll::Ready(self.0.pop_front())
+        }
+    }
+
+    async fn collect_ok_bytes<S>(mut stream: S) -> Vec<u8>
+    where
+        S: Stream<Item = Result<Bytes, reqwest::Error>> + Unpin,
+    {
+        let mut out = Vec::new();
+        while let Some(item) = std::future::poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await
+        {
+            out.extend_from_slice(&item.expect("stream should not error in test"));
+        }
+        out
+    }
+
+    #[tokio::test]
+    async fn completed_stream_is_forwarded_without_stitching() {
+        let body = b"event: message_start\ndata: {\"type\":\"message_start\",\"message\":{}}\n\nevent: content_block_start\ndata: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}\n\nevent: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Hi\"}}\n\nevent: message_stop\ndata: {\"type\":\"message_stop\"}\n\n";
+        let (head, tail) = body.split_at(40);
+        let upstream = VecBytesStream(VecDeque::from(vec![
+            Ok(Bytes::copy_from_slice(head)),
+            Ok(Bytes::copy_from_slice(tail)),
+        ]));
+        let special_settings = Arc::new(Mutex::new(Vec::new()));
+        let retry = StreamStitchRetry {
+            client: reqwest::Client::new(),
+            method: Method::POST,
+            url: reqwest::Url::parse("http://127.0.0.1:9/v1/messages").unwrap(),
+            headers: HeaderMap::new(),
+            body: Bytes::from_static(b"{\"messages\":[]}"),
+        };
+        let stream = StitchingStream::new(upstream, Some(retry), special_settings.clone());
+
+        assert_eq!(collect_ok_bytes(stream).await, body.to_vec());
+        assert!(special_settings.lock().unwrap().is_empty());
+    }
+}
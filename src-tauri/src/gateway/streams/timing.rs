@@ -97,6 +97,7 @@ where
                 if this.first_byte_ms.is_none() {
                     this.first_byte_ms = Some(this.ctx.started.elapsed().as_millis());
                 }
+                this.ctx.net_stats.record_chunk(chunk.as_ref().len());
                 Poll::Ready(Some(Ok(chunk)))
             }
             Poll::Ready(Some(Err(err))) => {
@@ -5,13 +5,16 @@ use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 use super::super::events::FailoverAttempt;
+use super::super::proxy::{LiveAttemptGuard, ProviderQualityTracker};
+use super::StreamNetStats;
 
 pub(in crate::gateway) struct StreamFinalizeCtx {
-    pub(in crate::gateway) app: tauri::AppHandle,
+    pub(in crate::gateway) app: crate::AppHandle,
     pub(in crate::gateway) db: db::Db,
     pub(in crate::gateway) log_tx: tokio::sync::mpsc::Sender<request_logs::RequestLogInsert>,
     pub(in crate::gateway) circuit: Arc<circuit_breaker::CircuitBreaker>,
     pub(in crate::gateway) session: Arc<session_manager::SessionManager>,
+    pub(in crate::gateway) quality: Arc<Mutex<ProviderQualityTracker>>,
     pub(in crate::gateway) session_id: Option<String>,
     pub(in crate::gateway) sort_mode_id: Option<i64>,
     pub(in crate::gateway) trace_id: String,
@@ -34,4 +37,11 @@ pub(in crate::gateway) struct StreamFinalizeCtx {
     pub(in crate::gateway) provider_id: i64,
     pub(in crate::gateway) provider_name: String,
     pub(in crate::gateway) base_url: String,
+    pub(in crate::gateway) net_stats: Arc<StreamNetStats>,
+    /// Client request body size, for bandwidth accounting.
+    pub(in crate::gateway) request_bytes: u64,
+    /// Extended thinking budget sent to this provider.
+    pub(in crate::gateway) thinking_budget_tokens: Option<u64>,
+    /// Keeps the provider counted as in flight / streaming until the relay ends.
+    pub(in crate::gateway) live_attempt: Option<LiveAttemptGuard>,
 }
@@ -2,16 +2,20 @@
 
 use crate::usage;
 use axum::body::{Body, Bytes};
+use bytes::BytesMut;
 use futures_core::Stream;
 use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use std::time::Duration;
 
-use super::super::proxy::GatewayErrorCode;
+use super::super::proxy::{CallCostEstimate, GatewayErrorCode};
 use super::super::util::now_unix_seconds;
 use super::request_end::emit_request_event_and_spawn_request_log;
-use super::{RelayBodyStream, StreamFinalizeCtx};
+use super::stall_watchdog::{
+    report_stall, StallTick, StallWatchdog, StreamStallConfig, SSE_KEEPALIVE_COMMENT,
+};
+use super::{client_sse_body, RelayBodyStream, StreamFinalizeCtx};
 
 fn is_codex_responses_path(cli_key: &str, path: &str) -> bool {
     if cli_key != "codex" {
@@ -87,6 +91,8 @@ async fn next_item<S: Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
     NextFuture(stream).await
 }
 
+type CostTrailerFuture = Pin<Box<dyn Future<Output = Option<Bytes>> + Send>>;
+
 pub(in crate::gateway) struct UsageSseTeeStream<S, B>
 where
     S: Stream<Item = Result<B, reqwest::Error>> + Unpin,
@@ -99,6 +105,9 @@ where
     idle_timeout: Option<Duration>,
     idle_sleep: Option<Pin<Box<tokio::time::Sleep>>>,
     finalized: bool,
+    /// Usage + model of a stream that finalized without error (drives the trailing cost comment).
+    completed_usage: Option<(crate::usage::UsageMetrics, Option<String>)>,
+    cost_trailer: Option<CostTrailerFuture>,
 }
 
 impl<S, B> UsageSseTeeStream<S, B>
@@ -120,6 +129,8 @@ where
             idle_timeout,
             idle_sleep: idle_timeout.map(|d| Box::pin(tokio::time::sleep(d))),
             finalized: false,
+            completed_usage: None,
+            cost_trailer: None,
         }
     }
 
@@ -137,6 +148,12 @@ where
             .clone()
             .or_else(|| self.tracker.best_effort_model());
 
+        if error_code.is_none() && (200..300).contains(&self.ctx.status) {
+            if let Some(metrics) = usage_metrics.clone() {
+                self.completed_usage = Some((metrics, requested_model.clone()));
+            }
+        }
+
         emit_request_event_and_spawn_request_log(
             &self.ctx,
             error_code,
@@ -146,17 +163,43 @@ where
             usage,
         );
     }
+
+    fn cost_trailer(&mut self) -> Option<CostTrailerFuture> {
+        let (metrics, model) = self.completed_usage.take()?;
+        let app = self.ctx.app.clone();
+        let db = self.ctx.db.clone();
+        let cli_key = self.ctx.cli_key.clone();
+        let provider_id = self.ctx.provider_id;
+        Some(Box::pin(async move {
+            CallCostEstimate::compute(&app, &db, &cli_key, provider_id, model.as_deref(), metrics)
+                .await
+                .sse_comment()
+        }))
+    }
 }
 
 impl<S, B> Stream for UsageSseTeeStream<S, B>
 where
     S: Stream<Item = Result<B, reqwest::Error>> + Unpin,
-    B: AsRef<[u8]>,
+    B: AsRef<[u8]> + From<Bytes>,
 {
     type Item = Result<B, reqwest::Error>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let this = self.as_mut().get_mut();
+        if let Some(trailer) = this.cost_trailer.as_mut() {
+            return match trailer.as_mut().poll(cx) {
+                Poll::Pending => Poll::Pending,
+                Poll::Ready(comment) => {
+                    this.cost_trailer = None;
+                    Poll::Ready(comment.map(|bytes| Ok(B::from(bytes))))
+                }
+            };
+        }
+        if this.finalized {
+            return Poll::Ready(None);
+        }
+
         let next = Pin::new(&mut this.upstream).poll_next(cx);
 
         match next {
@@ -171,7 +214,15 @@ where
             }
             Poll::Ready(None) => {
                 this.finalize(this.ctx.error_code);
-                Poll::Ready(None)
+                // Successful streams end with an SSE comment carrying the call's cost/usage.
+                match this.cost_trailer() {
+                    Some(trailer) => {
+                        this.cost_trailer = Some(trailer);
+                        cx.waker().wake_by_ref();
+                        Poll::Pending
+                    }
+                    None => Poll::Ready(None),
+                }
             }
             Poll::Ready(Some(Ok(chunk))) => {
                 if this.first_byte_ms.is_none() {
@@ -234,11 +285,97 @@ where
 
 const SSE_RELAY_BUFFER_CAPACITY: usize = 32;
 
+/// Relay-side coalescing of small SSE chunks (`flush_after: None` = forward every chunk as is).
+#[derive(Debug, Clone, Copy, Default)]
+pub(in crate::gateway) struct SseCoalesceConfig {
+    pub(in crate::gateway) flush_after: Option<Duration>,
+    pub(in crate::gateway) flush_bytes: usize,
+}
+
+impl SseCoalesceConfig {
+    pub(in crate::gateway) fn new(flush_ms: u32, flush_bytes: u32) -> Self {
+        Self {
+            flush_after: (flush_ms > 0).then(|| Duration::from_millis(u64::from(flush_ms))),
+            flush_bytes: flush_bytes as usize,
+        }
+    }
+}
+
+fn ends_at_event_boundary(buf: &[u8]) -> bool {
+    buf.ends_with(b"\n\n") || buf.ends_with(b"\r\n\r\n")
+}
+
+/// Merges chunks that arrive faster than `flush_after`. Usage is tracked by the tee before this
+/// point, so coalescing only changes how bytes are framed downstream, never their content.
+struct SseCoalescer {
+    config: SseCoalesceConfig,
+    pending: BytesMut,
+    pending_since: Option<tokio::time::Instant>,
+    last_flush: Option<tokio::time::Instant>,
+}
+
+impl SseCoalescer {
+    fn new(config: SseCoalesceConfig) -> Self {
+        Self {
+            config,
+            pending: BytesMut::new(),
+            pending_since: None,
+            last_flush: None,
+        }
+    }
+
+    /// Returns the bytes to forward now, if any.
+    fn push(&mut self, chunk: Bytes, now: tokio::time::Instant) -> Option<Bytes> {
+        let Some(flush_after) = self.config.flush_after else {
+            return Some(chunk);
+        };
+        // Slow streams pass straight through: only a burst right after a flush gets buffered.
+        let recently_flushed = self
+            .last_flush
+            .is_some_and(|at| now.duration_since(at) < flush_after);
+        if self.pending.is_empty() && !recently_flushed {
+            self.last_flush = Some(now);
+            return Some(chunk);
+        }
+
+        if self.pending.is_empty() {
+            self.pending_since = Some(now);
+        }
+        self.pending.extend_from_slice(&chunk);
+        let held_long_enough = self
+            .pending_since
+            .is_some_and(|since| now.duration_since(since) >= flush_after);
+        if self.pending.len() >= self.config.flush_bytes
+            || (held_long_enough && ends_at_event_boundary(&self.pending))
+        {
+            return self.flush(now);
+        }
+        None
+    }
+
+    /// When buffered bytes must go out even if no further chunk arrives.
+    fn deadline(&self) -> Option<tokio::time::Instant> {
+        Some(self.pending_since? + self.config.flush_after?)
+    }
+
+    fn flush(&mut self, now: tokio::time::Instant) -> Option<Bytes> {
+        if self.pending.is_empty() {
+            return None;
+        }
+        self.pending_since = None;
+        self.last_flush = Some(now);
+        Some(self.pending.split().freeze())
+    }
+}
+
 pub(in crate::gateway) fn spawn_usage_sse_relay_body<S>(
     upstream: S,
     ctx: StreamFinalizeCtx,
     idle_timeout: Option<Duration>,
     initial_first_byte_ms: Option<u128>,
+    coalesce: SseCoalesceConfig,
+    stall: StreamStallConfig,
+    error_event: Option<Bytes>,
 ) -> Body
 where
     S: Stream<Item = Result<Bytes, reqwest::Error>> + Unpin + Send + 'static,
@@ -247,6 +384,8 @@ where
         tokio::sync::mpsc::channel::<Result<Bytes, reqwest::Error>>(SSE_RELAY_BUFFER_CAPACITY);
 
     let mut tee = UsageSseTeeStream::new(upstream, ctx, idle_timeout, initial_first_byte_ms);
+    let mut coalescer = SseCoalescer::new(coalesce);
+    let mut watchdog = StallWatchdog::new(stall, tokio::time::Instant::now());
 
     tokio::spawn(async move {
         let mut forwarded_chunks: i64 = 0;
@@ -256,6 +395,8 @@ where
         let mut client_abort_detected_by: Option<&'static str> = None;
         let mut downstream_closed = false;
         let mut upstream_ended_normally = false;
+        // Keep-alive comments may only go out between events, never inside a half-sent one.
+        let mut at_event_boundary = true;
 
         let is_codex_responses = is_codex_responses_path(&tee.ctx.cli_key, &tee.ctx.path);
         let mut drain_deadline: Option<tokio::time::Instant> = None;
@@ -322,7 +463,9 @@ where
                 continue;
             }
 
-            tokio::select! {
+            let flush_deadline = coalescer.deadline();
+            let stall_deadline = watchdog.deadline();
+            let outgoing = tokio::select! {
                 // 如果客户端提前断开，但上游短时间没有新 chunk，就会卡在 next_item().await。
                 // 这里通过监听 rx 端被 drop 来更早感知断开，避免误记 GW_STREAM_ABORTED。
                 _ = tx.closed() => {
@@ -334,6 +477,34 @@ where
                     }
                     break;
                 }
+                _ = async {
+                    if let Some(deadline) = flush_deadline {
+                        tokio::time::sleep_until(deadline).await;
+                    }
+                }, if flush_deadline.is_some() => {
+                    coalescer.flush(tokio::time::Instant::now())
+                }
+                _ = async {
+                    if let Some(deadline) = stall_deadline {
+                        tokio::time::sleep_until(deadline).await;
+                    }
+                }, if stall_deadline.is_some() => {
+                    let now = tokio::time::Instant::now();
+                    if let StallTick::Stalled { idle } = watchdog.on_deadline(now) {
+                        report_stall(&tee.ctx, "stalled", idle, idle_timeout, watchdog.keepalive());
+                    }
+                    let pending = coalescer.flush(now);
+                    let boundary = pending
+                        .as_deref()
+                        .map_or(at_event_boundary, ends_at_event_boundary);
+                    if watchdog.keepalive() && boundary {
+                        let mut out = pending.map(BytesMut::from).unwrap_or_default();
+                        out.extend_from_slice(SSE_KEEPALIVE_COMMENT);
+                        Some(out.freeze())
+                    } else {
+                        pending
+                    }
+                }
                 item = next_item(&mut tee) => {
                     let Some(item) = item else {
                         upstream_ended_normally = true;
@@ -342,28 +513,55 @@ where
 
                     match item {
                         Ok(chunk) => {
-                            let chunk_len = chunk.len().min(i64::MAX as usize) as i64;
-
-                            if tx.send(Ok(chunk)).await.is_err() {
-                                client_abort_detected_by = Some("send_failed");
-                                downstream_closed = true;
-                                if is_codex_responses {
-                                    drain_deadline = Some(tokio::time::Instant::now() + drain_grace);
-                                    continue;
-                                }
-                                break;
+                            let now = tokio::time::Instant::now();
+                            if let Some(idle) = watchdog.on_chunk(now) {
+                                report_stall(
+                                    &tee.ctx,
+                                    "resumed",
+                                    idle,
+                                    idle_timeout,
+                                    watchdog.keepalive(),
+                                );
                             }
-
-                            forwarded_chunks = forwarded_chunks.saturating_add(1);
-                            forwarded_bytes = forwarded_bytes.saturating_add(chunk_len);
+                            coalescer.push(chunk, now)
                         }
                         Err(err) => {
+                            if let Some(rest) = coalescer.flush(tokio::time::Instant::now()) {
+                                let _ = tx.send(Ok(rest)).await;
+                            }
                             // 尽力把流错误透传给客户端
                             let _ = tx.send(Err(err)).await;
                             break;
                         }
                     }
                 }
+            };
+
+            let Some(chunk) = outgoing else {
+                continue;
+            };
+            let chunk_len = chunk.len().min(i64::MAX as usize) as i64;
+            at_event_boundary = ends_at_event_boundary(&chunk);
+            if tx.send(Ok(chunk)).await.is_err() {
+                client_abort_detected_by = Some("send_failed");
+                downstream_closed = true;
+                if is_codex_responses {
+                    drain_deadline = Some(tokio::time::Instant::now() + drain_grace);
+                    continue;
+                }
+                break;
+            }
+            forwarded_chunks = forwarded_chunks.saturating_add(1);
+            forwarded_bytes = forwarded_bytes.saturating_add(chunk_len);
+        }
+
+        if !downstream_closed {
+            if let Some(rest) = coalescer.flush(tokio::time::Instant::now()) {
+                let rest_len = rest.len().min(i64::MAX as usize) as i64;
+                if tx.send(Ok(rest)).await.is_ok() {
+                    forwarded_chunks = forwarded_chunks.saturating_add(1);
+                    forwarded_bytes = forwarded_bytes.saturating_add(rest_len);
+                }
             }
         }
 
@@ -427,7 +625,7 @@ where
         }
     });
 
-    Body::from_stream(RelayBodyStream::new(rx))
+    client_sse_body(RelayBodyStream::new(rx), error_event)
 }
 
 pub(in crate::gateway) struct UsageBodyBufferTeeStream<S, B>
@@ -538,6 +736,7 @@ where
                 if this.first_byte_ms.is_none() {
                     this.first_byte_ms = Some(this.ctx.started.elapsed().as_millis());
                 }
+                this.ctx.net_stats.record_chunk(chunk.as_ref().len());
                 if !this.truncated {
                     let bytes = chunk.as_ref();
                     if this.buffer.len().saturating_add(bytes.len()) <= this.max_bytes {
@@ -590,8 +789,48 @@ where
 mod tests {
     use super::{
         is_codex_body_buffer_drop_successish, is_codex_client_abort_successish,
-        is_codex_drop_successish, is_codex_responses_path,
+        is_codex_drop_successish, is_codex_responses_path, SseCoalesceConfig, SseCoalescer,
     };
+    use axum::body::Bytes;
+    use std::time::Duration;
+
+    #[test]
+    fn sse_coalescer_batches_bursts_and_passes_slow_chunks_through() {
+        let start = tokio::time::Instant::now();
+        let ms = |v: u64| start + Duration::from_millis(v);
+        let mut c = SseCoalescer::new(SseCoalesceConfig::new(20, 1024));
+
+        let first = c.push(Bytes::from_static(b"data: a\n\n"), ms(0));
+        assert_eq!(first.as_deref(), Some(&b"data: a\n\n"[..]));
+        assert!(c.push(Bytes::from_static(b"data: b\n\n"), ms(2)).is_none());
+        assert!(c.push(Bytes::from_static(b"data: "), ms(10)).is_none());
+        assert_eq!(c.deadline(), Some(ms(22)));
+        // Held past the window but mid-event: wait for the boundary.
+        assert!(c.push(Bytes::from_static(b"c"), ms(23)).is_none());
+        let merged = c.push(Bytes::from_static(b"\n\n"), ms(24));
+        assert_eq!(merged.as_deref(), Some(&b"data: b\n\ndata: c\n\n"[..]));
+        assert_eq!(c.deadline(), None);
+
+        let slow = c.push(Bytes::from_static(b"data: d\n\n"), ms(100));
+        assert_eq!(slow.as_deref(), Some(&b"data: d\n\n"[..]));
+    }
+
+    #[test]
+    fn sse_coalescer_flushes_on_size_and_can_be_disabled() {
+        let now = tokio::time::Instant::now();
+        let mut c = SseCoalescer::new(SseCoalesceConfig::new(50, 8));
+        assert!(c.push(Bytes::from_static(b"x"), now).is_some());
+        assert!(c.push(Bytes::from_static(b"12345"), now).is_none());
+        assert_eq!(
+            c.push(Bytes::from_static(b"678"), now).as_deref(),
+            Some(&b"12345678"[..])
+        );
+
+        let mut off = SseCoalescer::new(SseCoalesceConfig::new(0, 8));
+        assert!(off.push(Bytes::from_static(b"a"), now).is_some());
+        assert!(off.push(Bytes::from_static(b"b"), now).is_some());
+        assert_eq!(off.deadline(), None);
+    }
 
     #[test]
     fn codex_responses_path_accepts_v1_and_backend_style_paths() {
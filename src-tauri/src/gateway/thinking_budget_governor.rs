@@ -0,0 +1,112 @@
+//! Usage: Per-provider cap on Claude extended thinking budgets (`providers.thinking_budget_cap`).
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ThinkingBudgetAction {
+    Clamp,
+    Strip,
+}
+
+impl ThinkingBudgetAction {
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            Self::Clamp => "clamp",
+            Self::Strip => "strip",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct ThinkingBudgetGovernorResult {
+    pub(super) action: ThinkingBudgetAction,
+    pub(super) requested_budget_tokens: u64,
+    /// `None` once thinking was stripped.
+    pub(super) effective_budget_tokens: Option<u64>,
+}
+
+/// `thinking.budget_tokens` of an enabled thinking block (adaptive thinking has no budget).
+pub(super) fn requested_budget_tokens(message: &serde_json::Value) -> Option<u64> {
+    let thinking = message.get("thinking")?.as_object()?;
+    if thinking.get("type").and_then(|v| v.as_str()) != Some("enabled") {
+        return None;
+    }
+    thinking.get("budget_tokens").and_then(|v| v.as_u64())
+}
+
+/// Applies `cap` to the message: `0` removes top-level `thinking`, otherwise larger budgets are
+/// lowered to `cap`. Returns `None` when the message was left untouched.
+pub(super) fn govern_anthropic_request_message(
+    message: &mut serde_json::Value,
+    cap: u32,
+) -> Option<ThinkingBudgetGovernorResult> {
+    let requested_budget_tokens = requested_budget_tokens(message)?;
+    let message_obj = message.as_object_mut()?;
+
+    if cap == 0 {
+        message_obj.remove("thinking");
+        return Some(ThinkingBudgetGovernorResult {
+            action: ThinkingBudgetAction::Strip,
+            requested_budget_tokens,
+            effective_budget_tokens: None,
+        });
+    }
+
+    let cap = u64::from(cap);
+    if requested_budget_tokens <= cap {
+        return None;
+    }
+    message_obj
+        .get_mut("thinking")
+        .and_then(|v| v.as_object_mut())?
+        .insert(
+            "budget_tokens".to_string(),
+            serde_json::Value::Number(serde_json::Number::from(cap)),
+        );
+    Some(ThinkingBudgetGovernorResult {
+        action: ThinkingBudgetAction::Clamp,
+        requested_budget_tokens,
+        effective_budget_tokens: Some(cap),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn message(budget: u64) -> serde_json::Value {
+        json!({
+            "model": "claude-test",
+            "max_tokens": 64000,
+            "thinking": { "type": "enabled", "budget_tokens": budget },
+            "messages": [ { "role": "user", "content": "hi" } ]
+        })
+    }
+
+    #[test]
+    fn clamps_budgets_above_cap() {
+        let mut msg = message(32_000);
+        let result = govern_anthropic_request_message(&mut msg, 4096).expect("clamped");
+        assert_eq!(result.action, ThinkingBudgetAction::Clamp);
+        assert_eq!(result.requested_budget_tokens, 32_000);
+        assert_eq!(result.effective_budget_tokens, Some(4096));
+        assert_eq!(requested_budget_tokens(&msg), Some(4096));
+        assert_eq!(msg["max_tokens"], 64000);
+
+        let mut small = message(2048);
+        assert!(govern_anthropic_request_message(&mut small, 4096).is_none());
+        assert_eq!(requested_budget_tokens(&small), Some(2048));
+    }
+
+    #[test]
+    fn zero_cap_strips_thinking_and_ignores_adaptive() {
+        let mut msg = message(8000);
+        let result = govern_anthropic_request_message(&mut msg, 0).expect("stripped");
+        assert_eq!(result.action, ThinkingBudgetAction::Strip);
+        assert_eq!(result.effective_budget_tokens, None);
+        assert!(msg.get("thinking").is_none());
+
+        let mut adaptive = json!({ "thinking": { "type": "adaptive" } });
+        assert!(govern_anthropic_request_message(&mut adaptive, 0).is_none());
+        assert!(adaptive.get("thinking").is_some());
+    }
+}
@@ -0,0 +1,191 @@
+//! Usage: Offline approximation of Claude `count_tokens` (used when no provider can answer).
+//!
+//! Mimics a BPE tokenizer's pre-tokenization: words (with their leading space), digit groups,
+//! punctuation runs and whitespace runs each become one piece, long pieces split into several
+//! tokens, and non-ASCII scripts count roughly one token per character. Expect an error of
+//! ±15% on typical prompts; the result only has to keep CLI context meters moving.
+
+use serde_json::Value;
+
+/// Letters per token inside a long word (common words are a single token).
+const WORD_CHARS_PER_TOKEN: usize = 6;
+/// Digits are grouped in threes.
+const DIGITS_PER_TOKEN: usize = 3;
+const PUNCT_CHARS_PER_TOKEN: usize = 2;
+const WHITESPACE_CHARS_PER_TOKEN: usize = 8;
+/// Role markers and block framing around each message.
+const MESSAGE_OVERHEAD_TOKENS: u64 = 4;
+/// Images and documents: a typical screenshot; their real size is unknown without decoding.
+const MEDIA_BLOCK_TOKENS: u64 = 1_600;
+/// Tool-use preamble the API adds once tools are present.
+const TOOLS_OVERHEAD_TOKENS: u64 = 300;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Word,
+    Digit,
+    Space,
+    Punct,
+    Wide,
+}
+
+fn classify(c: char) -> CharClass {
+    if c.is_ascii_alphabetic() || c == '_' {
+        CharClass::Word
+    } else if c.is_ascii_digit() {
+        CharClass::Digit
+    } else if c.is_whitespace() {
+        CharClass::Space
+    } else if c.is_ascii() {
+        CharClass::Punct
+    } else if c.is_alphabetic() && (c as u32) < 0x2E80 {
+        // Latin extensions, Greek, Cyrillic: still word-like, just less compressed.
+        CharClass::Word
+    } else {
+        CharClass::Wide
+    }
+}
+
+fn piece_tokens(class: CharClass, len: usize) -> u64 {
+    let per_token = match class {
+        CharClass::Word => WORD_CHARS_PER_TOKEN,
+        CharClass::Digit => DIGITS_PER_TOKEN,
+        CharClass::Punct => PUNCT_CHARS_PER_TOKEN,
+        CharClass::Space => WHITESPACE_CHARS_PER_TOKEN,
+        CharClass::Wide => 1,
+    };
+    len.div_ceil(per_token) as u64
+}
+
+/// Approximate token count of plain text.
+pub(super) fn estimate_text_tokens(text: &str) -> u64 {
+    let mut total = 0u64;
+    let mut current: Option<(CharClass, usize)> = None;
+
+    for c in text.chars() {
+        let class = classify(c);
+        match current {
+            Some((cur, len)) if cur == class && class != CharClass::Wide => {
+                current = Some((cur, len + 1));
+            }
+            _ => {
+                if let Some((cur, len)) = current.take() {
+                    // A single space merges into the following word, as in BPE vocabularies.
+                    if !(cur == CharClass::Space && len == 1 && class == CharClass::Word) {
+                        total += piece_tokens(cur, len);
+                    }
+                }
+                current = Some((class, 1));
+            }
+        }
+    }
+    if let Some((cur, len)) = current {
+        total += piece_tokens(cur, len);
+    }
+    total
+}
+
+fn is_media_block(map: &serde_json::Map<String, Value>) -> bool {
+    matches!(
+        map.get("type").and_then(Value::as_str),
+        Some("image" | "document")
+    )
+}
+
+/// Text-bearing values of a content tree; structural keys (`type`, ids, cache hints) are skipped.
+fn estimate_content_tokens(value: &Value) -> u64 {
+    match value {
+        Value::String(s) => estimate_text_tokens(s),
+        Value::Array(items) => items.iter().map(estimate_content_tokens).sum(),
+        Value::Object(map) if is_media_block(map) => MEDIA_BLOCK_TOKENS,
+        Value::Object(map) => map
+            .iter()
+            .filter(|(key, _)| {
+                !matches!(
+                    key.as_str(),
+                    "type" | "id" | "tool_use_id" | "cache_control" | "signature"
+                )
+            })
+            .map(|(key, value)| match (key.as_str(), value) {
+                // Tool inputs are sent as JSON text.
+                ("input", Value::Object(_)) => estimate_text_tokens(&value.to_string()),
+                _ => estimate_content_tokens(value),
+            })
+            .sum(),
+        Value::Number(n) => estimate_text_tokens(&n.to_string()),
+        Value::Bool(_) | Value::Null => 0,
+    }
+}
+
+/// Approximate `input_tokens` for an Anthropic `count_tokens` request body.
+pub(super) fn estimate_count_tokens_request(body: &Value) -> u64 {
+    let mut total = 0u64;
+    if let Some(system) = body.get("system") {
+        total += estimate_content_tokens(system);
+    }
+    if let Some(messages) = body.get("messages").and_then(Value::as_array) {
+        for message in messages {
+            total += MESSAGE_OVERHEAD_TOKENS;
+            if let Some(content) = message.get("content") {
+                total += estimate_content_tokens(content);
+            }
+        }
+    }
+    if let Some(tools) = body.get("tools").and_then(Value::as_array) {
+        if !tools.is_empty() {
+            total += TOOLS_OVERHEAD_TOKENS;
+        }
+        for tool in tools {
+            // Schemas are tokenized as JSON, keys included.
+            total += estimate_text_tokens(&tool.to_string());
+        }
+    }
+    total.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn text_estimate_tracks_bpe_like_piece_counts() {
+        assert_eq!(estimate_text_tokens(""), 0);
+        assert_eq!(estimate_text_tokens("hello world"), 2);
+        assert_eq!(estimate_text_tokens("internationalization"), 4);
+        assert_eq!(estimate_text_tokens("1234567"), 3);
+        assert_eq!(estimate_text_tokens("fn main() {}"), 5);
+        assert_eq!(estimate_text_tokens("你好世界"), 4);
+
+        let prose = "The quick brown fox jumps over the lazy dog. ".repeat(20);
+        let estimate = estimate_text_tokens(&prose);
+        // ~10 tokens per sentence with a real tokenizer.
+        assert!((180..=240).contains(&estimate), "estimate={estimate}");
+    }
+
+    #[test]
+    fn request_estimate_covers_system_messages_tools_and_media() {
+        let body = json!({
+            "model": "claude-sonnet-4-5",
+            "system": [{"type": "text", "text": "You are terse.", "cache_control": {"type": "ephemeral"}}],
+            "messages": [
+                {"role": "user", "content": "hello world"},
+                {"role": "user", "content": [
+                    {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "AAAA"}},
+                    {"type": "tool_result", "tool_use_id": "toolu_1", "content": "ok"}
+                ]}
+            ],
+            "tools": [{"name": "read", "description": "Read a file", "input_schema": {"type": "object"}}]
+        });
+
+        let without_tools = {
+            let mut b = body.clone();
+            b.as_object_mut().unwrap().remove("tools");
+            estimate_count_tokens_request(&b)
+        };
+        assert!(without_tools > MEDIA_BLOCK_TOKENS + 2 * MESSAGE_OVERHEAD_TOKENS);
+        assert!(without_tools < MEDIA_BLOCK_TOKENS + 30);
+        assert!(estimate_count_tokens_request(&body) > without_tools + TOOLS_OVERHEAD_TOKENS);
+        assert_eq!(estimate_count_tokens_request(&json!({})), 1);
+    }
+}
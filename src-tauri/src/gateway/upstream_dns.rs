@@ -0,0 +1,430 @@
+//! Usage: DNS resolver installed on the shared gateway upstream client.
+//!
+//! reqwest only hands a resolver the host name, so before each attempt the failover loop binds the
+//! target host to the provider's DNS options (the last bind wins when providers share a host).
+//! Hosts without options go through the system resolver unchanged. Options only affect new
+//! connections; pooled connections keep the addresses they were opened with.
+
+use crate::provider_dns_options::{DnsIpStrategy, ProviderDnsOptions};
+use crate::shared::mutex_ext::MutexExt;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::collections::HashMap;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const DOH_TIMEOUT: Duration = Duration::from_secs(5);
+const DOH_MIN_TTL_SECS: u64 = 30;
+const DOH_MAX_TTL_SECS: u64 = 600;
+const DOH_RECORD_A: u64 = 1;
+const DOH_RECORD_AAAA: u64 = 28;
+/// Upper bound for the connect race; without a winner the resolved order is kept.
+const RACE_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy)]
+struct HostBinding {
+    provider_id: i64,
+    port: u16,
+}
+
+/// Outcome of one resolution under provider DNS options, attached to the attempt that caused it.
+#[derive(Debug, Clone)]
+pub(super) struct DnsResolution {
+    provider_id: i64,
+    host: String,
+    ip_strategy: DnsIpStrategy,
+    resolver: &'static str,
+    addresses: Vec<IpAddr>,
+    raced_winner: Option<IpAddr>,
+    duration_ms: u64,
+    error: Option<String>,
+    resolved_at: Instant,
+}
+
+impl DnsResolution {
+    pub(super) fn special_setting(&self, retry_index: u32) -> serde_json::Value {
+        serde_json::json!({
+            "type": "upstream_dns",
+            "scope": "attempt",
+            "providerId": self.provider_id,
+            "retryIndex": retry_index,
+            "host": self.host,
+            "ipStrategy": self.ip_strategy.as_str(),
+            "resolver": self.resolver,
+            "addresses": self.addresses.iter().map(IpAddr::to_string).collect::<Vec<_>>(),
+            "racedWinner": self.raced_winner.map(|ip| ip.to_string()),
+            "durationMs": self.duration_ms,
+            "error": self.error,
+        })
+    }
+}
+
+#[derive(Default)]
+struct ResolverState {
+    options: HashMap<i64, ProviderDnsOptions>,
+    hosts: HashMap<String, HostBinding>,
+    doh_cache: HashMap<(String, String, bool), (Vec<IpAddr>, Instant)>,
+    resolutions: HashMap<String, DnsResolution>,
+}
+
+struct ResolverInner {
+    state: Mutex<ResolverState>,
+    doh_client: reqwest::Client,
+}
+
+#[derive(Clone)]
+pub(super) struct UpstreamDnsResolver {
+    inner: Arc<ResolverInner>,
+}
+
+impl UpstreamDnsResolver {
+    pub(super) fn new(options: HashMap<i64, ProviderDnsOptions>) -> Self {
+        // The DoH client itself resolves through the system resolver.
+        let doh_client = reqwest::Client::builder()
+            .user_agent(format!(
+                "aio-coding-hub-gateway/{}",
+                env!("CARGO_PKG_VERSION")
+            ))
+            .timeout(DOH_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            inner: Arc::new(ResolverInner {
+                state: Mutex::new(ResolverState {
+                    options,
+                    ..Default::default()
+                }),
+                doh_client,
+            }),
+        }
+    }
+
+    pub(super) fn set_provider_options(&self, options: ProviderDnsOptions) {
+        let mut state = self.inner.state.lock_or_recover();
+        if options.is_default() {
+            state.options.remove(&options.provider_id);
+        } else {
+            state.options.insert(options.provider_id, options);
+        }
+    }
+
+    /// Route `url`'s host through the provider's options; returns the host when options apply.
+    pub(super) fn bind(&self, provider_id: i64, url: &reqwest::Url) -> Option<String> {
+        let host = url.host_str()?.to_ascii_lowercase();
+        let mut state = self.inner.state.lock_or_recover();
+        if !state.options.contains_key(&provider_id) {
+            state.hosts.remove(&host);
+            return None;
+        }
+        let port = url.port_or_known_default().unwrap_or(443);
+        state
+            .hosts
+            .insert(host.clone(), HostBinding { provider_id, port });
+        Some(host)
+    }
+
+    /// The resolution of `host` performed since `since`, if the attempt opened a new connection.
+    pub(super) fn take_resolution(&self, host: &str, since: Instant) -> Option<DnsResolution> {
+        let mut state = self.inner.state.lock_or_recover();
+        let fresh = state
+            .resolutions
+            .get(host)
+            .is_some_and(|r| r.resolved_at >= since);
+        if fresh {
+            state.resolutions.remove(host)
+        } else {
+            None
+        }
+    }
+
+    async fn resolve_host(&self, host: String) -> io::Result<Vec<SocketAddr>> {
+        let bound = {
+            let state = self.inner.state.lock_or_recover();
+            state.hosts.get(&host).and_then(|binding| {
+                state
+                    .options
+                    .get(&binding.provider_id)
+                    .map(|options| (*binding, options.clone()))
+            })
+        };
+        let Some((binding, options)) = bound else {
+            return system_lookup(&host).await;
+        };
+
+        let started = Instant::now();
+        let ipv4_only = options.ip_strategy == DnsIpStrategy::Ipv4Only;
+        let (resolver, lookup) = match options.doh_url.as_deref() {
+            Some(doh_url) => ("doh", self.doh_lookup(doh_url, &host, ipv4_only).await),
+            None => (
+                "system",
+                system_lookup(&host)
+                    .await
+                    .map(|addrs| addrs.into_iter().map(|addr| addr.ip()).collect()),
+            ),
+        };
+        let lookup = lookup.and_then(|ips: Vec<IpAddr>| {
+            let ips: Vec<IpAddr> = ips
+                .into_iter()
+                .filter(|ip| !ipv4_only || ip.is_ipv4())
+                .collect();
+            if ips.is_empty() {
+                Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("no usable addresses for {host}"),
+                ))
+            } else {
+                Ok(ips)
+            }
+        });
+
+        let (addresses, raced_winner, error) = match lookup {
+            Ok(ips) if options.race_connections && ips.len() > 1 => {
+                let (ordered, winner) = race_connect(ips, binding.port).await;
+                (ordered, winner, None)
+            }
+            Ok(ips) => (ips, None, None),
+            Err(err) => (Vec::new(), None, Some(err.to_string())),
+        };
+
+        let resolution = DnsResolution {
+            provider_id: binding.provider_id,
+            host: host.clone(),
+            ip_strategy: options.ip_strategy,
+            resolver,
+            addresses: addresses.clone(),
+            raced_winner,
+            duration_ms: started.elapsed().as_millis().min(u64::MAX as u128) as u64,
+            error: error.clone(),
+            resolved_at: started,
+        };
+        tracing::debug!(
+            provider_id = binding.provider_id,
+            host = %host,
+            resolver,
+            addresses = addresses.len(),
+            raced_winner = ?raced_winner,
+            duration_ms = resolution.duration_ms,
+            "upstream dns resolved"
+        );
+        self.inner
+            .state
+            .lock_or_recover()
+            .resolutions
+            .insert(host, resolution);
+
+        match error {
+            Some(err) => Err(io::Error::other(err)),
+            None => Ok(addresses
+                .into_iter()
+                .map(|ip| SocketAddr::new(ip, 0))
+                .collect()),
+        }
+    }
+
+    /// IPv4 answers are listed first so the connector's happy-eyeballs fallback covers IPv6.
+    async fn doh_lookup(
+        &self,
+        doh_url: &str,
+        host: &str,
+        ipv4_only: bool,
+    ) -> io::Result<Vec<IpAddr>> {
+        let cache_key = (doh_url.to_string(), host.to_string(), ipv4_only);
+        let cached = self
+            .inner
+            .state
+            .lock_or_recover()
+            .doh_cache
+            .get(&cache_key)
+            .filter(|(_, expires_at)| Instant::now() < *expires_at)
+            .map(|(ips, _)| ips.clone());
+        if let Some(ips) = cached {
+            return Ok(ips);
+        }
+
+        let v4 = self.doh_query(doh_url, host, "A", DOH_RECORD_A);
+        let (v4, v6) = if ipv4_only {
+            (v4.await, Ok((Vec::new(), None)))
+        } else {
+            tokio::join!(v4, self.doh_query(doh_url, host, "AAAA", DOH_RECORD_AAAA))
+        };
+        let (mut ips, v4_ttl) = v4?;
+        // A broken AAAA answer must not sink a usable A answer.
+        let (v6_ips, v6_ttl) = v6.unwrap_or_default();
+        ips.extend(v6_ips);
+
+        let ttl = v4_ttl
+            .into_iter()
+            .chain(v6_ttl)
+            .min()
+            .unwrap_or(DOH_MIN_TTL_SECS)
+            .clamp(DOH_MIN_TTL_SECS, DOH_MAX_TTL_SECS);
+        self.inner.state.lock_or_recover().doh_cache.insert(
+            cache_key,
+            (ips.clone(), Instant::now() + Duration::from_secs(ttl)),
+        );
+        Ok(ips)
+    }
+
+    async fn doh_query(
+        &self,
+        doh_url: &str,
+        host: &str,
+        record_name: &str,
+        record_type: u64,
+    ) -> io::Result<(Vec<IpAddr>, Option<u64>)> {
+        let resp = self
+            .inner
+            .doh_client
+            .get(doh_url)
+            .query(&[("name", host), ("type", record_name)])
+            .header(reqwest::header::ACCEPT, "application/dns-json")
+            .send()
+            .await
+            .map_err(|e| io::Error::other(format!("doh request failed: {e}")))?;
+        if !resp.status().is_success() {
+            return Err(io::Error::other(format!(
+                "doh request failed: status={}",
+                resp.status().as_u16()
+            )));
+        }
+        let body: serde_json::Value = resp
+            .json()
+            .await
+            .map_err(|e| io::Error::other(format!("doh response is not json: {e}")))?;
+        Ok(parse_doh_answers(&body, record_type))
+    }
+}
+
+impl Resolve for UpstreamDnsResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let this = self.clone();
+        let host = name.as_str().to_ascii_lowercase();
+        Box::pin(async move {
+            let addrs = this.resolve_host(host).await?;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+async fn system_lookup(host: &str) -> io::Result<Vec<SocketAddr>> {
+    Ok(tokio::net::lookup_host((host, 0)).await?.collect())
+}
+
+/// Addresses of `record_type` from a DoH JSON answer plus the smallest TTL among them.
+fn parse_doh_answers(body: &serde_json::Value, record_type: u64) -> (Vec<IpAddr>, Option<u64>) {
+    let mut ips = Vec::new();
+    let mut ttl: Option<u64> = None;
+    let answers = body
+        .get("Answer")
+        .and_then(serde_json::Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    for answer in answers {
+        if answer.get("type").and_then(serde_json::Value::as_u64) != Some(record_type) {
+            continue;
+        }
+        let Some(ip) = answer
+            .get("data")
+            .and_then(serde_json::Value::as_str)
+            .and_then(|v| v.trim().parse::<IpAddr>().ok())
+        else {
+            continue;
+        };
+        ips.push(ip);
+        if let Some(answer_ttl) = answer.get("TTL").and_then(serde_json::Value::as_u64) {
+            ttl = Some(ttl.map_or(answer_ttl, |t| t.min(answer_ttl)));
+        }
+    }
+    (ips, ttl)
+}
+
+/// Opens a TCP probe to every address at once; the first to connect moves to the front.
+async fn race_connect(ips: Vec<IpAddr>, port: u16) -> (Vec<IpAddr>, Option<IpAddr>) {
+    let mut probes = tokio::task::JoinSet::new();
+    for ip in ips.iter().copied() {
+        probes.spawn(async move {
+            let connect = tokio::net::TcpStream::connect(SocketAddr::new(ip, port));
+            let connected = matches!(
+                tokio::time::timeout(RACE_PROBE_TIMEOUT, connect).await,
+                Ok(Ok(_))
+            );
+            (ip, connected)
+        });
+    }
+
+    let mut winner = None;
+    while let Some(result) = probes.join_next().await {
+        if let Ok((ip, true)) = result {
+            winner = Some(ip);
+            break;
+        }
+    }
+    probes.abort_all();
+
+    (order_with_winner(ips, winner), winner)
+}
+
+fn order_with_winner(ips: Vec<IpAddr>, winner: Option<IpAddr>) -> Vec<IpAddr> {
+    let Some(winner) = winner else {
+        return ips;
+    };
+    std::iter::once(winner)
+        .chain(ips.into_iter().filter(|ip| *ip != winner))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_doh_answers_filters_by_record_type_and_keeps_min_ttl() {
+        let body = serde_json::json!({
+            "Status": 0,
+            "Answer": [
+                {"name": "relay.example.", "type": 5, "TTL": 30, "data": "cdn.example."},
+                {"name": "cdn.example.", "type": 1, "TTL": 120, "data": "203.0.113.7"},
+                {"name": "cdn.example.", "type": 1, "TTL": 60, "data": "203.0.113.8"},
+                {"name": "cdn.example.", "type": 28, "TTL": 10, "data": "2001:db8::1"},
+            ]
+        });
+        let (ips, ttl) = parse_doh_answers(&body, DOH_RECORD_A);
+        assert_eq!(
+            ips,
+            vec![
+                "203.0.113.7".parse::<IpAddr>().unwrap(),
+                "203.0.113.8".parse::<IpAddr>().unwrap()
+            ]
+        );
+        assert_eq!(ttl, Some(60));
+
+        let (ips, _) = parse_doh_answers(&serde_json::json!({"Status": 3}), DOH_RECORD_AAAA);
+        assert!(ips.is_empty());
+    }
+
+    #[test]
+    fn order_with_winner_moves_winner_first() {
+        let a: IpAddr = "2001:db8::1".parse().unwrap();
+        let b: IpAddr = "203.0.113.7".parse().unwrap();
+        assert_eq!(order_with_winner(vec![a, b], Some(b)), vec![b, a]);
+        assert_eq!(order_with_winner(vec![a, b], None), vec![a, b]);
+    }
+
+    #[test]
+    fn bind_only_routes_providers_with_options() {
+        let mut options = ProviderDnsOptions::defaults(7);
+        options.ip_strategy = DnsIpStrategy::Ipv4Only;
+        let resolver = UpstreamDnsResolver::new(HashMap::from([(7, options)]));
+        let url = reqwest::Url::parse("https://Relay.Example.com/v1").unwrap();
+
+        assert_eq!(
+            resolver.bind(7, &url),
+            Some("relay.example.com".to_string())
+        );
+        assert_eq!(resolver.bind(8, &url), None);
+
+        resolver.set_provider_options(ProviderDnsOptions::defaults(7));
+        assert_eq!(resolver.bind(7, &url), None);
+    }
+}
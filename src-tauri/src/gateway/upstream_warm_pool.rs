@@ -0,0 +1,334 @@
+//! Usage: Per-provider upstream connection warm pool.
+//!
+//! After gateway start and whenever a provider origin has been idle for a while, a background
+//! loop issues `size` concurrent `HEAD /` probes through the shared gateway client so the
+//! connections (TCP + TLS, HTTP/2 where the upstream negotiates it) sit in the client pool
+//! before the next real request arrives.
+
+use crate::shared::cli_key::SUPPORTED_CLI_KEYS;
+use crate::shared::error::AppResult;
+use crate::shared::mutex_ext::MutexExt;
+use crate::{db, providers, settings};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::util::now_unix_seconds;
+use super::GatewayUpstreamWarmStatus;
+
+/// How often the loop wakes up to look for origins that went idle.
+const WARM_TICK_INTERVAL: Duration = Duration::from_secs(15);
+/// An origin is re-warmed after this long without real traffic or a warm pass. Must stay below
+/// the client's pool idle timeout so warmed connections are refreshed before they are evicted.
+const WARM_REFRESH_AFTER_SECS: i64 = 60;
+const WARM_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct WarmTarget {
+    provider_id: i64,
+    cli_key: String,
+    provider_name: String,
+    origin: String,
+    url: reqwest::Url,
+}
+
+impl WarmTarget {
+    fn parse(provider_id: i64, cli_key: &str, provider_name: &str, base_url: &str) -> Option<Self> {
+        let mut url = reqwest::Url::parse(base_url.trim()).ok()?;
+        if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+            return None;
+        }
+        url.set_path("/");
+        url.set_query(None);
+        url.set_fragment(None);
+        let _ = url.set_username("");
+        let _ = url.set_password(None);
+        Some(Self {
+            provider_id,
+            cli_key: cli_key.to_string(),
+            provider_name: provider_name.to_string(),
+            origin: url.origin().ascii_serialization(),
+            url,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct WarmOutcome {
+    warm_connections: u32,
+    http_version: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug)]
+struct WarmEntry {
+    cli_key: String,
+    provider_name: String,
+    warm_connections: u32,
+    http_version: Option<String>,
+    last_warmed_at: i64,
+    last_error: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct UpstreamWarmPool {
+    entries: HashMap<(i64, String), WarmEntry>,
+    last_used_by_origin: HashMap<String, i64>,
+}
+
+impl UpstreamWarmPool {
+    /// Real upstream traffic keeps the pooled connections alive, so it postpones the next warm pass.
+    pub(super) fn mark_used(&mut self, url: &reqwest::Url, now_unix: i64) {
+        if self.entries.is_empty() {
+            return;
+        }
+        self.last_used_by_origin
+            .insert(url.origin().ascii_serialization(), now_unix);
+    }
+
+    fn needs_warm(&self, target: &WarmTarget, now_unix: i64) -> bool {
+        let Some(entry) = self
+            .entries
+            .get(&(target.provider_id, target.origin.clone()))
+        else {
+            return true;
+        };
+        let last_used = self
+            .last_used_by_origin
+            .get(&target.origin)
+            .copied()
+            .unwrap_or(0);
+        now_unix.saturating_sub(entry.last_warmed_at.max(last_used)) >= WARM_REFRESH_AFTER_SECS
+    }
+
+    fn retain_targets(&mut self, targets: &[WarmTarget]) {
+        let keys: HashSet<(i64, &str)> = targets
+            .iter()
+            .map(|t| (t.provider_id, t.origin.as_str()))
+            .collect();
+        let origins: HashSet<&str> = targets.iter().map(|t| t.origin.as_str()).collect();
+        self.entries
+            .retain(|(provider_id, origin), _| keys.contains(&(*provider_id, origin.as_str())));
+        self.last_used_by_origin
+            .retain(|origin, _| origins.contains(origin.as_str()));
+    }
+
+    fn record(&mut self, target: &WarmTarget, outcome: WarmOutcome, now_unix: i64) {
+        self.entries.insert(
+            (target.provider_id, target.origin.clone()),
+            WarmEntry {
+                cli_key: target.cli_key.clone(),
+                provider_name: target.provider_name.clone(),
+                warm_connections: outcome.warm_connections,
+                http_version: outcome.http_version,
+                last_warmed_at: now_unix,
+                last_error: outcome.error,
+            },
+        );
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.last_used_by_origin.clear();
+    }
+
+    pub(super) fn snapshot(&self) -> Vec<GatewayUpstreamWarmStatus> {
+        let mut out: Vec<GatewayUpstreamWarmStatus> = self
+            .entries
+            .iter()
+            .map(|((provider_id, origin), entry)| GatewayUpstreamWarmStatus {
+                provider_id: *provider_id,
+                cli_key: entry.cli_key.clone(),
+                provider_name: entry.provider_name.clone(),
+                origin: origin.clone(),
+                warm_connections: entry.warm_connections,
+                http_version: entry.http_version.clone(),
+                last_warmed_at: Some(entry.last_warmed_at),
+                last_error: entry.last_error.clone(),
+            })
+            .collect();
+        out.sort_by(|a, b| {
+            a.cli_key
+                .cmp(&b.cli_key)
+                .then(a.provider_id.cmp(&b.provider_id))
+                .then(a.origin.cmp(&b.origin))
+        });
+        out
+    }
+}
+
+fn collect_targets(db: &db::Db) -> AppResult<Vec<WarmTarget>> {
+    let mut seen: HashSet<(i64, String)> = HashSet::new();
+    let mut targets = Vec::new();
+    for cli_key in SUPPORTED_CLI_KEYS {
+        let selection = providers::list_enabled_for_gateway_using_active_mode(db, cli_key)?;
+        for provider in selection.providers {
+            for base_url in &provider.base_urls {
+                let Some(target) =
+                    WarmTarget::parse(provider.id, cli_key, &provider.name, base_url)
+                else {
+                    continue;
+                };
+                if seen.insert((target.provider_id, target.origin.clone())) {
+                    targets.push(target);
+                }
+            }
+        }
+    }
+    Ok(targets)
+}
+
+async fn warm_origin(client: &reqwest::Client, url: &reqwest::Url, size: u32) -> WarmOutcome {
+    let mut probes = tokio::task::JoinSet::new();
+    for _ in 0..size.max(1) {
+        let request = client.head(url.clone()).timeout(WARM_PROBE_TIMEOUT);
+        probes.spawn(async move { request.send().await });
+    }
+
+    let mut ok: u32 = 0;
+    let mut http2 = false;
+    let mut http_version = None;
+    let mut error = None;
+    while let Some(joined) = probes.join_next().await {
+        match joined {
+            // Any status proves the connection is up; the probe body is irrelevant.
+            Ok(Ok(resp)) => {
+                ok += 1;
+                http2 |= resp.version() == reqwest::Version::HTTP_2;
+                http_version = Some(format!("{:?}", resp.version()));
+            }
+            Ok(Err(err)) => error = Some(err.to_string()),
+            Err(err) => error = Some(err.to_string()),
+        }
+    }
+
+    WarmOutcome {
+        // HTTP/2 multiplexes every probe over a single connection.
+        warm_connections: if http2 { ok.min(1) } else { ok },
+        http_version,
+        error: if ok == 0 { error } else { None },
+    }
+}
+
+async fn warm_once(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    client: &reqwest::Client,
+    pool: &Mutex<UpstreamWarmPool>,
+) {
+    let cfg = settings::read(app).unwrap_or_default();
+    if !cfg.upstream_warm_pool_enabled {
+        pool.lock_or_recover().clear();
+        return;
+    }
+
+    let targets = match crate::blocking::run("upstream_warm_pool_targets", {
+        let db = db.clone();
+        move || collect_targets(&db)
+    })
+    .await
+    {
+        Ok(targets) => targets,
+        Err(err) => {
+            tracing::warn!("upstream warm pool: failed to list providers: {}", err);
+            return;
+        }
+    };
+
+    let now_unix = now_unix_seconds() as i64;
+    let due: Vec<WarmTarget> = {
+        let mut guard = pool.lock_or_recover();
+        guard.retain_targets(&targets);
+        targets
+            .into_iter()
+            .filter(|t| guard.needs_warm(t, now_unix))
+            .collect()
+    };
+
+    for target in due {
+        let outcome = warm_origin(client, &target.url, cfg.upstream_warm_pool_size).await;
+        if let Some(err) = outcome.error.as_deref() {
+            tracing::debug!(
+                provider_id = target.provider_id,
+                origin = %target.origin,
+                "upstream warm pool: probe failed: {}",
+                err
+            );
+        }
+        pool.lock_or_recover()
+            .record(&target, outcome, now_unix_seconds() as i64);
+    }
+}
+
+/// Spawns the warm loop; the gateway aborts the returned handle on stop.
+pub(super) fn spawn(
+    app: crate::AppHandle,
+    db: db::Db,
+    client: reqwest::Client,
+    pool: Arc<Mutex<UpstreamWarmPool>>,
+) -> tauri::async_runtime::JoinHandle<()> {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            warm_once(&app, &db, &client, &pool).await;
+            tokio::time::sleep(WARM_TICK_INTERVAL).await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(provider_id: i64, base_url: &str) -> WarmTarget {
+        WarmTarget::parse(provider_id, "claude", "p", base_url).expect("target")
+    }
+
+    fn ok_outcome() -> WarmOutcome {
+        WarmOutcome {
+            warm_connections: 2,
+            http_version: Some("HTTP/1.1".to_string()),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn parse_reduces_base_url_to_origin_root() {
+        let t = target(1, "https://user:pw@relay.example.com:8443/v1/messages?x=1");
+        assert_eq!(t.origin, "https://relay.example.com:8443");
+        assert_eq!(t.url.as_str(), "https://relay.example.com:8443/");
+
+        assert!(WarmTarget::parse(1, "claude", "p", "ftp://relay.example.com").is_none());
+        assert!(WarmTarget::parse(1, "claude", "p", "not a url").is_none());
+    }
+
+    #[test]
+    fn rewarm_waits_for_idle_period_and_real_traffic_postpones_it() {
+        let mut pool = UpstreamWarmPool::default();
+        let t = target(1, "https://relay.example.com/v1");
+        assert!(pool.needs_warm(&t, 1_000));
+
+        pool.record(&t, ok_outcome(), 1_000);
+        assert!(!pool.needs_warm(&t, 1_000 + WARM_REFRESH_AFTER_SECS - 1));
+        assert!(pool.needs_warm(&t, 1_000 + WARM_REFRESH_AFTER_SECS));
+
+        pool.mark_used(&t.url, 1_030);
+        assert!(!pool.needs_warm(&t, 1_000 + WARM_REFRESH_AFTER_SECS));
+        assert!(pool.needs_warm(&t, 1_030 + WARM_REFRESH_AFTER_SECS));
+    }
+
+    #[test]
+    fn retain_targets_drops_removed_providers() {
+        let mut pool = UpstreamWarmPool::default();
+        let keep = target(1, "https://a.example.com");
+        let gone = target(2, "https://b.example.com");
+        pool.record(&keep, ok_outcome(), 10);
+        pool.record(&gone, ok_outcome(), 10);
+
+        pool.retain_targets(std::slice::from_ref(&keep));
+
+        let snapshot = pool.snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].provider_id, 1);
+        assert_eq!(snapshot[0].warm_connections, 2);
+    }
+}
@@ -22,7 +22,7 @@ const ENV_KEY_CLAUDE_CODE_DISABLE_NONESSENTIAL_TRAFFIC: &str =
 const ENV_KEY_CLAUDE_CODE_PROXY_RESOLVES_HOSTS: &str = "CLAUDE_CODE_PROXY_RESOLVES_HOSTS";
 const ENV_KEY_CLAUDE_CODE_SKIP_PROMPT_HISTORY: &str = "CLAUDE_CODE_SKIP_PROMPT_HISTORY";
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct ClaudeSettingsState {
     pub config_dir: String,
     pub settings_path: String,
@@ -59,7 +59,7 @@ pub struct ClaudeSettingsState {
     pub env_claude_code_skip_prompt_history: bool,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, specta::Type)]
 pub struct ClaudeSettingsPatch {
     pub model: Option<String>,
     pub output_style: Option<String>,
@@ -645,5 +645,215 @@ pub fn claude_settings_set<R: tauri::Runtime>(
     claude_settings_get(app)
 }
 
+/// A hook entry owned by aio-coding-hub inside `settings.json` `hooks`; user-authored hooks are
+/// never touched.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManagedClaudeHook {
+    pub event: String,
+    pub matcher: String,
+    pub command: String,
+    pub timeout_secs: Option<u32>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ManagedHooksManifest {
+    hooks: Vec<ManagedClaudeHook>,
+}
+
+fn managed_hooks_manifest_path<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> crate::shared::error::AppResult<PathBuf> {
+    Ok(crate::app_paths::app_data_dir(app)?
+        .join("hook-sync")
+        .join("claude")
+        .join("manifest.json"))
+}
+
+fn hook_group_matcher(group: &serde_json::Value) -> &str {
+    group
+        .get("matcher")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+}
+
+fn hook_entry_matches(entry: &serde_json::Value, hook: &ManagedClaudeHook) -> bool {
+    entry.get("type").and_then(|v| v.as_str()) == Some("command")
+        && entry.get("command").and_then(|v| v.as_str()) == Some(hook.command.as_str())
+}
+
+/// Drop the `previous` managed entries, then append `next`; empty matcher groups/events are pruned.
+fn patch_managed_hooks(
+    mut root: serde_json::Value,
+    previous: &[ManagedClaudeHook],
+    next: &[ManagedClaudeHook],
+) -> crate::shared::error::AppResult<serde_json::Value> {
+    root = ensure_json_object_root(root);
+    let obj = root
+        .as_object_mut()
+        .ok_or_else(|| "settings.json root must be a JSON object".to_string())?;
+
+    let mut hooks = match obj.remove("hooks") {
+        Some(serde_json::Value::Object(map)) => map,
+        None | Some(serde_json::Value::Null) => serde_json::Map::new(),
+        Some(_) => {
+            return Err(
+                "SEC_INVALID_INPUT: settings.json hooks must be a JSON object"
+                    .to_string()
+                    .into(),
+            )
+        }
+    };
+
+    for hook in previous {
+        let Some(groups) = hooks.get_mut(&hook.event).and_then(|v| v.as_array_mut()) else {
+            continue;
+        };
+        for group in groups.iter_mut() {
+            if hook_group_matcher(group) != hook.matcher {
+                continue;
+            }
+            let Some(entries) = group.get_mut("hooks").and_then(|v| v.as_array_mut()) else {
+                continue;
+            };
+            if let Some(pos) = entries.iter().position(|e| hook_entry_matches(e, hook)) {
+                entries.remove(pos);
+                break;
+            }
+        }
+        groups.retain(|group| {
+            group
+                .get("hooks")
+                .and_then(|v| v.as_array())
+                .map(|entries| !entries.is_empty())
+                .unwrap_or(true)
+        });
+    }
+    hooks.retain(|_, groups| groups.as_array().map(|g| !g.is_empty()).unwrap_or(true));
+
+    for hook in next {
+        let groups = hooks
+            .entry(hook.event.clone())
+            .or_insert_with(|| serde_json::json!([]));
+        let Some(groups) = groups.as_array_mut() else {
+            continue;
+        };
+
+        let mut entry = serde_json::json!({ "type": "command", "command": hook.command });
+        if let Some(timeout) = hook.timeout_secs {
+            entry["timeout"] = serde_json::json!(timeout);
+        }
+
+        let existing = groups.iter_mut().find(|group| {
+            hook_group_matcher(group) == hook.matcher
+                && group.get("hooks").is_some_and(|v| v.is_array())
+        });
+        match existing.and_then(|group| group["hooks"].as_array_mut()) {
+            Some(entries) => entries.push(entry),
+            None => {
+                let mut group = serde_json::json!({ "hooks": [entry] });
+                if !hook.matcher.is_empty() {
+                    group["matcher"] = serde_json::json!(hook.matcher);
+                }
+                groups.push(group);
+            }
+        }
+    }
+
+    if !hooks.is_empty() {
+        obj.insert("hooks".to_string(), serde_json::Value::Object(hooks));
+    }
+    Ok(root)
+}
+
+/// Captured `settings.json` + hook manifest bytes so a failed DB commit can undo a hook sync.
+pub struct ClaudeHooksSyncBackup {
+    settings_path: PathBuf,
+    settings: Option<Vec<u8>>,
+    manifest_path: PathBuf,
+    manifest: Option<Vec<u8>>,
+}
+
+impl ClaudeHooksSyncBackup {
+    pub fn capture<R: tauri::Runtime>(
+        app: &tauri::AppHandle<R>,
+    ) -> crate::shared::error::AppResult<Self> {
+        let settings_path = claude_settings_path(app)?;
+        let manifest_path = managed_hooks_manifest_path(app)?;
+        Ok(Self {
+            settings: read_optional_file(&settings_path)?,
+            manifest: read_optional_file(&manifest_path)?,
+            settings_path,
+            manifest_path,
+        })
+    }
+
+    pub fn restore(self) {
+        for (path, bytes) in [
+            (&self.settings_path, self.settings),
+            (&self.manifest_path, self.manifest),
+        ] {
+            let result = match bytes {
+                Some(bytes) => crate::shared::fs::write_file_atomic(path, &bytes),
+                None if path.exists() => std::fs::remove_file(path)
+                    .map_err(|e| format!("failed to remove {}: {e}", path.display()).into()),
+                None => Ok(()),
+            };
+            if let Err(err) = result {
+                tracing::warn!(path = %path.display(), "hook sync restore failed: {}", err);
+            }
+        }
+    }
+}
+
+/// Replace the hooks previously written by aio-coding-hub with `hooks` (also mirrored into the
+/// CLI proxy backup when takeover is active, so disabling the proxy keeps them).
+pub fn claude_settings_sync_hooks<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    hooks: &[ManagedClaudeHook],
+) -> crate::shared::error::AppResult<()> {
+    let path = claude_settings_path(app)?;
+    if path.exists() && is_symlink(&path)? {
+        return Err(format!(
+            "SEC_INVALID_INPUT: refusing to modify symlink path={}",
+            path.display()
+        )
+        .into());
+    }
+
+    let manifest_path = managed_hooks_manifest_path(app)?;
+    let previous = read_optional_file(&manifest_path)?
+        .and_then(|bytes| serde_json::from_slice::<ManagedHooksManifest>(&bytes).ok())
+        .unwrap_or_default();
+
+    let current = read_optional_file(&path)?;
+    if current.is_none() && previous.hooks.is_empty() && hooks.is_empty() {
+        return Ok(());
+    }
+
+    let patched = patch_managed_hooks(json_root_from_bytes(current), &previous.hooks, hooks)?;
+    let bytes = json_to_bytes(&patched, "claude/settings.json")?;
+    let _ = write_file_atomic_if_changed(&path, &bytes)?;
+
+    if let Some(backup_path) = super::cli_proxy::backup_file_path_for_enabled_manifest(
+        app,
+        "claude",
+        "claude_settings_json",
+        "settings.json",
+    )? {
+        let backup = read_optional_file(&backup_path)?;
+        let patched = patch_managed_hooks(json_root_from_bytes(backup), &previous.hooks, hooks)?;
+        let bytes = json_to_bytes(&patched, "claude/settings.json backup")?;
+        let _ = write_file_atomic_if_changed(&backup_path, &bytes)?;
+    }
+
+    let manifest = ManagedHooksManifest {
+        hooks: hooks.to_vec(),
+    };
+    let manifest_bytes = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| format!("failed to serialize hook-sync manifest: {e}"))?;
+    let _ = write_file_atomic_if_changed(&manifest_path, &manifest_bytes)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests;
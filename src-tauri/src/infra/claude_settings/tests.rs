@@ -252,3 +252,71 @@ fn patch_non_object_root_is_replaced_with_object() {
         Some("claude-3-5-sonnet")
     );
 }
+
+fn managed_hook(event: &str, matcher: &str, command: &str) -> ManagedClaudeHook {
+    ManagedClaudeHook {
+        event: event.to_string(),
+        matcher: matcher.to_string(),
+        command: command.to_string(),
+        timeout_secs: None,
+    }
+}
+
+#[test]
+fn patch_managed_hooks_keeps_user_hooks_and_replaces_managed_ones() {
+    let input = serde_json::json!({
+        "hooks": {
+            "PreToolUse": [
+                {
+                    "matcher": "Bash",
+                    "hooks": [
+                        { "type": "command", "command": "user-audit.sh" },
+                        { "type": "command", "command": "old-managed.sh" }
+                    ]
+                }
+            ]
+        }
+    });
+
+    let previous = vec![managed_hook("PreToolUse", "Bash", "old-managed.sh")];
+    let mut next_hook = managed_hook("PreToolUse", "Bash", "new-managed.sh");
+    next_hook.timeout_secs = Some(30);
+    let next = vec![next_hook, managed_hook("Stop", "", "notify.sh")];
+
+    let patched = patch_managed_hooks(input, &previous, &next).expect("patch");
+
+    assert_eq!(
+        patched["hooks"]["PreToolUse"],
+        serde_json::json!([
+            {
+                "matcher": "Bash",
+                "hooks": [
+                    { "type": "command", "command": "user-audit.sh" },
+                    { "type": "command", "command": "new-managed.sh", "timeout": 30 }
+                ]
+            }
+        ])
+    );
+    assert_eq!(
+        patched["hooks"]["Stop"],
+        serde_json::json!([{ "hooks": [{ "type": "command", "command": "notify.sh" }] }])
+    );
+}
+
+#[test]
+fn patch_managed_hooks_prunes_empty_groups_and_events() {
+    let previous = vec![managed_hook("PostToolUse", "Edit|Write", "fmt.sh")];
+    let input =
+        patch_managed_hooks(serde_json::json!({ "model": "x" }), &[], &previous).expect("apply");
+    assert!(input.get("hooks").is_some());
+
+    let patched = patch_managed_hooks(input, &previous, &[]).expect("remove");
+    assert!(patched.get("hooks").is_none());
+    assert_eq!(patched.get("model").and_then(|v| v.as_str()), Some("x"));
+}
+
+#[test]
+fn patch_managed_hooks_rejects_non_object_hooks() {
+    let input = serde_json::json!({ "hooks": ["bad"] });
+    assert!(patch_managed_hooks(input, &[], &[]).is_err());
+}
@@ -15,7 +15,7 @@ const LOGIN_SHELL_TIMEOUT: Duration = Duration::from_secs(2);
 const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
 const CMD_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct ClaudeCliInfo {
     pub found: bool,
     pub executable_path: Option<String>,
@@ -29,7 +29,7 @@ pub struct ClaudeCliInfo {
     pub disable_error_reporting: bool,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct SimpleCliInfo {
     pub found: bool,
     pub executable_path: Option<String>,
@@ -39,7 +39,7 @@ pub struct SimpleCliInfo {
     pub resolved_via: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct ClaudeEnvState {
     pub config_dir: String,
     pub settings_path: String,
@@ -286,8 +286,13 @@ fn find_exe_in_path(names: &[String]) -> Option<PathBuf> {
     None
 }
 
+/// Looks `cmd` up on `PATH` (with the platform's executable suffixes).
+pub(crate) fn find_executable_on_path(cmd: &str) -> Option<PathBuf> {
+    find_exe_in_path(&exe_names_for(cmd))
+}
+
 fn scan_executable(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     cmd: &str,
 ) -> crate::shared::error::AppResult<Option<PathBuf>> {
     let names = exe_names_for(cmd);
@@ -475,7 +480,7 @@ fn run_version(exe: &Path) -> crate::shared::error::AppResult<String> {
     .into())
 }
 
-fn cli_probe(app: &tauri::AppHandle, cmd: &str) -> crate::shared::error::AppResult<CliProbeResult> {
+fn cli_probe(app: &crate::AppHandle, cmd: &str) -> crate::shared::error::AppResult<CliProbeResult> {
     let shell = std::env::var("SHELL").ok();
 
     let (exe, resolved_via) = match resolve_executable_via_login_shell(cmd) {
@@ -508,7 +513,7 @@ fn cli_probe(app: &tauri::AppHandle, cmd: &str) -> crate::shared::error::AppResu
     })
 }
 
-pub fn claude_info_get(app: &tauri::AppHandle) -> crate::shared::error::AppResult<ClaudeCliInfo> {
+pub fn claude_info_get(app: &crate::AppHandle) -> crate::shared::error::AppResult<ClaudeCliInfo> {
     let config_dir = claude_config_dir(app)?;
     let settings_path = claude_settings_path(app)?;
     let (mcp_timeout_ms, disable_error_reporting) = read_claude_env(&settings_path)?;
@@ -529,7 +534,7 @@ pub fn claude_info_get(app: &tauri::AppHandle) -> crate::shared::error::AppResul
     })
 }
 
-pub fn codex_info_get(app: &tauri::AppHandle) -> crate::shared::error::AppResult<SimpleCliInfo> {
+pub fn codex_info_get(app: &crate::AppHandle) -> crate::shared::error::AppResult<SimpleCliInfo> {
     let probe = cli_probe(app, "codex")?;
     Ok(SimpleCliInfo {
         found: probe.found,
@@ -541,7 +546,7 @@ pub fn codex_info_get(app: &tauri::AppHandle) -> crate::shared::error::AppResult
     })
 }
 
-pub fn gemini_info_get(app: &tauri::AppHandle) -> crate::shared::error::AppResult<SimpleCliInfo> {
+pub fn gemini_info_get(app: &crate::AppHandle) -> crate::shared::error::AppResult<SimpleCliInfo> {
     let probe = cli_probe(app, "gemini")?;
     Ok(SimpleCliInfo {
         found: probe.found,
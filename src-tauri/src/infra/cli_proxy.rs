@@ -16,14 +16,14 @@ const CODEX_PROVIDER_KEY: &str = "aio";
 
 static TRACE_COUNTER: AtomicU64 = AtomicU64::new(1);
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct CliProxyStatus {
     pub cli_key: String,
     pub enabled: bool,
     pub base_origin: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct CliProxyResult {
     pub trace_id: String,
     pub cli_key: String,
@@ -54,6 +54,26 @@ struct CliProxyManifest {
     files: Vec<BackupFileEntry>,
 }
 
+/// A key `set_enabled` writes into a CLI config file, with its current on-disk value.
+#[derive(Debug, Clone)]
+pub struct ProxyKeyState {
+    /// Dotted path inside the file, e.g. `env.ANTHROPIC_BASE_URL`.
+    pub key: &'static str,
+    pub expected: String,
+    pub actual: Option<String>,
+    /// Holds the client API key; callers must not report the raw value.
+    pub secret: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProxyFileState {
+    pub kind: &'static str,
+    pub path: PathBuf,
+    pub exists: bool,
+    pub parse_error: Option<String>,
+    pub keys: Vec<ProxyKeyState>,
+}
+
 #[derive(Debug, Clone)]
 struct TargetFile {
     kind: &'static str,
@@ -67,6 +87,17 @@ fn new_trace_id(prefix: &str) -> String {
     format!("{prefix}-{ts}-{seq}")
 }
 
+/// API key written into CLI configs: the gateway access token when one is set (so clients
+/// reaching the gateway over a non-loopback address still authenticate), else the placeholder.
+fn client_api_key<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> String {
+    crate::settings::read(app)
+        .ok()
+        .as_ref()
+        .and_then(crate::settings::gateway_access_token)
+        .unwrap_or(PLACEHOLDER_KEY)
+        .to_string()
+}
+
 fn validate_cli_key(cli_key: &str) -> crate::shared::error::AppResult<()> {
     crate::shared::cli_key::validate_cli_key(cli_key)
 }
@@ -262,6 +293,17 @@ fn target_files<R: tauri::Runtime>(
     }
 }
 
+/// Config files `set_enabled` rewrites for `cli_key`.
+pub fn config_file_paths<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<Vec<PathBuf>> {
+    Ok(target_files(app, cli_key)?
+        .into_iter()
+        .map(|target| target.path)
+        .collect())
+}
+
 fn backup_for_enable<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     cli_key: &str,
@@ -366,6 +408,7 @@ fn restore_from_manifest<R: tauri::Runtime>(
 fn patch_json_set_env_base_url(
     mut root: serde_json::Value,
     base_url: &str,
+    api_key: &str,
 ) -> crate::shared::error::AppResult<serde_json::Value> {
     let obj = root.as_object_mut().ok_or_else(|| {
         crate::shared::error::AppError::from(
@@ -389,7 +432,7 @@ fn patch_json_set_env_base_url(
     );
     env.insert(
         "ANTHROPIC_AUTH_TOKEN".to_string(),
-        serde_json::Value::String(PLACEHOLDER_KEY.to_string()),
+        serde_json::Value::String(api_key.to_string()),
     );
 
     Ok(root)
@@ -398,6 +441,7 @@ fn patch_json_set_env_base_url(
 fn build_claude_settings_json(
     current: Option<Vec<u8>>,
     base_url: &str,
+    api_key: &str,
 ) -> crate::shared::error::AppResult<Vec<u8>> {
     let root = match current {
         Some(bytes) if bytes.is_empty() => serde_json::json!({}),
@@ -406,7 +450,7 @@ fn build_claude_settings_json(
         None => serde_json::json!({}),
     };
 
-    let patched = patch_json_set_env_base_url(root, base_url)?;
+    let patched = patch_json_set_env_base_url(root, base_url, api_key)?;
     let mut out = serde_json::to_vec_pretty(&patched)
         .map_err(|e| format!("failed to serialize settings.json: {e}"))?;
     out.push(b'\n');
@@ -660,7 +704,10 @@ fn build_codex_config_toml(
     Ok(out.into_bytes())
 }
 
-fn build_codex_auth_json(current: Option<Vec<u8>>) -> crate::shared::error::AppResult<Vec<u8>> {
+fn build_codex_auth_json(
+    current: Option<Vec<u8>>,
+    api_key: &str,
+) -> crate::shared::error::AppResult<Vec<u8>> {
     let mut value = match current {
         Some(bytes) if bytes.is_empty() => serde_json::json!({}),
         Some(bytes) => serde_json::from_slice::<serde_json::Value>(&bytes)
@@ -675,7 +722,7 @@ fn build_codex_auth_json(current: Option<Vec<u8>>) -> crate::shared::error::AppR
     })?;
     obj.insert(
         "OPENAI_API_KEY".to_string(),
-        serde_json::Value::String(PLACEHOLDER_KEY.to_string()),
+        serde_json::Value::String(api_key.to_string()),
     );
     obj.insert(
         "auth_mode".to_string(),
@@ -726,6 +773,7 @@ fn set_env_var_lines(input: &str, key: &str, value: &str) -> String {
 fn build_gemini_env(
     current: Option<Vec<u8>>,
     base_url: &str,
+    api_key: &str,
 ) -> crate::shared::error::AppResult<Vec<u8>> {
     let input = current
         .as_deref()
@@ -733,7 +781,7 @@ fn build_gemini_env(
         .unwrap_or_default();
 
     let mut next = set_env_var_lines(&input, "GOOGLE_GEMINI_BASE_URL", base_url);
-    next = set_env_var_lines(&next, "GEMINI_API_KEY", PLACEHOLDER_KEY);
+    next = set_env_var_lines(&next, "GEMINI_API_KEY", api_key);
     next.push('\n');
     Ok(next.into_bytes())
 }
@@ -761,6 +809,7 @@ fn is_proxy_config_applied<R: tauri::Runtime>(
     cli_key: &str,
     base_origin: &str,
 ) -> bool {
+    let api_key = client_api_key(app);
     match cli_key {
         "claude" => {
             let path = match claude_settings_path(app) {
@@ -782,6 +831,8 @@ fn is_proxy_config_applied<R: tauri::Runtime>(
                 return false;
             };
             base == format!("{base_origin}/claude")
+                && env.get("ANTHROPIC_AUTH_TOKEN").and_then(|v| v.as_str())
+                    == Some(api_key.as_str())
         }
         "codex" => {
             let config_path = match codex_config_path(app) {
@@ -823,9 +874,7 @@ fn is_proxy_config_applied<R: tauri::Runtime>(
                 Ok(v) => v,
                 Err(_) => return false,
             };
-            auth.get("OPENAI_API_KEY")
-                .and_then(|v| v.as_str())
-                .is_some()
+            auth.get("OPENAI_API_KEY").and_then(|v| v.as_str()) == Some(api_key.as_str())
         }
         "gemini" => {
             let path = match gemini_env_path(app) {
@@ -840,11 +889,156 @@ fn is_proxy_config_applied<R: tauri::Runtime>(
                 return false;
             };
             base == format!("{base_origin}/gemini")
+                && env_var_value(&content, "GEMINI_API_KEY").as_deref() == Some(api_key.as_str())
         }
         _ => false,
     }
 }
 
+/// Keys (with expected values and whether they are secret) written into a target file `kind`.
+fn managed_keys_for_kind(
+    kind: &str,
+    base_origin: &str,
+    api_key: &str,
+) -> Vec<(&'static str, String, bool)> {
+    match kind {
+        "claude_settings_json" => vec![
+            (
+                "env.ANTHROPIC_BASE_URL",
+                format!("{base_origin}/claude"),
+                false,
+            ),
+            ("env.ANTHROPIC_AUTH_TOKEN", api_key.to_string(), true),
+        ],
+        "codex_config_toml" => vec![
+            ("model_provider", CODEX_PROVIDER_KEY.to_string(), false),
+            ("preferred_auth_method", "apikey".to_string(), false),
+            (
+                "model_providers.aio.base_url",
+                format!("{base_origin}/v1"),
+                false,
+            ),
+        ],
+        "codex_auth_json" => vec![
+            ("OPENAI_API_KEY", api_key.to_string(), true),
+            ("auth_mode", "apikey".to_string(), false),
+        ],
+        "gemini_env" => vec![
+            (
+                "GOOGLE_GEMINI_BASE_URL",
+                format!("{base_origin}/gemini"),
+                false,
+            ),
+            ("GEMINI_API_KEY", api_key.to_string(), true),
+        ],
+        _ => Vec::new(),
+    }
+}
+
+fn json_scalar_to_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn toml_scalar_to_string(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Reads the current value of each dotted `key` from the content of a target file `kind`.
+fn read_managed_values(
+    kind: &str,
+    content: &str,
+    keys: &[&str],
+) -> Result<Vec<Option<String>>, String> {
+    match kind {
+        "claude_settings_json" | "codex_auth_json" => {
+            let root: serde_json::Value = if content.trim().is_empty() {
+                serde_json::json!({})
+            } else {
+                serde_json::from_str(content).map_err(|e| format!("invalid JSON: {e}"))?
+            };
+            Ok(keys
+                .iter()
+                .map(|key| {
+                    key.split('.')
+                        .try_fold(&root, |v, part| v.get(part))
+                        .map(json_scalar_to_string)
+                })
+                .collect())
+        }
+        "codex_config_toml" => {
+            let root: toml::Value =
+                toml::from_str(content).map_err(|e| format!("invalid TOML: {e}"))?;
+            Ok(keys
+                .iter()
+                .map(|key| {
+                    key.split('.')
+                        .try_fold(&root, |v, part| v.get(part))
+                        .map(toml_scalar_to_string)
+                })
+                .collect())
+        }
+        "gemini_env" => Ok(keys.iter().map(|key| env_var_value(content, key)).collect()),
+        _ => Err(format!("unknown cli proxy file kind={kind}")),
+    }
+}
+
+/// On-disk state of every key the proxy writes for `cli_key`; `None` while the proxy is off,
+/// since the hub then owns nothing in those files.
+pub fn managed_key_states<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<Option<Vec<ProxyFileState>>> {
+    validate_cli_key(cli_key)?;
+    let Some(manifest) = read_manifest(app, cli_key)?.filter(|m| m.enabled) else {
+        return Ok(None);
+    };
+    let Some(base_origin) = manifest.base_origin else {
+        return Ok(None);
+    };
+
+    let api_key = client_api_key(app);
+    let mut out = Vec::new();
+    for t in target_files(app, cli_key)? {
+        let expected = managed_keys_for_kind(t.kind, &base_origin, &api_key);
+        let content =
+            read_optional_file(&t.path)?.map(|bytes| String::from_utf8_lossy(&bytes).into_owned());
+        let exists = content.is_some();
+
+        let key_names: Vec<&str> = expected.iter().map(|(key, _, _)| *key).collect();
+        let (actual, parse_error) = match content.as_deref() {
+            Some(content) => match read_managed_values(t.kind, content, &key_names) {
+                Ok(values) => (values, None),
+                Err(err) => (vec![None; key_names.len()], Some(err)),
+            },
+            None => (vec![None; key_names.len()], None),
+        };
+
+        out.push(ProxyFileState {
+            kind: t.kind,
+            path: t.path,
+            exists,
+            parse_error,
+            keys: expected
+                .into_iter()
+                .zip(actual)
+                .map(|((key, expected, secret), actual)| ProxyKeyState {
+                    key,
+                    expected,
+                    actual,
+                    secret,
+                })
+                .collect(),
+        });
+    }
+    Ok(Some(out))
+}
+
 fn apply_proxy_config<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     cli_key: &str,
@@ -853,19 +1047,22 @@ fn apply_proxy_config<R: tauri::Runtime>(
     validate_cli_key(cli_key)?;
 
     let targets = target_files(app, cli_key)?;
+    let api_key = client_api_key(app);
 
     for t in targets {
         let current = read_optional_file(&t.path)?;
         let bytes = match cli_key {
-            "claude" => build_claude_settings_json(current, &format!("{base_origin}/claude"))?,
+            "claude" => {
+                build_claude_settings_json(current, &format!("{base_origin}/claude"), &api_key)?
+            }
             "codex" => {
                 if t.kind == "codex_config_toml" {
                     build_codex_config_toml(current, &format!("{base_origin}/v1"))?
                 } else {
-                    build_codex_auth_json(current)?
+                    build_codex_auth_json(current, &api_key)?
                 }
             }
-            "gemini" => build_gemini_env(current, &format!("{base_origin}/gemini"))?,
+            "gemini" => build_gemini_env(current, &format!("{base_origin}/gemini"), &api_key)?,
             _ => return Err(format!("SEC_INVALID_INPUT: unknown cli_key={cli_key}").into()),
         };
 
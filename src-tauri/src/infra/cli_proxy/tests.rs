@@ -136,7 +136,8 @@ fn codex_proxy_auth_json_preserves_existing_oauth_fields() {
   "OPENAI_API_KEY": "old-key"
 }"#;
 
-    let out = build_codex_auth_json(Some(input.as_bytes().to_vec())).expect("build auth");
+    let out = build_codex_auth_json(Some(input.as_bytes().to_vec()), PLACEHOLDER_KEY)
+        .expect("build auth");
     let value: serde_json::Value = serde_json::from_slice(&out).expect("parse output");
 
     assert_eq!(
@@ -156,7 +157,8 @@ fn codex_proxy_auth_json_preserves_existing_oauth_fields() {
 #[test]
 fn codex_proxy_auth_json_rejects_non_object_root() {
     let input = r#"["not", "an", "object"]"#;
-    let err = build_codex_auth_json(Some(input.as_bytes().to_vec())).expect_err("must fail");
+    let err = build_codex_auth_json(Some(input.as_bytes().to_vec()), PLACEHOLDER_KEY)
+        .expect_err("must fail");
     assert!(err
         .to_string()
         .contains("auth.json root must be a JSON object"));
@@ -165,7 +167,96 @@ fn codex_proxy_auth_json_rejects_non_object_root() {
 #[test]
 fn claude_proxy_settings_json_rejects_invalid_json() {
     let input = br#"{"env": "#.to_vec();
-    let err = build_claude_settings_json(Some(input), "http://127.0.0.1:1717/claude")
-        .expect_err("must fail");
+    let err =
+        build_claude_settings_json(Some(input), "http://127.0.0.1:1717/claude", PLACEHOLDER_KEY)
+            .expect_err("must fail");
     assert!(err.to_string().contains("CLI_PROXY_INVALID_SETTINGS_JSON"));
 }
+
+#[test]
+fn read_managed_values_follows_dotted_keys_per_file_kind() {
+    let settings = r#"{"env":{"ANTHROPIC_BASE_URL":"http://127.0.0.1:37123/claude","X":1}}"#;
+    let values = read_managed_values(
+        "claude_settings_json",
+        settings,
+        &[
+            "env.ANTHROPIC_BASE_URL",
+            "env.ANTHROPIC_AUTH_TOKEN",
+            "env.X",
+        ],
+    )
+    .expect("json");
+    assert_eq!(
+        values,
+        vec![
+            Some("http://127.0.0.1:37123/claude".to_string()),
+            None,
+            Some("1".to_string())
+        ]
+    );
+
+    let config = "model_provider = \"aio\"\n\n[model_providers.aio]\nbase_url = \"http://h/v1\"\n";
+    let values = read_managed_values(
+        "codex_config_toml",
+        config,
+        &[
+            "model_provider",
+            "preferred_auth_method",
+            "model_providers.aio.base_url",
+        ],
+    )
+    .expect("toml");
+    assert_eq!(
+        values,
+        vec![
+            Some("aio".to_string()),
+            None,
+            Some("http://h/v1".to_string())
+        ]
+    );
+
+    let env = "# comment\nexport GOOGLE_GEMINI_BASE_URL=http://h/gemini\n";
+    let values = read_managed_values(
+        "gemini_env",
+        env,
+        &["GOOGLE_GEMINI_BASE_URL", "GEMINI_API_KEY"],
+    )
+    .expect("env");
+    assert_eq!(values, vec![Some("http://h/gemini".to_string()), None]);
+
+    assert!(read_managed_values("claude_settings_json", "{", &["env.X"]).is_err());
+}
+
+#[test]
+fn managed_keys_cover_everything_apply_writes() {
+    let origin = "http://127.0.0.1:37123";
+    let cases = [
+        (
+            "claude_settings_json",
+            build_claude_settings_json(None, &format!("{origin}/claude"), "k").expect("claude"),
+        ),
+        (
+            "codex_config_toml",
+            build_codex_config_toml(None, &format!("{origin}/v1")).expect("codex"),
+        ),
+        (
+            "codex_auth_json",
+            build_codex_auth_json(None, "k").expect("auth"),
+        ),
+        (
+            "gemini_env",
+            build_gemini_env(None, &format!("{origin}/gemini"), "k").expect("gemini"),
+        ),
+    ];
+
+    for (kind, bytes) in cases {
+        let expected = managed_keys_for_kind(kind, origin, "k");
+        assert!(!expected.is_empty(), "{kind}");
+        let keys: Vec<&str> = expected.iter().map(|(key, _, _)| *key).collect();
+        let content = String::from_utf8(bytes).expect("utf8");
+        let actual = read_managed_values(kind, &content, &keys).expect("parse");
+        for ((key, want, _), have) in expected.iter().zip(actual) {
+            assert_eq!(have.as_deref(), Some(want.as_str()), "{kind} {key}");
+        }
+    }
+}
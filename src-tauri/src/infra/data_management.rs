@@ -3,12 +3,30 @@
 use crate::app_paths;
 use crate::db;
 use crate::shared::error::db_err;
+use crate::shared::time::now_unix_seconds;
 use rusqlite::{OptionalExtension, TransactionBehavior};
 use serde::Serialize;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicI64, Ordering};
 
-#[derive(Debug, Clone, Serialize)]
+mod config_apply;
+mod config_archive;
+mod readonly_query;
+mod selective_reset;
+mod usage_import;
+
+pub use config_apply::{config_apply_file, ConfigApplyChange, ConfigApplyReport};
+pub use config_archive::{config_export, config_import, ConfigExportResult, ConfigImportResult};
+pub use readonly_query::{db_query_readonly, DbQueryResult};
+pub use selective_reset::{
+    data_reset_apply, data_reset_plan, DataResetItem, DataResetReport, DataResetScope,
+};
+pub use usage_import::{import_usage_history, UsageImportResult};
+
+static LAST_CHECKPOINT_AT: AtomicI64 = AtomicI64::new(0);
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct DbDiskUsage {
     pub db_bytes: u64,
     pub wal_bytes: u64,
@@ -16,7 +34,29 @@ pub struct DbDiskUsage {
     pub total_bytes: u64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct DbHealth {
+    pub wal_bytes: u64,
+    pub page_size: i64,
+    pub page_count: i64,
+    pub freelist_pages: i64,
+    pub busy_waits: u64,
+    pub busy_timeouts: u64,
+    /// Unix seconds of the last manual checkpoint in this process; `None` if never run.
+    pub last_checkpoint_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct DbCheckpointResult {
+    /// `true` when a reader/writer prevented the checkpoint from completing.
+    pub busy: bool,
+    pub wal_frames: i64,
+    pub checkpointed_frames: i64,
+    pub wal_bytes_after: u64,
+    pub checkpointed_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct ClearRequestLogsResult {
     pub request_logs_deleted: u64,
     pub request_attempt_logs_deleted: u64,
@@ -55,7 +95,7 @@ fn db_related_paths(db_path: &Path) -> (PathBuf, PathBuf) {
     (wal_path, shm_path)
 }
 
-pub fn db_disk_usage_get(app: &tauri::AppHandle) -> crate::shared::error::AppResult<DbDiskUsage> {
+pub fn db_disk_usage_get(app: &crate::AppHandle) -> crate::shared::error::AppResult<DbDiskUsage> {
     let db_path = db::db_path(app)?;
     let (wal_path, shm_path) = db_related_paths(&db_path);
 
@@ -71,6 +111,67 @@ pub fn db_disk_usage_get(app: &tauri::AppHandle) -> crate::shared::error::AppRes
     })
 }
 
+fn pragma_i64(conn: &rusqlite::Connection, name: &str) -> crate::shared::error::AppResult<i64> {
+    conn.query_row(&format!("PRAGMA {name}"), [], |row| row.get(0))
+        .map_err(|e| db_err!("failed to read PRAGMA {name}: {e}"))
+}
+
+pub fn db_health_get(
+    app: &crate::AppHandle,
+    db: &db::Db,
+) -> crate::shared::error::AppResult<DbHealth> {
+    let (wal_path, _) = db_related_paths(&db::db_path(app)?);
+    let conn = db.open_connection()?;
+    let busy = db::busy_stats();
+    let last_checkpoint_at = LAST_CHECKPOINT_AT.load(Ordering::Relaxed);
+
+    Ok(DbHealth {
+        wal_bytes: file_len_or_zero(&wal_path)?,
+        page_size: pragma_i64(&conn, "page_size")?,
+        page_count: pragma_i64(&conn, "page_count")?,
+        freelist_pages: pragma_i64(&conn, "freelist_count")?,
+        busy_waits: busy.waits,
+        busy_timeouts: busy.timeouts,
+        last_checkpoint_at: (last_checkpoint_at > 0).then_some(last_checkpoint_at),
+    })
+}
+
+pub fn db_checkpoint_now(
+    app: &crate::AppHandle,
+    db: &db::Db,
+) -> crate::shared::error::AppResult<DbCheckpointResult> {
+    let (wal_path, _) = db_related_paths(&db::db_path(app)?);
+    let conn = db.open_connection()?;
+
+    let (busy, wal_frames, checkpointed_frames) = conn
+        .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })
+        .map_err(|e| db_err!("failed to run wal checkpoint: {e}"))?;
+
+    let checkpointed_at = now_unix_seconds();
+    LAST_CHECKPOINT_AT.store(checkpointed_at, Ordering::Relaxed);
+
+    tracing::info!(
+        busy = busy != 0,
+        wal_frames = wal_frames,
+        checkpointed_frames = checkpointed_frames,
+        "sqlite wal checkpoint completed"
+    );
+
+    Ok(DbCheckpointResult {
+        busy: busy != 0,
+        wal_frames,
+        checkpointed_frames,
+        wal_bytes_after: file_len_or_zero(&wal_path)?,
+        checkpointed_at,
+    })
+}
+
 pub fn request_logs_clear_all(
     db: &db::Db,
 ) -> crate::shared::error::AppResult<ClearRequestLogsResult> {
@@ -102,6 +203,8 @@ pub fn request_logs_clear_all(
     let request_logs_deleted = tx
         .execute("DELETE FROM request_logs", [])
         .map_err(|e| db_err!("failed to clear request_logs: {e}"))?;
+    tx.execute("DELETE FROM request_log_rollups", [])
+        .map_err(|e| db_err!("failed to clear request_log_rollups: {e}"))?;
 
     tx.commit()
         .map_err(|e| db_err!("failed to commit transaction: {e}"))?;
@@ -123,7 +226,7 @@ pub fn request_logs_clear_all(
     })
 }
 
-pub fn app_data_reset(app: &tauri::AppHandle) -> crate::shared::error::AppResult<bool> {
+pub fn app_data_reset(app: &crate::AppHandle) -> crate::shared::error::AppResult<bool> {
     tracing::error!(
         "app data reset initiated (destructive operation: deleting settings and database)"
     );
@@ -0,0 +1,906 @@
+//! Usage: Declarative configuration apply (`config_apply_file`).
+//!
+//! A YAML or JSON document describes providers, sort modes, routing (active sort mode and
+//! fallback chain per cli) and settings. It is diffed against the current state and applied in a
+//! single DB transaction; dry runs execute the same statements and roll them back, so the report
+//! lists exactly what a real apply would change. Settings are written once the transaction has
+//! committed.
+//!
+//! ```yaml
+//! version: 1
+//! prune: false              # archive providers / delete sort modes that are not listed
+//! settings:
+//!   preferred_port: 37123
+//! providers:
+//!   - cli_key: claude
+//!     name: relay-a
+//!     base_urls: ["https://relay-a.example.com"]
+//!     api_key_env: RELAY_A_KEY
+//!     priority: 10
+//! sort_modes:
+//!   - name: work
+//!     providers:
+//!       claude: [relay-a]
+//! routing:
+//!   claude:
+//!     active: work          # `default` is the default provider ordering
+//!     fallbacks: [default]
+//! ```
+//!
+//! Fields left out keep their current value. OAuth providers can be listed (to keep them when
+//! pruning or to change their flags) but their endpoint and credentials stay managed in the app.
+
+use crate::providers::{self, ProviderBaseUrlMode};
+use crate::shared::cli_key::validate_cli_key;
+use crate::shared::error::db_err;
+use crate::shared::time::now_unix_seconds;
+use crate::{db, settings, sort_modes};
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+const CONFIG_FILE_VERSION: u32 = 1;
+const MAX_CONFIG_FILE_BYTES: u64 = 1024 * 1024;
+const DEFAULT_MODE_NAME: &str = "default";
+const MAX_NOTE_CHARS: usize = 500;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigDocument {
+    version: u32,
+    #[serde(default)]
+    prune: bool,
+    settings: Option<Map<String, Value>>,
+    providers: Option<Vec<ProviderDecl>>,
+    sort_modes: Option<Vec<SortModeDecl>>,
+    #[serde(default)]
+    routing: BTreeMap<String, RoutingDecl>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProviderDecl {
+    cli_key: String,
+    name: String,
+    base_urls: Option<Vec<String>>,
+    base_url_mode: Option<ProviderBaseUrlMode>,
+    api_key: Option<String>,
+    /// Name of an environment variable holding the API key, so files can be shared safely.
+    api_key_env: Option<String>,
+    enabled: Option<bool>,
+    priority: Option<i64>,
+    cost_multiplier: Option<f64>,
+    tags: Option<Vec<String>>,
+    note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SortModeDecl {
+    name: String,
+    strategy: Option<String>,
+    /// cli_key -> provider names in order; clis not listed keep their current order.
+    #[serde(default)]
+    providers: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RoutingDecl {
+    active: Option<String>,
+    fallbacks: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ConfigApplyChange {
+    /// `settings`, `providers`, `sort_modes` or `routing`.
+    pub section: String,
+    pub cli_key: Option<String>,
+    /// Setting key, provider / sort mode name, or `active` / `fallbacks` for routing.
+    pub key: String,
+    /// `create`, `update`, `archive` or `delete`.
+    pub action: String,
+    /// Changed fields of an update; values are left out so secrets never show up here.
+    pub fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ConfigApplyReport {
+    pub path: String,
+    pub dry_run: bool,
+    /// `true` once changes were committed; always `false` for dry runs.
+    pub applied: bool,
+    pub changes: Vec<ConfigApplyChange>,
+    /// Declared entries that already matched the current state.
+    pub unchanged: u32,
+}
+
+#[derive(Default)]
+struct Plan {
+    changes: Vec<ConfigApplyChange>,
+    unchanged: u32,
+}
+
+impl Plan {
+    fn record(
+        &mut self,
+        section: &str,
+        cli_key: Option<&str>,
+        key: &str,
+        action: &str,
+        fields: Vec<String>,
+    ) {
+        self.changes.push(ConfigApplyChange {
+            section: section.to_string(),
+            cli_key: cli_key.map(str::to_string),
+            key: key.to_string(),
+            action: action.to_string(),
+            fields,
+        });
+    }
+}
+
+fn invalid(context: &str, message: impl std::fmt::Display) -> crate::shared::error::AppError {
+    let message = message.to_string();
+    let message = message
+        .strip_prefix("SEC_INVALID_INPUT: ")
+        .unwrap_or(&message);
+    format!("SEC_INVALID_INPUT: {context}: {message}").into()
+}
+
+fn parse_document(path: &Path, text: &str) -> crate::shared::error::AppResult<ConfigDocument> {
+    let is_json = path
+        .extension()
+        .and_then(|v| v.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let doc: ConfigDocument = if is_json {
+        serde_json::from_str(text).map_err(|e| invalid("config file", e))?
+    } else {
+        serde_yaml::from_str(text).map_err(|e| invalid("config file", e))?
+    };
+    if doc.version != CONFIG_FILE_VERSION {
+        return Err(invalid(
+            "config file",
+            format!(
+                "unsupported version {} (expected {CONFIG_FILE_VERSION})",
+                doc.version
+            ),
+        ));
+    }
+    Ok(doc)
+}
+
+fn read_document(path: &Path) -> crate::shared::error::AppResult<ConfigDocument> {
+    let meta = std::fs::metadata(path)
+        .map_err(|e| format!("IMPORT_READ_FAILED: {}: {e}", path.to_string_lossy()))?;
+    if meta.len() > MAX_CONFIG_FILE_BYTES {
+        return Err(invalid(
+            "config file",
+            format!(
+                "too large ({} bytes, max {MAX_CONFIG_FILE_BYTES})",
+                meta.len()
+            ),
+        ));
+    }
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("IMPORT_READ_FAILED: {}: {e}", path.to_string_lossy()))?;
+    parse_document(path, &text)
+}
+
+/// Numbers compare by value so `1` in YAML matches a stored `1.0`.
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64() == y.as_f64(),
+        _ => a == b,
+    }
+}
+
+/// Merges the declared keys over `current`; returns the merged document when anything changed.
+fn plan_settings(
+    current: Map<String, Value>,
+    declared: &Map<String, Value>,
+    plan: &mut Plan,
+) -> crate::shared::error::AppResult<Option<Value>> {
+    let mut merged = current;
+    let mut changed = false;
+    for (key, value) in declared {
+        if key == "schema_version" {
+            return Err(invalid("settings", "schema_version is managed by the app"));
+        }
+        let Some(existing) = merged.get(key) else {
+            return Err(invalid("settings", format!("unknown setting '{key}'")));
+        };
+        if same_value(existing, value) {
+            plan.unchanged += 1;
+            continue;
+        }
+        plan.record("settings", None, key, "update", vec![key.clone()]);
+        merged.insert(key.clone(), value.clone());
+        changed = true;
+    }
+    if !changed {
+        return Ok(None);
+    }
+
+    let merged = Value::Object(merged);
+    serde_json::from_value::<settings::AppSettings>(merged.clone())
+        .map_err(|e| invalid("settings", e))?;
+    Ok(Some(merged))
+}
+
+struct ProviderRow {
+    id: i64,
+    auth_mode: String,
+    base_urls: Vec<String>,
+    base_url_mode: String,
+    api_key: String,
+    enabled: bool,
+    priority: i64,
+    cost_multiplier: f64,
+    tags: Vec<String>,
+    note: String,
+    archived: bool,
+}
+
+fn load_provider(
+    conn: &Connection,
+    cli_key: &str,
+    name: &str,
+) -> crate::shared::error::AppResult<Option<ProviderRow>> {
+    conn.query_row(
+        r#"
+SELECT
+  id,
+  auth_mode,
+  base_url,
+  base_urls_json,
+  base_url_mode,
+  api_key_plaintext,
+  enabled,
+  priority,
+  cost_multiplier,
+  tags_json,
+  note,
+  archived_at
+FROM providers
+WHERE cli_key = ?1 AND name = ?2
+"#,
+        params![cli_key, name],
+        |row| {
+            let base_url: String = row.get("base_url")?;
+            let base_urls_json: String = row.get("base_urls_json")?;
+            let mut base_urls: Vec<String> =
+                serde_json::from_str(&base_urls_json).unwrap_or_default();
+            if base_urls.is_empty() && !base_url.is_empty() {
+                base_urls.push(base_url);
+            }
+            let tags_json: String = row.get("tags_json")?;
+            Ok(ProviderRow {
+                id: row.get("id")?,
+                auth_mode: row.get("auth_mode")?,
+                base_urls,
+                base_url_mode: row.get("base_url_mode")?,
+                api_key: row.get("api_key_plaintext")?,
+                enabled: row.get::<_, i64>("enabled")? != 0,
+                priority: row.get("priority")?,
+                cost_multiplier: row.get("cost_multiplier")?,
+                tags: serde_json::from_str(&tags_json).unwrap_or_default(),
+                note: row.get("note")?,
+                archived: row.get::<_, Option<i64>>("archived_at")?.is_some(),
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| db_err!("failed to query provider: {e}"))
+}
+
+fn resolve_api_key(
+    decl: &ProviderDecl,
+    context: &str,
+) -> crate::shared::error::AppResult<Option<String>> {
+    match (&decl.api_key, &decl.api_key_env) {
+        (Some(_), Some(_)) => Err(invalid(context, "set either api_key or api_key_env")),
+        (Some(key), None) => Ok(Some(key.trim().to_string()).filter(|v| !v.is_empty())),
+        (None, Some(var)) => match std::env::var(var.trim()) {
+            Ok(key) if !key.trim().is_empty() => Ok(Some(key.trim().to_string())),
+            _ => Err(invalid(
+                context,
+                format!("environment variable {} is not set", var.trim()),
+            )),
+        },
+        (None, None) => Ok(None),
+    }
+}
+
+fn apply_providers(
+    tx: &Connection,
+    decls: &[ProviderDecl],
+    prune: bool,
+    plan: &mut Plan,
+) -> crate::shared::error::AppResult<()> {
+    let now = now_unix_seconds();
+    let mut declared: HashSet<(String, String)> = HashSet::new();
+
+    for (idx, decl) in decls.iter().enumerate() {
+        let cli_key = decl.cli_key.trim();
+        let name = decl.name.trim();
+        let context = format!("providers[{idx}] ({cli_key}/{name})");
+        validate_cli_key(cli_key).map_err(|e| invalid(&context, e))?;
+        if name.is_empty() {
+            return Err(invalid(&context, "name is required"));
+        }
+        if !declared.insert((cli_key.to_string(), name.to_string())) {
+            return Err(invalid(&context, "declared more than once"));
+        }
+
+        let api_key = resolve_api_key(decl, &context)?;
+        let base_urls = match &decl.base_urls {
+            Some(urls) => Some(
+                providers::normalize_base_urls(urls.clone()).map_err(|e| invalid(&context, e))?,
+            ),
+            None => None,
+        };
+        if let Some(priority) = decl.priority {
+            if !(0..=1000).contains(&priority) {
+                return Err(invalid(&context, "priority must be within [0, 1000]"));
+            }
+        }
+        if let Some(cost) = decl.cost_multiplier {
+            if !cost.is_finite() || !(0.0..=1000.0).contains(&cost) {
+                return Err(invalid(
+                    &context,
+                    "cost_multiplier must be within [0, 1000]",
+                ));
+            }
+        }
+        let note = decl.note.as_deref().map(str::trim);
+        if note.is_some_and(|v| v.chars().count() > MAX_NOTE_CHARS) {
+            return Err(invalid(
+                &context,
+                format!("note must be at most {MAX_NOTE_CHARS} characters"),
+            ));
+        }
+        let tags = decl.tags.clone().map(providers::normalize_tags);
+
+        let Some(existing) = load_provider(tx, cli_key, name)? else {
+            let Some(base_urls) = base_urls else {
+                return Err(invalid(&context, "base_urls is required for new providers"));
+            };
+            let Some(api_key) = api_key else {
+                return Err(invalid(
+                    &context,
+                    "api_key or api_key_env is required for new providers",
+                ));
+            };
+            let base_urls_json =
+                serde_json::to_string(&base_urls).map_err(|e| format!("SYSTEM_ERROR: {e}"))?;
+            let tags_json = serde_json::to_string(&tags.unwrap_or_default())
+                .map_err(|e| format!("SYSTEM_ERROR: {e}"))?;
+            tx.execute(
+                r#"
+INSERT INTO providers(
+  cli_key,
+  name,
+  base_url,
+  base_urls_json,
+  base_url_mode,
+  auth_mode,
+  api_key_plaintext,
+  sort_order,
+  enabled,
+  priority,
+  cost_multiplier,
+  tags_json,
+  note,
+  created_at,
+  updated_at
+) VALUES (?1, ?2, ?3, ?4, ?5, 'api_key', ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?13)
+"#,
+                params![
+                    cli_key,
+                    name,
+                    base_urls[0],
+                    base_urls_json,
+                    decl.base_url_mode
+                        .unwrap_or(ProviderBaseUrlMode::Order)
+                        .as_str(),
+                    api_key,
+                    providers::next_sort_order(tx, cli_key)?,
+                    i64::from(decl.enabled.unwrap_or(true)),
+                    decl.priority.unwrap_or(providers::DEFAULT_PRIORITY),
+                    decl.cost_multiplier.unwrap_or(1.0),
+                    tags_json,
+                    note.unwrap_or(""),
+                    now
+                ],
+            )
+            .map_err(|e| db_err!("failed to insert provider: {e}"))?;
+            plan.record("providers", Some(cli_key), name, "create", Vec::new());
+            continue;
+        };
+
+        if existing.auth_mode == "oauth" && (base_urls.is_some() || api_key.is_some()) {
+            return Err(invalid(
+                &context,
+                "base_urls and api keys of OAuth providers are managed in the app",
+            ));
+        }
+
+        let mut fields = Vec::new();
+        let mut field = |name: &str, changed: bool| {
+            if changed {
+                fields.push(name.to_string());
+            }
+        };
+        let next_base_urls = base_urls.unwrap_or_else(|| existing.base_urls.clone());
+        field("base_urls", next_base_urls != existing.base_urls);
+        let next_base_url_mode = decl
+            .base_url_mode
+            .map(ProviderBaseUrlMode::as_str)
+            .unwrap_or(existing.base_url_mode.as_str());
+        field(
+            "base_url_mode",
+            next_base_url_mode != existing.base_url_mode,
+        );
+        let next_api_key = api_key.unwrap_or_else(|| existing.api_key.clone());
+        field("api_key", next_api_key != existing.api_key);
+        let next_enabled = decl.enabled.unwrap_or(existing.enabled);
+        field("enabled", next_enabled != existing.enabled);
+        let next_priority = decl.priority.unwrap_or(existing.priority);
+        field("priority", next_priority != existing.priority);
+        let next_cost = decl.cost_multiplier.unwrap_or(existing.cost_multiplier);
+        field("cost_multiplier", next_cost != existing.cost_multiplier);
+        let next_tags = tags.unwrap_or_else(|| existing.tags.clone());
+        field("tags", next_tags != existing.tags);
+        let next_note = note.unwrap_or(existing.note.as_str());
+        field("note", next_note != existing.note);
+        field("archived", existing.archived);
+
+        if fields.is_empty() {
+            plan.unchanged += 1;
+            continue;
+        }
+
+        let base_urls_json =
+            serde_json::to_string(&next_base_urls).map_err(|e| format!("SYSTEM_ERROR: {e}"))?;
+        let tags_json =
+            serde_json::to_string(&next_tags).map_err(|e| format!("SYSTEM_ERROR: {e}"))?;
+        tx.execute(
+            r#"
+UPDATE providers
+SET
+  base_url = ?1,
+  base_urls_json = ?2,
+  base_url_mode = ?3,
+  api_key_plaintext = ?4,
+  enabled = ?5,
+  priority = ?6,
+  cost_multiplier = ?7,
+  tags_json = ?8,
+  note = ?9,
+  archived_at = NULL,
+  updated_at = ?10
+WHERE id = ?11
+"#,
+            params![
+                next_base_urls.first().cloned().unwrap_or_default(),
+                base_urls_json,
+                next_base_url_mode,
+                next_api_key,
+                i64::from(next_enabled),
+                next_priority,
+                next_cost,
+                tags_json,
+                next_note,
+                now,
+                existing.id
+            ],
+        )
+        .map_err(|e| db_err!("failed to update provider: {e}"))?;
+        plan.record("providers", Some(cli_key), name, "update", fields);
+    }
+
+    if !prune {
+        return Ok(());
+    }
+
+    let cli_keys: HashSet<&str> = declared.iter().map(|(cli, _)| cli.as_str()).collect();
+    for cli_key in cli_keys {
+        let rows: Vec<(i64, String)> = {
+            let mut stmt = tx
+                .prepare_cached(
+                    "SELECT id, name FROM providers WHERE cli_key = ?1 AND archived_at IS NULL",
+                )
+                .map_err(|e| db_err!("failed to prepare provider prune query: {e}"))?;
+            let rows = stmt
+                .query_map(params![cli_key], |row| Ok((row.get(0)?, row.get(1)?)))
+                .map_err(|e| db_err!("failed to query providers: {e}"))?;
+            rows.collect::<Result<_, _>>()
+                .map_err(|e| db_err!("failed to read provider row: {e}"))?
+        };
+        for (id, name) in rows {
+            if declared.contains(&(cli_key.to_string(), name.clone())) {
+                continue;
+            }
+            // Same effect as `providers::archive`: history stays, routing forgets it.
+            tx.execute(
+                "UPDATE providers SET archived_at = ?1, enabled = 0, last_resort = 0, updated_at = ?1 WHERE id = ?2",
+                params![now, id],
+            )
+            .map_err(|e| db_err!("failed to archive provider: {e}"))?;
+            tx.execute(
+                "DELETE FROM sort_mode_providers WHERE provider_id = ?1",
+                params![id],
+            )
+            .map_err(|e| db_err!("failed to remove provider from sort modes: {e}"))?;
+            plan.record("providers", Some(cli_key), &name, "archive", Vec::new());
+        }
+    }
+    Ok(())
+}
+
+fn active_provider_id(
+    tx: &Connection,
+    cli_key: &str,
+    name: &str,
+) -> crate::shared::error::AppResult<Option<i64>> {
+    tx.query_row(
+        "SELECT id FROM providers WHERE cli_key = ?1 AND name = ?2 AND archived_at IS NULL",
+        params![cli_key, name.trim()],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(|e| db_err!("failed to query provider: {e}"))
+}
+
+fn mode_provider_ids(
+    tx: &Connection,
+    mode_id: i64,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<Vec<i64>> {
+    let mut stmt = tx
+        .prepare_cached(
+            "SELECT provider_id FROM sort_mode_providers WHERE mode_id = ?1 AND cli_key = ?2 ORDER BY sort_order ASC",
+        )
+        .map_err(|e| db_err!("failed to prepare sort_mode_providers query: {e}"))?;
+    let rows = stmt
+        .query_map(params![mode_id, cli_key], |row| row.get(0))
+        .map_err(|e| db_err!("failed to list sort_mode_providers: {e}"))?;
+    rows.collect::<Result<_, _>>()
+        .map_err(|e| db_err!("failed to read sort_mode_provider row: {e}"))
+}
+
+fn find_mode(
+    tx: &Connection,
+    name: &str,
+) -> crate::shared::error::AppResult<Option<(i64, String)>> {
+    tx.query_row(
+        "SELECT id, strategy FROM sort_modes WHERE name = ?1",
+        params![name],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .optional()
+    .map_err(|e| db_err!("failed to query sort_mode: {e}"))
+}
+
+fn apply_sort_modes(
+    tx: &Connection,
+    decls: &[SortModeDecl],
+    prune: bool,
+    plan: &mut Plan,
+) -> crate::shared::error::AppResult<()> {
+    let now = now_unix_seconds();
+    let mut declared: HashSet<String> = HashSet::new();
+
+    for (idx, decl) in decls.iter().enumerate() {
+        let context = format!("sort_modes[{idx}] ({})", decl.name.trim());
+        let name = sort_modes::validate_mode_name(&decl.name).map_err(|e| invalid(&context, e))?;
+        if !declared.insert(name.clone()) {
+            return Err(invalid(&context, "declared more than once"));
+        }
+        let strategy = decl
+            .strategy
+            .as_deref()
+            .map(sort_modes::validate_strategy)
+            .transpose()
+            .map_err(|e| invalid(&context, e))?;
+
+        let mut fields = Vec::new();
+        let (mode_id, created) = match find_mode(tx, &name)? {
+            Some((id, current_strategy)) => {
+                if let Some(strategy) = strategy.filter(|s| *s != current_strategy) {
+                    tx.execute(
+                        "UPDATE sort_modes SET strategy = ?1, updated_at = ?2 WHERE id = ?3",
+                        params![strategy, now, id],
+                    )
+                    .map_err(|e| db_err!("failed to update sort_mode strategy: {e}"))?;
+                    fields.push("strategy".to_string());
+                }
+                (id, false)
+            }
+            None => {
+                tx.execute(
+                    "INSERT INTO sort_modes(name, strategy, created_at, updated_at) VALUES (?1, ?2, ?3, ?3)",
+                    params![
+                        name,
+                        strategy.unwrap_or(sort_modes::SORT_MODE_STRATEGY_MANUAL),
+                        now
+                    ],
+                )
+                .map_err(|e| db_err!("failed to insert sort_mode: {e}"))?;
+                (tx.last_insert_rowid(), true)
+            }
+        };
+
+        for (cli_key, names) in &decl.providers {
+            let cli_key = cli_key.trim();
+            validate_cli_key(cli_key).map_err(|e| invalid(&context, e))?;
+            let mut ids = Vec::with_capacity(names.len());
+            for provider_name in names {
+                let id = active_provider_id(tx, cli_key, provider_name)?.ok_or_else(|| {
+                    invalid(
+                        &context,
+                        format!("unknown provider {cli_key}/{}", provider_name.trim()),
+                    )
+                })?;
+                if ids.contains(&id) {
+                    return Err(invalid(
+                        &context,
+                        format!("provider {cli_key}/{} listed twice", provider_name.trim()),
+                    ));
+                }
+                ids.push(id);
+            }
+            if mode_provider_ids(tx, mode_id, cli_key)? != ids {
+                sort_modes::write_mode_providers(tx, mode_id, cli_key, &ids)?;
+                fields.push(format!("providers.{cli_key}"));
+            }
+        }
+
+        if created {
+            plan.record("sort_modes", None, &name, "create", fields);
+        } else if fields.is_empty() {
+            plan.unchanged += 1;
+        } else {
+            plan.record("sort_modes", None, &name, "update", fields);
+        }
+    }
+
+    if !prune {
+        return Ok(());
+    }
+
+    let rows: Vec<(i64, String)> = {
+        let mut stmt = tx
+            .prepare_cached("SELECT id, name FROM sort_modes ORDER BY id ASC")
+            .map_err(|e| db_err!("failed to prepare sort_modes query: {e}"))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| db_err!("failed to list sort_modes: {e}"))?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|e| db_err!("failed to read sort_mode row: {e}"))?
+    };
+    for (id, name) in rows {
+        if declared.contains(&name) {
+            continue;
+        }
+        tx.execute("DELETE FROM sort_modes WHERE id = ?1", params![id])
+            .map_err(|e| db_err!("failed to delete sort_mode: {e}"))?;
+        plan.record("sort_modes", None, &name, "delete", Vec::new());
+    }
+    Ok(())
+}
+
+/// `default` (or an empty name) is the default provider ordering.
+fn resolve_mode(
+    tx: &Connection,
+    name: &str,
+    context: &str,
+) -> crate::shared::error::AppResult<Option<i64>> {
+    let name = name.trim();
+    if name.is_empty() || name.eq_ignore_ascii_case(DEFAULT_MODE_NAME) {
+        return Ok(None);
+    }
+    find_mode(tx, name)?
+        .map(|(id, _)| Some(id))
+        .ok_or_else(|| invalid(context, format!("unknown sort mode '{name}'")))
+}
+
+fn apply_routing(
+    tx: &Connection,
+    routing: &BTreeMap<String, RoutingDecl>,
+    plan: &mut Plan,
+) -> crate::shared::error::AppResult<()> {
+    let now = now_unix_seconds();
+    for (cli_key, decl) in routing {
+        let cli_key = cli_key.trim();
+        let context = format!("routing.{cli_key}");
+        validate_cli_key(cli_key).map_err(|e| invalid(&context, e))?;
+
+        if let Some(active) = &decl.active {
+            let next = resolve_mode(tx, active, &context)?;
+            let current: Option<i64> = tx
+                .query_row(
+                    "SELECT mode_id FROM sort_mode_active WHERE cli_key = ?1",
+                    params![cli_key],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(|e| db_err!("failed to query sort_mode_active: {e}"))?
+                .flatten();
+            if current == next {
+                plan.unchanged += 1;
+            } else {
+                tx.execute(
+                    r#"
+INSERT INTO sort_mode_active(cli_key, mode_id, updated_at) VALUES (?1, ?2, ?3)
+ON CONFLICT(cli_key) DO UPDATE SET
+  mode_id = excluded.mode_id,
+  updated_at = excluded.updated_at
+"#,
+                    params![cli_key, next, now],
+                )
+                .map_err(|e| db_err!("failed to upsert sort_mode_active: {e}"))?;
+                plan.record("routing", Some(cli_key), "active", "update", Vec::new());
+            }
+        }
+
+        if let Some(fallbacks) = &decl.fallbacks {
+            let mut next = Vec::with_capacity(fallbacks.len());
+            for name in fallbacks {
+                let mode_id = resolve_mode(tx, name, &context)?;
+                if next.contains(&mode_id) {
+                    return Err(invalid(
+                        &context,
+                        format!("fallback '{}' listed twice", name.trim()),
+                    ));
+                }
+                next.push(mode_id);
+            }
+            if sort_modes::read_fallback_chain(tx, cli_key)? == next {
+                plan.unchanged += 1;
+            } else {
+                sort_modes::write_fallback_chain(tx, cli_key, &next)?;
+                plan.record("routing", Some(cli_key), "fallbacks", "update", Vec::new());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn apply_tables(
+    tx: &Connection,
+    doc: &ConfigDocument,
+    plan: &mut Plan,
+) -> crate::shared::error::AppResult<()> {
+    if let Some(decls) = &doc.providers {
+        apply_providers(tx, decls, doc.prune, plan)?;
+    }
+    if let Some(decls) = &doc.sort_modes {
+        apply_sort_modes(tx, decls, doc.prune, plan)?;
+    }
+    apply_routing(tx, &doc.routing, plan)
+}
+
+pub fn config_apply_file<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    db: &db::Db,
+    path: &str,
+    dry_run: bool,
+) -> crate::shared::error::AppResult<ConfigApplyReport> {
+    let path = Path::new(path.trim());
+    let doc = read_document(path)?;
+    let mut plan = Plan::default();
+
+    let next_settings = match &doc.settings {
+        Some(declared) => {
+            let current = match serde_json::to_value(settings::read(app)?) {
+                Ok(Value::Object(map)) => map,
+                _ => return Err("SYSTEM_ERROR: failed to serialize settings".into()),
+            };
+            plan_settings(current, declared, &mut plan)?
+        }
+        None => None,
+    };
+
+    let mut conn = db.open_connection()?;
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| db_err!("failed to start config apply transaction: {e}"))?;
+    apply_tables(&tx, &doc, &mut plan)?;
+
+    let applied = !dry_run && !plan.changes.is_empty();
+    if applied {
+        tx.commit()
+            .map_err(|e| db_err!("failed to commit config apply: {e}"))?;
+        if let Some(next) = &next_settings {
+            settings::replace_from_json(app, next).map_err(|e| {
+                format!("config tables applied but settings were not ({e}); re-run to retry")
+            })?;
+        }
+    } else {
+        tx.rollback()
+            .map_err(|e| db_err!("failed to roll back config apply: {e}"))?;
+    }
+
+    if applied {
+        tracing::info!(
+            path = %path.display(),
+            changes = plan.changes.len(),
+            "declarative config applied"
+        );
+    }
+
+    Ok(ConfigApplyReport {
+        path: path.to_string_lossy().to_string(),
+        dry_run,
+        applied,
+        changes: plan.changes,
+        unchanged: plan.unchanged,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_yaml_and_json_documents_and_rejects_unknown_fields() {
+        let yaml = r#"
+version: 1
+providers:
+  - cli_key: claude
+    name: relay-a
+    base_urls: [https://relay-a.example.com]
+    base_url_mode: ping
+routing:
+  claude:
+    active: default
+"#;
+        let doc = parse_document(Path::new("hub.yaml"), yaml).expect("yaml");
+        let providers = doc.providers.expect("providers");
+        assert_eq!(providers[0].base_url_mode, Some(ProviderBaseUrlMode::Ping));
+        assert_eq!(doc.routing["claude"].active.as_deref(), Some("default"));
+
+        let json = r#"{"version":1,"sort_modes":[{"name":"work"}]}"#;
+        let doc = parse_document(Path::new("hub.json"), json).expect("json");
+        assert_eq!(doc.sort_modes.map(|m| m.len()), Some(1));
+
+        let err = parse_document(Path::new("hub.yaml"), "version: 1\nprovider: []\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("unknown field"), "{err}");
+        assert!(parse_document(Path::new("hub.yaml"), "version: 2\n").is_err());
+    }
+
+    #[test]
+    fn settings_plan_merges_declared_keys_only() {
+        let current = match serde_json::to_value(settings::AppSettings::default()).unwrap() {
+            Value::Object(map) => map,
+            _ => unreachable!(),
+        };
+        let port = current["preferred_port"].as_u64().unwrap();
+
+        let mut plan = Plan::default();
+        let declared: Map<String, Value> =
+            serde_json::from_value(serde_json::json!({ "preferred_port": port })).unwrap();
+        assert!(plan_settings(current.clone(), &declared, &mut plan)
+            .unwrap()
+            .is_none());
+        assert_eq!(plan.unchanged, 1);
+
+        let declared: Map<String, Value> =
+            serde_json::from_value(serde_json::json!({ "preferred_port": port + 1 })).unwrap();
+        let merged = plan_settings(current.clone(), &declared, &mut plan)
+            .unwrap()
+            .expect("changed");
+        assert_eq!(merged["preferred_port"], port + 1);
+        assert_eq!(plan.changes[0].key, "preferred_port");
+
+        let declared: Map<String, Value> =
+            serde_json::from_value(serde_json::json!({ "no_such_setting": true })).unwrap();
+        assert!(plan_settings(current, &declared, &mut plan).is_err());
+    }
+}
@@ -0,0 +1,842 @@
+//! Usage: Encrypted configuration snapshot (export / import) for disaster recovery.
+//!
+//! An archive holds settings.json plus the configuration tables (providers, sort modes, prompts,
+//! MCP servers, skills metadata, workspaces) as JSON rows. Request logs and other history are not
+//! part of it.
+//!
+//! File layout: `MAGIC | pbkdf2 iterations (u32 BE) | salt | nonce | AES-256-GCM(gzip(json))`.
+//! The header is bound as AEAD associated data, so tampering with it fails decryption.
+//!
+//! Older archives import cleanly into newer app versions: settings go through the regular
+//! settings.json migrations (keyed by their `schema_version`), and table rows are inserted by
+//! column name, so columns added since the export fall back to their schema defaults.
+
+use crate::shared::error::db_err;
+use crate::shared::time::now_unix_seconds;
+use crate::{app_paths, db, settings};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use rand::RngCore;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{Connection, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{Read, Write};
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MAGIC: &[u8; 8] = b"AIOCFG01";
+const SALT_LEN: usize = 16;
+const HEADER_LEN: usize = MAGIC.len() + 4 + SALT_LEN;
+const PBKDF2_ITERATIONS: u32 = 210_000;
+const MAX_PBKDF2_ITERATIONS: u32 = 10_000_000;
+const MIN_PASSWORD_CHARS: usize = 8;
+const MAX_ARCHIVE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Bump when the payload layout changes; `migrate_payload` must upgrade every older version.
+pub const CONFIG_ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+const BACKUP_DIR_NAME: &str = "config-import-backups";
+const KEEP_MAX_BACKUPS: usize = 10;
+
+/// Archived tables, parents before children (import inserts in this order).
+const ARCHIVE_TABLES: &[(&str, &str)] = &[
+    ("providers", "providers"),
+    ("providers", "provider_dns_options"),
+    ("providers", "provider_maintenance_windows"),
+    ("sort_modes", "sort_modes"),
+    ("sort_modes", "sort_mode_providers"),
+    ("sort_modes", "sort_mode_active"),
+    ("sort_modes", "sort_mode_fallbacks"),
+    ("sort_modes", "sort_mode_schedules"),
+    ("sort_modes", "sort_mode_schedule_rules"),
+    ("prompts", "prompts"),
+    ("mcp_servers", "mcp_servers"),
+    ("skills", "skill_repos"),
+    ("skills", "skills"),
+    ("workspaces", "workspaces"),
+    ("workspaces", "workspace_active"),
+    ("workspaces", "workspace_mcp_enabled"),
+    ("workspaces", "workspace_skill_enabled"),
+];
+
+/// Provider credential columns; blanked on export unless secrets are included.
+const PROVIDER_SECRET_COLUMNS: &[&str] = &[
+    "api_key_plaintext",
+    "oauth_access_token",
+    "oauth_refresh_token",
+    "oauth_id_token",
+    "oauth_client_secret",
+];
+
+/// Orphan cleanup passes after import (each pass may orphan rows one level further down).
+const MAX_ORPHAN_PASSES: usize = 8;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ConfigArchiveTableCount {
+    pub section: String,
+    pub table: String,
+    pub rows: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct ConfigArchiveManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    /// `schema_version` of the archived settings.json.
+    pub settings_schema_version: u32,
+    /// SQLite `user_version` of the exporting database.
+    pub db_schema_version: i64,
+    pub created_at: i64,
+    pub include_secrets: bool,
+    pub tables: Vec<ConfigArchiveTableCount>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ConfigExportResult {
+    pub path: String,
+    pub bytes: u64,
+    pub manifest: ConfigArchiveManifest,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ConfigImportResult {
+    pub manifest: ConfigArchiveManifest,
+    /// Encrypted snapshot of the configuration replaced by this import (same password).
+    pub backup_path: String,
+    pub tables: Vec<ConfigArchiveTableCount>,
+    /// Rows outside the archive removed because they referenced replaced records.
+    pub orphans_removed: u32,
+}
+
+type ArchiveRow = Map<String, Value>;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ConfigArchivePayload {
+    manifest: ConfigArchiveManifest,
+    settings: Value,
+    tables: BTreeMap<String, Vec<ArchiveRow>>,
+}
+
+fn validate_password(password: &str) -> crate::shared::error::AppResult<()> {
+    if password.chars().count() < MIN_PASSWORD_CHARS {
+        return Err(format!(
+            "SEC_INVALID_INPUT: archive password must be at least {MIN_PASSWORD_CHARS} characters"
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn derive_key(password: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let iterations = NonZeroU32::new(iterations).unwrap_or(NonZeroU32::MIN);
+    let mut key = [0u8; 32];
+    ring::pbkdf2::derive(
+        ring::pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        password.as_bytes(),
+        &mut key,
+    );
+    key
+}
+
+fn aead_key(password: &str, salt: &[u8], iterations: u32) -> LessSafeKey {
+    let key = derive_key(password, salt, iterations);
+    // AES-256 takes exactly 32 key bytes, which `derive_key` always produces.
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &key).expect("32-byte AES-256 key"))
+}
+
+fn seal(plain: &[u8], password: &str, iterations: u32) -> crate::shared::error::AppResult<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let mut out = Vec::with_capacity(HEADER_LEN + NONCE_LEN + plain.len() + 16);
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&iterations.to_be_bytes());
+    out.extend_from_slice(&salt);
+
+    let mut in_out = plain.to_vec();
+    aead_key(password, &salt, iterations)
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(&out[..HEADER_LEN]),
+            &mut in_out,
+        )
+        .map_err(|_| "CONFIG_ARCHIVE_ENCRYPT_FAILED: failed to encrypt archive".to_string())?;
+
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&in_out);
+    Ok(out)
+}
+
+fn open(sealed: &[u8], password: &str) -> crate::shared::error::AppResult<Vec<u8>> {
+    if sealed.len() < HEADER_LEN + NONCE_LEN || &sealed[..MAGIC.len()] != MAGIC {
+        return Err("SEC_INVALID_INPUT: not an aio-coding-hub config archive".into());
+    }
+    let mut iterations_bytes = [0u8; 4];
+    iterations_bytes.copy_from_slice(&sealed[MAGIC.len()..MAGIC.len() + 4]);
+    let iterations = u32::from_be_bytes(iterations_bytes);
+    if iterations == 0 || iterations > MAX_PBKDF2_ITERATIONS {
+        return Err("SEC_INVALID_INPUT: corrupted config archive header".into());
+    }
+    let salt = &sealed[MAGIC.len() + 4..HEADER_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce.copy_from_slice(&sealed[HEADER_LEN..HEADER_LEN + NONCE_LEN]);
+
+    let mut in_out = sealed[HEADER_LEN + NONCE_LEN..].to_vec();
+    let plain = aead_key(password, salt, iterations)
+        .open_in_place(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(&sealed[..HEADER_LEN]),
+            &mut in_out,
+        )
+        .map_err(|_| {
+            "CONFIG_ARCHIVE_DECRYPT_FAILED: wrong password or corrupted archive".to_string()
+        })?;
+    Ok(plain.to_vec())
+}
+
+fn encode_payload(
+    payload: &ConfigArchivePayload,
+    password: &str,
+    iterations: u32,
+) -> crate::shared::error::AppResult<Vec<u8>> {
+    let json = serde_json::to_vec(payload)
+        .map_err(|e| format!("failed to serialize config archive: {e}"))?;
+    let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder
+        .write_all(&json)
+        .map_err(|e| format!("failed to compress config archive: {e}"))?;
+    let compressed = encoder
+        .finish()
+        .map_err(|e| format!("failed to compress config archive: {e}"))?;
+    seal(&compressed, password, iterations)
+}
+
+fn decode_payload(
+    sealed: &[u8],
+    password: &str,
+) -> crate::shared::error::AppResult<ConfigArchivePayload> {
+    let compressed = open(sealed, password)?;
+    let mut json = Vec::new();
+    GzDecoder::new(compressed.as_slice())
+        .take(MAX_ARCHIVE_BYTES * 8)
+        .read_to_end(&mut json)
+        .map_err(|e| format!("SEC_INVALID_INPUT: corrupted config archive: {e}"))?;
+    let payload: ConfigArchivePayload = serde_json::from_slice(&json)
+        .map_err(|e| format!("SEC_INVALID_INPUT: invalid config archive payload: {e}"))?;
+    migrate_payload(payload)
+}
+
+/// Upgrades payloads written by older archive formats to the current layout.
+fn migrate_payload(
+    payload: ConfigArchivePayload,
+) -> crate::shared::error::AppResult<ConfigArchivePayload> {
+    let version = payload.manifest.format_version;
+    if version == 0 || version > CONFIG_ARCHIVE_FORMAT_VERSION {
+        return Err(format!(
+            "SEC_INVALID_INPUT: config archive format_version={version} is not supported by this app version (max {CONFIG_ARCHIVE_FORMAT_VERSION}); upgrade the app first"
+        )
+        .into());
+    }
+    if !payload.settings.is_object() {
+        return Err("SEC_INVALID_INPUT: config archive settings must be an object".into());
+    }
+    Ok(payload)
+}
+
+fn sql_to_json(value: SqlValue) -> Value {
+    match value {
+        SqlValue::Null => Value::Null,
+        SqlValue::Integer(v) => Value::from(v),
+        SqlValue::Real(v) => serde_json::Number::from_f64(v)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        SqlValue::Text(v) => Value::String(v),
+        // Archived tables hold no BLOB columns; keep the row importable if one appears.
+        SqlValue::Blob(v) => Value::String(String::from_utf8_lossy(&v).to_string()),
+    }
+}
+
+fn json_to_sql(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(v) => SqlValue::Integer(i64::from(*v)),
+        Value::Number(v) => match v.as_i64() {
+            Some(int) => SqlValue::Integer(int),
+            None => SqlValue::Real(v.as_f64().unwrap_or(0.0)),
+        },
+        Value::String(v) => SqlValue::Text(v.clone()),
+        other => SqlValue::Text(other.to_string()),
+    }
+}
+
+fn table_columns(conn: &Connection, table: &str) -> crate::shared::error::AppResult<Vec<String>> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({table})"))
+        .map_err(|e| db_err!("failed to prepare table_info for {table}: {e}"))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| db_err!("failed to query table_info for {table}: {e}"))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| db_err!("failed to read table_info for {table}: {e}"))?;
+    Ok(columns)
+}
+
+fn dump_table(conn: &Connection, table: &str) -> crate::shared::error::AppResult<Vec<ArchiveRow>> {
+    // Missing tables (not yet created on this install) export as empty.
+    if table_columns(conn, table)?.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = conn
+        .prepare(&format!("SELECT * FROM {table} ORDER BY rowid"))
+        .map_err(|e| db_err!("failed to prepare export for {table}: {e}"))?;
+    let names: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+    let mut rows = stmt
+        .query([])
+        .map_err(|e| db_err!("failed to export {table}: {e}"))?;
+
+    let mut out = Vec::new();
+    while let Some(row) = rows
+        .next()
+        .map_err(|e| db_err!("failed to read {table} row: {e}"))?
+    {
+        let mut item = ArchiveRow::new();
+        for (idx, name) in names.iter().enumerate() {
+            let value: SqlValue = row
+                .get(idx)
+                .map_err(|e| db_err!("failed to read {table}.{name}: {e}"))?;
+            item.insert(name.clone(), sql_to_json(value));
+        }
+        out.push(item);
+    }
+    Ok(out)
+}
+
+fn redact_provider_secrets(rows: &mut [ArchiveRow]) {
+    for row in rows {
+        for column in PROVIDER_SECRET_COLUMNS {
+            if let Some(value) = row.get_mut(*column) {
+                // `api_key_plaintext` is NOT NULL.
+                *value = if *column == "api_key_plaintext" {
+                    Value::String(String::new())
+                } else {
+                    Value::Null
+                };
+            }
+        }
+    }
+}
+
+fn user_version(conn: &Connection) -> crate::shared::error::AppResult<i64> {
+    conn.pragma_query_value(None, "user_version", |row| row.get(0))
+        .map_err(|e| db_err!("failed to read sqlite user_version: {e}"))
+}
+
+fn build_payload<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    conn: &Connection,
+    include_secrets: bool,
+) -> crate::shared::error::AppResult<ConfigArchivePayload> {
+    let current = settings::read(app)?;
+    let settings_json =
+        serde_json::to_value(&current).map_err(|e| format!("failed to serialize settings: {e}"))?;
+
+    let mut tables = BTreeMap::new();
+    let mut counts = Vec::with_capacity(ARCHIVE_TABLES.len());
+    for (section, table) in ARCHIVE_TABLES {
+        let mut rows = dump_table(conn, table)?;
+        if *table == "providers" && !include_secrets {
+            redact_provider_secrets(&mut rows);
+        }
+        counts.push(ConfigArchiveTableCount {
+            section: section.to_string(),
+            table: table.to_string(),
+            rows: rows.len() as u32,
+        });
+        tables.insert(table.to_string(), rows);
+    }
+
+    Ok(ConfigArchivePayload {
+        manifest: ConfigArchiveManifest {
+            format_version: CONFIG_ARCHIVE_FORMAT_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            settings_schema_version: current.schema_version,
+            db_schema_version: user_version(conn)?,
+            created_at: now_unix_seconds(),
+            include_secrets,
+            tables: counts,
+        },
+        settings: settings_json,
+        tables,
+    })
+}
+
+pub fn config_export(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    path: &str,
+    password: &str,
+    include_secrets: bool,
+) -> crate::shared::error::AppResult<ConfigExportResult> {
+    validate_password(password)?;
+    let path = Path::new(path.trim());
+    if path.as_os_str().is_empty() {
+        return Err("SEC_INVALID_INPUT: export path is required".into());
+    }
+
+    let conn = db.open_connection()?;
+    let payload = build_payload(app, &conn, include_secrets)?;
+    let bytes = encode_payload(&payload, password, PBKDF2_ITERATIONS)?;
+    crate::shared::fs::write_file_atomic(path, &bytes)?;
+
+    tracing::info!(
+        path = %path.display(),
+        bytes = bytes.len(),
+        include_secrets,
+        "config archive exported"
+    );
+
+    Ok(ConfigExportResult {
+        path: path.to_string_lossy().to_string(),
+        bytes: bytes.len() as u64,
+        manifest: payload.manifest,
+    })
+}
+
+fn backups_root<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> crate::shared::error::AppResult<PathBuf> {
+    Ok(app_paths::app_data_dir(app)?.join(BACKUP_DIR_NAME))
+}
+
+/// Oldest backup files beyond `keep` (names sort chronologically: `config.<unix_ms>.aiocfg`).
+fn backups_to_prune(mut names: Vec<String>, keep: usize) -> Vec<String> {
+    names.sort_by_key(|name| {
+        name.strip_prefix("config.")
+            .and_then(|rest| rest.strip_suffix(".aiocfg"))
+            .and_then(|ts| ts.parse::<i64>().ok())
+            .unwrap_or(0)
+    });
+    let excess = names.len().saturating_sub(keep);
+    names.truncate(excess);
+    names
+}
+
+fn write_backup(dir: &Path, sealed: &[u8]) -> crate::shared::error::AppResult<PathBuf> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("failed to create config import backup dir: {e}"))?;
+    let now_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let path = dir.join(format!("config.{now_ms}.aiocfg"));
+    crate::shared::fs::write_file_atomic(&path, sealed)?;
+
+    let names: Vec<String> = std::fs::read_dir(dir)
+        .map_err(|e| format!("failed to read config import backup dir: {e}"))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with("config.") && name.ends_with(".aiocfg"))
+        .collect();
+    for name in backups_to_prune(names, KEEP_MAX_BACKUPS) {
+        let _ = std::fs::remove_file(dir.join(name));
+    }
+    Ok(path)
+}
+
+/// Current provider credentials by id, used when the archive was exported without secrets.
+fn current_provider_secrets(
+    conn: &Connection,
+    columns: &HashSet<String>,
+) -> crate::shared::error::AppResult<HashMap<i64, ArchiveRow>> {
+    let secret_columns: Vec<&str> = PROVIDER_SECRET_COLUMNS
+        .iter()
+        .copied()
+        .filter(|column| columns.contains(*column))
+        .collect();
+    let mut out = HashMap::new();
+    if secret_columns.is_empty() {
+        return Ok(out);
+    }
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT id, {} FROM providers",
+            secret_columns.join(", ")
+        ))
+        .map_err(|e| db_err!("failed to prepare provider secrets query: {e}"))?;
+    let mut rows = stmt
+        .query([])
+        .map_err(|e| db_err!("failed to query provider secrets: {e}"))?;
+    while let Some(row) = rows
+        .next()
+        .map_err(|e| db_err!("failed to read provider secrets: {e}"))?
+    {
+        let id: i64 = row
+            .get(0)
+            .map_err(|e| db_err!("invalid provider id: {e}"))?;
+        let mut item = ArchiveRow::new();
+        for (idx, column) in secret_columns.iter().enumerate() {
+            let value: SqlValue = row
+                .get(idx + 1)
+                .map_err(|e| db_err!("failed to read providers.{column}: {e}"))?;
+            item.insert(column.to_string(), sql_to_json(value));
+        }
+        out.insert(id, item);
+    }
+    Ok(out)
+}
+
+fn insert_rows(
+    conn: &Connection,
+    table: &str,
+    rows: &[ArchiveRow],
+    columns: &HashSet<String>,
+) -> crate::shared::error::AppResult<u32> {
+    let mut inserted = 0u32;
+    for row in rows {
+        // Only columns known to this schema; newer columns keep their defaults.
+        let names: Vec<&String> = row.keys().filter(|key| columns.contains(*key)).collect();
+        if names.is_empty() {
+            continue;
+        }
+        let sql = format!(
+            "INSERT INTO {table} ({}) VALUES ({})",
+            names
+                .iter()
+                .map(|name| name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            db::sql_placeholders(names.len())
+        );
+        let values: Vec<SqlValue> = names.iter().map(|name| json_to_sql(&row[*name])).collect();
+        conn.execute(&sql, rusqlite::params_from_iter(values))
+            .map_err(|e| db_err!("failed to import {table} row: {e}"))?;
+        inserted += 1;
+    }
+    Ok(inserted)
+}
+
+/// Deletes rows whose foreign keys point at records that no longer exist.
+fn remove_foreign_key_orphans(conn: &Connection) -> crate::shared::error::AppResult<u32> {
+    let mut removed = 0u32;
+    for _ in 0..MAX_ORPHAN_PASSES {
+        let orphans: Vec<(String, i64)> = {
+            let mut stmt = conn
+                .prepare("PRAGMA foreign_key_check")
+                .map_err(|e| db_err!("failed to prepare foreign_key_check: {e}"))?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?))
+                })
+                .map_err(|e| db_err!("failed to run foreign_key_check: {e}"))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| db_err!("failed to read foreign_key_check: {e}"))?;
+            let mut unique: Vec<(String, i64)> = rows
+                .into_iter()
+                .filter_map(|(table, rowid)| rowid.map(|rowid| (table, rowid)))
+                .collect();
+            unique.sort();
+            unique.dedup();
+            unique
+        };
+        if orphans.is_empty() {
+            break;
+        }
+        for (table, rowid) in orphans {
+            let changed = conn
+                .execute(
+                    &format!("DELETE FROM \"{table}\" WHERE rowid = ?1"),
+                    [rowid],
+                )
+                .map_err(|e| db_err!("failed to remove orphan row from {table}: {e}"))?;
+            removed += changed as u32;
+        }
+    }
+    Ok(removed)
+}
+
+/// Replaces the archived tables inside one transaction.
+///
+/// Foreign keys are switched off meanwhile so deleting providers/workspaces does not cascade into
+/// history that is not part of the archive (ids are restored as exported); rows left dangling are
+/// removed afterwards.
+fn import_tables(
+    conn: &mut Connection,
+    payload: &ConfigArchivePayload,
+) -> crate::shared::error::AppResult<(Vec<ConfigArchiveTableCount>, u32)> {
+    conn.execute_batch("PRAGMA foreign_keys = OFF;")
+        .map_err(|e| db_err!("failed to disable foreign keys: {e}"))?;
+    let result = import_tables_tx(conn, payload);
+    let restored = conn.execute_batch("PRAGMA foreign_keys = ON;");
+    let out = result?;
+    restored.map_err(|e| db_err!("failed to re-enable foreign keys: {e}"))?;
+    Ok(out)
+}
+
+fn import_tables_tx(
+    conn: &mut Connection,
+    payload: &ConfigArchivePayload,
+) -> crate::shared::error::AppResult<(Vec<ConfigArchiveTableCount>, u32)> {
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| db_err!("failed to start config import transaction: {e}"))?;
+
+    let mut schema: HashMap<&str, HashSet<String>> = HashMap::new();
+    for (_, table) in ARCHIVE_TABLES {
+        let columns: HashSet<String> = table_columns(&tx, table)?.into_iter().collect();
+        if !columns.is_empty() {
+            schema.insert(*table, columns);
+        }
+    }
+
+    let kept_secrets = match schema.get("providers") {
+        Some(columns) if !payload.manifest.include_secrets => {
+            current_provider_secrets(&tx, columns)?
+        }
+        _ => HashMap::new(),
+    };
+
+    for (_, table) in ARCHIVE_TABLES.iter().rev() {
+        if schema.contains_key(table) {
+            tx.execute(&format!("DELETE FROM {table}"), [])
+                .map_err(|e| db_err!("failed to clear {table}: {e}"))?;
+        }
+    }
+
+    let mut counts = Vec::with_capacity(ARCHIVE_TABLES.len());
+    for (section, table) in ARCHIVE_TABLES {
+        let Some(columns) = schema.get(table) else {
+            continue;
+        };
+        let rows = payload.tables.get(*table).map(Vec::as_slice).unwrap_or(&[]);
+        let inserted = if *table == "providers" && !kept_secrets.is_empty() {
+            let merged: Vec<ArchiveRow> = rows
+                .iter()
+                .map(|row| {
+                    let mut row = row.clone();
+                    let secrets = row
+                        .get("id")
+                        .and_then(Value::as_i64)
+                        .and_then(|id| kept_secrets.get(&id));
+                    if let Some(secrets) = secrets {
+                        for (column, value) in secrets {
+                            row.insert(column.clone(), value.clone());
+                        }
+                    }
+                    row
+                })
+                .collect();
+            insert_rows(&tx, table, &merged, columns)?
+        } else {
+            insert_rows(&tx, table, rows, columns)?
+        };
+        counts.push(ConfigArchiveTableCount {
+            section: section.to_string(),
+            table: table.to_string(),
+            rows: inserted,
+        });
+    }
+
+    let orphans_removed = remove_foreign_key_orphans(&tx)?;
+
+    tx.commit()
+        .map_err(|e| db_err!("failed to commit config import: {e}"))?;
+    Ok((counts, orphans_removed))
+}
+
+pub fn config_import(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    path: &str,
+    password: &str,
+) -> crate::shared::error::AppResult<ConfigImportResult> {
+    validate_password(password)?;
+    let path = Path::new(path.trim());
+    let meta = std::fs::metadata(path)
+        .map_err(|e| format!("IMPORT_READ_FAILED: {}: {e}", path.to_string_lossy()))?;
+    if meta.len() > MAX_ARCHIVE_BYTES {
+        return Err(format!(
+            "SEC_INVALID_INPUT: config archive too large ({} bytes, max {MAX_ARCHIVE_BYTES})",
+            meta.len()
+        )
+        .into());
+    }
+    let sealed = std::fs::read(path)
+        .map_err(|e| format!("IMPORT_READ_FAILED: {}: {e}", path.to_string_lossy()))?;
+    let payload = decode_payload(&sealed, password)?;
+
+    let mut conn = db.open_connection()?;
+
+    // Snapshot what is about to be replaced, secrets included, under the same password.
+    let previous = build_payload(app, &conn, true)?;
+    let backup_path = write_backup(
+        &backups_root(app)?,
+        &encode_payload(&previous, password, PBKDF2_ITERATIONS)?,
+    )?;
+
+    let (tables, orphans_removed) = import_tables(&mut conn, &payload)?;
+    settings::replace_from_json(app, &payload.settings).map_err(|e| {
+        format!(
+            "config tables imported but settings were not applied ({e}); previous configuration saved to {}",
+            backup_path.display()
+        )
+    })?;
+
+    tracing::info!(
+        path = %path.display(),
+        format_version = payload.manifest.format_version,
+        app_version = %payload.manifest.app_version,
+        orphans_removed,
+        "config archive imported"
+    );
+
+    Ok(ConfigImportResult {
+        manifest: payload.manifest,
+        backup_path: backup_path.to_string_lossy().to_string(),
+        tables,
+        orphans_removed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload_with(
+        tables: BTreeMap<String, Vec<ArchiveRow>>,
+        include_secrets: bool,
+    ) -> ConfigArchivePayload {
+        ConfigArchivePayload {
+            manifest: ConfigArchiveManifest {
+                format_version: CONFIG_ARCHIVE_FORMAT_VERSION,
+                app_version: "0.0.0".to_string(),
+                settings_schema_version: 1,
+                db_schema_version: 30,
+                created_at: 0,
+                include_secrets,
+                tables: Vec::new(),
+            },
+            settings: serde_json::json!({ "schema_version": 1 }),
+            tables,
+        }
+    }
+
+    fn row(value: Value) -> ArchiveRow {
+        value.as_object().cloned().unwrap()
+    }
+
+    #[test]
+    fn sealed_payload_round_trips_and_rejects_wrong_password_or_newer_format() {
+        let mut tables = BTreeMap::new();
+        tables.insert(
+            "prompts".to_string(),
+            vec![row(serde_json::json!({ "id": 1, "name": "review" }))],
+        );
+        let payload = payload_with(tables, false);
+
+        let sealed = encode_payload(&payload, "correct horse", 1_000).unwrap();
+        assert!(sealed.starts_with(MAGIC));
+
+        let decoded = decode_payload(&sealed, "correct horse").unwrap();
+        assert_eq!(decoded.tables["prompts"][0]["name"], "review");
+
+        let err = decode_payload(&sealed, "wrong password").unwrap_err();
+        assert!(err.to_string().starts_with("CONFIG_ARCHIVE_DECRYPT_FAILED"));
+
+        let mut tampered = sealed.clone();
+        tampered[MAGIC.len() + 4] ^= 0xff;
+        assert!(decode_payload(&tampered, "correct horse").is_err());
+
+        let mut newer = payload_with(BTreeMap::new(), false);
+        newer.manifest.format_version = CONFIG_ARCHIVE_FORMAT_VERSION + 1;
+        let sealed = encode_payload(&newer, "correct horse", 1_000).unwrap();
+        let err = decode_payload(&sealed, "correct horse").unwrap_err();
+        assert!(err.to_string().contains("format_version"));
+
+        assert!(validate_password("short").is_err());
+    }
+
+    #[test]
+    fn import_replaces_tables_keeps_secrets_and_drops_orphans() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            r#"
+PRAGMA foreign_keys = ON;
+CREATE TABLE providers (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL,
+  api_key_plaintext TEXT NOT NULL,
+  priority INTEGER NOT NULL DEFAULT 100
+);
+CREATE TABLE provider_circuit_breakers (
+  provider_id INTEGER PRIMARY KEY,
+  state TEXT NOT NULL,
+  FOREIGN KEY(provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+INSERT INTO providers(id, name, api_key_plaintext) VALUES (1, 'old-a', 'sk-a'), (2, 'old-b', 'sk-b');
+INSERT INTO provider_circuit_breakers(provider_id, state) VALUES (1, 'open'), (2, 'open');
+"#,
+        )
+        .unwrap();
+
+        let mut tables = BTreeMap::new();
+        tables.insert(
+            "providers".to_string(),
+            vec![
+                // `legacy_column` no longer exists; `priority` is missing and takes its default.
+                row(serde_json::json!({
+                    "id": 1,
+                    "name": "restored-a",
+                    "api_key_plaintext": "",
+                    "legacy_column": true
+                })),
+                row(serde_json::json!({ "id": 3, "name": "new-c", "api_key_plaintext": "" })),
+            ],
+        );
+        let payload = payload_with(tables, false);
+
+        let (counts, orphans_removed) = import_tables(&mut conn, &payload).unwrap();
+        let providers = counts.iter().find(|c| c.table == "providers").unwrap();
+        assert_eq!(providers.rows, 2);
+        assert_eq!(orphans_removed, 1);
+
+        let rows: Vec<(i64, String, String, i64)> = conn
+            .prepare("SELECT id, name, api_key_plaintext, priority FROM providers ORDER BY id")
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                (1, "restored-a".to_string(), "sk-a".to_string(), 100),
+                (3, "new-c".to_string(), String::new(), 100),
+            ]
+        );
+
+        // Provider 1 kept its breaker (ids are restored as exported); provider 2's was orphaned.
+        let breakers: Vec<i64> = conn
+            .prepare("SELECT provider_id FROM provider_circuit_breakers")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(breakers, vec![1]);
+
+        let fk_enabled: i64 = conn
+            .pragma_query_value(None, "foreign_keys", |r| r.get(0))
+            .unwrap();
+        assert_eq!(fk_enabled, 1);
+    }
+}
@@ -0,0 +1,277 @@
+//! Usage: Ad-hoc SQL console (`db_query_readonly`) for answering questions without an export.
+//!
+//! Only a single `SELECT` (or `WITH … SELECT`) is accepted. Besides the keyword check, SQLite has
+//! to report the prepared statement as read-only, and it runs on its own connection opened with
+//! `SQLITE_OPEN_READ_ONLY` and `query_only`, so nothing typed here can write. Results are capped
+//! by row count and wall time (the statement is interrupted once the time limit passes).
+
+use crate::db;
+use crate::shared::error::{db_err, AppError, AppResult};
+use base64::Engine;
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, ErrorCode, OpenFlags};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+const DEFAULT_MAX_ROWS: u32 = 500;
+const MAX_ROWS_LIMIT: u32 = 5_000;
+const MAX_SQL_LEN: usize = 20_000;
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+const BUSY_TIMEOUT: Duration = Duration::from_millis(2000);
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct DbQueryResult {
+    pub columns: Vec<String>,
+    /// One array per row, in `columns` order; blobs are base64 strings.
+    pub rows: Vec<Vec<serde_json::Value>>,
+    /// `true` when the statement produced more than `max_rows` rows.
+    pub truncated: bool,
+    pub elapsed_ms: i64,
+}
+
+/// Skips whitespace and `--` / `/* */` comments before the first keyword.
+fn skip_leading_comments(mut sql: &str) -> &str {
+    loop {
+        sql = sql.trim_start();
+        if let Some(rest) = sql.strip_prefix("--") {
+            sql = rest.split_once('\n').map_or("", |(_, tail)| tail);
+        } else if let Some(rest) = sql.strip_prefix("/*") {
+            sql = rest.split_once("*/").map_or("", |(_, tail)| tail);
+        } else {
+            return sql;
+        }
+    }
+}
+
+/// Returns the statement without trailing semicolons once it looks like a single SELECT.
+fn validate_select(sql: &str) -> AppResult<&str> {
+    let sql = sql
+        .trim()
+        .trim_end_matches(|c: char| c == ';' || c.is_whitespace());
+    if sql.is_empty() {
+        return Err("SEC_INVALID_INPUT: sql is required".into());
+    }
+    if sql.len() > MAX_SQL_LEN {
+        return Err(
+            format!("SEC_INVALID_INPUT: sql must be at most {MAX_SQL_LEN} characters").into(),
+        );
+    }
+    let keyword = skip_leading_comments(sql)
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .next()
+        .unwrap_or_default();
+    if !keyword.eq_ignore_ascii_case("select") && !keyword.eq_ignore_ascii_case("with") {
+        return Err("SEC_INVALID_INPUT: only SELECT statements are allowed".into());
+    }
+    Ok(sql)
+}
+
+fn open_readonly(path: &Path) -> AppResult<Connection> {
+    let conn = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| db_err!("failed to open read-only connection: {e}"))?;
+    conn.busy_timeout(BUSY_TIMEOUT)
+        .map_err(|e| db_err!("failed to set busy_timeout: {e}"))?;
+    conn.pragma_update(None, "query_only", true)
+        .map_err(|e| db_err!("failed to enable query_only: {e}"))?;
+    Ok(conn)
+}
+
+fn cell_to_json(value: ValueRef<'_>) -> serde_json::Value {
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(v) => v.into(),
+        ValueRef::Real(v) => serde_json::Number::from_f64(v)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        ValueRef::Text(v) => String::from_utf8_lossy(v).into_owned().into(),
+        ValueRef::Blob(v) => base64::engine::general_purpose::STANDARD.encode(v).into(),
+    }
+}
+
+fn map_query_err(err: rusqlite::Error, timeout: Duration) -> AppError {
+    match err {
+        rusqlite::Error::SqliteFailure(ref e, _) if e.code == ErrorCode::OperationInterrupted => {
+            format!(
+                "DB_QUERY_TIMEOUT: query exceeded {}s and was interrupted",
+                timeout.as_secs()
+            )
+            .into()
+        }
+        err => format!("SEC_INVALID_INPUT: query failed: {err}").into(),
+    }
+}
+
+fn run_query(
+    conn: &Connection,
+    sql: &str,
+    max_rows: usize,
+    timeout: Duration,
+) -> AppResult<(Vec<String>, Vec<Vec<serde_json::Value>>, bool)> {
+    let mut stmt = conn
+        .prepare(sql)
+        .map_err(|e| format!("SEC_INVALID_INPUT: invalid sql: {e}"))?;
+    if !stmt.readonly() {
+        return Err("SEC_INVALID_INPUT: only read-only statements are allowed".into());
+    }
+    let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+
+    let mut rows_out = Vec::new();
+    let mut truncated = false;
+    let mut rows = stmt.query([]).map_err(|e| map_query_err(e, timeout))?;
+    while let Some(row) = rows.next().map_err(|e| map_query_err(e, timeout))? {
+        if rows_out.len() >= max_rows {
+            truncated = true;
+            break;
+        }
+        let cells = (0..columns.len())
+            .map(|idx| row.get_ref(idx).map(cell_to_json))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| map_query_err(e, timeout))?;
+        rows_out.push(cells);
+    }
+    Ok((columns, rows_out, truncated))
+}
+
+fn query_readonly_at(
+    path: &Path,
+    sql: &str,
+    max_rows: Option<u32>,
+    timeout: Duration,
+) -> AppResult<DbQueryResult> {
+    let sql = validate_select(sql)?;
+    let max_rows = max_rows
+        .unwrap_or(DEFAULT_MAX_ROWS)
+        .clamp(1, MAX_ROWS_LIMIT) as usize;
+    let conn = open_readonly(path)?;
+
+    let started = Instant::now();
+    let interrupt = conn.get_interrupt_handle();
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    let watchdog = std::thread::spawn(move || {
+        if let Err(RecvTimeoutError::Timeout) = done_rx.recv_timeout(timeout) {
+            interrupt.interrupt();
+        }
+    });
+
+    let result = run_query(&conn, sql, max_rows, timeout);
+    drop(done_tx);
+    let _ = watchdog.join();
+
+    let (columns, rows, truncated) = result?;
+    Ok(DbQueryResult {
+        columns,
+        rows,
+        truncated,
+        elapsed_ms: started.elapsed().as_millis() as i64,
+    })
+}
+
+/// Runs one SELECT against the app database on a read-only connection.
+pub fn db_query_readonly(
+    app: &crate::AppHandle,
+    sql: &str,
+    max_rows: Option<u32>,
+) -> AppResult<DbQueryResult> {
+    let path = db::db_path(app)?;
+    query_readonly_at(&path, sql, max_rows, QUERY_TIMEOUT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_db() -> (tempfile::TempDir, std::path::PathBuf) {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("test.db");
+        let db = db::init_for_tests(&path).expect("init db");
+        let conn = db.open_connection().expect("conn");
+        conn.execute_batch(
+            "CREATE TABLE console_test(id INTEGER PRIMARY KEY, name TEXT, score REAL, raw BLOB);
+             INSERT INTO console_test(name, score, raw) VALUES ('a', 1.5, x'0102'), ('b', NULL, NULL), ('c', 3.0, NULL);",
+        )
+        .expect("seed");
+        (dir, path)
+    }
+
+    #[test]
+    fn validate_select_accepts_only_select_statements() {
+        assert_eq!(
+            validate_select(" -- note\n/* x */ select 1; ").expect("select"),
+            "-- note\n/* x */ select 1"
+        );
+        assert!(validate_select("WITH t AS (SELECT 1) SELECT * FROM t").is_ok());
+        assert!(validate_select("DELETE FROM providers").is_err());
+        assert!(validate_select("PRAGMA journal_mode=DELETE").is_err());
+        assert!(validate_select("ATTACH 'x.db' AS x").is_err());
+        assert!(validate_select("  ;").is_err());
+    }
+
+    #[test]
+    fn query_returns_rows_as_json_and_truncates_at_max_rows() {
+        let (_dir, path) = test_db();
+        let result = query_readonly_at(
+            &path,
+            "SELECT name, score, raw FROM console_test ORDER BY id",
+            Some(2),
+            QUERY_TIMEOUT,
+        )
+        .expect("query");
+        assert_eq!(result.columns, vec!["name", "score", "raw"]);
+        assert!(result.truncated);
+        assert_eq!(
+            result.rows,
+            vec![
+                vec![
+                    serde_json::json!("a"),
+                    serde_json::json!(1.5),
+                    serde_json::json!("AQI=")
+                ],
+                vec![
+                    serde_json::json!("b"),
+                    serde_json::Value::Null,
+                    serde_json::Value::Null
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_are_rejected_even_when_disguised() {
+        let (_dir, path) = test_db();
+        let err = query_readonly_at(
+            &path,
+            "WITH t AS (SELECT 1) DELETE FROM console_test",
+            None,
+            QUERY_TIMEOUT,
+        )
+        .expect_err("write rejected");
+        assert!(err.to_string().contains("SEC_INVALID_INPUT"), "{err}");
+
+        let count = query_readonly_at(
+            &path,
+            "SELECT COUNT(*) FROM console_test",
+            None,
+            QUERY_TIMEOUT,
+        )
+        .expect("count");
+        assert_eq!(count.rows, vec![vec![serde_json::json!(3)]]);
+    }
+
+    #[test]
+    fn long_running_queries_are_interrupted() {
+        let (_dir, path) = test_db();
+        let err = query_readonly_at(
+            &path,
+            "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n) SELECT COUNT(*) FROM n",
+            None,
+            Duration::from_millis(50),
+        )
+        .expect_err("timeout");
+        assert!(err.to_string().contains("DB_QUERY_TIMEOUT"), "{err}");
+    }
+}
@@ -0,0 +1,415 @@
+//! Usage: Scoped data resets (usage stats, request logs, gateway state, CLI-side configs).
+//!
+//! Every scope is two-step: a dry run lists what would be removed and issues a single-use
+//! confirmation token; applying the reset requires that token and must happen within
+//! `CONFIRM_TOKEN_TTL_SECS`. Runtime gateway state (in-memory circuits, session bindings) is reset
+//! by the command layer; this module covers the database and the files the hub wrote.
+
+use crate::db;
+use crate::shared::error::db_err;
+use crate::shared::mutex_ext::MutexExt;
+use crate::shared::time::now_unix_seconds;
+use crate::{cli_proxy, mcp_sync, prompt_sync, subagent_sync};
+use rusqlite::{Connection, OptionalExtension, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+const CONFIRM_TOKEN_TTL_SECS: i64 = 10 * 60;
+
+/// Used when a proxy manifest lost its origin; `set_enabled` only needs a well-formed value to
+/// restore the backup.
+const FALLBACK_PROXY_ORIGIN: &str = "http://127.0.0.1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum DataResetScope {
+    /// Hide every request from stats/cost views and drop the rollups; logs stay browsable.
+    UsageStats,
+    /// Request logs, attempt logs, rollups and mirror logs.
+    RequestLogs,
+    /// Persisted circuit breaker state (open circuits and cooldowns).
+    GatewayState,
+    /// CLI files written by the hub: proxy config, MCP servers, prompts and subagents.
+    CliConfigs,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct DataResetItem {
+    /// `table`, `cli_proxy`, `mcp_servers`, `prompt`, `subagents` or `session_bindings`.
+    pub kind: String,
+    /// Table name or cli key.
+    pub target: String,
+    /// Rows / entries affected.
+    pub count: u64,
+    /// Set when applying this item failed; other items are still applied.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct DataResetReport {
+    pub scope: DataResetScope,
+    pub dry_run: bool,
+    pub items: Vec<DataResetItem>,
+    /// Issued by dry runs only; pass it back to apply the reset.
+    pub confirm_token: Option<String>,
+    pub confirm_token_expires_at: Option<i64>,
+}
+
+impl DataResetItem {
+    fn new(kind: &str, target: &str, count: u64) -> Self {
+        Self {
+            kind: kind.to_string(),
+            target: target.to_string(),
+            count,
+            error: None,
+        }
+    }
+}
+
+static CONFIRM_TOKENS: OnceLock<Mutex<HashMap<String, (DataResetScope, i64)>>> = OnceLock::new();
+
+fn confirm_tokens() -> &'static Mutex<HashMap<String, (DataResetScope, i64)>> {
+    CONFIRM_TOKENS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn issue_confirm_token(scope: DataResetScope, now: i64) -> (String, i64) {
+    use rand::RngCore;
+    let mut buf = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut buf);
+    let token: String = buf.iter().map(|b| format!("{b:02x}")).collect();
+    let expires_at = now + CONFIRM_TOKEN_TTL_SECS;
+
+    let mut tokens = confirm_tokens().lock_or_recover();
+    tokens.retain(|_, (_, exp)| *exp > now);
+    tokens.insert(token.clone(), (scope, expires_at));
+    (token, expires_at)
+}
+
+/// Consumes `token`; it must come from a dry run of the same scope and be unexpired.
+pub(crate) fn consume_confirm_token(
+    scope: DataResetScope,
+    token: Option<&str>,
+    now: i64,
+) -> crate::shared::error::AppResult<()> {
+    let Some(token) = token.map(str::trim).filter(|t| !t.is_empty()) else {
+        return Err("SEC_INVALID_INPUT: confirm_token is required; run a dry run first".into());
+    };
+    let mut tokens = confirm_tokens().lock_or_recover();
+    match tokens.remove(token) {
+        Some((token_scope, expires_at)) if token_scope == scope && expires_at > now => Ok(()),
+        Some((token_scope, _)) if token_scope != scope => {
+            Err("SEC_INVALID_INPUT: confirm_token was issued for a different scope".into())
+        }
+        _ => Err("SEC_INVALID_INPUT: confirm_token is invalid or expired".into()),
+    }
+}
+
+fn table_exists(conn: &Connection, table: &str) -> crate::shared::error::AppResult<bool> {
+    Ok(conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1 LIMIT 1",
+            [table],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query sqlite_master for {table}: {e}"))?
+        .unwrap_or(false))
+}
+
+fn count_rows(
+    conn: &Connection,
+    table: &str,
+    filter: &str,
+) -> crate::shared::error::AppResult<u64> {
+    if !table_exists(conn, table)? {
+        return Ok(0);
+    }
+    let sql = format!("SELECT COUNT(*) FROM {table} {filter}");
+    conn.query_row(&sql, [], |row| row.get::<_, i64>(0))
+        .map(|n| n.max(0) as u64)
+        .map_err(|e| db_err!("failed to count {table}: {e}"))
+}
+
+/// Tables touched by a DB scope, with the statement applied and the filter used to count.
+fn db_targets(scope: DataResetScope) -> &'static [(&'static str, &'static str, &'static str)] {
+    match scope {
+        DataResetScope::UsageStats => &[
+            (
+                "request_logs",
+                "UPDATE request_logs SET excluded_from_stats = 1 WHERE excluded_from_stats = 0",
+                "WHERE excluded_from_stats = 0",
+            ),
+            ("request_log_rollups", "DELETE FROM request_log_rollups", ""),
+        ],
+        DataResetScope::RequestLogs => &[
+            (
+                "request_attempt_logs",
+                "DELETE FROM request_attempt_logs",
+                "",
+            ),
+            ("request_logs", "DELETE FROM request_logs", ""),
+            ("request_log_rollups", "DELETE FROM request_log_rollups", ""),
+            ("request_mirror_logs", "DELETE FROM request_mirror_logs", ""),
+        ],
+        DataResetScope::GatewayState => &[(
+            "provider_circuit_breakers",
+            "DELETE FROM provider_circuit_breakers",
+            "",
+        )],
+        DataResetScope::CliConfigs => &[],
+    }
+}
+
+fn plan_db(
+    db: &db::Db,
+    scope: DataResetScope,
+) -> crate::shared::error::AppResult<Vec<DataResetItem>> {
+    let conn = db.open_connection()?;
+    db_targets(scope)
+        .iter()
+        .map(|(table, _, filter)| {
+            Ok(DataResetItem::new(
+                "table",
+                table,
+                count_rows(&conn, table, filter)?,
+            ))
+        })
+        .collect()
+}
+
+fn apply_db(
+    db: &db::Db,
+    scope: DataResetScope,
+) -> crate::shared::error::AppResult<Vec<DataResetItem>> {
+    let targets = db_targets(scope);
+    if targets.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut conn = db.open_connection()?;
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| db_err!("failed to start transaction: {e}"))?;
+
+    let mut items = Vec::with_capacity(targets.len());
+    for (table, sql, _) in targets {
+        let affected = if table_exists(&tx, table)? {
+            tx.execute(sql, [])
+                .map_err(|e| db_err!("failed to reset {table}: {e}"))?
+        } else {
+            0
+        };
+        items.push(DataResetItem::new("table", table, affected as u64));
+    }
+
+    tx.commit()
+        .map_err(|e| db_err!("failed to commit transaction: {e}"))?;
+
+    if scope == DataResetScope::RequestLogs {
+        // Best-effort: reclaim disk usage, as in `request_logs_clear_all`.
+        let _ = conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);");
+        let _ = conn.execute_batch("VACUUM;");
+        let _ = conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);");
+    }
+
+    Ok(items)
+}
+
+fn plan_cli_configs(app: &crate::AppHandle) -> crate::shared::error::AppResult<Vec<DataResetItem>> {
+    let mut items = Vec::new();
+    for status in cli_proxy::status_all(app)? {
+        if status.enabled {
+            items.push(DataResetItem::new("cli_proxy", &status.cli_key, 1));
+        }
+    }
+    for cli_key in crate::shared::cli_key::SUPPORTED_CLI_KEYS {
+        let keys = mcp_sync::read_managed_keys(app, cli_key)?;
+        if !keys.is_empty() {
+            items.push(DataResetItem::new(
+                "mcp_servers",
+                cli_key,
+                keys.len() as u64,
+            ));
+        }
+        if prompt_sync::is_applied(app, cli_key)? {
+            items.push(DataResetItem::new("prompt", cli_key, 1));
+        }
+    }
+    let agents = subagent_sync::managed_file_names(app)?;
+    if !agents.is_empty() {
+        items.push(DataResetItem::new(
+            "subagents",
+            "claude",
+            agents.len() as u64,
+        ));
+    }
+    Ok(items)
+}
+
+/// Restores the pre-hub CLI files. Hub DB entries (MCP servers, prompts, agents) are kept, so
+/// the next sync from the UI writes them again.
+fn apply_cli_configs(
+    app: &crate::AppHandle,
+) -> crate::shared::error::AppResult<Vec<DataResetItem>> {
+    let mut items = Vec::new();
+    for status in cli_proxy::status_all(app)? {
+        if !status.enabled {
+            continue;
+        }
+        let origin = status
+            .base_origin
+            .as_deref()
+            .unwrap_or(FALLBACK_PROXY_ORIGIN);
+        let mut item = DataResetItem::new("cli_proxy", &status.cli_key, 1);
+        match cli_proxy::set_enabled(app, &status.cli_key, false, origin) {
+            Ok(result) if result.ok => {}
+            Ok(result) => item.error = Some(result.message),
+            Err(err) => item.error = Some(err.to_string()),
+        }
+        items.push(item);
+    }
+
+    for cli_key in crate::shared::cli_key::SUPPORTED_CLI_KEYS {
+        let keys = mcp_sync::read_managed_keys(app, cli_key)?;
+        if !keys.is_empty() {
+            let mut item = DataResetItem::new("mcp_servers", cli_key, keys.len() as u64);
+            if let Err(err) = mcp_sync::sync_cli(app, cli_key, &[]) {
+                item.error = Some(err);
+            }
+            items.push(item);
+        }
+        if prompt_sync::is_applied(app, cli_key)? {
+            let mut item = DataResetItem::new("prompt", cli_key, 1);
+            if let Err(err) = prompt_sync::restore_disabled_prompt(app, cli_key) {
+                item.error = Some(err.to_string());
+            }
+            items.push(item);
+        }
+    }
+
+    let agents = subagent_sync::managed_file_names(app)?;
+    if !agents.is_empty() {
+        let mut item = DataResetItem::new("subagents", "claude", agents.len() as u64);
+        if let Err(err) = subagent_sync::sync_subagents(app, &[]) {
+            item.error = Some(err.to_string());
+        }
+        items.push(item);
+    }
+    Ok(items)
+}
+
+/// Lists what `scope` would remove and issues a confirmation token.
+pub fn data_reset_plan(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    scope: DataResetScope,
+) -> crate::shared::error::AppResult<DataResetReport> {
+    let items = match scope {
+        DataResetScope::CliConfigs => plan_cli_configs(app)?,
+        _ => plan_db(db, scope)?,
+    };
+    let (token, expires_at) = issue_confirm_token(scope, now_unix_seconds());
+    Ok(DataResetReport {
+        scope,
+        dry_run: true,
+        items,
+        confirm_token: Some(token),
+        confirm_token_expires_at: Some(expires_at),
+    })
+}
+
+/// Applies `scope`; `confirm_token` must come from a prior [`data_reset_plan`] of that scope.
+pub fn data_reset_apply(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    scope: DataResetScope,
+    confirm_token: Option<&str>,
+) -> crate::shared::error::AppResult<DataResetReport> {
+    consume_confirm_token(scope, confirm_token, now_unix_seconds())?;
+    tracing::warn!(scope = ?scope, "selective data reset initiated (user-confirmed)");
+
+    let items = match scope {
+        DataResetScope::CliConfigs => apply_cli_configs(app)?,
+        _ => apply_db(db, scope)?,
+    };
+    Ok(DataResetReport {
+        scope,
+        dry_run: false,
+        items,
+        confirm_token: None,
+        confirm_token_expires_at: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confirm_tokens_are_scoped_single_use_and_expire() {
+        let now = 1_000;
+        let (token, expires_at) = issue_confirm_token(DataResetScope::RequestLogs, now);
+        assert_eq!(expires_at, now + CONFIRM_TOKEN_TTL_SECS);
+
+        assert!(consume_confirm_token(DataResetScope::RequestLogs, None, now).is_err());
+        assert!(consume_confirm_token(DataResetScope::UsageStats, Some(&token), now).is_err());
+        // A mismatched attempt burns the token.
+        assert!(consume_confirm_token(DataResetScope::RequestLogs, Some(&token), now).is_err());
+
+        let (token, _) = issue_confirm_token(DataResetScope::RequestLogs, now);
+        assert!(consume_confirm_token(DataResetScope::RequestLogs, Some(&token), now).is_ok());
+        assert!(consume_confirm_token(DataResetScope::RequestLogs, Some(&token), now).is_err());
+
+        let (token, expires_at) = issue_confirm_token(DataResetScope::GatewayState, now);
+        assert!(
+            consume_confirm_token(DataResetScope::GatewayState, Some(&token), expires_at).is_err()
+        );
+    }
+
+    #[test]
+    fn db_scopes_count_then_clear_only_their_tables() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let db = crate::db::init_for_tests(&dir.path().join("test.db")).expect("init db");
+        let conn = db.open_connection().expect("conn");
+        conn.execute_batch(
+            r#"
+INSERT INTO request_logs(trace_id, cli_key, method, path, attempts_json, created_at)
+VALUES ('t1', 'claude', 'POST', '/v1/messages', '[]', 1),
+       ('t2', 'claude', 'POST', '/v1/messages', '[]', 2);
+INSERT INTO providers(id, cli_key, name, base_url, api_key_plaintext, created_at, updated_at)
+VALUES (1, 'claude', 'p1', 'https://example.com', 'sk-test', 1, 1);
+INSERT INTO provider_circuit_breakers(provider_id, state, failure_count, open_until, updated_at)
+VALUES (1, 'OPEN', 5, 100, 1);
+"#,
+        )
+        .expect("seed");
+
+        let plan = plan_db(&db, DataResetScope::UsageStats).expect("plan");
+        assert_eq!(plan[0].target, "request_logs");
+        assert_eq!(plan[0].count, 2);
+
+        apply_db(&db, DataResetScope::UsageStats).expect("apply usage stats");
+        assert_eq!(count_rows(&conn, "request_logs", "").expect("count"), 2);
+        assert_eq!(
+            count_rows(&conn, "request_logs", "WHERE excluded_from_stats = 0").expect("count"),
+            0
+        );
+        assert_eq!(
+            count_rows(&conn, "provider_circuit_breakers", "").expect("count"),
+            1
+        );
+
+        apply_db(&db, DataResetScope::GatewayState).expect("apply gateway state");
+        assert_eq!(
+            count_rows(&conn, "provider_circuit_breakers", "").expect("count"),
+            0
+        );
+        assert_eq!(count_rows(&conn, "request_logs", "").expect("count"), 2);
+
+        let items = apply_db(&db, DataResetScope::RequestLogs).expect("apply request logs");
+        let logs = items.iter().find(|i| i.target == "request_logs").unwrap();
+        assert_eq!(logs.count, 2);
+        assert_eq!(count_rows(&conn, "request_logs", "").expect("count"), 0);
+    }
+}
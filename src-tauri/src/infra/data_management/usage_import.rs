@@ -0,0 +1,504 @@
+//! Usage: Import usage history exported by other proxy tools into `request_logs`.
+//!
+//! Supported sources:
+//! - LiteLLM spend logs (`/spend/logs` JSON array, or JSONL of spend log rows).
+//! - claude-code-router log files (pino JSONL; lines carrying a `usage` object).
+//!
+//! Imported rows get a deterministic `trace_id` (`import-<source>-<id>`), so importing the
+//! same file twice updates rows instead of duplicating them. Cost is recomputed from local
+//! model prices by the regular insert path.
+
+use crate::db;
+use crate::request_logs::{self, RequestLogInsert};
+use crate::shared::cli_key::is_supported_cli_key;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+const MAX_IMPORT_FILE_BYTES: u64 = 256 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UsageImportSource {
+    LiteLlm,
+    ClaudeCodeRouter,
+}
+
+impl UsageImportSource {
+    fn parse(raw: &str) -> Option<Self> {
+        match raw.trim() {
+            "litellm" => Some(Self::LiteLlm),
+            "claude_code_router" => Some(Self::ClaudeCodeRouter),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::LiteLlm => "litellm",
+            Self::ClaudeCodeRouter => "claude_code_router",
+        }
+    }
+
+    fn trace_prefix(self) -> &'static str {
+        match self {
+            Self::LiteLlm => "import-litellm",
+            Self::ClaudeCodeRouter => "import-ccr",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct UsageImportResult {
+    pub source: String,
+    pub rows_read: u64,
+    pub rows_imported: u64,
+    pub rows_skipped: u64,
+}
+
+pub fn import_usage_history(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    source: &str,
+    path: &str,
+    cli_key: Option<&str>,
+) -> crate::shared::error::AppResult<UsageImportResult> {
+    let Some(source) = UsageImportSource::parse(source) else {
+        return Err(format!("SEC_INVALID_INPUT: unsupported import source={source}").into());
+    };
+    let cli_key = match cli_key.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(cli_key) if is_supported_cli_key(cli_key) => cli_key.to_string(),
+        Some(cli_key) => {
+            return Err(format!("SEC_INVALID_INPUT: unknown cli_key={cli_key}").into());
+        }
+        None => "claude".to_string(),
+    };
+
+    let path = Path::new(path.trim());
+    let meta = std::fs::metadata(path)
+        .map_err(|e| format!("IMPORT_READ_FAILED: {}: {e}", path.to_string_lossy()))?;
+    if meta.len() > MAX_IMPORT_FILE_BYTES {
+        return Err(format!(
+            "SEC_INVALID_INPUT: import file too large ({} bytes, max {MAX_IMPORT_FILE_BYTES})",
+            meta.len()
+        )
+        .into());
+    }
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("IMPORT_READ_FAILED: {}: {e}", path.to_string_lossy()))?;
+
+    let (records, unparsable) = parse_records(&text)?;
+    let mut items = Vec::with_capacity(records.len());
+    for (index, record) in records.iter().enumerate() {
+        let item = match source {
+            UsageImportSource::LiteLlm => litellm_row(record, &cli_key, index),
+            UsageImportSource::ClaudeCodeRouter => ccr_row(record, &cli_key, index),
+        };
+        if let Some(item) = item {
+            items.push(item);
+        }
+    }
+
+    request_logs::insert_imported(app, db, &items)?;
+
+    let rows_read = (records.len() + unparsable) as u64;
+    let rows_imported = items.len() as u64;
+    tracing::info!(
+        source = source.as_str(),
+        rows_read,
+        rows_imported,
+        "usage history imported"
+    );
+
+    Ok(UsageImportResult {
+        source: source.as_str().to_string(),
+        rows_read,
+        rows_imported,
+        rows_skipped: rows_read - rows_imported,
+    })
+}
+
+/// Accepts a JSON array, an object wrapping the array in `data`/`logs`, or JSONL.
+/// Returns the records plus the number of JSONL lines that failed to parse.
+fn parse_records(text: &str) -> crate::shared::error::AppResult<(Vec<Value>, usize)> {
+    let trimmed = text.trim_start_matches('\u{feff}').trim();
+    if trimmed.starts_with('[') {
+        let items: Vec<Value> = serde_json::from_str(trimmed)
+            .map_err(|e| format!("SEC_INVALID_INPUT: invalid JSON array: {e}"))?;
+        return Ok((items, 0));
+    }
+    if let Ok(Value::Object(mut obj)) = serde_json::from_str::<Value>(trimmed) {
+        for key in ["data", "logs"] {
+            if let Some(Value::Array(items)) = obj.remove(key) {
+                return Ok((items, 0));
+            }
+        }
+        return Ok((vec![Value::Object(obj)], 0));
+    }
+
+    let mut items = Vec::new();
+    let mut unparsable = 0;
+    for line in trimmed.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Value>(line) {
+            Ok(value @ Value::Object(_)) => items.push(value),
+            _ => unparsable += 1,
+        }
+    }
+    Ok((items, unparsable))
+}
+
+fn litellm_row(record: &Value, cli_key: &str, index: usize) -> Option<RequestLogInsert> {
+    if record
+        .get("status")
+        .and_then(Value::as_str)
+        .is_some_and(|s| s.eq_ignore_ascii_case("failure"))
+    {
+        return None;
+    }
+
+    let input_tokens = first_i64(record, &[&["prompt_tokens"]]);
+    let output_tokens = first_i64(record, &[&["completion_tokens"]]);
+    if input_tokens.is_none() && output_tokens.is_none() {
+        return None;
+    }
+    let total_tokens = first_i64(record, &[&["total_tokens"]])
+        .or_else(|| Some(input_tokens.unwrap_or(0) + output_tokens.unwrap_or(0)));
+
+    let started_ms = first_timestamp_ms(record, &[&["startTime"], &["start_time"]])?;
+    let ended_ms = first_timestamp_ms(record, &[&["endTime"], &["end_time"]]);
+    let duration_ms = ended_ms.map_or(0, |end| (end - started_ms).max(0));
+
+    let usage = get_path(record, &["metadata", "usage_object"]);
+    let cache_read = usage.and_then(|u| {
+        first_i64(
+            u,
+            &[
+                &["cache_read_input_tokens"],
+                &["prompt_tokens_details", "cached_tokens"],
+            ],
+        )
+    });
+    let cache_creation = usage.and_then(|u| first_i64(u, &[&["cache_creation_input_tokens"]]));
+
+    let id = first_string(record, &[&["request_id"], &["id"]])
+        .unwrap_or_else(|| format!("{started_ms}-{index}"));
+    let path = match record.get("call_type").and_then(Value::as_str) {
+        Some(t) if t.contains("anthropic") || t.contains("messages") => "/v1/messages",
+        Some(t) if t.contains("responses") => "/v1/responses",
+        _ => "/v1/chat/completions",
+    };
+
+    Some(imported_row(ImportedRow {
+        trace_id: format!("{}-{id}", UsageImportSource::LiteLlm.trace_prefix()),
+        cli_key,
+        path,
+        model: first_string(record, &[&["model"], &["model_group"]]),
+        created_at_ms: started_ms,
+        duration_ms,
+        input_tokens,
+        output_tokens,
+        total_tokens,
+        cache_read_input_tokens: cache_read,
+        cache_creation_input_tokens: cache_creation,
+        usage_json: usage.map(Value::to_string),
+    }))
+}
+
+fn ccr_row(record: &Value, cli_key: &str, index: usize) -> Option<RequestLogInsert> {
+    let usage = [
+        &["usage"][..],
+        &["response", "usage"],
+        &["data", "usage"],
+        &["message", "usage"],
+    ]
+    .iter()
+    .find_map(|path| get_path(record, path).filter(|v| v.is_object()))?;
+
+    let input_tokens = first_i64(usage, &[&["input_tokens"], &["prompt_tokens"]]);
+    let output_tokens = first_i64(usage, &[&["output_tokens"], &["completion_tokens"]]);
+    if input_tokens.is_none() && output_tokens.is_none() {
+        return None;
+    }
+    let cache_read = first_i64(usage, &[&["cache_read_input_tokens"]]);
+    let cache_creation = first_i64(usage, &[&["cache_creation_input_tokens"]]);
+    let total_tokens = Some(input_tokens.unwrap_or(0) + output_tokens.unwrap_or(0));
+
+    let created_at_ms = first_timestamp_ms(record, &[&["time"], &["timestamp"]])?;
+    let id = first_string(record, &[&["reqId"], &["requestId"], &["id"]])
+        .unwrap_or_else(|| format!("{created_at_ms}-{index}"));
+
+    Some(imported_row(ImportedRow {
+        trace_id: format!(
+            "{}-{id}",
+            UsageImportSource::ClaudeCodeRouter.trace_prefix()
+        ),
+        cli_key,
+        path: "/v1/messages",
+        model: first_string(
+            record,
+            &[
+                &["model"],
+                &["request", "model"],
+                &["data", "model"],
+                &["message", "model"],
+            ],
+        ),
+        created_at_ms,
+        duration_ms: first_i64(record, &[&["responseTime"], &["duration_ms"]]).unwrap_or(0),
+        input_tokens,
+        output_tokens,
+        total_tokens,
+        cache_read_input_tokens: cache_read,
+        cache_creation_input_tokens: cache_creation,
+        usage_json: Some(usage.to_string()),
+    }))
+}
+
+struct ImportedRow<'a> {
+    trace_id: String,
+    cli_key: &'a str,
+    path: &'static str,
+    model: Option<String>,
+    created_at_ms: i64,
+    duration_ms: i64,
+    input_tokens: Option<i64>,
+    output_tokens: Option<i64>,
+    total_tokens: Option<i64>,
+    cache_read_input_tokens: Option<i64>,
+    cache_creation_input_tokens: Option<i64>,
+    usage_json: Option<String>,
+}
+
+fn imported_row(row: ImportedRow<'_>) -> RequestLogInsert {
+    RequestLogInsert {
+        trace_id: row.trace_id,
+        cli_key: row.cli_key.to_string(),
+        session_id: None,
+        method: "POST".to_string(),
+        path: row.path.to_string(),
+        query: None,
+        excluded_from_stats: false,
+        special_settings_json: None,
+        status: Some(200),
+        error_code: None,
+        duration_ms: row.duration_ms,
+        ttfb_ms: None,
+        attempts_json: "[]".to_string(),
+        input_tokens: row.input_tokens,
+        output_tokens: row.output_tokens,
+        total_tokens: row.total_tokens,
+        cache_read_input_tokens: row.cache_read_input_tokens,
+        cache_creation_input_tokens: row.cache_creation_input_tokens,
+        cache_creation_5m_input_tokens: None,
+        cache_creation_1h_input_tokens: None,
+        usage_json: row.usage_json,
+        requested_model: row.model.map(|m| normalize_model(&m)),
+        created_at_ms: row.created_at_ms,
+        created_at: row.created_at_ms.div_euclid(1000),
+        request_bytes: None,
+        response_bytes: None,
+        thinking_budget_tokens: None,
+    }
+}
+
+/// Drops routing prefixes such as `anthropic/` or `openrouter,anthropic/`.
+fn normalize_model(raw: &str) -> String {
+    let raw = raw.trim();
+    raw.rsplit(['/', ',']).next().unwrap_or(raw).to_string()
+}
+
+fn get_path<'a>(value: &'a Value, path: &[&str]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |v, key| v.get(*key))
+}
+
+fn value_as_i64(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64().or_else(|| n.as_f64().map(|f| f as i64)),
+        Value::String(s) => s.trim().parse::<i64>().ok(),
+        _ => None,
+    }
+}
+
+fn first_i64(value: &Value, paths: &[&[&str]]) -> Option<i64> {
+    paths
+        .iter()
+        .find_map(|path| get_path(value, path).and_then(value_as_i64))
+        .filter(|v| *v >= 0)
+}
+
+fn first_string(value: &Value, paths: &[&[&str]]) -> Option<String> {
+    paths.iter().find_map(|path| match get_path(value, path)? {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    })
+}
+
+fn first_timestamp_ms(value: &Value, paths: &[&[&str]]) -> Option<i64> {
+    paths
+        .iter()
+        .find_map(|path| get_path(value, path).and_then(parse_timestamp_ms))
+}
+
+/// Epoch seconds / milliseconds (number or numeric string) or an RFC 3339 datetime.
+fn parse_timestamp_ms(value: &Value) -> Option<i64> {
+    let epoch = match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse::<f64>().ok(),
+        _ => None,
+    };
+    if let Some(epoch) = epoch {
+        return match epoch {
+            v if v <= 0.0 => None,
+            v if v >= 1e12 => Some(v as i64),
+            v => Some((v * 1000.0) as i64),
+        };
+    }
+    parse_rfc3339_ms(value.as_str()?.trim())
+}
+
+/// Minimal RFC 3339 parser: `YYYY-MM-DD[T ]HH:MM:SS[.fff][Z|±HH:MM]`; no offset means UTC.
+fn parse_rfc3339_ms(raw: &str) -> Option<i64> {
+    let bytes = raw.as_bytes();
+    if bytes.len() < 19 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return None;
+    }
+    if !matches!(bytes[10], b'T' | b't' | b' ') || bytes[13] != b':' || bytes[16] != b':' {
+        return None;
+    }
+    let num = |range: std::ops::Range<usize>| raw.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (num(0..4)?, num(5..7)?, num(8..10)?);
+    let (hour, minute, second) = (num(11..13)?, num(14..16)?, num(17..19)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 {
+        return None;
+    }
+
+    let mut rest = &raw[19..];
+    let mut millis = 0;
+    if let Some(frac) = rest.strip_prefix('.') {
+        let digits = frac.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return None;
+        }
+        let padded = format!("{:0<3}", &frac[..digits.min(3)]);
+        millis = padded.parse::<i64>().ok()?;
+        rest = &frac[digits..];
+    }
+
+    let offset_seconds = match rest {
+        "" | "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let tz = rest[1..].replace(':', "");
+            if tz.len() != 4 || !tz.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            let hours = tz[..2].parse::<i64>().ok()?;
+            let minutes = tz[2..].parse::<i64>().ok()?;
+            sign * (hours * 3600 + minutes * 60)
+        }
+    };
+
+    let days = days_from_civil(year, month, day);
+    let seconds = days * 86_400 + hour * 3600 + minute * 60 + second - offset_seconds;
+    Some(seconds * 1000 + millis)
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's algorithm).
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_timestamp_ms_handles_rfc3339_and_epochs() {
+        assert_eq!(
+            parse_timestamp_ms(&Value::from("1970-01-02T00:00:00Z")),
+            Some(86_400_000)
+        );
+        assert_eq!(
+            parse_timestamp_ms(&Value::from("2024-03-01T08:30:00.250+08:00")),
+            Some(1_709_253_000_250)
+        );
+        assert_eq!(
+            parse_timestamp_ms(&Value::from("2024-03-01 00:30:00.123456")),
+            Some(1_709_253_000_123)
+        );
+        assert_eq!(
+            parse_timestamp_ms(&Value::from(1_709_253_000)),
+            Some(1_709_253_000_000)
+        );
+        assert_eq!(
+            parse_timestamp_ms(&Value::from(1_709_253_000_250_i64)),
+            Some(1_709_253_000_250)
+        );
+        assert_eq!(parse_timestamp_ms(&Value::from("yesterday")), None);
+    }
+
+    #[test]
+    fn litellm_row_maps_spend_log_fields() {
+        let record = serde_json::json!({
+            "request_id": "chatcmpl-1",
+            "call_type": "acompletion",
+            "model": "anthropic/claude-sonnet-4",
+            "startTime": "2024-03-01T00:30:00Z",
+            "endTime": "2024-03-01T00:30:02.500Z",
+            "prompt_tokens": 120,
+            "completion_tokens": 30,
+            "total_tokens": 150,
+            "metadata": { "usage_object": { "cache_read_input_tokens": 100 } }
+        });
+        let row = litellm_row(&record, "claude", 0).expect("row");
+        assert_eq!(row.trace_id, "import-litellm-chatcmpl-1");
+        assert_eq!(row.requested_model.as_deref(), Some("claude-sonnet-4"));
+        assert_eq!(row.path, "/v1/chat/completions");
+        assert_eq!(row.duration_ms, 2500);
+        assert_eq!(row.input_tokens, Some(120));
+        assert_eq!(row.cache_read_input_tokens, Some(100));
+        assert_eq!(row.created_at, 1_709_253_000);
+
+        let failed = serde_json::json!({ "status": "failure", "prompt_tokens": 1 });
+        assert!(litellm_row(&failed, "claude", 1).is_none());
+    }
+
+    #[test]
+    fn ccr_jsonl_keeps_only_usage_lines() {
+        let text = concat!(
+            "{\"level\":30,\"time\":1709253000000,\"msg\":\"server started\"}\n",
+            "not json\n",
+            "{\"level\":30,\"time\":1709253001000,\"reqId\":\"req-7\",",
+            "\"model\":\"openrouter,anthropic/claude-sonnet-4\",",
+            "\"usage\":{\"input_tokens\":10,\"output_tokens\":5}}\n",
+        );
+        let (records, unparsable) = parse_records(text).expect("parse");
+        assert_eq!(records.len(), 2);
+        assert_eq!(unparsable, 1);
+
+        let rows: Vec<_> = records
+            .iter()
+            .enumerate()
+            .filter_map(|(i, r)| ccr_row(r, "claude", i))
+            .collect();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].trace_id, "import-ccr-req-7");
+        assert_eq!(rows[0].requested_model.as_deref(), Some("claude-sonnet-4"));
+        assert_eq!(rows[0].total_tokens, Some(15));
+        assert_eq!(rows[0].created_at_ms, 1_709_253_001_000);
+    }
+}
@@ -0,0 +1,78 @@
+//! Usage: SQLite busy handler that mirrors `busy_timeout` while counting lock contention.
+
+use rusqlite::Connection;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Same back-off schedule SQLite uses for its built-in busy timeout.
+const BUSY_DELAYS_MS: [u64; 12] = [1, 2, 5, 10, 15, 20, 25, 25, 25, 50, 50, 100];
+
+static BUSY_TIMEOUT_MS: AtomicU64 = AtomicU64::new(0);
+static BUSY_WAITS: AtomicU64 = AtomicU64::new(0);
+static BUSY_TIMEOUTS: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct BusyStats {
+    /// Statements that hit a lock and had to wait at least once.
+    pub(crate) waits: u64,
+    /// Statements that gave up with `SQLITE_BUSY` after exhausting the busy timeout.
+    pub(crate) timeouts: u64,
+}
+
+pub(crate) fn busy_stats() -> BusyStats {
+    BusyStats {
+        waits: BUSY_WAITS.load(Ordering::Relaxed),
+        timeouts: BUSY_TIMEOUTS.load(Ordering::Relaxed),
+    }
+}
+
+pub(super) fn install(conn: &Connection, timeout: Duration) -> rusqlite::Result<()> {
+    BUSY_TIMEOUT_MS.store(
+        u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX),
+        Ordering::Relaxed,
+    );
+    conn.busy_handler(Some(on_busy))
+}
+
+/// Returns `(delay, already_waited)` in milliseconds for the `count`-th retry.
+fn backoff_ms(count: usize) -> (u64, u64) {
+    let last = BUSY_DELAYS_MS.len() - 1;
+    if count <= last {
+        let waited = BUSY_DELAYS_MS[..count].iter().sum();
+        return (BUSY_DELAYS_MS[count], waited);
+    }
+    let waited = BUSY_DELAYS_MS.iter().sum::<u64>()
+        + BUSY_DELAYS_MS[last].saturating_mul((count - last - 1) as u64);
+    (BUSY_DELAYS_MS[last], waited)
+}
+
+fn on_busy(count: i32) -> bool {
+    let count = usize::try_from(count).unwrap_or(0);
+    if count == 0 {
+        BUSY_WAITS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    let timeout_ms = BUSY_TIMEOUT_MS.load(Ordering::Relaxed);
+    let (delay_ms, waited_ms) = backoff_ms(count);
+    if waited_ms >= timeout_ms {
+        BUSY_TIMEOUTS.fetch_add(1, Ordering::Relaxed);
+        return false;
+    }
+
+    std::thread::sleep(Duration::from_millis(delay_ms.min(timeout_ms - waited_ms)));
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::backoff_ms;
+
+    #[test]
+    fn backoff_follows_sqlite_schedule_then_caps_delay() {
+        assert_eq!(backoff_ms(0), (1, 0));
+        assert_eq!(backoff_ms(3), (10, 8));
+        assert_eq!(backoff_ms(11), (100, 228));
+        assert_eq!(backoff_ms(12), (100, 328));
+        assert_eq!(backoff_ms(14), (100, 528));
+    }
+}
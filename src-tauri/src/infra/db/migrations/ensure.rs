@@ -16,6 +16,37 @@ pub(super) fn apply_ensure_patches(conn: &mut Connection) -> crate::shared::erro
     ensure_usage_indexes(conn)?;
     ensure_provider_tags(conn)?;
     ensure_provider_note(conn)?;
+    ensure_sort_mode_fallbacks(conn)?;
+    ensure_provider_maintenance_windows(conn)?;
+    ensure_provider_model_catalog(conn)?;
+    ensure_claude_hooks(conn)?;
+    ensure_request_mirror_logs(conn)?;
+    ensure_request_log_error_catalog_code(conn)?;
+    ensure_request_log_response_headers(conn)?;
+    ensure_sort_mode_provider_time_windows(conn)?;
+    ensure_provider_last_resort(conn)?;
+    ensure_sort_mode_strategy(conn)?;
+    ensure_workspace_snapshots(conn)?;
+    ensure_skill_version_pins(conn)?;
+    ensure_provider_monthly_budgets(conn)?;
+    ensure_sort_mode_schedules(conn)?;
+    ensure_request_log_attempts_summary(conn)?;
+    ensure_claude_subagents(conn)?;
+    ensure_provider_dns_options(conn)?;
+    ensure_claude_model_validation_schedules(conn)?;
+    ensure_codex_config_profiles(conn)?;
+    ensure_request_log_bytes(conn)?;
+    ensure_provider_emulate_models_endpoint(conn)?;
+    ensure_request_log_rollups(conn)?;
+    ensure_thinking_budget_columns(conn)?;
+    ensure_provider_archived_at(conn)?;
+    ensure_provider_refusal_patterns(conn)?;
+    ensure_provider_anthropic_headers(conn)?;
+    ensure_provider_webhook_url(conn)?;
+    ensure_provider_tag_defaults(conn)?;
+    ensure_skill_repo_credentials(conn)?;
+    ensure_config_audit_log(conn)?;
+    ensure_stats_views(conn)?;
     Ok(())
 }
 
@@ -622,6 +653,20 @@ CREATE INDEX IF NOT EXISTS idx_request_logs_provider_success_cost
     )
     .map_err(|e| format!("failed to create idx_request_logs_provider_success_cost: {e}"))?;
 
+    // Index 3: Partial index for cost top-N (cost_stats.rs top_requests_v1)
+    // Walks the materialized cost_usd_femto in descending order instead of sorting every row
+    tx.execute_batch(
+        r#"
+CREATE INDEX IF NOT EXISTS idx_request_logs_success_cost_desc
+  ON request_logs(cost_usd_femto DESC, created_at_ms DESC)
+  WHERE status >= 200 AND status < 300
+    AND error_code IS NULL
+    AND cost_usd_femto IS NOT NULL
+    AND excluded_from_stats = 0;
+"#,
+    )
+    .map_err(|e| format!("failed to create idx_request_logs_success_cost_desc: {e}"))?;
+
     tx.commit()
         .map_err(|e| format!("failed to commit sqlite transaction: {e}"))?;
     Ok(())
@@ -708,6 +753,1064 @@ fn ensure_provider_note(conn: &mut Connection) -> Result<(), String> {
     Ok(())
 }
 
+// ---------------------------------------------------------------------------
+// ensure_sort_mode_fallbacks
+// ---------------------------------------------------------------------------
+
+fn ensure_sort_mode_fallbacks(conn: &mut Connection) -> Result<(), String> {
+    let has_sort_modes_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'sort_modes' LIMIT 1",
+            [],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| format!("failed to query sqlite_master: {e}"))?
+        .unwrap_or(false);
+
+    if !has_sort_modes_table {
+        return Ok(());
+    }
+
+    // `mode_id IS NULL` means the default provider ordering.
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS sort_mode_fallbacks (
+  cli_key TEXT NOT NULL,
+  position INTEGER NOT NULL,
+  mode_id INTEGER,
+  updated_at INTEGER NOT NULL,
+  PRIMARY KEY(cli_key, position),
+  FOREIGN KEY(mode_id) REFERENCES sort_modes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_sort_mode_fallbacks_mode_id ON sort_mode_fallbacks(mode_id);
+"#,
+    )
+    .map_err(|e| format!("failed to ensure sort_mode_fallbacks table: {e}"))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_provider_maintenance_windows
+// ---------------------------------------------------------------------------
+
+fn ensure_provider_maintenance_windows(conn: &mut Connection) -> Result<(), String> {
+    let has_providers_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'providers' LIMIT 1",
+            [],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| format!("failed to query sqlite_master: {e}"))?
+        .unwrap_or(false);
+
+    if !has_providers_table {
+        return Ok(());
+    }
+
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS provider_maintenance_windows (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  provider_id INTEGER NOT NULL,
+  starts_at INTEGER NOT NULL,
+  ends_at INTEGER NOT NULL,
+  repeat_interval_secs INTEGER,
+  note TEXT NOT NULL DEFAULT '',
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL,
+  FOREIGN KEY(provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_provider_maintenance_windows_provider_id
+  ON provider_maintenance_windows(provider_id);
+"#,
+    )
+    .map_err(|e| format!("failed to ensure provider_maintenance_windows table: {e}"))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_provider_model_catalog
+// ---------------------------------------------------------------------------
+
+fn ensure_provider_model_catalog(conn: &mut Connection) -> Result<(), String> {
+    let has_providers_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'providers' LIMIT 1",
+            [],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| format!("failed to query sqlite_master: {e}"))?
+        .unwrap_or(false);
+
+    if !has_providers_table {
+        return Ok(());
+    }
+
+    let columns = [
+        (
+            "provider_kind",
+            "ALTER TABLE providers ADD COLUMN provider_kind TEXT NOT NULL DEFAULT 'standard';",
+        ),
+        (
+            "model_catalog_json",
+            "ALTER TABLE providers ADD COLUMN model_catalog_json TEXT NOT NULL DEFAULT '[]';",
+        ),
+        (
+            "model_catalog_refreshed_at",
+            "ALTER TABLE providers ADD COLUMN model_catalog_refreshed_at INTEGER;",
+        ),
+        (
+            "model_catalog_error",
+            "ALTER TABLE providers ADD COLUMN model_catalog_error TEXT;",
+        ),
+    ];
+    for (column, sql) in columns {
+        if !column_exists(conn, "providers", column)? {
+            conn.execute_batch(sql)
+                .map_err(|e| format!("failed to ensure providers {column} column: {e}"))?;
+        }
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_claude_hooks
+// ---------------------------------------------------------------------------
+
+fn ensure_claude_hooks(conn: &mut Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS claude_hooks (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL,
+  event TEXT NOT NULL,
+  matcher TEXT NOT NULL DEFAULT '',
+  command TEXT NOT NULL,
+  timeout_secs INTEGER,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS workspace_hook_enabled (
+  workspace_id INTEGER NOT NULL,
+  hook_id INTEGER NOT NULL,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL,
+  PRIMARY KEY(workspace_id, hook_id),
+  FOREIGN KEY(workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+  FOREIGN KEY(hook_id) REFERENCES claude_hooks(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_workspace_hook_enabled_workspace_id
+  ON workspace_hook_enabled(workspace_id);
+CREATE INDEX IF NOT EXISTS idx_workspace_hook_enabled_hook_id
+  ON workspace_hook_enabled(hook_id);
+"#,
+    )
+    .map_err(|e| format!("failed to ensure claude_hooks tables: {e}"))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_request_mirror_logs
+// ---------------------------------------------------------------------------
+
+fn ensure_request_mirror_logs(conn: &mut Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS request_mirror_logs (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  trace_id TEXT NOT NULL,
+  cli_key TEXT NOT NULL,
+  provider_id INTEGER NOT NULL,
+  provider_name TEXT NOT NULL,
+  method TEXT NOT NULL,
+  path TEXT NOT NULL,
+  requested_model TEXT,
+  status INTEGER,
+  error TEXT,
+  ttfb_ms INTEGER,
+  duration_ms INTEGER NOT NULL,
+  input_tokens INTEGER,
+  output_tokens INTEGER,
+  cache_read_input_tokens INTEGER,
+  cache_creation_input_tokens INTEGER,
+  created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_request_mirror_logs_created_at
+  ON request_mirror_logs(created_at);
+CREATE INDEX IF NOT EXISTS idx_request_mirror_logs_provider_id
+  ON request_mirror_logs(provider_id);
+"#,
+    )
+    .map_err(|e| format!("failed to ensure request_mirror_logs table: {e}"))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_request_log_error_catalog_code
+// ---------------------------------------------------------------------------
+
+fn ensure_request_log_error_catalog_code(conn: &mut Connection) -> Result<(), String> {
+    let has_request_logs_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'request_logs' LIMIT 1",
+            [],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| format!("failed to query sqlite_master: {e}"))?
+        .unwrap_or(false);
+
+    if !has_request_logs_table {
+        return Ok(());
+    }
+
+    if !column_exists(conn, "request_logs", "error_catalog_code")? {
+        conn.execute_batch("ALTER TABLE request_logs ADD COLUMN error_catalog_code TEXT;")
+            .map_err(|e| format!("failed to ensure request_logs error_catalog_code column: {e}"))?;
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_request_log_response_headers
+// ---------------------------------------------------------------------------
+
+fn ensure_request_log_response_headers(conn: &mut Connection) -> Result<(), String> {
+    let has_request_logs_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'request_logs' LIMIT 1",
+            [],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| format!("failed to query sqlite_master: {e}"))?
+        .unwrap_or(false);
+
+    if !has_request_logs_table {
+        return Ok(());
+    }
+
+    if !column_exists(conn, "request_logs", "response_headers_json")? {
+        conn.execute_batch("ALTER TABLE request_logs ADD COLUMN response_headers_json TEXT;")
+            .map_err(|e| {
+                format!("failed to ensure request_logs response_headers_json column: {e}")
+            })?;
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_request_log_attempts_summary
+// ---------------------------------------------------------------------------
+
+fn ensure_request_log_attempts_summary(conn: &mut Connection) -> Result<(), String> {
+    let has_request_logs_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'request_logs' LIMIT 1",
+            [],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| format!("failed to query sqlite_master: {e}"))?
+        .unwrap_or(false);
+
+    if !has_request_logs_table {
+        return Ok(());
+    }
+
+    if !column_exists(conn, "request_logs", "attempts_summary_json")? {
+        conn.execute_batch("ALTER TABLE request_logs ADD COLUMN attempts_summary_json TEXT;")
+            .map_err(|e| {
+                format!("failed to ensure request_logs attempts_summary_json column: {e}")
+            })?;
+    }
+    if !column_exists(conn, "request_logs", "attempts_pruned_at")? {
+        conn.execute_batch("ALTER TABLE request_logs ADD COLUMN attempts_pruned_at INTEGER;")
+            .map_err(|e| format!("failed to ensure request_logs attempts_pruned_at column: {e}"))?;
+    }
+    conn.execute_batch(
+        r#"
+CREATE INDEX IF NOT EXISTS idx_request_logs_attempts_unpruned
+  ON request_logs(created_at)
+  WHERE attempts_pruned_at IS NULL;
+"#,
+    )
+    .map_err(|e| format!("failed to create idx_request_logs_attempts_unpruned: {e}"))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_sort_mode_provider_time_windows
+// ---------------------------------------------------------------------------
+
+fn ensure_sort_mode_provider_time_windows(conn: &mut Connection) -> Result<(), String> {
+    let has_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'sort_mode_providers' LIMIT 1",
+            [],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| format!("failed to query sqlite_master: {e}"))?
+        .unwrap_or(false);
+
+    if !has_table {
+        return Ok(());
+    }
+
+    let mut ddl = Vec::new();
+    if !column_exists(conn, "sort_mode_providers", "active_start")? {
+        ddl.push("ALTER TABLE sort_mode_providers ADD COLUMN active_start TEXT;");
+    }
+    if !column_exists(conn, "sort_mode_providers", "active_end")? {
+        ddl.push("ALTER TABLE sort_mode_providers ADD COLUMN active_end TEXT;");
+    }
+    if !ddl.is_empty() {
+        conn.execute_batch(&ddl.join("\n")).map_err(|e| {
+            format!("failed to ensure sort_mode_providers time window columns: {e}")
+        })?;
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_provider_last_resort
+// ---------------------------------------------------------------------------
+
+fn ensure_provider_last_resort(conn: &mut Connection) -> Result<(), String> {
+    let has_providers_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'providers' LIMIT 1",
+            [],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| format!("failed to query sqlite_master: {e}"))?
+        .unwrap_or(false);
+
+    if !has_providers_table {
+        return Ok(());
+    }
+
+    if !column_exists(conn, "providers", "last_resort")? {
+        conn.execute_batch(
+            "ALTER TABLE providers ADD COLUMN last_resort INTEGER NOT NULL DEFAULT 0;",
+        )
+        .map_err(|e| format!("failed to ensure providers last_resort column: {e}"))?;
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_sort_mode_strategy
+// ---------------------------------------------------------------------------
+
+fn ensure_sort_mode_strategy(conn: &mut Connection) -> Result<(), String> {
+    let has_sort_modes_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'sort_modes' LIMIT 1",
+            [],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| format!("failed to query sqlite_master: {e}"))?
+        .unwrap_or(false);
+
+    if !has_sort_modes_table {
+        return Ok(());
+    }
+
+    if !column_exists(conn, "sort_modes", "strategy")? {
+        conn.execute_batch(
+            "ALTER TABLE sort_modes ADD COLUMN strategy TEXT NOT NULL DEFAULT 'manual';",
+        )
+        .map_err(|e| format!("failed to ensure sort_modes strategy column: {e}"))?;
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_workspace_snapshots
+// ---------------------------------------------------------------------------
+
+fn ensure_workspace_snapshots(conn: &mut Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS workspace_snapshots (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  cli_key TEXT NOT NULL,
+  workspace_id INTEGER,
+  reason TEXT NOT NULL,
+  state_json TEXT NOT NULL,
+  created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_workspace_snapshots_cli_created_at
+  ON workspace_snapshots(cli_key, created_at);
+"#,
+    )
+    .map_err(|e| format!("failed to ensure workspace_snapshots table: {e}"))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_skill_version_pins
+// ---------------------------------------------------------------------------
+
+fn ensure_skill_version_pins(conn: &mut Connection) -> Result<(), String> {
+    let has_skills_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'skills' LIMIT 1",
+            [],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| format!("failed to query sqlite_master: {e}"))?
+        .unwrap_or(false);
+
+    if !has_skills_table {
+        return Ok(());
+    }
+
+    for (column, ddl) in [
+        (
+            "pinned_ref",
+            "ALTER TABLE skills ADD COLUMN pinned_ref TEXT;",
+        ),
+        (
+            "installed_commit",
+            "ALTER TABLE skills ADD COLUMN installed_commit TEXT;",
+        ),
+        (
+            "latest_commit",
+            "ALTER TABLE skills ADD COLUMN latest_commit TEXT;",
+        ),
+        (
+            "update_checked_at",
+            "ALTER TABLE skills ADD COLUMN update_checked_at INTEGER;",
+        ),
+        (
+            "last_update_changed_files",
+            "ALTER TABLE skills ADD COLUMN last_update_changed_files TEXT;",
+        ),
+    ] {
+        if !column_exists(conn, "skills", column)? {
+            conn.execute_batch(ddl)
+                .map_err(|e| format!("failed to ensure skills {column} column: {e}"))?;
+        }
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_provider_monthly_budgets
+// ---------------------------------------------------------------------------
+
+fn ensure_provider_monthly_budgets(conn: &mut Connection) -> Result<(), String> {
+    let has_providers_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'providers' LIMIT 1",
+            [],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| format!("failed to query sqlite_master: {e}"))?
+        .unwrap_or(false);
+
+    if !has_providers_table {
+        return Ok(());
+    }
+
+    if !column_exists(conn, "providers", "monthly_budget_usd")? {
+        conn.execute_batch("ALTER TABLE providers ADD COLUMN monthly_budget_usd REAL;")
+            .map_err(|e| format!("failed to ensure providers monthly_budget_usd column: {e}"))?;
+    }
+
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS provider_monthly_budgets (
+  provider_id INTEGER NOT NULL,
+  month TEXT NOT NULL,
+  grace_usd REAL NOT NULL DEFAULT 0,
+  notified_80_at INTEGER,
+  notified_100_at INTEGER,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL,
+  PRIMARY KEY(provider_id, month),
+  FOREIGN KEY(provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+"#,
+    )
+    .map_err(|e| format!("failed to ensure provider_monthly_budgets table: {e}"))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_sort_mode_schedules
+// ---------------------------------------------------------------------------
+
+fn ensure_sort_mode_schedules(conn: &mut Connection) -> Result<(), String> {
+    let has_sort_modes_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'sort_modes' LIMIT 1",
+            [],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| format!("failed to query sqlite_master: {e}"))?
+        .unwrap_or(false);
+
+    if !has_sort_modes_table {
+        return Ok(());
+    }
+
+    // `mode_id IS NULL` means the default provider ordering.
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS sort_mode_schedules (
+  cli_key TEXT PRIMARY KEY,
+  enabled INTEGER NOT NULL DEFAULT 0,
+  utc_offset_minutes INTEGER,
+  otherwise_enabled INTEGER NOT NULL DEFAULT 0,
+  otherwise_mode_id INTEGER,
+  updated_at INTEGER NOT NULL,
+  FOREIGN KEY(otherwise_mode_id) REFERENCES sort_modes(id) ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS sort_mode_schedule_rules (
+  cli_key TEXT NOT NULL,
+  position INTEGER NOT NULL,
+  days_mask INTEGER NOT NULL,
+  start_time TEXT,
+  end_time TEXT,
+  mode_id INTEGER,
+  PRIMARY KEY(cli_key, position),
+  FOREIGN KEY(mode_id) REFERENCES sort_modes(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_sort_mode_schedule_rules_mode_id ON sort_mode_schedule_rules(mode_id);
+"#,
+    )
+    .map_err(|e| format!("failed to ensure sort_mode_schedules tables: {e}"))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_claude_subagents
+// ---------------------------------------------------------------------------
+
+fn ensure_claude_subagents(conn: &mut Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS claude_subagents (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL,
+  description TEXT NOT NULL DEFAULT '',
+  prompt TEXT NOT NULL,
+  tools_json TEXT NOT NULL DEFAULT '[]',
+  model TEXT,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL,
+  UNIQUE(name)
+);
+
+CREATE TABLE IF NOT EXISTS workspace_subagent_enabled (
+  workspace_id INTEGER NOT NULL,
+  subagent_id INTEGER NOT NULL,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL,
+  PRIMARY KEY(workspace_id, subagent_id),
+  FOREIGN KEY(workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+  FOREIGN KEY(subagent_id) REFERENCES claude_subagents(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_workspace_subagent_enabled_workspace_id
+  ON workspace_subagent_enabled(workspace_id);
+CREATE INDEX IF NOT EXISTS idx_workspace_subagent_enabled_subagent_id
+  ON workspace_subagent_enabled(subagent_id);
+"#,
+    )
+    .map_err(|e| format!("failed to ensure claude_subagents tables: {e}"))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_provider_dns_options
+// ---------------------------------------------------------------------------
+
+fn ensure_provider_dns_options(conn: &mut Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS provider_dns_options (
+  provider_id INTEGER PRIMARY KEY,
+  ip_strategy TEXT NOT NULL DEFAULT 'system',
+  doh_url TEXT,
+  race_connections INTEGER NOT NULL DEFAULT 0,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL,
+  FOREIGN KEY(provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+"#,
+    )
+    .map_err(|e| format!("failed to ensure provider_dns_options table: {e}"))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_claude_model_validation_schedules
+// ---------------------------------------------------------------------------
+
+fn ensure_claude_model_validation_schedules(conn: &mut Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS claude_model_validation_schedules (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  provider_id INTEGER NOT NULL,
+  model TEXT NOT NULL,
+  interval_minutes INTEGER NOT NULL,
+  enabled INTEGER NOT NULL DEFAULT 1,
+  last_run_at INTEGER,
+  last_ok INTEGER,
+  last_error TEXT,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL,
+  UNIQUE(provider_id, model),
+  FOREIGN KEY(provider_id) REFERENCES providers(id) ON DELETE CASCADE
+);
+"#,
+    )
+    .map_err(|e| format!("failed to ensure claude_model_validation_schedules table: {e}"))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_codex_config_profiles
+// ---------------------------------------------------------------------------
+
+fn ensure_codex_config_profiles(conn: &mut Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS codex_config_profiles (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL,
+  normalized_name TEXT NOT NULL,
+  toml TEXT NOT NULL,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL,
+  UNIQUE(normalized_name)
+);
+
+CREATE TABLE IF NOT EXISTS workspace_codex_profile (
+  workspace_id INTEGER PRIMARY KEY,
+  profile_id INTEGER NOT NULL,
+  applied_at INTEGER NOT NULL,
+  FOREIGN KEY(workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+  FOREIGN KEY(profile_id) REFERENCES codex_config_profiles(id) ON DELETE CASCADE
+);
+"#,
+    )
+    .map_err(|e| format!("failed to ensure codex_config_profiles tables: {e}"))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_request_log_bytes
+// ---------------------------------------------------------------------------
+
+fn ensure_request_log_bytes(conn: &mut Connection) -> Result<(), String> {
+    let has_request_logs_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'request_logs' LIMIT 1",
+            [],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| format!("failed to query sqlite_master: {e}"))?
+        .unwrap_or(false);
+
+    if !has_request_logs_table {
+        return Ok(());
+    }
+
+    for column in ["request_bytes", "response_bytes"] {
+        if !column_exists(conn, "request_logs", column)? {
+            conn.execute_batch(&format!(
+                "ALTER TABLE request_logs ADD COLUMN {column} INTEGER;"
+            ))
+            .map_err(|e| format!("failed to ensure request_logs {column} column: {e}"))?;
+        }
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_provider_emulate_models_endpoint
+// ---------------------------------------------------------------------------
+
+fn ensure_provider_emulate_models_endpoint(conn: &mut Connection) -> Result<(), String> {
+    let has_providers_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'providers' LIMIT 1",
+            [],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| format!("failed to query sqlite_master: {e}"))?
+        .unwrap_or(false);
+
+    if !has_providers_table {
+        return Ok(());
+    }
+
+    if !column_exists(conn, "providers", "emulate_models_endpoint")? {
+        conn.execute_batch(
+            "ALTER TABLE providers ADD COLUMN emulate_models_endpoint INTEGER NOT NULL DEFAULT 0;",
+        )
+        .map_err(|e| format!("failed to ensure providers emulate_models_endpoint column: {e}"))?;
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_request_log_rollups
+// ---------------------------------------------------------------------------
+
+fn ensure_request_log_rollups(conn: &mut Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS request_log_rollups (
+  bucket_start INTEGER NOT NULL,
+  cli_key TEXT NOT NULL,
+  final_provider_id INTEGER NOT NULL DEFAULT 0,
+  requested_model TEXT NOT NULL DEFAULT '',
+  request_count INTEGER NOT NULL DEFAULT 0,
+  requests_with_usage INTEGER NOT NULL DEFAULT 0,
+  duration_ms_sum INTEGER NOT NULL DEFAULT 0,
+  ttfb_ms_sum INTEGER NOT NULL DEFAULT 0,
+  ttfb_count INTEGER NOT NULL DEFAULT 0,
+  generation_ms_sum INTEGER NOT NULL DEFAULT 0,
+  output_tokens_for_rate_sum INTEGER NOT NULL DEFAULT 0,
+  input_tokens INTEGER NOT NULL DEFAULT 0,
+  output_tokens INTEGER NOT NULL DEFAULT 0,
+  total_tokens INTEGER NOT NULL DEFAULT 0,
+  cache_read_input_tokens INTEGER NOT NULL DEFAULT 0,
+  cache_creation_input_tokens INTEGER NOT NULL DEFAULT 0,
+  cache_creation_5m_input_tokens INTEGER NOT NULL DEFAULT 0,
+  cache_creation_1h_input_tokens INTEGER NOT NULL DEFAULT 0,
+  cost_usd_femto INTEGER NOT NULL DEFAULT 0,
+  request_bytes INTEGER NOT NULL DEFAULT 0,
+  response_bytes INTEGER NOT NULL DEFAULT 0,
+  PRIMARY KEY(bucket_start, cli_key, final_provider_id, requested_model)
+);
+"#,
+    )
+    .map_err(|e| format!("failed to ensure request_log_rollups table: {e}"))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_thinking_budget_columns
+// ---------------------------------------------------------------------------
+
+fn ensure_thinking_budget_columns(conn: &mut Connection) -> Result<(), String> {
+    for (table, column) in [
+        ("providers", "thinking_budget_cap"),
+        ("request_logs", "thinking_budget_tokens"),
+    ] {
+        let has_table: bool = conn
+            .query_row(
+                "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1 LIMIT 1",
+                [table],
+                |_| Ok(true),
+            )
+            .optional()
+            .map_err(|e| format!("failed to query sqlite_master: {e}"))?
+            .unwrap_or(false);
+
+        if has_table && !column_exists(conn, table, column)? {
+            conn.execute_batch(&format!("ALTER TABLE {table} ADD COLUMN {column} INTEGER;"))
+                .map_err(|e| format!("failed to ensure {table} {column} column: {e}"))?;
+        }
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_provider_archived_at
+// ---------------------------------------------------------------------------
+
+fn ensure_provider_archived_at(conn: &mut Connection) -> Result<(), String> {
+    let has_providers_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'providers' LIMIT 1",
+            [],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| format!("failed to query sqlite_master: {e}"))?
+        .unwrap_or(false);
+
+    if !has_providers_table {
+        return Ok(());
+    }
+
+    if !column_exists(conn, "providers", "archived_at")? {
+        conn.execute_batch("ALTER TABLE providers ADD COLUMN archived_at INTEGER;")
+            .map_err(|e| format!("failed to ensure providers archived_at column: {e}"))?;
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_provider_refusal_patterns
+// ---------------------------------------------------------------------------
+
+fn ensure_provider_refusal_patterns(conn: &mut Connection) -> Result<(), String> {
+    let has_providers_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'providers' LIMIT 1",
+            [],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| format!("failed to query sqlite_master: {e}"))?
+        .unwrap_or(false);
+
+    if !has_providers_table {
+        return Ok(());
+    }
+
+    if !column_exists(conn, "providers", "refusal_patterns_json")? {
+        conn.execute_batch(
+            "ALTER TABLE providers ADD COLUMN refusal_patterns_json TEXT NOT NULL DEFAULT '[]';",
+        )
+        .map_err(|e| format!("failed to ensure providers refusal_patterns_json column: {e}"))?;
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_provider_anthropic_headers
+// ---------------------------------------------------------------------------
+
+fn ensure_provider_anthropic_headers(conn: &mut Connection) -> Result<(), String> {
+    let has_providers_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'providers' LIMIT 1",
+            [],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| format!("failed to query sqlite_master: {e}"))?
+        .unwrap_or(false);
+
+    if !has_providers_table {
+        return Ok(());
+    }
+
+    for (column, ddl) in [
+        ("anthropic_version", "TEXT"),
+        ("anthropic_betas_json", "TEXT NOT NULL DEFAULT '[]'"),
+        ("anthropic_beta_strip", "INTEGER NOT NULL DEFAULT 0"),
+    ] {
+        if !column_exists(conn, "providers", column)? {
+            conn.execute_batch(&format!("ALTER TABLE providers ADD COLUMN {column} {ddl};"))
+                .map_err(|e| format!("failed to ensure providers {column} column: {e}"))?;
+        }
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_provider_webhook_url
+// ---------------------------------------------------------------------------
+
+fn ensure_provider_webhook_url(conn: &mut Connection) -> Result<(), String> {
+    let has_providers_table: bool = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'providers' LIMIT 1",
+            [],
+            |_| Ok(true),
+        )
+        .optional()
+        .map_err(|e| format!("failed to query sqlite_master: {e}"))?
+        .unwrap_or(false);
+
+    if !has_providers_table {
+        return Ok(());
+    }
+
+    if !column_exists(conn, "providers", "webhook_url")? {
+        conn.execute_batch("ALTER TABLE providers ADD COLUMN webhook_url TEXT;")
+            .map_err(|e| format!("failed to ensure providers webhook_url column: {e}"))?;
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_provider_tag_defaults
+// ---------------------------------------------------------------------------
+
+fn ensure_provider_tag_defaults(conn: &mut Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS provider_tag_defaults (
+  cli_key TEXT PRIMARY KEY,
+  tag TEXT NOT NULL,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL
+);
+"#,
+    )
+    .map_err(|e| format!("failed to ensure provider_tag_defaults table: {e}"))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_skill_repo_credentials
+// ---------------------------------------------------------------------------
+
+fn ensure_skill_repo_credentials(conn: &mut Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS skill_repo_credentials (
+  repo_key TEXT PRIMARY KEY,
+  auth_kind TEXT NOT NULL,
+  token TEXT,
+  ssh_key_path TEXT,
+  updated_at INTEGER NOT NULL
+);
+"#,
+    )
+    .map_err(|e| format!("failed to ensure skill_repo_credentials table: {e}"))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_config_audit_log
+// ---------------------------------------------------------------------------
+
+fn ensure_config_audit_log(conn: &mut Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS config_audit_log (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  created_at INTEGER NOT NULL,
+  command TEXT NOT NULL,
+  origin TEXT NOT NULL,
+  target TEXT,
+  summary TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_config_audit_log_created_at
+  ON config_audit_log(created_at);
+CREATE INDEX IF NOT EXISTS idx_config_audit_log_command
+  ON config_audit_log(command);
+"#,
+    )
+    .map_err(|e| format!("failed to ensure config_audit_log table: {e}"))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// ensure_stats_views
+// ---------------------------------------------------------------------------
+
+/// Stable, versioned views for external readers (see `db::open_existing`). Internal tables may
+/// change freely; `stats_v1_*` keep their columns, and a breaking change ships as `stats_v2_*`
+/// next to them. Views are recreated on every start so they track internal renames.
+fn ensure_stats_views(conn: &mut Connection) -> Result<(), String> {
+    conn.execute_batch(&format!(
+        r#"
+DROP VIEW IF EXISTS stats_meta;
+CREATE VIEW stats_meta AS
+SELECT {version} AS stats_views_version;
+
+DROP VIEW IF EXISTS stats_v1_requests;
+CREATE VIEW stats_v1_requests AS
+SELECT
+  r.id AS id,
+  r.trace_id AS trace_id,
+  r.cli_key AS cli_key,
+  r.created_at AS created_at,
+  r.status AS status,
+  r.error_code AS error_code,
+  r.duration_ms AS duration_ms,
+  r.ttfb_ms AS ttfb_ms,
+  r.requested_model AS model,
+  r.final_provider_id AS provider_id,
+  p.name AS provider_name,
+  r.input_tokens AS input_tokens,
+  r.output_tokens AS output_tokens,
+  r.total_tokens AS total_tokens,
+  r.cache_read_input_tokens AS cache_read_input_tokens,
+  r.cache_creation_input_tokens AS cache_creation_input_tokens,
+  r.cost_usd_femto / 1e15 AS cost_usd
+FROM request_logs r
+LEFT JOIN providers p ON p.id = r.final_provider_id
+WHERE r.excluded_from_stats = 0;
+
+DROP VIEW IF EXISTS stats_v1_daily;
+CREATE VIEW stats_v1_daily AS
+SELECT
+  day,
+  cli_key,
+  provider_id,
+  model,
+  SUM(requests) AS requests,
+  SUM(requests_failed) AS requests_failed,
+  SUM(input_tokens) AS input_tokens,
+  SUM(output_tokens) AS output_tokens,
+  SUM(total_tokens) AS total_tokens,
+  SUM(cost_usd) AS cost_usd
+FROM (
+  SELECT
+    strftime('%Y-%m-%d', created_at, 'unixepoch', 'localtime') AS day,
+    cli_key,
+    COALESCE(provider_id, 0) AS provider_id,
+    COALESCE(model, '') AS model,
+    COUNT(*) AS requests,
+    SUM(CASE WHEN status >= 200 AND status < 300 AND error_code IS NULL THEN 0 ELSE 1 END)
+      AS requests_failed,
+    SUM(COALESCE(input_tokens, 0)) AS input_tokens,
+    SUM(COALESCE(output_tokens, 0)) AS output_tokens,
+    SUM(COALESCE(total_tokens, COALESCE(input_tokens, 0) + COALESCE(output_tokens, 0)))
+      AS total_tokens,
+    SUM(COALESCE(cost_usd, 0)) AS cost_usd
+  FROM stats_v1_requests
+  GROUP BY 1, 2, 3, 4
+  UNION ALL
+  -- Sampled-out successes only exist as hourly rollups.
+  SELECT
+    strftime('%Y-%m-%d', bucket_start, 'unixepoch', 'localtime') AS day,
+    cli_key,
+    final_provider_id AS provider_id,
+    requested_model AS model,
+    SUM(request_count) AS requests,
+    0 AS requests_failed,
+    SUM(input_tokens) AS input_tokens,
+    SUM(output_tokens) AS output_tokens,
+    SUM(total_tokens) AS total_tokens,
+    SUM(cost_usd_femto) / 1e15 AS cost_usd
+  FROM request_log_rollups
+  GROUP BY 1, 2, 3, 4
+)
+GROUP BY day, cli_key, provider_id, model;
+"#,
+        version = super::super::STATS_VIEWS_VERSION,
+    ))
+    .map_err(|e| format!("failed to ensure stats views: {e}"))?;
+    Ok(())
+}
+
 // ---------------------------------------------------------------------------
 // Shared helper
 // ---------------------------------------------------------------------------
@@ -93,6 +93,13 @@ pub(super) fn apply_migrations(conn: &mut Connection) -> crate::shared::error::A
     Ok(())
 }
 
+/// `(user_version, LATEST_SCHEMA_VERSION)`; both match once `apply_migrations` has run.
+pub(super) fn schema_version_status(
+    conn: &Connection,
+) -> crate::shared::error::AppResult<(i64, i64)> {
+    Ok((read_user_version(conn)?, LATEST_SCHEMA_VERSION))
+}
+
 fn read_user_version(conn: &Connection) -> crate::shared::error::AppResult<i64> {
     conn.pragma_query_value(None, "user_version", |row| row.get(0))
         .map_err(|e| format!("failed to read sqlite user_version: {e}").into())
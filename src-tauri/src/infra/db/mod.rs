@@ -1,16 +1,24 @@
 //! Usage: SQLite connection setup, schema migrations, and common DB helpers.
+//!
+//! The app owns the database: it creates it, runs migrations and keeps it in WAL mode so other
+//! processes can read while the gateway writes. A second process (e.g. a companion stats CLI)
+//! should use [`open_existing`] with [`DbAccessMode::ReadOnly`], query only the versioned
+//! `stats_v1_*` views, and keep read transactions short so WAL checkpoints are not held back.
 
+mod busy;
 mod migrations;
 
+pub(crate) use busy::{busy_stats, BusyStats};
+
 use crate::app_paths;
 use crate::shared::error::db_err;
 use crate::shared::error::AppResult;
 use crate::shared::time::now_unix_seconds;
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
-use rusqlite::Connection;
+use rusqlite::{Connection, OpenFlags, OptionalExtension};
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 const DB_FILE_NAME: &str = "aio-coding-hub.db";
@@ -22,6 +30,9 @@ const PRAGMA_SYNCHRONOUS_DEFAULT: &str = "NORMAL";
 const PRAGMA_MMAP_SIZE_DEFAULT: i64 = 268_435_456;
 const DB_OPTIMIZE_STAMP_FILE_NAME: &str = "db_optimize.stamp";
 const DB_OPTIMIZE_MIN_INTERVAL_SECS: i64 = 24 * 60 * 60;
+/// Version of the `stats_meta` / `stats_v1_*` views external readers may rely on.
+pub(crate) const STATS_VIEWS_VERSION: i64 = 1;
+pub(crate) const READONLY_FLAG: &str = "--readonly";
 
 #[derive(Debug, Clone)]
 struct DbRuntimeConfig {
@@ -140,6 +151,28 @@ fn parse_pragma_synchronous(raw: &str) -> Option<String> {
     }
 }
 
+/// How a process other than the app opens the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DbAccessMode {
+    ReadWrite,
+    ReadOnly,
+}
+
+impl DbAccessMode {
+    /// `--readonly` anywhere in `args` selects [`DbAccessMode::ReadOnly`].
+    pub(crate) fn from_args<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        if args.into_iter().any(|arg| arg.as_ref() == READONLY_FLAG) {
+            Self::ReadOnly
+        } else {
+            Self::ReadWrite
+        }
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct Db {
     pool: Pool<SqliteConnectionManager>,
@@ -155,6 +188,12 @@ impl Db {
     }
 }
 
+/// Schema version of the open database next to the version this build migrates to.
+pub(crate) fn schema_version_status(db: &Db) -> AppResult<(i64, i64)> {
+    let conn = db.open_connection()?;
+    migrations::schema_version_status(&conn)
+}
+
 pub(crate) fn sql_placeholders(count: usize) -> String {
     if count == 0 {
         return String::new();
@@ -203,7 +242,7 @@ pub fn init<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> AppResult<Db> {
     let manager = SqliteConnectionManager::file(&path).with_init({
         let config = config.clone();
         move |conn| {
-            conn.busy_timeout(config.busy_timeout)?;
+            busy::install(conn, config.busy_timeout)?;
             configure_connection(conn, &config)
         }
     });
@@ -232,7 +271,7 @@ pub(crate) fn init_for_tests(path: &std::path::Path) -> AppResult<Db> {
     let manager = SqliteConnectionManager::file(path).with_init({
         let config = config.clone();
         move |conn| {
-            conn.busy_timeout(config.busy_timeout)?;
+            busy::install(conn, config.busy_timeout)?;
             configure_connection(conn, &config)
         }
     });
@@ -252,6 +291,65 @@ pub(crate) fn init_for_tests(path: &std::path::Path) -> AppResult<Db> {
     Ok(Db { pool })
 }
 
+/// Opens a database the app already created, without creating it or running migrations.
+///
+/// Fails unless the file is in WAL mode (readers would otherwise block the app's writes) and
+/// carries the stats views this build expects.
+pub(crate) fn open_existing(path: &Path, mode: DbAccessMode) -> AppResult<Db> {
+    if !path.exists() {
+        return Err(db_err!("database not found at {}", path.display()));
+    }
+
+    let config = DbRuntimeConfig::from_env();
+    let access_flag = match mode {
+        DbAccessMode::ReadOnly => OpenFlags::SQLITE_OPEN_READ_ONLY,
+        DbAccessMode::ReadWrite => OpenFlags::SQLITE_OPEN_READ_WRITE,
+    };
+    let manager = SqliteConnectionManager::file(path)
+        .with_flags(access_flag | OpenFlags::SQLITE_OPEN_NO_MUTEX | OpenFlags::SQLITE_OPEN_URI)
+        .with_init({
+            let config = config.clone();
+            move |conn| {
+                busy::install(conn, config.busy_timeout)?;
+                configure_external_connection(conn, &config, mode)
+            }
+        });
+
+    let pool = Pool::builder()
+        .max_size(config.pool_max_size.min(2))
+        .min_idle(Some(0))
+        .connection_timeout(config.pool_connection_timeout)
+        .build(manager)
+        .map_err(|e| db_err!("failed to create db pool: {e}"))?;
+    let conn = pool
+        .get()
+        .map_err(|e| db_err!("failed to get startup connection: {e}"))?;
+
+    let journal_mode: String = conn
+        .pragma_query_value(None, "journal_mode", |row| row.get(0))
+        .map_err(|e| db_err!("failed to read journal_mode: {e}"))?;
+    if !journal_mode.eq_ignore_ascii_case("wal") {
+        return Err(db_err!(
+            "database is in {journal_mode} mode; start the app once to switch it to WAL"
+        ));
+    }
+
+    let stats_views_version: Option<i64> = conn
+        .query_row("SELECT stats_views_version FROM stats_meta", [], |row| {
+            row.get(0)
+        })
+        .optional()
+        .map_err(|e| db_err!("failed to read stats_meta (start the app once to create it): {e}"))?;
+    if stats_views_version.unwrap_or(0) < STATS_VIEWS_VERSION {
+        return Err(db_err!(
+            "stats views v{STATS_VIEWS_VERSION} missing; update and start the app once"
+        ));
+    }
+
+    drop(conn);
+    Ok(Db { pool })
+}
+
 fn db_optimize_enabled() -> bool {
     env::var("AIO_DB_ENABLE_OPTIMIZE")
         .ok()
@@ -335,11 +433,52 @@ PRAGMA mmap_size = {mmap_size};
     Ok(())
 }
 
+fn configure_external_connection(
+    conn: &Connection,
+    config: &DbRuntimeConfig,
+    mode: DbAccessMode,
+) -> rusqlite::Result<()> {
+    // Journal mode and synchronous are the app's to choose; an external process only tunes its
+    // own connection.
+    let mut sql = format!(
+        r#"
+PRAGMA foreign_keys = ON;
+PRAGMA temp_store = MEMORY;
+PRAGMA mmap_size = {mmap_size};
+"#,
+        mmap_size = config.pragma_mmap_size
+    );
+    if mode == DbAccessMode::ReadOnly {
+        sql.push_str("PRAGMA query_only = ON;\n");
+    }
+    if let Some(cache_size) = config.pragma_cache_size {
+        sql.push_str(&format!("PRAGMA cache_size = {cache_size};\n"));
+    }
+
+    conn.execute_batch(&sql)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::collections::HashMap;
 
+    #[test]
+    fn access_mode_from_args_detects_readonly_flag() {
+        assert_eq!(
+            DbAccessMode::from_args(["stats", "--readonly"]),
+            DbAccessMode::ReadOnly
+        );
+        assert_eq!(
+            DbAccessMode::from_args(["stats", "--readonly=false"]),
+            DbAccessMode::ReadWrite
+        );
+        assert_eq!(
+            DbAccessMode::from_args(Vec::<String>::new()),
+            DbAccessMode::ReadWrite
+        );
+    }
+
     #[test]
     fn sql_placeholders_zero_returns_empty() {
         assert_eq!(sql_placeholders(0), "");
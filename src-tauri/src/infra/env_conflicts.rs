@@ -1,14 +1,21 @@
 //! Usage: Detect environment variables that may override local CLI configuration.
 //!
-//! This module is intentionally read-only: it reports potential conflicts but does not attempt to
-//! modify system settings or shell configuration files.
+//! Detection is read-only; `fix` applies explicit user-requested edits (with backups and undo).
 
+use crate::settings::EnvConflictIgnoreRule;
 use serde::Serialize;
 use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 #[cfg(not(target_os = "windows"))]
 use tauri::Manager;
 
-#[derive(Debug, Clone, Serialize)]
+mod fix;
+pub use fix::{
+    fix_env_conflicts, undo_env_conflicts_fix, EnvConflictFixAction, EnvConflictFixOutcome,
+    EnvConflictFixResult, EnvConflictFixTarget,
+};
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct EnvConflict {
     pub var_name: String,
     pub source_type: String, // "system" | "file"
@@ -38,6 +45,50 @@ fn conflict_dedupe_key(conflict: &EnvConflict) -> String {
     format!("{}|{}|{}", var_name, conflict.source_type, source_path)
 }
 
+fn is_ignored(conflict: &EnvConflict, rules: &[EnvConflictIgnoreRule]) -> bool {
+    let source_path = strip_trailing_line_number(&conflict.source_path);
+    rules.iter().any(|rule| {
+        rule.var_name.eq_ignore_ascii_case(&conflict.var_name)
+            && (rule.source_type.is_empty() || rule.source_type == conflict.source_type)
+            && (rule.source_path.is_empty()
+                || strip_trailing_line_number(&rule.source_path) == source_path)
+    })
+}
+
+/// Shell startup files scanned for exported variables (and the only files `fix` may edit).
+fn shell_config_files(home_dir: &Path) -> Vec<PathBuf> {
+    vec![
+        home_dir.join(".bashrc"),
+        home_dir.join(".bash_profile"),
+        home_dir.join(".bash_login"),
+        home_dir.join(".zshrc"),
+        home_dir.join(".zshenv"),
+        home_dir.join(".zprofile"),
+        home_dir.join(".profile"),
+        PathBuf::from("/etc/profile"),
+        PathBuf::from("/etc/bashrc"),
+        PathBuf::from("/etc/bash.bashrc"),
+        PathBuf::from("/etc/zshrc"),
+        PathBuf::from("/etc/zshenv"),
+        PathBuf::from("/etc/zprofile"),
+        PathBuf::from("/etc/zsh/zshrc"),
+        PathBuf::from("/etc/zsh/zshenv"),
+        PathBuf::from("/etc/zsh/zprofile"),
+    ]
+}
+
+/// Variable name assigned by a (possibly `export`ed) shell line; `None` for comments and others.
+fn assigned_var_name(line: &str) -> Option<&str> {
+    let trimmed = line.trim();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return None;
+    }
+    let export_line = trimmed.strip_prefix("export ").unwrap_or(trimmed);
+    let eq_pos = export_line.find('=')?;
+    let var_name = export_line[..eq_pos].trim();
+    (!var_name.is_empty()).then_some(var_name)
+}
+
 fn strip_trailing_line_number(source_path: &str) -> &str {
     let Some((path, suffix)) = source_path.rsplit_once(':') else {
         return source_path;
@@ -57,33 +108,13 @@ fn check_shell_configs<R: tauri::Runtime>(
     seen: &mut HashSet<String>,
 ) -> crate::shared::error::AppResult<()> {
     use std::fs;
-    use std::path::PathBuf;
 
     let home_dir = app
         .path()
         .home_dir()
         .map_err(|e| format!("failed to resolve home dir: {e}"))?;
 
-    let config_files: Vec<PathBuf> = vec![
-        home_dir.join(".bashrc"),
-        home_dir.join(".bash_profile"),
-        home_dir.join(".bash_login"),
-        home_dir.join(".zshrc"),
-        home_dir.join(".zshenv"),
-        home_dir.join(".zprofile"),
-        home_dir.join(".profile"),
-        PathBuf::from("/etc/profile"),
-        PathBuf::from("/etc/bashrc"),
-        PathBuf::from("/etc/bash.bashrc"),
-        PathBuf::from("/etc/zshrc"),
-        PathBuf::from("/etc/zshenv"),
-        PathBuf::from("/etc/zprofile"),
-        PathBuf::from("/etc/zsh/zshrc"),
-        PathBuf::from("/etc/zsh/zshenv"),
-        PathBuf::from("/etc/zsh/zprofile"),
-    ];
-
-    for file_path in config_files {
+    for file_path in shell_config_files(&home_dir) {
         let bytes = match fs::read(&file_path) {
             Ok(v) => v,
             Err(_) => continue,
@@ -91,21 +122,10 @@ fn check_shell_configs<R: tauri::Runtime>(
         let content = String::from_utf8_lossy(&bytes);
 
         for (line_num, line) in content.lines().enumerate() {
-            let trimmed = line.trim();
-            if trimmed.is_empty() || trimmed.starts_with('#') {
-                continue;
-            }
-
-            let export_line = trimmed.strip_prefix("export ").unwrap_or(trimmed);
-            let Some(eq_pos) = export_line.find('=') else {
+            let Some(var_name) = assigned_var_name(line) else {
                 continue;
             };
 
-            let var_name = export_line[..eq_pos].trim();
-            if var_name.is_empty() {
-                continue;
-            }
-
             let var_name_upper = var_name.to_ascii_uppercase();
             if !keywords.iter().any(|k| var_name_upper.contains(k)) {
                 continue;
@@ -159,6 +179,11 @@ pub fn check_env_conflicts<R: tauri::Runtime>(
     #[cfg(not(target_os = "windows"))]
     check_shell_configs(app, &keywords, &mut out, &mut seen)?;
 
+    let ignore_rules = crate::settings::read(app)
+        .map(|settings| settings.env_conflict_ignore_rules)
+        .unwrap_or_default();
+    out.retain(|conflict| !is_ignored(conflict, &ignore_rules));
+
     out.sort_by(|a, b| {
         let a_type = a.source_type.as_str();
         let b_type = b.source_type.as_str();
@@ -196,6 +221,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ignore_rules_match_var_and_optional_source() {
+        let conflict = EnvConflict {
+            var_name: "ANTHROPIC_BASE_URL".to_string(),
+            source_type: "file".to_string(),
+            source_path: "/home/u/.zshrc:12".to_string(),
+        };
+        let rule = |source_type: &str, source_path: &str| EnvConflictIgnoreRule {
+            var_name: "anthropic_base_url".to_string(),
+            source_type: source_type.to_string(),
+            source_path: source_path.to_string(),
+        };
+
+        assert!(is_ignored(&conflict, &[rule("", "")]));
+        assert!(is_ignored(&conflict, &[rule("file", "/home/u/.zshrc")]));
+        assert!(is_ignored(&conflict, &[rule("file", "/home/u/.zshrc:40")]));
+        assert!(!is_ignored(&conflict, &[rule("system", "")]));
+        assert!(!is_ignored(&conflict, &[rule("file", "/home/u/.bashrc")]));
+        assert_eq!(assigned_var_name("  export FOO_KEY = bar"), Some("FOO_KEY"));
+        assert_eq!(assigned_var_name("# export FOO_KEY=bar"), None);
+    }
+
     #[test]
     fn dedupe_key_for_system_keeps_source_path() {
         let conflict = EnvConflict {
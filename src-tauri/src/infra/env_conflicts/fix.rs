@@ -0,0 +1,540 @@
+//! Usage: Apply (and undo) user-requested fixes for reported env conflicts.
+//!
+//! Shell rc entries are commented out or rewritten in place; on Windows the user-level
+//! (`HKCU\Environment`) variable is deleted or rewritten. Every touched source is backed up under
+//! `<app_data>/env-conflict-backups/<backup_id>/` so the whole fix can be undone.
+
+use super::{assigned_var_name, shell_config_files, strip_trailing_line_number};
+use crate::app_paths;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::Manager;
+
+const BACKUP_DIR_NAME: &str = "env-conflict-backups";
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+const MAX_REWRITE_VALUE_CHARS: usize = 4096;
+const COMMENT_PREFIX: &str = "# [aio-coding-hub] ";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvConflictFixAction {
+    /// Comment the rc line out (Windows user env: delete the variable).
+    CommentOut,
+    /// Replace the assigned value.
+    Rewrite,
+}
+
+#[derive(Debug, Clone, Deserialize, specta::Type)]
+pub struct EnvConflictFixTarget {
+    pub var_name: String,
+    pub source_type: String,
+    pub source_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct EnvConflictFixOutcome {
+    pub var_name: String,
+    pub source_path: String,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct EnvConflictFixResult {
+    /// Pass to `env_conflicts_fix_undo`; `None` when nothing was changed.
+    pub backup_id: Option<String>,
+    pub outcomes: Vec<EnvConflictFixOutcome>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum BackupEntry {
+    File {
+        path: PathBuf,
+        backup_file: String,
+    },
+    WindowsUserEnv {
+        var_name: String,
+        /// `(reg type, value)` before the fix; `None` = the variable did not exist.
+        previous: Option<(String, String)>,
+    },
+}
+
+fn outcome(target: &EnvConflictFixTarget, error: Option<String>) -> EnvConflictFixOutcome {
+    EnvConflictFixOutcome {
+        var_name: target.var_name.clone(),
+        source_path: target.source_path.clone(),
+        ok: error.is_none(),
+        error,
+    }
+}
+
+fn validate_rewrite_value(
+    action: EnvConflictFixAction,
+    value: Option<&str>,
+) -> crate::shared::error::AppResult<()> {
+    if action != EnvConflictFixAction::Rewrite {
+        return Ok(());
+    }
+    let Some(value) = value else {
+        return Err("SEC_INVALID_INPUT: value is required for rewrite".into());
+    };
+    if value.chars().count() > MAX_REWRITE_VALUE_CHARS {
+        return Err(format!(
+            "SEC_INVALID_INPUT: value is too long (max {MAX_REWRITE_VALUE_CHARS} chars)"
+        )
+        .into());
+    }
+    if value.contains(['\n', '\r', '\0']) {
+        return Err("SEC_INVALID_INPUT: value must be a single line".into());
+    }
+    Ok(())
+}
+
+fn shell_quote(value: &str) -> String {
+    let is_plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_./:@%+=,-".contains(c));
+    if is_plain {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+/// Applies `action` to one rc line, keeping indentation, `export` and the line ending.
+fn edit_rc_line(line: &str, action: EnvConflictFixAction, value: Option<&str>) -> String {
+    let body_end = line.trim_end_matches(['\n', '\r']).len();
+    let (body, eol) = line.split_at(body_end);
+    let indent_len = body.len() - body.trim_start().len();
+    let (indent, rest) = body.split_at(indent_len);
+
+    match action {
+        EnvConflictFixAction::CommentOut => format!("{indent}{COMMENT_PREFIX}{rest}{eol}"),
+        EnvConflictFixAction::Rewrite => {
+            let export = if rest.starts_with("export ") {
+                "export "
+            } else {
+                ""
+            };
+            let name = assigned_var_name(rest).unwrap_or_default();
+            let quoted = shell_quote(value.unwrap_or_default());
+            format!("{indent}{export}{name}={quoted}{eol}")
+        }
+    }
+}
+
+fn parse_file_source(source_path: &str) -> Option<(PathBuf, usize)> {
+    let path = strip_trailing_line_number(source_path);
+    let line: usize = source_path.get(path.len() + 1..)?.parse().ok()?;
+    (line > 0).then(|| (PathBuf::from(path), line))
+}
+
+struct FileFix<'a> {
+    path: &'a Path,
+    lines: Vec<String>,
+}
+
+impl FileFix<'_> {
+    fn apply(
+        &mut self,
+        line_no: usize,
+        var_name: &str,
+        action: EnvConflictFixAction,
+        value: Option<&str>,
+    ) -> Result<(), String> {
+        let Some(line) = self.lines.get_mut(line_no - 1) else {
+            return Err(format!(
+                "{}:{line_no} no longer exists; re-run the check",
+                self.path.display()
+            ));
+        };
+        let assigns = assigned_var_name(line).is_some_and(|n| n.eq_ignore_ascii_case(var_name));
+        if !assigns {
+            return Err(format!(
+                "{}:{line_no} no longer assigns {var_name}; re-run the check",
+                self.path.display()
+            ));
+        }
+        *line = edit_rc_line(line, action, value);
+        Ok(())
+    }
+}
+
+struct BackupWriter {
+    id: String,
+    dir: PathBuf,
+    entries: Vec<BackupEntry>,
+}
+
+impl BackupWriter {
+    fn backup_file(&mut self, path: &Path, original: &[u8]) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("failed to create backup dir: {e}"))?;
+        let backup_file = format!("{}.bak", self.entries.len());
+        std::fs::write(self.dir.join(&backup_file), original)
+            .map_err(|e| format!("failed to back up {}: {e}", path.display()))?;
+        self.entries.push(BackupEntry::File {
+            path: path.to_path_buf(),
+            backup_file,
+        });
+        Ok(())
+    }
+
+    fn finish(self) -> crate::shared::error::AppResult<Option<String>> {
+        if self.entries.is_empty() {
+            return Ok(None);
+        }
+        let manifest = serde_json::to_vec_pretty(&self.entries)
+            .map_err(|e| format!("SYSTEM_ERROR: failed to encode backup manifest: {e}"))?;
+        std::fs::write(self.dir.join(MANIFEST_FILE_NAME), manifest)
+            .map_err(|e| format!("failed to write backup manifest: {e}"))?;
+        Ok(Some(self.id))
+    }
+}
+
+fn backups_root<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> crate::shared::error::AppResult<PathBuf> {
+    Ok(app_paths::app_data_dir(app)?.join(BACKUP_DIR_NAME))
+}
+
+/// Fixes the given conflicts; each target reports its own outcome and the changed sources share
+/// one backup. Only the scanned shell rc files (and the Windows user environment) are editable.
+pub fn fix_env_conflicts<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    targets: Vec<EnvConflictFixTarget>,
+    action: EnvConflictFixAction,
+    value: Option<String>,
+) -> crate::shared::error::AppResult<EnvConflictFixResult> {
+    if targets.is_empty() {
+        return Err("SEC_INVALID_INPUT: targets is required".into());
+    }
+    let value = value.as_deref();
+    validate_rewrite_value(action, value)?;
+
+    let home_dir = app
+        .path()
+        .home_dir()
+        .map_err(|e| format!("failed to resolve home dir: {e}"))?;
+    let allowed_files = shell_config_files(&home_dir);
+
+    let now_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let backup_id = now_ms.to_string();
+    let mut backups = BackupWriter {
+        dir: backups_root(app)?.join(&backup_id),
+        id: backup_id,
+        entries: Vec::new(),
+    };
+
+    let mut outcomes: Vec<Option<EnvConflictFixOutcome>> = vec![None; targets.len()];
+    let mut by_file: BTreeMap<PathBuf, Vec<(usize, usize)>> = BTreeMap::new();
+    for (idx, target) in targets.iter().enumerate() {
+        match target.source_type.as_str() {
+            "file" => match parse_file_source(&target.source_path) {
+                Some((path, line)) if allowed_files.contains(&path) => {
+                    by_file.entry(path).or_default().push((idx, line));
+                }
+                Some(_) => {
+                    outcomes[idx] = Some(outcome(
+                        target,
+                        Some("SEC_INVALID_INPUT: file is not a scanned shell config".to_string()),
+                    ));
+                }
+                None => {
+                    outcomes[idx] = Some(outcome(
+                        target,
+                        Some("SEC_INVALID_INPUT: invalid source_path".to_string()),
+                    ));
+                }
+            },
+            "system" => {
+                let result = fix_system_var(&target.var_name, action, value, &mut backups);
+                outcomes[idx] = Some(outcome(target, result.err()));
+            }
+            _ => {
+                outcomes[idx] = Some(outcome(
+                    target,
+                    Some("SEC_INVALID_INPUT: unknown source_type".to_string()),
+                ));
+            }
+        }
+    }
+
+    for (path, entries) in by_file {
+        let file_result = std::fs::read(&path)
+            .map_err(|e| format!("failed to read {}: {e}", path.display()))
+            .and_then(|bytes| {
+                String::from_utf8(bytes)
+                    .map_err(|_| format!("{} is not valid UTF-8", path.display()))
+            });
+        let original = match file_result {
+            Ok(v) => v,
+            Err(err) => {
+                for (idx, _) in entries {
+                    outcomes[idx] = Some(outcome(&targets[idx], Some(err.clone())));
+                }
+                continue;
+            }
+        };
+
+        let mut fix = FileFix {
+            path: &path,
+            lines: original.split_inclusive('\n').map(str::to_string).collect(),
+        };
+        let mut applied = Vec::new();
+        for (idx, line) in entries {
+            match fix.apply(line, &targets[idx].var_name, action, value) {
+                Ok(()) => applied.push(idx),
+                Err(err) => outcomes[idx] = Some(outcome(&targets[idx], Some(err))),
+            }
+        }
+        if applied.is_empty() {
+            continue;
+        }
+
+        // Written in place (not renamed) so symlinked dotfiles keep pointing at their target.
+        let write_result = backups
+            .backup_file(&path, original.as_bytes())
+            .and_then(|()| {
+                std::fs::write(&path, fix.lines.concat())
+                    .map_err(|e| format!("failed to write {}: {e}", path.display()))
+            });
+        for idx in applied {
+            outcomes[idx] = Some(outcome(&targets[idx], write_result.clone().err()));
+        }
+    }
+
+    Ok(EnvConflictFixResult {
+        backup_id: backups.finish()?,
+        outcomes: outcomes.into_iter().flatten().collect(),
+    })
+}
+
+#[cfg(windows)]
+fn fix_system_var(
+    var_name: &str,
+    action: EnvConflictFixAction,
+    value: Option<&str>,
+    backups: &mut BackupWriter,
+) -> Result<(), String> {
+    let Some((reg_type, previous_value)) = windows_user_env::query(var_name)? else {
+        return Err(format!(
+            "{var_name} is not set in the user environment (machine-wide or inherited); change it manually"
+        ));
+    };
+    match action {
+        EnvConflictFixAction::CommentOut => windows_user_env::delete(var_name)?,
+        EnvConflictFixAction::Rewrite => {
+            windows_user_env::set(var_name, &reg_type, value.unwrap_or_default())?
+        }
+    }
+    backups.entries.push(BackupEntry::WindowsUserEnv {
+        var_name: var_name.to_string(),
+        previous: Some((reg_type, previous_value)),
+    });
+    std::fs::create_dir_all(&backups.dir).map_err(|e| format!("failed to create backup dir: {e}"))
+}
+
+#[cfg(not(windows))]
+fn fix_system_var(
+    var_name: &str,
+    _action: EnvConflictFixAction,
+    _value: Option<&str>,
+    _backups: &mut BackupWriter,
+) -> Result<(), String> {
+    Err(format!(
+        "{var_name} is inherited from the environment that launched the app; fix the shell config that exports it"
+    ))
+}
+
+/// Restores every source recorded under `backup_id`, then removes the backup.
+pub fn undo_env_conflicts_fix<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    backup_id: &str,
+) -> crate::shared::error::AppResult<u32> {
+    if backup_id.is_empty() || !backup_id.chars().all(|c| c.is_ascii_digit()) {
+        return Err("SEC_INVALID_INPUT: invalid backup_id".into());
+    }
+    let dir = backups_root(app)?.join(backup_id);
+    let manifest = std::fs::read(dir.join(MANIFEST_FILE_NAME))
+        .map_err(|_| "DB_NOT_FOUND: env conflict backup not found".to_string())?;
+    let entries: Vec<BackupEntry> = serde_json::from_slice(&manifest)
+        .map_err(|e| format!("SYSTEM_ERROR: invalid backup manifest: {e}"))?;
+
+    let mut restored = 0u32;
+    for entry in &entries {
+        match entry {
+            BackupEntry::File { path, backup_file } => {
+                let bytes = std::fs::read(dir.join(backup_file))
+                    .map_err(|e| format!("failed to read backup {backup_file}: {e}"))?;
+                std::fs::write(path, bytes)
+                    .map_err(|e| format!("failed to restore {}: {e}", path.display()))?;
+            }
+            BackupEntry::WindowsUserEnv { var_name, previous } => {
+                restore_system_var(var_name, previous.as_ref())?;
+            }
+        }
+        restored += 1;
+    }
+
+    if let Err(err) = std::fs::remove_dir_all(&dir) {
+        tracing::warn!(backup_id = %backup_id, "env conflict backup cleanup failed: {}", err);
+    }
+    Ok(restored)
+}
+
+#[cfg(windows)]
+fn restore_system_var(
+    var_name: &str,
+    previous: Option<&(String, String)>,
+) -> crate::shared::error::AppResult<()> {
+    match previous {
+        Some((reg_type, value)) => windows_user_env::set(var_name, reg_type, value)?,
+        None => windows_user_env::delete(var_name)?,
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn restore_system_var(
+    var_name: &str,
+    _previous: Option<&(String, String)>,
+) -> crate::shared::error::AppResult<()> {
+    Err(
+        format!("SYSTEM_ERROR: cannot restore Windows user env var {var_name} on this platform")
+            .into(),
+    )
+}
+
+#[cfg(windows)]
+mod windows_user_env {
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    const ENV_KEY: &str = r"HKCU\Environment";
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    fn reg(args: &[&str]) -> Result<std::process::Output, String> {
+        Command::new("reg")
+            .args(args)
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map_err(|e| format!("failed to run reg.exe: {e}"))
+    }
+
+    fn run(args: &[&str]) -> Result<(), String> {
+        let output = reg(args)?;
+        if !output.status.success() {
+            return Err(format!(
+                "reg.exe failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        broadcast_environment_change();
+        Ok(())
+    }
+
+    /// `(reg type, value)` of the user-level variable, if set.
+    pub(super) fn query(var_name: &str) -> Result<Option<(String, String)>, String> {
+        let output = reg(&["query", ENV_KEY, "/v", var_name])?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout.lines().find_map(|line| {
+            let mut parts = line.trim().splitn(3, "    ");
+            let name = parts.next()?;
+            let reg_type = parts.next()?;
+            name.eq_ignore_ascii_case(var_name).then(|| {
+                (
+                    reg_type.trim().to_string(),
+                    parts.next().unwrap_or_default().trim().to_string(),
+                )
+            })
+        }))
+    }
+
+    pub(super) fn set(var_name: &str, reg_type: &str, value: &str) -> Result<(), String> {
+        run(&[
+            "add", ENV_KEY, "/v", var_name, "/t", reg_type, "/d", value, "/f",
+        ])
+    }
+
+    pub(super) fn delete(var_name: &str) -> Result<(), String> {
+        run(&["delete", ENV_KEY, "/v", var_name, "/f"])
+    }
+
+    /// Lets Explorer (and terminals it starts later) pick up the change without a re-login.
+    fn broadcast_environment_change() {
+        use windows_sys::Win32::UI::WindowsAndMessaging::{
+            SendMessageTimeoutW, HWND_BROADCAST, SMTO_ABORTIFHUNG, WM_SETTINGCHANGE,
+        };
+        let param: Vec<u16> = "Environment\0".encode_utf16().collect();
+        let mut result: usize = 0;
+        // SAFETY: `param` is a NUL-terminated UTF-16 string that outlives the call.
+        unsafe {
+            SendMessageTimeoutW(
+                HWND_BROADCAST,
+                WM_SETTINGCHANGE,
+                0,
+                param.as_ptr() as isize,
+                SMTO_ABORTIFHUNG,
+                2000,
+                &mut result,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_rc_line_comments_out_and_rewrites_preserving_layout() {
+        assert_eq!(
+            edit_rc_line(
+                "  export ANTHROPIC_BASE_URL=https://x\n",
+                EnvConflictFixAction::CommentOut,
+                None
+            ),
+            "  # [aio-coding-hub] export ANTHROPIC_BASE_URL=https://x\n"
+        );
+        assert_eq!(
+            edit_rc_line(
+                "export ANTHROPIC_BASE_URL=https://x\r\n",
+                EnvConflictFixAction::Rewrite,
+                Some("http://127.0.0.1:37123")
+            ),
+            "export ANTHROPIC_BASE_URL=http://127.0.0.1:37123\r\n"
+        );
+        assert_eq!(
+            edit_rc_line(
+                "OPENAI_API_KEY=\"old\"",
+                EnvConflictFixAction::Rewrite,
+                Some("it's new")
+            ),
+            r"OPENAI_API_KEY='it'\''s new'"
+        );
+    }
+
+    #[test]
+    fn parse_file_source_requires_line_number() {
+        assert_eq!(
+            parse_file_source("/home/u/.zshrc:12"),
+            Some((PathBuf::from("/home/u/.zshrc"), 12))
+        );
+        assert_eq!(parse_file_source("/home/u/.zshrc"), None);
+        assert_eq!(parse_file_source("/home/u/.zshrc:0"), None);
+        assert!(validate_rewrite_value(EnvConflictFixAction::Rewrite, None).is_err());
+        assert!(validate_rewrite_value(EnvConflictFixAction::Rewrite, Some("a\nb")).is_err());
+        assert!(validate_rewrite_value(EnvConflictFixAction::CommentOut, None).is_ok());
+    }
+}
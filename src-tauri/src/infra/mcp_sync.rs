@@ -18,6 +18,7 @@ mod types;
 pub(crate) use types::McpServerForSync;
 
 pub use fs::{read_target_bytes, restore_target_bytes};
-pub use manifest::{read_manifest_bytes, restore_manifest_bytes};
-pub(crate) use sync::build_next_bytes;
+pub use manifest::{read_managed_keys, read_manifest_bytes, restore_manifest_bytes};
+pub(crate) use paths::mcp_target_path;
 pub use sync::sync_cli;
+pub(crate) use sync::{build_next_bytes, render_server_spec};
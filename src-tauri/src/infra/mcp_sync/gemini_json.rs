@@ -3,7 +3,9 @@
 use super::json_patch::{json_root_from_bytes, json_to_bytes, patch_json_mcp_servers};
 use super::McpServerForSync;
 
-fn build_gemini_mcp_spec(server: &McpServerForSync) -> Result<serde_json::Value, String> {
+pub(super) fn build_gemini_mcp_spec(
+    server: &McpServerForSync,
+) -> Result<serde_json::Value, String> {
     let transport = server.transport.as_str();
     match transport {
         "stdio" => {
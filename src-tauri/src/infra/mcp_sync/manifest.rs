@@ -60,6 +60,19 @@ pub fn restore_manifest_bytes<R: tauri::Runtime>(
     }
 }
 
+/// Server keys currently owned by the hub in the `cli_key` target file.
+///
+/// Returns an empty list when sync has never been enabled (or was disabled) for the CLI.
+pub fn read_managed_keys<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<Vec<String>> {
+    Ok(read_manifest(app, cli_key)?
+        .filter(|m| m.enabled)
+        .map(|m| m.managed_keys)
+        .unwrap_or_default())
+}
+
 pub(super) fn read_manifest<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     cli_key: &str,
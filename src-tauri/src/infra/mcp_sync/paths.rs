@@ -17,7 +17,7 @@ pub(super) fn home_dir<R: tauri::Runtime>(
         .map_err(|e| format!("failed to resolve home dir: {e}").into())
 }
 
-pub(super) fn mcp_target_path<R: tauri::Runtime>(
+pub(crate) fn mcp_target_path<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     cli_key: &str,
 ) -> crate::shared::error::AppResult<PathBuf> {
@@ -3,10 +3,10 @@
 use crate::shared::time::now_unix_seconds;
 use std::collections::HashSet;
 
-use super::claude_json::build_claude_config_json;
+use super::claude_json::{build_claude_config_json, build_claude_mcp_spec};
 use super::codex_toml::build_codex_config_toml;
 use super::fs::{read_optional_file, write_file_atomic_if_changed};
-use super::gemini_json::build_gemini_settings_json;
+use super::gemini_json::{build_gemini_mcp_spec, build_gemini_settings_json};
 use super::manifest::{backup_for_enable, read_manifest, write_manifest};
 use super::paths::{mcp_target_path, validate_cli_key};
 use super::McpServerForSync;
@@ -25,6 +25,34 @@ pub(crate) fn build_next_bytes(
     }
 }
 
+/// Render the config entry `server` would produce in the `cli_key` target file, as JSON.
+///
+/// Drift detection compares DB state and on-disk entries through this so both sides share the
+/// exact shape the sync writer emits (e.g. Codex drops `headers` for stdio servers).
+pub(crate) fn render_server_spec(
+    cli_key: &str,
+    server: &McpServerForSync,
+) -> Result<serde_json::Value, String> {
+    match cli_key {
+        "claude" => build_claude_mcp_spec(server),
+        "gemini" => build_gemini_mcp_spec(server),
+        "codex" => {
+            let bytes = build_codex_config_toml(None, &[], std::slice::from_ref(server))?;
+            let text = String::from_utf8_lossy(&bytes);
+            let root: toml::Value = toml::from_str(&text)
+                .map_err(|e| format!("failed to parse rendered codex toml: {e}"))?;
+            let spec = root
+                .get("mcp_servers")
+                .and_then(|v| v.get(server.server_key.as_str()))
+                .cloned()
+                .unwrap_or_else(|| toml::Value::Table(toml::value::Table::new()));
+            serde_json::to_value(spec)
+                .map_err(|e| format!("failed to convert codex mcp spec to json: {e}"))
+        }
+        _ => Err(format!("SEC_INVALID_INPUT: unknown cli_key={cli_key}")),
+    }
+}
+
 fn normalized_keys(servers: &[McpServerForSync]) -> Vec<String> {
     let mut keys: Vec<String> = servers.iter().map(|s| s.server_key.to_string()).collect();
     keys.sort();
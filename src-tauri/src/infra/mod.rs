@@ -13,10 +13,15 @@ pub(crate) mod env_conflicts;
 pub(crate) mod mcp_sync;
 pub(crate) mod model_price_aliases;
 pub(crate) mod model_prices;
+pub(crate) mod model_prices_import;
 pub(crate) mod model_prices_sync;
+pub(crate) mod observer_mode;
 pub(crate) mod prompt_sync;
 pub(crate) mod provider_circuit_breakers;
+pub(crate) mod redaction;
 pub(crate) mod request_attempt_logs;
 pub(crate) mod request_logs;
 pub(crate) mod settings;
+pub(crate) mod ssh_clients;
+pub(crate) mod subagent_sync;
 pub(crate) mod wsl;
@@ -12,7 +12,7 @@ const ALIASES_FILE_NAME: &str = "price-aliases.json";
 const ALIASES_SCHEMA_VERSION_V1: i64 = 1;
 const MAX_MODEL_LEN: usize = 200;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 #[serde(rename_all = "snake_case")]
 pub enum ModelPriceAliasMatchTypeV1 {
     Exact,
@@ -20,7 +20,7 @@ pub enum ModelPriceAliasMatchTypeV1 {
     Wildcard,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct ModelPriceAliasRuleV1 {
     pub cli_key: String,
     pub match_type: ModelPriceAliasMatchTypeV1,
@@ -29,7 +29,7 @@ pub struct ModelPriceAliasRuleV1 {
     pub enabled: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 #[serde(default)]
 pub struct ModelPriceAliasesV1 {
     pub version: i64,
@@ -49,13 +49,13 @@ fn validate_cli_key(cli_key: &str) -> Result<(), String> {
     crate::shared::cli_key::validate_cli_key(cli_key).map_err(Into::into)
 }
 
-fn model_prices_dir(app: &tauri::AppHandle) -> crate::shared::error::AppResult<PathBuf> {
+fn model_prices_dir(app: &crate::AppHandle) -> crate::shared::error::AppResult<PathBuf> {
     let dir = app_paths::app_data_dir(app)?.join(MODEL_PRICE_DIR_NAME);
     std::fs::create_dir_all(&dir).map_err(|e| format!("failed to create model-prices dir: {e}"))?;
     Ok(dir)
 }
 
-fn aliases_path(app: &tauri::AppHandle) -> crate::shared::error::AppResult<PathBuf> {
+fn aliases_path(app: &crate::AppHandle) -> crate::shared::error::AppResult<PathBuf> {
     Ok(model_prices_dir(app)?.join(ALIASES_FILE_NAME))
 }
 
@@ -164,7 +164,7 @@ fn write_json_atomically(path: &Path, json_bytes: Vec<u8>) -> crate::shared::err
     Ok(())
 }
 
-pub fn read_fail_open(app: &tauri::AppHandle) -> ModelPriceAliasesV1 {
+pub fn read_fail_open(app: &crate::AppHandle) -> ModelPriceAliasesV1 {
     match read(app) {
         Ok(v) => v,
         Err(err) => {
@@ -174,7 +174,7 @@ pub fn read_fail_open(app: &tauri::AppHandle) -> ModelPriceAliasesV1 {
     }
 }
 
-pub fn read(app: &tauri::AppHandle) -> crate::shared::error::AppResult<ModelPriceAliasesV1> {
+pub fn read(app: &crate::AppHandle) -> crate::shared::error::AppResult<ModelPriceAliasesV1> {
     let path = aliases_path(app)?;
     if !path.exists() {
         return Ok(ModelPriceAliasesV1::default());
@@ -188,7 +188,7 @@ pub fn read(app: &tauri::AppHandle) -> crate::shared::error::AppResult<ModelPric
 }
 
 pub fn write(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     aliases: ModelPriceAliasesV1,
 ) -> crate::shared::error::AppResult<ModelPriceAliasesV1> {
     let aliases = validate_aliases(aliases)?;
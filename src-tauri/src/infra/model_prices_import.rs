@@ -0,0 +1,571 @@
+//! Usage: Import a user-supplied price table (CSV/JSON) into `model_prices`, with a dry-run diff.
+//!
+//! Each row carries a model, input/output/cache prices and an optional provider scope (a cli key
+//! or the vendor name: `anthropic`/`openai`/`google`). Prices are merged into the existing
+//! `price_json`, so fields the table does not mention (e.g. above-200k tiers) are kept.
+
+use super::model_prices_sync::{
+    cli_key_from_basellm_provider, json_scalar_to_string, load_existing_price_map,
+    shift_cost_per_1m_to_per_token,
+};
+use crate::db;
+use crate::shared::error::db_err;
+use crate::shared::time::now_unix_seconds;
+use rusqlite::params;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+const MAX_IMPORT_BYTES: usize = 2 * 1024 * 1024;
+const MAX_IMPORT_ROWS: usize = 5_000;
+/// Upper bound for any single price, in USD per 1M tokens.
+const MAX_PRICE_PER_1M: f64 = 10_000.0;
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ModelPriceImportChange {
+    pub line: u32,
+    pub cli_key: String,
+    pub model: String,
+    /// `insert` | `update`
+    pub action: String,
+    pub before_json: Option<String>,
+    pub after_json: String,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ModelPriceImportIssue {
+    pub line: u32,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct ModelPricesImportReport {
+    pub dry_run: bool,
+    /// `false` for dry runs and whenever any row was rejected (imports are all-or-nothing).
+    pub applied: bool,
+    pub inserted: u32,
+    pub updated: u32,
+    pub unchanged: u32,
+    pub changes: Vec<ModelPriceImportChange>,
+    pub errors: Vec<ModelPriceImportIssue>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Provider,
+    Model,
+    Unit,
+    Input,
+    Output,
+    CacheRead,
+    CacheWrite,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        match name
+            .trim()
+            .to_ascii_lowercase()
+            .replace([' ', '-'], "_")
+            .as_str()
+        {
+            "provider" | "cli_key" | "cli" => Some(Self::Provider),
+            "model" => Some(Self::Model),
+            "unit" => Some(Self::Unit),
+            "input" | "input_price" => Some(Self::Input),
+            "output" | "output_price" => Some(Self::Output),
+            "cache_read" | "cache_read_price" => Some(Self::CacheRead),
+            "cache_write" | "cache_write_price" => Some(Self::CacheWrite),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ImportRecord {
+    line: u32,
+    values: HashMap<&'static str, String>,
+}
+
+impl ImportRecord {
+    fn set(&mut self, field: Field, value: String) {
+        let key = match field {
+            Field::Provider => "provider",
+            Field::Model => "model",
+            Field::Unit => "unit",
+            Field::Input => "input",
+            Field::Output => "output",
+            Field::CacheRead => "cache_read",
+            Field::CacheWrite => "cache_write",
+        };
+        let value = value.trim();
+        if !value.is_empty() {
+            self.values.insert(key, value.to_string());
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ImportRow {
+    line: u32,
+    cli_key: String,
+    model: String,
+    /// Per-token price fields to merge into `price_json`.
+    fields: serde_json::Map<String, Value>,
+}
+
+fn detect_format(
+    content: &str,
+    format: Option<&str>,
+) -> crate::shared::error::AppResult<ImportFormat> {
+    match format.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+        Some("csv") => Ok(ImportFormat::Csv),
+        Some("json") => Ok(ImportFormat::Json),
+        Some("") | None => {
+            let first = content
+                .trim_start_matches('\u{feff}')
+                .trim_start()
+                .chars()
+                .next();
+            Ok(if matches!(first, Some('[' | '{')) {
+                ImportFormat::Json
+            } else {
+                ImportFormat::Csv
+            })
+        }
+        Some(other) => Err(format!("SEC_INVALID_INPUT: unsupported import format={other}").into()),
+    }
+}
+
+/// Splits one CSV line; supports double-quoted cells with `""` escapes (no embedded newlines).
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if cell.trim().is_empty() => {
+                cell.clear();
+                in_quotes = true;
+            }
+            (',', false) => cells.push(std::mem::take(&mut cell)),
+            _ => cell.push(c),
+        }
+    }
+    cells.push(cell);
+    cells
+}
+
+fn parse_csv_records(content: &str) -> crate::shared::error::AppResult<Vec<ImportRecord>> {
+    let mut lines = content
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'));
+
+    let Some((_, header)) = lines.next() else {
+        return Ok(Vec::new());
+    };
+    let mut columns = Vec::new();
+    for name in split_csv_line(header) {
+        let field = Field::parse(&name).ok_or_else(|| {
+            format!(
+                "SEC_INVALID_INPUT: unknown price table column={}",
+                name.trim()
+            )
+        })?;
+        columns.push(field);
+    }
+    if !columns.contains(&Field::Model) {
+        return Err("SEC_INVALID_INPUT: price table is missing the model column"
+            .to_string()
+            .into());
+    }
+
+    let mut records = Vec::new();
+    for (idx, line) in lines {
+        let mut record = ImportRecord {
+            line: idx as u32 + 1,
+            ..Default::default()
+        };
+        for (field, value) in columns.iter().zip(split_csv_line(line)) {
+            record.set(*field, value);
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+fn parse_json_records(content: &str) -> crate::shared::error::AppResult<Vec<ImportRecord>> {
+    let root: Value = serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("SEC_INVALID_INPUT: price table is not valid JSON: {e}"))?;
+    let items = match &root {
+        Value::Array(items) => items,
+        Value::Object(map) => match map.get("prices").or_else(|| map.get("models")) {
+            Some(Value::Array(items)) => items,
+            _ => {
+                return Err(
+                    "SEC_INVALID_INPUT: JSON price table must have a prices array"
+                        .to_string()
+                        .into(),
+                )
+            }
+        },
+        _ => {
+            return Err("SEC_INVALID_INPUT: JSON price table must be an array"
+                .to_string()
+                .into())
+        }
+    };
+
+    let mut records = Vec::new();
+    for (idx, item) in items.iter().enumerate() {
+        let mut record = ImportRecord {
+            line: idx as u32 + 1,
+            ..Default::default()
+        };
+        let Some(map) = item.as_object() else {
+            // Left empty so validation reports it against this entry.
+            records.push(record);
+            continue;
+        };
+        for (key, value) in map {
+            let field = Field::parse(key)
+                .ok_or_else(|| format!("SEC_INVALID_INPUT: unknown price table field={key}"))?;
+            if let Some(value) = json_scalar_to_string(value) {
+                record.set(field, value);
+            }
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Decimal exponent that turns a price in `unit` into a price per 1M tokens.
+fn unit_exponent(unit: Option<&str>) -> Result<i64, String> {
+    match unit.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+        None | Some("per_1m" | "1m" | "per_million" | "mtok") => Ok(0),
+        Some("per_1k" | "1k" | "per_thousand" | "ktok") => Ok(3),
+        Some("per_token" | "token") => Ok(6),
+        Some(other) => Err(format!(
+            "unknown unit={other} (expected per_1m, per_1k or per_token)"
+        )),
+    }
+}
+
+/// Validates one price and converts it to the per-token string stored in `price_json`.
+fn price_per_token(name: &str, raw: &str, unit_exp: i64) -> Result<String, String> {
+    let raw = raw.trim().trim_start_matches('$').trim();
+    let value: f64 = raw
+        .parse()
+        .map_err(|_| format!("{name} is not a number: {raw}"))?;
+    let per_1m = value * 10f64.powi(unit_exp as i32);
+    if !per_1m.is_finite() || per_1m < 0.0 {
+        return Err(format!("{name} must be a non-negative number"));
+    }
+    if per_1m > MAX_PRICE_PER_1M {
+        return Err(format!(
+            "{name} exceeds {MAX_PRICE_PER_1M} USD per 1M tokens (check the unit)"
+        ));
+    }
+
+    // Scale by moving the decimal exponent, keeping the decimal string exact.
+    let scaled = match raw.split_once(['e', 'E']) {
+        Some((mantissa, exp)) => {
+            let exp: i64 = exp
+                .trim()
+                .parse()
+                .map_err(|_| format!("{name} is not a number: {raw}"))?;
+            format!("{mantissa}e{}", exp + unit_exp)
+        }
+        None => format!("{raw}e{unit_exp}"),
+    };
+    shift_cost_per_1m_to_per_token(&scaled).ok_or_else(|| format!("{name} is not a number: {raw}"))
+}
+
+fn validate_record(
+    record: &ImportRecord,
+    default_cli_key: Option<&str>,
+) -> Result<ImportRow, String> {
+    let cli_key = match record.get("provider") {
+        Some(provider) => {
+            let lower = provider.to_ascii_lowercase();
+            if crate::shared::cli_key::is_supported_cli_key(&lower) {
+                lower
+            } else {
+                cli_key_from_basellm_provider(&lower)
+                    .map(str::to_string)
+                    .ok_or_else(|| format!("unknown provider={provider}"))?
+            }
+        }
+        None => default_cli_key
+            .map(str::to_string)
+            .ok_or_else(|| "provider is required (no default cli_key given)".to_string())?,
+    };
+    let model = record
+        .get("model")
+        .ok_or_else(|| "model is required".to_string())?
+        .to_string();
+    let unit_exp = unit_exponent(record.get("unit"))?;
+
+    let mut fields = serde_json::Map::new();
+    for (column, key) in [
+        ("input", "input_cost_per_token"),
+        ("output", "output_cost_per_token"),
+        ("cache_read", "cache_read_input_token_cost"),
+        ("cache_write", "cache_creation_input_token_cost"),
+    ] {
+        if let Some(raw) = record.get(column) {
+            fields.insert(
+                key.to_string(),
+                Value::String(price_per_token(column, raw, unit_exp)?),
+            );
+        }
+    }
+    if !fields.contains_key("input_cost_per_token") && !fields.contains_key("output_cost_per_token")
+    {
+        return Err("at least one of input/output price is required".to_string());
+    }
+    // Same as the basellm sync: the 1h cache write tier falls back to the base write price.
+    if let Some(v) = fields.get("cache_creation_input_token_cost").cloned() {
+        fields.insert("cache_creation_input_token_cost_above_1hr".to_string(), v);
+    }
+
+    Ok(ImportRow {
+        line: record.line,
+        cli_key,
+        model,
+        fields,
+    })
+}
+
+fn parse_rows(
+    content: &str,
+    format: Option<&str>,
+    default_cli_key: Option<&str>,
+) -> crate::shared::error::AppResult<(Vec<ImportRow>, Vec<ModelPriceImportIssue>)> {
+    if content.len() > MAX_IMPORT_BYTES {
+        return Err(format!(
+            "SEC_INVALID_INPUT: price table exceeds {} bytes",
+            MAX_IMPORT_BYTES
+        )
+        .into());
+    }
+    if let Some(cli_key) = default_cli_key {
+        crate::shared::cli_key::validate_cli_key(cli_key)?;
+    }
+
+    let records = match detect_format(content, format)? {
+        ImportFormat::Csv => parse_csv_records(content)?,
+        ImportFormat::Json => parse_json_records(content)?,
+    };
+    if records.len() > MAX_IMPORT_ROWS {
+        return Err(
+            format!("SEC_INVALID_INPUT: price table exceeds {MAX_IMPORT_ROWS} rows").into(),
+        );
+    }
+
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    let mut seen: HashMap<(String, String), u32> = HashMap::new();
+    for record in &records {
+        match validate_record(record, default_cli_key) {
+            Ok(row) => {
+                let key = (row.cli_key.clone(), row.model.clone());
+                if let Some(first) = seen.get(&key) {
+                    errors.push(ModelPriceImportIssue {
+                        line: row.line,
+                        message: format!("duplicate of line {first}"),
+                    });
+                    continue;
+                }
+                seen.insert(key, row.line);
+                rows.push(row);
+            }
+            Err(message) => errors.push(ModelPriceImportIssue {
+                line: record.line,
+                message,
+            }),
+        }
+    }
+    Ok((rows, errors))
+}
+
+fn merge_price_json(existing: Option<&str>, fields: &serde_json::Map<String, Value>) -> String {
+    let mut merged = existing
+        .and_then(|raw| serde_json::from_str::<Value>(raw).ok())
+        .and_then(|v| match v {
+            Value::Object(map) => Some(map),
+            _ => None,
+        })
+        .unwrap_or_default();
+    for (key, value) in fields {
+        merged.insert(key.clone(), value.clone());
+    }
+    serde_json::to_string(&Value::Object(merged)).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Validates and diffs `content` against `model_prices`; writes only when `dry_run` is false and
+/// every row is valid.
+pub fn import(
+    db: &db::Db,
+    content: &str,
+    format: Option<&str>,
+    default_cli_key: Option<&str>,
+    dry_run: bool,
+) -> crate::shared::error::AppResult<ModelPricesImportReport> {
+    let default_cli_key = default_cli_key.map(str::trim).filter(|v| !v.is_empty());
+    let (rows, errors) = parse_rows(content, format, default_cli_key)?;
+
+    let mut conn = db.open_connection()?;
+    let tx = conn
+        .transaction()
+        .map_err(|e| db_err!("failed to start sqlite transaction: {e}"))?;
+
+    let mut existing_by_cli: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut changes = Vec::new();
+    let mut unchanged: u32 = 0;
+    for row in &rows {
+        if !existing_by_cli.contains_key(&row.cli_key) {
+            let existing = load_existing_price_map(&tx, &row.cli_key)?;
+            existing_by_cli.insert(row.cli_key.clone(), existing);
+        }
+        let before = existing_by_cli
+            .get(&row.cli_key)
+            .and_then(|m| m.get(&row.model))
+            .cloned();
+        let after = merge_price_json(before.as_deref(), &row.fields);
+        if before.as_deref() == Some(after.as_str()) {
+            unchanged += 1;
+            continue;
+        }
+        changes.push(ModelPriceImportChange {
+            line: row.line,
+            cli_key: row.cli_key.clone(),
+            model: row.model.clone(),
+            action: if before.is_some() { "update" } else { "insert" }.to_string(),
+            before_json: before,
+            after_json: after,
+        });
+    }
+
+    let applied = !dry_run && errors.is_empty();
+    if applied {
+        let now = now_unix_seconds();
+        {
+            let mut stmt = tx
+                .prepare_cached(
+                    r#"
+            INSERT INTO model_prices(cli_key, model, price_json, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?4)
+            ON CONFLICT(cli_key, model) DO UPDATE SET
+              price_json = excluded.price_json,
+              updated_at = excluded.updated_at
+            "#,
+                )
+                .map_err(|e| db_err!("failed to prepare model_prices upsert: {e}"))?;
+            for change in &changes {
+                stmt.execute(params![
+                    change.cli_key,
+                    change.model,
+                    change.after_json,
+                    now
+                ])
+                .map_err(|e| db_err!("failed to upsert model_price: {e}"))?;
+            }
+        }
+        tx.commit()
+            .map_err(|e| db_err!("failed to commit model_prices import transaction: {e}"))?;
+    }
+
+    let inserted = changes.iter().filter(|c| c.action == "insert").count() as u32;
+    Ok(ModelPricesImportReport {
+        dry_run,
+        applied,
+        inserted,
+        updated: changes.len() as u32 - inserted,
+        unchanged,
+        changes,
+        errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_csv_with_units_and_provider_scope() {
+        let csv = "model,provider,input,output,cache_read,unit\n\
+                   \"claude-x, beta\",anthropic,3,15,0.3,\n\
+                   gpt-x,,0.0025,0.01,,per_1k\n";
+        let (rows, errors) = parse_rows(csv, None, Some("codex")).unwrap();
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(rows.len(), 2);
+
+        assert_eq!(rows[0].cli_key, "claude");
+        assert_eq!(rows[0].model, "claude-x, beta");
+        assert_eq!(rows[0].fields["input_cost_per_token"], "0.000003");
+        assert_eq!(rows[0].fields["cache_read_input_token_cost"], "0.0000003");
+
+        assert_eq!(rows[1].cli_key, "codex");
+        assert_eq!(rows[1].line, 3);
+        assert_eq!(rows[1].fields["input_cost_per_token"], "0.0000025");
+        assert_eq!(rows[1].fields["output_cost_per_token"], "0.00001");
+    }
+
+    #[test]
+    fn reports_invalid_rows_without_failing_the_table() {
+        let json = r#"{"prices":[
+            {"model":"a","input":-1,"output":2},
+            {"model":"b","input":20000,"output":2},
+            {"model":"c","input":1,"unit":"per_hour"},
+            {"model":"d","cache_read":1},
+            {"model":"e","input":"1e-6","unit":"per_token"},
+            {"model":"e","input":2}
+        ]}"#;
+        let (rows, errors) = parse_rows(json, Some("json"), Some("gemini")).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].fields["input_cost_per_token"], "0.000001");
+        let lines: Vec<u32> = errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![1, 2, 3, 4, 6]);
+        assert_eq!(errors[4].message, "duplicate of line 5");
+
+        assert!(parse_rows("model,price\nx,1\n", None, None).is_err());
+        assert!(parse_rows("model,input\nx,1\n", None, Some("cursor")).is_err());
+    }
+
+    #[test]
+    fn merge_keeps_fields_the_table_does_not_mention() {
+        let mut fields = serde_json::Map::new();
+        fields.insert(
+            "input_cost_per_token".to_string(),
+            Value::String("0.000002".to_string()),
+        );
+        let merged = merge_price_json(
+            Some(
+                r#"{"input_cost_per_token":"0.000001","input_cost_per_token_above_200k_tokens":"0.000004"}"#,
+            ),
+            &fields,
+        );
+        let merged: Value = serde_json::from_str(&merged).unwrap();
+        assert_eq!(merged["input_cost_per_token"], "0.000002");
+        assert_eq!(merged["input_cost_per_token_above_200k_tokens"], "0.000004");
+    }
+}
@@ -13,7 +13,7 @@ use std::time::Duration;
 
 const BASELLM_ALL_JSON_URL: &str = "https://basellm.github.io/llm-metadata/api/all.json";
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct ModelPricesSyncReport {
     pub status: String,
     pub inserted: u32,
@@ -36,17 +36,17 @@ struct ModelPriceRow {
     price_json: String,
 }
 
-fn model_prices_dir(app: &tauri::AppHandle) -> crate::shared::error::AppResult<PathBuf> {
+fn model_prices_dir(app: &crate::AppHandle) -> crate::shared::error::AppResult<PathBuf> {
     let dir = app_paths::app_data_dir(app)?.join("model-prices");
     std::fs::create_dir_all(&dir).map_err(|e| format!("failed to create model-prices dir: {e}"))?;
     Ok(dir)
 }
 
-fn basellm_cache_path(app: &tauri::AppHandle) -> crate::shared::error::AppResult<PathBuf> {
+fn basellm_cache_path(app: &crate::AppHandle) -> crate::shared::error::AppResult<PathBuf> {
     Ok(model_prices_dir(app)?.join("basellm-cache.json"))
 }
 
-fn read_basellm_cache(app: &tauri::AppHandle) -> BasellmCacheMeta {
+fn read_basellm_cache(app: &crate::AppHandle) -> BasellmCacheMeta {
     let path = match basellm_cache_path(app) {
         Ok(v) => v,
         Err(_) => return BasellmCacheMeta::default(),
@@ -90,7 +90,7 @@ fn write_json_atomically(path: &Path, json_bytes: Vec<u8>) -> crate::shared::err
 }
 
 fn write_basellm_cache(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     cache: &BasellmCacheMeta,
 ) -> crate::shared::error::AppResult<()> {
     let path = basellm_cache_path(app)?;
@@ -99,7 +99,7 @@ fn write_basellm_cache(
     write_json_atomically(&path, content)
 }
 
-fn cli_key_from_basellm_provider(provider: &str) -> Option<&'static str> {
+pub(super) fn cli_key_from_basellm_provider(provider: &str) -> Option<&'static str> {
     let provider = provider.trim().to_ascii_lowercase();
     match provider.as_str() {
         "openai" => Some("codex"),
@@ -110,7 +110,7 @@ fn cli_key_from_basellm_provider(provider: &str) -> Option<&'static str> {
     }
 }
 
-fn json_scalar_to_string(v: &Value) -> Option<String> {
+pub(super) fn json_scalar_to_string(v: &Value) -> Option<String> {
     match v {
         Value::Number(n) => Some(n.to_string()),
         Value::String(s) => {
@@ -125,7 +125,7 @@ fn json_scalar_to_string(v: &Value) -> Option<String> {
     }
 }
 
-fn shift_cost_per_1m_to_per_token(cost_per_1m: &str) -> Option<String> {
+pub(super) fn shift_cost_per_1m_to_per_token(cost_per_1m: &str) -> Option<String> {
     let s = cost_per_1m.trim();
     if s.is_empty() {
         return None;
@@ -323,7 +323,7 @@ fn parse_basellm_all_json(root: &Value) -> crate::shared::error::AppResult<Vec<M
     Ok(rows)
 }
 
-fn load_existing_price_map(
+pub(super) fn load_existing_price_map(
     tx: &rusqlite::Transaction<'_>,
     cli_key: &str,
 ) -> crate::shared::error::AppResult<HashMap<String, String>> {
@@ -472,7 +472,7 @@ fn add_cache_headers(mut headers: HeaderMap, cache: &BasellmCacheMeta) -> Header
 }
 
 pub async fn sync_basellm(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     db: db::Db,
     force: bool,
 ) -> crate::shared::error::AppResult<ModelPricesSyncReport> {
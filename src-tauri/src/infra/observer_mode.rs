@@ -0,0 +1,328 @@
+//! Usage: Read-only observer mode for screen-sharing and demos (`observer_mode` setting).
+//!
+//! While enabled, commands that would reveal secrets are refused with `SEC_OBSERVER_MODE`,
+//! settings come back with the gateway access token and notification sink URLs masked (provider
+//! webhook URLs too), and request log views (commands and realtime gateway events) mask request
+//! paths, query strings and session ids so client project names do not show up on screen. Captured upstream error
+//! bodies and response headers are dropped, since they often echo request paths and prompts.
+
+use crate::providers::ProviderSummary;
+use crate::request_attempt_logs::RequestAttemptLog;
+use crate::request_logs::{RequestLogDetail, RequestLogSummary};
+use crate::settings::AppSettings;
+use crate::shared::error::{AppError, AppResult};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const MASK: &str = "***";
+const SESSION_ID_VISIBLE_SUFFIX: usize = 4;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Call at startup and whenever the setting changes.
+pub fn configure(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub(crate) fn ensure_secrets_allowed(action: &str) -> AppResult<()> {
+    if is_enabled() {
+        return Err(AppError::new(
+            "SEC_OBSERVER_MODE",
+            format!("{action} is disabled while observer mode is on"),
+        ));
+    }
+    Ok(())
+}
+
+/// Keeps the first path segment (API version) so the route family stays readable.
+pub(crate) fn mask_path(path: &str) -> String {
+    let trimmed = path.trim_start_matches('/');
+    match trimmed.split_once('/') {
+        Some((first, rest)) if !rest.is_empty() => format!("/{first}/{MASK}"),
+        _ if trimmed.is_empty() => path.to_string(),
+        _ => format!("/{MASK}"),
+    }
+}
+
+pub(crate) fn mask_query(query: Option<String>) -> Option<String> {
+    query.filter(|q| !q.is_empty()).map(|_| MASK.to_string())
+}
+
+pub(crate) fn mask_session_id(session_id: &str) -> String {
+    let chars: Vec<char> = session_id.chars().collect();
+    if chars.len() <= SESSION_ID_VISIBLE_SUFFIX * 2 {
+        return MASK.to_string();
+    }
+    let suffix: String = chars[chars.len() - SESSION_ID_VISIBLE_SUFFIX..]
+        .iter()
+        .collect();
+    format!("{MASK}{suffix}")
+}
+
+fn mask_session_values(value: &mut serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut changed = false;
+            for (key, item) in map.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if key.contains("session") || key == "user_id" {
+                    if let serde_json::Value::String(text) = item {
+                        *text = mask_session_id(text);
+                        changed = true;
+                        continue;
+                    }
+                }
+                changed |= mask_session_values(item);
+            }
+            changed
+        }
+        serde_json::Value::Array(items) => items
+            .iter_mut()
+            .fold(false, |changed, item| mask_session_values(item) || changed),
+        _ => false,
+    }
+}
+
+/// Masks string values of session-like keys (`session_id`, `user_id`, ...) in a JSON document.
+pub(crate) fn mask_session_ids_json(raw: &str) -> String {
+    match serde_json::from_str::<serde_json::Value>(raw) {
+        Ok(mut value) => {
+            if !mask_session_values(&mut value) {
+                return raw.to_string();
+            }
+            serde_json::to_string(&value).unwrap_or_else(|_| raw.to_string())
+        }
+        Err(_) => raw.to_string(),
+    }
+}
+
+fn mask_secret(value: &mut String) {
+    if !value.is_empty() {
+        *value = MASK.to_string();
+    }
+}
+
+fn mask_settings_secrets(mut settings: AppSettings) -> AppSettings {
+    mask_secret(&mut settings.gateway_access_token);
+    // Webhook URLs embed their secret (Slack/Discord paths, Telegram bot token).
+    for sink in &mut settings.notification_sinks {
+        mask_secret(&mut sink.url);
+    }
+    // Connection URLs carry the database password.
+    mask_secret(&mut settings.storage_postgres_url);
+    settings
+}
+
+pub(crate) fn mask_app_settings(settings: AppSettings) -> AppSettings {
+    if !is_enabled() {
+        return settings;
+    }
+    mask_settings_secrets(settings)
+}
+
+fn mask_provider_secrets(mut provider: ProviderSummary) -> ProviderSummary {
+    if let Some(url) = provider.webhook_url.as_mut() {
+        mask_secret(url);
+    }
+    provider
+}
+
+pub(crate) fn mask_provider_summary(provider: ProviderSummary) -> ProviderSummary {
+    if !is_enabled() {
+        return provider;
+    }
+    mask_provider_secrets(provider)
+}
+
+pub(crate) fn mask_provider_summaries(items: Vec<ProviderSummary>) -> Vec<ProviderSummary> {
+    if !is_enabled() {
+        return items;
+    }
+    items.into_iter().map(mask_provider_secrets).collect()
+}
+
+/// Drops captured upstream error bodies and response headers from a failover attempts JSON array.
+fn strip_attempt_captures_json(raw: &str) -> String {
+    let Ok(mut value) = serde_json::from_str::<serde_json::Value>(raw) else {
+        return raw.to_string();
+    };
+    let Some(items) = value.as_array_mut() else {
+        return raw.to_string();
+    };
+    for item in items.iter_mut().filter_map(|item| item.as_object_mut()) {
+        item.remove("error_body");
+        item.remove("response_headers");
+    }
+    serde_json::to_string(&value).unwrap_or_else(|_| raw.to_string())
+}
+
+pub(crate) fn mask_request_log_summaries(
+    mut items: Vec<RequestLogSummary>,
+) -> Vec<RequestLogSummary> {
+    if !is_enabled() {
+        return items;
+    }
+    for item in &mut items {
+        item.path = mask_path(&item.path);
+    }
+    items
+}
+
+pub(crate) fn mask_request_log_detail(mut detail: RequestLogDetail) -> RequestLogDetail {
+    if !is_enabled() {
+        return detail;
+    }
+    detail.path = mask_path(&detail.path);
+    detail.query = mask_query(detail.query.take());
+    detail.special_settings_json = detail
+        .special_settings_json
+        .map(|raw| mask_session_ids_json(&raw));
+    detail.attempts_json = strip_attempt_captures_json(&detail.attempts_json);
+    detail.response_headers_json = None;
+    detail
+}
+
+pub(crate) fn mask_request_attempt_logs(
+    mut items: Vec<RequestAttemptLog>,
+) -> Vec<RequestAttemptLog> {
+    if !is_enabled() {
+        return items;
+    }
+    for item in &mut items {
+        item.response_headers = None;
+        item.error_body = None;
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_paths_queries_and_session_ids() {
+        assert_eq!(mask_path("/v1/messages"), "/v1/***");
+        assert_eq!(
+            mask_path("/v1beta/models/gemini-2.5-pro:generateContent"),
+            "/v1beta/***"
+        );
+        assert_eq!(mask_path("/responses"), "/***");
+        assert_eq!(mask_path("/"), "/");
+
+        assert_eq!(
+            mask_query(Some("key=abc".to_string())),
+            Some("***".to_string())
+        );
+        assert_eq!(mask_query(Some(String::new())), None);
+        assert_eq!(mask_query(None), None);
+
+        assert_eq!(
+            mask_session_id("0199a1b2-c3d4-7e8f-9a0b-1c2d3e4f5a6b"),
+            "***5a6b"
+        );
+        assert_eq!(mask_session_id("short"), "***");
+
+        let raw = r#"[{"type":"codex_session_id_completion","session_id":"sess-1234567890"},{"type":"other","n":1}]"#;
+        let masked = mask_session_ids_json(raw);
+        assert!(masked.contains(r#""session_id":"***7890""#), "{masked}");
+        assert!(masked.contains(r#""n":1"#));
+
+        let untouched = r#"{"type":"other"}"#;
+        assert_eq!(mask_session_ids_json(untouched), untouched);
+    }
+
+    #[test]
+    fn masks_access_token_and_sink_urls_in_settings() {
+        let settings = AppSettings {
+            gateway_access_token: "aio-secret-token".to_string(),
+            storage_postgres_url: "postgres://hub:pw@db/hub".to_string(),
+            notification_sinks: vec![
+                crate::settings::NotificationSink {
+                    name: "telegram".to_string(),
+                    url: "https://api.telegram.org/bot123:abc".to_string(),
+                    telegram_chat_id: "42".to_string(),
+                    ..Default::default()
+                },
+                crate::settings::NotificationSink::default(),
+            ],
+            ..Default::default()
+        };
+
+        let masked = mask_settings_secrets(settings);
+        assert_eq!(masked.gateway_access_token, "***");
+        assert_eq!(masked.storage_postgres_url, "***");
+        assert_eq!(masked.notification_sinks[0].url, "***");
+        assert_eq!(masked.notification_sinks[0].name, "telegram");
+        assert_eq!(masked.notification_sinks[0].telegram_chat_id, "42");
+        // Empty values stay empty so the UI still shows "not configured".
+        assert_eq!(masked.notification_sinks[1].url, "");
+        assert_eq!(
+            mask_settings_secrets(AppSettings::default()).gateway_access_token,
+            ""
+        );
+    }
+
+    #[test]
+    fn masks_provider_webhook_urls() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let db = crate::db::init_for_tests(&dir.path().join("test.db")).expect("init db");
+        let insert = |name: &str| {
+            crate::providers::upsert(
+                &db,
+                crate::providers::ProviderUpsertParams {
+                    provider_id: None,
+                    cli_key: "claude".to_string(),
+                    name: name.to_string(),
+                    base_urls: vec!["https://example.com".to_string()],
+                    base_url_mode: crate::providers::ProviderBaseUrlMode::Order,
+                    auth_mode: None,
+                    api_key: Some("k".to_string()),
+                    enabled: true,
+                    cost_multiplier: 1.0,
+                    priority: Some(100),
+                    claude_models: None,
+                    limit_5h_usd: None,
+                    limit_daily_usd: None,
+                    daily_reset_mode: Some(crate::providers::DailyResetMode::Fixed),
+                    daily_reset_time: Some("00:00:00".to_string()),
+                    limit_weekly_usd: None,
+                    limit_monthly_usd: None,
+                    limit_total_usd: None,
+                    tags: None,
+                    note: None,
+                    provider_kind: None,
+                },
+            )
+            .expect("insert provider")
+        };
+        let hooked = insert("P1");
+        let plain = insert("P2");
+        let hooked = crate::providers::set_webhook_url(
+            &db,
+            hooked.id,
+            Some("https://hooks.slack.com/services/T0/B0/secret".to_string()),
+        )
+        .expect("set webhook");
+
+        let masked = mask_provider_secrets(hooked);
+        assert_eq!(masked.webhook_url.as_deref(), Some("***"));
+        assert_eq!(masked.name, "P1");
+        // No webhook stays `None` so the UI still shows "not configured".
+        assert_eq!(mask_provider_secrets(plain).webhook_url, None);
+    }
+
+    #[test]
+    fn strips_captured_bodies_and_headers_from_attempts() {
+        let raw = r#"[{"provider_id":1,"error_body":"{\"path\":\"/home/me/project\"}","response_headers":{"x-request-id":"r1"},"status":500}]"#;
+        let stripped: serde_json::Value =
+            serde_json::from_str(&strip_attempt_captures_json(raw)).expect("json");
+        assert_eq!(
+            stripped,
+            serde_json::json!([{ "provider_id": 1, "status": 500 }])
+        );
+        assert_eq!(strip_attempt_captures_json("not json"), "not json");
+    }
+}
@@ -8,6 +8,7 @@ use crate::shared::fs::{
 };
 use crate::shared::time::now_unix_seconds;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
 use tauri::Manager;
 
@@ -32,21 +33,43 @@ struct PromptSyncManifest {
     created_at: i64,
     updated_at: i64,
     file: PromptSyncFileEntry,
+    // sha256 of the bytes last written by the hub; absent in manifests from older versions.
+    #[serde(default)]
+    synced_sha256: Option<String>,
+}
+
+/// Target file no longer matches what the hub last wrote (edited or deleted by the user).
+#[derive(Debug, Clone)]
+pub struct PromptSyncDrift {
+    pub applied_prompt_id: Option<i64>,
+    pub target_path: String,
+    pub local_bytes: Option<Vec<u8>>,
+}
+
+fn content_sha256(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn has_local_edits(synced_sha256: Option<&str>, local_bytes: Option<&[u8]>) -> bool {
+    let Some(synced) = synced_sha256 else {
+        return false;
+    };
+    local_bytes.is_none_or(|bytes| content_sha256(bytes) != synced)
 }
 
 fn validate_cli_key(cli_key: &str) -> Result<(), String> {
     crate::shared::cli_key::validate_cli_key(cli_key).map_err(Into::into)
 }
 
-fn home_dir(app: &tauri::AppHandle) -> crate::shared::error::AppResult<PathBuf> {
+fn home_dir(app: &crate::AppHandle) -> crate::shared::error::AppResult<PathBuf> {
     Ok(app
         .path()
         .home_dir()
         .map_err(|e| format!("failed to resolve home dir: {e}"))?)
 }
 
-fn prompt_target_path(
-    app: &tauri::AppHandle,
+pub(crate) fn prompt_target_path(
+    app: &crate::AppHandle,
     cli_key: &str,
 ) -> crate::shared::error::AppResult<PathBuf> {
     validate_cli_key(cli_key)?;
@@ -61,7 +84,7 @@ fn prompt_target_path(
 }
 
 fn prompt_sync_root_dir(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     cli_key: &str,
 ) -> crate::shared::error::AppResult<PathBuf> {
     Ok(app_paths::app_data_dir(app)?
@@ -82,7 +105,7 @@ fn prompt_sync_manifest_path(root: &Path) -> PathBuf {
 }
 
 fn legacy_prompt_sync_roots(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     cli_key: &str,
 ) -> crate::shared::error::AppResult<Vec<PathBuf>> {
     let home = home_dir(app)?;
@@ -93,7 +116,7 @@ fn legacy_prompt_sync_roots(
 }
 
 fn try_migrate_legacy_prompt_sync_dir(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     cli_key: &str,
 ) -> crate::shared::error::AppResult<bool> {
     let new_root = prompt_sync_root_dir(app, cli_key)?;
@@ -126,7 +149,7 @@ fn try_migrate_legacy_prompt_sync_dir(
 }
 
 pub fn read_target_bytes(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     cli_key: &str,
 ) -> crate::shared::error::AppResult<Option<Vec<u8>>> {
     let path = prompt_target_path(app, cli_key)?;
@@ -134,7 +157,7 @@ pub fn read_target_bytes(
 }
 
 pub fn restore_target_bytes(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     cli_key: &str,
     bytes: Option<Vec<u8>>,
 ) -> crate::shared::error::AppResult<()> {
@@ -152,7 +175,7 @@ pub fn restore_target_bytes(
 }
 
 pub fn read_manifest_bytes(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     cli_key: &str,
 ) -> crate::shared::error::AppResult<Option<Vec<u8>>> {
     let root = prompt_sync_root_dir(app, cli_key)?;
@@ -161,7 +184,7 @@ pub fn read_manifest_bytes(
 }
 
 pub fn restore_manifest_bytes(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     cli_key: &str,
     bytes: Option<Vec<u8>>,
 ) -> crate::shared::error::AppResult<()> {
@@ -179,8 +202,17 @@ pub fn restore_manifest_bytes(
     }
 }
 
+/// Whether the hub currently owns the `cli_key` prompt file (sync enabled).
+pub(crate) fn is_applied(
+    app: &crate::AppHandle,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<bool> {
+    validate_cli_key(cli_key)?;
+    Ok(read_manifest(app, cli_key)?.is_some_and(|m| m.enabled))
+}
+
 fn read_manifest(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     cli_key: &str,
 ) -> crate::shared::error::AppResult<Option<PromptSyncManifest>> {
     let root = prompt_sync_root_dir(app, cli_key)?;
@@ -211,7 +243,7 @@ fn read_manifest(
 }
 
 fn write_manifest(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     cli_key: &str,
     manifest: &PromptSyncManifest,
 ) -> crate::shared::error::AppResult<()> {
@@ -227,7 +259,7 @@ fn write_manifest(
 }
 
 fn backup_for_enable(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     cli_key: &str,
     existing: Option<PromptSyncManifest>,
 ) -> crate::shared::error::AppResult<PromptSyncManifest> {
@@ -270,6 +302,7 @@ fn backup_for_enable(
             existed,
             backup_rel,
         },
+        synced_sha256: None,
     })
 }
 
@@ -283,7 +316,7 @@ pub(crate) fn prompt_content_to_bytes(content: &str) -> Vec<u8> {
 }
 
 fn restore_from_manifest(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     manifest: &PromptSyncManifest,
 ) -> crate::shared::error::AppResult<()> {
     let cli_key = manifest.cli_key.as_str();
@@ -354,11 +387,72 @@ fn restore_from_manifest(
     Ok(())
 }
 
+/// Reports local edits to an enabled prompt file; `None` while it still matches the last sync.
+pub fn detect_drift(
+    app: &crate::AppHandle,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<Option<PromptSyncDrift>> {
+    validate_cli_key(cli_key)?;
+    let Some(manifest) = read_manifest(app, cli_key)?.filter(|m| m.enabled) else {
+        return Ok(None);
+    };
+
+    let target_path = prompt_target_path(app, cli_key)?;
+    let local_bytes = read_optional_file(&target_path)?;
+    if !has_local_edits(manifest.synced_sha256.as_deref(), local_bytes.as_deref()) {
+        return Ok(None);
+    }
+
+    Ok(Some(PromptSyncDrift {
+        applied_prompt_id: manifest.applied_prompt_id,
+        target_path: target_path.to_string_lossy().to_string(),
+        local_bytes,
+    }))
+}
+
+/// Accept the current file content as synced (after adopting it into the DB).
+pub fn mark_synced(app: &crate::AppHandle, cli_key: &str) -> crate::shared::error::AppResult<()> {
+    validate_cli_key(cli_key)?;
+    let Some(mut manifest) = read_manifest(app, cli_key)?.filter(|m| m.enabled) else {
+        return Err(format!("PROMPT_SYNC_NOT_APPLIED: cli_key={cli_key}").into());
+    };
+
+    let target_path = prompt_target_path(app, cli_key)?;
+    let Some(bytes) = read_optional_file(&target_path)? else {
+        return Err(format!("PROMPT_SYNC_FILE_MISSING: {}", target_path.display()).into());
+    };
+    manifest.synced_sha256 = Some(content_sha256(&bytes));
+    manifest.updated_at = now_unix_seconds();
+    write_manifest(app, cli_key, &manifest)
+}
+
+/// Writes the prompt unless the user edited the file since the last sync; such edits are kept
+/// and stay visible through `detect_drift` until resolved.
 pub fn apply_enabled_prompt(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
+    cli_key: &str,
+    prompt_id: i64,
+    content: &str,
+) -> crate::shared::error::AppResult<()> {
+    apply_prompt(app, cli_key, prompt_id, content, false)
+}
+
+/// Writes the prompt even over local edits.
+pub fn overwrite_enabled_prompt(
+    app: &crate::AppHandle,
+    cli_key: &str,
+    prompt_id: i64,
+    content: &str,
+) -> crate::shared::error::AppResult<()> {
+    apply_prompt(app, cli_key, prompt_id, content, true)
+}
+
+fn apply_prompt(
+    app: &crate::AppHandle,
     cli_key: &str,
     prompt_id: i64,
     content: &str,
+    overwrite_local_edits: bool,
 ) -> crate::shared::error::AppResult<()> {
     validate_cli_key(cli_key)?;
 
@@ -386,7 +480,21 @@ pub fn apply_enabled_prompt(
     manifest.file.path = target_path.to_string_lossy().to_string();
 
     let bytes = prompt_content_to_bytes(content);
-    write_file_atomic_if_changed(&target_path, &bytes)?;
+    let local_bytes = read_optional_file(&target_path)?;
+    let keep_local = !should_backup
+        && !overwrite_local_edits
+        && local_bytes.as_deref() != Some(bytes.as_slice())
+        && has_local_edits(manifest.synced_sha256.as_deref(), local_bytes.as_deref());
+    if keep_local {
+        tracing::warn!(
+            cli_key = %cli_key,
+            prompt_id,
+            "prompt sync: local edits detected, keeping file until the conflict is resolved"
+        );
+    } else {
+        write_file_atomic_if_changed(&target_path, &bytes)?;
+        manifest.synced_sha256 = Some(content_sha256(&bytes));
+    }
 
     manifest.enabled = true;
     manifest.applied_prompt_id = Some(prompt_id);
@@ -397,7 +505,7 @@ pub fn apply_enabled_prompt(
 }
 
 pub fn restore_disabled_prompt(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     cli_key: &str,
 ) -> crate::shared::error::AppResult<()> {
     validate_cli_key(cli_key)?;
@@ -450,17 +558,57 @@ pub fn restore_disabled_prompt(
                 existed: target_path.exists(),
                 backup_rel,
             },
+            synced_sha256: None,
         };
         write_manifest(app, cli_key, &manifest)?;
         return Ok(());
     };
 
+    if manifest.enabled {
+        let target_path = PathBuf::from(&manifest.file.path);
+        if let Some(local_bytes) = read_optional_file(&target_path)? {
+            if has_local_edits(
+                manifest.synced_sha256.as_deref(),
+                Some(local_bytes.as_slice()),
+            ) {
+                // The restore below replaces the file; keep the user's edits recoverable.
+                let root = prompt_sync_root_dir(app, cli_key)?;
+                let safety_dir = prompt_sync_safety_dir(&root);
+                std::fs::create_dir_all(&safety_dir)
+                    .map_err(|e| format!("failed to create {}: {e}", safety_dir.display()))?;
+                let safe_name = format!("{}_prompt_local_edits", now_unix_seconds());
+                write_file_atomic(&safety_dir.join(safe_name), &local_bytes)?;
+            }
+        }
+    }
+
     restore_from_manifest(app, &manifest)?;
 
     manifest.enabled = false;
     manifest.applied_prompt_id = None;
+    manifest.synced_sha256 = None;
     manifest.updated_at = now_unix_seconds();
     write_manifest(app, cli_key, &manifest)?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_edits_compare_against_last_synced_hash() {
+        let synced = content_sha256(b"hello\n");
+        assert!(!has_local_edits(None, Some(&b"anything"[..])));
+        assert!(!has_local_edits(
+            Some(synced.as_str()),
+            Some(&b"hello\n"[..])
+        ));
+        assert!(has_local_edits(
+            Some(synced.as_str()),
+            Some(b"hello, edited\n")
+        ));
+        assert!(has_local_edits(Some(synced.as_str()), None));
+    }
+}
@@ -0,0 +1,229 @@
+//! Usage: Redaction engine applied to request logs and gateway events before they leave the process.
+//!
+//! Built-in rules mask credentials (Authorization/Bearer values, API key fields, well-known key
+//! prefixes); user rules from settings (`redaction_rules`) are applied afterwards.
+
+use crate::settings::RedactionRule;
+use crate::shared::error::AppResult;
+use regex::Regex;
+use std::borrow::Cow;
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+
+pub(crate) const REDACTED: &str = "[REDACTED]";
+pub const MAX_REDACTION_RULES: usize = 50;
+const MAX_PATTERN_LEN: usize = 500;
+
+const BUILTIN_RULES: &[(&str, &str)] = &[
+    (r"(?i)\b(bearer)\s+[A-Za-z0-9._~+/=-]{8,}", "$1 [REDACTED]"),
+    (
+        r#"(?i)("?(?:authorization|x-api-key|x-goog-api-key|x-aio-gateway-key|api[_-]?key|access[_-]?token|refresh[_-]?token)"?\s*[:=]\s*"?)[^\s",}]{6,}"#,
+        "${1}[REDACTED]",
+    ),
+    (r"\bsk-[A-Za-z0-9_-]{16,}", "sk-[REDACTED]"),
+    (r"\bAIza[0-9A-Za-z_-]{35}\b", "[REDACTED]"),
+    (r"\baio-[0-9a-f]{48}\b", "[REDACTED]"),
+];
+
+struct CompiledRule {
+    regex: Regex,
+    replacement: String,
+}
+
+pub(crate) struct Redactor {
+    rules: Vec<CompiledRule>,
+}
+
+static REDACTOR: OnceLock<RwLock<Arc<Redactor>>> = OnceLock::new();
+
+fn compile_rule(pattern: &str) -> AppResult<Regex> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return Err("SEC_INVALID_INPUT: redaction pattern is required".into());
+    }
+    if pattern.len() > MAX_PATTERN_LEN {
+        return Err(format!(
+            "SEC_INVALID_INPUT: redaction pattern must be <= {MAX_PATTERN_LEN} chars"
+        )
+        .into());
+    }
+    Regex::new(pattern).map_err(|e| {
+        format!("SEC_INVALID_INPUT: invalid redaction pattern `{pattern}`: {e}").into()
+    })
+}
+
+impl Redactor {
+    /// Builds the built-in rules plus every enabled user rule that compiles (invalid ones are skipped).
+    fn new(user_rules: &[RedactionRule]) -> Self {
+        let mut rules: Vec<CompiledRule> = BUILTIN_RULES
+            .iter()
+            .map(|(pattern, replacement)| CompiledRule {
+                regex: Regex::new(pattern).expect("builtin redaction pattern"),
+                replacement: (*replacement).to_string(),
+            })
+            .collect();
+
+        for rule in user_rules
+            .iter()
+            .filter(|r| r.enabled)
+            .take(MAX_REDACTION_RULES)
+        {
+            match compile_rule(&rule.pattern) {
+                Ok(regex) => rules.push(CompiledRule {
+                    regex,
+                    replacement: rule.replacement.clone(),
+                }),
+                Err(err) => tracing::warn!(rule = %rule.name, "redaction rule skipped: {}", err),
+            }
+        }
+
+        Self { rules }
+    }
+
+    pub(crate) fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut out = Cow::Borrowed(text);
+        for rule in &self.rules {
+            let replaced = match rule.regex.replace_all(&out, rule.replacement.as_str()) {
+                Cow::Owned(next) => Some(next),
+                Cow::Borrowed(_) => None,
+            };
+            if let Some(next) = replaced {
+                out = Cow::Owned(next);
+            }
+        }
+        out
+    }
+
+    /// Returns whether any string was rewritten.
+    fn redact_value(&self, value: &mut serde_json::Value) -> bool {
+        match value {
+            serde_json::Value::String(s) => match self.redact(s) {
+                Cow::Owned(next) => {
+                    *s = next;
+                    true
+                }
+                Cow::Borrowed(_) => false,
+            },
+            serde_json::Value::Array(items) => items
+                .iter_mut()
+                .fold(false, |changed, item| self.redact_value(item) || changed),
+            serde_json::Value::Object(map) => map
+                .values_mut()
+                .fold(false, |changed, item| self.redact_value(item) || changed),
+            _ => false,
+        }
+    }
+}
+
+fn cell() -> &'static RwLock<Arc<Redactor>> {
+    REDACTOR.get_or_init(|| RwLock::new(Arc::new(Redactor::new(&[]))))
+}
+
+fn current() -> Arc<Redactor> {
+    cell()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Check user rules before persisting them, so a bad pattern is reported instead of silently skipped.
+pub fn validate_rules(rules: &[RedactionRule]) -> AppResult<()> {
+    if rules.len() > MAX_REDACTION_RULES {
+        return Err(format!(
+            "SEC_INVALID_INPUT: at most {MAX_REDACTION_RULES} redaction rules are allowed"
+        )
+        .into());
+    }
+    for rule in rules {
+        compile_rule(&rule.pattern)?;
+    }
+    Ok(())
+}
+
+/// Swap in a new rule set; call at startup and whenever settings change.
+pub fn configure(user_rules: &[RedactionRule]) {
+    let next = Arc::new(Redactor::new(user_rules));
+    *cell().write().unwrap_or_else(PoisonError::into_inner) = next;
+}
+
+pub(crate) fn redact_string(text: String) -> String {
+    match current().redact(&text) {
+        Cow::Borrowed(_) => text,
+        Cow::Owned(next) => next,
+    }
+}
+
+/// Redact every string inside a JSON document; non-JSON input is redacted as plain text.
+/// Untouched documents are returned verbatim (no re-serialization, key order kept).
+pub(crate) fn redact_json_str(raw: &str) -> String {
+    let redactor = current();
+    match serde_json::from_str::<serde_json::Value>(raw) {
+        Ok(mut value) => {
+            if !redactor.redact_value(&mut value) {
+                return raw.to_string();
+            }
+            serde_json::to_string(&value).unwrap_or_else(|_| raw.to_string())
+        }
+        Err(_) => redactor.redact(raw).into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, replacement: &str) -> RedactionRule {
+        RedactionRule {
+            name: "test".to_string(),
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn builtin_rules_mask_credentials() {
+        let redactor = Redactor::new(&[]);
+        let text =
+            "upstream said: Authorization: Bearer abcdefghijkl key=sk-ant-0123456789abcdefXYZ";
+        let out = redactor.redact(text);
+        assert!(!out.contains("abcdefghijkl"), "{out}");
+        assert!(!out.contains("0123456789abcdef"), "{out}");
+        assert!(out.contains(REDACTED));
+
+        let json = r#"{"x-api-key":"secret-value-123"}"#;
+        assert_eq!(redactor.redact(json), r#"{"x-api-key":"[REDACTED]"}"#);
+    }
+
+    #[test]
+    fn user_rules_apply_after_builtins_and_skip_disabled() {
+        let mut disabled = rule(r"internal\.corp", "[host]");
+        disabled.enabled = false;
+        let redactor = Redactor::new(&[rule(r"[\w.+-]+@[\w-]+\.[\w.]+", "[email]"), disabled]);
+        let out = redactor.redact("contact ops@example.com via internal.corp");
+        assert_eq!(out, "contact [email] via internal.corp");
+    }
+
+    #[test]
+    fn plain_text_without_matches_is_borrowed() {
+        let redactor = Redactor::new(&[]);
+        assert!(matches!(redactor.redact("all clear"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn json_values_are_redacted_structurally() {
+        let redactor = Redactor::new(&[rule("secret", "***")]);
+        let mut value = serde_json::json!([{ "reason": "a secret \"quoted\"", "n": 1 }]);
+        assert!(redactor.redact_value(&mut value));
+        assert_eq!(
+            value,
+            serde_json::json!([{ "reason": "a *** \"quoted\"", "n": 1 }])
+        );
+    }
+
+    #[test]
+    fn validate_rules_rejects_bad_patterns() {
+        assert!(validate_rules(&[rule("(unclosed", "x")]).is_err());
+        assert!(validate_rules(&[rule("  ", "x")]).is_err());
+        assert!(validate_rules(&[rule(r"\d{4}", "x")]).is_ok());
+    }
+}
@@ -4,8 +4,12 @@ use crate::db;
 use crate::shared::error::db_err;
 use rusqlite::{params, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 
-#[derive(Debug, Clone, Serialize)]
+const HEALTH_MAX_REQUESTS: i64 = 2000;
+const AUTH_ACTIVITY_MAX_REQUESTS: i64 = 10_000;
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct RequestAttemptLog {
     pub id: i64,
     pub trace_id: String,
@@ -18,6 +22,15 @@ pub struct RequestAttemptLog {
     pub status: Option<i64>,
     pub attempt_started_ms: i64,
     pub attempt_duration_ms: i64,
+    pub bytes_received: Option<i64>,
+    pub chunk_count: Option<i64>,
+    pub max_chunk_gap_ms: Option<i64>,
+    /// Allow-listed upstream response headers (`captured_response_headers` setting).
+    pub response_headers: Option<BTreeMap<String, String>>,
+    /// Redacted, truncated upstream error body (`upstream_error_body_capture_bytes` setting).
+    pub error_body: Option<String>,
+    /// Gateway wait after this attempt before retrying the same provider (`retry_backoff` setting).
+    pub retry_backoff_ms: Option<i64>,
     pub created_at: i64,
 }
 
@@ -33,6 +46,13 @@ struct AttemptRow {
     status: Option<i64>,
     attempt_started_ms: Option<i64>,
     attempt_duration_ms: Option<i64>,
+    bytes_received: Option<i64>,
+    chunk_count: Option<i64>,
+    max_chunk_gap_ms: Option<i64>,
+    response_headers: Option<BTreeMap<String, String>>,
+    error_body: Option<String>,
+    retry_backoff_ms: Option<i64>,
+    reason_code: Option<String>,
 }
 
 fn parse_attempts(attempts_json: &str) -> Vec<AttemptRow> {
@@ -93,9 +113,517 @@ LIMIT 1
             status: attempt.status,
             attempt_started_ms: attempt.attempt_started_ms.unwrap_or(0),
             attempt_duration_ms: attempt.attempt_duration_ms.unwrap_or(0),
+            bytes_received: attempt.bytes_received,
+            chunk_count: attempt.chunk_count,
+            max_chunk_gap_ms: attempt.max_chunk_gap_ms,
+            response_headers: attempt.response_headers,
+            error_body: attempt.error_body,
+            retry_backoff_ms: attempt.retry_backoff_ms,
             created_at,
         });
     }
 
     Ok(out)
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestTimelineSegmentKind {
+    /// Request received until the first upstream attempt (body read, routing, gates).
+    Queue,
+    /// Attempt sent until its response: connect, TLS and headers/first byte (not split upstream).
+    Upstream,
+    /// First byte until the response finished relaying.
+    Stream,
+    /// Time between two attempts (OAuth pacing, retry/failover bookkeeping).
+    Gap,
+    /// Deliberate wait before retrying the same provider (`retry_backoff` setting).
+    Backoff,
+    /// Zero-length marker for a provider skipped without a request (circuit, cooldown, limits).
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct RequestTimelineSegment {
+    pub kind: RequestTimelineSegmentKind,
+    pub label: String,
+    /// Milliseconds since the gateway received the request.
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub attempt_index: Option<i64>,
+    pub provider_id: Option<i64>,
+    pub outcome: Option<String>,
+}
+
+/// Waterfall of one request, reconstructed from `request_logs` timing and `attempts_json`.
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct RequestTimeline {
+    pub trace_id: String,
+    pub duration_ms: i64,
+    pub ttfb_ms: Option<i64>,
+    pub segments: Vec<RequestTimelineSegment>,
+}
+
+fn attempt_label(attempt: &AttemptRow) -> String {
+    if attempt.provider_name.is_empty() {
+        format!("provider #{}", attempt.provider_id)
+    } else {
+        attempt.provider_name.clone()
+    }
+}
+
+fn build_timeline(
+    duration_ms: i64,
+    ttfb_ms: Option<i64>,
+    attempts: &[AttemptRow],
+) -> Vec<RequestTimelineSegment> {
+    let duration_ms = duration_ms.max(0);
+    let mut segments = Vec::new();
+    let mut cursor = 0_i64;
+    let mut first_attempt = true;
+    let mut pending_backoff_ms = 0_i64;
+
+    for (idx, attempt) in attempts.iter().enumerate() {
+        let attempt_index = (idx as i64).saturating_add(1);
+        let start = attempt
+            .attempt_started_ms
+            .unwrap_or(cursor)
+            .clamp(cursor, duration_ms.max(cursor));
+        let backoff_end = cursor.saturating_add(pending_backoff_ms).min(start);
+        if backoff_end > cursor {
+            segments.push(RequestTimelineSegment {
+                kind: RequestTimelineSegmentKind::Backoff,
+                label: "retry backoff".to_string(),
+                start_ms: cursor,
+                end_ms: backoff_end,
+                attempt_index: None,
+                provider_id: None,
+                outcome: None,
+            });
+            cursor = backoff_end;
+        }
+        pending_backoff_ms = attempt.retry_backoff_ms.unwrap_or(0).max(0);
+        if start > cursor {
+            let (kind, label) = if first_attempt {
+                (RequestTimelineSegmentKind::Queue, "queued")
+            } else {
+                (RequestTimelineSegmentKind::Gap, "wait")
+            };
+            segments.push(RequestTimelineSegment {
+                kind,
+                label: label.to_string(),
+                start_ms: cursor,
+                end_ms: start,
+                attempt_index: None,
+                provider_id: None,
+                outcome: None,
+            });
+        }
+        first_attempt = false;
+
+        if attempt.outcome == "skipped" {
+            segments.push(RequestTimelineSegment {
+                kind: RequestTimelineSegmentKind::Skipped,
+                label: match attempt.reason_code.as_deref() {
+                    Some(reason) => format!("{} skipped ({reason})", attempt_label(attempt)),
+                    None => format!("{} skipped", attempt_label(attempt)),
+                },
+                start_ms: start,
+                end_ms: start,
+                attempt_index: Some(attempt_index),
+                provider_id: Some(attempt.provider_id),
+                outcome: Some(attempt.outcome.clone()),
+            });
+            cursor = start;
+            continue;
+        }
+
+        let mut end = start.saturating_add(attempt.attempt_duration_ms.unwrap_or(0).max(0));
+        let is_success = attempt.outcome == "success";
+        if is_success {
+            // The relayed attempt waits until the first byte; the request's ttfb is authoritative.
+            if let Some(ttfb_ms) = ttfb_ms.filter(|v| *v >= start) {
+                end = ttfb_ms;
+            }
+        }
+        let end = end.min(duration_ms.max(start));
+        segments.push(RequestTimelineSegment {
+            kind: RequestTimelineSegmentKind::Upstream,
+            label: attempt_label(attempt),
+            start_ms: start,
+            end_ms: end,
+            attempt_index: Some(attempt_index),
+            provider_id: Some(attempt.provider_id),
+            outcome: Some(attempt.outcome.clone()),
+        });
+        cursor = end;
+
+        if is_success && duration_ms > end {
+            segments.push(RequestTimelineSegment {
+                kind: RequestTimelineSegmentKind::Stream,
+                label: attempt_label(attempt),
+                start_ms: end,
+                end_ms: duration_ms,
+                attempt_index: Some(attempt_index),
+                provider_id: Some(attempt.provider_id),
+                outcome: Some(attempt.outcome.clone()),
+            });
+            cursor = duration_ms;
+        }
+    }
+
+    if attempts.is_empty() && duration_ms > 0 {
+        segments.push(RequestTimelineSegment {
+            kind: RequestTimelineSegmentKind::Queue,
+            label: "queued".to_string(),
+            start_ms: 0,
+            end_ms: duration_ms,
+            attempt_index: None,
+            provider_id: None,
+            outcome: None,
+        });
+    } else if cursor < duration_ms {
+        segments.push(RequestTimelineSegment {
+            kind: RequestTimelineSegmentKind::Gap,
+            label: "finalize".to_string(),
+            start_ms: cursor,
+            end_ms: duration_ms,
+            attempt_index: None,
+            provider_id: None,
+            outcome: None,
+        });
+    }
+    segments
+}
+
+pub fn timeline_by_trace_id(
+    db: &db::Db,
+    trace_id: &str,
+) -> crate::shared::error::AppResult<Option<RequestTimeline>> {
+    let trace_id = trace_id.trim();
+    if trace_id.is_empty() {
+        return Err("SEC_INVALID_INPUT: trace_id is required".into());
+    }
+
+    let conn = db.open_connection()?;
+    let row = conn
+        .query_row(
+            r#"
+SELECT
+  attempts_json,
+  duration_ms,
+  ttfb_ms
+FROM request_logs
+WHERE trace_id = ?1
+LIMIT 1
+"#,
+            params![trace_id],
+            |row| {
+                let attempts_json: String = row.get("attempts_json")?;
+                let duration_ms: i64 = row.get("duration_ms")?;
+                let ttfb_ms: Option<i64> = row.get("ttfb_ms")?;
+                Ok((attempts_json, duration_ms, ttfb_ms))
+            },
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query request timeline by trace_id: {e}"))?;
+
+    let Some((attempts_json, duration_ms, ttfb_ms)) = row else {
+        return Ok(None);
+    };
+    let attempts = parse_attempts(&attempts_json);
+    Ok(Some(RequestTimeline {
+        trace_id: trace_id.to_string(),
+        duration_ms,
+        ttfb_ms,
+        segments: build_timeline(duration_ms, ttfb_ms, &attempts),
+    }))
+}
+
+/// Rolling attempt health for one provider (used by adaptive sort modes).
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ProviderAttemptHealth {
+    pub(crate) provider_id: i64,
+    pub(crate) attempts: usize,
+    pub(crate) successes: usize,
+    pub(crate) p95_duration_ms: Option<i64>,
+}
+
+impl ProviderAttemptHealth {
+    pub(crate) fn success_rate(&self) -> f64 {
+        if self.attempts == 0 {
+            0.0
+        } else {
+            self.successes as f64 / self.attempts as f64
+        }
+    }
+}
+
+fn percentile_95(values: &mut [i64]) -> Option<i64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let rank = ((values.len() as f64) * 0.95).ceil() as usize;
+    Some(values[rank.clamp(1, values.len()) - 1])
+}
+
+fn aggregate_health(attempts: impl IntoIterator<Item = AttemptRow>) -> Vec<ProviderAttemptHealth> {
+    let mut by_provider: HashMap<i64, (usize, usize, Vec<i64>)> = HashMap::new();
+    for attempt in attempts {
+        // Skipped/started entries never reached the upstream.
+        if attempt.provider_id <= 0 || matches!(attempt.outcome.as_str(), "skipped" | "started") {
+            continue;
+        }
+        let entry = by_provider.entry(attempt.provider_id).or_default();
+        entry.0 += 1;
+        if attempt.outcome == "success" {
+            entry.1 += 1;
+            if let Some(duration_ms) = attempt.attempt_duration_ms {
+                entry.2.push(duration_ms);
+            }
+        }
+    }
+
+    let mut out: Vec<ProviderAttemptHealth> = by_provider
+        .into_iter()
+        .map(
+            |(provider_id, (attempts, successes, mut durations))| ProviderAttemptHealth {
+                provider_id,
+                attempts,
+                successes,
+                p95_duration_ms: percentile_95(&mut durations),
+            },
+        )
+        .collect();
+    out.sort_by_key(|item| item.provider_id);
+    out
+}
+
+/// Aggregate attempts of the cli's most recent requests created at or after `since_unix`.
+pub(crate) fn provider_health_since(
+    db: &db::Db,
+    cli_key: &str,
+    since_unix: i64,
+) -> crate::shared::error::AppResult<Vec<ProviderAttemptHealth>> {
+    let conn = db.open_connection()?;
+    let mut stmt = conn
+        .prepare_cached(
+            r#"
+SELECT
+  attempts_json
+FROM request_logs
+WHERE cli_key = ?1
+  AND created_at >= ?2
+ORDER BY created_at DESC
+LIMIT ?3
+"#,
+        )
+        .map_err(|e| db_err!("failed to prepare attempt health query: {e}"))?;
+
+    let rows = stmt
+        .query_map(params![cli_key, since_unix, HEALTH_MAX_REQUESTS], |row| {
+            row.get::<_, String>(0)
+        })
+        .map_err(|e| db_err!("failed to query attempt health: {e}"))?;
+
+    let mut attempts = Vec::new();
+    for row in rows {
+        let attempts_json = row.map_err(|e| db_err!("failed to read attempt health row: {e}"))?;
+        attempts.extend(parse_attempts(&attempts_json));
+    }
+    Ok(aggregate_health(attempts))
+}
+
+/// Latest auth outcomes seen for one provider (credentials panel).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct ProviderAuthActivity {
+    pub(crate) provider_id: i64,
+    /// `created_at` of the latest request where the provider answered 2xx.
+    pub(crate) last_success_at: Option<i64>,
+    /// `created_at` of the latest request where the provider answered 401/403.
+    pub(crate) last_auth_failure_at: Option<i64>,
+    pub(crate) last_auth_failure_status: Option<i64>,
+}
+
+/// Expects `(created_at, attempt)` pairs newest first; keeps the first hit per provider.
+fn aggregate_auth_activity(
+    attempts: impl IntoIterator<Item = (i64, AttemptRow)>,
+) -> Vec<ProviderAuthActivity> {
+    let mut by_provider: HashMap<i64, ProviderAuthActivity> = HashMap::new();
+    for (created_at, attempt) in attempts {
+        let Some(status) = attempt.status else {
+            continue;
+        };
+        if attempt.provider_id <= 0 {
+            continue;
+        }
+        let entry =
+            by_provider
+                .entry(attempt.provider_id)
+                .or_insert_with(|| ProviderAuthActivity {
+                    provider_id: attempt.provider_id,
+                    ..Default::default()
+                });
+        if (200..300).contains(&status) && entry.last_success_at.is_none() {
+            entry.last_success_at = Some(created_at);
+        } else if matches!(status, 401 | 403) && entry.last_auth_failure_at.is_none() {
+            entry.last_auth_failure_at = Some(created_at);
+            entry.last_auth_failure_status = Some(status);
+        }
+    }
+
+    let mut out: Vec<ProviderAuthActivity> = by_provider.into_values().collect();
+    out.sort_by_key(|item| item.provider_id);
+    out
+}
+
+/// Scans the most recent requests (any cli) created at or after `since_unix`.
+pub(crate) fn provider_auth_activity_since(
+    db: &db::Db,
+    since_unix: i64,
+) -> crate::shared::error::AppResult<Vec<ProviderAuthActivity>> {
+    let conn = db.open_connection()?;
+    let mut stmt = conn
+        .prepare_cached(
+            r#"
+SELECT
+  created_at,
+  attempts_json
+FROM request_logs
+WHERE created_at >= ?1
+ORDER BY created_at DESC, id DESC
+LIMIT ?2
+"#,
+        )
+        .map_err(|e| db_err!("failed to prepare auth activity query: {e}"))?;
+
+    let rows = stmt
+        .query_map(params![since_unix, AUTH_ACTIVITY_MAX_REQUESTS], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|e| db_err!("failed to query auth activity: {e}"))?;
+
+    let mut attempts = Vec::new();
+    for row in rows {
+        let (created_at, attempts_json) =
+            row.map_err(|e| db_err!("failed to read auth activity row: {e}"))?;
+        // Later attempts of a request are newer, so walk them back to front.
+        let mut parsed = parse_attempts(&attempts_json);
+        parsed.reverse();
+        attempts.extend(parsed.into_iter().map(|attempt| (created_at, attempt)));
+    }
+    Ok(aggregate_auth_activity(attempts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        aggregate_auth_activity, aggregate_health, build_timeline, parse_attempts,
+        RequestTimelineSegmentKind,
+    };
+
+    #[test]
+    fn aggregate_auth_activity_keeps_latest_success_and_auth_failure() {
+        let attempts = [
+            (300, r#"{"provider_id":1,"outcome":"success","status":200}"#),
+            (
+                200,
+                r#"{"provider_id":1,"outcome":"upstream_error","status":401}"#,
+            ),
+            (
+                150,
+                r#"{"provider_id":1,"outcome":"upstream_error","status":403}"#,
+            ),
+            (
+                100,
+                r#"{"provider_id":2,"outcome":"upstream_error","status":500}"#,
+            ),
+            (90, r#"{"provider_id":2,"outcome":"skipped"}"#),
+        ]
+        .into_iter()
+        .flat_map(|(created_at, json)| {
+            parse_attempts(&format!("[{json}]"))
+                .into_iter()
+                .map(move |attempt| (created_at, attempt))
+        });
+
+        let activity = aggregate_auth_activity(attempts);
+        assert_eq!(activity.len(), 2);
+        assert_eq!(activity[0].last_success_at, Some(300));
+        assert_eq!(activity[0].last_auth_failure_at, Some(200));
+        assert_eq!(activity[0].last_auth_failure_status, Some(401));
+        assert_eq!(activity[1].last_success_at, None);
+        assert_eq!(activity[1].last_auth_failure_at, None);
+    }
+
+    #[test]
+    fn aggregate_health_computes_success_rate_and_p95() {
+        let mut attempts = parse_attempts(
+            r#"[
+              {"provider_id":1,"outcome":"skipped"},
+              {"provider_id":1,"outcome":"upstream_error: status=500","attempt_duration_ms":50},
+              {"provider_id":2,"outcome":"success","attempt_duration_ms":900}
+            ]"#,
+        );
+        for duration in 1..=20 {
+            attempts.extend(parse_attempts(&format!(
+                r#"[{{"provider_id":1,"outcome":"success","attempt_duration_ms":{}}}]"#,
+                duration * 10
+            )));
+        }
+
+        let health = aggregate_health(attempts);
+        assert_eq!(health.len(), 2);
+        assert_eq!(health[0].provider_id, 1);
+        assert_eq!(health[0].attempts, 21);
+        assert_eq!(health[0].successes, 20);
+        assert_eq!(health[0].p95_duration_ms, Some(190));
+        assert_eq!(health[1].p95_duration_ms, Some(900));
+        assert_eq!(health[1].success_rate(), 1.0);
+    }
+
+    #[test]
+    fn build_timeline_reconstructs_queue_gaps_and_stream() {
+        let attempts = parse_attempts(
+            r#"[
+              {"provider_id":1,"provider_name":"a","outcome":"skipped","reason_code":"circuit_cooldown","attempt_started_ms":12,"attempt_duration_ms":0},
+              {"provider_id":2,"provider_name":"b","outcome":"upstream_error: status=503","attempt_started_ms":15,"attempt_duration_ms":100,"retry_backoff_ms":20},
+              {"provider_id":2,"provider_name":"b","outcome":"success","attempt_started_ms":140,"attempt_duration_ms":200}
+            ]"#,
+        );
+
+        let segments = build_timeline(1000, Some(400), &attempts);
+        let shape: Vec<_> = segments
+            .iter()
+            .map(|s| (s.kind, s.start_ms, s.end_ms))
+            .collect();
+        assert_eq!(
+            shape,
+            vec![
+                (RequestTimelineSegmentKind::Queue, 0, 12),
+                (RequestTimelineSegmentKind::Skipped, 12, 12),
+                (RequestTimelineSegmentKind::Gap, 12, 15),
+                (RequestTimelineSegmentKind::Upstream, 15, 115),
+                (RequestTimelineSegmentKind::Backoff, 115, 135),
+                (RequestTimelineSegmentKind::Gap, 135, 140),
+                (RequestTimelineSegmentKind::Upstream, 140, 400),
+                (RequestTimelineSegmentKind::Stream, 400, 1000),
+            ]
+        );
+        assert_eq!(segments[1].label, "a skipped (circuit_cooldown)");
+        assert_eq!(segments[6].attempt_index, Some(3));
+    }
+
+    #[test]
+    fn build_timeline_clamps_inconsistent_timings() {
+        let attempts = parse_attempts(
+            r#"[{"provider_id":1,"outcome":"upstream_error","attempt_started_ms":80,"attempt_duration_ms":500}]"#,
+        );
+        let segments = build_timeline(300, None, &attempts);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[1].end_ms, 300);
+        assert_eq!(segments[1].label, "provider #1");
+        assert!(build_timeline(0, None, &[]).is_empty());
+    }
+}
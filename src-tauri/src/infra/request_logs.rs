@@ -2,9 +2,10 @@
 
 use crate::shared::error::db_err;
 use crate::shared::time::now_unix_seconds;
-use crate::{cost, db, model_price_aliases};
+use crate::{app_paths, cost, db, model_price_aliases};
 use rusqlite::{params, params_from_iter, ErrorCode, OptionalExtension, TransactionBehavior};
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::time::Duration;
 use tokio::sync::mpsc;
 
@@ -15,14 +16,29 @@ pub use types::{
 };
 
 mod costing;
+pub use costing::estimate_cost_usd_femto;
 use costing::{has_any_cost_usage, is_success_status, usage_for_cost};
 
 mod queries;
-use queries::{final_provider_from_attempts, parse_attempts, validate_cli_key};
+use queries::{
+    error_catalog_code_from_attempts, final_provider_from_attempts, parse_attempts,
+    response_headers_json_from_attempts, validate_cli_key,
+};
 pub use queries::{
     get_by_id, get_by_trace_id, list_after_id, list_after_id_all, list_recent, list_recent_all,
 };
 
+mod retention;
+pub use retention::prune_attempt_details_before;
+
+mod sampling;
+pub use sampling::configure_sampling;
+
+mod store;
+use store::RequestLogStore;
+
+mod wal;
+
 const WRITE_BUFFER_CAPACITY: usize = 512;
 const WRITE_BATCH_MAX: usize = 50;
 const INSERT_RETRY_MAX_ATTEMPTS: u32 = 8;
@@ -172,30 +188,99 @@ fn fetch_model_price_json(
 }
 
 pub fn start_buffered_writer(
-    app: tauri::AppHandle,
+    app: crate::AppHandle,
     db: db::Db,
 ) -> (
     mpsc::Sender<RequestLogInsert>,
     tauri::async_runtime::JoinHandle<()>,
 ) {
+    let wal_leftovers = match app_paths::app_data_dir(&app) {
+        Ok(dir) => wal::init(&dir),
+        Err(err) => {
+            tracing::warn!("request log wal disabled: {}", err);
+            Vec::new()
+        }
+    };
     let (tx, rx) = mpsc::channel::<RequestLogInsert>(WRITE_BUFFER_CAPACITY);
     let task = tauri::async_runtime::spawn_blocking(move || {
+        replay_wal_leftovers(&app, &db, wal_leftovers);
         writer_loop(app, db, rx);
     });
     (tx, task)
 }
 
-pub fn spawn_write_through(app: tauri::AppHandle, db: db::Db, item: RequestLogInsert) {
+/// Hands `item` to the buffered writer through a reserved slot, mirroring it to the WAL first.
+pub fn send_buffered(permit: mpsc::Permit<'_, RequestLogInsert>, item: RequestLogInsert) {
+    wal::append(&item);
+    permit.send(item);
+}
+
+/// `try_send` counterpart of [`send_buffered`].
+pub fn try_send_buffered(
+    tx: &mpsc::Sender<RequestLogInsert>,
+    item: RequestLogInsert,
+) -> Result<(), mpsc::error::TrySendError<RequestLogInsert>> {
+    wal::append(&item);
+    tx.try_send(item).inspect_err(|_| wal::ack(1))
+}
+
+/// Writes entries a crashed run left in the WAL; files are kept only when SQLite was busy.
+fn replay_wal_leftovers(app: &crate::AppHandle, db: &db::Db, files: Vec<PathBuf>) {
+    let mut cache = InsertBatchCache::default();
+    for path in files {
+        let items = match wal::read_entries(&path) {
+            Ok(items) => items,
+            Err(err) => {
+                tracing::warn!(path = %path.display(), "request log wal replay read failed: {}", err);
+                continue;
+            }
+        };
+
+        let mut keep = false;
+        for chunk in items.chunks(WRITE_BATCH_MAX) {
+            if let Err(err) = insert_batch_with_retries(app, db, chunk, &mut cache, true) {
+                tracing::error!(error = %err.message, "request log wal replay insert failed");
+                keep = err.is_retryable();
+                break;
+            }
+        }
+        if keep {
+            continue;
+        }
+        if let Err(err) = std::fs::remove_file(&path) {
+            tracing::warn!(path = %path.display(), "request log wal replay cleanup failed: {}", err);
+        }
+        tracing::info!(
+            count = items.len(),
+            "request log wal replayed after unclean shutdown"
+        );
+    }
+}
+
+pub fn spawn_write_through(app: crate::AppHandle, db: db::Db, item: RequestLogInsert) {
     tauri::async_runtime::spawn_blocking(move || {
         let mut cache = InsertBatchCache::default();
         let items = [item];
-        if let Err(err) = insert_batch_with_retries(&app, &db, &items, &mut cache) {
+        if let Err(err) = insert_batch_with_retries(&app, &db, &items, &mut cache, true) {
             tracing::error!(error = %err.message, "request log write-through insert failed");
         }
     });
 }
 
-fn writer_loop(app: tauri::AppHandle, db: db::Db, mut rx: mpsc::Receiver<RequestLogInsert>) {
+/// Synchronously insert externally sourced rows (history import), costed like gateway rows.
+pub fn insert_imported(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    items: &[RequestLogInsert],
+) -> crate::shared::error::AppResult<()> {
+    let mut cache = InsertBatchCache::default();
+    for chunk in items.chunks(WRITE_BATCH_MAX) {
+        insert_batch_with_retries(app, db, chunk, &mut cache, false).map_err(|err| err.message)?;
+    }
+    Ok(())
+}
+
+fn writer_loop(app: crate::AppHandle, db: db::Db, mut rx: mpsc::Receiver<RequestLogInsert>) {
     let mut buffer: Vec<RequestLogInsert> = Vec::with_capacity(WRITE_BATCH_MAX);
     let mut cache = InsertBatchCache::default();
 
@@ -210,28 +295,31 @@ fn writer_loop(app: tauri::AppHandle, db: db::Db, mut rx: mpsc::Receiver<Request
             }
         }
 
-        if let Err(err) = insert_batch_with_retries(&app, &db, &buffer, &mut cache) {
+        if let Err(err) = insert_batch_with_retries(&app, &db, &buffer, &mut cache, true) {
             tracing::error!(error = %err.message, "request log batch insert failed");
         }
+        wal::ack(buffer.len());
         buffer.clear();
     }
 
     if !buffer.is_empty() {
-        if let Err(err) = insert_batch_with_retries(&app, &db, &buffer, &mut cache) {
+        if let Err(err) = insert_batch_with_retries(&app, &db, &buffer, &mut cache, true) {
             tracing::error!(error = %err.message, "request log final batch insert failed");
         }
+        wal::ack(buffer.len());
     }
 }
 
 fn insert_batch_with_retries(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     db: &db::Db,
     items: &[RequestLogInsert],
     cache: &mut InsertBatchCache,
+    sampling: bool,
 ) -> Result<(), DbWriteError> {
     let mut attempt: u32 = 0;
     loop {
-        match insert_batch_once(app, db, items, cache) {
+        match insert_batch_once(app, db, items, cache, sampling) {
             Ok(()) => return Ok(()),
             Err(err) => {
                 attempt = attempt.saturating_add(1);
@@ -252,10 +340,11 @@ fn insert_batch_with_retries(
 }
 
 fn insert_batch_once(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     db: &db::Db,
     items: &[RequestLogInsert],
     cache: &mut InsertBatchCache,
+    sampling: bool,
 ) -> Result<(), DbWriteError> {
     if items.is_empty() {
         return Ok(());
@@ -270,6 +359,7 @@ fn insert_batch_once(
         .transaction_with_behavior(TransactionBehavior::Immediate)
         .map_err(|e| DbWriteError::from_rusqlite("failed to start transaction", e))?;
 
+    let mut rows: Vec<store::PricedRequestLog<'_>> = Vec::with_capacity(items.len());
     {
         let mut stmt_multiplier = tx
             .prepare_cached("SELECT cost_multiplier FROM providers WHERE id = ?1")
@@ -280,71 +370,6 @@ fn insert_batch_once(
             .prepare_cached("SELECT price_json FROM model_prices WHERE cli_key = ?1 AND model = ?2")
             .map_err(|e| DbWriteError::from_rusqlite("failed to prepare model_price query", e))?;
 
-        let mut stmt = tx
-            .prepare_cached(
-                r#"
-		INSERT INTO request_logs (
-		  trace_id,
-		  cli_key,
-		  session_id,
-		  method,
-		  path,
-		  query,
-		  excluded_from_stats,
-		  special_settings_json,
-		  status,
-		  error_code,
-		  duration_ms,
-		  ttfb_ms,
-		  attempts_json,
-		  input_tokens,
-		  output_tokens,
-		  total_tokens,
-		  cache_read_input_tokens,
-		  cache_creation_input_tokens,
-		  cache_creation_5m_input_tokens,
-		  cache_creation_1h_input_tokens,
-		  usage_json,
-		  requested_model,
-		  cost_usd_femto,
-		  cost_multiplier,
-		  created_at_ms,
-		  created_at,
-		  final_provider_id
-		) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27)
-		ON CONFLICT(trace_id) DO UPDATE SET
-		  method = excluded.method,
-		  path = excluded.path,
-		  query = excluded.query,
-		  excluded_from_stats = excluded.excluded_from_stats,
-	  special_settings_json = excluded.special_settings_json,
-	  status = excluded.status,
-	  error_code = excluded.error_code,
-	  duration_ms = excluded.duration_ms,
-	  ttfb_ms = excluded.ttfb_ms,
-	  attempts_json = excluded.attempts_json,
-	  input_tokens = excluded.input_tokens,
-	  output_tokens = excluded.output_tokens,
-	  total_tokens = excluded.total_tokens,
-	  cache_read_input_tokens = excluded.cache_read_input_tokens,
-	  cache_creation_input_tokens = excluded.cache_creation_input_tokens,
-	  cache_creation_5m_input_tokens = excluded.cache_creation_5m_input_tokens,
-	  cache_creation_1h_input_tokens = excluded.cache_creation_1h_input_tokens,
-		  usage_json = excluded.usage_json,
-		  requested_model = excluded.requested_model,
-		  cost_usd_femto = excluded.cost_usd_femto,
-		  cost_multiplier = excluded.cost_multiplier,
-		  session_id = excluded.session_id,
-		  created_at_ms = CASE
-		    WHEN request_logs.created_at_ms = 0 THEN excluded.created_at_ms
-		    ELSE request_logs.created_at_ms
-		  END,
-		  created_at = CASE WHEN request_logs.created_at = 0 THEN excluded.created_at ELSE request_logs.created_at END,
-		  final_provider_id = excluded.final_provider_id
-		"#,
-            )
-            .map_err(|e| DbWriteError::from_rusqlite("failed to prepare insert", e))?;
-
         let mut batch_multiplier: HashMap<i64, f64> = HashMap::new();
         let mut batch_price_json: HashMap<String, Option<String>> = HashMap::new();
 
@@ -354,6 +379,12 @@ fn insert_batch_once(
             let attempts = parse_attempts(&item.attempts_json);
             let (final_provider_id, _) = final_provider_from_attempts(&attempts);
             let final_provider_id_db = (final_provider_id > 0).then_some(final_provider_id);
+            let error_catalog_code = if is_success_status(item.status, item.error_code.as_deref()) {
+                None
+            } else {
+                error_catalog_code_from_attempts(&attempts)
+            };
+            let response_headers_json = response_headers_json_from_attempts(&attempts);
 
             let cost_multiplier = if final_provider_id > 0 {
                 if let Some(v) = batch_multiplier.get(&final_provider_id) {
@@ -436,39 +467,19 @@ fn insert_batch_once(
                 None
             };
 
-            stmt.execute(params![
-                item.trace_id,
-                item.cli_key,
-                item.session_id,
-                item.method,
-                item.path,
-                item.query,
-                if item.excluded_from_stats { 1i64 } else { 0i64 },
-                item.special_settings_json,
-                item.status,
-                item.error_code,
-                item.duration_ms,
-                item.ttfb_ms,
-                item.attempts_json,
-                item.input_tokens,
-                item.output_tokens,
-                item.total_tokens,
-                item.cache_read_input_tokens,
-                item.cache_creation_input_tokens,
-                item.cache_creation_5m_input_tokens,
-                item.cache_creation_1h_input_tokens,
-                item.usage_json,
-                item.requested_model,
+            rows.push(store::PricedRequestLog {
+                item,
+                final_provider_id,
+                final_provider_id_db,
+                error_catalog_code,
+                response_headers_json,
                 cost_usd_femto,
                 cost_multiplier,
-                item.created_at_ms,
-                item.created_at,
-                final_provider_id_db
-            ])
-            .map_err(|e| DbWriteError::from_rusqlite("failed to insert request_log", e))?;
+            });
         }
     }
 
+    store::SqliteStore::new(&tx).write_rows(&rows, sampling)?;
     tx.commit()
         .map_err(|e| DbWriteError::from_rusqlite("failed to commit transaction", e))?;
 
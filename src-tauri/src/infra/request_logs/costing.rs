@@ -1,6 +1,8 @@
 //! Usage: Cost and pricing helpers for request logs.
 
-use crate::cost;
+use crate::shared::error::db_err;
+use crate::{cost, db, model_price_aliases};
+use rusqlite::{params, OptionalExtension};
 
 use super::RequestLogInsert;
 
@@ -33,3 +35,80 @@ pub(super) fn has_any_cost_usage(usage: &cost::CostUsage) -> bool {
         || usage.cache_creation_5m_input_tokens > 0
         || usage.cache_creation_1h_input_tokens > 0
 }
+
+fn usage_from_metrics(metrics: &crate::usage::UsageMetrics) -> cost::CostUsage {
+    cost::CostUsage {
+        input_tokens: metrics.input_tokens.unwrap_or(0),
+        output_tokens: metrics.output_tokens.unwrap_or(0),
+        cache_read_input_tokens: metrics.cache_read_input_tokens.unwrap_or(0),
+        cache_creation_input_tokens: metrics.cache_creation_input_tokens.unwrap_or(0),
+        cache_creation_5m_input_tokens: metrics.cache_creation_5m_input_tokens.unwrap_or(0),
+        cache_creation_1h_input_tokens: metrics.cache_creation_1h_input_tokens.unwrap_or(0),
+    }
+}
+
+/// Prices a single call the way the log writer does (model price, alias fallback, provider
+/// cost multiplier), for callers that need the figure before the log row is written.
+pub fn estimate_cost_usd_femto(
+    app: &crate::AppHandle,
+    db: &db::Db,
+    cli_key: &str,
+    provider_id: i64,
+    model: &str,
+    metrics: &crate::usage::UsageMetrics,
+) -> crate::shared::error::AppResult<Option<i64>> {
+    let model = model.trim();
+    let usage = usage_from_metrics(metrics);
+    if model.is_empty() || !has_any_cost_usage(&usage) {
+        return Ok(None);
+    }
+
+    let conn = db.open_connection()?;
+    let price_json_for = |model: &str| -> crate::shared::error::AppResult<Option<String>> {
+        conn.query_row(
+            "SELECT price_json FROM model_prices WHERE cli_key = ?1 AND model = ?2",
+            params![cli_key, model],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query model price: {e}"))
+    };
+
+    let mut priced_model = model;
+    let mut price_json = price_json_for(model)?;
+    let price_aliases;
+    if price_json.is_none() {
+        price_aliases = model_price_aliases::read_fail_open(app);
+        if let Some(target_model) = price_aliases.resolve_target_model(cli_key, model) {
+            if target_model != model {
+                priced_model = target_model;
+                price_json = price_json_for(target_model)?;
+            }
+        }
+    }
+    let Some(price_json) = price_json else {
+        return Ok(None);
+    };
+
+    let multiplier = if provider_id > 0 {
+        conn.query_row(
+            "SELECT cost_multiplier FROM providers WHERE id = ?1",
+            params![provider_id],
+            |row| row.get::<_, f64>(0),
+        )
+        .optional()
+        .map_err(|e| db_err!("failed to query provider cost_multiplier: {e}"))?
+        .filter(|v| v.is_finite() && *v >= 0.0)
+        .unwrap_or(1.0)
+    } else {
+        1.0
+    };
+
+    Ok(cost::calculate_cost_usd_femto(
+        &usage,
+        &price_json,
+        multiplier,
+        cli_key,
+        priced_model,
+    ))
+}
@@ -3,7 +3,8 @@
 use crate::db;
 use crate::shared::error::db_err;
 use rusqlite::{params, OptionalExtension};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 use super::costing::cost_usd_from_femto;
 use super::{RequestLogDetail, RequestLogRouteHop, RequestLogSummary};
@@ -30,6 +31,9 @@ const REQUEST_LOG_SUMMARY_FIELDS: &str = "
   cache_creation_1h_input_tokens,
   cost_usd_femto,
   cost_multiplier,
+  error_catalog_code,
+  response_headers_json,
+  attempts_summary_json,
   created_at_ms,
   created_at
 ";
@@ -60,6 +64,8 @@ const REQUEST_LOG_DETAIL_FIELDS: &str = "
   requested_model,
   cost_usd_femto,
   cost_multiplier,
+  error_catalog_code,
+  response_headers_json,
   created_at_ms,
   created_at
 ";
@@ -79,12 +85,31 @@ pub(super) struct AttemptRow {
     decision: Option<String>,
     reason: Option<String>,
     session_reuse: Option<bool>,
+    error_catalog_code: Option<String>,
+    response_headers: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
 pub(super) fn parse_attempts(attempts_json: &str) -> Vec<AttemptRow> {
     serde_json::from_str(attempts_json).unwrap_or_default()
 }
 
+/// Catalog code of the last attempt whose upstream error body was recognized.
+pub(super) fn error_catalog_code_from_attempts(attempts: &[AttemptRow]) -> Option<String> {
+    attempts
+        .iter()
+        .rev()
+        .find_map(|a| a.error_catalog_code.clone())
+}
+
+/// Captured response headers of the last attempt that recorded any, as a JSON object.
+pub(super) fn response_headers_json_from_attempts(attempts: &[AttemptRow]) -> Option<String> {
+    attempts
+        .iter()
+        .rev()
+        .find_map(|a| a.response_headers.as_ref())
+        .and_then(|headers| serde_json::to_string(headers).ok())
+}
+
 pub(super) fn start_provider_from_attempts(attempts: &[AttemptRow]) -> (i64, String) {
     let first = attempts
         .iter()
@@ -179,18 +204,72 @@ pub(super) fn route_from_attempts(attempts: &[AttemptRow]) -> Vec<RequestLogRout
     out
 }
 
+/// Aggregate kept on request_logs once the per-attempt details have been pruned.
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct AttemptsSummary {
+    pub(super) attempt_count: i64,
+    /// Attempts per outcome kind (`upstream_error: status=500` is counted as `upstream_error`).
+    pub(super) outcomes: BTreeMap<String, i64>,
+    pub(super) route: Vec<RequestLogRouteHop>,
+    #[serde(default)]
+    pub(super) session_reuse: bool,
+}
+
+pub(super) fn summarize_attempts(attempts: &[AttemptRow]) -> AttemptsSummary {
+    let mut outcomes: BTreeMap<String, i64> = BTreeMap::new();
+    for attempt in attempts {
+        let kind = attempt.outcome.split(':').next().unwrap_or_default().trim();
+        let kind = if kind.is_empty() { "unknown" } else { kind };
+        *outcomes.entry(kind.to_string()).or_default() += 1;
+    }
+
+    AttemptsSummary {
+        attempt_count: attempts.len() as i64,
+        outcomes,
+        route: route_from_attempts(attempts),
+        session_reuse: attempts
+            .iter()
+            .any(|row| row.session_reuse.unwrap_or(false)),
+    }
+}
+
+/// One attempt per route hop: enough for start/final provider lookups once details are gone.
+pub(super) fn compact_attempts_json(route: &[RequestLogRouteHop]) -> String {
+    let rows: Vec<serde_json::Value> = route
+        .iter()
+        .map(|hop| {
+            serde_json::json!({
+                "provider_id": hop.provider_id,
+                "provider_name": hop.provider_name,
+                "outcome": if hop.ok { "success" } else { "failed" },
+                "status": hop.status,
+                "error_code": hop.error_code,
+                "decision": hop.decision,
+                "reason": hop.reason,
+            })
+        })
+        .collect();
+    serde_json::to_string(&rows).unwrap_or_else(|_| "[]".to_string())
+}
+
 fn row_to_summary(row: &rusqlite::Row<'_>) -> Result<RequestLogSummary, rusqlite::Error> {
     let attempts_json: String = row.get("attempts_json")?;
     let attempts = parse_attempts(&attempts_json);
-    let attempt_count = attempts.len() as i64;
     let (start_provider_id, start_provider_name) = start_provider_from_attempts(&attempts);
     let (final_provider_id, final_provider_name) = final_provider_from_attempts(&attempts);
-    let route = route_from_attempts(&attempts);
+    // 明细已被清理的请求：attempts_json 只剩每个 hop 一条，计数与 route 以汇总为准
+    let summary = row
+        .get::<_, Option<String>>("attempts_summary_json")?
+        .and_then(|raw| serde_json::from_str::<AttemptsSummary>(&raw).ok())
+        .unwrap_or_else(|| summarize_attempts(&attempts));
+    let AttemptsSummary {
+        attempt_count,
+        route,
+        session_reuse,
+        ..
+    } = summary;
     // has_failover: 真正切换过 provider（route 中有多个 hop，skipped 已被过滤）
     let has_failover = route.len() > 1;
-    let session_reuse = attempts
-        .iter()
-        .any(|row| row.session_reuse.unwrap_or(false));
     let cost_usd = cost_usd_from_femto(row.get("cost_usd_femto")?);
 
     Ok(RequestLogSummary {
@@ -221,6 +300,8 @@ fn row_to_summary(row: &rusqlite::Row<'_>) -> Result<RequestLogSummary, rusqlite
         cache_creation_1h_input_tokens: row.get("cache_creation_1h_input_tokens")?,
         cost_usd,
         cost_multiplier: row.get("cost_multiplier")?,
+        error_catalog_code: row.get("error_catalog_code")?,
+        response_headers_json: row.get("response_headers_json")?,
         created_at_ms: row.get("created_at_ms")?,
         created_at: row.get("created_at")?,
     })
@@ -365,6 +446,8 @@ pub fn get_by_id(db: &db::Db, log_id: i64) -> crate::shared::error::AppResult<Re
             requested_model: row.get("requested_model")?,
             cost_usd,
             cost_multiplier: row.get("cost_multiplier")?,
+            error_catalog_code: row.get("error_catalog_code")?,
+            response_headers_json: row.get("response_headers_json")?,
             created_at_ms: row.get("created_at_ms")?,
             created_at: row.get("created_at")?,
         })
@@ -415,6 +498,8 @@ pub fn get_by_trace_id(
             requested_model: row.get("requested_model")?,
             cost_usd,
             cost_multiplier: row.get("cost_multiplier")?,
+            error_catalog_code: row.get("error_catalog_code")?,
+            response_headers_json: row.get("response_headers_json")?,
             created_at_ms: row.get("created_at_ms")?,
             created_at: row.get("created_at")?,
         })
@@ -426,10 +511,43 @@ pub fn get_by_trace_id(
 #[cfg(test)]
 mod tests {
     use super::{
-        final_provider_from_attempts, parse_attempts, route_from_attempts,
-        start_provider_from_attempts,
+        compact_attempts_json, error_catalog_code_from_attempts, final_provider_from_attempts,
+        parse_attempts, response_headers_json_from_attempts, route_from_attempts,
+        start_provider_from_attempts, summarize_attempts,
     };
 
+    #[test]
+    fn error_catalog_code_uses_last_classified_attempt() {
+        let attempts = parse_attempts(
+            r#"[
+                {"provider_id":1,"provider_name":"A","outcome":"failed","status":401,"error_code":"GW_UPSTREAM_4XX","decision":"failover","reason":"status=401","error_catalog_code":"PROVIDER_INVALID_API_KEY"},
+                {"provider_id":2,"provider_name":"B","outcome":"failed","status":429,"error_code":"GW_UPSTREAM_4XX","decision":"failover","reason":"status=429","error_catalog_code":"PROVIDER_QUOTA_EXCEEDED"},
+                {"provider_id":3,"provider_name":"C","outcome":"failed","status":500,"error_code":"GW_UPSTREAM_5XX","decision":"failover","reason":"status=500"}
+            ]"#,
+        );
+        assert_eq!(
+            error_catalog_code_from_attempts(&attempts).as_deref(),
+            Some("PROVIDER_QUOTA_EXCEEDED")
+        );
+        assert_eq!(error_catalog_code_from_attempts(&[]), None);
+    }
+
+    #[test]
+    fn response_headers_json_uses_last_attempt_with_headers() {
+        let attempts = parse_attempts(
+            r#"[
+                {"provider_id":1,"provider_name":"A","outcome":"failed","status":529,"response_headers":{"x-backend-model":"opus"}},
+                {"provider_id":2,"provider_name":"B","outcome":"success","status":200,"response_headers":{"x-backend-model":"sonnet"}},
+                {"provider_id":3,"provider_name":"C","outcome":"skipped","status":null}
+            ]"#,
+        );
+        assert_eq!(
+            response_headers_json_from_attempts(&attempts).as_deref(),
+            Some(r#"{"x-backend-model":"sonnet"}"#)
+        );
+        assert_eq!(response_headers_json_from_attempts(&[]), None);
+    }
+
     #[test]
     fn route_excludes_skipped_attempts() {
         let attempts = parse_attempts(
@@ -500,4 +618,29 @@ mod tests {
         assert_eq!(route[0].attempts, 1);
         assert!(route[0].ok);
     }
+
+    #[test]
+    fn compacted_attempts_keep_route_endpoints() {
+        let attempts = parse_attempts(
+            r#"[
+                {"provider_id":3,"provider_name":"C","outcome":"skipped","status":null,"error_code":null,"decision":"skip","reason":"circuit_open"},
+                {"provider_id":1,"provider_name":"A","outcome":"upstream_error: status=500","status":500,"error_code":"GW_UPSTREAM_5XX","decision":"retry","reason":"status=500"},
+                {"provider_id":1,"provider_name":"A","outcome":"upstream_error: status=502","status":502,"error_code":"GW_UPSTREAM_5XX","decision":"failover","reason":"status=502"},
+                {"provider_id":2,"provider_name":"B","outcome":"success","status":200,"error_code":null,"decision":"success","reason":null,"session_reuse":true}
+            ]"#,
+        );
+        let summary = summarize_attempts(&attempts);
+        assert_eq!(summary.attempt_count, 4);
+        assert_eq!(summary.outcomes.get("upstream_error"), Some(&2));
+        assert_eq!(summary.outcomes.get("skipped"), Some(&1));
+        assert_eq!(summary.outcomes.get("success"), Some(&1));
+        assert_eq!(summary.route.len(), 2);
+        assert_eq!(summary.route[0].attempts, 2);
+        assert!(summary.session_reuse);
+
+        let compact = parse_attempts(&compact_attempts_json(&summary.route));
+        assert_eq!(compact.len(), 2);
+        assert_eq!(start_provider_from_attempts(&compact), (1, "A".to_string()));
+        assert_eq!(final_provider_from_attempts(&compact), (2, "B".to_string()));
+    }
 }
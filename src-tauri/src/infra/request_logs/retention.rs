@@ -0,0 +1,91 @@
+//! Usage: Attempt detail retention (folds old attempts_json into per-request summaries).
+
+use crate::db;
+use crate::shared::error::db_err;
+use crate::shared::time::now_unix_seconds;
+use rusqlite::{params, TransactionBehavior};
+
+use super::queries::{compact_attempts_json, parse_attempts, summarize_attempts};
+
+const PRUNE_BATCH_SIZE: i64 = 500;
+
+/// Prunes per-attempt details of requests created before `cutoff_unix`.
+///
+/// Each row keeps one attempt per route hop in `attempts_json` (so provider attribution keeps
+/// working) and gets its attempt count/outcome aggregate in `attempts_summary_json`. Returns the
+/// number of pruned rows.
+pub fn prune_attempt_details_before(
+    db: &db::Db,
+    cutoff_unix: i64,
+) -> crate::shared::error::AppResult<usize> {
+    let mut conn = db.open_connection()?;
+    let pruned_at = now_unix_seconds();
+    let mut pruned = 0usize;
+
+    loop {
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(|e| db_err!("failed to start attempt prune transaction: {e}"))?;
+
+        let batch: Vec<(i64, String)> = {
+            let mut stmt = tx
+                .prepare_cached(
+                    r#"
+SELECT id, attempts_json
+FROM request_logs
+WHERE attempts_pruned_at IS NULL
+  AND created_at < ?1
+ORDER BY id
+LIMIT ?2
+"#,
+                )
+                .map_err(|e| db_err!("failed to prepare attempt prune query: {e}"))?;
+            let rows = stmt
+                .query_map(params![cutoff_unix, PRUNE_BATCH_SIZE], |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+                })
+                .map_err(|e| db_err!("failed to query attempts to prune: {e}"))?;
+            let mut batch = Vec::new();
+            for row in rows {
+                batch.push(row.map_err(|e| db_err!("failed to read attempt prune row: {e}"))?);
+            }
+            batch
+        };
+
+        {
+            let mut stmt = tx
+                .prepare_cached(
+                    r#"
+UPDATE request_logs
+SET attempts_json = ?1,
+    attempts_summary_json = ?2,
+    attempts_pruned_at = ?3
+WHERE id = ?4
+"#,
+                )
+                .map_err(|e| db_err!("failed to prepare attempt prune update: {e}"))?;
+            for (id, attempts_json) in &batch {
+                let summary = summarize_attempts(&parse_attempts(attempts_json));
+                let summary_json = serde_json::to_string(&summary)
+                    .map_err(|e| db_err!("failed to encode attempts summary: {e}"))?;
+                stmt.execute(params![
+                    compact_attempts_json(&summary.route),
+                    summary_json,
+                    pruned_at,
+                    id
+                ])
+                .map_err(|e| db_err!("failed to prune request_log attempts: {e}"))?;
+            }
+        }
+
+        tx.commit()
+            .map_err(|e| db_err!("failed to commit attempt prune transaction: {e}"))?;
+
+        pruned += batch.len();
+        if (batch.len() as i64) < PRUNE_BATCH_SIZE {
+            break;
+        }
+    }
+
+    Ok(pruned)
+}
@@ -0,0 +1,224 @@
+//! Usage: Request log sampling (`request_log_sample_percent` / `request_log_slow_threshold_ms`).
+//!
+//! Errors and slow requests always get a `request_logs` row. Successful fast requests are kept at
+//! the configured percent (decided by a stable hash of the trace id); the rest are folded into
+//! hourly `request_log_rollups` buckets so usage totals stay exact.
+
+use super::costing::is_success_status;
+use super::RequestLogInsert;
+use rusqlite::{params, Transaction};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+static SAMPLE_PERCENT: AtomicU32 = AtomicU32::new(100);
+static SLOW_THRESHOLD_MS: AtomicU32 = AtomicU32::new(30_000);
+
+const ROLLUP_BUCKET_SECS: i64 = 60 * 60;
+
+/// Call at startup and whenever the settings change.
+pub fn configure_sampling(sample_percent: u32, slow_threshold_ms: u32) {
+    SAMPLE_PERCENT.store(sample_percent.clamp(1, 100), Ordering::Relaxed);
+    SLOW_THRESHOLD_MS.store(slow_threshold_ms.max(1), Ordering::Relaxed);
+}
+
+fn trace_bucket(trace_id: &str) -> u32 {
+    // FNV-1a: stable across restarts so WAL replays make the same decision.
+    let mut hash: u32 = 0x811c_9dc5;
+    for byte in trace_id.as_bytes() {
+        hash ^= u32::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash % 100
+}
+
+fn should_sample_out_with(item: &RequestLogInsert, sample_percent: u32, slow_ms: u32) -> bool {
+    if sample_percent >= 100 || item.excluded_from_stats {
+        return false;
+    }
+    if !is_success_status(item.status, item.error_code.as_deref())
+        || item.duration_ms >= i64::from(slow_ms)
+    {
+        return false;
+    }
+    trace_bucket(&item.trace_id) >= sample_percent
+}
+
+pub(super) fn should_sample_out(item: &RequestLogInsert) -> bool {
+    should_sample_out_with(
+        item,
+        SAMPLE_PERCENT.load(Ordering::Relaxed),
+        SLOW_THRESHOLD_MS.load(Ordering::Relaxed),
+    )
+}
+
+/// Adds one sampled-out (successful) request to its hourly rollup bucket.
+pub(super) fn fold_into_rollup(
+    tx: &Transaction<'_>,
+    item: &RequestLogInsert,
+    final_provider_id: i64,
+    cost_usd_femto: Option<i64>,
+) -> rusqlite::Result<()> {
+    let input = item.input_tokens.unwrap_or(0);
+    let output = item.output_tokens.unwrap_or(0);
+    let cache_read = item.cache_read_input_tokens.unwrap_or(0);
+    let cache_creation = item.cache_creation_input_tokens.unwrap_or(0);
+    // Mirrors the effective token expressions used by usage_stats.
+    let effective_input = if matches!(item.cli_key.as_str(), "codex" | "gemini") {
+        (input - cache_read).max(0)
+    } else {
+        input
+    };
+    let effective_total = effective_input + output + cache_creation + cache_read;
+    let has_usage = item.total_tokens.is_some()
+        || item.input_tokens.is_some()
+        || item.output_tokens.is_some()
+        || item.cache_read_input_tokens.is_some()
+        || item.cache_creation_input_tokens.is_some()
+        || item.cache_creation_5m_input_tokens.is_some()
+        || item.cache_creation_1h_input_tokens.is_some()
+        || item.usage_json.is_some();
+    let ttfb = item.ttfb_ms.filter(|ttfb| *ttfb < item.duration_ms);
+    let (generation_ms, output_for_rate) = match (ttfb, item.output_tokens) {
+        (Some(ttfb), Some(output)) => (item.duration_ms - ttfb, output),
+        _ => (0, 0),
+    };
+    let bucket_start = item.created_at - item.created_at.rem_euclid(ROLLUP_BUCKET_SECS);
+    let requested_model = item.requested_model.as_deref().map(str::trim).unwrap_or("");
+
+    tx.prepare_cached(
+        r#"
+INSERT INTO request_log_rollups (
+  bucket_start,
+  cli_key,
+  final_provider_id,
+  requested_model,
+  request_count,
+  requests_with_usage,
+  duration_ms_sum,
+  ttfb_ms_sum,
+  ttfb_count,
+  generation_ms_sum,
+  output_tokens_for_rate_sum,
+  input_tokens,
+  output_tokens,
+  total_tokens,
+  cache_read_input_tokens,
+  cache_creation_input_tokens,
+  cache_creation_5m_input_tokens,
+  cache_creation_1h_input_tokens,
+  cost_usd_femto,
+  request_bytes,
+  response_bytes
+) VALUES (?1, ?2, ?3, ?4, 1, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)
+ON CONFLICT(bucket_start, cli_key, final_provider_id, requested_model) DO UPDATE SET
+  request_count = request_count + 1,
+  requests_with_usage = requests_with_usage + excluded.requests_with_usage,
+  duration_ms_sum = duration_ms_sum + excluded.duration_ms_sum,
+  ttfb_ms_sum = ttfb_ms_sum + excluded.ttfb_ms_sum,
+  ttfb_count = ttfb_count + excluded.ttfb_count,
+  generation_ms_sum = generation_ms_sum + excluded.generation_ms_sum,
+  output_tokens_for_rate_sum = output_tokens_for_rate_sum + excluded.output_tokens_for_rate_sum,
+  input_tokens = input_tokens + excluded.input_tokens,
+  output_tokens = output_tokens + excluded.output_tokens,
+  total_tokens = total_tokens + excluded.total_tokens,
+  cache_read_input_tokens = cache_read_input_tokens + excluded.cache_read_input_tokens,
+  cache_creation_input_tokens = cache_creation_input_tokens + excluded.cache_creation_input_tokens,
+  cache_creation_5m_input_tokens = cache_creation_5m_input_tokens + excluded.cache_creation_5m_input_tokens,
+  cache_creation_1h_input_tokens = cache_creation_1h_input_tokens + excluded.cache_creation_1h_input_tokens,
+  cost_usd_femto = cost_usd_femto + excluded.cost_usd_femto,
+  request_bytes = request_bytes + excluded.request_bytes,
+  response_bytes = response_bytes + excluded.response_bytes
+"#,
+    )?
+    .execute(params![
+        bucket_start,
+        item.cli_key,
+        final_provider_id.max(0),
+        requested_model,
+        i64::from(has_usage),
+        item.duration_ms,
+        ttfb.unwrap_or(0),
+        i64::from(ttfb.is_some()),
+        generation_ms,
+        output_for_rate,
+        effective_input,
+        output,
+        effective_total,
+        cache_read,
+        cache_creation,
+        item.cache_creation_5m_input_tokens.unwrap_or(0),
+        item.cache_creation_1h_input_tokens.unwrap_or(0),
+        cost_usd_femto.unwrap_or(0),
+        item.request_bytes.unwrap_or(0),
+        item.response_bytes.unwrap_or(0),
+    ])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(trace_id: &str, status: Option<i64>, duration_ms: i64) -> RequestLogInsert {
+        RequestLogInsert {
+            trace_id: trace_id.to_string(),
+            cli_key: "claude".to_string(),
+            session_id: None,
+            method: "POST".to_string(),
+            path: "/v1/messages".to_string(),
+            query: None,
+            excluded_from_stats: false,
+            special_settings_json: None,
+            status,
+            error_code: None,
+            duration_ms,
+            ttfb_ms: None,
+            attempts_json: "[]".to_string(),
+            input_tokens: None,
+            output_tokens: None,
+            total_tokens: None,
+            cache_read_input_tokens: None,
+            cache_creation_input_tokens: None,
+            cache_creation_5m_input_tokens: None,
+            cache_creation_1h_input_tokens: None,
+            usage_json: None,
+            requested_model: None,
+            created_at_ms: 0,
+            created_at: 0,
+            request_bytes: None,
+            response_bytes: None,
+            thinking_budget_tokens: None,
+        }
+    }
+
+    #[test]
+    fn errors_slow_requests_and_full_rate_are_always_kept() {
+        for n in 0..50 {
+            let trace_id = format!("trace-{n}");
+            assert!(!should_sample_out_with(
+                &item(&trace_id, Some(200), 100),
+                100,
+                5000
+            ));
+            assert!(!should_sample_out_with(
+                &item(&trace_id, Some(502), 100),
+                1,
+                5000
+            ));
+            assert!(!should_sample_out_with(
+                &item(&trace_id, Some(200), 5000),
+                1,
+                5000
+            ));
+        }
+    }
+
+    #[test]
+    fn fast_successes_are_sampled_near_the_configured_rate() {
+        let kept = (0..1000)
+            .filter(|n| {
+                !should_sample_out_with(&item(&format!("trace-{n}"), Some(200), 100), 10, 5000)
+            })
+            .count();
+        assert!((50..=150).contains(&kept), "kept={kept}");
+    }
+}
@@ -0,0 +1,178 @@
+//! Usage: Request log row store (the write side of `request_logs`).
+//!
+//! The writer prices a batch against SQLite (providers, model prices) and hands the finished rows
+//! to a [`RequestLogStore`]. SQLite is the only store: the log, usage and cost read paths still
+//! query it directly, so another backend needs those reads behind this seam first.
+
+use super::{sampling, DbWriteError, RequestLogInsert};
+use rusqlite::{params, Transaction};
+
+/// A request log row with its derived provider, error and cost columns filled in.
+pub(super) struct PricedRequestLog<'a> {
+    pub(super) item: &'a RequestLogInsert,
+    /// Provider that served the request (0 when no attempt reached one).
+    pub(super) final_provider_id: i64,
+    pub(super) final_provider_id_db: Option<i64>,
+    pub(super) error_catalog_code: Option<String>,
+    pub(super) response_headers_json: Option<String>,
+    pub(super) cost_usd_femto: Option<i64>,
+    pub(super) cost_multiplier: f64,
+}
+
+/// Destination for priced request log rows.
+pub(super) trait RequestLogStore {
+    /// Upserts `rows` by `trace_id`; `sampling` lets the store fold sampled-out rows into rollups.
+    fn write_rows(
+        &mut self,
+        rows: &[PricedRequestLog<'_>],
+        sampling: bool,
+    ) -> Result<(), DbWriteError>;
+}
+
+/// Writes into `request_logs` inside the caller's SQLite transaction.
+pub(super) struct SqliteStore<'a, 'conn> {
+    tx: &'a Transaction<'conn>,
+}
+
+impl<'a, 'conn> SqliteStore<'a, 'conn> {
+    pub(super) fn new(tx: &'a Transaction<'conn>) -> Self {
+        Self { tx }
+    }
+}
+
+impl RequestLogStore for SqliteStore<'_, '_> {
+    fn write_rows(
+        &mut self,
+        rows: &[PricedRequestLog<'_>],
+        sampling: bool,
+    ) -> Result<(), DbWriteError> {
+        let mut stmt = self
+            .tx
+            .prepare_cached(
+                r#"
+		INSERT INTO request_logs (
+		  trace_id,
+		  cli_key,
+		  session_id,
+		  method,
+		  path,
+		  query,
+		  excluded_from_stats,
+		  special_settings_json,
+		  status,
+		  error_code,
+		  duration_ms,
+		  ttfb_ms,
+		  attempts_json,
+		  input_tokens,
+		  output_tokens,
+		  total_tokens,
+		  cache_read_input_tokens,
+		  cache_creation_input_tokens,
+		  cache_creation_5m_input_tokens,
+		  cache_creation_1h_input_tokens,
+		  usage_json,
+		  requested_model,
+		  cost_usd_femto,
+		  cost_multiplier,
+		  created_at_ms,
+		  created_at,
+		  final_provider_id,
+		  error_catalog_code,
+		  response_headers_json,
+		  request_bytes,
+		  response_bytes,
+		  thinking_budget_tokens
+		) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31, ?32)
+		ON CONFLICT(trace_id) DO UPDATE SET
+		  method = excluded.method,
+		  path = excluded.path,
+		  query = excluded.query,
+		  excluded_from_stats = excluded.excluded_from_stats,
+	  special_settings_json = excluded.special_settings_json,
+	  status = excluded.status,
+	  error_code = excluded.error_code,
+	  duration_ms = excluded.duration_ms,
+	  ttfb_ms = excluded.ttfb_ms,
+	  attempts_json = excluded.attempts_json,
+	  input_tokens = excluded.input_tokens,
+	  output_tokens = excluded.output_tokens,
+	  total_tokens = excluded.total_tokens,
+	  cache_read_input_tokens = excluded.cache_read_input_tokens,
+	  cache_creation_input_tokens = excluded.cache_creation_input_tokens,
+	  cache_creation_5m_input_tokens = excluded.cache_creation_5m_input_tokens,
+	  cache_creation_1h_input_tokens = excluded.cache_creation_1h_input_tokens,
+		  usage_json = excluded.usage_json,
+		  requested_model = excluded.requested_model,
+		  cost_usd_femto = excluded.cost_usd_femto,
+		  cost_multiplier = excluded.cost_multiplier,
+		  session_id = excluded.session_id,
+		  created_at_ms = CASE
+		    WHEN request_logs.created_at_ms = 0 THEN excluded.created_at_ms
+		    ELSE request_logs.created_at_ms
+		  END,
+		  created_at = CASE WHEN request_logs.created_at = 0 THEN excluded.created_at ELSE request_logs.created_at END,
+		  final_provider_id = excluded.final_provider_id,
+		  error_catalog_code = excluded.error_catalog_code,
+		  response_headers_json = excluded.response_headers_json,
+		  request_bytes = excluded.request_bytes,
+		  response_bytes = excluded.response_bytes,
+		  thinking_budget_tokens = excluded.thinking_budget_tokens
+		"#,
+            )
+            .map_err(|e| DbWriteError::from_rusqlite("failed to prepare insert", e))?;
+
+        for row in rows {
+            let item = row.item;
+            if sampling && sampling::should_sample_out(item) {
+                sampling::fold_into_rollup(
+                    self.tx,
+                    item,
+                    row.final_provider_id,
+                    row.cost_usd_femto,
+                )
+                .map_err(|e| {
+                    DbWriteError::from_rusqlite("failed to fold request_log into rollup", e)
+                })?;
+                continue;
+            }
+
+            stmt.execute(params![
+                item.trace_id,
+                item.cli_key,
+                item.session_id,
+                item.method,
+                item.path,
+                item.query,
+                if item.excluded_from_stats { 1i64 } else { 0i64 },
+                item.special_settings_json,
+                item.status,
+                item.error_code,
+                item.duration_ms,
+                item.ttfb_ms,
+                item.attempts_json,
+                item.input_tokens,
+                item.output_tokens,
+                item.total_tokens,
+                item.cache_read_input_tokens,
+                item.cache_creation_input_tokens,
+                item.cache_creation_5m_input_tokens,
+                item.cache_creation_1h_input_tokens,
+                item.usage_json,
+                item.requested_model,
+                row.cost_usd_femto,
+                row.cost_multiplier,
+                item.created_at_ms,
+                item.created_at,
+                row.final_provider_id_db,
+                row.error_catalog_code,
+                row.response_headers_json,
+                item.request_bytes,
+                item.response_bytes,
+                item.thinking_budget_tokens
+            ])
+            .map_err(|e| DbWriteError::from_rusqlite("failed to insert request_log", e))?;
+        }
+        Ok(())
+    }
+}
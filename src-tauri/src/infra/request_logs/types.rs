@@ -1,8 +1,8 @@
 //! Usage: Request log DTOs and insertion payloads.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequestLogInsert {
     pub trace_id: String,
     pub cli_key: String,
@@ -28,9 +28,18 @@ pub struct RequestLogInsert {
     pub requested_model: Option<String>,
     pub created_at_ms: i64,
     pub created_at: i64,
+    /// Client request body size (missing in WAL files written by older builds).
+    #[serde(default)]
+    pub request_bytes: Option<i64>,
+    /// Response body bytes returned to the client.
+    #[serde(default)]
+    pub response_bytes: Option<i64>,
+    /// Extended thinking budget sent upstream (Claude only).
+    #[serde(default)]
+    pub thinking_budget_tokens: Option<i64>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct RequestLogRouteHop {
     pub provider_id: i64,
     pub provider_name: String,
@@ -53,7 +62,7 @@ fn is_false(v: &bool) -> bool {
     !v
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct RequestLogSummary {
     pub id: i64,
     pub trace_id: String,
@@ -82,11 +91,14 @@ pub struct RequestLogSummary {
     pub cache_creation_1h_input_tokens: Option<i64>,
     pub cost_usd: Option<f64>,
     pub cost_multiplier: f64,
+    pub error_catalog_code: Option<String>,
+    /// Allow-listed upstream response headers of the relayed attempt (JSON object).
+    pub response_headers_json: Option<String>,
     pub created_at_ms: i64,
     pub created_at: i64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct RequestLogDetail {
     pub id: i64,
     pub trace_id: String,
@@ -112,6 +124,9 @@ pub struct RequestLogDetail {
     pub requested_model: Option<String>,
     pub cost_usd: Option<f64>,
     pub cost_multiplier: f64,
+    pub error_catalog_code: Option<String>,
+    /// Allow-listed upstream response headers of the relayed attempt (JSON object).
+    pub response_headers_json: Option<String>,
     pub created_at_ms: i64,
     pub created_at: i64,
 }
@@ -0,0 +1,197 @@
+//! Usage: Append-only spill file mirroring the buffered request log queue.
+//!
+//! Entries are appended before they enter the channel and the file is truncated whenever the writer
+//! has drained everything it was handed. A crash leaves the unwritten tail on disk; the next start
+//! moves it aside and replays it into SQLite (inserts upsert by `trace_id`, so replays are safe).
+
+use super::RequestLogInsert;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::shared::mutex_ext::MutexExt;
+
+const WAL_FILE_NAME: &str = "request_logs.wal";
+const REPLAY_SUFFIX: &str = ".replay";
+
+struct WalState {
+    file: File,
+    /// Appended entries the writer has not consumed yet; the file is truncated when this hits 0.
+    pending: u64,
+}
+
+static WAL: OnceLock<Option<Mutex<WalState>>> = OnceLock::new();
+
+fn replay_path(dir: &Path, now_ms: u128) -> PathBuf {
+    dir.join(format!("{WAL_FILE_NAME}.{now_ms}{REPLAY_SUFFIX}"))
+}
+
+/// Opens the process-wide WAL under `dir` (first call only) and returns leftovers to replay.
+pub(super) fn init(dir: &Path) -> Vec<PathBuf> {
+    let mut leftovers = Vec::new();
+    WAL.get_or_init(|| {
+        let path = dir.join(WAL_FILE_NAME);
+        let has_tail = std::fs::metadata(&path).is_ok_and(|m| m.len() > 0);
+        if has_tail {
+            let now_ms = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or(0);
+            let target = replay_path(dir, now_ms);
+            if let Err(err) = std::fs::rename(&path, &target) {
+                tracing::warn!(path = %path.display(), "request log wal rename failed: {}", err);
+            }
+        }
+        leftovers = list_replay_files(dir);
+
+        match OpenOptions::new().create(true).append(true).open(&path) {
+            Ok(file) => Some(Mutex::new(WalState { file, pending: 0 })),
+            Err(err) => {
+                tracing::warn!(path = %path.display(), "request log wal unavailable: {}", err);
+                None
+            }
+        }
+    });
+    leftovers
+}
+
+fn list_replay_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name.starts_with(WAL_FILE_NAME) && name.ends_with(REPLAY_SUFFIX)
+                })
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn wal() -> Option<&'static Mutex<WalState>> {
+    WAL.get().and_then(Option::as_ref)
+}
+
+/// Records an entry that is about to be handed to the writer channel.
+pub(super) fn append(item: &RequestLogInsert) {
+    let Some(wal) = wal() else {
+        return;
+    };
+    let mut line = match serde_json::to_vec(item) {
+        Ok(v) => v,
+        Err(err) => {
+            tracing::warn!(trace_id = %item.trace_id, "request log wal encode failed: {}", err);
+            return;
+        }
+    };
+    line.push(b'\n');
+
+    let mut state = wal.lock_or_recover();
+    state.pending = state.pending.saturating_add(1);
+    // A single write keeps lines whole; no fsync, the goal is surviving a process crash.
+    if let Err(err) = state.file.write_all(&line) {
+        tracing::warn!(trace_id = %item.trace_id, "request log wal append failed: {}", err);
+    }
+}
+
+/// Marks `count` appended entries as consumed (written, or handed to another path).
+pub(super) fn ack(count: usize) {
+    let Some(wal) = wal() else {
+        return;
+    };
+    let mut state = wal.lock_or_recover();
+    state.pending = state.pending.saturating_sub(count as u64);
+    if state.pending == 0 {
+        if let Err(err) = state.file.set_len(0) {
+            tracing::warn!("request log wal truncate failed: {}", err);
+        }
+    }
+}
+
+/// Parses a replay file; a torn last line (crash mid-append) is skipped.
+pub(super) fn read_entries(path: &Path) -> std::io::Result<Vec<RequestLogInsert>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut items = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<RequestLogInsert>(&line) {
+            Ok(item) => items.push(item),
+            Err(err) => {
+                tracing::warn!(path = %path.display(), "request log wal entry skipped: {}", err)
+            }
+        }
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insert(trace_id: &str) -> RequestLogInsert {
+        RequestLogInsert {
+            trace_id: trace_id.to_string(),
+            cli_key: "claude".to_string(),
+            session_id: None,
+            method: "POST".to_string(),
+            path: "/v1/messages".to_string(),
+            query: None,
+            excluded_from_stats: false,
+            special_settings_json: None,
+            status: Some(200),
+            error_code: None,
+            duration_ms: 12,
+            ttfb_ms: Some(3),
+            attempts_json: "[]".to_string(),
+            input_tokens: Some(1),
+            output_tokens: Some(2),
+            total_tokens: Some(3),
+            cache_read_input_tokens: None,
+            cache_creation_input_tokens: None,
+            cache_creation_5m_input_tokens: None,
+            cache_creation_1h_input_tokens: None,
+            usage_json: None,
+            requested_model: Some("claude-sonnet".to_string()),
+            created_at_ms: 1_700_000_000_000,
+            created_at: 1_700_000_000,
+            request_bytes: Some(512),
+            response_bytes: Some(2048),
+            thinking_budget_tokens: Some(4096),
+        }
+    }
+
+    #[test]
+    fn read_entries_skips_torn_tail_and_lists_replay_files_in_order() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = replay_path(dir.path(), 2);
+        let mut body = Vec::new();
+        for trace_id in ["t1", "t2"] {
+            body.extend(serde_json::to_vec(&insert(trace_id)).expect("encode"));
+            body.push(b'\n');
+        }
+        body.extend_from_slice(b"{\"trace_id\":\"t3\",\"cli_");
+        std::fs::write(&path, body).expect("write replay file");
+        std::fs::write(replay_path(dir.path(), 1), b"").expect("write older replay file");
+        std::fs::write(dir.path().join(WAL_FILE_NAME), b"").expect("write live wal");
+
+        let items = read_entries(&path).expect("read entries");
+        let trace_ids: Vec<&str> = items.iter().map(|i| i.trace_id.as_str()).collect();
+        assert_eq!(trace_ids, vec!["t1", "t2"]);
+
+        assert_eq!(
+            list_replay_files(dir.path()),
+            vec![replay_path(dir.path(), 1), path]
+        );
+    }
+}
@@ -0,0 +1,333 @@
+//! Usage: Remote Linux hosts reached over SSH (`ssh_hosts` setting), analogous to WSL distros.
+//!
+//! Uses the system `ssh` client in batch mode, so keys/agents and `~/.ssh/config` aliases work as
+//! in a terminal but password prompts do not. The CLI config scripts are the ones run inside WSL.
+
+use crate::settings::{self, SshHost};
+use crate::shared::error::AppResult;
+use crate::wsl::{self, WslConfigureCliReport};
+use serde::Serialize;
+use std::process::Stdio;
+
+pub const MAX_SSH_HOSTS: usize = 20;
+const CONNECT_TIMEOUT_SECS: u32 = 10;
+const MAX_FIELD_LEN: usize = 512;
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SshHostCheckReport {
+    pub name: String,
+    pub ssh_ok: bool,
+    /// `uname -sm` of the remote host.
+    pub remote_system: Option<String>,
+    pub gateway_origin: String,
+    /// Whether the remote host got an HTTP 200 from the gateway `/health` endpoint.
+    pub gateway_reachable: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SshConfigureHostReport {
+    pub name: String,
+    pub ok: bool,
+    pub results: Vec<WslConfigureCliReport>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct SshConfigureReport {
+    pub ok: bool,
+    pub message: String,
+    pub hosts: Vec<SshConfigureHostReport>,
+}
+
+fn validate_field(label: &str, value: &str) -> AppResult<()> {
+    if value.len() > MAX_FIELD_LEN {
+        return Err(format!(
+            "SEC_INVALID_INPUT: ssh {label} must be at most {MAX_FIELD_LEN} characters"
+        )
+        .into());
+    }
+    // Values are passed as separate argv entries, but a leading '-' would still read as an option.
+    if value.starts_with('-') || value.chars().any(|c| c.is_control()) {
+        return Err(format!("SEC_INVALID_INPUT: invalid ssh {label}").into());
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_hosts(hosts: &[SshHost]) -> AppResult<()> {
+    if hosts.len() > MAX_SSH_HOSTS {
+        return Err(
+            format!("SEC_INVALID_INPUT: at most {MAX_SSH_HOSTS} ssh hosts are allowed").into(),
+        );
+    }
+    let mut names = std::collections::HashSet::new();
+    for host in hosts {
+        let name = host.name.trim();
+        if name.is_empty() {
+            return Err("SEC_INVALID_INPUT: ssh host name is required".into());
+        }
+        if !names.insert(name) {
+            return Err(format!("SEC_INVALID_INPUT: duplicate ssh host name={name}").into());
+        }
+        let destination = host.destination.trim();
+        if destination.is_empty() || destination.chars().any(char::is_whitespace) {
+            return Err(format!("SEC_INVALID_INPUT: invalid ssh destination for {name}").into());
+        }
+        validate_field("destination", destination)?;
+        validate_field("identity file", host.identity_file.trim())?;
+        let gateway_host = host.gateway_host.trim();
+        if gateway_host.contains(['/', '?', '#', '@']) || gateway_host.contains(char::is_whitespace)
+        {
+            return Err(format!("SEC_INVALID_INPUT: invalid gateway host for {name}").into());
+        }
+        validate_field("gateway host", gateway_host)?;
+        if host.port == Some(0) {
+            return Err(format!("SEC_INVALID_INPUT: invalid ssh port for {name}").into());
+        }
+    }
+    Ok(())
+}
+
+/// Gateway origin as seen from the remote host.
+pub fn gateway_origin(host: &SshHost, port: u16) -> String {
+    let gateway_host = match host.gateway_host.trim() {
+        "" => "127.0.0.1",
+        value => value,
+    };
+    format!(
+        "http://{}",
+        crate::gateway::listen::format_host_port(gateway_host, port)
+    )
+}
+
+fn run_remote_script(host: &SshHost, script: &str) -> AppResult<String> {
+    let mut cmd = wsl::hide_window_cmd("ssh");
+    cmd.args([
+        "-o",
+        "BatchMode=yes",
+        "-o",
+        &format!("ConnectTimeout={CONNECT_TIMEOUT_SECS}"),
+        "-o",
+        "ServerAliveInterval=5",
+        "-o",
+        "ServerAliveCountMax=2",
+    ]);
+    if let Some(port) = host.port {
+        cmd.args(["-p", &port.to_string()]);
+    }
+    let identity_file = host.identity_file.trim();
+    if !identity_file.is_empty() {
+        cmd.args(["-i", identity_file, "-o", "IdentitiesOnly=yes"]);
+    }
+    cmd.args(["--", host.destination.trim(), "bash", "-s"]);
+    cmd.stdin(Stdio::piped());
+    cmd.stdout(Stdio::piped());
+    cmd.stderr(Stdio::piped());
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("SSH_ERROR: failed to spawn ssh: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        use std::io::Write;
+        stdin
+            .write_all(script.as_bytes())
+            .map_err(|e| format!("SSH_ERROR: failed to write ssh stdin: {e}"))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("SSH_ERROR: failed to wait for ssh: {e}"))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if output.status.success() {
+        return Ok(stdout);
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    let msg = if !stderr.is_empty() {
+        stderr
+    } else {
+        stdout.trim().to_string()
+    };
+    Err(format!(
+        "SSH_ERROR: {}",
+        if msg.is_empty() {
+            "unknown error"
+        } else {
+            &msg
+        }
+    )
+    .into())
+}
+
+fn check_script(origin: &str) -> String {
+    let health_url = wsl::bash_single_quote(&format!("{origin}/health"));
+    format!(
+        r#"
+echo "AIO_SSH_SYSTEM=$(uname -sm 2>/dev/null)"
+health_url={health_url}
+code=000
+if command -v curl >/dev/null 2>&1; then
+  code="$(curl -s -m 5 -o /dev/null -w '%{{http_code}}' "$health_url" 2>/dev/null || true)"
+elif command -v wget >/dev/null 2>&1; then
+  wget -q -T 5 -O /dev/null "$health_url" 2>/dev/null && code=200
+else
+  code=none
+fi
+echo "AIO_SSH_GATEWAY=$code"
+"#
+    )
+}
+
+fn parse_check_output(stdout: &str) -> (Option<String>, Option<String>) {
+    let mut system = None;
+    let mut gateway = None;
+    for line in stdout.lines() {
+        if let Some(value) = line.trim().strip_prefix("AIO_SSH_SYSTEM=") {
+            system = Some(value.trim().to_string()).filter(|v| !v.is_empty());
+        } else if let Some(value) = line.trim().strip_prefix("AIO_SSH_GATEWAY=") {
+            gateway = Some(value.trim().to_string());
+        }
+    }
+    (system, gateway)
+}
+
+/// Connects to the host and probes the gateway from the remote side.
+pub fn check(host: &SshHost, port: u16) -> SshHostCheckReport {
+    let origin = gateway_origin(host, port);
+    let mut report = SshHostCheckReport {
+        name: host.name.clone(),
+        ssh_ok: false,
+        remote_system: None,
+        gateway_origin: origin.clone(),
+        gateway_reachable: false,
+        message: String::new(),
+    };
+
+    let stdout = match run_remote_script(host, &check_script(&origin)) {
+        Ok(v) => v,
+        Err(err) => {
+            report.message = err.to_string();
+            return report;
+        }
+    };
+    let (system, gateway) = parse_check_output(&stdout);
+    report.ssh_ok = true;
+    report.remote_system = system;
+    report.gateway_reachable = gateway.as_deref() == Some("200");
+    report.message = match gateway.as_deref() {
+        Some("200") => "ok".to_string(),
+        Some("none") => "remote host has neither curl nor wget".to_string(),
+        Some(code) if code != "000" => format!("gateway answered HTTP {code}"),
+        _ if host.gateway_host.trim().is_empty() => format!(
+            "gateway unreachable; forward it with `ssh -R {port}:127.0.0.1:{port}` or set a gateway host"
+        ),
+        _ => "gateway unreachable from the remote host".to_string(),
+    };
+    report
+}
+
+fn configure_host(host: &SshHost, proxy_origin: &str, api_key: &str) -> SshConfigureHostReport {
+    let mut results = Vec::new();
+    for (cli_key, enabled, script) in [
+        (
+            "claude",
+            host.targets.claude,
+            wsl::claude_configure_script as fn(&str, &str) -> String,
+        ),
+        ("codex", host.targets.codex, wsl::codex_configure_script),
+        ("gemini", host.targets.gemini, wsl::gemini_configure_script),
+    ] {
+        if !enabled {
+            continue;
+        }
+        let result = run_remote_script(host, &script(proxy_origin, api_key));
+        results.push(WslConfigureCliReport {
+            cli_key: cli_key.to_string(),
+            ok: result.is_ok(),
+            message: result.map_or_else(|err| err.to_string(), |_| "ok".to_string()),
+        });
+    }
+
+    SshConfigureHostReport {
+        name: host.name.clone(),
+        ok: results.iter().all(|r| r.ok),
+        results,
+    }
+}
+
+/// Points the selected CLIs on every host at the gateway listening on `port`.
+pub fn configure_clients(
+    app: &crate::AppHandle,
+    hosts: &[SshHost],
+    port: u16,
+) -> SshConfigureReport {
+    // Remote clients may come in through a non-loopback address, so they need the access token.
+    let api_key = settings::read(app)
+        .ok()
+        .as_ref()
+        .and_then(settings::gateway_access_token)
+        .unwrap_or("aio-coding-hub")
+        .to_string();
+
+    let host_reports: Vec<SshConfigureHostReport> = hosts
+        .iter()
+        .map(|host| configure_host(host, &gateway_origin(host, port), &api_key))
+        .collect();
+
+    let success_ops: usize = host_reports
+        .iter()
+        .map(|h| h.results.iter().filter(|r| r.ok).count())
+        .sum();
+    let error_ops: usize = host_reports
+        .iter()
+        .map(|h| h.results.iter().filter(|r| !r.ok).count())
+        .sum();
+    let message = if error_ops > 0 {
+        format!("已配置：{success_ops} 项；失败：{error_ops} 项（可展开查看每台主机的详细结果）")
+    } else {
+        format!("配置成功：{success_ops} 项")
+    };
+
+    SshConfigureReport {
+        ok: success_ops > 0,
+        message,
+        hosts: host_reports,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(destination: &str) -> SshHost {
+        SshHost {
+            name: "dev".to_string(),
+            destination: destination.to_string(),
+            ..SshHost::default()
+        }
+    }
+
+    #[test]
+    fn validates_destination_and_names() {
+        assert!(validate_hosts(&[host("me@devbox")]).is_ok());
+        assert!(validate_hosts(&[host("-oProxyCommand=x")]).is_err());
+        assert!(validate_hosts(&[host("me@dev box")]).is_err());
+        assert!(validate_hosts(&[host("a"), host("b")]).is_err());
+
+        let mut with_gateway = host("devbox");
+        with_gateway.gateway_host = "http://10.0.0.2".to_string();
+        assert!(validate_hosts(&[with_gateway]).is_err());
+    }
+
+    #[test]
+    fn gateway_origin_defaults_to_reverse_tunnel() {
+        let mut h = host("devbox");
+        assert_eq!(gateway_origin(&h, 37123), "http://127.0.0.1:37123");
+        h.gateway_host = "fd00::2".to_string();
+        assert_eq!(gateway_origin(&h, 37124), "http://[fd00::2]:37124");
+
+        let (system, gateway) =
+            parse_check_output("AIO_SSH_SYSTEM=Linux x86_64\nAIO_SSH_GATEWAY=200\n");
+        assert_eq!(system.as_deref(), Some("Linux x86_64"));
+        assert_eq!(gateway.as_deref(), Some("200"));
+    }
+}
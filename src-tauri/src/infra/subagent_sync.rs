@@ -0,0 +1,354 @@
+//! Usage: Sync Claude Code subagent definitions into `~/.claude/agents/*.md` (infra adapter).
+//!
+//! Only files listed in the manifest are ever rewritten or removed; a user file that already
+//! existed under the same name is backed up first and restored once the subagent is unmanaged.
+
+use crate::app_paths;
+use crate::shared::fs::{read_optional_file, write_file_atomic, write_file_atomic_if_changed};
+use crate::shared::time::now_unix_seconds;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+const MANIFEST_SCHEMA_VERSION: u32 = 1;
+const MANAGED_BY: &str = "aio-coding-hub";
+
+#[derive(Debug, Clone)]
+pub struct ManagedSubagent {
+    pub name: String,
+    pub description: String,
+    pub prompt: String,
+    pub tools: Vec<String>,
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SubagentSyncFileEntry {
+    file_name: String,
+    existed: bool,
+    backup_rel: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SubagentSyncManifest {
+    schema_version: u32,
+    managed_by: String,
+    created_at: i64,
+    updated_at: i64,
+    files: Vec<SubagentSyncFileEntry>,
+}
+
+fn home_dir<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> crate::shared::error::AppResult<PathBuf> {
+    Ok(app
+        .path()
+        .home_dir()
+        .map_err(|e| format!("failed to resolve home dir: {e}"))?)
+}
+
+fn agents_dir<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> crate::shared::error::AppResult<PathBuf> {
+    Ok(home_dir(app)?.join(".claude").join("agents"))
+}
+
+fn sync_root_dir<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> crate::shared::error::AppResult<PathBuf> {
+    Ok(app_paths::app_data_dir(app)?
+        .join("subagent-sync")
+        .join("claude"))
+}
+
+fn sync_files_dir(root: &Path) -> PathBuf {
+    root.join("files")
+}
+
+fn sync_manifest_path(root: &Path) -> PathBuf {
+    root.join("manifest.json")
+}
+
+pub(crate) fn agent_file_name(name: &str) -> String {
+    format!("{name}.md")
+}
+
+/// Renders the agent file: YAML frontmatter (`name`, `description`, optional `tools`/`model`)
+/// followed by the system prompt.
+pub(crate) fn render_agent_markdown(agent: &ManagedSubagent) -> Vec<u8> {
+    // JSON string literals are valid YAML double-quoted scalars.
+    let description =
+        serde_json::to_string(&agent.description).unwrap_or_else(|_| "\"\"".to_string());
+
+    let mut out = String::new();
+    out.push_str("---\n");
+    out.push_str(&format!("name: {}\n", agent.name));
+    out.push_str(&format!("description: {description}\n"));
+    if !agent.tools.is_empty() {
+        out.push_str(&format!("tools: {}\n", agent.tools.join(", ")));
+    }
+    if let Some(model) = agent.model.as_deref() {
+        out.push_str(&format!("model: {model}\n"));
+    }
+    out.push_str("---\n\n");
+    out.push_str(agent.prompt.trim_matches('\u{feff}').trim());
+    out.push('\n');
+    out.into_bytes()
+}
+
+/// Agent files currently written by aio-coding-hub.
+pub(crate) fn managed_file_names<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> crate::shared::error::AppResult<Vec<String>> {
+    Ok(read_manifest(app)?
+        .map(|m| m.files.into_iter().map(|e| e.file_name).collect())
+        .unwrap_or_default())
+}
+
+fn read_manifest<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> crate::shared::error::AppResult<Option<SubagentSyncManifest>> {
+    let path = sync_manifest_path(&sync_root_dir(app)?);
+    let Some(content) = read_optional_file(&path)? else {
+        return Ok(None);
+    };
+
+    let manifest: SubagentSyncManifest = serde_json::from_slice(&content)
+        .map_err(|e| format!("failed to parse subagent manifest.json: {e}"))?;
+
+    if manifest.managed_by != MANAGED_BY {
+        return Err(format!(
+            "subagent manifest managed_by mismatch: expected {MANAGED_BY}, got {}",
+            manifest.managed_by
+        )
+        .into());
+    }
+
+    Ok(Some(manifest))
+}
+
+fn write_manifest<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    manifest: &SubagentSyncManifest,
+) -> crate::shared::error::AppResult<()> {
+    let path = sync_manifest_path(&sync_root_dir(app)?);
+    let bytes = serde_json::to_vec_pretty(manifest)
+        .map_err(|e| format!("failed to serialize subagent manifest.json: {e}"))?;
+    write_file_atomic(&path, &bytes)
+}
+
+fn restore_entry(
+    agents_dir: &Path,
+    files_dir: &Path,
+    entry: &SubagentSyncFileEntry,
+) -> crate::shared::error::AppResult<()> {
+    let target_path = agents_dir.join(&entry.file_name);
+
+    if entry.existed {
+        let backup = entry
+            .backup_rel
+            .as_ref()
+            .map(|rel| files_dir.join(rel))
+            .filter(|path| path.exists());
+        match backup {
+            Some(backup_path) => {
+                let bytes = std::fs::read(&backup_path)
+                    .map_err(|e| format!("failed to read backup {}: {e}", backup_path.display()))?;
+                write_file_atomic(&target_path, &bytes)?;
+            }
+            None => {
+                tracing::warn!(
+                    file = %entry.file_name,
+                    "subagent sync: backup not found, keeping current file"
+                );
+            }
+        }
+        return Ok(());
+    }
+
+    if target_path.exists() {
+        std::fs::remove_file(&target_path)
+            .map_err(|e| format!("failed to remove {}: {e}", target_path.display()))?;
+    }
+    Ok(())
+}
+
+/// Replace the agent files previously written by aio-coding-hub with `agents`.
+pub fn sync_subagents<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    agents: &[ManagedSubagent],
+) -> crate::shared::error::AppResult<()> {
+    let agents_dir = agents_dir(app)?;
+    let files_dir = sync_files_dir(&sync_root_dir(app)?);
+    let now = now_unix_seconds();
+
+    let desired: BTreeMap<String, Vec<u8>> = agents
+        .iter()
+        .map(|agent| (agent_file_name(&agent.name), render_agent_markdown(agent)))
+        .collect();
+
+    let mut manifest = read_manifest(app)?.unwrap_or_else(|| SubagentSyncManifest {
+        schema_version: MANIFEST_SCHEMA_VERSION,
+        managed_by: MANAGED_BY.to_string(),
+        created_at: now,
+        updated_at: now,
+        files: Vec::new(),
+    });
+
+    let mut kept = Vec::new();
+    for entry in std::mem::take(&mut manifest.files) {
+        if desired.contains_key(&entry.file_name) {
+            kept.push(entry);
+        } else {
+            restore_entry(&agents_dir, &files_dir, &entry)?;
+        }
+    }
+    manifest.files = kept;
+
+    for file_name in desired.keys() {
+        if manifest.files.iter().any(|e| &e.file_name == file_name) {
+            continue;
+        }
+        let existing = read_optional_file(&agents_dir.join(file_name))?;
+        let backup_rel = match existing.as_ref() {
+            Some(bytes) => {
+                write_file_atomic(&files_dir.join(file_name), bytes)?;
+                Some(file_name.clone())
+            }
+            None => None,
+        };
+        manifest.files.push(SubagentSyncFileEntry {
+            file_name: file_name.clone(),
+            existed: existing.is_some(),
+            backup_rel,
+        });
+    }
+    manifest.files.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+    manifest.updated_at = now;
+
+    // Persist backups in the manifest before touching the agent files.
+    write_manifest(app, &manifest)?;
+
+    for (file_name, bytes) in &desired {
+        write_file_atomic_if_changed(&agents_dir.join(file_name), bytes)?;
+    }
+
+    Ok(())
+}
+
+/// Snapshot of the agents dir and sync manifest, restored when a later step of a sync fails.
+pub struct SubagentSyncBackup {
+    agents_dir: PathBuf,
+    files: BTreeMap<String, Vec<u8>>,
+    manifest_path: PathBuf,
+    manifest: Option<Vec<u8>>,
+}
+
+fn read_agent_files(dir: &Path) -> crate::shared::error::AppResult<BTreeMap<String, Vec<u8>>> {
+    let mut out = BTreeMap::new();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(out);
+    };
+    for entry in entries {
+        let entry =
+            entry.map_err(|e| format!("failed to read dir entry {}: {e}", dir.display()))?;
+        let path = entry.path();
+        if !path.is_file() || path.extension().and_then(|v| v.to_str()) != Some("md") {
+            continue;
+        }
+        let Some(file_name) = path.file_name().and_then(|v| v.to_str()) else {
+            continue;
+        };
+        let bytes =
+            std::fs::read(&path).map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+        out.insert(file_name.to_string(), bytes);
+    }
+    Ok(out)
+}
+
+impl SubagentSyncBackup {
+    pub fn capture<R: tauri::Runtime>(
+        app: &tauri::AppHandle<R>,
+    ) -> crate::shared::error::AppResult<Self> {
+        let agents_dir = agents_dir(app)?;
+        let manifest_path = sync_manifest_path(&sync_root_dir(app)?);
+        Ok(Self {
+            files: read_agent_files(&agents_dir)?,
+            manifest: read_optional_file(&manifest_path)?,
+            agents_dir,
+            manifest_path,
+        })
+    }
+
+    pub fn restore(self) {
+        let current = read_agent_files(&self.agents_dir).unwrap_or_default();
+        for file_name in current.keys() {
+            if self.files.contains_key(file_name) {
+                continue;
+            }
+            let path = self.agents_dir.join(file_name);
+            if let Err(err) = std::fs::remove_file(&path) {
+                tracing::warn!(path = %path.display(), "subagent sync restore failed: {}", err);
+            }
+        }
+        for (file_name, bytes) in &self.files {
+            let path = self.agents_dir.join(file_name);
+            if let Err(err) = write_file_atomic_if_changed(&path, bytes) {
+                tracing::warn!(path = %path.display(), "subagent sync restore failed: {}", err);
+            }
+        }
+
+        let result = match self.manifest {
+            Some(bytes) => write_file_atomic(&self.manifest_path, &bytes),
+            None if self.manifest_path.exists() => std::fs::remove_file(&self.manifest_path)
+                .map_err(|e| {
+                    format!("failed to remove {}: {e}", self.manifest_path.display()).into()
+                }),
+            None => Ok(()),
+        };
+        if let Err(err) = result {
+            tracing::warn!(
+                path = %self.manifest_path.display(),
+                "subagent sync restore failed: {}",
+                err
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_agent_markdown_writes_frontmatter_and_prompt() {
+        let agent = ManagedSubagent {
+            name: "code-reviewer".to_string(),
+            description: "Reviews diffs: \"strict\"".to_string(),
+            prompt: "\n You are a reviewer.\n\n".to_string(),
+            tools: vec!["Read".to_string(), "Grep".to_string()],
+            model: Some("sonnet".to_string()),
+        };
+        let rendered = String::from_utf8(render_agent_markdown(&agent)).unwrap();
+        assert_eq!(
+            rendered,
+            "---\nname: code-reviewer\ndescription: \"Reviews diffs: \\\"strict\\\"\"\ntools: Read, Grep\nmodel: sonnet\n---\n\nYou are a reviewer.\n"
+        );
+    }
+
+    #[test]
+    fn render_agent_markdown_omits_unset_tools_and_model() {
+        let agent = ManagedSubagent {
+            name: "helper".to_string(),
+            description: "Helps".to_string(),
+            prompt: "Help.".to_string(),
+            tools: Vec::new(),
+            model: None,
+        };
+        let rendered = String::from_utf8(render_agent_markdown(&agent)).unwrap();
+        assert!(!rendered.contains("tools:"));
+        assert!(!rendered.contains("model:"));
+        assert_eq!(agent_file_name(&agent.name), "helper.md");
+    }
+}
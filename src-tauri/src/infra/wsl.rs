@@ -9,13 +9,20 @@ use serde::Serialize;
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
 
-#[derive(Debug, Clone, Serialize)]
+mod diagnostics;
+pub use diagnostics::{
+    diagnose, ensure_gateway_firewall_rule, WslDiagnoseInput, WslDiagnosticFinding,
+    WslDiagnosticsReport, WslFindingSeverity, WslNetworkingMode, WslRepairAction, WslRepairOutcome,
+    WslRepairReport,
+};
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct WslDetection {
     pub detected: bool,
     pub distros: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct WslDistroConfigStatus {
     pub distro: String,
     pub claude: bool,
@@ -29,21 +36,21 @@ pub struct WslDistroConfigStatus {
     pub gemini_prompt: bool,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct WslConfigureCliReport {
     pub cli_key: String,
     pub ok: bool,
     pub message: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct WslConfigureDistroReport {
     pub distro: String,
     pub ok: bool,
     pub results: Vec<WslConfigureCliReport>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, specta::Type)]
 pub struct WslConfigureReport {
     pub ok: bool,
     pub message: String,
@@ -51,7 +58,7 @@ pub struct WslConfigureReport {
 }
 
 #[cfg(windows)]
-fn hide_window_cmd(program: &str) -> Command {
+pub(crate) fn hide_window_cmd(program: &str) -> Command {
     let mut cmd = Command::new(program);
     use std::os::windows::process::CommandExt;
     const CREATE_NO_WINDOW: u32 = 0x08000000;
@@ -60,7 +67,7 @@ fn hide_window_cmd(program: &str) -> Command {
 }
 
 #[cfg(not(windows))]
-fn hide_window_cmd(program: &str) -> Command {
+pub(crate) fn hide_window_cmd(program: &str) -> Command {
     Command::new(program)
 }
 
@@ -79,7 +86,7 @@ fn decode_utf16_le(mut bytes: &[u8]) -> String {
     String::from_utf16_lossy(&u16s)
 }
 
-fn bash_single_quote(value: &str) -> String {
+pub(crate) fn bash_single_quote(value: &str) -> String {
     if value.is_empty() {
         return "''".to_string();
     }
@@ -490,7 +497,7 @@ struct WslMcpManifest {
 }
 
 fn wsl_mcp_manifest_path(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     distro: &str,
     cli_key: &str,
 ) -> AppResult<std::path::PathBuf> {
@@ -501,7 +508,7 @@ fn wsl_mcp_manifest_path(
     Ok(dir.join("manifest.json"))
 }
 
-fn read_wsl_mcp_manifest(app: &tauri::AppHandle, distro: &str, cli_key: &str) -> Vec<String> {
+fn read_wsl_mcp_manifest(app: &crate::AppHandle, distro: &str, cli_key: &str) -> Vec<String> {
     let path = match wsl_mcp_manifest_path(app, distro, cli_key) {
         Ok(p) => p,
         Err(_) => return Vec::new(),
@@ -517,7 +524,7 @@ fn read_wsl_mcp_manifest(app: &tauri::AppHandle, distro: &str, cli_key: &str) ->
 }
 
 fn write_wsl_mcp_manifest(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     distro: &str,
     cli_key: &str,
     managed_keys: &[String],
@@ -562,7 +569,7 @@ struct WslPromptManifest {
 }
 
 fn wsl_prompt_sync_root_dir(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     distro: &str,
     cli_key: &str,
 ) -> AppResult<std::path::PathBuf> {
@@ -577,7 +584,7 @@ fn wsl_prompt_files_dir(root: &std::path::Path) -> std::path::PathBuf {
 }
 
 fn wsl_prompt_manifest_path(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     distro: &str,
     cli_key: &str,
 ) -> AppResult<std::path::PathBuf> {
@@ -585,7 +592,7 @@ fn wsl_prompt_manifest_path(
 }
 
 fn read_wsl_prompt_manifest(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     distro: &str,
     cli_key: &str,
 ) -> AppResult<Option<WslPromptManifest>> {
@@ -611,7 +618,7 @@ fn read_wsl_prompt_manifest(
 }
 
 fn write_wsl_prompt_manifest(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     distro: &str,
     cli_key: &str,
     manifest: &WslPromptManifest,
@@ -824,7 +831,7 @@ esac
 
 /// Sync a prompt file for a single CLI to a WSL distro.
 fn backup_wsl_prompt_for_enable(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     distro: &str,
     cli_key: &str,
     target_path: &str,
@@ -869,7 +876,7 @@ fn backup_wsl_prompt_for_enable(
 }
 
 fn restore_wsl_prompt_from_manifest(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     distro: &str,
     manifest: &WslPromptManifest,
 ) -> AppResult<()> {
@@ -893,7 +900,7 @@ fn restore_wsl_prompt_from_manifest(
 }
 
 fn sync_wsl_prompt_for_cli(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     distro: &str,
     cli_key: &str,
     content: Option<&str>,
@@ -991,12 +998,21 @@ LIMIT 1
     })
 }
 
-fn configure_wsl_claude(distro: &str, proxy_origin: &str) -> crate::shared::error::AppResult<()> {
+fn configure_wsl_claude(
+    distro: &str,
+    proxy_origin: &str,
+    api_key: &str,
+) -> crate::shared::error::AppResult<()> {
+    run_wsl_bash_script(distro, &claude_configure_script(proxy_origin, api_key))
+}
+
+/// Bash script pointing claude at the gateway; also run on SSH hosts by `ssh_clients`.
+pub(crate) fn claude_configure_script(proxy_origin: &str, api_key: &str) -> String {
     let base_url = format!("{proxy_origin}/claude");
     let base_url = bash_single_quote(&base_url);
-    let auth_token = bash_single_quote("aio-coding-hub");
+    let auth_token = bash_single_quote(api_key);
 
-    let script = format!(
+    format!(
         r#"
 set -euo pipefail
 
@@ -1173,18 +1189,25 @@ PY
   trap - EXIT
 fi
 "#
-    );
+    )
+}
 
-    run_wsl_bash_script(distro, &script)
+fn configure_wsl_codex(
+    distro: &str,
+    proxy_origin: &str,
+    api_key: &str,
+) -> crate::shared::error::AppResult<()> {
+    run_wsl_bash_script(distro, &codex_configure_script(proxy_origin, api_key))
 }
 
-fn configure_wsl_codex(distro: &str, proxy_origin: &str) -> crate::shared::error::AppResult<()> {
+/// Codex counterpart of [`claude_configure_script`].
+pub(crate) fn codex_configure_script(proxy_origin: &str, api_key: &str) -> String {
     let base_url = format!("{proxy_origin}/v1");
     let base_url = bash_single_quote(&base_url);
     let provider_key = bash_single_quote("aio");
-    let api_key = bash_single_quote("aio-coding-hub");
+    let api_key = bash_single_quote(api_key);
 
-    let script = format!(
+    format!(
         r#"
 set -euo pipefail
 
@@ -1435,17 +1458,24 @@ fi
 echo "Failed to write $config_path" >&2
 exit 1
 "#
-    );
+    )
+}
 
-    run_wsl_bash_script(distro, &script)
+fn configure_wsl_gemini(
+    distro: &str,
+    proxy_origin: &str,
+    api_key: &str,
+) -> crate::shared::error::AppResult<()> {
+    run_wsl_bash_script(distro, &gemini_configure_script(proxy_origin, api_key))
 }
 
-fn configure_wsl_gemini(distro: &str, proxy_origin: &str) -> crate::shared::error::AppResult<()> {
+/// Gemini counterpart of [`claude_configure_script`].
+pub(crate) fn gemini_configure_script(proxy_origin: &str, api_key: &str) -> String {
     let base_url = format!("{proxy_origin}/gemini");
     let base_url = bash_single_quote(&base_url);
-    let api_key = bash_single_quote("aio-coding-hub");
+    let api_key = bash_single_quote(api_key);
 
-    let script = format!(
+    format!(
         r#"
 set -euo pipefail
 
@@ -1597,9 +1627,7 @@ fi
 mv -f "$tmp_path" "$env_path"
 trap - EXIT
 "#
-    );
-
-    run_wsl_bash_script(distro, &script)
+    )
 }
 
 pub fn get_config_status(distros: &[String]) -> Vec<WslDistroConfigStatus> {
@@ -1791,7 +1819,7 @@ fn wsl_target_enabled(targets: &settings::WslTargetCli, cli_key: &str) -> bool {
 }
 
 pub fn configure_clients(
-    app: &tauri::AppHandle,
+    app: &crate::AppHandle,
     distros: &[String],
     targets: &settings::WslTargetCli,
     proxy_origin: &str,
@@ -1806,6 +1834,14 @@ pub fn configure_clients(
         };
     }
 
+    // WSL clients reach the gateway over a non-loopback address, so they need the access token.
+    let api_key = settings::read(app)
+        .ok()
+        .as_ref()
+        .and_then(settings::gateway_access_token)
+        .unwrap_or("aio-coding-hub")
+        .to_string();
+
     let mut distro_reports = Vec::new();
     let mut success_ops = 0usize;
     let mut error_ops = 0usize;
@@ -1815,7 +1851,7 @@ pub fn configure_clients(
 
         // ── Auth configuration ──
         if targets.claude {
-            match configure_wsl_claude(distro, proxy_origin) {
+            match configure_wsl_claude(distro, proxy_origin, &api_key) {
                 Ok(()) => results.push(WslConfigureCliReport {
                     cli_key: "claude".to_string(),
                     ok: true,
@@ -1830,7 +1866,7 @@ pub fn configure_clients(
         }
 
         if targets.codex {
-            match configure_wsl_codex(distro, proxy_origin) {
+            match configure_wsl_codex(distro, proxy_origin, &api_key) {
                 Ok(()) => results.push(WslConfigureCliReport {
                     cli_key: "codex".to_string(),
                     ok: true,
@@ -1845,7 +1881,7 @@ pub fn configure_clients(
         }
 
         if targets.gemini {
-            match configure_wsl_gemini(distro, proxy_origin) {
+            match configure_wsl_gemini(distro, proxy_origin, &api_key) {
                 Ok(()) => results.push(WslConfigureCliReport {
                     cli_key: "gemini".to_string(),
                     ok: true,
@@ -0,0 +1,551 @@
+//! Usage: WSL diagnostics (`wsl_diagnose`) and best-effort automated repair (`wsl_repair`).
+//!
+//! Checks run from the Windows side, except the reachability probe: it executes inside each
+//! distro (curl, falling back to bash `/dev/tcp`) against the gateway's `/health` route.
+//! Only findings with a `repair` action are touched by `wsl_repair`; the rest need the user.
+
+use super::{
+    bash_single_quote, decode_utf16_le, get_config_status, hide_window_cmd,
+    run_wsl_bash_script_capture, wsl_target_enabled,
+};
+use crate::settings;
+use crate::shared::error::AppResult;
+use serde::Serialize;
+
+const FIREWALL_RULE_NAME: &str = "AIO Coding Hub Gateway";
+const PROBE_TIMEOUT_SECS: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum WslNetworkingMode {
+    Nat,
+    Mirrored,
+    /// `virtioproxy` / `bridged` or an unrecognized value.
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum WslFindingSeverity {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum WslRepairAction {
+    /// Add (or refresh) the inbound Windows Firewall rule for the gateway port.
+    AddFirewallRule,
+    /// Rewrite the CLI client configs inside the distros (same as `wsl_configure_clients`).
+    ConfigureClients,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct WslDiagnosticFinding {
+    /// `distro_present` | `networking_mode` | `listen_mode` | `host_reachable` |
+    /// `firewall_rule` | `client_config`.
+    pub check: String,
+    pub distro: Option<String>,
+    pub severity: WslFindingSeverity,
+    pub message: String,
+    pub repair: Option<WslRepairAction>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct WslDiagnosticsReport {
+    pub detected: bool,
+    pub networking_mode: WslNetworkingMode,
+    /// Origin the distros are expected to use, e.g. `http://172.20.0.1:37123`.
+    pub gateway_origin: Option<String>,
+    pub findings: Vec<WslDiagnosticFinding>,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct WslRepairOutcome {
+    pub action: WslRepairAction,
+    pub ok: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, specta::Type)]
+pub struct WslRepairReport {
+    pub outcomes: Vec<WslRepairOutcome>,
+    /// Diagnostics re-run after the repairs.
+    pub diagnostics: WslDiagnosticsReport,
+}
+
+pub struct WslDiagnoseInput<'a> {
+    pub listen_mode: settings::GatewayListenMode,
+    /// Host the distros should use; `None` when it could not be resolved.
+    pub host: Option<&'a str>,
+    /// Gateway port; `None` when the gateway is not running.
+    pub port: Option<u16>,
+    pub targets: &'a settings::WslTargetCli,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DistroInfo {
+    name: String,
+    state: String,
+    version: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ProbeResult {
+    Reachable(String),
+    Unreachable(String),
+}
+
+impl WslDiagnosticsReport {
+    /// Distinct repair actions suggested by the findings, in first-seen order.
+    pub fn repair_actions(&self) -> Vec<WslRepairAction> {
+        let mut out = Vec::new();
+        for action in self.findings.iter().filter_map(|f| f.repair) {
+            if !out.contains(&action) {
+                out.push(action);
+            }
+        }
+        out
+    }
+}
+
+fn finding(
+    check: &str,
+    distro: Option<&str>,
+    severity: WslFindingSeverity,
+    message: impl Into<String>,
+    repair: Option<WslRepairAction>,
+) -> WslDiagnosticFinding {
+    WslDiagnosticFinding {
+        check: check.to_string(),
+        distro: distro.map(str::to_string),
+        severity,
+        message: message.into(),
+        repair,
+    }
+}
+
+/// Parses `wsl --list --verbose` (header row and the default-distro `*` are skipped).
+fn parse_list_verbose(text: &str) -> Vec<DistroInfo> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim_matches(&['\0', '\r'][..]).trim();
+            let line = line.strip_prefix('*').unwrap_or(line).trim();
+            let mut tokens: Vec<&str> = line.split_whitespace().collect();
+            let version = tokens.pop()?.parse::<u8>().ok()?;
+            let state = tokens.pop()?.to_string();
+            if tokens.is_empty() {
+                return None;
+            }
+            Some(DistroInfo {
+                name: tokens.join(" "),
+                state,
+                version,
+            })
+        })
+        .collect()
+}
+
+/// `networkingMode` from the `[wsl2]` section of `.wslconfig` (absent = NAT).
+fn parse_networking_mode(wslconfig: &str) -> WslNetworkingMode {
+    let mut in_wsl2 = false;
+    for line in wslconfig.lines() {
+        let line = line.trim();
+        if line.starts_with('#') || line.starts_with(';') || line.is_empty() {
+            continue;
+        }
+        if line.starts_with('[') {
+            in_wsl2 = line.eq_ignore_ascii_case("[wsl2]");
+            continue;
+        }
+        if !in_wsl2 {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        if key.trim().eq_ignore_ascii_case("networkingMode") {
+            return match value.trim().trim_matches('"').to_ascii_lowercase().as_str() {
+                "nat" | "" => WslNetworkingMode::Nat,
+                "mirrored" => WslNetworkingMode::Mirrored,
+                _ => WslNetworkingMode::Other,
+            };
+        }
+    }
+    WslNetworkingMode::Nat
+}
+
+fn probe_script(host: &str, port: u16) -> String {
+    let url = format!(
+        "http://{}/health",
+        crate::gateway::listen::format_host_port(host, port)
+    );
+    format!(
+        r#"
+url={url}
+host={host}
+port={port}
+if command -v curl >/dev/null 2>&1; then
+  code="$(curl -s -o /dev/null -w '%{{http_code}}' --max-time {timeout} "$url" 2>/dev/null || true)"
+  echo "curl:${{code:-000}}"
+elif timeout {timeout} bash -c "exec 3<>/dev/tcp/$host/$port" 2>/dev/null; then
+  echo "tcp:ok"
+else
+  echo "tcp:fail"
+fi
+"#,
+        url = bash_single_quote(&url),
+        host = bash_single_quote(host),
+        timeout = PROBE_TIMEOUT_SECS,
+    )
+}
+
+/// Any HTTP status (even 401 from the access-token guard) proves the port is reachable.
+fn parse_probe_output(stdout: &str) -> ProbeResult {
+    let line = stdout
+        .lines()
+        .map(str::trim)
+        .rfind(|l| !l.is_empty())
+        .unwrap_or("");
+    match line.split_once(':') {
+        Some(("curl", code)) if code != "000" && !code.is_empty() => {
+            ProbeResult::Reachable(format!("HTTP {code}"))
+        }
+        Some(("curl", _)) => ProbeResult::Unreachable("curl could not connect".to_string()),
+        Some(("tcp", "ok")) => ProbeResult::Reachable("TCP connect ok".to_string()),
+        Some(("tcp", _)) => ProbeResult::Unreachable("TCP connect failed".to_string()),
+        _ => ProbeResult::Unreachable(format!("unexpected probe output: {line}")),
+    }
+}
+
+fn list_distros_verbose() -> Vec<DistroInfo> {
+    let Ok(output) = hide_window_cmd("wsl")
+        .args(["--list", "--verbose"])
+        .output()
+    else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+    parse_list_verbose(&decode_utf16_le(&output.stdout))
+        .into_iter()
+        .filter(|d| !d.name.starts_with("Windows"))
+        .collect()
+}
+
+fn read_networking_mode() -> WslNetworkingMode {
+    let Some(profile) = std::env::var_os("USERPROFILE") else {
+        return WslNetworkingMode::Nat;
+    };
+    let path = std::path::PathBuf::from(profile).join(".wslconfig");
+    match std::fs::read(&path) {
+        Ok(bytes) => parse_networking_mode(&String::from_utf8_lossy(&bytes)),
+        Err(_) => WslNetworkingMode::Nat,
+    }
+}
+
+/// `Some(true)` = rule exists for `port`; `Some(false)` = missing/stale; `None` = netsh failed.
+fn firewall_rule_covers_port(port: u16) -> Option<bool> {
+    let output = hide_window_cmd("netsh")
+        .args([
+            "advfirewall",
+            "firewall",
+            "show",
+            "rule",
+            &format!("name={FIREWALL_RULE_NAME}"),
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return Some(false);
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    Some(
+        text.split(|c: char| !c.is_ascii_digit())
+            .any(|token| token == port.to_string()),
+    )
+}
+
+/// Replaces the gateway's inbound firewall rule (needs an elevated process on most setups).
+pub fn ensure_gateway_firewall_rule(port: u16) -> AppResult<()> {
+    if !cfg!(windows) {
+        return Err("WSL_ERROR: firewall rules are only managed on Windows".into());
+    }
+    let name = format!("name={FIREWALL_RULE_NAME}");
+    let _ = hide_window_cmd("netsh")
+        .args(["advfirewall", "firewall", "delete", "rule", &name])
+        .output();
+    let output = hide_window_cmd("netsh")
+        .args([
+            "advfirewall",
+            "firewall",
+            "add",
+            "rule",
+            &name,
+            "dir=in",
+            "action=allow",
+            "protocol=TCP",
+            &format!("localport={port}"),
+        ])
+        .output()
+        .map_err(|e| format!("WSL_ERROR: failed to run netsh: {e}"))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Err(
+        format!("WSL_ERROR: netsh failed (administrator rights are usually required): {stdout}")
+            .into(),
+    )
+}
+
+pub fn diagnose(input: &WslDiagnoseInput<'_>) -> WslDiagnosticsReport {
+    let mut report = WslDiagnosticsReport {
+        detected: false,
+        networking_mode: WslNetworkingMode::Nat,
+        gateway_origin: None,
+        findings: Vec::new(),
+    };
+
+    if !cfg!(windows) {
+        report.findings.push(finding(
+            "distro_present",
+            None,
+            WslFindingSeverity::Error,
+            "WSL is only available on Windows",
+            None,
+        ));
+        return report;
+    }
+
+    let distros = list_distros_verbose();
+    report.detected = !distros.is_empty();
+    if distros.is_empty() {
+        report.findings.push(finding(
+            "distro_present",
+            None,
+            WslFindingSeverity::Error,
+            "no WSL distro found (install one with `wsl --install`)",
+            None,
+        ));
+        return report;
+    }
+    for distro in &distros {
+        report.findings.push(finding(
+            "distro_present",
+            Some(&distro.name),
+            WslFindingSeverity::Ok,
+            format!("WSL {} ({})", distro.version, distro.state),
+            None,
+        ));
+    }
+
+    report.networking_mode = read_networking_mode();
+    let mirrored = report.networking_mode == WslNetworkingMode::Mirrored;
+    report.findings.push(finding(
+        "networking_mode",
+        None,
+        WslFindingSeverity::Ok,
+        match report.networking_mode {
+            WslNetworkingMode::Nat => "NAT: distros reach Windows via the vEthernet (WSL) address",
+            WslNetworkingMode::Mirrored => {
+                "mirrored: distros share the Windows network stack (127.0.0.1 works)"
+            }
+            WslNetworkingMode::Other => "non-default networkingMode in .wslconfig",
+        },
+        None,
+    ));
+
+    if input.listen_mode == settings::GatewayListenMode::Localhost {
+        let (severity, message) = if mirrored {
+            (
+                WslFindingSeverity::Ok,
+                "gateway listens on 127.0.0.1; reachable through mirrored networking",
+            )
+        } else {
+            (
+                WslFindingSeverity::Error,
+                "gateway listens on 127.0.0.1 only; switch the listen mode to WSL auto / LAN / custom",
+            )
+        };
+        report
+            .findings
+            .push(finding("listen_mode", None, severity, message, None));
+    }
+
+    let (Some(host), Some(port)) = (input.host, input.port) else {
+        report.findings.push(finding(
+            "host_reachable",
+            None,
+            WslFindingSeverity::Error,
+            "gateway is not running or its host could not be resolved",
+            None,
+        ));
+        return report;
+    };
+    report.gateway_origin = Some(format!(
+        "http://{}",
+        crate::gateway::listen::format_host_port(host, port)
+    ));
+
+    let is_loopback = host == "127.0.0.1" || host.eq_ignore_ascii_case("localhost");
+    let mut any_unreachable = false;
+    let script = probe_script(host, port);
+    for distro in &distros {
+        let result = match run_wsl_bash_script_capture(&distro.name, &script) {
+            Ok(stdout) => parse_probe_output(&stdout),
+            Err(err) => ProbeResult::Unreachable(err.to_string()),
+        };
+        let (severity, message) = match result {
+            ProbeResult::Reachable(detail) => (WslFindingSeverity::Ok, detail),
+            ProbeResult::Unreachable(detail) => {
+                any_unreachable = true;
+                (WslFindingSeverity::Error, detail)
+            }
+        };
+        report.findings.push(finding(
+            "host_reachable",
+            Some(&distro.name),
+            severity,
+            message,
+            None,
+        ));
+    }
+
+    if !is_loopback {
+        match firewall_rule_covers_port(port) {
+            Some(true) => report.findings.push(finding(
+                "firewall_rule",
+                None,
+                WslFindingSeverity::Ok,
+                format!("inbound rule \"{FIREWALL_RULE_NAME}\" allows TCP {port}"),
+                None,
+            )),
+            Some(false) => report.findings.push(finding(
+                "firewall_rule",
+                None,
+                if any_unreachable {
+                    WslFindingSeverity::Error
+                } else {
+                    WslFindingSeverity::Warning
+                },
+                format!("no inbound firewall rule allows TCP {port}"),
+                Some(WslRepairAction::AddFirewallRule),
+            )),
+            None => report.findings.push(finding(
+                "firewall_rule",
+                None,
+                WslFindingSeverity::Warning,
+                "could not query Windows Firewall (netsh)",
+                None,
+            )),
+        }
+        if mirrored && any_unreachable {
+            report.findings.push(finding(
+                "firewall_rule",
+                None,
+                WslFindingSeverity::Warning,
+                "mirrored mode also applies the Hyper-V firewall; allow inbound WSL traffic there",
+                None,
+            ));
+        }
+    }
+
+    let names: Vec<String> = distros.iter().map(|d| d.name.clone()).collect();
+    for status in get_config_status(&names) {
+        let configured = [
+            ("claude", status.claude),
+            ("codex", status.codex),
+            ("gemini", status.gemini),
+        ];
+        let missing: Vec<&str> = configured
+            .iter()
+            .filter(|(cli_key, ok)| wsl_target_enabled(input.targets, cli_key) && !ok)
+            .map(|(cli_key, _)| *cli_key)
+            .collect();
+        report.findings.push(if missing.is_empty() {
+            finding(
+                "client_config",
+                Some(&status.distro),
+                WslFindingSeverity::Ok,
+                "target CLI clients are configured",
+                None,
+            )
+        } else {
+            finding(
+                "client_config",
+                Some(&status.distro),
+                WslFindingSeverity::Warning,
+                format!("not configured: {}", missing.join(", ")),
+                Some(WslRepairAction::ConfigureClients),
+            )
+        });
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_list_verbose_and_networking_mode() {
+        let text = "  NAME            STATE           VERSION\r\n* Ubuntu 22.04    Running         2\r\n  Debian          Stopped         1\r\n";
+        assert_eq!(
+            parse_list_verbose(text),
+            vec![
+                DistroInfo {
+                    name: "Ubuntu 22.04".to_string(),
+                    state: "Running".to_string(),
+                    version: 2,
+                },
+                DistroInfo {
+                    name: "Debian".to_string(),
+                    state: "Stopped".to_string(),
+                    version: 1,
+                },
+            ]
+        );
+
+        assert_eq!(parse_networking_mode(""), WslNetworkingMode::Nat);
+        assert_eq!(
+            parse_networking_mode("[wsl2]\nmemory=8GB\nnetworkingMode=mirrored\n"),
+            WslNetworkingMode::Mirrored
+        );
+        assert_eq!(
+            parse_networking_mode("[experimental]\nnetworkingMode=mirrored\n"),
+            WslNetworkingMode::Nat
+        );
+        assert_eq!(
+            parse_networking_mode("[wsl2]\nnetworkingMode = virtioproxy\n"),
+            WslNetworkingMode::Other
+        );
+    }
+
+    #[test]
+    fn parses_probe_output() {
+        assert_eq!(
+            parse_probe_output("curl:200\n"),
+            ProbeResult::Reachable("HTTP 200".to_string())
+        );
+        assert!(matches!(
+            parse_probe_output("curl:401"),
+            ProbeResult::Reachable(_)
+        ));
+        assert!(matches!(
+            parse_probe_output("curl:000"),
+            ProbeResult::Unreachable(_)
+        ));
+        assert!(matches!(
+            parse_probe_output("tcp:ok"),
+            ProbeResult::Reachable(_)
+        ));
+        assert!(matches!(
+            parse_probe_output(""),
+            ProbeResult::Unreachable(_)
+        ));
+    }
+}
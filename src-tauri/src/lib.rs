@@ -6,21 +6,33 @@ mod infra;
 mod shared;
 pub mod test_support;
 
-pub(crate) use app::{app_state, notice, resident};
+pub(crate) use app::{app_state, notice, notification_sinks, resident};
 pub(crate) use domain::{
-    claude_model_validation, claude_model_validation_history, claude_plugins, cli_sessions, cost,
-    cost_stats, mcp, prompts, provider_limit_usage, providers, skills, sort_modes, usage,
-    usage_stats, workspace_switch, workspaces,
+    audit_log, claude_model_validation, claude_model_validation_history,
+    claude_model_validation_schedules, claude_plugins, cli_config_drift, cli_sessions,
+    codex_profiles, cost, cost_anomaly, cost_stats, hooks, mcp, prompts, provider_credentials,
+    provider_dns_options, provider_limit_usage, provider_maintenance, provider_model_catalog,
+    provider_scores, provider_tag_defaults, provider_validation, providers, request_mirror, skills,
+    sort_mode_schedules, sort_modes, subagents, usage, usage_stats, workspace_snapshots,
+    workspace_switch, workspaces,
 };
 pub(crate) use gateway::session_manager;
 pub(crate) use infra::{
     app_paths, base_url_probe, claude_settings, cli_manager, cli_proxy, codex_config, codex_paths,
     data_management, db, env_conflicts, mcp_sync, model_price_aliases, model_prices,
-    model_prices_sync, prompt_sync, provider_circuit_breakers, request_attempt_logs, request_logs,
-    settings, wsl,
+    model_prices_import, model_prices_sync, observer_mode, prompt_sync, provider_circuit_breakers,
+    redaction, request_attempt_logs, request_logs, settings, ssh_clients, subagent_sync, wsl,
 };
 pub(crate) use shared::{blocking, circuit_breaker};
 
+/// Tauri runtime the app runs on: the webview runtime, or Tauri's mock runtime in the `headless`
+/// build, which never creates an event loop, window or tray (no display server needed).
+#[cfg(not(feature = "headless"))]
+pub(crate) type AppRuntime = tauri::Wry;
+#[cfg(feature = "headless")]
+pub(crate) type AppRuntime = tauri::test::MockRuntime;
+pub(crate) type AppHandle = tauri::AppHandle<AppRuntime>;
+
 use app_state::{ensure_db_ready, DbInitState, GatewayState};
 use commands::*;
 use shared::mutex_ext::MutexExt;
@@ -32,211 +44,43 @@ static EXIT_CLEANUP_SPAWNED: AtomicBool = AtomicBool::new(false);
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let builder = tauri::Builder::default()
+    let headless = match crate::app::headless::options_from_env() {
+        Ok(v) => v,
+        Err(err) => {
+            eprintln!("aio-coding-hub: {err}");
+            std::process::exit(2);
+        }
+    };
+
+    #[cfg(not(feature = "headless"))]
+    let builder = tauri::Builder::default();
+    #[cfg(feature = "headless")]
+    let builder = tauri::test::mock_builder();
+
+    let builder = builder
         .manage(DbInitState::default())
         .manage(GatewayState::default())
         .manage(resident::ResidentState::default())
         .manage(crate::app::heartbeat_watchdog::HeartbeatWatchdogState::default())
+        .manage(crate::app::selftest::SelfTestState::default())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_clipboard_manager::init());
 
     #[cfg(desktop)]
+    let builder = builder.plugin(tauri_plugin_notification::init());
+
+    #[cfg(all(desktop, not(feature = "headless")))]
     let builder = builder
         .plugin(tauri_plugin_autostart::Builder::new().build())
-        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
             resident::show_main_window(app);
         }));
 
-    let app = builder
-        .on_window_event(resident::on_window_event)
-        .setup(|app| {
-            crate::app::logging::init(app.handle());
-            crate::app::heartbeat_watchdog::install(app.handle());
-
-            // Global panic hook: ensure any panic is written to disk logs for post-mortem diagnosis.
-            // Note: payload is intentionally NOT logged to avoid leaking user data (consistent with blocking.rs).
-            std::panic::set_hook(Box::new(|panic_info| {
-                let location = panic_info
-                    .location()
-                    .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
-                    .unwrap_or_else(|| "unknown".to_string());
-                tracing::error!(
-                    location = %location,
-                    "PANIC: application panicked at {location}. Check the log file for context leading up to this panic."
-                );
-            }));
-
-            #[cfg(desktop)]
-            {
-                if let Err(err) = app
-                    .handle()
-                    .plugin(tauri_plugin_updater::Builder::new().build())
-                {
-                    tracing::error!("updater initialization failed: {}", err);
-                }
-
-                if let Err(err) = resident::setup_tray(app.handle()) {
-                    tracing::error!("system tray initialization failed: {}", err);
-                }
-            }
-
-            #[cfg(debug_assertions)]
-            {
-                let enabled = std::env::var("AIO_CODING_HUB_DEV_DIAGNOSTICS")
-                    .ok()
-                    .map(|v| v.trim().to_ascii_lowercase())
-                    .is_some_and(|v| v == "1" || v == "true" || v == "yes");
-                if enabled {
-                    let identifier = &app.config().identifier;
-                    let product_name = app.config().product_name.as_deref().unwrap_or("<missing>");
-                    tracing::info!(identifier = %identifier, "[dev] tauri identifier");
-                    tracing::info!(product_name = %product_name, "[dev] productName");
-                    if let Ok(dotdir_name) = std::env::var("AIO_CODING_HUB_DOTDIR_NAME") {
-                        tracing::info!(dotdir_name = %dotdir_name, "[dev] AIO_CODING_HUB_DOTDIR_NAME");
-                    }
-                    if let Ok(dir) = app_paths::app_data_dir(app.handle()) {
-                        tracing::info!(dir = %dir.display(), "[dev] app data dir");
-                    }
-                }
-            }
-
-            let app_handle = app.handle().clone();
-            tauri::async_runtime::spawn(async move {
-                let db_state = app_handle.state::<DbInitState>();
-                let db = match ensure_db_ready(app_handle.clone(), db_state.inner()).await {
-                    Ok(db) => db,
-                    Err(err) => {
-                        tracing::error!("database initialization failed: {}", err);
-                        return;
-                    }
-                };
-
-                // M1: auto-start gateway on app launch (required for seamless CLI proxy experience).
-                // Port conflicts are handled by the gateway's bind-first-available strategy.
-                let settings = match blocking::run("startup_read_settings", {
-                    let app_handle = app_handle.clone();
-                    move || -> crate::shared::error::AppResult<settings::AppSettings> {
-                        Ok(settings::read(&app_handle).unwrap_or_default())
-                    }
-                })
-                .await
-                {
-                    Ok(cfg) => cfg,
-                    Err(err) => {
-                        tracing::warn!("settings read failed, using defaults: {}", err);
-                        settings::AppSettings::default()
-                    }
-                };
-
-                app_handle
-                    .state::<resident::ResidentState>()
-                    .set_tray_enabled(settings.tray_enabled);
-
-                // Window starts hidden (visible:false in tauri.conf.json) to prevent flash.
-                // Show it unless start_minimized is active.
-                if settings.start_minimized {
-                    resident::hide_main_window_on_startup(&app_handle);
-                } else {
-                    resident::show_main_window(&app_handle);
-                }
-
-                let preferred_port = settings.preferred_port;
-                let enable_cli_proxy_startup_recovery = settings.enable_cli_proxy_startup_recovery;
-                #[cfg(windows)]
-                let gateway_listen_mode = settings.gateway_listen_mode;
-
-                if enable_cli_proxy_startup_recovery {
-                    match blocking::run("startup_cli_proxy_repair_incomplete_enable", {
-                        let app_handle = app_handle.clone();
-                        move || cli_proxy::startup_repair_incomplete_enable(&app_handle)
-                    })
-                    .await
-                    {
-                        Ok(results) => {
-                            let mut repaired = Vec::new();
-                            for result in results {
-                                if result.ok {
-                                    repaired.push(result.cli_key);
-                                    continue;
-                                }
-
-                                tracing::warn!(
-                                    cli_key = %result.cli_key,
-                                    trace_id = %result.trace_id,
-                                    error_code = %result.error_code.unwrap_or_default(),
-                                    "startup recovery: cli_proxy enable state repair failed: {}",
-                                    result.message
-                                );
-                            }
-
-                            if !repaired.is_empty() {
-                                tracing::info!(
-                                    repaired = repaired.len(),
-                                    cli_keys = ?repaired,
-                                    "startup recovery: repaired cli_proxy enable state inconsistencies"
-                                );
-                            }
-                        }
-                        Err(err) => {
-                            tracing::warn!("startup recovery: cli_proxy enable state repair task failed: {}", err);
-                        }
-                    }
-                }
-
-                let status = match blocking::run("startup_gateway_autostart", {
-                    let app_handle = app_handle.clone();
-                    let db = db.clone();
-                    move || {
-                        let state = app_handle.state::<GatewayState>();
-                        let mut manager = state.0.lock_or_recover();
-                        manager.start(&app_handle, db, Some(preferred_port))
-                    }
-                })
-                .await
-                {
-                    Ok(status) => status,
-                    Err(err) => {
-                        tracing::error!("gateway auto-start failed: {}", err);
-                        if enable_cli_proxy_startup_recovery {
-                            crate::app::cleanup::restore_cli_proxy_keep_state_best_effort(
-                                &app_handle,
-                                "startup_cli_proxy_restore_keep_state",
-                                "startup_recovery_gateway_failed",
-                                true,
-                            )
-                            .await;
-                        }
-                        return;
-                    }
-                };
-
-                let _ = app_handle.emit("gateway:status", status.clone());
-
-                // WSL auto-detect and auto-configure (Windows only, gated by wsl_auto_config)
-                #[cfg(windows)]
-                if settings.wsl_auto_config {
-                    let auto_cfg_app = app_handle.clone();
-                    let auto_cfg_db = db.clone();
-                    let auto_cfg_port = status.port;
-
-                    tauri::async_runtime::spawn(async move {
-                        if let Err(err) = commands::wsl::wsl_auto_configure_on_startup(
-                            &auto_cfg_app,
-                            auto_cfg_db,
-                            gateway_listen_mode,
-                            auto_cfg_port,
-                        )
-                        .await
-                        {
-                            tracing::warn!("WSL startup auto-configure failed: {}", err);
-                        }
-                    });
-                }
-            });
+    let builder = builder.on_window_event(resident::on_window_event);
+    #[cfg(not(feature = "headless"))]
+    let builder = builder.setup(move |app| setup_app(app, headless));
 
-            Ok(())
-        })
+    let app = builder
         .invoke_handler(tauri::generate_handler![
             // ── settings ──
             settings_get,
@@ -244,15 +88,34 @@ pub fn run() {
             settings_gateway_rectifier_set,
             settings_circuit_breaker_notice_set,
             settings_codex_session_id_completion_set,
+            settings_gateway_access_token_rotate,
+            settings_gateway_access_token_clear,
+            settings_redaction_rules_set,
+            settings_env_conflict_ignore_rules_set,
+            settings_model_denylist_set,
+            settings_model_capabilities_set,
+            settings_captured_response_headers_set,
+            settings_upstream_error_body_capture_set,
+            settings_session_binding_ttl_set,
+            settings_retry_backoff_set,
+            settings_access_log_set,
+            settings_cost_anomaly_set,
+            settings_observer_mode_set,
+            settings_request_log_sampling_set,
+            settings_storage_postgres_url_set,
+            settings_notification_sinks_set,
+            settings_ssh_hosts_set,
             // ── app ──
             app_about_get,
             app_data_dir_get,
             app_exit,
             app_restart,
             app_heartbeat_pong,
+            app_selftest_report,
             app_frontend_error_report,
             // ── notice ──
             notice_send,
+            notification_sink_test,
             // ── cli_manager ──
             cli_manager_claude_info_get,
             cli_manager_codex_info_get,
@@ -261,35 +124,74 @@ pub fn run() {
             cli_manager_codex_config_toml_get,
             cli_manager_codex_config_toml_validate,
             cli_manager_codex_config_toml_set,
+            codex_profiles_list,
+            codex_profile_upsert,
+            codex_profile_delete,
+            codex_profile_apply,
             cli_manager_gemini_info_get,
             cli_manager_claude_env_set,
             cli_manager_claude_settings_get,
             cli_manager_claude_settings_set,
             // ── gateway ──
             gateway_start,
+            gateway_change_port,
             gateway_stop,
             gateway_status,
             gateway_check_port_available,
             gateway_sessions_list,
+            session_bindings_list,
+            session_binding_pin,
+            session_binding_unpin,
             gateway_circuit_status,
             gateway_circuit_reset_provider,
             gateway_circuit_reset_cli,
+            gateway_provider_quality_v1,
+            gateway_live_stats,
+            gateway_oauth_pacing_v1,
+            error_catalog_list,
             // ── wsl ──
             wsl_detect,
             wsl_host_address_get,
             wsl_config_status_get,
             wsl_configure_clients,
+            wsl_diagnose,
+            wsl_repair,
+            // ── ssh ──
+            ssh_host_check,
+            ssh_configure_clients,
             // ── cli_sessions ──
             cli_sessions_projects_list,
             cli_sessions_sessions_list,
             cli_sessions_messages_get,
             cli_sessions_session_delete,
+            cli_sessions_export,
             // ── providers ──
             providers_list,
+            providers_credential_status,
             provider_upsert,
             provider_set_enabled,
+            provider_set_last_resort,
+            provider_set_emulate_models_endpoint,
+            provider_set_thinking_budget_cap,
+            provider_set_refusal_patterns,
+            provider_set_anthropic_headers,
+            provider_set_webhook_url,
+            provider_set_monthly_budget,
             provider_delete,
+            provider_restore,
+            provider_usage_audit,
+            provider_purge,
+            provider_maintenance_windows_list,
+            provider_maintenance_window_upsert,
+            provider_maintenance_window_delete,
+            provider_dns_options_get,
+            provider_dns_options_set,
+            provider_default_tags_list,
+            provider_default_tag_set,
+            provider_model_catalog_refresh,
+            providers_validate_all,
             providers_reorder,
+            providers_bulk_update,
             provider_claude_terminal_launch_command,
             provider_get_api_key,
             base_url_ping_ms,
@@ -303,19 +205,30 @@ pub fn run() {
             claude_provider_get_api_key_plaintext,
             claude_validation_history_list,
             claude_validation_history_clear_provider,
+            claude_validation_schedules_list,
+            claude_validation_schedule_upsert,
+            claude_validation_schedule_delete,
             // ── sort_modes ──
             sort_modes_list,
             sort_mode_create,
             sort_mode_rename,
+            sort_mode_set_strategy,
             sort_mode_delete,
             sort_mode_active_list,
             sort_mode_active_set,
             sort_mode_providers_list,
             sort_mode_providers_set_order,
+            sort_mode_suggest_order,
             sort_mode_provider_set_enabled,
+            sort_mode_provider_set_time_window,
+            sort_mode_fallback_get,
+            sort_mode_fallback_set,
+            sort_mode_schedule_get,
+            sort_mode_schedule_set,
             // ── model_prices ──
             model_prices_list,
             model_price_upsert,
+            model_prices_import,
             model_prices_sync_basellm,
             model_price_aliases_get,
             model_price_aliases_set,
@@ -325,6 +238,8 @@ pub fn run() {
             prompt_upsert,
             prompt_set_enabled,
             prompt_delete,
+            prompt_file_conflicts_list,
+            prompt_file_conflict_resolve,
             // ── mcp ──
             mcp_servers_list,
             mcp_server_upsert,
@@ -333,19 +248,44 @@ pub fn run() {
             mcp_parse_json,
             mcp_import_servers,
             mcp_import_from_workspace_cli,
+            mcp_sync_status,
+            mcp_sync_apply,
+            mcp_sync_adopt,
+            mcp_server_supervise_start,
+            mcp_server_supervise_stop,
+            mcp_server_runtime_status,
+            // ── hooks ──
+            hooks_list,
+            hook_templates_list,
+            hook_upsert,
+            hook_set_enabled,
+            hook_delete,
+            // ── subagents ──
+            subagents_list,
+            subagents_upsert,
+            subagents_set_enabled,
+            subagents_delete,
             // ── skills ──
             skill_repos_list,
             skill_repo_upsert,
             skill_repo_delete,
+            skill_repo_auth_set,
+            skill_repo_test_access,
             skills_installed_list,
             skills_discover_available,
             skill_install,
+            skill_dependencies_preview,
+            skill_dependencies_check,
             skill_set_enabled,
             skill_uninstall,
             skill_return_to_local,
             skills_local_list,
             skill_import_local,
             skills_import_local_batch,
+            skills_check_updates,
+            skill_set_pin,
+            skill_update,
+            skill_package,
             skills_paths_get,
             // ── request_logs ──
             request_logs_list,
@@ -355,10 +295,25 @@ pub fn run() {
             request_log_get,
             request_log_get_by_trace_id,
             request_attempt_logs_by_trace_id,
+            request_timeline_by_trace_id,
+            // ── request_mirror ──
+            request_mirror_logs_list,
+            request_mirror_stats,
+            request_mirror_logs_clear,
+            // ── audit_log ──
+            audit_log_list,
             // ── data_management ──
             db_disk_usage_get,
+            db_health_get,
+            db_checkpoint_now,
+            db_query_readonly,
+            usage_history_import,
+            app_config_export,
+            app_config_import,
+            config_apply_file,
             request_logs_clear_all,
             app_data_reset,
+            app_data_reset_scope,
             // ── usage ──
             usage_summary,
             usage_summary_v2,
@@ -367,6 +322,9 @@ pub fn run() {
             usage_leaderboard_v2,
             usage_hourly_series,
             usage_provider_cache_rate_trend_v1,
+            usage_forecast_v1,
+            usage_bandwidth_v1,
+            usage_heatmap_v1,
             // ── cost ──
             cost_summary_v1,
             cost_trend_v1,
@@ -375,14 +333,21 @@ pub fn run() {
             cost_scatter_cli_provider_model_v1,
             cost_top_requests_v1,
             cost_backfill_missing_v1,
+            cost_anomalies_list,
+            cost_anomaly_acknowledge,
             // ── env_conflicts ──
             env_conflicts_check,
+            env_conflicts_fix,
+            env_conflicts_fix_undo,
             // ── cli_proxy ──
             cli_proxy_status_all,
             cli_proxy_set_enabled,
             cli_proxy_sync_enabled,
+            cli_config_drift_report,
             // ── provider_limit_usage ──
             provider_limit_usage_v1,
+            provider_monthly_budgets_list,
+            provider_monthly_budget_set_grace,
             // ── workspaces ──
             workspaces_list,
             workspace_create,
@@ -390,10 +355,23 @@ pub fn run() {
             workspace_delete,
             workspace_preview,
             workspace_apply,
+            workspace_snapshot_list,
+            workspace_rollback,
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application");
 
+    #[cfg(feature = "headless")]
+    {
+        let mut app = app;
+        if let Err(err) = setup_app(&mut app, headless) {
+            eprintln!("aio-coding-hub: setup failed: {err}");
+            std::process::exit(1);
+        }
+        crate::app::headless::run_until_shutdown(app.handle());
+    }
+
+    #[cfg(not(feature = "headless"))]
     app.run(|app_handle, event| {
         if let tauri::RunEvent::ExitRequested { api, code, .. } = &event {
             // Note: `prevent_exit` is ignored for restart requests.
@@ -428,23 +406,563 @@ pub fn run() {
     });
 }
 
+/// App setup shared by both runtimes; the headless build calls it directly because the mock
+/// runtime never runs the event loop that normally triggers it.
+fn setup_app(
+    app: &mut tauri::App<AppRuntime>,
+    headless: Option<crate::app::headless::HeadlessOptions>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    crate::app::logging::init(app.handle());
+    if headless.is_none() {
+        crate::app::heartbeat_watchdog::install(app.handle());
+    }
+
+    // Global panic hook: ensure any panic is written to disk logs for post-mortem diagnosis.
+    // Note: payload is intentionally NOT logged to avoid leaking user data (consistent with blocking.rs).
+    std::panic::set_hook(Box::new(|panic_info| {
+        let location = panic_info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+            .unwrap_or_else(|| "unknown".to_string());
+        tracing::error!(
+            location = %location,
+            "PANIC: application panicked at {location}. Check the log file for context leading up to this panic."
+        );
+    }));
+
+    #[cfg(all(desktop, not(feature = "headless")))]
+    if let Err(err) = app
+        .handle()
+        .plugin(tauri_plugin_updater::Builder::new().build())
+    {
+        tracing::error!("updater initialization failed: {}", err);
+    }
+
+    #[cfg(desktop)]
+    if headless.is_none() {
+        if let Err(err) = resident::setup_tray(app.handle()) {
+            tracing::error!("system tray initialization failed: {}", err);
+        }
+    }
+
+    match &headless {
+        Some(opts) => {
+            tracing::info!(admin_addr = %opts.admin_addr, "starting in headless mode");
+            crate::app::headless::mark_active();
+            // The headless build has no event loop to exit; it waits for the signal itself.
+            #[cfg(not(feature = "headless"))]
+            crate::app::headless::install_ctrl_c_exit(app.handle().clone());
+        }
+        None => {
+            if let Err(err) = resident::create_main_window(app.handle()) {
+                tracing::error!("main window initialization failed: {}", err);
+            }
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    {
+        let enabled = std::env::var("AIO_CODING_HUB_DEV_DIAGNOSTICS")
+            .ok()
+            .map(|v| v.trim().to_ascii_lowercase())
+            .is_some_and(|v| v == "1" || v == "true" || v == "yes");
+        if enabled {
+            let identifier = &app.config().identifier;
+            let product_name = app.config().product_name.as_deref().unwrap_or("<missing>");
+            tracing::info!(identifier = %identifier, "[dev] tauri identifier");
+            tracing::info!(product_name = %product_name, "[dev] productName");
+            if let Ok(dotdir_name) = std::env::var("AIO_CODING_HUB_DOTDIR_NAME") {
+                tracing::info!(dotdir_name = %dotdir_name, "[dev] AIO_CODING_HUB_DOTDIR_NAME");
+            }
+            if let Ok(dir) = app_paths::app_data_dir(app.handle()) {
+                tracing::info!(dir = %dir.display(), "[dev] app data dir");
+            }
+        }
+    }
+
+    let app_handle = app.handle().clone();
+    tauri::async_runtime::spawn(async move {
+        let db_state = app_handle.state::<DbInitState>();
+        let db = match ensure_db_ready(app_handle.clone(), db_state.inner()).await {
+            Ok(db) => db,
+            Err(err) => {
+                tracing::error!("database initialization failed: {}", err);
+                crate::app::selftest::run_startup(&app_handle).await;
+                return;
+            }
+        };
+
+        crate::app::sort_mode_scheduler::spawn(app_handle.clone(), db.clone());
+        crate::app::attempt_log_retention::spawn(app_handle.clone(), db.clone());
+        crate::app::claude_validation_scheduler::spawn(app_handle.clone(), db.clone());
+        crate::app::cost_anomaly_monitor::spawn(app_handle.clone(), db.clone());
+        if let Some(opts) = &headless {
+            crate::app::admin_api::spawn(app_handle.clone(), opts.admin_addr);
+        }
+
+        // M1: auto-start gateway on app launch (required for seamless CLI proxy experience).
+        // Port conflicts are handled by the gateway's bind-first-available strategy.
+        let settings = match blocking::run("startup_read_settings", {
+            let app_handle = app_handle.clone();
+            move || -> crate::shared::error::AppResult<settings::AppSettings> {
+                Ok(settings::read(&app_handle).unwrap_or_default())
+            }
+        })
+        .await
+        {
+            Ok(cfg) => cfg,
+            Err(err) => {
+                tracing::warn!("settings read failed, using defaults: {}", err);
+                settings::AppSettings::default()
+            }
+        };
+
+        redaction::configure(&settings.redaction_rules);
+        observer_mode::configure(settings.observer_mode);
+        request_logs::configure_sampling(
+            settings.request_log_sample_percent,
+            settings.request_log_slow_threshold_ms,
+        );
+        notification_sinks::configure(&settings.notification_sinks);
+        gateway::response_headers::configure(&settings.captured_response_headers);
+        gateway::error_body_capture::configure(settings.upstream_error_body_capture_bytes);
+        gateway::access_log::configure(
+            &app_handle,
+            settings.access_log_format,
+            settings.access_log_retention_days,
+        );
+
+        // Tray menu is built before settings load; relabel it for a non-default language.
+        if settings.ui_language != crate::shared::i18n::UiLanguage::default() {
+            resident::refresh_tray_menu(&app_handle);
+        }
+
+        app_handle
+            .state::<resident::ResidentState>()
+            .set_tray_enabled(settings.tray_enabled);
+
+        // Window starts hidden (visible:false in tauri.conf.json) to prevent flash.
+        // Show it unless start_minimized is active (headless mode has no window).
+        if headless.is_none() {
+            if settings.start_minimized {
+                resident::hide_main_window_on_startup(&app_handle);
+            } else {
+                resident::show_main_window(&app_handle);
+            }
+        }
+
+        let preferred_port = settings.preferred_port;
+        let enable_cli_proxy_startup_recovery = settings.enable_cli_proxy_startup_recovery;
+        #[cfg(windows)]
+        let gateway_listen_mode = settings.gateway_listen_mode;
+
+        if enable_cli_proxy_startup_recovery {
+            match blocking::run("startup_cli_proxy_repair_incomplete_enable", {
+                let app_handle = app_handle.clone();
+                move || cli_proxy::startup_repair_incomplete_enable(&app_handle)
+            })
+            .await
+            {
+                Ok(results) => {
+                    let mut repaired = Vec::new();
+                    for result in results {
+                        if result.ok {
+                            repaired.push(result.cli_key);
+                            continue;
+                        }
+
+                        tracing::warn!(
+                            cli_key = %result.cli_key,
+                            trace_id = %result.trace_id,
+                            error_code = %result.error_code.unwrap_or_default(),
+                            "startup recovery: cli_proxy enable state repair failed: {}",
+                            result.message
+                        );
+                    }
+
+                    if !repaired.is_empty() {
+                        tracing::info!(
+                            repaired = repaired.len(),
+                            cli_keys = ?repaired,
+                            "startup recovery: repaired cli_proxy enable state inconsistencies"
+                        );
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        "startup recovery: cli_proxy enable state repair task failed: {}",
+                        err
+                    );
+                }
+            }
+        }
+
+        let status = match blocking::run("startup_gateway_autostart", {
+            let app_handle = app_handle.clone();
+            let db = db.clone();
+            move || {
+                let state = app_handle.state::<GatewayState>();
+                let mut manager = state.0.lock_or_recover();
+                manager.start(&app_handle, db, Some(preferred_port))
+            }
+        })
+        .await
+        {
+            Ok(status) => status,
+            Err(err) => {
+                tracing::error!("gateway auto-start failed: {}", err);
+                if enable_cli_proxy_startup_recovery {
+                    crate::app::cleanup::restore_cli_proxy_keep_state_best_effort(
+                        &app_handle,
+                        "startup_cli_proxy_restore_keep_state",
+                        "startup_recovery_gateway_failed",
+                        true,
+                    )
+                    .await;
+                }
+                crate::app::selftest::run_startup(&app_handle).await;
+                return;
+            }
+        };
+
+        let _ = app_handle.emit("gateway:status", status.clone());
+
+        // WSL auto-detect and auto-configure (Windows only, gated by wsl_auto_config)
+        #[cfg(windows)]
+        if settings.wsl_auto_config {
+            let auto_cfg_app = app_handle.clone();
+            let auto_cfg_db = db.clone();
+            let auto_cfg_port = status.port;
+
+            tauri::async_runtime::spawn(async move {
+                if let Err(err) = commands::wsl::wsl_auto_configure_on_startup(
+                    &auto_cfg_app,
+                    auto_cfg_db,
+                    gateway_listen_mode,
+                    auto_cfg_port,
+                )
+                .await
+                {
+                    tracing::warn!("WSL startup auto-configure failed: {}", err);
+                }
+            });
+        }
+
+        // SSH remote hosts with auto_sync enabled
+        if settings.ssh_hosts.iter().any(|host| host.auto_sync) {
+            let ssh_sync_app = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(err) = commands::ssh_clients::ssh_auto_sync_core(&ssh_sync_app).await {
+                    tracing::warn!("SSH startup auto-sync failed: {}", err);
+                }
+            });
+        }
+
+        crate::app::selftest::run_startup(&app_handle).await;
+    });
+
+    Ok(())
+}
+
 /// Specta type export configuration.
 ///
-/// Uses `tauri_specta::Builder` to export TypeScript bindings for the subset of
-/// Tauri commands annotated with `#[specta::specta]`.
-/// Currently exports the `settings` and provider IPC contracts covered by `#[specta::specta]`.
+/// Uses `tauri_specta::Builder` to export TypeScript bindings for every Tauri command
+/// registered in `run` (all are annotated with `#[specta::specta]`); keep both lists in sync.
+/// Payloads the backend emits as events are exported too (`events` in the bindings).
 ///
 /// Run `cargo test export_bindings -- --ignored` to regenerate `src/generated/bindings.ts`.
 #[cfg(test)]
 #[test]
 #[ignore = "run manually: cargo test export_bindings -- --ignored"]
 fn export_bindings() {
-    let builder =
-        tauri_specta::Builder::<tauri::Wry>::new().commands(tauri_specta::collect_commands![
+    let builder = tauri_specta::Builder::<crate::AppRuntime>::new()
+        .commands(tauri_specta::collect_commands![
+            // ── settings ──
             commands::settings::settings_get,
             commands::settings::settings_set,
+            commands::settings::settings_gateway_rectifier_set,
+            commands::settings::settings_circuit_breaker_notice_set,
+            commands::settings::settings_codex_session_id_completion_set,
+            commands::settings::settings_gateway_access_token_rotate,
+            commands::settings::settings_gateway_access_token_clear,
+            commands::settings::settings_redaction_rules_set,
+            commands::settings::settings_env_conflict_ignore_rules_set,
+            commands::settings::settings_model_denylist_set,
+            commands::settings::settings_model_capabilities_set,
+            commands::settings::settings_captured_response_headers_set,
+            commands::settings::settings_upstream_error_body_capture_set,
+            commands::settings::settings_session_binding_ttl_set,
+            commands::settings::settings_retry_backoff_set,
+            commands::settings::settings_access_log_set,
+            commands::settings::settings_cost_anomaly_set,
+            commands::settings::settings_observer_mode_set,
+            commands::settings::settings_request_log_sampling_set,
+            commands::settings::settings_storage_postgres_url_set,
+            commands::settings::settings_notification_sinks_set,
+            commands::settings::settings_ssh_hosts_set,
+            // ── app ──
+            commands::app::app_about_get,
+            commands::data_management::app_data_dir_get,
+            commands::app::app_exit,
+            commands::app::app_restart,
+            commands::app::app_heartbeat_pong,
+            commands::app::app_selftest_report,
+            commands::app::app_frontend_error_report,
+            // ── notice ──
+            commands::notice::notice_send,
+            commands::notice::notification_sink_test,
+            // ── cli_manager ──
+            commands::cli_manager::cli_manager_claude_info_get,
+            commands::cli_manager::cli_manager_codex_info_get,
+            commands::cli_manager::cli_manager_codex_config_get,
+            commands::cli_manager::cli_manager_codex_config_set,
+            commands::cli_manager::cli_manager_codex_config_toml_get,
+            commands::cli_manager::cli_manager_codex_config_toml_validate,
+            commands::cli_manager::cli_manager_codex_config_toml_set,
+            commands::codex_profiles::codex_profiles_list,
+            commands::codex_profiles::codex_profile_upsert,
+            commands::codex_profiles::codex_profile_delete,
+            commands::codex_profiles::codex_profile_apply,
+            commands::cli_manager::cli_manager_gemini_info_get,
+            commands::cli_manager::cli_manager_claude_env_set,
+            commands::cli_manager::cli_manager_claude_settings_get,
+            commands::cli_manager::cli_manager_claude_settings_set,
+            // ── gateway ──
+            commands::gateway::gateway_start,
+            commands::gateway::gateway_change_port,
+            commands::gateway::gateway_stop,
+            commands::gateway::gateway_status,
+            commands::gateway::gateway_check_port_available,
+            commands::gateway::gateway_sessions_list,
+            commands::gateway::session_bindings_list,
+            commands::gateway::session_binding_pin,
+            commands::gateway::session_binding_unpin,
+            commands::gateway::gateway_circuit_status,
+            commands::gateway::gateway_circuit_reset_provider,
+            commands::gateway::gateway_circuit_reset_cli,
+            commands::gateway::gateway_provider_quality_v1,
+            commands::gateway::gateway_live_stats,
+            commands::gateway::gateway_oauth_pacing_v1,
+            commands::gateway::error_catalog_list,
+            // ── wsl ──
+            commands::wsl::wsl_detect,
+            commands::wsl::wsl_host_address_get,
+            commands::wsl::wsl_config_status_get,
+            commands::wsl::wsl_configure_clients,
+            commands::wsl::wsl_diagnose,
+            commands::wsl::wsl_repair,
+            // ── ssh ──
+            commands::ssh_clients::ssh_host_check,
+            commands::ssh_clients::ssh_configure_clients,
+            // ── cli_sessions ──
+            commands::cli_sessions::cli_sessions_projects_list,
+            commands::cli_sessions::cli_sessions_sessions_list,
+            commands::cli_sessions::cli_sessions_messages_get,
+            commands::cli_sessions::cli_sessions_session_delete,
+            commands::cli_sessions::cli_sessions_export,
+            // ── providers ──
             commands::providers::providers_list,
-            commands::providers::provider_upsert
+            commands::providers::providers_credential_status,
+            commands::providers::provider_upsert,
+            commands::providers::provider_set_enabled,
+            commands::providers::provider_set_last_resort,
+            commands::providers::provider_set_emulate_models_endpoint,
+            commands::providers::provider_set_thinking_budget_cap,
+            commands::providers::provider_set_refusal_patterns,
+            commands::providers::provider_set_anthropic_headers,
+            commands::providers::provider_set_webhook_url,
+            commands::providers::provider_set_monthly_budget,
+            commands::providers::provider_delete,
+            commands::providers::provider_restore,
+            commands::providers::provider_usage_audit,
+            commands::providers::provider_purge,
+            commands::providers::provider_maintenance_windows_list,
+            commands::providers::provider_maintenance_window_upsert,
+            commands::providers::provider_maintenance_window_delete,
+            commands::providers::provider_dns_options_get,
+            commands::providers::provider_dns_options_set,
+            commands::providers::provider_default_tags_list,
+            commands::providers::provider_default_tag_set,
+            commands::providers::provider_model_catalog_refresh,
+            commands::providers::providers_validate_all,
+            commands::providers::providers_reorder,
+            commands::providers::providers_bulk_update,
+            commands::providers::provider_claude_terminal_launch_command,
+            commands::providers::provider_get_api_key,
+            commands::providers::base_url_ping_ms,
+            commands::providers::provider_oauth_start_flow,
+            commands::providers::provider_oauth_refresh,
+            commands::providers::provider_oauth_disconnect,
+            commands::providers::provider_oauth_status,
+            commands::providers::provider_oauth_fetch_limits,
+            // ── claude_model_validation ──
+            commands::claude_model_validation::claude_provider_validate_model,
+            commands::claude_model_validation::claude_provider_get_api_key_plaintext,
+            commands::claude_model_validation::claude_validation_history_list,
+            commands::claude_model_validation::claude_validation_history_clear_provider,
+            commands::claude_model_validation::claude_validation_schedules_list,
+            commands::claude_model_validation::claude_validation_schedule_upsert,
+            commands::claude_model_validation::claude_validation_schedule_delete,
+            // ── sort_modes ──
+            commands::sort_modes::sort_modes_list,
+            commands::sort_modes::sort_mode_create,
+            commands::sort_modes::sort_mode_rename,
+            commands::sort_modes::sort_mode_set_strategy,
+            commands::sort_modes::sort_mode_delete,
+            commands::sort_modes::sort_mode_active_list,
+            commands::sort_modes::sort_mode_active_set,
+            commands::sort_modes::sort_mode_providers_list,
+            commands::sort_modes::sort_mode_providers_set_order,
+            commands::sort_modes::sort_mode_suggest_order,
+            commands::sort_modes::sort_mode_provider_set_enabled,
+            commands::sort_modes::sort_mode_provider_set_time_window,
+            commands::sort_modes::sort_mode_fallback_get,
+            commands::sort_modes::sort_mode_fallback_set,
+            commands::sort_modes::sort_mode_schedule_get,
+            commands::sort_modes::sort_mode_schedule_set,
+            // ── model_prices ──
+            commands::model_prices::model_prices_list,
+            commands::model_prices::model_price_upsert,
+            commands::model_prices::model_prices_import,
+            commands::model_prices::model_prices_sync_basellm,
+            commands::model_prices::model_price_aliases_get,
+            commands::model_prices::model_price_aliases_set,
+            // ── prompts ──
+            commands::prompts::prompts_list,
+            commands::prompts::prompts_default_sync_from_files,
+            commands::prompts::prompt_upsert,
+            commands::prompts::prompt_set_enabled,
+            commands::prompts::prompt_delete,
+            commands::prompts::prompt_file_conflicts_list,
+            commands::prompts::prompt_file_conflict_resolve,
+            // ── mcp ──
+            commands::mcp::mcp_servers_list,
+            commands::mcp::mcp_server_upsert,
+            commands::mcp::mcp_server_set_enabled,
+            commands::mcp::mcp_server_delete,
+            commands::mcp::mcp_parse_json,
+            commands::mcp::mcp_import_servers,
+            commands::mcp::mcp_import_from_workspace_cli,
+            commands::mcp::mcp_sync_status,
+            commands::mcp::mcp_sync_apply,
+            commands::mcp::mcp_sync_adopt,
+            commands::mcp::mcp_server_supervise_start,
+            commands::mcp::mcp_server_supervise_stop,
+            commands::mcp::mcp_server_runtime_status,
+            // ── hooks ──
+            commands::hooks::hooks_list,
+            commands::hooks::hook_templates_list,
+            commands::hooks::hook_upsert,
+            commands::hooks::hook_set_enabled,
+            commands::hooks::hook_delete,
+            // ── subagents ──
+            commands::subagents::subagents_list,
+            commands::subagents::subagents_upsert,
+            commands::subagents::subagents_set_enabled,
+            commands::subagents::subagents_delete,
+            // ── skills ──
+            commands::skills::skill_repos_list,
+            commands::skills::skill_repo_upsert,
+            commands::skills::skill_repo_delete,
+            commands::skills::skill_repo_auth_set,
+            commands::skills::skill_repo_test_access,
+            commands::skills::skills_installed_list,
+            commands::skills::skills_discover_available,
+            commands::skills::skill_install,
+            commands::skills::skill_dependencies_preview,
+            commands::skills::skill_dependencies_check,
+            commands::skills::skill_set_enabled,
+            commands::skills::skill_uninstall,
+            commands::skills::skill_return_to_local,
+            commands::skills::skills_local_list,
+            commands::skills::skill_import_local,
+            commands::skills::skills_import_local_batch,
+            commands::skills::skills_check_updates,
+            commands::skills::skill_set_pin,
+            commands::skills::skill_update,
+            commands::skills::skill_package,
+            commands::skills::skills_paths_get,
+            // ── request_logs ──
+            commands::request_logs::request_logs_list,
+            commands::request_logs::request_logs_list_all,
+            commands::request_logs::request_logs_list_after_id,
+            commands::request_logs::request_logs_list_after_id_all,
+            commands::request_logs::request_log_get,
+            commands::request_logs::request_log_get_by_trace_id,
+            commands::request_logs::request_attempt_logs_by_trace_id,
+            commands::request_logs::request_timeline_by_trace_id,
+            // ── request_mirror ──
+            commands::request_mirror::request_mirror_logs_list,
+            commands::request_mirror::request_mirror_stats,
+            commands::request_mirror::request_mirror_logs_clear,
+            // ── audit_log ──
+            commands::audit_log::audit_log_list,
+            // ── data_management ──
+            commands::data_management::db_disk_usage_get,
+            commands::data_management::db_health_get,
+            commands::data_management::db_checkpoint_now,
+            commands::data_management::db_query_readonly,
+            commands::data_management::usage_history_import,
+            commands::data_management::app_config_export,
+            commands::data_management::app_config_import,
+            commands::data_management::config_apply_file,
+            commands::data_management::request_logs_clear_all,
+            commands::data_management::app_data_reset,
+            commands::data_management::app_data_reset_scope,
+            // ── usage ──
+            commands::usage::usage_summary,
+            commands::usage::usage_summary_v2,
+            commands::usage::usage_leaderboard_provider,
+            commands::usage::usage_leaderboard_day,
+            commands::usage::usage_leaderboard_v2,
+            commands::usage::usage_hourly_series,
+            commands::usage::usage_provider_cache_rate_trend_v1,
+            commands::usage::usage_forecast_v1,
+            commands::usage::usage_bandwidth_v1,
+            commands::usage::usage_heatmap_v1,
+            // ── cost ──
+            commands::cost::cost_summary_v1,
+            commands::cost::cost_trend_v1,
+            commands::cost::cost_breakdown_provider_v1,
+            commands::cost::cost_breakdown_model_v1,
+            commands::cost::cost_scatter_cli_provider_model_v1,
+            commands::cost::cost_top_requests_v1,
+            commands::cost::cost_backfill_missing_v1,
+            commands::cost::cost_anomalies_list,
+            commands::cost::cost_anomaly_acknowledge,
+            // ── env_conflicts ──
+            commands::env_conflicts::env_conflicts_check,
+            commands::env_conflicts::env_conflicts_fix,
+            commands::env_conflicts::env_conflicts_fix_undo,
+            // ── cli_proxy ──
+            commands::cli_proxy::cli_proxy_status_all,
+            commands::cli_proxy::cli_proxy_set_enabled,
+            commands::cli_proxy::cli_proxy_sync_enabled,
+            commands::cli_proxy::cli_config_drift_report,
+            // ── provider_limit_usage ──
+            commands::provider_limit_usage::provider_limit_usage_v1,
+            commands::provider_limit_usage::provider_monthly_budgets_list,
+            commands::provider_limit_usage::provider_monthly_budget_set_grace,
+            // ── workspaces ──
+            commands::workspaces::workspaces_list,
+            commands::workspaces::workspace_create,
+            commands::workspaces::workspace_rename,
+            commands::workspaces::workspace_delete,
+            commands::workspaces::workspace_preview,
+            commands::workspaces::workspace_apply,
+            commands::workspaces::workspace_snapshot_list,
+            commands::workspaces::workspace_rollback
+        ])
+        .events(tauri_specta::collect_events![
+            gateway::GatewayStatus,
+            gateway::events::GatewayRequestStartEvent,
+            gateway::events::GatewayAttemptEvent,
+            gateway::events::GatewayRequestEvent,
+            gateway::events::GatewayCircuitEvent,
+            gateway::events::GatewayStreamStallEvent,
+            gateway::events::GatewayLogEvent,
+            notice::NoticeEventPayload,
+            crate::app::selftest::SelfTestReport
         ]);
 
     builder
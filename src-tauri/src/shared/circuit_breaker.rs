@@ -12,6 +12,8 @@ const DEFAULT_OPEN_DURATION_SECS: i64 = 30 * 60;
 pub enum CircuitState {
     Closed,
     Open,
+    /// Open period is over; user requests stay blocked until a probe request succeeds.
+    HalfOpen,
 }
 
 impl CircuitState {
@@ -19,12 +21,14 @@ impl CircuitState {
         match self {
             Self::Closed => "CLOSED",
             Self::Open => "OPEN",
+            Self::HalfOpen => "HALF_OPEN",
         }
     }
 
     pub fn from_str(raw: &str) -> Self {
         match raw {
             "OPEN" => Self::Open,
+            "HALF_OPEN" => Self::HalfOpen,
             _ => Self::Closed,
         }
     }
@@ -34,6 +38,9 @@ impl CircuitState {
 pub struct CircuitBreakerConfig {
     pub failure_threshold: u32,
     pub open_duration_secs: i64,
+    /// Expired OPEN circuits move to HALF_OPEN and wait for `record_probe_result` instead of
+    /// closing on their own.
+    pub half_open_probe: bool,
 }
 
 impl Default for CircuitBreakerConfig {
@@ -41,6 +48,7 @@ impl Default for CircuitBreakerConfig {
         Self {
             failure_threshold: DEFAULT_FAILURE_THRESHOLD,
             open_duration_secs: DEFAULT_OPEN_DURATION_SECS,
+            half_open_probe: false,
         }
     }
 }
@@ -143,7 +151,10 @@ impl CircuitBreaker {
         }
     }
 
-    #[allow(dead_code)]
+    pub fn half_open_probe(&self) -> bool {
+        self.config.half_open_probe
+    }
+
     pub fn snapshot(&self, provider_id: i64, now_unix: i64) -> CircuitSnapshot {
         let mut guard = self.health.lock_or_recover();
         let entry = guard
@@ -168,30 +179,37 @@ impl CircuitBreaker {
                 }
             }
 
-            if entry.state == CircuitState::Open {
-                let expired = entry.open_until.map(|t| now_unix >= t).unwrap_or(true);
-                if expired {
-                    let prev = entry.state;
+            let expired = match entry.state {
+                CircuitState::Open => entry.open_until.map(|t| now_unix >= t).unwrap_or(true),
+                // Left over from a run with probing enabled; nothing will probe it now.
+                CircuitState::HalfOpen => !self.config.half_open_probe,
+                CircuitState::Closed => false,
+            };
+            if expired {
+                let prev = entry.state;
+                if self.config.half_open_probe {
+                    entry.state = CircuitState::HalfOpen;
+                } else {
                     entry.state = CircuitState::Closed;
                     entry.failure_count = 0;
-                    entry.open_until = None;
-                    entry.updated_at = now_unix;
-
-                    let t = CircuitTransition {
-                        prev_state: prev,
-                        next_state: entry.state,
-                        reason: "OPEN_EXPIRED",
-                        snapshot: self.snapshot_from_health(provider_id, entry),
-                    };
-
-                    transition = Some(t);
-                    upsert = Some(self.persisted_from_health(provider_id, entry));
                 }
+                entry.open_until = None;
+                entry.updated_at = now_unix;
+
+                let t = CircuitTransition {
+                    prev_state: prev,
+                    next_state: entry.state,
+                    reason: "OPEN_EXPIRED",
+                    snapshot: self.snapshot_from_health(provider_id, entry),
+                };
+
+                transition = Some(t);
+                upsert = Some(self.persisted_from_health(provider_id, entry));
             }
 
             let after = self.snapshot_from_health(provider_id, entry);
             let cooldown_active = entry.cooldown_until.map(|t| now_unix < t).unwrap_or(false);
-            let allow = entry.state != CircuitState::Open && !cooldown_active;
+            let allow = entry.state == CircuitState::Closed && !cooldown_active;
             (after, allow)
         };
 
@@ -226,7 +244,8 @@ impl CircuitBreaker {
                         upsert = Some(self.persisted_from_health(provider_id, entry));
                     }
                 }
-                CircuitState::Open => {}
+                // Only a probe may close a half-open circuit.
+                CircuitState::Open | CircuitState::HalfOpen => {}
             }
 
             let after = self.snapshot_from_health(provider_id, entry);
@@ -279,7 +298,106 @@ impl CircuitBreaker {
 
                     upsert = Some(self.persisted_from_health(provider_id, entry));
                 }
-                CircuitState::Open => {}
+                CircuitState::Open | CircuitState::HalfOpen => {}
+            }
+
+            let after = self.snapshot_from_health(provider_id, entry);
+            (before, after)
+        };
+
+        if let Some(item) = upsert {
+            self.try_persist(item);
+        }
+
+        CircuitChange {
+            before,
+            after,
+            transition,
+        }
+    }
+
+    /// Providers waiting for a probe: expired OPEN circuits are moved to HALF_OPEN first (their
+    /// transitions are returned alongside). Empty when probing is disabled.
+    pub fn take_probe_candidates(&self, now_unix: i64) -> Vec<(i64, Option<CircuitTransition>)> {
+        if !self.config.half_open_probe {
+            return Vec::new();
+        }
+
+        let mut upserts: Vec<CircuitPersistedState> = Vec::new();
+        let mut out: Vec<(i64, Option<CircuitTransition>)> = Vec::new();
+        {
+            let mut guard = self.health.lock_or_recover();
+            for (provider_id, entry) in guard.iter_mut() {
+                let mut transition = None;
+                if entry.state == CircuitState::Open
+                    && entry.open_until.map(|t| now_unix >= t).unwrap_or(true)
+                {
+                    let prev = entry.state;
+                    entry.state = CircuitState::HalfOpen;
+                    entry.open_until = None;
+                    entry.updated_at = now_unix;
+                    transition = Some(CircuitTransition {
+                        prev_state: prev,
+                        next_state: entry.state,
+                        reason: "OPEN_EXPIRED",
+                        snapshot: self.snapshot_from_health(*provider_id, entry),
+                    });
+                    upserts.push(self.persisted_from_health(*provider_id, entry));
+                }
+                if entry.state == CircuitState::HalfOpen {
+                    out.push((*provider_id, transition));
+                }
+            }
+        }
+
+        for item in upserts {
+            self.try_persist(item);
+        }
+        out.sort_by_key(|(provider_id, _)| *provider_id);
+        out
+    }
+
+    /// Settle a HALF_OPEN circuit: a successful probe closes it, a failed one re-opens it for
+    /// another full open period. No-op for any other state.
+    pub fn record_probe_result(
+        &self,
+        provider_id: i64,
+        now_unix: i64,
+        success: bool,
+    ) -> CircuitChange {
+        let mut upsert: Option<CircuitPersistedState> = None;
+        let mut transition: Option<CircuitTransition> = None;
+
+        let (before, after) = {
+            let mut guard = self.health.lock_or_recover();
+            let entry = guard
+                .entry(provider_id)
+                .or_insert_with(|| ProviderHealth::closed(provider_id, now_unix).1);
+
+            let before = self.snapshot_from_health(provider_id, entry);
+
+            if entry.state == CircuitState::HalfOpen {
+                let prev = entry.state;
+                let reason = if success {
+                    entry.state = CircuitState::Closed;
+                    entry.failure_count = 0;
+                    entry.open_until = None;
+                    "PROBE_SUCCEEDED"
+                } else {
+                    entry.state = CircuitState::Open;
+                    entry.open_until =
+                        Some(now_unix.saturating_add(self.config.open_duration_secs));
+                    "PROBE_FAILED"
+                };
+                entry.updated_at = now_unix;
+
+                transition = Some(CircuitTransition {
+                    prev_state: prev,
+                    next_state: entry.state,
+                    reason,
+                    snapshot: self.snapshot_from_health(provider_id, entry),
+                });
+                upsert = Some(self.persisted_from_health(provider_id, entry));
             }
 
             let after = self.snapshot_from_health(provider_id, entry);
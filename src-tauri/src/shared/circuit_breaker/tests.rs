@@ -76,3 +76,53 @@ fn reset_clears_open_and_cooldown() {
     let allow = cb.should_allow(pid, now + 21);
     assert!(allow.allow);
 }
+
+#[test]
+fn half_open_probe_settles_expired_circuit() {
+    let cb = CircuitBreaker::new(
+        CircuitBreakerConfig {
+            half_open_probe: true,
+            ..Default::default()
+        },
+        HashMap::new(),
+        None,
+    );
+    let pid = 1;
+    let now = 1_000;
+    for i in 1..=DEFAULT_FAILURE_THRESHOLD {
+        cb.record_failure(pid, now + i as i64);
+    }
+    let open_until = cb.snapshot(pid, now + 10).open_until.expect("open_until");
+    assert!(cb.take_probe_candidates(open_until - 1).is_empty());
+
+    // Expired: user requests stay blocked until the probe settles the circuit.
+    let check = cb.should_allow(pid, open_until);
+    assert!(!check.allow);
+    assert_eq!(check.after.state, CircuitState::HalfOpen);
+    let candidates = cb.take_probe_candidates(open_until);
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].0, pid);
+    assert!(candidates[0].1.is_none());
+
+    cb.record_success(pid, open_until + 1);
+    assert_eq!(
+        cb.snapshot(pid, open_until + 1).state,
+        CircuitState::HalfOpen
+    );
+
+    let failed = cb.record_probe_result(pid, open_until + 2, false);
+    assert_eq!(failed.after.state, CircuitState::Open);
+    assert_eq!(failed.transition.map(|t| t.reason), Some("PROBE_FAILED"));
+
+    let reopened_until = failed.after.open_until.expect("open_until");
+    let candidates = cb.take_probe_candidates(reopened_until);
+    assert_eq!(
+        candidates[0].1.as_ref().map(|t| t.next_state),
+        Some(CircuitState::HalfOpen)
+    );
+
+    let closed = cb.record_probe_result(pid, reopened_until + 1, true);
+    assert_eq!(closed.after.state, CircuitState::Closed);
+    assert_eq!(closed.after.failure_count, 0);
+    assert!(cb.should_allow(pid, reopened_until + 2).allow);
+}
@@ -32,6 +32,14 @@ impl AppError {
             source: None,
         }
     }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
 }
 
 fn split_code_message(raw: &str) -> Option<(&str, &str)> {
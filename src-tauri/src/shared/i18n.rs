@@ -0,0 +1,217 @@
+//! Usage: Message catalogs for user-facing backend text (gateway error bodies, notices, tray).
+//!
+//! Callers keep stable keys (gateway error codes, `notice.*`, `tray.*`); the text is picked from
+//! the catalog of the active `ui_language` at render time. Missing translations fall back to zh,
+//! then to the key itself.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "snake_case")]
+pub enum UiLanguage {
+    #[default]
+    Zh,
+    En,
+}
+
+impl UiLanguage {
+    const fn to_u8(self) -> u8 {
+        match self {
+            Self::Zh => 0,
+            Self::En => 1,
+        }
+    }
+
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::En,
+            _ => Self::Zh,
+        }
+    }
+}
+
+static CURRENT_LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+/// Switch the language used by `text`/`format`. Called whenever settings are loaded or saved.
+pub(crate) fn set_language(language: UiLanguage) {
+    CURRENT_LANGUAGE.store(language.to_u8(), Ordering::Relaxed);
+}
+
+pub(crate) fn language() -> UiLanguage {
+    UiLanguage::from_u8(CURRENT_LANGUAGE.load(Ordering::Relaxed))
+}
+
+// (key, zh, en). An empty translation means "not translated yet" and falls back to zh.
+#[rustfmt::skip]
+const CATALOG: &[(&str, &str, &str)] = &[
+    // Gateway error codes (`error_response` bodies).
+    ("GW_ALL_PROVIDERS_UNAVAILABLE", "所有供应商暂不可用（熔断/冷却/限额中）", "All providers are temporarily unavailable (circuit open, cooling down or over limit)"),
+    ("GW_UPSTREAM_ALL_FAILED", "所有供应商请求均失败", "Requests to all providers failed"),
+    ("GW_NO_ENABLED_PROVIDER", "没有已启用的供应商", "No provider is enabled"),
+    ("GW_UPSTREAM_TIMEOUT", "上游请求超时", "Upstream request timed out"),
+    ("GW_UPSTREAM_CONNECT_FAILED", "无法连接到上游", "Failed to connect to upstream"),
+    ("GW_UPSTREAM_5XX", "上游服务端错误", "Upstream server error"),
+    ("GW_UPSTREAM_4XX", "上游拒绝了请求", "Upstream rejected the request"),
+    ("GW_UPSTREAM_READ_ERROR", "读取上游响应失败", "Failed to read upstream response"),
+    ("GW_UPSTREAM_BODY_READ_ERROR", "读取上游响应体失败", "Failed to read upstream response body"),
+    ("GW_STREAM_ERROR", "流式响应出错", "Streaming response failed"),
+    ("GW_STREAM_ABORTED", "流式响应被中断", "Streaming response was aborted"),
+    ("GW_STREAM_IDLE_TIMEOUT", "流式响应空闲超时", "Streaming response idle timeout"),
+    ("GW_REQUEST_ABORTED", "请求已取消", "Request was aborted"),
+    ("GW_INTERNAL_ERROR", "网关内部错误", "Internal gateway error"),
+    ("GW_BODY_TOO_LARGE", "请求体过大", "Request body is too large"),
+    ("GW_INVALID_CLI_KEY", "无效的 CLI 标识", "Invalid CLI key"),
+    ("GW_INVALID_BASE_URL", "供应商 Base URL 无效", "Provider base URL is invalid"),
+    ("GW_PORT_IN_USE", "端口已被占用", "Port is already in use"),
+    ("GW_RESPONSE_BUILD_ERROR", "构建响应失败", "Failed to build response"),
+    ("GW_PROVIDER_RATE_LIMITED", "供应商已达到限额", "Provider is rate limited"),
+    ("GW_PROVIDER_CIRCUIT_OPEN", "供应商处于熔断状态", "Provider circuit breaker is open"),
+    ("GW_PROVIDER_MAINTENANCE", "供应商维护中", "Provider is under maintenance"),
+    ("GW_PROVIDER_MODEL_UNSUPPORTED", "供应商不支持该模型", "Provider does not support this model"),
+    ("GW_CLI_PROXY_DISABLED", "该 CLI 的代理未启用", "Proxy is disabled for this CLI"),
+    ("GW_CLI_PROXY_GUARD_ERROR", "CLI 代理状态检查失败", "CLI proxy guard check failed"),
+    ("GW_SESSION_THROTTLED", "会话因费用异常被限速", "Session is rate limited after a cost anomaly"),
+    ("GW_SESSION_FAILURE_BUDGET_EXCEEDED", "会话失败次数过多，已暂停转发", "Session exceeded its failed-attempt budget"),
+    ("GW_MODEL_DENIED", "该模型已被模型黑名单禁用", "Model is blocked by the model denylist"),
+    ("GW_CONTENT_REFUSAL", "上游返回疑似拒答内容，已切换供应商", "Upstream answered with a refusal; failed over"),
+    ("GW_ROUTING_PAUSED", "网关路由已暂停，请在托盘菜单中恢复", "Gateway routing is paused; resume it from the tray menu"),
+    ("GW_UNAUTHORIZED", "访问令牌缺失或无效", "Missing or invalid access token"),
+    ("GW_HTTP_CLIENT_INIT", "HTTP 客户端初始化失败", "Failed to initialize HTTP client"),
+    // Notice default titles.
+    ("notice.title.info", "提示", "Info"),
+    ("notice.title.success", "成功", "Success"),
+    ("notice.title.warning", "提醒", "Warning"),
+    ("notice.title.error", "错误", "Error"),
+    // Shared notice lines.
+    ("notice.line.cli", "CLI：{cli_key}", "CLI: {cli_key}"),
+    ("notice.line.provider", "Provider：{name} (id={id})", "Provider: {name} (id={id})"),
+    ("notice.line.trace", "Trace：{trace_id}", "Trace: {trace_id}"),
+    // Circuit breaker notice.
+    ("notice.circuit.title_open", "熔断触发：{name}", "Circuit opened: {name}"),
+    ("notice.circuit.title_closed", "熔断恢复：{name}", "Circuit recovered: {name}"),
+    ("notice.circuit.state_closed", "正常", "closed"),
+    ("notice.circuit.state_open", "熔断", "open"),
+    ("notice.circuit.state_half_open", "半开（探测中）", "half-open (probing)"),
+    ("notice.circuit.reason_failure_threshold", "失败次数达到阈值", "failure threshold reached"),
+    ("notice.circuit.reason_open_expired", "熔断到期自动恢复", "open period expired"),
+    ("notice.circuit.reason_probe_succeeded", "探测请求成功", "probe request succeeded"),
+    ("notice.circuit.reason_probe_failed", "探测请求失败，重新熔断", "probe request failed, reopened"),
+    ("notice.circuit.line_base_url", "Base URL：{base_url}", "Base URL: {base_url}"),
+    ("notice.circuit.line_state", "状态：{prev} → {next}", "State: {prev} → {next}"),
+    ("notice.circuit.line_failures", "失败：{count} / {threshold}", "Failures: {count} / {threshold}"),
+    ("notice.circuit.line_reason", "原因：{text}（{code}）", "Reason: {text} ({code})"),
+    ("notice.circuit.line_open_until", "熔断至：{until}（约 {minutes} 分钟后）", "Open until: {until} (in ~{minutes} min)"),
+    ("notice.circuit.line_open_until_expired", "熔断至：{until}（已到期）", "Open until: {until} (expired)"),
+    ("notice.circuit.line_open_until_none", "熔断至：—", "Open until: —"),
+    // Provider monthly budget notice.
+    ("notice.budget.title_warn", "供应商月度预算已用 80%：{name}", "Provider monthly budget 80% used: {name}"),
+    ("notice.budget.title_exhausted", "供应商月度预算已耗尽：{name}", "Provider monthly budget exhausted: {name}"),
+    ("notice.budget.action_warn", "达到 100% 后将停止路由到该供应商", "Routing to this provider stops at 100%"),
+    ("notice.budget.action_exhausted", "本月剩余时间内该供应商已被停用（可设置宽限额度临时放行）", "This provider is disabled for the rest of the month (add a grace amount to allow it temporarily)"),
+    ("notice.budget.line_month", "月份：{month}", "Month: {month}"),
+    ("notice.budget.line_spent", "已用：${spent} / ${budget}", "Spent: ${spent} / ${budget}"),
+    // Provider quarantine notice.
+    ("notice.quarantine.title", "供应商自动隔离：{name}", "Provider quarantined: {name}"),
+    ("notice.quarantine.reason_error_rate", "错误率过高", "error rate too high"),
+    ("notice.quarantine.reason_truncated_stream_rate", "流式响应截断率过高", "truncated stream rate too high"),
+    ("notice.quarantine.line_reason", "原因：{text}（{percent}% / 最近 {samples} 次请求）", "Reason: {text} ({percent}% of the last {samples} requests)"),
+    ("notice.quarantine.line_until", "隔离至：{until}（约 {minutes} 分钟）", "Quarantined until: {until} (~{minutes} min)"),
+    // Scheduled Claude model validation regression notice.
+    ("notice.validation.title_regression", "模型验证回归：{name}", "Model validation regression: {name}"),
+    ("notice.validation.line_model", "模型：{model}", "Model: {model}"),
+    ("notice.validation.line_error", "错误：{error}", "Error: {error}"),
+    ("notice.validation.action", "该供应商与模型上次定时验证通过，本次失败", "This provider and model passed the previous scheduled validation and now fail"),
+    // Cost anomaly notice.
+    ("notice.cost_anomaly.title", "会话费用异常：{name}", "Session cost anomaly: {name}"),
+    ("notice.cost_anomaly.line_session", "会话：{session_id}", "Session: {session_id}"),
+    ("notice.cost_anomaly.line_cost", "近 30 分钟：${recent}（此前平均每 30 分钟 ${baseline}，阈值 {multiplier} 倍）", "Last 30 min: ${recent} (previous average ${baseline} per 30 min, threshold {multiplier}x)"),
+    ("notice.cost_anomaly.action", "请检查该会话是否陷入循环", "Check whether this session is stuck in a loop"),
+    ("notice.cost_anomaly.action_throttled", "该会话已被限速，在应用中确认后恢复", "This session is rate limited until the anomaly is acknowledged in the app"),
+    // Gateway failure notice (external notification sinks).
+    ("notice.gateway_failure.title", "网关请求失败：{cli_key}", "Gateway request failed: {cli_key}"),
+    ("notice.gateway_failure.line_error", "错误：{code}（HTTP {status}）", "Error: {code} (HTTP {status})"),
+    // Notification sink test message.
+    ("notice.sink_test.title", "测试通知", "Test notification"),
+    ("notice.sink_test.body", "通知渠道配置成功", "This notification channel is configured correctly"),
+    // Gateway port change progress (`gateway:port_change` events).
+    ("gateway.port_change.bound", "已在端口 {to_port} 上启动监听", "Listening on port {to_port}"),
+    ("gateway.port_change.configs_synced", "CLI 代理配置已切换到新端口", "CLI proxy configs now point to the new port"),
+    ("gateway.port_change.draining", "正在关闭旧端口 {from_port} 上的连接", "Closing connections on the old port {from_port}"),
+    ("gateway.port_change.done", "网关端口已从 {from_port} 切换到 {to_port}", "Gateway port changed from {from_port} to {to_port}"),
+    // Tray menu.
+    ("tray.toggle", "显示/隐藏", "Show/Hide"),
+    ("tray.cli_proxy", "{cli} 代理", "{cli} proxy"),
+    ("tray.pause_routing", "暂停所有路由", "Pause all routing"),
+    ("tray.quit", "退出", "Quit"),
+];
+
+fn lookup(language: UiLanguage, key: &str) -> Option<&'static str> {
+    let (_, zh, en) = CATALOG.iter().find(|(k, _, _)| *k == key)?;
+    let localized = match language {
+        UiLanguage::Zh => zh,
+        UiLanguage::En => en,
+    };
+    Some(if localized.is_empty() { zh } else { localized })
+}
+
+/// Localized text for `key` in the active language (zh fallback, then the key itself).
+pub(crate) fn text(key: &'static str) -> &'static str {
+    lookup(language(), key).unwrap_or(key)
+}
+
+/// `text` with `{name}` placeholders substituted from `args`.
+pub(crate) fn format(key: &'static str, args: &[(&str, &dyn Display)]) -> String {
+    let mut out = text(key).to_string();
+    for (name, value) in args {
+        let placeholder = format!("{{{name}}}");
+        if out.contains(&placeholder) {
+            out = out.replace(&placeholder, &value.to_string());
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_falls_back_to_zh_then_key() {
+        assert_eq!(lookup(UiLanguage::En, "tray.quit"), Some("Quit"));
+        assert_eq!(lookup(UiLanguage::Zh, "tray.quit"), Some("退出"));
+        assert_eq!(lookup(UiLanguage::En, "no.such.key"), None);
+
+        for (key, zh, _) in CATALOG {
+            assert!(!zh.is_empty(), "missing zh text for {key}");
+            assert_eq!(
+                CATALOG.iter().filter(|(k, _, _)| k == key).count(),
+                1,
+                "duplicate catalog key {key}"
+            );
+        }
+    }
+
+    #[test]
+    fn placeholders_match_across_languages() {
+        fn placeholders(text: &str) -> Vec<&str> {
+            let mut out: Vec<&str> = text
+                .split('{')
+                .skip(1)
+                .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+                .collect();
+            out.sort_unstable();
+            out
+        }
+        for (key, zh, en) in CATALOG {
+            if !en.is_empty() {
+                assert_eq!(
+                    placeholders(zh),
+                    placeholders(en),
+                    "placeholder mismatch for {key}"
+                );
+            }
+        }
+    }
+}
@@ -5,6 +5,7 @@ pub(crate) mod circuit_breaker;
 pub(crate) mod cli_key;
 pub(crate) mod error;
 pub(crate) mod fs;
+pub(crate) mod i18n;
 pub(crate) mod mutex_ext;
 pub(crate) mod security;
 pub(crate) mod sqlite;
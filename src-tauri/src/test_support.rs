@@ -36,9 +36,12 @@ fn parse_provider_base_url_mode(
     match input.trim() {
         "order" => Ok(crate::providers::ProviderBaseUrlMode::Order),
         "ping" => Ok(crate::providers::ProviderBaseUrlMode::Ping),
-        _ => Err("SEC_INVALID_INPUT: base_url_mode must be 'order' or 'ping'"
-            .to_string()
-            .into()),
+        "race" => Ok(crate::providers::ProviderBaseUrlMode::Race),
+        _ => Err(
+            "SEC_INVALID_INPUT: base_url_mode must be 'order', 'ping' or 'race'"
+                .to_string()
+                .into(),
+        ),
     }
 }
 
@@ -142,6 +145,25 @@ pub fn mcp_servers_list_json<R: tauri::Runtime>(
     serialize_json(rows)
 }
 
+pub fn mcp_sync_status_json<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    cli_key: Option<&str>,
+) -> crate::shared::error::AppResult<serde_json::Value> {
+    let db = crate::infra::db::init(app)?;
+    let rows = crate::domain::mcp::sync_status(app, &db, cli_key)?;
+    serialize_json(rows)
+}
+
+pub fn mcp_sync_adopt_json<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    cli_key: &str,
+    server_keys: Vec<String>,
+) -> crate::shared::error::AppResult<serde_json::Value> {
+    let db = crate::infra::db::init(app)?;
+    let status = crate::domain::mcp::adopt_external_edits(app, &db, cli_key, &server_keys)?;
+    serialize_json(status)
+}
+
 pub fn workspace_active_id_by_cli<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     cli_key: &str,
@@ -261,6 +283,7 @@ pub fn provider_upsert_json<R: tauri::Runtime>(
             limit_total_usd,
             tags: None,
             note: None,
+            provider_kind: None,
         },
     )?;
     serialize_json(provider)
@@ -285,6 +308,55 @@ pub fn provider_delete<R: tauri::Runtime>(
     Ok(true)
 }
 
+pub fn provider_archive_json<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    provider_id: i64,
+) -> crate::shared::error::AppResult<serde_json::Value> {
+    let db = crate::infra::db::init(app)?;
+    let provider = crate::providers::archive(&db, provider_id)?;
+    serialize_json(provider)
+}
+
+pub fn provider_restore_json<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    provider_id: i64,
+) -> crate::shared::error::AppResult<serde_json::Value> {
+    let db = crate::infra::db::init(app)?;
+    let provider = crate::providers::unarchive(&db, provider_id)?;
+    serialize_json(provider)
+}
+
+pub fn provider_purge<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    provider_id: i64,
+) -> crate::shared::error::AppResult<bool> {
+    let db = crate::infra::db::init(app)?;
+    crate::providers::purge(&db, provider_id)?;
+    Ok(true)
+}
+
+pub fn provider_maintenance_window_upsert_json<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    provider_id: i64,
+    starts_at: i64,
+    ends_at: i64,
+    repeat_interval_secs: Option<i64>,
+) -> crate::shared::error::AppResult<serde_json::Value> {
+    let db = crate::infra::db::init(app)?;
+    let window = crate::provider_maintenance::upsert_window(
+        &db,
+        crate::provider_maintenance::ProviderMaintenanceWindowUpsertParams {
+            window_id: None,
+            provider_id,
+            starts_at,
+            ends_at,
+            repeat_interval_secs,
+            note: None,
+        },
+    )?;
+    serialize_json(window)
+}
+
 pub fn providers_reorder_json<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     cli_key: &str,
@@ -295,6 +367,18 @@ pub fn providers_reorder_json<R: tauri::Runtime>(
     serialize_json(providers)
 }
 
+pub fn providers_bulk_update_json<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    input: serde_json::Value,
+    dry_run: bool,
+) -> crate::shared::error::AppResult<serde_json::Value> {
+    let db = crate::infra::db::init(app)?;
+    let input: crate::providers::ProviderBulkUpdate = serde_json::from_value(input)
+        .map_err(|e| format!("SEC_INVALID_INPUT: invalid bulk update: {e}"))?;
+    let result = crate::providers::bulk_update(&db, input, dry_run)?;
+    serialize_json(result)
+}
+
 pub fn cli_proxy_set_enabled_json<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
     cli_key: &str,
@@ -529,6 +613,25 @@ pub fn sort_mode_active_set_json<R: tauri::Runtime>(
     serialize_json(row)
 }
 
+pub fn sort_mode_fallback_set_json<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    cli_key: &str,
+    mode_ids: Vec<Option<i64>>,
+) -> crate::shared::error::AppResult<serde_json::Value> {
+    let db = crate::infra::db::init(app)?;
+    let chain = crate::sort_modes::set_fallback_chain(&db, cli_key, mode_ids)?;
+    serialize_json(chain)
+}
+
+pub fn sort_mode_fallback_get_json<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    cli_key: &str,
+) -> crate::shared::error::AppResult<serde_json::Value> {
+    let db = crate::infra::db::init(app)?;
+    let chain = crate::sort_modes::get_fallback_chain(&db, cli_key)?;
+    serialize_json(chain)
+}
+
 // ---------------------------------------------------------------------------
 // Data Management
 // ---------------------------------------------------------------------------
@@ -570,3 +673,65 @@ pub fn request_logs_clear_all_json<R: tauri::Runtime>(
     let result = crate::data_management::request_logs_clear_all(&db)?;
     serialize_json(result)
 }
+
+pub fn config_apply_file_json<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    path: &std::path::Path,
+    dry_run: bool,
+) -> crate::shared::error::AppResult<serde_json::Value> {
+    let db = crate::infra::db::init(app)?;
+    let report =
+        crate::data_management::config_apply_file(app, &db, &path.to_string_lossy(), dry_run)?;
+    serialize_json(report)
+}
+
+/// Opens the app database the way a companion process would (`args` may carry `--readonly`)
+/// and returns the stats views version plus the `stats_v1_daily` rows.
+pub fn stats_reader_daily_json(
+    db_path: &std::path::Path,
+    args: &[&str],
+) -> crate::shared::error::AppResult<serde_json::Value> {
+    let mode = crate::infra::db::DbAccessMode::from_args(args);
+    let db = crate::infra::db::open_existing(db_path, mode)?;
+    let conn = db.open_connection()?;
+    let version: i64 = conn
+        .query_row("SELECT stats_views_version FROM stats_meta", [], |row| {
+            row.get(0)
+        })
+        .map_err(|e| format!("DB_ERROR: failed to read stats_meta: {e}"))?;
+    let mut stmt = conn
+        .prepare("SELECT day, cli_key, provider_id, model, requests FROM stats_v1_daily")
+        .map_err(|e| format!("DB_ERROR: failed to prepare stats_v1_daily query: {e}"))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(serde_json::json!({
+                "day": row.get::<_, String>(0)?,
+                "cli_key": row.get::<_, String>(1)?,
+                "provider_id": row.get::<_, i64>(2)?,
+                "model": row.get::<_, String>(3)?,
+                "requests": row.get::<_, i64>(4)?,
+            }))
+        })
+        .map_err(|e| format!("DB_ERROR: failed to query stats_v1_daily: {e}"))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("DB_ERROR: failed to read stats_v1_daily row: {e}"))?;
+
+    serialize_json(serde_json::json!({
+        "stats_views_version": version,
+        "daily": rows,
+    }))
+}
+
+/// Runs `sql` through a companion-process connection (`args` may carry `--readonly`).
+pub fn stats_reader_execute(
+    db_path: &std::path::Path,
+    args: &[&str],
+    sql: &str,
+) -> crate::shared::error::AppResult<usize> {
+    let mode = crate::infra::db::DbAccessMode::from_args(args);
+    let db = crate::infra::db::open_existing(db_path, mode)?;
+    let conn = db.open_connection()?;
+    Ok(conn
+        .execute(sql, [])
+        .map_err(|e| format!("DB_ERROR: failed to execute statement: {e}"))?)
+}
@@ -1,6 +1,6 @@
 mod support;
 
-use support::json_u64;
+use support::{json_array, json_bool, json_i64, json_str, json_u64};
 
 #[test]
 fn db_disk_usage_after_init() {
@@ -71,3 +71,95 @@ fn disk_usage_after_clear_logs() {
         "DB should still have non-zero size after clear"
     );
 }
+
+#[test]
+fn config_apply_file_diffs_applies_and_prunes() {
+    let app = support::TestApp::new();
+    let handle = app.handle();
+
+    aio_coding_hub_lib::test_support::init_db(&handle).expect("init db");
+
+    let dir = aio_coding_hub_lib::test_support::app_data_dir(&handle).expect("app data dir");
+    let path = dir.join("hub.yaml");
+    std::fs::write(
+        &path,
+        r#"
+version: 1
+providers:
+  - cli_key: claude
+    name: relay-a
+    base_urls: ["https://relay-a.example.com"]
+    api_key: sk-a
+    priority: 10
+  - cli_key: claude
+    name: relay-b
+    base_urls: ["https://relay-b.example.com"]
+    api_key: sk-b
+sort_modes:
+  - name: work
+    providers:
+      claude: [relay-b, relay-a]
+routing:
+  claude:
+    active: work
+    fallbacks: [default]
+"#,
+    )
+    .expect("write config");
+
+    let preview = aio_coding_hub_lib::test_support::config_apply_file_json(&handle, &path, true)
+        .expect("dry run");
+    assert!(!json_bool(&preview, "applied"));
+    assert_eq!(json_array(preview["changes"].clone()).len(), 5);
+    let providers = aio_coding_hub_lib::test_support::providers_list_by_cli_json(&handle, "claude")
+        .expect("list providers");
+    assert!(json_array(providers).is_empty(), "dry run must not write");
+
+    let applied = aio_coding_hub_lib::test_support::config_apply_file_json(&handle, &path, false)
+        .expect("apply");
+    assert!(json_bool(&applied, "applied"));
+    let providers = json_array(
+        aio_coding_hub_lib::test_support::providers_list_by_cli_json(&handle, "claude")
+            .expect("list providers"),
+    );
+    assert_eq!(providers.len(), 2);
+    let modes = json_array(
+        aio_coding_hub_lib::test_support::sort_modes_list_json(&handle).expect("list modes"),
+    );
+    assert_eq!(json_str(&modes[0], "name"), "work");
+    let chain = aio_coding_hub_lib::test_support::sort_mode_fallback_get_json(&handle, "claude")
+        .expect("fallback chain");
+    assert_eq!(chain["mode_ids"], serde_json::json!([null]));
+
+    let again = aio_coding_hub_lib::test_support::config_apply_file_json(&handle, &path, false)
+        .expect("re-apply");
+    assert!(json_array(again["changes"].clone()).is_empty());
+    assert_eq!(json_i64(&again, "unchanged"), 5);
+
+    std::fs::write(
+        &path,
+        r#"
+version: 1
+prune: true
+providers:
+  - cli_key: claude
+    name: relay-a
+    priority: 20
+"#,
+    )
+    .expect("write config");
+    let pruned = aio_coding_hub_lib::test_support::config_apply_file_json(&handle, &path, false)
+        .expect("prune");
+    let changes = json_array(pruned["changes"].clone());
+    assert_eq!(changes.len(), 2);
+    assert_eq!(json_str(&changes[0], "action"), "update");
+    assert_eq!(changes[0]["fields"], serde_json::json!(["priority"]));
+    assert_eq!(json_str(&changes[1], "key"), "relay-b");
+    assert_eq!(json_str(&changes[1], "action"), "archive");
+    let providers = json_array(
+        aio_coding_hub_lib::test_support::providers_list_by_cli_json(&handle, "claude")
+            .expect("list providers"),
+    );
+    assert_eq!(providers.len(), 1);
+    assert_eq!(json_u64(&providers[0], "priority"), 20);
+}
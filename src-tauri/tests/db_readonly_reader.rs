@@ -0,0 +1,52 @@
+mod support;
+
+#[test]
+fn companion_reader_sees_stats_views_and_cannot_write_in_readonly_mode() {
+    let app = support::TestApp::new();
+    let handle = app.handle();
+
+    aio_coding_hub_lib::test_support::init_db(&handle).expect("init db");
+    let db_path = aio_coding_hub_lib::test_support::db_path(&handle).expect("db_path");
+
+    let stats =
+        aio_coding_hub_lib::test_support::stats_reader_daily_json(&db_path, &["--readonly"])
+            .expect("read stats views");
+    assert_eq!(
+        stats.get("stats_views_version").and_then(|v| v.as_i64()),
+        Some(1)
+    );
+    assert_eq!(
+        stats
+            .get("daily")
+            .and_then(|v| v.as_array())
+            .map(|rows| rows.len()),
+        Some(0)
+    );
+
+    let err = aio_coding_hub_lib::test_support::stats_reader_execute(
+        &db_path,
+        &["--readonly"],
+        "DELETE FROM request_logs",
+    )
+    .expect_err("readonly connection must reject writes");
+    assert!(err.to_string().contains("DB_ERROR"), "{err}");
+
+    let deleted = aio_coding_hub_lib::test_support::stats_reader_execute(
+        &db_path,
+        &[],
+        "DELETE FROM request_logs",
+    )
+    .expect("read-write connection may write");
+    assert_eq!(deleted, 0);
+}
+
+#[test]
+fn companion_reader_refuses_missing_database() {
+    let app = support::TestApp::new();
+    let missing = app.home_dir().join("missing.db");
+    assert!(
+        aio_coding_hub_lib::test_support::stats_reader_daily_json(&missing, &["--readonly"])
+            .is_err()
+    );
+    assert!(!missing.exists());
+}
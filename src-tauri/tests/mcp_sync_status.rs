@@ -0,0 +1,95 @@
+mod support;
+
+use serde_json::json;
+
+fn claude_status(handle: &tauri::AppHandle<tauri::test::MockRuntime>) -> serde_json::Value {
+    let rows = aio_coding_hub_lib::test_support::mcp_sync_status_json(handle, Some("claude"))
+        .expect("claude sync status");
+    support::json_array(rows)
+        .into_iter()
+        .next()
+        .expect("claude status row")
+}
+
+#[test]
+fn mcp_sync_status_reports_external_entries_and_adopts_them() {
+    let app = support::TestApp::new();
+    let handle = app.handle();
+
+    let source = json!({
+      "mcpServers": {
+        "fetch": {
+          "type": "stdio",
+          "command": "uvx",
+          "args": ["mcp-server-fetch"]
+        }
+      }
+    });
+    let bytes = serde_json::to_vec(&source).expect("json bytes");
+    aio_coding_hub_lib::test_support::mcp_restore_target_bytes(&handle, "claude", Some(bytes))
+        .expect("write claude target");
+
+    let status = claude_status(&handle);
+    assert!(support::json_bool(&status, "target_exists"));
+    assert!(status.get("parse_error").is_some_and(|v| v.is_null()));
+    let servers = support::json_array(status.get("servers").cloned().unwrap_or_default());
+    assert_eq!(servers.len(), 1);
+    assert_eq!(support::json_str(&servers[0], "server_key"), "fetch");
+    assert_eq!(support::json_str(&servers[0], "status"), "external");
+
+    let adopted = aio_coding_hub_lib::test_support::mcp_sync_adopt_json(
+        &handle,
+        "claude",
+        vec!["fetch".to_string()],
+    )
+    .expect("adopt fetch");
+    assert!(support::json_bool(&adopted, "in_sync"));
+    let servers = support::json_array(adopted.get("servers").cloned().unwrap_or_default());
+    assert_eq!(servers.len(), 1);
+    assert_eq!(support::json_str(&servers[0], "status"), "in_sync");
+
+    let workspace_id =
+        aio_coding_hub_lib::test_support::workspace_active_id_by_cli(&handle, "claude")
+            .expect("claude active workspace");
+    let rows = aio_coding_hub_lib::test_support::mcp_servers_list_json(&handle, workspace_id)
+        .expect("list adopted rows");
+    let rows = support::json_array(rows);
+    assert_eq!(rows.len(), 1);
+    assert_eq!(support::json_str(&rows[0], "server_key"), "fetch");
+    assert!(support::json_bool(&rows[0], "enabled"));
+}
+
+#[test]
+fn mcp_sync_status_flags_hand_edited_managed_entries() {
+    let app = support::TestApp::new();
+    let handle = app.handle();
+
+    let source = json!({
+      "mcpServers": {
+        "fetch": { "type": "stdio", "command": "uvx", "args": ["mcp-server-fetch"] }
+      }
+    });
+    let bytes = serde_json::to_vec(&source).expect("json bytes");
+    aio_coding_hub_lib::test_support::mcp_restore_target_bytes(&handle, "claude", Some(bytes))
+        .expect("write claude target");
+    aio_coding_hub_lib::test_support::mcp_sync_adopt_json(
+        &handle,
+        "claude",
+        vec!["fetch".to_string()],
+    )
+    .expect("adopt fetch");
+
+    let edited = json!({
+      "mcpServers": {
+        "fetch": { "type": "stdio", "command": "uvx", "args": ["mcp-server-fetch", "--verbose"] }
+      }
+    });
+    let bytes = serde_json::to_vec(&edited).expect("json bytes");
+    aio_coding_hub_lib::test_support::mcp_restore_target_bytes(&handle, "claude", Some(bytes))
+        .expect("hand edit claude target");
+
+    let status = claude_status(&handle);
+    assert!(!support::json_bool(&status, "in_sync"));
+    let servers = support::json_array(status.get("servers").cloned().unwrap_or_default());
+    assert_eq!(support::json_str(&servers[0], "status"), "modified");
+}
@@ -139,3 +139,213 @@ fn providers_crud_roundtrip() {
         "unexpected error: {err}"
     );
 }
+
+#[test]
+fn providers_list_reports_active_and_next_maintenance_window() {
+    let app = support::TestApp::new();
+    let handle = app.handle();
+
+    let provider = aio_coding_hub_lib::test_support::provider_upsert_json(
+        &handle,
+        ProviderUpsertJsonInput {
+            provider_id: None,
+            cli_key: "claude".to_string(),
+            name: "Maintained".to_string(),
+            base_urls: vec!["https://api.anthropic.com".to_string()],
+            base_url_mode: "order".to_string(),
+            api_key: Some("k".to_string()),
+            enabled: true,
+            cost_multiplier: 1.0,
+            priority: Some(100),
+            claude_models: None,
+            limit_5h_usd: None,
+            limit_daily_usd: None,
+            daily_reset_mode: None,
+            daily_reset_time: None,
+            limit_weekly_usd: None,
+            limit_monthly_usd: None,
+            limit_total_usd: None,
+        },
+    )
+    .expect("insert provider");
+    let provider_id = json_i64(&provider, "id");
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("system time")
+        .as_secs() as i64;
+
+    let active = aio_coding_hub_lib::test_support::provider_maintenance_window_upsert_json(
+        &handle,
+        provider_id,
+        now - 60,
+        now + 3600,
+        None,
+    )
+    .expect("insert active window");
+    let upcoming = aio_coding_hub_lib::test_support::provider_maintenance_window_upsert_json(
+        &handle,
+        provider_id,
+        now + 86_400,
+        now + 90_000,
+        None,
+    )
+    .expect("insert upcoming window");
+
+    aio_coding_hub_lib::test_support::provider_maintenance_window_upsert_json(
+        &handle,
+        provider_id,
+        now + 100,
+        now + 50,
+        None,
+    )
+    .expect_err("ends_at before starts_at should fail");
+
+    let list = aio_coding_hub_lib::test_support::providers_list_by_cli_json(&handle, "claude")
+        .expect("list providers");
+    let list = json_array(list);
+    assert_eq!(list.len(), 1);
+    assert_eq!(
+        json_i64(&list[0]["maintenance_active"], "window_id"),
+        json_i64(&active, "id")
+    );
+    assert_eq!(
+        json_i64(&list[0]["maintenance_next"], "window_id"),
+        json_i64(&upcoming, "id")
+    );
+}
+
+fn insert_claude_provider(handle: &tauri::AppHandle<tauri::test::MockRuntime>, name: &str) -> i64 {
+    let provider = aio_coding_hub_lib::test_support::provider_upsert_json(
+        handle,
+        ProviderUpsertJsonInput {
+            provider_id: None,
+            cli_key: "claude".to_string(),
+            name: name.to_string(),
+            base_urls: vec!["https://api.anthropic.com".to_string()],
+            base_url_mode: "order".to_string(),
+            api_key: Some("k".to_string()),
+            enabled: true,
+            cost_multiplier: 2.0,
+            priority: Some(100),
+            claude_models: None,
+            limit_5h_usd: None,
+            limit_daily_usd: None,
+            daily_reset_mode: None,
+            daily_reset_time: None,
+            limit_weekly_usd: None,
+            limit_monthly_usd: None,
+            limit_total_usd: None,
+        },
+    )
+    .expect("insert provider");
+    json_i64(&provider, "id")
+}
+
+#[test]
+fn providers_bulk_update_dry_run_and_atomic_apply() {
+    let app = support::TestApp::new();
+    let handle = app.handle();
+
+    let id1 = insert_claude_provider(&handle, "P1");
+    let id2 = insert_claude_provider(&handle, "P2");
+    let mode = aio_coding_hub_lib::test_support::sort_mode_create_json(&handle, "Work")
+        .expect("create sort mode");
+    let mode_id = json_i64(&mode, "id");
+
+    let input = serde_json::json!({
+        "provider_ids": [id1, id2],
+        "enabled": false,
+        "tags": ["relay", " relay ", "cheap"],
+        "cost_multiplier": null,
+        "cost_multiplier_scale": 0.5,
+        "remove_from_sort_mode_id": null,
+        "add_to_sort_mode_id": mode_id,
+    });
+
+    let preview =
+        aio_coding_hub_lib::test_support::providers_bulk_update_json(&handle, input.clone(), true)
+            .expect("dry run");
+    assert!(json_bool(&preview, "dry_run"));
+    assert!(!json_bool(&preview, "applied"));
+    let outcomes = json_array(preview["outcomes"].clone());
+    assert_eq!(outcomes.len(), 2);
+    assert!(outcomes.iter().all(|o| json_bool(o, "ok")));
+    assert_eq!(json_array(outcomes[0]["changes"].clone()).len(), 4);
+
+    let list = aio_coding_hub_lib::test_support::providers_list_by_cli_json(&handle, "claude")
+        .expect("list providers");
+    assert!(json_array(list).iter().all(|p| json_bool(p, "enabled")));
+
+    // One invalid provider keeps the whole batch from being written.
+    let mut with_missing = input.clone();
+    with_missing["provider_ids"] = serde_json::json!([id1, 999_999]);
+    let rejected =
+        aio_coding_hub_lib::test_support::providers_bulk_update_json(&handle, with_missing, false)
+            .expect("bulk update with missing provider");
+    assert!(!json_bool(&rejected, "applied"));
+    let outcomes = json_array(rejected["outcomes"].clone());
+    assert!(json_bool(&outcomes[0], "ok"));
+    assert!(!json_bool(&outcomes[1], "ok"));
+    let list = aio_coding_hub_lib::test_support::providers_list_by_cli_json(&handle, "claude")
+        .expect("list providers");
+    assert!(json_array(list).iter().all(|p| json_bool(p, "enabled")));
+
+    let applied =
+        aio_coding_hub_lib::test_support::providers_bulk_update_json(&handle, input, false)
+            .expect("apply");
+    assert!(json_bool(&applied, "applied"));
+    let list = aio_coding_hub_lib::test_support::providers_list_by_cli_json(&handle, "claude")
+        .expect("list providers");
+    for provider in json_array(list) {
+        assert!(!json_bool(&provider, "enabled"));
+        assert_eq!(json_f64(&provider, "cost_multiplier"), Some(1.0));
+        assert_eq!(provider["tags"], serde_json::json!(["relay", "cheap"]));
+    }
+
+    let invalid = serde_json::json!({
+        "provider_ids": [id1],
+        "enabled": null,
+        "tags": null,
+        "cost_multiplier": 1.0,
+        "cost_multiplier_scale": 2.0,
+        "remove_from_sort_mode_id": null,
+        "add_to_sort_mode_id": null,
+    });
+    aio_coding_hub_lib::test_support::providers_bulk_update_json(&handle, invalid, true)
+        .expect_err("set and scale together should fail");
+}
+
+#[test]
+fn provider_archive_hides_then_purge_deletes() {
+    let app = support::TestApp::new();
+    let handle = app.handle();
+    let id = insert_claude_provider(&handle, "Archived");
+
+    let archived = aio_coding_hub_lib::test_support::provider_archive_json(&handle, id)
+        .expect("archive provider");
+    assert!(!json_bool(&archived, "enabled"));
+    assert!(archived["archived_at"].is_i64());
+    let list = aio_coding_hub_lib::test_support::providers_list_by_cli_json(&handle, "claude")
+        .expect("list providers");
+    assert!(json_array(list).is_empty());
+
+    let err = aio_coding_hub_lib::test_support::provider_set_enabled_json(&handle, id, true)
+        .expect_err("archived provider cannot be enabled");
+    assert!(
+        err.to_string().contains("archived"),
+        "unexpected error: {err}"
+    );
+
+    let restored = aio_coding_hub_lib::test_support::provider_restore_json(&handle, id)
+        .expect("restore provider");
+    assert!(restored["archived_at"].is_null());
+    assert!(!json_bool(&restored, "enabled"));
+    aio_coding_hub_lib::test_support::provider_purge(&handle, id)
+        .expect_err("active provider cannot be purged");
+
+    aio_coding_hub_lib::test_support::provider_archive_json(&handle, id).expect("archive again");
+    assert!(aio_coding_hub_lib::test_support::provider_purge(&handle, id).expect("purge"));
+    aio_coding_hub_lib::test_support::provider_restore_json(&handle, id)
+        .expect_err("purged provider is gone");
+}
@@ -151,3 +151,49 @@ fn sort_mode_delete_nonexistent_fails() {
     let err = err.to_string();
     assert!(err.contains("not found"), "unexpected error: {err}");
 }
+
+#[test]
+fn sort_mode_fallback_chain_roundtrip_and_prunes_deleted_modes() {
+    let app = support::TestApp::new();
+    let handle = app.handle();
+
+    aio_coding_hub_lib::test_support::init_db(&handle).expect("init db");
+
+    let a = aio_coding_hub_lib::test_support::sort_mode_create_json(&handle, "Mode A")
+        .expect("create mode a");
+    let b = aio_coding_hub_lib::test_support::sort_mode_create_json(&handle, "Mode B")
+        .expect("create mode b");
+    let a_id = json_i64(&a, "id");
+    let b_id = json_i64(&b, "id");
+
+    let chain = aio_coding_hub_lib::test_support::sort_mode_fallback_set_json(
+        &handle,
+        "claude",
+        vec![Some(b_id), Some(a_id), None],
+    )
+    .expect("set fallback chain");
+    assert_eq!(
+        chain["mode_ids"],
+        serde_json::json!([b_id, a_id, serde_json::Value::Null])
+    );
+
+    let err = aio_coding_hub_lib::test_support::sort_mode_fallback_set_json(
+        &handle,
+        "claude",
+        vec![Some(a_id), Some(a_id)],
+    )
+    .expect_err("duplicate mode should fail");
+    assert!(
+        err.to_string().contains("duplicate"),
+        "unexpected error: {err}"
+    );
+
+    aio_coding_hub_lib::test_support::sort_mode_delete(&handle, b_id).expect("delete mode b");
+
+    let chain = aio_coding_hub_lib::test_support::sort_mode_fallback_get_json(&handle, "claude")
+        .expect("get fallback chain");
+    assert_eq!(
+        chain["mode_ids"],
+        serde_json::json!([a_id, serde_json::Value::Null])
+    );
+}
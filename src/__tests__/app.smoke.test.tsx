@@ -25,6 +25,10 @@ vi.mock("../services/noticeEvents", () => ({
   listenNoticeEvents: vi.fn().mockResolvedValue(() => {}),
 }));
 
+vi.mock("../services/appSelftest", () => ({
+  listenAppSelftestEvents: vi.fn().mockResolvedValue(() => {}),
+}));
+
 vi.mock("../services/settings", async () => {
   const actual =
     await vi.importActual<typeof import("../services/settings")>("../services/settings");
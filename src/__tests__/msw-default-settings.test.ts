@@ -6,7 +6,7 @@ describe("MSW defaults", () => {
     resetMswState();
 
     expect(getSettingsState()).toEqual({
-      schema_version: 19,
+      schema_version: 34,
       preferred_port: 37123,
       gateway_listen_mode: "localhost",
       gateway_custom_listen_address: "",
@@ -14,21 +14,46 @@ describe("MSW defaults", () => {
       wsl_target_cli: { claude: true, codex: true, gemini: true },
       wsl_host_address_mode: "auto",
       wsl_custom_host_address: "127.0.0.1",
+      gateway_access_token: "",
       auto_start: false,
       start_minimized: false,
       tray_enabled: true,
       enable_cli_proxy_startup_recovery: true,
       log_retention_days: 7,
+      attempt_log_retention_days: 30,
       provider_cooldown_seconds: 30,
       provider_base_url_ping_cache_ttl_seconds: 60,
       upstream_first_byte_timeout_seconds: 0,
       upstream_stream_idle_timeout_seconds: 0,
       upstream_request_timeout_non_streaming_seconds: 0,
+      count_tokens_hedge_delay_ms: 0,
+      count_tokens_local_estimate_enabled: false,
+      first_byte_race_delay_ms: 0,
+      non_streaming_hedge_delay_ms: 0,
+      upstream_warm_pool_enabled: false,
+      upstream_warm_pool_size: 2,
+      request_mirror_enabled: false,
+      request_mirror_provider_id: 0,
+      request_mirror_percent: 10,
+      request_dedup_enabled: false,
+      stream_truncation_recovery_enabled: false,
+      sse_coalesce_flush_ms: 0,
+      sse_coalesce_flush_bytes: 16384,
+      stream_stall_warn_seconds: 60,
+      stream_stall_keepalive_enabled: false,
+      stream_first_byte_failover_enabled: true,
+      stream_error_event_enabled: true,
       update_releases_url: "https://github.com/dyndynjyxa/aio-coding-hub/releases",
       failover_max_attempts_per_provider: 5,
       failover_max_providers_to_try: 5,
       circuit_breaker_failure_threshold: 5,
       circuit_breaker_open_duration_minutes: 30,
+      circuit_breaker_half_open_probe_enabled: false,
+      retry_backoff: {
+        rate_limited: { strategy: "linear", base_ms: 80, max_ms: 800 },
+        upstream_error: { strategy: "none", base_ms: 0, max_ms: 0 },
+        network_error: { strategy: "none", base_ms: 0, max_ms: 0 },
+      },
       enable_circuit_breaker_notice: false,
       verbose_provider_error: true,
       intercept_anthropic_warmup_requests: true,
@@ -44,6 +69,29 @@ describe("MSW defaults", () => {
       response_fixer_fix_truncated_json: true,
       response_fixer_max_json_depth: 200,
       response_fixer_max_fix_size: 1048576,
+      response_fixer_spill_to_disk: false,
+      response_fixer_spill_max_size: 8388608,
+      response_fixer_transcode_charsets: ["gbk", "big5", "shift_jis"],
+      redaction_rules: [],
+      env_conflict_ignore_rules: [],
+      model_denylist: [],
+      model_capabilities: [],
+      capability_gate_policy: "skip_provider",
+      storage_postgres_url: "",
+      ui_language: "zh",
+      captured_response_headers: [],
+      upstream_error_body_capture_bytes: 2048,
+      session_binding_ttl_seconds: 300,
+      access_log_format: "off",
+      access_log_retention_days: 14,
+      enable_cost_anomaly_detection: true,
+      cost_anomaly_multiplier: 5,
+      cost_anomaly_auto_throttle: false,
+      observer_mode: false,
+      request_log_sample_percent: 100,
+      request_log_slow_threshold_ms: 30000,
+      notification_sinks: [],
+      ssh_hosts: [],
     });
   });
 });
@@ -4,6 +4,7 @@ import { useGatewayQuerySync } from "../hooks/useGatewayQuerySync";
 import { useStartupTask } from "../hooks/useStartupTask";
 import { logToConsole } from "../services/consoleLog";
 import { listenAppHeartbeat } from "../services/appHeartbeat";
+import { listenAppSelftestEvents } from "../services/appSelftest";
 import { setCacheAnomalyMonitorEnabled } from "../services/cacheAnomalyMonitor";
 import { listenGatewayEvents } from "../services/gatewayEvents";
 import { listenNoticeEvents } from "../services/noticeEvents";
@@ -23,6 +24,11 @@ export function useAppBootstrap() {
   useAsyncListener(listenAppHeartbeat, "listenAppHeartbeat", "应用心跳监听初始化失败");
   useAsyncListener(listenGatewayEvents, "listenGatewayEvents", "网关事件监听初始化失败");
   useAsyncListener(listenNoticeEvents, "listenNoticeEvents", "通知事件监听初始化失败");
+  useAsyncListener(
+    listenAppSelftestEvents,
+    "listenAppSelftestEvents",
+    "启动自检事件监听初始化失败"
+  );
   useAsyncListener(
     listenTaskCompleteNotifyEvents,
     "listenTaskCompleteNotifyEvents",
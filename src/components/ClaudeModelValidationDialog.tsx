@@ -1923,7 +1923,11 @@ export function ClaudeModelValidationDialog({
                   </div>
                   <div className="flex items-center gap-2">
                     <span className="font-semibold text-slate-900 dark:text-slate-100">
-                      {provider.base_url_mode === "ping" ? "自动测速" : "顺序轮询"}
+                      {provider.base_url_mode === "ping"
+                        ? "自动测速"
+                        : provider.base_url_mode === "race"
+                          ? "双地址竞速"
+                          : "顺序轮询"}
                     </span>
                     <span className="inline-flex items-center rounded-md bg-slate-100/80 dark:bg-slate-800 px-2 py-0.5 text-xs font-medium text-slate-600 dark:text-slate-300 ring-1 ring-inset ring-slate-200 dark:ring-slate-700">
                       {provider.base_urls.length} 个地址
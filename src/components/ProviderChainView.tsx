@@ -11,6 +11,9 @@ export type ProviderChainAttemptLog = {
   status: number | null;
   attempt_started_ms?: number | null;
   attempt_duration_ms?: number | null;
+  bytes_received?: number | null;
+  chunk_count?: number | null;
+  max_chunk_gap_ms?: number | null;
 };
 
 type ProviderChainAttemptJson = {
@@ -34,6 +37,9 @@ type ProviderChainAttemptJson = {
   circuit_state_after?: string | null;
   circuit_failure_count?: number | null;
   circuit_failure_threshold?: number | null;
+  bytes_received?: number | null;
+  chunk_count?: number | null;
+  max_chunk_gap_ms?: number | null;
 };
 
 type ProviderChainAttempt = {
@@ -58,6 +64,9 @@ type ProviderChainAttempt = {
   circuit_state_after: string | null;
   circuit_failure_count: number | null;
   circuit_failure_threshold: number | null;
+  bytes_received: number | null;
+  chunk_count: number | null;
+  max_chunk_gap_ms: number | null;
 };
 
 export function ProviderChainView({
@@ -112,6 +121,9 @@ export function ProviderChainView({
         circuit_state_after: a.circuit_state_after ?? null,
         circuit_failure_count: a.circuit_failure_count ?? null,
         circuit_failure_threshold: a.circuit_failure_threshold ?? null,
+        bytes_received: a.bytes_received ?? null,
+        chunk_count: a.chunk_count ?? null,
+        max_chunk_gap_ms: a.max_chunk_gap_ms ?? null,
       }));
     }
 
@@ -149,6 +161,9 @@ export function ProviderChainView({
           circuit_state_after: json?.circuit_state_after ?? null,
           circuit_failure_count: json?.circuit_failure_count ?? null,
           circuit_failure_threshold: json?.circuit_failure_threshold ?? null,
+          bytes_received: log.bytes_received ?? json?.bytes_received ?? null,
+          chunk_count: log.chunk_count ?? json?.chunk_count ?? null,
+          max_chunk_gap_ms: log.max_chunk_gap_ms ?? json?.max_chunk_gap_ms ?? null,
         };
       });
 
@@ -308,6 +323,12 @@ export function ProviderChainView({
                       耗时 {attempt.attempt_duration_ms}ms
                     </span>
                   ) : null}
+                  {attempt.chunk_count != null ? (
+                    <span className="rounded-full bg-slate-100 dark:bg-slate-700 px-2 py-0.5 text-xs text-slate-700 dark:text-slate-300">
+                      {attempt.chunk_count} chunks · {attempt.bytes_received ?? 0}B · 最大间隔{" "}
+                      {attempt.max_chunk_gap_ms ?? 0}ms
+                    </span>
+                  ) : null}
                 </div>
 
                 {attempt.base_url ? (
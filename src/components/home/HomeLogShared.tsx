@@ -29,6 +29,9 @@ const ERROR_CODE_LABELS: Record<string, string> = {
   [GatewayErrorCodes.RESPONSE_BUILD_ERROR]: "响应构建错误",
   [GatewayErrorCodes.PROVIDER_RATE_LIMITED]: "供应商限额",
   [GatewayErrorCodes.PROVIDER_CIRCUIT_OPEN]: "供应商熔断",
+  [GatewayErrorCodes.PROVIDER_MAINTENANCE]: "供应商维护",
+  [GatewayErrorCodes.PROVIDER_MODEL_UNSUPPORTED]: "模型不支持",
+  [GatewayErrorCodes.UNAUTHORIZED]: "未授权访问",
 };
 
 const CLIENT_ABORT_ERROR_CODES: ReadonlySet<string> = new Set([
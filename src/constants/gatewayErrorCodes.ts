@@ -24,8 +24,16 @@ export const GatewayErrorCodes = {
   RESPONSE_BUILD_ERROR: "GW_RESPONSE_BUILD_ERROR",
   PROVIDER_RATE_LIMITED: "GW_PROVIDER_RATE_LIMITED",
   PROVIDER_CIRCUIT_OPEN: "GW_PROVIDER_CIRCUIT_OPEN",
+  PROVIDER_MAINTENANCE: "GW_PROVIDER_MAINTENANCE",
+  PROVIDER_MODEL_UNSUPPORTED: "GW_PROVIDER_MODEL_UNSUPPORTED",
   CLI_PROXY_DISABLED: "GW_CLI_PROXY_DISABLED",
   CLI_PROXY_GUARD_ERROR: "GW_CLI_PROXY_GUARD_ERROR",
+  ROUTING_PAUSED: "GW_ROUTING_PAUSED",
+  SESSION_THROTTLED: "GW_SESSION_THROTTLED",
+  SESSION_FAILURE_BUDGET_EXCEEDED: "GW_SESSION_FAILURE_BUDGET_EXCEEDED",
+  MODEL_DENIED: "GW_MODEL_DENIED",
+  CONTENT_REFUSAL: "GW_CONTENT_REFUSAL",
+  UNAUTHORIZED: "GW_UNAUTHORIZED",
   HTTP_CLIENT_INIT: "GW_HTTP_CLIENT_INIT",
   ATTEMPT_LOG_CHANNEL_CLOSED: "GW_ATTEMPT_LOG_CHANNEL_CLOSED",
   ATTEMPT_LOG_ENQUEUE_TIMEOUT: "GW_ATTEMPT_LOG_ENQUEUE_TIMEOUT",
@@ -128,6 +136,14 @@ export const GatewayErrorDescriptions: Record<string, GatewayErrorDescription> =
     desc: "Provider 已熔断",
     suggestion: "该 Provider 因连续失败已被熔断，请求已自动跳过。熔断将在设定时间后自动恢复。",
   },
+  GW_PROVIDER_MAINTENANCE: {
+    desc: "Provider 维护中",
+    suggestion: "该 Provider 处于计划维护时段，请求已自动跳过。维护结束后将自动恢复。",
+  },
+  GW_PROVIDER_MODEL_UNSUPPORTED: {
+    desc: "Provider 不支持该模型",
+    suggestion: "聚合型 Provider 的模型目录中不包含请求的模型，已自动跳过。可在供应商设置中刷新模型目录或检查模型名称。",
+  },
   GW_CLI_PROXY_DISABLED: {
     desc: "CLI Proxy 未启用",
     suggestion: "该 CLI 的代理功能未启用。请在设置中启用对应 CLI 的代理。",
@@ -136,6 +152,30 @@ export const GatewayErrorDescriptions: Record<string, GatewayErrorDescription> =
     desc: "CLI Proxy 守卫错误",
     suggestion: "CLI Proxy 在处理请求时发生内部错误。请重试或查看日志。",
   },
+  GW_ROUTING_PAUSED: {
+    desc: "网关路由已暂停",
+    suggestion: "已在托盘菜单中开启「暂停所有路由」，网关对所有请求返回 503。取消勾选后即可恢复。",
+  },
+  GW_SESSION_THROTTLED: {
+    desc: "会话因费用异常被限速",
+    suggestion: "该会话近 30 分钟花费远超其平均水平，已被自动限速（每分钟 1 个请求）。确认该异常后即可恢复。",
+  },
+  GW_SESSION_FAILURE_BUDGET_EXCEEDED: {
+    desc: "会话失败次数超出预算",
+    suggestion: "该会话 5 分钟内失败尝试已达 20 次，网关返回 429 以避免重试风暴耗尽供应商冷却。请检查请求内容或供应商状态，稍后重试。",
+  },
+  GW_MODEL_DENIED: {
+    desc: "模型已被黑名单禁用",
+    suggestion: "请求的模型命中了该 CLI 的模型黑名单规则（精确名称或 * 通配），网关在选择供应商前直接返回 403。如需使用该模型，请在设置中移除对应规则。",
+  },
+  GW_CONTENT_REFUSAL: {
+    desc: "上游返回拒答内容",
+    suggestion: "供应商的 2xx 响应开头命中了该供应商配置的拒答关键词，网关已切换到下一个供应商（每个请求最多 2 次）。若误判，请在供应商设置中调整拒答关键词。",
+  },
+  GW_UNAUTHORIZED: {
+    desc: "网关访问令牌无效",
+    suggestion: "非本机客户端需携带网关访问令牌（x-aio-gateway-key 或 Authorization: Bearer）。请在设置中查看令牌，或重新同步 CLI 代理配置。",
+  },
   GW_HTTP_CLIENT_INIT: {
     desc: "HTTP 客户端初始化失败",
     suggestion: "无法创建 HTTP 客户端。可能是系统资源不足或 TLS 配置问题。",
@@ -21,81 +21,4217 @@ export const commands = {
       else return { status: "error", error: e as any };
     }
   },
-  async providersList(cliKey: string): Promise<Result<ProviderSummary[], string>> {
+  async settingsGatewayRectifierSet(
+    verboseProviderError: boolean,
+    interceptAnthropicWarmupRequests: boolean,
+    enableThinkingSignatureRectifier: boolean,
+    enableThinkingBudgetRectifier: boolean,
+    enableClaudeMetadataUserIdInjection: boolean,
+    enableResponseFixer: boolean,
+    responseFixerFixEncoding: boolean,
+    responseFixerFixSseFormat: boolean,
+    responseFixerFixTruncatedJson: boolean,
+    responseFixerMaxJsonDepth: number,
+    responseFixerMaxFixSize: number,
+    responseFixerSpillToDisk: boolean | null,
+    responseFixerSpillMaxSize: number | null,
+    responseFixerTranscodeCharsets: ResponseFixerCharset[] | null,
+  ): Promise<Result<AppSettings, string>> {
     try {
-      return { status: "ok", data: await TAURI_INVOKE("providers_list", { cliKey }) };
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("settings_gateway_rectifier_set", {
+          verboseProviderError,
+          interceptAnthropicWarmupRequests,
+          enableThinkingSignatureRectifier,
+          enableThinkingBudgetRectifier,
+          enableClaudeMetadataUserIdInjection,
+          enableResponseFixer,
+          responseFixerFixEncoding,
+          responseFixerFixSseFormat,
+          responseFixerFixTruncatedJson,
+          responseFixerMaxJsonDepth,
+          responseFixerMaxFixSize,
+          responseFixerSpillToDisk,
+          responseFixerSpillMaxSize,
+          responseFixerTranscodeCharsets,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async settingsCircuitBreakerNoticeSet(
+    enableCircuitBreakerNotice: boolean,
+  ): Promise<Result<AppSettings, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("settings_circuit_breaker_notice_set", {
+          enableCircuitBreakerNotice,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async settingsCodexSessionIdCompletionSet(
+    enableCodexSessionIdCompletion: boolean,
+  ): Promise<Result<AppSettings, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("settings_codex_session_id_completion_set", {
+          enableCodexSessionIdCompletion,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async settingsGatewayAccessTokenRotate(): Promise<Result<AppSettings, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("settings_gateway_access_token_rotate") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async settingsGatewayAccessTokenClear(): Promise<Result<AppSettings, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("settings_gateway_access_token_clear") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async settingsRedactionRulesSet(
+    redactionRules: RedactionRule[],
+  ): Promise<Result<AppSettings, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("settings_redaction_rules_set", { redactionRules }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async settingsEnvConflictIgnoreRulesSet(
+    envConflictIgnoreRules: EnvConflictIgnoreRule[],
+  ): Promise<Result<AppSettings, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("settings_env_conflict_ignore_rules_set", {
+          envConflictIgnoreRules,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async settingsModelDenylistSet(
+    modelDenylist: ModelDenyRule[],
+  ): Promise<Result<AppSettings, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("settings_model_denylist_set", { modelDenylist }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async settingsModelCapabilitiesSet(
+    modelCapabilities: ModelCapabilityRule[],
+    capabilityGatePolicy: CapabilityGatePolicy,
+  ): Promise<Result<AppSettings, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("settings_model_capabilities_set", {
+          modelCapabilities,
+          capabilityGatePolicy,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async settingsCapturedResponseHeadersSet(
+    capturedResponseHeaders: string[],
+  ): Promise<Result<AppSettings, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("settings_captured_response_headers_set", {
+          capturedResponseHeaders,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async settingsUpstreamErrorBodyCaptureSet(
+    upstreamErrorBodyCaptureBytes: number,
+  ): Promise<Result<AppSettings, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("settings_upstream_error_body_capture_set", {
+          upstreamErrorBodyCaptureBytes,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async settingsSessionBindingTtlSet(
+    sessionBindingTtlSeconds: number,
+  ): Promise<Result<AppSettings, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("settings_session_binding_ttl_set", { sessionBindingTtlSeconds }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async settingsRetryBackoffSet(
+    retryBackoff: RetryBackoffSettings,
+  ): Promise<Result<AppSettings, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("settings_retry_backoff_set", { retryBackoff }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async settingsAccessLogSet(
+    accessLogFormat: AccessLogFormat,
+    accessLogRetentionDays: number,
+  ): Promise<Result<AppSettings, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("settings_access_log_set", {
+          accessLogFormat,
+          accessLogRetentionDays,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async settingsCostAnomalySet(
+    enableCostAnomalyDetection: boolean,
+    costAnomalyMultiplier: number,
+    costAnomalyAutoThrottle: boolean,
+  ): Promise<Result<AppSettings, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("settings_cost_anomaly_set", {
+          enableCostAnomalyDetection,
+          costAnomalyMultiplier,
+          costAnomalyAutoThrottle,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async settingsObserverModeSet(observerMode: boolean): Promise<Result<AppSettings, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("settings_observer_mode_set", { observerMode }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async settingsRequestLogSamplingSet(
+    requestLogSamplePercent: number,
+    requestLogSlowThresholdMs: number,
+  ): Promise<Result<AppSettings, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("settings_request_log_sampling_set", {
+          requestLogSamplePercent,
+          requestLogSlowThresholdMs,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async settingsStoragePostgresUrlSet(
+    storagePostgresUrl: string,
+  ): Promise<Result<AppSettings, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("settings_storage_postgres_url_set", { storagePostgresUrl }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async settingsNotificationSinksSet(
+    notificationSinks: NotificationSink[],
+  ): Promise<Result<AppSettings, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("settings_notification_sinks_set", { notificationSinks }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async settingsSshHostsSet(sshHosts: SshHost[]): Promise<Result<AppSettings, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("settings_ssh_hosts_set", { sshHosts }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async appAboutGet(): Promise<AppAboutInfo> {
+    return await TAURI_INVOKE("app_about_get");
+  },
+  async appDataDirGet(): Promise<Result<string, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("app_data_dir_get") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async appExit(): Promise<Result<boolean, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("app_exit") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async appRestart(): Promise<Result<boolean, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("app_restart") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async appHeartbeatPong(): Promise<Result<boolean, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("app_heartbeat_pong") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  /**
+   * Startup self-test report; `None` until the startup sequence has finished.
+   */
+  async appSelftestReport(): Promise<SelfTestReport | null> {
+    return await TAURI_INVOKE("app_selftest_report");
+  },
+  async appFrontendErrorReport(
+    source: string,
+    message: string,
+    stack: string | null,
+    detailsJson: string | null,
+    href: string | null,
+    userAgent: string | null,
+  ): Promise<Result<boolean, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("app_frontend_error_report", {
+          source,
+          message,
+          stack,
+          detailsJson,
+          href,
+          userAgent,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async noticeSend(
+    level: NoticeLevel,
+    title: string | null,
+    body: string,
+  ): Promise<Result<boolean, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("notice_send", { level, title, body }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async notificationSinkTest(sink: NotificationSink): Promise<Result<boolean, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("notification_sink_test", { sink }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliManagerClaudeInfoGet(): Promise<Result<ClaudeCliInfo, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("cli_manager_claude_info_get") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliManagerCodexInfoGet(): Promise<Result<SimpleCliInfo, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("cli_manager_codex_info_get") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliManagerCodexConfigGet(): Promise<Result<CodexConfigState, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("cli_manager_codex_config_get") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliManagerCodexConfigSet(
+    patch: CodexConfigPatch,
+  ): Promise<Result<CodexConfigState, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("cli_manager_codex_config_set", { patch }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliManagerCodexConfigTomlGet(): Promise<Result<CodexConfigTomlState, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("cli_manager_codex_config_toml_get") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliManagerCodexConfigTomlValidate(
+    toml: string,
+  ): Promise<Result<CodexConfigTomlValidationResult, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("cli_manager_codex_config_toml_validate", { toml }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliManagerCodexConfigTomlSet(toml: string): Promise<Result<CodexConfigState, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("cli_manager_codex_config_toml_set", { toml }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async codexProfilesList(
+    workspaceId: number | null,
+  ): Promise<Result<CodexProfilesListResult, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("codex_profiles_list", { workspaceId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async codexProfileUpsert(
+    profileId: number | null,
+    name: string,
+    toml: string,
+  ): Promise<Result<CodexProfile, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("codex_profile_upsert", { profileId, name, toml }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async codexProfileDelete(profileId: number): Promise<Result<boolean, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("codex_profile_delete", { profileId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async codexProfileApply(
+    workspaceId: number,
+    profileId: number,
+  ): Promise<Result<CodexProfileApplyResult, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("codex_profile_apply", { workspaceId, profileId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliManagerGeminiInfoGet(): Promise<Result<SimpleCliInfo, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("cli_manager_gemini_info_get") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliManagerClaudeEnvSet(
+    mcpTimeoutMs: number | null,
+    disableErrorReporting: boolean,
+  ): Promise<Result<ClaudeEnvState, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("cli_manager_claude_env_set", {
+          mcpTimeoutMs,
+          disableErrorReporting,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliManagerClaudeSettingsGet(): Promise<Result<ClaudeSettingsState, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("cli_manager_claude_settings_get") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliManagerClaudeSettingsSet(
+    patch: ClaudeSettingsPatch,
+  ): Promise<Result<ClaudeSettingsState, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("cli_manager_claude_settings_set", { patch }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async gatewayStart(preferredPort: number | null): Promise<Result<GatewayStatus, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("gateway_start", { preferredPort }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  /**
+   * Moves a running gateway to `port` without a stop/start gap: the new listener is bound first,
+   * clients are re-pointed, and only then is the old listener drained (in-flight requests finish).
+   */
+  async gatewayChangePort(port: number): Promise<Result<GatewayStatus, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("gateway_change_port", { port }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async gatewayStop(): Promise<Result<GatewayStatus, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("gateway_stop") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async gatewayStatus(): Promise<GatewayStatus> {
+    return await TAURI_INVOKE("gateway_status");
+  },
+  async gatewayCheckPortAvailable(port: number): Promise<boolean> {
+    return await TAURI_INVOKE("gateway_check_port_available", { port });
+  },
+  async gatewaySessionsList(
+    limit: number | null,
+  ): Promise<Result<GatewayActiveSessionSummary[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("gateway_sessions_list", { limit }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  /**
+   * Raw routing state of live session bindings (pinned first); no request log aggregation.
+   */
+  async sessionBindingsList(
+    limit: number | null,
+  ): Promise<Result<SessionBindingSummary[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("session_bindings_list", { limit }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async sessionBindingPin(
+    cliKey: string,
+    sessionId: string,
+    providerId: number,
+  ): Promise<Result<boolean, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("session_binding_pin", { cliKey, sessionId, providerId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async sessionBindingUnpin(cliKey: string, sessionId: string): Promise<Result<boolean, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("session_binding_unpin", { cliKey, sessionId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async gatewayCircuitStatus(
+    cliKey: string,
+  ): Promise<Result<GatewayProviderCircuitStatus[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("gateway_circuit_status", { cliKey }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async gatewayCircuitResetProvider(providerId: number): Promise<Result<boolean, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("gateway_circuit_reset_provider", { providerId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async gatewayCircuitResetCli(cliKey: string): Promise<Result<number, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("gateway_circuit_reset_cli", { cliKey }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async gatewayProviderQualityV1(): Promise<GatewayProviderQuality[]> {
+    return await TAURI_INVOKE("gateway_provider_quality_v1");
+  },
+  async gatewayLiveStats(): Promise<GatewayProviderLiveStats[]> {
+    return await TAURI_INVOKE("gateway_live_stats");
+  },
+  async gatewayOauthPacingV1(): Promise<GatewayOAuthPacingState[]> {
+    return await TAURI_INVOKE("gateway_oauth_pacing_v1");
+  },
+  async errorCatalogList(): Promise<ErrorCatalogEntry[]> {
+    return await TAURI_INVOKE("error_catalog_list");
+  },
+  async wslDetect(): Promise<WslDetection> {
+    return await TAURI_INVOKE("wsl_detect");
+  },
+  async wslHostAddressGet(): Promise<string | null> {
+    return await TAURI_INVOKE("wsl_host_address_get");
+  },
+  async wslConfigStatusGet(distros: string[] | null): Promise<WslDistroConfigStatus[]> {
+    return await TAURI_INVOKE("wsl_config_status_get", { distros });
+  },
+  async wslConfigureClients(): Promise<Result<WslConfigureReport, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("wsl_configure_clients") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async wslDiagnose(): Promise<Result<WslDiagnosticsReport, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("wsl_diagnose") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async wslRepair(): Promise<Result<WslRepairReport, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("wsl_repair") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async sshHostCheck(name: string): Promise<Result<SshHostCheckReport, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("ssh_host_check", { name }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  /**
+   * Configures one host by name, or every configured host when `name` is omitted.
+   */
+  async sshConfigureClients(name: string | null): Promise<Result<SshConfigureReport, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("ssh_configure_clients", { name }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliSessionsProjectsList(
+    source: string,
+    wslDistro: string | null,
+  ): Promise<Result<CliSessionsProjectSummary[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("cli_sessions_projects_list", { source, wslDistro }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliSessionsSessionsList(
+    source: string,
+    projectId: string,
+    wslDistro: string | null,
+  ): Promise<Result<CliSessionsSessionSummary[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("cli_sessions_sessions_list", { source, projectId, wslDistro }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliSessionsMessagesGet(
+    source: string,
+    filePath: string,
+    page: number,
+    pageSize: number,
+    fromEnd: boolean | null,
+    wslDistro: string | null,
+  ): Promise<Result<CliSessionsPaginatedMessages, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("cli_sessions_messages_get", {
+          source,
+          filePath,
+          page,
+          pageSize,
+          fromEnd,
+          wslDistro,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliSessionsSessionDelete(
+    source: string,
+    filePaths: string[],
+    wslDistro: string | null,
+  ): Promise<Result<string[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("cli_sessions_session_delete", { source, filePaths, wslDistro }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliSessionsExport(
+    source: string,
+    filePath: string,
+    format: string,
+    outputPath: string,
+    wslDistro: string | null,
+  ): Promise<Result<CliSessionsExportResult, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("cli_sessions_export", {
+          source,
+          filePath,
+          format,
+          outputPath,
+          wslDistro,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providersList(
+    cliKey: string,
+    includeArchived: boolean | null,
+  ): Promise<Result<ProviderSummary[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("providers_list", { cliKey, includeArchived }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  /**
+   * Credential health of every provider (all clis when `cli_key` is omitted).
+   */
+  async providersCredentialStatus(
+    cliKey: string | null,
+  ): Promise<Result<ProviderCredentialStatus[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("providers_credential_status", { cliKey }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerUpsert(input: ProviderUpsertInput): Promise<Result<ProviderSummary, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("provider_upsert", { input }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerSetEnabled(
+    providerId: number,
+    enabled: boolean,
+  ): Promise<Result<ProviderSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_set_enabled", { providerId, enabled }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerSetLastResort(
+    providerId: number,
+    lastResort: boolean,
+  ): Promise<Result<ProviderSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_set_last_resort", { providerId, lastResort }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerSetEmulateModelsEndpoint(
+    providerId: number,
+    enabled: boolean,
+  ): Promise<Result<ProviderSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_set_emulate_models_endpoint", { providerId, enabled }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerSetThinkingBudgetCap(
+    providerId: number,
+    thinkingBudgetCap: number | null,
+  ): Promise<Result<ProviderSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_set_thinking_budget_cap", {
+          providerId,
+          thinkingBudgetCap,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerSetRefusalPatterns(
+    providerId: number,
+    refusalPatterns: string[],
+  ): Promise<Result<ProviderSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_set_refusal_patterns", { providerId, refusalPatterns }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerSetAnthropicHeaders(
+    providerId: number,
+    anthropicVersion: string | null,
+    anthropicBetas: string[],
+    anthropicBetaStrip: boolean,
+  ): Promise<Result<ProviderSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_set_anthropic_headers", {
+          providerId,
+          anthropicVersion,
+          anthropicBetas,
+          anthropicBetaStrip,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerSetWebhookUrl(
+    providerId: number,
+    webhookUrl: string | null,
+  ): Promise<Result<ProviderSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_set_webhook_url", { providerId, webhookUrl }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerSetMonthlyBudget(
+    providerId: number,
+    monthlyBudgetUsd: number | null,
+  ): Promise<Result<ProviderSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_set_monthly_budget", { providerId, monthlyBudgetUsd }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  /**
+   * Soft delete: archives the provider. `provider_purge` removes it for good.
+   */
+  async providerDelete(providerId: number): Promise<Result<boolean, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("provider_delete", { providerId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerRestore(providerId: number): Promise<Result<ProviderSummary, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("provider_restore", { providerId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerUsageAudit(providerId: number): Promise<Result<ProviderUsageAudit, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("provider_usage_audit", { providerId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerPurge(providerId: number): Promise<Result<boolean, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("provider_purge", { providerId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerMaintenanceWindowsList(
+    providerId: number,
+  ): Promise<Result<ProviderMaintenanceWindow[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_maintenance_windows_list", { providerId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerMaintenanceWindowUpsert(
+    input: ProviderMaintenanceWindowInput,
+  ): Promise<Result<ProviderMaintenanceWindow, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_maintenance_window_upsert", { input }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerMaintenanceWindowDelete(windowId: number): Promise<Result<boolean, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_maintenance_window_delete", { windowId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerDnsOptionsGet(providerId: number): Promise<Result<ProviderDnsOptions, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("provider_dns_options_get", { providerId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerDnsOptionsSet(
+    providerId: number,
+    ipStrategy: DnsIpStrategy,
+    dohUrl: string | null,
+    raceConnections: boolean,
+  ): Promise<Result<ProviderDnsOptions, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_dns_options_set", {
+          providerId,
+          ipStrategy,
+          dohUrl,
+          raceConnections,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerDefaultTagsList(): Promise<Result<ProviderTagDefault[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("provider_default_tags_list") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerDefaultTagSet(
+    cliKey: string,
+    tag: string | null,
+  ): Promise<Result<ProviderTagDefault, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_default_tag_set", { cliKey, tag }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerModelCatalogRefresh(
+    providerId: number,
+  ): Promise<Result<ProviderModelCatalog, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_model_catalog_refresh", { providerId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providersValidateAll(
+    claudeModel: string | null,
+  ): Promise<Result<ProviderValidationReport, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("providers_validate_all", { claudeModel }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providersReorder(
+    cliKey: string,
+    orderedProviderIds: number[],
+  ): Promise<Result<ProviderSummary[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("providers_reorder", { cliKey, orderedProviderIds }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providersBulkUpdate(
+    input: ProviderBulkUpdate,
+    dryRun: boolean,
+  ): Promise<Result<ProviderBulkUpdateResult, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("providers_bulk_update", { input, dryRun }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerClaudeTerminalLaunchCommand(providerId: number): Promise<Result<string, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_claude_terminal_launch_command", { providerId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerGetApiKey(providerId: number): Promise<Result<string, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("provider_get_api_key", { providerId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async baseUrlPingMs(baseUrl: string): Promise<Result<number, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("base_url_ping_ms", { baseUrl }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerOauthStartFlow(
+    cliKey: string,
+    providerId: number,
+  ): Promise<Result<JsonValue, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_oauth_start_flow", { cliKey, providerId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerOauthRefresh(providerId: number): Promise<Result<JsonValue, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("provider_oauth_refresh", { providerId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerOauthDisconnect(providerId: number): Promise<Result<JsonValue, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_oauth_disconnect", { providerId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerOauthStatus(providerId: number): Promise<Result<JsonValue, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("provider_oauth_status", { providerId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerOauthFetchLimits(providerId: number): Promise<Result<JsonValue, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_oauth_fetch_limits", { providerId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async claudeProviderValidateModel(
+    providerId: number,
+    baseUrl: string,
+    requestJson: string,
+  ): Promise<Result<ClaudeModelValidationResult, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("claude_provider_validate_model", {
+          providerId,
+          baseUrl,
+          requestJson,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async claudeProviderGetApiKeyPlaintext(providerId: number): Promise<Result<string, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("claude_provider_get_api_key_plaintext", { providerId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async claudeValidationHistoryList(
+    providerId: number,
+    limit: number | null,
+  ): Promise<Result<ClaudeModelValidationRunRow[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("claude_validation_history_list", { providerId, limit }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async claudeValidationHistoryClearProvider(providerId: number): Promise<Result<boolean, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("claude_validation_history_clear_provider", { providerId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async claudeValidationSchedulesList(
+    providerId: number | null,
+  ): Promise<Result<ClaudeModelValidationSchedule[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("claude_validation_schedules_list", { providerId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async claudeValidationScheduleUpsert(
+    providerId: number,
+    model: string,
+    intervalMinutes: number,
+    enabled: boolean,
+  ): Promise<Result<ClaudeModelValidationSchedule, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("claude_validation_schedule_upsert", {
+          providerId,
+          model,
+          intervalMinutes,
+          enabled,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async claudeValidationScheduleDelete(scheduleId: number): Promise<Result<boolean, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("claude_validation_schedule_delete", { scheduleId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async sortModesList(): Promise<Result<SortModeSummary[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("sort_modes_list") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async sortModeCreate(name: string): Promise<Result<SortModeSummary, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("sort_mode_create", { name }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async sortModeRename(modeId: number, name: string): Promise<Result<SortModeSummary, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("sort_mode_rename", { modeId, name }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async sortModeSetStrategy(
+    modeId: number,
+    strategy: string,
+  ): Promise<Result<SortModeSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("sort_mode_set_strategy", { modeId, strategy }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async sortModeDelete(modeId: number): Promise<Result<boolean, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("sort_mode_delete", { modeId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async sortModeActiveList(): Promise<Result<SortModeActiveRow[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("sort_mode_active_list") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async sortModeActiveSet(
+    cliKey: string,
+    modeId: number | null,
+  ): Promise<Result<SortModeActiveRow, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("sort_mode_active_set", { cliKey, modeId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async sortModeProvidersList(
+    modeId: number,
+    cliKey: string,
+  ): Promise<Result<SortModeProviderRow[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("sort_mode_providers_list", { modeId, cliKey }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async sortModeProvidersSetOrder(
+    modeId: number,
+    cliKey: string,
+    orderedProviderIds: number[],
+  ): Promise<Result<SortModeProviderRow[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("sort_mode_providers_set_order", {
+          modeId,
+          cliKey,
+          orderedProviderIds,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  /**
+   * Ranks the providers of `mode_id` (or the default order) by score; apply the result with
+   * `sort_mode_providers_set_order` / `providers_reorder`.
+   */
+  async sortModeSuggestOrder(
+    cliKey: string,
+    modeId: number | null,
+  ): Promise<Result<SortOrderSuggestion, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("sort_mode_suggest_order", { cliKey, modeId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async sortModeProviderSetEnabled(
+    modeId: number,
+    cliKey: string,
+    providerId: number,
+    enabled: boolean,
+  ): Promise<Result<SortModeProviderRow, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("sort_mode_provider_set_enabled", {
+          modeId,
+          cliKey,
+          providerId,
+          enabled,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async sortModeProviderSetTimeWindow(
+    modeId: number,
+    cliKey: string,
+    providerId: number,
+    activeStart: string | null,
+    activeEnd: string | null,
+  ): Promise<Result<SortModeProviderRow, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("sort_mode_provider_set_time_window", {
+          modeId,
+          cliKey,
+          providerId,
+          activeStart,
+          activeEnd,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async sortModeFallbackGet(cliKey: string): Promise<Result<SortModeFallbackChain, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("sort_mode_fallback_get", { cliKey }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async sortModeFallbackSet(
+    cliKey: string,
+    modeIds: (number | null)[],
+  ): Promise<Result<SortModeFallbackChain, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("sort_mode_fallback_set", { cliKey, modeIds }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async sortModeScheduleGet(cliKey: string): Promise<Result<SortModeSchedule, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("sort_mode_schedule_get", { cliKey }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async sortModeScheduleSet(
+    cliKey: string,
+    enabled: boolean,
+    utcOffsetMinutes: number | null,
+    rules: SortModeScheduleRule[],
+    otherwiseEnabled: boolean,
+    otherwiseModeId: number | null,
+  ): Promise<Result<SortModeSchedule, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("sort_mode_schedule_set", {
+          cliKey,
+          enabled,
+          utcOffsetMinutes,
+          rules,
+          otherwiseEnabled,
+          otherwiseModeId,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async modelPricesList(cliKey: string): Promise<Result<ModelPriceSummary[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("model_prices_list", { cliKey }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async modelPriceUpsert(
+    cliKey: string,
+    model: string,
+    priceJson: string,
+  ): Promise<Result<ModelPriceSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("model_price_upsert", { cliKey, model, priceJson }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async modelPricesImport(
+    content: string,
+    format: string | null,
+    cliKey: string | null,
+    dryRun: boolean,
+  ): Promise<Result<ModelPricesImportReport, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("model_prices_import", { content, format, cliKey, dryRun }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async modelPricesSyncBasellm(
+    force: boolean | null,
+  ): Promise<Result<ModelPricesSyncReport, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("model_prices_sync_basellm", { force }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async modelPriceAliasesGet(): Promise<Result<ModelPriceAliasesV1, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("model_price_aliases_get") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async modelPriceAliasesSet(
+    aliases: ModelPriceAliasesV1,
+  ): Promise<Result<ModelPriceAliasesV1, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("model_price_aliases_set", { aliases }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async promptsList(workspaceId: number): Promise<Result<PromptSummary[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("prompts_list", { workspaceId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async promptsDefaultSyncFromFiles(): Promise<Result<DefaultPromptSyncReport, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("prompts_default_sync_from_files") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async promptUpsert(
+    promptId: number | null,
+    workspaceId: number,
+    name: string,
+    content: string,
+    enabled: boolean,
+  ): Promise<Result<PromptSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("prompt_upsert", {
+          promptId,
+          workspaceId,
+          name,
+          content,
+          enabled,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async promptSetEnabled(
+    promptId: number,
+    enabled: boolean,
+  ): Promise<Result<PromptSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("prompt_set_enabled", { promptId, enabled }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async promptDelete(promptId: number): Promise<Result<boolean, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("prompt_delete", { promptId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async promptFileConflictsList(): Promise<Result<PromptFileConflict[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("prompt_file_conflicts_list") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async promptFileConflictResolve(
+    cliKey: string,
+    resolution: PromptConflictResolution,
+  ): Promise<Result<boolean, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("prompt_file_conflict_resolve", { cliKey, resolution }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async mcpServersList(workspaceId: number): Promise<Result<McpServerSummary[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("mcp_servers_list", { workspaceId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async mcpServerUpsert(
+    serverId: number | null,
+    serverKey: string,
+    name: string,
+    transport: string,
+    command: string | null,
+    args: string[],
+    env: Partial<{ [key in string]: string }>,
+    cwd: string | null,
+    url: string | null,
+    headers: Partial<{ [key in string]: string }>,
+  ): Promise<Result<McpServerSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("mcp_server_upsert", {
+          serverId,
+          serverKey,
+          name,
+          transport,
+          command,
+          args,
+          env,
+          cwd,
+          url,
+          headers,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async mcpServerSetEnabled(
+    workspaceId: number,
+    serverId: number,
+    enabled: boolean,
+  ): Promise<Result<McpServerSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("mcp_server_set_enabled", { workspaceId, serverId, enabled }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async mcpServerDelete(serverId: number): Promise<Result<boolean, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("mcp_server_delete", { serverId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async mcpParseJson(jsonText: string): Promise<Result<McpParseResult, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("mcp_parse_json", { jsonText }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async mcpImportServers(
+    workspaceId: number,
+    servers: McpImportServer[],
+  ): Promise<Result<McpImportReport, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("mcp_import_servers", { workspaceId, servers }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async mcpImportFromWorkspaceCli(workspaceId: number): Promise<Result<McpImportReport, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("mcp_import_from_workspace_cli", { workspaceId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async mcpSyncStatus(cliKey: string | null): Promise<Result<McpSyncCliStatus[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("mcp_sync_status", { cliKey }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async mcpSyncApply(cliKey: string): Promise<Result<McpSyncCliStatus, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("mcp_sync_apply", { cliKey }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async mcpSyncAdopt(
+    cliKey: string,
+    serverKeys: string[],
+  ): Promise<Result<McpSyncCliStatus, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("mcp_sync_adopt", { cliKey, serverKeys }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async mcpServerSuperviseStart(serverId: number): Promise<Result<McpServerRuntimeStatus, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("mcp_server_supervise_start", { serverId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async mcpServerSuperviseStop(serverId: number): Promise<Result<boolean, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("mcp_server_supervise_stop", { serverId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async mcpServerRuntimeStatus(): Promise<Result<McpServerRuntimeStatus[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("mcp_server_runtime_status") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async hooksList(workspaceId: number): Promise<Result<HookSummary[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("hooks_list", { workspaceId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async hookTemplatesList(): Promise<HookTemplate[]> {
+    return await TAURI_INVOKE("hook_templates_list");
+  },
+  async hookUpsert(
+    hookId: number | null,
+    workspaceId: number,
+    name: string,
+    event: string,
+    matcher: string,
+    command: string,
+    timeoutSecs: number | null,
+  ): Promise<Result<HookSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("hook_upsert", {
+          hookId,
+          workspaceId,
+          name,
+          event,
+          matcher,
+          command,
+          timeoutSecs,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async hookSetEnabled(
+    workspaceId: number,
+    hookId: number,
+    enabled: boolean,
+  ): Promise<Result<HookSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("hook_set_enabled", { workspaceId, hookId, enabled }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async hookDelete(hookId: number): Promise<Result<boolean, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("hook_delete", { hookId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async subagentsList(workspaceId: number): Promise<Result<SubagentSummary[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("subagents_list", { workspaceId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async subagentsUpsert(
+    subagentId: number | null,
+    workspaceId: number,
+    name: string,
+    description: string,
+    prompt: string,
+    tools: string[],
+    model: string | null,
+  ): Promise<Result<SubagentSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("subagents_upsert", {
+          subagentId,
+          workspaceId,
+          name,
+          description,
+          prompt,
+          tools,
+          model,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async subagentsSetEnabled(
+    workspaceId: number,
+    subagentId: number,
+    enabled: boolean,
+  ): Promise<Result<SubagentSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("subagents_set_enabled", { workspaceId, subagentId, enabled }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async subagentsDelete(subagentId: number): Promise<Result<boolean, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("subagents_delete", { subagentId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillReposList(): Promise<Result<SkillRepoSummary[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("skill_repos_list") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillRepoUpsert(
+    repoId: number | null,
+    gitUrl: string,
+    branch: string,
+    enabled: boolean,
+  ): Promise<Result<SkillRepoSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("skill_repo_upsert", { repoId, gitUrl, branch, enabled }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillRepoDelete(repoId: number): Promise<Result<boolean, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("skill_repo_delete", { repoId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillRepoAuthSet(
+    repoId: number,
+    authKind: SkillRepoAuthKind,
+    token: string | null,
+    sshKeyPath: string | null,
+  ): Promise<Result<SkillRepoSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("skill_repo_auth_set", { repoId, authKind, token, sshKeyPath }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillRepoTestAccess(repoId: number): Promise<Result<SkillRepoAccessReport, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("skill_repo_test_access", { repoId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillsInstalledList(workspaceId: number): Promise<Result<InstalledSkillSummary[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("skills_installed_list", { workspaceId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillsDiscoverAvailable(
+    refresh: boolean,
+  ): Promise<Result<AvailableSkillSummary[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("skills_discover_available", { refresh }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillInstall(
+    workspaceId: number,
+    gitUrl: string,
+    branch: string,
+    sourceSubdir: string,
+    enabled: boolean,
+  ): Promise<Result<InstalledSkillSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("skill_install", {
+          workspaceId,
+          gitUrl,
+          branch,
+          sourceSubdir,
+          enabled,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillDependenciesPreview(
+    workspaceId: number,
+    gitUrl: string,
+    branch: string,
+    sourceSubdir: string,
+  ): Promise<Result<SkillDependencyReport, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("skill_dependencies_preview", {
+          workspaceId,
+          gitUrl,
+          branch,
+          sourceSubdir,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillDependenciesCheck(
+    workspaceId: number,
+    skillId: number,
+  ): Promise<Result<SkillDependencyReport, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("skill_dependencies_check", { workspaceId, skillId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillSetEnabled(
+    workspaceId: number,
+    skillId: number,
+    enabled: boolean,
+  ): Promise<Result<InstalledSkillSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("skill_set_enabled", { workspaceId, skillId, enabled }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillUninstall(skillId: number): Promise<Result<boolean, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("skill_uninstall", { skillId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillReturnToLocal(workspaceId: number, skillId: number): Promise<Result<boolean, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("skill_return_to_local", { workspaceId, skillId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillsLocalList(workspaceId: number): Promise<Result<LocalSkillSummary[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("skills_local_list", { workspaceId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillImportLocal(
+    workspaceId: number,
+    dirName: string,
+  ): Promise<Result<InstalledSkillSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("skill_import_local", { workspaceId, dirName }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillsImportLocalBatch(
+    workspaceId: number,
+    dirNames: string[],
+  ): Promise<Result<SkillImportLocalBatchReport, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("skills_import_local_batch", { workspaceId, dirNames }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillsCheckUpdates(): Promise<Result<SkillUpdateStatus[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("skills_check_updates") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillSetPin(
+    skillId: number,
+    pinnedRef: string | null,
+  ): Promise<Result<InstalledSkillSummary, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("skill_set_pin", { skillId, pinnedRef }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillUpdate(skillId: number, dryRun: boolean): Promise<Result<SkillUpdateReport, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("skill_update", { skillId, dryRun }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillPackage(
+    sourceDir: string,
+    outputDir: string | null,
+    pushRepoId: number | null,
+  ): Promise<Result<SkillPackageReport, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("skill_package", { sourceDir, outputDir, pushRepoId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async skillsPathsGet(cliKey: string): Promise<Result<SkillsPaths, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("skills_paths_get", { cliKey }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async requestLogsList(
+    cliKey: string,
+    limit: number | null,
+  ): Promise<Result<RequestLogSummary[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("request_logs_list", { cliKey, limit }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async requestLogsListAll(limit: number | null): Promise<Result<RequestLogSummary[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("request_logs_list_all", { limit }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async requestLogsListAfterId(
+    cliKey: string,
+    afterId: number,
+    limit: number | null,
+  ): Promise<Result<RequestLogSummary[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("request_logs_list_after_id", { cliKey, afterId, limit }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async requestLogsListAfterIdAll(
+    afterId: number,
+    limit: number | null,
+  ): Promise<Result<RequestLogSummary[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("request_logs_list_after_id_all", { afterId, limit }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async requestLogGet(logId: number): Promise<Result<RequestLogDetail, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("request_log_get", { logId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async requestLogGetByTraceId(traceId: string): Promise<Result<RequestLogDetail | null, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("request_log_get_by_trace_id", { traceId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async requestAttemptLogsByTraceId(
+    traceId: string,
+    limit: number | null,
+  ): Promise<Result<RequestAttemptLog[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("request_attempt_logs_by_trace_id", { traceId, limit }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async requestTimelineByTraceId(traceId: string): Promise<Result<RequestTimeline | null, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("request_timeline_by_trace_id", { traceId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async requestMirrorLogsList(
+    limit: number | null,
+  ): Promise<Result<RequestMirrorLogRow[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("request_mirror_logs_list", { limit }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async requestMirrorStats(
+    sinceUnix: number | null,
+  ): Promise<Result<RequestMirrorProviderStats[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("request_mirror_stats", { sinceUnix }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async requestMirrorLogsClear(): Promise<Result<number, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("request_mirror_logs_clear") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  /**
+   * Newest first; every filter is optional and `until_unix` is exclusive.
+   */
+  async auditLogList(
+    command: string | null,
+    origin: AuditOrigin | null,
+    target: string | null,
+    sinceUnix: number | null,
+    untilUnix: number | null,
+    limit: number | null,
+  ): Promise<Result<AuditLogEntry[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("audit_log_list", {
+          command,
+          origin,
+          target,
+          sinceUnix,
+          untilUnix,
+          limit,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async dbDiskUsageGet(): Promise<Result<DbDiskUsage, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("db_disk_usage_get") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async dbHealthGet(): Promise<Result<DbHealth, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("db_health_get") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async dbCheckpointNow(): Promise<Result<DbCheckpointResult, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("db_checkpoint_now") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  /**
+   * Runs a single SELECT on a read-only connection; refused in observer mode because the
+   * database holds provider keys.
+   */
+  async dbQueryReadonly(
+    sql: string,
+    maxRows: number | null,
+  ): Promise<Result<DbQueryResult, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("db_query_readonly", { sql, maxRows }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async usageHistoryImport(
+    source: string,
+    path: string,
+    cliKey: string | null,
+  ): Promise<Result<UsageImportResult, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("usage_history_import", { source, path, cliKey }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async appConfigExport(
+    path: string,
+    password: string,
+    includeSecrets: boolean,
+  ): Promise<Result<ConfigExportResult, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("app_config_export", { path, password, includeSecrets }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async appConfigImport(
+    path: string,
+    password: string,
+  ): Promise<Result<ConfigImportResult, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("app_config_import", { path, password }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async configApplyFile(path: string, dryRun: boolean): Promise<Result<ConfigApplyReport, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("config_apply_file", { path, dryRun }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async requestLogsClearAll(): Promise<Result<ClearRequestLogsResult, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("request_logs_clear_all") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async appDataReset(): Promise<Result<boolean, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("app_data_reset") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async appDataResetScope(
+    scope: DataResetScope,
+    dryRun: boolean,
+    confirmToken: string | null,
+  ): Promise<Result<DataResetReport, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("app_data_reset_scope", { scope, dryRun, confirmToken }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async usageSummary(range: string, cliKey: string | null): Promise<Result<UsageSummary, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("usage_summary", { range, cliKey }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async usageSummaryV2(params: UsageQueryParams): Promise<Result<UsageSummary, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("usage_summary_v2", { params }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async usageLeaderboardProvider(
+    range: string,
+    cliKey: string | null,
+    limit: number | null,
+  ): Promise<Result<UsageProviderRow[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("usage_leaderboard_provider", { range, cliKey, limit }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async usageLeaderboardDay(
+    range: string,
+    cliKey: string | null,
+    limit: number | null,
+  ): Promise<Result<UsageDayRow[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("usage_leaderboard_day", { range, cliKey, limit }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async usageLeaderboardV2(
+    scope: string,
+    params: UsageQueryParams,
+    limit: number | null,
+  ): Promise<Result<UsageLeaderboardRow[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("usage_leaderboard_v2", { scope, params, limit }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async usageHourlySeries(days: number): Promise<Result<UsageHourlyRow[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("usage_hourly_series", { days }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async usageProviderCacheRateTrendV1(
+    params: UsageQueryParams,
+    limit: number | null,
+  ): Promise<Result<UsageProviderCacheRateTrendRowV1[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("usage_provider_cache_rate_trend_v1", { params, limit }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async usageForecastV1(cliKey: string | null): Promise<Result<UsageForecastV1, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("usage_forecast_v1", { cliKey }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async usageBandwidthV1(params: UsageQueryParams): Promise<Result<UsageBandwidthRowV1[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("usage_bandwidth_v1", { params }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async usageHeatmapV1(params: UsageQueryParams): Promise<Result<UsageHeatmapCellV1[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("usage_heatmap_v1", { params }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async costSummaryV1(params: CostQueryParams): Promise<Result<CostSummaryV1, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("cost_summary_v1", { params }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async costTrendV1(params: CostQueryParams): Promise<Result<CostTrendRowV1[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("cost_trend_v1", { params }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async costBreakdownProviderV1(
+    params: CostQueryParams,
+    limit: number | null,
+  ): Promise<Result<CostProviderBreakdownRowV1[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("cost_breakdown_provider_v1", { params, limit }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async costBreakdownModelV1(
+    params: CostQueryParams,
+    limit: number | null,
+  ): Promise<Result<CostModelBreakdownRowV1[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("cost_breakdown_model_v1", { params, limit }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async costScatterCliProviderModelV1(
+    params: CostQueryParams,
+    limit: number | null,
+  ): Promise<Result<CostScatterCliProviderModelRowV1[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("cost_scatter_cli_provider_model_v1", { params, limit }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async costTopRequestsV1(
+    params: CostQueryParams,
+    limit: number | null,
+  ): Promise<Result<CostTopRequestRowV1[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("cost_top_requests_v1", { params, limit }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async costBackfillMissingV1(
+    params: CostQueryParams,
+    maxRows: number | null,
+  ): Promise<Result<CostBackfillReportV1, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("cost_backfill_missing_v1", { params, maxRows }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async costAnomaliesList(): Promise<Result<CostAnomaly[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("cost_anomalies_list") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async costAnomalyAcknowledge(sessionId: string): Promise<Result<boolean, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("cost_anomaly_acknowledge", { sessionId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async envConflictsCheck(cliKey: string): Promise<Result<EnvConflict[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("env_conflicts_check", { cliKey }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async envConflictsFix(
+    targets: EnvConflictFixTarget[],
+    action: EnvConflictFixAction,
+    value: string | null,
+  ): Promise<Result<EnvConflictFixResult, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("env_conflicts_fix", { targets, action, value }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async envConflictsFixUndo(backupId: string): Promise<Result<number, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("env_conflicts_fix_undo", { backupId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliProxyStatusAll(): Promise<Result<CliProxyStatus[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("cli_proxy_status_all") };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliProxySetEnabled(
+    cliKey: string,
+    enabled: boolean,
+  ): Promise<Result<CliProxyResult, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("cli_proxy_set_enabled", { cliKey, enabled }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliProxySyncEnabled(baseOrigin: string): Promise<Result<CliProxyResult[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("cli_proxy_sync_enabled", { baseOrigin }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async cliConfigDriftReport(cliKey: string | null): Promise<Result<CliConfigDriftReport, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("cli_config_drift_report", { cliKey }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerLimitUsageV1(
+    cliKey: string | null,
+  ): Promise<Result<ProviderLimitUsageRow[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("provider_limit_usage_v1", { cliKey }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerMonthlyBudgetsList(
+    cliKey: string | null,
+  ): Promise<Result<ProviderMonthlyBudgetRow[], string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_monthly_budgets_list", { cliKey }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async providerMonthlyBudgetSetGrace(
+    providerId: number,
+    graceUsd: number,
+  ): Promise<Result<ProviderMonthlyBudgetRow, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("provider_monthly_budget_set_grace", { providerId, graceUsd }),
+      };
     } catch (e) {
       if (e instanceof Error) throw e;
       else return { status: "error", error: e as any };
     }
   },
-  async providerUpsert(input: ProviderUpsertInput): Promise<Result<ProviderSummary, string>> {
+  async workspacesList(cliKey: string): Promise<Result<WorkspacesListResult, string>> {
     try {
-      return { status: "ok", data: await TAURI_INVOKE("provider_upsert", { input }) };
+      return { status: "ok", data: await TAURI_INVOKE("workspaces_list", { cliKey }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async workspaceCreate(
+    cliKey: string,
+    name: string,
+    cloneFromActive: boolean | null,
+  ): Promise<Result<WorkspaceSummary, string>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("workspace_create", { cliKey, name, cloneFromActive }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async workspaceRename(
+    workspaceId: number,
+    name: string,
+  ): Promise<Result<WorkspaceSummary, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("workspace_rename", { workspaceId, name }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async workspaceDelete(workspaceId: number): Promise<Result<boolean, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("workspace_delete", { workspaceId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async workspacePreview(workspaceId: number): Promise<Result<WorkspacePreview, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("workspace_preview", { workspaceId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async workspaceApply(workspaceId: number): Promise<Result<WorkspaceApplyReport, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("workspace_apply", { workspaceId }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async workspaceSnapshotList(cliKey: string): Promise<Result<WorkspaceSnapshotSummary[], string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("workspace_snapshot_list", { cliKey }) };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async workspaceRollback(snapshotId: number): Promise<Result<WorkspaceRollbackReport, string>> {
+    try {
+      return { status: "ok", data: await TAURI_INVOKE("workspace_rollback", { snapshotId }) };
     } catch (e) {
       if (e instanceof Error) throw e;
       else return { status: "error", error: e as any };
     }
   },
 };
-
-/** user-defined events **/
-
-/** user-defined constants **/
-
-/** user-defined types **/
-
-export type AppSettings = {
-  schema_version: number;
-  preferred_port: number;
-  gateway_listen_mode: GatewayListenMode;
-  gateway_custom_listen_address: string;
-  wsl_auto_config: boolean;
-  wsl_target_cli: WslTargetCli;
-  wsl_host_address_mode: WslHostAddressMode;
-  wsl_custom_host_address: string;
-  auto_start: boolean;
-  start_minimized: boolean;
-  tray_enabled: boolean;
-  enable_cli_proxy_startup_recovery: boolean;
-  log_retention_days: number;
-  provider_cooldown_seconds: number;
-  provider_base_url_ping_cache_ttl_seconds: number;
-  upstream_first_byte_timeout_seconds: number;
-  upstream_stream_idle_timeout_seconds: number;
-  upstream_request_timeout_non_streaming_seconds: number;
-  update_releases_url: string;
-  failover_max_attempts_per_provider: number;
-  failover_max_providers_to_try: number;
-  circuit_breaker_failure_threshold: number;
-  circuit_breaker_open_duration_minutes: number;
-  enable_circuit_breaker_notice: boolean;
-  verbose_provider_error: boolean;
-  intercept_anthropic_warmup_requests: boolean;
-  enable_thinking_signature_rectifier: boolean;
-  enable_thinking_budget_rectifier: boolean;
-  enable_codex_session_id_completion: boolean;
-  enable_claude_metadata_user_id_injection: boolean;
-  enable_cache_anomaly_monitor: boolean;
-  enable_task_complete_notify: boolean;
-  enable_response_fixer: boolean;
-  response_fixer_fix_encoding: boolean;
-  response_fixer_fix_sse_format: boolean;
-  response_fixer_fix_truncated_json: boolean;
-  response_fixer_max_json_depth: number;
-  response_fixer_max_fix_size: number;
+
+/** user-defined events **/
+
+export const events = __makeEvents__<{
+  gatewayAttemptEvent: GatewayAttemptEvent;
+  gatewayCircuitEvent: GatewayCircuitEvent;
+  gatewayLogEvent: GatewayLogEvent;
+  gatewayRequestEvent: GatewayRequestEvent;
+  gatewayRequestStartEvent: GatewayRequestStartEvent;
+  gatewayStatus: GatewayStatus;
+  gatewayStreamStallEvent: GatewayStreamStallEvent;
+  noticeEventPayload: NoticeEventPayload;
+  selfTestReport: SelfTestReport;
+}>({
+  gatewayAttemptEvent: "gateway:attempt",
+  gatewayCircuitEvent: "gateway:circuit",
+  gatewayLogEvent: "gateway:log",
+  gatewayRequestEvent: "gateway:request",
+  gatewayRequestStartEvent: "gateway:request_start",
+  gatewayStatus: "gateway:status",
+  gatewayStreamStallEvent: "gateway:stream_stall",
+  noticeEventPayload: "notice:notify",
+  selfTestReport: "app:selftest_failed",
+});
+
+/** user-defined constants **/
+
+/** user-defined types **/
+
+/**
+ * On-disk gateway access log format (`off` disables the log).
+ */
+export type AccessLogFormat = "off" | "clf" | "json";
+export type AppAboutInfo = {
+  os: string;
+  arch: string;
+  profile: string;
+  app_version: string;
+  bundle_type: string | null;
+  run_mode: string;
+};
+export type AppSettings = {
+  schema_version: number;
+  preferred_port: number;
+  gateway_listen_mode: GatewayListenMode;
+  gateway_custom_listen_address: string;
+  wsl_auto_config: boolean;
+  wsl_target_cli: WslTargetCli;
+  wsl_host_address_mode: WslHostAddressMode;
+  wsl_custom_host_address: string;
+  gateway_access_token: string;
+  auto_start: boolean;
+  start_minimized: boolean;
+  tray_enabled: boolean;
+  enable_cli_proxy_startup_recovery: boolean;
+  log_retention_days: number;
+  attempt_log_retention_days: number;
+  provider_cooldown_seconds: number;
+  provider_base_url_ping_cache_ttl_seconds: number;
+  upstream_first_byte_timeout_seconds: number;
+  upstream_stream_idle_timeout_seconds: number;
+  upstream_request_timeout_non_streaming_seconds: number;
+  count_tokens_hedge_delay_ms: number;
+  non_streaming_hedge_delay_ms: number;
+  count_tokens_local_estimate_enabled: boolean;
+  first_byte_race_delay_ms: number;
+  upstream_warm_pool_enabled: boolean;
+  upstream_warm_pool_size: number;
+  request_mirror_enabled: boolean;
+  request_mirror_provider_id: number;
+  request_mirror_percent: number;
+  request_dedup_enabled: boolean;
+  stream_truncation_recovery_enabled: boolean;
+  sse_coalesce_flush_ms: number;
+  sse_coalesce_flush_bytes: number;
+  stream_stall_warn_seconds: number;
+  stream_stall_keepalive_enabled: boolean;
+  stream_first_byte_failover_enabled: boolean;
+  stream_error_event_enabled: boolean;
+  update_releases_url: string;
+  failover_max_attempts_per_provider: number;
+  failover_max_providers_to_try: number;
+  circuit_breaker_failure_threshold: number;
+  circuit_breaker_open_duration_minutes: number;
+  circuit_breaker_half_open_probe_enabled: boolean;
+  retry_backoff: RetryBackoffSettings;
+  enable_circuit_breaker_notice: boolean;
+  verbose_provider_error: boolean;
+  intercept_anthropic_warmup_requests: boolean;
+  enable_thinking_signature_rectifier: boolean;
+  enable_thinking_budget_rectifier: boolean;
+  enable_codex_session_id_completion: boolean;
+  enable_claude_metadata_user_id_injection: boolean;
+  enable_cache_anomaly_monitor: boolean;
+  enable_task_complete_notify: boolean;
+  enable_response_fixer: boolean;
+  response_fixer_fix_encoding: boolean;
+  response_fixer_fix_sse_format: boolean;
+  response_fixer_fix_truncated_json: boolean;
+  response_fixer_max_json_depth: number;
+  response_fixer_max_fix_size: number;
+  response_fixer_spill_to_disk: boolean;
+  response_fixer_spill_max_size: number;
+  response_fixer_transcode_charsets: ResponseFixerCharset[];
+  redaction_rules: RedactionRule[];
+  env_conflict_ignore_rules: EnvConflictIgnoreRule[];
+  model_denylist: ModelDenyRule[];
+  model_capabilities: ModelCapabilityRule[];
+  capability_gate_policy: CapabilityGatePolicy;
+  storage_postgres_url: string;
+  ui_language: UiLanguage;
+  captured_response_headers: string[];
+  upstream_error_body_capture_bytes: number;
+  session_binding_ttl_seconds: number;
+  access_log_format: AccessLogFormat;
+  access_log_retention_days: number;
+  enable_cost_anomaly_detection: boolean;
+  cost_anomaly_multiplier: number;
+  cost_anomaly_auto_throttle: boolean;
+  observer_mode: boolean;
+  request_log_sample_percent: number;
+  request_log_slow_threshold_ms: number;
+  notification_sinks: NotificationSink[];
+  ssh_hosts: SshHost[];
+};
+export type AuditLogEntry = {
+  id: number;
+  created_at: number;
+  command: string;
+  origin: AuditOrigin;
+  /**
+   * What was changed, e.g. `provider:12`, `sort_mode:3` or `cli:claude`.
+   */
+  target: string | null;
+  summary: string;
+};
+export type AuditOrigin = "ui" | "rest" | "headless";
+export type AvailableSkillSummary = {
+  name: string;
+  description: string;
+  source_git_url: string;
+  source_branch: string;
+  source_subdir: string;
+  installed: boolean;
+};
+/**
+ * What the gateway does when a request needs a capability the provider's model lacks.
+ */
+export type CapabilityGatePolicy = "off" | "skip_provider" | "strip_feature";
+export type ClaudeCliInfo = {
+  found: boolean;
+  executable_path: string | null;
+  version: string | null;
+  error: string | null;
+  shell: string | null;
+  resolved_via: string;
+  config_dir: string;
+  settings_path: string;
+  mcp_timeout_ms: number | null;
+  disable_error_reporting: boolean;
+};
+export type ClaudeEnvState = {
+  config_dir: string;
+  settings_path: string;
+  mcp_timeout_ms: number | null;
+  disable_error_reporting: boolean;
+};
+export type ClaudeModelValidationResult = {
+  ok: boolean;
+  provider_id: number;
+  provider_name: string;
+  base_url: string;
+  target_url: string;
+  status: number | null;
+  duration_ms: number;
+  requested_model: string | null;
+  responded_model: string | null;
+  stream: boolean;
+  output_text_chars: number;
+  output_text_preview: string;
+  checks: JsonValue;
+  signals: JsonValue;
+  response_headers: JsonValue;
+  usage: JsonValue | null;
+  error: string | null;
+  raw_excerpt: string;
+  request: JsonValue;
+};
+export type ClaudeModelValidationRunRow = {
+  id: number;
+  provider_id: number;
+  created_at: number;
+  request_json: string;
+  result_json: string;
+};
+export type ClaudeModelValidationSchedule = {
+  id: number;
+  provider_id: number;
+  model: string;
+  interval_minutes: number;
+  enabled: boolean;
+  last_run_at: number | null;
+  /**
+   * `None` until the first scheduled run finished.
+   */
+  last_ok: boolean | null;
+  last_error: string | null;
+  created_at: number;
+  updated_at: number;
+};
+export type ClaudeModels = {
+  main_model?: string | null;
+  reasoning_model?: string | null;
+  haiku_model?: string | null;
+  sonnet_model?: string | null;
+  opus_model?: string | null;
+};
+export type ClaudeSettingsPatch = {
+  model: string | null;
+  output_style: string | null;
+  language: string | null;
+  always_thinking_enabled: boolean | null;
+  show_turn_duration: boolean | null;
+  spinner_tips_enabled: boolean | null;
+  terminal_progress_bar_enabled: boolean | null;
+  respect_gitignore: boolean | null;
+  permissions_allow: string[] | null;
+  permissions_ask: string[] | null;
+  permissions_deny: string[] | null;
+  env_mcp_timeout_ms: number | null;
+  env_mcp_tool_timeout_ms: number | null;
+  env_experimental_agent_teams: boolean | null;
+  env_disable_background_tasks: boolean | null;
+  env_disable_terminal_title: boolean | null;
+  env_claude_bash_no_login: boolean | null;
+  env_claude_code_attribution_header: boolean | null;
+  env_claude_code_blocking_limit_override: number | null;
+  env_claude_code_max_output_tokens: number | null;
+  env_enable_experimental_mcp_cli: boolean | null;
+  env_enable_tool_search: boolean | null;
+  env_max_mcp_output_tokens: number | null;
+  env_claude_code_disable_nonessential_traffic: boolean | null;
+  env_claude_code_proxy_resolves_hosts: boolean | null;
+  env_claude_code_skip_prompt_history: boolean | null;
+};
+export type ClaudeSettingsState = {
+  config_dir: string;
+  settings_path: string;
+  exists: boolean;
+  model: string | null;
+  output_style: string | null;
+  language: string | null;
+  always_thinking_enabled: boolean | null;
+  show_turn_duration: boolean | null;
+  spinner_tips_enabled: boolean | null;
+  terminal_progress_bar_enabled: boolean | null;
+  respect_gitignore: boolean | null;
+  permissions_allow: string[];
+  permissions_ask: string[];
+  permissions_deny: string[];
+  env_mcp_timeout_ms: number | null;
+  env_mcp_tool_timeout_ms: number | null;
+  env_experimental_agent_teams: boolean;
+  env_disable_background_tasks: boolean;
+  env_disable_terminal_title: boolean;
+  env_claude_bash_no_login: boolean;
+  env_claude_code_attribution_header: boolean;
+  env_claude_code_blocking_limit_override: number | null;
+  env_claude_code_max_output_tokens: number | null;
+  env_enable_experimental_mcp_cli: boolean;
+  env_enable_tool_search: boolean;
+  env_max_mcp_output_tokens: number | null;
+  env_claude_code_disable_nonessential_traffic: boolean;
+  env_claude_code_proxy_resolves_hosts: boolean;
+  env_claude_code_skip_prompt_history: boolean;
+};
+export type ClearRequestLogsResult = {
+  request_logs_deleted: number;
+  request_attempt_logs_deleted: number;
+};
+export type CliConfigDriftCli = { cli_key: string; in_sync: boolean; files: CliConfigDriftFile[] };
+export type CliConfigDriftFile = {
+  /**
+   * `cli_proxy` | `mcp` | `prompt`.
+   */
+  area: string;
+  path: string;
+  exists: boolean;
+  parse_error: string | null;
+  in_sync: boolean;
+  keys: CliConfigDriftKey[];
+};
+export type CliConfigDriftKey = {
+  /**
+   * Dotted path inside the file, e.g. `env.ANTHROPIC_BASE_URL` or `mcp_servers.fetch`.
+   */
+  key: string;
+  status: CliConfigDriftStatus;
+  /**
+   * Secrets are masked; prompt files report no values.
+   */
+  expected: JsonValue | null;
+  actual: JsonValue | null;
+};
+export type CliConfigDriftReport = {
+  generated_at: number;
+  in_sync: boolean;
+  clis: CliConfigDriftCli[];
+};
+export type CliConfigDriftStatus = "in_sync" | "changed" | "missing" | "stale";
+export type CliProxyResult = {
+  trace_id: string;
+  cli_key: string;
+  enabled: boolean;
+  ok: boolean;
+  error_code: string | null;
+  message: string;
+  base_origin: string | null;
+};
+export type CliProxyStatus = { cli_key: string; enabled: boolean; base_origin: string | null };
+export type CliSessionsDisplayContentBlock =
+  | { type: "text"; text: string }
+  | { type: "thinking"; thinking: string }
+  | { type: "tool_use"; id: string; name: string; input: string }
+  | { type: "tool_result"; tool_use_id: string; content: string; is_error: boolean }
+  | { type: "reasoning"; text: string }
+  | { type: "function_call"; name: string; arguments: string; call_id: string }
+  | { type: "function_call_output"; call_id: string; output: string };
+export type CliSessionsDisplayMessage = {
+  uuid: string | null;
+  role: string;
+  timestamp: string | null;
+  model: string | null;
+  content: CliSessionsDisplayContentBlock[];
+};
+export type CliSessionsExportResult = {
+  output_path: string;
+  format: string;
+  message_count: number;
+  bytes: number;
+};
+export type CliSessionsPaginatedMessages = {
+  messages: CliSessionsDisplayMessage[];
+  total: number;
+  page: number;
+  page_size: number;
+  has_more: boolean;
+};
+export type CliSessionsProjectSummary = {
+  source: string;
+  id: string;
+  display_path: string;
+  short_name: string;
+  session_count: number;
+  last_modified: number | null;
+  model_provider: string | null;
+  wsl_distro: string | null;
+};
+export type CliSessionsSessionSummary = {
+  source: string;
+  session_id: string;
+  file_path: string;
+  first_prompt: string | null;
+  message_count: number;
+  created_at: number | null;
+  modified_at: number | null;
+  git_branch: string | null;
+  project_path: string | null;
+  is_sidechain: boolean | null;
+  cwd: string | null;
+  model_provider: string | null;
+  cli_version: string | null;
+  wsl_distro: string | null;
+};
+export type CodexConfigPatch = {
+  model: string | null;
+  approval_policy: string | null;
+  sandbox_mode: string | null;
+  model_reasoning_effort: string | null;
+  plan_mode_reasoning_effort: string | null;
+  web_search: string | null;
+  personality: string | null;
+  model_context_window: number | null | null;
+  model_auto_compact_token_limit: number | null | null;
+  service_tier: string | null;
+  sandbox_workspace_write_network_access: boolean | null;
+  features_unified_exec: boolean | null;
+  features_shell_snapshot: boolean | null;
+  features_apply_patch_freeform: boolean | null;
+  features_shell_tool: boolean | null;
+  features_exec_policy: boolean | null;
+  features_remote_compaction: boolean | null;
+  features_fast_mode: boolean | null;
+  features_responses_websockets_v2: boolean | null;
+  features_multi_agent: boolean | null;
+};
+export type CodexConfigState = {
+  config_dir: string;
+  config_path: string;
+  can_open_config_dir: boolean;
+  exists: boolean;
+  model: string | null;
+  approval_policy: string | null;
+  sandbox_mode: string | null;
+  model_reasoning_effort: string | null;
+  plan_mode_reasoning_effort: string | null;
+  web_search: string | null;
+  personality: string | null;
+  model_context_window: number | null;
+  model_auto_compact_token_limit: number | null;
+  service_tier: string | null;
+  sandbox_workspace_write_network_access: boolean | null;
+  features_unified_exec: boolean | null;
+  features_shell_snapshot: boolean | null;
+  features_apply_patch_freeform: boolean | null;
+  features_shell_tool: boolean | null;
+  features_exec_policy: boolean | null;
+  features_remote_compaction: boolean | null;
+  features_fast_mode: boolean | null;
+  features_responses_websockets_v2: boolean | null;
+  features_multi_agent: boolean | null;
+};
+export type CodexConfigTomlState = { config_path: string; exists: boolean; toml: string };
+export type CodexConfigTomlValidationError = {
+  message: string;
+  line: number | null;
+  column: number | null;
+};
+export type CodexConfigTomlValidationResult = {
+  ok: boolean;
+  error: CodexConfigTomlValidationError | null;
+};
+export type CodexProfile = {
+  id: number;
+  name: string;
+  toml: string;
+  created_at: number;
+  updated_at: number;
+};
+export type CodexProfileApplyResult = {
+  workspace_id: number;
+  profile_id: number;
+  config_path: string;
+  /**
+   * Copy of the replaced config.toml; `None` when there was no file to replace.
+   */
+  backup_path: string | null;
+  applied_at: number;
+};
+export type CodexProfilesListResult = {
+  /**
+   * Profile last applied to the requested workspace, if any.
+   */
+  active_id: number | null;
+  items: CodexProfile[];
+};
+export type ConfigApplyChange = {
+  /**
+   * `settings`, `providers`, `sort_modes` or `routing`.
+   */
+  section: string;
+  cli_key: string | null;
+  /**
+   * Setting key, provider / sort mode name, or `active` / `fallbacks` for routing.
+   */
+  key: string;
+  /**
+   * `create`, `update`, `archive` or `delete`.
+   */
+  action: string;
+  /**
+   * Changed fields of an update; values are left out so secrets never show up here.
+   */
+  fields: string[];
+};
+export type ConfigApplyReport = {
+  path: string;
+  dry_run: boolean;
+  /**
+   * `true` once changes were committed; always `false` for dry runs.
+   */
+  applied: boolean;
+  changes: ConfigApplyChange[];
+  /**
+   * Declared entries that already matched the current state.
+   */
+  unchanged: number;
+};
+export type ConfigArchiveManifest = {
+  format_version: number;
+  app_version: string;
+  /**
+   * `schema_version` of the archived settings.json.
+   */
+  settings_schema_version: number;
+  /**
+   * SQLite `user_version` of the exporting database.
+   */
+  db_schema_version: number;
+  created_at: number;
+  include_secrets: boolean;
+  tables: ConfigArchiveTableCount[];
+};
+export type ConfigArchiveTableCount = { section: string; table: string; rows: number };
+export type ConfigExportResult = { path: string; bytes: number; manifest: ConfigArchiveManifest };
+export type ConfigImportResult = {
+  manifest: ConfigArchiveManifest;
+  /**
+   * Encrypted snapshot of the configuration replaced by this import (same password).
+   */
+  backup_path: string;
+  tables: ConfigArchiveTableCount[];
+  /**
+   * Rows outside the archive removed because they referenced replaced records.
+   */
+  orphans_removed: number;
+};
+export type CostAnomaly = {
+  session_id: string;
+  cli_key: string;
+  provider_id: number | null;
+  provider_name: string | null;
+  /**
+   * Cost over the last 30 minutes.
+   */
+  recent_cost_usd: number;
+  /**
+   * Average cost per 30 minutes before that.
+   */
+  baseline_cost_usd: number;
+  multiplier: number;
+  detected_at: number;
+  throttled: boolean;
+};
+export type CostBackfillReportV1 = {
+  scanned: number;
+  updated: number;
+  skipped_no_model: number;
+  skipped_no_usage: number;
+  skipped_no_price: number;
+  skipped_other: number;
+  capped: boolean;
+  max_rows: number;
+};
+export type CostModelBreakdownRowV1 = {
+  model: string;
+  requests_success: number;
+  cost_covered_success: number;
+  cost_usd: number;
+};
+export type CostProviderBreakdownRowV1 = {
+  cli_key: string;
+  provider_id: number;
+  provider_name: string;
+  requests_success: number;
+  cost_covered_success: number;
+  cost_usd: number;
+  /**
+   * Successful requests sent with an extended thinking budget.
+   */
+  thinking_requests: number;
+  /**
+   * Sum of the thinking budgets sent upstream. Thinking is billed as output tokens without a
+   * separate count, so this is an upper bound of the thinking spend.
+   */
+  thinking_budget_tokens: number;
+};
+/**
+ * Common query parameters shared by all cost analytics endpoints.
+ */
+export type CostQueryParams = {
+  period: string;
+  startTs: number | null;
+  endTs: number | null;
+  cliKey: string | null;
+  providerId: number | null;
+  model: string | null;
+};
+export type CostScatterCliProviderModelRowV1 = {
+  cli_key: string;
+  provider_name: string;
+  model: string;
+  requests_success: number;
+  total_cost_usd: number;
+  total_duration_ms: number;
+};
+export type CostSummaryV1 = {
+  requests_total: number;
+  requests_success: number;
+  requests_failed: number;
+  cost_covered_success: number;
+  total_cost_usd: number;
+  avg_cost_usd_per_covered_success: number | null;
+};
+export type CostTopRequestRowV1 = {
+  log_id: number;
+  trace_id: string;
+  cli_key: string;
+  method: string;
+  path: string;
+  requested_model: string | null;
+  provider_id: number;
+  provider_name: string;
+  duration_ms: number;
+  ttfb_ms: number | null;
+  cost_usd: number;
+  cost_multiplier: number;
+  created_at: number;
+};
+export type CostTrendRowV1 = {
+  day: string;
+  hour: number | null;
+  cost_usd: number;
+  requests_success: number;
+  cost_covered_success: number;
+};
+export type DailyResetMode = "fixed" | "rolling";
+export type DataResetItem = {
+  /**
+   * `table`, `cli_proxy`, `mcp_servers`, `prompt`, `subagents` or `session_bindings`.
+   */
+  kind: string;
+  /**
+   * Table name or cli key.
+   */
+  target: string;
+  /**
+   * Rows / entries affected.
+   */
+  count: number;
+  /**
+   * Set when applying this item failed; other items are still applied.
+   */
+  error: string | null;
+};
+export type DataResetReport = {
+  scope: DataResetScope;
+  dry_run: boolean;
+  items: DataResetItem[];
+  /**
+   * Issued by dry runs only; pass it back to apply the reset.
+   */
+  confirm_token: string | null;
+  confirm_token_expires_at: number | null;
+};
+export type DataResetScope = "usage_stats" | "request_logs" | "gateway_state" | "cli_configs";
+export type DbCheckpointResult = {
+  /**
+   * `true` when a reader/writer prevented the checkpoint from completing.
+   */
+  busy: boolean;
+  wal_frames: number;
+  checkpointed_frames: number;
+  wal_bytes_after: number;
+  checkpointed_at: number;
+};
+export type DbDiskUsage = {
+  db_bytes: number;
+  wal_bytes: number;
+  shm_bytes: number;
+  total_bytes: number;
+};
+export type DbHealth = {
+  wal_bytes: number;
+  page_size: number;
+  page_count: number;
+  freelist_pages: number;
+  busy_waits: number;
+  busy_timeouts: number;
+  /**
+   * Unix seconds of the last manual checkpoint in this process; `None` if never run.
+   */
+  last_checkpoint_at: number | null;
+};
+export type DbQueryResult = {
+  columns: string[];
+  /**
+   * One array per row, in `columns` order; blobs are base64 strings.
+   */
+  rows: JsonValue[][];
+  /**
+   * `true` when the statement produced more than `max_rows` rows.
+   */
+  truncated: boolean;
+  elapsed_ms: number;
+};
+export type DefaultPromptSyncItem = { cli_key: string; action: string; message: string | null };
+export type DefaultPromptSyncReport = { items: DefaultPromptSyncItem[] };
+export type DnsIpStrategy = "system" | "ipv4_only";
+export type EnvConflict = { var_name: string; source_type: string; source_path: string };
+export type EnvConflictFixAction = "comment_out" | "rewrite";
+export type EnvConflictFixOutcome = {
+  var_name: string;
+  source_path: string;
+  ok: boolean;
+  error: string | null;
+};
+export type EnvConflictFixResult = {
+  /**
+   * Pass to `env_conflicts_fix_undo`; `None` when nothing was changed.
+   */
+  backup_id: string | null;
+  outcomes: EnvConflictFixOutcome[];
+};
+export type EnvConflictFixTarget = { var_name: string; source_type: string; source_path: string };
+/**
+ * Hides a reported env conflict; an empty `source_path` matches the variable from any source.
+ */
+export type EnvConflictIgnoreRule = { var_name: string; source_type: string; source_path: string };
+export type ErrorCatalogEntry = { code: string; title: string; hint: string };
+export type FailoverAttempt = {
+  provider_id: number;
+  provider_name: string;
+  base_url: string;
+  outcome: string;
+  status: number | null;
+  provider_index: number | null;
+  retry_index: number | null;
+  session_reuse: boolean | null;
+  error_category: string | null;
+  error_code: string | null;
+  decision: string | null;
+  reason: string | null;
+  selection_method: string | null;
+  reason_code: string | null;
+  attempt_started_ms: number | null;
+  attempt_duration_ms: number | null;
+  circuit_state_before: string | null;
+  circuit_state_after: string | null;
+  circuit_failure_count: number | null;
+  circuit_failure_threshold: number | null;
+  /**
+   * Provider error catalog code when the upstream error body was recognized.
+   */
+  error_catalog_code?: string | null;
+  /**
+   * Allow-listed upstream response headers (`captured_response_headers` setting).
+   */
+  response_headers?: Partial<{ [key in string]: string }> | null;
+  /**
+   * Redacted, truncated upstream error body (`upstream_error_body_capture_bytes` setting).
+   */
+  error_body?: string | null;
+  /**
+   * Wait applied after this attempt before retrying the same provider (`retry_backoff` setting).
+   */
+  retry_backoff_ms?: number | null;
+};
+export type GatewayActiveSessionSummary = {
+  cli_key: string;
+  session_id: string;
+  session_suffix: string;
+  provider_id: number;
+  provider_name: string;
+  expires_at: number;
+  request_count: number | null;
+  total_input_tokens: number | null;
+  total_output_tokens: number | null;
+  total_cost_usd: number | null;
+  total_duration_ms: number | null;
+};
+export type GatewayAttemptEvent = {
+  trace_id: string;
+  cli_key: string;
+  method: string;
+  path: string;
+  query: string | null;
+  attempt_index: number;
+  provider_id: number;
+  session_reuse: boolean | null;
+  provider_name: string;
+  base_url: string;
+  outcome: string;
+  status: number | null;
+  attempt_started_ms: number;
+  attempt_duration_ms: number;
+  circuit_state_before: string | null;
+  circuit_state_after: string | null;
+  circuit_failure_count: number | null;
+  circuit_failure_threshold: number | null;
+};
+export type GatewayCircuitEvent = {
+  trace_id: string;
+  cli_key: string;
+  provider_id: number;
+  provider_name: string;
+  base_url: string;
+  prev_state: string;
+  next_state: string;
+  failure_count: number;
+  failure_threshold: number;
+  open_until: number | null;
+  cooldown_until: number | null;
+  reason: string;
+  ts: number;
+};
+export type GatewayListenMode = "localhost" | "wsl_auto" | "lan" | "custom";
+export type GatewayLogEvent = {
+  level: string;
+  error_code: string;
+  message: string;
+  requested_port: number;
+  bound_port: number;
+  base_url: string;
+};
+export type GatewayOAuthPacingState = {
+  provider_id: number;
+  cli_key: string;
+  provider_name: string;
+  /**
+   * Sustainable rate learned from quota headers; `None` while no window constrains it.
+   */
+  requests_per_minute: number | null;
+  tokens: number;
+  burst: number;
+  paced_requests: number;
+  total_delay_ms: number;
+  last_delay_ms: number;
+  observed_at: number;
+  windows: GatewayOAuthQuotaWindow[];
+};
+export type GatewayOAuthQuotaWindow = {
+  /**
+   * `primary` / `secondary` (Codex usage windows) or `requests` (`x-ratelimit-*`).
+   */
+  name: string;
+  used_percent: number | null;
+  remaining_requests: number | null;
+  window_minutes: number | null;
+  resets_at: number;
+  /**
+   * Learned usage percent one request costs.
+   */
+  percent_per_request: number | null;
+};
+export type GatewayProviderCircuitStatus = {
+  provider_id: number;
+  state: string;
+  failure_count: number;
+  failure_threshold: number;
+  open_until: number | null;
+  cooldown_until: number | null;
+};
+/**
+ * Realtime per-provider load, computed from in-memory gateway counters.
+ */
+export type GatewayProviderLiveStats = {
+  provider_id: number;
+  cli_key: string;
+  provider_name: string;
+  /**
+   * Upstream attempts currently in flight (streams count until the body relay ends).
+   */
+  in_flight: number;
+  active_streams: number;
+  requests_last_minute: number;
+  /**
+   * Rolling average time to first byte over the last 20 successful attempts.
+   */
+  avg_ttft_ms: number | null;
+  circuit_state: string;
+};
+export type GatewayProviderQuality = {
+  provider_id: number;
+  cli_key: string;
+  provider_name: string;
+  window_seconds: number;
+  total: number;
+  errors: number;
+  truncated: number;
+  error_rate: number;
+  truncated_rate: number;
+  quarantined_until: number | null;
+  quarantine_count: number;
+};
+export type GatewayRequestEvent = {
+  trace_id: string;
+  cli_key: string;
+  method: string;
+  path: string;
+  query: string | null;
+  status: number | null;
+  error_category: string | null;
+  error_code: string | null;
+  duration_ms: number;
+  ttfb_ms: number | null;
+  attempts: FailoverAttempt[];
+  input_tokens: number | null;
+  output_tokens: number | null;
+  total_tokens: number | null;
+  cache_read_input_tokens: number | null;
+  cache_creation_input_tokens: number | null;
+  cache_creation_5m_input_tokens: number | null;
+  cache_creation_1h_input_tokens: number | null;
+};
+export type GatewayRequestStartEvent = {
+  trace_id: string;
+  cli_key: string;
+  method: string;
+  path: string;
+  query: string | null;
+  requested_model: string | null;
+  ts: number;
+};
+export type GatewayStatus = {
+  running: boolean;
+  port: number | null;
+  base_url: string | null;
+  listen_addr: string | null;
+  warm_pool: GatewayUpstreamWarmStatus[];
+};
+export type GatewayStreamStallEvent = {
+  trace_id: string;
+  cli_key: string;
+  provider_id: number;
+  provider_name: string;
+  /**
+   * `stalled` once upstream has been silent past the soft threshold, `resumed` when data
+   * flows again.
+   */
+  state: string;
+  idle_ms: number;
+  /**
+   * Hard idle timeout that will abort the stream, if one is configured.
+   */
+  idle_timeout_ms: number | null;
+  keepalive: boolean;
+  ts: number;
+};
+export type GatewayUpstreamWarmStatus = {
+  provider_id: number;
+  cli_key: string;
+  provider_name: string;
+  origin: string;
+  warm_connections: number;
+  http_version: string | null;
+  last_warmed_at: number | null;
+  last_error: string | null;
+};
+export type HookSummary = {
+  id: number;
+  name: string;
+  event: string;
+  matcher: string;
+  command: string;
+  timeout_secs: number | null;
+  enabled: boolean;
+  created_at: number;
+  updated_at: number;
+};
+export type HookTemplate = {
+  key: string;
+  name: string;
+  description: string;
+  event: string;
+  matcher: string;
+  command: string;
+  timeout_secs: number | null;
+};
+export type InstalledSkillSummary = {
+  id: number;
+  skill_key: string;
+  name: string;
+  description: string;
+  source_git_url: string;
+  source_branch: string;
+  source_subdir: string;
+  enabled: boolean;
+  /**
+   * Tag, branch or commit the skill is pinned to; `None` tracks `source_branch`.
+   */
+  pinned_ref: string | null;
+  installed_commit: string | null;
+  /**
+   * Remote commit seen by the last `skills_check_updates` run.
+   */
+  latest_commit: string | null;
+  update_available: boolean;
+  last_update_changed_files: SkillChangedFile[];
+  created_at: number;
+  updated_at: number;
+};
+export type JsonValue =
+  | null
+  | boolean
+  | number
+  | string
+  | JsonValue[]
+  | Partial<{ [key in string]: JsonValue }>;
+export type LocalSkillSummary = {
+  dir_name: string;
+  path: string;
+  name: string;
+  description: string;
+};
+export type McpDriftServer = {
+  server_key: string;
+  status: McpDriftStatus;
+  desired: JsonValue | null;
+  actual: JsonValue | null;
+};
+export type McpDriftStatus = "in_sync" | "modified" | "missing" | "stale" | "external";
+export type McpImportReport = { inserted: number; updated: number; skipped: McpImportSkip[] };
+export type McpImportServer = {
+  server_key: string;
+  name: string;
+  transport: string;
+  command: string | null;
+  args: string[];
+  env: Partial<{ [key in string]: string }>;
+  cwd: string | null;
+  url: string | null;
+  headers: Partial<{ [key in string]: string }>;
+  enabled: boolean;
+};
+export type McpImportSkip = { name: string; reason: string };
+export type McpParseResult = { servers: McpImportServer[] };
+export type McpRuntimeState = "starting" | "running" | "backoff" | "stopped" | "failed";
+export type McpServerRuntimeStatus = {
+  server_id: number;
+  server_key: string;
+  name: string;
+  state: McpRuntimeState;
+  pid: number | null;
+  started_at: number | null;
+  restart_count: number;
+  last_exit_code: number | null;
+  last_exit_at: number | null;
+  last_error: string | null;
+  cpu_percent: number | null;
+  memory_bytes: number | null;
+  /**
+   * Most recent stderr lines (oldest first).
+   */
+  stderr_tail: string[];
+};
+export type McpServerSummary = {
+  id: number;
+  server_key: string;
+  name: string;
+  transport: string;
+  command: string | null;
+  args: string[];
+  env: Partial<{ [key in string]: string }>;
+  cwd: string | null;
+  url: string | null;
+  headers: Partial<{ [key in string]: string }>;
+  enabled: boolean;
+  created_at: number;
+  updated_at: number;
+};
+export type McpSyncCliStatus = {
+  cli_key: string;
+  workspace_id: number | null;
+  target_exists: boolean;
+  parse_error: string | null;
+  in_sync: boolean;
+  servers: McpDriftServer[];
+};
+/**
+ * Capabilities of the models for `cli_key` matching `pattern` (same syntax as
+ * [`ModelDenyRule`]). `None` means unknown and never gates a request.
+ */
+export type ModelCapabilityRule = {
+  cli_key: string;
+  /**
+   * Restricts the rule to one provider; provider rules win over rules without one.
+   */
+  provider_id: number | null;
+  pattern: string;
+  vision: boolean | null;
+  tool_use: boolean | null;
+  thinking: boolean | null;
+  max_context_tokens: number | null;
+};
+/**
+ * Rejects requests for `cli_key` whose model matches `pattern` (case-insensitive; `*` matches
+ * any run of characters, so `claude-opus-*` or `*opus*` work alongside exact names).
+ */
+export type ModelDenyRule = { cli_key: string; pattern: string };
+export type ModelPriceAliasMatchTypeV1 = "exact" | "prefix" | "wildcard";
+export type ModelPriceAliasRuleV1 = {
+  cli_key: string;
+  match_type: ModelPriceAliasMatchTypeV1;
+  pattern: string;
+  target_model: string;
+  enabled: boolean;
+};
+export type ModelPriceAliasesV1 = { version: number; rules: ModelPriceAliasRuleV1[] };
+export type ModelPriceImportChange = {
+  line: number;
+  cli_key: string;
+  model: string;
+  /**
+   * `insert` | `update`
+   */
+  action: string;
+  before_json: string | null;
+  after_json: string;
+};
+export type ModelPriceImportIssue = { line: number; message: string };
+export type ModelPriceSummary = {
+  id: number;
+  cli_key: string;
+  model: string;
+  currency: string;
+  created_at: number;
+  updated_at: number;
 };
-export type ClaudeModels = {
-  main_model?: string | null;
-  reasoning_model?: string | null;
-  haiku_model?: string | null;
-  sonnet_model?: string | null;
-  opus_model?: string | null;
+export type ModelPricesImportReport = {
+  dry_run: boolean;
+  /**
+   * `false` for dry runs and whenever any row was rejected (imports are all-or-nothing).
+   */
+  applied: boolean;
+  inserted: number;
+  updated: number;
+  unchanged: number;
+  changes: ModelPriceImportChange[];
+  errors: ModelPriceImportIssue[];
+};
+export type ModelPricesSyncReport = {
+  status: string;
+  inserted: number;
+  updated: number;
+  skipped: number;
+  total: number;
+};
+export type NoticeEventPayload = { level: NoticeLevel; title: string; body: string };
+export type NoticeLevel = "info" | "success" | "warning" | "error";
+/**
+ * Notice events that can be routed to external sinks.
+ */
+export type NotificationEventKind = "circuit_open" | "budget_alert" | "gateway_failure";
+/**
+ * External notification channel; only events listed in `events` are delivered to it.
+ */
+export type NotificationSink = {
+  name: string;
+  kind: NotificationSinkKind;
+  url: string;
+  telegram_chat_id: string;
+  events: NotificationEventKind[];
+  enabled: boolean;
+};
+/**
+ * Delivery channel of a [`NotificationSink`].
+ */
+export type NotificationSinkKind = "webhook" | "slack" | "telegram";
+export type PromptConflictResolution = "adopt_local" | "overwrite";
+/**
+ * An enabled prompt whose CLI file was edited outside the hub since the last sync.
+ */
+export type PromptFileConflict = {
+  cli_key: string;
+  prompt_id: number | null;
+  prompt_name: string | null;
+  target_path: string;
+  db_content: string | null;
+  /**
+   * `None` when the file was deleted.
+   */
+  local_content: string | null;
+};
+export type PromptSummary = {
+  id: number;
+  workspace_id: number;
+  cli_key: string;
+  name: string;
+  content: string;
+  enabled: boolean;
+  created_at: number;
+  updated_at: number;
 };
-export type DailyResetMode = "fixed" | "rolling";
-export type GatewayListenMode = "localhost" | "wsl_auto" | "lan" | "custom";
 export type ProviderAuthMode = "api_key" | "oauth";
-export type ProviderBaseUrlMode = "order" | "ping";
+export type ProviderBaseUrlMode = "order" | "ping" | "race";
+export type ProviderBulkChange = { field: string; from: string; to: string };
+export type ProviderBulkOutcome = {
+  provider_id: number;
+  name: string | null;
+  ok: boolean;
+  error: string | null;
+  changes: ProviderBulkChange[];
+};
+/**
+ * Every `Some` field is applied to each listed provider; `None` leaves that field untouched.
+ */
+export type ProviderBulkUpdate = {
+  provider_ids: number[];
+  enabled: boolean | null;
+  /**
+   * Replaces the tag list.
+   */
+  tags: string[] | null;
+  /**
+   * Sets the multiplier outright; mutually exclusive with `cost_multiplier_scale`.
+   */
+  cost_multiplier: number | null;
+  /**
+   * Multiplies each provider's current multiplier.
+   */
+  cost_multiplier_scale: number | null;
+  /**
+   * Removes the providers from this sort mode (no-op for providers not in it).
+   */
+  remove_from_sort_mode_id: number | null;
+  /**
+   * Appends the providers to the end of this sort mode (no-op for providers already in it).
+   */
+  add_to_sort_mode_id: number | null;
+};
+export type ProviderBulkUpdateResult = {
+  dry_run: boolean;
+  /**
+   * False when `dry_run` is set or any provider failed validation (nothing is written then).
+   */
+  applied: boolean;
+  outcomes: ProviderBulkOutcome[];
+};
+export type ProviderCredentialStatus = {
+  provider_id: number;
+  cli_key: string;
+  name: string;
+  enabled: boolean;
+  auth_mode: string;
+  /**
+   * OAuth: seconds since the last token refresh; API key: since the provider was last saved.
+   */
+  credential_age_secs: number | null;
+  /**
+   * Latest 2xx from this provider within the activity window.
+   */
+  last_success_at: number | null;
+  /**
+   * Latest 401/403 from this provider within the activity window.
+   */
+  last_auth_failure_at: number | null;
+  last_auth_failure_status: number | null;
+  /**
+   * OAuth only; negative once the access token has expired.
+   */
+  oauth_expires_in_secs: number | null;
+  /**
+   * OAuth only: `false` when the last refresh left an error behind.
+   */
+  oauth_last_refresh_ok: boolean | null;
+  oauth_last_error: string | null;
+  window_secs: number;
+};
+export type ProviderDnsOptions = {
+  provider_id: number;
+  ip_strategy: DnsIpStrategy;
+  /**
+   * DNS-over-HTTPS endpoint speaking the JSON API (`?name=&type=`); `None` = system resolver.
+   */
+  doh_url: string | null;
+  /**
+   * Probe all resolved addresses in parallel and connect to the fastest first.
+   */
+  race_connections: boolean;
+};
+export type ProviderKind = "standard" | "aggregated" | "federated";
+export type ProviderLimitUsageRow = {
+  cli_key: string;
+  provider_id: number;
+  provider_name: string;
+  enabled: boolean;
+  limit_5h_usd: number | null;
+  limit_daily_usd: number | null;
+  daily_reset_mode: string | null;
+  daily_reset_time: string | null;
+  limit_weekly_usd: number | null;
+  limit_monthly_usd: number | null;
+  limit_total_usd: number | null;
+  usage_5h_usd: number;
+  usage_daily_usd: number;
+  usage_weekly_usd: number;
+  usage_monthly_usd: number;
+  usage_total_usd: number;
+  window_5h_start_ts: number;
+  window_daily_start_ts: number;
+  window_weekly_start_ts: number;
+  window_monthly_start_ts: number;
+};
+/**
+ * A single concrete occurrence of a (possibly repeating) maintenance window.
+ */
+export type ProviderMaintenanceOccurrence = {
+  window_id: number;
+  starts_at: number;
+  ends_at: number;
+};
+export type ProviderMaintenanceWindow = {
+  id: number;
+  provider_id: number;
+  starts_at: number;
+  ends_at: number;
+  /**
+   * When set, the window repeats every `repeat_interval_secs` after `starts_at`.
+   */
+  repeat_interval_secs: number | null;
+  note: string;
+  created_at: number;
+  updated_at: number;
+};
+export type ProviderMaintenanceWindowInput = {
+  windowId: number | null;
+  providerId: number;
+  startsAt: number;
+  endsAt: number;
+  repeatIntervalSecs: number | null;
+  note: string | null;
+};
+export type ProviderModelCatalog = {
+  provider_id: number;
+  models: string[];
+  refreshed_at: number | null;
+  error: string | null;
+};
+export type ProviderMonthlyBudgetRow = {
+  cli_key: string;
+  provider_id: number;
+  provider_name: string;
+  /**
+   * Calendar month in local time, `YYYY-MM`.
+   */
+  month: string;
+  budget_usd: number;
+  /**
+   * Extra allowance granted for this month only.
+   */
+  grace_usd: number;
+  spent_usd: number;
+  remaining_usd: number;
+  blocked: boolean;
+  notified_80_at: number | null;
+  notified_100_at: number | null;
+  month_start_ts: number;
+  next_month_start_ts: number;
+};
+export type ProviderScore = {
+  provider_id: number;
+  provider_name: string;
+  /**
+   * Weighted score in `0..=100`.
+   */
+  score: number;
+  attempts: number;
+  success_rate: number | null;
+  p95_duration_ms: number | null;
+  cost_multiplier: number;
+  /**
+   * Smallest remaining share across configured spend limits (`None` without limits).
+   */
+  quota_headroom: number | null;
+  explanations: string[];
+};
 export type ProviderSummary = {
   id: number;
   cli_key: string;
@@ -113,6 +4249,10 @@ export type ProviderSummary = {
   limit_weekly_usd: number | null;
   limit_monthly_usd: number | null;
   limit_total_usd: number | null;
+  /**
+   * Hard calendar-month budget; the provider is blocked once this month's spend reaches it.
+   */
+  monthly_budget_usd: number | null;
   tags: string[];
   note: string;
   created_at: number;
@@ -122,6 +4262,56 @@ export type ProviderSummary = {
   oauth_email: string | null;
   oauth_expires_at: number | null;
   oauth_last_error: string | null;
+  maintenance_active: ProviderMaintenanceOccurrence | null;
+  maintenance_next: ProviderMaintenanceOccurrence | null;
+  provider_kind: ProviderKind;
+  model_catalog: string[];
+  model_catalog_refreshed_at: number | null;
+  model_catalog_error: string | null;
+  /**
+   * Kept out of normal routing; tried once when every other provider failed.
+   */
+  last_resort: boolean;
+  /**
+   * claude only: the gateway answers `GET /v1/models` from the model slots itself.
+   */
+  emulate_models_endpoint: boolean;
+  /**
+   * claude only: caps `thinking.budget_tokens` on outgoing requests; `0` strips thinking.
+   */
+  thinking_budget_cap: number | null;
+  /**
+   * Set when the provider was deleted (archived); it keeps its row so stats still resolve
+   * its name, and is removed for good by `purge`.
+   */
+  archived_at: number | null;
+  /**
+   * Case-insensitive phrases marking a 2xx answer as a relay refusal; empty = detection off.
+   */
+  refusal_patterns: string[];
+  /**
+   * claude only: overrides the `anthropic-version` header sent upstream.
+   */
+  anthropic_version: string | null;
+  /**
+   * claude only: betas merged into `anthropic-beta` on outgoing requests.
+   */
+  anthropic_betas: string[];
+  /**
+   * claude only: drop client-sent betas not in `anthropic_betas` (relays rejecting unknown betas).
+   */
+  anthropic_beta_strip: boolean;
+  /**
+   * POSTed when the provider's circuit opens or closes, or it gets quarantined.
+   */
+  webhook_url: string | null;
+};
+export type ProviderTagDefault = {
+  cli_key: string;
+  /**
+   * `None` = no default; every provider of the cli stays eligible.
+   */
+  tag: string | null;
 };
 export type ProviderUpsertInput = {
   providerId: number | null;
@@ -144,6 +4334,297 @@ export type ProviderUpsertInput = {
   limitTotalUsd: number | null;
   tags: string[] | null;
   note: string | null;
+  providerKind: ProviderKind | null;
+};
+export type ProviderUsageAudit = {
+  provider_id: number;
+  cli_key: string;
+  name: string;
+  archived_at: number | null;
+  /**
+   * Request logs whose final provider is this one; purging keeps them but loses the name.
+   */
+  request_log_count: number;
+  last_used_at: number | null;
+  total_cost_usd: number;
+  /**
+   * Grace allowance granted on this month's budget.
+   */
+  current_month_grace_usd: number;
+  /**
+   * A granted budget grace is still in effect this month; purge is refused until it lapses.
+   */
+  budget_hold: boolean;
+};
+export type ProviderValidationCheck = "claude_messages" | "models_list";
+export type ProviderValidationEntry = {
+  provider_id: number;
+  cli_key: string;
+  provider_name: string;
+  check: ProviderValidationCheck;
+  ok: boolean;
+  base_url: string | null;
+  status: number | null;
+  duration_ms: number;
+  responded_model: string | null;
+  models_count: number | null;
+  error: string | null;
+};
+export type ProviderValidationReport = {
+  generated_at: number;
+  /**
+   * True when every enabled provider passed.
+   */
+  ok: boolean;
+  total: number;
+  passed: number;
+  failed: number;
+  entries: ProviderValidationEntry[];
+};
+/**
+ * User-defined regex applied to logs/events after the built-in credential rules.
+ */
+export type RedactionRule = {
+  name: string;
+  pattern: string;
+  replacement: string;
+  enabled: boolean;
+};
+export type RequestAttemptLog = {
+  id: number;
+  trace_id: string;
+  cli_key: string;
+  attempt_index: number;
+  provider_id: number;
+  provider_name: string;
+  base_url: string;
+  outcome: string;
+  status: number | null;
+  attempt_started_ms: number;
+  attempt_duration_ms: number;
+  bytes_received: number | null;
+  chunk_count: number | null;
+  max_chunk_gap_ms: number | null;
+  /**
+   * Allow-listed upstream response headers (`captured_response_headers` setting).
+   */
+  response_headers: Partial<{ [key in string]: string }> | null;
+  /**
+   * Redacted, truncated upstream error body (`upstream_error_body_capture_bytes` setting).
+   */
+  error_body: string | null;
+  /**
+   * Gateway wait after this attempt before retrying the same provider (`retry_backoff` setting).
+   */
+  retry_backoff_ms: number | null;
+  created_at: number;
+};
+export type RequestLogDetail = {
+  id: number;
+  trace_id: string;
+  cli_key: string;
+  method: string;
+  path: string;
+  query: string | null;
+  excluded_from_stats: boolean;
+  special_settings_json: string | null;
+  status: number | null;
+  error_code: string | null;
+  duration_ms: number;
+  ttfb_ms: number | null;
+  attempts_json: string;
+  input_tokens: number | null;
+  output_tokens: number | null;
+  total_tokens: number | null;
+  cache_read_input_tokens: number | null;
+  cache_creation_input_tokens: number | null;
+  cache_creation_5m_input_tokens: number | null;
+  cache_creation_1h_input_tokens: number | null;
+  usage_json: string | null;
+  requested_model: string | null;
+  cost_usd: number | null;
+  cost_multiplier: number;
+  error_catalog_code: string | null;
+  /**
+   * Allow-listed upstream response headers of the relayed attempt (JSON object).
+   */
+  response_headers_json: string | null;
+  created_at_ms: number;
+  created_at: number;
+};
+export type RequestLogRouteHop = {
+  provider_id: number;
+  provider_name: string;
+  ok: boolean;
+  attempts: number;
+  /**
+   * 该 provider 是否被跳过（熔断/限流等，请求未实际发送）
+   */
+  skipped?: boolean;
+  status?: number | null;
+  error_code?: string | null;
+  decision?: string | null;
+  reason?: string | null;
+};
+export type RequestLogSummary = {
+  id: number;
+  trace_id: string;
+  cli_key: string;
+  method: string;
+  path: string;
+  requested_model: string | null;
+  status: number | null;
+  error_code: string | null;
+  duration_ms: number;
+  ttfb_ms: number | null;
+  attempt_count: number;
+  has_failover: boolean;
+  start_provider_id: number;
+  start_provider_name: string;
+  final_provider_id: number;
+  final_provider_name: string;
+  route: RequestLogRouteHop[];
+  session_reuse: boolean;
+  input_tokens: number | null;
+  output_tokens: number | null;
+  total_tokens: number | null;
+  cache_read_input_tokens: number | null;
+  cache_creation_input_tokens: number | null;
+  cache_creation_5m_input_tokens: number | null;
+  cache_creation_1h_input_tokens: number | null;
+  cost_usd: number | null;
+  cost_multiplier: number;
+  error_catalog_code: string | null;
+  /**
+   * Allow-listed upstream response headers of the relayed attempt (JSON object).
+   */
+  response_headers_json: string | null;
+  created_at_ms: number;
+  created_at: number;
+};
+export type RequestMirrorLogRow = {
+  id: number;
+  trace_id: string;
+  cli_key: string;
+  provider_id: number;
+  provider_name: string;
+  method: string;
+  path: string;
+  requested_model: string | null;
+  status: number | null;
+  error: string | null;
+  ttfb_ms: number | null;
+  duration_ms: number;
+  input_tokens: number | null;
+  output_tokens: number | null;
+  cache_read_input_tokens: number | null;
+  cache_creation_input_tokens: number | null;
+  created_at: number;
+};
+export type RequestMirrorProviderStats = {
+  provider_id: number;
+  provider_name: string;
+  cli_key: string;
+  requests_total: number;
+  requests_success: number;
+  avg_ttfb_ms: number | null;
+  avg_duration_ms: number | null;
+  input_tokens: number;
+  output_tokens: number;
+};
+/**
+ * Waterfall of one request, reconstructed from `request_logs` timing and `attempts_json`.
+ */
+export type RequestTimeline = {
+  trace_id: string;
+  duration_ms: number;
+  ttfb_ms: number | null;
+  segments: RequestTimelineSegment[];
+};
+export type RequestTimelineSegment = {
+  kind: RequestTimelineSegmentKind;
+  label: string;
+  /**
+   * Milliseconds since the gateway received the request.
+   */
+  start_ms: number;
+  end_ms: number;
+  attempt_index: number | null;
+  provider_id: number | null;
+  outcome: string | null;
+};
+export type RequestTimelineSegmentKind =
+  | "queue"
+  | "upstream"
+  | "stream"
+  | "gap"
+  | "backoff"
+  | "skipped";
+/**
+ * Legacy charset the response fixer may transcode non-UTF-8 bodies from.
+ */
+export type ResponseFixerCharset = "gbk" | "big5" | "shift_jis";
+export type RetryBackoffPolicy = {
+  strategy: RetryBackoffStrategy;
+  base_ms: number;
+  max_ms: number;
+};
+/**
+ * Retry backoff per error category, applied only when the same provider is retried.
+ */
+export type RetryBackoffSettings = {
+  /**
+   * 408 / 429 responses.
+   */
+  rate_limited: RetryBackoffPolicy;
+  /**
+   * Retryable 5xx responses.
+   */
+  upstream_error: RetryBackoffPolicy;
+  /**
+   * Timeouts, connection failures and broken streams.
+   */
+  network_error: RetryBackoffPolicy;
+};
+/**
+ * How the failover loop waits before retrying the same provider.
+ */
+export type RetryBackoffStrategy = "none" | "fixed" | "linear" | "exponential_jitter";
+export type SelfTestCheck = {
+  /**
+   * `db_migrations` | `settings` | `gateway_port` | `cli_config_write` | `wsl_reachability`.
+   */
+  check: string;
+  status: SelfTestStatus;
+  /**
+   * A failed critical check leaves the gateway unusable and triggers `app:selftest_failed`.
+   */
+  critical: boolean;
+  message: string;
+  /**
+   * What the user can do about a non-ok result.
+   */
+  action: string | null;
+};
+export type SelfTestReport = {
+  ran_at: number;
+  /**
+   * `false` when any critical check failed.
+   */
+  ok: boolean;
+  checks: SelfTestCheck[];
+};
+export type SelfTestStatus = "ok" | "warning" | "failed" | "skipped";
+export type SessionBindingSummary = {
+  cli_key: string;
+  session_id: string;
+  session_suffix: string;
+  provider_id: number;
+  provider_name: string | null;
+  sort_mode_id: number | null;
+  provider_tag: string | null;
+  pinned: boolean;
+  expires_at: number;
 };
 /**
  * Encapsulates all fields for the `settings_set` command.
@@ -157,11 +4638,29 @@ export type SettingsUpdate = {
   trayEnabled: boolean | null;
   enableCliProxyStartupRecovery: boolean | null;
   logRetentionDays: number;
+  attemptLogRetentionDays: number | null;
   providerCooldownSeconds: number | null;
   providerBaseUrlPingCacheTtlSeconds: number | null;
   upstreamFirstByteTimeoutSeconds: number | null;
   upstreamStreamIdleTimeoutSeconds: number | null;
   upstreamRequestTimeoutNonStreamingSeconds: number | null;
+  countTokensHedgeDelayMs: number | null;
+  countTokensLocalEstimateEnabled: boolean | null;
+  firstByteRaceDelayMs: number | null;
+  nonStreamingHedgeDelayMs: number | null;
+  upstreamWarmPoolEnabled: boolean | null;
+  upstreamWarmPoolSize: number | null;
+  requestMirrorEnabled: boolean | null;
+  requestMirrorProviderId: number | null;
+  requestMirrorPercent: number | null;
+  requestDedupEnabled: boolean | null;
+  streamTruncationRecoveryEnabled: boolean | null;
+  sseCoalesceFlushMs: number | null;
+  sseCoalesceFlushBytes: number | null;
+  streamStallWarnSeconds: number | null;
+  streamStallKeepaliveEnabled: boolean | null;
+  streamFirstByteFailoverEnabled: boolean | null;
+  streamErrorEventEnabled: boolean | null;
   interceptAnthropicWarmupRequests: boolean | null;
   enableThinkingSignatureRectifier: boolean | null;
   enableThinkingBudgetRectifier: boolean | null;
@@ -177,13 +4676,544 @@ export type SettingsUpdate = {
   failoverMaxProvidersToTry: number;
   circuitBreakerFailureThreshold: number | null;
   circuitBreakerOpenDurationMinutes: number | null;
+  circuitBreakerHalfOpenProbeEnabled: boolean | null;
   updateReleasesUrl: string | null;
   wslAutoConfig: boolean | null;
   wslTargetCli: WslTargetCli | null;
   wslHostAddressMode: WslHostAddressMode | null;
   wslCustomHostAddress: string | null;
+  uiLanguage: UiLanguage | null;
+};
+export type SimpleCliInfo = {
+  found: boolean;
+  executable_path: string | null;
+  version: string | null;
+  error: string | null;
+  shell: string | null;
+  resolved_via: string;
+};
+export type SkillChangedFile = {
+  path: string;
+  /**
+   * One of `added`, `modified`, `removed`.
+   */
+  change: string;
+};
+export type SkillDependency = {
+  kind: SkillDependencyKind;
+  name: string;
+  status: SkillDependencyStatus;
+  /**
+   * Resolved binary path, or the id of the installed skill / MCP server.
+   */
+  detail: string | null;
+  /**
+   * For missing skills: a matching skill found in the same source repository.
+   */
+  install_candidate: AvailableSkillSummary | null;
+};
+export type SkillDependencyKind = "skill" | "mcp_server" | "binary";
+export type SkillDependencyReport = {
+  skill_name: string;
+  /**
+   * True when every declared dependency is satisfied; enabling is blocked otherwise.
+   */
+  satisfied: boolean;
+  dependencies: SkillDependency[];
+};
+export type SkillDependencyStatus = "satisfied" | "disabled" | "missing";
+export type SkillImportIssue = { dir_name: string; error_code: string | null; message: string };
+export type SkillImportLocalBatchReport = {
+  imported: InstalledSkillSummary[];
+  skipped: SkillImportIssue[];
+  failed: SkillImportIssue[];
+};
+export type SkillPackageFile = {
+  /**
+   * Path relative to the skill directory, `/`-separated.
+   */
+  path: string;
+  size: number;
+  sha256: string;
+};
+export type SkillPackagePush = {
+  repo_id: number;
+  git_url: string;
+  branch: string;
+  commit: string;
+  /**
+   * `false` when the repo already held an identical copy and nothing was pushed.
+   */
+  changed: boolean;
+};
+export type SkillPackageReport = {
+  name: string;
+  description: string;
+  dir_name: string;
+  archive_path: string;
+  /**
+   * SHA-256 of the archive, also written next to it as `<archive>.sha256`.
+   */
+  checksum_sha256: string;
+  files: SkillPackageFile[];
+  total_bytes: number;
+  /**
+   * Non-blocking lint findings; blocking ones fail the command instead.
+   */
+  warnings: string[];
+  push: SkillPackagePush | null;
+};
+export type SkillRepoAccessReport = {
+  ok: boolean;
+  auth_kind: SkillRepoAuthKind;
+  /**
+   * Remote commit of the repo branch when access succeeded.
+   */
+  commit: string | null;
+  /**
+   * e.g. `SKILL_REPO_AUTH_FAILED`, `SKILL_GITHUB_REPO_NOT_FOUND`, `SKILL_GIT_ERROR`.
+   */
+  error_code: string | null;
+  message: string | null;
+};
+export type SkillRepoAuthKind = "none" | "token" | "ssh_key";
+export type SkillRepoSummary = {
+  id: number;
+  git_url: string;
+  branch: string;
+  enabled: boolean;
+  /**
+   * Credential used for private hosts; a stored token is never returned.
+   */
+  auth_kind: SkillRepoAuthKind;
+  ssh_key_path: string | null;
+  created_at: number;
+  updated_at: number;
+};
+export type SkillUpdateReport = {
+  skill_id: number;
+  from_commit: string | null;
+  to_commit: string;
+  changed_files: SkillChangedFile[];
+  /**
+   * `false` for previews (`dry_run`), where nothing on disk or in the db was touched.
+   */
+  applied: boolean;
+};
+export type SkillUpdateStatus = {
+  skill_id: number;
+  skill_key: string;
+  name: string;
+  pinned_ref: string | null;
+  installed_commit: string | null;
+  latest_commit: string | null;
+  update_available: boolean;
+  error: string | null;
+  checked_at: number;
+};
+export type SkillsPaths = { ssot_dir: string; repos_dir: string; cli_dir: string };
+export type SortModeActiveRow = { cli_key: string; mode_id: number | null; updated_at: number };
+export type SortModeFallbackChain = {
+  cli_key: string;
+  /**
+   * Modes tried in order when the active mode has no available provider; `None` is the
+   * default provider ordering.
+   */
+  mode_ids: (number | null)[];
+};
+export type SortModeProviderRow = {
+  provider_id: number;
+  enabled: boolean;
+  /**
+   * Local-time window (`HH:MM`, end exclusive, may wrap midnight) during which the entry keeps
+   * its position; outside it the gateway moves the provider behind in-window entries.
+   */
+  active_start: string | null;
+  active_end: string | null;
+};
+export type SortModeSchedule = {
+  cli_key: string;
+  enabled: boolean;
+  /**
+   * Fixed UTC offset the rules are evaluated in; `None` follows the system time zone.
+   */
+  utc_offset_minutes: number | null;
+  /**
+   * Evaluated in order, first match wins.
+   */
+  rules: SortModeScheduleRule[];
+  /**
+   * When no rule matches, switch to `otherwise_mode_id`; when off, the current mode is kept.
+   */
+  otherwise_enabled: boolean;
+  otherwise_mode_id: number | null;
+  updated_at: number;
 };
+export type SortModeScheduleRule = {
+  /**
+   * Weekdays the rule applies to, bit 0 = Sunday ... bit 6 = Saturday. For windows that wrap
+   * midnight the day is the one the window starts on.
+   */
+  days_mask: number;
+  /**
+   * `HH:MM` window (end exclusive, may wrap midnight); both `None` covers the whole day.
+   */
+  start: string | null;
+  end: string | null;
+  /**
+   * Mode to activate; `None` is the default provider ordering.
+   */
+  mode_id: number | null;
+};
+export type SortModeSummary = {
+  id: number;
+  name: string;
+  /**
+   * `manual` keeps the configured order; `adaptive` re-ranks providers by recent p95 latency
+   * and success rate.
+   */
+  strategy: string;
+  created_at: number;
+  updated_at: number;
+};
+export type SortOrderSuggestion = {
+  cli_key: string;
+  /**
+   * `None` = the default provider order (no sort mode).
+   */
+  mode_id: number | null;
+  window_secs: number;
+  current_order: number[];
+  suggested_order: number[];
+  changed: boolean;
+  /**
+   * In suggested order.
+   */
+  providers: ProviderScore[];
+};
+export type SshConfigureHostReport = {
+  name: string;
+  ok: boolean;
+  results: WslConfigureCliReport[];
+};
+export type SshConfigureReport = { ok: boolean; message: string; hosts: SshConfigureHostReport[] };
+/**
+ * Remote Linux machine whose CLIs are pointed at this gateway over SSH (see `ssh_clients`).
+ */
+export type SshHost = {
+  name: string;
+  /**
+   * `user@host` or a `Host` alias from `~/.ssh/config`.
+   */
+  destination: string;
+  port: number | null;
+  identity_file: string;
+  /**
+   * Address the remote uses to reach this machine; empty means `127.0.0.1` through a reverse
+   * tunnel forwarding the gateway port (`ssh -R <port>:127.0.0.1:<port>`).
+   */
+  gateway_host: string;
+  targets: WslTargetCli;
+  /**
+   * Re-applied on startup and whenever the gateway port changes.
+   */
+  auto_sync: boolean;
+};
+export type SshHostCheckReport = {
+  name: string;
+  ssh_ok: boolean;
+  /**
+   * `uname -sm` of the remote host.
+   */
+  remote_system: string | null;
+  gateway_origin: string;
+  /**
+   * Whether the remote host got an HTTP 200 from the gateway `/health` endpoint.
+   */
+  gateway_reachable: boolean;
+  message: string;
+};
+export type SubagentSummary = {
+  id: number;
+  name: string;
+  description: string;
+  prompt: string;
+  tools: string[];
+  model: string | null;
+  enabled: boolean;
+  created_at: number;
+  updated_at: number;
+};
+export type UiLanguage = "zh" | "en";
+export type UsageBandwidthRowV1 = {
+  /**
+   * `YYYY-MM-DD` (local time).
+   */
+  day: string;
+  cli_key: string;
+  provider_id: number;
+  provider_name: string | null;
+  requests: number;
+  /**
+   * Requests with recorded body sizes (older logs have none).
+   */
+  requests_with_bytes: number;
+  request_bytes: number;
+  response_bytes: number;
+};
+export type UsageDayRow = {
+  day: string;
+  requests_total: number;
+  input_tokens: number;
+  output_tokens: number;
+  total_tokens: number;
+  cache_read_input_tokens: number;
+  cache_creation_input_tokens: number;
+  cache_creation_5m_input_tokens: number;
+  cache_creation_1h_input_tokens: number;
+};
+export type UsageForecastRowV1 = {
+  provider_id: number;
+  cli_key: string;
+  provider_name: string;
+  month_to_date_tokens: number;
+  expected_total_tokens: number;
+  expected_total_tokens_low: number;
+  expected_total_tokens_high: number;
+  month_to_date_cost_usd: number;
+  expected_total_cost_usd: number;
+  expected_total_cost_usd_low: number;
+  expected_total_cost_usd_high: number;
+  /**
+   * Tighter of `limit_monthly_usd` / `monthly_budget_usd`.
+   */
+  budget_usd: number | null;
+  /**
+   * 0 = today; `None` when the budget is not reached before the month ends.
+   */
+  days_until_budget_exhausted: number | null;
+};
+export type UsageForecastV1 = {
+  /**
+   * `YYYY-MM` (local time).
+   */
+  month: string;
+  day_of_month: number;
+  days_in_month: number;
+  history_days: number;
+  rows: UsageForecastRowV1[];
+};
+export type UsageHeatmapCellV1 = {
+  /**
+   * Local weekday, `0` = Sunday … `6` = Saturday.
+   */
+  weekday: number;
+  /**
+   * Local hour `0..=23`.
+   */
+  hour: number;
+  requests: number;
+  input_tokens: number;
+  output_tokens: number;
+  total_tokens: number;
+  cost_usd: number;
+};
+export type UsageHourlyRow = {
+  day: string;
+  hour: number;
+  requests_total: number;
+  requests_with_usage: number;
+  requests_success: number;
+  requests_failed: number;
+  total_tokens: number;
+};
+export type UsageImportResult = {
+  source: string;
+  rows_read: number;
+  rows_imported: number;
+  rows_skipped: number;
+};
+export type UsageLeaderboardRow = {
+  key: string;
+  name: string;
+  requests_total: number;
+  requests_success: number;
+  requests_failed: number;
+  total_tokens: number;
+  io_total_tokens: number;
+  input_tokens: number;
+  output_tokens: number;
+  cache_creation_input_tokens: number;
+  cache_read_input_tokens: number;
+  avg_duration_ms: number | null;
+  avg_ttfb_ms: number | null;
+  avg_output_tokens_per_second: number | null;
+  cost_usd: number | null;
+};
+export type UsageProviderCacheRateTrendRowV1 = {
+  day: string;
+  hour: number | null;
+  key: string;
+  name: string;
+  denom_tokens: number;
+  cache_read_input_tokens: number;
+  requests_success: number;
+};
+export type UsageProviderRow = {
+  cli_key: string;
+  provider_id: number;
+  provider_name: string;
+  requests_total: number;
+  requests_success: number;
+  requests_failed: number;
+  avg_duration_ms: number | null;
+  avg_ttfb_ms: number | null;
+  avg_output_tokens_per_second: number | null;
+  input_tokens: number;
+  output_tokens: number;
+  total_tokens: number;
+  cache_read_input_tokens: number;
+  cache_creation_input_tokens: number;
+  cache_creation_5m_input_tokens: number;
+  cache_creation_1h_input_tokens: number;
+};
+export type UsageQueryParams = {
+  period: string;
+  startTs: number | null;
+  endTs: number | null;
+  cliKey: string | null;
+  providerId: number | null;
+};
+export type UsageSummary = {
+  requests_total: number;
+  requests_with_usage: number;
+  requests_success: number;
+  requests_failed: number;
+  avg_duration_ms: number | null;
+  avg_ttfb_ms: number | null;
+  avg_output_tokens_per_second: number | null;
+  input_tokens: number;
+  output_tokens: number;
+  io_total_tokens: number;
+  total_tokens: number;
+  cache_read_input_tokens: number;
+  cache_creation_input_tokens: number;
+  cache_creation_5m_input_tokens: number;
+  cache_creation_1h_input_tokens: number;
+};
+export type WorkspaceApplyReport = {
+  cli_key: string;
+  from_workspace_id: number | null;
+  to_workspace_id: number;
+  applied_at: number;
+};
+export type WorkspaceEnabledPromptPreview = { name: string; excerpt: string };
+export type WorkspaceItemsPreview = {
+  from_enabled: string[];
+  to_enabled: string[];
+  added: string[];
+  removed: string[];
+};
+export type WorkspacePreview = {
+  cli_key: string;
+  from_workspace_id: number | null;
+  to_workspace_id: number;
+  prompts: WorkspacePromptsPreview;
+  mcp: WorkspaceItemsPreview;
+  skills: WorkspaceItemsPreview;
+};
+export type WorkspacePromptsPreview = {
+  from_enabled: WorkspaceEnabledPromptPreview | null;
+  to_enabled: WorkspaceEnabledPromptPreview | null;
+  will_change: boolean;
+};
+export type WorkspaceRollbackReport = {
+  cli_key: string;
+  snapshot_id: number;
+  workspace_id: number;
+  /**
+   * Snapshot of the state replaced by this rollback, so it can be undone.
+   */
+  undo_snapshot_id: number;
+  rolled_back_at: number;
+};
+export type WorkspaceSnapshotSummary = {
+  id: number;
+  cli_key: string;
+  workspace_id: number | null;
+  /**
+   * `workspace_apply` or `rollback` (state captured right before a rollback).
+   */
+  reason: string;
+  enabled_prompt_name: string | null;
+  enabled_mcp_server_keys: string[];
+  enabled_skill_keys: string[];
+  created_at: number;
+};
+export type WorkspaceSummary = {
+  id: number;
+  cli_key: string;
+  name: string;
+  created_at: number;
+  updated_at: number;
+};
+export type WorkspacesListResult = { active_id: number | null; items: WorkspaceSummary[] };
+export type WslConfigureCliReport = { cli_key: string; ok: boolean; message: string };
+export type WslConfigureDistroReport = {
+  distro: string;
+  ok: boolean;
+  results: WslConfigureCliReport[];
+};
+export type WslConfigureReport = {
+  ok: boolean;
+  message: string;
+  distros: WslConfigureDistroReport[];
+};
+export type WslDetection = { detected: boolean; distros: string[] };
+export type WslDiagnosticFinding = {
+  /**
+   * `distro_present` | `networking_mode` | `listen_mode` | `host_reachable` |
+   * `firewall_rule` | `client_config`.
+   */
+  check: string;
+  distro: string | null;
+  severity: WslFindingSeverity;
+  message: string;
+  repair: WslRepairAction | null;
+};
+export type WslDiagnosticsReport = {
+  detected: boolean;
+  networking_mode: WslNetworkingMode;
+  /**
+   * Origin the distros are expected to use, e.g. `http://172.20.0.1:37123`.
+   */
+  gateway_origin: string | null;
+  findings: WslDiagnosticFinding[];
+};
+export type WslDistroConfigStatus = {
+  distro: string;
+  claude: boolean;
+  codex: boolean;
+  gemini: boolean;
+  claude_mcp: boolean;
+  codex_mcp: boolean;
+  gemini_mcp: boolean;
+  claude_prompt: boolean;
+  codex_prompt: boolean;
+  gemini_prompt: boolean;
+};
+export type WslFindingSeverity = "ok" | "warning" | "error";
 export type WslHostAddressMode = "auto" | "custom";
+export type WslNetworkingMode = "nat" | "mirrored" | "other";
+export type WslRepairAction = "add_firewall_rule" | "configure_clients";
+export type WslRepairOutcome = { action: WslRepairAction; ok: boolean; message: string };
+export type WslRepairReport = {
+  outcomes: WslRepairOutcome[];
+  /**
+   * Diagnostics re-run after the repairs.
+   */
+  diagnostics: WslDiagnosticsReport;
+};
 export type WslTargetCli = { claude: boolean; codex: boolean; gemini: boolean };
 
 /** tauri-specta globals **/
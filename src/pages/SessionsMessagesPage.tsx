@@ -28,7 +28,7 @@ import {
 } from "../utils/formatters";
 
 function normalizeSource(raw: string | undefined): CliSessionsSource | null {
-  if (raw === "claude" || raw === "codex") return raw;
+  if (raw === "claude" || raw === "codex" || raw === "gemini") return raw;
   return null;
 }
 
@@ -42,6 +42,7 @@ function safeDecodeURIComponent(raw: string) {
 
 function buildResumeCommand(source: CliSessionsSource, sessionId: string) {
   const escapedId = escapeShellArg(sessionId);
+  if (source === "gemini") return `gemini --resume ${escapedId}`;
   return source === "claude" ? `claude --resume ${escapedId}` : `codex resume ${escapedId}`;
 }
 
@@ -147,7 +148,10 @@ function messageSide(roleRaw: string): MessageSide {
 function senderLabel(source: CliSessionsSource, roleRaw: string) {
   const role = roleRaw.trim().toLowerCase();
   if (role === "user") return "你";
-  if (role === "assistant") return source === "claude" ? "Claude" : "Codex";
+  if (role === "assistant") {
+    if (source === "gemini") return "Gemini";
+    return source === "claude" ? "Claude" : "Codex";
+  }
   if (role === "system") return "System";
   if (role.startsWith("tool")) return "Tool";
   return roleRaw || "unknown";
@@ -229,7 +233,7 @@ export function SessionsMessagesPage() {
     return (
       <ErrorState
         title="无效来源"
-        message="source 仅支持 claude / codex"
+        message="source 仅支持 claude / codex / gemini"
         onRetry={() => navigate("/sessions", { replace: true })}
       />
     );
@@ -24,6 +24,7 @@ import { formatRelativeTimeFromUnixSeconds, formatUnixSeconds } from "../utils/f
 const SOURCE_TABS: Array<{ key: CliSessionsSource; label: string }> = [
   { key: "claude", label: "Claude" },
   { key: "codex", label: "Codex" },
+  { key: "gemini", label: "Gemini" },
 ];
 
 type ProjectSortKey = "recent" | "sessions" | "name";
@@ -33,7 +34,7 @@ function isWindowsRuntime() {
 }
 
 function normalizeSource(raw: string | null): CliSessionsSource | null {
-  if (raw === "claude" || raw === "codex") return raw;
+  if (raw === "claude" || raw === "codex" || raw === "gemini") return raw;
   return null;
 }
 
@@ -44,9 +45,11 @@ function pickProjects(data: CliSessionsProjectSummary[] | null | undefined) {
 function sourceDirHint(source: CliSessionsSource, distro?: string) {
   if (distro) {
     if (source === "claude") return `\\\\wsl$\\${distro}\\~/.claude/projects`;
+    if (source === "gemini") return `\\\\wsl$\\${distro}\\~/.gemini/tmp`;
     return `\\\\wsl$\\${distro}\\~/.codex/sessions`;
   }
   if (source === "claude") return "~/.claude/projects";
+  if (source === "gemini") return "~/.gemini/tmp";
   return "$CODEX_HOME/sessions 或 ~/.codex/sessions";
 }
 
@@ -199,10 +202,12 @@ export function SessionsPage() {
           title="未找到任何项目"
           description={
             activeDistro
-              ? `请确认 WSL ${activeDistro} 中 ${source === "claude" ? "~/.claude/projects" : "~/.codex/sessions"} 目录存在并且包含会话文件。`
+              ? `请确认 WSL ${activeDistro} 中 ${source === "claude" ? "~/.claude/projects" : source === "gemini" ? "~/.gemini/tmp" : "~/.codex/sessions"} 目录存在并且包含会话文件。`
               : source === "claude"
                 ? "请确认 ~/.claude/projects 目录存在并且包含会话文件。"
-                : "请确认 $CODEX_HOME/sessions 或 ~/.codex/sessions 目录存在并且包含会话文件。"
+                : source === "gemini"
+                  ? "请确认 ~/.gemini/tmp 目录存在并且包含会话文件。"
+                  : "请确认 $CODEX_HOME/sessions 或 ~/.codex/sessions 目录存在并且包含会话文件。"
           }
           variant="dashed"
         />
@@ -31,7 +31,7 @@ import { formatRelativeTimeFromUnixSeconds, formatUnixSeconds } from "../utils/f
 type SessionSortKey = "recent" | "messages" | "created";
 
 function normalizeSource(raw: string | undefined): CliSessionsSource | null {
-  if (raw === "claude" || raw === "codex") return raw;
+  if (raw === "claude" || raw === "codex" || raw === "gemini") return raw;
   return null;
 }
 
@@ -41,6 +41,7 @@ function pickSessions(data: CliSessionsSessionSummary[] | null | undefined) {
 
 function buildResumeCommand(source: CliSessionsSource, sessionId: string) {
   const escapedId = escapeShellArg(sessionId);
+  if (source === "gemini") return `gemini --resume ${escapedId}`;
   return source === "claude" ? `claude --resume ${escapedId}` : `codex resume ${escapedId}`;
 }
 
@@ -228,7 +229,7 @@ export function SessionsProjectPage() {
     return (
       <ErrorState
         title="无效来源"
-        message="source 仅支持 claude / codex"
+        message="source 仅支持 claude / codex / gemini"
         onRetry={() => navigate("/sessions", { replace: true })}
       />
     );
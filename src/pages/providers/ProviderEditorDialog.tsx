@@ -731,6 +731,7 @@ export function ProviderEditorDialog(props: ProviderEditorDialogProps) {
                   items={[
                     { value: "order", label: "顺序" },
                     { value: "ping", label: "Ping" },
+                    { value: "race", label: "竞速" },
                   ]}
                   ariaLabel="Base URL 模式"
                   value={baseUrlMode}
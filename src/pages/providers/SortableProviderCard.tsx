@@ -187,7 +187,11 @@ export function SortableProviderCard({
             </div>
             <div className="mt-1 flex items-center gap-2">
               <span className="shrink-0 rounded-full bg-slate-50 px-2 py-0.5 font-mono text-[10px] text-slate-700 dark:bg-slate-700 dark:text-slate-300">
-                {provider.base_url_mode === "ping" ? "Ping" : "顺序"}
+                {provider.base_url_mode === "ping"
+                  ? "Ping"
+                  : provider.base_url_mode === "race"
+                    ? "竞速"
+                    : "顺序"}
               </span>
               <span className="shrink-0 rounded-full bg-slate-50 px-2 py-0.5 font-mono text-[10px] text-slate-700 dark:bg-slate-700 dark:text-slate-300">
                 倍率 {provider.cost_multiplier}x
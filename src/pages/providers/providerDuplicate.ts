@@ -7,7 +7,7 @@ export type ProviderEditorInitialValues = {
   api_key: string;
   auth_mode: "api_key" | "oauth";
   base_urls: string[];
-  base_url_mode: "order" | "ping";
+  base_url_mode: "order" | "ping" | "race";
   claude_models: ClaudeModels;
   enabled: boolean;
   cost_multiplier: number;
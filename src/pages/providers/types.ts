@@ -1,6 +1,6 @@
 // Usage: Shared types for `src/pages/providers/*` modules.
 
-export type ProviderBaseUrlMode = "order" | "ping";
+export type ProviderBaseUrlMode = "order" | "ping" | "race";
 
 export type BaseUrlPingState =
   | { status: "idle" }
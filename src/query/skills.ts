@@ -5,6 +5,7 @@ import { keepPreviousData, useMutation, useQuery, useQueryClient } from "@tansta
 import type { CliKey } from "../services/providers";
 import {
   skillInstall,
+  skillRepoAuthSet,
   skillRepoDelete,
   skillRepoUpsert,
   skillReposList,
@@ -22,6 +23,7 @@ import {
   type LocalSkillSummary,
   type SkillImportIssue,
   type SkillImportLocalBatchReport,
+  type SkillRepoAuthKind,
   type SkillRepoSummary,
   type SkillsPaths,
 } from "../services/skills";
@@ -136,6 +138,32 @@ export function useSkillRepoUpsertMutation() {
   });
 }
 
+export function useSkillRepoAuthSetMutation() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (input: {
+      repoId: number;
+      authKind: SkillRepoAuthKind;
+      token?: string | null;
+      sshKeyPath?: string | null;
+    }) =>
+      skillRepoAuthSet({
+        repo_id: input.repoId,
+        auth_kind: input.authKind,
+        token: input.token,
+        ssh_key_path: input.sshKeyPath,
+      }),
+    onSuccess: (next) => {
+      if (!next) return;
+      queryClient.setQueryData<SkillRepoSummary[]>(skillsKeys.reposList(), (cur) =>
+        (cur ?? []).map((r) => (r.id === next.id ? next : r))
+      );
+      queryClient.invalidateQueries({ queryKey: skillsKeys.discoverAvailable(false) });
+    },
+  });
+}
+
 export function useSkillRepoDeleteMutation() {
   const queryClient = useQueryClient();
 
@@ -292,6 +320,7 @@ export type {
   LocalSkillSummary,
   SkillImportIssue,
   SkillImportLocalBatchReport,
+  SkillRepoAuthKind,
   SkillRepoSummary,
   SkillsPaths,
 };
@@ -1,7 +1,12 @@
 import { describe, expect, it, vi } from "vitest";
 import { logToConsole } from "../consoleLog";
 import { invokeTauriOrNull } from "../tauriInvoke";
-import { cliProxySetEnabled, cliProxyStatusAll, cliProxySyncEnabled } from "../cliProxy";
+import {
+  cliConfigDriftReport,
+  cliProxySetEnabled,
+  cliProxyStatusAll,
+  cliProxySyncEnabled,
+} from "../cliProxy";
 
 vi.mock("../tauriInvoke", async () => {
   const actual = await vi.importActual<typeof import("../tauriInvoke")>("../tauriInvoke");
@@ -56,5 +61,11 @@ describe("services/cliProxy", () => {
     expect(invokeTauriOrNull).toHaveBeenCalledWith("cli_proxy_sync_enabled", {
       baseOrigin: "http://127.0.0.1:37123",
     });
+
+    await cliConfigDriftReport();
+    expect(invokeTauriOrNull).toHaveBeenCalledWith("cli_config_drift_report", { cliKey: null });
+
+    await cliConfigDriftReport("codex");
+    expect(invokeTauriOrNull).toHaveBeenCalledWith("cli_config_drift_report", { cliKey: "codex" });
   });
 });
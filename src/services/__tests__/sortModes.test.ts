@@ -9,6 +9,8 @@ import {
   sortModeProviderSetEnabled,
   sortModeProvidersSetOrder,
   sortModeRename,
+  sortModeScheduleGet,
+  sortModeScheduleSet,
   sortModesList,
 } from "../sortModes";
 
@@ -74,5 +76,26 @@ describe("services/sortModes", () => {
       providerId: 9,
       enabled: false,
     });
+
+    await sortModeScheduleGet({ cli_key: "codex" as any });
+    expect(invokeTauriOrNull).toHaveBeenCalledWith("sort_mode_schedule_get", { cliKey: "codex" });
+
+    const rules = [{ days_mask: 0b0111110, start: "09:00", end: "18:00", mode_id: 1 }];
+    await sortModeScheduleSet({
+      cli_key: "claude" as any,
+      enabled: true,
+      utc_offset_minutes: null,
+      rules,
+      otherwise_enabled: true,
+      otherwise_mode_id: 2,
+    });
+    expect(invokeTauriOrNull).toHaveBeenCalledWith("sort_mode_schedule_set", {
+      cliKey: "claude",
+      enabled: true,
+      utcOffsetMinutes: null,
+      rules,
+      otherwiseEnabled: true,
+      otherwiseModeId: 2,
+    });
   });
 });
@@ -0,0 +1,70 @@
+/**
+ * 启动自检 - 报告读取与失败事件监听
+ *
+ * 用法：
+ * - 在 `src/App.tsx` 启动时调用 `listenAppSelftestEvents()`（只需要注册一次）
+ * - 关键检查失败时后端发送一次 `app:selftest_failed`；窗口晚于自检完成时由主动读取报告补齐
+ */
+
+import { toast } from "sonner";
+import { logToConsole } from "./consoleLog";
+import { invokeServiceCommand } from "./invokeServiceCommand";
+
+export type SelfTestStatus = "ok" | "warning" | "failed" | "skipped";
+
+export type SelfTestCheck = {
+  check: string;
+  status: SelfTestStatus;
+  critical: boolean;
+  message: string;
+  action: string | null;
+};
+
+export type SelfTestReport = {
+  ran_at: number;
+  ok: boolean;
+  checks: SelfTestCheck[];
+};
+
+export async function appSelftestReport() {
+  return invokeServiceCommand<SelfTestReport | null, null>({
+    title: "读取启动自检报告失败",
+    cmd: "app_selftest_report",
+    nullResultBehavior: "return_fallback",
+  });
+}
+
+let lastNotifiedRanAt: number | null = null;
+
+function notifySelftestFailure(report: SelfTestReport) {
+  if (report.ok || lastNotifiedRanAt === report.ran_at) return;
+  lastNotifiedRanAt = report.ran_at;
+
+  const failed = report.checks.filter((c) => c.critical && c.status === "failed");
+  logToConsole("error", "启动自检发现关键问题", { checks: failed });
+  for (const c of failed) {
+    toast.error(`启动自检失败：${c.check}`, {
+      description: c.action ? `${c.message}\n${c.action}` : c.message,
+      duration: Infinity,
+    });
+  }
+}
+
+export async function listenAppSelftestEvents(): Promise<() => void> {
+  const { listen } = await import("@tauri-apps/api/event");
+
+  const unlisten = await listen<SelfTestReport>("app:selftest_failed", (event) => {
+    if (event.payload) notifySelftestFailure(event.payload);
+  });
+
+  // The event may have fired before this listener was registered.
+  appSelftestReport()
+    .then((report) => {
+      if (report) notifySelftestFailure(report);
+    })
+    .catch(() => {});
+
+  return () => {
+    unlisten();
+  };
+}
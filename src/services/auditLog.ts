@@ -0,0 +1,32 @@
+import { invokeService } from "./invokeServiceCommand";
+
+export type AuditOrigin = "ui" | "rest" | "headless";
+
+export type AuditLogEntry = {
+  id: number;
+  created_at: number;
+  command: string;
+  origin: AuditOrigin;
+  target: string | null;
+  summary: string;
+};
+
+export type AuditLogFilter = {
+  command?: string | null;
+  origin?: AuditOrigin | null;
+  target?: string | null;
+  sinceUnix?: number | null;
+  untilUnix?: number | null;
+  limit?: number | null;
+};
+
+export async function auditLogList(filter: AuditLogFilter = {}) {
+  return invokeService<AuditLogEntry[]>("读取配置变更记录失败", "audit_log_list", {
+    command: filter.command ?? null,
+    origin: filter.origin ?? null,
+    target: filter.target ?? null,
+    sinceUnix: filter.sinceUnix ?? null,
+    untilUnix: filter.untilUnix ?? null,
+    limit: filter.limit ?? null,
+  });
+}
@@ -28,3 +28,54 @@ export async function claudeValidationHistoryClearProvider(input: { provider_id:
     }
   );
 }
+
+export type ClaudeModelValidationSchedule = {
+  id: number;
+  provider_id: number;
+  model: string;
+  interval_minutes: number;
+  enabled: boolean;
+  last_run_at: number | null;
+  last_ok: boolean | null;
+  last_error: string | null;
+  created_at: number;
+  updated_at: number;
+};
+
+export async function claudeValidationSchedulesList(input?: { provider_id?: number }) {
+  return invokeService<ClaudeModelValidationSchedule[]>(
+    "读取 Claude 模型定时验证失败",
+    "claude_validation_schedules_list",
+    {
+      providerId: input?.provider_id,
+    }
+  );
+}
+
+export async function claudeValidationScheduleUpsert(input: {
+  provider_id: number;
+  model: string;
+  interval_minutes: number;
+  enabled: boolean;
+}) {
+  return invokeService<ClaudeModelValidationSchedule>(
+    "保存 Claude 模型定时验证失败",
+    "claude_validation_schedule_upsert",
+    {
+      providerId: input.provider_id,
+      model: input.model,
+      intervalMinutes: input.interval_minutes,
+      enabled: input.enabled,
+    }
+  );
+}
+
+export async function claudeValidationScheduleDelete(input: { schedule_id: number }) {
+  return invokeService<boolean>(
+    "删除 Claude 模型定时验证失败",
+    "claude_validation_schedule_delete",
+    {
+      scheduleId: input.schedule_id,
+    }
+  );
+}
@@ -33,3 +33,35 @@ export async function cliProxySyncEnabled(base_origin: string) {
     baseOrigin: base_origin,
   });
 }
+
+export type CliConfigDriftStatus = "in_sync" | "changed" | "missing" | "stale";
+
+export type CliConfigDriftKey = {
+  key: string;
+  status: CliConfigDriftStatus;
+  expected: unknown | null;
+  actual: unknown | null;
+};
+
+export type CliConfigDriftFile = {
+  area: "cli_proxy" | "mcp" | "prompt";
+  path: string;
+  exists: boolean;
+  parse_error: string | null;
+  in_sync: boolean;
+  keys: CliConfigDriftKey[];
+};
+
+export type CliConfigDriftReport = {
+  generated_at: number;
+  in_sync: boolean;
+  clis: { cli_key: CliKey; in_sync: boolean; files: CliConfigDriftFile[] }[];
+};
+
+export async function cliConfigDriftReport(cli_key?: CliKey) {
+  return invokeService<CliConfigDriftReport>(
+    "读取 CLI 配置漂移报告失败",
+    "cli_config_drift_report",
+    { cliKey: cli_key ?? null }
+  );
+}
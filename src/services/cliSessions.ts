@@ -1,6 +1,6 @@
 import { invokeService } from "./invokeServiceCommand";
 
-export type CliSessionsSource = "claude" | "codex";
+export type CliSessionsSource = "claude" | "codex" | "gemini";
 
 export type CliSessionsProjectSummary = {
   source: CliSessionsSource;
@@ -55,6 +55,15 @@ export type CliSessionsPaginatedMessages = {
   has_more: boolean;
 };
 
+export type CliSessionsExportFormat = "markdown" | "html";
+
+export type CliSessionsExportResult = {
+  output_path: string;
+  format: CliSessionsExportFormat;
+  message_count: number;
+  bytes: number;
+};
+
 export async function cliSessionsProjectsList(source: CliSessionsSource, wslDistro?: string) {
   return invokeService<CliSessionsProjectSummary[]>(
     "读取会话项目列表失败",
@@ -116,6 +125,22 @@ export async function cliSessionsSessionDelete(input: {
   });
 }
 
+export async function cliSessionsExport(input: {
+  source: CliSessionsSource;
+  file_path: string;
+  format: CliSessionsExportFormat;
+  output_path: string;
+  wsl_distro?: string;
+}) {
+  return invokeService<CliSessionsExportResult>("导出会话失败", "cli_sessions_export", {
+    source: input.source,
+    filePath: input.file_path,
+    format: input.format,
+    outputPath: input.output_path,
+    wslDistro: input.wsl_distro ?? null,
+  });
+}
+
 /**
  * Escapes a shell argument for safe command execution across platforms.
  *
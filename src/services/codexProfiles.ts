@@ -0,0 +1,51 @@
+import { invokeService } from "./invokeServiceCommand";
+
+export type CodexProfile = {
+  id: number;
+  name: string;
+  toml: string;
+  created_at: number;
+  updated_at: number;
+};
+
+export type CodexProfilesListResult = {
+  active_id: number | null;
+  items: CodexProfile[];
+};
+
+export type CodexProfileApplyResult = {
+  workspace_id: number;
+  profile_id: number;
+  config_path: string;
+  backup_path: string | null;
+  applied_at: number;
+};
+
+export async function codexProfilesList(workspaceId?: number | null) {
+  return invokeService<CodexProfilesListResult>("读取 Codex 配置方案失败", "codex_profiles_list", {
+    workspaceId: workspaceId ?? null,
+  });
+}
+
+export async function codexProfileUpsert(input: {
+  profile_id?: number | null;
+  name: string;
+  toml: string;
+}) {
+  return invokeService<CodexProfile>("保存 Codex 配置方案失败", "codex_profile_upsert", {
+    profileId: input.profile_id ?? null,
+    name: input.name,
+    toml: input.toml,
+  });
+}
+
+export async function codexProfileDelete(profileId: number) {
+  return invokeService<boolean>("删除 Codex 配置方案失败", "codex_profile_delete", { profileId });
+}
+
+export async function codexProfileApply(input: { workspace_id: number; profile_id: number }) {
+  return invokeService<CodexProfileApplyResult>("应用 Codex 配置方案失败", "codex_profile_apply", {
+    workspaceId: input.workspace_id,
+    profileId: input.profile_id,
+  });
+}
@@ -30,6 +30,8 @@ export type CostProviderBreakdownRowV1 = {
   requests_success: number;
   cost_covered_success: number;
   cost_usd: number;
+  thinking_requests?: number;
+  thinking_budget_tokens?: number;
 };
 
 export type CostModelBreakdownRowV1 = {
@@ -74,6 +76,18 @@ export type CostBackfillReportV1 = {
   max_rows: number;
 };
 
+export type CostAnomaly = {
+  session_id: string;
+  cli_key: CliKey;
+  provider_id: number | null;
+  provider_name: string | null;
+  recent_cost_usd: number;
+  baseline_cost_usd: number;
+  multiplier: number;
+  detected_at: number;
+  throttled: boolean;
+};
+
 type CostQueryInput = {
   startTs?: number | null;
   endTs?: number | null;
@@ -165,3 +179,11 @@ export async function costBackfillMissingV1(
     maxRows: input?.maxRows ?? null,
   });
 }
+
+export async function costAnomaliesList() {
+  return invokeService<CostAnomaly[]>("读取费用异常会话失败", "cost_anomalies_list");
+}
+
+export async function costAnomalyAcknowledge(sessionId: string) {
+  return invokeService<boolean>("确认费用异常失败", "cost_anomaly_acknowledge", { sessionId });
+}
@@ -7,15 +7,164 @@ export type DbDiskUsage = {
   total_bytes: number;
 };
 
+export type DbHealth = {
+  wal_bytes: number;
+  page_size: number;
+  page_count: number;
+  freelist_pages: number;
+  busy_waits: number;
+  busy_timeouts: number;
+  last_checkpoint_at: number | null;
+};
+
+export type DbCheckpointResult = {
+  busy: boolean;
+  wal_frames: number;
+  checkpointed_frames: number;
+  wal_bytes_after: number;
+  checkpointed_at: number;
+};
+
+export type DbQueryResult = {
+  columns: string[];
+  rows: unknown[][];
+  truncated: boolean;
+  elapsed_ms: number;
+};
+
+export type UsageImportSource = "litellm" | "claude_code_router";
+
+export type UsageImportResult = {
+  source: UsageImportSource;
+  rows_read: number;
+  rows_imported: number;
+  rows_skipped: number;
+};
+
 export type ClearRequestLogsResult = {
   request_logs_deleted: number;
   request_attempt_logs_deleted: number;
 };
 
+export type ConfigArchiveTableCount = {
+  section: string;
+  table: string;
+  rows: number;
+};
+
+export type ConfigArchiveManifest = {
+  format_version: number;
+  app_version: string;
+  settings_schema_version: number;
+  db_schema_version: number;
+  created_at: number;
+  include_secrets: boolean;
+  tables: ConfigArchiveTableCount[];
+};
+
+export type ConfigExportResult = {
+  path: string;
+  bytes: number;
+  manifest: ConfigArchiveManifest;
+};
+
+export type ConfigImportResult = {
+  manifest: ConfigArchiveManifest;
+  backup_path: string;
+  tables: ConfigArchiveTableCount[];
+  orphans_removed: number;
+};
+
+export type ConfigApplyChange = {
+  section: string;
+  cli_key: string | null;
+  key: string;
+  action: string;
+  fields: string[];
+};
+
+export type ConfigApplyReport = {
+  path: string;
+  dry_run: boolean;
+  applied: boolean;
+  changes: ConfigApplyChange[];
+  unchanged: number;
+};
+
+export type DataResetScope = "usage_stats" | "request_logs" | "gateway_state" | "cli_configs";
+
+export type DataResetItem = {
+  kind: string;
+  target: string;
+  count: number;
+  error: string | null;
+};
+
+export type DataResetReport = {
+  scope: DataResetScope;
+  dry_run: boolean;
+  items: DataResetItem[];
+  confirm_token: string | null;
+  confirm_token_expires_at: number | null;
+};
+
 export async function dbDiskUsageGet() {
   return invokeService<DbDiskUsage>("读取数据库磁盘用量失败", "db_disk_usage_get");
 }
 
+export async function dbHealthGet() {
+  return invokeService<DbHealth>("读取数据库健康状态失败", "db_health_get");
+}
+
+export async function dbCheckpointNow() {
+  return invokeService<DbCheckpointResult>("执行数据库 WAL 检查点失败", "db_checkpoint_now");
+}
+
+export async function dbQueryReadonly(sql: string, maxRows?: number | null) {
+  return invokeService<DbQueryResult>("执行只读查询失败", "db_query_readonly", {
+    sql,
+    maxRows: maxRows ?? null,
+  });
+}
+
+export async function usageHistoryImport(input: {
+  source: UsageImportSource;
+  path: string;
+  cliKey?: string | null;
+}) {
+  return invokeService<UsageImportResult>("导入历史用量失败", "usage_history_import", {
+    source: input.source,
+    path: input.path,
+    cliKey: input.cliKey ?? null,
+  });
+}
+
+export async function appConfigExport(input: {
+  path: string;
+  password: string;
+  includeSecrets: boolean;
+}) {
+  return invokeService<ConfigExportResult>("导出配置失败", "app_config_export", {
+    path: input.path,
+    password: input.password,
+    includeSecrets: input.includeSecrets,
+  });
+}
+
+export async function appConfigImport(input: { path: string; password: string }) {
+  return invokeService<ConfigImportResult>("导入配置失败", "app_config_import", {
+    path: input.path,
+    password: input.password,
+  });
+}
+
+export async function configApplyFile(input: { path: string; dryRun: boolean }) {
+  return invokeService<ConfigApplyReport>("应用声明式配置失败", "config_apply_file", {
+    path: input.path,
+    dryRun: input.dryRun,
+  });
+}
+
 export async function requestLogsClearAll() {
   return invokeService<ClearRequestLogsResult>("清空请求日志失败", "request_logs_clear_all");
 }
@@ -24,6 +173,18 @@ export async function appDataReset() {
   return invokeService<boolean>("重置应用数据失败", "app_data_reset");
 }
 
+export async function appDataResetScope(input: {
+  scope: DataResetScope;
+  dryRun: boolean;
+  confirmToken?: string | null;
+}) {
+  return invokeService<DataResetReport>("重置指定数据失败", "app_data_reset_scope", {
+    scope: input.scope,
+    dryRun: input.dryRun,
+    confirmToken: input.confirmToken ?? null,
+  });
+}
+
 export async function appDataDirGet() {
   return invokeService<string>("读取应用数据目录失败", "app_data_dir_get");
 }
@@ -12,3 +12,37 @@ export async function envConflictsCheck(cliKey: CliKey): Promise<EnvConflict[] |
     cliKey,
   });
 }
+
+export type EnvConflictFixAction = "comment_out" | "rewrite";
+
+export type EnvConflictFixTarget = Pick<EnvConflict, "var_name" | "source_type" | "source_path">;
+
+export type EnvConflictFixOutcome = {
+  var_name: string;
+  source_path: string;
+  ok: boolean;
+  error: string | null;
+};
+
+export type EnvConflictFixResult = {
+  backup_id: string | null;
+  outcomes: EnvConflictFixOutcome[];
+};
+
+export async function envConflictsFix(
+  targets: EnvConflictFixTarget[],
+  action: EnvConflictFixAction,
+  value?: string | null
+): Promise<EnvConflictFixResult | null> {
+  return invokeService<EnvConflictFixResult>("修复环境变量冲突失败", "env_conflicts_fix", {
+    targets,
+    action,
+    value: value ?? null,
+  });
+}
+
+export async function envConflictsFixUndo(backupId: string): Promise<number | null> {
+  return invokeService<number>("撤销环境变量修复失败", "env_conflicts_fix_undo", {
+    backupId,
+  });
+}
@@ -5,6 +5,18 @@ export type GatewayStatus = {
   port: number | null;
   base_url: string | null;
   listen_addr: string | null;
+  warm_pool?: GatewayUpstreamWarmStatus[];
+};
+
+export type GatewayUpstreamWarmStatus = {
+  provider_id: number;
+  cli_key: string;
+  provider_name: string;
+  origin: string;
+  warm_connections: number;
+  http_version: string | null;
+  last_warmed_at: number | null;
+  last_error: string | null;
 };
 
 export type GatewayActiveSession = {
@@ -21,6 +33,18 @@ export type GatewayActiveSession = {
   total_duration_ms: number | null;
 };
 
+export type SessionBindingSummary = {
+  cli_key: string;
+  session_id: string;
+  session_suffix: string;
+  provider_id: number;
+  provider_name: string | null;
+  sort_mode_id: number | null;
+  provider_tag: string | null;
+  pinned: boolean;
+  expires_at: number;
+};
+
 export type GatewayProviderCircuitStatus = {
   provider_id: number;
   state: string;
@@ -30,6 +54,60 @@ export type GatewayProviderCircuitStatus = {
   cooldown_until: number | null;
 };
 
+export type GatewayProviderQuality = {
+  provider_id: number;
+  cli_key: string;
+  provider_name: string;
+  window_seconds: number;
+  total: number;
+  errors: number;
+  truncated: number;
+  error_rate: number;
+  truncated_rate: number;
+  quarantined_until: number | null;
+  quarantine_count: number;
+};
+
+export type GatewayProviderLiveStats = {
+  provider_id: number;
+  cli_key: string;
+  provider_name: string;
+  in_flight: number;
+  active_streams: number;
+  requests_last_minute: number;
+  avg_ttft_ms: number | null;
+  circuit_state: string;
+};
+
+export type ErrorCatalogEntry = {
+  code: string;
+  title: string;
+  hint: string;
+};
+
+export type GatewayOAuthQuotaWindow = {
+  name: string;
+  used_percent: number | null;
+  remaining_requests: number | null;
+  window_minutes: number | null;
+  resets_at: number;
+  percent_per_request: number | null;
+};
+
+export type GatewayOAuthPacingState = {
+  provider_id: number;
+  cli_key: string;
+  provider_name: string;
+  requests_per_minute: number | null;
+  tokens: number;
+  burst: number;
+  paced_requests: number;
+  total_delay_ms: number;
+  last_delay_ms: number;
+  observed_at: number;
+  windows: GatewayOAuthQuotaWindow[];
+};
+
 export async function gatewayStatus() {
   return invokeServiceWithDetails<GatewayStatus>("获取网关状态失败", "gateway_status");
 }
@@ -45,6 +123,22 @@ export async function gatewayStart(preferredPort?: number) {
   );
 }
 
+export type GatewayPortChangeProgress = {
+  stage: "bound" | "configs_synced" | "draining" | "done";
+  from_port: number;
+  to_port: number;
+  message: string;
+};
+
+export async function gatewayChangePort(port: number) {
+  return invokeServiceWithDetails<GatewayStatus>(
+    "切换网关端口失败",
+    "gateway_change_port",
+    { port },
+    { port }
+  );
+}
+
 export async function gatewayStop() {
   return invokeServiceWithDetails<GatewayStatus>("停止网关失败", "gateway_stop");
 }
@@ -67,6 +161,37 @@ export async function gatewaySessionsList(limit?: number) {
   );
 }
 
+export async function sessionBindingsList(limit?: number) {
+  return invokeServiceWithDetails<SessionBindingSummary[]>(
+    "获取会话绑定失败",
+    "session_bindings_list",
+    { limit: limit ?? null },
+    { limit }
+  );
+}
+
+export async function sessionBindingPin(input: {
+  cliKey: string;
+  sessionId: string;
+  providerId: number;
+}) {
+  return invokeServiceWithDetails<boolean>(
+    "固定会话供应商失败",
+    "session_binding_pin",
+    input,
+    { cliKey: input.cliKey, providerId: input.providerId }
+  );
+}
+
+export async function sessionBindingUnpin(input: { cliKey: string; sessionId: string }) {
+  return invokeServiceWithDetails<boolean>(
+    "取消固定会话供应商失败",
+    "session_binding_unpin",
+    input,
+    { cliKey: input.cliKey }
+  );
+}
+
 export async function gatewayCircuitStatus(cliKey: string) {
   return invokeServiceWithDetails<GatewayProviderCircuitStatus[]>(
     "获取熔断器状态失败",
@@ -76,6 +201,31 @@ export async function gatewayCircuitStatus(cliKey: string) {
   );
 }
 
+export async function gatewayProviderQuality() {
+  return invokeServiceWithDetails<GatewayProviderQuality[]>(
+    "获取供应商质量统计失败",
+    "gateway_provider_quality_v1"
+  );
+}
+
+export async function gatewayLiveStats() {
+  return invokeServiceWithDetails<GatewayProviderLiveStats[]>(
+    "获取供应商实时状态失败",
+    "gateway_live_stats"
+  );
+}
+
+export async function gatewayOAuthPacing() {
+  return invokeServiceWithDetails<GatewayOAuthPacingState[]>(
+    "获取 OAuth 账号节流状态失败",
+    "gateway_oauth_pacing_v1"
+  );
+}
+
+export async function errorCatalogList() {
+  return invokeServiceWithDetails<ErrorCatalogEntry[]>("获取错误目录失败", "error_catalog_list");
+}
+
 export async function gatewayCircuitResetProvider(providerId: number) {
   return invokeServiceWithDetails<boolean>(
     "重置 Provider 熔断器失败",
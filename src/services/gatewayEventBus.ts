@@ -7,6 +7,7 @@ export const gatewayEventNames = {
   request: "gateway:request",
   log: "gateway:log",
   circuit: "gateway:circuit",
+  liveStats: "gateway:live_stats",
 } as const;
 
 export type GatewayEventName = (typeof gatewayEventNames)[keyof typeof gatewayEventNames];
@@ -11,6 +11,10 @@ export type GatewayAttempt = {
   base_url: string;
   outcome: string;
   status: number | null;
+  error_catalog_code?: string | null;
+  response_headers?: Record<string, string> | null;
+  error_body?: string | null;
+  retry_backoff_ms?: number | null;
 };
 
 export type GatewayRequestEvent = {
@@ -0,0 +1,78 @@
+import { invokeService } from "./invokeServiceCommand";
+
+export type HookEvent =
+  | "PreToolUse"
+  | "PostToolUse"
+  | "Notification"
+  | "UserPromptSubmit"
+  | "Stop"
+  | "SubagentStop"
+  | "PreCompact"
+  | "SessionStart"
+  | "SessionEnd";
+
+export type HookSummary = {
+  id: number;
+  name: string;
+  event: HookEvent;
+  matcher: string;
+  command: string;
+  timeout_secs: number | null;
+  enabled: boolean;
+  created_at: number;
+  updated_at: number;
+};
+
+export type HookTemplate = {
+  key: string;
+  name: string;
+  description: string;
+  event: HookEvent;
+  matcher: string;
+  command: string;
+  timeout_secs: number | null;
+};
+
+export async function hooksList(workspaceId: number) {
+  return invokeService<HookSummary[]>("读取 Hooks 列表失败", "hooks_list", { workspaceId });
+}
+
+export async function hookTemplatesList() {
+  return invokeService<HookTemplate[]>("读取 Hook 模板失败", "hook_templates_list");
+}
+
+export async function hookUpsert(input: {
+  hook_id?: number | null;
+  workspace_id: number;
+  name: string;
+  event: HookEvent;
+  matcher?: string;
+  command: string;
+  timeout_secs?: number | null;
+}) {
+  return invokeService<HookSummary>("保存 Hook 失败", "hook_upsert", {
+    hookId: input.hook_id ?? null,
+    workspaceId: input.workspace_id,
+    name: input.name,
+    event: input.event,
+    matcher: input.matcher ?? "",
+    command: input.command,
+    timeoutSecs: input.timeout_secs ?? null,
+  });
+}
+
+export async function hookSetEnabled(input: {
+  workspace_id: number;
+  hook_id: number;
+  enabled: boolean;
+}) {
+  return invokeService<HookSummary>("更新 Hook 启用状态失败", "hook_set_enabled", {
+    workspaceId: input.workspace_id,
+    hookId: input.hook_id,
+    enabled: input.enabled,
+  });
+}
+
+export async function hookDelete(hookId: number) {
+  return invokeService<boolean>("删除 Hook 失败", "hook_delete", { hookId });
+}
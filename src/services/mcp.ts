@@ -115,3 +115,77 @@ export async function mcpImportFromWorkspaceCli(workspace_id: number) {
     }
   );
 }
+
+export type McpDriftStatus = "in_sync" | "modified" | "missing" | "stale" | "external";
+
+export type McpDriftServer = {
+  server_key: string;
+  status: McpDriftStatus;
+  desired: Record<string, unknown> | null;
+  actual: Record<string, unknown> | null;
+};
+
+export type McpSyncCliStatus = {
+  cli_key: string;
+  workspace_id: number | null;
+  target_exists: boolean;
+  parse_error: string | null;
+  in_sync: boolean;
+  servers: McpDriftServer[];
+};
+
+export async function mcpSyncStatus(cliKey?: string | null) {
+  return invokeService<McpSyncCliStatus[]>("读取 MCP 同步状态失败", "mcp_sync_status", {
+    cliKey: cliKey ?? null,
+  });
+}
+
+export async function mcpSyncApply(cliKey: string) {
+  return invokeService<McpSyncCliStatus>("覆盖 MCP 配置失败", "mcp_sync_apply", { cliKey });
+}
+
+export async function mcpSyncAdopt(input: { cli_key: string; server_keys: string[] }) {
+  return invokeService<McpSyncCliStatus>("采纳外部 MCP 配置失败", "mcp_sync_adopt", {
+    cliKey: input.cli_key,
+    serverKeys: input.server_keys,
+  });
+}
+
+export type McpRuntimeState = "starting" | "running" | "backoff" | "stopped" | "failed";
+
+export type McpServerRuntimeStatus = {
+  server_id: number;
+  server_key: string;
+  name: string;
+  state: McpRuntimeState;
+  pid: number | null;
+  started_at: number | null;
+  restart_count: number;
+  last_exit_code: number | null;
+  last_exit_at: number | null;
+  last_error: string | null;
+  cpu_percent: number | null;
+  memory_bytes: number | null;
+  stderr_tail: string[];
+};
+
+export async function mcpServerSuperviseStart(serverId: number) {
+  return invokeService<McpServerRuntimeStatus>(
+    "启动 MCP 托管进程失败",
+    "mcp_server_supervise_start",
+    { serverId }
+  );
+}
+
+export async function mcpServerSuperviseStop(serverId: number) {
+  return invokeService<boolean>("停止 MCP 托管进程失败", "mcp_server_supervise_stop", {
+    serverId,
+  });
+}
+
+export async function mcpServerRuntimeStatus() {
+  return invokeService<McpServerRuntimeStatus[]>(
+    "读取 MCP 运行状态失败",
+    "mcp_server_runtime_status"
+  );
+}
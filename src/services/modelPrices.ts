@@ -74,6 +74,39 @@ export async function modelPricesList(cliKey: CliKey) {
   });
 }
 
+export type ModelPriceImportChange = {
+  line: number;
+  cli_key: CliKey;
+  model: string;
+  action: "insert" | "update";
+  before_json: string | null;
+  after_json: string;
+};
+
+export type ModelPricesImportReport = {
+  dry_run: boolean;
+  applied: boolean;
+  inserted: number;
+  updated: number;
+  unchanged: number;
+  changes: ModelPriceImportChange[];
+  errors: { line: number; message: string }[];
+};
+
+export async function modelPricesImport(input: {
+  content: string;
+  format?: "csv" | "json";
+  cliKey?: CliKey;
+  dryRun: boolean;
+}) {
+  return invokeService<ModelPricesImportReport>("导入模型价格表失败", "model_prices_import", {
+    content: input.content,
+    format: input.format ?? null,
+    cliKey: input.cliKey ?? null,
+    dryRun: input.dryRun,
+  });
+}
+
 export async function modelPricesSyncBasellm(force = false) {
   return invokeService<ModelPricesSyncReport>("同步模型价格失败", "model_prices_sync_basellm", {
     force,
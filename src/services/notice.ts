@@ -6,7 +6,8 @@
  * - `title` 为空时，Rust 会按 level 生成默认标题并追加固定前缀
  */
 
-import { invokeServiceCommand } from "./invokeServiceCommand";
+import { invokeService, invokeServiceCommand } from "./invokeServiceCommand";
+import type { NotificationSink } from "./settings";
 
 export type NoticeLevel = "info" | "success" | "warning" | "error";
 
@@ -24,3 +25,7 @@ export async function noticeSend(params: NoticeSendParams): Promise<boolean> {
     fallback: false,
   });
 }
+
+export async function notificationSinkTest(sink: NotificationSink) {
+  return invokeService<boolean>("发送测试通知失败", "notification_sink_test", { sink });
+}
@@ -22,6 +22,17 @@ export type DefaultPromptSyncReport = {
   items: DefaultPromptSyncItem[];
 };
 
+export type PromptFileConflict = {
+  cli_key: CliKey;
+  prompt_id: number | null;
+  prompt_name: string | null;
+  target_path: string;
+  db_content: string | null;
+  local_content: string | null;
+};
+
+export type PromptConflictResolution = "adopt_local" | "overwrite";
+
 export async function promptsList(workspaceId: number) {
   return invokeService<PromptSummary[]>("读取提示词列表失败", "prompts_list", { workspaceId });
 }
@@ -59,3 +70,17 @@ export async function promptSetEnabled(promptId: number, enabled: boolean) {
 export async function promptDelete(promptId: number) {
   return invokeService<boolean>("删除提示词失败", "prompt_delete", { promptId });
 }
+
+export async function promptFileConflictsList() {
+  return invokeService<PromptFileConflict[]>("读取提示词冲突失败", "prompt_file_conflicts_list");
+}
+
+export async function promptFileConflictResolve(
+  cliKey: CliKey,
+  resolution: PromptConflictResolution
+) {
+  return invokeService<boolean>("处理提示词冲突失败", "prompt_file_conflict_resolve", {
+    cliKey,
+    resolution,
+  });
+}
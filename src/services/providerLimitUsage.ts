@@ -39,3 +39,41 @@ export async function providerLimitUsageV1(cliKey?: CliKey | null) {
     }
   );
 }
+
+export type ProviderMonthlyBudgetRow = {
+  cli_key: CliKey;
+  provider_id: number;
+  provider_name: string;
+  // Calendar month in local time, YYYY-MM
+  month: string;
+  budget_usd: number;
+  grace_usd: number;
+  spent_usd: number;
+  remaining_usd: number;
+  blocked: boolean;
+  notified_80_at: number | null;
+  notified_100_at: number | null;
+  month_start_ts: number;
+  next_month_start_ts: number;
+};
+
+export async function providerMonthlyBudgetsList(cliKey?: CliKey | null) {
+  return invokeService<ProviderMonthlyBudgetRow[]>(
+    "读取供应商月度预算失败",
+    "provider_monthly_budgets_list",
+    {
+      cliKey: cliKey ?? null,
+    }
+  );
+}
+
+export async function providerMonthlyBudgetSetGrace(providerId: number, graceUsd: number) {
+  return invokeService<ProviderMonthlyBudgetRow>(
+    "设置供应商预算宽限额度失败",
+    "provider_monthly_budget_set_grace",
+    {
+      providerId,
+      graceUsd,
+    }
+  );
+}
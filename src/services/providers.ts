@@ -16,7 +16,7 @@ export type ProviderSummary = {
   cli_key: CliKey;
   name: string;
   base_urls: string[];
-  base_url_mode: "order" | "ping";
+  base_url_mode: "order" | "ping" | "race";
   claude_models: ClaudeModels;
   enabled: boolean;
   priority: number;
@@ -37,10 +37,106 @@ export type ProviderSummary = {
   oauth_email: string | null;
   oauth_expires_at: number | null;
   oauth_last_error: string | null;
+  maintenance_active?: ProviderMaintenanceOccurrence | null;
+  maintenance_next?: ProviderMaintenanceOccurrence | null;
+  provider_kind?: ProviderKind;
+  model_catalog?: string[];
+  model_catalog_refreshed_at?: number | null;
+  model_catalog_error?: string | null;
+  last_resort?: boolean;
+  emulate_models_endpoint?: boolean;
+  thinking_budget_cap?: number | null;
+  monthly_budget_usd?: number | null;
+  archived_at?: number | null;
+  refusal_patterns?: string[];
+  anthropic_version?: string | null;
+  anthropic_betas?: string[];
+  anthropic_beta_strip?: boolean;
+  webhook_url?: string | null;
 };
 
-export async function providersList(cliKey: CliKey) {
-  return invokeService<ProviderSummary[]>("读取供应商列表失败", "providers_list", { cliKey });
+export type ProviderUsageAudit = {
+  provider_id: number;
+  cli_key: CliKey;
+  name: string;
+  archived_at: number | null;
+  request_log_count: number;
+  last_used_at: number | null;
+  total_cost_usd: number;
+  current_month_grace_usd: number;
+  budget_hold: boolean;
+};
+
+export type ProviderCredentialStatus = {
+  provider_id: number;
+  cli_key: CliKey;
+  name: string;
+  enabled: boolean;
+  auth_mode: string;
+  credential_age_secs: number | null;
+  last_success_at: number | null;
+  last_auth_failure_at: number | null;
+  last_auth_failure_status: number | null;
+  oauth_expires_in_secs: number | null;
+  oauth_last_refresh_ok: boolean | null;
+  oauth_last_error: string | null;
+  window_secs: number;
+};
+
+export type ProviderKind = "standard" | "aggregated" | "federated";
+
+export type ProviderModelCatalog = {
+  provider_id: number;
+  models: string[];
+  refreshed_at: number | null;
+  error: string | null;
+};
+
+export type ProviderMaintenanceOccurrence = {
+  window_id: number;
+  starts_at: number;
+  ends_at: number;
+};
+
+export type ProviderMaintenanceWindow = {
+  id: number;
+  provider_id: number;
+  starts_at: number;
+  ends_at: number;
+  repeat_interval_secs: number | null;
+  note: string;
+  created_at: number;
+  updated_at: number;
+};
+
+export type DnsIpStrategy = "system" | "ipv4_only";
+
+export type ProviderDnsOptions = {
+  provider_id: number;
+  ip_strategy: DnsIpStrategy;
+  doh_url: string | null;
+  race_connections: boolean;
+};
+
+export type ProviderTagDefault = {
+  cli_key: CliKey;
+  tag: string | null;
+};
+
+export async function providersList(cliKey: CliKey, includeArchived?: boolean) {
+  return invokeService<ProviderSummary[]>(
+    "读取供应商列表失败",
+    "providers_list",
+    includeArchived ? { cliKey, includeArchived } : { cliKey }
+  );
+}
+
+export async function providersCredentialStatus(cliKey?: CliKey) {
+  return invokeService<ProviderCredentialStatus[]>(
+    "读取供应商凭证状态失败",
+    "providers_credential_status",
+    { cliKey: cliKey ?? null }
+  );
 }
 
 export async function providerUpsert(input: {
@@ -48,7 +144,7 @@ export async function providerUpsert(input: {
   cli_key: CliKey;
   name: string;
   base_urls: string[];
-  base_url_mode: "order" | "ping";
+  base_url_mode: "order" | "ping" | "race";
   auth_mode?: "api_key" | "oauth" | null;
   api_key?: string | null;
   enabled: boolean;
@@ -64,6 +160,7 @@ export async function providerUpsert(input: {
   limit_total_usd: number | null;
   tags?: string[];
   note?: string;
+  provider_kind?: ProviderKind | null;
 }) {
   return invokeService<ProviderSummary>("保存供应商失败", "provider_upsert", {
     input: {
@@ -87,6 +184,7 @@ export async function providerUpsert(input: {
       limitTotalUsd: input.limit_total_usd,
       tags: input.tags ?? null,
       note: input.note ?? null,
+      providerKind: input.provider_kind ?? null,
     },
   });
 }
@@ -102,10 +200,234 @@ export async function providerSetEnabled(providerId: number, enabled: boolean) {
   });
 }
 
+export async function providerSetLastResort(providerId: number, lastResort: boolean) {
+  return invokeService<ProviderSummary>("更新兜底供应商失败", "provider_set_last_resort", {
+    providerId,
+    lastResort,
+  });
+}
+
+export async function providerSetEmulateModelsEndpoint(providerId: number, enabled: boolean) {
+  return invokeService<ProviderSummary>(
+    "更新模型列表模拟失败",
+    "provider_set_emulate_models_endpoint",
+    { providerId, enabled }
+  );
+}
+
+export async function providerSetThinkingBudgetCap(
+  providerId: number,
+  thinkingBudgetCap: number | null
+) {
+  return invokeService<ProviderSummary>(
+    "更新思考预算上限失败",
+    "provider_set_thinking_budget_cap",
+    { providerId, thinkingBudgetCap }
+  );
+}
+
+export async function providerSetRefusalPatterns(providerId: number, refusalPatterns: string[]) {
+  return invokeService<ProviderSummary>("更新拒答关键词失败", "provider_set_refusal_patterns", {
+    providerId,
+    refusalPatterns,
+  });
+}
+
+export async function providerSetAnthropicHeaders(
+  providerId: number,
+  input: { anthropicVersion: string | null; anthropicBetas: string[]; anthropicBetaStrip: boolean }
+) {
+  return invokeService<ProviderSummary>(
+    "更新 Anthropic 请求头配置失败",
+    "provider_set_anthropic_headers",
+    { providerId, ...input }
+  );
+}
+
+export async function providerSetWebhookUrl(providerId: number, webhookUrl: string | null) {
+  return invokeService<ProviderSummary>("更新供应商 Webhook 失败", "provider_set_webhook_url", {
+    providerId,
+    webhookUrl,
+  });
+}
+
+export async function providerSetMonthlyBudget(
+  providerId: number,
+  monthlyBudgetUsd: number | null
+) {
+  return invokeService<ProviderSummary>("更新供应商月度预算失败", "provider_set_monthly_budget", {
+    providerId,
+    monthlyBudgetUsd,
+  });
+}
+
+/** Archives the provider; `providerPurge` deletes it for good. */
 export async function providerDelete(providerId: number) {
   return invokeService<boolean>("删除供应商失败", "provider_delete", { providerId });
 }
 
+export async function providerRestore(providerId: number) {
+  return invokeService<ProviderSummary>("恢复供应商失败", "provider_restore", { providerId });
+}
+
+export async function providerUsageAudit(providerId: number) {
+  return invokeService<ProviderUsageAudit>("读取供应商使用情况失败", "provider_usage_audit", {
+    providerId,
+  });
+}
+
+export async function providerPurge(providerId: number) {
+  return invokeService<boolean>("彻底删除供应商失败", "provider_purge", { providerId });
+}
+
+export async function providerMaintenanceWindowsList(providerId: number) {
+  return invokeService<ProviderMaintenanceWindow[]>(
+    "读取供应商维护时段失败",
+    "provider_maintenance_windows_list",
+    { providerId }
+  );
+}
+
+export async function providerMaintenanceWindowUpsert(input: {
+  window_id?: number | null;
+  provider_id: number;
+  starts_at: number;
+  ends_at: number;
+  repeat_interval_secs?: number | null;
+  note?: string | null;
+}) {
+  return invokeService<ProviderMaintenanceWindow>(
+    "保存供应商维护时段失败",
+    "provider_maintenance_window_upsert",
+    {
+      input: {
+        windowId: input.window_id ?? null,
+        providerId: input.provider_id,
+        startsAt: input.starts_at,
+        endsAt: input.ends_at,
+        repeatIntervalSecs: input.repeat_interval_secs ?? null,
+        note: input.note ?? null,
+      },
+    }
+  );
+}
+
+export async function providerMaintenanceWindowDelete(windowId: number) {
+  return invokeService<boolean>("删除供应商维护时段失败", "provider_maintenance_window_delete", {
+    windowId,
+  });
+}
+
+export type ProviderBulkUpdateInput = {
+  provider_ids: number[];
+  enabled?: boolean | null;
+  tags?: string[] | null;
+  cost_multiplier?: number | null;
+  cost_multiplier_scale?: number | null;
+  remove_from_sort_mode_id?: number | null;
+  add_to_sort_mode_id?: number | null;
+};
+
+export type ProviderBulkOutcome = {
+  provider_id: number;
+  name: string | null;
+  ok: boolean;
+  error: string | null;
+  changes: { field: string; from: string; to: string }[];
+};
+
+export type ProviderBulkUpdateResult = {
+  dry_run: boolean;
+  applied: boolean;
+  outcomes: ProviderBulkOutcome[];
+};
+
+export async function providersBulkUpdate(input: ProviderBulkUpdateInput, dryRun: boolean) {
+  return invokeService<ProviderBulkUpdateResult>("批量更新供应商失败", "providers_bulk_update", {
+    input: {
+      provider_ids: input.provider_ids,
+      enabled: input.enabled ?? null,
+      tags: input.tags ?? null,
+      cost_multiplier: input.cost_multiplier ?? null,
+      cost_multiplier_scale: input.cost_multiplier_scale ?? null,
+      remove_from_sort_mode_id: input.remove_from_sort_mode_id ?? null,
+      add_to_sort_mode_id: input.add_to_sort_mode_id ?? null,
+    },
+    dryRun,
+  });
+}
+
+export async function providerDnsOptionsGet(providerId: number) {
+  return invokeService<ProviderDnsOptions>("读取供应商 DNS 设置失败", "provider_dns_options_get", {
+    providerId,
+  });
+}
+
+export async function providerDnsOptionsSet(input: {
+  provider_id: number;
+  ip_strategy: DnsIpStrategy;
+  doh_url?: string | null;
+  race_connections: boolean;
+}) {
+  return invokeService<ProviderDnsOptions>("更新供应商 DNS 设置失败", "provider_dns_options_set", {
+    providerId: input.provider_id,
+    ipStrategy: input.ip_strategy,
+    dohUrl: input.doh_url ?? null,
+    raceConnections: input.race_connections,
+  });
+}
+
+export async function providerDefaultTagsList() {
+  return invokeService<ProviderTagDefault[]>(
+    "读取默认供应商标签失败",
+    "provider_default_tags_list"
+  );
+}
+
+export async function providerDefaultTagSet(cliKey: CliKey, tag: string | null) {
+  return invokeService<ProviderTagDefault>("更新默认供应商标签失败", "provider_default_tag_set", {
+    cliKey,
+    tag,
+  });
+}
+
+export async function providerModelCatalogRefresh(providerId: number) {
+  return invokeService<ProviderModelCatalog>(
+    "刷新供应商模型目录失败",
+    "provider_model_catalog_refresh",
+    { providerId }
+  );
+}
+
+export type ProviderValidationEntry = {
+  provider_id: number;
+  cli_key: CliKey;
+  provider_name: string;
+  check: "claude_messages" | "models_list";
+  ok: boolean;
+  base_url: string | null;
+  status: number | null;
+  duration_ms: number;
+  responded_model: string | null;
+  models_count: number | null;
+  error: string | null;
+};
+
+export type ProviderValidationReport = {
+  generated_at: number;
+  ok: boolean;
+  total: number;
+  passed: number;
+  failed: number;
+  entries: ProviderValidationEntry[];
+};
+
+export async function providersValidateAll(claudeModel?: string | null) {
+  return invokeService<ProviderValidationReport>("批量验证供应商失败", "providers_validate_all", {
+    claudeModel: claudeModel ?? null,
+  });
+}
+
 export async function providersReorder(cliKey: CliKey, orderedProviderIds: number[]) {
   return invokeService<ProviderSummary[]>("调整供应商顺序失败", "providers_reorder", {
     cliKey,
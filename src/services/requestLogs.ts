@@ -29,6 +29,8 @@ export type RequestLogSummary = {
   cache_creation_1h_input_tokens: number | null;
   cost_usd: number | null;
   cost_multiplier: number;
+  error_catalog_code?: string | null;
+  response_headers_json?: string | null;
   created_at_ms: number | null;
   created_at: number;
 };
@@ -71,6 +73,8 @@ export type RequestLogDetail = {
   requested_model: string | null;
   cost_usd: number | null;
   cost_multiplier: number;
+  error_catalog_code?: string | null;
+  response_headers_json?: string | null;
   created_at_ms: number | null;
   created_at: number;
 };
@@ -87,9 +91,41 @@ export type RequestAttemptLog = {
   status: number | null;
   attempt_started_ms: number;
   attempt_duration_ms: number;
+  bytes_received: number | null;
+  chunk_count: number | null;
+  max_chunk_gap_ms: number | null;
+  response_headers?: Record<string, string> | null;
+  error_body?: string | null;
+  retry_backoff_ms?: number | null;
   created_at: number;
 };
 
+export type RequestTimelineSegmentKind =
+  | "queue"
+  | "upstream"
+  | "stream"
+  | "gap"
+  | "backoff"
+  | "skipped";
+
+export type RequestTimelineSegment = {
+  kind: RequestTimelineSegmentKind;
+  label: string;
+  // Milliseconds since the gateway received the request.
+  start_ms: number;
+  end_ms: number;
+  attempt_index: number | null;
+  provider_id: number | null;
+  outcome: string | null;
+};
+
+export type RequestTimeline = {
+  trace_id: string;
+  duration_ms: number;
+  ttfb_ms: number | null;
+  segments: RequestTimelineSegment[];
+};
+
 export async function requestLogsList(cliKey: CliKey, limit?: number) {
   return invokeService<RequestLogSummary[]>("读取请求日志失败", "request_logs_list", {
     cliKey,
@@ -146,3 +182,13 @@ export async function requestAttemptLogsByTraceId(traceId: string, limit?: numbe
     }
   );
 }
+
+export async function requestTimelineByTraceId(traceId: string) {
+  return invokeService<RequestTimeline | null>(
+    "读取请求时间线失败",
+    "request_timeline_by_trace_id",
+    {
+      traceId,
+    }
+  );
+}
@@ -0,0 +1,52 @@
+import { invokeService } from "./invokeServiceCommand";
+import type { CliKey } from "./providers";
+
+export type RequestMirrorLog = {
+  id: number;
+  trace_id: string;
+  cli_key: CliKey;
+  provider_id: number;
+  provider_name: string;
+  method: string;
+  path: string;
+  requested_model: string | null;
+  status: number | null;
+  error: string | null;
+  ttfb_ms: number | null;
+  duration_ms: number;
+  input_tokens: number | null;
+  output_tokens: number | null;
+  cache_read_input_tokens: number | null;
+  cache_creation_input_tokens: number | null;
+  created_at: number;
+};
+
+export type RequestMirrorProviderStats = {
+  provider_id: number;
+  provider_name: string;
+  cli_key: CliKey;
+  requests_total: number;
+  requests_success: number;
+  avg_ttfb_ms: number | null;
+  avg_duration_ms: number | null;
+  input_tokens: number;
+  output_tokens: number;
+};
+
+export async function requestMirrorLogsList(limit?: number) {
+  return invokeService<RequestMirrorLog[]>("读取镜像请求记录失败", "request_mirror_logs_list", {
+    limit,
+  });
+}
+
+export async function requestMirrorStats(sinceUnix?: number | null) {
+  return invokeService<RequestMirrorProviderStats[]>(
+    "读取镜像请求统计失败",
+    "request_mirror_stats",
+    { sinceUnix: sinceUnix ?? null }
+  );
+}
+
+export async function requestMirrorLogsClear() {
+  return invokeService<number>("清空镜像请求记录失败", "request_mirror_logs_clear");
+}
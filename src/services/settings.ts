@@ -1,15 +1,100 @@
 import { invokeService } from "./invokeServiceCommand";
+import type { CliKey } from "./providers";
 
 export type GatewayListenMode = "localhost" | "wsl_auto" | "lan" | "custom";
 
 export type WslHostAddressMode = "auto" | "custom";
 
+export type UiLanguage = "zh" | "en";
+
+export type AccessLogFormat = "off" | "clf" | "json";
+
 export type WslTargetCli = {
   claude: boolean;
   codex: boolean;
   gemini: boolean;
 };
 
+export type RedactionRule = {
+  name: string;
+  pattern: string;
+  replacement: string;
+  enabled: boolean;
+};
+
+export type NotificationSinkKind = "webhook" | "slack" | "telegram";
+
+export type NotificationEventKind = "circuit_open" | "budget_alert" | "gateway_failure";
+
+export type NotificationSink = {
+  name: string;
+  kind: NotificationSinkKind;
+  // Webhook / Slack: endpoint URL. Telegram: bot token.
+  url: string;
+  telegram_chat_id: string;
+  events: NotificationEventKind[];
+  enabled: boolean;
+};
+
+export type SshHost = {
+  name: string;
+  // `user@host` or a Host alias from ~/.ssh/config.
+  destination: string;
+  port: number | null;
+  identity_file: string;
+  // Empty = 127.0.0.1 through a reverse tunnel (ssh -R <port>:127.0.0.1:<port>).
+  gateway_host: string;
+  targets: WslTargetCli;
+  auto_sync: boolean;
+};
+
+export type RetryBackoffStrategy = "none" | "fixed" | "linear" | "exponential_jitter";
+
+export type RetryBackoffPolicy = {
+  strategy: RetryBackoffStrategy;
+  base_ms: number;
+  max_ms: number;
+};
+
+export type RetryBackoffSettings = {
+  // 408 / 429 responses.
+  rate_limited: RetryBackoffPolicy;
+  // Retryable 5xx responses.
+  upstream_error: RetryBackoffPolicy;
+  // Timeouts, connection failures and broken streams.
+  network_error: RetryBackoffPolicy;
+};
+
+export type ResponseFixerCharset = "gbk" | "big5" | "shift_jis";
+
+export type EnvConflictIgnoreRule = {
+  var_name: string;
+  // Empty = any source type / any source path.
+  source_type: "" | "system" | "file";
+  source_path: string;
+};
+
+export type ModelDenyRule = {
+  cli_key: CliKey;
+  // Case-insensitive; `*` matches any run of characters.
+  pattern: string;
+};
+
+export type ModelCapabilityRule = {
+  cli_key: CliKey;
+  // Null = applies to every provider; a provider-specific rule wins over a global one.
+  provider_id: number | null;
+  // Same matching as ModelDenyRule.pattern.
+  pattern: string;
+  // Null = unknown, never gates the request.
+  vision: boolean | null;
+  tool_use: boolean | null;
+  thinking: boolean | null;
+  max_context_tokens: number | null;
+};
+
+export type CapabilityGatePolicy = "off" | "skip_provider" | "strip_feature";
+
 export type AppSettings = {
   schema_version: number;
   preferred_port: number;
@@ -19,21 +104,42 @@ export type AppSettings = {
   wsl_target_cli: WslTargetCli;
   wsl_host_address_mode: WslHostAddressMode;
   wsl_custom_host_address: string;
+  gateway_access_token: string;
   auto_start: boolean;
   start_minimized: boolean;
   tray_enabled: boolean;
   enable_cli_proxy_startup_recovery: boolean;
   log_retention_days: number;
+  attempt_log_retention_days: number;
   provider_cooldown_seconds: number;
   provider_base_url_ping_cache_ttl_seconds: number;
   upstream_first_byte_timeout_seconds: number;
   upstream_stream_idle_timeout_seconds: number;
   upstream_request_timeout_non_streaming_seconds: number;
+  count_tokens_hedge_delay_ms: number;
+  count_tokens_local_estimate_enabled: boolean;
+  first_byte_race_delay_ms: number;
+  non_streaming_hedge_delay_ms: number;
+  upstream_warm_pool_enabled: boolean;
+  upstream_warm_pool_size: number;
+  request_mirror_enabled: boolean;
+  request_mirror_provider_id: number;
+  request_mirror_percent: number;
+  request_dedup_enabled: boolean;
+  stream_truncation_recovery_enabled: boolean;
+  sse_coalesce_flush_ms: number;
+  sse_coalesce_flush_bytes: number;
+  stream_stall_warn_seconds: number;
+  stream_stall_keepalive_enabled: boolean;
+  stream_first_byte_failover_enabled: boolean;
+  stream_error_event_enabled: boolean;
   update_releases_url: string;
   failover_max_attempts_per_provider: number;
   failover_max_providers_to_try: number;
   circuit_breaker_failure_threshold: number;
   circuit_breaker_open_duration_minutes: number;
+  circuit_breaker_half_open_probe_enabled: boolean;
+  retry_backoff: RetryBackoffSettings;
   enable_circuit_breaker_notice: boolean;
   verbose_provider_error: boolean;
   intercept_anthropic_warmup_requests: boolean;
@@ -49,6 +155,29 @@ export type AppSettings = {
   response_fixer_fix_truncated_json: boolean;
   response_fixer_max_json_depth: number;
   response_fixer_max_fix_size: number;
+  response_fixer_spill_to_disk: boolean;
+  response_fixer_spill_max_size: number;
+  response_fixer_transcode_charsets: ResponseFixerCharset[];
+  redaction_rules: RedactionRule[];
+  env_conflict_ignore_rules: EnvConflictIgnoreRule[];
+  model_denylist: ModelDenyRule[];
+  model_capabilities: ModelCapabilityRule[];
+  capability_gate_policy: CapabilityGatePolicy;
+  storage_postgres_url: string;
+  ui_language: UiLanguage;
+  captured_response_headers: string[];
+  upstream_error_body_capture_bytes: number;
+  session_binding_ttl_seconds: number;
+  access_log_format: AccessLogFormat;
+  access_log_retention_days: number;
+  enable_cost_anomaly_detection: boolean;
+  cost_anomaly_multiplier: number;
+  cost_anomaly_auto_throttle: boolean;
+  observer_mode: boolean;
+  request_log_sample_percent: number;
+  request_log_slow_threshold_ms: number;
+  notification_sinks: NotificationSink[];
+  ssh_hosts: SshHost[];
 };
 
 export type SettingsSetInput = {
@@ -60,11 +189,29 @@ export type SettingsSetInput = {
   trayEnabled?: boolean;
   enableCliProxyStartupRecovery?: boolean;
   logRetentionDays: number;
+  attemptLogRetentionDays?: number;
   providerCooldownSeconds?: number;
   providerBaseUrlPingCacheTtlSeconds?: number;
   upstreamFirstByteTimeoutSeconds?: number;
   upstreamStreamIdleTimeoutSeconds?: number;
   upstreamRequestTimeoutNonStreamingSeconds?: number;
+  countTokensHedgeDelayMs?: number;
+  countTokensLocalEstimateEnabled?: boolean;
+  firstByteRaceDelayMs?: number;
+  nonStreamingHedgeDelayMs?: number;
+  upstreamWarmPoolEnabled?: boolean;
+  upstreamWarmPoolSize?: number;
+  requestMirrorEnabled?: boolean;
+  requestMirrorProviderId?: number;
+  requestMirrorPercent?: number;
+  requestDedupEnabled?: boolean;
+  streamTruncationRecoveryEnabled?: boolean;
+  sseCoalesceFlushMs?: number;
+  sseCoalesceFlushBytes?: number;
+  streamStallWarnSeconds?: number;
+  streamStallKeepaliveEnabled?: boolean;
+  streamFirstByteFailoverEnabled?: boolean;
+  streamErrorEventEnabled?: boolean;
   verboseProviderError?: boolean;
   interceptAnthropicWarmupRequests?: boolean;
   enableThinkingSignatureRectifier?: boolean;
@@ -81,10 +228,12 @@ export type SettingsSetInput = {
   failoverMaxProvidersToTry: number;
   circuitBreakerFailureThreshold?: number;
   circuitBreakerOpenDurationMinutes?: number;
+  circuitBreakerHalfOpenProbeEnabled?: boolean;
   wslAutoConfig?: boolean;
   wslTargetCli?: WslTargetCli;
   wslHostAddressMode?: WslHostAddressMode;
   wslCustomHostAddress?: string;
+  uiLanguage?: UiLanguage;
 };
 
 export async function settingsGet() {
@@ -94,3 +243,138 @@ export async function settingsGet() {
 export async function settingsSet(input: SettingsSetInput) {
   return invokeService<AppSettings>("更新设置失败", "settings_set", { update: input });
 }
+
+export async function settingsGatewayAccessTokenRotate() {
+  return invokeService<AppSettings>(
+    "生成网关访问令牌失败",
+    "settings_gateway_access_token_rotate"
+  );
+}
+
+export async function settingsGatewayAccessTokenClear() {
+  return invokeService<AppSettings>(
+    "清除网关访问令牌失败",
+    "settings_gateway_access_token_clear"
+  );
+}
+
+export async function settingsRedactionRulesSet(redactionRules: RedactionRule[]) {
+  return invokeService<AppSettings>("保存脱敏规则失败", "settings_redaction_rules_set", {
+    redactionRules,
+  });
+}
+
+export async function settingsEnvConflictIgnoreRulesSet(
+  envConflictIgnoreRules: EnvConflictIgnoreRule[]
+) {
+  return invokeService<AppSettings>(
+    "保存环境变量忽略规则失败",
+    "settings_env_conflict_ignore_rules_set",
+    { envConflictIgnoreRules }
+  );
+}
+
+export async function settingsModelDenylistSet(modelDenylist: ModelDenyRule[]) {
+  return invokeService<AppSettings>("保存模型黑名单失败", "settings_model_denylist_set", {
+    modelDenylist,
+  });
+}
+
+export async function settingsModelCapabilitiesSet(
+  modelCapabilities: ModelCapabilityRule[],
+  capabilityGatePolicy: CapabilityGatePolicy
+) {
+  return invokeService<AppSettings>("保存模型能力表失败", "settings_model_capabilities_set", {
+    modelCapabilities,
+    capabilityGatePolicy,
+  });
+}
+
+export async function settingsCapturedResponseHeadersSet(capturedResponseHeaders: string[]) {
+  return invokeService<AppSettings>(
+    "保存响应头采集列表失败",
+    "settings_captured_response_headers_set",
+    { capturedResponseHeaders }
+  );
+}
+
+export async function settingsUpstreamErrorBodyCaptureSet(upstreamErrorBodyCaptureBytes: number) {
+  return invokeService<AppSettings>(
+    "保存上游错误响应采集设置失败",
+    "settings_upstream_error_body_capture_set",
+    { upstreamErrorBodyCaptureBytes }
+  );
+}
+
+export async function settingsSessionBindingTtlSet(sessionBindingTtlSeconds: number) {
+  return invokeService<AppSettings>(
+    "保存会话绑定时长设置失败",
+    "settings_session_binding_ttl_set",
+    { sessionBindingTtlSeconds }
+  );
+}
+
+export async function settingsRetryBackoffSet(retryBackoff: RetryBackoffSettings) {
+  return invokeService<AppSettings>("保存重试退避设置失败", "settings_retry_backoff_set", {
+    retryBackoff,
+  });
+}
+
+export async function settingsAccessLogSet(
+  accessLogFormat: AccessLogFormat,
+  accessLogRetentionDays: number
+) {
+  return invokeService<AppSettings>("保存访问日志设置失败", "settings_access_log_set", {
+    accessLogFormat,
+    accessLogRetentionDays,
+  });
+}
+
+export async function settingsCostAnomalySet(input: {
+  enableCostAnomalyDetection: boolean;
+  costAnomalyMultiplier: number;
+  costAnomalyAutoThrottle: boolean;
+}) {
+  return invokeService<AppSettings>(
+    "保存费用异常检测设置失败",
+    "settings_cost_anomaly_set",
+    input
+  );
+}
+
+export async function settingsObserverModeSet(observerMode: boolean) {
+  return invokeService<AppSettings>("保存观察者模式设置失败", "settings_observer_mode_set", {
+    observerMode,
+  });
+}
+
+export async function settingsNotificationSinksSet(notificationSinks: NotificationSink[]) {
+  return invokeService<AppSettings>("保存通知渠道失败", "settings_notification_sinks_set", {
+    notificationSinks,
+  });
+}
+
+export async function settingsSshHostsSet(sshHosts: SshHost[]) {
+  return invokeService<AppSettings>("保存 SSH 主机失败", "settings_ssh_hosts_set", {
+    sshHosts,
+  });
+}
+
+export async function settingsRequestLogSamplingSet(input: {
+  requestLogSamplePercent: number;
+  requestLogSlowThresholdMs: number;
+}) {
+  return invokeService<AppSettings>(
+    "保存请求日志采样设置失败",
+    "settings_request_log_sampling_set",
+    input
+  );
+}
+
+export async function settingsStoragePostgresUrlSet(storagePostgresUrl: string) {
+  return invokeService<AppSettings>(
+    "保存 PostgreSQL 连接地址失败",
+    "settings_storage_postgres_url_set",
+    { storagePostgresUrl }
+  );
+}
@@ -1,5 +1,5 @@
 import { invokeService } from "./invokeServiceCommand";
-import type { AppSettings } from "./settings";
+import type { AppSettings, ResponseFixerCharset } from "./settings";
 
 export type GatewayRectifierSettingsPatch = {
   verbose_provider_error: boolean;
@@ -13,6 +13,9 @@ export type GatewayRectifierSettingsPatch = {
   response_fixer_fix_truncated_json: boolean;
   response_fixer_max_json_depth: number;
   response_fixer_max_fix_size: number;
+  response_fixer_spill_to_disk?: boolean;
+  response_fixer_spill_max_size?: number;
+  response_fixer_transcode_charsets?: ResponseFixerCharset[];
 };
 
 export async function settingsGatewayRectifierSet(input: GatewayRectifierSettingsPatch) {
@@ -28,5 +31,8 @@ export async function settingsGatewayRectifierSet(input: GatewayRectifierSetting
     responseFixerFixTruncatedJson: input.response_fixer_fix_truncated_json,
     responseFixerMaxJsonDepth: input.response_fixer_max_json_depth,
     responseFixerMaxFixSize: input.response_fixer_max_fix_size,
+    responseFixerSpillToDisk: input.response_fixer_spill_to_disk ?? null,
+    responseFixerSpillMaxSize: input.response_fixer_spill_max_size ?? null,
+    responseFixerTranscodeCharsets: input.response_fixer_transcode_charsets ?? null,
   });
 }
@@ -1,15 +1,28 @@
 import { invokeService } from "./invokeServiceCommand";
 import type { CliKey } from "./providers";
 
+export type SkillRepoAuthKind = "none" | "token" | "ssh_key";
+
 export type SkillRepoSummary = {
   id: number;
   git_url: string;
   branch: string;
   enabled: boolean;
+  // Credential for private hosts; a stored token is never returned.
+  auth_kind?: SkillRepoAuthKind;
+  ssh_key_path?: string | null;
   created_at: number;
   updated_at: number;
 };
 
+export type SkillRepoAccessReport = {
+  ok: boolean;
+  auth_kind: SkillRepoAuthKind;
+  commit: string | null;
+  error_code: string | null;
+  message: string | null;
+};
+
 export type InstalledSkillSummary = {
   id: number;
   skill_key: string;
@@ -19,6 +32,11 @@ export type InstalledSkillSummary = {
   source_branch: string;
   source_subdir: string;
   enabled: boolean;
+  pinned_ref?: string | null;
+  installed_commit?: string | null;
+  latest_commit?: string | null;
+  update_available?: boolean;
+  last_update_changed_files?: SkillChangedFile[];
   created_at: number;
   updated_at: number;
 };
@@ -57,6 +75,75 @@ export type SkillImportLocalBatchReport = {
   failed: SkillImportIssue[];
 };
 
+export type SkillChangedFile = {
+  path: string;
+  change: "added" | "modified" | "removed";
+};
+
+export type SkillUpdateStatus = {
+  skill_id: number;
+  skill_key: string;
+  name: string;
+  pinned_ref: string | null;
+  installed_commit: string | null;
+  latest_commit: string | null;
+  update_available: boolean;
+  error: string | null;
+  checked_at: number;
+};
+
+export type SkillUpdateReport = {
+  skill_id: number;
+  from_commit: string | null;
+  to_commit: string;
+  changed_files: SkillChangedFile[];
+  applied: boolean;
+};
+
+export type SkillDependencyKind = "skill" | "mcp_server" | "binary";
+
+export type SkillDependencyStatus = "satisfied" | "disabled" | "missing";
+
+export type SkillDependency = {
+  kind: SkillDependencyKind;
+  name: string;
+  status: SkillDependencyStatus;
+  detail: string | null;
+  install_candidate: AvailableSkillSummary | null;
+};
+
+export type SkillDependencyReport = {
+  skill_name: string;
+  satisfied: boolean;
+  dependencies: SkillDependency[];
+};
+
+export type SkillPackageFile = {
+  path: string;
+  size: number;
+  sha256: string;
+};
+
+export type SkillPackagePush = {
+  repo_id: number;
+  git_url: string;
+  branch: string;
+  commit: string;
+  changed: boolean;
+};
+
+export type SkillPackageReport = {
+  name: string;
+  description: string;
+  dir_name: string;
+  archive_path: string;
+  checksum_sha256: string;
+  files: SkillPackageFile[];
+  total_bytes: number;
+  warnings: string[];
+  push: SkillPackagePush | null;
+};
+
 export async function skillReposList() {
   return invokeService<SkillRepoSummary[]>("读取技能仓库列表失败", "skill_repos_list");
 }
@@ -79,6 +166,26 @@ export async function skillRepoDelete(repoId: number) {
   return invokeService<boolean>("删除技能仓库失败", "skill_repo_delete", { repoId });
 }
 
+export async function skillRepoAuthSet(input: {
+  repo_id: number;
+  auth_kind: SkillRepoAuthKind;
+  token?: string | null;
+  ssh_key_path?: string | null;
+}) {
+  return invokeService<SkillRepoSummary>("保存技能仓库凭据失败", "skill_repo_auth_set", {
+    repoId: input.repo_id,
+    authKind: input.auth_kind,
+    token: input.token ?? null,
+    sshKeyPath: input.ssh_key_path ?? null,
+  });
+}
+
+export async function skillRepoTestAccess(repoId: number) {
+  return invokeService<SkillRepoAccessReport>("测试技能仓库访问失败", "skill_repo_test_access", {
+    repoId,
+  });
+}
+
 export async function skillsInstalledList(workspaceId: number) {
   return invokeService<InstalledSkillSummary[]>("读取已安装技能失败", "skills_installed_list", {
     workspaceId,
@@ -107,6 +214,27 @@ export async function skillInstall(input: {
   });
 }
 
+export async function skillDependenciesPreview(input: {
+  workspace_id: number;
+  git_url: string;
+  branch: string;
+  source_subdir: string;
+}) {
+  return invokeService<SkillDependencyReport>("检查技能依赖失败", "skill_dependencies_preview", {
+    workspaceId: input.workspace_id,
+    gitUrl: input.git_url,
+    branch: input.branch,
+    sourceSubdir: input.source_subdir,
+  });
+}
+
+export async function skillDependenciesCheck(input: { workspace_id: number; skill_id: number }) {
+  return invokeService<SkillDependencyReport>("检查技能依赖失败", "skill_dependencies_check", {
+    workspaceId: input.workspace_id,
+    skillId: input.skill_id,
+  });
+}
+
 export async function skillSetEnabled(input: {
   workspace_id: number;
   skill_id: number;
@@ -154,6 +282,36 @@ export async function skillsImportLocalBatch(input: { workspace_id: number; dir_
   );
 }
 
+export async function skillPackage(input: {
+  source_dir: string;
+  output_dir?: string | null;
+  push_repo_id?: number | null;
+}) {
+  return invokeService<SkillPackageReport>("打包技能失败", "skill_package", {
+    sourceDir: input.source_dir,
+    outputDir: input.output_dir ?? null,
+    pushRepoId: input.push_repo_id ?? null,
+  });
+}
+
 export async function skillsPathsGet(cliKey: CliKey) {
   return invokeService<SkillsPaths>("读取技能路径失败", "skills_paths_get", { cliKey });
 }
+
+export async function skillsCheckUpdates() {
+  return invokeService<SkillUpdateStatus[]>("检查技能更新失败", "skills_check_updates");
+}
+
+export async function skillSetPin(input: { skill_id: number; pinned_ref: string | null }) {
+  return invokeService<InstalledSkillSummary>("设置技能版本固定失败", "skill_set_pin", {
+    skillId: input.skill_id,
+    pinnedRef: input.pinned_ref,
+  });
+}
+
+export async function skillUpdate(input: { skill_id: number; dry_run: boolean }) {
+  return invokeService<SkillUpdateReport>("更新技能失败", "skill_update", {
+    skillId: input.skill_id,
+    dryRun: input.dry_run,
+  });
+}
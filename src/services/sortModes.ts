@@ -1,9 +1,12 @@
 import { invokeService } from "./invokeServiceCommand";
-import type { CliKey } from "./providers";
+import { providersReorder, type CliKey } from "./providers";
+
+export type SortModeStrategy = "manual" | "adaptive";
 
 export type SortModeSummary = {
   id: number;
   name: string;
+  strategy?: SortModeStrategy;
   created_at: number;
   updated_at: number;
 };
@@ -17,6 +20,65 @@ export type SortModeActiveRow = {
 export type SortModeProviderRow = {
   provider_id: number;
   enabled: boolean;
+  active_start?: string | null;
+  active_end?: string | null;
+};
+
+export type ProviderScore = {
+  provider_id: number;
+  provider_name: string;
+  // 0..100
+  score: number;
+  attempts: number;
+  success_rate: number | null;
+  p95_duration_ms: number | null;
+  cost_multiplier: number;
+  quota_headroom: number | null;
+  explanations: string[];
+};
+
+export type SortOrderSuggestion = {
+  cli_key: CliKey;
+  // null = default provider order (no sort mode)
+  mode_id: number | null;
+  window_secs: number;
+  current_order: number[];
+  suggested_order: number[];
+  changed: boolean;
+  providers: ProviderScore[];
+};
+
+export type SortModeFallbackChain = {
+  cli_key: CliKey;
+  mode_ids: Array<number | null>;
+};
+
+export type SortModeScheduleRule = {
+  /** bit 0 = 周日 ... bit 6 = 周六 */
+  days_mask: number;
+  start: string | null;
+  end: string | null;
+  mode_id: number | null;
+};
+
+export type SortModeSchedule = {
+  cli_key: CliKey;
+  enabled: boolean;
+  utc_offset_minutes: number | null;
+  rules: SortModeScheduleRule[];
+  otherwise_enabled: boolean;
+  otherwise_mode_id: number | null;
+  updated_at: number;
+};
+
+export const SORT_MODE_SCHEDULE_SWITCHED_EVENT = "sort_mode:schedule_switched";
+
+export type SortModeScheduleSwitchedEvent = {
+  cli_key: CliKey;
+  previous_mode_id: number | null;
+  mode_id: number | null;
+  rule_index: number | null;
+  local_time: string;
 };
 
 export async function sortModesList() {
@@ -36,6 +98,13 @@ export async function sortModeRename(input: { mode_id: number; name: string }) {
   });
 }
 
+export async function sortModeSetStrategy(input: { mode_id: number; strategy: SortModeStrategy }) {
+  return invokeService<SortModeSummary>("更新排序模板策略失败", "sort_mode_set_strategy", {
+    modeId: input.mode_id,
+    strategy: input.strategy,
+  });
+}
+
 export async function sortModeDelete(input: { mode_id: number }) {
   return invokeService<boolean>("删除排序模板失败", "sort_mode_delete", {
     modeId: input.mode_id,
@@ -80,6 +149,24 @@ export async function sortModeProvidersSetOrder(input: {
   );
 }
 
+export async function sortModeSuggestOrder(input: { cli_key: CliKey; mode_id: number | null }) {
+  return invokeService<SortOrderSuggestion>("生成推荐排序失败", "sort_mode_suggest_order", {
+    cliKey: input.cli_key,
+    modeId: input.mode_id,
+  });
+}
+
+export async function sortModeApplySuggestedOrder(suggestion: SortOrderSuggestion) {
+  if (suggestion.mode_id == null) {
+    return providersReorder(suggestion.cli_key, suggestion.suggested_order);
+  }
+  return sortModeProvidersSetOrder({
+    mode_id: suggestion.mode_id,
+    cli_key: suggestion.cli_key,
+    ordered_provider_ids: suggestion.suggested_order,
+  });
+}
+
 export async function sortModeProviderSetEnabled(input: {
   mode_id: number;
   cli_key: CliKey;
@@ -97,3 +184,63 @@ export async function sortModeProviderSetEnabled(input: {
     }
   );
 }
+
+export async function sortModeProviderSetTimeWindow(input: {
+  mode_id: number;
+  cli_key: CliKey;
+  provider_id: number;
+  active_start: string | null;
+  active_end: string | null;
+}) {
+  return invokeService<SortModeProviderRow>(
+    "更新排序模板供应商时间窗口失败",
+    "sort_mode_provider_set_time_window",
+    {
+      modeId: input.mode_id,
+      cliKey: input.cli_key,
+      providerId: input.provider_id,
+      activeStart: input.active_start,
+      activeEnd: input.active_end,
+    }
+  );
+}
+
+export async function sortModeFallbackGet(input: { cli_key: CliKey }) {
+  return invokeService<SortModeFallbackChain>("读取排序模板回退链失败", "sort_mode_fallback_get", {
+    cliKey: input.cli_key,
+  });
+}
+
+export async function sortModeFallbackSet(input: {
+  cli_key: CliKey;
+  mode_ids: Array<number | null>;
+}) {
+  return invokeService<SortModeFallbackChain>("更新排序模板回退链失败", "sort_mode_fallback_set", {
+    cliKey: input.cli_key,
+    modeIds: input.mode_ids,
+  });
+}
+
+export async function sortModeScheduleGet(input: { cli_key: CliKey }) {
+  return invokeService<SortModeSchedule>("读取排序模板定时切换失败", "sort_mode_schedule_get", {
+    cliKey: input.cli_key,
+  });
+}
+
+export async function sortModeScheduleSet(input: {
+  cli_key: CliKey;
+  enabled: boolean;
+  utc_offset_minutes: number | null;
+  rules: SortModeScheduleRule[];
+  otherwise_enabled: boolean;
+  otherwise_mode_id: number | null;
+}) {
+  return invokeService<SortModeSchedule>("更新排序模板定时切换失败", "sort_mode_schedule_set", {
+    cliKey: input.cli_key,
+    enabled: input.enabled,
+    utcOffsetMinutes: input.utc_offset_minutes,
+    rules: input.rules,
+    otherwiseEnabled: input.otherwise_enabled,
+    otherwiseModeId: input.otherwise_mode_id,
+  });
+}
@@ -0,0 +1,35 @@
+import { invokeService } from "./invokeServiceCommand";
+import type { WslConfigureCliReport } from "./wsl";
+
+export type SshHostCheckReport = {
+  name: string;
+  ssh_ok: boolean;
+  remote_system: string | null;
+  gateway_origin: string;
+  gateway_reachable: boolean;
+  message: string;
+};
+
+export type SshConfigureHostReport = {
+  name: string;
+  ok: boolean;
+  results: WslConfigureCliReport[];
+};
+
+export type SshConfigureReport = {
+  ok: boolean;
+  message: string;
+  hosts: SshConfigureHostReport[];
+};
+
+export async function sshHostCheck(name: string) {
+  return invokeService<SshHostCheckReport>("检测 SSH 主机失败", "ssh_host_check", { name });
+}
+
+export async function sshConfigureClients(name?: string) {
+  return invokeService<SshConfigureReport>(
+    "配置 SSH 主机客户端失败",
+    "ssh_configure_clients",
+    name !== undefined ? { name } : undefined
+  );
+}
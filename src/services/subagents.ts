@@ -0,0 +1,55 @@
+import { invokeService } from "./invokeServiceCommand";
+
+export type SubagentSummary = {
+  id: number;
+  name: string;
+  description: string;
+  prompt: string;
+  tools: string[];
+  model: string | null;
+  enabled: boolean;
+  created_at: number;
+  updated_at: number;
+};
+
+export async function subagentsList(workspaceId: number) {
+  return invokeService<SubagentSummary[]>("读取子代理列表失败", "subagents_list", {
+    workspaceId,
+  });
+}
+
+export async function subagentsUpsert(input: {
+  subagent_id?: number | null;
+  workspace_id: number;
+  name: string;
+  description: string;
+  prompt: string;
+  tools?: string[];
+  model?: string | null;
+}) {
+  return invokeService<SubagentSummary>("保存子代理失败", "subagents_upsert", {
+    subagentId: input.subagent_id ?? null,
+    workspaceId: input.workspace_id,
+    name: input.name,
+    description: input.description,
+    prompt: input.prompt,
+    tools: input.tools ?? [],
+    model: input.model ?? null,
+  });
+}
+
+export async function subagentsSetEnabled(input: {
+  workspace_id: number;
+  subagent_id: number;
+  enabled: boolean;
+}) {
+  return invokeService<SubagentSummary>("更新子代理启用状态失败", "subagents_set_enabled", {
+    workspaceId: input.workspace_id,
+    subagentId: input.subagent_id,
+    enabled: input.enabled,
+  });
+}
+
+export async function subagentsDelete(subagentId: number) {
+  return invokeService<boolean>("删除子代理失败", "subagents_delete", { subagentId });
+}
@@ -174,3 +174,66 @@ export async function usageProviderCacheRateTrendV1(
     }
   );
 }
+
+export type UsageForecastRowV1 = {
+  provider_id: number;
+  cli_key: CliKey;
+  provider_name: string;
+  month_to_date_tokens: number;
+  expected_total_tokens: number;
+  expected_total_tokens_low: number;
+  expected_total_tokens_high: number;
+  month_to_date_cost_usd: number;
+  expected_total_cost_usd: number;
+  expected_total_cost_usd_low: number;
+  expected_total_cost_usd_high: number;
+  budget_usd: number | null;
+  days_until_budget_exhausted: number | null;
+};
+
+export type UsageForecastV1 = {
+  month: string;
+  day_of_month: number;
+  days_in_month: number;
+  history_days: number;
+  rows: UsageForecastRowV1[];
+};
+
+export async function usageForecastV1(cliKey?: CliKey | null) {
+  return invokeService<UsageForecastV1>("读取用量预测失败", "usage_forecast_v1", {
+    cliKey: cliKey ?? null,
+  });
+}
+
+export type UsageBandwidthRowV1 = {
+  day: string;
+  cli_key: CliKey;
+  provider_id: number;
+  provider_name: string | null;
+  requests: number;
+  requests_with_bytes: number;
+  request_bytes: number;
+  response_bytes: number;
+};
+
+export async function usageBandwidthV1(period: UsagePeriod, input?: UsageQueryInputV2) {
+  return invokeService<UsageBandwidthRowV1[]>("读取供应商流量统计失败", "usage_bandwidth_v1", {
+    params: buildQueryParamsV2(period, input),
+  });
+}
+
+export type UsageHeatmapCellV1 = {
+  weekday: number;
+  hour: number;
+  requests: number;
+  input_tokens: number;
+  output_tokens: number;
+  total_tokens: number;
+  cost_usd: number;
+};
+
+export async function usageHeatmapV1(period: UsagePeriod, input?: UsageQueryInputV2) {
+  return invokeService<UsageHeatmapCellV1[]>("读取用量热力图失败", "usage_heatmap_v1", {
+    params: buildQueryParamsV2(period, input),
+  });
+}
@@ -82,3 +82,36 @@ export async function workspaceApply(workspaceId: number) {
     workspaceId,
   });
 }
+
+export type WorkspaceSnapshotSummary = {
+  id: number;
+  cli_key: CliKey;
+  workspace_id: number | null;
+  reason: "workspace_apply" | "rollback";
+  enabled_prompt_name: string | null;
+  enabled_mcp_server_keys: string[];
+  enabled_skill_keys: string[];
+  created_at: number;
+};
+
+export type WorkspaceRollbackReport = {
+  cli_key: CliKey;
+  snapshot_id: number;
+  workspace_id: number;
+  undo_snapshot_id: number;
+  rolled_back_at: number;
+};
+
+export async function workspaceSnapshotList(cliKey: CliKey) {
+  return invokeService<WorkspaceSnapshotSummary[]>(
+    "读取工作区快照失败",
+    "workspace_snapshot_list",
+    { cliKey }
+  );
+}
+
+export async function workspaceRollback(snapshotId: number) {
+  return invokeService<WorkspaceRollbackReport>("回滚工作区快照失败", "workspace_rollback", {
+    snapshotId,
+  });
+}
@@ -60,3 +60,43 @@ export async function wslConfigStatusGet(distros?: string[]) {
 export async function wslConfigureClients() {
   return invokeService<WslConfigureReport>("配置 WSL 客户端失败", "wsl_configure_clients");
 }
+
+export type WslNetworkingMode = "nat" | "mirrored" | "other";
+
+export type WslFindingSeverity = "ok" | "warning" | "error";
+
+export type WslRepairAction = "add_firewall_rule" | "configure_clients";
+
+export type WslDiagnosticFinding = {
+  check: string;
+  distro: string | null;
+  severity: WslFindingSeverity;
+  message: string;
+  repair: WslRepairAction | null;
+};
+
+export type WslDiagnosticsReport = {
+  detected: boolean;
+  networking_mode: WslNetworkingMode;
+  gateway_origin: string | null;
+  findings: WslDiagnosticFinding[];
+};
+
+export type WslRepairOutcome = {
+  action: WslRepairAction;
+  ok: boolean;
+  message: string;
+};
+
+export type WslRepairReport = {
+  outcomes: WslRepairOutcome[];
+  diagnostics: WslDiagnosticsReport;
+};
+
+export async function wslDiagnose() {
+  return invokeService<WslDiagnosticsReport>("WSL 诊断失败", "wsl_diagnose");
+}
+
+export async function wslRepair() {
+  return invokeService<WslRepairReport>("WSL 修复失败", "wsl_repair");
+}
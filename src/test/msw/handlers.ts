@@ -141,7 +141,8 @@ export const handlers = [
       cli_key: cliKey,
       name: input.name,
       base_urls: input.baseUrls.map((value) => String(value)),
-      base_url_mode: input.baseUrlMode === "ping" ? "ping" : "order",
+      base_url_mode:
+        input.baseUrlMode === "ping" ? "ping" : input.baseUrlMode === "race" ? "race" : "order",
       claude_models:
         input.claudeModels && typeof input.claudeModels === "object"
           ? (input.claudeModels as ClaudeModels)
@@ -174,6 +175,15 @@ export const handlers = [
       oauth_email: existing?.oauth_email ?? null,
       oauth_expires_at: existing?.oauth_expires_at ?? null,
       oauth_last_error: existing?.oauth_last_error ?? null,
+      provider_kind:
+        input.providerKind === "aggregated" ||
+        input.providerKind === "standard" ||
+        input.providerKind === "federated"
+          ? input.providerKind
+          : (existing?.provider_kind ?? "standard"),
+      model_catalog: existing?.model_catalog ?? [],
+      model_catalog_refreshed_at: existing?.model_catalog_refreshed_at ?? null,
+      model_catalog_error: existing?.model_catalog_error ?? null,
     };
 
     setProvidersState(
@@ -279,6 +289,16 @@ export const handlers = [
   // ---- Skills ----
   http.post(`${TAURI_ENDPOINT}/skill_repos_list`, () => HttpResponse.json([])),
   http.post(`${TAURI_ENDPOINT}/skill_repo_upsert`, () => HttpResponse.json(null)),
+  http.post(`${TAURI_ENDPOINT}/skill_repo_auth_set`, () => HttpResponse.json(null)),
+  http.post(`${TAURI_ENDPOINT}/skill_repo_test_access`, () =>
+    HttpResponse.json({
+      ok: true,
+      auth_kind: "none",
+      commit: null,
+      error_code: null,
+      message: null,
+    })
+  ),
   http.post(`${TAURI_ENDPOINT}/skill_repo_delete`, () => HttpResponse.json(true)),
   http.post(`${TAURI_ENDPOINT}/skills_installed_list`, () => HttpResponse.json([])),
   http.post(`${TAURI_ENDPOINT}/skills_discover_available`, () => HttpResponse.json([])),